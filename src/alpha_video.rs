@@ -0,0 +1,322 @@
+use crate::app_state::VideoSettings;
+use crate::comprehensive_codec_registry::{is_alpha_pixel_format, CodecInfo, ComprehensiveCodecRegistry};
+use crate::ffmpeg_worker_simple::MediaInfo;
+use crate::language::Translations;
+
+/// Alpha-preserving encoders offered for transparent sources, with the container each is delivered in
+pub const ALPHA_CODECS: [(&str, &str); 4] = [
+    ("prores_ks", "mov"),
+    ("qtrle", "mov"),
+    ("libvpx-vp9", "webm"),
+    ("ffv1", "mkv"),
+];
+
+/// Background used when the user chooses to flatten instead of keeping alpha
+pub const DEFAULT_BACKGROUND: &str = "0x000000";
+
+/// ProRes 4444 in prores_ks profile numbering
+const PRORES_4444_PROFILE: &str = "4";
+
+/// Alpha pixel format of the first video stream, if it has one
+pub fn source_alpha_format(info: &MediaInfo) -> Option<&str> {
+    info.video_streams.first()
+        .and_then(|stream| stream.pix_fmt.as_deref())
+        .filter(|pix_fmt| is_alpha_pixel_format(pix_fmt))
+}
+
+pub fn codec_preserves_alpha(codec: &str) -> bool {
+    ComprehensiveCodecRegistry::get_video_codecs().get(codec).is_some_and(|info| info.supports_alpha())
+}
+
+/// Alpha-preserving encoder delivered in this container
+pub fn auto_codec_for(container: &str) -> Option<&'static str> {
+    ALPHA_CODECS.iter().find(|(_, format)| *format == container).map(|(codec, _)| *codec)
+}
+
+/// Alpha-preserving encoder for an "auto" codec when the source is transparent and is not being flattened
+pub fn auto_codec_for_settings(video: &VideoSettings, container: &str) -> Option<&'static str> {
+    if video.codec == "auto" && video.source_alpha && video.alpha_background.is_none() {
+        auto_codec_for(container)
+    } else {
+        None
+    }
+}
+
+/// Codec the task will actually encode with, resolving "auto" the way the executor does for alpha sources
+fn effective_codec(video: &VideoSettings) -> String {
+    if video.codec == "auto" {
+        auto_codec_for(&video.container_format).unwrap_or("auto").to_string()
+    } else {
+        video.codec.clone()
+    }
+}
+
+/// Whether the output keeps the source's alpha channel with the current settings
+pub fn output_keeps_alpha(video: &VideoSettings) -> bool {
+    video.copy_video || (video.alpha_background.is_none() && codec_preserves_alpha(&effective_codec(video)))
+}
+
+/// Encoder flags that keep alpha: the codec's alpha pixel format plus ProRes 4444 or the VP9 alt-ref switch.
+/// Explicit pixel format and profile settings win.
+pub fn encoder_args(codec_info: &CodecInfo, video: &VideoSettings) -> Vec<String> {
+    if !video.source_alpha || video.alpha_background.is_some() {
+        return Vec::new();
+    }
+    let Some(pix_fmt) = codec_info.alpha_pixel_format() else {
+        return Vec::new();
+    };
+
+    let mut args = Vec::new();
+    if video.pixel_format == "auto" {
+        args.push("-pix_fmt".to_string());
+        args.push(pix_fmt.to_string());
+    }
+    match codec_info.name.as_str() {
+        "prores_ks" if video.profile == "auto" => {
+            args.push("-profile:v".to_string());
+            args.push(PRORES_4444_PROFILE.to_string());
+        }
+        // libvpx drops the alpha plane when alternate reference frames are on
+        "libvpx" | "libvpx-vp9" => {
+            args.push("-auto-alt-ref".to_string());
+            args.push("0".to_string());
+        }
+        _ => {}
+    }
+    args
+}
+
+/// Filter chain that composites the video over a solid `color` box and drops alpha
+pub fn flatten_filter(color: &str) -> String {
+    format!(
+        "format=yuva444p,split[alpha_fg][alpha_bg];[alpha_bg]drawbox=c={}:t=fill:replace=1[alpha_base];[alpha_base][alpha_fg]overlay=format=auto,format=yuv420p",
+        color
+    )
+}
+
+fn codec_display_name(codec: &str) -> String {
+    ComprehensiveCodecRegistry::get_video_codecs().get(codec)
+        .map(|info| info.display_name.clone())
+        .unwrap_or_else(|| codec.to_string())
+}
+
+fn select_alpha_codec(video: &mut VideoSettings, codec: &str, container: &str) {
+    video.codec = codec.to_string();
+    if !ComprehensiveCodecRegistry::is_compatible(codec, &video.container_format) {
+        video.container_format = container.to_string();
+    }
+}
+
+/// Codec picker restricted to alpha-preserving encoders, used while the source has alpha
+pub fn show_alpha_codec_selector(ui: &mut egui::Ui, video: &mut VideoSettings, translations: &Translations) {
+    let selected = if video.codec == "auto" {
+        translations.auto_recommended().to_string()
+    } else {
+        codec_display_name(&video.codec)
+    };
+    egui::ComboBox::from_id_salt("alpha_codec_selector")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(video.codec == "auto", translations.auto_recommended()).clicked() {
+                video.codec = "auto".to_string();
+                if auto_codec_for(&video.container_format).is_none() {
+                    video.container_format = ALPHA_CODECS[0].1.to_string();
+                }
+            }
+            for (codec, container) in ALPHA_CODECS {
+                let label = format!("{} (.{})", codec_display_name(codec), container);
+                if ui.selectable_label(video.codec == codec, label).clicked() {
+                    select_alpha_codec(video, codec, container);
+                }
+            }
+        });
+    ui.weak(translations.alpha_codecs_only());
+}
+
+/// Alpha status under the settings: what happens to transparency and how to keep or flatten it
+pub fn show_alpha_panel(ui: &mut egui::Ui, info: &MediaInfo, video: &mut VideoSettings, translations: &Translations) {
+    let Some(pix_fmt) = source_alpha_format(info) else {
+        return;
+    };
+
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(translations.alpha_detected(pix_fmt)).strong());
+
+        if output_keeps_alpha(video) {
+            ui.colored_label(egui::Color32::from_rgb(100, 200, 100),
+                format!("✅ {}", translations.alpha_preserved(&codec_display_name(&effective_codec(video)))));
+        } else if video.alpha_background.is_none() {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100),
+                egui::RichText::new(format!("⚠ {}", translations.alpha_dropped(&codec_display_name(&effective_codec(video))))).strong());
+            ui.horizontal_wrapped(|ui| {
+                ui.label(translations.alpha_keep_with());
+                for (codec, container) in ALPHA_CODECS {
+                    if ui.button(codec_display_name(codec)).clicked() {
+                        select_alpha_codec(video, codec, container);
+                    }
+                }
+            });
+        }
+
+        let mut flatten = video.alpha_background.is_some();
+        if ui.checkbox(&mut flatten, translations.alpha_flatten()).changed() {
+            video.alpha_background = flatten.then(|| DEFAULT_BACKGROUND.to_string());
+        }
+        if let Some(background) = &mut video.alpha_background {
+            let mut rgb = parse_hex_color(background).unwrap_or([0, 0, 0]);
+            ui.horizontal(|ui| {
+                ui.label(translations.alpha_background_color());
+                if ui.color_edit_button_srgb(&mut rgb).changed() {
+                    *background = format!("0x{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2]);
+                }
+            });
+        }
+    });
+}
+
+/// Parse "0xRRGGBB" / "#RRGGBB" as used for the background color
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim_start_matches("0x").trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+    fn media(pix_fmts: &[&str]) -> MediaInfo {
+        let streams: Vec<serde_json::Value> = pix_fmts.iter().enumerate()
+            .map(|(index, pix_fmt)| serde_json::json!({
+                "index": index, "codec": "png", "width": 640, "height": 360, "fps": 25.0, "pix_fmt": pix_fmt,
+            }))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "filename": "overlay.mov", "duration": 5.0, "video_streams": streams, "audio_streams": [],
+        })).unwrap()
+    }
+
+    fn alpha_settings(codec: &str, container: &str) -> VideoSettings {
+        VideoSettings {
+            codec: codec.to_string(),
+            container_format: container.to_string(),
+            source_alpha: true,
+            ..Default::default()
+        }
+    }
+
+    fn command(video: &VideoSettings, output: &str) -> Vec<String> {
+        ComprehensiveCommandBuilder::build_video_conversion_command("overlay.mov", output, video, None).unwrap()
+    }
+
+    fn has(args: &[String], pair: [&str; 2]) -> bool {
+        args.windows(2).any(|window| window == pair)
+    }
+
+    #[test]
+    fn alpha_sources_are_detected_from_the_first_stream() {
+        for pix_fmt in ["yuva420p", "yuva444p10le", "rgba", "bgra", "argb", "gbrap", "gbrap10le", "ya8"] {
+            assert_eq!(source_alpha_format(&media(&[pix_fmt])), Some(pix_fmt));
+        }
+        for pix_fmt in ["yuv420p", "yuv444p10le", "rgb24", "gray", "gbrp", "nv12"] {
+            assert_eq!(source_alpha_format(&media(&[pix_fmt])), None, "{pix_fmt}");
+        }
+        assert_eq!(source_alpha_format(&media(&["yuv420p", "rgba"])), None);
+        assert_eq!(source_alpha_format(&media(&[])), None);
+    }
+
+    #[test]
+    fn prores_4444_keeps_alpha() {
+        let video = alpha_settings("prores_ks", "mov");
+        assert!(output_keeps_alpha(&video));
+        let args = command(&video, "out.mov");
+        assert!(has(&args, ["-c:v", "prores_ks"]), "{args:?}");
+        assert!(has(&args, ["-pix_fmt", "yuva444p10le"]), "{args:?}");
+        assert!(has(&args, ["-profile:v", "4"]), "{args:?}");
+
+        // A profile chosen by hand is left alone
+        let video = VideoSettings { profile: "3".to_string(), ..alpha_settings("prores_ks", "mov") };
+        let args = command(&video, "out.mov");
+        assert!(!has(&args, ["-profile:v", "4"]), "{args:?}");
+        assert!(has(&args, ["-pix_fmt", "yuva444p10le"]), "{args:?}");
+    }
+
+    #[test]
+    fn vp9_alpha_turns_off_alt_ref_frames() {
+        let video = alpha_settings("libvpx-vp9", "webm");
+        assert!(output_keeps_alpha(&video));
+        let args = command(&video, "out.webm");
+        assert!(has(&args, ["-c:v", "libvpx-vp9"]), "{args:?}");
+        assert!(has(&args, ["-pix_fmt", "yuva420p"]), "{args:?}");
+        assert!(has(&args, ["-auto-alt-ref", "0"]), "{args:?}");
+    }
+
+    #[test]
+    fn opaque_sources_and_explicit_pixel_formats_get_no_alpha_flags() {
+        let opaque = VideoSettings { source_alpha: false, ..alpha_settings("libvpx-vp9", "webm") };
+        let args = command(&opaque, "out.webm");
+        assert!(!has(&args, ["-pix_fmt", "yuva420p"]) && !has(&args, ["-auto-alt-ref", "0"]), "{args:?}");
+
+        let codecs = ComprehensiveCodecRegistry::get_video_codecs();
+        let explicit = VideoSettings { pixel_format: "yuv420p".to_string(), ..alpha_settings("libvpx-vp9", "webm") };
+        assert_eq!(encoder_args(&codecs["libvpx-vp9"], &explicit), ["-auto-alt-ref", "0"]);
+        // Encoders without an alpha pixel format add nothing
+        assert!(encoder_args(&codecs["libx264"], &alpha_settings("libx264", "mp4")).is_empty());
+    }
+
+    #[test]
+    fn flattening_composites_onto_the_background() {
+        let video = VideoSettings { alpha_background: Some("0xFFFFFF".to_string()), ..alpha_settings("prores_ks", "mov") };
+        assert!(!output_keeps_alpha(&video));
+        let args = command(&video, "out.mov");
+        assert!(args.iter().any(|arg| arg.contains("drawbox=c=0xFFFFFF:t=fill:replace=1")), "{args:?}");
+        assert!(!has(&args, ["-pix_fmt", "yuva444p10le"]) && !has(&args, ["-profile:v", "4"]), "{args:?}");
+        assert!(flatten_filter("0x102030").ends_with("format=yuv420p"));
+
+        // Without alpha in the source the background does nothing
+        let opaque = VideoSettings { source_alpha: false, ..video };
+        assert!(!command(&opaque, "out.mov").iter().any(|arg| arg.contains("drawbox")));
+    }
+
+    #[test]
+    fn auto_codec_follows_the_container() {
+        assert_eq!(auto_codec_for("mov"), Some("prores_ks"));
+        assert_eq!(auto_codec_for("webm"), Some("libvpx-vp9"));
+        assert_eq!(auto_codec_for("mkv"), Some("ffv1"));
+        assert_eq!(auto_codec_for("mp4"), None);
+
+        let auto = alpha_settings("auto", "webm");
+        assert_eq!(auto_codec_for_settings(&auto, "webm"), Some("libvpx-vp9"));
+        assert!(output_keeps_alpha(&auto));
+        assert!(!output_keeps_alpha(&alpha_settings("auto", "mp4")));
+        let flattened = VideoSettings { alpha_background: Some(DEFAULT_BACKGROUND.to_string()), ..auto.clone() };
+        assert_eq!(auto_codec_for_settings(&flattened, "webm"), None);
+        let opaque = VideoSettings { source_alpha: false, ..auto };
+        assert_eq!(auto_codec_for_settings(&opaque, "webm"), None);
+    }
+
+    #[test]
+    fn codecs_that_drop_alpha() {
+        for (codec, _) in ALPHA_CODECS {
+            assert!(codec_preserves_alpha(codec), "{codec}");
+        }
+        for codec in ["libx264", "libx265", "mpeg4", "no_such_codec"] {
+            assert!(!codec_preserves_alpha(codec), "{codec}");
+        }
+        assert!(!output_keeps_alpha(&alpha_settings("libx264", "mp4")));
+        // Stream copy passes the alpha plane through whatever the codec setting says
+        assert!(output_keeps_alpha(&VideoSettings { copy_video: true, ..alpha_settings("libx264", "mp4") }));
+    }
+
+    #[test]
+    fn background_colors() {
+        assert_eq!(parse_hex_color("0xFF8000"), Some([255, 128, 0]));
+        assert_eq!(parse_hex_color("#00ff7f"), Some([0, 255, 127]));
+        assert_eq!(parse_hex_color("0xFFF"), None);
+        assert_eq!(parse_hex_color("0xGG0000"), None);
+    }
+}
@@ -7,6 +7,8 @@ pub enum OperationType {
     VideoCrop,              // Video cropping
     VideoRotate,            // Video rotation
     VideoFilter,            // Video filters
+    CutAndJoin,             // Keep selected segments and join them
+    FrameRateConvert,       // Frame rate conversion (fps, motion interpolation, speed change)
 
     // Audio processing
     AudioConvert,           // Audio format conversion
@@ -15,6 +17,7 @@ pub enum OperationType {
     AudioVolume,            // Volume adjustment
     AudioTrim,              // Audio trimming
     AudioMerge,             // Audio merging
+    AudioNormalize,         // Two-pass loudness normalization
 
     // Video/Audio combinations
     VideoAudioMerge,        // Video+Audio synthesis
@@ -31,6 +34,7 @@ pub enum OperationType {
     FrameExtract,           // Extract frames
     VideoToGif,             // Video to GIF
     GifResize,              // GIF resize
+    ReviewClip,             // Small proxy of a range for client review
 }
 
 impl OperationType {
@@ -42,6 +46,8 @@ impl OperationType {
             OperationType::VideoCrop => format!("✂ {}", translations.video_crop()),
             OperationType::VideoRotate => format!("🔄 {}", translations.video_rotate()),
             OperationType::VideoFilter => format!("✨ {}", translations.video_filter()),
+            OperationType::CutAndJoin => format!("🎞 {}", translations.cut_and_join()),
+            OperationType::FrameRateConvert => format!("⚡ {}", translations.frame_rate_convert()),
             
             OperationType::AudioConvert => format!("🎵 {}", translations.audio_convert()),
             OperationType::AudioCompress => format!("🗜 {}", translations.audio_compress()),
@@ -49,6 +55,7 @@ impl OperationType {
             OperationType::AudioVolume => format!("🔊 {}", translations.audio_volume()),
             OperationType::AudioTrim => format!("✂ {}", translations.audio_trim()),
             OperationType::AudioMerge => format!("🔗 {}", translations.audio_merge()),
+            OperationType::AudioNormalize => format!("📊 {}", translations.audio_normalize()),
             
             OperationType::VideoAudioMerge => format!("🎭 {}", translations.video_audio_merge()),
             OperationType::VideoAudioSplit => format!("🎯 {}", translations.video_audio_split()),
@@ -62,6 +69,7 @@ impl OperationType {
             OperationType::FrameExtract => format!("📷 {}", translations.frame_extract()),
             OperationType::VideoToGif => format!("🎞 {}", translations.video_to_gif()),
             OperationType::GifResize => format!("🖼 {}", translations.gif_resize()),
+            OperationType::ReviewClip => format!("🎬 {}", translations.review_clip_operation()),
         }
     }
 
@@ -69,11 +77,14 @@ impl OperationType {
         match self {
             OperationType::VideoConvert | OperationType::VideoCompress | 
             OperationType::VideoResize | OperationType::VideoCrop | 
-            OperationType::VideoRotate | OperationType::VideoFilter => translations.video_processing().to_string(),
+            OperationType::VideoRotate | OperationType::VideoFilter |
+            OperationType::CutAndJoin | OperationType::ReviewClip |
+            OperationType::FrameRateConvert => translations.video_processing().to_string(),
             
             OperationType::AudioConvert | OperationType::AudioCompress | 
             OperationType::AudioResample | OperationType::AudioVolume | 
-            OperationType::AudioTrim | OperationType::AudioMerge => translations.audio_processing().to_string(),
+            OperationType::AudioTrim | OperationType::AudioMerge |
+            OperationType::AudioNormalize => translations.audio_processing().to_string(),
             
             OperationType::VideoAudioMerge | OperationType::VideoAudioSplit | 
             OperationType::ExtractAudio | OperationType::ExtractVideo => translations.video_audio_operations().to_string(),
@@ -105,15 +116,41 @@ pub struct VideoSettings {
     // Format conversion
     pub container_format: String,
     pub copy_video: bool,
+    #[serde(default)]
+    pub container_options: std::collections::BTreeMap<String, bool>, // Muxer option overrides; missing keys use the registry default
+    #[serde(skip)]
+    pub input_program: Option<u32>, // Transport stream program to map, chosen per input file
+    #[serde(skip)]
+    pub source_alpha: bool,        // Input pixel format has an alpha channel, set per input file
+    #[serde(default)]
+    pub alpha_background: Option<String>, // Flatten alpha onto this color instead of preserving it
+    #[serde(default)]
+    pub keep_output_extension: bool, // Keep a mismatched output extension and force the container with -f
+    #[serde(skip)]
+    pub max_threads: usize,        // Encoder thread cap from the power policy, set when the task starts (0 = no cap)
+    #[serde(default = "default_true")]
+    pub preserve_chapters: bool,   // Copy the source chapters (or the edited ones) to the output
+    #[serde(skip)]
+    pub chapter_edits: Option<Vec<crate::chapters::Chapter>>, // Edited chapters of the current input, None keeps the source's
+    #[serde(skip)]
+    pub chapter_metadata_file: Option<String>, // FFMETADATA file with the edited chapters, set when the task starts
+    #[serde(skip)]
+    pub resume_at: Option<f64>,    // Seconds of the partial output kept when resuming after the destination dropped out
     
     // Compression
     pub crf: i32,
     pub target_size_mb: i32,
+    #[serde(default)]
+    pub two_pass: bool,            // Two-pass encoding at `bitrate` instead of CRF
+    #[serde(default)]
+    pub size_target_confirm: bool, // Show the bitrate computed from target_size_mb for approval instead of applying it at run time
     
     // Resolution
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub maintain_aspect_ratio: bool,
+    #[serde(default)]
+    pub frame_fit: Option<FrameFit>, // Fill width x height exactly by cropping or padding; None scales to it directly
     
     // Crop
     pub crop_top: Option<u32>,
@@ -135,6 +172,8 @@ pub struct VideoSettings {
     pub brightness: f32,
     pub contrast: f32,
     pub saturation: f32,
+    #[serde(default)]
+    pub filter_stack: Vec<crate::filter_stack::FilterStackEntry>, // Ordered VideoFilter chain; empty falls back to the fields above
     
     // Batch processing
     pub batch_naming_pattern: String,
@@ -148,20 +187,36 @@ pub struct VideoSettings {
     
     // Watermark settings  
     pub watermark_file: String,
-    pub watermark_position: String, // "top-left", "top-right", "bottom-left", "bottom-right", "center"
+    pub watermark_position: String, // "top-left", "top-right", "bottom-left", "bottom-right", "center", "custom" (watermark_x/y)
     pub watermark_opacity: f32,
     pub watermark_scale: f32,
     pub watermark_x: i32,       // X coordinate for watermark
     pub watermark_y: i32,       // Y coordinate for watermark
+    #[serde(default)]
+    pub watermark_text: String, // Text drawn with drawtext; {column} placeholders are filled per row in data-driven batches
+    #[serde(default = "default_watermark_font_size")]
+    pub watermark_font_size: u32,
+    #[serde(default)]
+    pub watermark_font: String, // Family, font file name or path for drawtext; empty uses ffmpeg's default
     
     // Frame extraction settings
-    pub frame_extract_mode: String, // "all", "interval", "time"
+    pub frame_extract_mode: String, // "all", "interval", "seconds" ("time" in older settings)
     pub frame_interval: i32,        // Extract every N frames
+    #[serde(default = "default_frame_interval_seconds")]
+    pub frame_interval_seconds: f32, // Extract one frame every N seconds
     pub frame_start_time: String,   // Start time for extraction
     pub frame_end_time: String,     // End time for extraction  
     pub frame_format: String,       // "png", "jpg", "bmp"
     pub frame_quality: i32,         // JPEG quality (1-31, lower is better)
+    #[serde(default = "default_png_compression")]
+    pub frame_png_compression: i32, // PNG zlib level (0-9), only affects size and speed
+    #[serde(default)]
+    pub frame_output_dir: String,   // Folder the extracted frames are written to
+    #[serde(default)]
+    pub frame_name_pattern: String, // File name pattern with a %d frame number, without extension
     pub frame_rate: i32,            // Frame rate for extraction
+    #[serde(default = "default_true")]
+    pub accurate_color_conversion: bool, // Explicit range/matrix conversion for exported stills
     
     // Subtitle style settings
     pub subtitle_font_family: String,
@@ -170,6 +225,10 @@ pub struct VideoSettings {
     pub subtitle_outline_color: String,
     pub subtitle_background_color: String,
     pub subtitle_alignment: String,
+    #[serde(default)]
+    pub subtitle_offset_ms: i64,   // Timing shift applied to the subtitle file
+    #[serde(default = "default_subtitle_stretch")]
+    pub subtitle_stretch: f64,     // Linear timing stretch for framerate mismatches
     
     // GIF conversion settings
     pub gif_fps: f32,
@@ -182,6 +241,56 @@ pub struct VideoSettings {
     pub speed_priority: bool,      // Prioritize speed over quality
     pub gif_dither: String,  // "none", "bayer", "floyd_steinberg"
     pub gif_colors: i32,     // Number of colors (2-256)
+    
+    // Cut and join settings
+    #[serde(default)]
+    pub cut_segments: Vec<CutSegment>,
+    #[serde(default)]
+    pub cut_reencode: bool,        // Frame-accurate re-encode instead of keyframe stream copy
+    
+    // Frame rate conversion
+    #[serde(default)]
+    pub fps_conversion: crate::fps_conversion::FpsConversionSettings,
+    
+    // Review clip tasks only; the range is the single cut segment
+    #[serde(skip)]
+    pub review_clip: Option<crate::review_clip::ReviewClipSettings>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_frame_interval_seconds() -> f32 {
+    1.0
+}
+
+fn default_png_compression() -> i32 {
+    6
+}
+
+fn default_subtitle_stretch() -> f64 {
+    1.0
+}
+
+fn default_watermark_font_size() -> u32 {
+    32
+}
+
+/// How a source with a different aspect ratio is fitted into an exact output size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FrameFit {
+    /// Scale to cover the frame and cut off the overflow
+    Crop,
+    /// Scale to fit inside the frame and fill the rest with black bars
+    Pad,
+}
+
+/// Time range (in seconds) kept by the cut-and-join operation
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CutSegment {
+    pub start: f64,
+    pub end: f64,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -197,6 +306,14 @@ pub struct AudioSettings {
     // Format conversion
     pub format: String,
     pub copy_audio: bool,
+    #[serde(default)]
+    pub keep_output_extension: bool, // Keep a mismatched output extension and force the format with -f
+    #[serde(default = "default_true")]
+    pub preserve_chapters: bool,   // Copy the source chapters (or the edited ones) to the output
+    #[serde(skip)]
+    pub chapter_edits: Option<Vec<crate::chapters::Chapter>>, // Edited chapters of the current input, None keeps the source's
+    #[serde(skip)]
+    pub chapter_metadata_file: Option<String>, // FFMETADATA file with the edited chapters, set when the task starts
     
     // Compression
     pub vbr_quality: i32,
@@ -208,26 +325,44 @@ pub struct AudioSettings {
     pub normalize: bool,
     pub target_lufs: f32,
     
+    // Noise reduction
+    #[serde(default)]
+    pub denoise: crate::audio_denoise::AudioDenoiseSettings,
+    
+    // Loudness normalization (two-pass loudnorm)
+    #[serde(default)]
+    pub loudness: crate::loudness::LoudnessTarget,
+    #[serde(skip)]
+    pub content_type: Option<crate::audio_advice::ContentType>, // Voice or music, guessed from the input's spectrum
+    
     // Trim
     pub start_time: String,
     pub end_time: String,
-    pub fade_in: bool,
-    pub fade_out: bool,
+    #[serde(default)]
+    pub fade: crate::audio_fade::AudioFadeSettings,
+    #[serde(skip)]
+    pub fade_duration: Option<f64>, // Length of the audio being faded, probed when the task starts
     
     // Merge
     pub merge_mode: String,
     pub add_silence: bool,
     pub silence_duration: f32,
+    #[serde(default)]
+    pub mix: crate::audio_mix::AudioMixSettings,
     
     // Video/Audio merge
     pub sync_audio: bool,
     pub audio_delay: f32,
+    #[serde(default)]
+    pub merge_shortest: bool, // End the merged file with the shorter of the two streams
     
     // Extract
     pub extract_all_tracks: bool,
 }
 
-#[derive(Clone, Debug)]
+/// Unfinished tasks are saved on exit and offered for restore on the next start (see queue_store);
+/// timing, environment and other run state is not kept
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ProcessingTask {
     pub id: usize,
     pub operation: OperationType,
@@ -238,27 +373,125 @@ pub struct ProcessingTask {
     pub progress: f32,
     pub status: TaskStatus,
     pub error_message: Option<String>,
+    #[serde(skip)]
     pub start_time: Option<std::time::Instant>,
+    /// When the task was paused; on resume start_time moves forward by the pause
+    #[serde(skip)]
+    pub paused_at: Option<std::time::Instant>,
+    /// Running time the task will take, from ffmpeg's reported speed; excludes pauses
+    #[serde(skip)]
     pub estimated_total_time: Option<std::time::Duration>,
+    /// ffmpeg reports progress but the input duration is unknown (live inputs, some GIFs)
+    #[serde(skip)]
+    pub progress_unknown: bool,
+    #[serde(skip)]
     pub completion_time: Option<std::time::Duration>,
+    /// Captured when the task starts running
+    #[serde(skip)]
+    pub environment: Option<crate::environment::EnvironmentSnapshot>,
+    /// Output size against the requested target size, recorded on completion
+    #[serde(skip)]
+    pub size_outcome: Option<crate::size_target::SizeOutcome>,
+    /// argv of every ffmpeg run the task made, in order
+    #[serde(default)]
+    pub executed_commands: Vec<Vec<String>>,
+    /// Name the user gave the task in the task list, shown instead of the output file name
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Set by the UI; the executor stops the task and is the one that marks it Cancelled
+    #[serde(skip)]
+    pub cancel_requested: bool,
+    /// Remove whatever the task wrote once it has stopped after a cancel
+    #[serde(skip)]
+    pub delete_partial_output: bool,
+    /// SHA-256 of the output, computed after the task completes when requested
+    #[serde(default)]
+    pub checksum: crate::checksum::TaskChecksum,
+    /// Starts only when no normal pending task can, and runs ffmpeg at lowered priority
+    #[serde(default)]
+    pub low_priority: bool,
+    /// Archive (.zip or .tar.gz) the output and a manifest are packaged into after the task completes
+    #[serde(default)]
+    pub archive: Option<String>,
+    /// Copy of the source's file times and recording date onto the output after the task completes
+    #[serde(default)]
+    pub timestamps: crate::file_times::TaskTimestamps,
+    /// Maximum output duration and size, and which one stopped the task
+    #[serde(default)]
+    pub guards: crate::output_guard::TaskGuards,
+    /// Recent ffmpeg stderr lines, shown in the task details
+    #[serde(skip)]
+    pub log: crate::task_log::TaskLog,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum TaskStatus {
     Pending,
     Running,
     Completed,
     Failed,
     Cancelled,
+    /// Started, with its ffmpeg process suspended until resumed
+    Paused,
+    /// The output destination went away mid-encode; waits for a restart, resume or redirect
+    NeedsOutputRecovery,
 }
 
+static TASK_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
+
 impl ProcessingTask {
+    /// Running or paused: the task holds the worker and its ffmpeg process exists
+    pub fn is_started(&self) -> bool {
+        matches!(self.status, TaskStatus::Running | TaskStatus::Paused)
+    }
+
+    /// Time spent running so far; pauses are not counted
+    pub fn active_elapsed(&self) -> Option<std::time::Duration> {
+        let start = self.start_time?;
+        Some(self.paused_at.unwrap_or_else(std::time::Instant::now).saturating_duration_since(start))
+    }
+
+    /// Id the next new task gets
+    pub fn next_id() -> usize {
+        TASK_COUNTER.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Make sure new tasks get ids from `next` on, so they do not collide with restored ones
+    pub fn reserve_ids_below(next: usize) {
+        TASK_COUNTER.fetch_max(next, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// A pending copy with a new id: same operation, files, settings and post-completion steps,
+    /// none of the run state
+    pub fn retry(&self) -> Self {
+        let mut retry = Self::new(self.operation.clone(), self.input_files.clone(), self.output_file.clone());
+        retry.video_settings = self.video_settings.clone();
+        if let Some(settings) = retry.video_settings.as_mut() {
+            settings.resume_at = None;
+        }
+        retry.audio_settings = self.audio_settings.clone();
+        retry.label = self.label.clone();
+        retry.low_priority = self.low_priority;
+        retry.archive = self.archive.clone();
+        retry.checksum = crate::checksum::TaskChecksum::new(self.checksum.requested, self.checksum.write_sidecar);
+        retry.timestamps = crate::file_times::TaskTimestamps::new(self.timestamps.copy_times, self.timestamps.write_creation_time);
+        retry.guards = crate::output_guard::TaskGuards::new(self.guards.limits.clone());
+        retry
+    }
+
+    /// Time left: from ffmpeg's speed when reported, else extrapolated from the progress so far
+    pub fn remaining_time(&self) -> Option<std::time::Duration> {
+        let elapsed = self.active_elapsed()?;
+        match self.estimated_total_time {
+            Some(total) => Some(total.saturating_sub(elapsed)),
+            None if self.progress > 0.0 && !self.progress_unknown => Some(elapsed.div_f32(self.progress).saturating_sub(elapsed)),
+            None => None,
+        }
+    }
+
     pub fn new(operation: OperationType, input_files: Vec<String>, output_file: String) -> Self {
-        use std::sync::atomic::{AtomicUsize, Ordering};
-        static TASK_COUNTER: AtomicUsize = AtomicUsize::new(1);
-        
         Self {
-            id: TASK_COUNTER.fetch_add(1, Ordering::Relaxed),
+            id: TASK_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
             operation,
             input_files,
             output_file,
@@ -268,8 +501,22 @@ impl ProcessingTask {
             status: TaskStatus::Pending,
             error_message: None,
             start_time: None,
+            paused_at: None,
             estimated_total_time: None,
+            progress_unknown: false,
             completion_time: None,
+            environment: None,
+            size_outcome: None,
+            executed_commands: Vec::new(),
+            label: None,
+            cancel_requested: false,
+            delete_partial_output: false,
+            checksum: Default::default(),
+            low_priority: false,
+            archive: None,
+            timestamps: Default::default(),
+            guards: Default::default(),
+            log: Default::default(),
         }
     }
 }
@@ -293,15 +540,28 @@ impl Default for VideoSettings {
             // Format conversion
             container_format: "mp4".to_string(),
             copy_video: false,
+            container_options: std::collections::BTreeMap::new(),
+            input_program: None,
+            source_alpha: false,
+            alpha_background: None,
+            keep_output_extension: false,
+            max_threads: 0,
+            preserve_chapters: true,
+            chapter_edits: None,
+            chapter_metadata_file: None,
+            resume_at: None,
             
             // Compression
             crf: 23,
             target_size_mb: 0,
+            two_pass: false,
+            size_target_confirm: false,
             
             // Resolution
             width: None,
             height: None,
             maintain_aspect_ratio: true,
+            frame_fit: None,
             
             // Crop
             crop_top: None,
@@ -323,6 +583,7 @@ impl Default for VideoSettings {
             brightness: 0.0,
             contrast: 1.0,
             saturation: 1.0,
+            filter_stack: Vec::new(),
             
             // Batch processing
             batch_naming_pattern: "{name}_converted".to_string(),
@@ -341,15 +602,23 @@ impl Default for VideoSettings {
             watermark_scale: 1.0,
             watermark_x: 10,
             watermark_y: 10,
+            watermark_text: String::new(),
+            watermark_font_size: default_watermark_font_size(),
+            watermark_font: String::new(),
             
             // Frame extraction settings
             frame_extract_mode: "interval".to_string(),
             frame_interval: 30,
+            frame_interval_seconds: default_frame_interval_seconds(),
             frame_start_time: "00:00:00".to_string(),
             frame_end_time: String::new(),
             frame_format: "png".to_string(),
             frame_quality: 2,
+            frame_png_compression: default_png_compression(),
+            frame_output_dir: String::new(),
+            frame_name_pattern: crate::frame_extract::DEFAULT_NAME_PATTERN.to_string(),
             frame_rate: 1,
+            accurate_color_conversion: true,
             
             // Subtitle style settings
             subtitle_font_family: "Arial".to_string(),
@@ -358,6 +627,8 @@ impl Default for VideoSettings {
             subtitle_outline_color: "black".to_string(),
             subtitle_background_color: "transparent".to_string(),
             subtitle_alignment: "center".to_string(),
+            subtitle_offset_ms: 0,
+            subtitle_stretch: 1.0,
             
             // GIF conversion settings
             gif_fps: 10.0,
@@ -367,6 +638,11 @@ impl Default for VideoSettings {
             gif_dither: "floyd_steinberg".to_string(),
             gif_colors: 256,
             
+            cut_segments: Vec::new(),
+            cut_reencode: false,
+            fps_conversion: crate::fps_conversion::FpsConversionSettings::default(),
+            review_clip: None,
+            
             // Smart encoder preferences
             quality_preset: "Balanced".to_string(),
             speed_priority: false,
@@ -388,6 +664,10 @@ impl Default for AudioSettings {
             // Format conversion
             format: "mp3".to_string(),
             copy_audio: false,
+            keep_output_extension: false,
+            preserve_chapters: true,
+            chapter_edits: None,
+            chapter_metadata_file: None,
             
             // Compression
             vbr_quality: 2,
@@ -399,20 +679,27 @@ impl Default for AudioSettings {
             normalize: false,
             target_lufs: -16.0,
             
+            // Noise reduction
+            denoise: crate::audio_denoise::AudioDenoiseSettings::default(),
+            loudness: crate::loudness::LoudnessTarget::default(),
+            content_type: None,
+            
             // Trim
             start_time: "00:00:00".to_string(),
             end_time: String::new(),
-            fade_in: false,
-            fade_out: false,
+            fade: crate::audio_fade::AudioFadeSettings::default(),
+            fade_duration: None,
             
             // Merge
             merge_mode: "concat".to_string(),
             add_silence: false,
             silence_duration: 1.0,
+            mix: crate::audio_mix::AudioMixSettings::default(),
             
             // Video/Audio merge
             sync_audio: false,
             audio_delay: 0.0,
+            merge_shortest: false,
             
             // Extract
             extract_all_tracks: false,
@@ -455,15 +742,18 @@ impl Default for ProjectConfig {
     }
 }
 
+/// Main-window state a project file stores; undo restores the same set so both follow new fields
+#[derive(Clone, Debug)]
+pub struct SessionSnapshot {
+    pub current_operation: Option<OperationType>,
+    pub input_files: Vec<String>,
+    pub output_file: String,
+    pub video_settings: VideoSettings,
+    pub audio_settings: AudioSettings,
+}
+
 impl ProjectConfig {
-    pub fn from_app_state(
-        current_operation: Option<OperationType>,
-        input_files: Vec<String>,
-        output_file: String,
-        video_settings: VideoSettings,
-        audio_settings: AudioSettings,
-        project_name: Option<String>,
-    ) -> Self {
+    pub fn from_snapshot(snapshot: SessionSnapshot, project_name: Option<String>) -> Self {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -472,16 +762,41 @@ impl ProjectConfig {
         Self {
             version: "1.0.0".to_string(),
             project_name: project_name.unwrap_or_else(|| "Untitled Project".to_string()),
-            current_operation,
-            input_files,
-            output_file,
-            video_settings,
-            audio_settings,
+            current_operation: snapshot.current_operation,
+            input_files: snapshot.input_files,
+            output_file: snapshot.output_file,
+            video_settings: snapshot.video_settings,
+            audio_settings: snapshot.audio_settings,
             created_at: now.clone(),
             modified_at: now,
         }
     }
     
+    /// Task running the project's operation on its inputs, as the main window would queue it
+    pub fn into_task(self) -> Result<ProcessingTask, String> {
+        let operation = self.current_operation.ok_or("the project has no operation selected")?;
+        if self.input_files.is_empty() {
+            return Err("the project has no input files".to_string());
+        }
+        if self.output_file.trim().is_empty() {
+            return Err("the project has no output file".to_string());
+        }
+        let mut task = ProcessingTask::new(operation, self.input_files, self.output_file);
+        task.video_settings = Some(self.video_settings);
+        task.audio_settings = Some(self.audio_settings);
+        Ok(task)
+    }
+    
+    pub fn into_snapshot(self) -> SessionSnapshot {
+        SessionSnapshot {
+            current_operation: self.current_operation,
+            input_files: self.input_files,
+            output_file: self.output_file,
+            video_settings: self.video_settings,
+            audio_settings: self.audio_settings,
+        }
+    }
+    
     pub fn save_to_file(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(file_path, json)?;
@@ -558,12 +873,39 @@ impl ProjectConfig {
                     "profile" => if let Some(s) = val.as_str() { default.profile = s.to_string(); },
                     "tune" => if let Some(s) = val.as_str() { default.tune = s.to_string(); },
                     "container_format" => if let Some(s) = val.as_str() { default.container_format = s.to_string(); },
+                    "container_options" => if let Ok(options) = serde_json::from_value(val.clone()) { default.container_options = options; },
                     "crf" => if let Some(i) = val.as_i64() { default.crf = i as i32; },
                     "target_size_mb" => if let Some(i) = val.as_i64() { default.target_size_mb = i as i32; },
+                    "two_pass" => if let Some(b) = val.as_bool() { default.two_pass = b; },
+                    "size_target_confirm" => if let Some(b) = val.as_bool() { default.size_target_confirm = b; },
+                    "alpha_background" => if let Ok(color) = serde_json::from_value(val.clone()) { default.alpha_background = color; },
+                    "frame_fit" => if let Ok(fit) = serde_json::from_value(val.clone()) { default.frame_fit = fit; },
+                    "keep_output_extension" => if let Some(b) = val.as_bool() { default.keep_output_extension = b; },
+                    "preserve_chapters" => if let Some(b) = val.as_bool() { default.preserve_chapters = b; },
                     "rotation" => if let Some(i) = val.as_i64() { default.rotation = i as i32; },
                     "use_custom_rotation" => if let Some(b) = val.as_bool() { default.use_custom_rotation = b; },
                     "custom_rotation_angle" => if let Some(f) = val.as_f64() { default.custom_rotation_angle = f as f32; },
                     "batch_operation_type" => if let Some(s) = val.as_str() { default.batch_operation_type = s.to_string(); },
+                    "cut_segments" => if let Ok(segments) = serde_json::from_value(val.clone()) { default.cut_segments = segments; },
+                    "filter_stack" => if let Ok(stack) = serde_json::from_value(val.clone()) { default.filter_stack = stack; },
+                    "cut_reencode" => if let Some(b) = val.as_bool() { default.cut_reencode = b; },
+                    "frame_extract_mode" => if let Some(s) = val.as_str() { default.frame_extract_mode = s.to_string(); },
+                    "frame_interval" => if let Some(i) = val.as_i64() { default.frame_interval = i as i32; },
+                    "frame_interval_seconds" => if let Some(f) = val.as_f64() { default.frame_interval_seconds = f as f32; },
+                    "frame_start_time" => if let Some(s) = val.as_str() { default.frame_start_time = s.to_string(); },
+                    "frame_end_time" => if let Some(s) = val.as_str() { default.frame_end_time = s.to_string(); },
+                    "frame_format" => if let Some(s) = val.as_str() { default.frame_format = s.to_string(); },
+                    "frame_quality" => if let Some(i) = val.as_i64() { default.frame_quality = i as i32; },
+                    "frame_png_compression" => if let Some(i) = val.as_i64() { default.frame_png_compression = i as i32; },
+                    "frame_output_dir" => if let Some(s) = val.as_str() { default.frame_output_dir = s.to_string(); },
+                    "frame_name_pattern" => if let Some(s) = val.as_str() { default.frame_name_pattern = s.to_string(); },
+                    "accurate_color_conversion" => if let Some(b) = val.as_bool() { default.accurate_color_conversion = b; },
+                    "subtitle_offset_ms" => if let Some(i) = val.as_i64() { default.subtitle_offset_ms = i; },
+                    "subtitle_stretch" => if let Some(f) = val.as_f64() { default.subtitle_stretch = f; },
+                    "watermark_text" => if let Some(s) = val.as_str() { default.watermark_text = s.to_string(); },
+                    "watermark_font_size" => if let Some(i) = val.as_u64() { default.watermark_font_size = i as u32; },
+                    "watermark_font" => if let Some(s) = val.as_str() { default.watermark_font = s.to_string(); },
+                    "subtitle_font_family" => if let Some(s) = val.as_str() { default.subtitle_font_family = s.to_string(); },
                     _ => {} // Ignore unknown fields for forward compatibility
                 }
             }
@@ -581,10 +923,396 @@ impl ProjectConfig {
                     "volume" => if let Some(f) = val.as_f64() { default.volume = f as f32; },
                     "quality" => if let Some(s) = val.as_str() { default.quality = s.to_string(); },
                     "format" => if let Some(s) = val.as_str() { default.format = s.to_string(); },
+                    "keep_output_extension" => if let Some(b) = val.as_bool() { default.keep_output_extension = b; },
+                    "preserve_chapters" => if let Some(b) = val.as_bool() { default.preserve_chapters = b; },
                     "vbr_quality" => if let Some(i) = val.as_i64() { default.vbr_quality = i as i32; },
+                    "denoise" => if let Ok(denoise) = serde_json::from_value(val.clone()) { default.denoise = denoise; },
+                    "merge_mode" => if let Some(s) = val.as_str() { default.merge_mode = s.to_string(); },
+                    "add_silence" => if let Some(b) = val.as_bool() { default.add_silence = b; },
+                    "silence_duration" => if let Some(f) = val.as_f64() { default.silence_duration = f as f32; },
+                    "mix" => if let Ok(mix) = serde_json::from_value(val.clone()) { default.mix = mix; },
                     _ => {} // Ignore unknown fields for forward compatibility
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Current settings bundle format version; bundles from newer versions are rejected on import
+pub const SETTINGS_BUNDLE_VERSION: u32 = 1;
+
+/// Bundle section with the user preferences, except the watch folder
+pub const BUNDLE_SECTION_PREFERENCES: &str = "preferences";
+/// Bundle section with the default video and audio settings
+pub const BUNDLE_SECTION_ENCODING_DEFAULTS: &str = "encoding_defaults";
+/// Bundle section with the workflow library files
+pub const BUNDLE_SECTION_WORKFLOWS: &str = "workflows";
+/// Bundle section with the saved group templates
+pub const BUNDLE_SECTION_TEMPLATES: &str = "templates";
+/// Bundle section with the watch folder definition and whether it is on
+pub const BUNDLE_SECTION_WATCH_FOLDER: &str = "watch_folder";
+
+/// Application preferences stored in a settings bundle
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UserPreferences {
+    pub language: String,
+    pub dark_mode: bool,
+    #[serde(default)]
+    pub max_tasks_per_destination: usize,
+    /// Tasks the executor runs at once
+    #[serde(default = "default_max_concurrent_tasks")]
+    pub max_concurrent_tasks: usize,
+    /// Keys of encoding advisory rules the user turned off
+    #[serde(default)]
+    pub suppressed_advisories: std::collections::BTreeSet<String>,
+    #[serde(default)]
+    pub encoder_policy: EncoderPolicy,
+    /// Folder new outputs go to; empty means next to the input
+    #[serde(default)]
+    pub default_output_dir: String,
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    /// Open the workflow editor inside the main window instead of a separate viewport
+    #[serde(default)]
+    pub force_embedded_workflow_editor: bool,
+    /// Encoding limits while running on battery
+    #[serde(default)]
+    pub power_policy: crate::power::PowerPolicy,
+    /// Starred output folders and the default folder per operation category
+    #[serde(default)]
+    pub output_favorites: crate::output_favorites::OutputFavorites,
+    /// Default for new tasks: SHA-256 of the output after it completes
+    #[serde(default)]
+    pub checksum_outputs: bool,
+    /// Default for new tasks: write `<output>.sha256` next to the output
+    #[serde(default)]
+    pub checksum_sidecar: bool,
+    /// Default for new tasks: give the output the source's modified and created times
+    #[serde(default)]
+    pub keep_source_times: bool,
+    /// Default for new tasks: write the source's recording date into the output's creation_time
+    #[serde(default)]
+    pub write_creation_time: bool,
+    /// Maximum output duration and size per task
+    #[serde(default)]
+    pub output_guards: crate::output_guard::OutputGuards,
+    /// Operation last picked in the drop chooser, per dropped file combination
+    #[serde(default)]
+    pub drop_choices: std::collections::BTreeMap<String, OperationType>,
+    /// Skip workflow nodes whose outputs from an earlier run are still up to date
+    #[serde(default)]
+    pub incremental_workflow_execution: bool,
+    /// Leave the intermediate files of a stopped workflow run behind for debugging
+    #[serde(default)]
+    pub keep_stopped_workflow_files: bool,
+    /// Options of the last review clip
+    #[serde(default)]
+    pub review_clip: crate::review_clip::ReviewClipSettings,
+    /// Time-of-day throttling profiles
+    #[serde(default)]
+    pub schedule_policy: crate::schedule::SchedulePolicy,
+    /// Leave each task's scratch directory (two-pass logs, segment lists) behind for debugging
+    #[serde(default)]
+    pub keep_scratch_files: bool,
+    /// Give a new task's output a free name instead of replacing an existing file
+    #[serde(default)]
+    pub keep_existing_outputs: bool,
+    /// Size cap of the cache of downloaded remote inputs
+    #[serde(default = "default_download_cache_cap_mb")]
+    pub download_cache_cap_mb: u64,
+    /// Periodically save unsaved workflow changes for recovery after a crash
+    #[serde(default = "default_workflow_autosave")]
+    pub workflow_autosave: bool,
+    #[serde(default = "default_workflow_autosave_secs")]
+    pub workflow_autosave_secs: u64,
+    #[serde(default)]
+    pub watch_folder: crate::watch_folder::WatchFolderConfig,
+    /// Watch the folder from startup on
+    #[serde(default)]
+    pub watch_folder_enabled: bool,
+}
+
+fn default_workflow_autosave() -> bool {
+    true
+}
+
+fn default_workflow_autosave_secs() -> u64 {
+    crate::workflow_autosave::DEFAULT_INTERVAL_SECS
+}
+
+fn default_download_cache_cap_mb() -> u64 {
+    crate::download_cache::DEFAULT_CAP_MB
+}
+
+/// Placeholders: {name} = input file stem, {timestamp} = unique timestamp
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "output_{timestamp}";
+
+fn default_max_concurrent_tasks() -> usize {
+    crate::task_executor::DEFAULT_MAX_CONCURRENT_TASKS
+}
+
+fn default_filename_template() -> String {
+    DEFAULT_FILENAME_TEMPLATE.to_string()
+}
+
+/// How an "auto" video codec is resolved when hardware encoders are available
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum EncoderPolicy {
+    PreferHardware,
+    #[default]
+    PreferQuality,
+    AlwaysAsk,
+}
+
+impl EncoderPolicy {
+    pub fn all() -> [EncoderPolicy; 3] {
+        [EncoderPolicy::PreferHardware, EncoderPolicy::PreferQuality, EncoderPolicy::AlwaysAsk]
+    }
+}
+
+impl UserPreferences {
+    /// Preferences file in the per-user config directory; its absence marks the first run
+    pub fn file_path() -> Option<std::path::PathBuf> {
+        let base = std::env::var_os("APPDATA")
+            .or_else(|| std::env::var_os("XDG_CONFIG_HOME"))
+            .map(std::path::PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".config")))?;
+        Some(base.join("ffmpeg_gui").join("preferences.json"))
+    }
+
+    pub fn load() -> Option<Self> {
+        let json = std::fs::read_to_string(Self::file_path()?).ok()?;
+        match serde_json::from_str(&json) {
+            Ok(preferences) => Some(preferences),
+            Err(e) => {
+                log_warn!("Ignoring unreadable preferences file: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::file_path().ok_or("no user config directory")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Default encoding settings stored in a settings bundle
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct EncodingDefaults {
+    pub video_settings: VideoSettings,
+    pub audio_settings: AudioSettings,
+}
+
+/// Watch folder definition stored in a settings bundle
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WatchFolderSection {
+    pub config: crate::watch_folder::WatchFolderConfig,
+    pub enabled: bool,
+}
+
+/// The `.json` files of a managed folder (workflow library, templates) stored in a settings
+/// bundle, by file name, exactly as they are on disk
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FolderSection {
+    pub files: std::collections::BTreeMap<String, String>,
+}
+
+impl FolderSection {
+    /// Every `.json` file in `dir`; a missing folder gives an empty section
+    pub fn read(dir: &std::path::Path) -> Result<Self, String> {
+        let mut section = Self::default();
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return Ok(section);
+        };
+        for path in read_dir.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if !path.is_file() || !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            section.files.insert(name.to_string(), content);
+        }
+        Ok(section)
+    }
+
+    /// Write the files into `dir`, replacing files of the same name and leaving the others. Every
+    /// file is checked with `check` first, so a section with a bad file writes nothing.
+    pub fn write(&self, dir: &std::path::Path, check: impl Fn(&str) -> Result<(), String>) -> Result<(), String> {
+        for (name, content) in &self.files {
+            let plain_name = std::path::Path::new(name).file_name().is_some_and(|file_name| file_name == name.as_str());
+            if !plain_name || !name.to_lowercase().ends_with(".json") {
+                return Err(format!("'{}' is not a file name", name));
+            }
+            check(content).map_err(|e| format!("{}: {}", name, e))?;
+        }
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        for (name, content) in &self.files {
+            std::fs::write(dir.join(name), content).map_err(|e| format!("{}: {}", name, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings bundle for moving configuration between machines. Each section is kept as raw JSON
+/// so that a broken section can be skipped without affecting the others.
+///
+/// No section needs a restart: preferences and encoding settings take effect on import, a
+/// running watch folder restarts with the imported definition, and imported workflows and
+/// templates are listed the next time the library or the template menu is shown.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SettingsBundle {
+    pub version: u32,
+    pub created_at: String,
+    pub sections: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl SettingsBundle {
+    pub fn new() -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .to_string();
+        Self {
+            version: SETTINGS_BUNDLE_VERSION,
+            created_at: now,
+            sections: std::collections::BTreeMap::new(),
+        }
+    }
+    
+    pub fn add_section<T: serde::Serialize>(&mut self, name: &str, section: &T) -> Result<(), Box<dyn std::error::Error>> {
+        self.sections.insert(name.to_string(), serde_json::to_value(section)?);
+        Ok(())
+    }
+    
+    /// Parse a single section; a failure leaves the caller's current value untouched
+    pub fn get_section<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T, String> {
+        let value = self.sections.get(name)
+            .ok_or_else(|| format!("section '{}' not present", name))?;
+        serde_json::from_value(value.clone()).map_err(|e| e.to_string())
+    }
+    
+    pub fn save_to_file(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(file_path, json)?;
+        Ok(())
+    }
+    
+    pub fn load_from_file(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(file_path)?;
+        let bundle: SettingsBundle = serde_json::from_str(&json)?;
+        if bundle.version > SETTINGS_BUNDLE_VERSION {
+            return Err(format!("unsupported settings bundle version {} (this build supports up to {})",
+                bundle.version, SETTINGS_BUNDLE_VERSION).into());
+        }
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn check_workflow(content: &str) -> Result<(), String> {
+        crate::workflow_file::parse(content).map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn bundle_sections_round_trip() {
+        let dir = temp_dir("bundle_sections");
+        let preferences: UserPreferences = serde_json::from_str(r#"{"language": "zh", "dark_mode": false, "max_concurrent_tasks": 3}"#).unwrap();
+        let video_settings = VideoSettings { crf: 21, ..VideoSettings::default() };
+        let encoding_defaults = EncodingDefaults { video_settings, audio_settings: AudioSettings::default() };
+        let watch_folder = WatchFolderSection {
+            config: crate::watch_folder::WatchFolderConfig {
+                watch_dir: "/media/in".to_string(),
+                output_dir: "/media/out".to_string(),
+                include_glob: "*.mov".to_string(),
+                workflow_path: "/flows/proxy.json".to_string(),
+                debounce_secs: 9,
+            },
+            enabled: true,
+        };
+
+        let mut bundle = SettingsBundle::new();
+        bundle.add_section(BUNDLE_SECTION_PREFERENCES, &preferences).unwrap();
+        bundle.add_section(BUNDLE_SECTION_ENCODING_DEFAULTS, &encoding_defaults).unwrap();
+        bundle.add_section(BUNDLE_SECTION_WATCH_FOLDER, &watch_folder).unwrap();
+        let path = dir.join("settings.ffsettings").display().to_string();
+        bundle.save_to_file(&path).unwrap();
+
+        let loaded = SettingsBundle::load_from_file(&path).unwrap();
+        assert_eq!(loaded.get_section::<UserPreferences>(BUNDLE_SECTION_PREFERENCES).unwrap(), preferences);
+        assert_eq!(loaded.get_section::<WatchFolderSection>(BUNDLE_SECTION_WATCH_FOLDER).unwrap(), watch_folder);
+        let defaults = loaded.get_section::<EncodingDefaults>(BUNDLE_SECTION_ENCODING_DEFAULTS).unwrap();
+        assert_eq!(serde_json::to_value(&defaults).unwrap(), serde_json::to_value(&encoding_defaults).unwrap());
+        assert!(loaded.get_section::<FolderSection>(BUNDLE_SECTION_TEMPLATES).unwrap_err().contains("not present"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn folder_section_reproduces_files_byte_for_byte() {
+        let dir = temp_dir("bundle_folder");
+        let library = dir.join("workflows");
+        std::fs::create_dir_all(&library).unwrap();
+        let workflow = include_str!("../tests/fixtures/workflows/v2.json");
+        std::fs::write(library.join("proxy.json"), workflow).unwrap();
+        std::fs::write(library.join("legacy.json"), include_str!("../tests/fixtures/workflows/v0.json")).unwrap();
+        std::fs::write(library.join("notes.txt"), "not exported").unwrap();
+
+        let mut bundle = SettingsBundle::new();
+        bundle.add_section(BUNDLE_SECTION_WORKFLOWS, &FolderSection::read(&library).unwrap()).unwrap();
+        let path = dir.join("settings.ffsettings").display().to_string();
+        bundle.save_to_file(&path).unwrap();
+
+        // A clean profile has no library folder yet
+        let clean = dir.join("clean_profile").join("workflows");
+        let section: FolderSection = SettingsBundle::load_from_file(&path).unwrap().get_section(BUNDLE_SECTION_WORKFLOWS).unwrap();
+        section.write(&clean, check_workflow).unwrap();
+        let mut names: Vec<String> = std::fs::read_dir(&clean).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["legacy.json", "proxy.json"]);
+        for name in names {
+            assert_eq!(std::fs::read(clean.join(&name)).unwrap(), std::fs::read(library.join(&name)).unwrap(), "{}", name);
+        }
+        assert_eq!(FolderSection::read(&dir.join("missing")).unwrap(), FolderSection::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn section_with_a_bad_file_writes_nothing() {
+        let dir = temp_dir("bundle_bad_file");
+        let target = dir.join("workflows");
+        let mut section = FolderSection::default();
+        section.files.insert("good.json".to_string(), include_str!("../tests/fixtures/workflows/v2.json").to_string());
+        section.files.insert("truncated.json".to_string(), "{\"nodes\": {".to_string());
+        assert!(section.write(&target, check_workflow).unwrap_err().starts_with("truncated.json: "));
+        assert!(!target.exists());
+
+        let mut escaping = FolderSection::default();
+        escaping.files.insert("../outside.json".to_string(), "{}".to_string());
+        assert_eq!(escaping.write(&target, |_| Ok(())).unwrap_err(), "'../outside.json' is not a file name");
+        assert!(!dir.join("outside.json").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn newer_bundles_are_rejected() {
+        let dir = temp_dir("bundle_version");
+        let mut bundle = SettingsBundle::new();
+        bundle.version = SETTINGS_BUNDLE_VERSION + 1;
+        let path = dir.join("settings.ffsettings").display().to_string();
+        bundle.save_to_file(&path).unwrap();
+        let error = SettingsBundle::load_from_file(&path).unwrap_err().to_string();
+        assert!(error.starts_with(&format!("unsupported settings bundle version {}", SETTINGS_BUNDLE_VERSION + 1)), "{}", error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
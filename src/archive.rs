@@ -0,0 +1,1038 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use flate2::read::{DeflateDecoder, GzDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::{Compression, Crc};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::ProcessingTask;
+use crate::checksum::{to_hex, Sha256};
+use crate::language::Translations;
+
+/// Name of the manifest entry, written after the files it describes
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// Bumped whenever a manifest field changes meaning; newer manifests are rejected on extraction
+pub const MANIFEST_VERSION: u32 = 1;
+/// Manifests are read into memory; anything bigger is not one of ours
+const MAX_MANIFEST_BYTES: u64 = 16 << 20;
+/// Bytes copied per step; memory stays flat no matter how large the packaged files are
+const CHUNK_SIZE: usize = 1 << 20;
+/// Entries with these extensions are deflated in zip archives; media is already compressed and stored
+const DEFLATE_EXTENSIONS: &[&str] = &["json", "txt", "srt", "vtt", "ass", "ssa", "csv", "log", "xml", "wav", "y4m", "yuv", "pcm"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+
+    /// Format named by a node parameter ("zip", "tar.gz", "tgz")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "tar.gz" | "tgz" | "targz" => Some(ArchiveFormat::TarGz),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// One packaged file as described in the manifest
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Name inside the archive
+    pub name: String,
+    pub size: u64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Path the file was packaged from
+    #[serde(default)]
+    pub source: Option<String>,
+    /// ffprobe information of media files
+    #[serde(default)]
+    pub probe: Option<serde_json::Value>,
+}
+
+/// JSON description of an archive written by the app
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub created: String,
+    pub generator: String,
+    pub entries: Vec<ManifestEntry>,
+    /// Settings the packaged outputs were produced with
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+}
+
+impl Manifest {
+    pub fn new(entries: Vec<ManifestEntry>, settings: Option<serde_json::Value>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            created: chrono::Local::now().to_rfc3339(),
+            generator: format!("ffmpeg_gui {}", env!("CARGO_PKG_VERSION")),
+            entries,
+            settings,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let manifest: Manifest = serde_json::from_str(json).map_err(|e| format!("Invalid manifest: {}", e))?;
+        if manifest.version > MANIFEST_VERSION {
+            return Err(format!("Manifest version {} is newer than this app supports ({})", manifest.version, MANIFEST_VERSION));
+        }
+        Ok(manifest)
+    }
+}
+
+/// Outcome of an extraction
+#[derive(Clone, Debug, Default)]
+pub struct ExtractReport {
+    pub files: Vec<PathBuf>,
+    pub manifest: Option<Manifest>,
+    /// Files whose SHA-256 matched the manifest
+    pub verified: usize,
+}
+
+/// Reader that hashes what passes through it and reports the byte count
+struct HashingReader<'a, R: Read> {
+    inner: R,
+    hasher: &'a mut Sha256,
+    on_read: &'a mut dyn FnMut(u64),
+}
+
+impl<R: Read> Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        (self.on_read)(read as u64);
+        Ok(read)
+    }
+}
+
+/// Writer that counts and CRCs the bytes written through it
+struct CountingWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn copy_chunked(reader: &mut dyn Read, writer: &mut dyn Write, mut on_chunk: impl FnMut(&[u8])) -> io::Result<u64> {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(total),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        on_chunk(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        total += read as u64;
+    }
+}
+
+/// Archive names for the inputs: plain file names, numbered when two inputs share one
+pub fn entry_names(inputs: &[PathBuf]) -> Vec<String> {
+    let mut used: HashMap<String, usize> = HashMap::new();
+    inputs.iter().map(|input| {
+        let name = input.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| "file".to_string());
+        let count = used.entry(name.to_lowercase()).or_insert(0);
+        *count += 1;
+        if *count == 1 && name != MANIFEST_NAME {
+            return name;
+        }
+        let path = Path::new(&name);
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        match path.extension() {
+            Some(ext) => format!("{}_{}.{}", stem, count, ext.to_string_lossy()),
+            None => format!("{}_{}", stem, count),
+        }
+    }).collect()
+}
+
+/// Package `inputs` and a manifest into `archive_path` (format from its extension). `progress`
+/// receives the bytes of input read so far and the total.
+pub fn create(
+    archive_path: &Path,
+    inputs: &[PathBuf],
+    include_probe: bool,
+    settings: Option<serde_json::Value>,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<Manifest, String> {
+    let format = ArchiveFormat::from_path(archive_path)
+        .ok_or_else(|| format!("{} is not a .zip or .tar.gz file", archive_path.display()))?;
+    if inputs.is_empty() {
+        return Err("Nothing to archive".to_string());
+    }
+    let sizes = inputs.iter()
+        .map(|input| std::fs::metadata(input).map(|metadata| metadata.len()).map_err(|e| format!("{}: {}", input.display(), e)))
+        .collect::<Result<Vec<u64>, String>>()?;
+    let total: u64 = sizes.iter().sum();
+    if let Some(parent) = archive_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(archive_path).map_err(|e| format!("{}: {}", archive_path.display(), e))?;
+    let mut writer = match format {
+        ArchiveFormat::Zip => ArchiveWriter::Zip(ZipWriter::new(BufWriter::new(file))),
+        ArchiveFormat::TarGz => ArchiveWriter::Tar(TarWriter::new(GzEncoder::new(BufWriter::new(file), Compression::default()))),
+    };
+
+    let mut done = 0u64;
+    let mut entries = Vec::new();
+    for ((input, name), size) in inputs.iter().zip(entry_names(inputs)).zip(sizes) {
+        let source = File::open(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+        let mut hasher = Sha256::new();
+        let mut on_read = |read: u64| {
+            done += read;
+            progress(done, total);
+        };
+        let mut reader = HashingReader { inner: source, hasher: &mut hasher, on_read: &mut on_read };
+        writer.add_file(&name, size, &mut reader).map_err(|e| format!("{}: {}", name, e))?;
+        let probe = if include_probe {
+            crate::probe_cache::probe_cache().get_file_info(&input.display().to_string()).ok()
+                .and_then(|info| serde_json::to_value(info).ok())
+        } else {
+            None
+        };
+        entries.push(ManifestEntry {
+            name,
+            size,
+            sha256: Some(to_hex(&hasher.finalize())),
+            source: Some(input.display().to_string()),
+            probe,
+        });
+    }
+
+    let manifest = Manifest::new(entries, settings);
+    let json = manifest.to_json();
+    writer.add_file(MANIFEST_NAME, json.len() as u64, &mut json.as_bytes()).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    log_info!("Archived {} file(s), {} bytes, into {}", manifest.entries.len(), total, archive_path.display());
+    Ok(manifest)
+}
+
+/// Restore the files of an archive into `dest_dir`, keeping their folders; an entry landing on a
+/// path an earlier entry took is numbered. With `verify`, every file listed in the
+/// manifest must be present and match its SHA-256. `progress` receives archive bytes read and the
+/// archive size.
+pub fn extract(archive_path: &Path, dest_dir: &Path, verify: bool, progress: &mut dyn FnMut(u64, u64)) -> Result<ExtractReport, String> {
+    let format = ArchiveFormat::from_path(archive_path)
+        .ok_or_else(|| format!("{} is not a .zip or .tar.gz file", archive_path.display()))?;
+    std::fs::create_dir_all(dest_dir).map_err(|e| e.to_string())?;
+
+    let mut report = ExtractReport::default();
+    let mut hashes: HashMap<String, String> = HashMap::new();
+    let mut visit = |name: &str, reader: &mut dyn Read| -> Result<(), String> {
+        if name == MANIFEST_NAME {
+            let mut json = String::new();
+            reader.take(MAX_MANIFEST_BYTES).read_to_string(&mut json).map_err(|e| e.to_string())?;
+            report.manifest = Some(Manifest::from_json(&json)?);
+            return Ok(());
+        }
+        let Some(relative) = entry_path(name) else {
+            return Ok(());
+        };
+        let path = unused_path(dest_dir.join(relative), &report.files);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        let mut output = BufWriter::new(File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?);
+        let mut hasher = Sha256::new();
+        copy_chunked(reader, &mut output, |chunk| hasher.update(chunk)).map_err(|e| format!("{}: {}", name, e))?;
+        output.flush().map_err(|e| e.to_string())?;
+        hashes.insert(name.to_string(), to_hex(&hasher.finalize()));
+        report.files.push(path);
+        Ok(())
+    };
+    match format {
+        ArchiveFormat::Zip => read_zip(archive_path, progress, &mut visit)?,
+        ArchiveFormat::TarGz => read_tar(archive_path, progress, &mut visit)?,
+    }
+
+    if verify {
+        if let Some(manifest) = &report.manifest {
+            for entry in &manifest.entries {
+                let Some(expected) = &entry.sha256 else {
+                    continue;
+                };
+                match hashes.get(&entry.name) {
+                    Some(actual) if actual.eq_ignore_ascii_case(expected) => report.verified += 1,
+                    Some(_) => return Err(format!("Checksum mismatch for {}", entry.name)),
+                    None => return Err(format!("{} is listed in the manifest but missing from the archive", entry.name)),
+                }
+            }
+        }
+    }
+    log_info!("Extracted {} file(s) from {} ({} verified)", report.files.len(), archive_path.display(), report.verified);
+    Ok(report)
+}
+
+/// Relative path an entry is extracted to: its folders are kept, but root, drive and ".." parts
+/// are dropped so it cannot escape the destination. None for entries naming no file.
+fn entry_path(name: &str) -> Option<PathBuf> {
+    if name.ends_with(['/', '\\']) {
+        return None;
+    }
+    let relative: PathBuf = name.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != "." && *part != ".." && !part.ends_with(':'))
+        .collect();
+    relative.file_name().is_some().then_some(relative)
+}
+
+/// `path`, numbered when an earlier entry of the same archive was already extracted there
+fn unused_path(path: PathBuf, extracted: &[PathBuf]) -> PathBuf {
+    let taken = |candidate: &Path| extracted.iter().any(|file| file.to_string_lossy().eq_ignore_ascii_case(&candidate.to_string_lossy()));
+    if !taken(&path) {
+        return path;
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|count| path.with_file_name(format!("{}_{}{}", stem, count, extension)))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or(path)
+}
+
+enum ArchiveWriter {
+    Zip(ZipWriter),
+    Tar(TarWriter),
+}
+
+impl ArchiveWriter {
+    fn add_file(&mut self, name: &str, size: u64, reader: &mut dyn Read) -> io::Result<()> {
+        match self {
+            ArchiveWriter::Zip(zip) => zip.add_file(name, size, reader),
+            ArchiveWriter::Tar(tar) => tar.add_file(name, size, reader),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            ArchiveWriter::Zip(zip) => zip.finish(),
+            ArchiveWriter::Tar(tar) => tar.finish(),
+        }
+    }
+}
+
+// ---- zip ----
+
+const ZIP_LOCAL_HEADER: u32 = 0x0403_4b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x0201_4b50;
+const ZIP_END: u32 = 0x0605_4b50;
+const ZIP64_END: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+const ZIP64_EXTRA: u16 = 0x0001;
+const ZIP_UTF8_FLAG: u16 = 0x0800;
+const ZIP_STORED: u16 = 0;
+const ZIP_DEFLATED: u16 = 8;
+/// Sizes from here on go in the zip64 extra field; deflate may grow incompressible data a little
+const ZIP64_THRESHOLD: u64 = 0xFFFF_0000;
+
+struct ZipEntry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed: u64,
+    size: u64,
+    offset: u64,
+}
+
+struct ZipWriter {
+    out: BufWriter<File>,
+    offset: u64,
+    entries: Vec<ZipEntry>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// u32 field of a zip record, or the zip64 marker when the value does not fit
+fn zip32(value: u64) -> u32 {
+    if value >= 0xFFFF_FFFF { 0xFFFF_FFFF } else { value as u32 }
+}
+
+impl ZipWriter {
+    fn new(out: BufWriter<File>) -> Self {
+        use chrono::{Datelike, Timelike};
+        let now = chrono::Local::now();
+        Self {
+            out,
+            offset: 0,
+            entries: Vec::new(),
+            dos_time: ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16,
+            dos_date: (((now.year().clamp(1980, 2107) - 1980) as u32) << 9 | (now.month() << 5) | now.day()) as u16,
+        }
+    }
+
+    /// Local header with placeholder CRC and sizes, the data, then the header patched in place
+    fn add_file(&mut self, name: &str, size: u64, reader: &mut dyn Read) -> io::Result<()> {
+        let extension = Path::new(name).extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let method = if DEFLATE_EXTENSIONS.contains(&extension.as_str()) { ZIP_DEFLATED } else { ZIP_STORED };
+        let zip64 = size >= ZIP64_THRESHOLD;
+        let offset = self.offset;
+
+        let mut header = Vec::with_capacity(30 + name.len() + 20);
+        put_u32(&mut header, ZIP_LOCAL_HEADER);
+        put_u16(&mut header, if zip64 { 45 } else { 20 });
+        put_u16(&mut header, ZIP_UTF8_FLAG);
+        put_u16(&mut header, method);
+        put_u16(&mut header, self.dos_time);
+        put_u16(&mut header, self.dos_date);
+        put_u32(&mut header, 0);
+        put_u32(&mut header, if zip64 { 0xFFFF_FFFF } else { 0 });
+        put_u32(&mut header, if zip64 { 0xFFFF_FFFF } else { 0 });
+        put_u16(&mut header, name.len() as u16);
+        put_u16(&mut header, if zip64 { 20 } else { 0 });
+        header.extend_from_slice(name.as_bytes());
+        if zip64 {
+            put_u16(&mut header, ZIP64_EXTRA);
+            put_u16(&mut header, 16);
+            put_u64(&mut header, 0);
+            put_u64(&mut header, 0);
+        }
+        self.out.write_all(&header)?;
+
+        let mut crc = Crc::new();
+        let (written, compressed) = if method == ZIP_DEFLATED {
+            let mut encoder = DeflateEncoder::new(CountingWriter { inner: &mut self.out, written: 0 }, Compression::default());
+            let written = copy_chunked(reader, &mut encoder, |chunk| crc.update(chunk))?;
+            let counter = encoder.finish()?;
+            (written, counter.written)
+        } else {
+            let written = copy_chunked(reader, &mut self.out, |chunk| crc.update(chunk))?;
+            (written, written)
+        };
+        if !zip64 && (written >= 0xFFFF_FFFF || compressed >= 0xFFFF_FFFF) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "file grew past the size it was packaged for"));
+        }
+
+        let end = offset + header.len() as u64 + compressed;
+        self.out.seek(SeekFrom::Start(offset + 14))?;
+        self.out.write_all(&crc.sum().to_le_bytes())?;
+        if zip64 {
+            self.out.seek(SeekFrom::Start(offset + 30 + name.len() as u64 + 4))?;
+            self.out.write_all(&written.to_le_bytes())?;
+            self.out.write_all(&compressed.to_le_bytes())?;
+        } else {
+            self.out.write_all(&(compressed as u32).to_le_bytes())?;
+            self.out.write_all(&(written as u32).to_le_bytes())?;
+        }
+        self.out.seek(SeekFrom::Start(end))?;
+        self.offset = end;
+        self.entries.push(ZipEntry { name: name.to_string(), method, crc: crc.sum(), compressed, size: written, offset });
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        let directory_offset = self.offset;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            let mut extra = Vec::new();
+            if entry.size >= 0xFFFF_FFFF {
+                put_u64(&mut extra, entry.size);
+            }
+            if entry.compressed >= 0xFFFF_FFFF {
+                put_u64(&mut extra, entry.compressed);
+            }
+            if entry.offset >= 0xFFFF_FFFF {
+                put_u64(&mut extra, entry.offset);
+            }
+            let zip64 = !extra.is_empty();
+            put_u32(&mut directory, ZIP_CENTRAL_HEADER);
+            put_u16(&mut directory, 45);
+            put_u16(&mut directory, if zip64 { 45 } else { 20 });
+            put_u16(&mut directory, ZIP_UTF8_FLAG);
+            put_u16(&mut directory, entry.method);
+            put_u16(&mut directory, self.dos_time);
+            put_u16(&mut directory, self.dos_date);
+            put_u32(&mut directory, entry.crc);
+            put_u32(&mut directory, zip32(entry.compressed));
+            put_u32(&mut directory, zip32(entry.size));
+            put_u16(&mut directory, entry.name.len() as u16);
+            put_u16(&mut directory, if zip64 { extra.len() as u16 + 4 } else { 0 });
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, 0);
+            put_u32(&mut directory, 0);
+            put_u32(&mut directory, zip32(entry.offset));
+            directory.extend_from_slice(entry.name.as_bytes());
+            if zip64 {
+                put_u16(&mut directory, ZIP64_EXTRA);
+                put_u16(&mut directory, extra.len() as u16);
+                directory.extend_from_slice(&extra);
+            }
+        }
+        let directory_size = directory.len() as u64;
+        let count = self.entries.len() as u64;
+
+        if count >= 0xFFFF || directory_offset >= 0xFFFF_FFFF || directory_size >= 0xFFFF_FFFF {
+            let zip64_end_offset = directory_offset + directory_size;
+            put_u32(&mut directory, ZIP64_END);
+            put_u64(&mut directory, 44);
+            put_u16(&mut directory, 45);
+            put_u16(&mut directory, 45);
+            put_u32(&mut directory, 0);
+            put_u32(&mut directory, 0);
+            put_u64(&mut directory, count);
+            put_u64(&mut directory, count);
+            put_u64(&mut directory, directory_size);
+            put_u64(&mut directory, directory_offset);
+            put_u32(&mut directory, ZIP64_LOCATOR);
+            put_u32(&mut directory, 0);
+            put_u64(&mut directory, zip64_end_offset);
+            put_u32(&mut directory, 1);
+        }
+        put_u32(&mut directory, ZIP_END);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, count.min(0xFFFF) as u16);
+        put_u16(&mut directory, count.min(0xFFFF) as u16);
+        put_u32(&mut directory, zip32(directory_size));
+        put_u32(&mut directory, zip32(directory_offset));
+        put_u16(&mut directory, 0);
+        self.out.write_all(&directory)?;
+        self.out.flush()
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn invalid(message: &str) -> String {
+    format!("Not a valid archive: {}", message)
+}
+
+/// Walk the central directory and hand each entry's decompressed data to `visit`
+fn read_zip(path: &Path, progress: &mut dyn FnMut(u64, u64), visit: &mut dyn FnMut(&str, &mut dyn Read) -> Result<(), String>) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let length = file.metadata().map_err(|e| e.to_string())?.len();
+
+    // End of central directory record, possibly followed by a comment of up to 64 KiB
+    let tail_length = length.min(22 + 0xFFFF);
+    let mut tail = vec![0u8; tail_length as usize];
+    file.seek(SeekFrom::Start(length - tail_length)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut tail).map_err(|e| e.to_string())?;
+    let end = (0..tail.len().saturating_sub(21)).rev()
+        .find(|&at| read_u32(&tail, at) == ZIP_END)
+        .ok_or_else(|| invalid("no end of central directory"))?;
+    let mut count = read_u16(&tail, end + 10) as u64;
+    let mut directory_size = read_u32(&tail, end + 12) as u64;
+    let mut directory_offset = read_u32(&tail, end + 16) as u64;
+    if end >= 20 && read_u32(&tail, end - 20) == ZIP64_LOCATOR {
+        let zip64_end_offset = read_u64(&tail, end - 20 + 8);
+        if zip64_end_offset.saturating_add(56) > length {
+            return Err(invalid("bad zip64 end record"));
+        }
+        let mut record = [0u8; 56];
+        file.seek(SeekFrom::Start(zip64_end_offset)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut record).map_err(|e| e.to_string())?;
+        if read_u32(&record, 0) != ZIP64_END {
+            return Err(invalid("bad zip64 end record"));
+        }
+        count = read_u64(&record, 32);
+        directory_size = read_u64(&record, 40);
+        directory_offset = read_u64(&record, 48);
+    }
+
+    // The sizes come from the file, so they are checked against it before anything is read;
+    // every central directory entry takes at least 46 bytes
+    let directory_end = directory_offset.checked_add(directory_size).filter(|end| *end <= length);
+    if directory_end.is_none() || count.saturating_mul(46) > directory_size {
+        return Err(invalid("central directory outside the file"));
+    }
+    let mut directory = Vec::new();
+    file.seek(SeekFrom::Start(directory_offset)).map_err(|e| e.to_string())?;
+    (&mut file).take(directory_size).read_to_end(&mut directory).map_err(|e| e.to_string())?;
+    if directory.len() as u64 != directory_size {
+        return Err(invalid("truncated central directory"));
+    }
+    let mut entries = Vec::new();
+    let mut at = 0usize;
+    for _ in 0..count {
+        if at + 46 > directory.len() || read_u32(&directory, at) != ZIP_CENTRAL_HEADER {
+            return Err(invalid("bad central directory entry"));
+        }
+        let method = read_u16(&directory, at + 10);
+        let crc = read_u32(&directory, at + 16);
+        let mut compressed = read_u32(&directory, at + 20) as u64;
+        let mut size = read_u32(&directory, at + 24) as u64;
+        let name_length = read_u16(&directory, at + 28) as usize;
+        let extra_length = read_u16(&directory, at + 30) as usize;
+        let comment_length = read_u16(&directory, at + 32) as usize;
+        let mut offset = read_u32(&directory, at + 42) as u64;
+        let name_end = at + 46 + name_length;
+        if name_end + extra_length > directory.len() {
+            return Err(invalid("truncated central directory"));
+        }
+        let name = String::from_utf8_lossy(&directory[at + 46..name_end]).to_string();
+
+        // zip64 extra: only the fields whose 32-bit value is the marker, in this order
+        let mut extra = &directory[name_end..name_end + extra_length];
+        while extra.len() >= 4 {
+            let id = read_u16(extra, 0);
+            let length = (read_u16(extra, 2) as usize).min(extra.len() - 4);
+            if id == ZIP64_EXTRA {
+                let mut fields = extra[4..4 + length].chunks_exact(8).map(|field| read_u64(field, 0));
+                if size == 0xFFFF_FFFF {
+                    size = fields.next().unwrap_or(size);
+                }
+                if compressed == 0xFFFF_FFFF {
+                    compressed = fields.next().unwrap_or(compressed);
+                }
+                if offset == 0xFFFF_FFFF {
+                    offset = fields.next().unwrap_or(offset);
+                }
+            }
+            extra = &extra[4 + length..];
+        }
+        entries.push(ZipEntry { name, method, crc, compressed, size, offset });
+        at = name_end + extra_length + comment_length;
+    }
+
+    let mut done = 0u64;
+    for entry in entries {
+        if entry.name.ends_with('/') {
+            continue;
+        }
+        let mut header = [0u8; 30];
+        file.seek(SeekFrom::Start(entry.offset)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut header).map_err(|e| e.to_string())?;
+        if read_u32(&header, 0) != ZIP_LOCAL_HEADER {
+            return Err(invalid("bad local header"));
+        }
+        let data_start = entry.offset + 30 + read_u16(&header, 26) as u64 + read_u16(&header, 28) as u64;
+        file.seek(SeekFrom::Start(data_start)).map_err(|e| e.to_string())?;
+
+        let data = BufReader::new((&mut file).take(entry.compressed));
+        let mut data: Box<dyn Read + '_> = match entry.method {
+            ZIP_STORED => Box::new(data),
+            ZIP_DEFLATED => Box::new(DeflateDecoder::new(data)),
+            method => return Err(format!("{}: unsupported compression method {}", entry.name, method)),
+        };
+        let mut crc = Crc::new();
+        let mut checked = CrcReader { inner: &mut data, crc: &mut crc };
+        visit(&entry.name, &mut checked)?;
+        // Drain whatever the visitor left so the CRC covers the whole entry
+        io::copy(&mut checked, &mut io::sink()).map_err(|e| e.to_string())?;
+        if crc.sum() != entry.crc || crc.amount() as u64 != entry.size & 0xFFFF_FFFF {
+            return Err(format!("{}: CRC mismatch, the archive is damaged", entry.name));
+        }
+        done += entry.compressed;
+        progress(done, length);
+    }
+    Ok(())
+}
+
+struct CrcReader<'a, R: Read + ?Sized> {
+    inner: &'a mut R,
+    crc: &'a mut Crc,
+}
+
+impl<R: Read + ?Sized> Read for CrcReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.crc.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+// ---- tar.gz ----
+
+const TAR_BLOCK: usize = 512;
+/// Largest size the 11 octal digits of a ustar header hold
+const TAR_MAX_OCTAL_SIZE: u64 = 0o77777777777;
+
+struct TarWriter {
+    out: GzEncoder<BufWriter<File>>,
+}
+
+fn tar_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let text = format!("{:0width$o}", value, width = digits);
+    field[..digits].copy_from_slice(&text.as_bytes()[text.len() - digits..]);
+    field[digits] = 0;
+}
+
+fn tar_header(name: &str, size: u64, kind: u8) -> [u8; TAR_BLOCK] {
+    let mut header = [0u8; TAR_BLOCK];
+    let name_bytes = name.as_bytes();
+    let name_length = name_bytes.len().min(100);
+    header[..name_length].copy_from_slice(&name_bytes[..name_length]);
+    tar_octal(&mut header[100..108], 0o644);
+    tar_octal(&mut header[108..116], 0);
+    tar_octal(&mut header[116..124], 0);
+    tar_octal(&mut header[124..136], size.min(TAR_MAX_OCTAL_SIZE));
+    tar_octal(&mut header[136..148], chrono::Utc::now().timestamp().max(0) as u64);
+    header[156] = kind;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    // The checksum is computed with its own field read as spaces
+    header[148..156].fill(b' ');
+    let checksum: u64 = header.iter().map(|byte| *byte as u64).sum();
+    tar_octal(&mut header[148..155], checksum);
+    header[155] = b' ';
+    header
+}
+
+/// One "<length> key=value\n" PAX record; the length counts itself
+fn pax_record(key: &str, value: &str) -> String {
+    let body = format!(" {}={}\n", key, value);
+    let mut length = body.len() + 1;
+    while length.to_string().len() + body.len() != length {
+        length += 1;
+    }
+    format!("{}{}", length, body)
+}
+
+impl TarWriter {
+    fn new(out: GzEncoder<BufWriter<File>>) -> Self {
+        Self { out }
+    }
+
+    fn pad(&mut self, written: u64) -> io::Result<()> {
+        let padding = (TAR_BLOCK - (written % TAR_BLOCK as u64) as usize) % TAR_BLOCK;
+        self.out.write_all(&vec![0u8; padding])
+    }
+
+    /// ustar header, preceded by a PAX header for names over 100 bytes and sizes over 8 GiB
+    fn add_file(&mut self, name: &str, size: u64, reader: &mut dyn Read) -> io::Result<()> {
+        let mut records = String::new();
+        if name.len() > 100 || !name.is_ascii() {
+            records.push_str(&pax_record("path", name));
+        }
+        if size > TAR_MAX_OCTAL_SIZE {
+            records.push_str(&pax_record("size", &size.to_string()));
+        }
+        if !records.is_empty() {
+            self.out.write_all(&tar_header("PaxHeader", records.len() as u64, b'x'))?;
+            self.out.write_all(records.as_bytes())?;
+            self.pad(records.len() as u64)?;
+        }
+        self.out.write_all(&tar_header(name, size, b'0'))?;
+        let written = copy_chunked(&mut reader.take(size), &mut self.out, |_| {})?;
+        if written != size {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "file shrank while it was packaged"));
+        }
+        self.pad(written)
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&[0u8; TAR_BLOCK * 2])?;
+        self.out.finish()?.flush()
+    }
+}
+
+/// Reader that counts the compressed bytes taken from the archive file
+struct ProgressReader<'a, R: Read> {
+    inner: R,
+    read: u64,
+    total: u64,
+    progress: &'a mut dyn FnMut(u64, u64),
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read as u64;
+        (self.progress)(self.read, self.total);
+        Ok(read)
+    }
+}
+
+fn tar_number(field: &[u8]) -> u64 {
+    // GNU base-256 for values that do not fit in octal
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return field[1..].iter().fold(0u64, |value, byte| (value << 8) | *byte as u64);
+    }
+    let text = String::from_utf8_lossy(field);
+    u64::from_str_radix(text.trim_matches(|c: char| c == '\0' || c == ' '), 8).unwrap_or(0)
+}
+
+fn tar_string(field: &[u8]) -> String {
+    let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+fn read_tar(path: &Path, progress: &mut dyn FnMut(u64, u64), visit: &mut dyn FnMut(&str, &mut dyn Read) -> Result<(), String>) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let total = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut archive = GzDecoder::new(BufReader::new(ProgressReader { inner: file, read: 0, total, progress }));
+    let mut pax_path: Option<String> = None;
+    let mut pax_size: Option<u64> = None;
+    let mut header = [0u8; TAR_BLOCK];
+    loop {
+        match archive.read_exact(&mut header) {
+            Ok(()) => {}
+            // Some writers stop after the last entry without the two end blocks
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        }
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(());
+        }
+        let size = pax_size.take().unwrap_or_else(|| tar_number(&header[124..136]));
+        let padding = (TAR_BLOCK as u64 - size % TAR_BLOCK as u64) % TAR_BLOCK as u64;
+        let kind = header[156];
+        match kind {
+            b'x' => {
+                let mut records = String::new();
+                (&mut archive).take(size).read_to_string(&mut records).map_err(|e| e.to_string())?;
+                for record in records.lines() {
+                    let Some((_, entry)) = record.split_once(' ') else {
+                        continue;
+                    };
+                    match entry.split_once('=') {
+                        Some(("path", value)) => pax_path = Some(value.to_string()),
+                        Some(("size", value)) => pax_size = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            b'0' | 0 => {
+                let name = pax_path.take().unwrap_or_else(|| {
+                    let name = tar_string(&header[..100]);
+                    let prefix = tar_string(&header[345..500]);
+                    if &header[257..262] == b"ustar" && !prefix.is_empty() { format!("{}/{}", prefix, name) } else { name }
+                });
+                let mut data = (&mut archive).take(size);
+                visit(&name, &mut data)?;
+                io::copy(&mut data, &mut io::sink()).map_err(|e| e.to_string())?;
+            }
+            _ => {
+                pax_path = None;
+                io::copy(&mut (&mut archive).take(size), &mut io::sink()).map_err(|e| e.to_string())?;
+            }
+        }
+        if kind != b'x' {
+            pax_size = None;
+        }
+        io::copy(&mut (&mut archive).take(padding), &mut io::sink()).map_err(|e| e.to_string())?;
+    }
+}
+
+// ---- post-completion task option ----
+
+lazy_static::lazy_static! {
+    /// Bytes packaged so far and total of the archive each task is writing, by task id
+    static ref ACTIVE: Mutex<HashMap<usize, (u64, u64)>> = Mutex::new(HashMap::new());
+}
+
+/// Package a finished task's output with a manifest carrying the task's settings
+pub fn package_task(task: &ProcessingTask, archive_path: &str) -> Result<Manifest, String> {
+    let settings = serde_json::json!({
+        "operation": task.operation,
+        "inputs": task.input_files,
+        "video_settings": task.video_settings,
+        "audio_settings": task.audio_settings,
+    });
+    let task_id = task.id;
+    let mut progress = |done: u64, total: u64| {
+        ACTIVE.lock().unwrap().insert(task_id, (done, total));
+    };
+    let result = create(Path::new(archive_path), &[PathBuf::from(&task.output_file)], true, Some(settings), &mut progress);
+    ACTIVE.lock().unwrap().remove(&task_id);
+    result
+}
+
+/// Progress bar fraction and text while a task's output is being archived
+pub fn row_status(task_id: usize, translations: &Translations) -> Option<(f32, String)> {
+    let (done, total) = *ACTIVE.lock().unwrap().get(&task_id)?;
+    let fraction = if total > 0 { done as f32 / total as f32 } else { 0.0 };
+    Some((fraction, format!("{} {:.0}%", translations.archive_packaging(), fraction * 100.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn write_tar(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = File::create(path).unwrap();
+        let mut tar = TarWriter::new(GzEncoder::new(BufWriter::new(file), Compression::default()));
+        for (name, data) in entries {
+            tar.add_file(name, data.len() as u64, &mut &data[..]).unwrap();
+        }
+        tar.finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = ZipWriter::new(BufWriter::new(File::create(path).unwrap()));
+        for (name, data) in entries {
+            zip.add_file(name, data.len() as u64, &mut &data[..]).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn manifest_json_round_trip() {
+        let manifest = Manifest::new(vec![ManifestEntry {
+            name: "clip.mp4".to_string(),
+            size: 42,
+            sha256: Some("ab".repeat(32)),
+            source: Some("/videos/clip.mp4".to_string()),
+            probe: Some(serde_json::json!({ "duration": 1.5 })),
+        }], Some(serde_json::json!({ "operation": "Convert" })));
+        assert_eq!(Manifest::from_json(&manifest.to_json()).unwrap(), manifest);
+
+        let newer = manifest.to_json().replace("\"version\": 1", &format!("\"version\": {}", MANIFEST_VERSION + 1));
+        assert!(Manifest::from_json(&newer).unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn create_and_extract_round_trip() {
+        for format in [ArchiveFormat::Zip, ArchiveFormat::TarGz] {
+            let dir = temp_dir("archive_round_trip");
+            std::fs::create_dir_all(dir.join("a")).unwrap();
+            std::fs::create_dir_all(dir.join("b")).unwrap();
+            let inputs = vec![dir.join("a/notes.txt"), dir.join("b/notes.txt"), dir.join("a/video.bin")];
+            let contents: Vec<Vec<u8>> = vec![
+                b"first notes\n".repeat(1000),
+                b"second notes\n".to_vec(),
+                (0..200_000u32).map(|i| (i * 7 % 251) as u8).collect(),
+            ];
+            for (input, content) in inputs.iter().zip(&contents) {
+                std::fs::write(input, content).unwrap();
+            }
+
+            let archive = dir.join(format!("package.{}", format.extension()));
+            let mut last = (0, 0);
+            let manifest = create(&archive, &inputs, false, Some(serde_json::json!({ "crf": 23 })), &mut |done, total| last = (done, total)).unwrap();
+            let total: u64 = contents.iter().map(|content| content.len() as u64).sum();
+            assert_eq!(last, (total, total));
+            let names: Vec<&str> = manifest.entries.iter().map(|entry| entry.name.as_str()).collect();
+            assert_eq!(names, ["notes.txt", "notes_2.txt", "video.bin"]);
+
+            let out = dir.join("out");
+            let report = extract(&archive, &out, true, &mut |_, _| {}).unwrap();
+            assert_eq!(report.manifest.as_ref(), Some(&manifest));
+            assert_eq!(report.verified, 3);
+            for (entry, content) in manifest.entries.iter().zip(&contents) {
+                assert_eq!(&std::fs::read(out.join(&entry.name)).unwrap(), content, "{:?} {}", format, entry.name);
+            }
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn entries_with_the_same_file_name_do_not_overwrite_each_other() {
+        let dir = temp_dir("archive_same_name");
+        let entries: [(&str, &[u8]); 3] = [("day1/clip.txt", b"one"), ("day2/clip.txt", b"two"), ("day1/clip.txt", b"three")];
+        for archive in [dir.join("a.zip"), dir.join("a.tar.gz")] {
+            match ArchiveFormat::from_path(&archive).unwrap() {
+                ArchiveFormat::Zip => write_zip(&archive, &entries),
+                ArchiveFormat::TarGz => write_tar(&archive, &entries),
+            }
+            let out = dir.join(format!("out_{}", archive.file_name().unwrap().to_string_lossy()));
+            let report = extract(&archive, &out, false, &mut |_, _| {}).unwrap();
+            assert_eq!(report.files, vec![out.join("day1/clip.txt"), out.join("day2/clip.txt"), out.join("day1/clip_2.txt")]);
+            assert_eq!(std::fs::read(out.join("day1/clip.txt")).unwrap(), b"one");
+            assert_eq!(std::fs::read(out.join("day2/clip.txt")).unwrap(), b"two");
+            assert_eq!(std::fs::read(out.join("day1/clip_2.txt")).unwrap(), b"three");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entry_paths_stay_inside_the_destination() {
+        assert_eq!(entry_path("../../etc/passwd"), Some(PathBuf::from("etc/passwd")));
+        assert_eq!(entry_path("/abs/./clip.mp4"), Some(PathBuf::from("abs/clip.mp4")));
+        assert_eq!(entry_path("C:\\Users\\me\\clip.mp4"), Some(PathBuf::from("Users/me/clip.mp4")));
+        assert_eq!(entry_path("folder/"), None);
+        assert_eq!(entry_path("../"), None);
+    }
+
+    #[test]
+    fn checksum_mismatch_fails_verification() {
+        let dir = temp_dir("archive_mismatch");
+        let mut hasher = Sha256::new();
+        hasher.update(b"other");
+        let manifest = Manifest::new(vec![ManifestEntry {
+            name: "clip.txt".to_string(),
+            size: 5,
+            sha256: Some(to_hex(&hasher.finalize())),
+            source: None,
+            probe: None,
+        }], None);
+        let json = manifest.to_json();
+        let archive = dir.join("bad.tar.gz");
+        write_tar(&archive, &[("clip.txt", b"hello"), (MANIFEST_NAME, json.as_bytes())]);
+        assert_eq!(extract(&archive, &dir.join("out"), true, &mut |_, _| {}).unwrap_err(), "Checksum mismatch for clip.txt");
+        // Without verification the files are still restored
+        assert_eq!(extract(&archive, &dir.join("out2"), false, &mut |_, _| {}).unwrap().files.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn oversized_central_directory_is_rejected() {
+        let dir = temp_dir("archive_directory_size");
+        let archive = dir.join("bad.zip");
+        write_zip(&archive, &[("clip.txt", b"hello")]);
+        let mut bytes = std::fs::read(&archive).unwrap();
+        let end = bytes.len() - 22;
+        assert_eq!(read_u32(&bytes, end), ZIP_END);
+        bytes[end + 12..end + 16].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        std::fs::write(&archive, &bytes).unwrap();
+        let error = extract(&archive, &dir.join("out"), false, &mut |_, _| {}).unwrap_err();
+        assert_eq!(error, invalid("central directory outside the file"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
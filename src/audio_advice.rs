@@ -0,0 +1,279 @@
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+/// Seconds of audio the voice/music heuristic looks at
+const CONTENT_PROBE_SECONDS: &str = "30";
+/// A bitrate this much above the top of the recommended range is wasted
+const ABOVE_RANGE: f64 = 1.25;
+
+/// What the audio mostly contains, guessed from its spectrum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    Voice,
+    Music,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelLayout {
+    Mono,
+    Stereo,
+    /// 3 to 6 channels, budgeted as 5.1
+    Surround51,
+    /// 7 or more channels, budgeted as 7.1
+    Surround71,
+}
+
+impl ChannelLayout {
+    pub fn from_channels(channels: u32) -> Self {
+        match channels {
+            0 | 1 => ChannelLayout::Mono,
+            2 => ChannelLayout::Stereo,
+            3..=6 => ChannelLayout::Surround51,
+            _ => ChannelLayout::Surround71,
+        }
+    }
+}
+
+/// Averages of ffmpeg's aspectralstats over the probed part, in Hz
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralSummary {
+    pub centroid_hz: f64,
+    pub rolloff_hz: f64,
+}
+
+/// Speech keeps most of its energy below ~4 kHz while music spreads well above it.
+/// None when the spectrum is in between.
+pub fn classify(summary: &SpectralSummary) -> Option<ContentType> {
+    if summary.rolloff_hz < 4500.0 && summary.centroid_hz < 2000.0 {
+        Some(ContentType::Voice)
+    } else if summary.rolloff_hz > 6500.0 || summary.centroid_hz > 2500.0 {
+        Some(ContentType::Music)
+    } else {
+        None
+    }
+}
+
+/// Average centroid and rolloff of the first channel from `ametadata=mode=print` output
+pub fn parse_spectral_stats(output: &str) -> Option<SpectralSummary> {
+    let average = |key: &str| -> Option<f64> {
+        let values: Vec<f64> = output.lines()
+            .filter_map(|line| line.trim().strip_prefix(key))
+            .filter_map(|value| value.trim().parse::<f64>().ok())
+            .filter(|value| value.is_finite())
+            .collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    Some(SpectralSummary {
+        centroid_hz: average("lavfi.aspectralstats.1.centroid=")?,
+        rolloff_hz: average("lavfi.aspectralstats.1.rolloff=")?,
+    })
+}
+
+/// Guess voice or music from the spectrum of the first audio stream; None if the probe fails or is undecided
+pub fn probe_content(input_file: &str) -> Option<ContentType> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    let output = ffmpeg.command()
+        .args(["-hide_banner", "-nostats", "-v", "error", "-t", CONTENT_PROBE_SECONDS, "-i", input_file,
+            "-map", "0:a:0", "-af", "aspectralstats,ametadata=mode=print:file=-", "-f", "null", "-"])
+        .output()
+        .ok()?;
+    let summary = parse_spectral_stats(&String::from_utf8_lossy(&output.stdout))?;
+    log_debug!("Audio spectrum of {}: centroid {:.0} Hz, rolloff {:.0} Hz", input_file, summary.centroid_hz, summary.rolloff_hz);
+    classify(&summary)
+}
+
+/// Recommended bitrate range in kbps for a codec family (see encode_advisor::codec_family).
+/// Unknown content uses the music range. None for lossless and unknown codecs.
+pub fn recommended_kbps(codec_family: &str, layout: ChannelLayout, content: Option<ContentType>) -> Option<(u32, u32)> {
+    use ChannelLayout::*;
+    let voice = content == Some(ContentType::Voice);
+    let range = match (codec_family, layout) {
+        ("aac", Mono) => if voice { (64, 96) } else { (96, 128) },
+        ("aac", Stereo) => if voice { (96, 128) } else { (192, 256) },
+        ("aac", Surround51) => if voice { (256, 384) } else { (384, 512) },
+        ("aac", Surround71) => if voice { (384, 512) } else { (512, 768) },
+        ("opus", Mono) => if voice { (48, 64) } else { (64, 96) },
+        ("opus", Stereo) => if voice { (64, 96) } else { (128, 160) },
+        ("opus", Surround51) => if voice { (192, 256) } else { (256, 384) },
+        ("opus", Surround71) => if voice { (256, 384) } else { (384, 512) },
+        ("vorbis", Mono) => if voice { (64, 80) } else { (96, 128) },
+        ("vorbis", Stereo) => if voice { (96, 128) } else { (160, 224) },
+        ("vorbis", Surround51) => if voice { (256, 320) } else { (320, 448) },
+        ("vorbis", Surround71) => if voice { (320, 448) } else { (448, 640) },
+        // MP3 has no surround; the encoder downmixes
+        ("mp3", Mono) => if voice { (64, 96) } else { (128, 160) },
+        ("mp3", _) => if voice { (96, 128) } else { (192, 320) },
+        ("ac3" | "eac3", Mono) => (96, 128),
+        ("ac3" | "eac3", Stereo) => (192, 256),
+        ("ac3", _) => (384, 640),
+        ("eac3", Surround51) => (384, 640),
+        ("eac3", Surround71) => (640, 1024),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// Lowest sane bitrate for the layout whatever the content: two thirds of the voice range's low end
+pub fn floor_kbps(codec_family: &str, layout: ChannelLayout) -> Option<u32> {
+    recommended_kbps(codec_family, layout, Some(ContentType::Voice)).map(|(low, _)| low * 2 / 3)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdviceKind {
+    /// Configured bitrate below the sanity floor for the channel layout
+    BelowFloor,
+    /// Configured bitrate well above the recommended range
+    AboveRange,
+    /// "auto" leaves surround audio at the encoder's stereo-sized default
+    AutoSurround,
+}
+
+/// Bitrate advice for one audio output
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioAdvice {
+    pub kind: AdviceKind,
+    pub codec_family: &'static str,
+    pub channels: u32,
+    pub content: Option<ContentType>,
+    /// Configured bitrate; None for "auto"
+    pub target_kbps: Option<u64>,
+    pub recommended: (u32, u32),
+}
+
+impl AudioAdvice {
+    /// Bitrate the one-click fix applies: the nearest end of the recommended range
+    pub fn suggested_bitrate(&self) -> String {
+        let kbps = match self.kind {
+            AdviceKind::BelowFloor | AdviceKind::AutoSurround => self.recommended.0,
+            AdviceKind::AboveRange => self.recommended.1,
+        };
+        format!("{}k", kbps)
+    }
+}
+
+/// Advice for encoding `channels` channels with `codec_family` at `target_kbps` (None for "auto")
+pub fn advise(codec_family: &'static str, channels: u32, content: Option<ContentType>, target_kbps: Option<u64>) -> Option<AudioAdvice> {
+    let layout = ChannelLayout::from_channels(channels);
+    let recommended = recommended_kbps(codec_family, layout, content)?;
+    let floor = floor_kbps(codec_family, layout)?;
+    let kind = match target_kbps {
+        Some(kbps) if kbps < floor as u64 => AdviceKind::BelowFloor,
+        Some(kbps) if kbps as f64 > recommended.1 as f64 * ABOVE_RANGE => AdviceKind::AboveRange,
+        None if matches!(layout, ChannelLayout::Surround51 | ChannelLayout::Surround71) && codec_family != "mp3" => AdviceKind::AutoSurround,
+        _ => return None,
+    };
+    Some(AudioAdvice { kind, codec_family, channels, content, target_kbps, recommended })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spectrum(centroid_hz: f64, rolloff_hz: f64) -> SpectralSummary {
+        SpectralSummary { centroid_hz, rolloff_hz }
+    }
+
+    #[test]
+    fn voice_and_music_from_the_spectrum() {
+        assert_eq!(classify(&spectrum(1200.0, 3500.0)), Some(ContentType::Voice));
+        assert_eq!(classify(&spectrum(3000.0, 9000.0)), Some(ContentType::Music));
+        // A bright spectrum alone is enough for music
+        assert_eq!(classify(&spectrum(1500.0, 7000.0)), Some(ContentType::Music));
+        assert_eq!(classify(&spectrum(2600.0, 4000.0)), Some(ContentType::Music));
+        // In between stays undecided
+        assert_eq!(classify(&spectrum(2200.0, 5000.0)), None);
+        assert_eq!(classify(&spectrum(1500.0, 4600.0)), None);
+    }
+
+    #[test]
+    fn spectral_stats_average_the_first_channel() {
+        let output = "frame:0    pts:0       pts_time:0\n\
+            lavfi.aspectralstats.1.centroid=1000.5\n\
+            lavfi.aspectralstats.1.rolloff=3000\n\
+            lavfi.aspectralstats.2.centroid=9000\n\
+            frame:1    pts:1024    pts_time:0.0213\n\
+            lavfi.aspectralstats.1.centroid=1999.5\n\
+            lavfi.aspectralstats.1.rolloff=nan\n\
+            lavfi.aspectralstats.1.rolloff=5000\n";
+        assert_eq!(parse_spectral_stats(output), Some(spectrum(1500.0, 4000.0)));
+        assert_eq!(parse_spectral_stats("lavfi.aspectralstats.1.centroid=1000\n"), None);
+        assert_eq!(parse_spectral_stats(""), None);
+    }
+
+    #[test]
+    fn channel_counts_map_to_budget_layouts() {
+        let layouts: Vec<ChannelLayout> = [0, 1, 2, 3, 6, 7, 8].into_iter().map(ChannelLayout::from_channels).collect();
+        assert_eq!(layouts, [
+            ChannelLayout::Mono, ChannelLayout::Mono, ChannelLayout::Stereo,
+            ChannelLayout::Surround51, ChannelLayout::Surround51, ChannelLayout::Surround71, ChannelLayout::Surround71,
+        ]);
+    }
+
+    #[test]
+    fn recommended_ranges_depend_on_layout_and_content() {
+        assert_eq!(recommended_kbps("aac", ChannelLayout::Stereo, Some(ContentType::Music)), Some((192, 256)));
+        assert_eq!(recommended_kbps("aac", ChannelLayout::Stereo, Some(ContentType::Voice)), Some((96, 128)));
+        // Unknown content is budgeted as music
+        assert_eq!(recommended_kbps("aac", ChannelLayout::Stereo, None), Some((192, 256)));
+        assert_eq!(recommended_kbps("opus", ChannelLayout::Surround51, None), Some((256, 384)));
+        assert_eq!(recommended_kbps("mp3", ChannelLayout::Surround71, None), recommended_kbps("mp3", ChannelLayout::Stereo, None));
+        assert_eq!(recommended_kbps("ac3", ChannelLayout::Surround71, Some(ContentType::Voice)), Some((384, 640)));
+        assert_eq!(recommended_kbps("eac3", ChannelLayout::Surround71, None), Some((640, 1024)));
+        assert_eq!(recommended_kbps("flac", ChannelLayout::Stereo, None), None);
+
+        assert_eq!(floor_kbps("aac", ChannelLayout::Stereo), Some(64));
+        assert_eq!(floor_kbps("aac", ChannelLayout::Surround51), Some(170));
+        assert_eq!(floor_kbps("opus", ChannelLayout::Mono), Some(32));
+        assert_eq!(floor_kbps("pcm", ChannelLayout::Mono), None);
+    }
+
+    #[test]
+    fn advice_for_low_high_and_auto_bitrates() {
+        // 5.1 at a stereo bitrate
+        let low = advise("aac", 6, None, Some(128)).unwrap();
+        assert_eq!(low.kind, AdviceKind::BelowFloor);
+        assert_eq!(low.recommended, (384, 512));
+        assert_eq!(low.suggested_bitrate(), "384k");
+
+        // Music bitrate for a podcast
+        let high = advise("aac", 1, Some(ContentType::Voice), Some(256)).unwrap();
+        assert_eq!(high.kind, AdviceKind::AboveRange);
+        assert_eq!(high.suggested_bitrate(), "96k");
+        // Up to a quarter above the range is fine
+        assert_eq!(advise("aac", 1, Some(ContentType::Voice), Some(120)), None);
+        assert!(advise("aac", 1, Some(ContentType::Voice), Some(121)).is_some());
+
+        // The floor is the same for voice and music
+        assert_eq!(advise("aac", 2, Some(ContentType::Music), Some(64)), None);
+        assert_eq!(advise("aac", 2, Some(ContentType::Music), Some(63)).unwrap().kind, AdviceKind::BelowFloor);
+
+        let auto = advise("opus", 8, Some(ContentType::Music), None).unwrap();
+        assert_eq!(auto.kind, AdviceKind::AutoSurround);
+        assert_eq!((auto.channels, auto.target_kbps), (8, None));
+        assert_eq!(auto.suggested_bitrate(), "384k");
+        // "auto" is fine for stereo, and MP3 downmixes surround anyway
+        assert_eq!(advise("opus", 2, None, None), None);
+        assert_eq!(advise("mp3", 6, None, None), None);
+        assert_eq!(advise("flac", 6, None, Some(32)), None);
+    }
+
+    #[test]
+    fn advisor_uses_the_output_channel_count() {
+        use crate::app_state::{AudioSettings, VideoSettings};
+
+        let info: crate::ffmpeg_worker_simple::MediaInfo = serde_json::from_value(serde_json::json!({
+            "filename": "film.mkv", "duration": 60.0, "video_streams": [],
+            "audio_streams": [{ "index": 0, "codec": "ac3", "sample_rate": 48000, "channels": 6 }],
+        })).unwrap();
+        let video = VideoSettings::default();
+        let mut audio = AudioSettings { codec: "aac".to_string(), bitrate: "128k".to_string(), channels: String::new(), ..Default::default() };
+        let check = |audio: &AudioSettings| crate::encode_advisor::check_audio_bitrate_for_layout(&info, &video, audio);
+
+        assert!(check(&audio).is_some());
+        // Downmixed to stereo, 128k is enough
+        audio.channels = "2".to_string();
+        assert!(check(&audio).is_none());
+        audio.channels = "6".to_string();
+        audio.copy_audio = true;
+        assert!(check(&audio).is_none());
+    }
+}
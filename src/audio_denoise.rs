@@ -0,0 +1,369 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Frequencies offered for the clean-up filters, in Hz
+const HIGHPASS_RANGE: std::ops::RangeInclusive<f32> = 20.0..=1000.0;
+const LOWPASS_RANGE: std::ops::RangeInclusive<f32> = 1000.0..=20000.0;
+
+/// ffmpeg filter doing the noise reduction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenoiseEngine {
+    /// FFT based (afftdn): fast, supports learning the noise profile
+    Afftdn,
+    /// Non-local means (anlmdn): slower, better on broadband hiss
+    Anlmdn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenoisePreset {
+    Light,
+    Medium,
+    Aggressive,
+    Voice,
+    /// Raw parameters edited by hand
+    Custom,
+}
+
+impl DenoisePreset {
+    pub fn all() -> [DenoisePreset; 4] {
+        [DenoisePreset::Light, DenoisePreset::Medium, DenoisePreset::Aggressive, DenoisePreset::Voice]
+    }
+}
+
+/// Audio noise reduction shared by the audio settings and the AudioDeNoise node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioDenoiseSettings {
+    pub enabled: bool,
+    pub engine: DenoiseEngine,
+    pub preset: DenoisePreset,
+    /// afftdn noise reduction in dB (0.01-97)
+    pub reduction_db: f32,
+    /// afftdn noise floor in dB (-80 to -20)
+    pub noise_floor_db: f32,
+    /// Sample the noise profile from the first `learn_seconds` instead of estimating it (afftdn only)
+    pub learn_profile: bool,
+    pub learn_seconds: f32,
+    /// anlmdn denoising strength (0.00001-10)
+    pub nlm_strength: f32,
+    /// Clean-up before and after the noise reduction: rumble below and hiss above these
+    /// frequencies in Hz is cut; None leaves that end alone
+    pub highpass_hz: Option<f32>,
+    pub lowpass_hz: Option<f32>,
+}
+
+impl Default for AudioDenoiseSettings {
+    fn default() -> Self {
+        let mut settings = Self {
+            enabled: false,
+            engine: DenoiseEngine::Afftdn,
+            preset: DenoisePreset::Custom,
+            reduction_db: 0.0,
+            noise_floor_db: 0.0,
+            learn_profile: false,
+            learn_seconds: 0.0,
+            nlm_strength: 0.0,
+            highpass_hz: None,
+            lowpass_hz: None,
+        };
+        settings.apply_preset(DenoisePreset::Medium);
+        settings
+    }
+}
+
+impl AudioDenoiseSettings {
+    /// Fill the raw parameters from a preset; the engine is left alone
+    pub fn apply_preset(&mut self, preset: DenoisePreset) {
+        let (reduction_db, noise_floor_db, learn_seconds, nlm_strength) = match preset {
+            DenoisePreset::Light => (6.0, -50.0, None, 0.0001),
+            DenoisePreset::Medium => (12.0, -45.0, None, 0.001),
+            DenoisePreset::Aggressive => (24.0, -35.0, None, 0.01),
+            // Recordings usually start with a moment of room tone before anyone speaks
+            DenoisePreset::Voice => (18.0, -40.0, Some(1.0), 0.002),
+            DenoisePreset::Custom => return,
+        };
+        self.preset = preset;
+        self.reduction_db = reduction_db;
+        self.noise_floor_db = noise_floor_db;
+        self.learn_profile = learn_seconds.is_some();
+        self.learn_seconds = learn_seconds.unwrap_or(self.learn_seconds.max(0.5));
+        self.nlm_strength = nlm_strength;
+    }
+
+    /// Check parameters against the ranges ffmpeg accepts
+    pub fn validate(&self) -> Result<()> {
+        match self.engine {
+            DenoiseEngine::Afftdn => {
+                if !(0.01..=97.0).contains(&self.reduction_db) {
+                    return Err(anyhow!("Noise reduction must be between 0.01 and 97 dB, got {}", self.reduction_db));
+                }
+                if !(-80.0..=-20.0).contains(&self.noise_floor_db) {
+                    return Err(anyhow!("Noise floor must be between -80 and -20 dB, got {}", self.noise_floor_db));
+                }
+                if self.learn_profile && self.learn_seconds <= 0.0 {
+                    return Err(anyhow!("Noise profile learning needs a duration above 0 seconds"));
+                }
+            }
+            DenoiseEngine::Anlmdn => {
+                if !(0.00001..=10.0).contains(&self.nlm_strength) {
+                    return Err(anyhow!("Denoise strength must be between 0.00001 and 10, got {}", self.nlm_strength));
+                }
+            }
+        }
+        if let Some(highpass) = self.highpass_hz.filter(|hz| !HIGHPASS_RANGE.contains(hz)) {
+            return Err(anyhow!("The high-pass frequency must be between {} and {} Hz, got {}", HIGHPASS_RANGE.start(), HIGHPASS_RANGE.end(), highpass));
+        }
+        if let Some(lowpass) = self.lowpass_hz.filter(|hz| !LOWPASS_RANGE.contains(hz)) {
+            return Err(anyhow!("The low-pass frequency must be between {} and {} Hz, got {}", LOWPASS_RANGE.start(), LOWPASS_RANGE.end(), lowpass));
+        }
+        if let (Some(highpass), Some(lowpass)) = (self.highpass_hz, self.lowpass_hz) {
+            if highpass >= lowpass {
+                return Err(anyhow!("The high-pass frequency ({} Hz) must be below the low-pass one ({} Hz)", highpass, lowpass));
+            }
+        }
+        Ok(())
+    }
+
+    /// Filter chain for the -af argument, None when noise reduction is off
+    pub fn to_filter(&self) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let denoise = match self.engine {
+            DenoiseEngine::Afftdn => {
+                let afftdn = format!("afftdn=nr={}:nf={}", self.reduction_db, self.noise_floor_db);
+                if self.learn_profile {
+                    // afftdn samples noise between the "sn start" and "sn stop" commands
+                    format!("asendcmd=0.0 afftdn sn start,asendcmd={} afftdn sn stop,{}", self.learn_seconds, afftdn)
+                } else {
+                    afftdn
+                }
+            }
+            DenoiseEngine::Anlmdn => format!("anlmdn=s={}", self.nlm_strength),
+        };
+        let mut filters: Vec<String> = self.highpass_hz.map(|hz| format!("highpass=f={}", hz)).into_iter().collect();
+        filters.push(denoise);
+        filters.extend(self.lowpass_hz.map(|hz| format!("lowpass=f={}", hz)));
+        Some(filters.join(","))
+    }
+}
+
+/// An optional clean-up frequency as a checkbox plus logarithmic slider
+fn show_cutoff(ui: &mut egui::Ui, label: &str, hz: &mut Option<f32>, range: std::ops::RangeInclusive<f32>, fallback: f32) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let mut enabled = hz.is_some();
+        if ui.checkbox(&mut enabled, label).changed() {
+            *hz = enabled.then_some(fallback);
+            changed = true;
+        }
+        if let Some(value) = hz {
+            changed |= ui.add(egui::Slider::new(value, range).logarithmic(true).suffix(" Hz")).changed();
+        }
+    });
+    changed
+}
+
+/// Engine, preset and raw parameter editor. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut AudioDenoiseSettings, translations: &Translations) -> bool {
+    let mut changed = ui.checkbox(&mut settings.enabled, translations.denoise_enable()).changed();
+
+    ui.add_enabled_ui(settings.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(translations.denoise_engine());
+            changed |= ui.radio_value(&mut settings.engine, DenoiseEngine::Afftdn, "afftdn").changed();
+            changed |= ui.radio_value(&mut settings.engine, DenoiseEngine::Anlmdn, "anlmdn").changed();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(translations.denoise_preset());
+            for preset in DenoisePreset::all() {
+                if ui.selectable_label(settings.preset == preset, translations.denoise_preset_name(preset)).clicked() {
+                    settings.apply_preset(preset);
+                    changed = true;
+                }
+            }
+            if settings.preset == DenoisePreset::Custom {
+                ui.label(egui::RichText::new(translations.denoise_preset_name(DenoisePreset::Custom)).weak());
+            }
+        });
+
+        let mut raw_changed = false;
+        match settings.engine {
+            DenoiseEngine::Afftdn => {
+                ui.horizontal(|ui| {
+                    ui.label(translations.denoise_reduction());
+                    raw_changed |= ui.add(egui::Slider::new(&mut settings.reduction_db, 0.01..=97.0).suffix(" dB")).changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(translations.denoise_noise_floor());
+                    raw_changed |= ui.add(egui::Slider::new(&mut settings.noise_floor_db, -80.0..=-20.0).suffix(" dB")).changed();
+                });
+                ui.horizontal(|ui| {
+                    raw_changed |= ui.checkbox(&mut settings.learn_profile, translations.denoise_learn_profile()).changed();
+                    ui.add_enabled_ui(settings.learn_profile, |ui| {
+                        raw_changed |= ui.add(egui::DragValue::new(&mut settings.learn_seconds)
+                            .range(0.1..=30.0)
+                            .speed(0.1)
+                            .suffix(" s")).changed();
+                    });
+                }).response.on_hover_text(translations.denoise_learn_hint());
+            }
+            DenoiseEngine::Anlmdn => {
+                ui.horizontal(|ui| {
+                    ui.label(translations.denoise_strength());
+                    raw_changed |= ui.add(egui::Slider::new(&mut settings.nlm_strength, 0.00001..=10.0).logarithmic(true)).changed();
+                });
+            }
+        }
+        if raw_changed {
+            settings.preset = DenoisePreset::Custom;
+            changed = true;
+        }
+
+        changed |= show_cutoff(ui, translations.denoise_highpass(), &mut settings.highpass_hz, HIGHPASS_RANGE, 80.0);
+        changed |= show_cutoff(ui, translations.denoise_lowpass(), &mut settings.lowpass_hz, LOWPASS_RANGE, 12000.0);
+
+        if let Err(e) = settings.validate() {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+        } else if let Some(filter) = settings.to_filter() {
+            ui.label(egui::RichText::new(filter).monospace().weak());
+        }
+    });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(engine: DenoiseEngine, preset: DenoisePreset) -> AudioDenoiseSettings {
+        let mut settings = AudioDenoiseSettings { enabled: true, engine, ..Default::default() };
+        settings.apply_preset(preset);
+        settings
+    }
+
+    fn settings_with_cutoffs(highpass_hz: Option<f32>, lowpass_hz: Option<f32>) -> AudioDenoiseSettings {
+        AudioDenoiseSettings { enabled: true, highpass_hz, lowpass_hz, ..Default::default() }
+    }
+
+    #[test]
+    fn disabled_settings_add_no_filter() {
+        let settings = AudioDenoiseSettings::default();
+        assert!(!settings.enabled);
+        assert_eq!(settings.preset, DenoisePreset::Medium);
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.to_filter(), None);
+    }
+
+    #[test]
+    fn afftdn_filter_per_preset() {
+        let expected = [
+            (DenoisePreset::Light, "afftdn=nr=6:nf=-50"),
+            (DenoisePreset::Medium, "afftdn=nr=12:nf=-45"),
+            (DenoisePreset::Aggressive, "afftdn=nr=24:nf=-35"),
+            (DenoisePreset::Voice, "asendcmd=0.0 afftdn sn start,asendcmd=1 afftdn sn stop,afftdn=nr=18:nf=-40"),
+        ];
+        for (preset, filter) in expected {
+            let settings = settings(DenoiseEngine::Afftdn, preset);
+            assert_eq!(settings.preset, preset);
+            assert!(settings.validate().is_ok(), "{preset:?}");
+            assert_eq!(settings.to_filter().as_deref(), Some(filter), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn anlmdn_filter_per_preset() {
+        let expected = [
+            (DenoisePreset::Light, "anlmdn=s=0.0001"),
+            (DenoisePreset::Medium, "anlmdn=s=0.001"),
+            (DenoisePreset::Aggressive, "anlmdn=s=0.01"),
+            // Profile learning is an afftdn feature and leaves anlmdn alone
+            (DenoisePreset::Voice, "anlmdn=s=0.002"),
+        ];
+        for (preset, filter) in expected {
+            let settings = settings(DenoiseEngine::Anlmdn, preset);
+            assert!(settings.validate().is_ok(), "{preset:?}");
+            assert_eq!(settings.to_filter().as_deref(), Some(filter), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn learn_profile_mode_samples_the_given_duration() {
+        let mut settings = settings(DenoiseEngine::Afftdn, DenoisePreset::Medium);
+        assert!(!settings.learn_profile);
+        // Presets without learning keep a usable duration for when it is switched on by hand
+        assert_eq!(settings.learn_seconds, 0.5);
+
+        settings.learn_profile = true;
+        settings.learn_seconds = 2.5;
+        assert_eq!(
+            settings.to_filter().as_deref(),
+            Some("asendcmd=0.0 afftdn sn start,asendcmd=2.5 afftdn sn stop,afftdn=nr=12:nf=-45"),
+        );
+
+        settings.learn_seconds = 0.0;
+        assert!(settings.validate().is_err());
+
+        // Switching back to a preset without learning turns it off and restores a duration
+        settings.apply_preset(DenoisePreset::Light);
+        assert!(!settings.learn_profile);
+        assert_eq!(settings.learn_seconds, 0.5);
+        assert_eq!(settings.to_filter().as_deref(), Some("afftdn=nr=6:nf=-50"));
+    }
+
+    #[test]
+    fn custom_preset_keeps_raw_parameters() {
+        let mut settings = settings(DenoiseEngine::Afftdn, DenoisePreset::Aggressive);
+        settings.reduction_db = 30.0;
+        settings.apply_preset(DenoisePreset::Custom);
+        assert_eq!(settings.preset, DenoisePreset::Aggressive);
+        assert_eq!(settings.reduction_db, 30.0);
+    }
+
+    #[test]
+    fn cutoffs_wrap_the_denoiser() {
+        let mut settings = settings(DenoiseEngine::Anlmdn, DenoisePreset::Medium);
+        settings.highpass_hz = Some(80.0);
+        assert_eq!(settings.to_filter().as_deref(), Some("highpass=f=80,anlmdn=s=0.001"));
+        settings.lowpass_hz = Some(12000.0);
+        assert_eq!(settings.to_filter().as_deref(), Some("highpass=f=80,anlmdn=s=0.001,lowpass=f=12000"));
+        settings.highpass_hz = None;
+        assert_eq!(settings.to_filter().as_deref(), Some("anlmdn=s=0.001,lowpass=f=12000"));
+    }
+
+    #[test]
+    fn validation_follows_the_engine() {
+        let mut settings = settings(DenoiseEngine::Afftdn, DenoisePreset::Medium);
+        settings.reduction_db = 0.0;
+        assert!(settings.validate().is_err());
+        // Only the selected engine's parameters are checked
+        settings.engine = DenoiseEngine::Anlmdn;
+        assert!(settings.validate().is_ok());
+        settings.nlm_strength = 11.0;
+        assert!(settings.validate().is_err());
+
+        let mut settings = settings_with_cutoffs(Some(10.0), None);
+        assert!(settings.validate().is_err());
+        settings = settings_with_cutoffs(None, Some(25000.0));
+        assert!(settings.validate().is_err());
+        settings = settings_with_cutoffs(Some(1000.0), Some(1000.0));
+        assert!(settings.validate().is_err());
+        settings = settings_with_cutoffs(Some(1000.0), Some(1001.0));
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn settings_round_trip_through_serde() {
+        let settings = settings(DenoiseEngine::Anlmdn, DenoisePreset::Voice);
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains("\"engine\":\"anlmdn\"") && json.contains("\"preset\":\"voice\""));
+        assert_eq!(serde_json::from_str::<AudioDenoiseSettings>(&json).unwrap(), settings);
+        // Missing fields fall back to the defaults
+        assert_eq!(serde_json::from_str::<AudioDenoiseSettings>("{}").unwrap(), AudioDenoiseSettings::default());
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Echo rows the editor offers; more only muddies the sound
+const MAX_TAPS: usize = 8;
+
+/// One reflection: how long after the original it is heard and how loud
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EchoTap {
+    /// Milliseconds, 1 to 90000
+    pub delay_ms: f32,
+    /// Loudness relative to the original, 0 to 1
+    pub decay: f32,
+}
+
+/// aecho settings of an AudioEcho node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EchoSettings {
+    /// Gain of the input and of the output (original plus reflections), 0 to 1
+    pub in_gain: f32,
+    pub out_gain: f32,
+    pub taps: Vec<EchoTap>,
+}
+
+impl Default for EchoSettings {
+    fn default() -> Self {
+        Self { in_gain: 0.6, out_gain: 0.3, taps: vec![EchoTap { delay_ms: 1000.0, decay: 0.5 }] }
+    }
+}
+
+impl EchoSettings {
+    /// Taps from aecho's "|" separated lists, e.g. "1000|1800" and "0.3|0.25". A missing decay
+    /// repeats the last one.
+    pub fn parse_taps(delays: &str, decays: &str) -> Option<Vec<EchoTap>> {
+        let delays: Vec<f32> = delays.split('|').map(|delay| delay.trim().parse().ok()).collect::<Option<_>>()?;
+        let decays: Vec<f32> = decays.split('|').map(|decay| decay.trim().parse().ok()).collect::<Option<_>>()?;
+        let taps = delays.iter().enumerate()
+            .map(|(i, delay_ms)| EchoTap { delay_ms: *delay_ms, decay: decays.get(i).or(decays.last()).copied().unwrap_or(0.5) })
+            .collect();
+        Some(taps)
+    }
+
+    /// Whole milliseconds
+    pub fn delays_param(&self) -> String {
+        self.taps.iter().map(|tap| tap.delay_ms.round().to_string()).collect::<Vec<_>>().join("|")
+    }
+
+    /// Three decimals, so slider values stay readable
+    pub fn decays_param(&self) -> String {
+        self.taps.iter().map(|tap| ((tap.decay * 1000.0).round() / 1000.0).to_string()).collect::<Vec<_>>().join("|")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, gain) in [("input", self.in_gain), ("output", self.out_gain)] {
+            if !(gain > 0.0 && gain <= 1.0) {
+                return Err(anyhow!("The {} gain must be above 0 and at most 1, got {}", name, gain));
+            }
+        }
+        if self.taps.is_empty() {
+            return Err(anyhow!("Add at least one echo"));
+        }
+        for tap in &self.taps {
+            if !(1.0..=90000.0).contains(&tap.delay_ms) {
+                return Err(anyhow!("Echo delays must be between 1 and 90000 ms, got {}", tap.delay_ms));
+            }
+            if !(tap.decay > 0.0 && tap.decay <= 1.0) {
+                return Err(anyhow!("Echo decays must be above 0 and at most 1, got {}", tap.decay));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn filter(&self) -> Result<String> {
+        self.validate()?;
+        Ok(format!("aecho=in_gain={}:out_gain={}:delays={}:decays={}",
+            self.in_gain, self.out_gain, self.delays_param(), self.decays_param()))
+    }
+}
+
+/// Gains and echo rows with add/remove. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut EchoSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.echo_in_gain());
+        changed |= ui.add(egui::Slider::new(&mut settings.in_gain, 0.01..=1.0)).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.echo_out_gain());
+        changed |= ui.add(egui::Slider::new(&mut settings.out_gain, 0.01..=1.0)).changed();
+    });
+
+    let mut removed = None;
+    egui::Grid::new("audio_echo_taps").num_columns(3).spacing([8.0, 4.0]).show(ui, |ui| {
+        ui.strong(translations.echo_delay());
+        ui.strong(translations.echo_decay());
+        ui.end_row();
+        for (i, tap) in settings.taps.iter_mut().enumerate() {
+            changed |= ui.add(egui::Slider::new(&mut tap.delay_ms, 1.0..=5000.0).logarithmic(true).suffix(" ms")).changed();
+            changed |= ui.add(egui::Slider::new(&mut tap.decay, 0.01..=1.0)).changed();
+            // The first echo stays so there is always one
+            if i > 0 && ui.small_button("🗑").on_hover_text(translations.echo_remove()).clicked() {
+                removed = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = removed {
+        settings.taps.remove(i);
+        changed = true;
+    }
+    if settings.taps.len() < MAX_TAPS && ui.button(translations.echo_add()).clicked() {
+        let last = settings.taps.last().copied().unwrap_or(EchoTap { delay_ms: 500.0, decay: 0.6 });
+        settings.taps.push(EchoTap { delay_ms: (last.delay_ms * 1.8).min(5000.0), decay: (last.decay * 0.8).max(0.01) });
+        changed = true;
+    }
+
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Gain range of each band, in dB
+pub const BAND_RANGE: std::ops::RangeInclusive<f32> = -20.0..=20.0;
+
+/// Three-band equalizer of an AudioEqualizer node plus an optional hand-written chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EqualizerSettings {
+    /// Shelf below 100 Hz, in dB
+    pub bass_db: f32,
+    /// Peak around 1 kHz, two octaves wide, in dB
+    pub mid_db: f32,
+    /// Shelf above 3 kHz, in dB
+    pub treble_db: f32,
+    /// Audio filters appended after the bands, e.g. "equalizer=f=60:t=q:w=1:g=-6"
+    pub advanced: String,
+}
+
+impl Default for EqualizerSettings {
+    fn default() -> Self {
+        Self { bass_db: 0.0, mid_db: 0.0, treble_db: 0.0, advanced: String::new() }
+    }
+}
+
+/// Gain rounded to a tenth of a dB, so slider values stay readable
+fn gain(db: f32) -> f32 {
+    (db * 10.0).round() / 10.0
+}
+
+impl EqualizerSettings {
+    pub fn validate(&self) -> Result<()> {
+        for (name, db) in [("Bass", self.bass_db), ("Mid", self.mid_db), ("Treble", self.treble_db)] {
+            if !BAND_RANGE.contains(&db) {
+                return Err(anyhow!("{} gain must be between {} and {} dB, got {}", name, BAND_RANGE.start(), BAND_RANGE.end(), db));
+            }
+        }
+        if !crate::workflow_plan::is_simple_chain(&self.advanced) {
+            return Err(anyhow!("The advanced filters must be a plain chain like \"a,b\", without labels or ';'"));
+        }
+        Ok(())
+    }
+
+    /// Filter chain for the -af argument, None when every band is flat and nothing is typed
+    pub fn filter(&self) -> Result<Option<String>> {
+        self.validate()?;
+        let mut filters = Vec::new();
+        if gain(self.bass_db) != 0.0 {
+            filters.push(format!("bass=g={}:f=100", gain(self.bass_db)));
+        }
+        if gain(self.mid_db) != 0.0 {
+            filters.push(format!("equalizer=f=1000:t=o:w=2:g={}", gain(self.mid_db)));
+        }
+        if gain(self.treble_db) != 0.0 {
+            filters.push(format!("treble=g={}:f=3000", gain(self.treble_db)));
+        }
+        let advanced = self.advanced.trim().trim_matches(',');
+        if !advanced.is_empty() {
+            filters.push(advanced.to_string());
+        }
+        Ok((!filters.is_empty()).then(|| filters.join(",")))
+    }
+}
+
+/// Band sliders and the advanced filter field. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut EqualizerSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new("audio_equalizer_bands").num_columns(2).spacing([8.0, 4.0]).show(ui, |ui| {
+        for (label, db) in [
+            (translations.equalizer_bass(), &mut settings.bass_db),
+            (translations.equalizer_mid(), &mut settings.mid_db),
+            (translations.equalizer_treble(), &mut settings.treble_db),
+        ] {
+            ui.label(label);
+            changed |= ui.add(egui::Slider::new(db, BAND_RANGE).step_by(0.5).suffix(" dB")).changed();
+            ui.end_row();
+        }
+    });
+    if ui.button(translations.equalizer_flat()).clicked() {
+        settings.bass_db = 0.0;
+        settings.mid_db = 0.0;
+        settings.treble_db = 0.0;
+        changed = true;
+    }
+
+    ui.label(translations.equalizer_advanced());
+    changed |= ui.add(egui::TextEdit::singleline(&mut settings.advanced)
+        .hint_text("equalizer=f=60:t=q:w=1:g=-6")
+        .desired_width(f32::INFINITY)).changed();
+
+    match settings.filter() {
+        Ok(Some(filter)) => { ui.weak(format!("-af {}", filter)); }
+        Ok(None) => { ui.weak(translations.equalizer_flat_hint()); }
+        Err(e) => { ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e)); }
+    }
+
+    changed
+}
@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// afade curve shapes offered in the editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeCurve {
+    /// Linear
+    Tri,
+    /// Quarter of a sine wave
+    Qsin,
+    /// Exponential sine wave
+    Esin,
+    /// Logarithmic
+    Log,
+}
+
+impl FadeCurve {
+    pub fn all() -> [FadeCurve; 4] {
+        [FadeCurve::Tri, FadeCurve::Qsin, FadeCurve::Esin, FadeCurve::Log]
+    }
+
+    /// Value of afade's curve option, also used as the node parameter
+    pub fn key(&self) -> &'static str {
+        match self {
+            FadeCurve::Tri => "tri",
+            FadeCurve::Qsin => "qsin",
+            FadeCurve::Esin => "esin",
+            FadeCurve::Log => "log",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<FadeCurve> {
+        FadeCurve::all().into_iter().find(|curve| curve.key() == key.trim())
+    }
+}
+
+/// Fade-in and fade-out shared by the audio settings and the AudioFade node; 0 seconds is no fade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioFadeSettings {
+    pub fade_in_seconds: f64,
+    pub fade_out_seconds: f64,
+    pub curve: FadeCurve,
+}
+
+impl Default for AudioFadeSettings {
+    fn default() -> Self {
+        Self {
+            fade_in_seconds: 0.0,
+            fade_out_seconds: 0.0,
+            curve: FadeCurve::Tri,
+        }
+    }
+}
+
+impl AudioFadeSettings {
+    /// The fade-out starts counting back from the end, so the length of the audio must be known
+    pub fn needs_duration(&self) -> bool {
+        self.fade_out_seconds > 0.0
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, seconds) in [("Fade-in", self.fade_in_seconds), ("Fade-out", self.fade_out_seconds)] {
+            if !seconds.is_finite() || seconds < 0.0 {
+                return Err(anyhow!("{} must be 0 seconds or more, got {}", name, seconds));
+            }
+        }
+        Ok(())
+    }
+
+    /// afade filters for the -af chain; `duration` is the length of the audio being filtered
+    pub fn filters(&self, duration: Option<f64>) -> Result<Vec<String>> {
+        self.validate()?;
+        let mut filters = Vec::new();
+        if self.fade_in_seconds > 0.0 {
+            filters.push(format!("afade=t=in:d={}:curve={}", self.fade_in_seconds, self.curve.key()));
+        }
+        if self.needs_duration() {
+            let duration = duration.filter(|duration| duration.is_finite() && *duration > 0.0)
+                .ok_or_else(|| anyhow!("The duration of the input is unknown, cannot place the fade-out"))?;
+            if self.fade_out_seconds > duration {
+                return Err(anyhow!("The {}s fade-out is longer than the {:.2}s audio", self.fade_out_seconds, duration));
+            }
+            filters.push(format!(
+                "afade=t=out:st={:.3}:d={}:curve={}",
+                duration - self.fade_out_seconds, self.fade_out_seconds, self.curve.key()
+            ));
+        }
+        Ok(filters)
+    }
+}
+
+/// Fade durations and curve. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut AudioFadeSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.audio_fade_fade_in());
+        changed |= ui.add(egui::DragValue::new(&mut settings.fade_in_seconds).range(0.0..=600.0).speed(0.1).suffix(" s")).changed();
+        ui.label(translations.audio_fade_fade_out());
+        changed |= ui.add(egui::DragValue::new(&mut settings.fade_out_seconds).range(0.0..=600.0).speed(0.1).suffix(" s")).changed();
+    });
+    ui.add_enabled_ui(settings.fade_in_seconds > 0.0 || settings.fade_out_seconds > 0.0, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(translations.audio_fade_curve());
+            for curve in FadeCurve::all() {
+                let label = match curve {
+                    FadeCurve::Tri => translations.audio_fade_tri(),
+                    FadeCurve::Qsin => translations.audio_fade_qsin(),
+                    FadeCurve::Esin => translations.audio_fade_esin(),
+                    FadeCurve::Log => translations.audio_fade_log(),
+                };
+                changed |= ui.radio_value(&mut settings.curve, curve, label).changed();
+            }
+        });
+    });
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    } else if settings.needs_duration() {
+        ui.label(egui::RichText::new(translations.audio_fade_fade_out_hint()).weak());
+    }
+
+    changed
+}
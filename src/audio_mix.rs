@@ -0,0 +1,299 @@
+use serde::{Serialize, Deserialize};
+use crate::app_state::AudioSettings;
+use crate::language::Translations;
+
+/// AudioSettings::merge_mode values
+pub const MODE_CONCAT: &str = "concat";
+pub const MODE_MIX: &str = "mix";
+
+/// Length of the mixed output, amix's duration option
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MixDuration {
+    First,
+    Longest,
+    Shortest,
+}
+
+impl MixDuration {
+    pub fn all() -> [MixDuration; 3] {
+        [MixDuration::First, MixDuration::Longest, MixDuration::Shortest]
+    }
+
+    /// Value passed to amix, also the translation key
+    pub fn key(&self) -> &'static str {
+        match self {
+            MixDuration::First => "first",
+            MixDuration::Longest => "longest",
+            MixDuration::Shortest => "shortest",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<MixDuration> {
+        Self::all().into_iter().find(|duration| duration.key() == key)
+    }
+}
+
+/// Mixing and crossfade options for AudioMerge, shared by the audio settings and the AudioMerge node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioMixSettings {
+    /// Gain per input in dB, in input order; inputs without an entry stay at 0 dB
+    pub gains_db: Vec<f32>,
+    /// Let amix scale inputs down so the sum does not clip
+    pub normalize: bool,
+    pub duration: MixDuration,
+    /// Concatenate mode: overlap consecutive inputs with acrossfade, 0 disables
+    pub crossfade_seconds: f32,
+}
+
+impl Default for AudioMixSettings {
+    fn default() -> Self {
+        Self {
+            gains_db: Vec::new(),
+            normalize: true,
+            duration: MixDuration::Longest,
+            crossfade_seconds: 0.0,
+        }
+    }
+}
+
+impl AudioMixSettings {
+    pub fn gain_db(&self, input: usize) -> f32 {
+        self.gains_db.get(input).copied().unwrap_or(0.0)
+    }
+
+    /// Keep one gain entry per input so the editor rows line up with the file list
+    pub fn fit_gains(&mut self, inputs: usize) {
+        self.gains_db.resize(inputs, 0.0);
+    }
+}
+
+/// -filter_complex graph merging `inputs` audio inputs into the [out] label
+pub fn merge_filtergraph(settings: &AudioSettings, inputs: usize) -> String {
+    if settings.merge_mode == MODE_MIX {
+        mix_filtergraph(&settings.mix, inputs)
+    } else if settings.mix.crossfade_seconds > 0.0 {
+        crossfade_filtergraph(settings.mix.crossfade_seconds, inputs)
+    } else {
+        concat_filtergraph(settings.add_silence.then_some(settings.silence_duration), inputs)
+    }
+}
+
+/// Each input through its own volume filter, then amix over the [gN] labels
+fn mix_filtergraph(mix: &AudioMixSettings, inputs: usize) -> String {
+    let mut chains: Vec<String> = (0..inputs)
+        .map(|i| format!("[{}:a]volume={}dB[g{}]", i, mix.gain_db(i), i))
+        .collect();
+    let labels: String = (0..inputs).map(|i| format!("[g{}]", i)).collect();
+    chains.push(format!(
+        "{}amix=inputs={}:duration={}:normalize={}[out]",
+        labels,
+        inputs,
+        mix.duration.key(),
+        if mix.normalize { 1 } else { 0 }
+    ));
+    chains.join(";")
+}
+
+/// acrossfade takes two inputs, so longer lists are folded pairwise through [xN] labels
+fn crossfade_filtergraph(seconds: f32, inputs: usize) -> String {
+    let mut chains = Vec::new();
+    let mut previous = "[0:a]".to_string();
+    for i in 1..inputs {
+        let label = if i + 1 == inputs { "[out]".to_string() } else { format!("[x{}]", i) };
+        chains.push(format!("{}[{}:a]acrossfade=d={}{}", previous, i, seconds, label));
+        previous = label;
+    }
+    chains.join(";")
+}
+
+/// Plain concat; with a silence gap every input but the last is padded first
+fn concat_filtergraph(silence_seconds: Option<f32>, inputs: usize) -> String {
+    let mut chains = Vec::new();
+    let mut labels = String::new();
+    for i in 0..inputs {
+        match silence_seconds {
+            Some(seconds) if i + 1 < inputs => {
+                chains.push(format!("[{}:a]apad=pad_dur={}[p{}]", i, seconds, i));
+                labels.push_str(&format!("[p{}]", i));
+            }
+            _ => labels.push_str(&format!("[{}:a]", i)),
+        }
+    }
+    chains.push(format!("{}concat=n={}:v=0:a=1[out]", labels, inputs));
+    chains.join(";")
+}
+
+/// Mode selector with the concatenate or mix options. Returns true when the settings changed.
+pub fn show_merge_editor(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.audio_mix_mode());
+        changed |= ui.radio_value(&mut settings.merge_mode, MODE_CONCAT.to_string(), translations.audio_mix_concat()).changed();
+        changed |= ui.radio_value(&mut settings.merge_mode, MODE_MIX.to_string(), translations.audio_mix_mix()).changed();
+    });
+
+    ui.add_space(10.0);
+
+    if settings.merge_mode == MODE_MIX {
+        changed |= ui.checkbox(&mut settings.mix.normalize, translations.audio_mix_normalize()).changed();
+        ui.horizontal(|ui| {
+            ui.label(translations.audio_mix_duration());
+            for duration in MixDuration::all() {
+                let label = match duration {
+                    MixDuration::First => translations.audio_mix_first(),
+                    MixDuration::Longest => translations.audio_mix_longest(),
+                    MixDuration::Shortest => translations.audio_mix_shortest(),
+                };
+                changed |= ui.radio_value(&mut settings.mix.duration, duration, label).changed();
+            }
+        });
+        ui.label(egui::RichText::new(translations.audio_mix_gain_hint()).weak());
+    } else {
+        ui.horizontal(|ui| {
+            ui.label(translations.audio_mix_crossfade());
+            changed |= ui.add(egui::DragValue::new(&mut settings.mix.crossfade_seconds)
+                .range(0.0..=10.0)
+                .speed(0.1)
+                .suffix(" s")).changed();
+        });
+
+        // acrossfade overlaps the inputs, so a silence gap would never be heard
+        ui.add_enabled_ui(settings.mix.crossfade_seconds <= 0.0, |ui| {
+            changed |= ui.checkbox(&mut settings.add_silence, translations.audio_mix_add_silence()).changed();
+            if settings.add_silence {
+                ui.horizontal(|ui| {
+                    ui.label(translations.audio_mix_silence_duration());
+                    changed |= ui.add(egui::DragValue::new(&mut settings.silence_duration)
+                        .range(0.0..=10.0)
+                        .suffix(" s")).changed();
+                });
+            }
+        });
+    }
+
+    changed
+}
+
+/// Gain in dB next to each input given as (path or port name, duration in seconds). Returns true when a gain changed.
+pub fn show_input_gains(ui: &mut egui::Ui, inputs: &[(String, Option<f64>)], mix: &mut AudioMixSettings, translations: &Translations) -> bool {
+    mix.fit_gains(inputs.len());
+    let mut changed = false;
+
+    egui::Grid::new("audio_mix_gains")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (i, (input, duration)) in inputs.iter().enumerate() {
+                let name = std::path::Path::new(input)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| input.clone());
+                ui.label(name).on_hover_text(input);
+
+                let duration = duration.map(|seconds| format!("{:.1} s", seconds)).unwrap_or_default();
+                ui.label(egui::RichText::new(duration).weak());
+
+                changed |= ui.add(egui::DragValue::new(&mut mix.gains_db[i])
+                    .range(-60.0..=20.0)
+                    .speed(0.1)
+                    .prefix(translations.audio_mix_gain())
+                    .suffix(" dB")).changed();
+                ui.end_row();
+            }
+        });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: &str, mix: AudioMixSettings) -> AudioSettings {
+        AudioSettings { merge_mode: mode.to_string(), mix, add_silence: false, ..Default::default() }
+    }
+
+    #[test]
+    fn mix_sets_a_volume_per_input_before_amix() {
+        let mix = AudioMixSettings { gains_db: vec![-6.0, 3.5], ..Default::default() };
+        assert_eq!(
+            merge_filtergraph(&settings(MODE_MIX, mix), 3),
+            "[0:a]volume=-6dB[g0];[1:a]volume=3.5dB[g1];[2:a]volume=0dB[g2];[g0][g1][g2]amix=inputs=3:duration=longest:normalize=1[out]",
+        );
+    }
+
+    #[test]
+    fn mix_duration_and_normalization() {
+        let mix = AudioMixSettings { normalize: false, duration: MixDuration::Shortest, ..Default::default() };
+        assert_eq!(
+            merge_filtergraph(&settings(MODE_MIX, mix), 2),
+            "[0:a]volume=0dB[g0];[1:a]volume=0dB[g1];[g0][g1]amix=inputs=2:duration=shortest:normalize=0[out]",
+        );
+        let mix = AudioMixSettings { duration: MixDuration::First, ..Default::default() };
+        assert!(merge_filtergraph(&settings(MODE_MIX, mix), 2).ends_with("amix=inputs=2:duration=first:normalize=1[out]"));
+
+        for duration in MixDuration::all() {
+            assert_eq!(MixDuration::from_key(duration.key()), Some(duration));
+        }
+        assert_eq!(MixDuration::from_key("all"), None);
+    }
+
+    #[test]
+    fn mix_ignores_crossfade_and_silence() {
+        let mix = AudioMixSettings { crossfade_seconds: 2.0, ..Default::default() };
+        let mut audio = settings(MODE_MIX, mix);
+        audio.add_silence = true;
+        let graph = merge_filtergraph(&audio, 2);
+        assert!(graph.contains("amix") && !graph.contains("acrossfade") && !graph.contains("apad"), "{graph}");
+    }
+
+    #[test]
+    fn concatenation() {
+        assert_eq!(merge_filtergraph(&settings(MODE_CONCAT, AudioMixSettings::default()), 3), "[0:a][1:a][2:a]concat=n=3:v=0:a=1[out]");
+
+        // Silence goes after every input but the last
+        let mut audio = settings(MODE_CONCAT, AudioMixSettings::default());
+        audio.add_silence = true;
+        audio.silence_duration = 1.5;
+        assert_eq!(
+            merge_filtergraph(&audio, 3),
+            "[0:a]apad=pad_dur=1.5[p0];[1:a]apad=pad_dur=1.5[p1];[p0][p1][2:a]concat=n=3:v=0:a=1[out]",
+        );
+    }
+
+    #[test]
+    fn crossfades_fold_pairwise() {
+        let mix = AudioMixSettings { crossfade_seconds: 2.0, ..Default::default() };
+        assert_eq!(merge_filtergraph(&settings(MODE_CONCAT, mix.clone()), 2), "[0:a][1:a]acrossfade=d=2[out]");
+        assert_eq!(
+            merge_filtergraph(&settings(MODE_CONCAT, mix.clone()), 4),
+            "[0:a][1:a]acrossfade=d=2[x1];[x1][2:a]acrossfade=d=2[x2];[x2][3:a]acrossfade=d=2[out]",
+        );
+        // Gains only apply when mixing
+        let with_gains = AudioMixSettings { gains_db: vec![-20.0, -20.0], ..mix };
+        assert!(!merge_filtergraph(&settings(MODE_CONCAT, with_gains), 2).contains("volume"));
+    }
+
+    #[test]
+    fn gains_follow_the_input_list() {
+        let mut mix = AudioMixSettings { gains_db: vec![-3.0], ..Default::default() };
+        assert_eq!(mix.gain_db(0), -3.0);
+        assert_eq!(mix.gain_db(5), 0.0);
+        mix.fit_gains(3);
+        assert_eq!(mix.gains_db, [-3.0, 0.0, 0.0]);
+        mix.fit_gains(1);
+        assert_eq!(mix.gains_db, [-3.0]);
+    }
+
+    #[test]
+    fn settings_round_trip_through_serde() {
+        let mix = AudioMixSettings { gains_db: vec![-6.0, 2.0], normalize: false, duration: MixDuration::First, crossfade_seconds: 0.5 };
+        let json = serde_json::to_string(&mix).unwrap();
+        assert!(json.contains("\"duration\":\"first\""));
+        assert_eq!(serde_json::from_str::<AudioMixSettings>(&json).unwrap(), mix);
+        assert_eq!(serde_json::from_str::<AudioMixSettings>("{}").unwrap(), AudioMixSettings::default());
+    }
+}
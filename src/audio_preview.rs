@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+use lazy_static::lazy_static;
+
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+use crate::language::Translations;
+
+/// PCM format between the decoder and the output device
+const SAMPLE_RATE: u32 = 44100;
+const CHANNELS: u32 = 2;
+const BYTES_PER_SECOND: u64 = SAMPLE_RATE as u64 * CHANNELS as u64 * 2;
+/// Bytes moved from the decoder to the device per step; about 23 ms of audio
+const CHUNK_BYTES: usize = 4096;
+
+lazy_static! {
+    /// Only one preview plays at a time
+    static ref PLAYER: Mutex<Player> = Mutex::new(Player::default());
+    static ref OUTPUT_DEVICE: Mutex<DeviceStatus> = Mutex::new(DeviceStatus::Unchecked);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaybackState {
+    Stopped,
+    Playing,
+    Paused,
+    /// Decoder restarting at a new position; `paused` is the state to return to
+    Seeking { paused: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackEvent {
+    Play,
+    Pause,
+    Seek,
+    /// First PCM of a restarted decoder reached the device
+    DecoderStarted,
+    Stop,
+    Finished,
+    Failed,
+}
+
+impl PlaybackState {
+    /// Next state; events that make no sense in a state leave it unchanged
+    pub fn next(self, event: PlaybackEvent) -> PlaybackState {
+        use PlaybackEvent::*;
+        use PlaybackState::*;
+        match (self, event) {
+            (_, Stop) | (_, Finished) | (_, Failed) => Stopped,
+            (Stopped, Play) | (Paused, Play) => Playing,
+            (Playing, Pause) => Paused,
+            (Playing, Seek) => Seeking { paused: false },
+            (Paused, Seek) => Seeking { paused: true },
+            (Seeking { .. }, Play) => Seeking { paused: false },
+            (Seeking { .. }, Pause) => Seeking { paused: true },
+            (Seeking { paused }, DecoderStarted) => if paused { Paused } else { Playing },
+            (state, _) => state,
+        }
+    }
+}
+
+/// Platform audio output reached through ffmpeg's own output devices
+#[derive(Clone, Debug, PartialEq)]
+enum DeviceStatus {
+    Unchecked,
+    Checking,
+    Available { format: &'static str, target: &'static str },
+    Unavailable(String),
+}
+
+/// Output devices ffmpeg can play raw PCM on, in order of preference
+fn device_candidates() -> &'static [(&'static str, &'static str)] {
+    if cfg!(target_os = "macos") {
+        &[("audiotoolbox", "-")]
+    } else if cfg!(target_os = "linux") {
+        &[("pulse", "default"), ("alsa", "default")]
+    } else {
+        &[]
+    }
+}
+
+/// Ask ffmpeg once, on a background thread, which output device it supports
+fn output_device() -> DeviceStatus {
+    let mut status = OUTPUT_DEVICE.lock().unwrap();
+    if *status == DeviceStatus::Unchecked {
+        *status = DeviceStatus::Checking;
+        std::thread::spawn(|| {
+            let result = detect_output_device();
+            if let Err(reason) = &result {
+                log_warn!("Audio preview unavailable: {}", reason);
+            }
+            *OUTPUT_DEVICE.lock().unwrap() = match result {
+                Ok((format, target)) => DeviceStatus::Available { format, target },
+                Err(reason) => DeviceStatus::Unavailable(reason),
+            };
+        });
+    }
+    status.clone()
+}
+
+fn detect_output_device() -> Result<(&'static str, &'static str), String> {
+    let ffmpeg = get_bundled_ffmpeg().map_err(|e| e.to_string())?;
+    let output = ffmpeg.run_ffmpeg(&["-hide_banner", "-devices"]).map_err(|e| e.to_string())?;
+    let listing = String::from_utf8_lossy(&output.stdout);
+    // Lines look like " DE pulse           PulseAudio sound server"; E marks an output device
+    let outputs: Vec<&str> = listing.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let flags = parts.next()?;
+            let name = parts.next()?;
+            flags.contains('E').then_some(name)
+        })
+        .collect();
+    device_candidates().iter()
+        .find(|(format, _)| outputs.contains(format))
+        .copied()
+        .ok_or_else(|| "this FFmpeg build has no audio output device for this platform".to_string())
+}
+
+/// Decoder and device processes of the preview that is playing
+struct Pipeline {
+    decoder: Child,
+    device: Child,
+    stop: Arc<AtomicBool>,
+}
+
+impl Pipeline {
+    fn kill(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.decoder.kill();
+        let _ = self.device.kill();
+        let _ = self.decoder.wait();
+        let _ = self.device.wait();
+    }
+}
+
+struct Player {
+    path: String,
+    state: PlaybackState,
+    /// Seconds into the file where the current decoder started
+    start_offset: f64,
+    bytes_played: Arc<AtomicU64>,
+    paused: Arc<AtomicBool>,
+    pipeline: Option<Pipeline>,
+    /// Files that could not be decoded, with the reason
+    failures: HashMap<String, String>,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            state: PlaybackState::Stopped,
+            start_offset: 0.0,
+            bytes_played: Arc::new(AtomicU64::new(0)),
+            paused: Arc::new(AtomicBool::new(false)),
+            pipeline: None,
+            failures: HashMap::new(),
+        }
+    }
+}
+
+impl Player {
+    fn apply(&mut self, event: PlaybackEvent) {
+        self.state = self.state.next(event);
+        self.paused.store(matches!(self.state, PlaybackState::Paused | PlaybackState::Seeking { paused: true }), Ordering::Relaxed);
+    }
+
+    fn position(&self) -> f64 {
+        self.start_offset + self.bytes_played.load(Ordering::Relaxed) as f64 / BYTES_PER_SECOND as f64
+    }
+
+    fn stop(&mut self) {
+        if let Some(pipeline) = self.pipeline.take() {
+            pipeline.kill();
+        }
+        self.apply(PlaybackEvent::Stop);
+    }
+
+    /// Start decoding `path` at `offset` seconds and stream it to the device
+    fn start(&mut self, path: &str, offset: f64, format: &'static str, target: &'static str) -> Result<(), String> {
+        if let Some(pipeline) = self.pipeline.take() {
+            pipeline.kill();
+        }
+        self.path = path.to_string();
+        self.start_offset = offset.max(0.0);
+        self.bytes_played = Arc::new(AtomicU64::new(0));
+
+        let ffmpeg = get_bundled_ffmpeg().map_err(|e| e.to_string())?;
+        let offset_arg = format!("{:.3}", self.start_offset);
+        let rate = SAMPLE_RATE.to_string();
+        let channels = CHANNELS.to_string();
+        let mut decoder = background_command(ffmpeg.command());
+        decoder.args(["-hide_banner", "-loglevel", "error", "-nostdin", "-ss", &offset_arg, "-i", path,
+                "-vn", "-f", "s16le", "-ar", &rate, "-ac", &channels, "pipe:1"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut device = background_command(ffmpeg.command());
+        device.args(["-hide_banner", "-loglevel", "error", "-f", "s16le", "-ar", &rate, "-ac", &channels, "-i", "pipe:0",
+                "-f", format, target])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        let mut decoder = decoder.spawn().map_err(|e| e.to_string())?;
+        let mut device = match device.spawn() {
+            Ok(device) => device,
+            Err(e) => {
+                let _ = decoder.kill();
+                return Err(e.to_string());
+            }
+        };
+
+        let (Some(pcm), Some(errors), Some(sink)) = (decoder.stdout.take(), decoder.stderr.take(), device.stdin.take()) else {
+            let _ = decoder.kill();
+            let _ = device.kill();
+            return Err("could not open the decoder pipes".to_string());
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        spawn_pump(path.to_string(), pcm, errors, sink, stop.clone(), self.paused.clone(), self.bytes_played.clone());
+        self.pipeline = Some(Pipeline { decoder, device, stop });
+        Ok(())
+    }
+}
+
+fn background_command(mut command: Command) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command.env("AV_LOG_FORCE_NOCOLOR", "1");
+    command
+}
+
+/// Copy PCM from the decoder to the device chunk by chunk, holding back while paused so the
+/// decoder blocks on the full pipe instead of running ahead
+fn spawn_pump(
+    path: String,
+    mut pcm: impl Read + Send + 'static,
+    mut errors: impl Read + Send + 'static,
+    mut sink: impl Write + Send + 'static,
+    stop: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    bytes_played: Arc<AtomicU64>,
+) {
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; CHUNK_BYTES];
+        let mut started = false;
+        let end = loop {
+            while paused.load(Ordering::Relaxed) && started && !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(std::time::Duration::from_millis(30));
+            }
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let read = match pcm.read(&mut buffer) {
+                Ok(0) => break Ok(started),
+                Ok(read) => read,
+                Err(e) => break Err(e.to_string()),
+            };
+            if let Err(e) = sink.write_all(&buffer[..read]) {
+                break Err(e.to_string());
+            }
+            if !started {
+                started = true;
+                if let Ok(mut player) = PLAYER.lock() {
+                    if player.path == path && !stop.load(Ordering::Relaxed) {
+                        player.apply(PlaybackEvent::DecoderStarted);
+                    }
+                }
+            }
+            bytes_played.fetch_add(read as u64, Ordering::Relaxed);
+        };
+        drop(sink);
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut stderr = String::new();
+        let _ = errors.read_to_string(&mut stderr);
+        let Ok(mut player) = PLAYER.lock() else {
+            return;
+        };
+        if player.path != path || player.pipeline.as_ref().is_none_or(|pipeline| !Arc::ptr_eq(&pipeline.stop, &stop)) {
+            return;
+        }
+        match end {
+            Ok(true) => player.apply(PlaybackEvent::Finished),
+            Ok(false) => {
+                let reason = stderr.lines().last().unwrap_or("no audio could be decoded").to_string();
+                log_warn!("Audio preview of {} failed: {}", path, reason);
+                player.failures.insert(path.clone(), reason);
+                player.apply(PlaybackEvent::Failed);
+            }
+            Err(e) => {
+                log_warn!("Audio preview of {} stopped: {}", path, e);
+                player.failures.insert(path.clone(), e);
+                player.apply(PlaybackEvent::Failed);
+            }
+        }
+        if let Some(pipeline) = player.pipeline.take() {
+            pipeline.kill();
+        }
+    });
+}
+
+/// Stop the preview if it is playing `path`; called before a task writes to that file
+pub fn stop_if_playing(path: &str) {
+    if let Ok(mut player) = PLAYER.lock() {
+        if player.path == path && player.state != PlaybackState::Stopped {
+            log_debug!("Stopping audio preview of {} before it is overwritten", path);
+            player.stop();
+        }
+    }
+}
+
+/// Whether a preview is playing or starting, so the UI keeps its position moving
+pub fn is_active() -> bool {
+    PLAYER.lock().is_ok_and(|player| matches!(player.state, PlaybackState::Playing | PlaybackState::Seeking { .. }))
+}
+
+/// Why `path` cannot be previewed, if it cannot
+fn unavailable_reason(path: &str, translations: &Translations) -> Option<String> {
+    match output_device() {
+        DeviceStatus::Unchecked | DeviceStatus::Checking => Some(translations.audio_preview_checking().to_string()),
+        DeviceStatus::Unavailable(reason) => Some(format!("{}: {}", translations.audio_preview_no_device(), reason)),
+        DeviceStatus::Available { .. } => PLAYER.lock().ok()
+            .and_then(|player| player.failures.get(path).cloned())
+            .map(|reason| format!("{}: {}", translations.audio_preview_failed(), reason)),
+    }
+}
+
+fn play_or_pause(path: &str) {
+    let DeviceStatus::Available { format, target } = output_device() else {
+        return;
+    };
+    let mut player = PLAYER.lock().unwrap();
+    if player.path == path && player.pipeline.is_some() {
+        let event = if matches!(player.state, PlaybackState::Playing | PlaybackState::Seeking { paused: false }) {
+            PlaybackEvent::Pause
+        } else {
+            PlaybackEvent::Play
+        };
+        player.apply(event);
+        return;
+    }
+    player.stop();
+    match player.start(path, 0.0, format, target) {
+        Ok(()) => player.apply(PlaybackEvent::Play),
+        Err(e) => {
+            log_warn!("Failed to start audio preview of {}: {}", path, e);
+            player.failures.insert(path.to_string(), e);
+        }
+    }
+}
+
+/// Coarse seek: restart the decoder at `seconds`
+fn seek(path: &str, seconds: f64) {
+    let DeviceStatus::Available { format, target } = output_device() else {
+        return;
+    };
+    let mut player = PLAYER.lock().unwrap();
+    if player.path != path || player.pipeline.is_none() {
+        return;
+    }
+    player.apply(PlaybackEvent::Seek);
+    if let Err(e) = player.start(path, seconds, format, target) {
+        log_warn!("Failed to seek audio preview of {}: {}", path, e);
+        player.failures.insert(path.to_string(), e);
+        player.apply(PlaybackEvent::Failed);
+    }
+}
+
+/// State and position of the preview of `path`
+fn status_of(path: &str) -> (PlaybackState, f64) {
+    match PLAYER.lock() {
+        Ok(player) if player.path == path => (player.state, player.position()),
+        _ => (PlaybackState::Stopped, 0.0),
+    }
+}
+
+/// Play/pause button; disabled with the reason as tooltip when the file cannot be previewed
+pub fn show_button(ui: &mut egui::Ui, path: &str, translations: &Translations) {
+    let (state, _) = status_of(path);
+    let playing = matches!(state, PlaybackState::Playing | PlaybackState::Seeking { paused: false });
+    let reason = unavailable_reason(path, translations);
+    let button = egui::Button::new(if playing { "⏸" } else { "▶" }).small();
+    let response = ui.add_enabled(reason.is_none(), button);
+    let response = match &reason {
+        Some(reason) => response.on_disabled_hover_text(reason),
+        None => response.on_hover_text(if playing { translations.audio_preview_pause() } else { translations.audio_preview_play() }),
+    };
+    if response.clicked() {
+        play_or_pause(path);
+    }
+}
+
+/// Play/pause, seek bar and stop for the file info area
+pub fn show_player(ui: &mut egui::Ui, path: &str, duration: Option<f64>, translations: &Translations) {
+    ui.horizontal(|ui| {
+        show_button(ui, path, translations);
+        let (state, position) = status_of(path);
+        let stopped = state == PlaybackState::Stopped;
+        if ui.add_enabled(!stopped, egui::Button::new("⏹").small()).on_hover_text(translations.audio_preview_stop()).clicked() {
+            stop_if_playing(path);
+        }
+        if let Some(duration) = duration.filter(|duration| *duration > 0.0) {
+            let mut seconds = position.min(duration);
+            let slider = egui::Slider::new(&mut seconds, 0.0..=duration)
+                .show_value(false)
+                .trailing_fill(true);
+            let response = ui.add_enabled(!stopped, slider);
+            // Restart only once the handle is let go; every restart spawns a new decoder
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                seek(path, seconds);
+            }
+            ui.monospace(format!("{} / {}", format_time(seconds), format_time(duration)));
+        } else if !stopped {
+            ui.monospace(format_time(position));
+        }
+        if matches!(state, PlaybackState::Seeking { .. }) {
+            ui.spinner();
+        }
+    });
+}
+
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlaybackEvent::*;
+    use PlaybackState::*;
+
+    /// Writer that hands what it gets to the test
+    struct SharedSink(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedSink {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn play_pause_and_stop() {
+        assert_eq!(Stopped.next(Play), Playing);
+        assert_eq!(Playing.next(Pause), Paused);
+        assert_eq!(Paused.next(Play), Playing);
+        for state in [Stopped, Playing, Paused, Seeking { paused: false }, Seeking { paused: true }] {
+            assert_eq!(state.next(Stop), Stopped);
+            assert_eq!(state.next(Finished), Stopped);
+            assert_eq!(state.next(Failed), Stopped);
+        }
+    }
+
+    #[test]
+    fn seeking_remembers_whether_to_resume() {
+        assert_eq!(Playing.next(Seek), Seeking { paused: false });
+        assert_eq!(Paused.next(Seek), Seeking { paused: true });
+        assert_eq!(Seeking { paused: false }.next(DecoderStarted), Playing);
+        assert_eq!(Seeking { paused: true }.next(DecoderStarted), Paused);
+        // Play/pause while the decoder restarts only changes where it lands
+        assert_eq!(Seeking { paused: false }.next(Pause), Seeking { paused: true });
+        assert_eq!(Seeking { paused: true }.next(Play), Seeking { paused: false });
+        assert_eq!(Seeking { paused: true }.next(Play).next(DecoderStarted), Playing);
+        // A second seek keeps waiting
+        assert_eq!(Seeking { paused: true }.next(Seek), Seeking { paused: true });
+    }
+
+    #[test]
+    fn events_that_do_not_apply_are_ignored() {
+        assert_eq!(Stopped.next(Pause), Stopped);
+        assert_eq!(Stopped.next(Seek), Stopped);
+        assert_eq!(Stopped.next(DecoderStarted), Stopped);
+        assert_eq!(Playing.next(Play), Playing);
+        assert_eq!(Playing.next(DecoderStarted), Playing);
+        assert_eq!(Paused.next(Pause), Paused);
+        assert_eq!(Paused.next(DecoderStarted), Paused);
+    }
+
+    #[test]
+    fn player_pauses_the_pump_and_tracks_position() {
+        let mut player = Player::default();
+        player.apply(Play);
+        assert!(!player.paused.load(Ordering::Relaxed));
+        player.apply(Pause);
+        assert!(player.paused.load(Ordering::Relaxed));
+        // Paused while seeking: the pump holds back as soon as the new decoder delivers
+        player.apply(Seek);
+        assert_eq!(player.state, Seeking { paused: true });
+        assert!(player.paused.load(Ordering::Relaxed));
+        player.apply(Play);
+        assert!(!player.paused.load(Ordering::Relaxed));
+
+        player.start_offset = 30.0;
+        player.bytes_played.store(BYTES_PER_SECOND * 5 / 2, Ordering::Relaxed);
+        assert_eq!(player.position(), 32.5);
+        player.stop();
+        assert_eq!(player.state, Stopped);
+    }
+
+    #[test]
+    fn pump_copies_everything_and_reports_the_first_chunk() {
+        let path = format!("pump_test_{}.wav", uuid::Uuid::new_v4());
+        {
+            let mut player = PLAYER.lock().unwrap();
+            player.path = path.clone();
+            player.state = Seeking { paused: false };
+        }
+        let pcm: Vec<u8> = (0..CHUNK_BYTES * 3 + 100).map(|byte| byte as u8).collect();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let bytes_played = Arc::new(AtomicU64::new(0));
+        spawn_pump(
+            path.clone(),
+            std::io::Cursor::new(pcm.clone()),
+            std::io::empty(),
+            SharedSink(received.clone()),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+            bytes_played.clone(),
+        );
+        for _ in 0..500 {
+            if bytes_played.load(Ordering::Relaxed) == pcm.len() as u64 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(*received.lock().unwrap(), pcm);
+        let mut player = PLAYER.lock().unwrap();
+        assert_eq!(player.state, Playing);
+        *player = Player::default();
+    }
+
+    #[test]
+    fn times_show_minutes_and_seconds() {
+        assert_eq!(format_time(0.0), "0:00");
+        assert_eq!(format_time(59.9), "0:59");
+        assert_eq!(format_time(3725.0), "62:05");
+        assert_eq!(format_time(-3.0), "0:00");
+    }
+}
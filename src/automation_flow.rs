@@ -1,13 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use egui::{Pos2, Vec2, Color32};
-use crate::ffmpeg_worker_simple::FFmpegWorker;
 use crate::{log_debug, log_error};
 use crate::codec_manager::CodecManager;
 use crate::comprehensive_codec_registry::{ComprehensiveCodecRegistry, CodecType};
 use crate::comprehensive_ui_components::{ComprehensiveUIComponents, FormatPurpose};
 use crate::app_state::{ProcessingTask, VideoSettings, AudioSettings, OperationType};
 use crate::task_executor::TaskExecutor;
+use crate::workflow_report::{NodeRunRecord, RunOutcome, SessionStats, WorkflowRunReport};
 use anyhow::Result;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use chrono;
@@ -20,6 +20,15 @@ fn generate_unique_id() -> String {
     format!("id_{}", id)
 }
 
+/// Id for a node added in the editor; random so it never clashes with ids in loaded workflows
+pub fn new_node_id() -> String {
+    format!("node_{}", &uuid::Uuid::new_v4().to_string()[..8])
+}
+
+pub fn new_connection_id() -> String {
+    format!("conn_{}", &uuid::Uuid::new_v4().to_string()[..8])
+}
+
 /// Data stream types - define data types that can be passed between nodes
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataType {
@@ -222,9 +231,38 @@ pub enum NodeType {
     ExtractArchive,
     /// Multi-resolution output
     MultiResOutput,
+    
+    /// Nodes collapsed into one; the contents travel in the node's `group`
+    Group,
+    
+    /// Node type this build does not know, from a workflow saved by a newer version
+    Unknown(String),
 }
 
 impl NodeType {
+    /// Every node type that sits between an input and an output, in palette order
+    pub const PROCESSING: &'static [NodeType] = &[
+        NodeType::ExtractAudio, NodeType::AudioResample, NodeType::AudioConvert,
+        NodeType::AudioCompress, NodeType::AudioVolume, NodeType::AudioTrim, NodeType::AudioMerge,
+        NodeType::AudioNormalize, NodeType::AudioDeNoise, NodeType::AudioEqualizer,
+        NodeType::AudioFade, NodeType::AudioEcho, NodeType::AudioSpeed, NodeType::ExtractVideo,
+        NodeType::VideoRecode, NodeType::VideoConvert, NodeType::VideoCompress,
+        NodeType::VideoResize, NodeType::VideoCrop, NodeType::VideoRotate, NodeType::VideoFilter,
+        NodeType::FrameExtract, NodeType::VideoFPS, NodeType::VideoStabilize,
+        NodeType::VideoDeinterlace, NodeType::VideoColorCorrect, NodeType::VideoBrightness,
+        NodeType::VideoSaturation, NodeType::VideoGamma, NodeType::Combine,
+        NodeType::SplitAudioVideo, NodeType::VideoOverlay, NodeType::VideoPiP,
+        NodeType::VideoSideBySide, NodeType::VideoToGif, NodeType::GifResize,
+        NodeType::VideoToImages, NodeType::ImagesToVideo, NodeType::BatchConvert,
+        NodeType::AddSubtitle, NodeType::AddWatermark, NodeType::AddText, NodeType::AddLogo,
+        NodeType::AddTimecode, NodeType::StreamPrep, NodeType::VideoEncrypt, NodeType::VideoDecrypt,
+        NodeType::MultiPassEncode, NodeType::BatchProcess, NodeType::QualityAnalysis,
+        NodeType::FormatValidation, NodeType::AudioVideoSync, NodeType::AudioDelay,
+        NodeType::VideoDelay, NodeType::ExtractMetadata, NodeType::AddMetadata,
+        NodeType::RemoveMetadata, NodeType::CreateArchive, NodeType::ExtractArchive,
+        NodeType::MultiResOutput,
+    ];
+
     /// Get display name for node type
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -240,11 +278,11 @@ impl NodeType {
             NodeType::AudioVolume => "🔊 Audio Volume",
             NodeType::AudioTrim => "✂ Audio Trim",
             NodeType::AudioMerge => "🔗 Audio Merge (FAKE)",
-            NodeType::AudioNormalize => "📊 Audio Normalize (FAKE)",
-            NodeType::AudioDeNoise => "🔇 Audio DeNoise (FAKE)",
-            NodeType::AudioEqualizer => "🎛 Audio EQ (FAKE)",
-            NodeType::AudioFade => "📉 Audio Fade (FAKE)",
-            NodeType::AudioEcho => "🔄 Audio Echo (FAKE)",
+            NodeType::AudioNormalize => "📊 Audio Normalize",
+            NodeType::AudioDeNoise => "🔇 Audio DeNoise",
+            NodeType::AudioEqualizer => "🎛 Audio EQ",
+            NodeType::AudioFade => "📉 Audio Fade",
+            NodeType::AudioEcho => "🔄 Audio Echo",
             NodeType::AudioSpeed => "⏩ Audio Speed (FAKE)",
             
             // Video processing  
@@ -257,8 +295,8 @@ impl NodeType {
             NodeType::VideoRotate => "🔄 Video Rotate",
             NodeType::VideoFilter => "✨ Video Filter",
             NodeType::FrameExtract => "📷 Frame Extract",
-            NodeType::VideoFPS => "⚡ Video FPS (FAKE)", 
-            NodeType::VideoStabilize => "🎯 Video Stabilize (FAKE)",
+            NodeType::VideoFPS => "⚡ Video FPS", 
+            NodeType::VideoStabilize => "🎯 Video Stabilize",
             NodeType::VideoDeinterlace => "📺 Deinterlace (FAKE)",
             NodeType::VideoColorCorrect => "🎨 Color Correct (FAKE)",
             NodeType::VideoBrightness => "☀ Brightness (FAKE)",
@@ -266,32 +304,32 @@ impl NodeType {
             NodeType::VideoGamma => "🔆 Gamma (FAKE)",
             
             // Combination and splitting
-            NodeType::Combine => "🎭 Combine A/V (FAKE)",
+            NodeType::Combine => "🎭 Combine A/V",
             NodeType::SplitAudioVideo => "🎯 Split A/V",
-            NodeType::VideoOverlay => "🖼 Video Overlay (FAKE)",
-            NodeType::VideoPiP => "📱 Picture-in-Picture (FAKE)",
+            NodeType::VideoOverlay => "🖼 Video Overlay",
+            NodeType::VideoPiP => "📱 Picture-in-Picture",
             NodeType::VideoSideBySide => "📐 Side-by-Side (FAKE)",
             
             // Special conversions
             NodeType::VideoToGif => "🎞 Video to GIF",
             NodeType::GifResize => "🖼 GIF Resize",
-            NodeType::VideoToImages => "📸 Video to Images (FAKE)",
-            NodeType::ImagesToVideo => "🎥 Images to Video (FAKE)",
+            NodeType::VideoToImages => "📸 Video to Images",
+            NodeType::ImagesToVideo => "🎥 Images to Video",
             
             // Text and graphics
             NodeType::AddSubtitle => "💬 Add Subtitle",
             NodeType::AddWatermark => "🏷 Add Watermark",
-            NodeType::AddText => "📝 Add Text (FAKE)",
+            NodeType::AddText => "📝 Add Text",
             NodeType::AddLogo => "🏢 Add Logo (FAKE)",
             NodeType::AddTimecode => "⏰ Add Timecode (FAKE)",
             
             // Advanced processing
-            NodeType::StreamPrep => "📡 Stream Prep (FAKE)",
-            NodeType::VideoEncrypt => "🔒 Video Encrypt (FAKE)",
-            NodeType::VideoDecrypt => "🔓 Video Decrypt (FAKE)",
+            NodeType::StreamPrep => "📡 Stream Prep",
+            NodeType::VideoEncrypt => "🔒 Video Encrypt",
+            NodeType::VideoDecrypt => "🔓 Video Decrypt",
             NodeType::MultiPassEncode => "🔄 Multi-Pass Encode",
             NodeType::BatchProcess => "📦 Batch Process (FAKE)",
-            NodeType::QualityAnalysis => "📊 Quality Analysis (FAKE)",
+            NodeType::QualityAnalysis => "📊 Quality Analysis",
             NodeType::FormatValidation => "✅ Format Validation (FAKE)",
             
             // Sync operations
@@ -305,10 +343,12 @@ impl NodeType {
             NodeType::RemoveMetadata => "🗑 Remove Metadata (FAKE)",
             
             // Archive operations
-            NodeType::CreateArchive => "📦 Create Archive (FAKE)",
-            NodeType::ExtractArchive => "📂 Extract Archive (FAKE)",
+            NodeType::CreateArchive => "📦 Create Archive",
+            NodeType::ExtractArchive => "📂 Extract Archive",
             NodeType::MultiResOutput => "📺 Multi-Resolution",
             NodeType::BatchConvert => "🔄 Batch Convert",
+            NodeType::Group => "📦 Group",
+            NodeType::Unknown(_) => "❓ Unknown node",
         }
     }
     
@@ -361,6 +401,9 @@ impl NodeType {
             // Archive operations - Brown tones
             NodeType::CreateArchive | NodeType::ExtractArchive | NodeType::MultiResOutput | NodeType::BatchConvert => 
                 Color32::from_rgb(180, 140, 120),
+            
+            NodeType::Group => Color32::from_rgb(90, 100, 130),
+            NodeType::Unknown(_) => Color32::from_rgb(110, 110, 110),
         }
     }
     
@@ -430,7 +473,10 @@ impl NodeType {
             NodeType::VideoToGif => vec![("video".to_string(), DataType::VideoStream)],
             NodeType::GifResize => vec![("gif".to_string(), DataType::MediaFile)],
             NodeType::VideoToImages => vec![("video".to_string(), DataType::VideoStream)],
-            NodeType::ImagesToVideo => vec![("images".to_string(), DataType::Text)],
+            NodeType::ImagesToVideo => vec![
+                ("images".to_string(), DataType::Text),
+                ("audio".to_string(), DataType::AudioStream),
+            ],
             
             // Text and graphics
             NodeType::AddSubtitle => vec![
@@ -454,7 +500,10 @@ impl NodeType {
             NodeType::VideoDecrypt => vec![("video".to_string(), DataType::VideoStream)],
             NodeType::MultiPassEncode => vec![("video".to_string(), DataType::VideoStream)],
             NodeType::BatchProcess => vec![("inputs".to_string(), DataType::Text)],
-            NodeType::QualityAnalysis => vec![("input".to_string(), DataType::MediaFile)],
+            NodeType::QualityAnalysis => vec![
+                ("reference".to_string(), DataType::MediaFile),
+                ("distorted".to_string(), DataType::MediaFile),
+            ],
             NodeType::FormatValidation => vec![("input".to_string(), DataType::MediaFile)],
             
             // Sync operations
@@ -471,10 +520,13 @@ impl NodeType {
             NodeType::RemoveMetadata => vec![("input".to_string(), DataType::MediaFile)],
             
             // Archive operations
-            NodeType::CreateArchive => vec![("inputs".to_string(), DataType::Text)],
+            NodeType::CreateArchive => vec![("inputs".to_string(), DataType::MediaFile)],
             NodeType::ExtractArchive => vec![("archive".to_string(), DataType::MediaFile)],
             NodeType::MultiResOutput => vec![("input".to_string(), DataType::MediaFile)],
             NodeType::BatchConvert => vec![("inputs".to_string(), DataType::Text)],
+            // Ports of an unknown node come from its saved JSON
+            // A group's ports are those of its contents
+            NodeType::Group | NodeType::Unknown(_) => vec![],
         }
     }
     
@@ -683,90 +735,651 @@ impl NodeType {
                     description: "Flip horizontally".to_string(),
                 },
                 NodeParameter {
-                    name: "flip_vertical".to_string(),
-                    value: "false".to_string(),
-                    param_type: DataType::Boolean,
-                    default_value: "false".to_string(),
-                    description: "Flip vertically".to_string(),
+                    name: "flip_vertical".to_string(),
+                    value: "false".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "false".to_string(),
+                    description: "Flip vertically".to_string(),
+                },
+            ],
+            
+            // Video Filter
+            NodeType::VideoFilter => vec![
+                NodeParameter {
+                    name: "filter_stack".to_string(),
+                    value: "[]".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "[]".to_string(),
+                    description: "Ordered filter stack (JSON, edited in the node properties)".to_string(),
+                },
+            ],
+            
+            // Audio Resample
+            NodeType::AudioResample => vec![
+                NodeParameter {
+                    name: "sample_rate".to_string(),
+                    value: "44100".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "44100".to_string(),
+                    description: "Target sample rate in Hz".to_string(),
+                },
+                NodeParameter {
+                    name: "bit_depth".to_string(),
+                    value: "16".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "16".to_string(),
+                    description: "Bit depth (16, 24, 32)".to_string(),
+                },
+            ],
+            
+            // Audio Volume
+            NodeType::AudioVolume => vec![
+                NodeParameter {
+                    name: "volume".to_string(),
+                    value: "1.0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "1.0".to_string(),
+                    description: "Volume multiplier (0.0 to 2.0)".to_string(),
+                },
+                NodeParameter {
+                    name: "normalize".to_string(),
+                    value: "false".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "false".to_string(),
+                    description: "Normalize audio levels".to_string(),
+                },
+            ],
+            
+            // Audio Trim
+            NodeType::AudioTrim => vec![
+                NodeParameter {
+                    name: "start_time".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0".to_string(),
+                    description: "Start time in seconds".to_string(),
+                },
+                NodeParameter {
+                    name: "duration".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0".to_string(),
+                    description: "Duration in seconds (0=until end)".to_string(),
+                },
+            ],
+            
+            // Audio DeNoise
+            NodeType::AudioDeNoise => vec![
+                NodeParameter {
+                    name: "denoise".to_string(),
+                    value: default_denoise_param(),
+                    param_type: DataType::Text,
+                    default_value: default_denoise_param(),
+                    description: "Noise reduction settings (JSON, edited in the node properties)".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "wav".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "wav".to_string(),
+                    description: "Output audio format".to_string(),
+                },
+            ],
+            
+            // Audio Echo
+            NodeType::AudioEcho => vec![
+                NodeParameter {
+                    name: "in_gain".to_string(),
+                    value: "0.6".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0.6".to_string(),
+                    description: "Input gain (0-1)".to_string(),
+                },
+                NodeParameter {
+                    name: "out_gain".to_string(),
+                    value: "0.3".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0.3".to_string(),
+                    description: "Output gain (0-1)".to_string(),
+                },
+                NodeParameter {
+                    name: "delays".to_string(),
+                    value: "1000".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "1000".to_string(),
+                    description: "Echo delays in ms, separated by |".to_string(),
+                },
+                NodeParameter {
+                    name: "decays".to_string(),
+                    value: "0.5".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "0.5".to_string(),
+                    description: "Echo loudness (0-1) per delay, separated by |".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "wav".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "wav".to_string(),
+                    description: "Output audio format".to_string(),
+                },
+            ],
+            
+            // Audio Equalizer
+            NodeType::AudioEqualizer => vec![
+                NodeParameter {
+                    name: "bass".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0".to_string(),
+                    description: "Bass gain in dB (-20 to 20)".to_string(),
+                },
+                NodeParameter {
+                    name: "mid".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0".to_string(),
+                    description: "Mid gain in dB (-20 to 20)".to_string(),
+                },
+                NodeParameter {
+                    name: "treble".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Number,
+                    default_value: "0".to_string(),
+                    description: "Treble gain in dB (-20 to 20)".to_string(),
+                },
+                NodeParameter {
+                    name: "advanced".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Extra audio filters appended after the bands".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "wav".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "wav".to_string(),
+                    description: "Output audio format".to_string(),
+                },
+            ],
+            
+            // Audio Normalize
+            NodeType::AudioNormalize => vec![
+                NodeParameter {
+                    name: "loudness".to_string(),
+                    value: default_loudness_param(),
+                    param_type: DataType::Text,
+                    default_value: default_loudness_param(),
+                    description: "Target loudness, true peak and loudness range (JSON, edited in the node properties)".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "wav".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "wav".to_string(),
+                    description: "Output audio format".to_string(),
+                },
+            ],
+            
+            // Audio Fade
+            NodeType::AudioFade => vec![
+                NodeParameter {
+                    name: "fade_in_seconds".to_string(),
+                    value: "1".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "1".to_string(),
+                    description: "Fade-in duration in seconds (0 = none)".to_string(),
+                },
+                NodeParameter {
+                    name: "fade_out_seconds".to_string(),
+                    value: "1".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "1".to_string(),
+                    description: "Fade-out duration in seconds, ending with the audio (0 = none)".to_string(),
+                },
+                NodeParameter {
+                    name: "curve".to_string(),
+                    value: "tri".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "tri".to_string(),
+                    description: "Fade curve: tri, qsin, esin, log".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "wav".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "wav".to_string(),
+                    description: "Output audio format".to_string(),
+                },
+            ],
+            
+            // Video Stabilize
+            NodeType::VideoStabilize => vec![
+                NodeParameter {
+                    name: "smoothing".to_string(),
+                    value: "10".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "10".to_string(),
+                    description: "Frames averaged into the camera path (0 = static camera)".to_string(),
+                },
+                NodeParameter {
+                    name: "zoom".to_string(),
+                    value: "0".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "0".to_string(),
+                    description: "Extra zoom in percent, negative zooms out".to_string(),
+                },
+                NodeParameter {
+                    name: "optblur".to_string(),
+                    value: "false".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "false".to_string(),
+                    description: "Sharpen the result against the transform's blur: true, false".to_string(),
+                },
+            ],
+            
+            // Video Overlay
+            NodeType::VideoOverlay => vec![
+                NodeParameter {
+                    name: "x".to_string(),
+                    value: "10".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "10".to_string(),
+                    description: "Overlay x position: pixels or an expression like main_w-overlay_w-10".to_string(),
+                },
+                NodeParameter {
+                    name: "y".to_string(),
+                    value: "10".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "10".to_string(),
+                    description: "Overlay y position: pixels or an expression like main_h-overlay_h-10".to_string(),
+                },
+                NodeParameter {
+                    name: "opacity".to_string(),
+                    value: "1.0".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "1.0".to_string(),
+                    description: "Overlay opacity: 0.0 (invisible) to 1.0 (opaque)".to_string(),
+                },
+                NodeParameter {
+                    name: "audio".to_string(),
+                    value: "main".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "main".to_string(),
+                    description: "Audio: main (main video only), mix (both inputs)".to_string(),
+                },
+            ],
+            
+            // Picture-in-Picture
+            NodeType::VideoPiP => vec![
+                NodeParameter {
+                    name: "pip_scale".to_string(),
+                    value: "30".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "30".to_string(),
+                    description: "Inset width in percent of the main video's width".to_string(),
+                },
+                NodeParameter {
+                    name: "position".to_string(),
+                    value: "bottom_right".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "bottom_right".to_string(),
+                    description: "Inset position: top_left, top_right, bottom_left, bottom_right, center".to_string(),
+                },
+                NodeParameter {
+                    name: "margin".to_string(),
+                    value: "20".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "20".to_string(),
+                    description: "Distance from the edges in pixels".to_string(),
+                },
+                NodeParameter {
+                    name: "audio".to_string(),
+                    value: "main".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "main".to_string(),
+                    description: "Audio: main (main video only), mix (both inputs)".to_string(),
+                },
+            ],
+            
+            // Video to Images
+            NodeType::VideoToImages => vec![
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "png".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "png".to_string(),
+                    description: "Image format: png, jpg, webp".to_string(),
+                },
+                NodeParameter {
+                    name: "jpg_quality".to_string(),
+                    value: "2".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "2".to_string(),
+                    description: "JPEG quality: 1 (best) to 31".to_string(),
+                },
+                NodeParameter {
+                    name: "fps".to_string(),
+                    value: "all".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "all".to_string(),
+                    description: "Frames per second to keep, or all for every frame".to_string(),
+                },
+                NodeParameter {
+                    name: "name_pattern".to_string(),
+                    value: "frame_%06d".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "frame_%06d".to_string(),
+                    description: "File name with one %d or %0Nd for the frame number".to_string(),
+                },
+                NodeParameter {
+                    name: "output_dir".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Folder for the images; empty uses the workflow's scratch folder".to_string(),
+                },
+            ],
+            
+            // Images to Video
+            NodeType::ImagesToVideo => vec![
+                NodeParameter {
+                    name: "directory".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Image folder, used when no Video to Images node is connected".to_string(),
+                },
+                NodeParameter {
+                    name: "pattern".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Empty for every image in the folder, a numbered pattern (frame_%06d.png) or a glob (*.jpg)".to_string(),
+                },
+                NodeParameter {
+                    name: "fps".to_string(),
+                    value: "25".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "25".to_string(),
+                    description: "Frame rate of the video".to_string(),
+                },
+                NodeParameter {
+                    name: "codec".to_string(),
+                    value: "libx264".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "libx264".to_string(),
+                    description: "Video codec: libx264, libx265, libvpx-vp9, prores_ks, ffv1".to_string(),
+                },
+                NodeParameter {
+                    name: "pixel_format".to_string(),
+                    value: "yuv420p".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "yuv420p".to_string(),
+                    description: "Pixel format; yuv420p plays everywhere".to_string(),
+                },
+                NodeParameter {
+                    name: "audio_file".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Soundtrack, used when nothing is connected to the audio input".to_string(),
+                },
+            ],
+            
+            // Quality Analysis
+            NodeType::QualityAnalysis => vec![
+                NodeParameter {
+                    name: "metrics".to_string(),
+                    value: "psnr,ssim".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "psnr,ssim".to_string(),
+                    description: "Metrics to compute: psnr, ssim, vmaf (vmaf needs an ffmpeg with libvmaf)".to_string(),
+                },
+                NodeParameter {
+                    name: "csv_path".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Optional CSV file for the per-frame scores".to_string(),
+                },
+            ],
+            
+            // Multi-Resolution
+            NodeType::MultiResOutput => vec![
+                NodeParameter {
+                    name: "renditions".to_string(),
+                    value: "1080:5000k:192k;720:2500k:128k;480:1000k:96k".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "1080:5000k:192k;720:2500k:128k;480:1000k:96k".to_string(),
+                    description: "Renditions as height:video bitrate:audio bitrate, separated by ;".to_string(),
+                },
+                NodeParameter {
+                    name: "video_codec".to_string(),
+                    value: "libx264".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "libx264".to_string(),
+                    description: "Video encoder for every rendition, software or hardware".to_string(),
+                },
+                NodeParameter {
+                    name: "container".to_string(),
+                    value: "mp4".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "mp4".to_string(),
+                    description: "Container: mp4, mkv, mov or webm".to_string(),
+                },
+                NodeParameter {
+                    name: "basename".to_string(),
+                    value: "output".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "output".to_string(),
+                    description: "Outputs are named <basename>_<height>p.<container>".to_string(),
+                },
+                NodeParameter {
+                    name: "output_dir".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Folder for the renditions; empty uses the workflow's scratch folder".to_string(),
+                },
+            ],
+            
+            // Stream Prep
+            NodeType::StreamPrep => vec![
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "hls".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "hls".to_string(),
+                    description: "Streaming format: hls or dash".to_string(),
+                },
+                NodeParameter {
+                    name: "segment_seconds".to_string(),
+                    value: "6".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "6".to_string(),
+                    description: "Segment duration in seconds".to_string(),
+                },
+                NodeParameter {
+                    name: "playlist_type".to_string(),
+                    value: "vod".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "vod".to_string(),
+                    description: "HLS playlist type: vod or event".to_string(),
+                },
+                NodeParameter {
+                    name: "fragment_seconds".to_string(),
+                    value: "2".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "2".to_string(),
+                    description: "DASH fragment duration in seconds".to_string(),
+                },
+                NodeParameter {
+                    name: "video_codec".to_string(),
+                    value: "auto".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "auto".to_string(),
+                    description: "auto (copy H.264/HEVC, else encode H.264), copy, libx264 or libx265".to_string(),
+                },
+                NodeParameter {
+                    name: "audio_codec".to_string(),
+                    value: "auto".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "auto".to_string(),
+                    description: "auto (copy AAC, else encode AAC), copy or aac".to_string(),
+                },
+                NodeParameter {
+                    name: "name".to_string(),
+                    value: "stream".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "stream".to_string(),
+                    description: "Playlist or manifest name; segments are named after it".to_string(),
+                },
+                NodeParameter {
+                    name: "output_dir".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Folder for the playlists and segments; empty uses the workflow's scratch folder".to_string(),
+                },
+            ],
+            
+            // Video Encrypt
+            NodeType::VideoEncrypt => vec![
+                NodeParameter {
+                    name: "key_file".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "AES-128 key file; empty keeps <name>.key next to the playlist".to_string(),
+                },
+                NodeParameter {
+                    name: "generate_key".to_string(),
+                    value: "true".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "true".to_string(),
+                    description: "Write a new random key instead of reusing the key file".to_string(),
+                },
+                NodeParameter {
+                    name: "segment_seconds".to_string(),
+                    value: "6".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "6".to_string(),
+                    description: "Segment duration in seconds".to_string(),
+                },
+                NodeParameter {
+                    name: "name".to_string(),
+                    value: "encrypted".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "encrypted".to_string(),
+                    description: "Playlist name; segments and the default key are named after it".to_string(),
+                },
+                NodeParameter {
+                    name: "output_dir".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Folder for the playlist, segments and key; empty uses the workflow's scratch folder".to_string(),
+                },
+            ],
+            
+            // Video Decrypt
+            NodeType::VideoDecrypt => vec![
+                NodeParameter {
+                    name: "key_file".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "AES-128 key file; empty uses the key the playlist names".to_string(),
+                },
+                NodeParameter {
+                    name: "container".to_string(),
+                    value: "mp4".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "mp4".to_string(),
+                    description: "Output container: mp4 or mkv".to_string(),
                 },
             ],
             
-            // Video Filter
-            NodeType::VideoFilter => vec![
+            // Add Text
+            NodeType::AddText => vec![
                 NodeParameter {
-                    name: "brightness".to_string(),
-                    value: "0.0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "0.0".to_string(),
-                    description: "Brightness adjustment (-1.0 to 1.0)".to_string(),
+                    name: "text".to_string(),
+                    value: "Sample Text".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "Sample Text".to_string(),
+                    description: "Text to draw; ':', quotes and '%' are drawn as typed".to_string(),
                 },
                 NodeParameter {
-                    name: "contrast".to_string(),
-                    value: "1.0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "1.0".to_string(),
-                    description: "Contrast adjustment (0.0 to 3.0)".to_string(),
+                    name: "font".to_string(),
+                    value: crate::text_overlay::default_font().to_string(),
+                    param_type: DataType::Text,
+                    default_value: crate::text_overlay::default_font().to_string(),
+                    description: "Font family, font file name or path; empty uses ffmpeg's default font".to_string(),
                 },
                 NodeParameter {
-                    name: "saturation".to_string(),
-                    value: "1.0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "1.0".to_string(),
-                    description: "Saturation adjustment (0.0 to 3.0)".to_string(),
+                    name: "font_size".to_string(),
+                    value: "48".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "48".to_string(),
+                    description: "Font size in pixels".to_string(),
                 },
-            ],
-            
-            // Audio Resample
-            NodeType::AudioResample => vec![
                 NodeParameter {
-                    name: "sample_rate".to_string(),
-                    value: "44100".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "44100".to_string(),
-                    description: "Target sample rate in Hz".to_string(),
+                    name: "color".to_string(),
+                    value: "0xFFFFFFFF".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "0xFFFFFFFF".to_string(),
+                    description: "Text color with alpha, 0xRRGGBBAA".to_string(),
                 },
                 NodeParameter {
-                    name: "bit_depth".to_string(),
-                    value: "16".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "16".to_string(),
-                    description: "Bit depth (16, 24, 32)".to_string(),
+                    name: "position".to_string(),
+                    value: "bottom_center".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "bottom_center".to_string(),
+                    description: "Position: top_left, top_center, top_right, center, bottom_left, bottom_center, bottom_right, custom".to_string(),
                 },
-            ],
-            
-            // Audio Volume
-            NodeType::AudioVolume => vec![
                 NodeParameter {
-                    name: "volume".to_string(),
-                    value: "1.0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "1.0".to_string(),
-                    description: "Volume multiplier (0.0 to 2.0)".to_string(),
+                    name: "x".to_string(),
+                    value: "(w-tw)/2".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "(w-tw)/2".to_string(),
+                    description: "Custom x expression; w is the frame width, tw the text width".to_string(),
                 },
                 NodeParameter {
-                    name: "normalize".to_string(),
+                    name: "y".to_string(),
+                    value: "h-th-40".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "h-th-40".to_string(),
+                    description: "Custom y expression; h is the frame height, th the text height".to_string(),
+                },
+                NodeParameter {
+                    name: "box".to_string(),
                     value: "false".to_string(),
-                    param_type: DataType::Boolean,
+                    param_type: DataType::Text,
                     default_value: "false".to_string(),
-                    description: "Normalize audio levels".to_string(),
+                    description: "Draw a box behind the text (true/false)".to_string(),
                 },
-            ],
-            
-            // Audio Trim
-            NodeType::AudioTrim => vec![
                 NodeParameter {
-                    name: "start_time".to_string(),
-                    value: "0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "0".to_string(),
-                    description: "Start time in seconds".to_string(),
+                    name: "box_color".to_string(),
+                    value: "0x00000080".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "0x00000080".to_string(),
+                    description: "Box color with alpha, 0xRRGGBBAA".to_string(),
                 },
                 NodeParameter {
-                    name: "duration".to_string(),
-                    value: "0".to_string(),
-                    param_type: DataType::Number,
-                    default_value: "0".to_string(),
-                    description: "Duration in seconds (0=until end)".to_string(),
+                    name: "start".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Seconds the text appears at; empty shows it from the start".to_string(),
+                },
+                NodeParameter {
+                    name: "end".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Seconds the text disappears at; empty shows it until the end".to_string(),
                 },
             ],
             
@@ -786,6 +1399,27 @@ impl NodeType {
                     default_value: "auto".to_string(),
                     description: "Audio sync mode (auto, async)".to_string(),
                 },
+                NodeParameter {
+                    name: "copy_streams".to_string(),
+                    value: "true".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "true".to_string(),
+                    description: "Copy the audio as well as the video without re-encoding; a stream the output container cannot hold is encoded".to_string(),
+                },
+                NodeParameter {
+                    name: "shortest".to_string(),
+                    value: "false".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "false".to_string(),
+                    description: "End the output with the shorter of the video and audio".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "mp4".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "mp4".to_string(),
+                    description: "Output container format".to_string(),
+                },
             ],
             
             // VideoToGif
@@ -838,6 +1472,49 @@ impl NodeType {
                 },
             ],
             
+            // CreateArchive
+            NodeType::CreateArchive => vec![
+                NodeParameter {
+                    name: "archive_path".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Archive file to write; empty writes to the workflow temp folder".to_string(),
+                },
+                NodeParameter {
+                    name: "format".to_string(),
+                    value: "zip".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "zip".to_string(),
+                    description: "Archive format when no path is given (zip, tar.gz)".to_string(),
+                },
+                NodeParameter {
+                    name: "include_probe".to_string(),
+                    value: "true".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "true".to_string(),
+                    description: "Add ffprobe information of each file to the manifest".to_string(),
+                },
+            ],
+
+            // ExtractArchive
+            NodeType::ExtractArchive => vec![
+                NodeParameter {
+                    name: "output_dir".to_string(),
+                    value: "".to_string(),
+                    param_type: DataType::Text,
+                    default_value: "".to_string(),
+                    description: "Folder to restore the files into; empty uses the workflow temp folder".to_string(),
+                },
+                NodeParameter {
+                    name: "verify_checksums".to_string(),
+                    value: "true".to_string(),
+                    param_type: DataType::Boolean,
+                    default_value: "true".to_string(),
+                    description: "Fail when a file does not match the SHA-256 in the manifest".to_string(),
+                },
+            ],
+
             // Default case for other node types
             _ => vec![],
         }
@@ -930,6 +1607,8 @@ impl NodeType {
             NodeType::ExtractArchive => vec![("outputs".to_string(), DataType::Text)],
             NodeType::MultiResOutput => vec![("outputs".to_string(), DataType::Text)],
             NodeType::BatchConvert => vec![("outputs".to_string(), DataType::Text)],
+            // A group's ports are those of its contents
+            NodeType::Group | NodeType::Unknown(_) => vec![],
         }
     }
 }
@@ -993,6 +1672,12 @@ pub struct AutomationNode {
     pub output_ports: Vec<NodePort>,
     pub parameters: HashMap<String, NodeParameter>,
     pub enabled: bool,
+    /// JSON an Unknown node was loaded from; written back unchanged apart from the position
+    #[serde(skip)]
+    pub raw_json: Option<serde_json::Value>,
+    /// Contents of a Group node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<Box<NodeGroup>>,
 }
 
 fn serialize_pos2<S>(pos: &Pos2, serializer: S) -> Result<S::Ok, S::Error>
@@ -1027,6 +1712,261 @@ where
     Ok(Vec2::from(serializable_vec))
 }
 
+/// AudioDeNoise nodes reduce noise by default
+fn default_denoise_param() -> String {
+    let denoise = crate::audio_denoise::AudioDenoiseSettings { enabled: true, ..Default::default() };
+    serde_json::to_string(&denoise).unwrap_or_default()
+}
+
+/// Noise reduction stored as JSON in the "denoise" parameter; other nodes have none and stay disabled
+pub(crate) fn denoise_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::audio_denoise::AudioDenoiseSettings {
+    parameters.get("denoise")
+        .and_then(|p| serde_json::from_str(&p.value).ok())
+        .unwrap_or_default()
+}
+
+/// Frame rate conversion of a VideoFPS node; nodes saved before the mode existed have "fps" and "filter"
+pub(crate) fn fps_conversion_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::fps_conversion::FpsConversionSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::fps_conversion::FpsConversionSettings::default();
+    crate::fps_conversion::FpsConversionSettings {
+        target_fps: value("target_fps").or(value("fps")).map(str::to_string).unwrap_or(defaults.target_fps),
+        mode: value("mode").or(value("filter")).and_then(crate::fps_conversion::FpsMode::from_key).unwrap_or(defaults.mode),
+        mi_mode: value("mi_mode").map(str::to_string).unwrap_or(defaults.mi_mode),
+        mc_mode: value("mc_mode").map(str::to_string).unwrap_or(defaults.mc_mode),
+    }
+}
+
+/// Fades of an AudioFade node; durations accept "1,5", "1.5" or "00:01.5"
+fn fade_from_params(parameters: &HashMap<String, NodeParameter>) -> Result<crate::audio_fade::AudioFadeSettings, String> {
+    let seconds = |name: &str| -> Result<f64, String> {
+        match parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty()) {
+            Some(value) => crate::locale_format::parse_duration(value)
+                .map_err(|_| format!("Invalid {} duration '{}'", name, value)),
+            None => Ok(0.0),
+        }
+    };
+    let curve = parameters.get("curve").map(|p| p.value.as_str()).unwrap_or("tri");
+    Ok(crate::audio_fade::AudioFadeSettings {
+        fade_in_seconds: seconds("fade_in_seconds")?,
+        fade_out_seconds: seconds("fade_out_seconds")?,
+        curve: crate::audio_fade::FadeCurve::from_key(curve).ok_or_else(|| format!("Unknown fade curve '{}'", curve))?,
+    })
+}
+
+/// aecho settings of an AudioEcho node; unparsable delay/decay lists fall back to one echo
+pub(crate) fn echo_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::audio_echo::EchoSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::audio_echo::EchoSettings::default();
+    let gain = |name: &str, default: f32| value(name).and_then(|v| crate::locale_format::parse_decimal(v).ok()).map(|v| v as f32).unwrap_or(default);
+    crate::audio_echo::EchoSettings {
+        in_gain: gain("in_gain", defaults.in_gain),
+        out_gain: gain("out_gain", defaults.out_gain),
+        taps: value("delays")
+            .and_then(|delays| crate::audio_echo::EchoSettings::parse_taps(delays, value("decays").unwrap_or("0.5")))
+            .unwrap_or(defaults.taps),
+    }
+}
+
+/// Bands and advanced chain of an AudioEqualizer node
+pub(crate) fn equalizer_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::audio_equalizer::EqualizerSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::audio_equalizer::EqualizerSettings::default();
+    let db = |name: &str, default: f32| value(name).and_then(|v| crate::locale_format::parse_decimal(v).ok()).map(|v| v as f32).unwrap_or(default);
+    crate::audio_equalizer::EqualizerSettings {
+        bass_db: db("bass", defaults.bass_db),
+        mid_db: db("mid", defaults.mid_db),
+        treble_db: db("treble", defaults.treble_db),
+        advanced: value("advanced").map(str::to_string).unwrap_or(defaults.advanced),
+    }
+}
+
+/// vid.stab settings of a VideoStabilize node
+fn stabilize_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::stabilize::StabilizeSettings {
+    let defaults = crate::stabilize::StabilizeSettings::default();
+    crate::stabilize::StabilizeSettings {
+        smoothing: parameters.get("smoothing").and_then(|p| p.value.trim().parse().ok()).unwrap_or(defaults.smoothing),
+        zoom: parameters.get("zoom").and_then(|p| p.value.trim().parse().ok()).unwrap_or(defaults.zoom),
+        optblur: parameters.get("optblur").map(|p| p.value == "true").unwrap_or(defaults.optblur),
+    }
+}
+
+/// Overlay position, opacity and audio of a VideoOverlay node
+fn overlay_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::overlay::OverlaySettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::overlay::OverlaySettings::default();
+    crate::overlay::OverlaySettings {
+        x: value("x").map(str::to_string).unwrap_or(defaults.x),
+        y: value("y").map(str::to_string).unwrap_or(defaults.y),
+        opacity: value("opacity").and_then(|value| value.parse().ok()).unwrap_or(defaults.opacity),
+        audio: value("audio").and_then(crate::overlay::OverlayAudio::from_key).unwrap_or(defaults.audio),
+    }
+}
+
+/// Inset size, position and audio of a VideoPiP node
+fn pip_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::overlay::PipSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::overlay::PipSettings::default();
+    crate::overlay::PipSettings {
+        scale_percent: value("pip_scale").and_then(|value| value.parse().ok()).unwrap_or(defaults.scale_percent),
+        anchor: value("position").and_then(crate::overlay::PipAnchor::from_key).unwrap_or(defaults.anchor),
+        margin: value("margin").and_then(|value| value.parse().ok()).unwrap_or(defaults.margin),
+        audio: value("audio").and_then(crate::overlay::OverlayAudio::from_key).unwrap_or(defaults.audio),
+    }
+}
+
+/// Text, font, colors, position and time range of an AddText node. The text and font are taken as
+/// typed: spaces can matter in the text and an empty font means ffmpeg's default.
+pub(crate) fn text_overlay_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::text_overlay::TextOverlaySettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let seconds = |name: &str| value(name).and_then(|value| value.parse().ok());
+    let defaults = crate::text_overlay::TextOverlaySettings::default();
+    crate::text_overlay::TextOverlaySettings {
+        text: parameters.get("text").map(|p| p.value.clone()).unwrap_or(defaults.text),
+        font: parameters.get("font").map(|p| p.value.trim().to_string()).unwrap_or(defaults.font),
+        font_size: value("font_size").and_then(|value| value.parse().ok()).unwrap_or(defaults.font_size),
+        color: value("color").and_then(crate::text_overlay::parse_color).unwrap_or(defaults.color),
+        position: value("position").and_then(crate::text_overlay::TextPosition::from_key).unwrap_or(defaults.position),
+        x: value("x").map(str::to_string).unwrap_or(defaults.x),
+        y: value("y").map(str::to_string).unwrap_or(defaults.y),
+        background_box: value("box").map(|value| value == "true").unwrap_or(defaults.background_box),
+        box_color: value("box_color").and_then(crate::text_overlay::parse_color).unwrap_or(defaults.box_color),
+        start_seconds: seconds("start"),
+        end_seconds: seconds("end"),
+    }
+}
+
+/// Image format, rate, names and folder of a VideoToImages node
+fn image_extract_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::image_sequence::ExtractSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::image_sequence::ExtractSettings::default();
+    crate::image_sequence::ExtractSettings {
+        format: value("format").and_then(crate::image_sequence::ImageFormat::from_extension).unwrap_or(defaults.format),
+        jpg_quality: value("jpg_quality").and_then(|value| value.parse().ok()).unwrap_or(defaults.jpg_quality),
+        rate: match value("fps") {
+            Some(fps) if !fps.eq_ignore_ascii_case("all") => crate::frame_rate::FrameRate::parse(fps),
+            _ => None,
+        },
+        name_pattern: value("name_pattern").map(str::to_string).unwrap_or(defaults.name_pattern),
+        output_dir: value("output_dir").map(str::to_string).unwrap_or(defaults.output_dir),
+    }
+}
+
+/// Image folder, pattern and encoding of an ImagesToVideo node
+fn image_sequence_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::image_sequence::SequenceSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::image_sequence::SequenceSettings::default();
+    crate::image_sequence::SequenceSettings {
+        directory: value("directory").map(str::to_string).unwrap_or(defaults.directory),
+        pattern: value("pattern").map(str::to_string).unwrap_or(defaults.pattern),
+        rate: value("fps").and_then(crate::frame_rate::FrameRate::parse).unwrap_or(defaults.rate),
+        codec: value("codec").map(str::to_string).unwrap_or(defaults.codec),
+        pixel_format: value("pixel_format").map(str::to_string).unwrap_or(defaults.pixel_format),
+        audio_file: value("audio_file").map(str::to_string).unwrap_or(defaults.audio_file),
+    }
+}
+
+/// Metrics and per-frame export of a QualityAnalysis node
+fn quality_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::quality_metrics::QualitySettings {
+    let defaults = crate::quality_metrics::QualitySettings::default();
+    crate::quality_metrics::QualitySettings {
+        metrics: parameters.get("metrics")
+            .map(|p| crate::quality_metrics::QualitySettings::parse_metrics(&p.value))
+            .unwrap_or(defaults.metrics),
+        csv_path: parameters.get("csv_path").map(|p| p.value.trim().to_string()).unwrap_or(defaults.csv_path),
+    }
+}
+
+/// Renditions, encoder and output naming of a MultiResOutput node
+fn renditions_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::renditions::RenditionSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::renditions::RenditionSettings::default();
+    crate::renditions::RenditionSettings {
+        renditions: parameters.get("renditions")
+            .map(|p| crate::renditions::parse_renditions(&p.value))
+            .unwrap_or(defaults.renditions),
+        video_codec: value("video_codec").map(str::to_string).unwrap_or(defaults.video_codec),
+        container: value("container").map(str::to_string).unwrap_or(defaults.container),
+        basename: value("basename").map(str::to_string).unwrap_or(defaults.basename),
+        output_dir: value("output_dir").map(str::to_string).unwrap_or(defaults.output_dir),
+    }
+}
+
+/// Format, segmenting, codecs and output naming of a StreamPrep node
+fn stream_packaging_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::stream_packaging::StreamSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::stream_packaging::StreamSettings::default();
+    crate::stream_packaging::StreamSettings {
+        format: value("format").and_then(crate::stream_packaging::StreamFormat::from_key).unwrap_or(defaults.format),
+        segment_seconds: value("segment_seconds").and_then(|value| value.parse().ok()).unwrap_or(defaults.segment_seconds),
+        playlist_type: value("playlist_type").and_then(crate::stream_packaging::PlaylistType::from_key).unwrap_or(defaults.playlist_type),
+        fragment_seconds: value("fragment_seconds").and_then(|value| value.parse().ok()).unwrap_or(defaults.fragment_seconds),
+        video_codec: value("video_codec").map(str::to_string).unwrap_or(defaults.video_codec),
+        audio_codec: value("audio_codec").map(str::to_string).unwrap_or(defaults.audio_codec),
+        name: value("name").map(str::to_string).unwrap_or(defaults.name),
+        output_dir: value("output_dir").map(str::to_string).unwrap_or(defaults.output_dir),
+    }
+}
+
+/// Key, segmenting and output naming of a VideoEncrypt node
+fn encrypt_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::hls_encryption::EncryptSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::hls_encryption::EncryptSettings::default();
+    crate::hls_encryption::EncryptSettings {
+        key_file: value("key_file").map(str::to_string).unwrap_or(defaults.key_file),
+        generate_key: value("generate_key").map(|value| value == "true").unwrap_or(defaults.generate_key),
+        segment_seconds: value("segment_seconds").and_then(|value| value.parse().ok()).unwrap_or(defaults.segment_seconds),
+        name: value("name").map(str::to_string).unwrap_or(defaults.name),
+        output_dir: value("output_dir").map(str::to_string).unwrap_or(defaults.output_dir),
+    }
+}
+
+/// Key and output container of a VideoDecrypt node
+fn decrypt_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::hls_encryption::DecryptSettings {
+    let value = |name: &str| parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty());
+    let defaults = crate::hls_encryption::DecryptSettings::default();
+    crate::hls_encryption::DecryptSettings {
+        key_file: value("key_file").map(str::to_string).unwrap_or(defaults.key_file),
+        container: value("container").map(str::to_string).unwrap_or(defaults.container),
+    }
+}
+
+fn default_loudness_param() -> String {
+    serde_json::to_string(&crate::loudness::LoudnessTarget::default()).unwrap_or_default()
+}
+
+/// Loudness targets stored as JSON in the "loudness" parameter
+fn loudness_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::loudness::LoudnessTarget {
+    parameters.get("loudness")
+        .and_then(|p| serde_json::from_str(&p.value).ok())
+        .unwrap_or_default()
+}
+
+fn default_mix_param() -> String {
+    serde_json::to_string(&crate::audio_mix::AudioMixSettings::default()).unwrap_or_default()
+}
+
+/// Mix options stored as JSON in the "mix" parameter; older AudioMerge nodes only had a "duration" parameter
+fn mix_from_params(parameters: &HashMap<String, NodeParameter>) -> crate::audio_mix::AudioMixSettings {
+    parameters.get("mix")
+        .and_then(|p| serde_json::from_str(&p.value).ok())
+        .unwrap_or_else(|| crate::audio_mix::AudioMixSettings {
+            duration: parameters.get("duration")
+                .and_then(|p| crate::audio_mix::MixDuration::from_key(&p.value))
+                .unwrap_or(crate::audio_mix::MixDuration::Longest),
+            ..Default::default()
+        })
+}
+
+/// AudioMerge nodes from before the mode selector carried "mix_mode" and always mixed
+fn merge_mode_from_params(parameters: &HashMap<String, NodeParameter>) -> String {
+    match (parameters.get("merge_mode"), parameters.get("mix_mode")) {
+        (Some(mode), _) => mode.value.clone(),
+        (None, Some(_)) => crate::audio_mix::MODE_MIX.to_string(),
+        (None, None) => crate::audio_mix::MODE_CONCAT.to_string(),
+    }
+}
+
 impl AutomationNode {
     pub fn new(id: String, node_type: NodeType, position: Pos2) -> Self {
         let input_port_configs = node_type.get_input_ports();
@@ -1550,26 +2490,19 @@ impl AutomationNode {
                 });
             },
             NodeType::AudioMerge => {
-                parameters.insert("mix_mode".to_string(), NodeParameter {
-                    name: "Mix Mode".to_string(),
-                    value: "amix".to_string(),
+                parameters.insert("merge_mode".to_string(), NodeParameter {
+                    name: "Merge Mode".to_string(),
+                    value: crate::audio_mix::MODE_MIX.to_string(),
                     param_type: DataType::Text,
-                    default_value: "amix".to_string(),
-                    description: "Mix mode: amix, amerge, join".to_string(),
-                });
-                parameters.insert("inputs".to_string(), NodeParameter {
-                    name: "Input Count".to_string(),
-                    value: "2".to_string(),
-                    param_type: DataType::Text,
-                    default_value: "2".to_string(),
-                    description: "Number of inputs to mix: 2, 3, 4, 5".to_string(),
+                    default_value: crate::audio_mix::MODE_MIX.to_string(),
+                    description: "Merge mode: concat, mix".to_string(),
                 });
-                parameters.insert("duration".to_string(), NodeParameter {
-                    name: "Duration".to_string(),
-                    value: "longest".to_string(),
+                parameters.insert("mix".to_string(), NodeParameter {
+                    name: "mix".to_string(),
+                    value: default_mix_param(),
                     param_type: DataType::Text,
-                    default_value: "longest".to_string(),
-                    description: "Output duration: shortest, longest, first".to_string(),
+                    default_value: default_mix_param(),
+                    description: "Gains, normalize, duration and crossfade (JSON, edited in the node properties)".to_string(),
                 });
             },
 
@@ -1597,19 +2530,34 @@ impl AutomationNode {
                 });
             },
             NodeType::VideoFPS => {
-                parameters.insert("fps".to_string(), NodeParameter {
+                let defaults = crate::fps_conversion::FpsConversionSettings::default();
+                parameters.insert("target_fps".to_string(), NodeParameter {
                     name: "Target FPS".to_string(),
-                    value: "30".to_string(),
+                    value: defaults.target_fps.clone(),
                     param_type: DataType::Text,
-                    default_value: "30".to_string(),
-                    description: "Target frame rate: 24, 25, 30, 50, 60".to_string(),
+                    default_value: defaults.target_fps,
+                    description: "Target frame rate, e.g. 24, 30, 60 or 24000/1001".to_string(),
                 });
-                parameters.insert("filter".to_string(), NodeParameter {
-                    name: "Filter".to_string(),
-                    value: "fps".to_string(),
+                parameters.insert("mode".to_string(), NodeParameter {
+                    name: "Mode".to_string(),
+                    value: defaults.mode.key().to_string(),
+                    param_type: DataType::Text,
+                    default_value: defaults.mode.key().to_string(),
+                    description: "fps (drop/duplicate frames), minterpolate (synthesize frames), speed_change (keep every frame)".to_string(),
+                });
+                parameters.insert("mi_mode".to_string(), NodeParameter {
+                    name: "Interpolation".to_string(),
+                    value: defaults.mi_mode.clone(),
+                    param_type: DataType::Text,
+                    default_value: defaults.mi_mode,
+                    description: "minterpolate mi_mode: mci, blend, dup".to_string(),
+                });
+                parameters.insert("mc_mode".to_string(), NodeParameter {
+                    name: "Motion compensation".to_string(),
+                    value: defaults.mc_mode.clone(),
                     param_type: DataType::Text,
-                    default_value: "fps".to_string(),
-                    description: "Filter type: fps, minterpolate".to_string(),
+                    default_value: defaults.mc_mode,
+                    description: "minterpolate mc_mode: obmc, aobmc".to_string(),
                 });
             },
             NodeType::VideoBrightness => {
@@ -1637,29 +2585,6 @@ impl AutomationNode {
                     description: "Saturation level: 0.0 (grayscale) to 3.0".to_string(),
                 });
             },
-            NodeType::StreamPrep => {
-                parameters.insert("bitrate".to_string(), NodeParameter {
-                    name: "Bitrate".to_string(),
-                    value: "2000k".to_string(),
-                    param_type: DataType::Text,
-                    default_value: "2000k".to_string(),
-                    description: "Video bitrate for streaming: 1000k, 2000k, 5000k".to_string(),
-                });
-                parameters.insert("resolution".to_string(), NodeParameter {
-                    name: "Resolution".to_string(),
-                    value: "1920x1080".to_string(),
-                    param_type: DataType::Text,
-                    default_value: "1920x1080".to_string(),
-                    description: "Target resolution: 1280x720, 1920x1080".to_string(),
-                });
-                parameters.insert("fps".to_string(), NodeParameter {
-                    name: "Frame Rate".to_string(),
-                    value: "30".to_string(),
-                    param_type: DataType::Text,
-                    default_value: "30".to_string(),
-                    description: "Frame rate: 24, 30, 60".to_string(),
-                });
-            },
             NodeType::AudioVideoSync => {
                 parameters.insert("sync_offset".to_string(), NodeParameter {
                     name: "Sync Offset".to_string(),
@@ -1710,15 +2635,6 @@ impl AutomationNode {
                     description: "Comment metadata".to_string(),
                 });
             },
-            NodeType::VideoEncrypt => {
-                parameters.insert("password".to_string(), NodeParameter {
-                    name: "Password".to_string(),
-                    value: "".to_string(),
-                    param_type: DataType::Text,
-                    default_value: "".to_string(),
-                    description: "Encryption password".to_string(),
-                });
-            },
             NodeType::MultiPassEncode => {
                 parameters.insert("bitrate".to_string(), NodeParameter {
                     name: "Bitrate".to_string(),
@@ -1750,9 +2666,33 @@ impl AutomationNode {
             output_ports,
             parameters,
             enabled: true,
+            raw_json: None,
+            group: None,
         }
     }
     
+    /// Group node showing `group`'s exposed ports, sized to fit them
+    pub fn new_group(id: String, group: NodeGroup, position: Pos2) -> Self {
+        let mut node = Self::new(id.clone(), NodeType::Group, position);
+        let port = |inner: &GroupPort, i: usize, is_input: bool| {
+            let ports = group.nodes.get(&inner.node).map(|node| if is_input { &node.input_ports } else { &node.output_ports });
+            let inner_port = ports.and_then(|ports| ports.get(inner.port));
+            NodePort {
+                id: format!("{}_{}_{}", id, i, if is_input { "in" } else { "out" }),
+                name: inner_port.map(|port| port.name.clone()).unwrap_or_default(),
+                data_type: inner_port.map(|port| port.data_type.clone()).unwrap_or(DataType::MediaFile),
+                is_input,
+                connections: Vec::new(),
+            }
+        };
+        node.input_ports = group.inputs.iter().enumerate().map(|(i, inner)| port(inner, i, true)).collect();
+        node.output_ports = group.outputs.iter().enumerate().map(|(i, inner)| port(inner, i, false)).collect();
+        let ports = node.input_ports.len().max(node.output_ports.len());
+        node.size.y = node.size.y.max(crate::viewport_geometry::node_height_for_ports(ports));
+        node.group = Some(Box::new(group));
+        node
+    }
+    
 
     pub fn get_input_port_position(&self, port_index: usize) -> Option<Pos2> {
         if port_index < self.input_ports.len() {
@@ -1779,20 +2719,83 @@ impl AutomationNode {
             NodeType::InputFile => {
                 self.show_input_file_parameters(ui, translations);
             },
-            NodeType::OutputFile => {
-                self.show_output_file_parameters(ui, translations);
+            NodeType::OutputFile => {
+                self.show_output_file_parameters(ui, translations);
+            },
+            NodeType::VideoConvert => {
+                self.show_video_convert_parameters(ui, translations, cached_hw_encoders);
+            },
+            NodeType::AudioConvert => {
+                self.show_audio_convert_parameters(ui, translations);
+            },
+            NodeType::VideoCompress => {
+                self.show_video_compress_parameters(ui, translations);
+            },
+            NodeType::AudioCompress => {
+                self.show_audio_compress_parameters(ui, translations);
+            },
+            NodeType::VideoFilter => {
+                self.show_video_filter_parameters(ui, translations);
+            },
+            NodeType::AudioDeNoise => {
+                self.show_audio_denoise_parameters(ui, translations);
+            },
+            NodeType::AudioNormalize => {
+                self.show_audio_normalize_parameters(ui, translations);
+            },
+            NodeType::VideoFPS => {
+                self.show_video_fps_parameters(ui, translations);
+            },
+            NodeType::AudioFade => {
+                self.show_audio_fade_parameters(ui, translations);
+            },
+            NodeType::AudioEcho => {
+                self.show_audio_echo_parameters(ui, translations);
+            },
+            NodeType::AudioEqualizer => {
+                self.show_audio_equalizer_parameters(ui, translations);
+            },
+            NodeType::VideoStabilize => {
+                self.show_video_stabilize_parameters(ui, translations);
+            },
+            NodeType::VideoOverlay => {
+                self.show_video_overlay_parameters(ui, translations);
+            },
+            NodeType::AddText => {
+                self.show_add_text_parameters(ui, translations);
+            },
+            NodeType::VideoToImages => {
+                self.show_video_to_images_parameters(ui, translations);
+            },
+            NodeType::QualityAnalysis => {
+                self.show_quality_analysis_parameters(ui, translations);
+            },
+            NodeType::MultiResOutput => {
+                self.show_multi_res_output_parameters(ui, translations, cached_hw_encoders);
+            },
+            NodeType::StreamPrep => {
+                self.show_stream_prep_parameters(ui, translations);
+            },
+            NodeType::VideoEncrypt => {
+                self.show_video_encrypt_parameters(ui, translations);
+            },
+            NodeType::VideoDecrypt => {
+                self.show_video_decrypt_parameters(ui, translations);
             },
-            NodeType::VideoConvert => {
-                self.show_video_convert_parameters(ui, translations, cached_hw_encoders);
+            NodeType::ImagesToVideo => {
+                self.show_images_to_video_parameters(ui, translations);
             },
-            NodeType::AudioConvert => {
-                self.show_audio_convert_parameters(ui, translations);
+            NodeType::VideoPiP => {
+                self.show_video_pip_parameters(ui, translations);
             },
-            NodeType::VideoCompress => {
-                self.show_video_compress_parameters(ui, translations);
+            NodeType::AudioMerge => {
+                self.show_audio_merge_parameters(ui, translations);
             },
-            NodeType::AudioCompress => {
-                self.show_audio_compress_parameters(ui, translations);
+            NodeType::Group => {
+                self.show_group_parameters(ui, translations);
+            },
+            NodeType::Unknown(_) => {
+                ui.colored_label(Color32::from_rgb(255, 200, 100), translations.workflow_load_unknown_node_hint());
             },
             _ => {
                 // Fallback to simple text inputs for other node types
@@ -1922,6 +2925,358 @@ impl AutomationNode {
         }
     }
     
+    /// Show the video filter stack editor; the stack is stored as JSON in the `filter_stack` parameter
+    fn show_video_filter_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading("✨ Video Filter Settings");
+        
+        let mut stack: Vec<crate::filter_stack::FilterStackEntry> = match self.parameters.get("filter_stack") {
+            Some(param) => serde_json::from_str(&param.value).unwrap_or_default(),
+            // Nodes saved before the stack existed only have the brightness/contrast/saturation trio
+            None => {
+                let legacy = VideoSettings {
+                    brightness: self.parameters.get("brightness").and_then(|p| p.value.parse().ok()).unwrap_or(0.0),
+                    contrast: self.parameters.get("contrast").and_then(|p| p.value.parse().ok()).unwrap_or(1.0),
+                    saturation: self.parameters.get("saturation").and_then(|p| p.value.parse().ok()).unwrap_or(1.0),
+                    ..VideoSettings::default()
+                };
+                crate::filter_stack::from_legacy(&legacy)
+            }
+        };
+        
+        ui.label(translations.filter_stack_title());
+        if crate::filter_stack::show_editor(ui, &mut stack, translations, None) {
+            let value = serde_json::to_string(&stack).unwrap_or_else(|_| "[]".to_string());
+            self.parameters.entry("filter_stack".to_string())
+                .or_insert_with(|| NodeParameter {
+                    name: "filter_stack".to_string(),
+                    value: String::new(),
+                    param_type: DataType::Text,
+                    default_value: "[]".to_string(),
+                    description: "Ordered filter stack (JSON, edited in the node properties)".to_string(),
+                })
+                .value = value;
+        }
+    }
+    
+    fn show_audio_merge_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::AudioMerge));
+        
+        let mut settings = AudioSettings {
+            merge_mode: merge_mode_from_params(&self.parameters),
+            mix: mix_from_params(&self.parameters),
+            ..Default::default()
+        };
+        let port_names: Vec<(String, Option<f64>)> = self.input_ports.iter().map(|port| (translations.port_name(&port.name), None)).collect();
+        let mut changed = crate::audio_mix::show_merge_editor(ui, &mut settings, translations);
+        if settings.merge_mode == crate::audio_mix::MODE_MIX {
+            changed |= crate::audio_mix::show_input_gains(ui, &port_names, &mut settings.mix, translations);
+        }
+        
+        if changed {
+            for (key, value, description) in [
+                ("merge_mode", settings.merge_mode.clone(), "Merge mode: concat, mix"),
+                ("mix", serde_json::to_string(&settings.mix).unwrap_or_else(|_| default_mix_param()), "Gains, normalize, duration and crossfade (JSON, edited in the node properties)"),
+            ] {
+                self.parameters.entry(key.to_string())
+                    .or_insert_with(|| NodeParameter {
+                        name: key.to_string(),
+                        value: String::new(),
+                        param_type: DataType::Text,
+                        default_value: String::new(),
+                        description: description.to_string(),
+                    })
+                    .value = value;
+            }
+        }
+    }
+    
+    fn show_audio_denoise_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.audio_denoise());
+        
+        let mut denoise = denoise_from_params(&self.parameters);
+        if crate::audio_denoise::show_editor(ui, &mut denoise, translations) {
+            let value = serde_json::to_string(&denoise).unwrap_or_else(|_| default_denoise_param());
+            self.parameters.entry("denoise".to_string())
+                .or_insert_with(|| NodeParameter {
+                    name: "denoise".to_string(),
+                    value: String::new(),
+                    param_type: DataType::Text,
+                    default_value: default_denoise_param(),
+                    description: "Noise reduction settings (JSON, edited in the node properties)".to_string(),
+                })
+                .value = value;
+        }
+    }
+    
+    fn show_audio_normalize_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.audio_normalize());
+        
+        let mut loudness = loudness_from_params(&self.parameters);
+        if crate::loudness::show_editor(ui, &mut loudness, translations) {
+            let value = serde_json::to_string(&loudness).unwrap_or_else(|_| default_loudness_param());
+            self.parameters.entry("loudness".to_string())
+                .or_insert_with(|| NodeParameter {
+                    name: "loudness".to_string(),
+                    value: String::new(),
+                    param_type: DataType::Text,
+                    default_value: default_loudness_param(),
+                    description: "Target loudness, true peak and loudness range (JSON, edited in the node properties)".to_string(),
+                })
+                .value = value;
+        }
+    }
+    
+    fn show_video_fps_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.frame_rate_convert());
+        
+        let mut conversion = fps_conversion_from_params(&self.parameters);
+        if crate::fps_conversion::show_editor(ui, &mut conversion, translations) {
+            self.set_text_parameters([
+                ("target_fps", conversion.target_fps.clone()),
+                ("mode", conversion.mode.key().to_string()),
+                ("mi_mode", conversion.mi_mode.clone()),
+                ("mc_mode", conversion.mc_mode.clone()),
+            ]);
+        }
+    }
+    
+    fn show_audio_fade_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.audio_fade());
+        
+        let mut fade = match fade_from_params(&self.parameters) {
+            Ok(fade) => fade,
+            Err(e) => {
+                ui.colored_label(Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+                crate::audio_fade::AudioFadeSettings::default()
+            }
+        };
+        if crate::audio_fade::show_editor(ui, &mut fade, translations) {
+            self.set_text_parameters([
+                ("fade_in_seconds", fade.fade_in_seconds.to_string()),
+                ("fade_out_seconds", fade.fade_out_seconds.to_string()),
+                ("curve", fade.curve.key().to_string()),
+            ]);
+        }
+    }
+    
+    fn show_group_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::Group));
+        
+        if let Some(group) = self.group.as_deref_mut() {
+            crate::node_group::show_editor(ui, group, translations);
+        }
+    }
+    
+    fn show_audio_echo_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::AudioEcho));
+        
+        let mut echo = echo_from_params(&self.parameters);
+        if crate::audio_echo::show_editor(ui, &mut echo, translations) {
+            self.set_text_parameters([
+                ("in_gain", echo.in_gain.to_string()),
+                ("out_gain", echo.out_gain.to_string()),
+                ("delays", echo.delays_param()),
+                ("decays", echo.decays_param()),
+            ]);
+        }
+    }
+    
+    fn show_audio_equalizer_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::AudioEqualizer));
+        
+        let mut equalizer = equalizer_from_params(&self.parameters);
+        if crate::audio_equalizer::show_editor(ui, &mut equalizer, translations) {
+            self.set_text_parameters([
+                ("bass", equalizer.bass_db.to_string()),
+                ("mid", equalizer.mid_db.to_string()),
+                ("treble", equalizer.treble_db.to_string()),
+                ("advanced", equalizer.advanced.clone()),
+            ]);
+        }
+    }
+    
+    fn show_video_overlay_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoOverlay));
+        
+        let mut settings = overlay_from_params(&self.parameters);
+        if crate::overlay::show_overlay_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("x", settings.x.clone()),
+                ("y", settings.y.clone()),
+                ("opacity", settings.opacity.to_string()),
+                ("audio", settings.audio.key().to_string()),
+            ]);
+        }
+    }
+    
+    fn show_video_pip_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoPiP));
+        
+        let mut settings = pip_from_params(&self.parameters);
+        if crate::overlay::show_pip_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("pip_scale", settings.scale_percent.to_string()),
+                ("position", settings.anchor.key().to_string()),
+                ("margin", settings.margin.to_string()),
+                ("audio", settings.audio.key().to_string()),
+            ]);
+        }
+    }
+    
+    fn show_quality_analysis_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::QualityAnalysis));
+        
+        let mut settings = quality_from_params(&self.parameters);
+        let last_report = crate::quality_metrics::last_report(&self.id);
+        if crate::quality_metrics::show_editor(ui, &mut settings, last_report.as_ref(), translations) {
+            self.set_text_parameters([
+                ("metrics", settings.metrics_param()),
+                ("csv_path", settings.csv_path.clone()),
+            ]);
+        }
+    }
+    
+    fn show_multi_res_output_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations, cached_hw_encoders: &[String]) {
+        ui.heading(translations.node_type_name(&NodeType::MultiResOutput));
+        
+        let mut settings = renditions_from_params(&self.parameters);
+        if crate::renditions::show_editor(ui, &mut settings, cached_hw_encoders, translations) {
+            self.set_text_parameters([
+                ("renditions", crate::renditions::renditions_param(&settings.renditions)),
+                ("video_codec", settings.video_codec.clone()),
+                ("container", settings.container.clone()),
+                ("basename", settings.basename.clone()),
+                ("output_dir", settings.output_dir.clone()),
+            ]);
+        }
+    }
+    
+    fn show_stream_prep_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::StreamPrep));
+        
+        let mut settings = stream_packaging_from_params(&self.parameters);
+        if crate::stream_packaging::show_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("format", settings.format.key().to_string()),
+                ("segment_seconds", settings.segment_seconds.to_string()),
+                ("playlist_type", settings.playlist_type.key().to_string()),
+                ("fragment_seconds", settings.fragment_seconds.to_string()),
+                ("video_codec", settings.video_codec.clone()),
+                ("audio_codec", settings.audio_codec.clone()),
+                ("name", settings.name.clone()),
+                ("output_dir", settings.output_dir.clone()),
+            ]);
+        }
+    }
+    
+    fn show_video_encrypt_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoEncrypt));
+        
+        let mut settings = encrypt_from_params(&self.parameters);
+        if crate::hls_encryption::show_encrypt_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("key_file", settings.key_file.clone()),
+                ("generate_key", settings.generate_key.to_string()),
+                ("segment_seconds", settings.segment_seconds.to_string()),
+                ("name", settings.name.clone()),
+                ("output_dir", settings.output_dir.clone()),
+            ]);
+        }
+    }
+    
+    fn show_video_decrypt_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoDecrypt));
+        
+        let mut settings = decrypt_from_params(&self.parameters);
+        if crate::hls_encryption::show_decrypt_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("key_file", settings.key_file.clone()),
+                ("container", settings.container.clone()),
+            ]);
+        }
+    }
+    
+    fn show_video_to_images_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoToImages));
+        
+        let mut settings = image_extract_from_params(&self.parameters);
+        if crate::image_sequence::show_extract_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("format", settings.format.extension().to_string()),
+                ("jpg_quality", settings.jpg_quality.to_string()),
+                ("fps", settings.rate.map(|rate| rate.ffmpeg_arg()).unwrap_or_else(|| "all".to_string())),
+                ("name_pattern", settings.name_pattern.clone()),
+                ("output_dir", settings.output_dir.clone()),
+            ]);
+        }
+    }
+    
+    fn show_images_to_video_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::ImagesToVideo));
+        
+        let mut settings = image_sequence_from_params(&self.parameters);
+        if crate::image_sequence::show_sequence_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("directory", settings.directory.clone()),
+                ("pattern", settings.pattern.clone()),
+                ("fps", settings.rate.ffmpeg_arg()),
+                ("codec", settings.codec.clone()),
+                ("pixel_format", settings.pixel_format.clone()),
+                ("audio_file", settings.audio_file.clone()),
+            ]);
+        }
+    }
+    
+    fn show_add_text_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::AddText));
+        
+        let mut settings = text_overlay_from_params(&self.parameters);
+        if crate::text_overlay::show_editor(ui, &mut settings, translations) {
+            let seconds = |value: Option<f64>| value.map(|seconds| seconds.to_string()).unwrap_or_default();
+            self.set_text_parameters([
+                ("text", settings.text.clone()),
+                ("font", settings.font.clone()),
+                ("font_size", settings.font_size.to_string()),
+                ("color", crate::text_overlay::color_value(settings.color)),
+                ("position", settings.position.key().to_string()),
+                ("x", settings.x.clone()),
+                ("y", settings.y.clone()),
+                ("box", settings.background_box.to_string()),
+                ("box_color", crate::text_overlay::color_value(settings.box_color)),
+                ("start", seconds(settings.start_seconds)),
+                ("end", seconds(settings.end_seconds)),
+            ]);
+        }
+    }
+    
+    /// Store values edited through a settings editor, adding parameters the node does not have yet
+    fn set_text_parameters<const N: usize>(&mut self, values: [(&str, String); N]) {
+        for (name, value) in values {
+            self.parameters.entry(name.to_string())
+                .or_insert_with(|| NodeParameter {
+                    name: name.to_string(),
+                    value: String::new(),
+                    param_type: DataType::Text,
+                    default_value: String::new(),
+                    description: String::new(),
+                })
+                .value = value;
+        }
+    }
+    
+    fn show_video_stabilize_parameters(&mut self, ui: &mut egui::Ui, translations: &crate::language::Translations) {
+        ui.heading(translations.node_type_name(&NodeType::VideoStabilize));
+        
+        let mut settings = stabilize_from_params(&self.parameters);
+        if crate::stabilize::show_editor(ui, &mut settings, translations) {
+            self.set_text_parameters([
+                ("smoothing", settings.smoothing.to_string()),
+                ("zoom", settings.zoom.to_string()),
+                ("optblur", settings.optblur.to_string()),
+            ]);
+        }
+    }
+    
     /// Show video compress parameters
     fn show_video_compress_parameters(&mut self, ui: &mut egui::Ui, _translations: &crate::language::Translations) {
         ui.heading("🗁 Video Compression Settings");
@@ -2403,10 +3758,94 @@ pub struct NodeConnection {
     pub data_type: DataType,
 }
 
+/// End of a connection being dragged to another port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionEnd {
+    /// The output port the connection leaves
+    Source,
+    /// The input port the connection enters
+    Target,
+}
+
+/// Inner node port behind one port of a Group node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupPort {
+    pub node: String,
+    pub port: usize,
+}
+
+/// Nodes collapsed into a Group node, kept whole so ungrouping restores them as they were
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeGroup {
+    pub name: String,
+    pub nodes: HashMap<String, AutomationNode>,
+    /// Connections between the inner nodes
+    pub connections: HashMap<String, NodeConnection>,
+    /// Inner port behind each input port of the group node, in port order
+    pub inputs: Vec<GroupPort>,
+    /// Inner port behind each output port of the group node, in port order
+    pub outputs: Vec<GroupPort>,
+}
+
+impl NodeGroup {
+    /// Top-left corner of the inner nodes' layout
+    pub fn origin(&self) -> Pos2 {
+        self.nodes.values()
+            .map(|node| node.position)
+            .reduce(|a, b| a.min(b))
+            .unwrap_or(Pos2::ZERO)
+    }
+    
+    /// Ids of the nodes that run for this group, inside nested groups too
+    pub fn leaf_ids(&self) -> Vec<String> {
+        self.nodes.values()
+            .flat_map(|node| match &node.group {
+                Some(group) => group.leaf_ids(),
+                None => vec![node.id.clone()],
+            })
+            .collect()
+    }
+    
+    /// Deep copy with fresh node and connection ids, for inserting a template more than once
+    pub fn with_fresh_ids(&self) -> NodeGroup {
+        let ids: HashMap<&String, String> = self.nodes.keys().map(|id| (id, new_node_id())).collect();
+        let nodes = self.nodes.iter().map(|(old_id, node)| {
+            let id = ids[old_id].clone();
+            let mut copy = node.clone();
+            copy.id = id.clone();
+            copy.group = node.group.as_ref().map(|group| Box::new(group.with_fresh_ids()));
+            for (i, port) in copy.input_ports.iter_mut().enumerate() {
+                port.id = format!("{}_{}_in", id, i);
+            }
+            for (i, port) in copy.output_ports.iter_mut().enumerate() {
+                port.id = format!("{}_{}_out", id, i);
+            }
+            (id, copy)
+        }).collect();
+        let connections = self.connections.values().filter_map(|connection| {
+            let id = new_connection_id();
+            let copy = NodeConnection {
+                id: id.clone(),
+                from_node: ids.get(&connection.from_node)?.clone(),
+                to_node: ids.get(&connection.to_node)?.clone(),
+                ..connection.clone()
+            };
+            Some((id, copy))
+        }).collect();
+        // A port naming a missing node keeps its place so the group's port numbers stay valid
+        let remap = |ports: &[GroupPort]| ports.iter()
+            .map(|inner| GroupPort { node: ids.get(&inner.node).cloned().unwrap_or_else(|| inner.node.clone()), port: inner.port })
+            .collect();
+        NodeGroup { name: self.name.clone(), nodes, connections, inputs: remap(&self.inputs), outputs: remap(&self.outputs) }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AutomationWorkflow {
     pub id: String,
     pub name: String,
+    /// Shown in the workflow library; empty when none was written
+    #[serde(default)]
     pub description: String,
     pub nodes: HashMap<String, AutomationNode>,
     pub connections: HashMap<String, NodeConnection>,
@@ -2482,7 +3921,254 @@ impl AutomationWorkflow {
         self.connections.remove(connection_id);
         self.update_modified_time();
     }
+
+    /// Input and output port of `node` that can take the place of connection `connection_id`: the
+    /// first input accepting the connection's source and the first output its target accepts
+    pub fn insertion_ports(&self, connection_id: &str, node: &AutomationNode) -> Option<(usize, usize)> {
+        let connection = self.connections.get(connection_id)?;
+        let from_type = &self.nodes.get(&connection.from_node)?.output_ports.get(connection.from_port)?.data_type;
+        let to_type = &self.nodes.get(&connection.to_node)?.input_ports.get(connection.to_port)?.data_type;
+        let input = node.input_ports.iter().position(|port| Self::are_types_compatible(from_type, &port.data_type))?;
+        let output = node.output_ports.iter().position(|port| Self::are_types_compatible(&port.data_type, to_type))?;
+        Some((input, output))
+    }
+
+    /// Split connection `connection_id` with `node`, which is added to the workflow. Nothing
+    /// changes when the node's ports cannot carry the connection's data.
+    pub fn insert_node_on_connection(&mut self, connection_id: &str, node: AutomationNode) -> Result<(), String> {
+        if self.insertion_ports(connection_id, &node).is_none() {
+            return Err(format!("{} cannot be inserted on this connection", node.node_type.display_name()));
+        }
+        let node_id = node.id.clone();
+        self.add_node(node);
+        self.splice_node(connection_id, &node_id)
+    }
+    
+    /// Split connection `connection_id` with `node_id`, a node already in the workflow
+    pub fn splice_node(&mut self, connection_id: &str, node_id: &str) -> Result<(), String> {
+        let node = self.nodes.get(node_id).ok_or_else(|| format!("Node {} not found in workflow", node_id))?;
+        let (input, output) = self.insertion_ports(connection_id, node)
+            .ok_or_else(|| format!("{} cannot be inserted on this connection", node.node_type.display_name()))?;
+        let output_type = node.output_ports[output].data_type.clone();
+        let connection = self.connections.remove(connection_id).ok_or("连接不存在")?;
+        let node_id = node_id.to_string();
+        let upstream = NodeConnection {
+            id: new_connection_id(),
+            from_node: connection.from_node.clone(),
+            from_port: connection.from_port,
+            to_node: node_id.clone(),
+            to_port: input,
+            data_type: connection.data_type.clone(),
+        };
+        let downstream = NodeConnection {
+            id: new_connection_id(),
+            from_node: node_id,
+            from_port: output,
+            to_node: connection.to_node.clone(),
+            to_port: connection.to_port,
+            data_type: output_type,
+        };
+        self.connections.insert(upstream.id.clone(), upstream);
+        self.connections.insert(downstream.id.clone(), downstream);
+        self.update_modified_time();
+        Ok(())
+    }
+    
+    /// Move one end of connection `connection_id` to `port` of `node_id`, keeping the connection's
+    /// id. Nothing changes when the port does not exist, the data types do not match or both ends
+    /// would be on one node.
+    pub fn reroute_connection(&mut self, connection_id: &str, end: ConnectionEnd, node_id: &str, port: usize) -> Result<(), String> {
+        let mut connection = self.connections.get(connection_id).cloned().ok_or("连接不存在")?;
+        match end {
+            ConnectionEnd::Source => (connection.from_node, connection.from_port) = (node_id.to_string(), port),
+            ConnectionEnd::Target => (connection.to_node, connection.to_port) = (node_id.to_string(), port),
+        }
+        if connection.from_node == connection.to_node {
+            return Err("Cannot connect node to itself".to_string());
+        }
+        let from_type = self.nodes.get(&connection.from_node)
+            .and_then(|node| node.output_ports.get(connection.from_port))
+            .map(|port| port.data_type.clone())
+            .ok_or("输出端口不存在")?;
+        let to_type = self.nodes.get(&connection.to_node)
+            .and_then(|node| node.input_ports.get(connection.to_port))
+            .map(|port| port.data_type.clone())
+            .ok_or("输入端口不存在")?;
+        if !Self::are_types_compatible(&from_type, &to_type) {
+            return Err(format!("数据类型不匹配: {} -> {}", from_type.display_name(), to_type.display_name()));
+        }
+        connection.data_type = from_type;
+        self.connections.insert(connection_id.to_string(), connection);
+        self.update_modified_time();
+        Ok(())
+    }
+    
+    /// Collapse `node_ids` into one Group node placed at their top-left. Inputs not fed from
+    /// inside the group become its inputs, and outputs not used inside it or also used outside
+    /// become its outputs, numbered top to bottom. Connections crossing the boundary move to the
+    /// group's ports. Returns the group node's id.
+    pub fn group_nodes(&mut self, node_ids: &[String], name: &str) -> Result<String, String> {
+        let members: std::collections::HashSet<&String> = node_ids.iter().collect();
+        if members.is_empty() {
+            return Err("No nodes to group".to_string());
+        }
+        for id in &members {
+            match self.nodes.get(*id) {
+                None => return Err(format!("Node {} not found in workflow", id)),
+                Some(node) if matches!(node.node_type, NodeType::Unknown(_)) => {
+                    return Err("Placeholders of unknown node types cannot be grouped".to_string());
+                }
+                Some(_) => {}
+            }
+        }
+        let inside = |id: &String| members.contains(id);
+        
+        let mut ordered: Vec<&AutomationNode> = members.iter().filter_map(|id| self.nodes.get(*id)).collect();
+        ordered.sort_by(|a, b| a.position.y.total_cmp(&b.position.y)
+            .then(a.position.x.total_cmp(&b.position.x))
+            .then(a.id.cmp(&b.id)));
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for node in ordered {
+            for port in 0..node.input_ports.len() {
+                let fed_inside = self.connections.values()
+                    .any(|connection| connection.to_node == node.id && connection.to_port == port && inside(&connection.from_node));
+                if !fed_inside {
+                    inputs.push(GroupPort { node: node.id.clone(), port });
+                }
+            }
+            for port in 0..node.output_ports.len() {
+                let mut targets = self.connections.values()
+                    .filter(|connection| connection.from_node == node.id && connection.from_port == port)
+                    .map(|connection| inside(&connection.to_node))
+                    .peekable();
+                if targets.peek().is_none() || targets.any(|target_inside| !target_inside) {
+                    outputs.push(GroupPort { node: node.id.clone(), port });
+                }
+            }
+        }
+        
+        let group_id = new_node_id();
+        let mut inner_connections = HashMap::new();
+        for (id, mut connection) in std::mem::take(&mut self.connections) {
+            match (inside(&connection.from_node), inside(&connection.to_node)) {
+                (true, true) => {
+                    inner_connections.insert(id, connection);
+                    continue;
+                }
+                (false, true) => {
+                    let Some(port) = inputs.iter().position(|inner| inner.node == connection.to_node && inner.port == connection.to_port) else {
+                        continue;
+                    };
+                    connection.to_node = group_id.clone();
+                    connection.to_port = port;
+                }
+                (true, false) => {
+                    let Some(port) = outputs.iter().position(|inner| inner.node == connection.from_node && inner.port == connection.from_port) else {
+                        continue;
+                    };
+                    connection.from_node = group_id.clone();
+                    connection.from_port = port;
+                }
+                (false, false) => {}
+            }
+            self.connections.insert(id, connection);
+        }
+        
+        let nodes = members.iter().filter_map(|id| self.nodes.remove(*id).map(|node| (node.id.clone(), node))).collect();
+        let group = NodeGroup { name: name.to_string(), nodes, connections: inner_connections, inputs, outputs };
+        let origin = group.origin();
+        self.add_node(AutomationNode::new_group(group_id.clone(), group, origin));
+        Ok(group_id)
+    }
+    
+    /// Put a Group node's contents back where it stands, with its connections moved to the inner
+    /// ports again. Returns the ids of the restored nodes.
+    pub fn ungroup_node(&mut self, group_id: &str) -> Result<Vec<String>, String> {
+        let group = self.nodes.get(group_id).and_then(|node| node.group.as_ref()).ok_or("Not a group node")?;
+        if let Some(id) = group.nodes.keys().find(|id| self.nodes.contains_key(*id)) {
+            return Err(format!("Node {} is already in the workflow", id));
+        }
+        let Some(node) = self.nodes.remove(group_id) else {
+            return Err(format!("Node {} not found in workflow", group_id));
+        };
+        let group = *node.group.ok_or("Not a group node")?;
+        let offset = node.position - group.origin();
+        let restored: Vec<String> = group.nodes.keys().cloned().collect();
+        
+        let mut dropped = Vec::new();
+        for connection in self.connections.values_mut() {
+            let inner = |ports: &[GroupPort], port: usize| ports.get(port).filter(|inner| group.nodes.contains_key(&inner.node)).cloned();
+            if connection.to_node == group_id {
+                match inner(&group.inputs, connection.to_port) {
+                    Some(inner) => (connection.to_node, connection.to_port) = (inner.node, inner.port),
+                    None => dropped.push(connection.id.clone()),
+                }
+            }
+            if connection.from_node == group_id {
+                match inner(&group.outputs, connection.from_port) {
+                    Some(inner) => (connection.from_node, connection.from_port) = (inner.node, inner.port),
+                    None => dropped.push(connection.id.clone()),
+                }
+            }
+        }
+        for id in dropped {
+            self.connections.remove(&id);
+        }
+        self.connections.extend(group.connections);
+        for (id, mut inner) in group.nodes {
+            inner.position += offset;
+            self.nodes.insert(id, inner);
+        }
+        self.update_modified_time();
+        Ok(restored)
+    }
     
+    /// Copy with every Group node, nested ones too, replaced by its contents: the graph that runs
+    pub fn flattened(&self) -> AutomationWorkflow {
+        let mut flat = self.clone();
+        while let Some(group_id) = flat.nodes.values().find(|node| node.group.is_some()).map(|node| node.id.clone()) {
+            if let Err(e) = flat.ungroup_node(&group_id) {
+                log_warn!("Group {} left out of the run: {}", group_id, e);
+                flat.remove_node(&group_id);
+            }
+        }
+        flat
+    }
+
+    /// Copy of `node_ids` with the connections between them, for the clipboard
+    pub fn copy_nodes(&self, node_ids: &std::collections::HashSet<String>) -> NodeGroup {
+        NodeGroup {
+            name: String::new(),
+            nodes: self.nodes.iter().filter(|(id, _)| node_ids.contains(*id)).map(|(id, node)| (id.clone(), node.clone())).collect(),
+            connections: self.connections.iter()
+                .filter(|(_, connection)| node_ids.contains(&connection.from_node) && node_ids.contains(&connection.to_node))
+                .map(|(id, connection)| (id.clone(), connection.clone()))
+                .collect(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Add a copy of copied nodes moved by `offset`, with fresh ids and their connections to each
+    /// other. Returns the ids of the new nodes.
+    pub fn paste_nodes(&mut self, copied: &NodeGroup, offset: Vec2) -> Vec<String> {
+        let fresh = copied.with_fresh_ids();
+        let ids = fresh.nodes.keys().cloned().collect();
+        for (_, mut node) in fresh.nodes {
+            node.position += offset;
+            self.add_node(node);
+        }
+        self.connections.extend(fresh.connections);
+        ids
+    }
+
+    pub fn remove_nodes(&mut self, node_ids: &std::collections::HashSet<String>) {
+        for node_id in node_ids {
+            self.remove_node(node_id);
+        }
+    }
+
     pub fn get_execution_order(&self) -> Result<Vec<String>, String> {
         let mut in_degree = HashMap::new();
         let mut graph = HashMap::new();
@@ -2553,6 +4239,135 @@ pub enum ExecutionStatus {
     Cancelled,
 }
 
+/// Scratch folder named in a command preview; the real one is only created by a run
+pub const DRY_RUN_TEMP_DIR: &str = "$TMP";
+
+/// Where a node stands in the latest run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRunStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    /// Interrupted by a stop
+    Cancelled,
+    /// Not reached before a stop
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeRunState {
+    pub status: NodeRunStatus,
+    /// 0.0 to 1.0 while running, for nodes that report ffmpeg progress
+    pub progress: f32,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct RunStates {
+    workflow_id: String,
+    nodes: HashMap<String, NodeRunState>,
+}
+
+/// Node states of the latest run, shared between a run on a background thread and the canvas,
+/// which reads them every frame
+#[derive(Debug, Clone, Default)]
+pub struct RunStateHandle(std::sync::Arc<std::sync::Mutex<RunStates>>);
+
+impl RunStateHandle {
+    fn lock(&self) -> std::sync::MutexGuard<'_, RunStates> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Start over for a run of `workflow_id`, every node in `order` pending
+    fn reset(&self, workflow_id: &str, order: &[String]) {
+        let mut states = self.lock();
+        states.workflow_id = workflow_id.to_string();
+        states.nodes = order.iter()
+            .map(|id| (id.clone(), NodeRunState { status: NodeRunStatus::Pending, progress: 0.0, error: None }))
+            .collect();
+    }
+
+    fn set_status(&self, node_id: &str, status: NodeRunStatus) {
+        if let Some(state) = self.lock().nodes.get_mut(node_id) {
+            state.status = status;
+            if status == NodeRunStatus::Done {
+                state.progress = 1.0;
+            }
+        }
+    }
+
+    fn set_progress(&self, node_id: &str, progress: f32) {
+        if let Some(state) = self.lock().nodes.get_mut(node_id) {
+            state.progress = progress.clamp(0.0, 1.0);
+        }
+    }
+
+    fn fail(&self, node_id: &str, error: &str) {
+        if let Some(state) = self.lock().nodes.get_mut(node_id) {
+            state.status = NodeRunStatus::Failed;
+            state.error = Some(error.to_string());
+        }
+    }
+
+    /// After a stop: nodes that were running become cancelled, and so does one that failed
+    /// because its ffmpeg was killed; nodes that never started are skipped
+    fn mark_cancelled(&self) {
+        for state in self.lock().nodes.values_mut() {
+            match state.status {
+                NodeRunStatus::Running | NodeRunStatus::Failed => {
+                    state.status = NodeRunStatus::Cancelled;
+                    state.error = None;
+                }
+                NodeRunStatus::Pending => state.status = NodeRunStatus::Skipped,
+                _ => {}
+            }
+        }
+    }
+    
+    /// State of `node_id` if the latest run was of `workflow_id`
+    pub fn get(&self, workflow_id: &str, node_id: &str) -> Option<NodeRunState> {
+        let states = self.lock();
+        if states.workflow_id != workflow_id {
+            return None;
+        }
+        states.nodes.get(node_id).cloned()
+    }
+
+    /// State of a group made of `node_ids`: failed or cancelled if any node was, running while
+    /// some have started and others have not, done when all are. Finished nodes count as complete
+    /// in the progress.
+    pub fn get_combined(&self, workflow_id: &str, node_ids: &[String]) -> Option<NodeRunState> {
+        let states = self.lock();
+        if states.workflow_id != workflow_id {
+            return None;
+        }
+        let members: Vec<&NodeRunState> = node_ids.iter().filter_map(|id| states.nodes.get(id)).collect();
+        if members.is_empty() {
+            return None;
+        }
+        let all = |status: NodeRunStatus| members.iter().all(|state| state.status == status);
+        let any = |status: NodeRunStatus| members.iter().find(|state| state.status == status);
+        let progress = members.iter()
+            .map(|state| if state.status == NodeRunStatus::Done { 1.0 } else { state.progress })
+            .sum::<f32>() / members.len() as f32;
+        let (status, error) = if let Some(failed) = any(NodeRunStatus::Failed) {
+            (NodeRunStatus::Failed, failed.error.clone())
+        } else if any(NodeRunStatus::Cancelled).is_some() {
+            (NodeRunStatus::Cancelled, None)
+        } else if all(NodeRunStatus::Done) {
+            (NodeRunStatus::Done, None)
+        } else if all(NodeRunStatus::Pending) {
+            (NodeRunStatus::Pending, None)
+        } else if all(NodeRunStatus::Skipped) {
+            (NodeRunStatus::Skipped, None)
+        } else {
+            (NodeRunStatus::Running, None)
+        };
+        Some(NodeRunState { status, progress, error })
+    }
+}
+
 /// Workflow execution result for each node
 #[derive(Debug, Clone)]
 pub struct NodeExecutionResult {
@@ -2561,6 +4376,8 @@ pub struct NodeExecutionResult {
     pub output_files: Vec<String>,
     pub error_message: Option<String>,
     pub execution_time: std::time::Duration,
+    /// Outputs reused from an earlier run instead of executing the node
+    pub cached: bool,
 }
 
 /// Workflow execution engine
@@ -2573,7 +4390,27 @@ pub struct WorkflowExecutor {
     pub temp_files: Vec<String>,
     pub workflow_temp_dir: Option<String>,
     pub current_step: usize,
+    // Nodes of the running workflow, for nodes that report progress within their own step
+    total_steps: usize,
     pub cached_hardware_encoders: Vec<String>,
+    pub last_run_report: Option<WorkflowRunReport>,
+    pub session_stats: SessionStats,
+    // Commands of the tasks created by the node currently executing, for the run report
+    node_commands: std::cell::RefCell<Vec<String>>,
+    // Copies an Output File node would make, recorded instead of made by a dry run
+    node_copies: std::cell::RefCell<Vec<(String, String)>>,
+    /// Reuse intermediate outputs of earlier runs for nodes whose inputs and parameters are unchanged
+    pub incremental: bool,
+    // Set for one run by a forced full re-run; results are still written to the cache
+    bypass_cache: bool,
+    /// Per-node status of the latest run, for the canvas
+    pub run_state: RunStateHandle,
+    // Set for a command preview: commands are built and recorded but nothing runs or is written
+    dry_run: bool,
+    // Stop request of the run started by spawn_run, shared with its thread
+    cancel_token: crate::run_cancel::CancelToken,
+    /// Leave the intermediate files of a stopped run in its temp folder for debugging
+    pub keep_files_on_cancel: bool,
 }
 
 impl WorkflowExecutor {
@@ -2587,7 +4424,18 @@ impl WorkflowExecutor {
             temp_files: Vec::new(),
             workflow_temp_dir: None,
             current_step: 0,
+            total_steps: 0,
             cached_hardware_encoders: Vec::new(),
+            last_run_report: None,
+            session_stats: SessionStats::default(),
+            node_commands: std::cell::RefCell::new(Vec::new()),
+            node_copies: std::cell::RefCell::new(Vec::new()),
+            incremental: false,
+            bypass_cache: false,
+            run_state: RunStateHandle::default(),
+            dry_run: false,
+            cancel_token: crate::run_cancel::CancelToken::default(),
+            keep_files_on_cancel: false,
         }
     }
     
@@ -2602,7 +4450,18 @@ impl WorkflowExecutor {
             temp_files: Vec::new(),
             workflow_temp_dir: None,
             current_step: 0,
+            total_steps: 0,
             cached_hardware_encoders: hardware_encoders,
+            last_run_report: None,
+            session_stats: SessionStats::default(),
+            node_commands: std::cell::RefCell::new(Vec::new()),
+            node_copies: std::cell::RefCell::new(Vec::new()),
+            incremental: false,
+            bypass_cache: false,
+            run_state: RunStateHandle::default(),
+            dry_run: false,
+            cancel_token: crate::run_cancel::CancelToken::default(),
+            keep_files_on_cancel: false,
         }
     }
     
@@ -2614,6 +4473,125 @@ impl WorkflowExecutor {
     
     /// Execute the entire workflow
     pub fn execute_workflow(&mut self, workflow: AutomationWorkflow) -> Result<(), String> {
+        let workflow = workflow.flattened();
+        let mut report = WorkflowRunReport::start(&workflow);
+        let result = self.run_workflow_nodes(workflow, &mut report);
+        
+        match &result {
+            Ok(_) if self.execution_status == ExecutionStatus::Cancelled => report.finish(RunOutcome::Cancelled, None),
+            Ok(_) => report.finish(RunOutcome::Success, None),
+            Err(e) => report.finish(RunOutcome::Failed, Some(e.clone())),
+        }
+        log_info!("Workflow run finished in {} ms ({} nodes, {} of artifacts)",
+            report.wall_time_ms, report.nodes.len(), crate::workflow_report::format_bytes(report.artifact_bytes()));
+        self.session_stats.record(&report);
+        self.last_run_report = Some(report);
+        
+        result
+    }
+    
+    /// Run every node even if the cache holds its outputs; the cache is refreshed with the new results
+    pub fn execute_workflow_uncached(&mut self, workflow: AutomationWorkflow) -> Result<(), String> {
+        self.bypass_cache = true;
+        let result = self.execute_workflow(workflow);
+        self.bypass_cache = false;
+        result
+    }
+    
+    /// Run `workflow` on a background thread with this executor's settings. The canvas follows it
+    /// through the shared `run_state`; the joined executor goes to `finish_background_run`.
+    pub fn spawn_run(&mut self, workflow: AutomationWorkflow, force_full: bool) -> std::thread::JoinHandle<(WorkflowExecutor, Result<(), String>)> {
+        self.cancel_token = crate::run_cancel::CancelToken::default();
+        let mut runner = self.runner(&self.cancel_token);
+        runner.run_state = self.run_state.clone();
+        self.execution_status = ExecutionStatus::Running;
+        // Cleared before the thread starts so the previous run is never drawn over the new one
+        if let Ok(order) = workflow.flattened().get_execution_order() {
+            self.run_state.reset(&workflow.id, &order);
+        }
+        let token = self.cancel_token.clone();
+        std::thread::spawn(move || {
+            let result = crate::run_cancel::with_token(&token, || {
+                if force_full { runner.execute_workflow_uncached(workflow) } else { runner.execute_workflow(workflow) }
+            });
+            (runner, result)
+        })
+    }
+    
+    /// Executor for a run on another thread with this executor's settings, stopped by `token`.
+    /// Its run state is its own; the canvas does not follow it.
+    pub fn runner(&self, token: &crate::run_cancel::CancelToken) -> WorkflowExecutor {
+        let mut runner = WorkflowExecutor::new_with_hardware_cache(self.cached_hardware_encoders.clone());
+        runner.incremental = self.incremental;
+        runner.keep_files_on_cancel = self.keep_files_on_cancel;
+        runner.cancel_token = token.clone();
+        runner
+    }
+    
+    /// Stop the run started by `spawn_run`: its running ffmpeg is killed and no further node starts
+    pub fn cancel(&self) {
+        log_info!("Stopping the workflow run");
+        self.cancel_token.cancel();
+    }
+    
+    /// Every command a run of `workflow` would execute, built by the same node code as a real run
+    /// but without running anything. Intermediate files are named under [`DRY_RUN_TEMP_DIR`]; a
+    /// node whose commands cannot be built reports the error and the preview goes on.
+    pub fn preview_workflow(&self, workflow: &AutomationWorkflow) -> Result<Vec<crate::workflow_plan::PreviewStep>, String> {
+        let workflow = &workflow.flattened();
+        let order = workflow.get_execution_order()?;
+        let plan = crate::workflow_plan::ExecutionPlan::build(workflow, &order);
+        let mut runner = WorkflowExecutor::new_with_hardware_cache(self.cached_hardware_encoders.clone());
+        runner.dry_run = true;
+        runner.workflow_temp_dir = Some(DRY_RUN_TEMP_DIR.to_string());
+        runner.current_workflow = Some(workflow.clone());
+        runner.total_steps = order.len();
+        let mut report = WorkflowRunReport::start(workflow);
+        
+        let mut steps = plan.preview(workflow, &order);
+        for step in &mut steps {
+            let head = step.nodes[0].clone();
+            let index = order.iter().position(|id| *id == head).unwrap_or_default();
+            runner.current_node = Some(head.clone());
+            runner.current_step = index + 1;
+            runner.node_commands.borrow_mut().clear();
+            runner.node_copies.borrow_mut().clear();
+            let outcome = match (plan.segment_starting_at(&head), workflow.nodes.get(&head)) {
+                (Some(segment), _) => runner.run_segment(workflow, segment, index, order.len(), None, None, &mut report),
+                (None, Some(node)) => runner.execute_node(node, workflow).and_then(|result| {
+                    if !result.success {
+                        return Err(result.error_message.unwrap_or_else(|| "Unknown error".to_string()));
+                    }
+                    // Later nodes find their input among the results
+                    runner.execution_results.push(result);
+                    Ok(())
+                }),
+                (None, None) => Err(format!("Node {} not found in workflow", head)),
+            };
+            // A segment records its pipeline with its first node
+            step.commands = if step.fused {
+                report.nodes.iter().filter(|record| record.node_id == head).flat_map(|record| record.commands.clone()).collect()
+            } else {
+                runner.node_commands.borrow_mut().drain(..).collect()
+            };
+            step.copies = runner.node_copies.borrow_mut().drain(..).collect();
+            step.error = outcome.err();
+        }
+        Ok(steps)
+    }
+    
+    /// Take over the outcome, results and report of a run made by `spawn_run`
+    pub fn finish_background_run(&mut self, runner: WorkflowExecutor) {
+        self.execution_status = runner.execution_status;
+        self.execution_results = runner.execution_results;
+        self.progress = runner.progress;
+        if let Some(report) = &runner.last_run_report {
+            self.session_stats.record(report);
+        }
+        self.last_run_report = runner.last_run_report;
+    }
+    
+    fn run_workflow_nodes(&mut self, workflow: AutomationWorkflow, report: &mut WorkflowRunReport) -> Result<(), String> {
         self.current_workflow = Some(workflow.clone());
         self.execution_status = ExecutionStatus::Running;
         self.progress = 0.0;
@@ -2626,27 +4604,137 @@ impl WorkflowExecutor {
         
         // Get execution order using topological sort
         let execution_order = workflow.get_execution_order()?;
+        self.run_state.reset(&workflow.id, &execution_order);
+        
+        // Incremental runs: cache keys chain through upstream keys, so an edit invalidates everything below it
+        let mut cache = if self.incremental { crate::workflow_cache::WorkflowCache::open(&workflow.id) } else { None };
+        let cache_keys = if cache.is_some() {
+            crate::workflow_cache::node_keys(&workflow, &execution_order, &crate::workflow_cache::file_identity)
+        } else {
+            HashMap::new()
+        };
+        let plan = crate::workflow_plan::ExecutionPlan::build(&workflow, &execution_order);
+        let (collapsed, piped) = plan.streamed_edge_counts();
+        if collapsed + piped > 0 {
+            log_info!("Streaming {} edges without intermediate files ({} collapsed, {} piped)", collapsed + piped, collapsed, piped);
+        }
+        let mut result = self.run_nodes_in_order(&workflow, &execution_order, &plan, cache.as_mut(), &cache_keys, report);
+        if self.cancel_token.is_cancelled() {
+            // A node whose ffmpeg was killed fails; the run still counts as stopped, not failed
+            log_info!("Workflow run stopped at node {}", self.current_node.as_deref().unwrap_or("-"));
+            self.execution_status = ExecutionStatus::Cancelled;
+            self.current_node = None;
+            self.run_state.mark_cancelled();
+            match self.workflow_temp_dir.as_deref() {
+                Some(temp_dir) if self.keep_files_on_cancel => log_info!("Intermediate files of the stopped run kept in {}", temp_dir),
+                _ => self.cleanup_workflow_temp_dir(),
+            }
+            result = Ok(());
+        }
+        if let Some(cache) = &cache {
+            cache.save();
+            crate::workflow_cache::collect_garbage();
+        }
+        result
+    }
+    
+    fn run_nodes_in_order(
+        &mut self,
+        workflow: &AutomationWorkflow,
+        execution_order: &[String],
+        plan: &crate::workflow_plan::ExecutionPlan,
+        mut cache: Option<&mut crate::workflow_cache::WorkflowCache>,
+        cache_keys: &HashMap<String, String>,
+        report: &mut WorkflowRunReport,
+    ) -> Result<(), String> {
         let total_nodes = execution_order.len();
         
         // Execute nodes in order with proper error handling
         for (index, node_id) in execution_order.iter().enumerate() {
+            if self.cancel_token.is_cancelled() {
+                self.execution_status = ExecutionStatus::Cancelled;
+            }
             if let ExecutionStatus::Cancelled = self.execution_status {
                 break;
             }
             
+            // Already run together with the first node of its segment
+            if plan.runs_with_earlier_node(node_id) {
+                continue;
+            }
+            
             self.current_node = Some(node_id.clone());
             self.current_step = index + 1;
+            self.total_steps = total_nodes;
             self.progress = (index as f32) / (total_nodes as f32);
             
+            if let Some(segment) = plan.segment_starting_at(node_id) {
+                let cache_key = cache_keys.get(segment.tail());
+                for id in &segment.nodes {
+                    self.run_state.set_status(id, NodeRunStatus::Running);
+                }
+                if let Err(e) = self.run_segment(workflow, segment, index, total_nodes, cache.as_deref_mut(), cache_key, report) {
+                    for id in &segment.nodes {
+                        self.run_state.fail(id, &e);
+                    }
+                    log_error!("Streamed nodes {} failed: {}", segment.nodes.join(" → "), e);
+                    self.execution_status = ExecutionStatus::Failed(e.clone());
+                    self.cleanup_workflow_temp_dir();
+                    return Err(format!("Node {} failed: {}", node_id, e));
+                }
+                for id in &segment.nodes {
+                    self.run_state.set_status(id, NodeRunStatus::Done);
+                }
+                continue;
+            }
+            
             if let Some(node) = workflow.nodes.get(node_id) {
+                let started_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+                let cache_key = cache_keys.get(node_id).filter(|_| crate::workflow_cache::is_cacheable(&node.node_type));
+                let cached_outputs = match (cache.as_deref_mut(), cache_key) {
+                    (Some(cache), Some(key)) if !self.bypass_cache => cache.lookup(key),
+                    _ => None,
+                };
+                if let Some(output_files) = cached_outputs {
+                    log_info!("Skipping node {}/{}: {} ({}), outputs are cached", index + 1, total_nodes, node_id, node.node_type.display_name());
+                    let result = NodeExecutionResult {
+                        node_id: node_id.clone(),
+                        success: true,
+                        output_files,
+                        error_message: None,
+                        execution_time: std::time::Duration::ZERO,
+                        cached: true,
+                    };
+                    report.record_node(NodeRunRecord::new(index + 1, &node.node_type, started_at, &result, Vec::new()), false);
+                    self.execution_results.push(result);
+                    self.run_state.set_status(node_id, NodeRunStatus::Done);
+                    continue;
+                }
+                
                 log_info!("Executing node {}/{}: {} ({})", index + 1, total_nodes, node_id, node.node_type.display_name());
-                match self.execute_node(node, &workflow) {
-                    Ok(result) => {
+                self.node_commands.borrow_mut().clear();
+                self.run_state.set_status(node_id, NodeRunStatus::Running);
+                match self.execute_node(node, workflow) {
+                    Ok(mut result) => {
+                        if let (Some(cache), Some(key), Some(temp_dir)) = (cache.as_deref_mut(), cache_key, self.workflow_temp_dir.as_deref()) {
+                            if result.success {
+                                if let Some(stored) = cache.store(key, &result.output_files, temp_dir) {
+                                    result.output_files = stored;
+                                }
+                            }
+                        }
+                        let commands = self.node_commands.borrow_mut().drain(..).collect();
+                        report.record_node(
+                            NodeRunRecord::new(index + 1, &node.node_type, started_at, &result, commands),
+                            node.node_type == NodeType::OutputFile,
+                        );
                         if result.success {
                             log_info!("Node {} completed successfully", node_id);
                             self.execution_results.push(result);
+                            self.run_state.set_status(node_id, NodeRunStatus::Done);
                         } else {
                             let error_msg = result.error_message.unwrap_or_else(|| "Unknown error".to_string());
+                            self.run_state.fail(node_id, &error_msg);
                             log_error!("Node {} failed: {}", node_id, error_msg);
                             self.execution_status = ExecutionStatus::Failed(error_msg.clone());
                             self.cleanup_workflow_temp_dir();
@@ -2654,6 +4742,7 @@ impl WorkflowExecutor {
                         }
                     }
                     Err(e) => {
+                        self.run_state.fail(node_id, &e);
                         log_error!("Node {} failed: {}", node_id, e);
                         self.execution_status = ExecutionStatus::Failed(e.clone());
                         self.cleanup_workflow_temp_dir();
@@ -2669,6 +4758,11 @@ impl WorkflowExecutor {
             }
         }
         
+        if self.execution_status == ExecutionStatus::Cancelled {
+            self.current_node = None;
+            return Ok(());
+        }
+        
         self.progress = 1.0;
         self.execution_status = ExecutionStatus::Success;
         self.current_node = None;
@@ -2676,6 +4770,92 @@ impl WorkflowExecutor {
         Ok(())
     }
     
+    /// Run the nodes of a streamed segment as one ffmpeg, or several piped into each other. Only the
+    /// last node has an output file; the others are recorded without artifacts.
+    #[allow(clippy::too_many_arguments)]
+    fn run_segment(
+        &mut self,
+        workflow: &AutomationWorkflow,
+        segment: &crate::workflow_plan::Segment,
+        index: usize,
+        total_nodes: usize,
+        mut cache: Option<&mut crate::workflow_cache::WorkflowCache>,
+        cache_key: Option<&String>,
+        report: &mut WorkflowRunReport,
+    ) -> Result<(), String> {
+        let started_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        let start_time = std::time::Instant::now();
+        let head = workflow.nodes.get(segment.head()).ok_or_else(|| format!("Node {} not found in workflow", segment.head()))?;
+        
+        let cached_outputs = match (cache.as_deref_mut(), cache_key) {
+            (Some(cache), Some(key)) if !self.bypass_cache => cache.lookup(key),
+            _ => None,
+        };
+        let (output_files, commands, cached) = match cached_outputs {
+            Some(output_files) => {
+                log_info!("Skipping streamed nodes {}, outputs are cached", segment.nodes.join(" → "));
+                (output_files, Vec::new(), true)
+            }
+            None => {
+                let input_file = self.get_input_file_for_node(head, workflow)?;
+                let output_file = self.generate_temp_file("streamed", segment.output_extension(workflow));
+                let commands = segment.commands(&input_file, &output_file);
+                // Recorded as one shell pipeline
+                let command_line = segment.command_line(&input_file, &output_file);
+                log_info!("Running {} nodes in {} ffmpeg process(es): {}", segment.nodes.len(), commands.len(), segment.nodes.join(" → "));
+                
+                // Progress of the segment follows the last process, the one writing the file
+                let duration = crate::probe_cache::probe_cache().get_file_info(&input_file).map(|info| info.duration).unwrap_or(0.0);
+                let span = segment.nodes.len() as f32 / total_nodes as f32;
+                let base = index as f32 / total_nodes as f32;
+                let progress = &mut self.progress;
+                let run_state = &self.run_state;
+                if !self.dry_run {
+                    crate::workflow_plan::run_pipeline(&commands, &mut |seconds| {
+                        if duration > 0.0 {
+                            let fraction = (seconds / duration).clamp(0.0, 1.0) as f32;
+                            *progress = base + span * fraction;
+                            for id in &segment.nodes {
+                                run_state.set_progress(id, fraction);
+                            }
+                        }
+                    })?;
+                }
+                if !self.file_written(&output_file) {
+                    return Err(format!("Failed to create output file: {}", output_file));
+                }
+                self.temp_files.push(output_file.clone());
+                
+                let mut output_files = vec![output_file];
+                if let (Some(cache), Some(key), Some(temp_dir)) = (cache, cache_key, self.workflow_temp_dir.as_deref()) {
+                    if let Some(stored) = cache.store(key, &output_files, temp_dir) {
+                        output_files = stored;
+                    }
+                }
+                (output_files, vec![command_line], false)
+            }
+        };
+        
+        for (offset, node_id) in segment.nodes.iter().enumerate() {
+            let Some(node) = workflow.nodes.get(node_id) else {
+                continue;
+            };
+            let is_tail = node_id == segment.tail();
+            let result = NodeExecutionResult {
+                node_id: node_id.clone(),
+                success: true,
+                output_files: if is_tail { output_files.clone() } else { Vec::new() },
+                error_message: None,
+                execution_time: if is_tail { start_time.elapsed() } else { std::time::Duration::ZERO },
+                cached,
+            };
+            let node_commands = if offset == 0 { commands.clone() } else { Vec::new() };
+            report.record_node(NodeRunRecord::new(index + offset + 1, &node.node_type, started_at.clone(), &result, node_commands), false);
+            self.execution_results.push(result);
+        }
+        Ok(())
+    }
+    
     /// Execute a single node
     fn execute_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<NodeExecutionResult, String> {
         let start_time = std::time::Instant::now();
@@ -2769,6 +4949,9 @@ impl WorkflowExecutor {
             NodeType::CreateArchive => self.execute_create_archive_node(node, workflow),
             NodeType::ExtractArchive => self.execute_extract_archive_node(node, workflow),
             NodeType::BatchConvert => self.execute_batch_convert_node(node, workflow),
+            // Runs replace groups by their contents first
+            NodeType::Group => Err("Group node without contents".to_string()),
+            NodeType::Unknown(ref type_name) => Err(format!("Node type {} is not supported by this version", type_name)),
         };
         
         let execution_time = start_time.elapsed();
@@ -2780,6 +4963,7 @@ impl WorkflowExecutor {
                 output_files,
                 error_message: None,
                 execution_time,
+                cached: false,
             }),
             Err(e) => Ok(NodeExecutionResult {
                 node_id: node.id.clone(),
@@ -2787,6 +4971,7 @@ impl WorkflowExecutor {
                 output_files: Vec::new(),
                 error_message: Some(e.clone()),
                 execution_time,
+                cached: false,
             })
         }
     }
@@ -2847,7 +5032,7 @@ impl WorkflowExecutor {
         log_info!("Extract Audio: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("Audio extraction completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -2876,7 +5061,7 @@ impl WorkflowExecutor {
         log_info!("Extract Video: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("Video extraction completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -2905,7 +5090,7 @@ impl WorkflowExecutor {
         log_info!("Audio Resample: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("Audio resampling completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -2983,7 +5168,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -3002,9 +5187,7 @@ impl WorkflowExecutor {
     
     /// Detect if a file is audio-only, video-only, or has both streams
     fn detect_media_type(&self, file_path: &str) -> Result<(bool, bool), String> {
-        // Use bundled FFmpeg worker for stream detection
-        let worker = FFmpegWorker::new();
-        match worker.detect_streams(file_path) {
+        match crate::probe_cache::probe_cache().detect_streams(file_path) {
             Ok((has_video, has_audio, _resolution)) => {
                 Ok((has_video, has_audio))
             }
@@ -3200,7 +5383,7 @@ impl WorkflowExecutor {
         log_debug!("Output node: Input file = {}", input_file);
         
         // Check if input file exists
-        if !std::path::Path::new(&input_file).exists() {
+        if !self.file_written(&input_file) {
             log_error!("Input file for output node does not exist: {}", input_file);
             return Err(format!("Input file for output node does not exist: {}", input_file));
         } else {
@@ -3217,6 +5400,25 @@ impl WorkflowExecutor {
         
         log_debug!("Output node: Output path = {}", output_path);
         
+        // Nothing to copy in a dry run
+        if self.dry_run {
+            self.node_copies.borrow_mut().push((input_file, output_path.clone()));
+            return Ok(vec![output_path]);
+        }
+        
+        // A folder of images from a Video to Images node is copied as a folder
+        if std::path::Path::new(&input_file).is_dir() {
+            let output = std::path::Path::new(&output_path);
+            let destination = match std::path::Path::new(&input_file).file_name() {
+                Some(name) if output.is_dir() => output.join(name),
+                _ => output.with_extension(""),
+            };
+            let copied = crate::image_sequence::copy_directory(&input_file, &destination)
+                .map_err(|e| format!("Failed to copy the images to {}: {}", destination.display(), e))?;
+            log_info!("Copied {} images to {}", copied, destination.display());
+            return Ok(vec![destination.display().to_string()]);
+        }
+        
         // Detect input file type to determine appropriate output format
         let (has_video, has_audio) = match self.detect_media_type(&input_file) {
             Ok(types) => {
@@ -3332,7 +5534,7 @@ impl WorkflowExecutor {
         log_info!("Audio Convert: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("Audio conversion completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3364,7 +5566,7 @@ impl WorkflowExecutor {
         log_info!("Video Convert: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("Video conversion completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3442,7 +5644,7 @@ impl WorkflowExecutor {
         log_info!("🎵 Audio Compress: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Audio compression completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3471,7 +5673,7 @@ impl WorkflowExecutor {
         log_info!("🔊 Audio Volume: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Audio volume adjustment completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3500,7 +5702,7 @@ impl WorkflowExecutor {
         log_info!("✂️ Audio Trim: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Audio trimming completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3531,7 +5733,7 @@ impl WorkflowExecutor {
         log_info!("🔀 Audio Merge: {} + {} -> {} (using mature implementation)", audio1_file, audio2_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Audio merging completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3543,165 +5745,147 @@ impl WorkflowExecutor {
         }
     }
     
-    /// Execute audio normalization
+    /// Execute audio normalization: two-pass loudnorm towards the node's loudness targets
     fn execute_audio_normalize_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("normalized_audio", "wav");
+        let format = node.parameters.get("format").map(|p| p.value.clone()).unwrap_or_else(|| "wav".to_string());
+        let output_file = self.generate_temp_file("normalized_audio", &format);
         
-        let command = format!(
-            "ffmpeg -i \"{}\" -af \"loudnorm\" \"{}\"",
-            input_file, output_file
+        let mut task = self.create_processing_task(
+            OperationType::AudioNormalize, 
+            &input_file, 
+            &output_file, 
+            node
         );
+        if let Some(audio_settings) = task.audio_settings.as_mut() {
+            audio_settings.format = format;
+        }
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("📊 Audio Normalize: {} -> {} (using mature implementation)", input_file, output_file);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        // Execute using mature TaskExecutor
+        match self.execute_task(&mut task) {
+            Ok(_) => {
+                log_info!("✅ Audio normalization completed successfully");
+                self.temp_files.push(output_file.clone());
+                Ok(vec![output_file])
+            }
+            Err(e) => {
+                Err(format!("Audio normalization failed: {}", e))
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
     }
     
     /// Execute audio noise reduction
     fn execute_audio_denoise_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("denoised_audio", "wav");
+        let format = node.parameters.get("format").map(|p| p.value.clone()).unwrap_or_else(|| "wav".to_string());
+        let output_file = self.generate_temp_file("denoised_audio", &format);
         
-        let command = format!(
-            "ffmpeg -i \"{}\" -af \"afftdn\" \"{}\"",
-            input_file, output_file
+        // Create ProcessingTask using mature implementation; the denoise filter goes into its audio filter chain
+        let mut task = self.create_processing_task(
+            OperationType::AudioConvert, 
+            &input_file, 
+            &output_file, 
+            node
         );
+        if let Some(audio_settings) = task.audio_settings.as_mut() {
+            audio_settings.format = format;
+        }
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("🔇 Audio DeNoise: {} -> {} (using mature implementation)", input_file, output_file);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        // Execute using mature TaskExecutor
+        match self.execute_task(&mut task) {
+            Ok(_) => {
+                log_info!("✅ Audio noise reduction completed successfully");
+                self.temp_files.push(output_file.clone());
+                Ok(vec![output_file])
+            }
+            Err(e) => {
+                Err(format!("Audio noise reduction failed: {}", e))
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
     }
     
-    /// Execute audio equalizer
+    /// Execute audio equalizer; flat bands with no advanced filters copy the audio through
     fn execute_audio_equalizer_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("equalized_audio", "wav");
-        
-        let eq_settings = node.parameters.get("eq_settings")
-            .map(|p| p.value.as_str())
-            .unwrap_or("equalizer=f=1000:t=h:w=200:g=2");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -af \"{}\" \"{}\"",
-            input_file, eq_settings, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
+        let format = node.parameters.get("format").map(|p| p.value.clone()).unwrap_or_else(|| "wav".to_string());
+        let output_file = self.generate_temp_file("equalized_audio", &format);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        let filter = equalizer_from_params(&node.parameters).filter()
+            .map_err(|e| format!("Audio EQ: {}", e))?;
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin", "-i", &input_file].map(String::from).to_vec();
+        match &filter {
+            Some(filter) => args.extend(["-af".to_string(), filter.clone()]),
+            None => log_info!("Audio EQ is flat, the audio is only converted"),
         }
+        args.extend(["-vn".to_string(), "-y".to_string(), output_file.clone()]);
         
+        log_info!("🎛 Audio EQ: {} -> {}", input_file, output_file);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Audio equalization failed: {}", e))?;
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
     
-    /// Execute audio fade
+    /// Execute audio fade; the fade-out is placed from the probed duration of the input
     fn execute_audio_fade_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("faded_audio", "wav");
+        let format = node.parameters.get("format").map(|p| p.value.clone()).unwrap_or_else(|| "wav".to_string());
+        let output_file = self.generate_temp_file("faded_audio", &format);
         
-        let fade_in = node.parameters.get("fade_in")
-            .map(|p| p.value.as_str())
-            .unwrap_or("1");
-        let fade_out = node.parameters.get("fade_out")
-            .map(|p| p.value.as_str())
-            .unwrap_or("1");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -af \"afade=t=in:d={},afade=t=out:st=10:d={}\" \"{}\"",
-            input_file, fade_in, fade_out, output_file
+        let fade = fade_from_params(&node.parameters)?;
+        fade.validate().map_err(|e| e.to_string())?;
+        if fade.needs_duration() {
+            let duration = crate::probe_cache::probe_cache().get_file_info(&input_file).ok()
+                .map(|info| info.duration)
+                .filter(|duration| duration.is_finite() && *duration > 0.0)
+                .ok_or_else(|| format!("Cannot fade out: the duration of {} could not be determined", input_file))?;
+            log_debug!("Fade-out of {}s starts at {:.3}s", fade.fade_out_seconds, duration - fade.fade_out_seconds);
+        }
+        
+        let mut task = self.create_processing_task(
+            OperationType::AudioConvert, 
+            &input_file, 
+            &output_file, 
+            node
         );
+        if let Some(audio_settings) = task.audio_settings.as_mut() {
+            audio_settings.format = format;
+            audio_settings.fade = fade;
+        }
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("📉 Audio Fade: {} -> {} (using mature implementation)", input_file, output_file);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        // Execute using mature TaskExecutor
+        match self.execute_task(&mut task) {
+            Ok(_) => {
+                log_info!("✅ Audio fade completed successfully");
+                self.temp_files.push(output_file.clone());
+                Ok(vec![output_file])
+            }
+            Err(e) => {
+                Err(format!("Audio fade failed: {}", e))
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
     }
     
     /// Execute audio echo
     fn execute_audio_echo_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("echo_audio", "wav");
-        
-        let delay = node.parameters.get("delay")
-            .map(|p| p.value.as_str())
-            .unwrap_or("0.5");
-        let decay = node.parameters.get("decay")
-            .map(|p| p.value.as_str())
-            .unwrap_or("0.5");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -af \"aecho={}:{}\" \"{}\"",
-            input_file, delay, decay, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
+        let format = node.parameters.get("format").map(|p| p.value.clone()).unwrap_or_else(|| "wav".to_string());
+        let output_file = self.generate_temp_file("echo_audio", &format);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
-        }
+        let filter = echo_from_params(&node.parameters).filter()
+            .map_err(|e| format!("Audio Echo: {}", e))?;
+        let args: Vec<String> = ["-hide_banner", "-nostdin", "-i", &input_file, "-af", &filter, "-vn", "-y", &output_file]
+            .map(String::from).to_vec();
         
+        log_info!("🔄 Audio Echo: {} -> {}", input_file, output_file);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Audio echo failed: {}", e))?;
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -3723,7 +5907,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -3755,14 +5939,15 @@ impl WorkflowExecutor {
             output_file.clone()
         );
         
-        // Set both video and audio settings
+        // Set both video and audio settings; the video is copied whenever the container accepts it
         task.video_settings = Some(self.node_params_to_video_settings(node));
-        task.audio_settings = Some(self.node_params_to_audio_settings(node));
-        
+        let mut audio_settings = self.node_params_to_audio_settings(node);
+        audio_settings.copy_audio = node.parameters.get("copy_streams").map(|p| p.value == "true").unwrap_or(true);
+        task.audio_settings = Some(audio_settings);
         log_info!("🔗 Combine A/V: {} + {} -> {} (using mature implementation)", video_input, audio_input, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Audio/Video combining completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3793,7 +5978,7 @@ impl WorkflowExecutor {
         log_info!("📉 Video Compress: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Video compression completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3816,7 +6001,7 @@ impl WorkflowExecutor {
         let mut task = self.create_processing_task(OperationType::VideoResize, &input_file, &output_file, node);
         log_info!("📏 Video Resize: {} -> {} (using mature implementation)", input_file, output_file);
         
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => { log_info!("✅ Video resizing completed successfully"); self.temp_files.push(output_file.clone()); Ok(vec![output_file]) }
             Err(e) => Err(format!("Video resizing failed: {}", e))
         }
@@ -3840,7 +6025,7 @@ impl WorkflowExecutor {
         log_info!("✂️ Video Crop: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Video cropping completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3869,7 +6054,7 @@ impl WorkflowExecutor {
         log_info!("🔄 Video Rotate: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Video rotation completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3898,7 +6083,7 @@ impl WorkflowExecutor {
         log_info!("🎨 Video Filter: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Video filtering completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -3927,7 +6112,7 @@ impl WorkflowExecutor {
         log_info!("🇫 Frame Extract: {} -> {} (using mature implementation)", input_file, output_pattern);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Frame extraction completed successfully");
                 self.temp_files.push(output_pattern.clone());
@@ -3944,59 +6129,58 @@ impl WorkflowExecutor {
         let input_file = self.get_input_file_for_node(node, workflow)?;
         let output_file = self.generate_temp_file("fps_converted_video", "mp4");
         
-        let fps = node.parameters.get("fps")
-            .map(|p| p.value.as_str())
-            .unwrap_or("30");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -filter:v fps={} \"{}\"",
-            input_file, fps, output_file
+        let mut task = self.create_processing_task(
+            OperationType::FrameRateConvert, 
+            &input_file, 
+            &output_file, 
+            node
         );
+        if let Some(video_settings) = task.video_settings.as_mut() {
+            video_settings.container_format = "mp4".to_string();
+        }
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("⚡ Video FPS: {} -> {} (using mature implementation)", input_file, output_file);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        // Execute using mature TaskExecutor
+        match self.execute_task(&mut task) {
+            Ok(_) => {
+                log_info!("✅ Frame rate conversion completed successfully");
+                self.temp_files.push(output_file.clone());
+                Ok(vec![output_file])
+            }
+            Err(e) => {
+                Err(format!("Frame rate conversion failed: {}", e))
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
     }
     
-    /// Execute video stabilization
+    /// Execute video stabilization: vidstabdetect writes the camera motion to a transforms file,
+    /// vidstabtransform applies the smoothed motion. Each pass fills half of the node's progress.
     fn execute_video_stabilize_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
+        if !crate::stabilize::vidstab_available() {
+            return Err("This ffmpeg build has no vid.stab filters (vidstabdetect/vidstabtransform); stabilizing needs an ffmpeg built with --enable-libvidstab".to_string());
+        }
         let input_file = self.get_input_file_for_node(node, workflow)?;
         let output_file = self.generate_temp_file("stabilized_video", "mp4");
+        let transforms_file = self.generate_temp_file("stabilize_transforms", "trf");
+        let settings = stabilize_from_params(&node.parameters);
         
-        let command = format!(
-            "ffmpeg -i \"{}\" -vf \"deshake\" \"{}\"",
-            input_file, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
+        let passes = [
+            crate::stabilize::StabilizeSettings::detect_args(&input_file, &transforms_file),
+            settings.transform_args(&input_file, &output_file, &transforms_file),
+        ];
+        log_info!("🎯 Video Stabilize: {} -> {}", input_file, output_file);
+        let result = self.run_node_passes(&passes, &input_file);
+        if let Err(e) = std::fs::remove_file(&transforms_file) {
+            log_debug!("Could not remove transforms file {}: {}", transforms_file, e);
+        }
+        result.map_err(|e| format!("Video stabilization failed: {}", e))?;
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
+        if !self.file_written(&output_file) {
             log_error!("Output file was not created: {}", output_file);
             return Err(format!("Failed to create output file: {}", output_file));
         }
-        
+        log_info!("✅ Video stabilization completed successfully");
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -4014,7 +6198,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4047,7 +6231,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4083,7 +6267,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4116,7 +6300,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4149,7 +6333,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4167,79 +6351,50 @@ impl WorkflowExecutor {
     
     // Combination and Advanced Operations
     
-    /// Execute video overlay
+    /// Execute video overlay: the second input laid over the first at the node's position
     fn execute_video_overlay_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let main_video = self.get_input_file_for_port(node, workflow, 0)?;
-        let overlay_video = self.get_input_file_for_port(node, workflow, 1)?;
-        let output_file = self.generate_temp_file("overlay_video", "mp4");
-        
-        let x = node.parameters.get("x")
-            .map(|p| p.value.as_str())
-            .unwrap_or("10");
-        let y = node.parameters.get("y")
-            .map(|p| p.value.as_str())
-            .unwrap_or("10");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -i \"{}\" -filter_complex \"[0:v][1:v]overlay={}:{}[v]\" -map \"[v]\" \"{}\"",
-            main_video, overlay_video, x, y, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
-        
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
-        }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
+        let settings = overlay_from_params(&node.parameters);
+        let graph = settings.video_graph().map_err(|e| e.to_string())?;
+        self.execute_two_video_node(node, workflow, "overlay_video", graph, settings.audio)
     }
     
-    /// Execute picture-in-picture
+    /// Execute picture-in-picture: the second input shrunk into a corner or the center of the first
     fn execute_video_pip_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let main_video = self.get_input_file_for_port(node, workflow, 0)?;
-        let pip_video = self.get_input_file_for_port(node, workflow, 1)?;
-        let output_file = self.generate_temp_file("pip_video", "mp4");
-        
-        let scale = node.parameters.get("scale")
-            .map(|p| p.value.as_str())
-            .unwrap_or("0.3");
-        let x = node.parameters.get("x")
-            .map(|p| p.value.as_str())
-            .unwrap_or("main_w-overlay_w-10");
-        let y = node.parameters.get("y")
-            .map(|p| p.value.as_str())
-            .unwrap_or("10");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -i \"{}\" -filter_complex \"[1:v]scale=iw*{}:ih*{}[pip];[0:v][pip]overlay={}:{}[v]\" -map \"[v]\" \"{}\"",
-            main_video, pip_video, scale, scale, x, y, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
-        
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
+        let settings = pip_from_params(&node.parameters);
+        let main_video = self.get_input_file_for_port(node, workflow, 0)?;
+        let main_width = crate::probe_cache::probe_cache().get_file_info(&main_video).ok()
+            .and_then(|info| info.video_streams.first().map(|stream| stream.width));
+        let graph = settings.video_graph(main_width).map_err(|e| e.to_string())?;
+        self.execute_two_video_node(node, workflow, "pip_video", graph, settings.audio)
+    }
+    
+    /// Run a filter_complex over the videos on input ports 0 (main) and 1, which may be files
+    /// written by earlier nodes. The output is as long as the main video.
+    fn execute_two_video_node(
+        &mut self,
+        node: &AutomationNode,
+        workflow: &AutomationWorkflow,
+        name: &str,
+        video_graph: String,
+        audio: crate::overlay::OverlayAudio,
+    ) -> Result<Vec<String>, String> {
+        let main_video = self.get_input_file_for_port(node, workflow, 0)?;
+        let second_video = self.get_input_file_for_port(node, workflow, 1)?;
+        let output_file = self.generate_temp_file(name, "mp4");
+        
+        let has_audio = |path: &str| crate::probe_cache::probe_cache().detect_streams(path).map(|(_, audio, _)| audio).unwrap_or(false);
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin", "-i", &main_video, "-i", &second_video].map(String::from).to_vec();
+        args.extend(crate::overlay::composition_args(video_graph, audio, has_audio(&main_video), has_audio(&second_video)));
+        args.extend(["-y".to_string(), output_file.clone()]);
+        
+        log_info!("{}: {} + {} -> {}", node.node_type.display_name(), main_video, second_video, output_file);
+        self.run_node_passes(&[args], &main_video)
+            .map_err(|e| format!("{} failed: {}", node.node_type.display_name(), e))?;
+        
+        if !self.file_written(&output_file) {
             log_error!("Output file was not created: {}", output_file);
             return Err(format!("Failed to create output file: {}", output_file));
         }
-        
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -4258,7 +6413,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4290,7 +6445,7 @@ impl WorkflowExecutor {
         log_info!("🎭 Video to GIF: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Video to GIF conversion completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -4318,7 +6473,7 @@ impl WorkflowExecutor {
         log_info!("📏 GIF Resize: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ GIF resizing completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -4330,54 +6485,75 @@ impl WorkflowExecutor {
         }
     }
     
-    /// Execute video to images conversion
+    /// Execute video to images: the frames go into a folder, which is the node's output
     fn execute_video_to_images_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_pattern = self.generate_temp_file("image_%04d", "png");
+        let settings = image_extract_from_params(&node.parameters);
+        let output_dir = match settings.output_dir.trim() {
+            "" => self.generate_temp_file("images", "frames"),
+            dir => dir.to_string(),
+        };
+        self.prepare_output_dir(&output_dir)
+            .map_err(|e| format!("Video to Images: {}", e))?;
+        let args = settings.args(&input_file, &output_dir)
+            .map_err(|e| format!("Video to Images: {}", e))?;
         
-        let fps = node.parameters.get("fps")
-            .map(|p| p.value.as_str())
-            .unwrap_or("1");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -vf fps={} \"{}\"",
-            input_file, fps, output_pattern
-        );
+        log_info!("📸 Video to Images: {} -> {}", input_file, output_dir);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Extracting images failed: {}", e))?;
+        if self.dry_run {
+            return Ok(vec![output_dir]);
+        }
         
-        self.execute_ffmpeg_command(&command)?;
-        self.temp_files.push(output_pattern.clone());
-        Ok(vec![output_pattern])
+        let written = crate::image_sequence::resolve_input(&output_dir, &settings.file_pattern())
+            .map_err(|e| format!("Video to Images wrote no images: {}", e))?;
+        log_info!("Wrote {} images to {}", written.count(), output_dir);
+        Ok(vec![output_dir])
     }
     
-    /// Execute images to video conversion
+    /// Execute images to video. The images come from a connected Video to Images folder or the
+    /// node's own folder; the soundtrack from the audio input or the node's audio file.
     fn execute_images_to_video_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let image_pattern = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("images_to_video", "mp4");
+        let settings = image_sequence_from_params(&node.parameters);
+        let (directory, pattern) = match self.get_input_file_for_port(node, workflow, 0) {
+            Ok(images) if std::path::Path::new(&images).is_dir() => (images, settings.pattern.clone()),
+            // A frame pattern path, as Frame Extract nodes write
+            Ok(images) => {
+                let path = std::path::Path::new(&images);
+                let directory = path.parent().map(|dir| dir.display().to_string()).unwrap_or_default();
+                let pattern = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                (directory, pattern)
+            }
+            Err(_) => (settings.directory.clone(), settings.pattern.clone()),
+        };
+        let audio_file = match self.get_input_file_for_port(node, workflow, 1) {
+            Ok(audio) => Some(audio),
+            Err(_) => Some(settings.audio_file.clone()).filter(|file| !file.is_empty()),
+        };
+        if let Some(audio) = audio_file.as_deref().filter(|audio| !std::path::Path::new(audio).is_file()) {
+            return Err(format!("Images to Video: the audio file {} does not exist", audio));
+        }
         
-        let fps = node.parameters.get("fps")
-            .map(|p| p.value.as_str())
-            .unwrap_or("25");
-            
-        let command = format!(
-            "ffmpeg -framerate {} -i \"{}\" -c:v libx264 -pix_fmt yuv420p \"{}\"",
-            fps, image_pattern, output_file
-        );
+        let input = crate::image_sequence::resolve_input(&directory, &pattern)
+            .map_err(|e| format!("Images to Video: {}", e))?;
+        let list_file = self.generate_temp_file("images_list", "txt");
+        // A dry run only names the list file
+        if let (false, crate::image_sequence::SequenceInput::Listed(files)) = (self.dry_run, &input) {
+            std::fs::write(&list_file, crate::image_sequence::concat_list(files, settings.rate))
+                .map_err(|e| format!("Failed to write the image list: {}", e))?;
+            self.temp_files.push(list_file.clone());
+        }
+        let output_file = self.generate_temp_file("images_to_video", crate::image_sequence::container_for_codec(&settings.codec));
+        let args = settings.args(&input, &list_file, audio_file.as_deref(), &output_file);
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("🎥 Images to Video: {} images from {} -> {}", input.count(), directory, output_file);
+        self.run_node_passes(&[args], audio_file.as_deref().unwrap_or_default())
+            .map_err(|e| format!("Images to Video failed: {}", e))?;
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
+        if !self.file_written(&output_file) {
             log_error!("Output file was not created: {}", output_file);
             return Err(format!("Failed to create output file: {}", output_file));
         }
-        
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -4401,7 +6577,7 @@ impl WorkflowExecutor {
         log_info!("📝 Add Subtitle: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Subtitle addition completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -4430,7 +6606,7 @@ impl WorkflowExecutor {
         log_info!("💧 Add Watermark: {} -> {} (using mature implementation)", input_file, output_file);
         
         // Execute using mature TaskExecutor
-        match TaskExecutor::execute_task(&mut task) {
+        match self.execute_task(&mut task) {
             Ok(_) => {
                 log_info!("✅ Watermark addition completed successfully");
                 self.temp_files.push(output_file.clone());
@@ -4446,40 +6622,20 @@ impl WorkflowExecutor {
     fn execute_add_text_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
         let output_file = self.generate_temp_file("text_overlay_video", "mp4");
+        let filter = text_overlay_from_params(&node.parameters).filter()
+            .map_err(|e| format!("Add Text: {}", e))?;
         
-        let text = node.parameters.get("text")
-            .map(|p| p.value.as_str())
-            .unwrap_or("Sample Text");
-        let x = node.parameters.get("x")
-            .map(|p| p.value.as_str())
-            .unwrap_or("10");
-        let y = node.parameters.get("y")
-            .map(|p| p.value.as_str())
-            .unwrap_or("10");
-        let font_size = node.parameters.get("font_size")
-            .map(|p| p.value.as_str())
-            .unwrap_or("24");
-            
-        let command = format!(
-            "ffmpeg -i \"{}\" -vf \"drawtext=text='{}':fontsize={}:x={}:y={}:fontcolor=white\" \"{}\"",
-            input_file, text, font_size, x, y, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
+        let args: Vec<String> = ["-hide_banner", "-nostdin", "-i", &input_file, "-vf", &filter, "-c:a", "copy", "-y", &output_file]
+            .map(String::from)
+            .to_vec();
+        log_info!("📝 Add Text: {} -> {}", input_file, output_file);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Adding text failed: {}", e))?;
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
+        if !self.file_written(&output_file) {
             log_error!("Output file was not created: {}", output_file);
             return Err(format!("Failed to create output file: {}", output_file));
         }
-        
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -4507,7 +6663,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4543,7 +6699,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4609,12 +6765,25 @@ impl WorkflowExecutor {
     }
     
     /// Helper: Generate temporary file path with workflow-specific naming
+    /// Whether a command wrote `path`. A dry run writes nothing, so every path counts.
+    fn file_written(&self, path: impl AsRef<std::path::Path>) -> bool {
+        self.dry_run || path.as_ref().exists()
+    }
+    
+    /// Create or empty a node's output folder; left alone in a dry run
+    fn prepare_output_dir(&self, dir: &str) -> Result<(), String> {
+        if self.dry_run {
+            return Ok(());
+        }
+        crate::frame_extract::prepare_output_dir(dir)
+    }
+    
     fn generate_temp_file(&self, node_type: &str, extension: &str) -> String {
         if let Some(ref workflow_dir) = self.workflow_temp_dir {
             log_debug!("📁 Using workflow temp directory: {}", workflow_dir);
             
-            // Check if directory exists
-            if !std::path::Path::new(workflow_dir).exists() {
+            // Check if directory exists; a dry run's directory is only a placeholder
+            if !self.dry_run && !std::path::Path::new(workflow_dir).exists() {
                 log_warn!("⚠️ Workflow temp directory does not exist! Attempting to create...");
                 if let Err(e) = std::fs::create_dir_all(workflow_dir) {
                     log_error!("❌ Failed to create workflow temp directory: {}", e);
@@ -4654,8 +6823,46 @@ impl WorkflowExecutor {
     }
     
     /// Helper: Execute FFmpeg command using TaskExecutor style
+    /// Run the ffmpeg passes of the current node one after another, recording them for the run
+    /// report. Each pass fills an equal part of the node's share of the progress, measured against
+    /// the duration of `timed_input`. Returns the last lines the final pass logged.
+    fn run_node_passes(&mut self, passes: &[Vec<String>], timed_input: &str) -> Result<Vec<String>, String> {
+        for args in passes {
+            let argv: Vec<String> = std::iter::once("ffmpeg".to_string()).chain(args.iter().cloned()).collect();
+            self.node_commands.borrow_mut().push(crate::shell_quote::command_line(&argv, crate::shell_quote::Shell::Posix));
+        }
+        if self.dry_run {
+            return Ok(Vec::new());
+        }
+        
+        let duration = crate::probe_cache::probe_cache().get_file_info(timed_input).map(|info| info.duration).unwrap_or(0.0);
+        let span = 1.0 / self.total_steps.max(1) as f32;
+        let base = self.current_step.saturating_sub(1) as f32 * span;
+        let pass_share = span / passes.len().max(1) as f32;
+        let mut log_tail = Vec::new();
+        for (pass, args) in passes.iter().enumerate() {
+            if passes.len() > 1 {
+                log_info!("Pass {} of {}", pass + 1, passes.len());
+            }
+            let progress = &mut self.progress;
+            let (run_state, node_id) = (&self.run_state, self.current_node.as_deref().unwrap_or_default());
+            log_tail = crate::workflow_plan::run_pipeline(std::slice::from_ref(args), &mut |seconds| {
+                if duration > 0.0 {
+                    let fraction = (seconds / duration).clamp(0.0, 1.0) as f32;
+                    *progress = base + pass_share * (pass as f32 + fraction);
+                    run_state.set_progress(node_id, (pass as f32 + fraction) / passes.len() as f32);
+                }
+            })?;
+        }
+        Ok(log_tail)
+    }
+    
     fn execute_ffmpeg_command(&self, command: &str) -> Result<(), String> {
         log_debug!("Executing FFmpeg command: {}", command);
+        self.node_commands.borrow_mut().push(command.to_string());
+        if self.dry_run {
+            return Ok(());
+        }
         
         // Parse the command to extract args (similar to TaskExecutor)
         let parts: Vec<&str> = command.split_whitespace().collect();
@@ -4666,12 +6873,10 @@ impl WorkflowExecutor {
         // Build command directly like TaskExecutor does
         let mut cmd = std::process::Command::new("ffmpeg");
         
-        // Add the arguments (skip the "ffmpeg" part)
-        for arg in &parts[1..] {
-            // Remove quotes from arguments
-            let clean_arg = arg.trim_matches('"');
-            cmd.arg(clean_arg);
-        }
+        // Add the arguments (skip the "ffmpeg" part), removing quotes
+        let args: Vec<String> = parts[1..].iter().map(|arg| arg.trim_matches('"').to_string()).collect();
+        // Outputs of watch-folder runs carry the tag the watcher skips
+        cmd.args(crate::watch_folder::tagged_output_args(&args).unwrap_or(args));
         
         // Set Windows-specific flags like TaskExecutor
         #[cfg(target_os = "windows")]
@@ -4687,7 +6892,12 @@ impl WorkflowExecutor {
         
         log_debug!("🔍 Executing direct FFmpeg command with args: {:?}", &parts[1..]);
         
-        let output = cmd.output();
+        // Piped like output() does, but spawned so a stop can end it
+        cmd.stdout(std::process::Stdio::piped()).stderr(std::process::Stdio::piped());
+        let output = cmd.spawn().and_then(|child| {
+            let _cancel_watch = crate::run_cancel::watch_process(child.id());
+            child.wait_with_output()
+        });
         
         match output {
             Ok(result) => {
@@ -4714,13 +6924,6 @@ impl WorkflowExecutor {
         }
     }
     
-
-    /// Cancel execution
-    pub fn cancel_execution(&mut self) {
-        self.execution_status = ExecutionStatus::Cancelled;
-        self.current_node = None;
-        self.progress = 0.0;
-    }
     
     /// Create dedicated temporary directory for workflow
     fn create_workflow_temp_dir(&mut self, workflow_id: &str) -> Result<(), String> {
@@ -4750,6 +6953,10 @@ impl WorkflowExecutor {
     
     /// Clean up workflow temporary directory
     pub fn cleanup_workflow_temp_dir(&mut self) {
+        // A stopped run keeps its files when asked to; the run logs where they are
+        if self.dry_run || (self.keep_files_on_cancel && self.cancel_token.is_cancelled()) {
+            return;
+        }
         if let Some(ref temp_dir) = self.workflow_temp_dir {
             if std::path::Path::new(temp_dir).exists() {
                 match std::fs::remove_dir_all(temp_dir) {
@@ -4793,41 +7000,38 @@ impl WorkflowExecutor {
     /// Get detailed execution status
     /// Execute stream preparation node
     fn execute_stream_prep_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("stream_prep", "mp4");
-        
-        let bitrate = node.parameters.get("bitrate")
-            .map(|p| p.value.clone())
-            .unwrap_or_else(|| "2000k".to_string());
-        let resolution = node.parameters.get("resolution")
-            .map(|p| p.value.clone())
-            .unwrap_or_else(|| "1920x1080".to_string());
-        let fps = node.parameters.get("fps")
-            .map(|p| p.value.clone())
-            .unwrap_or_else(|| "30".to_string());
+        let input = self.get_input_file_for_node(node, workflow)?;
+        let settings = stream_packaging_from_params(&node.parameters);
+        // A Multi-Resolution folder becomes the variants of one adaptive stream
+        let sources = if std::path::Path::new(&input).is_dir() {
+            crate::stream_packaging::variant_sources(&input)
+                .map_err(|e| format!("Stream Prep: {}", e))?
+        } else {
+            vec![crate::stream_packaging::StreamSource::probe(&input)]
+        };
+        for warning in settings.compatibility_warnings(&sources) {
+            log_warn!("Stream Prep: {}", warning);
+        }
         
-        let command = format!(
-            "ffmpeg -i \"{}\" -c:v libx264 -b:v {} -r {} -s {} -c:a aac -b:a 128k -f mp4 \"{}\"",
-            input_file, bitrate, fps, resolution, output_file
-        );
+        let output_dir = match settings.output_dir.trim() {
+            "" => self.generate_temp_file("stream", settings.format.key()),
+            dir => dir.to_string(),
+        };
+        self.prepare_output_dir(&output_dir)
+            .map_err(|e| format!("Stream Prep: {}", e))?;
+        let args = settings.args(&sources, &output_dir)
+            .map_err(|e| format!("Stream Prep: {}", e))?;
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("📡 Stream Prep: {} {} with {} variant(s) -> {}", settings.format.name(), input, sources.len(), output_dir);
+        self.run_node_passes(&[args], &sources[0].path)
+            .map_err(|e| format!("Stream packaging failed: {}", e))?;
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        let manifest = settings.manifest_path(&output_dir);
+        if !self.file_written(&manifest) {
+            return Err(format!("Stream Prep did not write {}", manifest.display()));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
+        log_info!("Wrote {}", manifest.display());
+        Ok(vec![output_dir])
     }
 
     /// Execute audio/video sync node
@@ -4854,7 +7058,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4887,7 +7091,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4920,7 +7124,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4949,7 +7153,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -4999,7 +7203,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -5028,7 +7232,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -5044,32 +7248,70 @@ impl WorkflowExecutor {
         Ok(vec![output_file])
     }
 
-    /// Execute quality analysis node
+    /// Execute quality analysis: the distorted input (port 1) compared against the reference
+    /// (port 0). The averages go to the log, the node panel and the node's report file.
     fn execute_quality_analysis_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("quality_report", "txt");
+        let reference = self.get_input_file_for_port(node, workflow, 0)?;
+        let distorted = self.get_input_file_for_port(node, workflow, 1)?;
+        let settings = quality_from_params(&node.parameters);
+        let (metrics, unavailable) = crate::quality_metrics::split_available(&settings.metrics)
+            .map_err(|e| format!("Quality Analysis: {}", e))?;
+        for metric in &unavailable {
+            log_warn!("Quality Analysis: skipping {}, this ffmpeg has no lib{} filter", metric.name(), metric.key());
+        }
+        
+        let size = |path: &str| crate::probe_cache::probe_cache().get_file_info(path).ok()
+            .and_then(|info| info.video_streams.first().map(|stream| (stream.width, stream.height)))
+            .filter(|(width, height)| *width > 0 && *height > 0);
+        let (reference_size, distorted_size) = (size(&reference), size(&distorted));
+        let scaled = match (distorted_size, reference_size) {
+            (Some(distorted_size), Some(reference_size)) if distorted_size != reference_size => Some((distorted_size, reference_size)),
+            _ => None,
+        };
         
-        // Use ffprobe to analyze quality metrics
-        let command = format!(
-            "ffprobe -v error -select_streams v:0 -show_entries frame=pkt_pts_time,pict_type,pkt_size -of csv=p=0 \"{}\" > \"{}\"",
-            input_file, output_file
-        );
+        let stats_files: Vec<(crate::quality_metrics::QualityMetric, String)> = metrics.iter()
+            .map(|metric| (*metric, self.generate_temp_file(&format!("{}_stats", metric.key()), "log")))
+            .collect();
+        self.temp_files.extend(stats_files.iter().map(|(_, file)| file.clone()));
+        let args = crate::quality_metrics::comparison_args(&distorted, &reference, scaled.map(|(_, reference_size)| reference_size), &stats_files);
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("📊 Quality Analysis: {} against {}", distorted, reference);
+        let log_tail = self.run_node_passes(&[args], &reference)
+            .map_err(|e| format!("Quality analysis failed: {}", e))?;
+        if self.dry_run {
+            return Ok(vec![self.generate_temp_file("quality_report", "txt")]);
+        }
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        let (scores, per_frame) = crate::quality_metrics::collect_scores(&log_tail, &stats_files);
+        if scores.is_empty() {
+            return Err("Quality analysis produced no scores".to_string());
+        }
+        let csv_path = match settings.csv_path.trim() {
+            "" => None,
+            path => {
+                crate::quality_metrics::write_csv(path, &metrics, &per_frame)
+                    .map_err(|e| format!("Quality Analysis: {}", e))?;
+                Some(path.to_string())
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        };
+        let report = crate::quality_metrics::QualityReport {
+            reference,
+            distorted,
+            scores,
+            unavailable,
+            scaled,
+            frames: per_frame.iter().map(|frames| frames.len()).max().unwrap_or(0),
+            csv_path,
+        };
+        let lines = report.lines();
+        for line in &lines {
+            log_info!("📊 {}", line);
         }
         
+        let output_file = self.generate_temp_file("quality_report", "txt");
+        std::fs::write(&output_file, lines.join("\n") + "\n")
+            .map_err(|e| format!("Failed to write the quality report: {}", e))?;
+        crate::quality_metrics::record_report(&node.id, report);
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -5088,7 +7330,7 @@ impl WorkflowExecutor {
         self.execute_ffmpeg_command(&command)?;
         
         // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
+        if self.file_written(&output_file) {
             log_info!("Output file created successfully: {}", output_file);
             if let Ok(metadata) = std::fs::metadata(&output_file) {
                 log_info!("File size: {} bytes", metadata.len());
@@ -5105,95 +7347,137 @@ impl WorkflowExecutor {
     }
 
     /// Execute multi-resolution output node
+    /// Execute multi-resolution output: every rendition comes out of one ffmpeg run into the
+    /// node's folder, which is the node's output
     fn execute_multi_res_output_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
+        let mut settings = renditions_from_params(&node.parameters);
+        if crate::hardware_refresh::is_hardware_encoder(&settings.video_codec)
+            && !self.cached_hardware_encoders.contains(&settings.video_codec) {
+            if let Some(software) = crate::hardware_refresh::software_encoder(&settings.video_codec) {
+                log_warn!("Multi-Resolution: {} is not available on this machine, using {}", settings.video_codec, software);
+                settings.video_codec = software.to_string();
+            }
+        }
+        let output_dir = match settings.output_dir.trim() {
+            "" => self.generate_temp_file("renditions", "renditions"),
+            dir => dir.to_string(),
+        };
+        self.prepare_output_dir(&output_dir)
+            .map_err(|e| format!("Multi-Resolution: {}", e))?;
+        let args = settings.args(&input_file, &output_dir)
+            .map_err(|e| format!("Multi-Resolution: {}", e))?;
         
-        let resolutions = vec!["480p", "720p", "1080p"];
-        let mut output_files = Vec::new();
-        
-        for res in resolutions {
-            let output_file = self.generate_temp_file(&format!("output_{}", res), "mp4");
-            
-            let (width, height, bitrate) = match res {
-                "480p" => ("854", "480", "1000k"),
-                "720p" => ("1280", "720", "2500k"),
-                "1080p" => ("1920", "1080", "5000k"),
-                _ => ("1920", "1080", "5000k"),
-            };
-            
-            let command = format!(
-                "ffmpeg -i \"{}\" -vf scale={}:{} -b:v {} -c:v libx264 -c:a aac \"{}\"",
-                input_file, width, height, bitrate, output_file
-            );
-            
-            self.execute_ffmpeg_command(&command)?;
-            self.temp_files.push(output_file.clone());
-            output_files.push(output_file);
+        let source_height = crate::probe_cache::probe_cache().get_file_info(&input_file).ok()
+            .and_then(|info| info.video_streams.first().map(|stream| stream.height));
+        if let Some(source_height) = source_height.filter(|height| *height > 0) {
+            for height in settings.upscaled(source_height) {
+                log_warn!("Multi-Resolution: {}p is larger than the {}p source and will be upscaled", height, source_height);
+            }
         }
         
-        Ok(output_files)
+        log_info!("📺 Multi-Resolution: {} -> {} renditions in {}", input_file, settings.renditions.len(), output_dir);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Multi-Resolution encoding failed: {}", e))?;
+        
+        for rendition in &settings.renditions {
+            let output_file = settings.output_path(&output_dir, rendition.height);
+            if !self.file_written(&output_file) {
+                return Err(format!("Multi-Resolution did not write {}", output_file.display()));
+            }
+            log_info!("Wrote {}", output_file.display());
+        }
+        Ok(vec![output_dir])
     }
 
-    /// Execute video encryption node
+    /// Execute video encryption node: the input is stream copied into AES-128 encrypted HLS with a
+    /// new or reused key
     fn execute_video_encrypt_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("encrypted", "mp4");
-        
-        let password = node.parameters.get("password")
-            .map(|p| p.value.clone())
-            .unwrap_or_else(|| "".to_string());
+        let settings = encrypt_from_params(&node.parameters);
+        settings.validate().map_err(|e| format!("Video Encrypt: {}", e))?;
+        let output_dir = match settings.output_dir.trim() {
+            "" => self.generate_temp_file("encrypted", "hls"),
+            dir => dir.to_string(),
+        };
+        self.prepare_output_dir(&output_dir)
+            .map_err(|e| format!("Video Encrypt: {}", e))?;
+        
+        let key_path = settings.key_path(&output_dir);
+        if self.dry_run {
+            log_debug!("Dry run: the key is neither generated nor read");
+        } else if settings.generate_key {
+            // A chosen key file may still be needed for earlier encryptions, so it is never replaced
+            if !settings.key_file.trim().is_empty() && key_path.exists() {
+                return Err(format!("Video Encrypt: {} already exists; turn off key generation to reuse it", key_path.display()));
+            }
+            let key = crate::hls_encryption::generate_key()
+                .map_err(|e| format!("Video Encrypt: cannot generate the key: {}", e))?;
+            crate::hls_encryption::write_key(&key_path, &key)
+                .map_err(|e| format!("Video Encrypt: cannot write the key: {}", e))?;
+            log_info!("🔑 Generated a new AES-128 key in {}", key_path.display());
+        } else {
+            crate::hls_encryption::read_key(&key_path).map_err(|e| format!("Video Encrypt: {}", e))?;
+        }
         
-        // Simple encryption by adding metadata
-        let command = format!(
-            "ffmpeg -i \"{}\" -metadata:s:v:0 encrypt=\"{}\" -c copy \"{}\"",
-            input_file, password, output_file
-        );
+        let key_info_path = std::path::PathBuf::from(self.generate_temp_file("keyinfo", "txt"));
+        let key_info = if self.dry_run {
+            None
+        } else {
+            Some(crate::hls_encryption::KeyInfoFile::write(&key_info_path, &settings.key_uri(&output_dir), &key_path)
+                .map_err(|e| format!("Video Encrypt: cannot write the key info: {}", e))?)
+        };
+        let args = settings.args(&input_file, &key_info_path, &output_dir)
+            .map_err(|e| format!("Video Encrypt: {}", e))?;
         
-        self.execute_ffmpeg_command(&command)?;
+        log_info!("🔒 Video Encrypt: {} -> {}", input_file, output_dir);
+        self.run_node_passes(&[args], &input_file)
+            .map_err(|e| format!("Encryption failed: {}", e))?;
+        drop(key_info);
         
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        let playlist = settings.playlist_path(&output_dir);
+        if !self.file_written(&playlist) {
+            return Err(format!("Video Encrypt did not write {}", playlist.display()));
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
+        log_info!("Wrote {}", playlist.display());
+        Ok(vec![output_dir])
     }
 
-    /// Execute video decryption node
+    /// Execute video decryption node: encrypted HLS from a Video Encrypt folder or playlist is
+    /// remuxed into one file
     fn execute_video_decrypt_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("decrypted", "mp4");
-        
-        // Simple decryption by removing encryption metadata
-        let command = format!(
-            "ffmpeg -i \"{}\" -map_metadata -1 -c copy \"{}\"",
-            input_file, output_file
-        );
-        
-        self.execute_ffmpeg_command(&command)?;
-        
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
-            }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        let input = self.get_input_file_for_node(node, workflow)?;
+        let settings = decrypt_from_params(&node.parameters);
+        // A dry run's input was never written, so it stands for the playlist itself
+        let mut playlist = match crate::hls_encryption::find_playlist(&input) {
+            Ok(playlist) => playlist,
+            Err(_) if self.dry_run => std::path::PathBuf::from(&input),
+            Err(e) => return Err(format!("Video Decrypt: {}", e)),
+        };
+        if self.dry_run {
+            log_debug!("Dry run: the playlist is not rewritten");
+        } else if !settings.key_file.trim().is_empty() {
+            let key_path = std::path::PathBuf::from(settings.key_file.trim());
+            crate::hls_encryption::read_key(&key_path).map_err(|e| format!("Video Decrypt: {}", e))?;
+            let rewritten = crate::hls_encryption::rewrite_playlist(&playlist, &key_path)
+                .map_err(|e| format!("Video Decrypt: cannot read {}: {}", playlist.display(), e))?;
+            let rewritten_path = self.generate_temp_file("decrypt_playlist", "m3u8");
+            std::fs::write(&rewritten_path, rewritten)
+                .map_err(|e| format!("Video Decrypt: cannot write the playlist: {}", e))?;
+            self.temp_files.push(rewritten_path.clone());
+            playlist = rewritten_path.into();
+        }
+        
+        let output_file = self.generate_temp_file("decrypted", &settings.container);
+        let args = settings.args(&playlist, &output_file)
+            .map_err(|e| format!("Video Decrypt: {}", e))?;
+        log_info!("🔓 Video Decrypt: {} -> {}", input, output_file);
+        self.run_node_passes(&[args], &playlist.display().to_string())
+            .map_err(|e| format!("Decryption failed: {}", e))?;
+        
+        if !self.file_written(&output_file) {
+            return Err(format!("Video Decrypt did not write {}", output_file));
         }
-        
         self.temp_files.push(output_file.clone());
         Ok(vec![output_file])
     }
@@ -5254,53 +7538,115 @@ impl WorkflowExecutor {
 
     /// Execute create archive node
     fn execute_create_archive_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
-        let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_file = self.generate_temp_file("archive", "tar.gz");
-        
-        // Create a simple archive using tar (cross-platform)
-        let command = if cfg!(target_os = "windows") {
-            format!("tar -czf \"{}\" \"{}\"", output_file, input_file)
+        let (inputs, settings) = self.get_archive_inputs(node, workflow);
+        if inputs.is_empty() {
+            return Err(format!("No input connected to node {}", node.id));
+        }
+        let parameter = |name: &str| node.parameters.get(name).map(|p| p.value.trim().to_string()).unwrap_or_default();
+        let archive_path = parameter("archive_path");
+        let (archive_path, is_temp) = if archive_path.is_empty() {
+            let format = crate::archive::ArchiveFormat::from_name(&parameter("format")).unwrap_or(crate::archive::ArchiveFormat::Zip);
+            (self.generate_temp_file("archive", format.extension()), true)
         } else {
-            format!("tar -czf \"{}\" \"{}\"", output_file, input_file)
+            (archive_path, false)
         };
-        
-        self.execute_ffmpeg_command(&command)?;
-        
-        // Check if the output file was actually created
-        if std::path::Path::new(&output_file).exists() {
-            log_info!("Output file created successfully: {}", output_file);
-            if let Ok(metadata) = std::fs::metadata(&output_file) {
-                log_info!("File size: {} bytes", metadata.len());
-            } else {
-                log_warn!("Could not read file metadata");
+        let include_probe = parameter("include_probe") != "false";
+        if self.dry_run {
+            return Ok(vec![archive_path]);
+        }
+
+        let inputs: Vec<std::path::PathBuf> = inputs.iter().map(std::path::PathBuf::from).collect();
+        let mut logged = 0;
+        let mut progress = |done: u64, total: u64| {
+            let percent = (done * 100).checked_div(total).unwrap_or(100);
+            if percent >= logged + 10 {
+                logged = percent - percent % 10;
+                log_info!("📦 Archiving {}: {}%", node.id, logged);
             }
-        } else {
-            log_error!("Output file was not created: {}", output_file);
-            return Err(format!("Failed to create output file: {}", output_file));
+        };
+        crate::archive::create(std::path::Path::new(&archive_path), &inputs, include_probe, Some(settings), &mut progress)?;
+        if is_temp {
+            self.temp_files.push(archive_path.clone());
         }
-        
-        self.temp_files.push(output_file.clone());
-        Ok(vec![output_file])
+        Ok(vec![archive_path])
     }
 
     /// Execute extract archive node
     fn execute_extract_archive_node(&mut self, node: &AutomationNode, workflow: &AutomationWorkflow) -> Result<Vec<String>, String> {
         let input_file = self.get_input_file_for_node(node, workflow)?;
-        let output_dir = self.generate_temp_file("extracted", "");
-        
-        // Create output directory
-        std::fs::create_dir_all(&output_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
-        
-        // Extract archive
-        let command = if cfg!(target_os = "windows") {
-            format!("tar -xzf \"{}\" -C \"{}\"", input_file, output_dir)
+        let parameter = |name: &str| node.parameters.get(name).map(|p| p.value.trim().to_string()).unwrap_or_default();
+        let output_dir = parameter("output_dir");
+        let (output_dir, is_temp) = if output_dir.is_empty() {
+            (self.generate_temp_file("extracted", ""), true)
         } else {
-            format!("tar -xzf \"{}\" -C \"{}\"", input_file, output_dir)
+            (output_dir, false)
         };
-        
-        self.execute_ffmpeg_command(&command)?;
-        self.temp_files.push(output_dir.clone());
-        Ok(vec![output_dir])
+        let verify = parameter("verify_checksums") != "false";
+        if self.dry_run {
+            return Ok(vec![output_dir]);
+        }
+
+        let mut logged = 0;
+        let mut progress = |done: u64, total: u64| {
+            let percent = (done * 100).checked_div(total).unwrap_or(100);
+            if percent >= logged + 10 {
+                logged = percent - percent % 10;
+                log_info!("📂 Extracting {}: {}%", node.id, logged);
+            }
+        };
+        let report = crate::archive::extract(std::path::Path::new(&input_file), std::path::Path::new(&output_dir), verify, &mut progress)?;
+        if verify && report.manifest.is_none() {
+            log_warn!("⚠️ {} has no manifest, checksums were not verified", input_file);
+        }
+        if is_temp {
+            self.temp_files.push(output_dir.clone());
+        }
+        Ok(report.files.iter().map(|file| file.display().to_string()).collect())
+    }
+
+    /// Helper: every file reaching the node's first input, in connection order, plus the parameters
+    /// of the nodes that produced them for the archive manifest. Text outputs contribute all their
+    /// files, media outputs the file of the connected port.
+    fn get_archive_inputs(&self, node: &AutomationNode, workflow: &AutomationWorkflow) -> (Vec<String>, serde_json::Value) {
+        let mut connections: Vec<&NodeConnection> = workflow.connections.values()
+            .filter(|connection| connection.to_node == node.id && connection.to_port == 0)
+            .collect();
+        connections.sort_by(|a, b| (&a.from_node, a.from_port).cmp(&(&b.from_node, b.from_port)));
+
+        let mut inputs: Vec<String> = Vec::new();
+        let mut settings = serde_json::Map::new();
+        for connection in connections {
+            let Some(source_node) = workflow.nodes.get(&connection.from_node) else {
+                continue;
+            };
+            let files: Vec<String> = if source_node.node_type == NodeType::InputFile {
+                source_node.parameters.get("file_path").map(|p| p.value.clone()).into_iter().collect()
+            } else {
+                let text_port = source_node.output_ports.get(connection.from_port)
+                    .is_some_and(|port| port.data_type == DataType::Text);
+                self.execution_results.iter()
+                    .filter(|result| result.node_id == source_node.id && result.success)
+                    .flat_map(|result| if text_port {
+                        result.output_files.clone()
+                    } else {
+                        result.output_files.get(connection.from_port).cloned().into_iter().collect()
+                    })
+                    .collect()
+            };
+            for file in files {
+                if std::path::Path::new(&file).is_file() && !inputs.contains(&file) {
+                    inputs.push(file);
+                }
+            }
+            let parameters: serde_json::Map<String, serde_json::Value> = source_node.parameters.iter()
+                .map(|(name, parameter)| (name.clone(), serde_json::Value::String(parameter.value.clone())))
+                .collect();
+            settings.insert(source_node.id.clone(), serde_json::json!({
+                "node": source_node.node_type.display_name(),
+                "parameters": parameters,
+            }));
+        }
+        (inputs, serde_json::Value::Object(settings))
     }
     
     /// Execute batch convert node
@@ -5375,20 +7721,29 @@ impl WorkflowExecutor {
             custom_args: node.parameters.get("custom_args").map(|p| p.value.clone()).unwrap_or_default(),
             format: node.parameters.get("format").or(node.parameters.get("output_format")).map(|p| p.value.clone()).unwrap_or_else(|| "mp3".to_string()),
             copy_audio: node.parameters.get("copy_audio").map(|p| p.value == "true").unwrap_or(false),
+            keep_output_extension: false,
+            preserve_chapters: true,
+            chapter_edits: None,
+            chapter_metadata_file: None,
             vbr_quality: node.parameters.get("vbr_quality").and_then(|p| p.value.parse().ok()).unwrap_or(2),
             resample_method: node.parameters.get("resample_method").map(|p| p.value.clone()).unwrap_or_else(|| "swr".to_string()),
             normalize: node.parameters.get("normalize").map(|p| p.value == "true").unwrap_or(false),
             target_lufs: node.parameters.get("target_lufs").and_then(|p| p.value.parse().ok()).unwrap_or(-16.0),
             start_time: node.parameters.get("start_time").map(|p| p.value.clone()).unwrap_or_else(|| "00:00:00".to_string()),
             end_time: node.parameters.get("end_time").map(|p| p.value.clone()).unwrap_or_default(),
-            fade_in: node.parameters.get("fade_in").map(|p| p.value == "true").unwrap_or(false),
-            fade_out: node.parameters.get("fade_out").map(|p| p.value == "true").unwrap_or(false),
-            merge_mode: node.parameters.get("merge_mode").map(|p| p.value.clone()).unwrap_or_else(|| "concat".to_string()),
+            fade: fade_from_params(&node.parameters).unwrap_or_default(),
+            fade_duration: None,
+            merge_mode: merge_mode_from_params(&node.parameters),
             add_silence: node.parameters.get("add_silence").map(|p| p.value == "true").unwrap_or(false),
             silence_duration: node.parameters.get("silence_duration").and_then(|p| p.value.parse().ok()).unwrap_or(1.0),
+            mix: mix_from_params(&node.parameters),
             sync_audio: node.parameters.get("sync_audio").map(|p| p.value == "true").unwrap_or(false),
             audio_delay: node.parameters.get("audio_delay").and_then(|p| p.value.parse().ok()).unwrap_or(0.0),
+            merge_shortest: node.parameters.get("shortest").map(|p| p.value == "true").unwrap_or(false),
             extract_all_tracks: node.parameters.get("extract_all_tracks").map(|p| p.value == "true").unwrap_or(false),
+            denoise: denoise_from_params(&node.parameters),
+            loudness: loudness_from_params(&node.parameters),
+            content_type: None,
         }
     }
     
@@ -5412,7 +7767,7 @@ impl WorkflowExecutor {
             
             // Compression
             crf: node.parameters.get("crf").and_then(|p| p.value.parse().ok()).unwrap_or(23),
-            target_size_mb: node.parameters.get("target_size").and_then(|p| p.value.parse().ok()).unwrap_or(0),
+            target_size_mb: node.parameters.get("target_size_mb").and_then(|p| p.value.parse().ok()).unwrap_or(0),
             
             // Resolution settings
             resolution: {
@@ -5444,6 +7799,7 @@ impl WorkflowExecutor {
             brightness: node.parameters.get("brightness").and_then(|p| p.value.parse().ok()).unwrap_or(0.0),
             contrast: node.parameters.get("contrast").and_then(|p| p.value.parse().ok()).unwrap_or(1.0),
             saturation: node.parameters.get("saturation").and_then(|p| p.value.parse().ok()).unwrap_or(1.0),
+            filter_stack: node.parameters.get("filter_stack").and_then(|p| serde_json::from_str(&p.value).ok()).unwrap_or_default(),
             
             // Subtitle settings
             subtitle_file: node.parameters.get("subtitle_file").map(|p| p.value.clone()).unwrap_or_default(),
@@ -5472,6 +7828,9 @@ impl WorkflowExecutor {
             batch_naming_pattern: node.parameters.get("naming_pattern").map(|p| p.value.clone()).unwrap_or_else(|| "converted".to_string()),
             batch_operation_type: node.parameters.get("batch_type").map(|p| p.value.clone()).unwrap_or_default(),
             
+            // Frame rate conversion
+            fps_conversion: fps_conversion_from_params(&node.parameters),
+            
             // Use defaults for all other fields
             ..VideoSettings::default()
         }
@@ -5501,7 +7860,7 @@ impl WorkflowExecutor {
             OperationType::VideoCrop | OperationType::VideoRotate | OperationType::VideoFilter |
             OperationType::VideoAudioMerge | OperationType::VideoAudioSplit | OperationType::ExtractVideo |
             OperationType::AddSubtitle | OperationType::AddWatermark | OperationType::VideoToGif |
-            OperationType::FrameExtract | OperationType::GifResize => {
+            OperationType::FrameExtract | OperationType::GifResize | OperationType::FrameRateConvert => {
                 let mut video_settings = self.node_params_to_video_settings(node);
                 
                 // Apply hardware acceleration only if user selected it or codec is "auto"
@@ -5537,6 +7896,7 @@ impl WorkflowExecutor {
         match operation {
             OperationType::AudioConvert | OperationType::AudioCompress | OperationType::AudioResample |
             OperationType::AudioVolume | OperationType::AudioTrim | OperationType::AudioMerge |
+            OperationType::AudioNormalize |
             OperationType::VideoAudioMerge | OperationType::VideoAudioSplit | OperationType::ExtractAudio => {
                 task.audio_settings = Some(self.node_params_to_audio_settings(node));
                 log_debug!("🔊 Applied audio settings for operation {:?}", operation);
@@ -5576,6 +7936,18 @@ impl WorkflowExecutor {
         task
     }
     
+    /// Run a task through the TaskExecutor, recording its command for the run report. A dry run
+    /// only records it.
+    fn execute_task(&self, task: &mut ProcessingTask) -> Result<()> {
+        if let Ok(command) = TaskExecutor::preview_command(task) {
+            self.node_commands.borrow_mut().push(command);
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        TaskExecutor::execute_task(task)
+    }
+    
     /// Map software codec to hardware equivalent if available
     fn get_hardware_codec_for_software(&self, software_codec: &str) -> Option<String> {
         match software_codec {
@@ -5686,3 +8058,125 @@ impl WorkflowExecutor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(workflow: &mut AutomationWorkflow, id: &str, node_type: NodeType, parameters: &[(&str, &str)]) {
+        let mut node = AutomationNode::new(id.to_string(), node_type, egui::Pos2::ZERO);
+        for (name, value) in parameters {
+            node.parameters.get_mut(*name).unwrap().value = value.to_string();
+        }
+        workflow.add_node(node);
+    }
+
+    fn connect(workflow: &mut AutomationWorkflow, from: &str, to: &str) {
+        let data_type = workflow.nodes[from].output_ports[0].data_type.clone();
+        workflow.add_connection(NodeConnection {
+            id: new_connection_id(),
+            from_node: from.to_string(),
+            from_port: 0,
+            to_node: to.to_string(),
+            to_port: 0,
+            data_type,
+        }).unwrap();
+    }
+
+    /// Dry run of input → resize → output; returns the executor and the input and output paths
+    fn dry_run(dir: &std::path::Path, input_contents: Option<&[u8]>) -> (WorkflowExecutor, Result<(), String>, String, String) {
+        let input = dir.join("input.mp4").display().to_string();
+        let output = dir.join("out.mp4").display().to_string();
+        if let Some(contents) = input_contents {
+            std::fs::write(&input, contents).unwrap();
+        }
+        let mut workflow = AutomationWorkflow::new("report test".to_string());
+        add(&mut workflow, "input", NodeType::InputFile, &[("file_path", &input)]);
+        add(&mut workflow, "resize", NodeType::VideoResize, &[("width", "1280"), ("height", "720")]);
+        add(&mut workflow, "output", NodeType::OutputFile, &[("output_path", &output)]);
+        connect(&mut workflow, "input", "resize");
+        connect(&mut workflow, "resize", "output");
+
+        let mut executor = WorkflowExecutor::new_with_hardware_cache(Vec::new());
+        executor.dry_run = true;
+        let result = executor.execute_workflow(workflow);
+        // A dry run leaves its (empty) temp directory alone
+        if let Some(temp_dir) = executor.workflow_temp_dir.take() {
+            let _ = std::fs::remove_dir_all(temp_dir);
+        }
+        (executor, result, input, output)
+    }
+
+    #[test]
+    fn dry_run_report_of_a_three_node_workflow() {
+        let dir = crate::test_support::temp_dir("report");
+        let (executor, result, input, output) = dry_run(&dir, Some(b"not really a movie"));
+        assert_eq!(result, Ok(()));
+        let report = executor.last_run_report.clone().unwrap();
+
+        assert_eq!(report.workflow_name, "report test");
+        assert_eq!(report.outcome, RunOutcome::Success);
+        assert_eq!(report.error, None);
+        assert!(report.finished_at.is_some());
+
+        let summary: Vec<(usize, &str, &str, bool, bool)> = report.nodes.iter()
+            .map(|node| (node.order, node.node_id.as_str(), node.node_type.as_str(), node.success, node.cached))
+            .collect();
+        assert_eq!(summary, [
+            (1, "input", NodeType::InputFile.display_name(), true, false),
+            (2, "resize", NodeType::VideoResize.display_name(), true, false),
+            (3, "output", NodeType::OutputFile.display_name(), true, false),
+        ]);
+
+        // The input is passed on as it is, sized
+        let [input_node, resize_node, output_node] = &report.nodes[..] else { unreachable!() };
+        assert!(input_node.commands.is_empty());
+        assert_eq!(input_node.artifacts, [crate::workflow_report::ArtifactInfo { path: input.clone(), size_bytes: Some(18) }]);
+
+        // The resize records the command it would run and an unwritten intermediate file
+        assert_eq!(resize_node.commands.len(), 1);
+        let command = &resize_node.commands[0];
+        assert!(command.starts_with("ffmpeg ") && command.contains(&input) && command.contains("-s 1280x720"), "{command}");
+        assert_eq!(resize_node.artifacts.len(), 1);
+        assert!(command.contains(&resize_node.artifacts[0].path));
+        assert_eq!(resize_node.artifacts[0].size_bytes, None);
+
+        // Only the output node's files are final outputs; nothing was written
+        assert!(output_node.commands.is_empty());
+        let expected_output = crate::workflow_report::ArtifactInfo { path: output.clone(), size_bytes: None };
+        assert_eq!(report.final_outputs, [expected_output]);
+        assert_eq!(output_node.artifacts, report.final_outputs);
+        assert!(!std::path::Path::new(&output).exists());
+        assert_eq!(report.artifact_bytes(), 18);
+
+        // The saved report reads back to the same structure
+        let json = report.to_json().unwrap();
+        let loaded: WorkflowRunReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+
+        assert_eq!(executor.session_stats.workflow_runs, 1);
+        assert_eq!(executor.session_stats.failed_runs, 0);
+        assert_eq!(executor.session_stats.nodes_executed, 3);
+        assert_eq!(executor.session_stats.artifact_bytes, 18);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dry_run_report_of_a_failing_workflow() {
+        let dir = crate::test_support::temp_dir("report");
+        let (executor, result, input, _) = dry_run(&dir, None);
+        let error = result.unwrap_err();
+        assert!(error.contains(&input), "{error}");
+
+        let report = executor.last_run_report.clone().unwrap();
+        assert_eq!(report.outcome, RunOutcome::Failed);
+        assert_eq!(report.error.as_deref(), Some(error.as_str()));
+        // The failing node is recorded with its error; nothing after it ran
+        assert_eq!(report.nodes.len(), 1);
+        assert_eq!((report.nodes[0].node_id.as_str(), report.nodes[0].success), ("input", false));
+        assert!(report.nodes[0].error.as_deref().is_some_and(|node_error| error.contains(node_error)));
+        assert!(report.final_outputs.is_empty());
+        assert_eq!(executor.session_stats.failed_runs, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -289,4 +289,22 @@ lazy_static! {
 /// Get the global BundledFFmpeg instance
 pub fn get_bundled_ffmpeg() -> Result<&'static BundledFFmpeg> {
     Ok(&*BUNDLED_FFMPEG)
-}
\ No newline at end of file
+}
+/// Whether the bundled ffmpeg has a filter; builds differ in the external libraries they include.
+/// The filter list is read once per session.
+pub fn has_filter(name: &str) -> bool {
+    static FILTERS: std::sync::OnceLock<Vec<String>> = std::sync::OnceLock::new();
+    FILTERS.get_or_init(|| {
+        let output = get_bundled_ffmpeg().and_then(|ffmpeg| ffmpeg.run_ffmpeg(&["-hide_banner", "-filters"]));
+        match output {
+            // " T.. name  V->V  description"
+            Ok(output) => String::from_utf8_lossy(&output.stdout).lines()
+                .filter_map(|line| line.split_whitespace().nth(1).map(str::to_string))
+                .collect(),
+            Err(e) => {
+                log_warn!("Could not list the ffmpeg filters: {}", e);
+                Vec::new()
+            }
+        }
+    }).iter().any(|filter| filter == name)
+}
@@ -0,0 +1,351 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::language::Translations;
+
+/// One chapter of a media file, in seconds. Shared by probing, the chapter editor and any
+/// per-chapter processing.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Chapter {
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+/// How well an output container keeps chapters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChapterSupport {
+    Full,
+    /// Written, but many players ignore them
+    Limited,
+    None,
+}
+
+pub fn container_support(container: &str) -> ChapterSupport {
+    match container.to_lowercase().as_str() {
+        "mp4" | "m4v" | "mov" | "mkv" | "m4a" | "m4b" => ChapterSupport::Full,
+        "webm" | "ogg" | "opus" => ChapterSupport::Limited,
+        _ => ChapterSupport::None,
+    }
+}
+
+/// Chapters from the "chapters" array of `ffprobe -show_chapters -of json`
+pub fn from_ffprobe_json(json: &serde_json::Value) -> Vec<Chapter> {
+    let seconds = |chapter: &serde_json::Value, key: &str| chapter[key].as_str().and_then(|s| s.parse::<f64>().ok());
+    json["chapters"].as_array()
+        .map(|chapters| chapters.iter()
+            .filter_map(|chapter| Some(Chapter {
+                start: seconds(chapter, "start_time")?,
+                end: seconds(chapter, "end_time")?,
+                title: chapter["tags"]["title"].as_str().unwrap_or("").to_string(),
+            }))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// FFMETADATA values escape '=', ';', '#', '\' and newlines with a backslash
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                unescaped.push(next);
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// FFMETADATA1 document with the chapters in millisecond time base
+pub fn to_ffmetadata(chapters: &[Chapter]) -> String {
+    let mut text = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        let start = (chapter.start.max(0.0) * 1000.0).round() as u64;
+        let end = ((chapter.end * 1000.0).round() as u64).max(start);
+        text.push_str(&format!("\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n", start, end, escape(&chapter.title)));
+    }
+    text
+}
+
+/// Chapters of an FFMETADATA1 document; other sections and keys are ignored
+pub fn parse_ffmetadata(text: &str) -> Vec<Chapter> {
+    struct Pending {
+        timebase: f64,
+        start: Option<i64>,
+        end: Option<i64>,
+        title: String,
+    }
+    let finish = |pending: Pending| -> Option<Chapter> {
+        Some(Chapter {
+            start: pending.start? as f64 * pending.timebase,
+            end: pending.end? as f64 * pending.timebase,
+            title: pending.title,
+        })
+    };
+
+    let mut chapters = Vec::new();
+    let mut current: Option<Pending> = None;
+    // Values may continue on the next line after an escaped newline, i.e. an odd number of trailing backslashes
+    let continues = |line: &str| line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1;
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let mut line = line.to_string();
+        while continues(&line) {
+            line.pop();
+            line.push_str("\\\n");
+            match lines.next() {
+                Some(next) => line.push_str(next),
+                None => break,
+            }
+        }
+        if line.starts_with('[') {
+            chapters.extend(current.take().and_then(finish));
+            if line.trim() == "[CHAPTER]" {
+                current = Some(Pending { timebase: 0.001, start: None, end: None, title: String::new() });
+            }
+            continue;
+        }
+        let Some(chapter) = current.as_mut() else {
+            continue;
+        };
+        if line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "TIMEBASE" => {
+                if let Some((numerator, denominator)) = value.trim().split_once('/') {
+                    if let (Ok(numerator), Ok(denominator)) = (numerator.parse::<f64>(), denominator.parse::<f64>()) {
+                        if denominator > 0.0 {
+                            chapter.timebase = numerator / denominator;
+                        }
+                    }
+                }
+            }
+            "START" => chapter.start = value.trim().parse().ok(),
+            "END" => chapter.end = value.trim().parse().ok(),
+            "title" => chapter.title = unescape(value),
+            _ => {}
+        }
+    }
+    chapters.extend(current.and_then(finish));
+    chapters
+}
+
+static METADATA_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn metadata_file_name(index: usize) -> String {
+    format!("chapters_{}.txt", index)
+}
+
+/// FFMETADATA file with the edited chapters when they should replace the source's, written next to
+/// the other temporary files. The caller removes it after encoding.
+pub fn write_metadata_file(preserve: bool, edits: Option<&[Chapter]>) -> std::io::Result<Option<String>> {
+    let Some(chapters) = edits.filter(|_| preserve) else {
+        return Ok(None);
+    };
+    let path = crate::temp_files::scratch_path(&metadata_file_name(METADATA_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    std::fs::write(&path, to_ffmetadata(chapters))?;
+    Ok(Some(path.display().to_string()))
+}
+
+/// Stand-in for the metadata file in command previews, which write nothing
+pub fn preview_metadata_file(preserve: bool, edits: Option<&[Chapter]>) -> Option<String> {
+    edits.filter(|_| preserve).map(|_| crate::temp_files::preview_path(&metadata_file_name(0)).display().to_string())
+}
+
+/// Chapter mapping for the output: from the source, from the FFMETADATA input at
+/// `metadata_input`, or none. -map_chapters keeps the source's global tags, which
+/// -map_metadata from the chapter file would replace.
+pub fn map_args(preserve: bool, metadata_input: Option<usize>) -> Vec<String> {
+    let source = match (preserve, metadata_input) {
+        (false, _) => "-1".to_string(),
+        (true, Some(input)) => input.to_string(),
+        (true, None) => "0".to_string(),
+    };
+    vec!["-map_chapters".to_string(), source]
+}
+
+/// "Preserve chapters" checkbox with the source's chapters in an editable table.
+/// `edits` stays None until a chapter is changed, so the source chapters are copied as they are.
+pub fn show_editor(
+    ui: &mut egui::Ui,
+    preserve: &mut bool,
+    edits: &mut Option<Vec<Chapter>>,
+    source_chapters: &[Chapter],
+    container: &str,
+    translations: &Translations,
+) {
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(preserve, translations.chapters_preserve());
+            ui.weak(format!("({})", translations.chapter_count(source_chapters.len())));
+        });
+        if !*preserve || source_chapters.is_empty() {
+            return;
+        }
+        match container_support(container) {
+            ChapterSupport::Full => {}
+            ChapterSupport::Limited => {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), translations.chapters_limited_container());
+            }
+            ChapterSupport::None => {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), translations.chapters_unsupported_container());
+            }
+        }
+
+        let mut working = edits.clone().unwrap_or_else(|| source_chapters.to_vec());
+        let mut changed = false;
+        egui::CollapsingHeader::new(translations.chapters_edit())
+            .id_salt("chapter_editor")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+                    egui::Grid::new("chapter_table").num_columns(3).striped(true).show(ui, |ui| {
+                        ui.strong(translations.chapters_title());
+                        ui.strong(translations.chapters_start());
+                        ui.strong(translations.chapters_end());
+                        ui.end_row();
+                        for (index, chapter) in working.iter_mut().enumerate() {
+                            changed |= ui.add(egui::TextEdit::singleline(&mut chapter.title).desired_width(200.0)).changed();
+                            changed |= crate::locale_format::duration_edit(ui, ("chapter_start", index), &mut chapter.start, translations).changed();
+                            changed |= crate::locale_format::duration_edit(ui, ("chapter_end", index), &mut chapter.end, translations).changed();
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.horizontal(|ui| {
+                    if ui.button(translations.chapters_import()).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("FFMETADATA", &["txt", "ini", "ffmeta"]).pick_file() {
+                            match std::fs::read_to_string(&path) {
+                                Ok(text) => {
+                                    working = parse_ffmetadata(&text);
+                                    changed = true;
+                                }
+                                Err(e) => log_warn!("Failed to read chapter file {}: {}", path.display(), e),
+                            }
+                        }
+                    }
+                    if edits.is_some() && ui.button(translations.chapters_reset()).clicked() {
+                        *edits = None;
+                    }
+                });
+            });
+        if changed {
+            *edits = Some(working);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start: f64, end: f64, title: &str) -> Chapter {
+        Chapter { start, end, title: title.to_string() }
+    }
+
+    #[test]
+    fn ffmetadata_round_trip() {
+        let chapters = vec![
+            chapter(0.0, 61.5, "Intro"),
+            chapter(61.5, 125.25, "Part 1 = setup; #1 \\ more"),
+            chapter(125.25, 130.0, "Two\nlines"),
+            chapter(130.0, 131.0, "Ends in a backslash\\"),
+            chapter(131.0, 132.0, "Backslash then newline\\\nsecond line"),
+            chapter(132.0, 140.0, ""),
+        ];
+        let text = to_ffmetadata(&chapters);
+        assert!(text.starts_with(";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=61500\ntitle=Intro\n"));
+        assert!(text.contains("title=Part 1 \\= setup\\; \\#1 \\\\ more\n"));
+        assert!(text.contains("title=Two\\\nlines\n"));
+        assert_eq!(parse_ffmetadata(&text), chapters);
+    }
+
+    #[test]
+    fn written_times_are_whole_milliseconds_and_never_backwards() {
+        let text = to_ffmetadata(&[chapter(-1.0, 0.0004, "a"), chapter(10.0, 5.0, "b")]);
+        assert!(text.contains("START=0\nEND=0\n"));
+        assert!(text.contains("START=10000\nEND=10000\n"));
+        assert_eq!(to_ffmetadata(&[]), ";FFMETADATA1\n");
+    }
+
+    #[test]
+    fn parses_documents_written_by_ffmpeg() {
+        let text = ";FFMETADATA1\n\
+            title=Global title\n\
+            encoder=Lavf60.3.100\n\
+            \n\
+            [CHAPTER]\n\
+            TIMEBASE=1/90000\n\
+            START=0\n\
+            END=900000\n\
+            title=First\n\
+            \n\
+            [STREAM]\n\
+            title=not a chapter\n\
+            [CHAPTER]\n\
+            ; comment inside a chapter\n\
+            START=10000\n\
+            END=20000\n\
+            artist=ignored\n\
+            [CHAPTER]\n\
+            TIMEBASE=1/0\n\
+            START=20000\n\
+            title=No end, dropped\n\
+            [CHAPTER]\n\
+            TIMEBASE=1/1\n\
+            START=30\n\
+            END=45\n";
+        assert_eq!(parse_ffmetadata(text), [
+            chapter(0.0, 10.0, "First"),
+            chapter(10.0, 20.0, ""),
+            chapter(30.0, 45.0, ""),
+        ]);
+        assert!(parse_ffmetadata(";FFMETADATA1\ntitle=Only global\n").is_empty());
+    }
+
+    #[test]
+    fn chapters_from_ffprobe() {
+        let json = serde_json::json!({ "chapters": [
+            { "start_time": "0.000000", "end_time": "12.500000", "tags": { "title": "Opening" } },
+            { "start_time": "12.500000", "end_time": "30.000000" },
+            { "start_time": "oops", "end_time": "31.000000" },
+        ]});
+        assert_eq!(from_ffprobe_json(&json), [chapter(0.0, 12.5, "Opening"), chapter(12.5, 30.0, "")]);
+        assert!(from_ffprobe_json(&serde_json::json!({ "format": {} })).is_empty());
+    }
+
+    #[test]
+    fn chapter_mapping_and_metadata_file() {
+        assert_eq!(map_args(false, Some(1)), ["-map_chapters", "-1"]);
+        assert_eq!(map_args(true, None), ["-map_chapters", "0"]);
+        assert_eq!(map_args(true, Some(2)), ["-map_chapters", "2"]);
+
+        let edits = [chapter(0.0, 5.0, "Only")];
+        assert_eq!(write_metadata_file(true, None).unwrap(), None);
+        assert_eq!(write_metadata_file(false, Some(&edits)).unwrap(), None);
+        assert!(preview_metadata_file(false, Some(&edits)).is_none());
+        let path = write_metadata_file(true, Some(&edits)).unwrap().unwrap();
+        assert_eq!(parse_ffmetadata(&std::fs::read_to_string(&path).unwrap()), edits);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(container_support("MKV"), ChapterSupport::Full);
+        assert_eq!(container_support("webm"), ChapterSupport::Limited);
+        assert_eq!(container_support("avi"), ChapterSupport::None);
+    }
+}
@@ -0,0 +1,661 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+
+use crate::app_state::ProcessingTask;
+use crate::language::Translations;
+
+/// Bytes read per step; memory stays flat no matter how large the output is
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Checksum threads currently running, so the UI keeps repainting their progress
+static ACTIVE_HASHES: AtomicUsize = AtomicUsize::new(0);
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Incremental SHA-256 (FIPS 180-4)
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    block_len: usize,
+    total_len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self { state: INITIAL_STATE, block: [0; 64], block_len: 0, total_len: 0 }
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+        if self.block_len > 0 {
+            let take = (64 - self.block_len).min(data.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+            if self.block_len < 64 {
+                return;
+            }
+            let block = self.block;
+            self.compress(&block);
+            self.block_len = 0;
+        }
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.block[..rest.len()].copy_from_slice(rest);
+        self.block_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len.wrapping_mul(8);
+        let mut padding = [0u8; 72];
+        padding[0] = 0x80;
+        let pad_len = if self.block_len < 56 { 56 - self.block_len } else { 120 - self.block_len };
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_len.to_be_bytes());
+        let total_len = self.total_len;
+        self.update(&padding[..pad_len + 8]);
+        self.total_len = total_len;
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut schedule = [0u32; 64];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = schedule[i - 15].rotate_right(7) ^ schedule[i - 15].rotate_right(18) ^ (schedule[i - 15] >> 3);
+            let s1 = schedule[i - 2].rotate_right(17) ^ schedule[i - 2].rotate_right(19) ^ (schedule[i - 2] >> 10);
+            schedule[i] = schedule[i - 16].wrapping_add(s0).wrapping_add(schedule[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(ROUND_CONSTANTS[i]).wrapping_add(schedule[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+/// Lowercase hex of a digest
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// SHA-256 of a file, read in fixed-size chunks. `hashed` counts the bytes read so far;
+/// returns Ok(None) when `cancel` was set before the end of the file.
+pub fn hash_file(path: &Path, cancel: &AtomicBool, hashed: &AtomicU64) -> std::io::Result<Option<String>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..read]);
+        hashed.fetch_add(read as u64, Ordering::Relaxed);
+    }
+    Ok(Some(to_hex(&hasher.finalize())))
+}
+
+/// `<output>.sha256` next to the output
+pub fn sidecar_path(output: &Path) -> PathBuf {
+    let mut path = output.as_os_str().to_owned();
+    path.push(".sha256");
+    PathBuf::from(path)
+}
+
+/// One line in the `sha256sum` format, so `sha256sum -c` can check the file
+pub fn format_sidecar(hash: &str, file_name: &str) -> String {
+    format!("{}  {}\n", hash, file_name)
+}
+
+/// Hash and file name of the first entry of a sidecar. Accepts the `sha256sum` text and binary
+/// forms (`<hash>  name`, `<hash> *name`), the BSD form (`SHA256 (name) = <hash>`) and a bare hash.
+pub fn parse_sidecar(contents: &str) -> Option<(String, Option<String>)> {
+    let line = contents.trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+    if let Some(rest) = line.strip_prefix("SHA256 (") {
+        let (name, hash) = rest.rsplit_once(") = ")?;
+        return normalize_hash(hash).map(|hash| (hash, Some(name.to_string())));
+    }
+
+    let (hash, name) = match line.split_once(char::is_whitespace) {
+        Some((hash, name)) => (hash, Some(name.trim_start().trim_start_matches('*').to_string())),
+        None => (line, None),
+    };
+    normalize_hash(hash).map(|hash| (hash, name.filter(|name| !name.is_empty())))
+}
+
+/// A pasted SHA-256 as lowercase hex; a pasted sidecar line works too
+pub fn normalize_hash(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.len() == 64 && text.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(text.to_ascii_lowercase());
+    }
+    if text.contains(char::is_whitespace) || text.starts_with("SHA256 (") {
+        return parse_sidecar(text).map(|(hash, _)| hash);
+    }
+    None
+}
+
+/// A checksum being computed on a background thread
+#[derive(Clone, Debug)]
+pub struct HashJob {
+    pub total_bytes: u64,
+    hashed: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl HashJob {
+    fn new(total_bytes: u64) -> Self {
+        Self { total_bytes, hashed: Arc::new(AtomicU64::new(0)), cancel: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            (self.hashed.load(Ordering::Relaxed) as f64 / self.total_bytes as f64).min(1.0) as f32
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum ChecksumState {
+    #[default]
+    NotStarted,
+    Hashing(HashJob),
+    Done(String),
+    Failed(String),
+    Cancelled,
+}
+
+/// Post-completion SHA-256 of a task's output
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct TaskChecksum {
+    /// Hash the output once the task completes
+    pub requested: bool,
+    /// Also write `<output>.sha256` next to the output
+    pub write_sidecar: bool,
+    #[serde(skip)]
+    pub state: ChecksumState,
+    /// Hash was computed but the sidecar could not be written
+    #[serde(skip)]
+    pub sidecar_error: Option<String>,
+}
+
+impl TaskChecksum {
+    pub fn new(requested: bool, write_sidecar: bool) -> Self {
+        Self { requested, write_sidecar, ..Self::default() }
+    }
+
+    pub fn sha256(&self) -> Option<&str> {
+        match &self.state {
+            ChecksumState::Done(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    pub fn is_hashing(&self) -> bool {
+        matches!(self.state, ChecksumState::Hashing(_))
+    }
+
+    /// Stop a running hash; the thread marks the checksum Cancelled
+    pub fn cancel(&self) -> bool {
+        match &self.state {
+            ChecksumState::Hashing(job) => {
+                job.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether any checksum thread is still running
+pub fn hashing_active() -> bool {
+    ACTIVE_HASHES.load(Ordering::Relaxed) > 0
+}
+
+/// Hash the task's output on a background thread. The task is already Completed; the result
+/// is written back into the shared list by id when the thread finishes.
+pub fn start_task_checksum(tasks: &Arc<Mutex<Vec<ProcessingTask>>>, task: &mut ProcessingTask) {
+    let output = PathBuf::from(&task.output_file);
+    let total_bytes = std::fs::metadata(&output).map(|metadata| metadata.len()).unwrap_or(0);
+    let job = HashJob::new(total_bytes);
+    task.checksum.state = ChecksumState::Hashing(job.clone());
+    task.checksum.sidecar_error = None;
+
+    let tasks = tasks.clone();
+    let task_id = task.id;
+    let write_sidecar = task.checksum.write_sidecar;
+    ACTIVE_HASHES.fetch_add(1, Ordering::Relaxed);
+    std::thread::spawn(move || {
+        let mut sidecar_error = None;
+        let state = match hash_file(&output, &job.cancel, &job.hashed) {
+            Ok(Some(hash)) => {
+                log_info!("SHA-256 of {}: {}", output.display(), hash);
+                if write_sidecar {
+                    let file_name = output.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                    let sidecar = sidecar_path(&output);
+                    if let Err(e) = std::fs::write(&sidecar, format_sidecar(&hash, &file_name)) {
+                        log_warn!("Failed to write {}: {}", sidecar.display(), e);
+                        sidecar_error = Some(e.to_string());
+                    }
+                }
+                ChecksumState::Done(hash)
+            }
+            Ok(None) => {
+                log_info!("Checksum of task {} cancelled", task_id);
+                ChecksumState::Cancelled
+            }
+            Err(e) => {
+                log_warn!("Failed to hash {}: {}", output.display(), e);
+                ChecksumState::Failed(e.to_string())
+            }
+        };
+        if let Ok(mut tasks) = tasks.lock() {
+            if let Some(task) = tasks.iter_mut().find(|t| t.id == task_id && t.checksum.is_hashing()) {
+                task.checksum.state = state;
+                task.checksum.sidecar_error = sidecar_error;
+            }
+        }
+        ACTIVE_HASHES.fetch_sub(1, Ordering::Relaxed);
+        crate::task_executor::mark_tasks_dirty();
+    });
+}
+
+/// Short trailing sub-status for the task row
+pub fn row_status(checksum: &TaskChecksum, translations: &Translations) -> Option<String> {
+    match &checksum.state {
+        ChecksumState::NotStarted => None,
+        ChecksumState::Hashing(job) => Some(format!("{} {:.0}%", translations.checksum_hashing(), job.progress() * 100.0)),
+        ChecksumState::Done(_) => Some(translations.checksum_done_short().to_string()),
+        ChecksumState::Failed(_) => Some(translations.checksum_failed_short().to_string()),
+        ChecksumState::Cancelled => Some(translations.checksum_cancelled_short().to_string()),
+    }
+}
+
+/// Where the verify tool gets the expected hash from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpectedSource {
+    #[default]
+    Sidecar,
+    Pasted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Match,
+    Mismatch { actual: String },
+    Error(String),
+    Cancelled,
+}
+
+/// "Verify checksum" window: hashes a file on a background thread and compares it with a
+/// sidecar or a pasted hash
+#[derive(Default)]
+pub struct VerifyDialog {
+    pub file: String,
+    pub sidecar: String,
+    pub pasted: String,
+    pub source: ExpectedSource,
+    expected: Option<String>,
+    job: Option<HashJob>,
+    result: Option<Arc<Mutex<Option<VerifyOutcome>>>>,
+    outcome: Option<VerifyOutcome>,
+}
+
+impl VerifyDialog {
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    fn set_file(&mut self, file: String) {
+        let sidecar = sidecar_path(Path::new(&file));
+        if sidecar.is_file() {
+            self.sidecar = sidecar.display().to_string();
+            self.source = ExpectedSource::Sidecar;
+        }
+        self.file = file;
+        self.outcome = None;
+    }
+
+    /// Expected hash from the chosen source, or the message explaining why there is none
+    fn read_expected(&self, translations: &Translations) -> Result<String, &'static str> {
+        match self.source {
+            ExpectedSource::Sidecar => {
+                let contents = std::fs::read_to_string(&self.sidecar).map_err(|_| translations.checksum_sidecar_unreadable())?;
+                parse_sidecar(&contents).map(|(hash, _)| hash).ok_or(translations.checksum_sidecar_invalid())
+            }
+            ExpectedSource::Pasted => normalize_hash(&self.pasted).ok_or(translations.checksum_hash_invalid()),
+        }
+    }
+
+    fn start(&mut self, translations: &Translations) {
+        let expected = match self.read_expected(translations) {
+            Ok(expected) => expected,
+            Err(message) => {
+                self.outcome = Some(VerifyOutcome::Error(message.to_string()));
+                return;
+            }
+        };
+        let path = PathBuf::from(&self.file);
+        let total_bytes = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        let job = HashJob::new(total_bytes);
+        let result = Arc::new(Mutex::new(None));
+        self.expected = Some(expected.clone());
+        self.outcome = None;
+        self.job = Some(job.clone());
+        self.result = Some(result.clone());
+
+        ACTIVE_HASHES.fetch_add(1, Ordering::Relaxed);
+        std::thread::spawn(move || {
+            let outcome = match hash_file(&path, &job.cancel, &job.hashed) {
+                Ok(Some(actual)) if actual == expected => VerifyOutcome::Match,
+                Ok(Some(actual)) => VerifyOutcome::Mismatch { actual },
+                Ok(None) => VerifyOutcome::Cancelled,
+                Err(e) => VerifyOutcome::Error(e.to_string()),
+            };
+            if let Ok(mut result) = result.lock() {
+                *result = Some(outcome);
+            }
+            ACTIVE_HASHES.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    fn poll(&mut self) {
+        let finished = self.result.as_ref()
+            .and_then(|result| result.lock().ok().and_then(|mut result| result.take()));
+        if let Some(outcome) = finished {
+            self.outcome = Some(outcome);
+            self.job = None;
+            self.result = None;
+        }
+    }
+
+    /// Draw the window; returns false once it was closed
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations) -> bool {
+        self.poll();
+        let mut open = true;
+        egui::Window::new(translations.checksum_verify_title())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(460.0);
+                let running = self.is_running();
+                ui.add_enabled_ui(!running, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(translations.checksum_file());
+                        ui.add(egui::TextEdit::singleline(&mut self.file).desired_width(300.0));
+                        if ui.button("📂").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_file() {
+                                self.set_file(path.display().to_string());
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.source, ExpectedSource::Sidecar, translations.checksum_from_sidecar());
+                        ui.radio_value(&mut self.source, ExpectedSource::Pasted, translations.checksum_from_pasted());
+                    });
+                    match self.source {
+                        ExpectedSource::Sidecar => {
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut self.sidecar)
+                                    .hint_text("file.mp4.sha256")
+                                    .desired_width(360.0));
+                                if ui.button("📂").clicked() {
+                                    if let Some(path) = rfd::FileDialog::new().add_filter("SHA-256", &["sha256"]).pick_file() {
+                                        self.sidecar = path.display().to_string();
+                                    }
+                                }
+                            });
+                        }
+                        ExpectedSource::Pasted => {
+                            ui.add(egui::TextEdit::singleline(&mut self.pasted)
+                                .hint_text(translations.checksum_paste_hint())
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY));
+                        }
+                    }
+                });
+                ui.add_space(6.0);
+
+                ui.horizontal(|ui| {
+                    if let Some(job) = &self.job {
+                        ui.add(egui::ProgressBar::new(job.progress()).show_percentage().desired_width(300.0));
+                        if ui.button(translations.cancel()).clicked() {
+                            job.cancel();
+                        }
+                    } else if ui.add_enabled(!self.file.trim().is_empty(), egui::Button::new(translations.checksum_verify())).clicked() {
+                        self.start(translations);
+                    }
+                });
+
+                match &self.outcome {
+                    Some(VerifyOutcome::Match) => {
+                        ui.colored_label(egui::Color32::from_rgb(80, 180, 80), translations.checksum_match());
+                    }
+                    Some(VerifyOutcome::Mismatch { actual }) => {
+                        ui.colored_label(egui::Color32::RED, translations.checksum_mismatch());
+                        egui::Grid::new("checksum_verify_mismatch").num_columns(2).show(ui, |ui| {
+                            ui.weak(translations.checksum_expected());
+                            ui.monospace(self.expected.as_deref().unwrap_or_default());
+                            ui.end_row();
+                            ui.weak(translations.checksum_actual());
+                            ui.monospace(actual);
+                            ui.end_row();
+                        });
+                    }
+                    Some(VerifyOutcome::Error(error)) => {
+                        ui.colored_label(egui::Color32::RED, format!("{}: {}", translations.error(), error));
+                    }
+                    Some(VerifyOutcome::Cancelled) => {
+                        ui.weak(translations.checksum_cancelled_short());
+                    }
+                    None => {}
+                }
+            });
+        if !open {
+            if let Some(job) = &self.job {
+                job.cancel();
+            }
+        }
+        open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+
+    fn sha256_hex(data: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        to_hex(&hasher.finalize())
+    }
+
+    fn pattern(length: usize) -> Vec<u8> {
+        (0..length).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        );
+        assert_eq!(
+            sha256_hex(&[b'a'; 1_000_000]),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+        );
+    }
+
+    #[test]
+    fn padding_block_boundaries() {
+        let expected = [
+            (55, "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59"),
+            (56, "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562"),
+            (63, "29af2686fd53374a36b0846694cc342177e428d1647515f078784d69cdb9e488"),
+            (64, "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108"),
+            (65, "4bfd2c8b6f1eec7a2afeb48b934ee4b2694182027e6d0fc075074f2fabb31781"),
+            (119, "da18797ed7c3a777f0847f429724a2d8cd5138e6ed2895c3fa1a6d39d18f7ec6"),
+            (120, "f52b23db1fbb6ded89ef42a23ce0c8922c45f25c50b568a93bf1c075420bbb7c"),
+        ];
+        for (length, hash) in expected {
+            assert_eq!(sha256_hex(&pattern(length)), hash, "{} bytes", length);
+        }
+    }
+
+    #[test]
+    fn chunked_updates_match_one_update() {
+        let data = pattern(1000);
+        let whole = sha256_hex(&data);
+        for chunk in [1, 3, 7, 63, 64, 65, 127, 500] {
+            let mut hasher = Sha256::new();
+            for piece in data.chunks(chunk) {
+                hasher.update(piece);
+            }
+            assert_eq!(to_hex(&hasher.finalize()), whole, "chunks of {}", chunk);
+        }
+        // Empty updates change nothing
+        let mut hasher = Sha256::new();
+        hasher.update(&data[..10]);
+        hasher.update(&[]);
+        hasher.update(&data[10..]);
+        assert_eq!(to_hex(&hasher.finalize()), whole);
+    }
+
+    #[test]
+    fn hash_file_streams_and_cancels() {
+        let path = std::env::temp_dir().join(format!("ffgui_checksum_{}.bin", uuid::Uuid::new_v4()));
+        // Larger than one read chunk
+        let data = pattern(CHUNK_SIZE + 12345);
+        std::fs::write(&path, &data).unwrap();
+        let hashed = AtomicU64::new(0);
+        assert_eq!(hash_file(&path, &AtomicBool::new(false), &hashed).unwrap(), Some(sha256_hex(&data)));
+        assert_eq!(hashed.load(Ordering::Relaxed), data.len() as u64);
+        assert_eq!(hash_file(&path, &AtomicBool::new(true), &AtomicU64::new(0)).unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sidecar_formats_parse() {
+        let hash = sha256_hex(b"abc");
+        let upper = hash.to_uppercase();
+        assert_eq!(parse_sidecar(&format_sidecar(&hash, "clip 1.mp4")), Some((hash.clone(), Some("clip 1.mp4".to_string()))));
+        assert_eq!(parse_sidecar(&format!("{} *clip.mp4\r\n", upper)), Some((hash.clone(), Some("clip.mp4".to_string()))));
+        assert_eq!(parse_sidecar(&format!("SHA256 (my (1).mp4) = {}\n", hash)), Some((hash.clone(), Some("my (1).mp4".to_string()))));
+        assert_eq!(parse_sidecar(&format!("\u{feff}# made by ffgui\n\n{}\n", hash)), Some((hash.clone(), None)));
+        assert_eq!(parse_sidecar(&format!("{}  a.mp4\n{}  b.mp4\n", hash, "0".repeat(64))).map(|(_, name)| name), Some(Some("a.mp4".to_string())));
+        assert_eq!(parse_sidecar(""), None);
+        assert_eq!(parse_sidecar("not a hash  clip.mp4"), None);
+        assert_eq!(parse_sidecar(&format!("{}  clip.mp4", &hash[..63])), None);
+
+        assert_eq!(normalize_hash(&format!("  {}  ", upper)), Some(hash.clone()));
+        assert_eq!(normalize_hash(&format_sidecar(&hash, "clip.mp4")), Some(hash.clone()));
+        assert_eq!(normalize_hash(&format!("{}g", &hash[..63])), None);
+    }
+
+    fn verify(dialog: &mut VerifyDialog, translations: &Translations) -> VerifyOutcome {
+        dialog.start(translations);
+        let started = std::time::Instant::now();
+        while dialog.is_running() && started.elapsed() < std::time::Duration::from_secs(10) {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            dialog.poll();
+        }
+        dialog.outcome.clone().expect("verification finished")
+    }
+
+    #[test]
+    fn sidecar_verifies_its_file() {
+        let translations = Translations::new(Language::English);
+        let path = std::env::temp_dir().join(format!("ffgui_verify_{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"original output").unwrap();
+        let sidecar = sidecar_path(&path);
+        std::fs::write(&sidecar, format_sidecar(&sha256_hex(b"original output"), "output.mp4")).unwrap();
+
+        let mut dialog = VerifyDialog::default();
+        dialog.set_file(path.display().to_string());
+        assert_eq!(dialog.source, ExpectedSource::Sidecar);
+        assert_eq!(dialog.sidecar, sidecar.display().to_string());
+        assert_eq!(verify(&mut dialog, &translations), VerifyOutcome::Match);
+
+        std::fs::write(&path, b"changed output").unwrap();
+        assert_eq!(verify(&mut dialog, &translations), VerifyOutcome::Mismatch { actual: sha256_hex(b"changed output") });
+
+        std::fs::write(&sidecar, "garbage").unwrap();
+        assert_eq!(verify(&mut dialog, &translations), VerifyOutcome::Error(translations.checksum_sidecar_invalid().to_string()));
+
+        dialog.source = ExpectedSource::Pasted;
+        dialog.pasted = sha256_hex(b"changed output").to_uppercase();
+        assert_eq!(verify(&mut dialog, &translations), VerifyOutcome::Match);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&sidecar).unwrap();
+    }
+}
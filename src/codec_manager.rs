@@ -1016,6 +1016,23 @@ impl CodecManager {
         formats
     }
 
+    /// Encoder standing for a probed stream codec in the compatibility tables, so a stream copy
+    /// can be checked against the output container
+    pub fn encoder_for_stream_codec(stream_codec: &str) -> &str {
+        match stream_codec {
+            "h264" => "libx264",
+            "hevc" => "libx265",
+            "vp8" => "libvpx",
+            "vp9" => "libvpx-vp9",
+            "av1" => "libaom-av1",
+            "prores" => "prores_ks",
+            "mp3" => "libmp3lame",
+            "opus" => "libopus",
+            "vorbis" => "libvorbis",
+            other => other,
+        }
+    }
+
     /// Get best audio encoder based on output format
     pub fn get_best_audio_codec_for_format(format: &str) -> String {
         // Use comprehensive codec registry for better compatibility
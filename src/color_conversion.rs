@@ -0,0 +1,172 @@
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+/// Color metadata of the first video stream as reported by ffprobe
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorMetadata {
+    pub range: Option<String>,      // "tv" (limited) or "pc" (full)
+    pub matrix: Option<String>,     // color_space, e.g. "bt709", "smpte170m"
+    pub transfer: Option<String>,
+    pub primaries: Option<String>,
+}
+
+impl ColorMetadata {
+    /// Short human readable summary for the file info panel
+    pub fn summary(&self) -> String {
+        let range = match self.range.as_deref() {
+            Some("tv") => "limited",
+            Some("pc") => "full",
+            Some(other) => other,
+            None => "unknown range",
+        };
+        format!("{} / {}", self.matrix.as_deref().unwrap_or("unknown matrix"), range)
+    }
+}
+
+/// Probe color range/matrix of the first video stream; None if the probe fails
+pub fn probe_color_metadata(input_file: &str) -> Option<ColorMetadata> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    let output = ffmpeg.run_ffprobe(&[
+        "-v", "error",
+        "-select_streams", "v:0",
+        "-show_entries", "stream=color_range,color_space,color_transfer,color_primaries",
+        "-of", "json",
+        input_file,
+    ]).ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let stream = json["streams"].as_array()?.first()?;
+    Some(from_stream_json(stream))
+}
+
+/// Extract color fields from an ffprobe stream object, ignoring "unknown" values
+pub fn from_stream_json(stream: &serde_json::Value) -> ColorMetadata {
+    let field = |name: &str| stream[name].as_str()
+        .filter(|v| !v.is_empty() && *v != "unknown" && *v != "unspecified")
+        .map(|v| v.to_string());
+    ColorMetadata {
+        range: field("color_range"),
+        matrix: field("color_space"),
+        transfer: field("color_transfer"),
+        primaries: field("color_primaries"),
+    }
+}
+
+/// Map an ffprobe color_space name to a swscale color matrix name
+fn swscale_matrix(color_space: &str) -> Option<&'static str> {
+    match color_space {
+        "bt709" => Some("bt709"),
+        "bt470bg" | "smpte170m" | "bt601" => Some("bt601"),
+        "fcc" => Some("fcc"),
+        "smpte240m" => Some("smpte240m"),
+        "bt2020nc" | "bt2020c" | "bt2020" => Some("bt2020"),
+        _ => None,
+    }
+}
+
+/// Build a scale filter that converts the source to full-range output for still image formats.
+/// Returns None when the source range is unknown so callers fall back to ffmpeg defaults.
+pub fn image_conversion_filter(metadata: &ColorMetadata, image_format: &str) -> Option<String> {
+    let in_range = match metadata.range.as_deref()? {
+        "tv" | "mpeg" | "limited" => "tv",
+        "pc" | "jpeg" | "full" => "pc",
+        _ => return None,
+    };
+
+    let mut params = vec![format!("in_range={}", in_range), "out_range=pc".to_string()];
+    if let Some(matrix) = metadata.matrix.as_deref().and_then(swscale_matrix) {
+        params.push(format!("in_color_matrix={}", matrix));
+    }
+
+    let output_format = match image_format {
+        "jpg" | "jpeg" => {
+            // JPEG stores full-range BT.601 YUV
+            params.push("out_color_matrix=bt601".to_string());
+            "yuvj444p"
+        }
+        "bmp" => "bgr24",
+        _ => "rgb24",
+    };
+    params.push("flags=accurate_rnd+full_chroma_int".to_string());
+
+    Some(format!("scale={},format={}", params.join(":"), output_format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(range: &str, matrix: Option<&str>) -> ColorMetadata {
+        ColorMetadata {
+            range: Some(range.to_string()),
+            matrix: matrix.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bt709_limited_source() {
+        let source = metadata("tv", Some("bt709"));
+        assert_eq!(
+            image_conversion_filter(&source, "png").as_deref(),
+            Some("scale=in_range=tv:out_range=pc:in_color_matrix=bt709:flags=accurate_rnd+full_chroma_int,format=rgb24"),
+        );
+        assert_eq!(
+            image_conversion_filter(&source, "jpg").as_deref(),
+            Some("scale=in_range=tv:out_range=pc:in_color_matrix=bt709:out_color_matrix=bt601:flags=accurate_rnd+full_chroma_int,format=yuvj444p"),
+        );
+        assert_eq!(
+            image_conversion_filter(&source, "bmp").as_deref(),
+            Some("scale=in_range=tv:out_range=pc:in_color_matrix=bt709:flags=accurate_rnd+full_chroma_int,format=bgr24"),
+        );
+    }
+
+    #[test]
+    fn bt601_sources() {
+        for matrix in ["smpte170m", "bt470bg", "bt601"] {
+            let filter = image_conversion_filter(&metadata("tv", Some(matrix)), "png").unwrap();
+            assert!(filter.contains("in_range=tv:out_range=pc:in_color_matrix=bt601:"), "{matrix}: {filter}");
+        }
+        assert_eq!(
+            image_conversion_filter(&metadata("mpeg", Some("smpte170m")), "jpeg").as_deref(),
+            Some("scale=in_range=tv:out_range=pc:in_color_matrix=bt601:out_color_matrix=bt601:flags=accurate_rnd+full_chroma_int,format=yuvj444p"),
+        );
+    }
+
+    #[test]
+    fn full_range_sources() {
+        for range in ["pc", "jpeg", "full"] {
+            assert_eq!(
+                image_conversion_filter(&metadata(range, Some("bt709")), "png").as_deref(),
+                Some("scale=in_range=pc:out_range=pc:in_color_matrix=bt709:flags=accurate_rnd+full_chroma_int,format=rgb24"),
+                "{range}",
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_range_or_matrix() {
+        assert_eq!(image_conversion_filter(&ColorMetadata::default(), "png"), None);
+        assert_eq!(image_conversion_filter(&metadata("weird", Some("bt709")), "png"), None);
+        // An unknown matrix leaves the input matrix to swscale's default
+        assert_eq!(
+            image_conversion_filter(&metadata("tv", Some("ictcp")), "png").as_deref(),
+            Some("scale=in_range=tv:out_range=pc:flags=accurate_rnd+full_chroma_int,format=rgb24"),
+        );
+        assert!(image_conversion_filter(&metadata("tv", Some("bt2020nc")), "png").unwrap().contains("in_color_matrix=bt2020"));
+    }
+
+    #[test]
+    fn ffprobe_stream_fields() {
+        let stream = serde_json::json!({
+            "color_range": "tv",
+            "color_space": "bt709",
+            "color_transfer": "unknown",
+            "color_primaries": "",
+        });
+        let parsed = from_stream_json(&stream);
+        assert_eq!(parsed, metadata("tv", Some("bt709")));
+        assert_eq!(parsed.summary(), "bt709 / limited");
+        assert_eq!(from_stream_json(&serde_json::json!({ "color_space": "unspecified" })), ColorMetadata::default());
+        assert_eq!(ColorMetadata::default().summary(), "unknown matrix / unknown range");
+        assert_eq!(metadata("pc", None).summary(), "unknown matrix / full");
+    }
+}
@@ -0,0 +1,257 @@
+use eframe::egui;
+
+use crate::comprehensive_codec_registry::{CodecType, ComprehensiveCodecRegistry};
+use crate::language::Translations;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    Supported,
+    Caveat(&'static str),
+    Unsupported,
+}
+
+/// Containers × codecs of one codec type, built from the same table the codec/format validation uses
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    pub codecs: Vec<String>,
+    pub containers: Vec<String>,
+    /// `cells[codec][container]`
+    pub cells: Vec<Vec<Support>>,
+}
+
+impl Matrix {
+    pub fn build(codec_type: CodecType) -> Self {
+        let compatibility = ComprehensiveCodecRegistry::get_codec_format_compatibility();
+        let audio_codecs = ComprehensiveCodecRegistry::get_audio_codecs();
+        let video_codecs = ComprehensiveCodecRegistry::get_video_codecs();
+        // Encoders missing from the detail tables are hardware video encoders or speech codecs
+        let type_of = |codec: &str| {
+            if audio_codecs.contains_key(codec) {
+                CodecType::Audio
+            } else if video_codecs.contains_key(codec) || crate::hardware_refresh::is_hardware_encoder(codec) {
+                CodecType::Video
+            } else {
+                CodecType::Audio
+            }
+        };
+
+        let mut codecs: Vec<String> = compatibility.keys().filter(|codec| type_of(codec) == codec_type).cloned().collect();
+        // Software encoders first, then hardware ones grouped by API
+        codecs.sort_by_key(|codec| {
+            let hardware = crate::hardware_refresh::is_hardware_encoder(codec);
+            let api = if hardware { codec.rsplit('_').next().unwrap_or_default().to_string() } else { String::new() };
+            (hardware, api, codec.clone())
+        });
+
+        // Every container the registry knows or a codec lists, without the ones no codec of this type fits
+        let mut containers: Vec<String> = ComprehensiveCodecRegistry::get_container_formats().into_keys()
+            .chain(compatibility.values().flatten().cloned())
+            .filter(|container| codecs.iter().any(|codec| compatibility[codec].contains(container)))
+            .collect();
+        containers.sort();
+        containers.dedup();
+
+        let cells = codecs.iter()
+            .map(|codec| containers.iter()
+                .map(|container| {
+                    if !compatibility[codec].contains(container) {
+                        Support::Unsupported
+                    } else if let Some(caveat) = ComprehensiveCodecRegistry::compatibility_caveat(codec, container) {
+                        Support::Caveat(caveat)
+                    } else {
+                        Support::Supported
+                    }
+                })
+                .collect())
+            .collect();
+        Self { codecs, containers, cells }
+    }
+}
+
+/// Help > Compatibility matrix: read-only containers × codecs table with video and audio tabs
+pub struct MatrixWindow {
+    video: Matrix,
+    audio: Matrix,
+    show_audio: bool,
+    filter: String,
+}
+
+impl Default for MatrixWindow {
+    fn default() -> Self {
+        Self {
+            video: Matrix::build(CodecType::Video),
+            audio: Matrix::build(CodecType::Audio),
+            show_audio: false,
+            filter: String::new(),
+        }
+    }
+}
+
+impl MatrixWindow {
+    /// Highlights the row and column of the codecs and container selected in the settings.
+    /// Returns false once closed.
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations, container: &str, video_codec: &str, audio_codec: &str) -> bool {
+        let mut open = true;
+        egui::Window::new(translations.compatibility_title())
+            .open(&mut open)
+            .resizable(true)
+            .default_size([720.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.show_audio, false, translations.compatibility_video());
+                    ui.selectable_value(&mut self.show_audio, true, translations.compatibility_audio());
+                    ui.separator();
+                    ui.label("🔍");
+                    ui.add(egui::TextEdit::singleline(&mut self.filter)
+                        .hint_text(translations.compatibility_filter_hint())
+                        .desired_width(180.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.colored_label(SUPPORTED_COLOR, format!("✔ {}", translations.compatibility_supported()));
+                    ui.colored_label(CAVEAT_COLOR, format!("⚠ {}", translations.compatibility_caveat()));
+                    ui.weak(format!("— {}", translations.compatibility_unsupported()));
+                });
+                ui.separator();
+
+                let (matrix, codec) = if self.show_audio { (&self.audio, audio_codec) } else { (&self.video, video_codec) };
+                show_grid(ui, matrix, &self.filter, codec, container, translations);
+            });
+        open
+    }
+}
+
+const SUPPORTED_COLOR: egui::Color32 = egui::Color32::from_rgb(100, 200, 100);
+const CAVEAT_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 180, 60);
+
+/// Rows and columns matching the filter; when nothing on one axis matches, that axis is shown whole
+fn filtered(names: &[String], filter: &str) -> Vec<usize> {
+    let matching: Vec<usize> = (0..names.len()).filter(|&i| names[i].to_lowercase().contains(filter)).collect();
+    if matching.is_empty() { (0..names.len()).collect() } else { matching }
+}
+
+fn show_grid(ui: &mut egui::Ui, matrix: &Matrix, filter: &str, selected_codec: &str, selected_container: &str, translations: &Translations) {
+    let filter = filter.trim().to_lowercase();
+    let rows = filtered(&matrix.codecs, &filter);
+    let columns = filtered(&matrix.containers, &filter);
+    let highlight = ui.visuals().selection.bg_fill.gamma_multiply(0.35);
+    let cell_text = |text: egui::RichText, highlighted: bool| if highlighted { text.background_color(highlight) } else { text };
+
+    egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+        egui::Grid::new("compatibility_matrix_grid").striped(true).min_col_width(36.0).show(ui, |ui| {
+            ui.label("");
+            for &column in &columns {
+                let container = &matrix.containers[column];
+                ui.label(cell_text(egui::RichText::new(container).strong(), container == selected_container));
+            }
+            ui.end_row();
+
+            for &row in &rows {
+                let codec = &matrix.codecs[row];
+                let codec_selected = codec == selected_codec;
+                ui.label(cell_text(egui::RichText::new(codec).monospace(), codec_selected));
+                for &column in &columns {
+                    let container = &matrix.containers[column];
+                    let highlighted = codec_selected || container == selected_container;
+                    let (text, hint) = match matrix.cells[row][column] {
+                        Support::Supported => (egui::RichText::new("✔").color(SUPPORTED_COLOR), translations.compatibility_supported()),
+                        Support::Caveat(caveat) => (egui::RichText::new("⚠").color(CAVEAT_COLOR), caveat),
+                        Support::Unsupported => (egui::RichText::new("—").weak(), translations.compatibility_unsupported()),
+                    };
+                    ui.label(cell_text(text, highlighted)).on_hover_text(format!("{} × {}\n{}", codec, container, hint));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(matrix: &Matrix, codec: &str, container: &str) -> Support {
+        let row = matrix.codecs.iter().position(|name| name == codec).unwrap_or_else(|| panic!("no row {codec}"));
+        let column = matrix.containers.iter().position(|name| name == container).unwrap_or_else(|| panic!("no column {container}"));
+        matrix.cells[row][column]
+    }
+
+    #[test]
+    fn dimensions_follow_the_registry() {
+        let compatibility = ComprehensiveCodecRegistry::get_codec_format_compatibility();
+        let video = Matrix::build(CodecType::Video);
+        let audio = Matrix::build(CodecType::Audio);
+        for matrix in [&video, &audio] {
+            assert_eq!(matrix.cells.len(), matrix.codecs.len());
+            assert!(matrix.cells.iter().all(|row| row.len() == matrix.containers.len()));
+            assert!(matrix.containers.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", matrix.containers);
+            // No column is empty
+            for column in 0..matrix.containers.len() {
+                assert!(matrix.cells.iter().any(|row| row[column] != Support::Unsupported), "{}", matrix.containers[column]);
+            }
+        }
+        // Every codec of the table lands on exactly one tab
+        assert_eq!(video.codecs.len() + audio.codecs.len(), compatibility.len());
+        assert!(video.codecs.iter().all(|codec| !audio.codecs.contains(codec)));
+        assert!(video.codecs.contains(&"h264_nvenc".to_string()));
+        assert!(audio.codecs.contains(&"amr_nb".to_string()));
+        // Audio-only containers only show up for audio
+        assert!(audio.containers.contains(&"mp3".to_string()) && !video.containers.contains(&"mp3".to_string()));
+    }
+
+    #[test]
+    fn hardware_encoders_follow_software_ones_by_api() {
+        let video = Matrix::build(CodecType::Video);
+        let first_hardware = video.codecs.iter().position(|codec| crate::hardware_refresh::is_hardware_encoder(codec)).unwrap();
+        assert!(video.codecs[first_hardware..].iter().all(|codec| crate::hardware_refresh::is_hardware_encoder(codec)));
+        let nvenc: Vec<usize> = (0..video.codecs.len()).filter(|&row| video.codecs[row].ends_with("_nvenc")).collect();
+        assert!(nvenc.windows(2).all(|pair| pair[1] == pair[0] + 1), "{:?}", video.codecs);
+    }
+
+    #[test]
+    fn known_cells() {
+        let video = Matrix::build(CodecType::Video);
+        let audio = Matrix::build(CodecType::Audio);
+        assert_eq!(cell(&audio, "aac", "webm"), Support::Unsupported);
+        assert_eq!(cell(&video, "libx264", "webm"), Support::Unsupported);
+        assert_eq!(cell(&audio, "libopus", "webm"), Support::Supported);
+        assert_eq!(cell(&video, "libvpx-vp9", "webm"), Support::Supported);
+        assert_eq!(cell(&audio, "aac", "mp4"), Support::Supported);
+        assert!(matches!(cell(&audio, "flac", "mp4"), Support::Caveat(caveat) if caveat.contains("players")));
+        assert!(matches!(cell(&video, "libx265", "mp4"), Support::Caveat(caveat) if caveat.contains("hvc1")));
+        assert!(matches!(cell(&video, "hevc_nvenc", "mov"), Support::Caveat(_)));
+        // The matrix agrees with the validation
+        for (matrix, codecs) in [(&video, &video.codecs), (&audio, &audio.codecs)] {
+            for codec in codecs {
+                for container in &matrix.containers {
+                    assert_eq!(cell(matrix, codec, container) != Support::Unsupported,
+                        ComprehensiveCodecRegistry::is_compatible(codec, container), "{codec} × {container}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matroska_takes_almost_everything() {
+        let supported = |matrix: &Matrix, container: &str| matrix.codecs.iter()
+            .filter(|codec| cell(matrix, codec, container) != Support::Unsupported)
+            .count();
+        for (matrix, legacy) in [
+            (Matrix::build(CodecType::Video), &["flv1", "qtrle", "wmv2"][..]),
+            (Matrix::build(CodecType::Audio), &["amr_nb", "amr_wb", "wmav2"][..]),
+        ] {
+            for codec in matrix.codecs.iter().filter(|codec| !legacy.contains(&codec.as_str())) {
+                assert_ne!(cell(&matrix, codec, "mkv"), Support::Unsupported, "{codec} in mkv");
+            }
+            let mkv = supported(&matrix, "mkv");
+            assert!(matrix.containers.iter().all(|container| supported(&matrix, container) <= mkv));
+        }
+    }
+
+    #[test]
+    fn filter_narrows_one_axis_at_a_time() {
+        let names: Vec<String> = ["libx264", "libx265", "h264_nvenc"].map(String::from).to_vec();
+        assert_eq!(filtered(&names, "264"), [0, 2]);
+        assert_eq!(filtered(&names, ""), [0, 1, 2]);
+        assert_eq!(filtered(&names, "mkv"), [0, 1, 2]);
+    }
+}
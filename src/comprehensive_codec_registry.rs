@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use serde::{Serialize, Deserialize};
 
 /// Comprehensive codec and format registry for FFmpeg
@@ -95,6 +95,71 @@ pub enum FormatCategory {
     ArchivalFormats,
 }
 
+/// Muxer option a container supports, part of its publishing profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerOption {
+    /// Key stored in `VideoSettings::container_options`
+    pub key: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    pub default_enabled: bool,
+}
+
+/// Values an encoder accepts, checked before ffmpeg is started
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EncoderLimits {
+    /// CRF/CQ/global_quality range; None when the encoder has no quality scale
+    pub quality_range: Option<(i32, i32)>,
+    /// Largest frame size, from the highest codec level or the hardware block
+    pub max_width: u32,
+    pub max_height: u32,
+    /// Width and height must be even (4:2:0 chroma subsampling)
+    pub even_dimensions: bool,
+    pub max_fps: f64,
+    /// Plausible bitrate range in kbps
+    pub bitrate_kbps: (f64, f64),
+}
+
+/// Limits for encoders not in the table; only catches values no encoder accepts
+const GENERIC_VIDEO_LIMITS: EncoderLimits = EncoderLimits {
+    quality_range: None,
+    max_width: 16384,
+    max_height: 16384,
+    even_dimensions: false,
+    max_fps: 240.0,
+    bitrate_kbps: (16.0, 1_000_000.0),
+};
+
+const GENERIC_AUDIO_LIMITS: EncoderLimits = EncoderLimits {
+    quality_range: None,
+    max_width: 0,
+    max_height: 0,
+    even_dimensions: false,
+    max_fps: 0.0,
+    bitrate_kbps: (6.0, 1536.0),
+};
+
+/// Whether an FFmpeg pixel format carries an alpha channel
+pub fn is_alpha_pixel_format(pix_fmt: &str) -> bool {
+    pix_fmt.starts_with("yuva")
+        || pix_fmt.starts_with("gbrap")
+        || pix_fmt.starts_with("ya")
+        || ["rgba", "bgra", "argb", "abgr"].iter().any(|prefix| pix_fmt.starts_with(prefix))
+}
+
+impl CodecInfo {
+    /// First supported pixel format with alpha; None when the encoder always drops alpha
+    pub fn alpha_pixel_format(&self) -> Option<&str> {
+        self.supported_pixel_formats.iter()
+            .map(|pix_fmt| pix_fmt.as_str())
+            .find(|pix_fmt| is_alpha_pixel_format(pix_fmt))
+    }
+
+    pub fn supports_alpha(&self) -> bool {
+        self.alpha_pixel_format().is_some()
+    }
+}
+
 pub struct ComprehensiveCodecRegistry;
 
 impl ComprehensiveCodecRegistry {
@@ -423,6 +488,70 @@ impl ComprehensiveCodecRegistry {
             category: CodecCategory::LegacyVideo,
         });
 
+        // === Intermediate / lossless (alpha-capable) ===
+        codecs.insert("prores_ks".to_string(), CodecInfo {
+            name: "prores_ks".to_string(),
+            display_name: "Apple ProRes (prores_ks)".to_string(),
+            description: "Apple ProRes intermediate codec; the 4444 profiles keep an alpha channel".to_string(),
+            codec_type: CodecType::Video,
+            supported_formats: vec!["mov".to_string(), "mkv".to_string()],
+            supported_sample_rates: vec![],
+            supported_bit_rates: vec![],
+            supported_pixel_formats: vec!["yuv422p10le".to_string(), "yuv444p10le".to_string(), "yuva444p10le".to_string()],
+            supports_vbr: false,
+            supports_cbr: false,
+            supports_hardware: false,
+            default_bitrate: "auto".to_string(),
+            default_sample_rate: 0,
+            quality_range: None,
+            preset_options: vec![],
+            profile_options: vec!["0".to_string(), "1".to_string(), "2".to_string(), "3".to_string(), "4".to_string(), "5".to_string()],
+            level_options: vec![],
+            category: CodecCategory::LosslessVideo,
+        });
+
+        codecs.insert("qtrle".to_string(), CodecInfo {
+            name: "qtrle".to_string(),
+            display_name: "QuickTime Animation (qtrle)".to_string(),
+            description: "Lossless run-length codec for motion graphics with alpha".to_string(),
+            codec_type: CodecType::Video,
+            supported_formats: vec!["mov".to_string()],
+            supported_sample_rates: vec![],
+            supported_bit_rates: vec![],
+            supported_pixel_formats: vec!["rgb24".to_string(), "argb".to_string()],
+            supports_vbr: false,
+            supports_cbr: false,
+            supports_hardware: false,
+            default_bitrate: "auto".to_string(),
+            default_sample_rate: 0,
+            quality_range: None,
+            preset_options: vec![],
+            profile_options: vec![],
+            level_options: vec![],
+            category: CodecCategory::LosslessVideo,
+        });
+
+        codecs.insert("ffv1".to_string(), CodecInfo {
+            name: "ffv1".to_string(),
+            display_name: "FFV1 (lossless)".to_string(),
+            description: "Lossless archival codec, optionally with alpha".to_string(),
+            codec_type: CodecType::Video,
+            supported_formats: vec!["mkv".to_string(), "avi".to_string(), "mov".to_string()],
+            supported_sample_rates: vec![],
+            supported_bit_rates: vec![],
+            supported_pixel_formats: vec!["yuv420p".to_string(), "yuva420p".to_string(), "yuv444p".to_string(), "yuva444p".to_string(), "yuv420p10le".to_string(), "rgb24".to_string(), "bgra".to_string()],
+            supports_vbr: false,
+            supports_cbr: false,
+            supports_hardware: false,
+            default_bitrate: "auto".to_string(),
+            default_sample_rate: 0,
+            quality_range: None,
+            preset_options: vec![],
+            profile_options: vec![],
+            level_options: vec![],
+            category: CodecCategory::LosslessVideo,
+        });
+
         codecs
     }
 
@@ -1078,6 +1207,9 @@ impl ComprehensiveCodecRegistry {
         compatibility.insert("libvpx".to_string(), vec!["webm".to_string(), "mkv".to_string(), "avi".to_string()]);
         compatibility.insert("libvpx-vp9".to_string(), vec!["webm".to_string(), "mkv".to_string()]);
         compatibility.insert("libaom-av1".to_string(), vec!["mp4".to_string(), "webm".to_string(), "mkv".to_string()]);
+        compatibility.insert("prores_ks".to_string(), vec!["mov".to_string(), "mkv".to_string()]);
+        compatibility.insert("qtrle".to_string(), vec!["mov".to_string()]);
+        compatibility.insert("ffv1".to_string(), vec!["mkv".to_string(), "avi".to_string(), "mov".to_string()]);
         
         // Legacy codecs for wmv and flv
         compatibility.insert("wmv2".to_string(), vec!["wmv".to_string(), "avi".to_string()]);
@@ -1118,11 +1250,11 @@ impl ComprehensiveCodecRegistry {
         compatibility.insert("libfdk_aac".to_string(), vec!["aac".to_string(), "m4a".to_string(), "mp4".to_string(), "mkv".to_string(), "mov".to_string(), "m4v".to_string()]);
         compatibility.insert("libopus".to_string(), vec!["opus".to_string(), "ogg".to_string(), "webm".to_string(), "mkv".to_string()]);
         compatibility.insert("libvorbis".to_string(), vec!["ogg".to_string(), "webm".to_string(), "mkv".to_string()]);
-        compatibility.insert("flac".to_string(), vec!["flac".to_string(), "mkv".to_string(), "ogg".to_string()]);
-        compatibility.insert("alac".to_string(), vec!["m4a".to_string(), "mp4".to_string(), "mov".to_string(), "m4v".to_string()]);
-        compatibility.insert("pcm_s16le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string()]);
-        compatibility.insert("pcm_s24le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string()]);
-        compatibility.insert("pcm_f32le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string()]);
+        compatibility.insert("flac".to_string(), vec!["flac".to_string(), "mkv".to_string(), "ogg".to_string(), "mp4".to_string()]);
+        compatibility.insert("alac".to_string(), vec!["m4a".to_string(), "mp4".to_string(), "mov".to_string(), "m4v".to_string(), "mkv".to_string()]);
+        compatibility.insert("pcm_s16le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string(), "mkv".to_string()]);
+        compatibility.insert("pcm_s24le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string(), "mkv".to_string()]);
+        compatibility.insert("pcm_f32le".to_string(), vec!["wav".to_string(), "mov".to_string(), "avi".to_string(), "mkv".to_string()]);
         
         // Legacy audio for wma/wmv
         compatibility.insert("wmav2".to_string(), vec!["wma".to_string(), "wmv".to_string(), "avi".to_string()]);
@@ -1144,6 +1276,30 @@ impl ComprehensiveCodecRegistry {
         }
     }
 
+    /// Why a combination `is_compatible` accepts may still disappoint: ffmpeg writes it fine, but
+    /// players or devices handle it poorly
+    pub fn compatibility_caveat(codec: &str, format: &str) -> Option<&'static str> {
+        match (codec, format) {
+            ("flac", "mp4") => Some("FLAC in MP4 is supported by ffmpeg, but many players cannot play it"),
+            ("libx265" | "hevc_nvenc" | "hevc_qsv" | "hevc_amf" | "hevc_vaapi", "mp4" | "mov" | "m4v") => {
+                Some("Apple players need the hvc1 tag (-tag:v hvc1) to play H.265")
+            }
+            ("libaom-av1" | "av1_nvenc" | "av1_qsv" | "av1_amf" | "av1_vaapi", "mp4") => {
+                Some("Older players and devices cannot decode AV1")
+            }
+            ("libx264" | "h264_nvenc" | "h264_qsv" | "h264_amf" | "h264_vaapi", "avi") => {
+                Some("AVI has no proper B-frame support; some players stutter or show wrong timestamps")
+            }
+            ("libx264" | "h264_nvenc" | "h264_qsv" | "h264_amf", "flv") => Some("FLV is a legacy Flash container"),
+            ("libmp3lame", "mp4" | "mov") => Some("Some players do not play MP3 audio in MP4/MOV"),
+            ("aac", "avi") => Some("AAC in AVI is non-standard and many players fail on it"),
+            ("ac3", "mp4") => Some("Few players outside home theater setups decode AC-3 in MP4"),
+            ("alac", "mp4" | "m4v") => Some("ALAC plays mostly on Apple devices"),
+            ("libopus", "ogg") => Some("Prefer the .opus extension; some players only look for Vorbis in .ogg"),
+            _ => None,
+        }
+    }
+
     /// Get recommended codecs for a format
     pub fn get_recommended_codecs_for_format(format: &str) -> (Vec<String>, Vec<String>) {
         let formats = Self::get_container_formats();
@@ -1206,4 +1362,230 @@ impl ComprehensiveCodecRegistry {
         
         by_category
     }
-}
\ No newline at end of file
+
+    /// Registry name for the codec names the UI and presets use ("h264", "libav1", ...)
+    pub fn canonical_encoder_name(codec: &str) -> &str {
+        match codec {
+            "h264" | "x264" => "libx264",
+            "hevc" | "h265" | "x265" => "libx265",
+            "vp8" => "libvpx",
+            "vp9" => "libvpx-vp9",
+            "av1" | "libav1" => "libaom-av1",
+            "mp3" => "libmp3lame",
+            "opus" => "libopus",
+            "vorbis" => "libvorbis",
+            _ => codec,
+        }
+    }
+
+    /// Parameter limits of a video encoder. Hardware encoders get their own rows since their
+    /// quality scale and maximum frame size differ from the software encoder of the same codec.
+    pub fn get_encoder_limits(codec: &str) -> EncoderLimits {
+        let limits = |quality_range: Option<(i32, i32)>, max_width: u32, max_height: u32, even_dimensions: bool| EncoderLimits {
+            quality_range,
+            max_width,
+            max_height,
+            even_dimensions,
+            ..GENERIC_VIDEO_LIMITS
+        };
+        match Self::canonical_encoder_name(codec) {
+            // H.264 level 6.2 / HEVC level 6.2
+            "libx264" | "libx265" => limits(Some((0, 51)), 8192, 4320, true),
+            // NVENC -cq is 0-51 for every codec; 0 lets the encoder choose
+            "h264_nvenc" => limits(Some((0, 51)), 4096, 4096, true),
+            "hevc_nvenc" | "av1_nvenc" => limits(Some((0, 51)), 8192, 8192, true),
+            "h264_qsv" => limits(Some((1, 51)), 4096, 2304, true),
+            "hevc_qsv" => limits(Some((1, 51)), 8192, 8192, true),
+            "h264_amf" => limits(Some((1, 51)), 4096, 2160, true),
+            "hevc_amf" => limits(Some((1, 51)), 7680, 4320, true),
+            "av1_amf" => limits(Some((1, 63)), 8192, 4352, true),
+            "h264_videotoolbox" => limits(Some((1, 100)), 4096, 2304, true),
+            "hevc_videotoolbox" => limits(Some((1, 100)), 8192, 4320, true),
+            "h264_vaapi" => limits(Some((1, 51)), 4096, 4096, true),
+            "hevc_vaapi" => limits(Some((1, 51)), 8192, 8192, true),
+            "libvpx" => limits(Some((4, 63)), 16383, 16383, false),
+            "libvpx-vp9" => limits(Some((0, 63)), 16384, 16384, false),
+            // AV1 level 6.3
+            "libaom-av1" | "libsvtav1" => limits(Some((0, 63)), 16384, 8704, true),
+            "wmv2" | "flv1" => limits(Some((1, 31)), 4096, 4096, true),
+            "prores_ks" | "prores_videotoolbox" => limits(None, 16384, 16384, true),
+            _ => GENERIC_VIDEO_LIMITS,
+        }
+    }
+
+    /// Bitrate limits of an audio encoder
+    pub fn get_audio_encoder_limits(codec: &str) -> EncoderLimits {
+        let bitrate_kbps = match Self::canonical_encoder_name(codec) {
+            "libmp3lame" => (8.0, 320.0),
+            "aac" | "libfdk_aac" => (8.0, 512.0),
+            "libopus" => (6.0, 510.0),
+            "libvorbis" => (32.0, 500.0),
+            "ac3" => (32.0, 640.0),
+            "wmav2" => (24.0, 384.0),
+            "amr_nb" => (4.75, 12.2),
+            "amr_wb" => (6.6, 23.85),
+            _ => GENERIC_AUDIO_LIMITS.bitrate_kbps,
+        };
+        EncoderLimits { bitrate_kbps, ..GENERIC_AUDIO_LIMITS }
+    }
+
+    /// Publishing profile of a container: muxer options with their defaults
+    pub fn get_container_options(format: &str) -> Vec<ContainerOption> {
+        match format {
+            "mp4" | "m4v" | "mov" => vec![
+                ContainerOption {
+                    key: "faststart",
+                    display_name: "Fast start",
+                    description: "Move the moov atom to the front so playback can begin while downloading",
+                    default_enabled: true,
+                },
+                ContainerOption {
+                    key: "fragmented",
+                    display_name: "Fragmented MP4",
+                    description: "Write self-contained fragments (frag_keyframe+empty_moov) for streaming; replaces fast start",
+                    default_enabled: false,
+                },
+            ],
+            "mkv" => vec![
+                ContainerOption {
+                    key: "cues_front",
+                    display_name: "Cues at front",
+                    description: "Write the seek index at the start of the file for faster seeking over the network",
+                    default_enabled: true,
+                },
+            ],
+            "webm" => vec![
+                ContainerOption {
+                    key: "dash",
+                    display_name: "DASH",
+                    description: "Write WebM DASH compatible clusters and cues",
+                    default_enabled: false,
+                },
+            ],
+            _ => vec![],
+        }
+    }
+
+    /// Whether `key` is enabled for `format`, taking user overrides into account
+    pub fn is_container_option_enabled(format: &str, key: &str, overrides: &BTreeMap<String, bool>) -> bool {
+        Self::get_container_options(format).iter()
+            .find(|option| option.key == key)
+            .map(|option| overrides.get(key).copied().unwrap_or(option.default_enabled))
+            .unwrap_or(false)
+    }
+
+    /// Muxer arguments for `format`, placed just before the output file.
+    /// Flags already present in `existing_args` (e.g. preset custom args) are not repeated.
+    pub fn build_muxer_args(format: &str, overrides: &BTreeMap<String, bool>, existing_args: &[String]) -> Vec<String> {
+        let enabled = |key: &str| Self::is_container_option_enabled(format, key, overrides);
+        let already_set = |flag: &str| existing_args.iter().any(|arg| arg == flag);
+        let mut args = Vec::new();
+
+        match format {
+            "mp4" | "m4v" | "mov" if !already_set("-movflags") => {
+                // Fragmented output has no single moov atom to move, so it takes precedence
+                if enabled("fragmented") {
+                    args.extend(["-movflags".to_string(), "frag_keyframe+empty_moov".to_string()]);
+                } else if enabled("faststart") {
+                    args.extend(["-movflags".to_string(), "+faststart".to_string()]);
+                }
+            }
+            "mkv" if enabled("cues_front") && !already_set("-cues_to_front") && !already_set("-reserve_index_space") => {
+                args.extend(["-cues_to_front".to_string(), "1".to_string()]);
+            }
+            "webm" if enabled("dash") && !already_set("-dash") => {
+                args.extend(["-dash".to_string(), "1".to_string()]);
+            }
+            _ => {}
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::VideoSettings;
+    use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+    fn overrides(entries: &[(&str, bool)]) -> BTreeMap<String, bool> {
+        entries.iter().map(|(key, enabled)| (key.to_string(), *enabled)).collect()
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn default_flags_per_container() {
+        let none = BTreeMap::new();
+        for format in ["mp4", "m4v", "mov"] {
+            assert_eq!(ComprehensiveCodecRegistry::build_muxer_args(format, &none, &[]), ["-movflags", "+faststart"], "{format}");
+        }
+        assert_eq!(ComprehensiveCodecRegistry::build_muxer_args("mkv", &none, &[]), ["-cues_to_front", "1"]);
+        // DASH clusters are opt-in
+        assert!(ComprehensiveCodecRegistry::build_muxer_args("webm", &none, &[]).is_empty());
+        for format in ["avi", "ts", "flv", "gif", ""] {
+            assert!(ComprehensiveCodecRegistry::get_container_options(format).is_empty(), "{format}");
+            assert!(ComprehensiveCodecRegistry::build_muxer_args(format, &none, &[]).is_empty(), "{format}");
+        }
+    }
+
+    #[test]
+    fn options_can_be_disabled_and_enabled() {
+        let args = |format: &str, entries: &[(&str, bool)]| ComprehensiveCodecRegistry::build_muxer_args(format, &overrides(entries), &[]);
+        assert!(args("mp4", &[("faststart", false)]).is_empty());
+        assert!(args("mkv", &[("cues_front", false)]).is_empty());
+        assert_eq!(args("webm", &[("dash", true)]), ["-dash", "1"]);
+
+        // Fragmented output replaces fast start, whatever fast start is set to
+        assert_eq!(args("mp4", &[("fragmented", true)]), ["-movflags", "frag_keyframe+empty_moov"]);
+        assert_eq!(args("mov", &[("fragmented", true), ("faststart", false)]), ["-movflags", "frag_keyframe+empty_moov"]);
+
+        // Overrides for options another container has are ignored
+        assert_eq!(args("mkv", &[("faststart", false), ("dash", true)]), ["-cues_to_front", "1"]);
+        assert!(!ComprehensiveCodecRegistry::is_container_option_enabled("webm", "faststart", &overrides(&[("faststart", true)])));
+        assert!(!ComprehensiveCodecRegistry::is_container_option_enabled("mp4", "unknown", &BTreeMap::new()));
+    }
+
+    #[test]
+    fn flags_already_in_custom_args_are_not_repeated() {
+        let none = BTreeMap::new();
+        assert!(ComprehensiveCodecRegistry::build_muxer_args("mp4", &none, &strings(&["-movflags", "+write_colr"])).is_empty());
+        assert!(ComprehensiveCodecRegistry::build_muxer_args("mkv", &none, &strings(&["-reserve_index_space", "50000"])).is_empty());
+        assert!(ComprehensiveCodecRegistry::build_muxer_args("webm", &overrides(&[("dash", true)]), &strings(&["-dash", "0"])).is_empty());
+        // Unrelated custom args leave the defaults in place
+        assert_eq!(ComprehensiveCodecRegistry::build_muxer_args("mp4", &none, &strings(&["-metadata", "title=x"])), ["-movflags", "+faststart"]);
+    }
+
+    #[test]
+    fn command_builder_picks_the_container_from_the_output() {
+        let settings = VideoSettings { container_format: "mp4".to_string(), ..Default::default() };
+        // The output extension decides, so a renamed output gets its own container's flags
+        assert_eq!(ComprehensiveCommandBuilder::muxer_args("out.MKV", &settings), ["-cues_to_front", "1"]);
+        assert_eq!(ComprehensiveCommandBuilder::muxer_args("out.mp4", &settings), ["-movflags", "+faststart"]);
+        // Without an extension the configured container applies
+        assert_eq!(ComprehensiveCommandBuilder::muxer_args("out", &settings), ["-movflags", "+faststart"]);
+
+        // A forced format keeps the configured container's flags under any extension
+        let forced = VideoSettings { container_format: "mp4".to_string(), keep_output_extension: true, ..Default::default() };
+        assert_eq!(ComprehensiveCommandBuilder::muxer_args("out.bin", &forced), ["-f", "mp4", "-movflags", "+faststart"]);
+
+        let custom = VideoSettings {
+            custom_args: "-movflags +faststart+write_colr".to_string(),
+            ..settings.clone()
+        };
+        assert!(ComprehensiveCommandBuilder::muxer_args("out.mp4", &custom).is_empty());
+
+        let disabled = VideoSettings { container_options: overrides(&[("faststart", false)]), ..settings };
+        assert!(ComprehensiveCommandBuilder::muxer_args("out.mp4", &disabled).is_empty());
+    }
+
+    #[test]
+    fn container_options_survive_saving_settings() {
+        let settings = VideoSettings { container_options: overrides(&[("faststart", false), ("fragmented", true)]), ..Default::default() };
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: VideoSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.container_options, settings.container_options);
+    }
+}
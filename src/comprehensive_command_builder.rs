@@ -1,5 +1,5 @@
 use crate::comprehensive_codec_registry::*;
-use crate::app_state::{VideoSettings, AudioSettings};
+use crate::app_state::{VideoSettings, AudioSettings, FrameFit};
 use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 
@@ -15,10 +15,18 @@ impl ComprehensiveCommandBuilder {
         audio_settings: Option<&AudioSettings>
     ) -> Result<Vec<String>> {
         let mut args = Vec::new();
+        let program_stream = crate::transport_stream::needs_fixups(input_file);
         
         // Input file
+        if program_stream {
+            args.extend(crate::transport_stream::input_args());
+        }
         args.push("-i".to_string());
         args.push(input_file.to_string());
+        if let Some(metadata_file) = &video_settings.chapter_metadata_file {
+            args.push("-i".to_string());
+            args.push(metadata_file.clone());
+        }
         
         // Video codec and settings
         if video_settings.copy_video {
@@ -73,6 +81,13 @@ impl ComprehensiveCommandBuilder {
         // Video filters and transformations
         let mut video_filters = Vec::new();
         
+        // Flatten transparent sources onto the chosen background before any other filter
+        if let Some(background) = video_settings.alpha_background.as_deref().filter(|_| video_settings.source_alpha) {
+            if !(video_settings.codec.contains("nvenc") && video_settings.use_hardware_acceleration) {
+                video_filters.push(crate::alpha_video::flatten_filter(background));
+            }
+        }
+        
         // Handle format conversion and scaling for NVENC with hardware acceleration
         if video_settings.codec.contains("nvenc") && video_settings.use_hardware_acceleration {
             // When using CUDA hardware acceleration, use CUDA-aware scaling and format conversion
@@ -97,7 +112,7 @@ impl ComprehensiveCommandBuilder {
             // Resolution for software path
             if let (Some(width), Some(height)) = (video_settings.width, video_settings.height) {
                 if width > 0 && height > 0 {
-                    video_filters.push(format!("scale={}:{}", width, height));
+                    video_filters.push(Self::scale_filter(width, height, video_settings.frame_fit));
                 }
             }
         }
@@ -162,11 +177,28 @@ impl ComprehensiveCommandBuilder {
         }
         
         // Frame rate
-        if video_settings.fps != "auto" && !video_settings.fps.is_empty() {
+        if let Some(rate) = crate::frame_rate::output_rate(&video_settings.fps) {
             args.push("-r".to_string());
-            args.push(video_settings.fps.clone());
+            args.push(rate);
+        }
+        
+        // TS/VOB: explicit program mapping and zero-based timestamps
+        if program_stream {
+            args.extend(crate::transport_stream::output_args(video_settings.input_program));
         }
         
+        // Thread cap from the power policy
+        if video_settings.max_threads > 0 {
+            args.push("-threads".to_string());
+            args.push(video_settings.max_threads.to_string());
+        }
+        
+        // Chapters from the source or from the edited chapter file (input 1)
+        args.extend(crate::chapters::map_args(video_settings.preserve_chapters, video_settings.chapter_metadata_file.as_ref().map(|_| 1)));
+        
+        // Container publishing profile (faststart, fragmented MP4, matroska cues, WebM DASH)
+        args.extend(Self::muxer_args(output_file, video_settings));
+        
         // Output file
         args.push("-y".to_string()); // Overwrite output file
         args.push(output_file.to_string());
@@ -174,6 +206,161 @@ impl ComprehensiveCommandBuilder {
         Ok(args)
     }
     
+    /// Two-pass bitrate encode as (first pass, second pass). The first pass only analyses the video
+    /// into `passlog` and throws its output away, so it skips audio and muxer flags.
+    pub fn build_two_pass_commands(
+        input_file: &str,
+        output_file: &str,
+        video_settings: &VideoSettings,
+        audio_settings: Option<&AudioSettings>,
+        passlog: &str,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let mut second = Self::build_video_conversion_command(input_file, output_file, video_settings, audio_settings)?;
+        // Pass options go in front of the trailing "-y <output>"
+        let output_args = second.split_off(second.len() - 2);
+        second.extend(["-pass", "2", "-passlogfile", passlog].map(String::from));
+        second.extend(output_args);
+        
+        let mut first = Self::build_video_conversion_command(input_file, output_file, video_settings, None)?;
+        let muxer_len = Self::muxer_args(output_file, video_settings).len();
+        first.truncate(first.len() - 2 - muxer_len);
+        let null_device = if cfg!(target_os = "windows") { "NUL" } else { "/dev/null" };
+        first.extend(["-pass", "1", "-passlogfile", passlog, "-an", "-f", "null", "-y", null_device].map(String::from));
+        
+        Ok((first, second))
+    }
+    
+    /// Frame rate conversion: a video conversion with the conversion filters of
+    /// `video_settings.fps_conversion` at the end of the -vf chain. The audio is copied, except in
+    /// speed change mode where it is re-encoded with `audio_settings` and retimed with atempo.
+    pub fn build_frame_rate_command(
+        input_file: &str,
+        output_file: &str,
+        video_settings: &VideoSettings,
+        audio_settings: Option<&AudioSettings>,
+        source_rate: Option<crate::frame_rate::FrameRate>,
+        has_audio: bool,
+    ) -> Result<Vec<String>> {
+        let conversion = &video_settings.fps_conversion;
+        let video_filter = conversion.video_filter(source_rate)?;
+        let audio_filter = conversion.audio_filter(source_rate)?.filter(|_| has_audio);
+        
+        // The conversion sets the rate itself, a separate -r would resample it again
+        let mut settings = video_settings.clone();
+        settings.fps = crate::frame_rate::SAME_AS_SOURCE.to_string();
+        let audio_settings = audio_settings.filter(|_| audio_filter.is_some()).map(|audio| AudioSettings {
+            copy_audio: false,
+            ..audio.clone()
+        });
+        let mut args = Self::build_video_conversion_command(input_file, output_file, &settings, audio_settings.as_ref())?;
+        
+        match args.iter().position(|arg| arg == "-vf") {
+            Some(filters) => args[filters + 1] = format!("{},{}", args[filters + 1], video_filter),
+            None => {
+                // In front of the trailing "-y <output>"
+                let at = args.len() - 2;
+                args.splice(at..at, ["-vf".to_string(), video_filter]);
+            }
+        }
+        let at = args.len() - 2;
+        match audio_filter {
+            Some(audio_filter) => args.splice(at..at, ["-af".to_string(), audio_filter]),
+            None => args.splice(at..at, ["-c:a".to_string(), "copy".to_string()]),
+        };
+        Ok(args)
+    }
+    
+    /// Scale to exactly width x height; with a frame fit the aspect ratio is kept and the
+    /// difference is cropped off or padded with black bars
+    pub fn scale_filter(width: u32, height: u32, fit: Option<FrameFit>) -> String {
+        match fit {
+            None => format!("scale={}:{}", width, height),
+            Some(FrameFit::Crop) => format!(
+                "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1", w = width, h = height),
+            Some(FrameFit::Pad) => format!(
+                "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1", w = width, h = height),
+        }
+    }
+    
+    /// Muxer flags for the container of `output_file`, skipping flags the user set in custom args.
+    /// An output that keeps a mismatched extension gets the container forced with -f.
+    pub fn muxer_args(output_file: &str, video_settings: &VideoSettings) -> Vec<String> {
+        let mut args = crate::output_extension::forced_format_args(&video_settings.container_format, video_settings.keep_output_extension);
+        let format = if args.is_empty() {
+            std::path::Path::new(output_file)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or(&video_settings.container_format)
+                .to_lowercase()
+        } else {
+            video_settings.container_format.to_lowercase()
+        };
+        let custom_args: Vec<String> = video_settings.custom_args.split_whitespace().map(|arg| arg.to_string()).collect();
+        args.extend(ComprehensiveCodecRegistry::build_muxer_args(&format, &video_settings.container_options, &custom_args));
+        args
+    }
+    
+    /// Review clip: the range of the single cut segment, scaled down to the preset height with a
+    /// capped H.264 bitrate, AAC audio, optional timecode and watermark text, faststart MP4
+    pub fn build_review_clip_command(
+        input_file: &str,
+        output_file: &str,
+        video_settings: &VideoSettings,
+        has_audio: bool,
+    ) -> Result<Vec<String>> {
+        let review = video_settings.review_clip.as_ref()
+            .ok_or_else(|| anyhow!("No review clip settings specified"))?;
+        let segment = crate::cut_join::normalize_segments(&video_settings.cut_segments, None)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No clip range specified"))?;
+        
+        // Seeking before the input is frame-accurate when re-encoding and skips decoding the lead-in
+        let mut args = vec![
+            "-ss".to_string(), format!("{:.3}", segment.start),
+            "-i".to_string(), input_file.to_string(),
+            "-t".to_string(), format!("{:.3}", segment.end - segment.start),
+        ];
+        
+        let height = review.preset.height();
+        let mut video_filters = vec![format!("scale=-2:'min({},ih)'", height)];
+        if review.burn_timecode {
+            // Timestamps restart at 0 after the seek; the offset shows the source time
+            let timecode = format!("%{{pts:hms:{:.3}}}", segment.start);
+            video_filters.push(format!(
+                "drawtext=text={}:fontsize={}:fontcolor=white:box=1:boxcolor=black@0.5:boxborderw=4:x=10:y=10",
+                Self::quote_filter_value(&timecode),
+                (height / 24).max(12)
+            ));
+        }
+        if !video_settings.watermark_text.trim().is_empty() {
+            video_filters.push(Self::drawtext_filter(video_settings));
+        }
+        video_filters.push("format=yuv420p".to_string());
+        args.push("-vf".to_string());
+        args.push(video_filters.join(","));
+        
+        let max_bitrate = review.preset.max_bitrate_kbps();
+        args.extend([
+            "-c:v".to_string(), "libx264".to_string(),
+            "-preset".to_string(), "veryfast".to_string(),
+            "-crf".to_string(), "23".to_string(),
+            "-maxrate".to_string(), format!("{}k", max_bitrate),
+            "-bufsize".to_string(), format!("{}k", max_bitrate * 2),
+        ]);
+        if has_audio {
+            args.extend(["-c:a", "aac", "-b:a", "128k", "-ac", "2"].map(String::from));
+        } else {
+            args.push("-an".to_string());
+        }
+        args.extend(["-map_metadata", "-1", "-map_chapters", "-1"].map(String::from));
+        
+        args.extend(Self::muxer_args(output_file, video_settings));
+        args.push("-y".to_string());
+        args.push(output_file.to_string());
+        Ok(args)
+    }
+    
     /// Build FFmpeg command for audio conversion
     pub fn build_audio_conversion_command(
         input_file: &str,
@@ -182,9 +369,23 @@ impl ComprehensiveCommandBuilder {
     ) -> Result<Vec<String>> {
         let mut args = Vec::new();
         
+        // Time range (trim) as input options, so the filters below only see the trimmed audio
+        if !audio_settings.start_time.is_empty() && audio_settings.start_time != "00:00:00" {
+            args.push("-ss".to_string());
+            args.push(audio_settings.start_time.clone());
+        }
+        if !audio_settings.end_time.is_empty() {
+            args.push("-to".to_string());
+            args.push(audio_settings.end_time.clone());
+        }
+        
         // Input file
         args.push("-i".to_string());
         args.push(input_file.to_string());
+        if let Some(metadata_file) = &audio_settings.chapter_metadata_file {
+            args.push("-i".to_string());
+            args.push(metadata_file.clone());
+        }
         
         // Audio codec and settings
         if audio_settings.copy_audio {
@@ -205,22 +406,7 @@ impl ComprehensiveCommandBuilder {
         }
         
         // Audio filters
-        let mut audio_filters = Vec::new();
-        
-        // Volume adjustment
-        if audio_settings.volume != 1.0 {
-            audio_filters.push(format!("volume={}", audio_settings.volume));
-        }
-        
-        // Fade in/out
-        if audio_settings.fade_in {
-            audio_filters.push("afade=t=in:d=3".to_string());
-        }
-        if audio_settings.fade_out {
-            audio_filters.push("afade=t=out:d=3".to_string());
-        }
-        
-        // Apply audio filters
+        let audio_filters = Self::audio_filter_chain(audio_settings)?;
         if !audio_filters.is_empty() {
             args.push("-af".to_string());
             args.push(audio_filters.join(","));
@@ -238,7 +424,19 @@ impl ComprehensiveCommandBuilder {
             args.push(audio_settings.channels.clone());
         }
         
-        // Time range (trim)
+        args.extend(crate::chapters::map_args(audio_settings.preserve_chapters, audio_settings.chapter_metadata_file.as_ref().map(|_| 1)));
+        args.extend(crate::output_extension::forced_format_args(&audio_settings.format, audio_settings.keep_output_extension));
+        
+        // Output file
+        args.push("-y".to_string());
+        args.push(output_file.to_string());
+        
+        Ok(args)
+    }
+    
+    /// First loudnorm pass: decode the audio of the (trimmed) input and only measure it
+    pub fn build_loudness_measure_command(input_file: &str, audio_settings: &AudioSettings) -> Vec<String> {
+        let mut args = Vec::new();
         if !audio_settings.start_time.is_empty() && audio_settings.start_time != "00:00:00" {
             args.push("-ss".to_string());
             args.push(audio_settings.start_time.clone());
@@ -247,12 +445,66 @@ impl ComprehensiveCommandBuilder {
             args.push("-to".to_string());
             args.push(audio_settings.end_time.clone());
         }
+        args.push("-i".to_string());
+        args.push(input_file.to_string());
+        let null_device = if cfg!(target_os = "windows") { "NUL" } else { "/dev/null" };
+        args.extend(["-vn", "-sn", "-dn", "-af", &audio_settings.loudness.measure_filter(), "-f", "null", "-y", null_device].map(String::from));
+        args
+    }
+    
+    /// Second loudnorm pass: an audio conversion with loudnorm fed the first pass's measurement
+    /// appended to the filter chain. Without a measurement loudnorm runs in its one-pass mode.
+    pub fn build_loudness_normalize_command(
+        input_file: &str,
+        output_file: &str,
+        audio_settings: &AudioSettings,
+        measured: Option<&crate::loudness::LoudnessMeasurement>,
+    ) -> Result<Vec<String>> {
+        let target = &audio_settings.loudness;
+        let loudnorm = match measured {
+            Some(measured) => target.normalize_filter(measured),
+            None => format!("loudnorm=I={}:TP={}:LRA={}", target.integrated_lufs, target.true_peak_db, target.range_lu),
+        };
+        let mut args = Self::build_audio_conversion_command(input_file, output_file, audio_settings)?;
+        // Extra output options go in front of the trailing "-y <output>"
+        let at = args.len() - 2;
+        match args.iter().position(|arg| arg == "-af") {
+            Some(filters) => {
+                args[filters + 1] = format!("{},{}", args[filters + 1], loudnorm);
+                args.insert(at, "-vn".to_string());
+            }
+            None => {
+                args.splice(at..at, ["-vn".to_string(), "-af".to_string(), loudnorm]);
+            }
+        }
+        Ok(args)
+    }
+    
+    /// Audio filters in processing order: noise reduction first so volume and fades work on the
+    /// cleaned signal, loudness normalization last
+    pub fn audio_filter_chain(audio_settings: &AudioSettings) -> Result<Vec<String>> {
+        let mut filters = Vec::new();
         
-        // Output file
-        args.push("-y".to_string());
-        args.push(output_file.to_string());
+        if audio_settings.denoise.enabled {
+            audio_settings.denoise.validate()?;
+        }
+        if let Some(denoise) = audio_settings.denoise.to_filter() {
+            filters.push(denoise);
+        }
         
-        Ok(args)
+        // Volume adjustment
+        if (audio_settings.volume - 1.0).abs() > 0.01 {
+            filters.push(format!("volume={}", audio_settings.volume));
+        }
+        
+        // Fade in/out; the fade-out is placed from the probed duration
+        filters.extend(audio_settings.fade.filters(audio_settings.fade_duration)?);
+        
+        if audio_settings.normalize {
+            filters.push(format!("loudnorm=I={}", audio_settings.target_lufs));
+        }
+        
+        Ok(filters)
     }
     
     /// Add video codec-specific parameters
@@ -261,8 +513,8 @@ impl ComprehensiveCommandBuilder {
         codec_info: &CodecInfo,
         settings: &VideoSettings
     ) -> Result<()> {
-        // Quality/CRF settings
-        if let Some((min_crf, max_crf)) = codec_info.quality_range {
+        // Quality/CRF settings; two-pass encodes target the bitrate instead
+        if let Some((min_crf, max_crf)) = codec_info.quality_range.filter(|_| !settings.two_pass) {
             if settings.crf >= min_crf as i32 && settings.crf <= max_crf as i32 {
                 args.push("-crf".to_string());
                 args.push(settings.crf.to_string());
@@ -301,9 +553,12 @@ impl ComprehensiveCommandBuilder {
             }
         }
         
-        // Bitrate (if not using CRF)
+        // Alpha channel: pixel format and profile that keep the source's transparency
+        args.extend(crate::alpha_video::encoder_args(codec_info, settings));
+        
+        // Bitrate (if not using CRF); computed bitrates from the size target are rarely in the preset list
         if settings.bitrate != "auto" && !settings.bitrate.is_empty() {
-            if codec_info.supported_bit_rates.contains(&settings.bitrate) {
+            if codec_info.supported_bit_rates.contains(&settings.bitrate) || settings.two_pass {
                 args.push("-b:v".to_string());
                 args.push(settings.bitrate.clone());
             }
@@ -410,4 +665,108 @@ impl ComprehensiveCommandBuilder {
         
         compatible_codecs
     }
-}
\ No newline at end of file
+    
+    /// Quote a filter option value so it survives both the filtergraph and the option parser unchanged
+    pub fn quote_filter_value(value: &str) -> String {
+        let quote = |text: &str| format!("'{}'", text.replace('\'', "'\\''"));
+        quote(&quote(value))
+    }
+    
+    /// drawtext filter for a text watermark; text expansion is off so '%' stays literal
+    pub fn drawtext_filter(video_settings: &VideoSettings) -> String {
+        let position = match video_settings.watermark_position.as_str() {
+            "top-left" => "x=10:y=10".to_string(),
+            "bottom-left" => "x=10:y=h-th-10".to_string(),
+            "bottom-right" => "x=w-tw-10:y=h-th-10".to_string(),
+            "center" => "x=(w-tw)/2:y=(h-th)/2".to_string(),
+            "custom" => format!("x={}:y={}", video_settings.watermark_x, video_settings.watermark_y),
+            _ => "x=w-tw-10:y=10".to_string(),
+        };
+        let opacity = video_settings.watermark_opacity.clamp(0.0, 1.0);
+        let mut filter = format!(
+            "drawtext=text={}:expansion=none:fontsize={}:fontcolor=white@{}:shadowcolor=black@{}:shadowx=2:shadowy=2:{}",
+            Self::quote_filter_value(&video_settings.watermark_text),
+            video_settings.watermark_font_size,
+            opacity,
+            opacity * 0.6,
+            position
+        );
+        if let Some(font) = crate::font_resolver::drawtext_font_option(&video_settings.watermark_font) {
+            filter.push(':');
+            filter.push_str(&font);
+        }
+        filter
+    }
+    
+    /// subtitles filter burning `subtitle_file` in with the configured font, size and colour;
+    /// the style goes into a single force_style so every field reaches libass
+    pub fn subtitles_filter(subtitle_file: &str, video_settings: &VideoSettings) -> String {
+        let mut filter = format!("subtitles=filename={}", crate::font_resolver::filter_path(std::path::Path::new(subtitle_file)));
+        let mut style = Vec::new();
+        if video_settings.subtitle_font_family != "Arial" {
+            let (fonts_dir, family) = crate::font_resolver::subtitle_font(&video_settings.subtitle_font_family);
+            if let Some(dir) = fonts_dir {
+                filter.push_str(&format!(":fontsdir={}", dir));
+            }
+            style.push(format!("FontName={}", family));
+        }
+        if video_settings.subtitle_font_size != 16 {
+            style.push(format!("FontSize={}", video_settings.subtitle_font_size));
+        }
+        // ASS colours are &HBBGGRR&
+        let color_code = match video_settings.subtitle_font_color.as_str() {
+            "black" => "&H000000&",
+            "red" => "&H0000ff&",
+            "blue" => "&Hff0000&",
+            "green" => "&H00ff00&",
+            "yellow" => "&H00ffff&",
+            _ => "&Hffffff&",
+        };
+        if color_code != "&Hffffff&" {
+            style.push(format!("PrimaryColour={}", color_code));
+        }
+        if !style.is_empty() {
+            filter.push_str(&format!(":force_style={}", Self::quote_filter_value(&style.join(","))));
+        }
+        filter
+    }
+    
+    /// filter_complex for AddWatermark: the image (input 1) when `has_image`, then the text if any
+    pub fn watermark_filter_complex(video_settings: &VideoSettings, has_image: bool) -> String {
+        let position = match video_settings.watermark_position.as_str() {
+            "top-left" => "10:10".to_string(),
+            "top-right" => "main_w-overlay_w-10:10".to_string(),
+            "bottom-left" => "10:main_h-overlay_h-10".to_string(),
+            "bottom-right" => "main_w-overlay_w-10:main_h-overlay_h-10".to_string(),
+            "center" => "(main_w-overlay_w)/2:(main_h-overlay_h)/2".to_string(),
+            "custom" => format!("{}:{}", video_settings.watermark_x, video_settings.watermark_y),
+            _ => "main_w-overlay_w-10:10".to_string(),
+        };
+        
+        // Handle scaling and opacity
+        let opacity = video_settings.watermark_opacity;
+        let scale = video_settings.watermark_scale;
+        
+        let mut filter_complex = if !has_image {
+            "[0:v]null".to_string()
+        } else if (opacity - 1.0).abs() > 0.01 {
+            // Apply opacity using alpha channel manipulation
+            format!(
+                "[1:v]scale=iw*{}:ih*{},format=rgba,colorchannelmixer=aa={}[wm];[0:v][wm]overlay={}",
+                scale, scale, opacity, position
+            )
+        } else if (scale - 1.0).abs() > 0.01 {
+            // Only scale, no opacity change
+            format!("[1:v]scale=iw*{}:ih*{}[wm];[0:v][wm]overlay={}", scale, scale, position)
+        } else {
+            // No scaling or opacity change
+            format!("[0:v][1:v]overlay={}", position)
+        };
+        
+        if !video_settings.watermark_text.trim().is_empty() {
+            filter_complex.push(',');
+            filter_complex.push_str(&Self::drawtext_filter(video_settings));
+        }
+        filter_complex
+    }
+}
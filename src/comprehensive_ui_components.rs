@@ -74,6 +74,15 @@ impl ComprehensiveUIComponents {
                     if selected_format.is_empty() || ComprehensiveCodecRegistry::is_compatible("libaom-av1", selected_format) {
                         Self::show_codec_option(ui, current_codec, "libaom-av1", "🚀 AV1 (libaom-av1)", None, translations);
                     }
+                    if selected_format.is_empty() || ComprehensiveCodecRegistry::is_compatible("prores_ks", selected_format) {
+                        Self::show_codec_option(ui, current_codec, "prores_ks", "🎞 ProRes (prores_ks)", None, translations);
+                    }
+                    if selected_format.is_empty() || ComprehensiveCodecRegistry::is_compatible("qtrle", selected_format) {
+                        Self::show_codec_option(ui, current_codec, "qtrle", "🎞 QuickTime Animation (qtrle)", None, translations);
+                    }
+                    if selected_format.is_empty() || ComprehensiveCodecRegistry::is_compatible("ffv1", selected_format) {
+                        Self::show_codec_option(ui, current_codec, "ffv1", "💎 FFV1 (lossless)", None, translations);
+                    }
                     
                     // Legacy codecs for specific formats
                     if selected_format == "wmv" {
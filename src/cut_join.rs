@@ -0,0 +1,243 @@
+use crate::app_state::CutSegment;
+use anyhow::Result;
+
+/// Command plan for a cut-and-join operation.
+/// Each command is the argument list passed to ffmpeg (without the program name).
+#[derive(Debug, Clone)]
+pub struct CutJoinPlan {
+    pub commands: Vec<Vec<String>>,
+    /// Concat demuxer list (path, contents) that must be written before the final command runs.
+    /// Segments and the list go in `temp_dir`, the task's scratch directory, removed with it.
+    pub concat_list: Option<(String, String)>,
+}
+
+/// Sort segments, clamp them to the input duration and reject empty or overlapping ranges
+pub fn normalize_segments(segments: &[CutSegment], duration: Option<f64>) -> Result<Vec<CutSegment>> {
+    if segments.is_empty() {
+        return Err(anyhow::anyhow!("No segments selected"));
+    }
+
+    let mut sorted: Vec<CutSegment> = segments.iter()
+        .map(|s| {
+            let end = match duration {
+                Some(d) if d > 0.0 => s.end.min(d),
+                _ => s.end,
+            };
+            CutSegment { start: s.start.max(0.0), end }
+        })
+        .collect();
+    sorted.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap_or(std::cmp::Ordering::Equal));
+
+    for (i, segment) in sorted.iter().enumerate() {
+        if segment.end <= segment.start {
+            return Err(anyhow::anyhow!("Segment {} is empty ({:.3}s - {:.3}s)", i + 1, segment.start, segment.end));
+        }
+        if i > 0 && segment.start < sorted[i - 1].end {
+            return Err(anyhow::anyhow!("Segment {} overlaps segment {}", i + 1, i));
+        }
+    }
+
+    Ok(sorted)
+}
+
+/// Stream-copy plan: cut each segment with -c copy, then join them with the concat demuxer.
+/// Cuts snap to the nearest preceding keyframe, so boundaries may be off by up to a GOP.
+pub fn plan_stream_copy(input: &str, output: &str, segments: &[CutSegment], temp_dir: &str) -> Result<CutJoinPlan> {
+    let segments = normalize_segments(segments, None)?;
+    let extension = std::path::Path::new(output)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mp4");
+
+    let mut commands = Vec::new();
+    let mut list_contents = String::new();
+    // Captures with timestamp gaps lose PTS on packets near the cut points
+    let program_stream = crate::transport_stream::is_program_stream_path(input);
+
+    for (i, segment) in segments.iter().enumerate() {
+        let segment_file = std::path::Path::new(temp_dir)
+            .join(format!("segment_{:03}.{}", i, extension))
+            .display().to_string();
+        let mut command = Vec::new();
+        if program_stream {
+            command.extend(crate::transport_stream::input_args());
+        }
+        command.extend([
+            "-ss".to_string(), format_seconds(segment.start),
+            "-i".to_string(), input.to_string(),
+            "-t".to_string(), format_seconds(segment.end - segment.start),
+            "-map".to_string(), "0".to_string(),
+            "-c".to_string(), "copy".to_string(),
+            "-avoid_negative_ts".to_string(), "make_zero".to_string(),
+            "-y".to_string(),
+            segment_file.clone(),
+        ]);
+        commands.push(command);
+        // Single quotes inside paths must be escaped for the concat demuxer
+        list_contents.push_str(&format!("file '{}'\n", segment_file.replace('\'', "'\\''")));
+    }
+
+    let list_path = std::path::Path::new(temp_dir)
+        .join("segments.txt")
+        .display().to_string();
+    commands.push(vec![
+        "-f".to_string(), "concat".to_string(),
+        "-safe".to_string(), "0".to_string(),
+        "-i".to_string(), list_path.clone(),
+        "-c".to_string(), "copy".to_string(),
+        "-y".to_string(),
+        output.to_string(),
+    ]);
+
+    Ok(CutJoinPlan {
+        commands,
+        concat_list: Some((list_path, list_contents)),
+    })
+}
+
+/// Frame-accurate plan: a single re-encode that keeps only the selected ranges via select/aselect
+pub fn plan_reencode(input: &str, output: &str, segments: &[CutSegment], video_codec: &str, audio_codec: &str, has_audio: bool) -> Result<CutJoinPlan> {
+    let segments = normalize_segments(segments, None)?;
+    let expression = segments.iter()
+        .map(|s| format!("between(t,{},{})", format_seconds(s.start), format_seconds(s.end)))
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let mut args = Vec::new();
+    if crate::transport_stream::is_program_stream_path(input) {
+        args.extend(crate::transport_stream::input_args());
+    }
+    args.extend([
+        "-i".to_string(), input.to_string(),
+        "-vf".to_string(), format!("select='{}',setpts=N/FRAME_RATE/TB", expression),
+    ]);
+    if has_audio {
+        args.push("-af".to_string());
+        args.push(format!("aselect='{}',asetpts=N/SR/TB", expression));
+        args.push("-c:a".to_string());
+        args.push(audio_codec.to_string());
+    } else {
+        args.push("-an".to_string());
+    }
+    args.push("-c:v".to_string());
+    args.push(video_codec.to_string());
+    args.push("-y".to_string());
+    args.push(output.to_string());
+
+    Ok(CutJoinPlan {
+        commands: vec![args],
+        concat_list: None,
+    })
+}
+
+/// Total duration kept by the segments
+pub fn kept_duration(segments: &[CutSegment]) -> f64 {
+    segments.iter().map(|s| (s.end - s.start).max(0.0)).sum()
+}
+
+fn format_seconds(seconds: f64) -> String {
+    format!("{:.3}", seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64) -> CutSegment {
+        CutSegment { start, end }
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn error(segments: &[CutSegment], duration: Option<f64>) -> String {
+        normalize_segments(segments, duration).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn segments_are_sorted_and_clamped() {
+        let segments = [segment(30.0, 95.0), segment(-2.0, 10.0), segment(10.0, 20.0)];
+        assert_eq!(
+            normalize_segments(&segments, Some(60.0)).unwrap(),
+            vec![segment(0.0, 10.0), segment(10.0, 20.0), segment(30.0, 60.0)]
+        );
+        // An unknown or zero duration leaves the ends alone
+        assert_eq!(normalize_segments(&segments, None).unwrap()[2], segment(30.0, 95.0));
+        assert_eq!(normalize_segments(&segments, Some(0.0)).unwrap()[2], segment(30.0, 95.0));
+    }
+
+    #[test]
+    fn empty_and_overlapping_segments_are_rejected() {
+        assert_eq!(error(&[], None), "No segments selected");
+        assert_eq!(error(&[segment(5.0, 5.0)], None), "Segment 1 is empty (5.000s - 5.000s)");
+        assert_eq!(error(&[segment(0.0, 4.0), segment(8.0, 6.0)], None), "Segment 2 is empty (8.000s - 6.000s)");
+        // Clamping a segment past the end of the input leaves nothing of it
+        assert_eq!(error(&[segment(0.0, 4.0), segment(70.0, 80.0)], Some(60.0)), "Segment 2 is empty (70.000s - 60.000s)");
+        // Numbers follow the sorted order
+        assert_eq!(error(&[segment(15.0, 30.0), segment(0.0, 20.0)], None), "Segment 2 overlaps segment 1");
+        assert_eq!(kept_duration(&[segment(0.0, 10.0), segment(30.0, 32.5)]), 12.5);
+    }
+
+    #[test]
+    fn stream_copy_cuts_each_segment_and_joins_the_list() {
+        let plan = plan_stream_copy("/media/in.mkv", "/out/cut.mkv", &[segment(30.0, 42.25), segment(0.0, 10.0)], "/tmp/job").unwrap();
+        assert_eq!(plan.commands, vec![
+            strings(&["-ss", "0.000", "-i", "/media/in.mkv", "-t", "10.000", "-map", "0", "-c", "copy",
+                "-avoid_negative_ts", "make_zero", "-y", "/tmp/job/segment_000.mkv"]),
+            strings(&["-ss", "30.000", "-i", "/media/in.mkv", "-t", "12.250", "-map", "0", "-c", "copy",
+                "-avoid_negative_ts", "make_zero", "-y", "/tmp/job/segment_001.mkv"]),
+            strings(&["-f", "concat", "-safe", "0", "-i", "/tmp/job/segments.txt", "-c", "copy", "-y", "/out/cut.mkv"]),
+        ]);
+        assert_eq!(plan.concat_list, Some((
+            "/tmp/job/segments.txt".to_string(),
+            "file '/tmp/job/segment_000.mkv'\nfile '/tmp/job/segment_001.mkv'\n".to_string(),
+        )));
+        assert!(plan_stream_copy("/media/in.mkv", "/out/cut.mkv", &[], "/tmp/job").is_err());
+    }
+
+    #[test]
+    fn concat_list_escapes_single_quotes_in_paths() {
+        let plan = plan_stream_copy("/media/in.mp4", "/out/cut", &[segment(0.0, 1.0)], "/tmp/Bob's job").unwrap();
+        assert_eq!(plan.concat_list.unwrap().1, "file '/tmp/Bob'\\''s job/segment_000.mp4'\n");
+    }
+
+    #[test]
+    fn program_streams_regenerate_timestamps_on_input() {
+        let plan = plan_stream_copy("/media/capture.M2TS", "/out/cut.ts", &[segment(0.0, 1.0)], "/tmp/job").unwrap();
+        assert_eq!(plan.commands[0][..4], strings(&["-fflags", "+genpts", "-ss", "0.000"]));
+        // The concat step reads the already fixed segments
+        assert_eq!(plan.commands[1][0], "-f");
+
+        let plan = plan_reencode("/media/dvd.vob", "/out/cut.mp4", &[segment(0.0, 1.0)], "libx264", "aac", true).unwrap();
+        assert_eq!(plan.commands[0][..4], strings(&["-fflags", "+genpts", "-i", "/media/dvd.vob"]));
+        let plan = plan_reencode("/media/in.mp4", "/out/cut.mp4", &[segment(0.0, 1.0)], "libx264", "aac", true).unwrap();
+        assert_eq!(plan.commands[0][0], "-i");
+    }
+
+    #[test]
+    fn reencode_selects_the_kept_ranges_in_one_pass() {
+        let plan = plan_reencode("/media/in.mp4", "/out/cut.mp4", &[segment(30.0, 42.25), segment(0.0, 10.0)], "libx264", "aac", true).unwrap();
+        assert_eq!(plan.concat_list, None);
+        assert_eq!(plan.commands, vec![strings(&[
+            "-i", "/media/in.mp4",
+            "-vf", "select='between(t,0.000,10.000)+between(t,30.000,42.250)',setpts=N/FRAME_RATE/TB",
+            "-af", "aselect='between(t,0.000,10.000)+between(t,30.000,42.250)',asetpts=N/SR/TB",
+            "-c:a", "aac",
+            "-c:v", "libx264",
+            "-y", "/out/cut.mp4",
+        ])]);
+    }
+
+    #[test]
+    fn reencode_without_audio_drops_the_audio_options() {
+        let plan = plan_reencode("/media/in.mp4", "/out/cut.mp4", &[segment(1.5, 2.0)], "libx265", "aac", false).unwrap();
+        assert_eq!(plan.commands, vec![strings(&[
+            "-i", "/media/in.mp4",
+            "-vf", "select='between(t,1.500,2.000)',setpts=N/FRAME_RATE/TB",
+            "-an",
+            "-c:v", "libx265",
+            "-y", "/out/cut.mp4",
+        ])]);
+    }
+}
@@ -0,0 +1,616 @@
+use std::path::Path;
+
+use anyhow::bail;
+use eframe::egui;
+
+use crate::app_state::VideoSettings;
+use crate::language::Translations;
+
+/// Positions a CSV "position" column may name
+const POSITIONS: &[&str] = &["top-left", "top-right", "bottom-left", "bottom-right", "center", "custom"];
+/// Rows shown in the preview table; validation still covers every row
+const PREVIEW_ROWS: usize = 500;
+
+/// One data line of a CSV file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRecord {
+    /// Line the record starts on, counting the header as row 1
+    pub row: usize,
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub records: Vec<CsvRecord>,
+}
+
+impl CsvTable {
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.headers.iter().position(|header| header.eq_ignore_ascii_case(name))
+    }
+
+    /// Field of `record` in column `name`; columns a short record lacks read as empty
+    pub fn value<'a>(&self, record: &'a CsvRecord, name: &str) -> Option<&'a str> {
+        let index = self.column_index(name)?;
+        Some(record.fields.get(index).map(|field| field.as_str()).unwrap_or(""))
+    }
+}
+
+/// Semicolon when the header row has more semicolons than commas outside quotes, as spreadsheets
+/// using a decimal comma export; otherwise comma
+pub fn detect_delimiter(text: &str) -> char {
+    let mut in_quotes = false;
+    let (mut commas, mut semicolons) = (0, 0);
+    for c in text.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => commas += 1,
+            ';' if !in_quotes => semicolons += 1,
+            '\n' if !in_quotes => break,
+            _ => {}
+        }
+    }
+    if semicolons > commas { ';' } else { ',' }
+}
+
+/// RFC 4180 CSV with a header row: quoted fields with "" escapes and line breaks, CRLF or LF line
+/// ends, an optional UTF-8 BOM and a comma or semicolon delimiter. Blank lines are skipped.
+pub fn parse_csv(text: &str) -> anyhow::Result<CsvTable> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let delimiter = detect_delimiter(text);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_row = 1;
+    let mut chars = text.chars().peekable();
+    let mut end_record = |fields: &mut Vec<String>, field: &mut String, row: usize| {
+        fields.push(std::mem::take(field));
+        // A blank line parses as one empty field
+        if fields.len() > 1 || !fields[0].trim().is_empty() {
+            records.push(CsvRecord { row, fields: std::mem::take(fields) });
+        }
+        fields.clear();
+    };
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    line += 1;
+                    field.push('\n');
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '"' => bail!("Row {}: quote inside an unquoted field; quote the whole field and double the inner quotes", record_row),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                end_record(&mut fields, &mut field, record_row);
+                line += 1;
+                record_row = line;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("Row {}: quoted field is never closed", record_row);
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        end_record(&mut fields, &mut field, record_row);
+    }
+
+    let mut records = records.into_iter();
+    let Some(header) = records.next() else {
+        bail!("The CSV file is empty");
+    };
+    let headers: Vec<String> = header.fields.iter().map(|name| name.trim().to_string()).collect();
+    for (index, name) in headers.iter().enumerate() {
+        if name.is_empty() {
+            bail!("Row {}: column {} has no name", header.row, index + 1);
+        }
+        if headers[..index].iter().any(|other| other.eq_ignore_ascii_case(name)) {
+            bail!("Row {}: column \"{}\" appears twice", header.row, name);
+        }
+    }
+    let records: Vec<CsvRecord> = records.collect();
+    for record in &records {
+        if record.fields.iter().skip(headers.len()).any(|extra| !extra.trim().is_empty()) {
+            bail!("Row {}: {} fields but the header has {} columns", record.row, record.fields.len(), headers.len());
+        }
+    }
+    Ok(CsvTable { headers, records })
+}
+
+/// Names of the {column} variables in a template; "{{" and "}}" are literal braces
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut variables = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if rest[start..].starts_with("{{") {
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        variables.push(rest[start + 1..start + end].trim().to_string());
+        rest = &rest[start + end + 1..];
+    }
+    variables
+}
+
+/// Replace every {column} in `template` with the record's field
+pub fn substitute(template: &str, table: &CsvTable, record: &CsvRecord) -> Result<String, RowIssue> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            result.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if let Some(after) = tail.strip_prefix('}') {
+            result.push('}');
+            rest = after;
+            continue;
+        }
+        let Some(end) = tail.find('}') else {
+            return Err(RowIssue::UnclosedVariable);
+        };
+        let name = tail[1..end].trim();
+        match table.value(record, name) {
+            Some(value) => result.push_str(value.trim()),
+            None => return Err(RowIssue::UnknownVariable(name.to_string())),
+        }
+        rest = &tail[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Why a row cannot be queued
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowIssue {
+    /// A template names a column the CSV does not have
+    UnknownVariable(String),
+    UnclosedVariable,
+    /// The input column or a column a template uses is empty
+    EmptyField(String),
+    MissingInput(String),
+    MissingWatermark(String),
+    InvalidPosition(String),
+    InvalidNumber(String),
+}
+
+impl RowIssue {
+    pub fn describe(&self, translations: &Translations) -> String {
+        match self {
+            RowIssue::UnknownVariable(name) => format!("{}: {{{}}}", translations.data_batch_unknown_variable(), name),
+            RowIssue::UnclosedVariable => translations.data_batch_unclosed_variable().to_string(),
+            RowIssue::EmptyField(name) => format!("{}: {}", translations.data_batch_empty_field(), name),
+            RowIssue::MissingInput(path) => format!("{}: {}", translations.data_batch_missing_input(), path),
+            RowIssue::MissingWatermark(path) => format!("{}: {}", translations.data_batch_missing_watermark(), path),
+            RowIssue::InvalidPosition(value) => format!("{}: {}", translations.data_batch_invalid_position(), value),
+            RowIssue::InvalidNumber(name) => format!("{}: {}", translations.data_batch_invalid_number(), name),
+        }
+    }
+}
+
+/// A CSV row with the templates filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedRow {
+    pub row: usize,
+    pub input: String,
+    pub text: String,
+    pub watermark_file: String,
+    pub position: String,
+    pub x: i32,
+    pub y: i32,
+    /// From an "output" column; otherwise the output is named like any other task
+    pub output: Option<String>,
+    pub issues: Vec<RowIssue>,
+}
+
+impl ResolvedRow {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// `base` with this row's text, image and position
+    pub fn apply(&self, base: &VideoSettings) -> VideoSettings {
+        let mut settings = base.clone();
+        settings.watermark_text = self.text.clone();
+        settings.watermark_file = self.watermark_file.clone();
+        settings.watermark_position = self.position.clone();
+        settings.watermark_x = self.x;
+        settings.watermark_y = self.y;
+        settings
+    }
+}
+
+/// Fill the watermark text and image templates of `settings` from every record and validate the result.
+/// Optional "position", "x", "y" and "output" columns override the settings per row.
+pub fn resolve_rows(table: &CsvTable, input_column: &str, settings: &VideoSettings) -> Vec<ResolvedRow> {
+    table.records.iter().map(|record| resolve_row(table, record, input_column, settings)).collect()
+}
+
+fn resolve_row(table: &CsvTable, record: &CsvRecord, input_column: &str, settings: &VideoSettings) -> ResolvedRow {
+    let mut issues = Vec::new();
+    let input = table.value(record, input_column).unwrap_or("").trim().to_string();
+    if input.is_empty() {
+        issues.push(RowIssue::EmptyField(input_column.to_string()));
+    } else if !Path::new(&input).is_file() {
+        issues.push(RowIssue::MissingInput(input.clone()));
+    }
+
+    let fill = |template: &str, issues: &mut Vec<RowIssue>| -> String {
+        for name in template_variables(template) {
+            if table.value(record, &name).is_some_and(|value| value.trim().is_empty()) && !issues.contains(&RowIssue::EmptyField(name.clone())) {
+                issues.push(RowIssue::EmptyField(name));
+            }
+        }
+        substitute(template, table, record).unwrap_or_else(|issue| {
+            issues.push(issue);
+            String::new()
+        })
+    };
+    let text = fill(&settings.watermark_text, &mut issues);
+    let watermark_file = fill(&settings.watermark_file, &mut issues);
+    if !watermark_file.is_empty() && !Path::new(&watermark_file).is_file() {
+        issues.push(RowIssue::MissingWatermark(watermark_file.clone()));
+    }
+
+    let optional = |name: &str| table.value(record, name).map(str::trim).filter(|value| !value.is_empty());
+    let number = |name: &str, default: i32, issues: &mut Vec<RowIssue>| match optional(name) {
+        Some(value) => value.parse::<i32>().unwrap_or_else(|_| {
+            issues.push(RowIssue::InvalidNumber(name.to_string()));
+            default
+        }),
+        None => default,
+    };
+    let x = number("x", settings.watermark_x, &mut issues);
+    let y = number("y", settings.watermark_y, &mut issues);
+    let position = match optional("position") {
+        Some(value) => {
+            let value = value.to_lowercase();
+            if !POSITIONS.contains(&value.as_str()) {
+                issues.push(RowIssue::InvalidPosition(value.clone()));
+            }
+            value
+        }
+        // Coordinates without a position column place the watermark at them
+        None if optional("x").is_some() || optional("y").is_some() => "custom".to_string(),
+        None => settings.watermark_position.clone(),
+    };
+
+    ResolvedRow {
+        row: record.row,
+        input,
+        text,
+        watermark_file,
+        position,
+        x,
+        y,
+        output: optional("output").map(str::to_string),
+        issues,
+    }
+}
+
+/// Column most likely to hold the input paths
+fn guess_input_column(headers: &[String]) -> String {
+    ["input", "file", "path", "source", "video"].iter()
+        .find_map(|candidate| headers.iter().find(|header| header.to_lowercase().contains(candidate)))
+        .or_else(|| headers.first())
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub enum DataBatchAction {
+    None,
+    Close,
+    /// Queue one task per row
+    Queue(Vec<ResolvedRow>),
+}
+
+/// Window for picking a CSV, previewing the substituted rows and queueing them
+#[derive(Default)]
+pub struct DataBatchDialog {
+    csv_path: String,
+    table: Option<CsvTable>,
+    error: Option<String>,
+    input_column: String,
+    rows: Vec<ResolvedRow>,
+    /// Input column and templates the rows were resolved with
+    resolved_for: Option<(String, String, String)>,
+}
+
+impl DataBatchDialog {
+    pub fn load(&mut self, path: &str) {
+        self.csv_path = path.to_string();
+        self.table = None;
+        self.rows.clear();
+        self.resolved_for = None;
+        let result = std::fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| parse_csv(&String::from_utf8_lossy(&bytes)));
+        match result {
+            Ok(table) => {
+                log_info!("Loaded {} CSV rows from {}", table.records.len(), path);
+                self.input_column = guess_input_column(&table.headers);
+                self.table = Some(table);
+                self.error = None;
+            }
+            Err(e) => {
+                log_warn!("Failed to read CSV {}: {}", path, e);
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Re-resolve when the input column or a template changed since the last frame
+    fn refresh(&mut self, settings: &VideoSettings) {
+        let Some(table) = &self.table else {
+            return;
+        };
+        let key = (self.input_column.clone(), settings.watermark_text.clone(), settings.watermark_file.clone());
+        if self.resolved_for.as_ref() != Some(&key) {
+            self.rows = resolve_rows(table, &self.input_column, settings);
+            self.resolved_for = Some(key);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, settings: &VideoSettings, translations: &Translations) -> DataBatchAction {
+        self.refresh(settings);
+        let mut open = true;
+        let mut action = DataBatchAction::None;
+        egui::Window::new(translations.data_batch_title())
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                ui.label(translations.data_batch_hint());
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.button(translations.data_batch_choose_csv()).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv", "txt"]).pick_file() {
+                            self.load(&path.display().to_string());
+                        }
+                    }
+                    ui.label(&self.csv_path);
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                let Some(table) = &self.table else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.label(translations.data_batch_input_column());
+                    egui::ComboBox::from_id_salt("data_batch_input_column")
+                        .selected_text(&self.input_column)
+                        .show_ui(ui, |ui| {
+                            for header in &table.headers {
+                                ui.selectable_value(&mut self.input_column, header.clone(), header);
+                            }
+                        });
+                });
+                ui.weak(format!("{}: {}", translations.data_batch_variables(),
+                    table.headers.iter().map(|header| format!("{{{}}}", header)).collect::<Vec<_>>().join(" ")));
+                ui.add_space(5.0);
+
+                egui::ScrollArea::both().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("data_batch_preview").striped(true).num_columns(6).show(ui, |ui| {
+                        for heading in [
+                            translations.data_batch_row(),
+                            translations.data_batch_input(),
+                            translations.data_batch_text(),
+                            translations.data_batch_watermark(),
+                            translations.data_batch_position(),
+                            translations.data_batch_status(),
+                        ] {
+                            ui.strong(heading);
+                        }
+                        ui.end_row();
+                        for row in self.rows.iter().take(PREVIEW_ROWS) {
+                            ui.label(row.row.to_string());
+                            ui.label(Path::new(&row.input).file_name().and_then(|name| name.to_str()).unwrap_or(&row.input))
+                                .on_hover_text(&row.input);
+                            ui.label(&row.text);
+                            ui.label(Path::new(&row.watermark_file).file_name().and_then(|name| name.to_str()).unwrap_or(""))
+                                .on_hover_text(&row.watermark_file);
+                            if row.position == "custom" {
+                                ui.label(format!("{}, {}", row.x, row.y));
+                            } else {
+                                ui.label(&row.position);
+                            }
+                            if row.is_valid() {
+                                ui.colored_label(egui::Color32::GREEN, translations.data_batch_ok());
+                            } else {
+                                let issues: Vec<String> = row.issues.iter().map(|issue| issue.describe(translations)).collect();
+                                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), issues.join("; "));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if self.rows.len() > PREVIEW_ROWS {
+                    ui.weak(translations.data_batch_more_rows(self.rows.len() - PREVIEW_ROWS));
+                }
+
+                ui.add_space(5.0);
+                let valid = self.rows.iter().filter(|row| row.is_valid()).count();
+                let invalid = self.rows.len() - valid;
+                if invalid > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), translations.data_batch_skipped(invalid));
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(valid > 0, egui::Button::new(translations.data_batch_queue(valid))).clicked() {
+                        action = DataBatchAction::Queue(self.rows.iter().filter(|row| row.is_valid()).cloned().collect());
+                    }
+                    if ui.button(translations.cancel()).clicked() {
+                        action = DataBatchAction::Close;
+                    }
+                });
+            });
+        if !open {
+            action = DataBatchAction::Close;
+        }
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(list: &[&str]) -> Vec<String> {
+        list.iter().map(|field| field.to_string()).collect()
+    }
+
+    fn error(text: &str) -> String {
+        parse_csv(text).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_a_messy_spreadsheet_export() {
+        let text = "\u{feff}input;text;x\r\n\
+            /a.mp4;\"Hello; world\";10\r\n\
+            \r\n\
+            /b.mp4;\"two\nlines \"\"quoted\"\"\";\r\n\
+            /c.mp4; plain ;5";
+        let table = parse_csv(text).unwrap();
+        assert_eq!(table.headers, fields(&["input", "text", "x"]));
+        assert_eq!(table.records, vec![
+            CsvRecord { row: 2, fields: fields(&["/a.mp4", "Hello; world", "10"]) },
+            CsvRecord { row: 4, fields: fields(&["/b.mp4", "two\nlines \"quoted\"", ""]) },
+            CsvRecord { row: 6, fields: fields(&["/c.mp4", " plain ", "5"]) },
+        ]);
+    }
+
+    #[test]
+    fn delimiter_ignores_separators_inside_quotes() {
+        assert_eq!(detect_delimiter("a;\"b,c,d\";e\n1,2,3,4"), ';');
+        assert_eq!(detect_delimiter("a,\"b;c;d\",e"), ',');
+        assert_eq!(detect_delimiter("single"), ',');
+    }
+
+    #[test]
+    fn short_rows_read_as_empty_and_trailing_empty_fields_are_allowed() {
+        let table = parse_csv("input,text,x\n/a.mp4\n/b.mp4,hi,1,,\n").unwrap();
+        assert_eq!(table.value(&table.records[0], "TEXT"), Some(""));
+        assert_eq!(table.value(&table.records[1], " x "), Some("1"));
+        assert_eq!(table.value(&table.records[1], "missing"), None);
+    }
+
+    #[test]
+    fn errors_name_the_row_they_start_on() {
+        assert_eq!(error(""), "The CSV file is empty");
+        assert_eq!(error("\u{feff}\r\n\n"), "The CSV file is empty");
+        assert_eq!(error("a,,b\n"), "Row 1: column 2 has no name");
+        assert_eq!(error("name,Name\n"), "Row 1: column \"Name\" appears twice");
+        assert_eq!(error("a,b\n1,2,3\n"), "Row 2: 3 fields but the header has 2 columns");
+        assert_eq!(error("a,b\n\"x\ny\",1\n1,2,3\n"), "Row 4: 3 fields but the header has 2 columns");
+        assert_eq!(error("a;b\n1;\"open\n2;3\n"), "Row 2: quoted field is never closed");
+        assert!(error("a,b\n\n1,x\"y\n").starts_with("Row 3: quote inside an unquoted field"));
+    }
+
+    #[test]
+    fn substitute_fills_variables_and_keeps_escaped_braces() {
+        let table = parse_csv("Name,city\n  Ana , \"Lisbon, PT\"\n").unwrap();
+        let record = &table.records[0];
+        assert_eq!(substitute("{name} from { CITY } {{x}} }", &table, record), Ok("Ana from Lisbon, PT {x} }".to_string()));
+        assert_eq!(substitute("no variables", &table, record), Ok("no variables".to_string()));
+        assert_eq!(substitute("{country}", &table, record), Err(RowIssue::UnknownVariable("country".to_string())));
+        assert_eq!(substitute("{name", &table, record), Err(RowIssue::UnclosedVariable));
+        assert_eq!(template_variables("{name} {{skip}} { city }"), fields(&["name", "city"]));
+    }
+
+    #[test]
+    fn resolve_rows_reports_every_issue_with_its_row() {
+        let dir = crate::test_support::temp_dir("data_batch");
+        let input = dir.join("clip.mp4").display().to_string();
+        let logo = dir.join("logo.png").display().to_string();
+        std::fs::write(&input, b"video").unwrap();
+        std::fs::write(&logo, b"png").unwrap();
+        let missing_input = dir.join("gone.mp4").display().to_string();
+        let missing_logo = dir.join("gone.png").display().to_string();
+
+        let text = format!(
+            "input;title;logo;position;x;y;output\n\
+             \"{input}\";\"Hi; there\";\"{logo}\";Bottom-Left;;;out1.mp4\n\
+             \"{missing_input}\";;\"{logo}\";;abc;;\n\
+             ;T3;\"{missing_logo}\";middle;;;\n\
+             \"{input}\";T4;\"{logo}\";;20;30;\n"
+        );
+        let table = parse_csv(&text).unwrap();
+        let mut settings = VideoSettings {
+            watermark_text: "{title}".to_string(),
+            watermark_file: "{logo}".to_string(),
+            watermark_position: "top-right".to_string(),
+            watermark_x: 7,
+            watermark_y: 8,
+            ..Default::default()
+        };
+        let rows = resolve_rows(&table, "input", &settings);
+
+        assert_eq!(rows.iter().map(|row| row.row).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert!(rows[0].is_valid());
+        assert_eq!(rows[0].text, "Hi; there");
+        assert_eq!(rows[0].watermark_file, logo);
+        assert_eq!(rows[0].position, "bottom-left");
+        assert_eq!((rows[0].x, rows[0].y), (7, 8));
+        assert_eq!(rows[0].output.as_deref(), Some("out1.mp4"));
+
+        assert_eq!(rows[1].issues, vec![
+            RowIssue::MissingInput(missing_input),
+            RowIssue::EmptyField("title".to_string()),
+            RowIssue::InvalidNumber("x".to_string()),
+        ]);
+        assert_eq!((rows[1].x, rows[1].position.as_str()), (7, "custom"));
+        assert_eq!(rows[1].output, None);
+
+        assert_eq!(rows[2].issues, vec![
+            RowIssue::EmptyField("input".to_string()),
+            RowIssue::MissingWatermark(missing_logo),
+            RowIssue::InvalidPosition("middle".to_string()),
+        ]);
+
+        assert!(rows[3].is_valid());
+        assert_eq!((rows[3].position.as_str(), rows[3].x, rows[3].y), ("custom", 20, 30));
+        let applied = rows[3].apply(&settings);
+        assert_eq!((applied.watermark_text.as_str(), applied.watermark_x), ("T4", 20));
+
+        settings.watermark_text = "{title} {nope}".to_string();
+        let rows = resolve_rows(&table, "input", &settings);
+        assert!(rows[0].issues.contains(&RowIssue::UnknownVariable("nope".to_string())));
+        assert_eq!(rows[0].text, "");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn input_column_guess_prefers_path_like_names() {
+        assert_eq!(guess_input_column(&fields(&["title", "Video File"])), "Video File");
+        assert_eq!(guess_input_column(&fields(&["title", "source"])), "source");
+        assert_eq!(guess_input_column(&fields(&["title", "caption"])), "title");
+        assert_eq!(guess_input_column(&[]), "");
+    }
+}
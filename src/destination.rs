@@ -0,0 +1,154 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+
+/// How often the mount table is re-read, so newly attached drives are picked up
+const MOUNT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    static ref MOUNT_CACHE: Mutex<Option<(Instant, Vec<String>)>> = Mutex::new(None);
+}
+
+/// Volume (drive root, UNC share or mount point) that an output path is written to
+pub fn destination_volume(path: &str) -> String {
+    let mounts = cached_mount_points();
+    volume_for_path(path, &mounts)
+}
+
+/// Resolve the volume of `path` against a list of known mount points.
+/// Windows drive letters and UNC shares are detected from the path itself.
+pub fn volume_for_path(path: &str, mount_points: &[String]) -> String {
+    let normalized = path.replace('/', "\\");
+
+    // UNC path: \\server\share\...
+    if let Some(rest) = normalized.strip_prefix("\\\\") {
+        let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+        if let (Some(server), Some(share)) = (parts.next(), parts.next()) {
+            return format!("\\\\{}\\{}", server.to_lowercase(), share.to_lowercase());
+        }
+    }
+
+    // Drive letter: C:\...
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return format!("{}:", (bytes[0] as char).to_ascii_uppercase());
+    }
+
+    // Unix: longest mount point that prefixes the path
+    if path.starts_with('/') {
+        return mount_points.iter()
+            .filter(|mount| {
+                mount.as_str() == "/" || path == mount.as_str() || path.starts_with(&format!("{}/", mount.trim_end_matches('/')))
+            })
+            .max_by_key(|mount| mount.len())
+            .cloned()
+            .unwrap_or_else(|| "/".to_string());
+    }
+
+    // Relative paths are written to the volume of the working directory
+    match std::env::current_dir() {
+        Ok(dir) if dir.is_absolute() && !path.is_empty() => volume_for_path(&dir.display().to_string(), mount_points),
+        _ => String::new(),
+    }
+}
+
+fn cached_mount_points() -> Vec<String> {
+    let mut cache = match MOUNT_CACHE.lock() {
+        Ok(cache) => cache,
+        Err(_) => return read_mount_points(),
+    };
+    if let Some((read_at, mounts)) = cache.as_ref() {
+        if read_at.elapsed() < MOUNT_REFRESH_INTERVAL {
+            return mounts.clone();
+        }
+    }
+    let mounts = read_mount_points();
+    *cache = Some((Instant::now(), mounts.clone()));
+    mounts
+}
+
+#[cfg(target_os = "linux")]
+fn read_mount_points() -> Vec<String> {
+    // /proc/mounts escapes spaces as \040
+    std::fs::read_to_string("/proc/mounts")
+        .map(|contents| contents.lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .map(|mount| mount.replace("\\040", " "))
+            .collect())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn read_mount_points() -> Vec<String> {
+    let mut mounts = vec!["/".to_string()];
+    if let Ok(entries) = std::fs::read_dir("/Volumes") {
+        mounts.extend(entries.flatten().map(|entry| entry.path().display().to_string()));
+    }
+    mounts
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_mount_points() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mounts(list: &[&str]) -> Vec<String> {
+        list.iter().map(|mount| mount.to_string()).collect()
+    }
+
+    #[test]
+    fn windows_drive_letters() {
+        assert_eq!(volume_for_path("C:\\Videos\\out.mp4", &[]), "C:");
+        assert_eq!(volume_for_path("c:\\videos\\out.mp4", &[]), "C:");
+        assert_eq!(volume_for_path("D:/renders/out.mkv", &[]), "D:");
+        assert_eq!(volume_for_path("e:out.mp4", &[]), "E:");
+        // The Unix mount table plays no part
+        assert_eq!(volume_for_path("C:\\out.mp4", &mounts(&["/", "/mnt/c"])), "C:");
+        assert_ne!(volume_for_path("C:\\a.mp4", &[]), volume_for_path("D:\\a.mp4", &[]));
+    }
+
+    #[test]
+    fn unc_shares() {
+        assert_eq!(volume_for_path("\\\\NAS\\Media\\out.mp4", &[]), "\\\\nas\\media");
+        assert_eq!(volume_for_path("\\\\nas\\media\\sub\\dir\\out.mp4", &[]), "\\\\nas\\media");
+        assert_eq!(volume_for_path("//nas/media/out.mp4", &[]), "\\\\nas\\media");
+        assert_eq!(volume_for_path("\\\\nas\\\\media\\out.mp4", &[]), "\\\\nas\\media");
+        // Different shares on one server are separate destinations
+        assert_ne!(volume_for_path("\\\\nas\\media\\a.mp4", &[]), volume_for_path("\\\\nas\\backup\\a.mp4", &[]));
+    }
+
+    #[test]
+    fn unix_mount_points() {
+        let mounts = mounts(&["/", "/home", "/mnt/data", "/mnt/data/archive", "/media/usb stick/", "/mnt/data2"]);
+        assert_eq!(volume_for_path("/tmp/out.mp4", &mounts), "/");
+        assert_eq!(volume_for_path("/home/user/out.mp4", &mounts), "/home");
+        assert_eq!(volume_for_path("/mnt/data/out.mp4", &mounts), "/mnt/data");
+        // The longest matching mount wins
+        assert_eq!(volume_for_path("/mnt/data/archive/2024/out.mp4", &mounts), "/mnt/data/archive");
+        // A mount is matched on whole path components only
+        assert_eq!(volume_for_path("/mnt/data2/out.mp4", &mounts), "/mnt/data2");
+        assert_eq!(volume_for_path("/mnt/database/out.mp4", &mounts), "/");
+        assert_eq!(volume_for_path("/homework/out.mp4", &mounts), "/");
+        // Mount points with a trailing slash or spaces, and the mount point itself
+        assert_eq!(volume_for_path("/media/usb stick/out.mp4", &mounts), "/media/usb stick/");
+        assert_eq!(volume_for_path("/mnt/data", &mounts), "/mnt/data");
+    }
+
+    #[test]
+    fn unknown_mount_tables_fall_back_to_root() {
+        assert_eq!(volume_for_path("/mnt/data/out.mp4", &[]), "/");
+        assert_eq!(volume_for_path("/mnt/data/out.mp4", &mounts(&["/mnt/other"])), "/");
+    }
+
+    #[test]
+    fn relative_paths_use_the_working_directory() {
+        let mounts = mounts(&["/"]);
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(volume_for_path("out.mp4", &mounts), volume_for_path(&cwd.display().to_string(), &mounts));
+        assert_eq!(volume_for_path("", &mounts), "");
+    }
+}
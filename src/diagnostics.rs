@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+use crate::app_state::{OperationType, ProcessingTask};
+use crate::environment::EnvironmentSnapshot;
+use crate::hardware_detector::HardwareInfo;
+
+/// One task of the queue as it appears in a diagnostic bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRecord {
+    pub id: usize,
+    pub operation: OperationType,
+    pub status: String,
+    pub progress: f32,
+    pub error_message: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub environment: Option<EnvironmentSnapshot>,
+    /// ffmpeg argv of every run, including input and output paths
+    pub commands: Vec<Vec<String>>,
+    /// SHA-256 of the output, when one was computed
+    pub sha256: Option<String>,
+}
+
+impl TaskRecord {
+    pub fn from_task(task: &ProcessingTask) -> Self {
+        Self {
+            id: task.id,
+            operation: task.operation.clone(),
+            status: format!("{:?}", task.status),
+            progress: task.progress,
+            error_message: task.error_message.clone(),
+            duration_seconds: task.completion_time.map(|duration| duration.as_secs_f64()),
+            environment: task.environment.clone(),
+            commands: task.executed_commands.clone(),
+            sha256: task.checksum.sha256().map(str::to_string),
+        }
+    }
+}
+
+/// Everything attached to a problem report, written as one JSON file
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticBundle {
+    pub created_at: String,
+    pub environment: EnvironmentSnapshot,
+    pub hardware: Option<HardwareInfo>,
+    pub hardware_encoders: Vec<String>,
+    /// Only when the user opted in
+    pub tasks: Option<Vec<TaskRecord>>,
+    /// Rotating log files, oldest first
+    pub logs: String,
+}
+
+impl DiagnosticBundle {
+    pub fn collect(hardware: Option<&HardwareInfo>, hardware_encoders: &[String], tasks: Option<&[ProcessingTask]>) -> Self {
+        Self {
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            environment: EnvironmentSnapshot::current(),
+            hardware: hardware.cloned(),
+            hardware_encoders: hardware_encoders.to_vec(),
+            tasks: tasks.map(|tasks| tasks.iter().map(TaskRecord::from_task).collect()),
+            logs: crate::logging::recent_logs(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Ask where to save a bundle and write it; Ok(None) when the dialog was cancelled
+pub fn save_bundle(bundle: &DiagnosticBundle) -> anyhow::Result<Option<std::path::PathBuf>> {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("JSON", &["json"])
+        .set_title("Save Diagnostic Bundle")
+        .set_file_name(format!("ffmpeg_gui_diagnostics_{}.json", chrono::Local::now().format("%Y%m%d_%H%M%S")))
+        .save_file() else {
+        return Ok(None);
+    };
+    std::fs::write(&path, bundle.to_json()?)?;
+    Ok(Some(path))
+}
@@ -0,0 +1,641 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::checksum::{to_hex, Sha256};
+use crate::language::Translations;
+
+/// Default size cap of the download cache
+pub const DEFAULT_CAP_MB: u64 = 4096;
+
+/// Total size of cached downloads; least recently used unpinned entries go first beyond it
+static CAP_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_CAP_MB * 1024 * 1024);
+
+/// How often a running download is checked for progress and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Time allowed for the header request of the freshness check
+const HEAD_TIMEOUT_SECS: &str = "15";
+/// curl exit code when a resumed transfer is refused because the server ignores ranges
+const CURL_RANGE_ERROR: i32 = 33;
+
+pub fn set_size_cap_mb(cap_mb: u64) {
+    CAP_BYTES.store(cap_mb.saturating_mul(1024 * 1024), Ordering::Relaxed);
+}
+
+/// http(s) inputs are fetched into the cache before a task runs
+pub fn is_remote(input: &str) -> bool {
+    let lower = input.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Last path segment of a URL without query or fragment, e.g. "clip.mp4"
+pub fn remote_file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let path = path.split_once("://").map(|(_, rest)| rest).unwrap_or(path);
+    let name = path.split_once('/').map(|(_, path)| path.rsplit('/').next().unwrap_or("")).unwrap_or("");
+    let name: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    if name.trim_matches(['.', '_']).is_empty() { "download".to_string() } else { name }
+}
+
+/// What a server says about a resource, from the response headers
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_length: Option<u64>,
+    #[serde(default)]
+    pub accept_ranges: bool,
+}
+
+/// Validators of the final response in `curl -I -L` output, which prints every response of the
+/// redirect chain. None when the final status is not a success.
+pub fn parse_headers(text: &str) -> Option<Validators> {
+    let last = text.split("\r\n\r\n").flat_map(|block| block.split("\n\n"))
+        .map(str::trim)
+        .filter(|block| block.starts_with("HTTP/"))
+        .last()?;
+    let mut lines = last.lines();
+    let status: u16 = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+    if !(200..300).contains(&status) {
+        return None;
+    }
+    let mut validators = Validators::default();
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "etag" if !value.is_empty() => validators.etag = Some(value.to_string()),
+            "last-modified" if !value.is_empty() => validators.last_modified = Some(value.to_string()),
+            "content-length" => validators.content_length = value.parse().ok(),
+            "accept-ranges" => validators.accept_ranges = value.eq_ignore_ascii_case("bytes"),
+            _ => {}
+        }
+    }
+    Some(validators)
+}
+
+/// Weak and strong ETags name the same content for reuse purposes
+fn etag_value(etag: &str) -> &str {
+    etag.strip_prefix("W/").unwrap_or(etag)
+}
+
+/// Whether `remote` still describes the resource `cached` was fetched from: the ETag decides when
+/// both have one, then Last-Modified, then the length. None when there is nothing to compare.
+fn same_resource(cached: &Validators, remote: &Validators) -> Option<bool> {
+    if let (Some(cached), Some(remote)) = (&cached.etag, &remote.etag) {
+        return Some(etag_value(cached) == etag_value(remote));
+    }
+    if let (Some(cached), Some(remote)) = (&cached.last_modified, &remote.last_modified) {
+        return Some(cached == remote);
+    }
+    if let (Some(cached), Some(remote)) = (cached.content_length, remote.content_length) {
+        return Some(cached == remote);
+    }
+    None
+}
+
+/// Whether a complete cached copy can be used. Without an answer from the server (offline, no
+/// HEAD support) or without validators to compare, the cached copy is kept.
+pub fn is_fresh(cached: &Validators, remote: Option<&Validators>) -> bool {
+    remote.and_then(|remote| same_resource(cached, remote)).unwrap_or(true)
+}
+
+/// Whether a partial download can continue where it stopped: the server takes ranges and
+/// positively confirms the partial bytes belong to the same resource
+pub fn can_resume(cached: &Validators, remote: Option<&Validators>, partial_bytes: u64) -> bool {
+    let Some(remote) = remote else {
+        return false;
+    };
+    partial_bytes > 0
+        && remote.accept_ranges
+        && same_resource(cached, remote) == Some(true)
+        && remote.content_length.is_none_or(|length| partial_bytes < length)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    /// File name inside the cache directory
+    pub file: String,
+    pub validators: Validators,
+    pub bytes: u64,
+    /// False while the file holds an interrupted download
+    pub complete: bool,
+    /// Never evicted by the size cap nor removed by Clear
+    #[serde(default)]
+    pub pinned: bool,
+    /// Unix seconds of the download
+    pub fetched_at: i64,
+    /// Unix seconds of the last task that used the entry
+    pub last_used: i64,
+}
+
+/// What to do with a URL given its cache entry and the server's current headers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FetchPlan {
+    UseCached,
+    /// Continue the partial download at the end of the file
+    Resume,
+    /// Fetch from scratch, replacing anything cached
+    Download,
+}
+
+fn plan_fetch(cached: Option<&CacheEntry>, remote: Option<&Validators>) -> FetchPlan {
+    match cached {
+        Some(entry) if entry.complete && is_fresh(&entry.validators, remote) => FetchPlan::UseCached,
+        Some(entry) if !entry.complete && can_resume(&entry.validators, remote, entry.bytes) => FetchPlan::Resume,
+        _ => FetchPlan::Download,
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+fn cache_dir() -> PathBuf {
+    crate::app_state::UserPreferences::file_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("download_cache")))
+        .unwrap_or_else(|| std::env::temp_dir().join("ffmpeg_gui_downloads"))
+}
+
+/// Cache entry key of a URL
+fn url_key(url: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.trim().as_bytes());
+    to_hex(&hasher.finalize())[..16].to_string()
+}
+
+/// Remote inputs fetched by earlier tasks, by URL key
+pub struct DownloadCache {
+    dir: PathBuf,
+    index: CacheIndex,
+}
+
+impl DownloadCache {
+    fn open(dir: PathBuf) -> Self {
+        let index = std::fs::read_to_string(dir.join("index.json")).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self { dir, index }
+    }
+
+    fn path_of(&self, entry: &CacheEntry) -> PathBuf {
+        self.dir.join(&entry.file)
+    }
+
+    /// Entries whose file disappeared are dropped
+    fn entry(&mut self, key: &str) -> Option<CacheEntry> {
+        let entry = self.index.entries.get(key)?.clone();
+        if !self.path_of(&entry).is_file() {
+            self.index.entries.remove(key);
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.index.entries.remove(key) {
+            let _ = std::fs::remove_file(self.path_of(&entry));
+            log_debug!("Removed download cache entry {}", entry.url);
+        }
+    }
+
+    /// Remove unpinned entries, least recently used first, until the total fits `cap_bytes`.
+    /// `keep` (the download that just finished) is never evicted.
+    fn evict(&mut self, cap_bytes: u64, keep: &str) {
+        let mut total: u64 = self.index.entries.values().map(|entry| entry.bytes).sum();
+        let mut candidates: Vec<(i64, String)> = self.index.entries.iter()
+            .filter(|(key, entry)| !entry.pinned && key.as_str() != keep)
+            .map(|(key, entry)| (entry.last_used, key.clone()))
+            .collect();
+        candidates.sort();
+        for (_, key) in candidates {
+            if total <= cap_bytes {
+                break;
+            }
+            total = total.saturating_sub(self.index.entries.get(&key).map_or(0, |entry| entry.bytes));
+            self.remove(&key);
+        }
+    }
+
+    fn save(&self) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&self.index).map_err(|e| e.to_string()))
+            .and_then(|json| std::fs::write(self.dir.join("index.json"), json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log_warn!("Failed to save download cache index: {}", e);
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<DownloadCache> = Mutex::new(DownloadCache::open(cache_dir()));
+    /// URL keys being downloaded; a second task wanting the same URL waits for the first
+    static ref IN_FLIGHT: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    /// Bytes so far and expected total of the download each task is waiting on, by task id
+    static ref ACTIVE: Mutex<HashMap<usize, (u64, Option<u64>)>> = Mutex::new(HashMap::new());
+}
+
+fn curl_command() -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new("curl");
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// Headers of `url`, following redirects; None when the server cannot be asked
+fn fetch_validators(url: &str) -> Option<Validators> {
+    let output = curl_command()
+        .args(["-sSIL", "--max-time", HEAD_TIMEOUT_SECS, url])
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    parse_headers(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Download `url` into `path`, continuing at the end of the file when `resume`.
+/// Returns the curl exit code.
+fn run_curl(url: &str, path: &Path, resume: bool, task_id: usize, total: Option<u64>, cancelled: &dyn Fn() -> bool) -> Result<i32, String> {
+    let mut cmd = curl_command();
+    cmd.args(["-sSL", "--fail", "-o"]).arg(path);
+    if resume {
+        cmd.args(["-C", "-"]);
+    }
+    cmd.arg(url).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped());
+    log_debug!("Downloading: {:?}", cmd);
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start curl: {}", e))?;
+    loop {
+        let written = std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+        ACTIVE.lock().unwrap().insert(task_id, (written, total));
+        if cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Download cancelled".to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() {
+                    let mut stderr = String::new();
+                    if let Some(mut pipe) = child.stderr.take() {
+                        let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+                    }
+                    log_warn!("curl exited with {} for {}: {}", status, url, stderr.trim());
+                }
+                return Ok(status.code().unwrap_or(-1));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for curl: {}", e)),
+        }
+    }
+}
+
+/// Local copy of `url`: the cached file when it is still fresh, otherwise a new or resumed
+/// download. An interrupted download stays in the cache as a partial entry for the next attempt.
+pub fn fetch(url: &str, task_id: usize, cancelled: &dyn Fn() -> bool) -> Result<PathBuf, String> {
+    let key = url_key(url);
+    // Only one task downloads a URL at a time; the others wait and then find it cached
+    loop {
+        if IN_FLIGHT.lock().unwrap().insert(key.clone()) {
+            break;
+        }
+        if cancelled() {
+            return Err("Download cancelled".to_string());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    let result = fetch_exclusive(url, &key, task_id, cancelled);
+    IN_FLIGHT.lock().unwrap().remove(&key);
+    ACTIVE.lock().unwrap().remove(&task_id);
+    result
+}
+
+fn fetch_exclusive(url: &str, key: &str, task_id: usize, cancelled: &dyn Fn() -> bool) -> Result<PathBuf, String> {
+    let remote = fetch_validators(url);
+    let now = chrono::Utc::now().timestamp();
+    let (cached, path) = {
+        let mut cache = CACHE.lock().unwrap();
+        let cached = cache.entry(key);
+        let path = cached.as_ref()
+            .map(|entry| cache.path_of(entry))
+            .unwrap_or_else(|| cache.dir.join(format!("{}_{}", key, remote_file_name(url))));
+        (cached, path)
+    };
+
+    let plan = plan_fetch(cached.as_ref(), remote.as_ref());
+    if plan == FetchPlan::UseCached {
+        log_info!("Using cached download of {}", url);
+        let mut cache = CACHE.lock().unwrap();
+        if let Some(entry) = cache.index.entries.get_mut(key) {
+            entry.last_used = now;
+        }
+        cache.save();
+        return Ok(path);
+    }
+    let resume = plan == FetchPlan::Resume;
+    if cached.is_some() {
+        log_info!("{} download of {}", if resume { "Resuming" } else { "Restarting" }, url);
+    }
+    if !resume {
+        let _ = std::fs::remove_file(&path);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create download cache directory: {}", e))?;
+    }
+
+    let validators = remote.clone().unwrap_or_default();
+    let total = validators.content_length;
+    let mut outcome = run_curl(url, &path, resume, task_id, total, cancelled);
+    if resume && matches!(outcome, Ok(CURL_RANGE_ERROR)) {
+        log_warn!("Server refused to resume {}, downloading it again", url);
+        let _ = std::fs::remove_file(&path);
+        outcome = run_curl(url, &path, false, task_id, total, cancelled);
+    }
+
+    let bytes = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+    let complete = matches!(outcome, Ok(0));
+    let mut cache = CACHE.lock().unwrap();
+    if bytes > 0 {
+        let pinned = cached.as_ref().is_some_and(|entry| entry.pinned);
+        let file = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        cache.index.entries.insert(key.to_string(), CacheEntry {
+            url: url.to_string(),
+            file,
+            validators,
+            bytes,
+            complete,
+            pinned,
+            fetched_at: now,
+            last_used: now,
+        });
+    } else {
+        cache.index.entries.remove(key);
+    }
+    if complete {
+        cache.evict(CAP_BYTES.load(Ordering::Relaxed), key);
+    }
+    cache.save();
+    match outcome {
+        Ok(0) => Ok(path),
+        Ok(code) => Err(format!("Download of {} failed (curl exit code {})", url, code)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace remote inputs with their cached local copies
+pub fn localize_inputs(inputs: &mut [String], task_id: usize, cancelled: &dyn Fn() -> bool) -> Result<(), String> {
+    for input in inputs.iter_mut().filter(|input| is_remote(input)) {
+        *input = fetch(input.trim(), task_id, cancelled)?.display().to_string();
+    }
+    Ok(())
+}
+
+/// Progress and text for the task row while the task is downloading its inputs
+pub fn row_status(task_id: usize, translations: &Translations) -> Option<(f32, String)> {
+    let (written, total) = *ACTIVE.lock().unwrap().get(&task_id)?;
+    let written_text = crate::workflow_report::format_bytes(written);
+    Some(match total.filter(|total| *total > 0) {
+        Some(total) => (
+            (written as f32 / total as f32).min(1.0),
+            format!("{} {} / {}", translations.download_cache_downloading(), written_text, crate::workflow_report::format_bytes(total)),
+        ),
+        None => (0.0, format!("{} {}", translations.download_cache_downloading(), written_text)),
+    })
+}
+
+fn format_age(seconds: i64, translations: &Translations) -> String {
+    let (value, unit) = match seconds.max(0) {
+        s if s < 3600 => (s / 60, translations.download_cache_minutes()),
+        s if s < 86400 => (s / 3600, translations.download_cache_hours()),
+        s => (s / 86400, translations.download_cache_days()),
+    };
+    format!("{} {}", value, unit)
+}
+
+/// Download cache window. Returns false once closed; `cap_mb` is the size cap preference and
+/// `cap_changed` is set when the user edits it.
+pub fn show_window(ctx: &egui::Context, translations: &Translations, cap_mb: &mut u64, cap_changed: &mut bool) -> bool {
+    let mut open = true;
+    egui::Window::new(translations.download_cache_title())
+        .open(&mut open)
+        .resizable(true)
+        .default_size([640.0, 360.0])
+        .show(ctx, |ui| {
+            let mut cache = CACHE.lock().unwrap();
+            let in_flight = IN_FLIGHT.lock().unwrap().clone();
+            let total: u64 = cache.index.entries.values().map(|entry| entry.bytes).sum();
+            ui.horizontal(|ui| {
+                ui.label(format!("{}: {}", translations.download_cache_total(), crate::workflow_report::format_bytes(total)));
+                ui.separator();
+                ui.label(translations.download_cache_cap());
+                let response = ui.add(egui::DragValue::new(cap_mb).range(64..=1_048_576).suffix(" MB"));
+                if response.changed() {
+                    set_size_cap_mb(*cap_mb);
+                    *cap_changed = true;
+                }
+                // Evict once the value is settled, not at every step of a drag
+                if (response.drag_stopped() || response.lost_focus()) && in_flight.is_empty() {
+                    cache.evict(CAP_BYTES.load(Ordering::Relaxed), "");
+                    cache.save();
+                }
+                if ui.add_enabled(in_flight.is_empty(), egui::Button::new(translations.download_cache_clear()))
+                    .on_hover_text(translations.download_cache_clear_hint())
+                    .clicked()
+                {
+                    let unpinned: Vec<String> = cache.index.entries.iter()
+                        .filter(|(_, entry)| !entry.pinned)
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                    for key in unpinned {
+                        cache.remove(&key);
+                    }
+                    cache.save();
+                }
+            });
+            ui.separator();
+
+            if cache.index.entries.is_empty() {
+                ui.weak(translations.download_cache_empty());
+                return;
+            }
+            let now = chrono::Utc::now().timestamp();
+            let mut removed = None;
+            let mut changed = false;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("download_cache_grid").striped(true).num_columns(5).show(ui, |ui| {
+                    ui.strong(translations.download_cache_url());
+                    ui.strong(translations.download_cache_size());
+                    ui.strong(translations.download_cache_age());
+                    ui.strong(translations.download_cache_pinned());
+                    ui.label("");
+                    ui.end_row();
+                    for (key, entry) in cache.index.entries.iter_mut() {
+                        ui.add(egui::Label::new(&entry.url).truncate()).on_hover_text(&entry.url);
+                        let size = crate::workflow_report::format_bytes(entry.bytes);
+                        if entry.complete {
+                            ui.label(size);
+                        } else {
+                            ui.label(format!("{} ({})", size, translations.download_cache_partial()));
+                        }
+                        ui.label(format_age(now - entry.fetched_at, translations));
+                        changed |= ui.checkbox(&mut entry.pinned, "").changed();
+                        if ui.add_enabled(!in_flight.contains(key), egui::Button::new("🗑").small()).clicked() {
+                            removed = Some(key.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+            if let Some(key) = removed {
+                cache.remove(&key);
+                changed = true;
+            }
+            if changed {
+                cache.save();
+            }
+        });
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETAG: &str = "\"abc123\"";
+    const MODIFIED: &str = "Tue, 01 Oct 2024 10:00:00 GMT";
+
+    fn validators(etag: Option<&str>, last_modified: Option<&str>, length: Option<u64>) -> Validators {
+        Validators {
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+            content_length: length,
+            accept_ranges: true,
+        }
+    }
+
+    fn entry(validators: Validators, bytes: u64, complete: bool) -> CacheEntry {
+        CacheEntry {
+            url: "https://example.com/clip.mp4".to_string(),
+            file: "0123456789abcdef_clip.mp4".to_string(),
+            validators,
+            bytes,
+            complete,
+            pinned: false,
+            fetched_at: 0,
+            last_used: 0,
+        }
+    }
+
+    fn head(status: &str, headers: &[&str]) -> String {
+        format!("HTTP/1.1 {}\r\n{}\r\n\r\n", status, headers.iter().map(|header| format!("{}\r\n", header)).collect::<String>())
+    }
+
+    #[test]
+    fn cache_key_ignores_surrounding_whitespace_only() {
+        let key = url_key("https://example.com/clip.mp4");
+        assert_eq!(key.len(), 16);
+        assert_eq!(url_key("  https://example.com/clip.mp4\n"), key);
+        assert_ne!(url_key("https://example.com/clip.mp4?v=2"), key);
+        assert_ne!(url_key("https://example.com/Clip.mp4"), key);
+    }
+
+    #[test]
+    fn headers_of_the_final_redirect_are_used() {
+        let text = head("301 Moved Permanently", &["Location: https://cdn.example.com/clip.mp4", "ETag: \"old\""])
+            + &head("200 OK", &["etag: W/\"abc123\"", "Last-Modified: Tue, 01 Oct 2024 10:00:00 GMT", "Content-Length: 1000", "Accept-Ranges: bytes"]);
+        assert_eq!(parse_headers(&text), Some(Validators {
+            etag: Some("W/\"abc123\"".to_string()),
+            last_modified: Some(MODIFIED.to_string()),
+            content_length: Some(1000),
+            accept_ranges: true,
+        }));
+        assert_eq!(parse_headers(&head("404 Not Found", &["ETag: \"x\""])), None);
+        assert_eq!(parse_headers(""), None);
+    }
+
+    #[test]
+    fn freshness_with_both_headers_follows_the_etag() {
+        let cached = entry(validators(Some(ETAG), Some(MODIFIED), Some(1000)), 1000, true);
+        let same = validators(Some(ETAG), Some("Wed, 02 Oct 2024 10:00:00 GMT"), Some(2000));
+        assert_eq!(plan_fetch(Some(&cached), Some(&same)), FetchPlan::UseCached);
+        let weak = validators(Some("W/\"abc123\""), Some(MODIFIED), Some(1000));
+        assert_eq!(plan_fetch(Some(&cached), Some(&weak)), FetchPlan::UseCached);
+        let changed = validators(Some("\"def456\""), Some(MODIFIED), Some(1000));
+        assert_eq!(plan_fetch(Some(&cached), Some(&changed)), FetchPlan::Download);
+    }
+
+    #[test]
+    fn freshness_with_one_header_compares_that_header() {
+        let etag_only = entry(validators(Some(ETAG), None, None), 1000, true);
+        assert_eq!(plan_fetch(Some(&etag_only), Some(&validators(Some(ETAG), Some(MODIFIED), None))), FetchPlan::UseCached);
+        assert_eq!(plan_fetch(Some(&etag_only), Some(&validators(Some("\"def456\""), None, None))), FetchPlan::Download);
+
+        let modified_only = entry(validators(None, Some(MODIFIED), None), 1000, true);
+        assert_eq!(plan_fetch(Some(&modified_only), Some(&validators(Some(ETAG), Some(MODIFIED), None))), FetchPlan::UseCached);
+        let later = validators(None, Some("Wed, 02 Oct 2024 10:00:00 GMT"), None);
+        assert_eq!(plan_fetch(Some(&modified_only), Some(&later)), FetchPlan::Download);
+    }
+
+    #[test]
+    fn freshness_without_validators_falls_back_to_the_length_then_keeps_the_copy() {
+        let cached = entry(validators(None, None, Some(1000)), 1000, true);
+        assert_eq!(plan_fetch(Some(&cached), Some(&validators(None, None, Some(1000)))), FetchPlan::UseCached);
+        assert_eq!(plan_fetch(Some(&cached), Some(&validators(None, None, Some(1200)))), FetchPlan::Download);
+
+        let bare = entry(validators(None, None, None), 1000, true);
+        assert_eq!(plan_fetch(Some(&bare), Some(&validators(Some(ETAG), Some(MODIFIED), Some(1000)))), FetchPlan::UseCached);
+        // Offline or no HEAD support
+        assert_eq!(plan_fetch(Some(&cached), None), FetchPlan::UseCached);
+    }
+
+    #[test]
+    fn not_modified_keeps_the_cached_copy() {
+        let remote = parse_headers(&head("304 Not Modified", &["ETag: \"def456\""]));
+        assert_eq!(remote, None);
+        let cached = entry(validators(Some(ETAG), None, None), 1000, true);
+        assert_eq!(plan_fetch(Some(&cached), remote.as_ref()), FetchPlan::UseCached);
+    }
+
+    #[test]
+    fn partial_downloads_resume_only_when_the_server_confirms_them() {
+        let partial = entry(validators(Some(ETAG), None, Some(1000)), 400, false);
+        assert_eq!(plan_fetch(Some(&partial), Some(&validators(Some(ETAG), None, Some(1000)))), FetchPlan::Resume);
+        // Changed ETag, no range support, no answer, nothing to compare or nothing left to fetch
+        assert_eq!(plan_fetch(Some(&partial), Some(&validators(Some("\"def456\""), None, Some(1000)))), FetchPlan::Download);
+        let no_ranges = Validators { accept_ranges: false, ..validators(Some(ETAG), None, Some(1000)) };
+        assert_eq!(plan_fetch(Some(&partial), Some(&no_ranges)), FetchPlan::Download);
+        assert_eq!(plan_fetch(Some(&partial), None), FetchPlan::Download);
+        assert_eq!(plan_fetch(Some(&partial), Some(&validators(None, None, None))), FetchPlan::Download);
+        assert_eq!(plan_fetch(Some(&partial), Some(&validators(Some(ETAG), None, Some(400)))), FetchPlan::Download);
+
+        let empty = entry(validators(Some(ETAG), None, Some(1000)), 0, false);
+        assert_eq!(plan_fetch(Some(&empty), Some(&validators(Some(ETAG), None, Some(1000)))), FetchPlan::Download);
+        assert_eq!(plan_fetch(None, Some(&validators(Some(ETAG), None, Some(1000)))), FetchPlan::Download);
+    }
+
+    #[test]
+    fn remote_file_names_are_safe_and_never_empty() {
+        assert_eq!(remote_file_name("https://example.com/media/clip.mp4?token=1#t=5"), "clip.mp4");
+        assert_eq!(remote_file_name("https://example.com/a%20b (1).mp4"), "a_20b__1_.mp4");
+        assert_eq!(remote_file_name("https://example.com/"), "download");
+        assert_eq!(remote_file_name("https://example.com"), "download");
+        assert!(is_remote("  HTTPS://example.com/x"));
+        assert!(!is_remote("/home/user/https.mp4"));
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+use crate::app_state::OperationType;
+use crate::language::Translations;
+
+const VIDEO_EXTENSIONS: [&str; 10] = ["mp4", "avi", "mov", "mkv", "wmv", "flv", "webm", "3gp", "ogv", "m4v"];
+const AUDIO_EXTENSIONS: [&str; 8] = ["mp3", "wav", "flac", "aac", "ogg", "wma", "opus", "m4a"];
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp"];
+const SUBTITLE_EXTENSIONS: [&str; 5] = ["srt", "ass", "ssa", "vtt", "sub"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MediaKind {
+    Video,
+    Audio,
+    Image,
+    Subtitle,
+}
+
+fn media_kind(path: &str) -> Option<MediaKind> {
+    let extension = std::path::Path::new(path).extension()?.to_string_lossy().to_lowercase();
+    if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaKind::Video)
+    } else if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaKind::Audio)
+    } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaKind::Image)
+    } else if SUBTITLE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(MediaKind::Subtitle)
+    } else {
+        None
+    }
+}
+
+/// File-type combination of a drop; the last operation picked is remembered per combination
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropKind {
+    Video,
+    Audio,
+    Videos,
+    Audios,
+    VideoAndAudio,
+    ImageAndVideo,
+    VideoAndSubtitle,
+}
+
+impl DropKind {
+    /// Key in the preferences file
+    pub fn key(&self) -> &'static str {
+        match self {
+            DropKind::Video => "video",
+            DropKind::Audio => "audio",
+            DropKind::Videos => "videos",
+            DropKind::Audios => "audios",
+            DropKind::VideoAndAudio => "video_audio",
+            DropKind::ImageAndVideo => "image_video",
+            DropKind::VideoAndSubtitle => "video_subtitle",
+        }
+    }
+
+    /// Operations offered for the combination, most likely first
+    pub fn operations(&self) -> Vec<OperationType> {
+        match self {
+            DropKind::Video => vec![OperationType::VideoConvert, OperationType::VideoCompress, OperationType::ExtractAudio],
+            DropKind::Audio => vec![OperationType::AudioConvert, OperationType::AudioCompress],
+            DropKind::Videos => vec![OperationType::BatchConvert],
+            DropKind::Audios => vec![OperationType::AudioMerge, OperationType::BatchConvert],
+            DropKind::VideoAndAudio => vec![OperationType::VideoAudioMerge],
+            DropKind::ImageAndVideo => vec![OperationType::AddWatermark],
+            DropKind::VideoAndSubtitle => vec![OperationType::AddSubtitle],
+        }
+    }
+}
+
+/// What a drop with no operation selected could become
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropSuggestion {
+    pub kind: DropKind,
+    pub operations: Vec<OperationType>,
+    /// Media files of the drop, in drop order
+    pub files: Vec<String>,
+}
+
+impl DropSuggestion {
+    /// Main video of an image+video, video+audio or video+subtitle drop
+    pub fn video(&self) -> Option<&str> {
+        self.files.iter().find(|file| media_kind(file) == Some(MediaKind::Video)).map(String::as_str)
+    }
+
+    /// Image of an image+video drop
+    pub fn image(&self) -> Option<&str> {
+        self.files.iter().find(|file| media_kind(file) == Some(MediaKind::Image)).map(String::as_str)
+    }
+
+    /// Subtitle file of a video+subtitle drop
+    pub fn subtitle(&self) -> Option<&str> {
+        self.files.iter().find(|file| media_kind(file) == Some(MediaKind::Subtitle)).map(String::as_str)
+    }
+}
+
+/// Suggested operations for a set of dropped files; None when the combination has no obvious
+/// operation (unsupported files only, images or subtitles only, mixed sets that fit no operation)
+pub fn suggest(files: &[String]) -> Option<DropSuggestion> {
+    let media: Vec<(String, MediaKind)> = files.iter()
+        .filter_map(|file| media_kind(file).map(|kind| (file.clone(), kind)))
+        .collect();
+    let count = |kind: MediaKind| media.iter().filter(|(_, k)| *k == kind).count();
+    let (videos, audios, images, subtitles) =
+        (count(MediaKind::Video), count(MediaKind::Audio), count(MediaKind::Image), count(MediaKind::Subtitle));
+
+    let kind = match (videos, audios, images, subtitles) {
+        (1, 0, 0, 0) => DropKind::Video,
+        (0, 1, 0, 0) => DropKind::Audio,
+        (v, 0, 0, 0) if v > 1 => DropKind::Videos,
+        (0, a, 0, 0) if a > 1 => DropKind::Audios,
+        (1, 1, 0, 0) => DropKind::VideoAndAudio,
+        (1, 0, 1, 0) => DropKind::ImageAndVideo,
+        (1, 0, 0, 1) => DropKind::VideoAndSubtitle,
+        _ => return None,
+    };
+    Some(DropSuggestion {
+        kind,
+        operations: kind.operations(),
+        files: media.into_iter().map(|(file, _)| file).collect(),
+    })
+}
+
+pub enum ChooserAction {
+    None,
+    Dismiss,
+    Choose(OperationType),
+}
+
+/// Compact chooser shown after a drop on the welcome screen
+pub struct DropChooser {
+    pub suggestion: DropSuggestion,
+    selected: usize,
+}
+
+impl DropChooser {
+    /// Pre-select the operation last chosen for this file combination
+    pub fn new(suggestion: DropSuggestion, last_choices: &BTreeMap<String, OperationType>) -> Self {
+        let selected = last_choices.get(suggestion.kind.key())
+            .and_then(|last| suggestion.operations.iter().position(|operation| operation == last))
+            .unwrap_or(0);
+        Self { suggestion, selected }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations) -> ChooserAction {
+        let mut action = ChooserAction::None;
+        let count = self.suggestion.operations.len();
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::Escape) {
+                action = ChooserAction::Dismiss;
+            } else if i.key_pressed(egui::Key::ArrowDown) {
+                self.selected = (self.selected + 1) % count;
+            } else if i.key_pressed(egui::Key::ArrowUp) {
+                self.selected = (self.selected + count - 1) % count;
+            } else if i.key_pressed(egui::Key::Enter) {
+                action = ChooserAction::Choose(self.suggestion.operations[self.selected].clone());
+            }
+        });
+
+        egui::Window::new(translations.drop_chooser_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let names: Vec<String> = self.suggestion.files.iter()
+                    .map(|file| std::path::Path::new(file).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default())
+                    .collect();
+                ui.weak(translations.drop_chooser_files(&names));
+                ui.add_space(4.0);
+                for (index, operation) in self.suggestion.operations.iter().enumerate() {
+                    if ui.selectable_label(index == self.selected, operation.display_name(translations)).clicked() {
+                        action = ChooserAction::Choose(operation.clone());
+                    }
+                }
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button(translations.cancel()).clicked() {
+                        action = ChooserAction::Dismiss;
+                    }
+                    ui.weak(translations.drop_chooser_keys());
+                });
+            });
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    /// Dropped files and the kind and operations they should suggest
+    type Case<'a> = (&'a [&'a str], Option<(DropKind, &'a [OperationType])>);
+
+    #[test]
+    fn drops_map_to_their_operations() {
+        use OperationType::*;
+        let cases: &[Case] = &[
+            (&["/v/clip.mp4"], Some((DropKind::Video, &[VideoConvert, VideoCompress, ExtractAudio]))),
+            (&["/v/CLIP.MOV"], Some((DropKind::Video, &[VideoConvert, VideoCompress, ExtractAudio]))),
+            (&["/a/song.flac"], Some((DropKind::Audio, &[AudioConvert, AudioCompress]))),
+            (&["/v/a.mkv", "/v/b.webm", "/v/c.m4v"], Some((DropKind::Videos, &[BatchConvert]))),
+            (&["/a/1.mp3", "/a/2.wav"], Some((DropKind::Audios, &[AudioMerge, BatchConvert]))),
+            (&["/a/voice.m4a", "/v/clip.mp4"], Some((DropKind::VideoAndAudio, &[VideoAudioMerge]))),
+            (&["/i/logo.png", "/v/clip.mp4"], Some((DropKind::ImageAndVideo, &[AddWatermark]))),
+            (&["/v/clip.mkv", "/s/clip.en.srt"], Some((DropKind::VideoAndSubtitle, &[AddSubtitle]))),
+            (&["/s/clip.ASS", "/v/clip.mp4"], Some((DropKind::VideoAndSubtitle, &[AddSubtitle]))),
+            // Image sequences and subtitles alone have no operation of their own
+            (&["/seq/frame_0001.png", "/seq/frame_0002.png", "/seq/frame_0003.png"], None),
+            (&["/i/still.jpg"], None),
+            (&["/s/clip.vtt"], None),
+            (&["/s/clip.srt", "/s/clip.ass"], None),
+            // Mixed sets that fit no single operation
+            (&["/v/a.mp4", "/v/b.mp4", "/a/music.mp3"], None),
+            (&["/v/a.mp4", "/a/music.mp3", "/i/logo.png"], None),
+            (&["/v/a.mp4", "/i/logo.png", "/i/logo2.png"], None),
+            (&["/v/a.mp4", "/s/a.srt", "/s/b.srt"], None),
+            (&["/v/a.mp4", "/i/logo.png", "/s/a.srt"], None),
+            // Unknown extensions are ignored
+            (&["/d/notes.txt", "/d/archive.zip"], None),
+            (&["/d/README", "/v/clip.mp4", "/d/notes.txt"], Some((DropKind::Video, &[VideoConvert, VideoCompress, ExtractAudio]))),
+            (&[], None),
+        ];
+        for (dropped, expected) in cases {
+            let suggestion = suggest(&files(dropped));
+            assert_eq!(
+                suggestion.as_ref().map(|suggestion| (suggestion.kind, suggestion.operations.as_slice())),
+                *expected,
+                "{:?}", dropped
+            );
+        }
+    }
+
+    #[test]
+    fn suggestions_keep_the_media_files_in_drop_order() {
+        let suggestion = suggest(&files(&["/d/notes.txt", "/i/logo.png", "/d/x.pdf", "/v/clip.mp4"])).unwrap();
+        assert_eq!(suggestion.files, files(&["/i/logo.png", "/v/clip.mp4"]));
+        assert_eq!(suggestion.video(), Some("/v/clip.mp4"));
+        assert_eq!(suggestion.image(), Some("/i/logo.png"));
+        assert_eq!(suggestion.subtitle(), None);
+
+        let suggestion = suggest(&files(&["/s/clip.srt", "/v/clip.mkv"])).unwrap();
+        assert_eq!(suggestion.video(), Some("/v/clip.mkv"));
+        assert_eq!(suggestion.subtitle(), Some("/s/clip.srt"));
+    }
+
+    #[test]
+    fn chooser_preselects_the_last_choice_for_the_combination() {
+        let suggestion = suggest(&files(&["/v/clip.mp4"])).unwrap();
+        let mut last = BTreeMap::new();
+        assert_eq!(DropChooser::new(suggestion.clone(), &last).selected, 0);
+        last.insert("audio".to_string(), OperationType::AudioCompress);
+        last.insert("video".to_string(), OperationType::ExtractAudio);
+        assert_eq!(DropChooser::new(suggestion.clone(), &last).selected, 2);
+        // A remembered operation the combination no longer offers falls back to the first
+        last.insert("video".to_string(), OperationType::GifResize);
+        assert_eq!(DropChooser::new(suggestion, &last).selected, 0);
+    }
+
+    #[test]
+    fn every_kind_has_its_own_key() {
+        let kinds = [
+            DropKind::Video, DropKind::Audio, DropKind::Videos, DropKind::Audios,
+            DropKind::VideoAndAudio, DropKind::ImageAndVideo, DropKind::VideoAndSubtitle,
+        ];
+        let keys: std::collections::BTreeSet<&str> = kinds.iter().map(DropKind::key).collect();
+        assert_eq!(keys.len(), kinds.len());
+        assert!(kinds.iter().all(|kind| !kind.operations().is_empty()));
+    }
+}
@@ -0,0 +1,243 @@
+use eframe::egui;
+
+use crate::app_state::{AudioSettings, OperationType, VideoSettings};
+use crate::codec_manager::CodecManager;
+use crate::language::Translations;
+
+/// Codecs a task will really encode with once "auto" is resolved; None for a stream the
+/// operation does not encode through the convert path
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EffectiveCodecs {
+    pub video: Option<String>,
+    pub audio: Option<String>,
+}
+
+/// Video codec for `container`: transparent sources get an alpha-preserving encoder, "auto" the
+/// container's preferred encoder and an explicit codec is kept as chosen
+pub fn resolve_video_codec(video: &VideoSettings, container: &str) -> String {
+    if let Some(codec) = crate::alpha_video::auto_codec_for_settings(video, container) {
+        codec.to_string()
+    } else if video.codec == "auto" || video.codec.is_empty() {
+        CodecManager::get_best_video_codec_for_format(container)
+    } else {
+        video.codec.clone()
+    }
+}
+
+/// Audio codec for `container`: "auto" becomes the container's default encoder, anything else (including "copy") is kept
+pub fn resolve_audio_codec(audio: &AudioSettings, container: &str) -> String {
+    if audio.codec == "auto" || audio.codec.is_empty() {
+        CodecManager::get_best_audio_codec_for_format(container)
+    } else {
+        audio.codec.clone()
+    }
+}
+
+/// Codecs `operation` will use for `container`, the way the executor resolves them. With
+/// `prefer_hardware` an "auto" video codec becomes the hardware encoder the policy picks from
+/// `hardware_encoders`, as new tasks do.
+pub fn resolve_effective_codecs(
+    operation: &OperationType,
+    container: &str,
+    video: &VideoSettings,
+    audio: &AudioSettings,
+    hardware_encoders: &[String],
+    prefer_hardware: bool,
+) -> EffectiveCodecs {
+    match operation {
+        OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize => {
+            let video_codec = if video.copy_video {
+                "copy".to_string()
+            } else {
+                // A transparent source keeps its alpha encoder; no hardware encoder writes alpha
+                let alpha = crate::alpha_video::auto_codec_for_settings(video, container).is_some();
+                let hardware = (prefer_hardware && video.codec == "auto" && !alpha)
+                    .then(|| crate::first_run::hardware_codec_for(container, &video.quality_preset, hardware_encoders))
+                    .flatten();
+                hardware.unwrap_or_else(|| resolve_video_codec(video, container))
+            };
+            let audio_codec = if audio.copy_audio { "copy".to_string() } else { resolve_audio_codec(audio, container) };
+            EffectiveCodecs { video: Some(video_codec), audio: Some(audio_codec) }
+        }
+        OperationType::AudioConvert | OperationType::AudioCompress | OperationType::AudioResample | OperationType::AudioVolume | OperationType::AudioNormalize => {
+            EffectiveCodecs { video: None, audio: Some(resolve_audio_codec(audio, container)) }
+        }
+        _ => EffectiveCodecs::default(),
+    }
+}
+
+/// "auto → libx264" under a codec dropdown that is set to auto
+pub fn show_auto_resolution(ui: &mut egui::Ui, selected: &str, resolved: Option<&str>, translations: &Translations) {
+    if selected != "auto" {
+        return;
+    }
+    if let Some(resolved) = resolved {
+        ui.weak(format!("auto → {}", resolved))
+            .on_hover_text(translations.auto_codec_resolution_hint());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIDEO_CONTAINERS: [&str; 11] = ["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv", "3gp", "ts", "m4v", "m2ts"];
+    const AUDIO_CONTAINERS: [&str; 9] = ["mp3", "aac", "m4a", "wav", "flac", "ogg", "wma", "opus", "mka"];
+
+    fn hardware() -> Vec<String> {
+        ["h264_nvenc", "hevc_nvenc", "h264_qsv", "vp9_qsv", "h264_vaapi"].iter().map(|codec| codec.to_string()).collect()
+    }
+
+    fn convert(container: &str, video: &VideoSettings, audio: &AudioSettings, encoders: &[String], prefer_hardware: bool) -> (String, String) {
+        let codecs = resolve_effective_codecs(&OperationType::VideoConvert, container, video, audio, encoders, prefer_hardware);
+        (codecs.video.unwrap(), codecs.audio.unwrap())
+    }
+
+    #[test]
+    fn auto_codecs_follow_each_video_container() {
+        let expected = [
+            ("mp4", "libx264", "aac"),
+            ("mkv", "libx264", "aac"),
+            ("avi", "libx264", "libmp3lame"),
+            ("mov", "libx264", "aac"),
+            ("webm", "libvpx-vp9", "libvorbis"),
+            ("flv", "flv", "aac"),
+            ("wmv", "wmv2", "wmav2"),
+            ("3gp", "h263", "aac"),
+            ("ts", "libx264", "aac"),
+            ("m4v", "libx264", "aac"),
+            ("m2ts", "libx264", "aac"),
+        ];
+        assert_eq!(expected.len(), VIDEO_CONTAINERS.len());
+        let (video, audio) = (VideoSettings::default(), AudioSettings::default());
+        for (container, video_codec, audio_codec) in expected {
+            let resolved = (video_codec.to_string(), audio_codec.to_string());
+            assert_eq!(convert(container, &video, &audio, &[], false), resolved, "{}", container);
+            // Without a hardware encoder the preference changes nothing, and without the preference neither do encoders
+            assert_eq!(convert(container, &video, &audio, &[], true), resolved, "{}", container);
+            assert_eq!(convert(container, &video, &audio, &hardware(), false), resolved, "{}", container);
+        }
+    }
+
+    #[test]
+    fn preferring_hardware_picks_an_encoder_the_container_can_hold() {
+        let expected = [
+            ("mp4", "h264_nvenc"),
+            ("mkv", "h264_nvenc"),
+            ("avi", "h264_nvenc"),
+            ("mov", "h264_nvenc"),
+            ("webm", "vp9_qsv"),
+            ("flv", "h264_nvenc"),
+            ("ts", "h264_nvenc"),
+            ("m4v", "h264_nvenc"),
+            // The registry rejects H.264 here, so the software default stays
+            ("wmv", "wmv2"),
+            ("3gp", "h263"),
+            ("m2ts", "libx264"),
+        ];
+        assert_eq!(expected.len(), VIDEO_CONTAINERS.len());
+        let (video, audio) = (VideoSettings::default(), AudioSettings::default());
+        for (container, video_codec) in expected {
+            assert_eq!(convert(container, &video, &audio, &hardware(), true).0, video_codec, "{}", container);
+        }
+    }
+
+    #[test]
+    fn explicit_and_copied_streams_are_kept() {
+        let video = VideoSettings { codec: "libx265".to_string(), ..Default::default() };
+        let audio = AudioSettings { codec: "libopus".to_string(), ..Default::default() };
+        for container in VIDEO_CONTAINERS {
+            assert_eq!(convert(container, &video, &audio, &hardware(), true), ("libx265".to_string(), "libopus".to_string()));
+        }
+
+        let video = VideoSettings { copy_video: true, ..Default::default() };
+        let audio = AudioSettings { copy_audio: true, ..Default::default() };
+        for container in VIDEO_CONTAINERS {
+            assert_eq!(convert(container, &video, &audio, &hardware(), true), ("copy".to_string(), "copy".to_string()));
+        }
+
+        let audio = AudioSettings::default();
+        assert_eq!(convert("webm", &video, &audio, &hardware(), true), ("copy".to_string(), "libvorbis".to_string()));
+    }
+
+    #[test]
+    fn transparent_sources_keep_their_alpha_encoder_over_hardware() {
+        let video = VideoSettings { source_alpha: true, ..Default::default() };
+        let audio = AudioSettings::default();
+        assert_eq!(convert("mov", &video, &audio, &hardware(), true).0, "prores_ks");
+        assert_eq!(convert("webm", &video, &audio, &hardware(), true).0, "libvpx-vp9");
+        assert_eq!(convert("mkv", &video, &audio, &hardware(), true).0, "ffv1");
+        // Containers without an alpha encoder fall back to the normal choice
+        assert_eq!(convert("mp4", &video, &audio, &hardware(), true).0, "h264_nvenc");
+
+        let flattened = VideoSettings { alpha_background: Some("0xffffff".to_string()), ..video };
+        assert_eq!(convert("mov", &flattened, &audio, &hardware(), true).0, "h264_nvenc");
+    }
+
+    #[test]
+    fn audio_operations_resolve_only_the_audio_codec() {
+        let expected = [
+            ("mp3", "libmp3lame"),
+            ("aac", "aac"),
+            ("m4a", "aac"),
+            ("wav", "pcm_s16le"),
+            ("flac", "flac"),
+            ("ogg", "libvorbis"),
+            ("wma", "wmav2"),
+            ("opus", "libopus"),
+        ];
+        let (video, audio) = (VideoSettings::default(), AudioSettings::default());
+        let operations = [
+            OperationType::AudioConvert,
+            OperationType::AudioCompress,
+            OperationType::AudioResample,
+            OperationType::AudioVolume,
+            OperationType::AudioNormalize,
+        ];
+        for operation in &operations {
+            for (container, codec) in expected {
+                assert_eq!(
+                    resolve_effective_codecs(operation, container, &video, &audio, &hardware(), true),
+                    EffectiveCodecs { video: None, audio: Some(codec.to_string()) },
+                    "{:?} {}", operation, container
+                );
+            }
+            let explicit = AudioSettings { codec: "copy".to_string(), ..Default::default() };
+            assert_eq!(resolve_effective_codecs(operation, "mp3", &video, &explicit, &[], false).audio.as_deref(), Some("copy"));
+        }
+    }
+
+    #[test]
+    fn other_operations_resolve_nothing() {
+        let (video, audio) = (VideoSettings::default(), AudioSettings::default());
+        let operations = [
+            OperationType::VideoCrop,
+            OperationType::VideoRotate,
+            OperationType::VideoFilter,
+            OperationType::CutAndJoin,
+            OperationType::FrameRateConvert,
+            OperationType::AudioTrim,
+            OperationType::AudioMerge,
+            OperationType::VideoAudioMerge,
+            OperationType::VideoAudioSplit,
+            OperationType::ExtractAudio,
+            OperationType::ExtractVideo,
+            OperationType::BatchConvert,
+            OperationType::AddSubtitle,
+            OperationType::AddWatermark,
+            OperationType::FrameExtract,
+            OperationType::VideoToGif,
+            OperationType::GifResize,
+            OperationType::ReviewClip,
+        ];
+        for operation in &operations {
+            for container in VIDEO_CONTAINERS.iter().chain(&AUDIO_CONTAINERS) {
+                assert_eq!(
+                    resolve_effective_codecs(operation, container, &video, &audio, &hardware(), true),
+                    EffectiveCodecs::default(),
+                    "{:?} {}", operation, container
+                );
+            }
+        }
+    }
+}
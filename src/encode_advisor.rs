@@ -0,0 +1,499 @@
+use crate::app_state::{VideoSettings, AudioSettings};
+use crate::audio_advice::{self, AudioAdvice};
+use crate::codec_manager::CodecManager;
+use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+use crate::ffmpeg_worker_simple::MediaInfo;
+use crate::frame_rate::FrameRate;
+use crate::language::Translations;
+
+/// A target bitrate this much above the source only makes the file bigger
+const SIGNIFICANTLY_ABOVE: f64 = 1.5;
+/// Bitrates within this ratio of the source count as "the same" for the stream copy rule
+const SIMILAR_BITRATE: std::ops::RangeInclusive<f64> = 0.75..=1.25;
+
+/// Advisory rules; each one can be suppressed in the preferences
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvisoryRule {
+    Upscale,
+    BitrateAboveSource,
+    PreferStreamCopy,
+    AudioBitrateForLayout,
+    FrameRateChange,
+}
+
+impl AdvisoryRule {
+    pub fn all() -> [AdvisoryRule; 5] {
+        [AdvisoryRule::Upscale, AdvisoryRule::BitrateAboveSource, AdvisoryRule::PreferStreamCopy, AdvisoryRule::AudioBitrateForLayout, AdvisoryRule::FrameRateChange]
+    }
+
+    /// Stable key stored in the preferences
+    pub fn key(&self) -> &'static str {
+        match self {
+            AdvisoryRule::Upscale => "upscale",
+            AdvisoryRule::BitrateAboveSource => "bitrate_above_source",
+            AdvisoryRule::PreferStreamCopy => "prefer_stream_copy",
+            AdvisoryRule::AudioBitrateForLayout => "audio_bitrate_layout",
+            AdvisoryRule::FrameRateChange => "frame_rate_change",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Advisory {
+    Upscale { source: (u32, u32), target: (u32, u32) },
+    VideoBitrateAboveSource { source_kbps: u64, target_kbps: u64 },
+    AudioBitrateAboveSource { source_kbps: u64, target_kbps: u64 },
+    VideoStreamCopy { codec: String, source_kbps: u64, target_kbps: u64 },
+    AudioStreamCopy { codec: String, source_kbps: u64, target_kbps: u64 },
+    AudioBitrateForLayout(AudioAdvice),
+    FrameRateChange { source: FrameRate, target: FrameRate },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdvisoryFix {
+    MatchSourceResolution,
+    VideoBitrate(String),
+    AudioBitrate(String),
+    CopyVideo,
+    CopyAudio,
+    RecommendedAudioBitrate(String),
+    MatchSourceFrameRate,
+}
+
+impl Advisory {
+    pub fn rule(&self) -> AdvisoryRule {
+        match self {
+            Advisory::Upscale { .. } => AdvisoryRule::Upscale,
+            Advisory::VideoBitrateAboveSource { .. } | Advisory::AudioBitrateAboveSource { .. } => AdvisoryRule::BitrateAboveSource,
+            Advisory::VideoStreamCopy { .. } | Advisory::AudioStreamCopy { .. } => AdvisoryRule::PreferStreamCopy,
+            Advisory::AudioBitrateForLayout(_) => AdvisoryRule::AudioBitrateForLayout,
+            Advisory::FrameRateChange { .. } => AdvisoryRule::FrameRateChange,
+        }
+    }
+
+    pub fn fix(&self) -> AdvisoryFix {
+        match self {
+            Advisory::Upscale { .. } => AdvisoryFix::MatchSourceResolution,
+            Advisory::VideoBitrateAboveSource { source_kbps, .. } => AdvisoryFix::VideoBitrate(format!("{}k", source_kbps)),
+            Advisory::AudioBitrateAboveSource { source_kbps, .. } => AdvisoryFix::AudioBitrate(format!("{}k", source_kbps)),
+            Advisory::VideoStreamCopy { .. } => AdvisoryFix::CopyVideo,
+            Advisory::AudioStreamCopy { .. } => AdvisoryFix::CopyAudio,
+            Advisory::AudioBitrateForLayout(advice) => AdvisoryFix::RecommendedAudioBitrate(advice.suggested_bitrate()),
+            Advisory::FrameRateChange { .. } => AdvisoryFix::MatchSourceFrameRate,
+        }
+    }
+}
+
+/// Codec family shared by ffprobe codec names and ffmpeg encoder names ("h264", "libx264", "h264_nvenc")
+pub fn codec_family(codec: &str) -> Option<&'static str> {
+    let codec = codec.to_lowercase();
+    let family = match codec.as_str() {
+        "h264" | "libx264" | "libx264rgb" | "libopenh264" => "h264",
+        "hevc" | "h265" | "libx265" => "hevc",
+        "vp8" | "libvpx" => "vp8",
+        "vp9" | "libvpx-vp9" => "vp9",
+        "av1" | "libaom-av1" | "libsvtav1" | "librav1e" => "av1",
+        "mpeg4" | "libxvid" => "mpeg4",
+        "mpeg2video" => "mpeg2",
+        "aac" | "libfdk_aac" | "aac_at" => "aac",
+        "mp3" | "libmp3lame" => "mp3",
+        "opus" | "libopus" => "opus",
+        "vorbis" | "libvorbis" => "vorbis",
+        "ac3" => "ac3",
+        "eac3" => "eac3",
+        // Hardware encoders are named <family>_<api>
+        _ => return ["h264", "hevc", "vp8", "vp9", "av1", "mpeg2", "mjpeg"]
+            .into_iter()
+            .find(|family| codec.strip_prefix(family).is_some_and(|rest| rest.starts_with('_'))),
+    };
+    Some(family)
+}
+
+/// Bitrate setting in kbps; None for "auto" and unparsable values
+pub fn bitrate_kbps(bitrate: &str) -> Option<u64> {
+    crate::locale_format::parse_bitrate(bitrate).ok()
+        .and_then(|parsed| parsed.strip_suffix('k').and_then(|kbps| kbps.parse().ok()))
+}
+
+/// Source video bitrate in kbps: the stream's own, or the container bitrate minus the audio streams
+pub fn source_video_kbps(info: &MediaInfo) -> Option<u64> {
+    let video = info.video_streams.first()?;
+    let bits = video.bit_rate.or_else(|| {
+        let audio: u64 = info.audio_streams.iter().map(|audio| audio.bit_rate.unwrap_or(0)).sum();
+        info.bit_rate.map(|total| total.saturating_sub(audio)).filter(|rest| *rest > 0)
+    })?;
+    Some(bits / 1000)
+}
+
+pub fn source_audio_kbps(info: &MediaInfo) -> Option<u64> {
+    info.audio_streams.first()?.bit_rate.map(|bits| bits / 1000)
+}
+
+/// Output size set in the video settings; None keeps the source size
+pub fn configured_resolution(video: &VideoSettings) -> Option<(u32, u32)> {
+    match (video.width, video.height) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ if video.resolution.0 > 0 && video.resolution.1 > 0 => Some(video.resolution),
+        _ => None,
+    }
+}
+
+fn target_video_codec(video: &VideoSettings) -> String {
+    if video.codec == "auto" {
+        CodecManager::get_best_video_codec_for_format(&video.container_format)
+    } else {
+        video.codec.clone()
+    }
+}
+
+fn target_audio_codec(audio: &AudioSettings, video: &VideoSettings) -> String {
+    if audio.codec == "auto" {
+        let format = if audio.format.is_empty() || audio.format == "auto" { &video.container_format } else { &audio.format };
+        CodecManager::get_best_audio_codec_for_format(format)
+    } else {
+        audio.codec.clone()
+    }
+}
+
+/// Flags an output size larger than the source on either side, regardless of orientation
+pub fn check_upscale(info: &MediaInfo, video: &VideoSettings) -> Option<Advisory> {
+    if video.copy_video {
+        return None;
+    }
+    let source_stream = info.video_streams.first()?;
+    let source = (source_stream.width, source_stream.height);
+    let target = configured_resolution(video)?;
+    let sides = |(w, h): (u32, u32)| (w.max(h), w.min(h));
+    let (source_long, source_short) = sides(source);
+    let (target_long, target_short) = sides(target);
+    if source_long > 0 && (target_long > source_long || target_short > source_short) {
+        Some(Advisory::Upscale { source, target })
+    } else {
+        None
+    }
+}
+
+/// Flags target bitrates well above the source when the codec family stays the same
+pub fn check_bitrate_above_source(info: &MediaInfo, video: &VideoSettings, audio: &AudioSettings) -> Vec<Advisory> {
+    let mut advisories = Vec::new();
+
+    if !video.copy_video {
+        let source_family = info.video_streams.first().and_then(|stream| codec_family(&stream.codec));
+        if let (Some(source_kbps), Some(target_kbps)) = (source_video_kbps(info), bitrate_kbps(&video.bitrate)) {
+            if source_family.is_some() && source_family == codec_family(&target_video_codec(video))
+                && target_kbps as f64 > source_kbps as f64 * SIGNIFICANTLY_ABOVE {
+                advisories.push(Advisory::VideoBitrateAboveSource { source_kbps, target_kbps });
+            }
+        }
+    }
+
+    if !audio.copy_audio {
+        let source_family = info.audio_streams.first().and_then(|stream| codec_family(&stream.codec));
+        if let (Some(source_kbps), Some(target_kbps)) = (source_audio_kbps(info), bitrate_kbps(&audio.bitrate)) {
+            if source_family.is_some() && source_family == codec_family(&target_audio_codec(audio, video))
+                && target_kbps as f64 > source_kbps as f64 * SIGNIFICANTLY_ABOVE {
+                advisories.push(Advisory::AudioBitrateAboveSource { source_kbps, target_kbps });
+            }
+        }
+    }
+
+    advisories
+}
+
+/// Flags lossy-to-lossy transcodes into the same codec family at about the source bitrate with
+/// nothing else changing: stream copy gives the same size without the generation loss
+pub fn check_prefer_stream_copy(info: &MediaInfo, video: &VideoSettings, audio: &AudioSettings) -> Vec<Advisory> {
+    let similar = |source: u64, target: u64| source > 0 && SIMILAR_BITRATE.contains(&(target as f64 / source as f64));
+    let mut advisories = Vec::new();
+
+    if let Some(stream) = info.video_streams.first().filter(|_| !video.copy_video) {
+        let unchanged = configured_resolution(video).is_none_or(|size| size == (stream.width, stream.height))
+            && crate::filter_stack::effective_stack(video).iter().all(|entry| !entry.enabled)
+            && crate::frame_rate::is_same_as_source(&video.fps)
+            && video.rotation == 0 && !video.flip_horizontal && !video.flip_vertical;
+        let family = codec_family(&stream.codec);
+        if let (Some(source_kbps), Some(target_kbps)) = (source_video_kbps(info), bitrate_kbps(&video.bitrate)) {
+            if unchanged && family.is_some() && family == codec_family(&target_video_codec(video)) && similar(source_kbps, target_kbps) {
+                advisories.push(Advisory::VideoStreamCopy { codec: stream.codec.clone(), source_kbps, target_kbps });
+            }
+        }
+    }
+
+    if let Some(stream) = info.audio_streams.first().filter(|_| !audio.copy_audio) {
+        let unchanged = ComprehensiveCommandBuilder::audio_filter_chain(audio).is_ok_and(|filters| filters.is_empty())
+            && (audio.sample_rate == "auto" || audio.sample_rate == stream.sample_rate.to_string())
+            && (audio.channels == "auto" || audio.channels == stream.channels.to_string());
+        let family = codec_family(&stream.codec);
+        if let (Some(source_kbps), Some(target_kbps)) = (source_audio_kbps(info), bitrate_kbps(&audio.bitrate)) {
+            if unchanged && family.is_some() && family == codec_family(&target_audio_codec(audio, video)) && similar(source_kbps, target_kbps) {
+                advisories.push(Advisory::AudioStreamCopy { codec: stream.codec.clone(), source_kbps, target_kbps });
+            }
+        }
+    }
+
+    advisories
+}
+
+/// Flags audio bitrates that do not suit the output channel count and (when detected) voice or music content
+pub fn check_audio_bitrate_for_layout(info: &MediaInfo, video: &VideoSettings, audio: &AudioSettings) -> Option<Advisory> {
+    let stream = info.audio_streams.first().filter(|_| !audio.copy_audio)?;
+    let channels = audio.channels.parse::<u32>().ok().filter(|channels| *channels > 0).unwrap_or(stream.channels);
+    let family = codec_family(&target_audio_codec(audio, video))?;
+    audio_advice::advise(family, channels, audio.content_type, bitrate_kbps(&audio.bitrate)).map(Advisory::AudioBitrateForLayout)
+}
+
+/// Flags an explicit output frame rate that differs from the source: frames get duplicated or
+/// dropped unevenly, which shows as judder
+pub fn check_frame_rate_change(info: &MediaInfo, video: &VideoSettings) -> Option<Advisory> {
+    let stream = info.video_streams.first().filter(|_| !video.copy_video)?;
+    let source = stream.avg_frame_rate.or(stream.r_frame_rate)?;
+    let target = FrameRate::parse(&video.fps).filter(|_| !crate::frame_rate::is_same_as_source(&video.fps))?;
+    (target != source).then_some(Advisory::FrameRateChange { source, target })
+}
+
+/// All advisories for encoding `info` with the given settings, before suppression
+pub fn evaluate(info: &MediaInfo, video: &VideoSettings, audio: &AudioSettings) -> Vec<Advisory> {
+    let mut advisories: Vec<Advisory> = check_upscale(info, video).into_iter().collect();
+    advisories.extend(check_bitrate_above_source(info, video, audio));
+    advisories.extend(check_prefer_stream_copy(info, video, audio));
+    advisories.extend(check_audio_bitrate_for_layout(info, video, audio));
+    advisories.extend(check_frame_rate_change(info, video));
+    advisories
+}
+
+pub fn apply_fix(fix: &AdvisoryFix, video: &mut VideoSettings, audio: &mut AudioSettings) {
+    match fix {
+        AdvisoryFix::MatchSourceResolution => {
+            // 0 / None keep the source size
+            video.resolution = (0, 0);
+            video.width = None;
+            video.height = None;
+        }
+        AdvisoryFix::VideoBitrate(bitrate) => video.bitrate = bitrate.clone(),
+        AdvisoryFix::AudioBitrate(bitrate) => audio.bitrate = bitrate.clone(),
+        AdvisoryFix::CopyVideo => video.copy_video = true,
+        AdvisoryFix::CopyAudio => audio.copy_audio = true,
+        AdvisoryFix::RecommendedAudioBitrate(bitrate) => audio.bitrate = bitrate.clone(),
+        AdvisoryFix::MatchSourceFrameRate => video.fps = crate::frame_rate::SAME_AS_SOURCE.to_string(),
+    }
+}
+
+/// Advisory list for the settings panel; never blocks processing
+pub fn show_advisories(
+    ui: &mut egui::Ui,
+    info: &MediaInfo,
+    video: &mut VideoSettings,
+    audio: &mut AudioSettings,
+    suppressed: &mut std::collections::BTreeSet<String>,
+    translations: &Translations,
+) {
+    let advisories: Vec<Advisory> = evaluate(info, video, audio)
+        .into_iter()
+        .filter(|advisory| !suppressed.contains(advisory.rule().key()))
+        .collect();
+    if advisories.is_empty() {
+        return;
+    }
+
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(translations.encoding_advisories()).strong());
+        for (index, advisory) in advisories.iter().enumerate() {
+            ui.push_id(("encode_advisory", index), |ui| {
+                ui.colored_label(egui::Color32::from_rgb(255, 180, 0), format!("⚠ {}", translations.advisory_message(advisory)));
+                ui.horizontal(|ui| {
+                    let fix = advisory.fix();
+                    if ui.button(translations.advisory_fix_label(&fix)).clicked() {
+                        apply_fix(&fix, video, audio);
+                    }
+                    if ui.small_button(translations.hide_advisory_rule()).clicked() {
+                        suppressed.insert(advisory.rule().key().to_string());
+                    }
+                });
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_advice::AdviceKind;
+
+    /// 1080p25 H.264 at 4 Mbit/s with 128 kbit/s stereo AAC at 48 kHz
+    fn source() -> MediaInfo {
+        serde_json::from_value(serde_json::json!({
+            "filename": "source.mp4", "duration": 120.0, "bit_rate": 4_128_000,
+            "video_streams": [{
+                "index": 0, "codec": "h264", "width": 1920, "height": 1080, "fps": 25.0, "bit_rate": 4_000_000,
+                "avg_frame_rate": { "num": 25, "den": 1 }, "r_frame_rate": { "num": 25, "den": 1 },
+            }],
+            "audio_streams": [{ "index": 1, "codec": "aac", "sample_rate": 48000, "channels": 2, "bit_rate": 128_000 }],
+        })).unwrap()
+    }
+
+    fn x264(bitrate: &str) -> VideoSettings {
+        VideoSettings { codec: "libx264".to_string(), bitrate: bitrate.to_string(), ..Default::default() }
+    }
+
+    fn aac(bitrate: &str) -> AudioSettings {
+        AudioSettings { codec: "aac".to_string(), bitrate: bitrate.to_string(), sample_rate: "auto".to_string(), ..Default::default() }
+    }
+
+    fn sized(width: u32, height: u32) -> VideoSettings {
+        VideoSettings { width: Some(width), height: Some(height), ..x264("auto") }
+    }
+
+    #[test]
+    fn upscale_fires_only_when_a_side_grows() {
+        let info = source();
+        assert_eq!(check_upscale(&info, &sized(3840, 2160)), Some(Advisory::Upscale { source: (1920, 1080), target: (3840, 2160) }));
+        assert_eq!(check_upscale(&info, &sized(1280, 1440)), Some(Advisory::Upscale { source: (1920, 1080), target: (1280, 1440) }));
+        let legacy = VideoSettings { resolution: (2560, 1440), ..x264("auto") };
+        assert!(check_upscale(&info, &legacy).is_some());
+
+        // Rotating to portrait at the same size, downscaling, keeping the size or copying is fine
+        assert_eq!(check_upscale(&info, &sized(1080, 1920)), None);
+        assert_eq!(check_upscale(&info, &sized(1280, 720)), None);
+        assert_eq!(check_upscale(&info, &x264("auto")), None);
+        assert_eq!(check_upscale(&info, &VideoSettings { copy_video: true, ..sized(3840, 2160) }), None);
+    }
+
+    #[test]
+    fn bitrate_above_source_fires_for_the_same_family_only() {
+        let info = source();
+        assert_eq!(
+            check_bitrate_above_source(&info, &x264("8M"), &aac("320k")),
+            vec![
+                Advisory::VideoBitrateAboveSource { source_kbps: 4000, target_kbps: 8000 },
+                Advisory::AudioBitrateAboveSource { source_kbps: 128, target_kbps: 320 },
+            ]
+        );
+        let nvenc = VideoSettings { codec: "h264_nvenc".to_string(), ..x264("8M") };
+        assert_eq!(check_bitrate_above_source(&info, &nvenc, &aac("auto")).len(), 1);
+
+        // At most 1.5x, another family, "auto" or a copied stream stays silent
+        assert!(check_bitrate_above_source(&info, &x264("6M"), &aac("192k")).is_empty());
+        let hevc = VideoSettings { codec: "libx265".to_string(), ..x264("20M") };
+        let opus = AudioSettings { codec: "libopus".to_string(), ..aac("320k") };
+        assert!(check_bitrate_above_source(&info, &hevc, &opus).is_empty());
+        assert!(check_bitrate_above_source(&info, &x264("auto"), &aac("auto")).is_empty());
+        let copied = (VideoSettings { copy_video: true, ..x264("8M") }, AudioSettings { copy_audio: true, ..aac("320k") });
+        assert!(check_bitrate_above_source(&info, &copied.0, &copied.1).is_empty());
+    }
+
+    #[test]
+    fn stream_copy_fires_when_nothing_but_the_encode_changes() {
+        let info = source();
+        assert_eq!(
+            check_prefer_stream_copy(&info, &x264("4M"), &aac("128k")),
+            vec![
+                Advisory::VideoStreamCopy { codec: "h264".to_string(), source_kbps: 4000, target_kbps: 4000 },
+                Advisory::AudioStreamCopy { codec: "aac".to_string(), source_kbps: 128, target_kbps: 128 },
+            ]
+        );
+        // The source size spelled out still counts as unchanged
+        assert_eq!(check_prefer_stream_copy(&info, &sized(1920, 1080), &aac("auto")), vec![]);
+        assert_eq!(check_prefer_stream_copy(&info, &VideoSettings { bitrate: "4500k".to_string(), ..sized(1920, 1080) }, &aac("auto")).len(), 1);
+
+        for changed in [
+            VideoSettings { bitrate: "4M".to_string(), ..sized(1280, 720) },
+            VideoSettings { fps: "30".to_string(), ..x264("4M") },
+            VideoSettings { rotation: 90, ..x264("4M") },
+            VideoSettings { flip_horizontal: true, ..x264("4M") },
+            VideoSettings { codec: "libx265".to_string(), ..x264("4M") },
+            x264("6M"),
+            VideoSettings { copy_video: true, ..x264("4M") },
+        ] {
+            assert_eq!(check_prefer_stream_copy(&info, &changed, &aac("auto")), vec![], "{:?}", changed);
+        }
+        for changed in [
+            AudioSettings { channels: "1".to_string(), ..aac("128k") },
+            AudioSettings { sample_rate: "44100".to_string(), ..aac("128k") },
+            AudioSettings { codec: "libmp3lame".to_string(), ..aac("128k") },
+            aac("64k"),
+            AudioSettings { copy_audio: true, ..aac("128k") },
+        ] {
+            assert_eq!(check_prefer_stream_copy(&info, &x264("auto"), &changed), vec![], "{:?}", changed);
+        }
+    }
+
+    #[test]
+    fn audio_bitrate_for_layout_fires_outside_the_sane_range() {
+        let info = source();
+        let kind = |audio: &AudioSettings| match check_audio_bitrate_for_layout(&info, &x264("auto"), audio) {
+            Some(Advisory::AudioBitrateForLayout(advice)) => Some(advice.kind),
+            _ => None,
+        };
+        assert_eq!(kind(&aac("32k")), Some(AdviceKind::BelowFloor));
+        assert_eq!(kind(&aac("1000k")), Some(AdviceKind::AboveRange));
+        // The configured channel count wins over the source's
+        assert_eq!(kind(&AudioSettings { channels: "6".to_string(), ..aac("auto") }), Some(AdviceKind::AutoSurround));
+
+        assert_eq!(kind(&aac("128k")), None);
+        assert_eq!(kind(&aac("auto")), None);
+        assert_eq!(kind(&AudioSettings { copy_audio: true, ..aac("32k") }), None);
+    }
+
+    #[test]
+    fn frame_rate_change_fires_for_an_explicit_different_rate() {
+        let info = source();
+        let fps = |fps: &str| VideoSettings { fps: fps.to_string(), ..x264("auto") };
+        assert_eq!(
+            check_frame_rate_change(&info, &fps("30")),
+            Some(Advisory::FrameRateChange { source: FrameRate { num: 25, den: 1 }, target: FrameRate { num: 30, den: 1 } })
+        );
+        assert_eq!(check_frame_rate_change(&info, &fps("25")), None);
+        assert_eq!(check_frame_rate_change(&info, &fps("50/2")), None);
+        assert_eq!(check_frame_rate_change(&info, &fps("auto")), None);
+        assert_eq!(check_frame_rate_change(&info, &VideoSettings { copy_video: true, ..fps("30") }), None);
+
+        let mut unknown_rate = source();
+        unknown_rate.video_streams[0].avg_frame_rate = None;
+        unknown_rate.video_streams[0].r_frame_rate = None;
+        assert_eq!(check_frame_rate_change(&unknown_rate, &fps("30")), None);
+    }
+
+    #[test]
+    fn evaluate_collects_every_rule_in_order_and_fixes_silence_them() {
+        let info = source();
+        assert_eq!(evaluate(&info, &x264("auto"), &aac("auto")), vec![]);
+
+        let mut video = VideoSettings { fps: "30".to_string(), bitrate: "8M".to_string(), ..sized(3840, 2160) };
+        let mut audio = aac("32k");
+        let advisories = evaluate(&info, &video, &audio);
+        assert_eq!(
+            advisories.iter().map(Advisory::rule).collect::<Vec<_>>(),
+            vec![AdvisoryRule::Upscale, AdvisoryRule::BitrateAboveSource, AdvisoryRule::AudioBitrateForLayout, AdvisoryRule::FrameRateChange]
+        );
+        for advisory in &advisories {
+            apply_fix(&advisory.fix(), &mut video, &mut audio);
+        }
+        // Source size, rate and bitrate leave nothing to encode for
+        assert_eq!(
+            evaluate(&info, &video, &audio),
+            vec![Advisory::VideoStreamCopy { codec: "h264".to_string(), source_kbps: 4000, target_kbps: 4000 }]
+        );
+
+        let mut video = x264("4M");
+        let mut audio = aac("128k");
+        assert_eq!(evaluate(&info, &video, &audio).len(), 2);
+        for advisory in evaluate(&info, &video, &audio) {
+            assert_eq!(advisory.rule(), AdvisoryRule::PreferStreamCopy);
+            apply_fix(&advisory.fix(), &mut video, &mut audio);
+        }
+        assert!(video.copy_video && audio.copy_audio);
+        assert_eq!(evaluate(&info, &video, &audio), vec![]);
+    }
+
+    #[test]
+    fn codec_families_cover_hardware_encoder_names() {
+        assert_eq!(codec_family("libx264"), Some("h264"));
+        assert_eq!(codec_family("HEVC_NVENC"), Some("hevc"));
+        assert_eq!(codec_family("av1_qsv"), Some("av1"));
+        assert_eq!(codec_family("libfdk_aac"), Some("aac"));
+        assert_eq!(codec_family("h264rgb"), None);
+        assert_eq!(codec_family("prores_ks"), None);
+        assert_eq!(bitrate_kbps("8M"), Some(8000));
+        assert_eq!(bitrate_kbps("auto"), None);
+    }
+}
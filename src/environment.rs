@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+use crate::app_state::ProcessingTask;
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+lazy_static! {
+    /// First line of `ffmpeg -version`, keyed by binary path
+    static ref FFMPEG_VERSION_CACHE: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    /// GPU name reported by hardware detection, set once detection finishes
+    static ref DETECTED_GPU: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Environment a task ran in, kept with the task so "it worked last week" reports can be compared.
+/// Only contains values the app itself selected - no host or user names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub app_version: String,
+    pub os: String,
+    pub ffmpeg_path: String,
+    pub ffmpeg_version: String,
+    #[serde(default)]
+    pub video_codec: Option<String>,
+    #[serde(default)]
+    pub hardware_acceleration: bool,
+    #[serde(default)]
+    pub gpu: Option<String>,
+    #[serde(default)]
+    pub speed_priority: bool,
+    /// Safe mode and the subsystems it skipped, when the app was started in it
+    #[serde(default)]
+    pub safe_mode: Option<String>,
+    pub captured_at: String,
+}
+
+impl EnvironmentSnapshot {
+    /// Capture the app, OS and ffmpeg part of the environment
+    pub fn current() -> Self {
+        Self::current_with(bundled_ffmpeg_version)
+    }
+
+    /// Like `current`, with the ffmpeg path and version from `ffmpeg`
+    fn current_with(ffmpeg: impl FnOnce() -> Option<(String, String)>) -> Self {
+        let (ffmpeg_path, ffmpeg_version) = ffmpeg().unwrap_or_default();
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            ffmpeg_path,
+            ffmpeg_version,
+            gpu: DETECTED_GPU.lock().ok().and_then(|gpu| gpu.clone()),
+            safe_mode: crate::safe_mode::describe(),
+            captured_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Capture the environment for a task about to run
+    pub fn capture(task: &ProcessingTask) -> Self {
+        Self::capture_with(task, bundled_ffmpeg_version)
+    }
+
+    fn capture_with(task: &ProcessingTask, ffmpeg: impl FnOnce() -> Option<(String, String)>) -> Self {
+        let video_settings = task.video_settings.as_ref();
+        Self {
+            video_codec: video_settings.map(|s| s.codec.clone()),
+            hardware_acceleration: video_settings.map(|s| s.use_hardware_acceleration).unwrap_or(false),
+            speed_priority: video_settings.map(|s| s.speed_priority).unwrap_or(false),
+            ..Self::current_with(ffmpeg)
+        }
+    }
+
+    /// (label, value) rows for the task panel and copied reports
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("App", self.app_version.clone()),
+            ("OS", self.os.clone()),
+            ("FFmpeg", self.ffmpeg_version.clone()),
+            ("FFmpeg path", self.ffmpeg_path.clone()),
+        ];
+        if let Some(codec) = &self.video_codec {
+            fields.push(("Video codec", codec.clone()));
+        }
+        fields.push(("Hardware acceleration", self.hardware_acceleration.to_string()));
+        if let Some(gpu) = &self.gpu {
+            fields.push(("GPU", gpu.clone()));
+        }
+        fields.push(("Speed priority", self.speed_priority.to_string()));
+        if let Some(safe_mode) = &self.safe_mode {
+            fields.push(("Safe mode", safe_mode.clone()));
+        }
+        fields.push(("Captured", self.captured_at.clone()));
+        fields
+    }
+
+    /// Plain text report of all fields
+    pub fn report(&self) -> String {
+        self.fields().iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Record the GPU found by hardware detection
+pub fn set_detected_gpu(gpu: Option<String>) {
+    if let Ok(mut detected) = DETECTED_GPU.lock() {
+        *detected = gpu;
+    }
+}
+
+/// Path and first `-version` line of the bundled ffmpeg, None when there is none
+fn bundled_ffmpeg_version() -> Option<(String, String)> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    let path = ffmpeg.ffmpeg_path().display().to_string();
+    let version = cached_ffmpeg_version(&path, || {
+        ffmpeg.run_ffmpeg(&["-version"])
+            .map(|output| String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or("").trim().to_string())
+            .unwrap_or_default()
+    });
+    Some((path, version))
+}
+
+fn cached_ffmpeg_version(path: &str, probe: impl FnOnce() -> String) -> String {
+    if let Ok(cache) = FFMPEG_VERSION_CACHE.lock() {
+        if let Some(version) = cache.get(path) {
+            return version.clone();
+        }
+    }
+    let version = probe();
+    if let Ok(mut cache) = FFMPEG_VERSION_CACHE.lock() {
+        cache.insert(path.to_string(), version.clone());
+    }
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::{OperationType, VideoSettings};
+
+    fn snapshot() -> EnvironmentSnapshot {
+        EnvironmentSnapshot {
+            app_version: "1.2.3".to_string(),
+            os: "linux x86_64".to_string(),
+            ffmpeg_path: "/usr/bin/ffmpeg".to_string(),
+            ffmpeg_version: "ffmpeg version 6.1".to_string(),
+            video_codec: Some("libx264".to_string()),
+            hardware_acceleration: true,
+            gpu: Some("Test GPU".to_string()),
+            speed_priority: true,
+            safe_mode: Some("hardware detection".to_string()),
+            captured_at: "2024-05-01 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_json() {
+        let snapshot = snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<EnvironmentSnapshot>(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn snapshots_from_before_the_optional_fields_still_load() {
+        // Written before codec, hardware, GPU, speed and safe-mode fields were recorded
+        let old = r#"{
+            "app_version": "0.9.0",
+            "os": "windows x86_64",
+            "ffmpeg_path": "C:\\ffmpeg\\ffmpeg.exe",
+            "ffmpeg_version": "ffmpeg version 5.1",
+            "captured_at": "2023-11-02 08:30:00"
+        }"#;
+        let loaded: EnvironmentSnapshot = serde_json::from_str(old).unwrap();
+        assert_eq!(loaded, EnvironmentSnapshot {
+            app_version: "0.9.0".to_string(),
+            os: "windows x86_64".to_string(),
+            ffmpeg_path: "C:\\ffmpeg\\ffmpeg.exe".to_string(),
+            ffmpeg_version: "ffmpeg version 5.1".to_string(),
+            captured_at: "2023-11-02 08:30:00".to_string(),
+            ..Default::default()
+        });
+
+        // Fields this version does not know about are ignored
+        let newer = old.replace("\"os\"", "\"locale\": \"de\", \"os\"");
+        assert_eq!(serde_json::from_str::<EnvironmentSnapshot>(&newer).unwrap(), loaded);
+
+        // The core fields are not invented when missing
+        assert!(serde_json::from_str::<EnvironmentSnapshot>(r#"{"app_version": "0.9.0"}"#).is_err());
+    }
+
+    #[test]
+    fn saved_queues_do_not_carry_the_snapshot() {
+        let mut task = ProcessingTask::new(OperationType::VideoConvert, vec!["in.mp4".to_string()], "out.mp4".to_string());
+        task.environment = Some(snapshot());
+        let json = serde_json::to_value(&task).unwrap();
+        assert!(json.get("environment").is_none());
+        let restored: ProcessingTask = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.environment, None);
+    }
+
+    #[test]
+    fn capture_records_the_task_settings() {
+        let fake_ffmpeg = || Some(("/fake/ffmpeg".to_string(), "ffmpeg version 9.9".to_string()));
+        let mut task = ProcessingTask::new(OperationType::VideoConvert, vec!["in.mp4".to_string()], "out.mp4".to_string());
+        let captured = EnvironmentSnapshot::capture_with(&task, fake_ffmpeg);
+        assert_eq!((captured.video_codec, captured.hardware_acceleration, captured.speed_priority), (None, false, false));
+
+        task.video_settings = Some(VideoSettings {
+            codec: "hevc_nvenc".to_string(),
+            use_hardware_acceleration: true,
+            speed_priority: true,
+            ..Default::default()
+        });
+        let captured = EnvironmentSnapshot::capture_with(&task, fake_ffmpeg);
+        assert_eq!(captured.video_codec.as_deref(), Some("hevc_nvenc"));
+        assert!(captured.hardware_acceleration && captured.speed_priority);
+        assert_eq!(captured.app_version, env!("CARGO_PKG_VERSION"));
+        assert!(captured.os.starts_with(std::env::consts::OS));
+        assert_eq!((captured.ffmpeg_path.as_str(), captured.ffmpeg_version.as_str()), ("/fake/ffmpeg", "ffmpeg version 9.9"));
+
+        // Without an ffmpeg the snapshot is still taken, with those fields empty
+        let captured = EnvironmentSnapshot::capture_with(&task, || None);
+        assert_eq!((captured.ffmpeg_path.as_str(), captured.ffmpeg_version.as_str()), ("", ""));
+        assert_eq!(captured.video_codec.as_deref(), Some("hevc_nvenc"));
+    }
+
+    #[test]
+    fn report_lists_optional_rows_only_when_set() {
+        let report = snapshot().report();
+        assert_eq!(report, "App: 1.2.3\nOS: linux x86_64\nFFmpeg: ffmpeg version 6.1\nFFmpeg path: /usr/bin/ffmpeg\n\
+            Video codec: libx264\nHardware acceleration: true\nGPU: Test GPU\nSpeed priority: true\n\
+            Safe mode: hardware detection\nCaptured: 2024-05-01 12:00:00");
+
+        let minimal = EnvironmentSnapshot { video_codec: None, gpu: None, safe_mode: None, ..snapshot() };
+        let labels: Vec<&str> = minimal.fields().iter().map(|(label, _)| *label).collect();
+        assert_eq!(labels, ["App", "OS", "FFmpeg", "FFmpeg path", "Hardware acceleration", "Speed priority", "Captured"]);
+    }
+
+    #[test]
+    fn ffmpeg_version_is_probed_once_per_binary() {
+        let path = format!("/test/{}/ffmpeg", uuid::Uuid::new_v4());
+        let mut probes = 0;
+        assert_eq!(cached_ffmpeg_version(&path, || { probes += 1; "ffmpeg version 7.0".to_string() }), "ffmpeg version 7.0");
+        assert_eq!(cached_ffmpeg_version(&path, || { probes += 1; "other".to_string() }), "ffmpeg version 7.0");
+        assert_eq!(probes, 1);
+    }
+}
@@ -54,6 +54,14 @@ pub struct MediaInfo {
     pub duration: f64,
     pub video_streams: Vec<VideoStreamInfo>,
     pub audio_streams: Vec<AudioStreamInfo>,
+    #[serde(default)]
+    pub format_name: String,
+    /// Transport stream programs; empty for ordinary containers
+    #[serde(default)]
+    pub programs: Vec<crate::transport_stream::ProgramInfo>,
+    /// Overall container bitrate in bits/s
+    #[serde(default)]
+    pub bit_rate: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -63,6 +71,45 @@ pub struct VideoStreamInfo {
     pub width: u32,
     pub height: u32,
     pub fps: f64,
+    #[serde(default)]
+    pub color_range: Option<String>,
+    #[serde(default)]
+    pub color_space: Option<String>,
+    /// Stream bitrate in bits/s; Matroska and WebM usually only report the container bitrate
+    #[serde(default)]
+    pub bit_rate: Option<u64>,
+    #[serde(default)]
+    pub pix_fmt: Option<String>,
+    /// Exact average and base (r_frame_rate) rates; they differ for variable frame rate sources
+    #[serde(default)]
+    pub avg_frame_rate: Option<crate::frame_rate::FrameRate>,
+    #[serde(default)]
+    pub r_frame_rate: Option<crate::frame_rate::FrameRate>,
+    /// Display rotation in degrees from the display matrix or rotate tag (phone recordings)
+    #[serde(default)]
+    pub rotation: i32,
+}
+
+impl VideoStreamInfo {
+    /// Size as played back: ffmpeg auto-rotates, so a quarter turn swaps width and height
+    pub fn display_size(&self) -> (u32, u32) {
+        if self.rotation.rem_euclid(180) == 90 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+}
+
+/// Rotation of a probed video stream; newer ffprobe reports a display matrix side data entry,
+/// older versions a "rotate" tag
+fn stream_rotation(stream: &serde_json::Value) -> i32 {
+    let from_side_data = stream["side_data_list"].as_array()
+        .and_then(|list| list.iter().find_map(|side_data| side_data["rotation"].as_f64()));
+    let from_tag = stream["tags"]["rotate"].as_str().and_then(|rotate| rotate.trim().parse::<f64>().ok());
+    from_side_data.or(from_tag)
+        .map(|degrees| (degrees.round() as i32).rem_euclid(360))
+        .unwrap_or(0)
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -71,6 +118,8 @@ pub struct AudioStreamInfo {
     pub codec: String,
     pub sample_rate: u32,
     pub channels: u32,
+    #[serde(default)]
+    pub bit_rate: Option<u64>,
 }
 
 pub struct FFmpegWorker {
@@ -248,6 +297,7 @@ impl FFmpegWorkerImpl {
             "-print_format", "json",
             "-show_format",
             "-show_streams",
+            "-show_programs",
             path
         ];
         
@@ -262,7 +312,19 @@ impl FFmpegWorkerImpl {
             .ok_or_else(|| anyhow::anyhow!("No format information found"))?;
         
         let duration_str = format["duration"].as_str().unwrap_or("0.0");
-        let duration = duration_str.parse::<f64>().unwrap_or(0.0);
+        let mut duration = duration_str.parse::<f64>().unwrap_or(0.0);
+        let format_name = format.get("format_name").and_then(|f| f.as_str()).unwrap_or("").to_string();
+        // ffprobe prints bitrates as strings
+        let parse_bit_rate = |value: &serde_json::Value| value.as_str().and_then(|s| s.parse::<u64>().ok()).filter(|rate| *rate > 0);
+        let bit_rate = format.get("bit_rate").and_then(parse_bit_rate);
+        
+        // TS/VOB container durations are often estimated from the bitrate or broken by timestamp resets
+        if crate::transport_stream::is_program_stream(path, &format_name) {
+            duration = crate::transport_stream::reconcile_duration(
+                duration,
+                crate::transport_stream::analyze_stream_duration(path),
+            );
+        }
         
         let streams = json["streams"].as_array()
             .ok_or_else(|| anyhow::anyhow!("No streams found"))?;
@@ -277,24 +339,24 @@ impl FFmpegWorkerImpl {
             if codec_type == "video" {
                 let width = stream["width"].as_u64().unwrap_or(0) as u32;
                 let height = stream["height"].as_u64().unwrap_or(0) as u32;
-                let fps = stream["avg_frame_rate"].as_str()
-                    .and_then(|fps_str| {
-                        let parts: Vec<&str> = fps_str.split('/').collect();
-                        if parts.len() == 2 {
-                            let num = parts[0].parse::<f64>().unwrap_or(0.0);
-                            let den = parts[1].parse::<f64>().unwrap_or(1.0);
-                            if den != 0.0 { Some(num / den) } else { None }
-                        } else {
-                            None
-                        }
-                    }).unwrap_or(0.0);
+                let avg_frame_rate = stream["avg_frame_rate"].as_str().and_then(crate::frame_rate::FrameRate::parse);
+                let r_frame_rate = stream["r_frame_rate"].as_str().and_then(crate::frame_rate::FrameRate::parse);
+                let fps = avg_frame_rate.or(r_frame_rate).map(|rate| rate.as_f64()).unwrap_or(0.0);
                 
+                let color = crate::color_conversion::from_stream_json(stream);
                 video_streams.push(VideoStreamInfo {
                     index: i as u32,
                     codec: codec_name,
                     width,
                     height,
                     fps,
+                    color_range: color.range,
+                    color_space: color.matrix,
+                    bit_rate: parse_bit_rate(&stream["bit_rate"]),
+                    pix_fmt: stream["pix_fmt"].as_str().map(|pix_fmt| pix_fmt.to_string()),
+                    avg_frame_rate,
+                    r_frame_rate,
+                    rotation: stream_rotation(stream),
                 });
             } else if codec_type == "audio" {
                 let sample_rate = stream["sample_rate"].as_str()
@@ -307,6 +369,7 @@ impl FFmpegWorkerImpl {
                     codec: codec_name,
                     sample_rate,
                     channels,
+                    bit_rate: parse_bit_rate(&stream["bit_rate"]),
                 });
             }
         }
@@ -320,6 +383,9 @@ impl FFmpegWorkerImpl {
             duration,
             video_streams,
             audio_streams,
+            programs: crate::transport_stream::parse_programs(&json),
+            format_name,
+            bit_rate,
         })
     }
 
@@ -0,0 +1,238 @@
+use std::fs::{File, FileTimes, OpenOptions};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+/// Coarsest timestamp resolution of common file systems (FAT stores modification times in 2 s steps)
+const TIME_GRANULARITY: Duration = Duration::from_secs(2);
+/// Containers whose muxers write a `creation_time` tag
+const CREATION_TIME_CONTAINERS: &[&str] = &["mp4", "m4v", "m4a", "mov", "3gp", "mkv", "mka", "webm"];
+
+/// Post-completion copy of the source's timestamps onto a task's output
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct TaskTimestamps {
+    /// Copy the source's modified time, and its created time where the platform allows, onto the output
+    pub copy_times: bool,
+    /// Write the recording date into the output container's `creation_time`
+    pub write_creation_time: bool,
+    /// What could not be applied; the task still completes
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+impl TaskTimestamps {
+    pub fn new(copy_times: bool, write_creation_time: bool) -> Self {
+        Self { copy_times, write_creation_time, warnings: Vec::new() }
+    }
+
+    pub fn requested(&self) -> bool {
+        self.copy_times || self.write_creation_time
+    }
+}
+
+/// Modified, accessed and created time of a file; accessed and created are None where the file
+/// system does not record them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SourceTimes {
+    pub modified: SystemTime,
+    pub accessed: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+}
+
+pub fn read_times(path: &Path) -> std::io::Result<SourceTimes> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(SourceTimes { modified: metadata.modified()?, accessed: metadata.accessed().ok(), created: metadata.created().ok() })
+}
+
+/// Set `times` on `path`. The created time is set on Windows and macOS; other Unix systems have
+/// no API to change it, so there only the modified time is copied.
+pub fn write_times(path: &Path, times: &SourceTimes) -> std::io::Result<()> {
+    let file = OpenOptions::new().write(true).open(path)?;
+    let file_times = FileTimes::new().set_modified(times.modified).set_accessed(times.accessed.unwrap_or(times.modified));
+    #[cfg(target_os = "windows")]
+    let file_times = {
+        use std::os::windows::fs::FileTimesExt;
+        match times.created {
+            Some(created) => file_times.set_created(created),
+            None => file_times,
+        }
+    };
+    #[cfg(target_os = "macos")]
+    let file_times = {
+        use std::os::macos::fs::FileTimesExt;
+        match times.created {
+            Some(created) => file_times.set_created(created),
+            None => file_times,
+        }
+    };
+    file.set_times(file_times)
+}
+
+/// Copy the modified and accessed (and where possible created) time of `source` onto `target`. Returns a
+/// warning when the times could not be set or the target's file system rounded them by more than
+/// its granularity.
+pub fn copy_times(source: &Path, target: &Path) -> Result<(), String> {
+    let times = read_times(source).map_err(|e| format!("cannot read the times of {}: {}", source.display(), e))?;
+    write_times(target, &times).map_err(|e| format!("cannot set the times of {}: {}", target.display(), e))?;
+    let written = File::open(target)
+        .and_then(|file| file.metadata())
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| e.to_string())?;
+    let difference = written.duration_since(times.modified).or_else(|_| times.modified.duration_since(written)).unwrap_or_default();
+    if difference > TIME_GRANULARITY {
+        return Err(format!("the file system stored the modified time {} s off", difference.as_secs()));
+    }
+    Ok(())
+}
+
+/// Recording date of a source: the container or first stream `creation_time` tag, or the file's
+/// modified time when it has none. RFC 3339 in UTC, as ffmpeg writes it.
+pub fn recording_date(source: &Path) -> Option<String> {
+    let from_metadata = get_bundled_ffmpeg().ok()
+        .and_then(|ffmpeg| ffmpeg.run_ffprobe(&[
+            "-v", "error",
+            "-show_entries", "format_tags=creation_time:stream_tags=creation_time",
+            "-of", "json",
+            &source.display().to_string(),
+        ]).ok())
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice::<serde_json::Value>(&output.stdout).ok())
+        .and_then(|json| {
+            let format_tag = json["format"]["tags"]["creation_time"].as_str().map(str::to_string);
+            format_tag.or_else(|| json["streams"].as_array()?.iter()
+                .find_map(|stream| stream["tags"]["creation_time"].as_str().map(str::to_string)))
+        })
+        .and_then(|tag| chrono::DateTime::parse_from_rfc3339(tag.trim()).ok())
+        // Cameras without a clock write 1970 or 1904 epochs
+        .filter(|date| date.timestamp() > 0)
+        .map(|date| date.with_timezone(&chrono::Utc));
+    let date = from_metadata.or_else(|| read_times(source).ok().map(|times| chrono::DateTime::<chrono::Utc>::from(times.modified)))?;
+    Some(date.to_rfc3339_opts(chrono::SecondsFormat::Micros, true))
+}
+
+/// Rewrite `output` with `creation_time` set, copying all streams; the original stays in place
+/// when the remux fails
+pub fn write_creation_time(output: &Path, date: &str) -> Result<(), String> {
+    let extension = output.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase();
+    if !CREATION_TIME_CONTAINERS.contains(&extension.as_str()) {
+        return Err(format!("{} files have no creation_time tag", extension));
+    }
+    let stem = output.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = output.with_file_name(format!("{}.creation_time.{}", stem, extension));
+    let ffmpeg = get_bundled_ffmpeg().map_err(|e| e.to_string())?;
+    let metadata = format!("creation_time={}", date);
+    let result = ffmpeg.run_ffmpeg(&[
+        "-v", "error",
+        "-i", &output.display().to_string(),
+        "-map", "0", "-map_metadata", "0", "-c", "copy",
+        "-metadata", &metadata,
+        "-y", &temp.display().to_string(),
+    ]).map_err(|e| e.to_string());
+    match result {
+        Ok(run) if run.status.success() => std::fs::rename(&temp, output).map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            e.to_string()
+        }),
+        Ok(run) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(String::from_utf8_lossy(&run.stderr).lines().next().unwrap_or("ffmpeg failed").to_string())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+/// Apply the requested timestamp options from `source` to `output`; returns the warnings. The
+/// container is rewritten first, since that changes the output's own times.
+pub fn apply(source: &Path, output: &Path, options: &TaskTimestamps) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if options.write_creation_time {
+        match recording_date(source) {
+            Some(date) => {
+                if let Err(e) = write_creation_time(output, &date) {
+                    warnings.push(format!("creation_time not written to {}: {}", output.display(), e));
+                }
+            }
+            None => warnings.push(format!("no recording date found for {}", source.display())),
+        }
+    }
+    if options.copy_times {
+        if let Err(e) = copy_times(source, output) {
+            warnings.push(e);
+        }
+    }
+    for warning in &warnings {
+        log_warn!("Timestamps: {}", warning);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_files() -> (std::path::PathBuf, std::path::PathBuf, std::path::PathBuf) {
+        let dir = crate::test_support::temp_dir("file_times");
+        let (source, target) = (dir.join("source.mp4"), dir.join("target.mp4"));
+        std::fs::write(&source, b"source").unwrap();
+        std::fs::write(&target, b"target").unwrap();
+        (dir, source, target)
+    }
+
+    #[test]
+    fn copied_times_read_back_from_the_target() {
+        let (dir, source, target) = temp_files();
+        // Whole seconds survive every file system's resolution
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let accessed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_650_000_000);
+        OpenOptions::new().write(true).open(&source).unwrap()
+            .set_times(FileTimes::new().set_modified(modified).set_accessed(accessed))
+            .unwrap();
+
+        assert_eq!(copy_times(&source, &target), Ok(()));
+        let copied = read_times(&target).unwrap();
+        assert_eq!(copied.modified, modified);
+        // Not every platform or mount keeps access times
+        if let Some(copied_accessed) = copied.accessed {
+            assert_eq!(copied_accessed, accessed);
+        }
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        assert_eq!(copied.created, read_times(&source).unwrap().created);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_access_time_falls_back_to_the_modified_time() {
+        let (dir, _, target) = temp_files();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        write_times(&target, &SourceTimes { modified, accessed: None, created: None }).unwrap();
+        let written = read_times(&target).unwrap();
+        assert_eq!(written.modified, modified);
+        if let Some(accessed) = written.accessed {
+            assert_eq!(accessed, modified);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copying_reports_unreadable_sources_and_targets() {
+        let (dir, source, target) = temp_files();
+        let missing = dir.join("missing.mp4");
+        assert!(copy_times(&missing, &target).unwrap_err().starts_with("cannot read the times of"));
+        assert!(copy_times(&source, &missing).unwrap_err().starts_with("cannot set the times of"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn creation_time_needs_a_container_with_the_tag() {
+        let (dir, _, _) = temp_files();
+        let avi = dir.join("clip.avi");
+        assert_eq!(write_creation_time(&avi, "2024-01-01T00:00:00.000000Z"), Err("avi files have no creation_time tag".to_string()));
+        assert!(!TaskTimestamps::default().requested());
+        assert!(TaskTimestamps::new(false, true).requested());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::app_state::VideoSettings;
+use crate::language::Translations;
+
+/// nlmeans on inputs this large (4K UHD) takes many times realtime on the CPU
+const HEAVY_FILTER_MIN_PIXELS: u64 = 3840 * 2160;
+
+/// One filter of the ordered VideoFilter stack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "filter", rename_all = "snake_case")]
+pub enum FilterStage {
+    Deinterlace { send_field: bool },
+    Hqdn3d { luma_spatial: f32, chroma_spatial: f32, luma_temporal: f32, chroma_temporal: f32 },
+    Nlmeans { strength: f32, patch_size: u32, research_size: u32 },
+    Unsharp { size: u32, amount: f32 },
+    Deband { threshold: f32, range: u32 },
+    Eq { brightness: f32, contrast: f32, saturation: f32 },
+}
+
+impl FilterStage {
+    /// Every stage with default parameters, in the order offered by the "add filter" menu
+    pub fn all() -> Vec<FilterStage> {
+        vec![
+            FilterStage::Deinterlace { send_field: true },
+            FilterStage::Hqdn3d { luma_spatial: 4.0, chroma_spatial: 3.0, luma_temporal: 6.0, chroma_temporal: 4.5 },
+            FilterStage::Nlmeans { strength: 1.0, patch_size: 7, research_size: 15 },
+            FilterStage::Unsharp { size: 5, amount: 1.0 },
+            FilterStage::Deband { threshold: 0.02, range: 16 },
+            FilterStage::Eq { brightness: 0.0, contrast: 1.0, saturation: 1.0 },
+        ]
+    }
+
+    /// Short name used in validation errors
+    pub fn key(&self) -> &'static str {
+        match self {
+            FilterStage::Deinterlace { .. } => "deinterlace",
+            FilterStage::Hqdn3d { .. } => "hqdn3d",
+            FilterStage::Nlmeans { .. } => "nlmeans",
+            FilterStage::Unsharp { .. } => "unsharp",
+            FilterStage::Deband { .. } => "deband",
+            FilterStage::Eq { .. } => "eq",
+        }
+    }
+
+    pub fn is_cpu_heavy(&self) -> bool {
+        matches!(self, FilterStage::Nlmeans { .. })
+    }
+
+    /// The ffmpeg filter expression for this stage
+    pub fn to_filter(&self) -> String {
+        match self {
+            FilterStage::Deinterlace { send_field } => format!("yadif={}", if *send_field { 1 } else { 0 }),
+            FilterStage::Hqdn3d { luma_spatial, chroma_spatial, luma_temporal, chroma_temporal } => {
+                format!("hqdn3d={}:{}:{}:{}", luma_spatial, chroma_spatial, luma_temporal, chroma_temporal)
+            }
+            FilterStage::Nlmeans { strength, patch_size, research_size } => {
+                format!("nlmeans=s={}:p={}:r={}", strength, patch_size, research_size)
+            }
+            FilterStage::Unsharp { size, amount } => format!("unsharp={}:{}:{}", size, size, amount),
+            FilterStage::Deband { threshold, range } => {
+                format!("deband=1thr={t}:2thr={t}:3thr={t}:range={}", range, t = threshold)
+            }
+            FilterStage::Eq { brightness, contrast, saturation } => {
+                format!("eq=brightness={}:contrast={}:saturation={}", brightness, contrast, saturation)
+            }
+        }
+    }
+
+    /// Check parameters against the ranges ffmpeg accepts
+    pub fn validate(&self) -> Result<()> {
+        let in_range = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(anyhow!("{} {} must be between {} and {}, got {}", self.key(), name, min, max, value))
+            }
+        };
+        let odd_in_range = |name: &str, value: u32, min: u32, max: u32| {
+            if (min..=max).contains(&value) && value % 2 == 1 {
+                Ok(())
+            } else {
+                Err(anyhow!("{} {} must be an odd number between {} and {}, got {}", self.key(), name, min, max, value))
+            }
+        };
+
+        match self {
+            FilterStage::Deinterlace { .. } => Ok(()),
+            FilterStage::Hqdn3d { luma_spatial, chroma_spatial, luma_temporal, chroma_temporal } => {
+                in_range("luma_spatial", *luma_spatial, 0.0, 50.0)?;
+                in_range("chroma_spatial", *chroma_spatial, 0.0, 50.0)?;
+                in_range("luma_temporal", *luma_temporal, 0.0, 50.0)?;
+                in_range("chroma_temporal", *chroma_temporal, 0.0, 50.0)
+            }
+            FilterStage::Nlmeans { strength, patch_size, research_size } => {
+                in_range("strength", *strength, 1.0, 30.0)?;
+                odd_in_range("patch_size", *patch_size, 1, 99)?;
+                odd_in_range("research_size", *research_size, 1, 99)
+            }
+            FilterStage::Unsharp { size, amount } => {
+                odd_in_range("size", *size, 3, 23)?;
+                in_range("amount", *amount, -2.0, 5.0)
+            }
+            FilterStage::Deband { threshold, range } => {
+                in_range("threshold", *threshold, 0.00003, 0.5)?;
+                in_range("range", *range as f32, 1.0, 64.0)
+            }
+            FilterStage::Eq { brightness, contrast, saturation } => {
+                in_range("brightness", *brightness, -1.0, 1.0)?;
+                in_range("contrast", *contrast, 0.0, 3.0)?;
+                in_range("saturation", *saturation, 0.0, 3.0)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterStackEntry {
+    pub enabled: bool,
+    pub stage: FilterStage,
+}
+
+impl FilterStackEntry {
+    pub fn new(stage: FilterStage) -> Self {
+        Self { enabled: true, stage }
+    }
+}
+
+/// Whether the old denoise/deinterlace/color fields request any filtering
+pub fn has_legacy_filters(settings: &VideoSettings) -> bool {
+    settings.denoise || settings.deinterlace
+        || settings.brightness != 0.0 || settings.contrast != 1.0 || settings.saturation != 1.0
+}
+
+/// Stack equivalent to the old fixed filter chain (denoise, deinterlace, then color)
+pub fn from_legacy(settings: &VideoSettings) -> Vec<FilterStackEntry> {
+    let mut stack = Vec::new();
+    if settings.denoise {
+        stack.push(FilterStackEntry::new(FilterStage::Hqdn3d {
+            luma_spatial: 4.0, chroma_spatial: 3.0, luma_temporal: 6.0, chroma_temporal: 4.5,
+        }));
+    }
+    if settings.deinterlace {
+        stack.push(FilterStackEntry::new(FilterStage::Deinterlace { send_field: true }));
+    }
+    if settings.brightness != 0.0 || settings.contrast != 1.0 || settings.saturation != 1.0 {
+        stack.push(FilterStackEntry::new(FilterStage::Eq {
+            brightness: settings.brightness,
+            contrast: settings.contrast,
+            saturation: settings.saturation,
+        }));
+    }
+    stack
+}
+
+/// The stack the VideoFilter operation runs: the explicit stack, or the legacy fields for old projects
+pub fn effective_stack(settings: &VideoSettings) -> Vec<FilterStackEntry> {
+    if settings.filter_stack.is_empty() {
+        from_legacy(settings)
+    } else {
+        settings.filter_stack.clone()
+    }
+}
+
+/// Validate the enabled stages and join them into a -vf chain, in stack order
+pub fn build_filtergraph(stack: &[FilterStackEntry]) -> Result<String> {
+    let mut filters = Vec::new();
+    for entry in stack.iter().filter(|entry| entry.enabled) {
+        entry.stage.validate()?;
+        filters.push(entry.stage.to_filter());
+    }
+    Ok(filters.join(","))
+}
+
+/// True when an enabled CPU-heavy filter would run on a 4K or larger input
+pub fn is_heavy_for_resolution(stack: &[FilterStackEntry], resolution: Option<(u32, u32)>) -> bool {
+    let large_input = resolution
+        .map(|(width, height)| width as u64 * height as u64 >= HEAVY_FILTER_MIN_PIXELS)
+        .unwrap_or(false);
+    large_input && stack.iter().any(|entry| entry.enabled && entry.stage.is_cpu_heavy())
+}
+
+/// Ordered stack editor: enable, reorder, remove, tune and add filters. Returns true when the stack changed.
+pub fn show_editor(ui: &mut egui::Ui, stack: &mut Vec<FilterStackEntry>, translations: &Translations, input_resolution: Option<(u32, u32)>) -> bool {
+    let mut changed = false;
+    let mut move_up = None;
+    let mut move_down = None;
+    let mut remove = None;
+    let count = stack.len();
+
+    if stack.is_empty() {
+        ui.label(egui::RichText::new(translations.filter_stack_empty()).weak());
+    }
+
+    for (index, entry) in stack.iter_mut().enumerate() {
+        ui.push_id(("filter_stack_entry", index), |ui| {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}.", index + 1));
+                    changed |= ui.checkbox(&mut entry.enabled, translations.filter_stage_name(&entry.stage)).changed();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(index);
+                        }
+                        if ui.add_enabled(index + 1 < count, egui::Button::new("⬇").small()).clicked() {
+                            move_down = Some(index);
+                        }
+                        if ui.add_enabled(index > 0, egui::Button::new("⬆").small()).clicked() {
+                            move_up = Some(index);
+                        }
+                    });
+                });
+                ui.add_enabled_ui(entry.enabled, |ui| {
+                    changed |= show_stage_parameters(ui, &mut entry.stage, translations);
+                });
+            });
+        });
+    }
+
+    if let Some(index) = move_up {
+        stack.swap(index, index - 1);
+        changed = true;
+    }
+    if let Some(index) = move_down {
+        stack.swap(index, index + 1);
+        changed = true;
+    }
+    if let Some(index) = remove {
+        stack.remove(index);
+        changed = true;
+    }
+
+    ui.menu_button(translations.add_filter(), |ui| {
+        for stage in FilterStage::all() {
+            if ui.button(translations.filter_stage_name(&stage)).clicked() {
+                stack.push(FilterStackEntry::new(stage));
+                changed = true;
+                ui.close_menu();
+            }
+        }
+    });
+
+    ui.add_space(4.0);
+    ui.label(translations.effective_filtergraph());
+    match build_filtergraph(stack) {
+        Ok(graph) if graph.is_empty() => {
+            ui.label(egui::RichText::new("-").monospace().weak());
+        }
+        Ok(graph) => {
+            let mut graph = graph;
+            ui.add(egui::TextEdit::multiline(&mut graph).code_editor().desired_rows(2).interactive(false));
+        }
+        Err(e) => {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+        }
+    }
+
+    if is_heavy_for_resolution(stack, input_resolution) {
+        ui.colored_label(egui::Color32::from_rgb(255, 180, 0), translations.heavy_filter_warning());
+    }
+
+    changed
+}
+
+fn show_stage_parameters(ui: &mut egui::Ui, stage: &mut FilterStage, translations: &Translations) -> bool {
+    let mut changed = false;
+    let slider = |ui: &mut egui::Ui, label: &str, value: &mut f32, range: std::ops::RangeInclusive<f32>| {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::Slider::new(value, range)).changed()
+        }).inner
+    };
+
+    match stage {
+        FilterStage::Deinterlace { send_field } => {
+            let mut field = *send_field;
+            ui.horizontal(|ui| {
+                ui.label(translations.filter_param_mode());
+                ui.radio_value(&mut field, false, translations.filter_param_send_frame());
+                ui.radio_value(&mut field, true, translations.filter_param_send_field());
+            });
+            if field != *send_field {
+                *send_field = field;
+                changed = true;
+            }
+        }
+        FilterStage::Hqdn3d { luma_spatial, chroma_spatial, luma_temporal, chroma_temporal } => {
+            changed |= slider(ui, translations.filter_param_luma_spatial(), luma_spatial, 0.0..=20.0);
+            changed |= slider(ui, translations.filter_param_chroma_spatial(), chroma_spatial, 0.0..=20.0);
+            changed |= slider(ui, translations.filter_param_luma_temporal(), luma_temporal, 0.0..=20.0);
+            changed |= slider(ui, translations.filter_param_chroma_temporal(), chroma_temporal, 0.0..=20.0);
+        }
+        FilterStage::Nlmeans { strength, patch_size, research_size } => {
+            changed |= slider(ui, translations.filter_param_strength(), strength, 1.0..=30.0);
+            changed |= odd_slider(ui, translations.filter_param_patch_size(), patch_size, 3..=15);
+            changed |= odd_slider(ui, translations.filter_param_research_size(), research_size, 5..=31);
+        }
+        FilterStage::Unsharp { size, amount } => {
+            changed |= odd_slider(ui, translations.filter_param_size(), size, 3..=23);
+            changed |= slider(ui, translations.filter_param_amount(), amount, -2.0..=5.0);
+        }
+        FilterStage::Deband { threshold, range } => {
+            changed |= slider(ui, translations.filter_param_threshold(), threshold, 0.003..=0.5);
+            ui.horizontal(|ui| {
+                ui.label(translations.filter_param_range());
+                changed |= ui.add(egui::Slider::new(range, 1..=64)).changed();
+            });
+        }
+        FilterStage::Eq { brightness, contrast, saturation } => {
+            changed |= slider(ui, translations.filter_param_brightness(), brightness, -1.0..=1.0);
+            changed |= slider(ui, translations.filter_param_contrast(), contrast, 0.0..=3.0);
+            changed |= slider(ui, translations.filter_param_saturation(), saturation, 0.0..=3.0);
+        }
+    }
+    changed
+}
+
+/// Slider for kernel sizes that must stay odd
+fn odd_slider(ui: &mut egui::Ui, label: &str, value: &mut u32, range: std::ops::RangeInclusive<u32>) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let response = ui.add(egui::Slider::new(value, range));
+        if value.is_multiple_of(2) {
+            *value += 1;
+        }
+        response.changed()
+    }).inner
+}
@@ -0,0 +1,368 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use crate::app_state::{EncoderPolicy, OperationType, ProcessingTask, TaskStatus, UserPreferences, VideoSettings};
+use crate::codec_manager::CodecManager;
+use crate::comprehensive_codec_registry::ComprehensiveCodecRegistry;
+use crate::hardware_detector::HardwareInfo;
+use crate::language::{Language, Translations};
+
+pub const PAGE_COUNT: usize = 4;
+/// Length of the generated self-test clip in seconds
+const SELF_TEST_SECONDS: u32 = 5;
+/// H.264 hardware encoders in the order the self-test tries them
+const HARDWARE_H264_ENCODERS: [&str; 5] = ["h264_nvenc", "h264_qsv", "h264_amf", "h264_videotoolbox", "h264_vaapi"];
+
+/// Hardware encoder the speed-first recommendation picks for `container`, if one is available and
+/// the container can hold it
+pub fn hardware_codec_for(container: &str, quality_preset: &str, available_hardware: &[String]) -> Option<String> {
+    let (codec, _) = CodecManager::get_smart_encoder_recommendation(container, quality_preset, true, available_hardware, false);
+    if available_hardware.contains(&codec) && ComprehensiveCodecRegistry::is_compatible(&codec, container) {
+        Some(codec)
+    } else {
+        None
+    }
+}
+
+/// Encoder the self-test runs: the hardware H.264 encoder the policy would pick, otherwise libx264
+pub fn self_test_codec(policy: EncoderPolicy, available_hardware: &[String]) -> String {
+    if policy == EncoderPolicy::PreferQuality {
+        return "libx264".to_string();
+    }
+    HARDWARE_H264_ENCODERS.iter()
+        .find(|codec| available_hardware.iter().any(|available| available == *codec))
+        .map(|codec| codec.to_string())
+        .unwrap_or_else(|| "libx264".to_string())
+}
+
+/// Output name from the filename template; unknown placeholders are left as written
+pub fn render_filename_template(template: &str, input_stem: &str, timestamp: &str) -> String {
+    let template = if template.trim().is_empty() { crate::app_state::DEFAULT_FILENAME_TEMPLATE } else { template };
+    template.replace("{name}", input_stem).replace("{timestamp}", timestamp)
+}
+
+pub enum SelfTest {
+    NotRun,
+    /// Rendering the testsrc clip into the sandbox
+    Preparing(Receiver<Result<String, String>>),
+    /// Encoding the clip through the task queue
+    Running { task_id: usize, codec: String },
+    Passed { codec: String },
+    /// Error text plus suggested fixes
+    Failed { error: String, hints: Vec<SelfTestHint> },
+}
+
+/// Fix suggested for a failed self-test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelfTestHint {
+    InstallFfmpeg,
+    EncoderMissing,
+    Driver,
+    OutputDir,
+    Generic,
+}
+
+/// Map an FFmpeg error to the fixes most likely to help
+pub fn suggested_fixes(error: &str) -> Vec<SelfTestHint> {
+    let lower = error.to_lowercase();
+    let mut hints = Vec::new();
+    if lower.contains("not found") && lower.contains("ffmpeg") {
+        hints.push(SelfTestHint::InstallFfmpeg);
+    }
+    if lower.contains("unknown encoder") || lower.contains("encoder not found") {
+        hints.push(SelfTestHint::EncoderMissing);
+    }
+    if ["nvenc", "nvcuda", "cuda", "qsv", "mfx", "amf", "vaapi", "videotoolbox", "device"].iter().any(|k| lower.contains(k)) {
+        hints.push(SelfTestHint::Driver);
+    }
+    if lower.contains("permission denied") || lower.contains("read-only") {
+        hints.push(SelfTestHint::OutputDir);
+    }
+    if hints.is_empty() {
+        hints.push(SelfTestHint::Generic);
+    }
+    hints
+}
+
+/// Render a short lossless testsrc clip into `sandbox`, off the UI thread
+fn prepare_source(sandbox: PathBuf) -> Receiver<Result<String, String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let result = (|| -> Result<String, String> {
+            std::fs::create_dir_all(&sandbox).map_err(|e| e.to_string())?;
+            let source = sandbox.join("testsrc.mkv");
+            let output = crate::bundled_ffmpeg::get_bundled_ffmpeg()
+                .map_err(|e| e.to_string())?
+                .command()
+                .args(["-hide_banner", "-nostdin", "-y", "-f", "lavfi", "-i"])
+                .arg(format!("testsrc=duration={}:size=1280x720:rate=30", SELF_TEST_SECONDS))
+                .args(["-c:v", "ffv1"])
+                .arg(&source)
+                .output()
+                .map_err(|e| format!("FFmpeg not found or not executable: {}", e))?;
+            if output.status.success() {
+                Ok(source.display().to_string())
+            } else {
+                Err(String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("").to_string())
+            }
+        })();
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// Wizard state; choices are only written to the preferences when the wizard finishes
+pub struct FirstRunWizard {
+    pub page: usize,
+    pub language: Language,
+    pub dark_mode: bool,
+    pub encoder_policy: EncoderPolicy,
+    pub default_output_dir: String,
+    pub filename_template: String,
+    pub self_test: SelfTest,
+    sandbox: Option<PathBuf>,
+}
+
+pub enum WizardAction {
+    LanguageChanged(Language),
+    ThemeChanged(bool),
+    Finish,
+    Skip,
+}
+
+impl FirstRunWizard {
+    pub fn new(preferences: &UserPreferences, language: Language) -> Self {
+        Self {
+            page: 0,
+            language,
+            dark_mode: preferences.dark_mode,
+            encoder_policy: preferences.encoder_policy,
+            default_output_dir: preferences.default_output_dir.clone(),
+            filename_template: preferences.filename_template.clone(),
+            self_test: SelfTest::NotRun,
+            sandbox: None,
+        }
+    }
+
+    pub fn start_self_test(&mut self) {
+        self.cleanup();
+        let sandbox = std::env::temp_dir().join(format!("ffmpeg_gui_self_test_{}", uuid::Uuid::new_v4()));
+        self.self_test = SelfTest::Preparing(prepare_source(sandbox.clone()));
+        self.sandbox = Some(sandbox);
+    }
+
+    /// Task that encodes the prepared clip with `codec`, through the normal task pipeline
+    pub fn self_test_task(&self, id: usize, source: &str, codec: &str) -> Option<ProcessingTask> {
+        let sandbox = self.sandbox.as_ref()?;
+        let mut task = ProcessingTask::new(OperationType::VideoConvert, vec![source.to_string()],
+            sandbox.join("self_test.mp4").display().to_string());
+        task.id = id;
+        task.video_settings = Some(VideoSettings {
+            codec: codec.to_string(),
+            container_format: "mp4".to_string(),
+            use_hardware_acceleration: codec != "libx264",
+            ..VideoSettings::default()
+        });
+        Some(task)
+    }
+
+    /// Advance the self-test from the task list; returns the source clip once it is ready to be queued
+    pub fn poll_self_test(&mut self, tasks: &[ProcessingTask]) -> Option<String> {
+        match &self.self_test {
+            SelfTest::Preparing(receiver) => match receiver.try_recv() {
+                Ok(Ok(source)) => return Some(source),
+                Ok(Err(error)) => {
+                    self.self_test = SelfTest::Failed { hints: suggested_fixes(&error), error };
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.self_test = SelfTest::Failed { error: "self-test aborted".to_string(), hints: vec![SelfTestHint::Generic] };
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            },
+            SelfTest::Running { task_id, codec } => {
+                if let Some(task) = tasks.iter().find(|task| task.id == *task_id) {
+                    match task.status {
+                        TaskStatus::Completed if Path::new(&task.output_file).exists() => {
+                            self.self_test = SelfTest::Passed { codec: codec.clone() };
+                        }
+                        TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::NeedsOutputRecovery => {
+                            let error = task.error_message.clone().unwrap_or_else(|| "no output written".to_string());
+                            self.self_test = SelfTest::Failed { hints: suggested_fixes(&error), error };
+                        }
+                        TaskStatus::Pending | TaskStatus::Running | TaskStatus::Paused => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn is_busy(&self) -> bool {
+        matches!(self.self_test, SelfTest::Preparing(_) | SelfTest::Running { .. })
+    }
+
+    /// Remove the self-test sandbox
+    pub fn cleanup(&mut self) {
+        if let Some(sandbox) = self.sandbox.take() {
+            if let Err(e) = std::fs::remove_dir_all(&sandbox) {
+                log_debug!("Could not remove self-test directory {}: {}", sandbox.display(), e);
+            }
+        }
+    }
+
+    pub fn apply_to(&self, preferences: &mut UserPreferences) {
+        preferences.language = self.language.code().to_string();
+        preferences.dark_mode = self.dark_mode;
+        preferences.encoder_policy = self.encoder_policy;
+        preferences.default_output_dir = self.default_output_dir.trim().to_string();
+        preferences.filename_template = self.filename_template.trim().to_string();
+    }
+}
+
+fn show_hardware(ui: &mut egui::Ui, hardware: Option<&HardwareInfo>, encoders: &[String], translations: &Translations) {
+    let Some(hardware) = hardware else {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label(translations.first_run_detecting());
+        });
+        return;
+    };
+    ui.label(format!("CPU: {}", hardware.cpu.name));
+    for gpu in &hardware.gpus {
+        ui.label(format!("GPU: {}", gpu.name));
+    }
+    if encoders.is_empty() {
+        ui.colored_label(egui::Color32::from_rgb(255, 180, 0), translations.first_run_no_hardware());
+    } else {
+        ui.colored_label(egui::Color32::from_rgb(100, 200, 100), format!("✅ {}", encoders.join(", ")));
+        ui.weak(translations.first_run_hardware_explained());
+    }
+}
+
+/// Draw the wizard window; the caller applies the returned action
+pub fn show_wizard(
+    ctx: &egui::Context,
+    wizard: &mut FirstRunWizard,
+    hardware: Option<&HardwareInfo>,
+    encoders: &[String],
+    translations: &Translations,
+) -> Option<WizardAction> {
+    let mut action = None;
+    let mut start_self_test = false;
+
+    egui::Window::new(translations.first_run_title())
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.set_width(460.0);
+            ui.weak(translations.first_run_step(wizard.page + 1, PAGE_COUNT));
+            ui.add_space(6.0);
+
+            match wizard.page {
+                0 => {
+                    ui.heading(translations.first_run_appearance());
+                    ui.horizontal(|ui| {
+                        ui.label(translations.menu_language());
+                        for lang in Language::all_languages() {
+                            if ui.selectable_label(lang == wizard.language, lang.display_name()).clicked() && lang != wizard.language {
+                                wizard.language = lang.clone();
+                                action = Some(WizardAction::LanguageChanged(lang));
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(wizard.dark_mode, translations.dark_mode()).clicked() && !wizard.dark_mode {
+                            wizard.dark_mode = true;
+                            action = Some(WizardAction::ThemeChanged(true));
+                        }
+                        if ui.selectable_label(!wizard.dark_mode, translations.light_mode()).clicked() && wizard.dark_mode {
+                            wizard.dark_mode = false;
+                            action = Some(WizardAction::ThemeChanged(false));
+                        }
+                    });
+                }
+                1 => {
+                    ui.heading(translations.first_run_hardware());
+                    show_hardware(ui, hardware, encoders, translations);
+                    ui.add_space(8.0);
+                    ui.label(translations.first_run_encoder_policy());
+                    for policy in EncoderPolicy::all() {
+                        ui.radio_value(&mut wizard.encoder_policy, policy, translations.encoder_policy_name(policy))
+                            .on_hover_text(translations.encoder_policy_description(policy));
+                    }
+                }
+                2 => {
+                    ui.heading(translations.first_run_output());
+                    ui.horizontal(|ui| {
+                        ui.label(translations.first_run_output_dir());
+                        ui.add(egui::TextEdit::singleline(&mut wizard.default_output_dir)
+                            .hint_text(translations.first_run_next_to_input()));
+                        if ui.button("📁").clicked() {
+                            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                wizard.default_output_dir = dir.display().to_string();
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(translations.first_run_filename_template());
+                        ui.text_edit_singleline(&mut wizard.filename_template);
+                    });
+                    ui.weak(translations.first_run_template_placeholders());
+                    ui.weak(format!("→ {}.mp4", render_filename_template(&wizard.filename_template, "holiday", "1700000000000_42")));
+                }
+                _ => {
+                    ui.heading(translations.first_run_self_test());
+                    ui.label(translations.first_run_self_test_explained());
+                    ui.add_space(6.0);
+                    let codec = self_test_codec(wizard.encoder_policy, encoders);
+                    if ui.add_enabled(!wizard.is_busy(), egui::Button::new(translations.run_self_test(&codec))).clicked() {
+                        start_self_test = true;
+                    }
+                    match &wizard.self_test {
+                        SelfTest::NotRun => {}
+                        SelfTest::Preparing(_) | SelfTest::Running { .. } => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label(translations.first_run_self_test_running());
+                            });
+                        }
+                        SelfTest::Passed { codec } => {
+                            ui.colored_label(egui::Color32::from_rgb(100, 200, 100), translations.self_test_passed(codec));
+                        }
+                        SelfTest::Failed { error, hints } => {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", error));
+                            for hint in hints {
+                                ui.label(format!("💡 {}", translations.self_test_hint(*hint)));
+                            }
+                        }
+                    }
+                }
+            }
+
+            ui.add_space(10.0);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(translations.first_run_skip()).clicked() {
+                    action = Some(WizardAction::Skip);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if wizard.page + 1 < PAGE_COUNT {
+                        if ui.button(translations.first_run_next()).clicked() {
+                            wizard.page += 1;
+                        }
+                    } else if ui.add_enabled(!wizard.is_busy(), egui::Button::new(translations.first_run_finish())).clicked() {
+                        action = Some(WizardAction::Finish);
+                    }
+                    if wizard.page > 0 && ui.button(translations.first_run_back()).clicked() {
+                        wizard.page -= 1;
+                    }
+                });
+            });
+        });
+
+    if start_self_test {
+        wizard.start_self_test();
+    }
+    action
+}
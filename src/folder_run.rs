@@ -0,0 +1,410 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::automation_flow::{AutomationWorkflow, ExecutionStatus, NodeType, WorkflowExecutor};
+use crate::checksum::{to_hex, Sha256};
+use crate::language::Translations;
+use crate::run_cancel::CancelToken;
+
+/// Files listed in the dialog before the rest is summarized as a count
+const PREVIEW_FILES: usize = 200;
+
+/// Where a folder run reads its inputs and writes its outputs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FolderRunSettings {
+    pub input_dir: String,
+    /// Semicolon separated `*` / `?` patterns; empty matches every file
+    pub pattern: String,
+    pub output_dir: String,
+    pub include_subfolders: bool,
+    /// Recreate each input's subfolder below the output folder
+    pub keep_subfolders: bool,
+    /// Run up to the concurrent task limit at once instead of one file after another
+    pub parallel: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed(String),
+    /// Stopped while running, or never started because the run was stopped
+    Stopped,
+}
+
+#[derive(Clone, Debug)]
+pub struct FileRun {
+    pub input: PathBuf,
+    pub outputs: Vec<PathBuf>,
+    pub status: FileStatus,
+}
+
+/// Files below the input folder matching the pattern, sorted by path
+pub fn matching_files(settings: &FolderRunSettings) -> Vec<PathBuf> {
+    fn scan(dir: &Path, settings: &FolderRunSettings, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.is_dir() {
+                if settings.include_subfolders {
+                    scan(&path, settings, files);
+                }
+            } else if path.file_name().and_then(|name| name.to_str())
+                .is_some_and(|name| crate::watch_folder::glob_matches(&settings.pattern, name))
+            {
+                files.push(path);
+            }
+        }
+    }
+    let mut files = Vec::new();
+    if !settings.input_dir.trim().is_empty() {
+        scan(Path::new(settings.input_dir.trim()), settings, &mut files);
+    }
+    files.sort();
+    files
+}
+
+/// Why a workflow cannot run on a folder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkflowProblem {
+    NoInput,
+    NoOutput,
+    SeveralInputs,
+}
+
+/// Reason `workflow` cannot run on a folder: it needs exactly one input file node to
+/// substitute each file into, and an output file node to redirect
+pub fn check_workflow(workflow: &AutomationWorkflow) -> Result<(), WorkflowProblem> {
+    let count = |node_type: NodeType| workflow.nodes.values().filter(|node| node.node_type == node_type).count();
+    match (count(NodeType::InputFile), count(NodeType::OutputFile)) {
+        (0, _) => Err(WorkflowProblem::NoInput),
+        (1, 0) => Err(WorkflowProblem::NoOutput),
+        (1, _) => Ok(()),
+        _ => Err(WorkflowProblem::SeveralInputs),
+    }
+}
+
+/// Point the workflow's input file nodes at `input`
+pub fn set_input(workflow: &mut AutomationWorkflow, input: &Path) {
+    for node in workflow.nodes.values_mut().filter(|node| node.node_type == NodeType::InputFile) {
+        if let Some(parameter) = node.parameters.get_mut("file_path") {
+            parameter.value = input.display().to_string();
+        }
+    }
+}
+
+/// File the workflow's input file node reads, if one is set
+pub fn input_path(workflow: &AutomationWorkflow) -> Option<PathBuf> {
+    workflow.nodes.values()
+        .filter(|node| node.node_type == NodeType::InputFile)
+        .filter_map(|node| node.parameters.get("file_path"))
+        .map(|parameter| parameter.value.trim())
+        .find(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Copy of the flattened `workflow` for one input file: the input node reads `input`, and every
+/// output node writes `<input name>.<extension>` into the output folder. The extension is the
+/// one the output node had; with several output nodes their old file names tell the outputs apart.
+pub fn workflow_for_file(workflow: &AutomationWorkflow, input: &Path, settings: &FolderRunSettings) -> (AutomationWorkflow, Vec<PathBuf>) {
+    let mut workflow = workflow.clone();
+    // Runs on different files get their own temp folder and incremental cache
+    let mut hasher = Sha256::new();
+    hasher.update(input.display().to_string().as_bytes());
+    workflow.id = format!("{}_{}", &to_hex(&hasher.finalize())[..8], workflow.id);
+    set_input(&mut workflow, input);
+
+    let mut output_dir = PathBuf::from(settings.output_dir.trim());
+    if settings.keep_subfolders {
+        if let Some(relative) = input.parent().and_then(|parent| parent.strip_prefix(settings.input_dir.trim()).ok()) {
+            output_dir.push(relative);
+        }
+    }
+    let stem = input.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let input_extension = input.extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut output_ids: Vec<String> = workflow.nodes.values()
+        .filter(|node| node.node_type == NodeType::OutputFile)
+        .map(|node| node.id.clone())
+        .collect();
+    // Top to bottom on the canvas, so the numbering of unnamed outputs is predictable
+    output_ids.sort_by(|a, b| {
+        let (a, b) = (&workflow.nodes[a].position, &workflow.nodes[b].position);
+        a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+    });
+    let several = output_ids.len() > 1;
+    let mut outputs = Vec::new();
+    for (index, node_id) in output_ids.iter().enumerate() {
+        let Some(parameter) = workflow.nodes.get_mut(node_id).and_then(|node| node.parameters.get_mut("output_path")) else {
+            continue;
+        };
+        let (old_stem, extension) = match parameter.value.strip_prefix("AUTO_FILL:") {
+            Some(extension) => (None, extension.to_string()),
+            None => {
+                let old = Path::new(parameter.value.trim());
+                (
+                    old.file_stem().map(|stem| stem.to_string_lossy().to_string()),
+                    old.extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_else(|| input_extension.clone()),
+                )
+            }
+        };
+        let mut name = match old_stem {
+            Some(old_stem) if several => format!("{}_{}", stem, old_stem),
+            _ if several => format!("{}_{}", stem, index + 1),
+            _ => stem.clone(),
+        };
+        let mut path = output_dir.join(format!("{}.{}", name, extension));
+        // Output folder and extension equal to the input's would overwrite the input
+        if path == input {
+            name.push_str("_out");
+            path = output_dir.join(format!("{}.{}", name, extension));
+        }
+        parameter.value = path.display().to_string();
+        outputs.push(path);
+    }
+    (workflow, outputs)
+}
+
+/// A workflow running once per file on background threads. A failed file is recorded and the
+/// next one starts; only a stop ends the run early.
+pub struct FolderRun {
+    files: Arc<Mutex<Vec<FileRun>>>,
+    token: CancelToken,
+    workers: Vec<std::thread::JoinHandle<()>>,
+}
+
+impl FolderRun {
+    /// Start running `workflow` on `inputs`, on `concurrency` threads, with the settings of `executor`
+    pub fn start(workflow: &AutomationWorkflow, inputs: Vec<PathBuf>, settings: &FolderRunSettings, executor: &WorkflowExecutor, concurrency: usize) -> Self {
+        let workflow = workflow.flattened();
+        let files: Vec<FileRun> = inputs.into_iter()
+            .map(|input| {
+                let (_, outputs) = workflow_for_file(&workflow, &input, settings);
+                FileRun { input, outputs, status: FileStatus::Pending }
+            })
+            .collect();
+        log_info!("Running workflow '{}' on {} file(s), {} at a time", workflow.name, files.len(), concurrency.max(1));
+        let worker_count = concurrency.clamp(1, files.len().max(1));
+        let files = Arc::new(Mutex::new(files));
+        let token = CancelToken::default();
+        let next = Arc::new(AtomicUsize::new(0));
+        let workers = (0..worker_count)
+            .map(|_| {
+                let files = Arc::clone(&files);
+                let next = Arc::clone(&next);
+                let token = token.clone();
+                let workflow = workflow.clone();
+                let settings = settings.clone();
+                let mut runner = executor.runner(&token);
+                std::thread::spawn(move || {
+                    let set_status = |index: usize, status: FileStatus| {
+                        if let Some(file) = files.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get_mut(index) {
+                            file.status = status;
+                        }
+                    };
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(input) = files.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(index).map(|file| file.input.clone()) else {
+                            break;
+                        };
+                        if token.is_cancelled() {
+                            set_status(index, FileStatus::Stopped);
+                            continue;
+                        }
+                        set_status(index, FileStatus::Running);
+                        let (file_workflow, outputs) = workflow_for_file(&workflow, &input, &settings);
+                        for dir in outputs.iter().filter_map(|output| output.parent()) {
+                            if let Err(e) = std::fs::create_dir_all(dir) {
+                                log_warn!("Could not create output folder {}: {}", dir.display(), e);
+                            }
+                        }
+                        let result = crate::run_cancel::with_token(&token, || runner.execute_workflow(file_workflow));
+                        let status = match result {
+                            Ok(()) if runner.execution_status == ExecutionStatus::Cancelled => FileStatus::Stopped,
+                            Ok(()) => FileStatus::Succeeded,
+                            Err(e) => {
+                                log_warn!("Folder run: {} failed: {}", input.display(), e);
+                                FileStatus::Failed(e)
+                            }
+                        };
+                        set_status(index, status);
+                    }
+                })
+            })
+            .collect();
+        Self { files, token, workers }
+    }
+
+    pub fn files(&self) -> Vec<FileRun> {
+        self.files.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.workers.iter().all(|worker| worker.is_finished())
+    }
+
+    /// Kill the running ffmpeg processes; files not started yet are not started
+    pub fn stop(&self) {
+        log_info!("Stopping the folder run");
+        self.token.cancel();
+    }
+}
+
+pub enum FolderRunAction {
+    None,
+    Close,
+    /// Start running the focused workflow on the listed files
+    Start(Vec<PathBuf>),
+}
+
+/// Window for picking the folders of a folder run, then following it to its summary
+#[derive(Default)]
+pub struct FolderRunDialog {
+    pub settings: FolderRunSettings,
+    files: Vec<PathBuf>,
+    /// Settings the file list was scanned with
+    scanned_for: Option<FolderRunSettings>,
+    pub run: Option<FolderRun>,
+}
+
+impl FolderRunDialog {
+    fn refresh(&mut self) {
+        if self.scanned_for.as_ref() != Some(&self.settings) {
+            self.files = matching_files(&self.settings);
+            self.scanned_for = Some(self.settings.clone());
+        }
+    }
+
+    /// `workflow` is the focused workflow the run would use
+    pub fn show(&mut self, ctx: &egui::Context, workflow: &AutomationWorkflow, concurrency: usize, translations: &Translations) -> FolderRunAction {
+        let mut open = true;
+        let mut action = FolderRunAction::None;
+        let running = self.run.as_ref().is_some_and(|run| !run.is_finished());
+        let mut window = egui::Window::new(translations.folder_run_title())
+            .collapsible(false)
+            .default_width(640.0);
+        if !running {
+            window = window.open(&mut open);
+        }
+        window.show(ctx, |ui| {
+            match &self.run {
+                Some(run) => self.show_progress(ui, run, running, translations, &mut action),
+                None => self.show_setup(ui, workflow, concurrency, translations, &mut action),
+            }
+        });
+        if running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        if !open {
+            action = FolderRunAction::Close;
+        }
+        action
+    }
+
+    fn show_setup(&mut self, ui: &mut egui::Ui, workflow: &AutomationWorkflow, concurrency: usize, translations: &Translations, action: &mut FolderRunAction) {
+        ui.label(translations.folder_run_workflow(&workflow.name));
+        ui.add_space(5.0);
+        egui::Grid::new("folder_run_settings").num_columns(2).show(ui, |ui| {
+            ui.label(translations.folder_run_input_dir());
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.settings.input_dir);
+                if ui.button(translations.folder_run_browse()).clicked() {
+                    if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                        self.settings.input_dir = dir.display().to_string();
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label(translations.folder_run_pattern());
+            ui.add(egui::TextEdit::singleline(&mut self.settings.pattern).hint_text("*.mkv;*.mp4"));
+            ui.end_row();
+            ui.label(translations.folder_run_output_dir());
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.settings.output_dir);
+                if ui.button(translations.folder_run_browse()).clicked() {
+                    if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                        self.settings.output_dir = dir.display().to_string();
+                    }
+                }
+            });
+            ui.end_row();
+        });
+        ui.checkbox(&mut self.settings.include_subfolders, translations.folder_run_include_subfolders());
+        ui.add_enabled_ui(self.settings.include_subfolders, |ui| {
+            ui.checkbox(&mut self.settings.keep_subfolders, translations.folder_run_keep_subfolders());
+        });
+        ui.checkbox(&mut self.settings.parallel, translations.folder_run_parallel(concurrency));
+        ui.add_space(5.0);
+
+        self.refresh();
+        let problem = match check_workflow(&workflow.flattened()) {
+            Err(WorkflowProblem::NoInput) => Some(translations.folder_run_no_input()),
+            Err(WorkflowProblem::NoOutput) => Some(translations.folder_run_no_output()),
+            Err(WorkflowProblem::SeveralInputs) => Some(translations.folder_run_several_inputs()),
+            Ok(()) => self.settings.output_dir.trim().is_empty().then(|| translations.folder_run_no_output_dir()),
+        };
+        if let Some(problem) = problem {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), problem);
+        }
+        ui.label(translations.folder_run_matches(self.files.len()));
+        egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+            for file in self.files.iter().take(PREVIEW_FILES) {
+                ui.weak(file.display().to_string());
+            }
+            if self.files.len() > PREVIEW_FILES {
+                ui.weak("…");
+            }
+        });
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            if ui.add_enabled(problem.is_none() && !self.files.is_empty(), egui::Button::new(translations.folder_run_start())).clicked() {
+                *action = FolderRunAction::Start(self.files.clone());
+            }
+            if ui.button(translations.cancel()).clicked() {
+                *action = FolderRunAction::Close;
+            }
+        });
+    }
+
+    fn show_progress(&self, ui: &mut egui::Ui, run: &FolderRun, running: bool, translations: &Translations, action: &mut FolderRunAction) {
+        let files = run.files();
+        let count = |wanted: fn(&FileStatus) -> bool| files.iter().filter(|file| wanted(&file.status)).count();
+        let succeeded = count(|status| *status == FileStatus::Succeeded);
+        let failed = count(|status| matches!(status, FileStatus::Failed(_)));
+        let stopped = count(|status| *status == FileStatus::Stopped);
+        let done = succeeded + failed + stopped;
+        ui.add(egui::ProgressBar::new(done as f32 / files.len().max(1) as f32).text(format!("{} / {}", done, files.len())));
+        ui.label(translations.folder_run_summary(succeeded, failed, stopped));
+        ui.add_space(5.0);
+        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            egui::Grid::new("folder_run_files").striped(true).num_columns(2).show(ui, |ui| {
+                for file in &files {
+                    let name = file.input.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                    ui.label(name).on_hover_text(file.input.display().to_string());
+                    match &file.status {
+                        FileStatus::Pending => ui.weak(translations.folder_run_pending()),
+                        FileStatus::Running => ui.label(translations.folder_run_running()),
+                        FileStatus::Succeeded => ui.colored_label(egui::Color32::GREEN, translations.folder_run_succeeded())
+                            .on_hover_text(file.outputs.iter().map(|output| output.display().to_string()).collect::<Vec<_>>().join("\n")),
+                        FileStatus::Failed(error) => ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error),
+                        FileStatus::Stopped => ui.colored_label(egui::Color32::from_rgb(255, 165, 0), translations.folder_run_stopped()),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button(translations.folder_run_stop()).clicked() {
+                    run.stop();
+                }
+            } else if ui.button(translations.close()).clicked() {
+                *action = FolderRunAction::Close;
+            }
+        });
+    }
+}
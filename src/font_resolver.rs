@@ -0,0 +1,535 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::language::Translations;
+
+/// Font value prefix for a font shipped with the application
+pub const BUNDLED_PREFIX: &str = "bundled:";
+
+/// Bundled fallback: egui's default proportional font, known to load in ffmpeg and libass
+pub const FALLBACK_FONT: &str = "Ubuntu-Light";
+
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+/// Font directories are searched this deep; distributions nest them by foundry and format
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Where a font setting points on this system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontResolution {
+    /// No font set: ffmpeg and libass pick their own default
+    Default,
+    /// A font file; drawtext gets it as fontfile=, libass through fontsdir=
+    File { path: PathBuf, family: Option<String> },
+    /// A family fontconfig knows, passed by name
+    Fontconfig(String),
+    Missing,
+}
+
+/// Whether a font value is a file (absolute, bare file name or bundled) rather than a family name
+pub fn is_file_value(value: &str) -> bool {
+    value.starts_with(BUNDLED_PREFIX) || Path::new(value).extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// File name of a path written on any system; a preset saved on Windows keeps its backslashes
+fn file_name_of(value: &str) -> &str {
+    value.rsplit(['/', '\\']).next().unwrap_or(value)
+}
+
+/// Family name from the `name` table of a TrueType/OpenType font or the first font of a collection
+pub fn read_family_name(path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut read_at = |offset: u64, length: usize| -> Option<Vec<u8>> {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut buffer = vec![0u8; length];
+        file.read_exact(&mut buffer).ok()?;
+        Some(buffer)
+    };
+    let u16_at = |bytes: &[u8], at: usize| bytes.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    let u32_at = |bytes: &[u8], at: usize| bytes.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+    let mut font_offset = 0u64;
+    let header = read_at(0, 16)?;
+    if &header[..4] == b"ttcf" {
+        font_offset = u32_at(&header, 12)? as u64;
+    }
+    let offset_table = read_at(font_offset, 12)?;
+    let table_count = u16_at(&offset_table, 4)? as usize;
+    let records = read_at(font_offset + 12, table_count * 16)?;
+    let (name_offset, name_length) = (0..table_count)
+        .map(|index| &records[index * 16..index * 16 + 16])
+        .find(|record| &record[..4] == b"name")
+        .and_then(|record| Some((u32_at(record, 8)? as u64, u32_at(record, 12)? as usize)))?;
+    let table = read_at(name_offset, name_length)?;
+
+    let count = u16_at(&table, 2)? as usize;
+    let strings = u16_at(&table, 4)? as usize;
+    let mut best: Option<(u8, String)> = None;
+    for index in 0..count {
+        let record = 6 + index * 12;
+        let (platform, language, name_id) = (u16_at(&table, record)?, u16_at(&table, record + 4)?, u16_at(&table, record + 6)?);
+        if name_id != 1 {
+            continue;
+        }
+        let length = u16_at(&table, record + 8)? as usize;
+        let start = strings + u16_at(&table, record + 10)? as usize;
+        let Some(bytes) = table.get(start..start + length) else {
+            continue;
+        };
+        // Windows English first, then any Unicode record, then Mac Roman read as Latin-1
+        let (rank, text) = match platform {
+            0 | 3 => {
+                let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+                (if platform == 3 && language == 0x409 { 0 } else { 1 }, String::from_utf16_lossy(&units))
+            }
+            1 => (2, bytes.iter().map(|&byte| byte as char).collect()),
+            _ => continue,
+        };
+        if best.as_ref().is_none_or(|(best_rank, _)| rank < *best_rank) {
+            best = Some((rank, text));
+        }
+    }
+    best.map(|(_, family)| family.trim().to_string()).filter(|family| !family.is_empty())
+}
+
+#[derive(Debug, Clone)]
+struct IndexedFont {
+    path: PathBuf,
+    family: Option<String>,
+}
+
+/// Finds fonts by family or file name in a set of font directories, with fontconfig asked first
+/// where it is installed
+pub struct FontResolver {
+    dirs: Vec<PathBuf>,
+    bundled_dir: Option<PathBuf>,
+    use_fontconfig: bool,
+    index: OnceLock<Vec<IndexedFont>>,
+}
+
+impl FontResolver {
+    pub fn new(dirs: Vec<PathBuf>, bundled_dir: Option<PathBuf>, use_fontconfig: bool) -> Self {
+        Self { dirs, bundled_dir, use_fontconfig, index: OnceLock::new() }
+    }
+
+    /// System and user font directories of this platform
+    pub fn system() -> Self {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let mut dirs = Vec::new();
+        if cfg!(target_os = "windows") {
+            let windir = std::env::var_os("WINDIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("C:\\Windows"));
+            dirs.push(windir.join("Fonts"));
+            if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+                dirs.push(PathBuf::from(local).join("Microsoft").join("Windows").join("Fonts"));
+            }
+        } else if cfg!(target_os = "macos") {
+            dirs.extend([PathBuf::from("/System/Library/Fonts"), PathBuf::from("/Library/Fonts")]);
+            dirs.extend(home.iter().map(|home| home.join("Library").join("Fonts")));
+        } else {
+            dirs.extend([PathBuf::from("/usr/share/fonts"), PathBuf::from("/usr/local/share/fonts")]);
+            dirs.extend(home.iter().flat_map(|home| [home.join(".local").join("share").join("fonts"), home.join(".fonts")]));
+        }
+        let bundled_dir = crate::app_state::UserPreferences::file_path()
+            .and_then(|path| path.parent().map(|dir| dir.join("fonts")));
+        Self::new(dirs, bundled_dir, cfg!(target_os = "linux"))
+    }
+
+    fn index(&self) -> &[IndexedFont] {
+        self.index.get_or_init(|| {
+            let mut fonts = Vec::new();
+            for dir in &self.dirs {
+                scan_dir(dir, 0, &mut fonts);
+            }
+            log_debug!("Indexed {} font files", fonts.len());
+            fonts
+        })
+    }
+
+    /// Resolve a font setting: empty, a family name, a file name, an absolute path or a bundled font.
+    /// An absolute path that does not exist here (e.g. from a preset made on another system) is
+    /// looked up by its file name.
+    pub fn resolve(&self, value: &str) -> FontResolution {
+        let value = value.trim();
+        if value.is_empty() {
+            return FontResolution::Default;
+        }
+        if let Some(name) = value.strip_prefix(BUNDLED_PREFIX) {
+            return match self.bundled_font(name) {
+                Some(path) => FontResolution::File { family: read_family_name(&path), path },
+                None => FontResolution::Missing,
+            };
+        }
+        if is_file_value(value) {
+            let path = Path::new(value);
+            if path.is_absolute() && path.is_file() {
+                return FontResolution::File { path: path.to_path_buf(), family: read_family_name(path) };
+            }
+            let file_name = file_name_of(value).to_lowercase();
+            return self.index().iter()
+                .find(|font| font.path.file_name().is_some_and(|name| name.to_string_lossy().to_lowercase() == file_name))
+                .map(|font| FontResolution::File { path: font.path.clone(), family: font.family.clone() })
+                .unwrap_or(FontResolution::Missing);
+        }
+        if self.use_fontconfig && fontconfig_has_family(value) {
+            return FontResolution::Fontconfig(value.to_string());
+        }
+        let wanted = value.to_lowercase();
+        self.index().iter()
+            .find(|font| font.family.as_ref().is_some_and(|family| family.to_lowercase() == wanted))
+            .map(|font| FontResolution::File { path: font.path.clone(), family: font.family.clone() })
+            .unwrap_or(FontResolution::Missing)
+    }
+
+    /// Path of a bundled font, written out of the application on first use
+    pub fn bundled_font(&self, name: &str) -> Option<PathBuf> {
+        let path = self.bundled_dir.as_ref()?.join(format!("{}.ttf", name));
+        if path.is_file() {
+            return Some(path);
+        }
+        let data = egui::FontDefinitions::default().font_data.get(name)?.font.to_vec();
+        std::fs::create_dir_all(path.parent()?).ok()?;
+        match std::fs::write(&path, data) {
+            Ok(()) => Some(path),
+            Err(e) => {
+                log_warn!("Could not write bundled font {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Value to store in settings and presets for a picked font file: a bundled font by name, a
+    /// file inside a font directory by file name so the preset also resolves on other systems,
+    /// anything else by absolute path
+    pub fn portable_value(&self, path: &Path) -> String {
+        if let (Some(bundled_dir), Some(stem)) = (&self.bundled_dir, path.file_stem()) {
+            if path.parent() == Some(bundled_dir.as_path()) {
+                return format!("{}{}", BUNDLED_PREFIX, stem.to_string_lossy());
+            }
+        }
+        if self.dirs.iter().any(|dir| path.starts_with(dir)) {
+            if let Some(name) = path.file_name() {
+                return name.to_string_lossy().to_string();
+            }
+        }
+        path.to_string_lossy().to_string()
+    }
+}
+
+fn scan_dir(dir: &Path, depth: usize, fonts: &mut Vec<IndexedFont>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                scan_dir(&path, depth + 1, fonts);
+            }
+        } else if is_file_value(&path.to_string_lossy()) {
+            fonts.push(IndexedFont { family: read_family_name(&path), path });
+        }
+    }
+}
+
+/// Whether fontconfig has a font of exactly this family; fc-match would substitute silently
+fn fontconfig_has_family(family: &str) -> bool {
+    // '-', ':' and ',' separate the parts of a fontconfig pattern
+    let escaped: String = family.chars()
+        .flat_map(|c| if matches!(c, '-' | ':' | ',' | '\\') { vec!['\\', c] } else { vec![c] })
+        .collect();
+    let output = std::process::Command::new("fc-list")
+        .arg(format!(":family={}", escaped))
+        .arg("family")
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            let wanted = family.to_lowercase();
+            String::from_utf8_lossy(&output.stdout).lines()
+                .flat_map(|line| line.split(','))
+                .any(|name| name.trim().to_lowercase() == wanted)
+        }
+        _ => false,
+    }
+}
+
+/// The application's resolver over the system font directories
+pub fn resolver() -> &'static FontResolver {
+    static RESOLVER: OnceLock<FontResolver> = OnceLock::new();
+    RESOLVER.get_or_init(FontResolver::system)
+}
+
+/// Resolve with the application's resolver; results are kept per value for the session
+pub fn resolve(value: &str) -> FontResolution {
+    static RESOLVED: OnceLock<Mutex<HashMap<String, FontResolution>>> = OnceLock::new();
+    let resolved = RESOLVED.get_or_init(Default::default);
+    if let Some(resolution) = resolved.lock().unwrap().get(value) {
+        return resolution.clone();
+    }
+    let resolution = resolver().resolve(value);
+    if resolution == FontResolution::Missing {
+        log_warn!("Font not found on this system: {}", value);
+    }
+    resolved.lock().unwrap().insert(value.to_string(), resolution.clone());
+    resolution
+}
+
+/// drawtext option selecting the font, with the path quoted for the filtergraph; None leaves
+/// drawtext's default
+pub fn drawtext_font_option(value: &str) -> Option<String> {
+    match resolve(value) {
+        FontResolution::Default => None,
+        FontResolution::File { path, .. } => Some(format!("fontfile={}", filter_path(&path))),
+        FontResolution::Fontconfig(family) => Some(format!("font={}", quote(&family))),
+        // Passed on as given so ffmpeg reports the font itself if the user went ahead anyway
+        FontResolution::Missing if is_file_value(value) => Some(format!("fontfile={}", filter_path(Path::new(value)))),
+        FontResolution::Missing => Some(format!("font={}", quote(value))),
+    }
+}
+
+/// libass options for a subtitle font: fontsdir= for a file plus the family for force_style
+pub fn subtitle_font(value: &str) -> (Option<String>, String) {
+    match resolve(value) {
+        FontResolution::File { path, family } => {
+            let family = family.unwrap_or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default());
+            (path.parent().map(filter_path), family)
+        }
+        FontResolution::Fontconfig(family) => (None, family),
+        FontResolution::Default | FontResolution::Missing => (None, value.to_string()),
+    }
+}
+
+fn quote(value: &str) -> String {
+    crate::comprehensive_command_builder::ComprehensiveCommandBuilder::quote_filter_value(value)
+}
+
+/// A path as a filter option value: forward slashes work on every platform and the quoting
+/// keeps a drive letter's colon from ending the option. A path escaped by hand the way ffmpeg's
+/// documentation shows (`C\:/Windows/Fonts/arial.ttf`) is unescaped first.
+pub fn filter_path(path: &Path) -> String {
+    quote(&path.to_string_lossy().replace("\\:", ":").replace('\\', "/"))
+}
+
+/// Picked font value, stored in the portable form
+fn pick_font_file() -> Option<String> {
+    let path = rfd::FileDialog::new()
+        .add_filter("Fonts", &FONT_EXTENSIONS)
+        .pick_file()?;
+    Some(resolver().portable_value(&path))
+}
+
+/// Missing fonts the user chose to go ahead with this session
+static ACCEPTED_MISSING: Mutex<std::collections::BTreeSet<String>> = Mutex::new(std::collections::BTreeSet::new());
+
+/// Settings row for a font value: the value, a file picker and, when the font cannot be found
+/// here, the ways out. Returns true when the value changed.
+pub fn show_font_row(ui: &mut egui::Ui, value: &mut String, translations: &Translations) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(translations.font_font());
+        changed |= ui.add(egui::TextEdit::singleline(value)
+            .hint_text(translations.font_font_hint())
+            .desired_width(180.0)).changed();
+        if ui.small_button("📂").on_hover_text(translations.font_pick()).clicked() {
+            if let Some(picked) = pick_font_file() {
+                *value = picked;
+                changed = true;
+            }
+        }
+    });
+    if resolve(value) != FontResolution::Missing || ACCEPTED_MISSING.lock().unwrap().contains(value.as_str()) {
+        return changed;
+    }
+    ui.colored_label(egui::Color32::from_rgb(255, 180, 0), format!("⚠ {}", translations.font_missing(value)));
+    ui.horizontal_wrapped(|ui| {
+        if ui.button(translations.font_pick()).clicked() {
+            if let Some(picked) = pick_font_file() {
+                *value = picked;
+                changed = true;
+            }
+        }
+        if ui.button(translations.font_use_fallback()).clicked() {
+            *value = format!("{}{}", BUNDLED_PREFIX, FALLBACK_FONT);
+            changed = true;
+        }
+        if ui.button(translations.font_proceed()).clicked() {
+            ACCEPTED_MISSING.lock().unwrap().insert(value.clone());
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// A `name` table with one family record per (platform, language, text)
+    fn name_table(records: &[(u16, u16, &str)]) -> Vec<u8> {
+        let encoded: Vec<Vec<u8>> = records.iter()
+            .map(|(platform, _, text)| match platform {
+                1 => text.bytes().collect(),
+                _ => text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect(),
+            })
+            .collect();
+        let mut table = Vec::new();
+        table.extend(0u16.to_be_bytes());
+        table.extend((records.len() as u16).to_be_bytes());
+        table.extend((6 + 12 * records.len() as u16).to_be_bytes());
+        let mut string_offset = 0u16;
+        for ((platform, language, _), bytes) in records.iter().zip(&encoded) {
+            for field in [*platform, 0, *language, 1, bytes.len() as u16, string_offset] {
+                table.extend(field.to_be_bytes());
+            }
+            string_offset += bytes.len() as u16;
+        }
+        table.extend(encoded.concat());
+        table
+    }
+
+    /// A TrueType font holding nothing but a `name` table, starting at `at` in the file
+    fn sfnt(records: &[(u16, u16, &str)], at: u32) -> Vec<u8> {
+        let table = name_table(records);
+        let mut font = Vec::new();
+        font.extend(0x0001_0000u32.to_be_bytes());
+        font.extend([0, 1, 0, 16, 0, 0, 0, 0]);
+        font.extend(b"name");
+        font.extend(0u32.to_be_bytes());
+        font.extend((at + 28).to_be_bytes());
+        font.extend((table.len() as u32).to_be_bytes());
+        font.extend(table);
+        font
+    }
+
+    fn write_font(path: &Path, family: &str) -> PathBuf {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, sfnt(&[(3, 0x409, family)], 0)).unwrap();
+        path.to_path_buf()
+    }
+
+    #[test]
+    fn family_name_prefers_windows_english_and_reads_collections() {
+        let dir = TempDir::new("fonts");
+
+        let mixed = dir.0.join("mixed.ttf");
+        std::fs::write(&mixed, sfnt(&[(1, 0, "Mac Name"), (3, 0x407, "Deutscher Name"), (3, 0x409, " Fake Sans ")], 0)).unwrap();
+        assert_eq!(read_family_name(&mixed).as_deref(), Some("Fake Sans"));
+
+        let mac_only = dir.0.join("mac.ttf");
+        std::fs::write(&mac_only, sfnt(&[(1, 0, "Mac Family")], 0)).unwrap();
+        assert_eq!(read_family_name(&mac_only).as_deref(), Some("Mac Family"));
+
+        // Collection header pointing at its first font 12 bytes in
+        let collection = dir.0.join("fake.ttc");
+        let mut bytes = Vec::new();
+        bytes.extend(b"ttcf");
+        bytes.extend([0, 1, 0, 0, 0, 0, 0, 1]);
+        bytes.extend(16u32.to_be_bytes());
+        bytes.extend(sfnt(&[(0, 0, "Fake Collection")], 16));
+        std::fs::write(&collection, bytes).unwrap();
+        assert_eq!(read_family_name(&collection).as_deref(), Some("Fake Collection"));
+
+        let garbage = dir.0.join("garbage.ttf");
+        std::fs::write(&garbage, b"not a font at all").unwrap();
+        assert_eq!(read_family_name(&garbage), None);
+        assert_eq!(read_family_name(&dir.0.join("absent.ttf")), None);
+    }
+
+    #[test]
+    fn resolves_family_and_file_names_from_the_font_dirs() {
+        let dir = TempDir::new("fonts");
+        let sans = write_font(&dir.0.join("truetype").join("fake").join("FakeSans-Regular.ttf"), "Fake Sans");
+        let serif = write_font(&dir.0.join("FakeSerif.OTF"), "Fake Serif");
+        write_font(&dir.0.join("a").join("b").join("c").join("d").join("e").join("Deep.ttf"), "Too Deep");
+        std::fs::write(dir.0.join("readme.txt"), "not a font").unwrap();
+        let resolver = FontResolver::new(vec![dir.0.clone(), dir.0.join("missing")], None, false);
+
+        assert_eq!(resolver.resolve("  "), FontResolution::Default);
+        // By family, case-insensitively, however deep the distribution nests it
+        assert_eq!(resolver.resolve("fake sans"), FontResolution::File { path: sans.clone(), family: Some("Fake Sans".into()) });
+        // By file name, also when the preset carries a path from another system
+        assert_eq!(resolver.resolve("fakeserif.otf"), FontResolution::File { path: serif.clone(), family: Some("Fake Serif".into()) });
+        assert_eq!(resolver.resolve("C:\\Windows\\Fonts\\FakeSans-Regular.ttf"), FontResolution::File { path: sans, family: Some("Fake Sans".into()) });
+        assert_eq!(resolver.resolve("/Library/Fonts/FakeSerif.otf"), FontResolution::File { path: serif, family: Some("Fake Serif".into()) });
+
+        assert_eq!(resolver.resolve("Too Deep"), FontResolution::Missing, "scan depth is limited");
+        assert_eq!(resolver.resolve("Nonexistent Family"), FontResolution::Missing);
+        assert_eq!(resolver.resolve("Nonexistent.ttf"), FontResolution::Missing);
+    }
+
+    #[test]
+    fn absolute_paths_outside_the_font_dirs_are_used_as_they_are() {
+        let fonts = TempDir::new("fonts");
+        let elsewhere = TempDir::new("fonts");
+        let own = write_font(&elsewhere.0.join("Own.ttf"), "Own Font");
+        let resolver = FontResolver::new(vec![fonts.0.clone()], None, false);
+
+        let value = own.to_string_lossy().to_string();
+        assert_eq!(resolver.resolve(&value), FontResolution::File { path: own.clone(), family: Some("Own Font".into()) });
+        assert_eq!(resolver.portable_value(&own), value);
+        // Not in a font dir, so the family alone does not find it
+        assert_eq!(resolver.resolve("Own Font"), FontResolution::Missing);
+    }
+
+    #[test]
+    fn bundled_fonts_are_written_out_once_and_stored_by_name() {
+        let fonts = TempDir::new("fonts");
+        let config = TempDir::new("fonts");
+        let bundled_dir = config.0.join("fonts");
+        let resolver = FontResolver::new(vec![fonts.0.clone()], Some(bundled_dir.clone()), false);
+
+        let value = format!("{}{}", BUNDLED_PREFIX, FALLBACK_FONT);
+        let FontResolution::File { path, family } = resolver.resolve(&value) else {
+            panic!("bundled fallback did not resolve");
+        };
+        assert_eq!(path, bundled_dir.join(format!("{}.ttf", FALLBACK_FONT)));
+        assert!(path.is_file());
+        assert!(family.is_some_and(|family| family.starts_with("Ubuntu")));
+        assert_eq!(resolver.portable_value(&path), value);
+
+        // Already written: served from disk
+        let written = std::fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(resolver.bundled_font(FALLBACK_FONT), Some(path.clone()));
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), written);
+
+        assert_eq!(resolver.resolve("bundled:NoSuchFont"), FontResolution::Missing);
+        assert_eq!(FontResolver::new(vec![], None, false).resolve(&value), FontResolution::Missing);
+    }
+
+    #[test]
+    fn picked_files_in_font_dirs_are_stored_by_file_name() {
+        let fonts = TempDir::new("fonts");
+        let sans = write_font(&fonts.0.join("sub").join("FakeSans-Regular.ttf"), "Fake Sans");
+        let resolver = FontResolver::new(vec![fonts.0.clone()], None, false);
+        assert_eq!(resolver.portable_value(&sans), "FakeSans-Regular.ttf");
+    }
+
+    #[test]
+    fn file_values_and_filter_paths() {
+        assert!(is_file_value("arial.TTF"));
+        assert!(is_file_value("C:\\Windows\\Fonts\\msgothic.ttc"));
+        assert!(is_file_value("bundled:Ubuntu-Light"));
+        assert!(!is_file_value("DejaVu Sans"));
+        assert!(!is_file_value("notes.txt"));
+
+        assert_eq!(filter_path(Path::new("C\\:/Windows/Fonts/arial.ttf")), quote("C:/Windows/Fonts/arial.ttf"));
+        assert_eq!(filter_path(Path::new("C:\\Windows\\Fonts\\arial.ttf")), quote("C:/Windows/Fonts/arial.ttf"));
+        assert_eq!(filter_path(Path::new("/usr/share/fonts/it's.ttf")), quote("/usr/share/fonts/it's.ttf"));
+    }
+
+    #[test]
+    fn drawtext_and_subtitle_options_for_a_font_file() {
+        let dir = TempDir::new("fonts");
+        let font = write_font(&dir.0.join("Drawn.ttf"), "Drawn Font");
+        let value = font.to_string_lossy().to_string();
+
+        assert_eq!(drawtext_font_option(""), None);
+        assert_eq!(drawtext_font_option(&value), Some(format!("fontfile={}", filter_path(&font))));
+        assert_eq!(subtitle_font(&value), (Some(filter_path(&dir.0)), "Drawn Font".to_string()));
+        assert_eq!(subtitle_font(""), (None, String::new()));
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::frame_rate::FrameRate;
+use crate::language::Translations;
+
+/// How frames get to the target rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FpsMode {
+    /// fps filter: frames are dropped or duplicated, the duration stays
+    Fps,
+    /// minterpolate: new frames are synthesized from motion between the source frames
+    Minterpolate,
+    /// setpts: every frame is kept and played at the target rate, so the video speeds up or slows down
+    SpeedChange,
+}
+
+impl FpsMode {
+    pub fn all() -> [FpsMode; 3] {
+        [FpsMode::Fps, FpsMode::Minterpolate, FpsMode::SpeedChange]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            FpsMode::Fps => "fps",
+            FpsMode::Minterpolate => "minterpolate",
+            FpsMode::SpeedChange => "speed_change",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<FpsMode> {
+        FpsMode::all().into_iter().find(|mode| mode.key() == key.trim())
+    }
+}
+
+/// minterpolate interpolation modes
+pub const MI_MODES: [&str; 3] = ["mci", "blend", "dup"];
+/// minterpolate motion compensation modes, used with mi_mode=mci
+pub const MC_MODES: [&str; 2] = ["obmc", "aobmc"];
+
+/// Frame rate conversion shared by the FrameRateConvert operation and the VideoFPS node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FpsConversionSettings {
+    /// Target rate as entered, e.g. "60" or "24000/1001"
+    pub target_fps: String,
+    pub mode: FpsMode,
+    pub mi_mode: String,
+    pub mc_mode: String,
+}
+
+impl Default for FpsConversionSettings {
+    fn default() -> Self {
+        Self {
+            target_fps: "60".to_string(),
+            mode: FpsMode::Fps,
+            mi_mode: "mci".to_string(),
+            mc_mode: "obmc".to_string(),
+        }
+    }
+}
+
+impl FpsConversionSettings {
+    pub fn target_rate(&self) -> Result<FrameRate> {
+        FrameRate::parse(&self.target_fps)
+            .ok_or_else(|| anyhow!("\"{}\" is not a frame rate", self.target_fps.trim()))
+    }
+
+    /// Whether the source rate must be known to build the filters
+    pub fn needs_source_rate(&self) -> bool {
+        self.mode == FpsMode::SpeedChange
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.target_rate()?;
+        if self.mode == FpsMode::Minterpolate {
+            if !MI_MODES.contains(&self.mi_mode.as_str()) {
+                return Err(anyhow!("Unknown minterpolate mi_mode: {}", self.mi_mode));
+            }
+            if !MC_MODES.contains(&self.mc_mode.as_str()) {
+                return Err(anyhow!("Unknown minterpolate mc_mode: {}", self.mc_mode));
+            }
+        }
+        Ok(())
+    }
+
+    /// -vf chain for the conversion; speed change needs the rate of the source
+    pub fn video_filter(&self, source_rate: Option<FrameRate>) -> Result<String> {
+        self.validate()?;
+        let target = self.target_rate()?;
+        Ok(match self.mode {
+            FpsMode::Fps => format!("fps={}", target.ffmpeg_arg()),
+            FpsMode::Minterpolate if self.mi_mode == "mci" => format!(
+                "minterpolate=fps={}:mi_mode=mci:mc_mode={}", target.ffmpeg_arg(), self.mc_mode
+            ),
+            FpsMode::Minterpolate => format!("minterpolate=fps={}:mi_mode={}", target.ffmpeg_arg(), self.mi_mode),
+            FpsMode::SpeedChange => {
+                let source = source_rate.ok_or_else(|| anyhow!("The source frame rate is unknown, cannot change the speed"))?;
+                // Stretch timestamps by source/target so each source frame lasts one target frame
+                format!(
+                    "setpts=PTS*{}/{},fps={}",
+                    source.num as u64 * target.den as u64,
+                    source.den as u64 * target.num as u64,
+                    target.ffmpeg_arg()
+                )
+            }
+        })
+    }
+
+    /// -af chain retiming the audio along with the video; None when the audio is left alone
+    pub fn audio_filter(&self, source_rate: Option<FrameRate>) -> Result<Option<String>> {
+        if self.mode != FpsMode::SpeedChange {
+            return Ok(None);
+        }
+        let source = source_rate.ok_or_else(|| anyhow!("The source frame rate is unknown, cannot change the speed"))?;
+        Ok(Some(atempo_chain(self.target_rate()?.as_f64() / source.as_f64())))
+    }
+}
+
+/// atempo filters multiplying the tempo by `tempo`; one atempo only covers 0.5-2.0 on older ffmpeg
+pub fn atempo_chain(tempo: f64) -> String {
+    let mut remaining = tempo;
+    let mut stages = Vec::new();
+    while remaining > 2.0 {
+        stages.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        stages.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    stages.push(format!("atempo={:.6}", remaining));
+    stages.join(",")
+}
+
+/// Target rate, mode and minterpolate options. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut FpsConversionSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.fps_conversion_target_fps());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.target_fps).desired_width(80.0)).changed();
+        for rate in [24, 25, 30, 50, 60] {
+            if ui.small_button(rate.to_string()).clicked() {
+                settings.target_fps = rate.to_string();
+                changed = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.fps_conversion_mode());
+        for mode in FpsMode::all() {
+            let (label, hint) = match mode {
+                FpsMode::Fps => (translations.fps_conversion_fps(), translations.fps_conversion_fps_hint()),
+                FpsMode::Minterpolate => (translations.fps_conversion_minterpolate(), translations.fps_conversion_minterpolate_hint()),
+                FpsMode::SpeedChange => (translations.fps_conversion_speed_change(), translations.fps_conversion_speed_change_hint()),
+            };
+            changed |= ui.radio_value(&mut settings.mode, mode, label).on_hover_text(hint).changed();
+        }
+    });
+    if settings.mode == FpsMode::Minterpolate {
+        ui.horizontal(|ui| {
+            ui.label("mi_mode:");
+            for mi_mode in MI_MODES {
+                if ui.radio(settings.mi_mode == mi_mode, mi_mode).clicked() {
+                    settings.mi_mode = mi_mode.to_string();
+                    changed = true;
+                }
+            }
+        });
+        ui.add_enabled_ui(settings.mi_mode == "mci", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("mc_mode:");
+                for mc_mode in MC_MODES {
+                    if ui.radio(settings.mc_mode == mc_mode, mc_mode).clicked() {
+                        settings.mc_mode = mc_mode.to_string();
+                        changed = true;
+                    }
+                }
+            });
+        });
+        ui.label(egui::RichText::new(translations.fps_conversion_minterpolate_slow()).weak());
+    }
+
+    match settings.validate() {
+        Err(e) => {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+        }
+        Ok(()) => {
+            let audio_hint = match settings.mode {
+                FpsMode::SpeedChange => translations.fps_conversion_audio_retimed(),
+                FpsMode::Fps | FpsMode::Minterpolate => translations.fps_conversion_audio_untouched(),
+            };
+            ui.label(egui::RichText::new(audio_hint).weak());
+        }
+    }
+
+    changed
+}
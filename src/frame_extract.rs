@@ -0,0 +1,257 @@
+use crate::app_state::VideoSettings;
+use crate::language::Translations;
+
+/// Used when the pattern field is empty
+pub const DEFAULT_NAME_PATTERN: &str = "frame_%04d";
+
+/// Frame extraction modes, VideoSettings::frame_extract_mode
+pub const MODE_ALL: &str = "all";
+pub const MODE_EVERY_N_FRAMES: &str = "interval";
+pub const MODE_EVERY_N_SECONDS: &str = "seconds";
+/// Older settings: one frame per second inside the time range
+const MODE_LEGACY_TIME: &str = "time";
+
+/// Name pattern with a frame number placeholder, which the image2 muxer needs to write more than one file
+fn name_pattern(settings: &VideoSettings) -> String {
+    let pattern = settings.frame_name_pattern.trim();
+    let pattern = if pattern.is_empty() { DEFAULT_NAME_PATTERN } else { pattern };
+    if pattern.contains('%') {
+        pattern.to_string()
+    } else {
+        format!("{}_%04d", pattern)
+    }
+}
+
+/// Output path handed to ffmpeg: the output directory joined with the name pattern and image extension
+pub fn output_pattern(settings: &VideoSettings) -> String {
+    std::path::Path::new(&settings.frame_output_dir)
+        .join(format!("{}.{}", name_pattern(settings), settings.frame_format))
+        .display()
+        .to_string()
+}
+
+/// ffmpeg output for a task: a path that already holds a pattern is used as is, a plain file name
+/// becomes "<name>_%03d.<format>" next to it (workflows and older tasks)
+pub fn pattern_for_output(output_file: &str, frame_format: &str) -> String {
+    if output_file.contains('%') {
+        return output_file.to_string();
+    }
+    let output_path = std::path::Path::new(output_file);
+    let dir = output_path.parent().unwrap_or(std::path::Path::new("."));
+    let base = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    format!("{}/{}_%03d.{}", dir.display(), base, frame_format)
+}
+
+/// File name the first frame gets, shown under the pattern field
+pub fn first_file_name(settings: &VideoSettings) -> String {
+    let pattern = name_pattern(settings);
+    // Only the common %d / %0Nd forms are expanded for the hint
+    let name = match pattern.find('%') {
+        Some(start) => {
+            let rest = &pattern[start + 1..];
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            match rest[digits.len()..].strip_prefix('d') {
+                Some(after) => {
+                    let width: usize = digits.parse().unwrap_or(0);
+                    format!("{}{:0width$}{}", &pattern[..start], 1, after, width = width)
+                }
+                None => pattern.clone(),
+            }
+        }
+        None => pattern.clone(),
+    };
+    format!("{}.{}", name, settings.frame_format)
+}
+
+/// Default folder for the frames of `input`: "<name>_frames" in the default output folder or next to the input
+pub fn default_output_dir(input: &str, default_output_dir: &str) -> String {
+    let input_path = std::path::Path::new(input);
+    let folder = if default_output_dir.is_empty() {
+        input_path.parent().map(|parent| parent.to_path_buf()).unwrap_or_default()
+    } else {
+        std::path::PathBuf::from(default_output_dir)
+    };
+    let stem = input_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("output");
+    folder.join(format!("{}_frames", stem)).display().to_string()
+}
+
+/// Create the output directory if needed and make sure it is a writable folder
+pub fn prepare_output_dir(dir: &str) -> Result<(), String> {
+    if dir.trim().is_empty() {
+        return Err("no output directory selected".to_string());
+    }
+    let path = std::path::Path::new(dir);
+    std::fs::create_dir_all(path).map_err(|e| format!("cannot create {}: {}", dir, e))?;
+    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", dir));
+    }
+    if metadata.permissions().readonly() {
+        return Err(format!("{} is read-only", dir));
+    }
+    Ok(())
+}
+
+/// Start and optional end of the extraction range in seconds; unparsable fields are ignored
+pub fn range_seconds(settings: &VideoSettings) -> (f64, Option<f64>) {
+    let start = crate::locale_format::parse_duration(&settings.frame_start_time).unwrap_or(0.0).max(0.0);
+    let end = crate::locale_format::parse_duration(&settings.frame_end_time).ok().filter(|end| *end > start);
+    (start, end)
+}
+
+/// Seconds between extracted frames in the every-N-seconds mode
+pub fn interval_seconds(settings: &VideoSettings) -> Option<f64> {
+    match settings.frame_extract_mode.as_str() {
+        MODE_EVERY_N_SECONDS => Some((settings.frame_interval_seconds as f64).max(0.001)),
+        MODE_LEGACY_TIME => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Frames the extraction should write for an input of `duration` seconds at `fps`, used for progress
+pub fn expected_frame_count(settings: &VideoSettings, duration: f64, fps: f64) -> Option<u64> {
+    let (start, end) = range_seconds(settings);
+    let span = end.unwrap_or(duration).min(duration) - start;
+    if span <= 0.0 {
+        return None;
+    }
+    let frames = if let Some(interval) = interval_seconds(settings) {
+        span / interval
+    } else if fps > 0.0 {
+        let source_frames = span * fps;
+        match settings.frame_extract_mode.as_str() {
+            MODE_ALL => source_frames,
+            _ => source_frames / settings.frame_interval.max(1) as f64,
+        }
+    } else {
+        return None;
+    };
+    Some((frames.ceil() as u64).max(1))
+}
+
+/// Output directory picker and file name pattern, replacing the single output file for this operation
+pub fn show_output_settings(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+    ui.group(|ui| {
+        ui.label(translations.frame_extract_output_dir());
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut settings.frame_output_dir)
+                .desired_width(ui.available_width() - 90.0));
+            if ui.button(translations.frame_extract_browse()).clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    settings.frame_output_dir = folder.display().to_string();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(translations.frame_extract_name_pattern());
+            ui.add(egui::TextEdit::singleline(&mut settings.frame_name_pattern)
+                .hint_text(DEFAULT_NAME_PATTERN)
+                .desired_width(160.0));
+            ui.weak(format!(".{}", settings.frame_format));
+        });
+        ui.weak(format!("{} {}", translations.frame_extract_first_file(), first_file_name(settings)));
+        if settings.frame_output_dir.is_empty() {
+            ui.colored_label(egui::Color32::from_rgb(255, 150, 100), translations.frame_extract_no_output_dir());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: &str, start: &str, end: &str) -> VideoSettings {
+        VideoSettings {
+            frame_extract_mode: mode.to_string(),
+            frame_start_time: start.to_string(),
+            frame_end_time: end.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn range_ignores_unparsable_and_backwards_ends() {
+        assert_eq!(range_seconds(&settings(MODE_ALL, "00:00:00", "")), (0.0, None));
+        assert_eq!(range_seconds(&settings(MODE_ALL, "1:30", "02:00.5")), (90.0, Some(120.5)));
+        assert_eq!(range_seconds(&settings(MODE_ALL, "abc", "10")), (0.0, Some(10.0)));
+        assert_eq!(range_seconds(&settings(MODE_ALL, "20", "10")), (20.0, None));
+        assert_eq!(range_seconds(&settings(MODE_ALL, "-5", "10")), (0.0, Some(10.0)));
+    }
+
+    #[test]
+    fn interval_applies_to_the_seconds_modes_only() {
+        let mut video = settings(MODE_EVERY_N_SECONDS, "", "");
+        video.frame_interval_seconds = 2.5;
+        assert_eq!(interval_seconds(&video), Some(2.5));
+        video.frame_interval_seconds = 0.0;
+        assert_eq!(interval_seconds(&video), Some(0.001));
+        assert_eq!(interval_seconds(&settings(MODE_LEGACY_TIME, "", "")), Some(1.0));
+        assert_eq!(interval_seconds(&settings(MODE_ALL, "", "")), None);
+        assert_eq!(interval_seconds(&settings(MODE_EVERY_N_FRAMES, "", "")), None);
+    }
+
+    #[test]
+    fn expected_frames_per_mode_inside_the_range() {
+        let mut seconds = settings(MODE_EVERY_N_SECONDS, "10", "40");
+        seconds.frame_interval_seconds = 2.0;
+        assert_eq!(expected_frame_count(&seconds, 120.0, 25.0), Some(15));
+        // The end is clamped to the input duration
+        assert_eq!(expected_frame_count(&seconds, 20.0, 25.0), Some(5));
+        // A range past the end writes nothing to count
+        assert_eq!(expected_frame_count(&seconds, 8.0, 25.0), None);
+
+        assert_eq!(expected_frame_count(&settings(MODE_ALL, "", "4"), 60.0, 25.0), Some(100));
+        let mut every_n = settings(MODE_EVERY_N_FRAMES, "", "");
+        every_n.frame_interval = 30;
+        assert_eq!(expected_frame_count(&every_n, 10.0, 30.0), Some(10));
+        // Without a frame rate only the seconds modes can be counted
+        assert_eq!(expected_frame_count(&every_n, 10.0, 0.0), None);
+        assert_eq!(expected_frame_count(&seconds, 120.0, 0.0), Some(15));
+        // Shorter than one interval still writes a frame
+        assert_eq!(expected_frame_count(&settings(MODE_EVERY_N_SECONDS, "", "0.2"), 60.0, 25.0), Some(1));
+    }
+
+    #[test]
+    fn name_patterns_get_a_frame_number() {
+        let mut video = VideoSettings { frame_output_dir: "shots".to_string(), frame_format: "jpg".to_string(), ..Default::default() };
+        assert_eq!(output_pattern(&video), std::path::Path::new("shots").join("frame_%04d.jpg").display().to_string());
+        assert_eq!(first_file_name(&video), "frame_0001.jpg");
+
+        video.frame_name_pattern = "  ".to_string();
+        assert_eq!(first_file_name(&video), "frame_0001.jpg");
+        video.frame_name_pattern = "still".to_string();
+        assert_eq!(first_file_name(&video), "still_0001.jpg");
+        video.frame_name_pattern = "take%d_cam".to_string();
+        assert_eq!(first_file_name(&video), "take1_cam.jpg");
+        video.frame_name_pattern = "img%03x".to_string();
+        assert_eq!(first_file_name(&video), "img%03x.jpg");
+    }
+
+    #[test]
+    fn task_outputs_become_patterns() {
+        assert_eq!(pattern_for_output("/out/shots/%05d.png", "png"), "/out/shots/%05d.png");
+        assert_eq!(pattern_for_output("/out/clip.png", "jpg"), "/out/clip_%03d.jpg");
+        assert_eq!(
+            default_output_dir("/media/clip.mov", ""),
+            std::path::Path::new("/media").join("clip_frames").display().to_string(),
+        );
+        assert_eq!(
+            default_output_dir("/media/clip.mov", "/exports"),
+            std::path::Path::new("/exports").join("clip_frames").display().to_string(),
+        );
+    }
+
+    #[test]
+    fn output_dir_is_created_and_checked() {
+        let dir = std::env::temp_dir().join(format!("ffgui_frames_{}", uuid::Uuid::new_v4()));
+        let nested = dir.join("a").join("b");
+        assert_eq!(prepare_output_dir(&nested.display().to_string()), Ok(()));
+        assert!(nested.is_dir());
+
+        let file = dir.join("file.png");
+        std::fs::write(&file, b"").unwrap();
+        assert!(prepare_output_dir(&file.display().to_string()).is_err());
+        assert!(prepare_output_dir(" ").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
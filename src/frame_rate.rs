@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+/// Frame rate setting that keeps the source rate: no -r is passed
+pub const SAME_AS_SOURCE: &str = "auto";
+
+/// NTSC rates are entered as 23.976 or 23.98 but are exactly n × 1000/1001
+const NTSC_BASES: [u32; 6] = [24, 30, 48, 60, 120, 240];
+const NTSC_TOLERANCE: f64 = 0.01;
+
+/// Exact frame rate as a reduced fraction, e.g. 24000/1001
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameRate {
+    pub num: u32,
+    pub den: u32,
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl FrameRate {
+    pub const fn new(num: u32, den: u32) -> Self {
+        Self { num, den }
+    }
+
+    fn reduced(num: u64, den: u64) -> Option<Self> {
+        if num == 0 || den == 0 {
+            return None;
+        }
+        let divisor = gcd(num, den);
+        let (num, den) = (num / divisor, den / divisor);
+        (num <= u32::MAX as u64 && den <= u32::MAX as u64).then_some(Self { num: num as u32, den: den as u32 })
+    }
+
+    /// "24000/1001", "25", "ntsc-film", or a decimal; decimals close to an NTSC rate become the
+    /// exact NTSC fraction. None for "auto", empty text, 0/0 (ffprobe's "unknown") and garbage.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(rate) = Self::named(&text.to_lowercase()) {
+            return Some(rate);
+        }
+        if let Some((num, den)) = text.split_once('/') {
+            return Self::reduced(num.trim().parse().ok()?, den.trim().parse().ok()?);
+        }
+        let value = crate::locale_format::parse_decimal(text).ok().filter(|value| *value > 0.0)?;
+        if value.fract() == 0.0 {
+            return Self::reduced(value as u64, 1);
+        }
+        if let Some(base) = NTSC_BASES.iter().find(|base| (value - **base as f64 * 1000.0 / 1001.0).abs() < NTSC_TOLERANCE) {
+            return Some(Self::new(base * 1000, 1001));
+        }
+        // Other decimals are taken as written: 12.5 is 25/2
+        let decimals = text.replace(',', ".").split_once('.').map(|(_, fraction)| fraction.len()).unwrap_or(0).min(6) as u32;
+        let scale = 10u64.pow(decimals);
+        Self::reduced((value * scale as f64).round() as u64, scale)
+    }
+
+    fn named(name: &str) -> Option<Self> {
+        match name {
+            "ntsc" => Some(Self::new(30000, 1001)),
+            "pal" => Some(Self::new(25, 1)),
+            "qntsc" | "sntsc" => Some(Self::new(30000, 1001)),
+            "qpal" | "spal" => Some(Self::new(25, 1)),
+            "film" => Some(Self::new(24, 1)),
+            "ntsc-film" => Some(Self::new(24000, 1001)),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// Value for -r: whole rates as integers, everything else as the exact fraction
+    pub fn ffmpeg_arg(&self) -> String {
+        if self.den == 1 { self.num.to_string() } else { format!("{}/{}", self.num, self.den) }
+    }
+}
+
+/// 23.976, 25, 29.97: up to three decimals without trailing zeros
+impl std::fmt::Display for FrameRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = format!("{:.3}", self.as_f64());
+        write!(f, "{}", text.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+/// Rates offered in the frame rate picker
+pub const COMMON_RATES: [FrameRate; 8] = [
+    FrameRate::new(24000, 1001),
+    FrameRate::new(24, 1),
+    FrameRate::new(25, 1),
+    FrameRate::new(30000, 1001),
+    FrameRate::new(30, 1),
+    FrameRate::new(50, 1),
+    FrameRate::new(60000, 1001),
+    FrameRate::new(60, 1),
+];
+
+pub fn is_same_as_source(fps: &str) -> bool {
+    let text = fps.trim();
+    text.is_empty() || text.eq_ignore_ascii_case(SAME_AS_SOURCE)
+}
+
+/// The -r value for an fps setting; None keeps the source rate. Text that is not a rate is passed
+/// through for ffmpeg to reject, the settings panel flags it first.
+pub fn output_rate(fps: &str) -> Option<String> {
+    if is_same_as_source(fps) {
+        return None;
+    }
+    Some(FrameRate::parse(fps).map(|rate| rate.ffmpeg_arg()).unwrap_or_else(|| fps.trim().to_string()))
+}
+
+/// "24000/1001 (23.976 fps)", or "25 fps" for whole rates
+pub fn describe(rate: FrameRate) -> String {
+    if rate.den == 1 {
+        format!("{} fps", rate.num)
+    } else {
+        format!("{} ({} fps)", rate.ffmpeg_arg(), rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::VideoSettings;
+    use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+    fn rate_arg(fps: &str) -> Option<String> {
+        let video = VideoSettings { fps: fps.to_string(), ..Default::default() };
+        let args = ComprehensiveCommandBuilder::build_video_conversion_command("in.mov", "out.mp4", &video, None).unwrap();
+        let rates: Vec<&String> = args.windows(2).filter(|pair| pair[0] == "-r").map(|pair| &pair[1]).collect();
+        assert!(rates.len() <= 1, "{:?}", args);
+        rates.first().map(|rate| rate.to_string())
+    }
+
+    #[test]
+    fn same_as_source_passes_no_rate() {
+        assert_eq!(VideoSettings::default().fps, SAME_AS_SOURCE);
+        for fps in ["auto", "AUTO", "", "  "] {
+            assert!(is_same_as_source(fps));
+            assert_eq!(output_rate(fps), None);
+            assert_eq!(rate_arg(fps), None, "{:?}", fps);
+        }
+    }
+
+    #[test]
+    fn explicit_rates_are_exact_fractions() {
+        assert_eq!(rate_arg("23.976").as_deref(), Some("24000/1001"));
+        assert_eq!(rate_arg("23,98").as_deref(), Some("24000/1001"));
+        assert_eq!(rate_arg("29.97").as_deref(), Some("30000/1001"));
+        assert_eq!(rate_arg("59.94").as_deref(), Some("60000/1001"));
+        assert_eq!(rate_arg("30000/1001").as_deref(), Some("30000/1001"));
+        assert_eq!(rate_arg("25").as_deref(), Some("25"));
+        assert_eq!(rate_arg("50/2").as_deref(), Some("25"));
+        assert_eq!(rate_arg("12.5").as_deref(), Some("25/2"));
+        assert_eq!(rate_arg("ntsc-film").as_deref(), Some("24000/1001"));
+        // Not a rate: left for ffmpeg to reject
+        assert_eq!(output_rate("fast"), Some("fast".to_string()));
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(FrameRate::parse("24000/1001"), Some(FrameRate::new(24000, 1001)));
+        assert_eq!(FrameRate::parse(" 48000 / 2002 "), Some(FrameRate::new(24000, 1001)));
+        assert_eq!(FrameRate::parse("119.88"), Some(FrameRate::new(120000, 1001)));
+        assert_eq!(FrameRate::parse("PAL"), Some(FrameRate::new(25, 1)));
+        assert_eq!(FrameRate::parse("24.5"), Some(FrameRate::new(49, 2)));
+        assert_eq!(FrameRate::parse("0/0"), None);
+        assert_eq!(FrameRate::parse("25/0"), None);
+        assert_eq!(FrameRate::parse("0"), None);
+        assert_eq!(FrameRate::parse("-25"), None);
+        assert_eq!(FrameRate::parse("auto"), None);
+        assert_eq!(FrameRate::parse("x/y"), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(FrameRate::new(24000, 1001).to_string(), "23.976");
+        assert_eq!(FrameRate::new(30000, 1001).to_string(), "29.97");
+        assert_eq!(FrameRate::new(25, 1).to_string(), "25");
+        assert_eq!(describe(FrameRate::new(60000, 1001)), "60000/1001 (59.94 fps)");
+        assert_eq!(describe(FrameRate::new(50, 1)), "50 fps");
+        // Every picker entry parses back from its own argument
+        for rate in COMMON_RATES {
+            assert_eq!(FrameRate::parse(&rate.ffmpeg_arg()), Some(rate));
+            assert_eq!(FrameRate::parse(&rate.to_string()), Some(rate));
+        }
+    }
+}
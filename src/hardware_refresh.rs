@@ -0,0 +1,353 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+use crate::app_state::{ProcessingTask, TaskStatus};
+use crate::hardware_detector::{HardwareDetector, HardwareInfo};
+
+/// Re-detections are started at most this often; later requests wait for the interval to pass
+pub const MIN_REDETECT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Encoder name suffixes of the hardware encoders
+const HARDWARE_ENCODER_SUFFIXES: &[&str] = &["_nvenc", "_qsv", "_amf", "_vaapi", "_videotoolbox", "_v4l2m2m"];
+
+/// ffmpeg errors (lowercased) that mean the driver or device went away or changed, rather than bad settings
+const DEVICE_FAILURE_SIGNATURES: &[&str] = &[
+    "no nvenc capable devices found",
+    "cannot load libcuda",
+    "cannot load nvcuda.dll",
+    "cannot load libnvidia-encode",
+    "cannot load nvencodeapi",
+    "driver does not support the required nvenc api version",
+    "openencodesessionex failed",
+    "cuda_error_no_device",
+    "error creating a mfx session",
+    "error initializing an mfx session",
+    "failed to initialise vaapi connection",
+    "no va display found",
+    "device creation failed",
+    "no device available for encoder",
+    "dll amfrt64.dll failed to open",
+    "failed to initialize amf",
+    "failed to create direct3d device",
+];
+
+type DetectionResult = (HardwareInfo, Vec<String>);
+
+/// Where hardware detection is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionState {
+    /// Never started
+    Idle,
+    /// First detection; nothing is known yet
+    Detecting,
+    Ready,
+    /// Re-detection; the previous results stay in use until it finishes
+    Refreshing,
+}
+
+/// Hardware and encoder detection on a background thread. Startup, the Re-detect button and automatic
+/// re-detection after device failures all go through `request`, which debounces re-detections.
+pub struct HardwareDetection {
+    state: DetectionState,
+    receiver: Option<Receiver<DetectionResult>>,
+    last_started: Option<Instant>,
+    /// A re-detection was asked for within the debounce interval and starts once it passes
+    queued: bool,
+    /// Runs on the detection thread
+    detect: fn() -> DetectionResult,
+    pub hardware_info: Option<HardwareInfo>,
+    pub encoders: Option<Vec<String>>,
+}
+
+impl Default for HardwareDetection {
+    fn default() -> Self {
+        Self {
+            state: DetectionState::Idle,
+            receiver: None,
+            last_started: None,
+            queued: false,
+            detect: detect_hardware_and_encoders,
+            hardware_info: None,
+            encoders: None,
+        }
+    }
+}
+
+impl HardwareDetection {
+    pub fn state(&self) -> DetectionState {
+        self.state
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, DetectionState::Detecting | DetectionState::Refreshing)
+    }
+
+    /// Time left before another re-detection may start
+    pub fn cooldown_remaining(&self) -> Option<Duration> {
+        self.last_started
+            .map(|started| started.elapsed())
+            .filter(|elapsed| *elapsed < MIN_REDETECT_INTERVAL)
+            .map(|elapsed| MIN_REDETECT_INTERVAL - elapsed)
+    }
+
+    /// Ask for a (re-)detection. Returns true when one was started now; while one runs the request is
+    /// dropped, and within the debounce interval it is queued until the interval passes.
+    pub fn request(&mut self, reason: &str) -> bool {
+        if self.is_running() {
+            return false;
+        }
+        if self.state != DetectionState::Idle && self.cooldown_remaining().is_some() {
+            if !self.queued {
+                log_info!("Hardware re-detection ({}) queued until the debounce interval passes", reason);
+            }
+            self.queued = true;
+            return false;
+        }
+        log_info!("Starting hardware detection ({})", reason);
+        self.start(self.detect);
+        true
+    }
+
+    fn start(&mut self, detect: fn() -> DetectionResult) {
+        let (tx, rx) = channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(detect());
+        });
+        self.receiver = Some(rx);
+        self.last_started = Some(Instant::now());
+        self.queued = false;
+        self.state = if self.encoders.is_some() { DetectionState::Refreshing } else { DetectionState::Detecting };
+    }
+
+    /// Pick up a finished detection and start a queued one whose interval passed. Returns the new
+    /// encoder list when a detection finished this call.
+    pub fn poll(&mut self) -> Option<Vec<String>> {
+        if self.queued && !self.is_running() && self.cooldown_remaining().is_none() {
+            self.request("queued");
+        }
+        let receiver = self.receiver.as_ref()?;
+        match receiver.try_recv() {
+            Ok((hardware_info, encoders)) => {
+                self.finish(Some(hardware_info), encoders.clone());
+                Some(encoders)
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                // The detection thread panicked; keep what was known so a later request can retry
+                log_warn!("Hardware detection thread ended without a result");
+                let encoders = self.encoders.clone().unwrap_or_default();
+                self.finish(self.hardware_info.clone(), encoders.clone());
+                Some(encoders)
+            }
+        }
+    }
+
+    fn finish(&mut self, hardware_info: Option<HardwareInfo>, encoders: Vec<String>) {
+        self.receiver = None;
+        self.hardware_info = hardware_info;
+        self.encoders = Some(encoders);
+        self.state = DetectionState::Ready;
+    }
+
+    /// Whether re-detection is waiting for the debounce interval, so the UI keeps polling
+    pub fn has_queued_request(&self) -> bool {
+        self.queued
+    }
+}
+
+fn detect_hardware_and_encoders() -> DetectionResult {
+    let hardware_info = HardwareDetector::detect_hardware();
+    let encoders = crate::codec_manager::CodecManager::detect_hardware_encoders().unwrap_or_default();
+    (hardware_info, encoders)
+}
+
+pub fn is_hardware_encoder(codec: &str) -> bool {
+    HARDWARE_ENCODER_SUFFIXES.iter().any(|suffix| codec.ends_with(suffix))
+}
+
+/// Software encoder producing the same format as a hardware encoder
+pub fn software_encoder(codec: &str) -> Option<&'static str> {
+    if !is_hardware_encoder(codec) {
+        return None;
+    }
+    match codec.split('_').next()? {
+        "h264" => Some("libx264"),
+        "hevc" => Some("libx265"),
+        "av1" => Some("libsvtav1"),
+        "vp9" => Some("libvpx-vp9"),
+        "vp8" => Some("libvpx"),
+        "mpeg2" => Some("mpeg2video"),
+        "mjpeg" => Some("mjpeg"),
+        "prores" => Some("prores_ks"),
+        _ => None,
+    }
+}
+
+/// A failed task whose hardware encoder could not reach its driver or device
+pub fn is_device_failure(task: &ProcessingTask) -> bool {
+    if task.status != TaskStatus::Failed {
+        return false;
+    }
+    let used_hardware = task.executed_commands.iter().flatten().any(|arg| is_hardware_encoder(arg))
+        || task.video_settings.as_ref().is_some_and(|settings| is_hardware_encoder(&settings.codec));
+    let error = task.error_message.as_deref().unwrap_or("").to_lowercase();
+    used_hardware && DEVICE_FAILURE_SIGNATURES.iter().any(|signature| error.contains(signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware_detector::{CpuInfo, CpuVendor};
+
+    fn fake_result(encoders: &[&str]) -> DetectionResult {
+        let hardware_info = HardwareInfo {
+            gpus: Vec::new(),
+            cpu: CpuInfo { name: "Test CPU".to_string(), vendor: CpuVendor::Intel, has_integrated_graphics: false, supports_quicksync: false },
+            supported_encoders: encoders.iter().map(|encoder| encoder.to_string()).collect(),
+        };
+        (hardware_info, encoders.iter().map(|encoder| encoder.to_string()).collect())
+    }
+
+    fn detect_nvenc() -> DetectionResult {
+        fake_result(&["h264_nvenc", "hevc_nvenc"])
+    }
+
+    fn detect_nothing() -> DetectionResult {
+        fake_result(&[])
+    }
+
+    fn detect_panics() -> DetectionResult {
+        panic!("detection failed")
+    }
+
+    fn detection(detect: fn() -> DetectionResult) -> HardwareDetection {
+        HardwareDetection { detect, ..Default::default() }
+    }
+
+    /// Poll until the detection thread delivers
+    fn finish(detection: &mut HardwareDetection) -> Vec<String> {
+        for _ in 0..500 {
+            if let Some(encoders) = detection.poll() {
+                return encoders;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        panic!("detection did not finish");
+    }
+
+    /// Pretend the last detection started a full debounce interval ago
+    fn let_interval_pass(detection: &mut HardwareDetection) {
+        detection.last_started = Instant::now().checked_sub(MIN_REDETECT_INTERVAL);
+    }
+
+    #[test]
+    fn first_detection_then_refresh() {
+        let mut detection = detection(detect_nvenc);
+        assert_eq!(detection.state(), DetectionState::Idle);
+        assert_eq!(detection.poll(), None);
+        assert_eq!(detection.cooldown_remaining(), None);
+
+        assert!(detection.request("startup"));
+        assert_eq!(detection.state(), DetectionState::Detecting);
+        assert!(detection.is_running());
+        // Requests while running are dropped, not queued
+        assert!(!detection.request("button"));
+        assert!(!detection.has_queued_request());
+
+        assert_eq!(finish(&mut detection), ["h264_nvenc", "hevc_nvenc"]);
+        assert_eq!(detection.state(), DetectionState::Ready);
+        assert_eq!(detection.hardware_info.as_ref().unwrap().supported_encoders.len(), 2);
+        assert!(detection.cooldown_remaining().is_some());
+
+        // A re-detection keeps the old results visible while it runs
+        let_interval_pass(&mut detection);
+        detection.detect = detect_nothing;
+        assert!(detection.request("button"));
+        assert_eq!(detection.state(), DetectionState::Refreshing);
+        assert_eq!(detection.encoders.as_deref(), Some(&["h264_nvenc".to_string(), "hevc_nvenc".to_string()][..]));
+        assert!(finish(&mut detection).is_empty());
+        assert_eq!(detection.encoders, Some(Vec::new()));
+        assert_eq!(detection.state(), DetectionState::Ready);
+    }
+
+    #[test]
+    fn requests_within_the_interval_are_queued_once() {
+        let mut detection = detection(detect_nvenc);
+        assert!(detection.request("startup"));
+        finish(&mut detection);
+
+        assert!(!detection.request("device failure"));
+        assert!(!detection.request("device failure"));
+        assert!(detection.has_queued_request());
+        assert_eq!(detection.state(), DetectionState::Ready);
+        // Still inside the interval: polling does not start it
+        assert_eq!(detection.poll(), None);
+        assert!(!detection.is_running());
+
+        let_interval_pass(&mut detection);
+        detection.poll();
+        assert_eq!(detection.state(), DetectionState::Refreshing);
+        assert!(!detection.has_queued_request());
+        assert_eq!(finish(&mut detection), ["h264_nvenc", "hevc_nvenc"]);
+        let remaining = detection.cooldown_remaining().unwrap();
+        assert!(remaining > MIN_REDETECT_INTERVAL - Duration::from_secs(5) && remaining <= MIN_REDETECT_INTERVAL);
+    }
+
+    #[test]
+    fn a_crashed_detection_keeps_the_previous_results() {
+        let mut detection = detection(detect_nvenc);
+        detection.request("startup");
+        finish(&mut detection);
+
+        let_interval_pass(&mut detection);
+        detection.detect = detect_panics;
+        assert!(detection.request("button"));
+        assert_eq!(finish(&mut detection), ["h264_nvenc", "hevc_nvenc"]);
+        assert_eq!(detection.state(), DetectionState::Ready);
+        assert!(detection.hardware_info.is_some());
+
+        // With nothing known yet the list is just empty
+        let mut first = self::detection(detect_panics);
+        first.request("startup");
+        assert!(finish(&mut first).is_empty());
+        assert!(first.hardware_info.is_none());
+    }
+
+    #[test]
+    fn hardware_encoders_and_their_software_fallbacks() {
+        assert!(is_hardware_encoder("hevc_videotoolbox"));
+        assert!(!is_hardware_encoder("libx264"));
+        assert_eq!(software_encoder("h264_nvenc"), Some("libx264"));
+        assert_eq!(software_encoder("hevc_qsv"), Some("libx265"));
+        assert_eq!(software_encoder("av1_amf"), Some("libsvtav1"));
+        assert_eq!(software_encoder("vp9_vaapi"), Some("libvpx-vp9"));
+        assert_eq!(software_encoder("libx264"), None);
+        assert_eq!(software_encoder("weird_nvenc"), None);
+    }
+
+    #[test]
+    fn only_device_errors_of_hardware_tasks_trigger_redetection() {
+        use crate::app_state::{OperationType, VideoSettings};
+
+        let mut task = ProcessingTask::new(OperationType::VideoConvert, vec!["in.mp4".to_string()], "out.mp4".to_string());
+        task.status = TaskStatus::Failed;
+        task.error_message = Some("[h264_nvenc @ 0x55] OpenEncodeSessionEx failed: unsupported device (2)".to_string());
+        task.executed_commands = vec![vec!["ffmpeg".to_string(), "-c:v".to_string(), "h264_nvenc".to_string()]];
+        assert!(is_device_failure(&task));
+
+        // The codec in the settings counts when no command was recorded
+        task.executed_commands.clear();
+        task.video_settings = Some(VideoSettings { codec: "hevc_qsv".to_string(), ..Default::default() });
+        assert!(is_device_failure(&task));
+
+        task.video_settings = Some(VideoSettings { codec: "libx264".to_string(), ..Default::default() });
+        assert!(!is_device_failure(&task));
+
+        task.video_settings = Some(VideoSettings { codec: "h264_nvenc".to_string(), ..Default::default() });
+        task.error_message = Some("Invalid argument".to_string());
+        assert!(!is_device_failure(&task));
+        task.error_message = Some("No NVENC capable devices found".to_string());
+        task.status = TaskStatus::Cancelled;
+        assert!(!is_device_failure(&task));
+    }
+}
@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::app_state::{ProjectConfig, TaskStatus, UserPreferences};
+use crate::automation_flow::{AutomationWorkflow, NodeRunStatus, WorkflowExecutor};
+use crate::folder_run::{self, FolderRunSettings};
+use crate::language::{Language, Translations};
+use crate::run_cancel::CancelToken;
+use crate::task_executor::TaskExecutor;
+use crate::workflow_file;
+
+pub const RUN_WORKFLOW_FLAG: &str = "--run-workflow";
+pub const RUN_PROJECT_FLAG: &str = "--run-project";
+const INPUT_FLAG: &str = "--input";
+const OUTPUT_DIR_FLAG: &str = "--output-dir";
+const VERBOSE_FLAG: &str = "--verbose";
+
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+/// What shells report for a process ended by Ctrl+C
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// How often a running job's progress is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const USAGE: &str = "\
+Usage:
+  ff-gui --run-workflow <flow.json> [--input <file>] [--output-dir <folder>] [--verbose]
+  ff-gui --run-project <project.ffcfg> [--verbose]
+
+Runs without opening a window and prints progress to stdout.
+  --input       run the workflow on this file instead of the one its input node names
+  --output-dir  write the workflow's outputs to this folder, named after the input
+  --verbose     also print the log lines the window would write to the console";
+
+enum Job {
+    Workflow { path: PathBuf, input: Option<PathBuf>, output_dir: Option<PathBuf> },
+    Project { path: PathBuf },
+}
+
+/// Run the job named on the command line without a window. None when the arguments ask for
+/// no headless run and the window should open; otherwise the process exit code.
+pub fn run_from_args(args: Vec<String>) -> Option<i32> {
+    if !args.iter().any(|arg| arg == RUN_WORKFLOW_FLAG || arg == RUN_PROJECT_FLAG) {
+        return None;
+    }
+    let (job, verbose) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("ff-gui: {}\n\n{}", e, USAGE);
+            return Some(EXIT_USAGE);
+        }
+    };
+    crate::logging::set_console(verbose);
+
+    let token = CancelToken::default();
+    stop_on_ctrl_c(&token);
+    let result = match job {
+        Job::Workflow { path, input, output_dir } => run_workflow(&path, input.as_deref(), output_dir.as_deref(), &token),
+        Job::Project { path } => run_project(&path, &token),
+    };
+    Some(match result {
+        _ if token.is_cancelled() => {
+            println!("Interrupted; stopped ffmpeg");
+            EXIT_INTERRUPTED
+        }
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("ff-gui: {}", e);
+            EXIT_FAILED
+        }
+    })
+}
+
+fn parse_args(args: Vec<String>) -> Result<(Job, bool), String> {
+    let mut workflow = None;
+    let mut project = None;
+    let mut input = None;
+    let mut output_dir = None;
+    let mut verbose = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().map(PathBuf::from).ok_or_else(|| format!("{} needs a value", flag));
+        match arg.as_str() {
+            RUN_WORKFLOW_FLAG => workflow = Some(value(RUN_WORKFLOW_FLAG)?),
+            RUN_PROJECT_FLAG => project = Some(value(RUN_PROJECT_FLAG)?),
+            INPUT_FLAG => input = Some(value(INPUT_FLAG)?),
+            OUTPUT_DIR_FLAG => output_dir = Some(value(OUTPUT_DIR_FLAG)?),
+            VERBOSE_FLAG => verbose = true,
+            crate::safe_mode::CLI_FLAG => {}
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    let job = match (workflow, project) {
+        (Some(path), None) => Job::Workflow { path, input, output_dir },
+        (None, Some(path)) if input.is_none() && output_dir.is_none() => Job::Project { path },
+        (None, Some(_)) => return Err(format!("{} and {} only apply to {}", INPUT_FLAG, OUTPUT_DIR_FLAG, RUN_WORKFLOW_FLAG)),
+        _ => return Err(format!("give either {} or {}", RUN_WORKFLOW_FLAG, RUN_PROJECT_FLAG)),
+    };
+    Ok((job, verbose))
+}
+
+/// Cancel `token` on Ctrl+C, which kills the ffmpeg processes registered with it
+fn stop_on_ctrl_c(token: &CancelToken) {
+    let token = token.clone();
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log_warn!("Ctrl+C will not stop ffmpeg cleanly: {}", e);
+                return;
+            }
+        };
+        if runtime.block_on(tokio::signal::ctrl_c()).is_ok() {
+            eprintln!("\nStopping...");
+            token.cancel();
+        }
+    });
+}
+
+fn run_workflow(path: &Path, input: Option<&Path>, output_dir: Option<&Path>, token: &CancelToken) -> Result<(), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let (workflow, issues) = workflow_file::parse(&content).map_err(|e| format!("{} is not a readable workflow: {}", path.display(), e))?;
+    if !issues.is_empty() {
+        println!("Loaded {} with {} issue(s); fix them in the editor if the run fails", path.display(), issues.len());
+    }
+    let workflow = workflow_for_run(workflow.flattened(), input, output_dir)?;
+    let order = workflow.get_execution_order()?;
+
+    let mut executor = WorkflowExecutor::new();
+    if let Some(preferences) = UserPreferences::load() {
+        executor.incremental = preferences.incremental_workflow_execution;
+        executor.keep_files_on_cancel = preferences.keep_stopped_workflow_files;
+    }
+    let mut runner = executor.runner(token);
+    let run_state = runner.run_state.clone();
+    println!("Running workflow '{}' ({} nodes)", workflow.name, order.len());
+
+    let translations = Translations::new(Language::English);
+    let names: Vec<String> = order.iter()
+        .map(|id| workflow.nodes.get(id).map_or_else(|| id.clone(), |node| translations.node_type_name(&node.node_type)))
+        .collect();
+    let workflow_id = workflow.id.clone();
+    let thread_token = token.clone();
+    let handle = std::thread::spawn(move || crate::run_cancel::with_token(&thread_token, || runner.execute_workflow(workflow)));
+
+    let mut printed = vec![None; order.len()];
+    loop {
+        let finished = handle.is_finished();
+        for (index, node_id) in order.iter().enumerate() {
+            let Some(state) = run_state.get(&workflow_id, node_id) else {
+                continue;
+            };
+            // Progress is printed in steps of 10% so a long encode does not flood the output
+            let line = (state.status, (state.progress * 10.0) as u32);
+            if printed[index] == Some(line) || state.status == NodeRunStatus::Pending {
+                continue;
+            }
+            printed[index] = Some(line);
+            let step = format!("[{}/{}] {}", index + 1, order.len(), names[index]);
+            match state.status {
+                NodeRunStatus::Running => println!("{}: running {:.0}%", step, state.progress * 100.0),
+                NodeRunStatus::Done => println!("{}: done", step),
+                NodeRunStatus::Failed => println!("{}: failed: {}", step, state.error.as_deref().unwrap_or("unknown error")),
+                NodeRunStatus::Cancelled => println!("{}: stopped", step),
+                NodeRunStatus::Skipped => println!("{}: skipped", step),
+                NodeRunStatus::Pending => {}
+            }
+        }
+        if finished {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    handle.join().map_err(|_| "the workflow run panicked".to_string())??;
+    if !token.is_cancelled() {
+        println!("Workflow finished");
+    }
+    Ok(())
+}
+
+/// `workflow` with its input replaced by `input` and its outputs moved to `output_dir`, each when given
+fn workflow_for_run(mut workflow: AutomationWorkflow, input: Option<&Path>, output_dir: Option<&Path>) -> Result<AutomationWorkflow, String> {
+    if input.is_none() && output_dir.is_none() {
+        return Ok(workflow);
+    }
+    folder_run::check_workflow(&workflow).map_err(|problem| match problem {
+        folder_run::WorkflowProblem::NoInput => format!("{} and {} need a workflow with an input file node", INPUT_FLAG, OUTPUT_DIR_FLAG),
+        folder_run::WorkflowProblem::NoOutput => format!("{} and {} need a workflow with an output node", INPUT_FLAG, OUTPUT_DIR_FLAG),
+        folder_run::WorkflowProblem::SeveralInputs => format!("{} and {} need a workflow with a single input file node", INPUT_FLAG, OUTPUT_DIR_FLAG),
+    })?;
+    if let Some(input) = input {
+        if !input.is_file() {
+            return Err(format!("input file {} does not exist", input.display()));
+        }
+        folder_run::set_input(&mut workflow, input);
+    }
+    let Some(output_dir) = output_dir else {
+        return Ok(workflow);
+    };
+    let input = folder_run::input_path(&workflow).ok_or("the workflow's input file node names no file; give one with --input")?;
+    std::fs::create_dir_all(output_dir).map_err(|e| format!("could not create {}: {}", output_dir.display(), e))?;
+    let settings = FolderRunSettings {
+        input_dir: input.parent().map(|dir| dir.display().to_string()).unwrap_or_default(),
+        output_dir: output_dir.display().to_string(),
+        ..FolderRunSettings::default()
+    };
+    Ok(folder_run::workflow_for_file(&workflow, &input, &settings).0)
+}
+
+fn run_project(path: &Path, token: &CancelToken) -> Result<(), String> {
+    let project = ProjectConfig::load_from_file(&path.to_string_lossy())
+        .map_err(|e| format!("{} is not a readable project: {}", path.display(), e))?;
+    let name = project.project_name.clone();
+    let mut task = project.into_task().map_err(|e| format!("cannot run {}: {}", path.display(), e))?;
+    println!("Running project '{}': {:?} -> {}", name, task.operation, task.output_file);
+
+    let tasks = Arc::new(Mutex::new(vec![task.clone()]));
+    let shared = tasks.clone();
+    let thread_token = token.clone();
+    let handle = std::thread::spawn(move || {
+        let result = crate::run_cancel::with_token(&thread_token, || TaskExecutor::execute_task_with_progress(&mut task, shared));
+        (task, result)
+    });
+
+    let mut printed = None;
+    while !handle.is_finished() {
+        let progress = tasks.lock().ok().and_then(|tasks| tasks.first().map(|task| task.progress));
+        let step = progress.map(|progress| (progress * 10.0) as u32);
+        if step.is_some() && step != printed {
+            printed = step;
+            println!("running {:.0}%", progress.unwrap_or_default() * 100.0);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    let (task, result) = handle.join().map_err(|_| "the project run panicked".to_string())?;
+    result.map_err(|e| e.to_string())?;
+    if task.status == TaskStatus::Failed {
+        return Err(task.error_message.unwrap_or_else(|| "the task failed".to_string()));
+    }
+    println!("Project finished: {}", task.output_file);
+    Ok(())
+}
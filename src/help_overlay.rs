@@ -0,0 +1,133 @@
+use egui::{Align2, Color32, FontId, Id, LayerId, Order, Pos2, Rect, Response, Stroke, Ui};
+use crate::language::Translations;
+
+/// Panel keys for the help overlays
+pub const PANEL_WORKFLOW: &str = "workflow";
+pub const PANEL_SETTINGS: &str = "settings";
+
+const CALLOUT_WIDTH: f32 = 240.0;
+const ACCENT: Color32 = Color32::from_rgb(255, 190, 60);
+
+// Overlay state lives in egui memory so the static panel functions can register callouts
+// without threading extra state through every signature.
+fn activation_id(panel: &str) -> Id {
+    Id::new(("help_overlay_active", panel))
+}
+
+fn anchors_id(panel: &str) -> Id {
+    Id::new(("help_overlay_anchors", panel))
+}
+
+/// Pass number the overlay was opened in, None when closed
+fn activated_frame(ctx: &egui::Context, panel: &str) -> Option<u64> {
+    ctx.data(|d| d.get_temp::<Option<u64>>(activation_id(panel)).flatten())
+}
+
+fn set_activated_frame(ctx: &egui::Context, panel: &str, frame: Option<u64>) {
+    ctx.data_mut(|d| {
+        d.insert_temp(activation_id(panel), frame);
+        d.remove_temp::<Vec<(String, Rect)>>(anchors_id(panel));
+    });
+}
+
+pub fn is_active(ctx: &egui::Context, panel: &str) -> bool {
+    activated_frame(ctx, panel).is_some()
+}
+
+/// "?" toggle shown in toolbars and panel headers
+pub fn toggle_button(ui: &mut Ui, panel: &str, translations: &Translations) {
+    let active = is_active(ui.ctx(), panel);
+    if ui.selectable_label(active, "❓").on_hover_text(translations.help_overlay_toggle()).clicked() {
+        let frame = if active { None } else { Some(ui.ctx().cumulative_pass_nr()) };
+        set_activated_frame(ui.ctx(), panel, frame);
+    }
+}
+
+/// Attach the help text for `key` as a tooltip and, while the overlay is open, register a callout for it
+pub fn callout(ui: &Ui, response: &Response, panel: &str, key: &str, translations: &Translations) {
+    let text = translations.help_text(key);
+    if text.is_empty() {
+        return;
+    }
+    response.clone().on_hover_text(text);
+    if is_active(ui.ctx(), panel) {
+        let rect = response.rect;
+        ui.ctx().data_mut(|d| {
+            d.get_temp_mut_or_default::<Vec<(String, Rect)>>(anchors_id(panel))
+                .push((key.to_string(), rect));
+        });
+    }
+}
+
+/// Paint the callouts registered this frame on top of the panel. Call after the panel contents.
+/// Esc or any click closes the overlay; clicks still reach the widget underneath.
+pub fn paint(ctx: &egui::Context, panel: &str, translations: &Translations) {
+    let opened_in = match activated_frame(ctx, panel) {
+        Some(frame) => frame,
+        None => return,
+    };
+    let anchors = ctx.data_mut(|d| d.remove_temp::<Vec<(String, Rect)>>(anchors_id(panel))).unwrap_or_default();
+
+    // Ignore the click that opened the overlay
+    let dismissed = ctx.input(|i| i.key_pressed(egui::Key::Escape) || i.pointer.any_click());
+    if dismissed && ctx.cumulative_pass_nr() > opened_in {
+        set_activated_frame(ctx, panel, None);
+        return;
+    }
+    if anchors.is_empty() {
+        return;
+    }
+
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new(("help_overlay", panel))));
+    let screen = ctx.screen_rect();
+    let area = anchors.iter().fold(anchors[0].1, |acc, (_, rect)| acc.union(*rect));
+
+    painter.rect_filled(area.expand(6.0), 4.0, Color32::from_black_alpha(60));
+    painter.text(
+        area.left_top() - egui::vec2(0.0, 8.0),
+        Align2::LEFT_BOTTOM,
+        translations.help_overlay_dismiss_hint(),
+        FontId::proportional(12.0),
+        ACCENT,
+    );
+
+    let mut placed: Vec<Rect> = Vec::new();
+    for (index, (key, anchor)) in anchors.iter().enumerate() {
+        painter.rect_stroke(anchor.expand(2.0), 3.0, Stroke::new(1.5, ACCENT));
+
+        // Numbered badge on the widget
+        let badge = anchor.left_top() + egui::vec2(-2.0, -2.0);
+        painter.circle_filled(badge, 8.0, ACCENT);
+        painter.text(badge, Align2::CENTER_CENTER, (index + 1).to_string(), FontId::proportional(10.0), Color32::BLACK);
+
+        let galley = painter.layout(
+            format!("{}. {}", index + 1, translations.help_text(key)),
+            FontId::proportional(12.0),
+            Color32::WHITE,
+            CALLOUT_WIDTH,
+        );
+        let box_size = galley.size() + egui::vec2(12.0, 8.0);
+
+        // Prefer the right side of the widget, fall back to the left, and push down past earlier callouts
+        let mut origin = if anchor.right() + 16.0 + box_size.x <= screen.right() {
+            Pos2::new(anchor.right() + 16.0, anchor.top())
+        } else {
+            Pos2::new((anchor.left() - 16.0 - box_size.x).max(screen.left()), anchor.top())
+        };
+        let mut box_rect = Rect::from_min_size(origin, box_size);
+        while let Some(overlap) = placed.iter().find(|other| other.intersects(box_rect)) {
+            origin.y = overlap.bottom() + 4.0;
+            box_rect = Rect::from_min_size(origin, box_size);
+        }
+        if box_rect.bottom() > screen.bottom() {
+            box_rect = box_rect.translate(egui::vec2(0.0, screen.bottom() - box_rect.bottom()));
+        }
+        placed.push(box_rect);
+
+        let leader_start = Pos2::new(if box_rect.left() > anchor.right() { anchor.right() } else { anchor.left() }, anchor.center().y);
+        let leader_end = Pos2::new(if box_rect.left() > anchor.right() { box_rect.left() } else { box_rect.right() }, box_rect.top() + 10.0);
+        painter.line_segment([leader_start, leader_end], Stroke::new(1.0, ACCENT));
+        painter.rect(box_rect, 4.0, Color32::from_rgba_unmultiplied(30, 30, 30, 235), Stroke::new(1.0, ACCENT));
+        painter.galley(box_rect.min + egui::vec2(6.0, 4.0), galley, Color32::WHITE);
+    }
+}
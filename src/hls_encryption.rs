@@ -0,0 +1,448 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// AES-128 key length in bytes
+pub const KEY_LEN: usize = 16;
+
+/// Containers a decrypted stream is remuxed into
+pub const DECRYPT_CONTAINERS: [&str; 2] = ["mp4", "mkv"];
+
+/// 16 bytes from the operating system's secure generator for an AES-128 key
+pub fn generate_key() -> Result<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    getrandom::fill(&mut key).map_err(|e| anyhow!("the system random generator failed: {}", e))?;
+    Ok(key)
+}
+
+/// Write a key readable by the current user only where the platform allows it
+pub fn write_key(path: &Path, key: &[u8; KEY_LEN]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    std::io::Write::write_all(&mut options.open(path)?, key)?;
+    Ok(())
+}
+
+pub fn read_key(path: &Path) -> Result<[u8; KEY_LEN]> {
+    let bytes = std::fs::read(path).map_err(|e| anyhow!("cannot read the key {}: {}", path.display(), e))?;
+    bytes.as_slice().try_into()
+        .map_err(|_| anyhow!("{} is not an AES-128 key: it holds {} bytes instead of {}", path.display(), bytes.len(), KEY_LEN))
+}
+
+/// The -hls_key_info_file for an encryption run, deleted when dropped so it never outlives it
+pub struct KeyInfoFile {
+    path: PathBuf,
+}
+
+impl KeyInfoFile {
+    /// Key URI written into the playlist, then the key file ffmpeg reads. Without an IV line
+    /// each segment's sequence number is its IV.
+    pub fn write(path: &Path, key_uri: &str, key_file: &Path) -> Result<KeyInfoFile> {
+        std::fs::write(path, format!("{}\n{}\n", key_uri, key_file.display()))?;
+        Ok(KeyInfoFile { path: path.to_path_buf() })
+    }
+}
+
+impl Drop for KeyInfoFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Input packaged as AES-128 encrypted HLS by a VideoEncrypt node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptSettings {
+    /// Key file; empty keeps the key as <name>.key next to the playlist
+    pub key_file: String,
+    /// Write a new random key instead of reusing the one in `key_file`
+    pub generate_key: bool,
+    pub segment_seconds: f64,
+    /// Playlist name without extension; segments and the default key are named after it
+    pub name: String,
+    /// Folder for the playlist and segments; empty uses the workflow's scratch folder
+    pub output_dir: String,
+}
+
+impl Default for EncryptSettings {
+    fn default() -> Self {
+        Self {
+            key_file: String::new(),
+            generate_key: true,
+            segment_seconds: 6.0,
+            name: "encrypted".to_string(),
+            output_dir: String::new(),
+        }
+    }
+}
+
+impl EncryptSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.segment_seconds.is_finite() && self.segment_seconds >= 1.0) {
+            return Err(anyhow!("Segments must be at least 1 second long"));
+        }
+        let name = self.name.trim();
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(anyhow!("The playlist name must be a file name without folders"));
+        }
+        if !self.generate_key && self.key_file.trim().is_empty() {
+            return Err(anyhow!("Choose the key file to reuse, or generate a new key"));
+        }
+        Ok(())
+    }
+
+    pub fn playlist_path(&self, output_dir: &str) -> PathBuf {
+        Path::new(output_dir).join(format!("{}.m3u8", self.name.trim()))
+    }
+
+    pub fn key_path(&self, output_dir: &str) -> PathBuf {
+        match self.key_file.trim() {
+            "" => Path::new(output_dir).join(format!("{}.key", self.name.trim())),
+            key_file => PathBuf::from(key_file),
+        }
+    }
+
+    /// How the playlist refers to the key: by name when it sits next to the playlist, so the
+    /// folder can be moved or served as a whole, otherwise by its path
+    pub fn key_uri(&self, output_dir: &str) -> String {
+        let key_path = self.key_path(output_dir);
+        match key_path.parent() {
+            Some(parent) if parent == Path::new(output_dir) =>
+                key_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+            _ => key_path.display().to_string(),
+        }
+    }
+
+    /// Stream copy into encrypted MPEG-TS segments and a VOD playlist
+    pub fn args(&self, input_file: &str, key_info: &Path, output_dir: &str) -> Result<Vec<String>> {
+        self.validate()?;
+        let segments = Path::new(output_dir).join(format!("{}_%05d.ts", self.name.trim()));
+        Ok(vec![
+            "-hide_banner".to_string(), "-nostdin".to_string(),
+            "-i".to_string(), input_file.to_string(),
+            "-map".to_string(), "0:v?".to_string(),
+            "-map".to_string(), "0:a?".to_string(),
+            "-c".to_string(), "copy".to_string(),
+            "-f".to_string(), "hls".to_string(),
+            "-hls_time".to_string(), self.segment_seconds.to_string(),
+            "-hls_playlist_type".to_string(), "vod".to_string(),
+            "-hls_key_info_file".to_string(), key_info.display().to_string(),
+            "-hls_segment_filename".to_string(), segments.display().to_string(),
+            "-y".to_string(), self.playlist_path(output_dir).display().to_string(),
+        ])
+    }
+}
+
+/// Encrypted HLS remuxed back into one file by a VideoDecrypt node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecryptSettings {
+    /// Key file; empty uses the key the playlist names
+    pub key_file: String,
+    pub container: String,
+}
+
+impl Default for DecryptSettings {
+    fn default() -> Self {
+        Self { key_file: String::new(), container: "mp4".to_string() }
+    }
+}
+
+impl DecryptSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !DECRYPT_CONTAINERS.contains(&self.container.as_str()) {
+            return Err(anyhow!("Decrypted video is written as mp4 or mkv, not {}", self.container));
+        }
+        Ok(())
+    }
+
+    pub fn args(&self, playlist: &Path, output_file: &str) -> Result<Vec<String>> {
+        self.validate()?;
+        Ok(vec![
+            "-hide_banner".to_string(), "-nostdin".to_string(),
+            "-i".to_string(), playlist.display().to_string(),
+            "-map".to_string(), "0:v?".to_string(),
+            "-map".to_string(), "0:a?".to_string(),
+            "-c".to_string(), "copy".to_string(),
+            "-y".to_string(), output_file.to_string(),
+        ])
+    }
+}
+
+fn is_encrypted_playlist(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|text| text.contains("#EXT-X-KEY:METHOD=AES-128"))
+}
+
+/// The encrypted playlist to decrypt: the file itself, or the one in a VideoEncrypt folder
+pub fn find_playlist(input: &str) -> Result<PathBuf> {
+    let path = Path::new(input);
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let mut playlists: Vec<PathBuf> = std::fs::read_dir(path)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|file| file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("m3u8")))
+        .collect();
+    playlists.sort();
+    playlists.iter().find(|playlist| is_encrypted_playlist(playlist)).or(playlists.first()).cloned()
+        .ok_or_else(|| anyhow!("{} holds no HLS playlist", input))
+}
+
+/// The playlist with every key URI pointing at `key_file` and every segment given by its full
+/// path, so the copy can be read from anywhere
+pub fn rewrite_playlist(playlist: &Path, key_file: &Path) -> Result<String> {
+    let text = std::fs::read_to_string(playlist)?;
+    let dir = playlist.parent().unwrap_or(Path::new(""));
+    let key_uri = key_file.display().to_string().replace('"', "%22");
+    let lines: Vec<String> = text.lines().map(|line| {
+        let uri = line.strip_prefix("#EXT-X-KEY:").and_then(|_| {
+            let start = line.find("URI=\"")? + "URI=\"".len();
+            Some((start, start + line[start..].find('"')?))
+        });
+        if let Some((start, end)) = uri {
+            format!("{}{}{}", &line[..start], key_uri, &line[end..])
+        } else if line.is_empty() || line.starts_with('#') || line.contains("://") || Path::new(line).is_absolute() {
+            line.to_string()
+        } else {
+            dir.join(line).display().to_string()
+        }
+    }).collect();
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Key file, segment length, name and folder. Returns true when the settings changed.
+pub fn show_encrypt_editor(ui: &mut egui::Ui, settings: &mut EncryptSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    changed |= ui.checkbox(&mut settings.generate_key, translations.encryption_generate_key())
+        .on_hover_text(translations.encryption_generate_hint()).changed();
+    let hint = if settings.generate_key { translations.encryption_key_auto_hint() } else { translations.encryption_key_existing_hint() };
+    changed |= show_key_row(ui, &mut settings.key_file, hint, !settings.generate_key, translations);
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_segment());
+        changed |= ui.add(egui::DragValue::new(&mut settings.segment_seconds).range(1.0..=60.0).speed(0.5).suffix(" s")).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_name());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.name).desired_width(120.0)).changed();
+        ui.weak(settings.playlist_path("").display().to_string());
+    });
+    changed |= crate::image_sequence::show_folder_row(ui, translations.frame_extract_output_dir(), &mut settings.output_dir,
+        translations.image_sequence_scratch_dir(), translations);
+    ui.weak(translations.encryption_key_warning());
+
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
+
+/// Key file and output container. Returns true when the settings changed.
+pub fn show_decrypt_editor(ui: &mut egui::Ui, settings: &mut DecryptSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    changed |= show_key_row(ui, &mut settings.key_file, translations.encryption_key_playlist_hint(), true, translations);
+    ui.horizontal(|ui| {
+        ui.label(translations.encryption_container());
+        for container in DECRYPT_CONTAINERS {
+            changed |= ui.radio_value(&mut settings.container, container.to_string(), container).changed();
+        }
+    });
+
+    changed
+}
+
+/// Key file path with a picker: an open dialog for an existing key, a save dialog for a new one
+fn show_key_row(ui: &mut egui::Ui, key_file: &mut String, hint: &str, existing: bool, translations: &Translations) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(translations.encryption_key_file());
+        changed |= ui.add(egui::TextEdit::singleline(key_file).hint_text(hint).desired_width(180.0)).changed();
+        if ui.small_button("📂").on_hover_text(translations.frame_extract_browse()).clicked() {
+            let dialog = rfd::FileDialog::new().add_filter("AES-128 key", &["key", "bin"]);
+            if let Some(file) = if existing { dialog.pick_file() } else { dialog.save_file() } {
+                *key_file = file.display().to_string();
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// AES-128-CBC through openssl, with the segment's sequence number as IV like ffmpeg's HLS
+    /// muxer uses when the key info has no IV line. None when openssl is not installed.
+    fn openssl_cbc(decrypt: bool, key: &[u8; KEY_LEN], sequence: u32, input: &Path, output: &Path) -> Option<()> {
+        let openssl = which::which("openssl").ok()?;
+        let mut command = std::process::Command::new(openssl);
+        command.args(["enc", "-aes-128-cbc", "-K", &hex(key), "-iv", &format!("{:032x}", sequence)]);
+        if decrypt {
+            command.arg("-d");
+        }
+        let status = command.arg("-in").arg(input).arg("-out").arg(output).status().ok()?;
+        assert!(status.success(), "openssl failed");
+        Some(())
+    }
+
+    #[test]
+    fn generated_keys_are_full_length_and_distinct() {
+        let keys: Vec<[u8; KEY_LEN]> = (0..32).map(|_| generate_key().unwrap()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(keys[i + 1..].iter().all(|other| other != key));
+        }
+        // Every byte position varies; UUID-derived keys had fixed version and variant bits
+        for position in 0..KEY_LEN {
+            assert!(keys.iter().any(|key| key[position] != keys[0][position]), "byte {} never changes", position);
+        }
+    }
+
+    #[test]
+    fn keys_round_trip_and_wrong_lengths_are_rejected() {
+        let dir = temp_dir("hls_key");
+        let path = dir.join("nested").join("video.key");
+        let key = generate_key().unwrap();
+        write_key(&path, &key).unwrap();
+        assert_eq!(read_key(&path).unwrap(), key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        std::fs::write(&path, [0u8; 15]).unwrap();
+        assert!(read_key(&path).unwrap_err().to_string().contains("15 bytes"));
+        assert!(read_key(&dir.join("missing.key")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn key_uri_is_relative_only_next_to_the_playlist() {
+        let dir = temp_dir("hls_uri");
+        let output_dir = dir.display().to_string();
+        let settings = EncryptSettings { name: "movie".to_string(), ..EncryptSettings::default() };
+        assert_eq!(settings.key_uri(&output_dir), "movie.key");
+
+        let elsewhere = dir.join("keys").join("shared.key");
+        let settings = EncryptSettings { key_file: elsewhere.display().to_string(), ..settings };
+        assert_eq!(settings.key_uri(&output_dir), elsewhere.display().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips_through_the_key_info_and_playlist() {
+        let dir = temp_dir("hls_round_trip");
+        let output_dir = dir.display().to_string();
+        let settings = EncryptSettings::default();
+        let key_path = settings.key_path(&output_dir);
+        let key = generate_key().unwrap();
+        write_key(&key_path, &key).unwrap();
+
+        // The key info names the playlist's URI, then the key file ffmpeg encrypts with
+        let key_info_path = dir.join("keyinfo.txt");
+        let key_info = KeyInfoFile::write(&key_info_path, &settings.key_uri(&output_dir), &key_path).unwrap();
+        let info = std::fs::read_to_string(&key_info_path).unwrap();
+        let lines: Vec<&str> = info.lines().collect();
+        assert_eq!(lines, vec!["encrypted.key", key_path.to_str().unwrap()]);
+        assert_eq!(read_key(Path::new(lines[1])).unwrap(), key);
+
+        let args = settings.args("in.mp4", &key_info_path, &output_dir).unwrap();
+        let after = |flag: &str| args.iter().position(|arg| arg == flag).map(|i| args[i + 1].clone());
+        assert_eq!(after("-hls_key_info_file"), Some(key_info_path.display().to_string()));
+        assert_eq!(after("-f").as_deref(), Some("hls"));
+        assert_eq!(args.last(), Some(&settings.playlist_path(&output_dir).display().to_string()));
+        drop(key_info);
+        assert!(!key_info_path.exists());
+
+        // What ffmpeg writes: segments encrypted with the key, and a playlist naming its URI
+        let segments: Vec<Vec<u8>> = (0..2u8).map(|n| (0..1000).map(|i| (i as u8).wrapping_mul(n + 7)).collect()).collect();
+        let mut playlist = format!("#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"{}\"\n", lines[0]);
+        for (sequence, plain) in segments.iter().enumerate() {
+            let plain_path = dir.join(format!("plain_{}.bin", sequence));
+            std::fs::write(&plain_path, plain).unwrap();
+            let segment = format!("encrypted_{:05}.ts", sequence);
+            if openssl_cbc(false, &key, sequence as u32, &plain_path, &dir.join(&segment)).is_none() {
+                eprintln!("openssl is not installed; skipping the cipher round trip");
+                let _ = std::fs::remove_dir_all(&dir);
+                return;
+            }
+            playlist.push_str(&format!("#EXTINF:6.0,\n{}\n", segment));
+        }
+        playlist.push_str("#EXT-X-ENDLIST\n");
+        std::fs::write(settings.playlist_path(&output_dir), playlist).unwrap();
+
+        // Decrypting: the playlist is found in the folder and rewritten to the key and full paths
+        let found = find_playlist(&output_dir).unwrap();
+        assert_eq!(found, settings.playlist_path(&output_dir));
+        let rewritten = rewrite_playlist(&found, &key_path).unwrap();
+        let uri_line = rewritten.lines().find(|line| line.starts_with("#EXT-X-KEY:")).unwrap();
+        assert!(uri_line.contains(&format!("URI=\"{}\"", key_path.display())));
+        let decrypt_key = read_key(&key_path).unwrap();
+        let segment_paths: Vec<&str> = rewritten.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(segment_paths.len(), segments.len());
+        for (sequence, (segment, plain)) in segment_paths.iter().zip(&segments).enumerate() {
+            assert!(Path::new(segment).is_absolute());
+            let encrypted = std::fs::read(segment).unwrap();
+            assert_ne!(&encrypted, plain);
+            let decrypted_path = dir.join(format!("decrypted_{}.bin", sequence));
+            openssl_cbc(true, &decrypt_key, sequence as u32, Path::new(segment), &decrypted_path).unwrap();
+            assert_eq!(&std::fs::read(&decrypted_path).unwrap(), plain);
+        }
+
+        let rewritten_path = dir.join("rewritten.m3u8");
+        let decrypt_args = DecryptSettings::default().args(&rewritten_path, "out.mp4").unwrap();
+        assert_eq!(decrypt_args[3], rewritten_path.display().to_string());
+        assert_eq!(decrypt_args.last().map(String::as_str), Some("out.mp4"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_wrong_key_does_not_decrypt() {
+        let dir = temp_dir("hls_wrong_key");
+        let key = generate_key().unwrap();
+        let plain: Vec<u8> = (0..=255).collect();
+        std::fs::write(dir.join("plain.bin"), &plain).unwrap();
+        if openssl_cbc(false, &key, 0, &dir.join("plain.bin"), &dir.join("segment.ts")).is_none() {
+            let _ = std::fs::remove_dir_all(&dir);
+            return;
+        }
+        let mut wrong = key;
+        wrong[0] ^= 1;
+        // A wrong key usually fails the padding check; when it does not, the bytes differ
+        let status = std::process::Command::new("openssl")
+            .args(["enc", "-d", "-aes-128-cbc", "-K", &hex(&wrong), "-iv", &format!("{:032x}", 0)])
+            .arg("-in").arg(dir.join("segment.ts")).arg("-out").arg(dir.join("out.bin"))
+            .stderr(std::process::Stdio::null())
+            .status().unwrap();
+        assert!(!status.success() || std::fs::read(dir.join("out.bin")).unwrap() != plain);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn settings_are_validated() {
+        assert!(EncryptSettings::default().validate().is_ok());
+        assert!(EncryptSettings { segment_seconds: 0.5, ..EncryptSettings::default() }.validate().is_err());
+        assert!(EncryptSettings { name: "a/b".to_string(), ..EncryptSettings::default() }.validate().is_err());
+        assert!(EncryptSettings { generate_key: false, ..EncryptSettings::default() }.validate().is_err());
+        assert!(DecryptSettings { container: "ts".to_string(), ..DecryptSettings::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,473 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::frame_rate::FrameRate;
+use crate::language::Translations;
+
+/// Image files an "every image in the folder" sequence picks up
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "webp", "bmp", "tif", "tiff"];
+
+/// Codecs offered for encoding an image sequence, with the container each is written to
+pub const SEQUENCE_CODECS: [(&str, &str); 5] = [
+    ("libx264", "mp4"),
+    ("libx265", "mp4"),
+    ("libvpx-vp9", "webm"),
+    ("prores_ks", "mov"),
+    ("ffv1", "mkv"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFormat {
+    Png,
+    Jpg,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn all() -> [ImageFormat; 3] {
+        [ImageFormat::Png, ImageFormat::Jpg, ImageFormat::Webp]
+    }
+
+    /// File extension, also used as the node parameter
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpg => "jpg",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        let extension = extension.trim().to_lowercase();
+        let extension = if extension == "jpeg" { "jpg" } else { extension.as_str() };
+        ImageFormat::all().into_iter().find(|format| format.extension() == extension)
+    }
+}
+
+/// A printf-style frame number pattern such as "frame_%06d.png", split around its placeholder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberedPattern {
+    pub prefix: String,
+    /// Zero padding of the number, 0 for none
+    pub width: usize,
+    pub suffix: String,
+}
+
+impl NumberedPattern {
+    /// The single %d or %0Nd of a pattern; "%%" stands for a literal '%'. None when the pattern has
+    /// no placeholder, more than one, or another conversion.
+    pub fn parse(pattern: &str) -> Option<NumberedPattern> {
+        let mut parts: Vec<String> = vec![String::new()];
+        let mut width = None;
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                parts.last_mut()?.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                parts.last_mut()?.push('%');
+                continue;
+            }
+            let mut digits = String::new();
+            while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                digits.push(*digit);
+                chars.next();
+            }
+            if chars.next() != Some('d') || width.is_some() {
+                return None;
+            }
+            width = Some(digits.parse().unwrap_or(0));
+            parts.push(String::new());
+        }
+        let suffix = parts.pop()?;
+        Some(NumberedPattern { prefix: parts.pop()?, width: width?, suffix })
+    }
+
+    /// The frame number of a file name written with this pattern
+    pub fn number_of(&self, file_name: &str) -> Option<u64> {
+        let digits = file_name.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) || digits.len() < self.width {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+/// Whether `name` matches a glob with '*' (any run of characters) and '?' (one character)
+pub fn glob_matches(glob: &str, name: &str) -> bool {
+    fn matches(glob: &[char], name: &[char]) -> bool {
+        match glob.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+        }
+    }
+    let fold = |text: &str| -> Vec<char> {
+        if cfg!(target_os = "windows") { text.to_lowercase().chars().collect() } else { text.chars().collect() }
+    };
+    matches(&fold(glob), &fold(name))
+}
+
+/// Sort key comparing runs of digits by value, so "img2" comes before "img10"
+fn natural_key(name: &str) -> Vec<(u8, u64, String)> {
+    let mut key = Vec::new();
+    let mut chars = name.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let digit = c.is_ascii_digit();
+        let mut run = String::new();
+        while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() == digit) {
+            run.push(c);
+            chars.next();
+        }
+        key.push(if digit { (0, run.parse().unwrap_or(u64::MAX), run) } else { (1, 0, run.to_lowercase()) });
+    }
+    key
+}
+
+/// The images an ImagesToVideo node reads
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceInput {
+    /// A numbered sequence read by the image2 demuxer from `start_number` on
+    Numbered { pattern_path: String, start_number: u64, count: usize },
+    /// Files in name order, read through a concat list
+    Listed(Vec<PathBuf>),
+}
+
+impl SequenceInput {
+    pub fn count(&self) -> usize {
+        match self {
+            SequenceInput::Numbered { count, .. } => *count,
+            SequenceInput::Listed(files) => files.len(),
+        }
+    }
+}
+
+/// Find the images `pattern` names in `dir` before ffmpeg is started, so a pattern that matches
+/// nothing is reported clearly. An empty pattern takes every image in the folder.
+pub fn resolve_input(dir: &str, pattern: &str) -> Result<SequenceInput> {
+    let dir_path = Path::new(dir);
+    if dir.trim().is_empty() {
+        return Err(anyhow!("No image folder set"));
+    }
+    if !dir_path.is_dir() {
+        return Err(anyhow!("The image folder {} does not exist", dir));
+    }
+    let names: Vec<String> = std::fs::read_dir(dir_path)?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    let pattern = pattern.trim();
+
+    if let Some(numbered) = NumberedPattern::parse(pattern) {
+        let mut numbers: Vec<u64> = names.iter().filter_map(|name| numbered.number_of(name)).collect();
+        numbers.sort_unstable();
+        let Some(&start_number) = numbers.first() else {
+            return Err(anyhow!("No files in {} match {}", dir, pattern));
+        };
+        // image2 stops at the first missing number
+        let count = numbers.iter().zip(start_number..).take_while(|(number, expected)| **number == *expected).count();
+        if count < numbers.len() {
+            log_warn!("{} is missing frame {}: only {} of {} images will be used", pattern, start_number + count as u64, count, numbers.len());
+        }
+        let pattern_path = dir_path.join(pattern).display().to_string();
+        return Ok(SequenceInput::Numbered { pattern_path, start_number, count });
+    }
+    if pattern.contains('%') {
+        return Err(anyhow!("{} is not a frame number pattern: use one %d or %0Nd, e.g. frame_%06d.png", pattern));
+    }
+
+    let mut matched: Vec<&String> = names.iter()
+        .filter(|name| if pattern.is_empty() {
+            Path::new(name).extension()
+                .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str()))
+        } else {
+            glob_matches(pattern, name)
+        })
+        .collect();
+    if matched.is_empty() {
+        return Err(if pattern.is_empty() {
+            anyhow!("There are no images in {}", dir)
+        } else {
+            anyhow!("No files in {} match {}", dir, pattern)
+        });
+    }
+    matched.sort_by_key(|name| natural_key(name));
+    Ok(SequenceInput::Listed(matched.into_iter().map(|name| dir_path.join(name)).collect()))
+}
+
+/// ffconcat list showing each file for one frame at `rate`. The last file is listed twice: the
+/// concat demuxer ignores the duration of the final entry.
+pub fn concat_list(files: &[PathBuf], rate: FrameRate) -> String {
+    let frame_seconds = rate.den as f64 / rate.num as f64;
+    let line = |file: &Path| format!("file '{}'\n", file.display().to_string().replace('\'', "'\\''"));
+    let mut list = String::from("ffconcat version 1.0\n");
+    for file in files {
+        list.push_str(&line(file));
+        list.push_str(&format!("duration {:.6}\n", frame_seconds));
+    }
+    if let Some(last) = files.last() {
+        list.push_str(&line(last));
+    }
+    list
+}
+
+/// Container a sequence encoded with `codec` is written to
+pub fn container_for_codec(codec: &str) -> &'static str {
+    SEQUENCE_CODECS.iter()
+        .find(|(name, _)| *name == codec)
+        .map(|(_, container)| *container)
+        .unwrap_or("mkv")
+}
+
+/// Frames written by a VideoToImages node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractSettings {
+    pub format: ImageFormat,
+    /// JPEG qscale, 1 (best) to 31
+    pub jpg_quality: u32,
+    /// Frames per second to keep; None writes every frame
+    pub rate: Option<FrameRate>,
+    /// File name without extension, with one %d or %0Nd for the frame number
+    pub name_pattern: String,
+    /// Folder the images are written to; empty uses the workflow's scratch folder
+    pub output_dir: String,
+}
+
+impl Default for ExtractSettings {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            jpg_quality: 2,
+            rate: None,
+            name_pattern: "frame_%06d".to_string(),
+            output_dir: String::new(),
+        }
+    }
+}
+
+impl ExtractSettings {
+    pub fn validate(&self) -> Result<()> {
+        if NumberedPattern::parse(self.name_pattern.trim()).is_none() {
+            return Err(anyhow!("The file name needs exactly one frame number placeholder, e.g. frame_%06d"));
+        }
+        if self.name_pattern.contains(['/', '\\']) {
+            return Err(anyhow!("The file name cannot contain a folder; set the output folder instead"));
+        }
+        Ok(())
+    }
+
+    /// File name pattern with the image extension
+    pub fn file_pattern(&self) -> String {
+        format!("{}.{}", self.name_pattern.trim(), self.format.extension())
+    }
+
+    pub fn args(&self, input_file: &str, output_dir: &str) -> Result<Vec<String>> {
+        self.validate()?;
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin", "-i", input_file, "-an"].map(String::from).to_vec();
+        match self.rate {
+            Some(rate) => args.extend(["-vf".to_string(), format!("fps={}", rate.ffmpeg_arg())]),
+            // One image per decoded frame, without duplicating or dropping any
+            None => args.extend(["-fps_mode".to_string(), "passthrough".to_string()]),
+        }
+        match self.format {
+            ImageFormat::Jpg => args.extend(["-qscale:v".to_string(), self.jpg_quality.clamp(1, 31).to_string()]),
+            ImageFormat::Png => args.extend(["-pix_fmt".to_string(), "rgb24".to_string()]),
+            ImageFormat::Webp => {}
+        }
+        args.extend(["-y".to_string(), Path::new(output_dir).join(self.file_pattern()).display().to_string()]);
+        Ok(args)
+    }
+}
+
+/// Video encoded from an image sequence by an ImagesToVideo node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SequenceSettings {
+    /// Folder holding the images, used when no VideoToImages node feeds the node
+    pub directory: String,
+    /// Empty for every image in the folder, a numbered pattern (frame_%06d.png) or a glob (*.jpg)
+    pub pattern: String,
+    pub rate: FrameRate,
+    pub codec: String,
+    pub pixel_format: String,
+    /// Soundtrack, used when nothing is connected to the audio input; empty for none
+    pub audio_file: String,
+}
+
+impl Default for SequenceSettings {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            pattern: String::new(),
+            rate: FrameRate::new(25, 1),
+            codec: "libx264".to_string(),
+            // Anything else and many players show nothing
+            pixel_format: "yuv420p".to_string(),
+            audio_file: String::new(),
+        }
+    }
+}
+
+impl SequenceSettings {
+    /// Arguments encoding `input`; `list_file` is where a Listed input's concat list was written
+    pub fn args(&self, input: &SequenceInput, list_file: &str, audio_file: Option<&str>, output_file: &str) -> Vec<String> {
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin"].map(String::from).to_vec();
+        match input {
+            SequenceInput::Numbered { pattern_path, start_number, .. } => args.extend([
+                "-framerate".to_string(), self.rate.ffmpeg_arg(),
+                "-start_number".to_string(), start_number.to_string(),
+                "-i".to_string(), pattern_path.clone(),
+            ]),
+            SequenceInput::Listed(_) => args.extend(
+                ["-f", "concat", "-safe", "0", "-i", list_file].map(String::from),
+            ),
+        }
+        if let Some(audio_file) = audio_file {
+            args.extend(["-i".to_string(), audio_file.to_string()]);
+            args.extend(["-map", "0:v", "-map", "1:a", "-c:a", "aac", "-shortest"].map(String::from));
+        }
+        args.extend(["-c:v".to_string(), self.codec.clone()]);
+        if !self.pixel_format.trim().is_empty() {
+            args.extend(["-pix_fmt".to_string(), self.pixel_format.trim().to_string()]);
+        }
+        args.extend(["-r".to_string(), self.rate.ffmpeg_arg(), "-y".to_string(), output_file.to_string()]);
+        args
+    }
+}
+
+/// Copy the files of a directory artifact into `destination`, creating it; returns how many
+pub fn copy_directory(source: &str, destination: &Path) -> Result<usize> {
+    std::fs::create_dir_all(destination)?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(source)?.flatten() {
+        if entry.path().is_file() {
+            std::fs::copy(entry.path(), destination.join(entry.file_name()))?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+pub(crate) fn show_folder_row(ui: &mut egui::Ui, label: &str, value: &mut String, hint: &str, translations: &Translations) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(label);
+        changed |= ui.add(egui::TextEdit::singleline(value).hint_text(hint).desired_width(180.0)).changed();
+        if ui.small_button("📂").on_hover_text(translations.frame_extract_browse()).clicked() {
+            if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                *value = folder.display().to_string();
+                changed = true;
+            }
+        }
+    });
+    changed
+}
+
+/// Image format, quality, rate, file names and folder. Returns true when the settings changed.
+pub fn show_extract_editor(ui: &mut egui::Ui, settings: &mut ExtractSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.image_sequence_format());
+        for format in ImageFormat::all() {
+            changed |= ui.radio_value(&mut settings.format, format, format.extension()).changed();
+        }
+    });
+    if settings.format == ImageFormat::Jpg {
+        ui.horizontal(|ui| {
+            ui.label(translations.image_sequence_jpg_quality());
+            changed |= ui.add(egui::Slider::new(&mut settings.jpg_quality, 1..=31)).changed();
+        });
+    }
+
+    let mut every_frame = settings.rate.is_none();
+    if ui.checkbox(&mut every_frame, translations.image_sequence_every_frame()).changed() {
+        settings.rate = (!every_frame).then(|| FrameRate::new(1, 1));
+        changed = true;
+    }
+    if let Some(rate) = &mut settings.rate {
+        let mut text = rate.ffmpeg_arg();
+        ui.horizontal(|ui| {
+            ui.label(translations.image_sequence_rate());
+            if ui.add(egui::TextEdit::singleline(&mut text).desired_width(80.0)).changed() {
+                if let Some(parsed) = FrameRate::parse(&text) {
+                    *rate = parsed;
+                    changed = true;
+                }
+            }
+        });
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(translations.frame_extract_name_pattern());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.name_pattern).desired_width(140.0)).changed();
+        ui.weak(format!(".{}", settings.format.extension()));
+    });
+    changed |= show_folder_row(ui, translations.frame_extract_output_dir(), &mut settings.output_dir,
+        translations.image_sequence_scratch_dir(), translations);
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
+
+/// Image folder and pattern, frame rate, codec, pixel format and soundtrack. Returns true when the
+/// settings changed.
+pub fn show_sequence_editor(ui: &mut egui::Ui, settings: &mut SequenceSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    changed |= show_folder_row(ui, translations.image_sequence_directory(), &mut settings.directory,
+        translations.image_sequence_directory_hint(), translations);
+    ui.horizontal(|ui| {
+        ui.label(translations.image_sequence_pattern());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.pattern)
+            .hint_text(translations.image_sequence_pattern_hint())
+            .desired_width(160.0)).changed();
+    }).response.on_hover_text(translations.image_sequence_pattern_tooltip());
+
+    let mut text = settings.rate.ffmpeg_arg();
+    ui.horizontal(|ui| {
+        ui.label(translations.image_sequence_rate());
+        if ui.add(egui::TextEdit::singleline(&mut text).desired_width(80.0)).changed() {
+            if let Some(parsed) = FrameRate::parse(&text) {
+                settings.rate = parsed;
+                changed = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.image_sequence_codec());
+        egui::ComboBox::from_id_salt("image_sequence_codec")
+            .selected_text(settings.codec.as_str())
+            .show_ui(ui, |ui| {
+                for (codec, container) in SEQUENCE_CODECS {
+                    changed |= ui.selectable_value(&mut settings.codec, codec.to_string(), format!("{} (.{})", codec, container)).changed();
+                }
+            });
+        ui.label(translations.image_sequence_pixel_format());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.pixel_format).desired_width(90.0)).changed();
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(translations.image_sequence_audio_file());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.audio_file)
+            .hint_text(translations.image_sequence_audio_hint())
+            .desired_width(180.0)).changed();
+        if ui.small_button("📂").clicked() {
+            if let Some(file) = rfd::FileDialog::new().pick_file() {
+                settings.audio_file = file.display().to_string();
+                changed = true;
+            }
+        }
+    });
+
+    changed
+}
@@ -15,79 +15,5842 @@ impl Language {
     pub fn all_languages() -> Vec<Language> {
         vec![Language::Chinese, Language::English]
     }
+
+    /// Short code used when persisting the language choice
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Chinese => "zh",
+            Language::English => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        match code {
+            "zh" => Some(Language::Chinese),
+            "en" => Some(Language::English),
+            _ => None,
+        }
+    }
+}
+
+/// Contextual help, keyed by "<panel>.<widget>": (key, English, Chinese).
+/// A missing Chinese entry falls back to the English text.
+const HELP_TEXTS: &[(&str, &str, Option<&str>)] = &[
+    // Workflow editor
+    ("workflow.file", "Create, save or load a workflow. Workflows are stored as .json files.",
+        Some("新建、保存或加载工作流。工作流以 .json 文件保存。")),
+    ("workflow.history", "Undo / redo the last edits to nodes and connections (Ctrl+Z / Ctrl+Y).",
+        Some("撤销/重做对节点和连接的最近修改 (Ctrl+Z / Ctrl+Y)。")),
+    ("workflow.delete", "Delete the selected node together with its connections (Del).",
+        Some("删除选中的节点及其连接 (Del)。")),
+    ("workflow.execute", "Run the workflow: nodes execute in connection order, starting from the input nodes.",
+        Some("运行工作流：从输入节点开始，按连接顺序执行各节点。")),
+    ("workflow.add_nodes", "Add processing nodes by category. Every workflow needs an Input File and an Output File node.",
+        Some("按类别添加处理节点。每个工作流都需要一个输入文件节点和一个输出文件节点。")),
+    ("workflow.canvas", "Drag nodes to arrange them. To connect ports, click an output port on the right of a node, then an input port on the left of another node. Esc cancels.",
+        Some("拖动节点进行排列。连接端口：先点击节点右侧的输出端口，再点击另一节点左侧的输入端口。按 Esc 取消。")),
+    ("workflow.status", "Hints for the current action and the node / connection count.",
+        Some("当前操作提示以及节点/连接数量。")),
+    ("workflow.properties", "Parameters of the selected node. Click a node on the canvas to edit it here.",
+        Some("选中节点的参数。在画布上点击节点即可在此编辑。")),
+    // Video settings
+    ("video.preset", "Encoder speed/efficiency trade-off. Slower presets give smaller files at the same quality.",
+        Some("编码速度与效率的权衡。越慢的预设在相同质量下文件越小。")),
+    ("video.profile", "H.264/H.265 feature set. Use Baseline or Main for old devices, High for everything else.",
+        Some("H.264/H.265 特性集。老设备请用 Baseline 或 Main，其他情况用 High。")),
+    ("video.tune", "Optimise the encoder for a content type (film, animation, grain) or use case (fast decode, low latency).",
+        Some("针对内容类型（电影、动画、颗粒）或用途（快速解码、低延迟）优化编码器。")),
+    ("video.quality", "CRF (Constant Rate Factor): lower means better quality and bigger files. 18-28 is the usual range, +6 roughly halves the size.",
+        Some("CRF（恒定质量因子）：数值越低质量越好、文件越大。常用范围 18-28，每增加 6 文件约减半。")),
+    ("video.bitrate", "Target bitrate. Overrides CRF quality control; leave 'auto' unless a fixed size or bandwidth is required.",
+        Some("目标码率。会覆盖 CRF 质量控制；除非需要固定大小或带宽，否则保持 auto。")),
+    ("video.fps", "Output frame rate. 'auto' keeps the source rate; lowering it drops frames.",
+        Some("输出帧率。auto 保持源帧率；降低帧率会丢帧。")),
+    ("video.resolution", "Output size in pixels. 0 keeps the original; set one side to keep the aspect ratio.",
+        Some("输出尺寸（像素）。0 表示保持原始；只设置一边可保持宽高比。")),
+    ("video.smart_encoder", "Suggests the best available encoder for the container, preferring detected hardware encoders.",
+        Some("根据容器推荐最佳编码器，优先使用检测到的硬件编码器。")),
+    ("video.custom_args", "Extra ffmpeg arguments appended to the command, for options not exposed in the UI.",
+        Some("附加到命令中的 ffmpeg 参数，用于界面未提供的选项。")),
+    ("video.container_options", "Muxer flags for the output container. They are added to the command automatically and skipped when custom parameters already set them.",
+        Some("输出容器的封装参数。会自动加入命令；若自定义参数中已设置则跳过。")),
+    // Audio settings
+    ("audio.bitrate", "Audio bitrate. 128 kbps is transparent for most AAC content; lossless codecs ignore it.",
+        Some("音频码率。大多数 AAC 内容 128 kbps 已足够；无损编码器会忽略此项。")),
+    ("audio.sample_rate", "Samples per second. 48 kHz is standard for video, 44.1 kHz for music. Upsampling adds no quality.",
+        Some("每秒采样数。视频标准为 48 kHz，音乐为 44.1 kHz。提高采样率不会提升音质。")),
+    ("audio.channels", "Number of output channels. Downmixing 5.1 to stereo is done automatically.",
+        Some("输出声道数。5.1 会自动缩混为立体声。")),
+    ("audio.vbr_quality", "Variable bitrate quality level, used instead of a fixed bitrate. The scale depends on the codec.",
+        Some("可变码率质量等级，代替固定码率。取值范围取决于编码器。")),
+    ("audio.volume", "Volume multiplier; 2.0 is about +6 dB. High values may clip.",
+        Some("音量倍数；2.0 约为 +6 dB。数值过高可能削波。")),
+    ("audio.denoise", "Removes steady background noise such as hiss or hum. Runs before volume changes and loudness normalization.",
+        Some("去除嘶嘶声、嗡嗡声等持续背景噪声。在音量调整和响度标准化之前执行。")),
+    ("audio.custom_args", "Extra ffmpeg audio arguments appended to the command.",
+        Some("附加到命令中的 ffmpeg 音频参数。")),
+];
+
+#[derive(Clone)]
+pub struct Translations {
+    pub language: Language,
 }
 
-#[derive(Clone)]
-pub struct Translations {
-    pub language: Language,
-}
+impl Translations {
+    pub fn new(language: Language) -> Self {
+        Self { language }
+    }
+
+    // Menu bar
+    pub fn menu_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件",
+            Language::English => "File",
+        }
+    }
+
+    pub fn menu_settings(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "设置",
+            Language::English => "Settings",
+        }
+    }
+
+    pub fn menu_language(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "语言",
+            Language::English => "Language",
+        }
+    }
+
+    pub fn menu_help(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "帮助",
+            Language::English => "Help",
+        }
+    }
+
+    pub fn new_project(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "新建项目",
+            Language::English => "New Project",
+        }
+    }
+
+    pub fn save_project(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保存项目",
+            Language::English => "Save Project",
+        }
+    }
+
+    pub fn load_project(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "加载项目",
+            Language::English => "Load Project",
+        }
+    }
+
+    pub fn exit(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "退出",
+            Language::English => "Exit",
+        }
+    }
+
+    pub fn show_advanced_settings(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "显示高级设置",
+            Language::English => "Show Advanced Settings",
+        }
+    }
+
+    pub fn max_tasks_per_destination(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每个目标磁盘最大同时任务数:",
+            Language::English => "Max simultaneous tasks per destination:",
+        }
+    }
+
+    pub fn max_concurrent_tasks(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最大并行任务数:",
+            Language::English => "Max parallel tasks:",
+        }
+    }
+
+    pub fn max_concurrent_tasks_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "同时运行的 FFmpeg 任务数；更改后从下一个任务开始生效，运行中的任务不受影响",
+            Language::English => "FFmpeg tasks run at the same time; a change applies from the next task picked up, running tasks are not stopped",
+        }
+    }
+
+    pub fn running_of_limit(&self, running: usize, limit: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("运行中: {} / 上限 {}", running, limit),
+            Language::English => format!("Running: {} / limit {}", running, limit),
+        }
+    }
+
+    pub fn unlimited(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不限",
+            Language::English => "Unlimited",
+        }
+    }
+
+    pub fn destination_volume(&self, volume: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("输出磁盘: {}", volume),
+            Language::English => format!("Destination: {}", volume),
+        }
+    }
+
+    pub fn task_environment(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运行环境",
+            Language::English => "Environment",
+        }
+    }
+
+    pub fn copy_environment_report(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制环境信息",
+            Language::English => "📋 Copy environment report",
+        }
+    }
+
+    pub fn copy_task_command(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制 FFmpeg 命令",
+            Language::English => "📋 Copy FFmpeg command",
+        }
+    }
+
+    pub fn copy_task_command_for(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "复制为…",
+            Language::English => "Copy for…",
+        }
+    }
+
+    /// Help text for a widget; falls back to English when no translation exists, empty for unknown keys
+    pub fn help_text(&self, key: &str) -> &'static str {
+        match HELP_TEXTS.iter().find(|(k, _, _)| *k == key) {
+            Some((_, english, chinese)) => match self.language {
+                Language::Chinese => chinese.unwrap_or(english),
+                Language::English => english,
+            },
+            None => "",
+        }
+    }
+
+    pub fn help_overlay_toggle(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "显示/隐藏帮助说明",
+            Language::English => "Show/hide help callouts",
+        }
+    }
+
+    pub fn help_overlay_dismiss_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按 Esc 或点击任意位置关闭帮助",
+            Language::English => "Press Esc or click anywhere to close help",
+        }
+    }
+
+    pub fn probe_cache_stats(&self, hits: u64, misses: u64, cached: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("媒体信息缓存: 命中 {} / 未命中 {} ({} 个文件)", hits, misses, cached),
+            Language::English => format!("Probe cache: {} hits / {} misses ({} files)", hits, misses, cached),
+        }
+    }
+
+    pub fn input_program(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📡 节目:",
+            Language::English => "📡 Program:",
+        }
+    }
+
+    pub fn all_programs(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "全部节目 (FFmpeg 默认选择)",
+            Language::English => "All programs (FFmpeg default selection)",
+        }
+    }
+
+    pub fn input_program_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "该传输流包含多个节目，只会映射所选节目的音视频流。",
+            Language::English => "This transport stream carries several programs; only the selected program's streams are mapped.",
+        }
+    }
+
+    pub fn task_filter_name(&self, filter: crate::ui_components::TaskFilter) -> &'static str {
+        use crate::ui_components::TaskFilter;
+        match (self.language.clone(), filter) {
+            (Language::Chinese, TaskFilter::All) => "全部",
+            (Language::Chinese, TaskFilter::Running) => "运行中",
+            (Language::Chinese, TaskFilter::Pending) => "等待中",
+            (Language::Chinese, TaskFilter::Failed) => "失败",
+            (Language::Chinese, TaskFilter::Completed) => "已完成",
+            (Language::English, TaskFilter::All) => "All",
+            (Language::English, TaskFilter::Running) => "Running",
+            (Language::English, TaskFilter::Pending) => "Pending",
+            (Language::English, TaskFilter::Failed) => "Failed",
+            (Language::English, TaskFilter::Completed) => "Completed",
+        }
+    }
+
+    pub fn output_favorites_star(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "收藏当前输出文件夹",
+            Language::English => "Star the current output folder",
+        }
+    }
+
+    pub fn output_favorites_unstar(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "取消收藏当前输出文件夹",
+            Language::English => "Unstar the current output folder",
+        }
+    }
+
+    pub fn output_favorites_favorites(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⭐ 收藏的文件夹",
+            Language::English => "⭐ Favorite folders",
+        }
+    }
+
+    pub fn output_favorites_empty(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "还没有收藏的文件夹，点击 ☆ 收藏当前输出文件夹",
+            Language::English => "No favorite folders yet; click ☆ to star the current output folder",
+        }
+    }
+
+    pub fn output_favorites_default(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "默认:",
+            Language::English => "Default:",
+        }
+    }
+
+    pub fn output_favorites_manage(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "管理收藏的文件夹",
+            Language::English => "Manage favorite folders",
+        }
+    }
+
+    pub fn output_favorites_default_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择视频、音频或批处理作为该类操作新输出的默认文件夹",
+            Language::English => "Pick Video, Audio or Batch to make a folder the default for new outputs of that kind",
+        }
+    }
+
+    pub fn output_favorites_offline(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件夹当前不可用（共享离线？）",
+            Language::English => "Folder is not available right now (share offline?)",
+        }
+    }
+
+    pub fn output_favorites_video(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频",
+            Language::English => "Video",
+        }
+    }
+
+    pub fn output_favorites_audio(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频",
+            Language::English => "Audio",
+        }
+    }
+
+    pub fn output_favorites_batch(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "批处理",
+            Language::English => "Batch",
+        }
+    }
+
+    pub fn auto_codec_resolution_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "“自动”将为当前容器和硬件使用的编码器",
+            Language::English => "Encoder \"auto\" will use for the current container and hardware",
+        }
+    }
+
+    pub fn task_queue_move_up(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⬆ 上移",
+            Language::English => "⬆ Move up",
+        }
+    }
+
+    pub fn task_queue_move_down(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⬇ 下移",
+            Language::English => "⬇ Move down",
+        }
+    }
+
+    pub fn task_queue_rename(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✏ 重命名",
+            Language::English => "✏ Rename",
+        }
+    }
+
+    pub fn filter_by_filename(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按文件名筛选...",
+            Language::English => "Filter by filename...",
+        }
+    }
+
+    pub fn queue_progress(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "队列进度",
+            Language::English => "Queue progress",
+        }
+    }
+
+    pub fn container_options(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📦 容器选项",
+            Language::English => "📦 Container options",
+        }
+    }
+
+    pub fn no_container_options(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "当前容器格式没有可调整的封装选项。",
+            Language::English => "The current container format has no adjustable muxer options.",
+        }
+    }
+
+    /// Localized (label, description) of a container publishing option; None falls back to the registry text
+    pub fn container_option_text(&self, key: &str) -> Option<(&'static str, &'static str)> {
+        match (&self.language, key) {
+            (Language::Chinese, "faststart") => Some(("快速启动", "将 moov 索引移到文件开头，下载时即可开始播放")),
+            (Language::Chinese, "fragmented") => Some(("分片 MP4", "写入独立分片 (frag_keyframe+empty_moov) 以便流式传输；启用后替代快速启动")),
+            (Language::Chinese, "cues_front") => Some(("索引前置", "将定位索引写在文件开头，网络播放时跳转更快")),
+            (Language::Chinese, "dash") => Some(("DASH", "写入兼容 WebM DASH 的簇和索引")),
+            _ => None,
+        }
+    }
+
+    pub fn filter_stack_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "滤镜栈 (按顺序应用)",
+            Language::English => "Filter stack (applied in order)",
+        }
+    }
+
+    pub fn filter_stack_empty(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "尚未添加滤镜",
+            Language::English => "No filters added yet",
+        }
+    }
+
+    pub fn add_filter(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 添加滤镜",
+            Language::English => "➕ Add filter",
+        }
+    }
+
+    pub fn effective_filtergraph(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "实际滤镜链:",
+            Language::English => "Effective filtergraph:",
+        }
+    }
+
+    pub fn heavy_filter_warning(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ nlmeans 在 4K 输入上非常耗费 CPU，处理可能比实时慢许多倍。",
+            Language::English => "⚠ nlmeans is very CPU-heavy on 4K input; processing may run many times slower than realtime.",
+        }
+    }
+
+    pub fn legacy_filters_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "当前使用旧版滤镜设置 (降噪/去隔行/色彩)。",
+            Language::English => "Legacy filter settings (denoise/deinterlace/color) are active.",
+        }
+    }
+
+    pub fn convert_to_filter_stack(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "转换为滤镜栈",
+            Language::English => "Convert to filter stack",
+        }
+    }
+
+    pub fn filter_stage_name(&self, stage: &crate::filter_stack::FilterStage) -> &'static str {
+        use crate::filter_stack::FilterStage;
+        match (self.language.clone(), stage) {
+            (Language::Chinese, FilterStage::Deinterlace { .. }) => "去隔行 (yadif)",
+            (Language::Chinese, FilterStage::Hqdn3d { .. }) => "降噪 (hqdn3d)",
+            (Language::Chinese, FilterStage::Nlmeans { .. }) => "高质量降噪 (nlmeans)",
+            (Language::Chinese, FilterStage::Unsharp { .. }) => "锐化 (unsharp)",
+            (Language::Chinese, FilterStage::Deband { .. }) => "去色带 (deband)",
+            (Language::Chinese, FilterStage::Eq { .. }) => "色彩调整 (eq)",
+            (Language::English, FilterStage::Deinterlace { .. }) => "Deinterlace (yadif)",
+            (Language::English, FilterStage::Hqdn3d { .. }) => "Denoise (hqdn3d)",
+            (Language::English, FilterStage::Nlmeans { .. }) => "High quality denoise (nlmeans)",
+            (Language::English, FilterStage::Unsharp { .. }) => "Sharpen (unsharp)",
+            (Language::English, FilterStage::Deband { .. }) => "Deband",
+            (Language::English, FilterStage::Eq { .. }) => "Color adjust (eq)",
+        }
+    }
+
+    pub fn filter_param_mode(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "模式:",
+            Language::English => "Mode:",
+        }
+    }
+
+    pub fn filter_param_send_frame(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每帧输出一帧",
+            Language::English => "Frame per frame",
+        }
+    }
+
+    pub fn filter_param_send_field(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每场输出一帧",
+            Language::English => "Frame per field",
+        }
+    }
+
+    pub fn filter_param_luma_spatial(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "亮度空间:",
+            Language::English => "Luma spatial:",
+        }
+    }
+
+    pub fn filter_param_chroma_spatial(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "色度空间:",
+            Language::English => "Chroma spatial:",
+        }
+    }
+
+    pub fn filter_param_luma_temporal(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "亮度时间:",
+            Language::English => "Luma temporal:",
+        }
+    }
+
+    pub fn filter_param_chroma_temporal(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "色度时间:",
+            Language::English => "Chroma temporal:",
+        }
+    }
+
+    pub fn filter_param_strength(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "强度:",
+            Language::English => "Strength:",
+        }
+    }
+
+    pub fn filter_param_patch_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "块大小:",
+            Language::English => "Patch size:",
+        }
+    }
+
+    pub fn filter_param_research_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "搜索范围:",
+            Language::English => "Research size:",
+        }
+    }
+
+    pub fn filter_param_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "矩阵大小:",
+            Language::English => "Matrix size:",
+        }
+    }
+
+    pub fn filter_param_amount(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "强度:",
+            Language::English => "Amount:",
+        }
+    }
+
+    pub fn filter_param_threshold(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "阈值:",
+            Language::English => "Threshold:",
+        }
+    }
+
+    pub fn filter_param_range(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "范围:",
+            Language::English => "Range:",
+        }
+    }
+
+    pub fn filter_param_brightness(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "亮度:",
+            Language::English => "Brightness:",
+        }
+    }
+
+    pub fn filter_param_contrast(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "对比度:",
+            Language::English => "Contrast:",
+        }
+    }
+
+    pub fn filter_param_saturation(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "饱和度:",
+            Language::English => "Saturation:",
+        }
+    }
+
+    pub fn view_last_run_report(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 查看上次运行报告",
+            Language::English => "📋 View last run report",
+        }
+    }
+
+    pub fn run_report_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 工作流运行报告",
+            Language::English => "📋 Workflow Run Report",
+        }
+    }
+
+    pub fn run_report_time(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "时间:",
+            Language::English => "Time:",
+        }
+    }
+
+    pub fn run_report_summary(&self, nodes: usize, wall_time_ms: u64, artifacts: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 个节点，总耗时 {:.1} 秒，产出文件 {}", nodes, wall_time_ms as f64 / 1000.0, artifacts),
+            Language::English => format!("{} nodes, {:.1} s wall time, {} of artifacts", nodes, wall_time_ms as f64 / 1000.0, artifacts),
+        }
+    }
+
+    pub fn workflow_plan_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🧾 命令预览",
+            Language::English => "🧾 Command Preview",
+        }
+    }
+
+    pub fn workflow_plan_fused(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "合并为一次 ffmpeg 运行",
+            Language::English => "Fused into one ffmpeg run",
+        }
+    }
+
+    pub fn workflow_plan_own(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "单独运行，输出写入中间文件",
+            Language::English => "Runs on its own, writing an intermediate file",
+        }
+    }
+
+    pub fn workflow_plan_copy(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制",
+            Language::English => "📋 Copy",
+        }
+    }
+
+    pub fn workflow_plan_copy_all(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制全部命令",
+            Language::English => "📋 Copy all commands",
+        }
+    }
+
+    pub fn workflow_plan_refresh(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔄 刷新",
+            Language::English => "🔄 Refresh",
+        }
+    }
+
+    pub fn workflow_plan_placeholder_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "$TMP 代表本次运行的临时文件夹；依赖前一步输出的步骤，如需读取该输出则只能在实际运行时确定",
+            Language::English => "$TMP stands for the run's scratch folder; steps that must read an earlier step's output can only be fully resolved by a real run",
+        }
+    }
+
+    pub fn workflow_plan_no_command(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不运行 ffmpeg",
+            Language::English => "Runs no ffmpeg command",
+        }
+    }
+
+    pub fn workflow_plan_file_endpoint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入/输出文件",
+            Language::English => "input or output file",
+        }
+    }
+
+    pub fn workflow_plan_branch(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出有多个去向，或下一节点有多个输入",
+            Language::English => "the output fans out or the next node has several inputs",
+        }
+    }
+
+    pub fn workflow_plan_needs_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "下一节点需要多次读取输入 (两遍编码、分析等)",
+            Language::English => "the next node reads its input more than once (two-pass encode, analysis)",
+        }
+    }
+
+    pub fn workflow_plan_not_streamable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "节点使用自己的命令生成器",
+            Language::English => "the node builds its own commands",
+        }
+    }
+
+    pub fn workflow_plan_disabled(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "节点已禁用",
+            Language::English => "the node is disabled",
+        }
+    }
+
+    pub fn workflow_plan_summary(&self, nodes: usize, steps: usize, fused: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 个节点分 {} 步执行，其中 {} 个节点被合并", nodes, steps, fused),
+            Language::English => format!("{} nodes run in {} steps, {} of them fused", nodes, steps, fused),
+        }
+    }
+
+    pub fn run_report_node_id(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "节点 ID",
+            Language::English => "Node ID",
+        }
+    }
+
+    pub fn run_report_cached(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已缓存，跳过",
+            Language::English => "cached, skipped",
+        }
+    }
+
+    pub fn run_report_final_outputs(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最终输出:",
+            Language::English => "Final outputs:",
+        }
+    }
+
+    pub fn run_report_export_json(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💾 导出为 JSON…",
+            Language::English => "💾 Export to JSON…",
+        }
+    }
+
+    pub fn run_report_exported(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运行报告已导出",
+            Language::English => "Run report exported",
+        }
+    }
+
+    pub fn workflow_session_stats(&self, runs: u32, failed: u32, nodes: u32, wall_time_ms: u64, artifacts: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("本次会话工作流: 运行 {} 次 (失败 {})，{} 个节点，耗时 {:.1} 秒，产出 {}", runs, failed, nodes, wall_time_ms as f64 / 1000.0, artifacts),
+            Language::English => format!("Workflows this session: {} runs ({} failed), {} nodes, {:.1} s, {} produced", runs, failed, nodes, wall_time_ms as f64 / 1000.0, artifacts),
+        }
+    }
+
+    pub fn input_error(&self, error: &crate::locale_format::InputError) -> String {
+        use crate::locale_format::InputError;
+        match (&self.language, error) {
+            (Language::Chinese, InputError::Empty) => "不能为空".to_string(),
+            (Language::Chinese, InputError::InvalidNumber) => "无效数字，可使用 1.5 或 1,5".to_string(),
+            (Language::Chinese, InputError::InvalidDuration) => "无效时长，可使用秒数、MM:SS 或 HH:MM:SS(.毫秒)".to_string(),
+            (Language::Chinese, InputError::InvalidBitrate) => "无效码率，例如 128k、2500k 或 2,5M".to_string(),
+            (Language::Chinese, InputError::OutOfRange { min, max }) => format!("超出范围 ({} – {})", min, max),
+            (_, InputError::Empty) => "Value required".to_string(),
+            (_, InputError::InvalidNumber) => "Not a number; 1.5 and 1,5 are both accepted".to_string(),
+            (_, InputError::InvalidDuration) => "Invalid duration; use seconds, MM:SS or HH:MM:SS(.ms)".to_string(),
+            (_, InputError::InvalidBitrate) => "Invalid bitrate, e.g. 128k, 2500k or 2,5M".to_string(),
+            (_, InputError::OutOfRange { min, max }) => format!("Out of range ({} – {})", min, max),
+        }
+    }
+
+    pub fn duration_input_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "秒数 (1,5 或 1.5)、MM:SS 或 HH:MM:SS(.毫秒)",
+            Language::English => "Seconds (1.5 or 1,5), MM:SS or HH:MM:SS(.ms)",
+        }
+    }
+
+    pub fn bitrate_input_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "kbps 数值或带单位: 128k、2500k、2,5M、auto",
+            Language::English => "kbps number or with unit: 128k, 2500k, 2.5M, auto",
+        }
+    }
+
+    pub fn audio_denoise(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔇 降噪",
+            Language::English => "🔇 Noise Reduction",
+        }
+    }
+
+    pub fn denoise_preset_name(&self, preset: crate::audio_denoise::DenoisePreset) -> &'static str {
+        use crate::audio_denoise::DenoisePreset;
+        match (self.language.clone(), preset) {
+            (Language::Chinese, DenoisePreset::Light) => "轻度",
+            (Language::Chinese, DenoisePreset::Medium) => "中等",
+            (Language::Chinese, DenoisePreset::Aggressive) => "强力",
+            (Language::Chinese, DenoisePreset::Voice) => "人声录音",
+            (Language::Chinese, DenoisePreset::Custom) => "(自定义)",
+            (Language::English, DenoisePreset::Light) => "Light",
+            (Language::English, DenoisePreset::Medium) => "Medium",
+            (Language::English, DenoisePreset::Aggressive) => "Aggressive",
+            (Language::English, DenoisePreset::Voice) => "Voice recording",
+            (Language::English, DenoisePreset::Custom) => "(custom)",
+        }
+    }
+
+    pub fn audio_mix_mode(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "合并模式:",
+            Language::English => "Merge Mode:",
+        }
+    }
+
+    pub fn audio_mix_concat(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "顺序连接",
+            Language::English => "Concatenate",
+        }
+    }
+
+    pub fn audio_mix_mix(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "混音",
+            Language::English => "Mix",
+        }
+    }
+
+    pub fn audio_mix_crossfade(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "交叉淡化:",
+            Language::English => "Crossfade:",
+        }
+    }
+
+    pub fn audio_mix_add_silence(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "在音频之间添加静音",
+            Language::English => "Add Silence Between Tracks",
+        }
+    }
+
+    pub fn audio_mix_silence_duration(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "静音时长 (秒):",
+            Language::English => "Silence Duration (seconds):",
+        }
+    }
+
+    pub fn audio_mix_normalize(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "混音时自动归一化（防止削波）",
+            Language::English => "Normalize on mix (prevents clipping)",
+        }
+    }
+
+    pub fn audio_mix_duration(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出时长:",
+            Language::English => "Output length:",
+        }
+    }
+
+    pub fn audio_mix_first(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "第一个输入",
+            Language::English => "First input",
+        }
+    }
+
+    pub fn audio_mix_longest(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最长",
+            Language::English => "Longest",
+        }
+    }
+
+    pub fn audio_mix_shortest(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最短",
+            Language::English => "Shortest",
+        }
+    }
+
+    pub fn audio_mix_gain(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "增益 ",
+            Language::English => "Gain ",
+        }
+    }
+
+    pub fn audio_mix_gain_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每个输入的增益在输入文件列表中设置",
+            Language::English => "Set each input's gain in the input file list",
+        }
+    }
+
+    pub fn merge_copy_audio(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "直接复制音频流（不重新编码）",
+            Language::English => "Copy the audio stream (no re-encode)",
+        }
+    }
+
+    pub fn merge_copy_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频流总是直接复制；输出容器不支持的流会自动重新编码",
+            Language::English => "The video stream is always copied; a stream the output container cannot hold is encoded instead",
+        }
+    }
+
+    pub fn merge_shortest(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "以较短的流结束（-shortest）",
+            Language::English => "End with the shorter stream (-shortest)",
+        }
+    }
+
+    pub fn denoise_enable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "启用降噪",
+            Language::English => "Reduce noise",
+        }
+    }
+
+    pub fn denoise_engine(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "引擎:",
+            Language::English => "Engine:",
+        }
+    }
+
+    pub fn denoise_preset(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预设:",
+            Language::English => "Preset:",
+        }
+    }
+
+    pub fn denoise_reduction(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "降噪量:",
+            Language::English => "Reduction:",
+        }
+    }
+
+    pub fn denoise_noise_floor(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "噪声底:",
+            Language::English => "Noise floor:",
+        }
+    }
+
+    pub fn denoise_learn_profile(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "从开头学习噪声样本",
+            Language::English => "Learn noise profile from the first",
+        }
+    }
+
+    pub fn denoise_strength(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "强度:",
+            Language::English => "Strength:",
+        }
+    }
+
+    pub fn denoise_highpass(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "切除低频 (高通):",
+            Language::English => "Cut rumble below (high-pass):",
+        }
+    }
+
+    pub fn denoise_lowpass(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "切除高频 (低通):",
+            Language::English => "Cut hiss above (low-pass):",
+        }
+    }
+
+    pub fn denoise_learn_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "开头几秒应只有背景噪声（如录音前的环境音），降噪会以此为样本。",
+            Language::English => "The first seconds should contain only background noise (e.g. room tone before speech); it is used as the noise sample.",
+        }
+    }
+
+    pub fn echo_in_gain(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入增益:",
+            Language::English => "Input gain:",
+        }
+    }
+
+    pub fn echo_out_gain(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出增益:",
+            Language::English => "Output gain:",
+        }
+    }
+
+    pub fn echo_delay(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "延迟",
+            Language::English => "Delay",
+        }
+    }
+
+    pub fn echo_decay(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "衰减",
+            Language::English => "Decay",
+        }
+    }
+
+    pub fn echo_remove(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除此回声",
+            Language::English => "Remove this echo",
+        }
+    }
+
+    pub fn echo_add(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 添加回声",
+            Language::English => "➕ Add echo",
+        }
+    }
+
+    pub fn equalizer_bass(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "低音 (100 Hz):",
+            Language::English => "Bass (100 Hz):",
+        }
+    }
+
+    pub fn equalizer_mid(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "中音 (1 kHz):",
+            Language::English => "Mid (1 kHz):",
+        }
+    }
+
+    pub fn equalizer_treble(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "高音 (3 kHz):",
+            Language::English => "Treble (3 kHz):",
+        }
+    }
+
+    pub fn equalizer_flat(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "重置为平直",
+            Language::English => "Reset to flat",
+        }
+    }
+
+    pub fn equalizer_advanced(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "高级滤镜 (追加在频段之后):",
+            Language::English => "Advanced filters (appended after the bands):",
+        }
+    }
+
+    pub fn equalizer_flat_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "所有频段为 0 dB，音频保持不变",
+            Language::English => "All bands at 0 dB, the audio is left unchanged",
+        }
+    }
+
+    pub fn audio_fade_fade_in(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "淡入:",
+            Language::English => "Fade in:",
+        }
+    }
+
+    pub fn audio_fade_fade_out(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "淡出:",
+            Language::English => "Fade out:",
+        }
+    }
+
+    pub fn audio_fade_curve(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "曲线:",
+            Language::English => "Curve:",
+        }
+    }
+
+    pub fn audio_fade_tri(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "线性",
+            Language::English => "Linear",
+        }
+    }
+
+    pub fn audio_fade_qsin(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "四分之一正弦",
+            Language::English => "Quarter sine",
+        }
+    }
+
+    pub fn audio_fade_esin(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "指数正弦",
+            Language::English => "Exponential sine",
+        }
+    }
+
+    pub fn audio_fade_log(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "对数",
+            Language::English => "Logarithmic",
+        }
+    }
+
+    pub fn audio_fade_fade_out_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "淡出从音频结尾倒数，开始前会先读取输入时长",
+            Language::English => "The fade-out counts back from the end; the input's duration is read before the task starts",
+        }
+    }
+
+    pub fn fps_conversion_target_fps(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "目标帧率:",
+            Language::English => "Target frame rate:",
+        }
+    }
+
+    pub fn fps_conversion_mode(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "方式:",
+            Language::English => "Mode:",
+        }
+    }
+
+    pub fn fps_conversion_fps(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "丢帧/补帧",
+            Language::English => "Drop/duplicate frames",
+        }
+    }
+
+    pub fn fps_conversion_minterpolate(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运动插帧",
+            Language::English => "Motion interpolation",
+        }
+    }
+
+    pub fn fps_conversion_speed_change(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "变速",
+            Language::English => "Speed change",
+        }
+    }
+
+    pub fn fps_conversion_fps_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按目标帧率丢弃或重复帧，时长不变",
+            Language::English => "Frames are dropped or duplicated to reach the target rate; the duration stays the same",
+        }
+    }
+
+    pub fn fps_conversion_minterpolate_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "根据画面运动生成中间帧，适合转换为 60fps 等更流畅的帧率",
+            Language::English => "New frames are synthesized from the motion between frames, e.g. for smooth 60 fps",
+        }
+    }
+
+    pub fn fps_conversion_speed_change_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保留所有帧并按目标帧率播放，视频会变快或变慢",
+            Language::English => "Every frame is kept and played at the target rate, so the video speeds up or slows down",
+        }
+    }
+
+    pub fn fps_conversion_minterpolate_slow(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运动插帧计算量大，比普通转换慢很多",
+            Language::English => "Motion interpolation is much slower than a plain conversion",
+        }
+    }
+
+    pub fn fps_conversion_audio_untouched(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频保持不变（直接复制）",
+            Language::English => "The audio is copied unchanged",
+        }
+    }
+
+    pub fn fps_conversion_audio_retimed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频会用 atempo 同步变速",
+            Language::English => "The audio is retimed with atempo to stay in sync",
+        }
+    }
+
+    pub fn overlay_position_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "像素值或 FFmpeg 表达式，如 main_w-overlay_w-10",
+            Language::English => "Pixels or an FFmpeg expression, e.g. main_w-overlay_w-10",
+        }
+    }
+
+    pub fn overlay_opacity(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不透明度:",
+            Language::English => "Opacity:",
+        }
+    }
+
+    pub fn overlay_audio(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频:",
+            Language::English => "Audio:",
+        }
+    }
+
+    pub fn overlay_main(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "仅主视频",
+            Language::English => "Main video only",
+        }
+    }
+
+    pub fn overlay_mix(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "混合两路",
+            Language::English => "Mix both",
+        }
+    }
+
+    pub fn overlay_pip_scale(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "画中画宽度:",
+            Language::English => "Inset width:",
+        }
+    }
+
+    pub fn overlay_anchor(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "位置:",
+            Language::English => "Position:",
+        }
+    }
+
+    pub fn overlay_top_left(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "左上",
+            Language::English => "Top left",
+        }
+    }
+
+    pub fn overlay_top_right(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "右上",
+            Language::English => "Top right",
+        }
+    }
+
+    pub fn overlay_bottom_left(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "左下",
+            Language::English => "Bottom left",
+        }
+    }
+
+    pub fn overlay_bottom_right(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "右下",
+            Language::English => "Bottom right",
+        }
+    }
+
+    pub fn overlay_center(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "居中",
+            Language::English => "Center",
+        }
+    }
+
+    pub fn overlay_margin(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "边距:",
+            Language::English => "Margin:",
+        }
+    }
+
+    pub fn image_sequence_format(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "图片格式:",
+            Language::English => "Image format:",
+        }
+    }
+
+    pub fn image_sequence_jpg_quality(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "JPEG 质量 (1 最佳):",
+            Language::English => "JPEG quality (1 best):",
+        }
+    }
+
+    pub fn image_sequence_every_frame(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "导出每一帧",
+            Language::English => "Every frame",
+        }
+    }
+
+    pub fn image_sequence_rate(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "帧率:",
+            Language::English => "Frame rate:",
+        }
+    }
+
+    pub fn image_sequence_scratch_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "留空则使用工作流临时目录",
+            Language::English => "Empty uses the workflow's scratch folder",
+        }
+    }
+
+    pub fn image_sequence_directory(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "图片文件夹:",
+            Language::English => "Image folder:",
+        }
+    }
+
+    pub fn image_sequence_directory_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "连接“视频转图片”节点时可留空",
+            Language::English => "Not needed when Video to Images is connected",
+        }
+    }
+
+    pub fn image_sequence_pattern(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件匹配:",
+            Language::English => "Files:",
+        }
+    }
+
+    pub fn image_sequence_pattern_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "留空表示文件夹中的全部图片",
+            Language::English => "Empty for every image in the folder",
+        }
+    }
+
+    pub fn image_sequence_pattern_tooltip(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编号序列如 frame_%06d.png，或通配符如 *.jpg；留空则按文件名顺序使用全部图片",
+            Language::English => "A numbered sequence such as frame_%06d.png or a glob such as *.jpg; empty takes every image in name order",
+        }
+    }
+
+    pub fn image_sequence_codec(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编码器:",
+            Language::English => "Codec:",
+        }
+    }
+
+    pub fn image_sequence_pixel_format(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "像素格式:",
+            Language::English => "Pixel format:",
+        }
+    }
+
+    pub fn image_sequence_audio_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音轨:",
+            Language::English => "Soundtrack:",
+        }
+    }
+
+    pub fn image_sequence_audio_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "可选；连接音频输入时忽略",
+            Language::English => "Optional; the audio input takes precedence",
+        }
+    }
+
+    pub fn quality_metrics(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "指标:",
+            Language::English => "Metrics:",
+        }
+    }
+
+    pub fn quality_vmaf_unavailable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "当前 FFmpeg 未包含 libvmaf，无法计算 VMAF",
+            Language::English => "This FFmpeg has no libvmaf, so VMAF cannot be computed",
+        }
+    }
+
+    pub fn quality_no_metric(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "请至少选择一个指标",
+            Language::English => "Select at least one metric",
+        }
+    }
+
+    pub fn quality_csv_path(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "逐帧 CSV:",
+            Language::English => "Per-frame CSV:",
+        }
+    }
+
+    pub fn quality_csv_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "可选，留空则不导出",
+            Language::English => "Optional; empty exports nothing",
+        }
+    }
+
+    pub fn quality_inputs_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入 1 为参考视频（原片），输入 2 为待评估视频（编码结果）；分辨率不同时会先缩放待评估视频",
+            Language::English => "Input 1 is the reference (the original), input 2 the distorted video (the encode); a distorted video of another size is scaled to the reference's first",
+        }
+    }
+
+    pub fn quality_last_report(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最近一次结果",
+            Language::English => "Latest result",
+        }
+    }
+
+    pub fn quality_no_report(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运行工作流后在此显示得分",
+            Language::English => "Scores show here after the workflow runs",
+        }
+    }
+
+    pub fn encryption_generate_key(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "生成新的随机密钥",
+            Language::English => "Generate a new random key",
+        }
+    }
+
+    pub fn encryption_generate_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "关闭后复用密钥文件中的已有密钥；已存在的指定密钥文件不会被覆盖",
+            Language::English => "Off reuses the key already in the key file; an existing chosen key file is never overwritten",
+        }
+    }
+
+    pub fn encryption_key_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "密钥文件:",
+            Language::English => "Key file:",
+        }
+    }
+
+    pub fn encryption_key_auto_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "留空则保存在播放列表旁",
+            Language::English => "Empty keeps it next to the playlist",
+        }
+    }
+
+    pub fn encryption_key_existing_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "要复用的 16 字节密钥",
+            Language::English => "The 16-byte key to reuse",
+        }
+    }
+
+    pub fn encryption_key_playlist_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "留空则使用播放列表指定的密钥",
+            Language::English => "Empty uses the key the playlist names",
+        }
+    }
+
+    pub fn encryption_key_warning(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "任何拿到密钥文件的人都能解密视频，请妥善保管",
+            Language::English => "Anyone with the key file can decrypt the video; keep it safe",
+        }
+    }
+
+    pub fn encryption_container(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出格式:",
+            Language::English => "Output format:",
+        }
+    }
+
+    pub fn renditions_height(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "高度",
+            Language::English => "Height",
+        }
+    }
+
+    pub fn renditions_video_bitrate(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频码率",
+            Language::English => "Video bitrate",
+        }
+    }
+
+    pub fn renditions_audio_bitrate(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频码率",
+            Language::English => "Audio bitrate",
+        }
+    }
+
+    pub fn renditions_remove(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除此输出",
+            Language::English => "Remove this rendition",
+        }
+    }
+
+    pub fn renditions_add(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 添加分辨率",
+            Language::English => "➕ Add rendition",
+        }
+    }
+
+    pub fn renditions_codec(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频编码器:",
+            Language::English => "Video codec:",
+        }
+    }
+
+    pub fn renditions_container(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "容器:",
+            Language::English => "Container:",
+        }
+    }
+
+    pub fn renditions_basename(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件名前缀:",
+            Language::English => "Base name:",
+        }
+    }
+
+    pub fn stream_packaging_format(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "格式:",
+            Language::English => "Format:",
+        }
+    }
+
+    pub fn stream_packaging_segment(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "分片时长:",
+            Language::English => "Segment length:",
+        }
+    }
+
+    pub fn stream_packaging_playlist_type(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "播放列表:",
+            Language::English => "Playlist:",
+        }
+    }
+
+    pub fn stream_packaging_vod(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "点播 (VOD)",
+            Language::English => "VOD",
+        }
+    }
+
+    pub fn stream_packaging_event(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "事件 (EVENT)",
+            Language::English => "Event",
+        }
+    }
+
+    pub fn stream_packaging_fragment(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "片段时长:",
+            Language::English => "Fragment length:",
+        }
+    }
+
+    pub fn stream_packaging_video_codec(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频:",
+            Language::English => "Video:",
+        }
+    }
+
+    pub fn stream_packaging_audio_codec(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频:",
+            Language::English => "Audio:",
+        }
+    }
+
+    pub fn stream_packaging_codec_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "auto：H.264/HEVC 与 AAC 直接复制，其他编码重新编码为 H.264/AAC",
+            Language::English => "auto copies H.264/HEVC and AAC and encodes anything else to H.264/AAC",
+        }
+    }
+
+    pub fn stream_packaging_copy_warning(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "复制模式保留原编码；播放器通常只支持 H.264/HEVC 视频和 AAC 音频",
+            Language::English => "Copy keeps the source codecs; players generally only play H.264/HEVC video with AAC audio",
+        }
+    }
+
+    pub fn stream_packaging_name(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "播放列表名称:",
+            Language::English => "Playlist name:",
+        }
+    }
+
+    pub fn stream_packaging_variants_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "连接“多分辨率输出”节点可生成带主播放列表的自适应码流",
+            Language::English => "Connect a Multi-Resolution node for an adaptive stream with a master playlist",
+        }
+    }
+
+    pub fn text_overlay_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文字:",
+            Language::English => "Text:",
+        }
+    }
+
+    pub fn text_overlay_font_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "字号:",
+            Language::English => "Size:",
+        }
+    }
+
+    pub fn text_overlay_color(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "颜色:",
+            Language::English => "Color:",
+        }
+    }
+
+    pub fn text_overlay_position(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "位置:",
+            Language::English => "Position:",
+        }
+    }
+
+    pub fn text_overlay_top_left(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "左上",
+            Language::English => "Top left",
+        }
+    }
+
+    pub fn text_overlay_top_center(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "顶部居中",
+            Language::English => "Top center",
+        }
+    }
+
+    pub fn text_overlay_top_right(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "右上",
+            Language::English => "Top right",
+        }
+    }
+
+    pub fn text_overlay_center(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "居中",
+            Language::English => "Center",
+        }
+    }
+
+    pub fn text_overlay_bottom_left(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "左下",
+            Language::English => "Bottom left",
+        }
+    }
+
+    pub fn text_overlay_bottom_center(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "底部居中",
+            Language::English => "Bottom center",
+        }
+    }
+
+    pub fn text_overlay_bottom_right(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "右下",
+            Language::English => "Bottom right",
+        }
+    }
+
+    pub fn text_overlay_custom(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自定义",
+            Language::English => "Custom",
+        }
+    }
+
+    pub fn text_overlay_position_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "像素值或 FFmpeg 表达式：w/h 为画面宽高，tw/th 为文字宽高，如 (w-tw)/2",
+            Language::English => "Pixels or an FFmpeg expression: w/h are the frame size, tw/th the text size, e.g. (w-tw)/2",
+        }
+    }
+
+    pub fn text_overlay_box(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "背景框",
+            Language::English => "Background box",
+        }
+    }
+
+    pub fn text_overlay_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "出现于",
+            Language::English => "Appear at",
+        }
+    }
+
+    pub fn text_overlay_end(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "消失于",
+            Language::English => "Disappear at",
+        }
+    }
+
+    pub fn stabilize_smoothing(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "平滑帧数:",
+            Language::English => "Smoothing (frames):",
+        }
+    }
+
+    pub fn stabilize_smoothing_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每帧前后参与平均的帧数，越大画面越稳，0 表示固定机位",
+            Language::English => "Frames before and after each frame averaged into the camera path; higher is steadier, 0 keeps the camera static",
+        }
+    }
+
+    pub fn stabilize_zoom(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "缩放:",
+            Language::English => "Zoom:",
+        }
+    }
+
+    pub fn stabilize_optblur(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "锐化以抵消变换产生的模糊",
+            Language::English => "Sharpen to counter the blur of the transform",
+        }
+    }
+
+    pub fn stabilize_two_pass_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "先分析镜头运动并写入变换文件，再按平滑后的轨迹稳定画面",
+            Language::English => "The camera motion is analysed into a transforms file first, then a second pass smooths it out",
+        }
+    }
+
+    pub fn stabilize_unavailable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 当前 FFmpeg 未包含 vid.stab（vidstabdetect/vidstabtransform），无法防抖。请使用带 --enable-libvidstab 编译的 FFmpeg。",
+            Language::English => "⚠ This FFmpeg has no vid.stab (vidstabdetect/vidstabtransform), so it cannot stabilize. Use an FFmpeg built with --enable-libvidstab.",
+        }
+    }
+
+    pub fn loudness_preset(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预设:",
+            Language::English => "Preset:",
+        }
+    }
+
+    pub fn loudness_streaming(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "流媒体 (-16 LUFS)",
+            Language::English => "Streaming (-16 LUFS)",
+        }
+    }
+
+    pub fn loudness_ebu_r128(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "EBU R128 (-23 LUFS)",
+            Language::English => "EBU R128 (-23 LUFS)",
+        }
+    }
+
+    pub fn loudness_custom(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自定义",
+            Language::English => "Custom",
+        }
+    }
+
+    pub fn loudness_integrated(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "目标响度:",
+            Language::English => "Target loudness:",
+        }
+    }
+
+    pub fn loudness_true_peak(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "真峰值上限:",
+            Language::English => "True peak limit:",
+        }
+    }
+
+    pub fn loudness_range(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "响度范围:",
+            Language::English => "Loudness range:",
+        }
+    }
+
+    pub fn loudness_two_pass_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "先测量输入响度，再按测量结果进行第二遍标准化",
+            Language::English => "The input's loudness is measured first, then a second pass normalizes it using the measurement",
+        }
+    }
+
+    pub fn encoding_advisories(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💡 编码建议",
+            Language::English => "💡 Encoding advisories",
+        }
+    }
+
+    pub fn advisory_message(&self, advisory: &crate::encode_advisor::Advisory) -> String {
+        use crate::encode_advisor::Advisory;
+        match (&self.language, advisory) {
+            (Language::Chinese, Advisory::Upscale { source, target }) => format!(
+                "输出分辨率 {}x{} 大于源 {}x{}。放大不会增加细节，只会让文件更大。", target.0, target.1, source.0, source.1),
+            (Language::Chinese, Advisory::VideoBitrateAboveSource { source_kbps, target_kbps }) => format!(
+                "视频码率 {} kbps 远高于源的 {} kbps（同一编码家族）。多出的码率无法恢复已丢失的画质。", target_kbps, source_kbps),
+            (Language::Chinese, Advisory::AudioBitrateAboveSource { source_kbps, target_kbps }) => format!(
+                "音频码率 {} kbps 远高于源的 {} kbps（同一编码家族）。多出的码率无法恢复已丢失的音质。", target_kbps, source_kbps),
+            (Language::Chinese, Advisory::VideoStreamCopy { codec, source_kbps, target_kbps }) => format!(
+                "源视频已是 {}（{} kbps），以相近码率 {} kbps 重新编码只会损失画质。直接复制视频流更快且无损。", codec, source_kbps, target_kbps),
+            (Language::Chinese, Advisory::AudioStreamCopy { codec, source_kbps, target_kbps }) => format!(
+                "源音频已是 {}（{} kbps），以相近码率 {} kbps 重新编码只会损失音质。直接复制音频流更快且无损。", codec, source_kbps, target_kbps),
+            (Language::Chinese, Advisory::FrameRateChange { source, target }) => format!(
+                "输出帧率 {} 与源 {} 不同。帧会被不均匀地重复或丢弃，画面出现抖动；若两者时长计算不一致，音画也可能逐渐不同步。",
+                crate::frame_rate::describe(*target), crate::frame_rate::describe(*source)),
+            (_, Advisory::Upscale { source, target }) => format!(
+                "Output size {}x{} is larger than the source {}x{}. Upscaling adds no detail, only file size.", target.0, target.1, source.0, source.1),
+            (_, Advisory::VideoBitrateAboveSource { source_kbps, target_kbps }) => format!(
+                "Video bitrate {} kbps is far above the source's {} kbps for the same codec family. The extra bits cannot restore lost quality.", target_kbps, source_kbps),
+            (_, Advisory::AudioBitrateAboveSource { source_kbps, target_kbps }) => format!(
+                "Audio bitrate {} kbps is far above the source's {} kbps for the same codec family. The extra bits cannot restore lost quality.", target_kbps, source_kbps),
+            (_, Advisory::VideoStreamCopy { codec, source_kbps, target_kbps }) => format!(
+                "The source video is already {} ({} kbps); re-encoding at a similar {} kbps only loses quality. Copying the stream is faster and lossless.", codec, source_kbps, target_kbps),
+            (_, Advisory::AudioStreamCopy { codec, source_kbps, target_kbps }) => format!(
+                "The source audio is already {} ({} kbps); re-encoding at a similar {} kbps only loses quality. Copying the stream is faster and lossless.", codec, source_kbps, target_kbps),
+            (_, Advisory::FrameRateChange { source, target }) => format!(
+                "Output frame rate {} differs from the source's {}. Frames get duplicated or dropped unevenly, which shows as judder, and audio can drift out of sync if the durations are computed differently.",
+                crate::frame_rate::describe(*target), crate::frame_rate::describe(*source)),
+            (_, Advisory::AudioBitrateForLayout(advice)) => self.audio_advice_message(advice),
+        }
+    }
+
+    fn audio_advice_message(&self, advice: &crate::audio_advice::AudioAdvice) -> String {
+        use crate::audio_advice::{AdviceKind, ContentType};
+        let (low, high) = advice.recommended;
+        let target = advice.target_kbps.unwrap_or(0);
+        match (&self.language, advice.kind) {
+            (Language::Chinese, kind) => {
+                let content = match advice.content {
+                    Some(ContentType::Voice) => "人声",
+                    Some(ContentType::Music) => "音乐",
+                    None => "音频",
+                };
+                let layout = format!("{} 声道{}", advice.channels, content);
+                match kind {
+                    AdviceKind::BelowFloor => format!(
+                        "{} kbps 对 {} ({}) 来说太低，会有明显失真。建议 {}–{} kbps。", target, layout, advice.codec_family, low, high),
+                    AdviceKind::AboveRange => format!(
+                        "{} kbps 对 {} ({}) 来说过高，文件更大但听不出差别。建议 {}–{} kbps。", target, layout, advice.codec_family, low, high),
+                    AdviceKind::AutoSurround => format!(
+                        "自动码率会让 {} ({}) 使用按立体声设定的默认值。建议 {}–{} kbps。", layout, advice.codec_family, low, high),
+                }
+            }
+            (_, kind) => {
+                let content = match advice.content {
+                    Some(ContentType::Voice) => " voice",
+                    Some(ContentType::Music) => " music",
+                    None => "",
+                };
+                let layout = format!("{}-channel{}", advice.channels, content);
+                match kind {
+                    AdviceKind::BelowFloor => format!(
+                        "{} kbps is too low for {} {} audio and will sound noticeably degraded. Recommended: {}–{} kbps.",
+                        target, layout, advice.codec_family, low, high),
+                    AdviceKind::AboveRange => format!(
+                        "{} kbps is more than {} {} audio needs; the file gets bigger without an audible difference. Recommended: {}–{} kbps.",
+                        target, layout, advice.codec_family, low, high),
+                    AdviceKind::AutoSurround => format!(
+                        "With an automatic bitrate, {} {} audio gets the encoder's stereo-sized default. Recommended: {}–{} kbps.",
+                        layout, advice.codec_family, low, high),
+                }
+            }
+        }
+    }
+
+    pub fn advisory_fix_label(&self, fix: &crate::encode_advisor::AdvisoryFix) -> String {
+        use crate::encode_advisor::AdvisoryFix;
+        match (&self.language, fix) {
+            (Language::Chinese, AdvisoryFix::MatchSourceResolution) => "匹配源分辨率".to_string(),
+            (Language::Chinese, AdvisoryFix::VideoBitrate(bitrate)) => format!("使用源视频码率 ({})", bitrate),
+            (Language::Chinese, AdvisoryFix::AudioBitrate(bitrate)) => format!("使用源音频码率 ({})", bitrate),
+            (Language::Chinese, AdvisoryFix::CopyVideo) => "复制视频流".to_string(),
+            (Language::Chinese, AdvisoryFix::CopyAudio) => "复制音频流".to_string(),
+            (Language::Chinese, AdvisoryFix::RecommendedAudioBitrate(bitrate)) => format!("使用推荐音频码率 ({})", bitrate),
+            (Language::Chinese, AdvisoryFix::MatchSourceFrameRate) => "匹配源帧率".to_string(),
+            (_, AdvisoryFix::MatchSourceResolution) => "Match source resolution".to_string(),
+            (_, AdvisoryFix::VideoBitrate(bitrate)) => format!("Use source video bitrate ({})", bitrate),
+            (_, AdvisoryFix::AudioBitrate(bitrate)) => format!("Use source audio bitrate ({})", bitrate),
+            (_, AdvisoryFix::CopyVideo) => "Copy video stream".to_string(),
+            (_, AdvisoryFix::CopyAudio) => "Copy audio stream".to_string(),
+            (_, AdvisoryFix::RecommendedAudioBitrate(bitrate)) => format!("Use recommended audio bitrate ({})", bitrate),
+            (_, AdvisoryFix::MatchSourceFrameRate) => "Match source frame rate".to_string(),
+        }
+    }
+
+    pub fn hide_advisory_rule(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不再提示此类建议",
+            Language::English => "Don't show this kind again",
+        }
+    }
+
+    pub fn advisory_rule_name(&self, rule: crate::encode_advisor::AdvisoryRule) -> &'static str {
+        use crate::encode_advisor::AdvisoryRule;
+        match (self.language.clone(), rule) {
+            (Language::Chinese, AdvisoryRule::Upscale) => "放大分辨率",
+            (Language::Chinese, AdvisoryRule::BitrateAboveSource) => "码率高于源文件",
+            (Language::Chinese, AdvisoryRule::PreferStreamCopy) => "建议直接复制流",
+            (Language::Chinese, AdvisoryRule::AudioBitrateForLayout) => "音频码率与声道/内容不匹配",
+            (Language::Chinese, AdvisoryRule::FrameRateChange) => "帧率与源不同",
+            (Language::English, AdvisoryRule::Upscale) => "Upscaling",
+            (Language::English, AdvisoryRule::BitrateAboveSource) => "Bitrate above source",
+            (Language::English, AdvisoryRule::PreferStreamCopy) => "Stream copy would be better",
+            (Language::English, AdvisoryRule::AudioBitrateForLayout) => "Audio bitrate unsuited to channels or content",
+            (Language::English, AdvisoryRule::FrameRateChange) => "Frame rate differs from source",
+        }
+    }
+
+    pub fn new_workflow_tab(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "新建工作流标签页",
+            Language::English => "New workflow tab",
+        }
+    }
+
+    pub fn close_workflow_tab(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "关闭此工作流",
+            Language::English => "Close this workflow",
+        }
+    }
+
+    pub fn unsaved_workflow_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "未保存的更改",
+            Language::English => "Unsaved changes",
+        }
+    }
+
+    pub fn unsaved_workflow_message(&self, name: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("工作流“{}”有未保存的更改，关闭前是否保存？", name),
+            Language::English => format!("Workflow \"{}\" has unsaved changes. Save before closing?", name),
+        }
+    }
+
+    pub fn save_workflow_and_close(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保存并关闭",
+            Language::English => "Save and close",
+        }
+    }
+
+    pub fn discard_workflow_changes(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不保存",
+            Language::English => "Discard",
+        }
+    }
+
+    pub fn size_target_planner(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎯 目标文件大小",
+            Language::English => "🎯 Target file size",
+        }
+    }
+
+    pub fn size_target_split(&self, total_kbps: u32, video_kbps: u32, audio_kbps: Option<u32>) -> String {
+        match (&self.language, audio_kbps) {
+            (Language::Chinese, Some(audio)) => format!("总码率 {} kbps = 视频 {} kbps + 音频 {} kbps", total_kbps, video_kbps, audio),
+            (Language::Chinese, None) => format!("总码率 {} kbps，全部用于视频（无音频）", total_kbps),
+            (_, Some(audio)) => format!("Total {} kbps = video {} kbps + audio {} kbps", total_kbps, video_kbps, audio),
+            (_, None) => format!("Total {} kbps, all of it video (no audio)", total_kbps),
+        }
+    }
+
+    pub fn size_target_too_low(&self, video_kbps: u32, suggested: (u32, u32)) -> String {
+        match self.language {
+            Language::Chinese => format!("{} kbps 对当前分辨率来说太低，画面会严重失真。建议降低到 {}x{}。", video_kbps, suggested.0, suggested.1),
+            Language::English => format!("{} kbps is far too low for this resolution and will look badly broken. Consider reducing it to {}x{}.", video_kbps, suggested.0, suggested.1),
+        }
+    }
+
+    pub fn size_target_reduce_resolution(&self, suggested: (u32, u32)) -> String {
+        match self.language {
+            Language::Chinese => format!("降低到 {}x{}", suggested.0, suggested.1),
+            Language::English => format!("Reduce to {}x{}", suggested.0, suggested.1),
+        }
+    }
+
+    pub fn size_target_confirm(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "先确认计算出的码率，而不是自动应用",
+            Language::English => "Review the computed bitrate instead of applying it automatically",
+        }
+    }
+
+    pub fn size_target_apply(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "使用此码率（两遍编码）",
+            Language::English => "Use this bitrate (two-pass)",
+        }
+    }
+
+    pub fn size_target_not_applied(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "尚未应用，当前仍按 CRF 编码",
+            Language::English => "Not applied yet, encoding still uses CRF",
+        }
+    }
+
+    pub fn size_target_automatic(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "开始处理时会按这些码率自动进行两遍编码",
+            Language::English => "Processing runs a two-pass encode at these bitrates automatically",
+        }
+    }
+
+    pub fn size_target_enable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按目标文件大小压缩",
+            Language::English => "Compress to a target file size",
+        }
+    }
+
+    pub fn two_pass_bitrate_enable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按目标码率两遍编码",
+            Language::English => "Two-pass encode at a target bitrate",
+        }
+    }
+
+    pub fn two_pass_bitrate_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "第一遍只分析视频，第二遍按分析结果编码，码率分配比 CRF 更均匀，但耗时约为两倍",
+            Language::English => "The first pass only analyses the video and the second encodes with what it learned; the bitrate is hit more evenly than with CRF, at about twice the time",
+        }
+    }
+
+    pub fn two_pass_bitrate_label(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频码率:",
+            Language::English => "Video bitrate:",
+        }
+    }
+
+    pub fn size_outcome(&self, outcome: &crate::size_target::SizeOutcome) -> String {
+        match self.language {
+            Language::Chinese => format!("输出 {:.1} MB，目标 {:.0} MB（偏差 {:+.1}%）", outcome.actual_mb, outcome.target_mb, outcome.deviation_percent()),
+            Language::English => format!("Output {:.1} MB for a {:.0} MB target ({:+.1}%)", outcome.actual_mb, outcome.target_mb, outcome.deviation_percent()),
+        }
+    }
+
+    /// Node name in the UI language; workflow files keep the NodeType identifiers
+    pub fn node_type_name(&self, node_type: &crate::automation_flow::NodeType) -> String {
+        use crate::automation_flow::NodeType;
+        if let NodeType::Unknown(type_name) = node_type {
+            return match self.language {
+                Language::Chinese => format!("❓ 未知节点（原类型 {}）", type_name),
+                Language::English => format!("❓ Unknown node (original type: {})", type_name),
+            };
+        }
+        let english = node_type.display_name();
+        if self.language != Language::Chinese {
+            return english.to_string();
+        }
+        let name = match node_type {
+            NodeType::InputFile => "输入文件",
+            NodeType::OutputFile => "输出文件",
+            NodeType::ExtractAudio => "提取音频",
+            NodeType::AudioResample => "音频重采样",
+            NodeType::AudioConvert => "音频转换",
+            NodeType::AudioCompress => "音频压缩",
+            NodeType::AudioVolume => "音量调整",
+            NodeType::AudioTrim => "音频裁剪",
+            NodeType::AudioMerge => "音频合并",
+            NodeType::AudioNormalize => "音频标准化",
+            NodeType::AudioDeNoise => "音频降噪",
+            NodeType::AudioEqualizer => "音频均衡器",
+            NodeType::AudioFade => "音频淡入淡出",
+            NodeType::AudioEcho => "音频回声",
+            NodeType::AudioSpeed => "音频变速",
+            NodeType::ExtractVideo => "提取视频",
+            NodeType::VideoRecode => "视频重编码",
+            NodeType::VideoConvert => "视频转换",
+            NodeType::VideoCompress => "视频压缩",
+            NodeType::VideoResize => "视频缩放",
+            NodeType::VideoCrop => "视频裁剪",
+            NodeType::VideoRotate => "视频旋转",
+            NodeType::VideoFilter => "视频滤镜",
+            NodeType::FrameExtract => "帧提取",
+            NodeType::VideoFPS => "视频帧率",
+            NodeType::VideoStabilize => "视频防抖",
+            NodeType::VideoDeinterlace => "去隔行",
+            NodeType::VideoColorCorrect => "色彩校正",
+            NodeType::VideoBrightness => "亮度",
+            NodeType::VideoSaturation => "饱和度",
+            NodeType::VideoGamma => "伽马",
+            NodeType::Combine => "合并音视频",
+            NodeType::SplitAudioVideo => "分离音视频",
+            NodeType::VideoOverlay => "视频叠加",
+            NodeType::VideoPiP => "画中画",
+            NodeType::VideoSideBySide => "并排视频",
+            NodeType::VideoToGif => "视频转GIF",
+            NodeType::GifResize => "GIF缩放",
+            NodeType::VideoToImages => "视频转图片",
+            NodeType::ImagesToVideo => "图片转视频",
+            NodeType::BatchConvert => "批量转换",
+            NodeType::AddSubtitle => "添加字幕",
+            NodeType::AddWatermark => "添加水印",
+            NodeType::AddText => "添加文字",
+            NodeType::AddLogo => "添加标志",
+            NodeType::AddTimecode => "添加时间码",
+            NodeType::StreamPrep => "直播准备",
+            NodeType::VideoEncrypt => "视频加密",
+            NodeType::VideoDecrypt => "视频解密",
+            NodeType::MultiPassEncode => "多遍编码",
+            NodeType::BatchProcess => "批量处理",
+            NodeType::QualityAnalysis => "质量分析",
+            NodeType::FormatValidation => "格式验证",
+            NodeType::AudioVideoSync => "音视频同步",
+            NodeType::AudioDelay => "音频延迟",
+            NodeType::VideoDelay => "视频延迟",
+            NodeType::ExtractMetadata => "提取元数据",
+            NodeType::AddMetadata => "添加元数据",
+            NodeType::RemoveMetadata => "删除元数据",
+            NodeType::CreateArchive => "创建归档",
+            NodeType::ExtractArchive => "解压归档",
+            NodeType::MultiResOutput => "多分辨率输出",
+            NodeType::Group => "节点组",
+            NodeType::Unknown(_) => "未知节点",
+        };
+        // Keep the icon and the unimplemented marker of the English name
+        let icon = english.split_whitespace().next().unwrap_or_default();
+        let marker = if english.ends_with("(FAKE)") { " (FAKE)" } else { "" };
+        format!("{} {}{}", icon, name, marker)
+    }
+
+    /// Port label for a port's internal name; unknown names are shown as they are
+    pub fn port_name(&self, name: &str) -> String {
+        let translated = match (&self.language, name) {
+            (Language::Chinese, "video") => "视频",
+            (Language::Chinese, "audio") => "音频",
+            (Language::Chinese, "input") => "输入",
+            (Language::Chinese, "inputs") => "输入组",
+            (Language::Chinese, "output") => "输出",
+            (Language::Chinese, "outputs") => "输出组",
+            (Language::Chinese, "archive") => "归档",
+            (Language::Chinese, "gif") => "GIF",
+            (Language::Chinese, "images") => "图片",
+            (Language::Chinese, "left_video") => "左侧视频",
+            (Language::Chinese, "right_video") => "右侧视频",
+            (Language::Chinese, "main_video") => "主视频",
+            (Language::Chinese, "overlay_video") => "叠加视频",
+            (Language::Chinese, "pip_video") => "画中画视频",
+            (Language::Chinese, "logo") => "标志",
+            (Language::Chinese, "watermark") => "水印",
+            (Language::Chinese, "subtitle") => "字幕",
+            (Language::Chinese, "metadata") => "元数据",
+            (Language::Chinese, "report") => "报告",
+            (Language::Chinese, "result") => "结果",
+            (Language::Chinese, "stream") => "流",
+            _ => return name.to_string(),
+        };
+        translated.to_string()
+    }
+
+    pub fn workflow_editor_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔧 自动化工作流编辑器",
+            Language::English => "🔧 Automation Workflow Editor",
+        }
+    }
+
+    pub fn workflow_editor_new(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🆕 新建工作流",
+            Language::English => "🆕 New Workflow",
+        }
+    }
+
+    pub fn workflow_editor_save(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💾 保存",
+            Language::English => "💾 Save",
+        }
+    }
+
+    pub fn workflow_editor_load(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📁 打开",
+            Language::English => "📁 Load",
+        }
+    }
+
+    pub fn workflow_editor_undo(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "撤销",
+            Language::English => "Undo",
+        }
+    }
+
+    pub fn workflow_editor_redo(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "重做",
+            Language::English => "Redo",
+        }
+    }
+
+    pub fn workflow_editor_delete(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🚮 删除",
+            Language::English => "🚮 Delete",
+        }
+    }
+
+    pub fn workflow_editor_fit_view(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔍 适应视图",
+            Language::English => "🔍 Fit to view",
+        }
+    }
+
+    pub fn workflow_editor_fit_view_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "平移并缩放画布以显示所有节点。中键拖动或按住空格拖动可平移，Ctrl+滚轮缩放",
+            Language::English => "Pan and zoom the canvas to show every node. Middle-drag or Space+drag pans, Ctrl+scroll zooms",
+        }
+    }
+
+    pub fn workflow_editor_execute(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🚀 执行",
+            Language::English => "🚀 Execute",
+        }
+    }
+
+    pub fn workflow_editor_incremental(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "增量执行",
+            Language::English => "Incremental",
+        }
+    }
+
+    pub fn workflow_editor_incremental_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "跳过输入和参数未改变的节点，复用上次运行的中间文件",
+            Language::English => "Skip nodes whose inputs and parameters are unchanged, reusing intermediate files from the last run",
+        }
+    }
+
+    pub fn workflow_editor_force_rerun(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔁 强制完整重新运行",
+            Language::English => "🔁 Force full re-run",
+        }
+    }
+
+    pub fn workflow_editor_plan(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🧾 预览命令",
+            Language::English => "🧾 Preview commands",
+        }
+    }
+
+    pub fn workflow_editor_stop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏹ 停止",
+            Language::English => "⏹ Stop",
+        }
+    }
+
+    pub fn workflow_editor_stop_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "终止正在运行的 ffmpeg，不再执行后续节点",
+            Language::English => "Kill the running ffmpeg and skip the remaining nodes",
+        }
+    }
+
+    pub fn workflow_editor_keep_on_stop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "停止时保留中间文件",
+            Language::English => "Keep files on stop",
+        }
+    }
+
+    pub fn workflow_editor_keep_on_stop_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "停止运行后不删除临时文件夹中已生成的中间文件，便于调试",
+            Language::English => "Leave the intermediate files a stopped run produced in its temp folder, for debugging",
+        }
+    }
+
+    pub fn workflow_editor_run_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "上次运行失败",
+            Language::English => "Failed in the last run",
+        }
+    }
+
+    pub fn workflow_editor_plan_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不实际运行，列出工作流将执行的全部 ffmpeg 命令，相邻的滤镜节点会合并为一条命令",
+            Language::English => "List every ffmpeg command the workflow will execute without running it, with neighbouring filter nodes fused into one command",
+        }
+    }
+
+    pub fn workflow_editor_close(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "❌ 关闭",
+            Language::English => "❌ Close",
+        }
+    }
+
+    pub fn workflow_editor_close_editor(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "❌ 关闭编辑器",
+            Language::English => "❌ Close Editor",
+        }
+    }
+
+    pub fn workflow_editor_toggle_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💡 按 Ctrl+W 显示/隐藏此窗口",
+            Language::English => "💡 Press Ctrl+W to toggle this window",
+        }
+    }
+
+    pub fn workflow_editor_add_nodes(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "添加节点:",
+            Language::English => "Add Nodes:",
+        }
+    }
+
+    pub fn workflow_editor_menu_io(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📁 输入/输出",
+            Language::English => "📁 Input/Output",
+        }
+    }
+
+    pub fn workflow_editor_menu_audio(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎵 音频处理",
+            Language::English => "🎵 Audio Processing",
+        }
+    }
+
+    pub fn workflow_editor_menu_video(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📺 视频处理",
+            Language::English => "📺 Video Processing",
+        }
+    }
+
+    pub fn workflow_editor_menu_av(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎭 音视频操作",
+            Language::English => "🎭 Audio/Video Operations",
+        }
+    }
+
+    pub fn workflow_editor_menu_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎨 文字与图形",
+            Language::English => "🎨 Text & Graphics",
+        }
+    }
+
+    pub fn workflow_editor_menu_format(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔄 格式转换",
+            Language::English => "🔄 Format Conversion",
+        }
+    }
+
+    pub fn workflow_editor_menu_batch(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📦 批处理与高级",
+            Language::English => "📦 Batch & Advanced",
+        }
+    }
+
+    pub fn workflow_editor_basic_operations(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "基本操作:",
+            Language::English => "Basic Operations:",
+        }
+    }
+
+    pub fn workflow_editor_audio_effects(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频效果:",
+            Language::English => "Audio Effects:",
+        }
+    }
+
+    pub fn workflow_editor_video_transformations(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频变换:",
+            Language::English => "Video Transformations:",
+        }
+    }
+
+    pub fn workflow_editor_video_effects(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "视频效果:",
+            Language::English => "Video Effects:",
+        }
+    }
+
+    pub fn workflow_editor_tips(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💡 提示:",
+            Language::English => "💡 Tips:",
+        }
+    }
+
+    pub fn workflow_editor_tip_connecting(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "点击输入端口（左侧）完成连接 | ESC 取消",
+            Language::English => "Click input port (left side) to complete connection | ESC to cancel",
+        }
+    }
+
+    pub fn workflow_editor_tip_idle(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "点击输出端口（右侧）开始连接 | 拖动连接线的端点可改接 | 把节点拖到连接线上可插入 | 右键连接线打开菜单 | Del=删除 | Ctrl+Z=撤销",
+            Language::English => "Click output ports (right) to start connections | Drag a connection's end to move it | Drop a node on a connection to insert it | Right-click a connection for its menu | Del=Delete | Ctrl+Z=Undo",
+        }
+    }
+
+    pub fn workflow_editor_shortcuts(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "快捷键:",
+            Language::English => "Shortcuts:",
+        }
+    }
+
+    pub fn workflow_editor_shortcut_list(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "中键/空格+拖动=平移 | Ctrl+滚轮=缩放 | Ctrl+点击/拖框=多选 | Ctrl+A=全选 | Ctrl+C/V=复制/粘贴 | Del=删除 | Ctrl+Z=撤销 | Ctrl+Y=重做 | ESC=取消",
+            Language::English => "Middle/Space+drag=Pan | Ctrl+scroll=Zoom | Ctrl+click/drag a box=Multi-select | Ctrl+A=Select all | Ctrl+C/V=Copy/Paste | Del=Delete | Ctrl+Z=Undo | Ctrl+Y=Redo | ESC=Cancel",
+        }
+    }
+
+    pub fn workflow_editor_instructions(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "说明:",
+            Language::English => "Instructions:",
+        }
+    }
+
+    pub fn workflow_editor_instruction_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "点击输出端口（右侧）开始连接，点击输入端口（左侧）完成连接。",
+            Language::English => "Click output ports (right side) to start connections, click input ports (left side) to complete them.",
+        }
+    }
+
+    pub fn workflow_editor_node_properties(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "节点属性",
+            Language::English => "Node Properties",
+        }
+    }
+
+    pub fn workflow_editor_select_node(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择一个节点以编辑其属性",
+            Language::English => "Select a node to edit its properties",
+        }
+    }
+
+    pub fn workflow_editor_browse_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📁 浏览文件...",
+            Language::English => "📁 Browse File...",
+        }
+    }
+
+    pub fn workflow_editor_save_as(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💾 另存为...",
+            Language::English => "💾 Save As...",
+        }
+    }
+
+    pub fn workflow_editor_delete_node(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🚮 删除节点",
+            Language::English => "🚮 Delete Node",
+        }
+    }
+
+    pub fn workflow_editor_parameters(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "参数:",
+            Language::English => "Parameters:",
+        }
+    }
+
+    pub fn workflow_editor_drop_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📥 拖放文件到此处",
+            Language::English => "📥 Drop file here",
+        }
+    }
+
+    pub fn workflow_editor_path_set(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💾 已设置路径",
+            Language::English => "💾 Path set",
+        }
+    }
+
+    pub fn workflow_editor_connection_pick(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "此处有多条连接，请选择一条:",
+            Language::English => "Several connections are here, pick one:",
+        }
+    }
+
+    pub fn workflow_editor_delete_connection(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🚮 删除连接",
+            Language::English => "🚮 Delete connection",
+        }
+    }
+
+    pub fn workflow_editor_insert_node(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 在此插入节点…",
+            Language::English => "➕ Insert node here…",
+        }
+    }
+
+    pub fn workflow_editor_no_insertable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "没有可插入此连接的节点",
+            Language::English => "No node fits this connection",
+        }
+    }
+
+    pub fn workflow_editor_show_endpoints(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎯 显示端点",
+            Language::English => "🎯 Show endpoints",
+        }
+    }
+
+    pub fn workflow_node_type(&self, name: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("类型: {}", name),
+            Language::English => format!("Type: {}", name),
+        }
+    }
+
+    pub fn workflow_node_context(&self, name: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("节点: {}", name),
+            Language::English => format!("Node: {}", name),
+        }
+    }
+
+    pub fn workflow_counts(&self, nodes: usize, connections: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("节点: {} | 连接: {}", nodes, connections),
+            Language::English => format!("Nodes: {} | Connections: {}", nodes, connections),
+        }
+    }
+
+    pub fn node_group_name(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "名称:",
+            Language::English => "Name:",
+        }
+    }
+
+    pub fn node_group_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "右键节点组可解散或保存为模板",
+            Language::English => "Right-click the group to ungroup it or save it as a template",
+        }
+    }
+
+    pub fn node_group_group(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📦 组合",
+            Language::English => "📦 Group",
+        }
+    }
+
+    pub fn node_group_group_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按住 Ctrl 点击选择多个节点，再组合为一个节点；未连接的端口成为组的端口",
+            Language::English => "Ctrl+click nodes to select several, then collapse them into one node; their unconnected ports become the group's ports",
+        }
+    }
+
+    pub fn node_group_ungroup(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📤 解散组合",
+            Language::English => "📤 Ungroup",
+        }
+    }
+
+    pub fn node_group_save_template(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 保存为模板",
+            Language::English => "📚 Save as template",
+        }
+    }
+
+    pub fn node_group_templates(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 模板",
+            Language::English => "📚 Templates",
+        }
+    }
+
+    pub fn node_group_no_templates(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "还没有模板。组合节点后右键保存为模板。",
+            Language::English => "No templates yet. Group nodes, then right-click the group to save it as a template.",
+        }
+    }
+
+    pub fn node_group_delete_template(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除模板",
+            Language::English => "Delete template",
+        }
+    }
+
+    pub fn node_group_open_folder(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📂 打开模板文件夹",
+            Language::English => "📂 Open templates folder",
+        }
+    }
+
+    pub fn node_group_default_name(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "节点组",
+            Language::English => "Group",
+        }
+    }
+
+    pub fn node_group_template_saved(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "模板已保存:",
+            Language::English => "Template saved:",
+        }
+    }
+
+    pub fn node_group_counts(&self, nodes: usize, inputs: usize, outputs: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 个节点，{} 个输入，{} 个输出", nodes, inputs, outputs),
+            Language::English => format!("{} nodes, {} inputs, {} outputs", nodes, inputs, outputs),
+        }
+    }
+
+    pub fn first_run_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🧭 初始设置向导",
+            Language::English => "🧭 Setup Wizard",
+        }
+    }
+
+    pub fn first_run_appearance(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "语言和主题",
+            Language::English => "Language and theme",
+        }
+    }
+
+    pub fn first_run_hardware(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "硬件加速",
+            Language::English => "Hardware acceleration",
+        }
+    }
+
+    pub fn first_run_detecting(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在检测硬件...",
+            Language::English => "Detecting hardware...",
+        }
+    }
+
+    pub fn first_run_no_hardware(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 未检测到可用的硬件编码器，将使用软件编码 (速度较慢，但兼容性最好)",
+            Language::English => "⚠ No usable hardware encoder found, software encoding will be used (slower, but most compatible)",
+        }
+    }
+
+    pub fn first_run_hardware_explained(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "硬件编码器速度快很多，但同样码率下画质通常略低于软件编码器。",
+            Language::English => "Hardware encoders are much faster, but usually look slightly worse than software encoders at the same bitrate.",
+        }
+    }
+
+    pub fn first_run_encoder_policy(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "默认编码器策略:",
+            Language::English => "Default encoder policy:",
+        }
+    }
+
+    pub fn first_run_output(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出位置",
+            Language::English => "Output location",
+        }
+    }
+
+    pub fn first_run_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "默认输出文件夹:",
+            Language::English => "Default output folder:",
+        }
+    }
+
+    pub fn first_run_next_to_input(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "与输入文件相同",
+            Language::English => "Next to the input file",
+        }
+    }
+
+    pub fn first_run_filename_template(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件名模板:",
+            Language::English => "Filename template:",
+        }
+    }
+
+    pub fn first_run_template_placeholders(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "{name} = 输入文件名, {timestamp} = 唯一时间戳",
+            Language::English => "{name} = input file name, {timestamp} = unique timestamp",
+        }
+    }
+
+    pub fn first_run_self_test(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自检",
+            Language::English => "Self-test",
+        }
+    }
+
+    pub fn first_run_self_test_explained(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "用所选编码器对5秒测试画面进行一次编码，在临时文件夹中运行，完成后自动删除。",
+            Language::English => "Encodes a 5-second test pattern with the chosen encoder in a temporary folder that is removed afterwards.",
+        }
+    }
+
+    pub fn first_run_self_test_running(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自检进行中...",
+            Language::English => "Self-test running...",
+        }
+    }
+
+    pub fn first_run_choose_encoder(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择编码器",
+            Language::English => "Choose Encoder",
+        }
+    }
+
+    pub fn first_run_skip(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "跳过",
+            Language::English => "Skip",
+        }
+    }
+
+    pub fn first_run_back(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⬅ 上一步",
+            Language::English => "⬅ Back",
+        }
+    }
+
+    pub fn first_run_next(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "下一步 ➡",
+            Language::English => "Next ➡",
+        }
+    }
+
+    pub fn first_run_finish(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✅ 完成",
+            Language::English => "✅ Finish",
+        }
+    }
+
+    pub fn first_run_saved(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "初始设置已保存",
+            Language::English => "Setup saved",
+        }
+    }
+
+    pub fn first_run_rerun(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🧭 初始设置向导...",
+            Language::English => "🧭 Setup Wizard...",
+        }
+    }
+    
+    pub fn first_run_step(&self, step: usize, total: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("第 {} 步，共 {} 步", step, total),
+            Language::English => format!("Step {} of {}", step, total),
+        }
+    }
+    
+    pub fn encoder_policy_name(&self, policy: crate::app_state::EncoderPolicy) -> &'static str {
+        use crate::app_state::EncoderPolicy;
+        match (&self.language, policy) {
+            (Language::Chinese, EncoderPolicy::PreferHardware) => "优先硬件编码",
+            (Language::Chinese, EncoderPolicy::PreferQuality) => "优先画质 (软件编码)",
+            (Language::Chinese, EncoderPolicy::AlwaysAsk) => "每次询问",
+            (Language::English, EncoderPolicy::PreferHardware) => "Prefer hardware",
+            (Language::English, EncoderPolicy::PreferQuality) => "Prefer quality (software)",
+            (Language::English, EncoderPolicy::AlwaysAsk) => "Always ask",
+        }
+    }
+    
+    pub fn encoder_policy_description(&self, policy: crate::app_state::EncoderPolicy) -> &'static str {
+        use crate::app_state::EncoderPolicy;
+        match (&self.language, policy) {
+            (Language::Chinese, EncoderPolicy::PreferHardware) => "编码器为\"自动\"时使用检测到的硬件编码器",
+            (Language::Chinese, EncoderPolicy::PreferQuality) => "编码器为\"自动\"时始终使用软件编码器",
+            (Language::Chinese, EncoderPolicy::AlwaysAsk) => "编码器为\"自动\"且有硬件编码器可用时，每次开始处理前询问",
+            (Language::English, EncoderPolicy::PreferHardware) => "Use a detected hardware encoder when the codec is \"auto\"",
+            (Language::English, EncoderPolicy::PreferQuality) => "Always use a software encoder when the codec is \"auto\"",
+            (Language::English, EncoderPolicy::AlwaysAsk) => "Ask before each start when the codec is \"auto\" and a hardware encoder is available",
+        }
+    }
+    
+    pub fn run_self_test(&self, codec: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("▶ 运行自检 ({})", codec),
+            Language::English => format!("▶ Run self-test ({})", codec),
+        }
+    }
+    
+    pub fn self_test_passed(&self, codec: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("✅ 自检通过，{} 工作正常", codec),
+            Language::English => format!("✅ Self-test passed, {} works", codec),
+        }
+    }
+    
+    pub fn self_test_hint(&self, hint: crate::first_run::SelfTestHint) -> &'static str {
+        use crate::first_run::SelfTestHint;
+        match (self.language.clone(), hint) {
+            (Language::Chinese, SelfTestHint::InstallFfmpeg) => "未找到FFmpeg: 请将ffmpeg放在程序旁边或添加到PATH",
+            (Language::Chinese, SelfTestHint::EncoderMissing) => "当前FFmpeg不包含该编码器: 请选择\"优先画质\"或使用完整版FFmpeg",
+            (Language::Chinese, SelfTestHint::Driver) => "硬件编码器无法启动: 请更新显卡驱动，或选择\"优先画质\"",
+            (Language::Chinese, SelfTestHint::OutputDir) => "无法写入文件: 请选择其他输出文件夹",
+            (Language::Chinese, SelfTestHint::Generic) => "请查看日志了解详情，或选择\"优先画质\"后重试",
+            (Language::English, SelfTestHint::InstallFfmpeg) => "FFmpeg was not found: place ffmpeg next to the program or add it to PATH",
+            (Language::English, SelfTestHint::EncoderMissing) => "This FFmpeg build lacks the encoder: choose \"Prefer quality\" or use a full FFmpeg build",
+            (Language::English, SelfTestHint::Driver) => "The hardware encoder could not start: update the GPU driver or choose \"Prefer quality\"",
+            (Language::English, SelfTestHint::OutputDir) => "Files could not be written: choose another output folder",
+            (Language::English, SelfTestHint::Generic) => "Check the log for details, or choose \"Prefer quality\" and try again",
+        }
+    }
+    
+    pub fn alpha_detected(&self, pix_fmt: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("🎞 源文件包含透明通道 ({})", pix_fmt),
+            Language::English => format!("🎞 Source has an alpha channel ({})", pix_fmt),
+        }
+    }
+    
+    pub fn alpha_preserved(&self, codec: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 会保留透明通道", codec),
+            Language::English => format!("{} keeps the alpha channel", codec),
+        }
+    }
+    
+    pub fn alpha_dropped(&self, codec: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 不支持透明通道，透明部分将丢失！", codec),
+            Language::English => format!("{} cannot store alpha, transparency will be lost!", codec),
+        }
+    }
+    
+    pub fn alpha_keep_with(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保留透明通道:",
+            Language::English => "Keep alpha with:",
+        }
+    }
+    
+    pub fn alpha_flatten(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "合成到纯色背景上 (去除透明通道)",
+            Language::English => "Flatten onto a solid background (drop alpha)",
+        }
+    }
+    
+    pub fn alpha_background_color(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "背景颜色:",
+            Language::English => "Background color:",
+        }
+    }
+    
+    pub fn alpha_codecs_only(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "源文件包含透明通道，仅显示可保留透明度的编码器",
+            Language::English => "The source has alpha, only encoders that keep it are listed",
+        }
+    }
+    
+    pub fn keep_scratch_files(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保留任务临时文件（调试用）",
+            Language::English => "Keep task scratch files (for debugging)",
+        }
+    }
+
+    pub fn keep_scratch_files_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "两遍编码日志、分段列表等临时文件在任务结束后不删除",
+            Language::English => "Two-pass logs, segment lists and other temporary files stay after a task ends",
+        }
+    }
+
+    pub fn keep_existing_outputs(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不覆盖已有的输出文件",
+            Language::English => "Keep existing output files",
+        }
+    }
+
+    pub fn keep_existing_outputs_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "新任务的输出文件已存在时改用带编号的文件名；队列中的任务之间始终不会共用输出文件",
+            Language::English => "A new task whose output file exists writes to a numbered name instead; queued tasks never share an output either way",
+        }
+    }
+
+    pub fn embedded_workflow_editor(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "在主窗口内打开工作流编辑器",
+            Language::English => "Open workflow editor inside the main window",
+        }
+    }
+    
+    pub fn embedded_editor_notice_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流编辑器已在主窗口内打开",
+            Language::English => "Workflow editor opened inside the main window",
+        }
+    }
+    
+    pub fn embedded_editor_notice_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "当前图形后端无法创建独立窗口（常见于部分 Wayland 或远程桌面环境），因此工作流编辑器改为在主窗口内显示，功能完全相同。本次运行期间将一直使用此方式；如需始终如此，可在“设置”菜单中开启“在主窗口内打开工作流编辑器”。",
+            Language::English => "The graphics backend could not create a separate window (common on some Wayland and remote-desktop setups), so the workflow editor is shown inside the main window instead, with the same features. It stays embedded for the rest of this session; to always use it, enable \"Open workflow editor inside the main window\" in the Settings menu.",
+        }
+    }
+    
+    pub fn extension_mismatch(&self, extension: &str, container: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("⚠ 输出文件扩展名 .{} 与容器格式 {} 不一致", extension, container),
+            Language::English => format!("⚠ Output extension .{} does not match the {} container", extension, container),
+        }
+    }
+
+    pub fn extension_fix(&self, fix: crate::output_extension::ExtensionFix, extension: &str, container: &str) -> String {
+        use crate::output_extension::ExtensionFix;
+        match (&self.language, fix) {
+            (Language::Chinese, ExtensionFix::ChangeContainer) => format!("将容器改为 .{}", extension),
+            (Language::Chinese, ExtensionFix::RenameOutput) => format!("将输出重命名为 .{}", container),
+            (Language::Chinese, ExtensionFix::KeepBoth) => format!("保留两者（高级，添加 -f {}）", container),
+            (Language::English, ExtensionFix::ChangeContainer) => format!("Change container to match .{}", extension),
+            (Language::English, ExtensionFix::RenameOutput) => format!("Rename output to .{}", container),
+            (Language::English, ExtensionFix::KeepBoth) => format!("Keep both (advanced, adds -f {})", container),
+        }
+    }
+
+    pub fn extension_kept(&self, extension: &str, container: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("ℹ 以 {} 格式写入 .{} 文件（-f）", container, extension),
+            Language::English => format!("ℹ Writing the .{} file as {} (-f)", extension, container),
+        }
+    }
+
+    pub fn extension_kept_undo(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不再保留扩展名",
+            Language::English => "Stop keeping the extension",
+        }
+    }
+
+    pub fn resolve_extension_mismatch(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "请先选择如何处理输出扩展名与容器格式不一致",
+            Language::English => "Choose how to resolve the output extension and container mismatch first",
+        }
+    }
+
+    pub fn power_rule_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔋 节能编码",
+            Language::English => "🔋 Power-aware encoding",
+        }
+    }
+
+    pub fn power_rule_enabled(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "使用电池时限制编码",
+            Language::English => "Limit encoding while on battery",
+        }
+    }
+
+    pub fn power_rule_lower_priority(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "降低 FFmpeg 进程优先级",
+            Language::English => "Lower ffmpeg process priority",
+        }
+    }
+
+    pub fn power_rule_cap_threads(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "线程数限制为核心数的一半",
+            Language::English => "Cap threads at half the cores",
+        }
+    }
+
+    pub fn power_rule_prefer_hardware(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "优先使用硬件编码器",
+            Language::English => "Prefer hardware encoders",
+        }
+    }
+
+    pub fn power_rule_prefer_hardware_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编码器为\"自动\"时，对使用电池期间添加的任务生效",
+            Language::English => "Applies to tasks added on battery when the codec is \"auto\"",
+        }
+    }
+
+    pub fn power_rule_pause_queue(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "暂停队列直到接通电源",
+            Language::English => "Pause the queue until AC power returns",
+        }
+    }
+
+    pub fn power_rule_status_limited(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔋 节能编码",
+            Language::English => "🔋 Power-aware",
+        }
+    }
+
+    pub fn power_rule_status_paused(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔋 ⏸ 队列已暂停",
+            Language::English => "🔋 ⏸ Queue paused",
+        }
+    }
+
+    pub fn power_rule_queue_paused(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在使用电池，队列已暂停，接通电源后继续",
+            Language::English => "On battery: queue paused until AC power returns",
+        }
+    }
+
+    pub fn power_rule_queue_resumed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已接通电源，队列继续",
+            Language::English => "AC power restored: queue resumed",
+        }
+    }
+
+    pub fn power_rule_limits_applied(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在使用电池，已启用节能编码",
+            Language::English => "On battery: power-aware encoding limits applied",
+        }
+    }
+
+    pub fn power_rule_limits_lifted(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已接通电源，节能编码限制已解除",
+            Language::English => "AC power restored: encoding limits lifted",
+        }
+    }
+
+    pub fn power_source_name(&self, source: crate::power::PowerSource) -> &'static str {
+        use crate::power::PowerSource;
+        match (&self.language, source) {
+            (Language::Chinese, PowerSource::Ac) => "电源: 外接电源",
+            (Language::Chinese, PowerSource::Battery) => "电源: 电池",
+            (Language::Chinese, PowerSource::Unknown) => "电源: 未知（按外接电源处理）",
+            (Language::English, PowerSource::Ac) => "Power: AC",
+            (Language::English, PowerSource::Battery) => "Power: battery",
+            (Language::English, PowerSource::Unknown) => "Power: unknown (treated as AC)",
+        }
+    }
+
+    pub fn compatibility_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📊 容器/编码器兼容性表",
+            Language::English => "📊 Container/codec compatibility",
+        }
+    }
+
+    pub fn compatibility_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "容器/编码器兼容性",
+            Language::English => "Container/codec compatibility",
+        }
+    }
+
+    pub fn compatibility_video(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎬 视频编码器",
+            Language::English => "🎬 Video codecs",
+        }
+    }
+
+    pub fn compatibility_audio(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎵 音频编码器",
+            Language::English => "🎵 Audio codecs",
+        }
+    }
+
+    pub fn compatibility_filter_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按编码器或容器筛选",
+            Language::English => "Filter by codec or container",
+        }
+    }
+
+    pub fn compatibility_supported(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "支持",
+            Language::English => "Supported",
+        }
+    }
+
+    pub fn compatibility_caveat(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "支持，但有注意事项",
+            Language::English => "Supported with caveats",
+        }
+    }
+
+    pub fn compatibility_unsupported(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不支持",
+            Language::English => "Not supported",
+        }
+    }
+
+    pub fn schedule_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🕘 定时限速",
+            Language::English => "🕘 Scheduled throttling",
+        }
+    }
+
+    pub fn schedule_enabled(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按时间段限制队列",
+            Language::English => "Limit the queue by time of day",
+        }
+    }
+
+    pub fn schedule_add(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 添加时间段",
+            Language::English => "➕ Add time window",
+        }
+    }
+
+    pub fn schedule_remove(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除此时间段",
+            Language::English => "Remove this time window",
+        }
+    }
+
+    pub fn schedule_window_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "本地时间；结束早于开始表示跨越午夜，相同表示全天",
+            Language::English => "Local time; an end before the start spans midnight, equal times mean all day",
+        }
+    }
+
+    pub fn schedule_max_threads(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最大编码线程:",
+            Language::English => "Max encoder threads:",
+        }
+    }
+
+    pub fn schedule_max_threads_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "0 表示不限制",
+            Language::English => "0 means no cap",
+        }
+    }
+
+    pub fn schedule_lower_priority(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "降低 FFmpeg 进程优先级",
+            Language::English => "Lower ffmpeg process priority",
+        }
+    }
+
+    pub fn schedule_allow_hardware(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "允许硬件编码器",
+            Language::English => "Allow hardware encoders",
+        }
+    }
+
+    pub fn schedule_pause_queue(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "暂停队列",
+            Language::English => "Pause the queue",
+        }
+    }
+
+    pub fn schedule_pause_queue_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在运行的任务会完成，之后不再开始新任务",
+            Language::English => "The running task finishes; no new task starts until the window ends",
+        }
+    }
+
+    pub fn schedule_ignore(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "忽略定时 2 小时",
+            Language::English => "Ignore schedule for 2 hours",
+        }
+    }
+
+    pub fn schedule_resume(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "恢复定时",
+            Language::English => "Resume schedule",
+        }
+    }
+
+    pub fn schedule_status_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "当前生效的定时限速时间段，对新开始的任务生效",
+            Language::English => "Active throttling profile; applies to tasks started from now on",
+        }
+    }
+
+    /// Day 0 is Monday
+    pub fn weekday_short(&self, day: usize) -> &'static str {
+        const CHINESE: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+        const ENGLISH: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+        match self.language {
+            Language::Chinese => CHINESE[day % 7],
+            Language::English => ENGLISH[day % 7],
+        }
+    }
+
+    pub fn schedule_entered(&self, name: &str, paused: bool) -> String {
+        match (&self.language, paused) {
+            (Language::Chinese, true) => format!("定时段 \"{}\" 开始：当前任务完成后暂停队列", name),
+            (Language::Chinese, false) => format!("定时段 \"{}\" 开始：已应用限速", name),
+            (Language::English, true) => format!("Schedule \"{}\" started: queue pauses after the running task", name),
+            (Language::English, false) => format!("Schedule \"{}\" started: limits applied", name),
+        }
+    }
+
+    pub fn schedule_left(&self, name: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("定时段 \"{}\" 结束，限速已解除", name),
+            Language::English => format!("Schedule \"{}\" ended: limits lifted", name),
+        }
+    }
+
+    pub fn schedule_ignored_until(&self, time: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("🕘 定时已忽略至 {}", time),
+            Language::English => format!("🕘 Schedule ignored until {}", time),
+        }
+    }
+
+    pub fn frame_extract_every_n_seconds(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "每N秒",
+            Language::English => "Every N seconds",
+        }
+    }
+
+    pub fn frame_extract_seconds_interval(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "间隔秒数:",
+            Language::English => "Interval:",
+        }
+    }
+
+    pub fn frame_extract_range(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "提取范围:",
+            Language::English => "Range:",
+        }
+    }
+
+    pub fn frame_extract_range_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "留空则从开头提取到结尾",
+            Language::English => "Leave empty to extract from the start to the end",
+        }
+    }
+
+    pub fn frame_extract_png_compression(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "PNG 压缩级别:",
+            Language::English => "PNG compression:",
+        }
+    }
+
+    pub fn frame_extract_png_compression_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无损压缩，级别越高文件越小但越慢 (0=不压缩, 9=最小)",
+            Language::English => "Lossless; higher levels give smaller files but are slower (0=none, 9=smallest)",
+        }
+    }
+
+    pub fn frame_extract_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出文件夹:",
+            Language::English => "Output folder:",
+        }
+    }
+
+    pub fn frame_extract_browse(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📁 浏览",
+            Language::English => "📁 Browse",
+        }
+    }
+
+    pub fn frame_extract_name_pattern(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件名模式:",
+            Language::English => "File name pattern:",
+        }
+    }
+
+    pub fn frame_extract_first_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "第一个文件:",
+            Language::English => "First file:",
+        }
+    }
+
+    pub fn frame_extract_no_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 请选择输出文件夹",
+            Language::English => "⚠ Select an output folder",
+        }
+    }
+
+    pub fn frame_extract_output_dir_error(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法使用输出文件夹",
+            Language::English => "Cannot use the output folder",
+        }
+    }
+
+    pub fn parameter_limit_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 参数超出范围",
+            Language::English => "⚠ Parameters out of range",
+        }
+    }
+
+    pub fn parameter_limit_intro(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "以下参数超出编码器支持的范围，FFmpeg 会在处理中途报错:",
+            Language::English => "These values are outside what the encoder accepts; ffmpeg would fail mid-task:",
+        }
+    }
+
+    pub fn parameter_limit_clamp(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "修正为",
+            Language::English => "Clamp to",
+        }
+    }
+
+    pub fn parameter_limit_clamp_all_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "全部修正并开始",
+            Language::English => "Clamp all and start",
+        }
+    }
+
+    pub fn parameter_limit_blocked(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "参数超出范围，请先修正",
+            Language::English => "Some parameters are out of range; fix them first",
+        }
+    }
+
+    pub fn parameter_field_name(&self, field: crate::parameter_limits::Field) -> &'static str {
+        use crate::parameter_limits::Field;
+        match (&self.language, field) {
+            (Language::Chinese, Field::Crf) => "CRF",
+            (Language::Chinese, Field::Quality) => "质量",
+            (Language::Chinese, Field::VideoBitrate) => "视频比特率",
+            (Language::Chinese, Field::Framerate) => "帧率",
+            (Language::Chinese, Field::Size) => "输出尺寸",
+            (Language::Chinese, Field::Resolution) => "分辨率",
+            (Language::Chinese, Field::AudioBitrate) => "音频比特率",
+            (Language::English, Field::Crf) => "CRF",
+            (Language::English, Field::Quality) => "Quality",
+            (Language::English, Field::VideoBitrate) => "Video bitrate",
+            (Language::English, Field::Framerate) => "Frame rate",
+            (Language::English, Field::Size) => "Output size",
+            (Language::English, Field::Resolution) => "Resolution",
+            (Language::English, Field::AudioBitrate) => "Audio bitrate",
+        }
+    }
+
+    pub fn parameter_out_of_range(&self, codec: &str, valid_range: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 的有效范围: {}", codec, valid_range),
+            Language::English => format!("Valid range for {}: {}", codec, valid_range),
+        }
+    }
+
+    pub fn undo_action(&self, action: crate::session_undo::UndoAction) -> &'static str {
+        use crate::session_undo::UndoAction;
+        match (&self.language, action) {
+            (Language::Chinese, UndoAction::ResetProject) => "撤销: 新建项目",
+            (Language::Chinese, UndoAction::ResetSettings) => "撤销: 重置设置",
+            (Language::Chinese, UndoAction::ClearInputFiles) => "撤销: 清空输入文件",
+            (Language::Chinese, UndoAction::ClearOutputFile) => "撤销: 清空输出文件",
+            (Language::Chinese, UndoAction::ApplyPreset) => "撤销: 应用预设",
+            (Language::Chinese, UndoAction::ApplyRecommendation) => "撤销: 应用推荐设置",
+            (Language::English, UndoAction::ResetProject) => "Undo: New project",
+            (Language::English, UndoAction::ResetSettings) => "Undo: Reset settings",
+            (Language::English, UndoAction::ClearInputFiles) => "Undo: Clear input files",
+            (Language::English, UndoAction::ClearOutputFile) => "Undo: Clear output file",
+            (Language::English, UndoAction::ApplyPreset) => "Undo: Apply preset",
+            (Language::English, UndoAction::ApplyRecommendation) => "Undo: Apply recommendation",
+        }
+    }
+
+    pub fn undo_restored(&self, action: crate::session_undo::UndoAction) -> String {
+        let action = self.undo_action(action);
+        let action = action.split_once(": ").map(|(_, name)| name).unwrap_or(action);
+        match self.language {
+            Language::Chinese => format!("已撤销: {}", action),
+            Language::English => format!("Undone: {}", action),
+        }
+    }
+
+    pub fn chapters_preserve(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保留章节",
+            Language::English => "Preserve chapters",
+        }
+    }
+
+    pub fn chapters_limited_container(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 此容器的章节支持有限，许多播放器会忽略",
+            Language::English => "⚠ This container has limited chapter support; many players ignore them",
+        }
+    }
+
+    pub fn chapters_unsupported_container(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 此容器不支持章节，章节将丢失",
+            Language::English => "⚠ This container cannot store chapters; they will be lost",
+        }
+    }
+
+    pub fn chapters_edit(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编辑章节",
+            Language::English => "Edit chapters",
+        }
+    }
+
+    pub fn chapters_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "标题",
+            Language::English => "Title",
+        }
+    }
+
+    pub fn chapters_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "开始",
+            Language::English => "Start",
+        }
+    }
+
+    pub fn chapters_end(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "结束",
+            Language::English => "End",
+        }
+    }
+
+    pub fn chapters_import(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📂 导入 FFMETADATA",
+            Language::English => "📂 Import FFMETADATA",
+        }
+    }
+
+    pub fn chapters_reset(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "↺ 恢复源章节",
+            Language::English => "↺ Restore source chapters",
+        }
+    }
+
+    pub fn chapter_count(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("源文件有 {} 个章节", count),
+            Language::English if count == 1 => "1 chapter in source".to_string(),
+            Language::English => format!("{} chapters in source", count),
+        }
+    }
+
+    pub fn hardware_detection_state(&self, state: crate::hardware_refresh::DetectionState) -> &'static str {
+        use crate::hardware_refresh::DetectionState;
+        match (&self.language, state) {
+            (Language::Chinese, DetectionState::Idle) => "硬件检测未开始",
+            (Language::Chinese, DetectionState::Detecting) => "正在检测硬件…",
+            (Language::Chinese, DetectionState::Ready) => "硬件检测完成",
+            (Language::Chinese, DetectionState::Refreshing) => "正在重新检测硬件…",
+            (Language::English, DetectionState::Idle) => "Hardware detection not started",
+            (Language::English, DetectionState::Detecting) => "Detecting hardware…",
+            (Language::English, DetectionState::Ready) => "Hardware detected",
+            (Language::English, DetectionState::Refreshing) => "Re-detecting hardware…",
+        }
+    }
+
+    pub fn hardware_encoder_count(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("检测到 {} 个硬件编码器", count),
+            Language::English if count == 1 => "1 hardware encoder detected".to_string(),
+            Language::English => format!("{} hardware encoders detected", count),
+        }
+    }
+
+    pub fn redetect_hardware(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔄 重新检测",
+            Language::English => "🔄 Re-detect",
+        }
+    }
+
+    pub fn redetect_queued(&self, seconds: u64) -> String {
+        match self.language {
+            Language::Chinese => format!("已排队，{} 秒后重新检测", seconds),
+            Language::English => format!("Queued, re-detecting in {} s", seconds),
+        }
+    }
+
+    pub fn diagnostics_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🩺 创建诊断包…",
+            Language::English => "🩺 Create diagnostic bundle…",
+        }
+    }
+
+    pub fn diagnostics_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "创建诊断包",
+            Language::English => "Create diagnostic bundle",
+        }
+    }
+
+    pub fn diagnostics_contents(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "诊断包是一个 JSON 文件，包含：最近的日志（含崩溃信息）、应用和 FFmpeg 版本、操作系统，以及硬件检测结果。不会自动上传，可将其附加到问题报告中。",
+            Language::English => "The bundle is a single JSON file with the recent logs (including crash reports), the app and FFmpeg versions, the operating system and the hardware detection results. Nothing is uploaded; attach the file to your issue.",
+        }
+    }
+
+    pub fn diagnostics_include_tasks(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "包含本次会话的任务历史",
+            Language::English => "Include this session's task history",
+        }
+    }
+
+    pub fn diagnostics_tasks_note(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "任务历史包含执行的 FFmpeg 命令，其中有输入和输出文件的完整路径。",
+            Language::English => "The task history holds the FFmpeg commands that ran, with the full paths of input and output files.",
+        }
+    }
+
+    pub fn diagnostics_save(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "💾 保存…",
+            Language::English => "💾 Save…",
+        }
+    }
+
+    pub fn diagnostics_saved(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "诊断包已保存",
+            Language::English => "Diagnostic bundle saved",
+        }
+    }
+
+    pub fn review_clip_operation(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "审阅片段",
+            Language::English => "Review clip",
+        }
+    }
+
+    pub fn review_clip_button(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎬 创建审阅片段…",
+            Language::English => "🎬 Create review clip…",
+        }
+    }
+
+    pub fn review_clip_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🎬 创建审阅片段",
+            Language::English => "🎬 Create Review Clip",
+        }
+    }
+
+    pub fn review_clip_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "开始:",
+            Language::English => "Start:",
+        }
+    }
+
+    pub fn review_clip_end(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "结束:",
+            Language::English => "End:",
+        }
+    }
+
+    pub fn review_clip_length(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "时长:",
+            Language::English => "Length:",
+        }
+    }
+
+    pub fn review_clip_preset(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "尺寸:",
+            Language::English => "Size:",
+        }
+    }
+
+    pub fn review_clip_timecode(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "叠加时间码",
+            Language::English => "Burn in timecode",
+        }
+    }
+
+    pub fn review_clip_watermark(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文字水印:",
+            Language::English => "Text watermark:",
+        }
+    }
+
+    pub fn review_clip_queue(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "➕ 加入队列（低优先级）",
+            Language::English => "➕ Queue (low priority)",
+        }
+    }
+
+    pub fn review_clip_queued(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "审阅片段已加入队列",
+            Language::English => "Review clip queued",
+        }
+    }
+
+    pub fn audio_preview_play(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "试听",
+            Language::English => "Play preview",
+        }
+    }
+
+    pub fn audio_preview_pause(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "暂停",
+            Language::English => "Pause",
+        }
+    }
+
+    pub fn audio_preview_stop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "停止",
+            Language::English => "Stop",
+        }
+    }
+
+    pub fn audio_preview_checking(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在检查音频输出设备…",
+            Language::English => "Checking the audio output…",
+        }
+    }
+
+    pub fn audio_preview_no_device(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法试听",
+            Language::English => "Preview unavailable",
+        }
+    }
+
+    pub fn audio_preview_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法播放此文件",
+            Language::English => "This file cannot be played",
+        }
+    }
+
+    pub fn checksum_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔐 输出校验和",
+            Language::English => "🔐 Output checksums",
+        }
+    }
+
+    pub fn checksum_compute(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "完成后计算 SHA-256",
+            Language::English => "Compute SHA-256 after completion",
+        }
+    }
+
+    pub fn checksum_compute_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "任务完成后在后台计算输出文件的 SHA-256，不会延迟任务完成。此设置是新任务的默认值，可在任务右键菜单中单独修改。",
+            Language::English => "Hashes the output on a background thread once the task finishes; the task is marked completed right away. This is the default for new tasks and can be changed per task from its context menu.",
+        }
+    }
+
+    pub fn checksum_sidecar(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "同时写入 .sha256 文件",
+            Language::English => "Also write a .sha256 file",
+        }
+    }
+
+    pub fn checksum_hashing(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "计算校验和",
+            Language::English => "hashing",
+        }
+    }
+
+    pub fn checksum_done_short(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔐 SHA-256",
+            Language::English => "🔐 SHA-256",
+        }
+    }
+
+    pub fn checksum_failed_short(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔐 校验和失败",
+            Language::English => "🔐 checksum failed",
+        }
+    }
+
+    pub fn checksum_cancelled_short(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "校验和已取消",
+            Language::English => "checksum cancelled",
+        }
+    }
+
+    pub fn checksum_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "校验和计算失败",
+            Language::English => "Checksum failed",
+        }
+    }
+
+    pub fn checksum_sidecar_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法写入 .sha256 文件",
+            Language::English => "Could not write the .sha256 file",
+        }
+    }
+
+    pub fn checksum_cancel_hashing(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏹ 停止计算校验和",
+            Language::English => "⏹ Stop checksum",
+        }
+    }
+
+    pub fn checksum_copy(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "复制校验和",
+            Language::English => "Copy checksum",
+        }
+    }
+
+    pub fn checksum_verify_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔐 校验文件…",
+            Language::English => "🔐 Verify checksum…",
+        }
+    }
+
+    pub fn checksum_verify_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "校验文件",
+            Language::English => "Verify checksum",
+        }
+    }
+
+    pub fn checksum_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件:",
+            Language::English => "File:",
+        }
+    }
+
+    pub fn checksum_from_sidecar(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => ".sha256 文件",
+            Language::English => ".sha256 file",
+        }
+    }
+
+    pub fn checksum_from_pasted(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "粘贴的校验和",
+            Language::English => "Pasted hash",
+        }
+    }
+
+    pub fn checksum_paste_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "64 位十六进制 SHA-256",
+            Language::English => "64-character hex SHA-256",
+        }
+    }
+
+    pub fn checksum_verify(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "▶ 校验",
+            Language::English => "▶ Verify",
+        }
+    }
+
+    pub fn checksum_match(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✅ 校验和一致",
+            Language::English => "✅ Checksum matches",
+        }
+    }
+
+    pub fn checksum_mismatch(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "❌ 校验和不一致",
+            Language::English => "❌ Checksum does not match",
+        }
+    }
+
+    pub fn checksum_expected(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预期",
+            Language::English => "Expected",
+        }
+    }
+
+    pub fn checksum_actual(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "实际",
+            Language::English => "Actual",
+        }
+    }
+
+    pub fn checksum_sidecar_unreadable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法读取 .sha256 文件",
+            Language::English => "Could not read the .sha256 file",
+        }
+    }
+
+    pub fn checksum_sidecar_invalid(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => ".sha256 文件中没有有效的 SHA-256",
+            Language::English => "The .sha256 file holds no valid SHA-256",
+        }
+    }
+
+    pub fn checksum_hash_invalid(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "粘贴的内容不是有效的 SHA-256",
+            Language::English => "The pasted text is not a valid SHA-256",
+        }
+    }
+
+    pub fn watermark_custom_position(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自定义坐标",
+            Language::English => "Custom coordinates",
+        }
+    }
+
+    pub fn watermark_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "水印文字:",
+            Language::English => "Watermark text:",
+        }
+    }
+
+    pub fn watermark_text_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "可选；CSV 批处理中可用 {列名}",
+            Language::English => "Optional; {column} works in CSV batches",
+        }
+    }
+
+    pub fn watermark_font_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "字号:",
+            Language::English => "Font size:",
+        }
+    }
+
+    pub fn download_cache_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "下载缓存…",
+            Language::English => "Download cache…",
+        }
+    }
+
+    pub fn download_cache_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "下载缓存",
+            Language::English => "Download Cache",
+        }
+    }
+
+    pub fn download_cache_total(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已用",
+            Language::English => "Used",
+        }
+    }
+
+    pub fn download_cache_cap(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "上限:",
+            Language::English => "Cap:",
+        }
+    }
+
+    pub fn download_cache_clear(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "清空",
+            Language::English => "Clear",
+        }
+    }
+
+    pub fn download_cache_clear_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除所有未固定的缓存文件",
+            Language::English => "Delete every cached file that is not pinned",
+        }
+    }
+
+    pub fn download_cache_empty(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "还没有下载过网络输入",
+            Language::English => "No remote inputs downloaded yet",
+        }
+    }
+
+    pub fn download_cache_url(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "地址",
+            Language::English => "URL",
+        }
+    }
+
+    pub fn download_cache_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "大小",
+            Language::English => "Size",
+        }
+    }
+
+    pub fn download_cache_age(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "下载于",
+            Language::English => "Fetched",
+        }
+    }
+
+    pub fn download_cache_pinned(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "固定",
+            Language::English => "Pinned",
+        }
+    }
+
+    pub fn download_cache_partial(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "未完成",
+            Language::English => "partial",
+        }
+    }
+
+    pub fn download_cache_downloading(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⬇ 下载中",
+            Language::English => "⬇ Downloading",
+        }
+    }
+
+    pub fn download_cache_minutes(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "分钟前",
+            Language::English => "min ago",
+        }
+    }
+
+    pub fn download_cache_hours(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "小时前",
+            Language::English => "h ago",
+        }
+    }
+
+    pub fn download_cache_days(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "天前",
+            Language::English => "days ago",
+        }
+    }
+
+    pub fn download_cache_add_url(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "添加网址",
+            Language::English => "Add URL",
+        }
+    }
+
+    pub fn download_cache_url_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "https://…",
+            Language::English => "https://…",
+        }
+    }
+
+    pub fn social_presets_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📱 社交媒体导出",
+            Language::English => "📱 Social Media Export",
+        }
+    }
+
+    pub fn social_presets_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "按平台要求设置分辨率、码率上限和快速启动，并根据源画面比例自动裁剪或加黑边",
+            Language::English => "Sets the platform's resolution, bitrate cap and faststart, and crops or pads depending on the source's aspect ratio",
+        }
+    }
+
+    pub fn social_presets_source(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "源视频:",
+            Language::English => "Source:",
+        }
+    }
+
+    pub fn social_presets_portrait(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "(竖屏)",
+            Language::English => "(Portrait)",
+        }
+    }
+
+    pub fn social_presets_landscape(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "(横屏)",
+            Language::English => "(Landscape)",
+        }
+    }
+
+    pub fn social_presets_square(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "(方形)",
+            Language::English => "(Square)",
+        }
+    }
+
+    pub fn social_presets_auto(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自动:",
+            Language::English => "Automatic:",
+        }
+    }
+
+    pub fn social_presets_crop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "裁剪填满",
+            Language::English => "crop to fill",
+        }
+    }
+
+    pub fn social_presets_pad(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "加黑边",
+            Language::English => "pad with bars",
+        }
+    }
+
+    pub fn social_presets_crop_keeps(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "裁剪后保留",
+            Language::English => "a crop keeps",
+        }
+    }
+
+    pub fn social_presets_use_instead(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "改为",
+            Language::English => "Use",
+        }
+    }
+
+    pub fn social_presets_no_source(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "尚未检测到源视频分辨率，将加黑边以免裁掉画面",
+            Language::English => "No source resolution detected yet; padding so nothing is cut off",
+        }
+    }
+
+    pub fn social_presets_too_long(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "源视频超过该平台的时长上限",
+            Language::English => "The source is longer than the platform's limit of",
+        }
+    }
+
+    pub fn social_presets_rendering(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "正在生成预览…",
+            Language::English => "Rendering preview…",
+        }
+    }
+
+    pub fn social_presets_apply(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "应用",
+            Language::English => "Apply",
+        }
+    }
+
+    pub fn output_recovery_waiting(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏏ 输出位置不可用，等待其重新连接…",
+            Language::English => "⏏ Output destination unavailable, waiting for it to return…",
+        }
+    }
+
+    pub fn output_recovery_reachable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏏ 输出位置已恢复:",
+            Language::English => "⏏ Output destination is back:",
+        }
+    }
+
+    pub fn output_recovery_restart(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "从头开始",
+            Language::English => "Restart",
+        }
+    }
+
+    pub fn output_recovery_resume(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "续传，保留至",
+            Language::English => "Resume, keeping up to",
+        }
+    }
+
+    pub fn output_recovery_resume_short(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "续传",
+            Language::English => "Resume",
+        }
+    }
+
+    pub fn output_recovery_redirect(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "改存到…",
+            Language::English => "Save elsewhere…",
+        }
+    }
+
+    pub fn archive_package_into(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📦 完成后打包到",
+            Language::English => "📦 Package into",
+        }
+    }
+
+    pub fn archive_no_package(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不打包",
+            Language::English => "Don't package",
+        }
+    }
+
+    pub fn archive_packaging(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "打包中",
+            Language::English => "Packaging",
+        }
+    }
+
+    pub fn workflow_library_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 工作流库",
+            Language::English => "📚 Workflow Library",
+        }
+    }
+
+    pub fn workflow_library_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 工作流库...",
+            Language::English => "📚 Workflow Library...",
+        }
+    }
+
+    pub fn workflow_library_library(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 库",
+            Language::English => "📚 Library",
+        }
+    }
+
+    pub fn workflow_library_save_to_library(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📚 保存到库",
+            Language::English => "📚 Save to Library",
+        }
+    }
+
+    pub fn workflow_library_save_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保存到工作流库失败",
+            Language::English => "Saving to the workflow library failed",
+        }
+    }
+
+    pub fn workflow_library_description_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流描述",
+            Language::English => "Workflow description",
+        }
+    }
+
+    pub fn workflow_library_refresh(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔄 刷新",
+            Language::English => "🔄 Refresh",
+        }
+    }
+
+    pub fn workflow_library_empty(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "库中还没有工作流。在编辑器中使用“保存到库”。",
+            Language::English => "No workflows in the library yet. Use \"Save to Library\" in the editor.",
+        }
+    }
+
+    pub fn workflow_library_nodes(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "个节点",
+            Language::English => "nodes",
+        }
+    }
+
+    pub fn workflow_library_no_description(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无描述",
+            Language::English => "No description",
+        }
+    }
+
+    pub fn workflow_library_unreadable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 无法读取的工作流文件",
+            Language::English => "⚠ Unreadable workflow file",
+        }
+    }
+
+    pub fn workflow_library_rename_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "双击重命名",
+            Language::English => "Double-click to rename",
+        }
+    }
+
+    pub fn workflow_library_open(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📂 打开",
+            Language::English => "📂 Open",
+        }
+    }
+
+    pub fn workflow_library_rename(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✏ 重命名",
+            Language::English => "✏ Rename",
+        }
+    }
+
+    pub fn workflow_library_duplicate(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制",
+            Language::English => "📋 Duplicate",
+        }
+    }
+
+    pub fn workflow_library_copy_suffix(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "副本",
+            Language::English => "copy",
+        }
+    }
+
+    pub fn workflow_library_reveal(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📁 在文件管理器中显示",
+            Language::English => "📁 Show in file manager",
+        }
+    }
+
+    pub fn workflow_library_delete(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🗑 删除",
+            Language::English => "🗑 Delete",
+        }
+    }
+
+    pub fn workflow_library_confirm_delete(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🗑 确认删除",
+            Language::English => "🗑 Confirm delete",
+        }
+    }
+
+    pub fn workflow_library_rename_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "重命名失败",
+            Language::English => "Rename failed",
+        }
+    }
+
+    pub fn workflow_library_duplicate_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "复制失败",
+            Language::English => "Duplicate failed",
+        }
+    }
+
+    pub fn workflow_library_delete_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除失败",
+            Language::English => "Delete failed",
+        }
+    }
+
+    pub fn file_times_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🕒 源文件时间",
+            Language::English => "🕒 Source timestamps",
+        }
+    }
+
+    pub fn file_times_copy_times(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "保留源文件的修改/创建时间",
+            Language::English => "Keep source modified/created times",
+        }
+    }
+
+    pub fn file_times_copy_times_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "完成后将源文件的修改时间复制到输出文件。创建时间仅在 Windows 和 macOS 上设置，其他系统尽力而为",
+            Language::English => "Copy the source file's modified time onto the output when the task completes. The created time is only set on Windows and macOS; elsewhere it is best-effort",
+        }
+    }
+
+    pub fn file_times_creation_time(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "写入录制日期 (creation_time)",
+            Language::English => "Write recording date (creation_time)",
+        }
+    }
+
+    pub fn file_times_creation_time_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "将源文件元数据中的录制日期（没有时使用修改时间）写入输出容器，需要额外的流复制",
+            Language::English => "Write the recording date from the source's metadata, or its modified time when absent, into the output container; costs an extra stream copy",
+        }
+    }
+
+    pub fn file_times_warning(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "时间戳未能完全应用:",
+            Language::English => "Timestamps were not fully applied:",
+        }
+    }
+
+    pub fn preset_comparison_button(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📊 比较",
+            Language::English => "📊 Compare",
+        }
+    }
+
+    pub fn preset_comparison_tooltip(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "比较各预设的预计文件大小和编码时间",
+            Language::English => "Compare estimated file size and encode time across presets",
+        }
+    }
+
+    pub fn preset_comparison_no_input(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "未选择输入文件：按每小时 1080p30 视频估算",
+            Language::English => "No input selected: estimates are per hour of 1080p30 video",
+        }
+    }
+
+    pub fn preset_comparison_preset(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预设",
+            Language::English => "Preset",
+        }
+    }
+
+    pub fn preset_comparison_codec(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编码器",
+            Language::English => "Codec",
+        }
+    }
+
+    pub fn preset_comparison_resolution(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "分辨率",
+            Language::English => "Resolution",
+        }
+    }
+
+    pub fn preset_comparison_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预计大小",
+            Language::English => "Est. size",
+        }
+    }
+
+    pub fn preset_comparison_size_per_hour(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "大小/小时",
+            Language::English => "Size / hour",
+        }
+    }
+
+    pub fn preset_comparison_time(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "预计编码时间",
+            Language::English => "Est. encode time",
+        }
+    }
+
+    pub fn preset_comparison_time_per_hour(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编码时间/小时",
+            Language::English => "Encode time / hour",
+        }
+    }
+
+    pub fn preset_comparison_apply_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "点击应用此预设",
+            Language::English => "click to apply this preset",
+        }
+    }
+
+    pub fn preset_comparison_formula(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "大小 = (视频 + 音频码率) × 时长 / 8 + 容器开销；CRF 码率 ≈ 宽 × 高 × 帧率 × 0.07 bpp × 编码器效率 × 2^((23 − CRF) / 6)；时间 = 帧数 / 按编码器、预设和分辨率估算的编码速度。仅为粗略估计",
+            Language::English => "Size = (video + audio bitrate) × duration / 8 + container overhead; CRF bitrate ≈ width × height × fps × 0.07 bpp × codec efficiency × 2^((23 − CRF) / 6); time = frames / encoder speed estimated from codec, preset and resolution. Rough estimates only",
+        }
+    }
+
+    pub fn output_guard_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🛡 输出限制",
+            Language::English => "🛡 Output guards",
+        }
+    }
+
+    pub fn output_guard_menu_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "防止直播、循环输入或监视文件夹产生无休止或占满磁盘的任务:",
+            Language::English => "Keep live, looped or watch-folder inputs from producing tasks that never end or fill the disk:",
+        }
+    }
+
+    pub fn output_guard_max_duration(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最长输出时长",
+            Language::English => "Maximum output duration",
+        }
+    }
+
+    pub fn output_guard_max_duration_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "以 -t 传给 FFmpeg；输出在此处被截断时任务标记为失败",
+            Language::English => "Passed to FFmpeg as -t; a task whose output is cut off there is marked failed",
+        }
+    }
+
+    pub fn output_guard_max_size(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "最大输出大小",
+            Language::English => "Maximum output size",
+        }
+    }
+
+    pub fn output_guard_max_size_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运行时每隔几秒检查输出文件大小，超出时停止 FFmpeg 并将任务标记为失败",
+            Language::English => "The output file is checked every few seconds while FFmpeg runs; past the limit FFmpeg is stopped and the task marked failed",
+        }
+    }
+
+    pub fn output_guard_advanced(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚙ 高级",
+            Language::English => "⚙ Advanced",
+        }
+    }
+
+    pub fn output_guard_override(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "为新任务覆盖输出限制",
+            Language::English => "Override output guards for new tasks",
+        }
+    }
+
+    pub fn output_guard_override_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "仅适用于从此按钮启动的任务，不会保存",
+            Language::English => "Only applies to tasks started from this button and is not saved",
+        }
+    }
+
+    pub fn output_guard_global(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "全局输出限制:",
+            Language::English => "Global output guards:",
+        }
+    }
+
+    pub fn output_guard_none(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "未设置输出限制",
+            Language::English => "No output guards set",
+        }
+    }
+
+    pub fn output_guard_active(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "此任务的输出限制",
+            Language::English => "Output guards of this task",
+        }
+    }
+
+    pub fn queue_restore_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "恢复任务队列",
+            Language::English => "Restore task queue",
+        }
+    }
+
+    pub fn queue_restore_restore(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "恢复",
+            Language::English => "Restore",
+        }
+    }
+
+    pub fn queue_restore_discard(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "丢弃",
+            Language::English => "Discard",
+        }
+    }
+
+    pub fn workflow_autosave_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流自动保存",
+            Language::English => "Workflow autosave",
+        }
+    }
+
+    pub fn workflow_autosave_enabled(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "自动保存未保存的工作流",
+            Language::English => "Autosave unsaved workflows",
+        }
+    }
+
+    pub fn workflow_autosave_enabled_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "定期保存未保存的工作流更改，以便在程序崩溃后恢复",
+            Language::English => "Periodically save unsaved workflow changes so they can be recovered after a crash",
+        }
+    }
+
+    pub fn workflow_autosave_interval(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "间隔:",
+            Language::English => "Interval:",
+        }
+    }
+
+    pub fn workflow_autosave_discard_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "丢弃自动保存",
+            Language::English => "Discard autosave",
+        }
+    }
+
+    pub fn workflow_autosave_discard_file_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除自动保存文件；未保存的更改仍留在编辑器中",
+            Language::English => "Delete the autosave file; unsaved changes stay in the editor",
+        }
+    }
+
+    pub fn workflow_autosave_discarded(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已丢弃工作流自动保存",
+            Language::English => "Workflow autosave discarded",
+        }
+    }
+
+    pub fn workflow_autosave_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "恢复工作流",
+            Language::English => "Recover workflow",
+        }
+    }
+
+    pub fn workflow_autosave_recover(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "恢复",
+            Language::English => "Recover",
+        }
+    }
+
+    pub fn workflow_autosave_discard(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "丢弃",
+            Language::English => "Discard",
+        }
+    }
+
+    pub fn recover_workflow_from(&self, timestamp: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("恢复 {} 的未保存工作流？", timestamp),
+            Language::English => format!("Recover unsaved workflow from {}?", timestamp),
+        }
+    }
+
+    pub fn restore_unfinished_tasks(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("恢复 {} 个未完成的任务？", count),
+            Language::English => format!("Restore {} unfinished task{}?", count, if count == 1 { "" } else { "s" }),
+        }
+    }
+
+    pub fn interrupted_tasks(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("其中 {} 个在运行时被中断，将标记为失败", count),
+            Language::English => format!("{} of them were interrupted while running and come back as failed", count),
+        }
+    }
+
+    pub fn tasks_missing_inputs(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("⚠ {} 个任务的输入文件已不存在", count),
+            Language::English => format!("⚠ {} task(s) have input files that no longer exist", count),
+        }
+    }
+
+    pub fn task_retry_retry(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔁 重试",
+            Language::English => "🔁 Retry",
+        }
+    }
+
+    pub fn task_retry_retry_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "以相同设置重新排队；若已有部分输出文件，则写入新文件名",
+            Language::English => "Queue the task again with the same settings; if a partial output exists it writes to a new name",
+        }
+    }
+
+    pub fn task_retry_retry_all(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔁 重试所有失败任务",
+            Language::English => "🔁 Retry All Failed",
+        }
+    }
+
+    pub fn task_cancel_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "取消任务？",
+            Language::English => "Cancel task?",
+        }
+    }
+
+    pub fn task_cancel_others_continue(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "只会停止此任务，其他任务继续运行。",
+            Language::English => "Only this task is stopped; other tasks keep running.",
+        }
+    }
+
+    pub fn task_cancel_delete_partial(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除已写入的部分输出",
+            Language::English => "Delete the partially written output",
+        }
+    }
+
+    pub fn task_cancel_delete_partial_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "默认保留部分输出文件，例如用于检查已编码的内容",
+            Language::English => "By default the partial output file is kept, e.g. to check what was encoded so far",
+        }
+    }
+
+    pub fn task_cancel_confirm(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "取消任务",
+            Language::English => "Cancel task",
+        }
+    }
+
+    pub fn task_cancel_keep_running(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "继续运行",
+            Language::English => "Keep running",
+        }
+    }
+
+    pub fn task_log_log(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📜 日志",
+            Language::English => "📜 Log",
+        }
+    }
+
+    pub fn task_log_copy(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 复制日志",
+            Language::English => "📋 Copy log",
+        }
+    }
+
+    pub fn task_log_dropped(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("已省略较早的 {} 行", count),
+            Language::English => format!("{} earlier lines dropped", count),
+        }
+    }
+
+    pub fn task_progress_unknown_duration(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "时长未知",
+            Language::English => "Duration unknown",
+        }
+    }
+
+    pub fn task_pause_pause(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏸ 暂停",
+            Language::English => "⏸ Pause",
+        }
+    }
+
+    pub fn task_pause_pause_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "挂起此任务的 FFmpeg 进程；进度和剩余时间保持不变，直到继续",
+            Language::English => "Suspend this task's FFmpeg process; progress and the remaining time hold until resumed",
+        }
+    }
+
+    pub fn task_pause_resume(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "▶ 继续",
+            Language::English => "▶ Resume",
+        }
+    }
+
+    pub fn task_pause_paused(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏸ 已暂停",
+            Language::English => "⏸ Paused",
+        }
+    }
+
+    pub fn safe_mode_banner(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🛟 安全模式",
+            Language::English => "🛟 Safe mode",
+        }
+    }
+
+    pub fn safe_mode_explanation(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "部分启动步骤已跳过，日志更详细。未检测硬件时只使用软件编码器；未加载设置时使用默认设置且不保存。可逐项重新启用以找出问题所在:",
+            Language::English => "Some startup steps were skipped and logging is verbose. Without hardware detection only software encoders are used; without saved preferences the defaults are used and nothing is saved. Re-enable one at a time to find the culprit:",
+        }
+    }
+
+    pub fn safe_mode_hardware_detection(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "启用硬件检测",
+            Language::English => "Enable hardware detection",
+        }
+    }
+
+    pub fn safe_mode_ffmpeg_extraction(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "初始化内置 FFmpeg",
+            Language::English => "Initialize bundled FFmpeg",
+        }
+    }
+
+    pub fn safe_mode_preferences(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "加载已保存的设置",
+            Language::English => "Load saved preferences",
+        }
+    }
+
+    pub fn workflow_diff_compare_saved(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔍 与已保存版本比较…",
+            Language::English => "🔍 Compare with saved…",
+        }
+    }
+
+    pub fn workflow_diff_compare_files(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "🔍 比较两个文件…",
+            Language::English => "🔍 Compare two files…",
+        }
+    }
+
+    pub fn workflow_diff_pick_old(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择旧版本工作流",
+            Language::English => "Choose the older workflow",
+        }
+    }
+
+    pub fn workflow_diff_pick_new(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择新版本工作流",
+            Language::English => "Choose the newer workflow",
+        }
+    }
+
+    pub fn workflow_diff_load_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无法读取要比较的工作流",
+            Language::English => "Could not read the workflow to compare",
+        }
+    }
+
+    pub fn workflow_diff_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流差异",
+            Language::English => "Workflow Differences",
+        }
+    }
+
+    pub fn workflow_diff_editor(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "编辑器中的当前版本",
+            Language::English => "current version in the editor",
+        }
+    }
+
+    pub fn workflow_diff_positions(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "包括仅位置变化",
+            Language::English => "Include position-only changes",
+        }
+    }
+
+    pub fn workflow_diff_highlight(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "在画布上标出",
+            Language::English => "Outline on canvas",
+        }
+    }
+
+    pub fn workflow_diff_identical(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "没有差异",
+            Language::English => "No differences",
+        }
+    }
+
+    pub fn workflow_diff_name(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "名称",
+            Language::English => "Name",
+        }
+    }
+
+    pub fn workflow_diff_description(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "描述已修改",
+            Language::English => "Description changed",
+        }
+    }
+
+    pub fn workflow_diff_removed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "删除的节点",
+            Language::English => "Removed nodes",
+        }
+    }
+
+    pub fn workflow_diff_added(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "新增的节点",
+            Language::English => "Added nodes",
+        }
+    }
+
+    pub fn workflow_diff_changed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "修改的节点",
+            Language::English => "Changed nodes",
+        }
+    }
+
+    pub fn workflow_diff_matched_by_position(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "ID 不同，按类型和位置对应",
+            Language::English => "Ids differ; matched by type and position",
+        }
+    }
+
+    pub fn workflow_diff_enabled(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "启用",
+            Language::English => "Enabled",
+        }
+    }
+
+    pub fn workflow_diff_moved(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "位置",
+            Language::English => "Position",
+        }
+    }
+
+    pub fn workflow_diff_absent(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "（无）",
+            Language::English => "(none)",
+        }
+    }
+
+    pub fn workflow_diff_connections(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "连接变化",
+            Language::English => "Connection changes",
+        }
+    }
+
+    pub fn font_font(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "字体:",
+            Language::English => "Font:",
+        }
+    }
+
+    pub fn font_font_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "字体名或字体文件，留空使用默认字体",
+            Language::English => "Family or font file; empty uses the default",
+        }
+    }
+
+    pub fn font_pick(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择字体文件…",
+            Language::English => "Choose font file…",
+        }
+    }
+
+    pub fn font_use_fallback(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "使用内置字体",
+            Language::English => "Use bundled font",
+        }
+    }
+
+    pub fn font_proceed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "仍然继续",
+            Language::English => "Proceed anyway",
+        }
+    }
+
+    pub fn font_missing(&self, value: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("本机找不到字体“{}”，ffmpeg 会改用其他字体", value),
+            _ => format!("Font \"{}\" is not installed here; ffmpeg would substitute another font", value),
+        }
+    }
+
+    pub fn folder_run_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📂 对文件夹运行…",
+            Language::English => "📂 Run on Folder…",
+        }
+    }
+
+    pub fn folder_run_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "对文件夹运行工作流",
+            Language::English => "Run Workflow on Folder",
+        }
+    }
+
+    pub fn folder_run_input_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入文件夹:",
+            Language::English => "Input folder:",
+        }
+    }
+
+    pub fn folder_run_pattern(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件筛选:",
+            Language::English => "File filter:",
+        }
+    }
+
+    pub fn folder_run_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出文件夹:",
+            Language::English => "Output folder:",
+        }
+    }
+
+    pub fn folder_run_browse(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "浏览…",
+            Language::English => "Browse…",
+        }
+    }
+
+    pub fn folder_run_include_subfolders(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "包含子文件夹",
+            Language::English => "Include subfolders",
+        }
+    }
+
+    pub fn folder_run_keep_subfolders(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "在输出文件夹中保留子文件夹结构",
+            Language::English => "Keep the subfolder structure in the output folder",
+        }
+    }
+
+    pub fn folder_run_no_input(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流没有输入文件节点",
+            Language::English => "The workflow has no Input File node",
+        }
+    }
+
+    pub fn folder_run_no_output(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流没有输出文件节点",
+            Language::English => "The workflow has no Output File node",
+        }
+    }
+
+    pub fn folder_run_several_inputs(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流有多个输入文件节点，无法确定替换哪一个",
+            Language::English => "The workflow has several Input File nodes; it is unclear which one each file replaces",
+        }
+    }
+
+    pub fn folder_run_no_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "请选择输出文件夹",
+            Language::English => "Choose an output folder",
+        }
+    }
+
+    pub fn folder_run_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "▶ 开始",
+            Language::English => "▶ Start",
+        }
+    }
+
+    pub fn folder_run_stop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏹ 停止",
+            Language::English => "⏹ Stop",
+        }
+    }
+
+    pub fn folder_run_pending(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "等待中",
+            Language::English => "Pending",
+        }
+    }
+
+    pub fn folder_run_running(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "运行中…",
+            Language::English => "Running…",
+        }
+    }
+
+    pub fn folder_run_succeeded(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✔ 完成",
+            Language::English => "✔ Done",
+        }
+    }
+
+    pub fn folder_run_stopped(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "已停止",
+            Language::English => "Stopped",
+        }
+    }
+
+    pub fn watch_folder_menu(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "👁 监视文件夹…",
+            Language::English => "👁 Watch Folder…",
+        }
+    }
+
+    pub fn watch_folder_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "监视文件夹",
+            Language::English => "Watch Folder",
+        }
+    }
+
+    pub fn watch_folder_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "监视文件夹中出现的新文件会在停止变化后由所选工作流处理，输出写入输出文件夹。已处理的文件会被记住，重启后不会再次处理。",
+            Language::English => "New files appearing in the watch folder are processed by the chosen workflow once they stop changing, with outputs going to the output folder. Processed files are remembered, so a restart does not process them again.",
+        }
+    }
+
+    pub fn watch_folder_watch_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "监视文件夹:",
+            Language::English => "Watch folder:",
+        }
+    }
+
+    pub fn watch_folder_output_dir(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输出文件夹:",
+            Language::English => "Output folder:",
+        }
+    }
+
+    pub fn watch_folder_debounce(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "稳定时间:",
+            Language::English => "Stable for:",
+        }
+    }
+
+    pub fn watch_folder_debounce_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件在这段时间内没有变化才会被处理，避免处理尚未写完的文件",
+            Language::English => "A file is processed once it has not changed for this long, so files still being written are not picked up",
+        }
+    }
+
+    pub fn watch_folder_workflow(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流文件:",
+            Language::English => "Workflow file:",
+        }
+    }
+
+    pub fn watch_folder_use_focused(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "使用当前工作流",
+            Language::English => "Use focused workflow",
+        }
+    }
+
+    pub fn watch_folder_start(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "▶ 开始监视",
+            Language::English => "▶ Start watching",
+        }
+    }
+
+    pub fn watch_folder_stop(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏹ 停止监视",
+            Language::English => "⏹ Stop watching",
+        }
+    }
+
+    pub fn watch_folder_restart(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⟳ 应用并重新开始",
+            Language::English => "⟳ Apply and restart",
+        }
+    }
+
+    pub fn watch_folder_restart_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "更改的设置在重新开始后生效",
+            Language::English => "Changed settings apply once the watcher restarts",
+        }
+    }
+
+    pub fn watch_folder_pause(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⏸",
+            Language::English => "⏸",
+        }
+    }
+
+    pub fn watch_folder_resume(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "▶",
+            Language::English => "▶",
+        }
+    }
+
+    pub fn watch_folder_failed(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "👁 ⚠ 监视未启动",
+            Language::English => "👁 ⚠ Watcher not running",
+        }
+    }
+
+    pub fn watch_folder_status(&self, folder: &str, processed: usize, last: Option<(String, &str)>) -> String {
+        match (&self.language, last) {
+            (Language::Chinese, Some((at, name))) => format!("正在监视 {}\n已处理 {} 个文件，最近一次：{}（{}）", folder, processed, name, at),
+            (Language::Chinese, None) => format!("正在监视 {}\n尚未处理任何文件", folder),
+            (Language::English, Some((at, name))) => format!("Watching {}\n{} file(s) processed, last: {} at {}", folder, processed, name, at),
+            (Language::English, None) => format!("Watching {}\nNo file processed yet", folder),
+        }
+    }
+
+    pub fn folder_run_workflow(&self, name: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("对每个匹配的文件运行工作流“{}”", name),
+            Language::English => format!("Runs the workflow \"{}\" once per matching file", name),
+        }
+    }
+
+    pub fn folder_run_parallel(&self, limit: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("同时运行最多 {} 个文件", limit),
+            Language::English => format!("Run up to {} files at once", limit),
+        }
+    }
 
-impl Translations {
-    pub fn new(language: Language) -> Self {
-        Self { language }
+    pub fn folder_run_matches(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 个匹配的文件", count),
+            Language::English => format!("{} matching file{}", count, if count == 1 { "" } else { "s" }),
+        }
     }
 
-    // Menu bar
-    pub fn menu_file(&self) -> &'static str {
+    pub fn folder_run_summary(&self, succeeded: usize, failed: usize, stopped: usize) -> String {
         match self.language {
-            Language::Chinese => "文件",
-            Language::English => "File",
+            Language::Chinese => format!("成功 {}，失败 {}，停止 {}", succeeded, failed, stopped),
+            Language::English => format!("{} succeeded, {} failed, {} stopped", succeeded, failed, stopped),
         }
     }
 
-    pub fn menu_settings(&self) -> &'static str {
+    pub fn workflow_script_menu(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "设置",
-            Language::English => "Settings",
+            Language::Chinese => "📜 导出为脚本…",
+            Language::English => "📜 Export as Script…",
         }
     }
 
-    pub fn menu_language(&self) -> &'static str {
+    pub fn workflow_script_title(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "语言",
-            Language::English => "Language",
+            Language::Chinese => "导出工作流脚本",
+            Language::English => "Export Workflow Script",
         }
     }
 
-    pub fn menu_help(&self) -> &'static str {
+    pub fn workflow_script_exported(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "帮助",
-            Language::English => "Help",
+            Language::Chinese => "工作流脚本已导出",
+            Language::English => "Workflow script exported",
         }
     }
 
-    pub fn new_project(&self) -> &'static str {
+    pub fn workflow_script_preview_failed(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "新建项目",
-            Language::English => "New Project",
+            Language::Chinese => "无法生成工作流的命令",
+            Language::English => "Could not build the workflow's commands",
         }
     }
 
-    pub fn save_project(&self) -> &'static str {
+    pub fn workflow_script_nothing_to_run(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "保存项目",
-            Language::English => "Save Project",
+            Language::Chinese => "工作流中没有要运行的命令",
+            Language::English => "The workflow has no commands to run",
         }
     }
 
-    pub fn load_project(&self) -> &'static str {
+    pub fn workflow_script_unscriptable(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "加载项目",
-            Language::English => "Load Project",
+            Language::Chinese => "该节点会遍历文件、根据 ffmpeg 的结果决定后续操作或在程序内完成工作，无法写成固定的命令序列",
+            Language::English => "it loops over files, acts on what ffmpeg reports or does its work inside the app, which a fixed list of commands cannot express",
         }
     }
 
-    pub fn exit(&self) -> &'static str {
+    pub fn workflow_script_preview_error(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "退出",
-            Language::English => "Exit",
+            Language::Chinese => "无法生成该节点的命令",
+            Language::English => "its commands could not be built",
         }
     }
 
-    pub fn show_advanced_settings(&self) -> &'static str {
+    pub fn workflow_script_app_work(&self) -> &'static str {
         match self.language {
-            Language::Chinese => "显示高级设置",
-            Language::English => "Show Advanced Settings",
+            Language::Chinese => "该节点的工作在程序内完成，不是 ffmpeg 命令",
+            Language::English => "its work is done inside the app rather than by ffmpeg",
+        }
+    }
+
+    pub fn workflow_script_runtime_file(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "该节点读取程序在运行时写入的文件",
+            Language::English => "it reads a file the app writes during the run",
+        }
+    }
+
+    pub fn workflow_script_shell_syntax(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "该节点使用管道或重定向，PowerShell 无法可靠执行；请导出为 sh / bash 脚本",
+            Language::English => "it pipes or redirects output, which PowerShell cannot do reliably; export an sh / bash script instead",
+        }
+    }
+
+    pub fn workflow_script_unknown_program(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "该节点运行的程序不是 ffmpeg 或 ffprobe",
+            Language::English => "it runs a program other than ffmpeg or ffprobe",
+        }
+    }
+
+    pub fn workflow_script_refusal(&self, node_name: &str, reason: &str, detail: Option<&str>) -> String {
+        let detail = detail.map(|detail| format!(" ({})", detail)).unwrap_or_default();
+        match self.language {
+            Language::Chinese => format!("无法导出脚本，原因在节点“{}”：{}{}", node_name, reason, detail),
+            Language::English => format!("Cannot export a script because of node '{}': {}{}", node_name, reason, detail),
+        }
+    }
+
+    pub fn data_batch_button(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📋 数据驱动批处理…",
+            Language::English => "📋 Data-driven batch…",
+        }
+    }
+
+    pub fn data_batch_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "数据驱动批处理",
+            Language::English => "Data-driven batch",
+        }
+    }
+
+    pub fn data_batch_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择一个 CSV 文件，每行一个输入文件。水印文字和水印图片路径中的 {列名} 会替换为该行的值；可选的 position、x、y 和 output 列会覆盖每行的位置和输出文件。",
+            Language::English => "Pick a CSV with one input file per row. {column} in the watermark text and image path is replaced by that row's value; optional position, x, y and output columns override the placement and output file per row.",
+        }
+    }
+
+    pub fn data_batch_choose_csv(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📂 选择 CSV…",
+            Language::English => "📂 Choose CSV…",
+        }
+    }
+
+    pub fn data_batch_input_column(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入文件列:",
+            Language::English => "Input file column:",
+        }
+    }
+
+    pub fn data_batch_variables(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "可用变量",
+            Language::English => "Variables",
+        }
+    }
+
+    pub fn data_batch_row(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "行",
+            Language::English => "Row",
+        }
+    }
+
+    pub fn data_batch_input(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入",
+            Language::English => "Input",
+        }
+    }
+
+    pub fn data_batch_text(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文字",
+            Language::English => "Text",
+        }
+    }
+
+    pub fn data_batch_watermark(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "水印图片",
+            Language::English => "Image",
+        }
+    }
+
+    pub fn data_batch_position(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "位置",
+            Language::English => "Position",
+        }
+    }
+
+    pub fn data_batch_status(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "状态",
+            Language::English => "Status",
+        }
+    }
+
+    pub fn data_batch_ok(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "✓ 就绪",
+            Language::English => "✓ Ready",
+        }
+    }
+
+    pub fn data_batch_unknown_variable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "未知变量",
+            Language::English => "Unknown variable",
+        }
+    }
+
+    pub fn data_batch_unclosed_variable(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "变量缺少 }",
+            Language::English => "Variable is missing its }",
+        }
+    }
+
+    pub fn data_batch_empty_field(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "字段为空",
+            Language::English => "Empty field",
+        }
+    }
+
+    pub fn data_batch_missing_input(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "输入文件不存在",
+            Language::English => "Input file not found",
+        }
+    }
+
+    pub fn data_batch_missing_watermark(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "水印图片不存在",
+            Language::English => "Watermark image not found",
+        }
+    }
+
+    pub fn data_batch_invalid_position(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "无效位置",
+            Language::English => "Invalid position",
+        }
+    }
+
+    pub fn data_batch_invalid_number(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "不是整数",
+            Language::English => "Not a whole number",
+        }
+    }
+
+    pub fn data_batch_more_rows(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("另有 {} 行未显示", count),
+            Language::English => format!("{} more rows not shown", count),
+        }
+    }
+
+    pub fn data_batch_skipped(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("{} 行有问题，将被跳过", count),
+            Language::English => format!("{} rows have problems and will be skipped", count),
+        }
+    }
+
+    pub fn data_batch_queue(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("▶ 添加 {} 个任务", count),
+            Language::English => format!("▶ Queue {} tasks", count),
+        }
+    }
+
+    pub fn data_batch_queued(&self, count: usize) -> String {
+        match self.language {
+            Language::Chinese => format!("已从 CSV 添加 {} 个水印任务", count),
+            Language::English => format!("Queued {} watermark tasks from the CSV", count),
+        }
+    }
+
+    pub fn workflow_load_report_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "⚠ 工作流加载报告",
+            Language::English => "⚠ Workflow Load Report",
+        }
+    }
+
+    pub fn workflow_load_report_intro(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "工作流已打开，但有部分内容无法完整读取：",
+            Language::English => "The workflow was opened, but parts of it could not be read completely:",
+        }
+    }
+
+    pub fn workflow_load_error_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "❌ 无法打开工作流",
+            Language::English => "❌ Cannot Open Workflow",
+        }
+    }
+
+    pub fn workflow_load_error_intro(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "文件已损坏或不是工作流文件：",
+            Language::English => "The file is damaged or not a workflow file:",
+        }
+    }
+
+    pub fn workflow_load_unknown_node_hint(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "此节点由更新的版本创建，本版本无法编辑或运行它。保存时会原样保留。",
+            Language::English => "This node was created by a newer version and cannot be edited or run here. It is kept unchanged when saving.",
+        }
+    }
+
+    pub fn workflow_load_close(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "关闭",
+            Language::English => "Close",
+        }
+    }
+
+    pub fn workflow_load_issue(&self, issue: &crate::workflow_file::LoadIssue) -> String {
+        use crate::workflow_file::LoadIssue;
+        match (&self.language, issue) {
+            (Language::Chinese, LoadIssue::NewerFormat(version)) => format!("文件由更新的版本保存（格式 {}），部分内容可能无法识别", version),
+            (Language::Chinese, LoadIssue::UnknownNodeType { node_id, type_name }) => format!("节点 {}：未知类型 {}，已保留为占位节点", node_id, type_name),
+            (Language::Chinese, LoadIssue::UnreadableNode { node_id, type_name, error }) => format!("节点 {}（{}）无法读取，已保留为占位节点：{}", node_id, type_name, error),
+            (Language::Chinese, LoadIssue::InvalidNode { node_id, error }) => format!("节点 {} 无法读取，已跳过：{}", node_id, error),
+            (Language::Chinese, LoadIssue::InvalidConnection { connection_id, error }) => format!("连接 {} 无法读取，已跳过：{}", connection_id, error),
+            (Language::Chinese, LoadIssue::DanglingConnection { connection_id, node_id }) => format!("连接 {} 指向不存在的节点 {}，已删除", connection_id, node_id),
+            (_, LoadIssue::NewerFormat(version)) => format!("Saved by a newer version (format {}); some content may not be recognized", version),
+            (_, LoadIssue::UnknownNodeType { node_id, type_name }) => format!("Node {}: unknown type {}, kept as a placeholder", node_id, type_name),
+            (_, LoadIssue::UnreadableNode { node_id, type_name, error }) => format!("Node {} ({}) could not be read, kept as a placeholder: {}", node_id, type_name, error),
+            (_, LoadIssue::InvalidNode { node_id, error }) => format!("Node {} could not be read and was skipped: {}", node_id, error),
+            (_, LoadIssue::InvalidConnection { connection_id, error }) => format!("Connection {} could not be read and was skipped: {}", connection_id, error),
+            (_, LoadIssue::DanglingConnection { connection_id, node_id }) => format!("Connection {} refers to missing node {} and was dropped", connection_id, node_id),
+        }
+    }
+
+    pub fn drop_chooser_title(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "要对这些文件做什么？",
+            Language::English => "What do you want to do?",
+        }
+    }
+
+    pub fn drop_chooser_keys(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "↑↓ 选择 · Enter 确认 · Esc 关闭",
+            Language::English => "↑↓ select · Enter confirm · Esc dismiss",
+        }
+    }
+
+    pub fn drop_chooser_files(&self, names: &[String]) -> String {
+        let shown = names.iter().take(3).cloned().collect::<Vec<_>>().join(", ");
+        let more = names.len().saturating_sub(3);
+        match (&self.language, more) {
+            (_, 0) => shown,
+            (Language::Chinese, more) => format!("{} 等另外 {} 个文件", shown, more),
+            (Language::English, more) => format!("{} and {} more", shown, more),
+        }
+    }
+
+    pub fn export_settings_bundle(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📤 导出设置包…",
+            Language::English => "📤 Export settings bundle…",
+        }
+    }
+
+    pub fn import_settings_bundle(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "📥 导入设置包…",
+            Language::English => "📥 Import settings bundle…",
+        }
+    }
+
+    pub fn settings_bundle_exported(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "设置包已导出",
+            Language::English => "Settings bundle exported",
+        }
+    }
+
+    pub fn settings_bundle_select_sections(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "选择要导入的部分:",
+            Language::English => "Select sections to import:",
+        }
+    }
+
+    pub fn settings_section_name(&self, section: &str) -> String {
+        match (section, &self.language) {
+            ("preferences", Language::Chinese) => "偏好设置 (语言、主题)".to_string(),
+            ("preferences", Language::English) => "Preferences (language, theme)".to_string(),
+            ("encoding_defaults", Language::Chinese) => "编码设置".to_string(),
+            ("encoding_defaults", Language::English) => "Encoding settings".to_string(),
+            ("workflows", Language::Chinese) => "工作流库".to_string(),
+            ("workflows", Language::English) => "Workflow library".to_string(),
+            ("templates", Language::Chinese) => "分组模板".to_string(),
+            ("templates", Language::English) => "Group templates".to_string(),
+            ("watch_folder", Language::Chinese) => "监视文件夹".to_string(),
+            ("watch_folder", Language::English) => "Watch folder".to_string(),
+            (other, _) => other.to_string(),
+        }
+    }
+
+    pub fn import(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "导入",
+            Language::English => "Import",
         }
     }
 
@@ -220,6 +5983,13 @@ impl Translations {
         }
     }
 
+    pub fn cut_and_join(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "剪切拼接",
+            Language::English => "Cut & Join",
+        }
+    }
+
     pub fn video_rotate(&self) -> &'static str {
         match self.language {
             Language::Chinese => "视频旋转",
@@ -262,6 +6032,27 @@ impl Translations {
         }
     }
 
+    pub fn audio_fade(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "音频淡入淡出",
+            Language::English => "Audio Fade",
+        }
+    }
+
+    pub fn frame_rate_convert(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "帧率转换",
+            Language::English => "Frame Rate Conversion",
+        }
+    }
+
+    pub fn audio_normalize(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "响度标准化",
+            Language::English => "Loudness Normalization",
+        }
+    }
+
     pub fn audio_trim(&self) -> &'static str {
         match self.language {
             Language::Chinese => "音频裁剪",
@@ -446,6 +6237,13 @@ impl Translations {
         }
     }
 
+    pub fn same_as_source_fps(&self) -> &'static str {
+        match self.language {
+            Language::Chinese => "与源相同",
+            Language::English => "Same as source",
+        }
+    }
+
     pub fn resolution(&self) -> &'static str {
         match self.language {
             Language::Chinese => "分辨率",
@@ -639,6 +6437,13 @@ impl Translations {
         }
     }
 
+    pub fn output_path_renamed(&self, operation_name: &str, path: &str) -> String {
+        match self.language {
+            Language::Chinese => format!("已添加任务: {} (输出路径已被占用，已重命名为 {})", operation_name, path),
+            Language::English => format!("Task added: {} (output path already in use, renamed to {})", operation_name, path),
+        }
+    }
+
     // New translation items
     pub fn smart_recommendations(&self) -> &'static str {
         match self.language {
@@ -1811,4 +7616,122 @@ pub fn detect_system_language() -> Language {
     
     // Default to English
     Language::English
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation_flow::{new_connection_id, AutomationNode, AutomationWorkflow, NodeConnection, NodeType};
+
+    fn is_cjk(c: char) -> bool {
+        ('\u{4e00}'..='\u{9fff}').contains(&c) || ('\u{3000}'..='\u{303f}').contains(&c) || ('\u{ff00}'..='\u{ffef}').contains(&c)
+    }
+
+    /// input → overlay ← logo, overlay → subtitle → output, plus an audio branch
+    fn workflow() -> AutomationWorkflow {
+        let mut workflow = AutomationWorkflow::new("language test".to_string());
+        let nodes = [
+            ("input", NodeType::InputFile),
+            ("logo", NodeType::InputFile),
+            ("overlay", NodeType::VideoOverlay),
+            ("subtitle", NodeType::AddSubtitle),
+            ("output", NodeType::OutputFile),
+            ("extract", NodeType::ExtractAudio),
+            ("denoise", NodeType::AudioDeNoise),
+            ("audio_out", NodeType::OutputFile),
+        ];
+        for (index, (id, node_type)) in nodes.into_iter().enumerate() {
+            let mut node = AutomationNode::new(id.to_string(), node_type, egui::pos2(index as f32 * 50.0, 20.0));
+            if let Some(parameter) = node.parameters.get_mut("file_path") {
+                parameter.value = format!("/media/{}.mp4", id);
+            }
+            workflow.add_node(node);
+        }
+        for (from, to, to_port) in [
+            ("input", "overlay", 0), ("logo", "overlay", 1), ("overlay", "subtitle", 0), ("subtitle", "output", 0),
+            ("input", "extract", 0), ("extract", "denoise", 0), ("denoise", "audio_out", 0),
+        ] {
+            let data_type = workflow.nodes[from].output_ports[0].data_type.clone();
+            workflow.add_connection(NodeConnection {
+                id: new_connection_id(),
+                from_node: from.to_string(),
+                from_port: 0,
+                to_node: to.to_string(),
+                to_port,
+                data_type,
+            }).unwrap();
+        }
+        workflow
+    }
+
+    /// Everything the editor shows for `workflow` in `translations`' language
+    fn displayed_names(workflow: &AutomationWorkflow, translations: &Translations) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut ids: Vec<&String> = workflow.nodes.keys().collect();
+        ids.sort();
+        for id in ids {
+            let node = &workflow.nodes[id];
+            names.push(translations.node_type_name(&node.node_type));
+            names.extend(node.input_ports.iter().chain(&node.output_ports).map(|port| translations.port_name(&port.name)));
+        }
+        names
+    }
+
+    #[test]
+    fn workflows_saved_under_one_language_load_under_another() {
+        let chinese = Translations::new(Language::Chinese);
+        let english = Translations::new(Language::English);
+        let workflow = workflow();
+
+        // Saved while the editor shows Chinese names: the file holds none of them
+        let shown_in_chinese = displayed_names(&workflow, &chinese);
+        assert!(shown_in_chinese.iter().any(|name| name.chars().any(is_cjk)));
+        let saved = crate::workflow_file::to_json(&workflow).unwrap();
+        assert!(!saved.chars().any(is_cjk), "translated text in the saved workflow");
+        for name in &shown_in_chinese {
+            let text = name.split_once(' ').map_or(name.as_str(), |(_, text)| text);
+            if text.chars().any(is_cjk) {
+                assert!(!saved.contains(text), "{text}");
+            }
+        }
+
+        // Loaded under English: same nodes, ports, connections and parameters
+        let (loaded, issues) = crate::workflow_file::parse(&saved).unwrap();
+        assert!(issues.is_empty(), "{issues:?}");
+        assert_eq!(crate::workflow_file::to_json(&loaded).unwrap(), saved);
+        assert_eq!(loaded.nodes.len(), workflow.nodes.len());
+        assert_eq!(loaded.connections.len(), workflow.connections.len());
+        for (id, node) in &workflow.nodes {
+            let other = &loaded.nodes[id];
+            assert_eq!(other.node_type, node.node_type);
+            assert_eq!(other.position, node.position);
+            let ports = |node: &AutomationNode| node.input_ports.iter().chain(&node.output_ports).map(|port| port.name.clone()).collect::<Vec<_>>();
+            assert_eq!(ports(other), ports(node));
+            for (name, parameter) in &node.parameters {
+                assert_eq!(other.parameters[name].value, parameter.value, "{id}.{name}");
+            }
+        }
+
+        // ...and shows the English names, or the Chinese ones again after switching back
+        let shown_in_english = displayed_names(&loaded, &english);
+        assert!(!shown_in_english.iter().any(|name| name.chars().any(is_cjk)));
+        assert_eq!(displayed_names(&loaded, &chinese), shown_in_chinese);
+    }
+
+    #[test]
+    fn node_names_keep_their_icon_and_markers() {
+        let chinese = Translations::new(Language::Chinese);
+        let english = Translations::new(Language::English);
+        for node_type in [NodeType::InputFile, NodeType::VideoOverlay, NodeType::AudioDeNoise, NodeType::OutputFile] {
+            let english_name = english.node_type_name(&node_type);
+            assert_eq!(english_name, node_type.display_name());
+            let chinese_name = chinese.node_type_name(&node_type);
+            assert_ne!(chinese_name, english_name);
+            assert_eq!(chinese_name.split_whitespace().next(), english_name.split_whitespace().next());
+        }
+        assert_eq!(chinese.port_name("video"), "视频");
+        assert_eq!(english.port_name("video"), "video");
+        // Port names without a translation are shown as they are
+        assert_eq!(chinese.port_name("custom_port"), "custom_port");
+    }
+}
@@ -0,0 +1,326 @@
+use crate::language::{Language, Translations};
+
+/// Why a settings field could not be parsed; shown inline next to the field
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    Empty,
+    InvalidNumber,
+    InvalidDuration,
+    InvalidBitrate,
+    OutOfRange { min: f64, max: f64 },
+}
+
+/// Decimal separator used when displaying numbers in `language`
+pub fn decimal_separator(language: &Language) -> char {
+    match language {
+        // Both supported UI languages write decimals with a dot
+        Language::Chinese | Language::English => '.',
+    }
+}
+
+/// Parse a decimal number written with either a comma or a dot ("1,5" or "1.5").
+/// Inputs with both separators are rejected rather than guessing which one groups thousands.
+pub fn parse_decimal(input: &str) -> Result<f64, InputError> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    if text.contains(',') && text.contains('.') || text.matches(',').count() > 1 {
+        return Err(InputError::InvalidNumber);
+    }
+    let normalized = text.replace(',', ".");
+    // f64::from_str also accepts "inf", "NaN" and exponents, none of which belong in a settings field
+    if !normalized.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+')) {
+        return Err(InputError::InvalidNumber);
+    }
+    normalized.parse::<f64>().map_err(|_| InputError::InvalidNumber)
+}
+
+pub fn parse_decimal_in_range(input: &str, min: f64, max: f64) -> Result<f64, InputError> {
+    let value = parse_decimal(input)?;
+    if value < min || value > max {
+        return Err(InputError::OutOfRange { min, max });
+    }
+    Ok(value)
+}
+
+/// Parse a duration in seconds: plain seconds ("90", "1,5"), MM:SS or HH:MM:SS, each with optional
+/// fractional seconds. A leading sign is allowed for offsets.
+pub fn parse_duration(input: &str) -> Result<f64, InputError> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    let (sign, body) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let parts: Vec<&str> = body.split(':').collect();
+    if parts.len() > 3 || parts.iter().any(|part| part.trim().is_empty()) {
+        return Err(InputError::InvalidDuration);
+    }
+
+    let (seconds_part, unit_parts) = parts.split_last().ok_or(InputError::InvalidDuration)?;
+    let seconds = parse_decimal(seconds_part).map_err(|_| InputError::InvalidDuration)?;
+    if seconds < 0.0 || (!unit_parts.is_empty() && seconds >= 60.0) {
+        return Err(InputError::InvalidDuration);
+    }
+
+    let mut total = 0.0;
+    for (index, part) in unit_parts.iter().enumerate() {
+        let value: u64 = part.trim().parse().map_err(|_| InputError::InvalidDuration)?;
+        // Minutes must stay below 60 when hours are given
+        if unit_parts.len() == 2 && index == 1 && value >= 60 {
+            return Err(InputError::InvalidDuration);
+        }
+        total = total * 60.0 + value as f64;
+    }
+    Ok(sign * (total * 60.0 + seconds))
+}
+
+/// Format seconds for display: MM:SS(.mmm) below an hour, HH:MM:SS(.mmm) above
+pub fn format_duration(seconds: f64, language: &Language) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let total_ms = (seconds.abs() * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let ms = total_ms % 1000;
+
+    let fraction = if ms > 0 {
+        format!("{}{:03}", decimal_separator(language), ms)
+    } else {
+        String::new()
+    };
+    if hours > 0 {
+        format!("{}{}:{:02}:{:02}{}", sign, hours, minutes, secs, fraction)
+    } else {
+        format!("{}{:02}:{:02}{}", sign, minutes, secs, fraction)
+    }
+}
+
+/// Duration in the form ffmpeg expects for -ss/-to, independent of the UI language
+pub fn format_ffmpeg_duration(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!("{:02}:{:02}:{:02}.{:03}",
+        total_ms / 3_600_000, (total_ms % 3_600_000) / 60_000, (total_ms % 60_000) / 1000, total_ms % 1000)
+}
+
+pub fn format_decimal(value: f64, decimals: usize, language: &Language) -> String {
+    let text = format!("{:.*}", decimals, value);
+    text.replace('.', &decimal_separator(language).to_string())
+}
+
+/// Parse a bitrate into ffmpeg form ("2500k"). Accepts "auto", plain kbps ("128"),
+/// k/M suffixes with either decimal separator ("2,5M") and optional "bps" / "b/s".
+pub fn parse_bitrate(input: &str) -> Result<String, InputError> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    if text.eq_ignore_ascii_case("auto") {
+        return Ok("auto".to_string());
+    }
+
+    let lower = text.to_lowercase().replace(' ', "");
+    let lower = lower.trim_end_matches("bps").trim_end_matches("b/s");
+    let (number, multiplier) = if let Some(number) = lower.strip_suffix('m') {
+        (number, 1000.0)
+    } else if let Some(number) = lower.strip_suffix('k') {
+        (number, 1.0)
+    } else {
+        (lower, 1.0)
+    };
+
+    let kbps = parse_decimal(number).map_err(|_| InputError::InvalidBitrate)? * multiplier;
+    if kbps <= 0.0 {
+        return Err(InputError::InvalidBitrate);
+    }
+    Ok(format!("{}k", kbps.round() as u64))
+}
+
+/// Show an ffmpeg bitrate ("2500k") with the language's conventions ("2.5 Mbps")
+pub fn format_bitrate(bitrate: &str, language: &Language) -> String {
+    match bitrate.strip_suffix('k').and_then(|kbps| kbps.parse::<f64>().ok()) {
+        Some(kbps) if kbps >= 1000.0 => format!("{} Mbps", format_decimal(kbps / 1000.0, 1, language)),
+        Some(kbps) => format!("{} kbps", kbps as u64),
+        None => bitrate.to_string(),
+    }
+}
+
+/// Text field bound to a parsed value. The typed text is kept while it is invalid so nothing is
+/// silently clamped or reverted; the field gets a red outline and the error as tooltip.
+pub fn parsed_edit<T>(
+    ui: &mut egui::Ui,
+    id_salt: impl std::hash::Hash,
+    value: &mut T,
+    desired_width: f32,
+    format: impl Fn(&T) -> String,
+    parse: impl Fn(&str) -> Result<T, InputError>,
+    translations: &Translations,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_salt);
+    let formatted = format(value);
+    // The edit buffer remembers the value it was typed against, so outside changes (reset buttons,
+    // presets) replace a stale buffer
+    let mut text = ui.data(|d| d.get_temp::<(String, String)>(id))
+        .filter(|(_, typed_against)| *typed_against == formatted)
+        .map(|(text, _)| text)
+        .unwrap_or_else(|| formatted.clone());
+
+    let mut response = ui.add(egui::TextEdit::singleline(&mut text).desired_width(desired_width));
+
+    match parse(&text) {
+        Ok(parsed) => {
+            if response.changed() {
+                *value = parsed;
+            }
+            if response.has_focus() {
+                let typed_against = format(value);
+                ui.data_mut(|d| d.insert_temp(id, (text, typed_against)));
+            } else {
+                // Re-format on blur so the field shows the canonical form
+                ui.data_mut(|d| d.remove_temp::<(String, String)>(id));
+            }
+        }
+        Err(error) => {
+            ui.data_mut(|d| d.insert_temp(id, (text, formatted)));
+            ui.painter().rect_stroke(response.rect.expand(1.0), 2.0, egui::Stroke::new(1.5, egui::Color32::from_rgb(230, 70, 70)));
+            response = response.on_hover_text(translations.input_error(&error));
+        }
+    }
+    response
+}
+
+/// Duration field in seconds (plain seconds, MM:SS or HH:MM:SS)
+pub fn duration_edit(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, seconds: &mut f64, translations: &Translations) -> egui::Response {
+    let language = translations.language.clone();
+    parsed_edit(ui, id_salt, seconds, 90.0, |value| format_duration(*value, &language), parse_duration, translations)
+        .on_hover_text(translations.duration_input_hint())
+}
+
+/// Optional duration stored as an ffmpeg time string; an empty field means "not set"
+pub fn optional_duration_edit(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, value: &mut String, translations: &Translations) -> egui::Response {
+    let language = translations.language.clone();
+    parsed_edit(
+        ui,
+        id_salt,
+        value,
+        90.0,
+        |stored| parse_duration(stored).map(|seconds| format_duration(seconds, &language)).unwrap_or_else(|_| stored.clone()),
+        |text| if text.trim().is_empty() {
+            Ok(String::new())
+        } else {
+            parse_duration(text).and_then(|seconds| if seconds < 0.0 {
+                Err(InputError::InvalidDuration)
+            } else {
+                Ok(format_ffmpeg_duration(seconds))
+            })
+        },
+        translations,
+    ).on_hover_text(translations.duration_input_hint())
+}
+
+/// Bitrate field stored in ffmpeg form ("2500k" or "auto")
+pub fn bitrate_edit(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, bitrate: &mut String, translations: &Translations) -> egui::Response {
+    parsed_edit(ui, id_salt, bitrate, 90.0, |stored| stored.clone(), parse_bitrate, translations)
+        .on_hover_text(translations.bitrate_input_hint())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(result: Result<f64, InputError>, expected: f64) -> bool {
+        matches!(result, Ok(value) if (value - expected).abs() < 1e-9)
+    }
+
+    #[test]
+    fn decimals_accept_comma_and_dot() {
+        assert!(close(parse_decimal("1.5"), 1.5));
+        assert!(close(parse_decimal("1,5"), 1.5));
+        assert!(close(parse_decimal(" 42 "), 42.0));
+        assert!(close(parse_decimal("-0,25"), -0.25));
+        assert!(close(parse_decimal("+3"), 3.0));
+        assert!(close(parse_decimal(",5"), 0.5));
+    }
+
+    #[test]
+    fn decimals_reject_ambiguous_and_foreign_input() {
+        assert_eq!(parse_decimal(""), Err(InputError::Empty));
+        assert_eq!(parse_decimal("   "), Err(InputError::Empty));
+        for input in ["1,000.5", "1.000,5", "1,000,000", "1.2.3", "abc", "inf", "NaN", "1e3", "12px", "-", "1 5"] {
+            assert_eq!(parse_decimal(input), Err(InputError::InvalidNumber), "{input}");
+        }
+    }
+
+    #[test]
+    fn decimal_ranges_are_inclusive() {
+        assert!(close(parse_decimal_in_range("0", 0.0, 1.0), 0.0));
+        assert!(close(parse_decimal_in_range("1,0", 0.0, 1.0), 1.0));
+        assert_eq!(parse_decimal_in_range("1,01", 0.0, 1.0), Err(InputError::OutOfRange { min: 0.0, max: 1.0 }));
+        assert_eq!(parse_decimal_in_range("-0.1", 0.0, 1.0), Err(InputError::OutOfRange { min: 0.0, max: 1.0 }));
+        assert_eq!(parse_decimal_in_range("x", 0.0, 1.0), Err(InputError::InvalidNumber));
+    }
+
+    #[test]
+    fn durations_parse_seconds_minutes_and_hours() {
+        assert!(close(parse_duration("90"), 90.0));
+        assert!(close(parse_duration("1,5"), 1.5));
+        assert!(close(parse_duration("0.25"), 0.25));
+        assert!(close(parse_duration("01:30"), 90.0));
+        assert!(close(parse_duration("1:30,5"), 90.5));
+        assert!(close(parse_duration("90:00"), 5400.0));
+        assert!(close(parse_duration("01:02:03"), 3723.0));
+        assert!(close(parse_duration("1:02:03.250"), 3723.25));
+        assert!(close(parse_duration("100:00:00"), 360_000.0));
+        assert!(close(parse_duration(" 00:00:00.001 "), 0.001));
+        assert!(close(parse_duration("-1:30"), -90.0));
+        assert!(close(parse_duration("+5"), 5.0));
+    }
+
+    #[test]
+    fn durations_reject_malformed_input() {
+        assert_eq!(parse_duration(""), Err(InputError::Empty));
+        for input in [
+            "1:60", "1:60:00", "1:02:60", "1:2:3:4", ":30", "1::30", "1:", "a:30", "1:30s",
+            "1.5:30", "-1:-30", "--5", "1:30,000.5", "inf",
+        ] {
+            assert_eq!(parse_duration(input), Err(InputError::InvalidDuration), "{input}");
+        }
+    }
+
+    #[test]
+    fn durations_round_trip_through_formatting() {
+        let language = Language::English;
+        assert_eq!(format_duration(90.0, &language), "01:30");
+        assert_eq!(format_duration(3723.25, &language), "1:02:03.250");
+        assert_eq!(format_duration(-90.5, &language), "-01:30.500");
+        for seconds in [0.0, 0.001, 59.999, 90.5, 3599.0, 3723.25, 360_000.0, -12.5] {
+            let text = format_duration(seconds, &Language::Chinese);
+            assert!(close(parse_duration(&text), seconds), "{seconds} -> {text}");
+        }
+
+        assert_eq!(format_ffmpeg_duration(3723.25), "01:02:03.250");
+        assert_eq!(format_ffmpeg_duration(-3.0), "00:00:00.000");
+    }
+
+    #[test]
+    fn bitrates_normalize_to_kbps() {
+        assert_eq!(parse_bitrate("128").as_deref(), Ok("128k"));
+        assert_eq!(parse_bitrate("2500k").as_deref(), Ok("2500k"));
+        assert_eq!(parse_bitrate("2,5M").as_deref(), Ok("2500k"));
+        assert_eq!(parse_bitrate("2.5 Mbps").as_deref(), Ok("2500k"));
+        assert_eq!(parse_bitrate("192 kb/s").as_deref(), Ok("192k"));
+        assert_eq!(parse_bitrate("AUTO").as_deref(), Ok("auto"));
+        assert_eq!(parse_bitrate(""), Err(InputError::Empty));
+        for input in ["0", "-5k", "fast", "1,000.5k", "5g"] {
+            assert_eq!(parse_bitrate(input), Err(InputError::InvalidBitrate), "{input}");
+        }
+
+        assert_eq!(format_bitrate("2500k", &Language::English), "2.5 Mbps");
+        assert_eq!(format_bitrate("128k", &Language::English), "128 kbps");
+        assert_eq!(format_bitrate("auto", &Language::English), "auto");
+    }
+}
@@ -1,28 +1,241 @@
-// Logging macros with timestamp and log level
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crossbeam_channel::{bounded, Sender};
+
+/// Size at which the current log file is rotated
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+/// ffmpeg_gui.log plus ffmpeg_gui.1.log and ffmpeg_gui.2.log
+const MAX_FILES: usize = 3;
+/// Lines waiting for the writer thread; further lines are dropped so logging never blocks the UI
+const QUEUE_CAPACITY: usize = 4096;
+
+static FILE_LOGGER: OnceLock<Sender<String>> = OnceLock::new();
+/// Debug lines go to the log file too; the console always gets them
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// Lines below ERROR are printed to the console; headless runs print their own progress instead
+static CONSOLE: AtomicBool = AtomicBool::new(true);
+
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn set_console(enabled: bool) {
+    CONSOLE.store(enabled, Ordering::Relaxed);
+}
+
+// Logging macros with timestamp and log level; lines also go to the rotating log file once
+// init_file_logging has run
 #[macro_export]
 macro_rules! log_debug {
     ($($arg:tt)*) => {
-        println!("[{}][DEBUG] {}", chrono::Local::now().format("%H:%M:%S"), format!($($arg)*))
+        $crate::logging::log("DEBUG", format!($($arg)*))
     };
 }
 
 #[macro_export]
 macro_rules! log_info {
     ($($arg:tt)*) => {
-        println!("[{}][INFO] {}", chrono::Local::now().format("%H:%M:%S"), format!($($arg)*))
+        $crate::logging::log("INFO", format!($($arg)*))
     };
 }
 
 #[macro_export]
 macro_rules! log_warn {
     ($($arg:tt)*) => {
-        println!("[{}][WARN] {}", chrono::Local::now().format("%H:%M:%S"), format!($($arg)*))
+        $crate::logging::log("WARN", format!($($arg)*))
     };
 }
 
 #[macro_export]
 macro_rules! log_error {
     ($($arg:tt)*) => {
-        eprintln!("[{}][ERROR] {}", chrono::Local::now().format("%H:%M:%S"), format!($($arg)*))
+        $crate::logging::log("ERROR", format!($($arg)*))
+    };
+}
+
+pub fn log(level: &str, message: String) {
+    let now = chrono::Local::now();
+    if level == "ERROR" {
+        eprintln!("[{}][{}] {}", now.format("%H:%M:%S"), level, message);
+    } else if CONSOLE.load(Ordering::Relaxed) {
+        println!("[{}][{}] {}", now.format("%H:%M:%S"), level, message);
+    }
+    if level == "DEBUG" && !VERBOSE.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(sender) = FILE_LOGGER.get() {
+        let line = format!("[{}][{}] {}\n", now.format("%Y-%m-%d %H:%M:%S%.3f"), level, message);
+        // When the writer is behind the line is dropped; that beats stalling the UI thread
+        let _ = sender.try_send(line);
+    }
+}
+
+/// Log folder next to the preferences file
+pub fn log_dir() -> Option<PathBuf> {
+    crate::app_state::UserPreferences::file_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("logs")))
+}
+
+/// Path of log file `index`; 0 is the file being written, higher indices are older
+pub fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join("ffmpeg_gui.log")
+    } else {
+        dir.join(format!("ffmpeg_gui.{}.log", index))
+    }
+}
+
+/// Whether appending `incoming` bytes to a file of `current` bytes should rotate first.
+/// A single line larger than the cap still goes into an empty file.
+pub fn needs_rotation(current: u64, incoming: u64, max_bytes: u64) -> bool {
+    current > 0 && current + incoming > max_bytes
+}
+
+/// Shift every file one index up, dropping the oldest so at most `max_files` remain
+pub fn rotate(dir: &Path, max_files: usize) -> std::io::Result<()> {
+    let oldest = log_file_path(dir, max_files.saturating_sub(1));
+    if oldest.exists() {
+        std::fs::remove_file(&oldest)?;
+    }
+    for index in (0..max_files.saturating_sub(1)).rev() {
+        let from = log_file_path(dir, index);
+        if from.exists() {
+            std::fs::rename(&from, log_file_path(dir, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Append `line`, rotating first when it would push the current file past `max_bytes`
+pub fn append_rotating(dir: &Path, line: &str, max_bytes: u64, max_files: usize) -> std::io::Result<()> {
+    let path = log_file_path(dir, 0);
+    let current = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+    if needs_rotation(current, line.len() as u64, max_bytes) {
+        rotate(dir, max_files)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Start the background writer for the rotating log file and install the panic hook
+pub fn init_file_logging() {
+    let Some(dir) = log_dir() else {
+        return;
     };
-}
\ No newline at end of file
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("Failed to create log folder {}: {}", dir.display(), e);
+        return;
+    }
+    let (sender, receiver) = bounded::<String>(QUEUE_CAPACITY);
+    if FILE_LOGGER.set(sender).is_err() {
+        return;
+    }
+    let writer_dir = dir.clone();
+    std::thread::Builder::new()
+        .name("log-writer".to_string())
+        .spawn(move || {
+            for line in receiver {
+                if let Err(e) = append_rotating(&writer_dir, &line, MAX_FILE_BYTES, MAX_FILES) {
+                    eprintln!("Failed to write log file: {}", e);
+                }
+            }
+        })
+        .ok();
+    install_panic_hook(dir);
+}
+
+/// Write panics with a backtrace straight to the log file; the writer thread may never get to
+/// queued lines once the process goes down
+fn install_panic_hook(dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let report = format!(
+            "[{}][PANIC] thread '{}' {}\n{}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            thread.name().unwrap_or("<unnamed>"),
+            info,
+            std::backtrace::Backtrace::force_capture(),
+        );
+        let _ = append_rotating(&dir, &report, MAX_FILE_BYTES, MAX_FILES);
+        previous(info);
+    }));
+}
+
+/// Contents of all log files, oldest first
+pub fn recent_logs() -> String {
+    log_dir().map(|dir| read_logs(&dir, MAX_FILES)).unwrap_or_default()
+}
+
+fn read_logs(dir: &Path, max_files: usize) -> String {
+    (0..max_files).rev()
+        .filter_map(|index| std::fs::read_to_string(log_file_path(dir, index)).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotation_threshold() {
+        assert!(!needs_rotation(0, 10, 100));
+        assert!(!needs_rotation(90, 10, 100));
+        assert!(needs_rotation(91, 10, 100));
+        // An oversized line still goes into an empty file
+        assert!(!needs_rotation(0, 500, 100));
+        assert!(needs_rotation(1, 500, 100));
+    }
+
+    #[test]
+    fn files_rotate_and_the_oldest_is_dropped() {
+        let dir = TempDir::new("logs");
+        // Ten bytes per line, two lines per file
+        for line in 0..7 {
+            append_rotating(&dir.0, &format!("line {:03}\n", line), 20, 3).unwrap();
+        }
+        assert_eq!(files(&dir.0), ["ffmpeg_gui.1.log", "ffmpeg_gui.2.log", "ffmpeg_gui.log"]);
+        let read = |index| std::fs::read_to_string(log_file_path(&dir.0, index)).unwrap();
+        assert_eq!(read(0), "line 006\n");
+        assert_eq!(read(1), "line 004\nline 005\n");
+        assert_eq!(read(2), "line 002\nline 003\n");
+        assert_eq!(read_logs(&dir.0, 3), "line 002\nline 003\nline 004\nline 005\nline 006\n");
+    }
+
+    #[test]
+    fn rotating_with_gaps_and_a_single_file() {
+        let dir = TempDir::new("logs");
+        std::fs::write(log_file_path(&dir.0, 0), "current").unwrap();
+        std::fs::write(log_file_path(&dir.0, 2), "oldest").unwrap();
+        rotate(&dir.0, 3).unwrap();
+        assert_eq!(files(&dir.0), ["ffmpeg_gui.1.log"]);
+        assert_eq!(std::fs::read_to_string(log_file_path(&dir.0, 1)).unwrap(), "current");
+
+        // With one file allowed, rotating just starts over
+        rotate(&dir.0, 1).unwrap();
+        append_rotating(&dir.0, "only\n", 20, 1).unwrap();
+        append_rotating(&dir.0, "a line past the cap\n", 20, 1).unwrap();
+        assert_eq!(std::fs::read_to_string(log_file_path(&dir.0, 0)).unwrap(), "a line past the cap\n");
+        assert_eq!(read_logs(&dir.0, 1), "a line past the cap\n");
+    }
+
+    #[test]
+    fn log_file_names() {
+        let dir = Path::new("logs");
+        assert_eq!(log_file_path(dir, 0), dir.join("ffmpeg_gui.log"));
+        assert_eq!(log_file_path(dir, 2), dir.join("ffmpeg_gui.2.log"));
+        assert_eq!(read_logs(Path::new("/nonexistent/ffgui/logs"), MAX_FILES), "");
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// A measured loudness this close to the target (in LU) needs no correction; the second pass
+/// still runs so the output is written in the requested format
+pub const TOLERANCE_LU: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoudnessPreset {
+    /// Common target of streaming platforms and podcasts
+    Streaming,
+    /// EBU R128 broadcast loudness
+    EbuR128,
+}
+
+impl LoudnessPreset {
+    pub fn all() -> [LoudnessPreset; 2] {
+        [LoudnessPreset::Streaming, LoudnessPreset::EbuR128]
+    }
+
+    pub fn target(&self) -> LoudnessTarget {
+        match self {
+            LoudnessPreset::Streaming => LoudnessTarget { integrated_lufs: -16.0, true_peak_db: -1.5, range_lu: 11.0 },
+            LoudnessPreset::EbuR128 => LoudnessTarget { integrated_lufs: -23.0, true_peak_db: -1.0, range_lu: 11.0 },
+        }
+    }
+}
+
+/// Targets of the loudnorm filter, shared by the AudioNormalize operation and workflow node
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoudnessTarget {
+    /// Integrated loudness (I), -70 to -5 LUFS
+    pub integrated_lufs: f32,
+    /// Maximum true peak (TP), -9 to 0 dBTP
+    pub true_peak_db: f32,
+    /// Loudness range (LRA), 1 to 20 LU
+    pub range_lu: f32,
+}
+
+impl Default for LoudnessTarget {
+    fn default() -> Self {
+        LoudnessPreset::Streaming.target()
+    }
+}
+
+impl LoudnessTarget {
+    /// The preset these targets match exactly, if any
+    pub fn preset(&self) -> Option<LoudnessPreset> {
+        LoudnessPreset::all().into_iter().find(|preset| preset.target() == *self)
+    }
+
+    /// Check the targets against the ranges loudnorm accepts
+    pub fn validate(&self) -> Result<()> {
+        if !(-70.0..=-5.0).contains(&self.integrated_lufs) {
+            return Err(anyhow!("Target loudness must be between -70 and -5 LUFS, got {}", self.integrated_lufs));
+        }
+        if !(-9.0..=0.0).contains(&self.true_peak_db) {
+            return Err(anyhow!("True peak must be between -9 and 0 dBTP, got {}", self.true_peak_db));
+        }
+        if !(1.0..=20.0).contains(&self.range_lu) {
+            return Err(anyhow!("Loudness range must be between 1 and 20 LU, got {}", self.range_lu));
+        }
+        Ok(())
+    }
+
+    fn targets(&self) -> String {
+        format!("I={}:TP={}:LRA={}", self.integrated_lufs, self.true_peak_db, self.range_lu)
+    }
+
+    /// First pass: analyse only, printing the measurement as JSON on stderr
+    pub fn measure_filter(&self) -> String {
+        format!("loudnorm={}:print_format=json", self.targets())
+    }
+
+    /// Second pass: normalize with the first pass's measurement, linearly where loudnorm can
+    pub fn normalize_filter(&self, measured: &LoudnessMeasurement) -> String {
+        format!(
+            "loudnorm={}:measured_I={:.2}:measured_TP={:.2}:measured_LRA={:.2}:measured_thresh={:.2}:offset={:.2}:linear=true:print_format=summary",
+            self.targets(), measured.integrated_lufs, measured.true_peak_db, measured.range_lu, measured.threshold_lufs, measured.offset_lu
+        )
+    }
+}
+
+/// What the first loudnorm pass measured on the input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessMeasurement {
+    pub integrated_lufs: f32,
+    pub true_peak_db: f32,
+    pub range_lu: f32,
+    pub threshold_lufs: f32,
+    /// Gain loudnorm suggests for the second pass
+    pub offset_lu: f32,
+}
+
+impl LoudnessMeasurement {
+    /// Whether the input already sits within TOLERANCE_LU of the target loudness
+    pub fn within_tolerance(&self, target: &LoudnessTarget) -> bool {
+        (self.integrated_lufs - target.integrated_lufs).abs() <= TOLERANCE_LU
+    }
+}
+
+/// Read the JSON block loudnorm prints at the end of the first pass out of ffmpeg's stderr
+pub fn parse_measurement(stderr: &[String]) -> Result<LoudnessMeasurement> {
+    let start = stderr.iter().rposition(|line| line.trim() == "{")
+        .ok_or_else(|| anyhow!("ffmpeg printed no loudness measurement"))?;
+    let end = stderr[start..].iter().position(|line| line.trim() == "}")
+        .ok_or_else(|| anyhow!("The loudness measurement is incomplete"))?;
+    let json = stderr[start..=start + end].join("\n");
+    let values: std::collections::HashMap<String, String> = serde_json::from_str(&json)
+        .map_err(|e| anyhow!("Could not read the loudness measurement: {}", e))?;
+    let value = |key: &str| -> Result<f32> {
+        values.get(key)
+            .and_then(|value| value.trim().parse::<f32>().ok())
+            .ok_or_else(|| anyhow!("The loudness measurement has no {}", key))
+    };
+    let measurement = LoudnessMeasurement {
+        integrated_lufs: value("input_i")?,
+        true_peak_db: value("input_tp")?,
+        range_lu: value("input_lra")?,
+        threshold_lufs: value("input_thresh")?,
+        offset_lu: value("target_offset")?,
+    };
+    // Silence measures as -inf, which the second pass rejects
+    if !measurement.integrated_lufs.is_finite() || !measurement.threshold_lufs.is_finite() {
+        return Err(anyhow!("The input is silent, there is no loudness to normalize"));
+    }
+    Ok(measurement)
+}
+
+/// Preset buttons and the three targets. Returns true when the targets changed.
+pub fn show_editor(ui: &mut egui::Ui, target: &mut LoudnessTarget, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.loudness_preset());
+        let current = target.preset();
+        for preset in LoudnessPreset::all() {
+            let label = match preset {
+                LoudnessPreset::Streaming => translations.loudness_streaming(),
+                LoudnessPreset::EbuR128 => translations.loudness_ebu_r128(),
+            };
+            if ui.selectable_label(current == Some(preset), label).clicked() {
+                *target = preset.target();
+                changed = true;
+            }
+        }
+        if current.is_none() {
+            ui.label(egui::RichText::new(translations.loudness_custom()).weak());
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.loudness_integrated());
+        changed |= ui.add(egui::DragValue::new(&mut target.integrated_lufs).range(-70.0..=-5.0).speed(0.1).suffix(" LUFS")).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.loudness_true_peak());
+        changed |= ui.add(egui::DragValue::new(&mut target.true_peak_db).range(-9.0..=0.0).speed(0.1).suffix(" dBTP")).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.loudness_range());
+        changed |= ui.add(egui::DragValue::new(&mut target.range_lu).range(1.0..=20.0).speed(0.1).suffix(" LU")).changed();
+    });
+    ui.label(egui::RichText::new(translations.loudness_two_pass_hint()).weak());
+
+    changed
+}
@@ -12,16 +12,96 @@ mod ui_components;
 mod language;
 mod task_executor;
 mod codec_manager;
+mod compatibility_matrix;
 mod comprehensive_codec_registry;
 mod comprehensive_ui_components;
 mod comprehensive_command_builder;
+mod preset_comparison;
 mod preset_manager;
 mod operation_settings;
 mod hardware_detector;
 mod automation_flow;
+mod alpha_video;
+mod archive;
+mod audio_advice;
+mod audio_denoise;
+mod audio_echo;
+mod audio_equalizer;
+mod audio_fade;
+mod audio_mix;
+mod audio_preview;
+mod chapters;
+mod checksum;
+mod color_conversion;
+mod cut_join;
+mod data_batch;
+mod destination;
+mod diagnostics;
+mod download_cache;
+mod drop_suggestion;
+mod effective_codecs;
+mod encode_advisor;
+mod environment;
+mod file_times;
+mod filter_stack;
+mod first_run;
+mod folder_run;
+mod font_resolver;
+mod fps_conversion;
+mod frame_extract;
+mod frame_rate;
+mod hardware_refresh;
+mod headless;
+mod help_overlay;
+mod hls_encryption;
+mod image_sequence;
+mod locale_format;
+mod loudness;
+mod node_group;
+mod output_extension;
+mod output_favorites;
+mod output_guard;
+mod output_recovery;
+mod overlay;
+mod parameter_limits;
+mod power;
+mod probe_cache;
+mod progressive_probe;
+mod quality_metrics;
+mod queue_store;
+mod renditions;
+mod review_clip;
+mod run_cancel;
+mod safe_mode;
+mod schedule;
+mod session_undo;
+mod shell_quote;
+mod social_presets;
+mod size_target;
+mod stabilize;
+mod stream_packaging;
+mod subtitle_timing;
+mod task_log;
+mod temp_files;
+#[cfg(test)]
+mod test_support;
+mod text_overlay;
+mod timeline;
+mod transport_stream;
+mod viewport_geometry;
+mod watch_folder;
+mod workflow_autosave;
+mod workflow_tabs;
+mod workflow_report;
+mod workflow_script;
+mod workflow_cache;
+mod workflow_file;
+mod workflow_plan;
+mod workflow_diff;
+mod workflow_library;
 
 use app_state::*;
-use app_state::ProjectConfig;
+use app_state::{ProjectConfig, SessionSnapshot};
 use ui_components::*;
 use language::*;
 use task_executor::*;
@@ -57,7 +137,53 @@ struct FFmpegGui {
     audio_settings: AudioSettings,
     
     tasks: Arc<Mutex<Vec<ProcessingTask>>>,
-    tasks_for_ui: Vec<ProcessingTask>,
+    task_view: TaskListView,
+    tasks_generation_seen: u64,
+    last_start_click: Option<std::time::Instant>,
+    max_tasks_per_destination: usize,
+    max_concurrent_tasks: usize,
+    suppressed_advisories: std::collections::BTreeSet<String>,
+    encoder_policy: EncoderPolicy,
+    default_output_dir: String,
+    output_favorites: output_favorites::OutputFavorites,
+    /// Defaults for new tasks' post-completion SHA-256 and sidecar
+    checksum_outputs: bool,
+    checksum_sidecar: bool,
+    /// Defaults for new tasks' source timestamp copy and creation_time
+    keep_source_times: bool,
+    write_creation_time: bool,
+    /// Maximum output duration and size new tasks run under
+    output_guards: output_guard::OutputGuards,
+    /// Guards for tasks started from the Start button instead of the global ones, not saved
+    guard_override: Option<output_guard::OutputGuards>,
+    checksum_verify: Option<checksum::VerifyDialog>,
+    compatibility_matrix: Option<compatibility_matrix::MatrixWindow>,
+    workflow_diff: Option<workflow_diff::DiffWindow>,
+    workflow_library: Option<workflow_library::LibraryWindow>,
+    folder_run: Option<folder_run::FolderRunDialog>,
+    /// Operation chooser for files dropped while no operation is selected
+    drop_chooser: Option<drop_suggestion::DropChooser>,
+    drop_choices: std::collections::BTreeMap<String, OperationType>,
+    /// Problems found while opening a workflow file
+    workflow_load_dialog: Option<workflow_file::LoadDialog>,
+    /// Review clip dialog for the analyzed input, and the options it starts with
+    review_clip: Option<review_clip::ReviewClipDialog>,
+    review_clip_settings: review_clip::ReviewClipSettings,
+    filename_template: String,
+    // Last output path generated from the template, so it is regenerated rather than suffixed
+    last_auto_output_file: String,
+    /// Frame folder filled in from the input, replaced again while the user has not edited it
+    last_auto_frame_dir: String,
+    first_run_wizard: Option<first_run::FirstRunWizard>,
+    // Start waiting for the hardware/software choice under the "always ask" policy
+    pending_encoder_choice: Option<(OperationType, String)>,
+    /// Out-of-range encoder parameters that blocked starting an operation
+    pending_parameter_issues: Option<(OperationType, Vec<parameter_limits::ParameterIssue>)>,
+    session_undo: session_undo::SessionUndo,
+    /// Unfinished tasks of the last session, offered for restore on startup
+    queue_restore: Vec<ProcessingTask>,
+    // Codec picked in that dialog, used by the next queued task only
+    encoder_choice_for_next_task: Option<String>,
     next_task_id: usize,
     task_executor: Option<TaskExecutor>,
     
@@ -72,14 +198,22 @@ struct FFmpegGui {
     
     dark_mode: bool,
     
-    hardware_info: Option<crate::hardware_detector::HardwareInfo>,
-    cached_hardware_encoders: Option<Vec<String>>,
-    hardware_detection_receiver: Option<std::sync::mpsc::Receiver<(Option<crate::hardware_detector::HardwareInfo>, Option<Vec<String>>)>>,
-    hardware_detection_started: bool,
+    hardware_detection: hardware_refresh::HardwareDetection,
+    /// Failed tasks already checked for hardware device errors
+    device_failures_checked: std::collections::HashSet<usize>,
     
     command_preview: String,
     
     show_about_dialog: bool,
+    show_run_report: bool,
+    /// Preview commands window: the dry run of the open workflow, None while closed
+    workflow_preview: Option<Result<Vec<workflow_plan::PreviewStep>, String>>,
+    /// Help > Create diagnostic bundle dialog: open, and whether to include the task history
+    show_diagnostics_dialog: bool,
+    diagnostics_include_tasks: bool,
+    data_batch: Option<data_batch::DataBatchDialog>,
+    pending_settings_import: Option<(SettingsBundle, Vec<(String, bool)>)>,
+    settings_import_report: Vec<String>,
     show_compatibility_warning: bool,
     compatibility_warning_message: String,
     compatibility_recommended_codec: Option<String>,
@@ -92,57 +226,138 @@ struct FFmpegGui {
     // Video orientation detection
     is_portrait_video: Option<bool>,
     detected_resolution: Option<(u32, u32)>,
+    detected_duration: Option<f64>,
+    detected_programs: Vec<transport_stream::ProgramInfo>,
+    detected_chapters: Vec<chapters::Chapter>,
+    /// Voice/music guess for the current input, running in the background
+    audio_content_probe: Option<std::sync::mpsc::Receiver<Option<audio_advice::ContentType>>>,
+    detected_media_info: Option<ffmpeg_worker_simple::MediaInfo>,
+    // Staged analysis of the single input file; its results fill the detected_* fields as they arrive
+    file_probe: Option<progressive_probe::ProgressiveProbe>,
+    timeline_state: timeline::TimelineState,
+    social_export: social_presets::SocialExportPanel,
+    subtitle_preview_time: f64,
+    subtitle_preview_texture: Option<egui::TextureHandle>,
+    subtitle_preview_receiver: Option<std::sync::mpsc::Receiver<Result<egui::ColorImage, String>>>,
+    subtitle_preview_error: Option<String>,
     
     // Cache to avoid repeated file analysis
     last_analyzed_file: String,
-    file_info_cache: String,
+    // Durations of AudioMerge inputs shown next to their gain; None when probing failed
+    merge_input_durations: std::collections::HashMap<String, Option<f64>>,
     
     show_automation_editor: bool,
-    current_workflow: Option<automation_flow::AutomationWorkflow>,
     workflow_executor: automation_flow::WorkflowExecutor,
-    selected_node: Option<String>,
-    dragging_node: Option<String>,
-    dragging_offset: egui::Vec2,
-    workflow_canvas_offset: egui::Vec2,
-    workflow_canvas_zoom: f32,
+    // Workflow run on a background thread, joined once it finishes
+    workflow_run: Option<std::thread::JoinHandle<(automation_flow::WorkflowExecutor, Result<(), String>)>>,
+    // Open workflows, one editor state per tab; never empty
+    workflow_tabs: Vec<workflow_tabs::WorkflowEditorState>,
+    active_workflow_tab: usize,
+    // Tab whose close is waiting for the unsaved-changes confirmation
+    pending_workflow_tab_close: Option<usize>,
     
     // Workflow window state
     workflow_window_pos: Option<egui::Pos2>,
     workflow_window_size: Option<egui::Vec2>,
-    workflow_window_context: Option<eframe::egui::Context>,
+    // Separate workflow viewport: geometry in physical pixels, remembered for the session
+    workflow_viewport_rect: Option<egui::Rect>,
+    // Where the open viewport was asked to appear; fixed while open so egui does not move it back
+    workflow_viewport_target: Option<egui::Rect>,
+    workflow_viewport_placed: bool,
+    workflow_viewport_ppp: Option<f32>,
     open_workflow_window_requested: bool,
     workflow_window_open: bool,
+    // Open the editor inside the main window instead of a separate viewport
+    force_embedded_workflow_editor: bool,
+    power_policy: power::PowerPolicy,
+    power_source: power::PowerSource,
+    power_receiver: std::sync::mpsc::Receiver<power::PowerSource>,
+    schedule_policy: schedule::SchedulePolicy,
+    // Name of the schedule profile whose limits are applied
+    active_schedule_profile: Option<String>,
+    schedule_override_until: Option<chrono::DateTime<chrono::Local>>,
+    schedule_checked: Option<std::time::Instant>,
+    keep_scratch_files: bool,
+    keep_existing_outputs: bool,
+    download_cache_cap_mb: u64,
+    workflow_autosave: bool,
+    workflow_autosave_secs: u64,
+    last_workflow_autosave: std::time::Instant,
+    /// A change was made since the last autosave
+    workflow_autosave_due: bool,
+    /// This session wrote the autosave file, so it is removed once nothing is unsaved
+    workflow_autosave_written: bool,
+    /// Unsaved workflows of an earlier session, offered until recovered or discarded
+    workflow_recovery: Option<workflow_autosave::Recovery>,
+    watch_folder: watch_folder::WatchFolderConfig,
+    watch_folder_enabled: bool,
+    folder_watcher: Option<watch_folder::FolderWatcher>,
+    /// Why the watcher could not start; it is not retried until started again
+    watch_folder_error: Option<String>,
+    show_watch_folder: bool,
+    show_download_cache: bool,
+    // Set once the separate viewport failed; the embedded editor is used for the rest of the session
+    workflow_viewport_failed: bool,
+    show_embedded_editor_notice: bool,
     
-    creating_connection: bool,
-    connection_source_node: Option<String>,
-    connection_source_port: Option<usize>,
-    connection_target_pos: egui::Pos2,
-    
-    workflow_history: Vec<automation_flow::AutomationWorkflow>,
-    history_index: isize,
     max_history_size: usize,
 }
 
+/// Preferences of a first run, and of safe mode
+fn default_preferences(language: &Language) -> UserPreferences {
+    UserPreferences {
+        language: language.code().to_string(),
+        dark_mode: true,
+        max_tasks_per_destination: 0,
+        max_concurrent_tasks: task_executor::DEFAULT_MAX_CONCURRENT_TASKS,
+        suppressed_advisories: std::collections::BTreeSet::new(),
+        encoder_policy: EncoderPolicy::default(),
+        default_output_dir: String::new(),
+        filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+        force_embedded_workflow_editor: false,
+        power_policy: power::PowerPolicy::default(),
+        output_favorites: output_favorites::OutputFavorites::default(),
+        checksum_outputs: false,
+        checksum_sidecar: false,
+        keep_source_times: false,
+        write_creation_time: false,
+        output_guards: Default::default(),
+        drop_choices: std::collections::BTreeMap::new(),
+        incremental_workflow_execution: false,
+        keep_stopped_workflow_files: false,
+        review_clip: review_clip::ReviewClipSettings::default(),
+        schedule_policy: schedule::SchedulePolicy::default(),
+        keep_scratch_files: false,
+        keep_existing_outputs: false,
+        download_cache_cap_mb: download_cache::DEFAULT_CAP_MB,
+        workflow_autosave: true,
+        workflow_autosave_secs: workflow_autosave::DEFAULT_INTERVAL_SECS,
+        watch_folder: watch_folder::WatchFolderConfig::default(),
+        watch_folder_enabled: false,
+    }
+}
+
 impl Default for FFmpegGui {
     fn default() -> Self {
-        let detected_language = detect_system_language();
+        // No preferences file yet means this is the first run
+        let stored_preferences = safe_mode::guarded(safe_mode::Subsystem::Preferences, UserPreferences::load).flatten();
+        let detected_language = stored_preferences.as_ref()
+            .and_then(|prefs| Language::from_code(&prefs.language))
+            .unwrap_or_else(detect_system_language);
+        let preferences = stored_preferences.clone().unwrap_or_else(|| default_preferences(&detected_language));
         let translations = Translations::new(detected_language.clone());
         let tasks = Arc::new(Mutex::new(Vec::new()));
         let task_executor = TaskExecutor::new(tasks.clone());
+        task_executor.set_max_tasks_per_destination(preferences.max_tasks_per_destination);
+        task_executor.set_max_concurrent_tasks(preferences.max_concurrent_tasks);
+        task_executor.set_keep_existing_outputs(preferences.keep_existing_outputs);
+        temp_files::registry().set_keep(preferences.keep_scratch_files);
+        download_cache::set_size_cap_mb(preferences.download_cache_cap_mb);
         task_executor.start();
         
         // Start hardware detection immediately
-        log_debug!("Starting hardware detection at program startup...");
-        let (tx, rx) = std::sync::mpsc::channel();
-        
-        std::thread::spawn(move || {
-            let hardware_info = Some(crate::hardware_detector::HardwareDetector::detect_hardware());
-            let encoders = match crate::codec_manager::CodecManager::detect_hardware_encoders() {
-                Ok(encoders) => Some(encoders),
-                Err(_) => Some(Vec::new()),
-            };
-            let _ = tx.send((hardware_info, encoders));
-        });
+        let mut hardware_detection = hardware_refresh::HardwareDetection::default();
+        safe_mode::guarded(safe_mode::Subsystem::HardwareDetection, || hardware_detection.request("startup"));
         
         Self {
             current_operation: None,
@@ -161,7 +376,45 @@ impl Default for FFmpegGui {
             video_settings: VideoSettings::default(),
             audio_settings: AudioSettings::default(),
             tasks: tasks.clone(),
-            tasks_for_ui: Vec::new(),
+            task_view: TaskListView::default(),
+            tasks_generation_seen: 0,
+            last_start_click: None,
+            max_tasks_per_destination: preferences.max_tasks_per_destination,
+            max_concurrent_tasks: preferences.max_concurrent_tasks,
+            suppressed_advisories: preferences.suppressed_advisories.clone(),
+            encoder_policy: preferences.encoder_policy,
+            default_output_dir: preferences.default_output_dir.clone(),
+            output_favorites: preferences.output_favorites.clone(),
+            checksum_outputs: preferences.checksum_outputs,
+            checksum_sidecar: preferences.checksum_sidecar,
+            keep_source_times: preferences.keep_source_times,
+            write_creation_time: preferences.write_creation_time,
+            output_guards: preferences.output_guards.clone(),
+            guard_override: None,
+            checksum_verify: None,
+            compatibility_matrix: None,
+            workflow_diff: None,
+            workflow_library: None,
+            folder_run: None,
+            drop_chooser: None,
+            drop_choices: preferences.drop_choices.clone(),
+            workflow_load_dialog: None,
+            review_clip: None,
+            review_clip_settings: preferences.review_clip.clone(),
+            filename_template: preferences.filename_template.clone(),
+            last_auto_output_file: String::new(),
+            last_auto_frame_dir: String::new(),
+            first_run_wizard: match stored_preferences {
+                // Safe mode runs on defaults without asking
+                _ if safe_mode::is_skipped(safe_mode::Subsystem::Preferences) => None,
+                Some(_) => None,
+                None => Some(first_run::FirstRunWizard::new(&preferences, detected_language.clone())),
+            },
+            pending_encoder_choice: None,
+            pending_parameter_issues: None,
+            session_undo: session_undo::SessionUndo::default(),
+            queue_restore: queue_store::load(),
+            encoder_choice_for_next_task: None,
             next_task_id: 1,
             task_executor: Some(task_executor),
             status_message: translations.ready().to_string(),
@@ -170,13 +423,18 @@ impl Default for FFmpegGui {
             right_panel_width: 300.0,
             current_language: detected_language,
             translations,
-            dark_mode: true,
-            hardware_info: None,
-            cached_hardware_encoders: None,
-            hardware_detection_receiver: Some(rx),
-            hardware_detection_started: true,
+            dark_mode: preferences.dark_mode,
+            hardware_detection,
+            device_failures_checked: std::collections::HashSet::new(),
             command_preview: String::new(),
             show_about_dialog: false,
+            show_run_report: false,
+            workflow_preview: None,
+            show_diagnostics_dialog: false,
+            diagnostics_include_tasks: true,
+            data_batch: None,
+            pending_settings_import: None,
+            settings_import_report: Vec::new(),
             show_compatibility_warning: false,
             compatibility_warning_message: String::new(),
             compatibility_recommended_codec: None,
@@ -187,33 +445,69 @@ impl Default for FFmpegGui {
             
             is_portrait_video: None,
             detected_resolution: None,
+            detected_duration: None,
+            detected_programs: Vec::new(),
+            detected_chapters: Vec::new(),
+            audio_content_probe: None,
+            detected_media_info: None,
+            file_probe: None,
+            timeline_state: timeline::TimelineState::default(),
+            social_export: social_presets::SocialExportPanel::default(),
+            subtitle_preview_time: 10.0,
+            subtitle_preview_texture: None,
+            subtitle_preview_receiver: None,
+            subtitle_preview_error: None,
             
             last_analyzed_file: String::new(),
-            file_info_cache: String::new(),
+            merge_input_durations: std::collections::HashMap::new(),
             
             show_automation_editor: false,
-            current_workflow: None,
-            workflow_executor: automation_flow::WorkflowExecutor::new(),
-            selected_node: None,
-            dragging_node: None,
-            dragging_offset: egui::Vec2::ZERO,
-            workflow_canvas_offset: egui::Vec2::ZERO,
-            workflow_canvas_zoom: 1.0,
+            workflow_executor: {
+                let mut executor = automation_flow::WorkflowExecutor::new();
+                executor.incremental = preferences.incremental_workflow_execution;
+                executor.keep_files_on_cancel = preferences.keep_stopped_workflow_files;
+                executor
+            },
+            workflow_run: None,
+            workflow_tabs: vec![workflow_tabs::WorkflowEditorState::new(automation_flow::AutomationWorkflow::new("New Workflow".to_string()))],
+            active_workflow_tab: 0,
+            pending_workflow_tab_close: None,
             
             // Workflow window state
             workflow_window_pos: None,
             workflow_window_size: None,
-            workflow_window_context: None,
+            workflow_viewport_rect: None,
+            workflow_viewport_target: None,
+            workflow_viewport_placed: false,
+            workflow_viewport_ppp: None,
             open_workflow_window_requested: false,
             workflow_window_open: false,
+            force_embedded_workflow_editor: preferences.force_embedded_workflow_editor,
+            power_policy: preferences.power_policy,
+            power_source: power::PowerSource::Unknown,
+            power_receiver: power::start_monitor(),
+            schedule_policy: preferences.schedule_policy.clone(),
+            active_schedule_profile: None,
+            schedule_override_until: None,
+            schedule_checked: None,
+            keep_scratch_files: preferences.keep_scratch_files,
+            keep_existing_outputs: preferences.keep_existing_outputs,
+            download_cache_cap_mb: preferences.download_cache_cap_mb,
+            workflow_autosave: preferences.workflow_autosave,
+            workflow_autosave_secs: preferences.workflow_autosave_secs,
+            last_workflow_autosave: std::time::Instant::now(),
+            workflow_autosave_due: false,
+            workflow_autosave_written: false,
+            workflow_recovery: workflow_autosave::load(),
+            watch_folder: preferences.watch_folder.clone(),
+            watch_folder_enabled: preferences.watch_folder_enabled,
+            folder_watcher: None,
+            watch_folder_error: None,
+            show_watch_folder: false,
+            show_download_cache: false,
+            workflow_viewport_failed: false,
+            show_embedded_editor_notice: false,
             
-            creating_connection: false,
-            connection_source_node: None,
-            connection_source_port: None,
-            connection_target_pos: egui::Pos2::ZERO,
-            
-            workflow_history: Vec::new(),
-            history_index: -1,
             max_history_size: 50,
         }
     }
@@ -221,36 +515,41 @@ impl Default for FFmpegGui {
 
 impl FFmpegGui {
     fn get_cached_hardware_encoders(&mut self) -> Vec<String> {
-        // Check if async task is completed (detection started at program startup)
-        if let Some(receiver) = &self.hardware_detection_receiver {
-            if let Ok((hardware_info, encoders)) = receiver.try_recv() {
-                self.hardware_info = hardware_info;
-                self.cached_hardware_encoders = encoders.clone();
-                self.hardware_detection_receiver = None;
-                
-                
-                // Update workflow executor hardware cache
-                if let Some(ref encoders) = encoders {
-                    self.workflow_executor.update_hardware_cache(encoders.clone());
-                    log_debug!("Hardware detection completed - updated workflow executor cache with {} encoders", encoders.len());
-                } else {
-                    log_debug!("Hardware detection completed - no encoders detected");
-                }
-            }
+        // Check if the background detection finished (started at program startup or by a re-detect)
+        if let Some(encoders) = self.hardware_detection.poll() {
+            crate::environment::set_detected_gpu(
+                self.hardware_detection.hardware_info.as_ref().and_then(|info| info.gpus.first()).map(|gpu| gpu.name.clone())
+            );
+            self.workflow_executor.update_hardware_cache(encoders.clone());
+            log_debug!("Hardware detection completed - updated workflow executor cache with {} encoders", encoders.len());
         }
         
         // Return empty vector if detection not yet complete
-        self.cached_hardware_encoders.clone().unwrap_or_else(Vec::new)
+        self.hardware_detection.encoders.clone().unwrap_or_default()
     }
     
-    fn refresh_hardware_cache(&mut self) {
-        self.hardware_info = None;
-        self.cached_hardware_encoders = None;
-        self.hardware_detection_started = false;
-        self.hardware_detection_receiver = None;
-        
-        // Clear workflow executor hardware cache
-        self.workflow_executor.update_hardware_cache(Vec::new());
+    /// Re-detect hardware and encoders, e.g. after a driver install; the previous results stay in use
+    /// until detection finishes
+    fn refresh_hardware_cache(&mut self, reason: &str) {
+        if !self.hardware_detection.request(reason) {
+            log_debug!("Hardware re-detection ({}) not started now: already running or debounced", reason);
+        }
+    }
+    
+    /// Re-detect hardware when a hardware encode failed because its driver or device is missing
+    fn check_hardware_device_failures(&mut self) {
+        let mut device_failure = false;
+        if let Ok(tasks) = self.tasks.try_lock() {
+            for task in tasks.iter().filter(|task| task.status == TaskStatus::Failed) {
+                if self.device_failures_checked.insert(task.id) && hardware_refresh::is_device_failure(task) {
+                    log_warn!("Task {} failed with a hardware device error", task.id);
+                    device_failure = true;
+                }
+            }
+        }
+        if device_failure {
+            self.refresh_hardware_cache("hardware encoder device error");
+        }
     }
 }
 
@@ -264,6 +563,14 @@ impl eframe::App for FFmpegGui {
             }
         });
         
+        // Ctrl+Z undoes main-window actions unless a text field or the embedded workflow editor
+        // (which has its own undo) takes it; the editor viewport gets its own input
+        let editor_has_keys = self.workflow_window_open && self.workflow_editor_embedded();
+        if !ctx.wants_keyboard_input() && !editor_has_keys
+            && ctx.input(|i| i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(egui::Key::Z)) {
+            self.undo_session_action();
+        }
+        
         if self.dark_mode {
             ctx.set_visuals(egui::Visuals::dark());
         } else {
@@ -273,17 +580,35 @@ impl eframe::App for FFmpegGui {
         self.handle_drag_and_drop(ctx);
         
         self.sync_tasks();
+        self.poll_file_probe();
+        self.poll_audio_content_probe();
+        self.poll_power_source();
+        self.poll_schedule(ctx);
+        
+        // Pick up hardware detection results as soon as they arrive
+        if self.hardware_detection.is_running() || self.hardware_detection.has_queued_request() {
+            self.get_cached_hardware_encoders();
+        }
+        if let Some(remaining) = self.hardware_detection.cooldown_remaining().filter(|_| self.hardware_detection.has_queued_request()) {
+            ctx.request_repaint_after(remaining);
+        }
         
         // Delayed hardware detection initialization to avoid blocking at startup
         // Hardware detection will be performed when first needed
-        // if self.cached_hardware_encoders.is_none() {
+        // if self.hardware_detection.encoders.is_none() {
         //     self.get_cached_hardware_encoders();
         // }
         
         // Check for container format changes and reset incompatible codecs
         self.check_and_reset_incompatible_codecs();
         
-        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        // Only repaint frequently while something is actually changing; otherwise
+        // rely on input-driven repaints plus a slow heartbeat
+        if self.needs_fast_repaint() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(1500));
+        }
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -328,10 +653,51 @@ impl eframe::App for FFmpegGui {
                         ui.close_menu();
                     }
                     
+                    if ui.button(self.translations.workflow_library_save_to_library()).clicked() {
+                        self.save_workflow_to_library();
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button(self.translations.workflow_library_menu()).clicked() {
+                        self.workflow_library = Some(workflow_library::LibraryWindow::new());
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button(self.translations.folder_run_menu()).clicked() {
+                        self.folder_run.get_or_insert_with(folder_run::FolderRunDialog::default);
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button(self.translations.watch_folder_menu()).clicked() {
+                        self.show_watch_folder = true;
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button(self.translations.workflow_script_menu()).clicked() {
+                        self.export_workflow_script();
+                        ui.close_menu();
+                    }
+                    
+                    let has_saved_file = self.editor().file_path.is_some();
+                    if ui.add_enabled(has_saved_file, egui::Button::new(self.translations.workflow_diff_compare_saved())).clicked() {
+                        self.compare_workflow_with_saved();
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.workflow_diff_compare_files()).clicked() {
+                        self.compare_workflow_files();
+                        ui.close_menu();
+                    }
+                    
                     ui.separator();
                     
                     if ui.button("🚀 Execute Workflow").clicked() {
-                        self.execute_workflow();
+                        self.execute_workflow(false);
+                        ui.close_menu();
+                    }
+                    
+                    let has_report = self.workflow_executor.last_run_report.is_some();
+                    if ui.add_enabled(has_report, egui::Button::new(self.translations.view_last_run_report())).clicked() {
+                        self.show_run_report = true;
                         ui.close_menu();
                     }
                 });
@@ -344,14 +710,169 @@ impl eframe::App for FFmpegGui {
                     };
                     if ui.button(format!("🌙 {}", theme_text)).clicked() {
                         self.dark_mode = !self.dark_mode;
+                        self.save_preferences();
+                        ui.close_menu();
+                    }
+                    
+                    ui.separator();
+                    
+                    ui.horizontal(|ui| {
+                        ui.label(self.translations.max_concurrent_tasks());
+                        let response = ui.add(egui::Slider::new(&mut self.max_concurrent_tasks, 1..=task_executor::MAX_CONCURRENT_TASKS_LIMIT))
+                            .on_hover_text(self.translations.max_concurrent_tasks_hint());
+                        if response.changed() {
+                            self.apply_max_concurrent_tasks();
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label(self.translations.max_tasks_per_destination());
+                        let unlimited = self.translations.unlimited();
+                        let response = ui.add(egui::DragValue::new(&mut self.max_tasks_per_destination)
+                            .range(0..=16)
+                            .custom_formatter(|v, _| if v == 0.0 { unlimited.to_string() } else { format!("{}", v) }));
+                        if response.changed() {
+                            self.apply_max_tasks_per_destination();
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.menu_button(self.translations.encoding_advisories(), |ui| {
+                        for rule in encode_advisor::AdvisoryRule::all() {
+                            let mut shown = !self.suppressed_advisories.contains(rule.key());
+                            if ui.checkbox(&mut shown, self.translations.advisory_rule_name(rule)).changed() {
+                                if shown {
+                                    self.suppressed_advisories.remove(rule.key());
+                                } else {
+                                    self.suppressed_advisories.insert(rule.key().to_string());
+                                }
+                                self.save_preferences();
+                            }
+                        }
+                    });
+                    
+                    if ui.checkbox(&mut self.force_embedded_workflow_editor, self.translations.embedded_workflow_editor()).changed() {
+                        self.save_preferences();
+                    }
+                    
+                    if ui.checkbox(&mut self.keep_existing_outputs, self.translations.keep_existing_outputs())
+                        .on_hover_text(self.translations.keep_existing_outputs_hint())
+                        .changed()
+                    {
+                        self.apply_keep_existing_outputs();
+                        self.save_preferences();
+                    }
+                    
+                    ui.menu_button(self.translations.checksum_menu(), |ui| {
+                        let mut changed = ui.checkbox(&mut self.checksum_outputs, self.translations.checksum_compute())
+                            .on_hover_text(self.translations.checksum_compute_hint())
+                            .changed();
+                        ui.add_enabled_ui(self.checksum_outputs, |ui| {
+                            changed |= ui.checkbox(&mut self.checksum_sidecar, self.translations.checksum_sidecar()).changed();
+                        });
+                        if changed {
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.menu_button(self.translations.file_times_menu(), |ui| {
+                        let mut changed = ui.checkbox(&mut self.keep_source_times, self.translations.file_times_copy_times())
+                            .on_hover_text(self.translations.file_times_copy_times_hint())
+                            .changed();
+                        changed |= ui.checkbox(&mut self.write_creation_time, self.translations.file_times_creation_time())
+                            .on_hover_text(self.translations.file_times_creation_time_hint())
+                            .changed();
+                        if changed {
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.menu_button(self.translations.output_guard_menu(), |ui| {
+                        ui.label(self.translations.output_guard_menu_hint());
+                        if output_guard::show_editor(ui, &mut self.output_guards, &self.translations) {
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    if ui.checkbox(&mut self.keep_scratch_files, self.translations.keep_scratch_files())
+                        .on_hover_text(self.translations.keep_scratch_files_hint())
+                        .changed()
+                    {
+                        temp_files::registry().set_keep(self.keep_scratch_files);
+                        self.save_preferences();
+                    }
+                    
+                    if ui.button(self.translations.download_cache_menu()).clicked() {
+                        self.show_download_cache = true;
+                        ui.close_menu();
+                    }
+                    
+                    ui.menu_button(self.translations.workflow_autosave_menu(), |ui| {
+                        let mut changed = ui.checkbox(&mut self.workflow_autosave, self.translations.workflow_autosave_enabled())
+                            .on_hover_text(self.translations.workflow_autosave_enabled_hint())
+                            .changed();
+                        ui.add_enabled_ui(self.workflow_autosave, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(self.translations.workflow_autosave_interval());
+                                changed |= ui.add(egui::DragValue::new(&mut self.workflow_autosave_secs).range(5..=3600).suffix(" s")).changed();
+                            });
+                        });
+                        if changed {
+                            self.save_preferences();
+                        }
+                        ui.separator();
+                        if ui.add_enabled(workflow_autosave::exists() && self.workflow_recovery.is_none(), egui::Button::new(self.translations.workflow_autosave_discard_file()))
+                            .on_hover_text(self.translations.workflow_autosave_discard_file_hint())
+                            .clicked()
+                        {
+                            workflow_autosave::discard();
+                            self.workflow_autosave_written = false;
+                            self.status_message = self.translations.workflow_autosave_discarded().to_string();
+                            ui.close_menu();
+                        }
+                    });
+                    
+                    ui.menu_button(self.translations.power_rule_menu(), |ui| {
+                        let mut changed = ui.checkbox(&mut self.power_policy.enabled, self.translations.power_rule_enabled()).changed();
+                        ui.add_enabled_ui(self.power_policy.enabled, |ui| {
+                            changed |= ui.checkbox(&mut self.power_policy.lower_priority, self.translations.power_rule_lower_priority()).changed();
+                            changed |= ui.checkbox(&mut self.power_policy.cap_threads, self.translations.power_rule_cap_threads()).changed();
+                            changed |= ui.checkbox(&mut self.power_policy.prefer_hardware, self.translations.power_rule_prefer_hardware())
+                                .on_hover_text(self.translations.power_rule_prefer_hardware_hint())
+                                .changed();
+                            changed |= ui.checkbox(&mut self.power_policy.pause_queue, self.translations.power_rule_pause_queue()).changed();
+                        });
+                        ui.separator();
+                        ui.weak(self.translations.power_source_name(self.power_source));
+                        if changed {
+                            self.apply_power_policy();
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.menu_button(self.translations.schedule_menu(), |ui| {
+                        if schedule::show_editor(ui, &mut self.schedule_policy, &self.translations) {
+                            self.refresh_schedule();
+                            self.save_preferences();
+                        }
+                    });
+                    
+                    ui.separator();
+                    
+                    if ui.button(self.translations.export_settings_bundle()).clicked() {
+                        self.export_settings_bundle();
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.import_settings_bundle()).clicked() {
+                        self.import_settings_bundle();
                         ui.close_menu();
                     }
                     
                     ui.separator();
                     
                     if ui.button(self.translations.reset_all_settings()).clicked() {
-                        self.video_settings = VideoSettings::default();
-                        self.audio_settings = AudioSettings::default();
+                        self.reset_settings();
                         ui.close_menu();
                     }
                 });
@@ -362,19 +883,37 @@ impl eframe::App for FFmpegGui {
                         let is_current = lang == self.current_language;
                         if ui.selectable_label(is_current, lang.display_name()).clicked() {
                             if !is_current {
-                                self.current_language = lang.clone();
-                                self.translations = Translations::new(lang);
-                                self.status_message = self.translations.ready().to_string();
+                                self.set_language(lang);
+                                self.save_preferences();
                                 language_changed = true;
                             }
                         }
                     }
                     if language_changed {
+                        // The workflow editor viewport reads the same translations; redraw it right away
+                        ui.ctx().request_repaint_of(egui::ViewportId::from_hash_of("workflow_editor"));
                         ui.close_menu();
                     }
                 });
                 
                 ui.menu_button(self.translations.menu_help(), |ui| {
+                    if ui.button(self.translations.first_run_rerun()).clicked() {
+                        let language = self.current_language.clone();
+                        self.first_run_wizard = Some(first_run::FirstRunWizard::new(&self.current_preferences(), language));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.checksum_verify_menu()).clicked() {
+                        self.checksum_verify.get_or_insert_with(checksum::VerifyDialog::default);
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.compatibility_menu()).clicked() {
+                        self.compatibility_matrix.get_or_insert_with(compatibility_matrix::MatrixWindow::default);
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.diagnostics_menu()).clicked() {
+                        self.show_diagnostics_dialog = true;
+                        ui.close_menu();
+                    }
                     if ui.button(self.translations.about()).clicked() {
                         self.show_about_dialog = true;
                         ui.close_menu();
@@ -387,18 +926,73 @@ impl eframe::App for FFmpegGui {
             });
         });
 
+        if let Some(subsystem) = safe_mode::show_banner(ctx, &self.translations) {
+            self.re_enable_subsystem(subsystem);
+        }
+
         egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(format!("{}: {}", 
-                    self.translations.active_tasks(),
-                    self.tasks_for_ui.iter().filter(|t| t.status == TaskStatus::Running).count()));
+                let counts = self.task_view.counts;
+                ui.label(self.translations.running_of_limit(counts.running, self.max_concurrent_tasks));
                 ui.separator();
-                ui.label(format!("{}: {}", self.translations.total_tasks(), self.tasks_for_ui.len()));
+                ui.label(format!("{}: {}", self.translations.total_tasks(), counts.total));
+                if counts.total > 0 {
+                    ui.separator();
+                    ui.label(self.translations.queue_progress());
+                    let progress = counts.overall_progress();
+                    ui.add(egui::ProgressBar::new(progress)
+                        .desired_width(160.0)
+                        .text(format!("{:.1}% ({}/{})", progress * 100.0, counts.completed + counts.failed, counts.total)));
+                }
+                
+                let constraints = self.power_constraints();
+                if constraints.is_constrained() {
+                    ui.separator();
+                    let text = if constraints.pause_queue {
+                        self.translations.power_rule_status_paused()
+                    } else {
+                        self.translations.power_rule_status_limited()
+                    };
+                    ui.label(text).on_hover_text(self.translations.power_source_name(self.power_source));
+                }
+                
+                if self.schedule_policy.enabled {
+                    ui.separator();
+                    match self.schedule_override_until {
+                        Some(until) => {
+                            ui.label(self.translations.schedule_ignored_until(&until.format("%H:%M").to_string()));
+                            if ui.small_button(self.translations.schedule_resume()).clicked() {
+                                self.schedule_override_until = None;
+                                self.refresh_schedule();
+                            }
+                        }
+                        None => {
+                            if let Some(name) = &self.active_schedule_profile {
+                                let icon = if self.schedule_constraints().pause_queue { "🕘 ⏸" } else { "🕘" };
+                                ui.label(format!("{} {}", icon, name)).on_hover_text(self.translations.schedule_status_hint());
+                            }
+                            if ui.small_button(self.translations.schedule_ignore()).clicked() {
+                                let until = chrono::Local::now() + schedule::OVERRIDE_DURATION;
+                                log_info!("Schedule ignored until {}", until.format("%H:%M"));
+                                self.schedule_override_until = Some(until);
+                                self.refresh_schedule();
+                            }
+                        }
+                    }
+                }
+                
+                if self.watch_folder_enabled {
+                    ui.separator();
+                    self.show_watch_folder_status(ui);
+                }
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button(self.translations.clear_completed_tasks()).clicked() {
                         self.clear_completed_tasks();
                     }
+                    if counts.failed > 0 && ui.button(self.translations.task_retry_retry_all()).clicked() {
+                        apply_task_actions(&self.tasks, &[TaskAction::RetryAllFailed]);
+                    }
                 });
             });
         });
@@ -433,19 +1027,8 @@ impl eframe::App for FFmpegGui {
                     ui.add_space(4.0);
                     ui.separator();
                     
-                    egui::ScrollArea::vertical()
-                        .id_salt("task_scroll") 
-                        .auto_shrink([false, false]) 
-                        .show(ui, |ui| {
-                            let old_len = self.tasks_for_ui.len();
-                            TaskPanel::show(ui, &mut self.tasks_for_ui, &self.translations);
-                            
-                            if self.tasks_for_ui.len() != old_len {
-                                if let Ok(mut tasks_guard) = self.tasks.try_lock() {
-                                    *tasks_guard = self.tasks_for_ui.clone();
-                                }
-                            }
-                        });
+                    let actions = TaskPanel::show(ui, &self.tasks, &mut self.task_view, tasks_generation(), &self.translations);
+                    apply_task_actions(&self.tasks, &actions);
                 });
             });
         
@@ -477,24 +1060,49 @@ impl eframe::App for FFmpegGui {
                                 .to_lowercase();
                             
                             if !output_ext.is_empty() {
+                                let before = self.session_snapshot();
+                                let mut applied = None;
                                 ui.collapsing(self.translations.smart_recommendations(), |ui| {
-                                    SettingsPanel::show_smart_recommendations(
+                                    if SettingsPanel::show_smart_recommendations(
                                         ui, 
                                         &output_ext, 
                                         &mut self.video_settings, 
                                         &mut self.audio_settings,
                                         &self.translations
-                                    );
+                                    ) {
+                                        applied = Some(session_undo::UndoAction::ApplyRecommendation);
+                                    }
                                 });
                                 
                                 ui.collapsing(self.translations.encoding_presets(), |ui| {
-                                    SettingsPanel::show_preset_selector(
+                                    if SettingsPanel::show_preset_selector(
                                         ui, 
                                         &mut self.video_settings, 
                                         &mut self.audio_settings,
+                                        self.detected_media_info.as_ref(),
                                         &self.translations
-                                    );
+                                    ) {
+                                        applied = Some(session_undo::UndoAction::ApplyPreset);
+                                    }
+                                });
+                                
+                                ui.collapsing(self.translations.social_presets_title(), |ui| {
+                                    if self.social_export.show(
+                                        ui,
+                                        self.input_files.first().map(String::as_str),
+                                        self.detected_resolution,
+                                        self.is_portrait_video,
+                                        self.detected_duration,
+                                        &mut self.video_settings,
+                                        &mut self.audio_settings,
+                                        &self.translations
+                                    ) {
+                                        applied = Some(session_undo::UndoAction::ApplyPreset);
+                                    }
                                 });
+                                if let Some(action) = applied {
+                                    self.session_undo.push(action, before);
+                                }
                             }
                         }
                     }
@@ -515,6 +1123,18 @@ impl eframe::App for FFmpegGui {
                                 .show(ui, |ui| {
                                     ui.monospace(&self.file_info);
                                 });
+                            let audio_only = self.detected_media_info.as_ref()
+                                .is_some_and(|media| media.video_streams.is_empty() && !media.audio_streams.is_empty());
+                            if audio_only && self.input_files.len() == 1 {
+                                ui.separator();
+                                audio_preview::show_player(ui, &self.input_files[0], self.detected_duration, &self.translations);
+                            }
+                            let has_video = self.detected_media_info.as_ref().is_some_and(|media| !media.video_streams.is_empty());
+                            if let Some(duration) = self.detected_duration.filter(|_| has_video && self.input_files.len() == 1) {
+                                if ui.button(self.translations.review_clip_button()).clicked() {
+                                    self.review_clip = Some(review_clip::ReviewClipDialog::new(&self.input_files[0], duration, &self.review_clip_settings));
+                                }
+                            }
                         });
                         ui.separator();
                     }
@@ -572,6 +1192,7 @@ impl eframe::App for FFmpegGui {
             });
         });
 
+        let mut redetect_hardware = false;
         if self.show_about_dialog {
             egui::Window::new(self.translations.about())
                 .collapsible(false)
@@ -600,6 +1221,35 @@ impl eframe::App for FFmpegGui {
                         ui.add_space(10.0);
                         
                         ui.label(self.translations.built_with_ffmpeg());
+                        let (hits, misses, cached) = probe_cache::probe_cache().stats();
+                        ui.weak(self.translations.probe_cache_stats(hits, misses, cached));
+                        ui.horizontal(|ui| {
+                            let detection = &self.hardware_detection;
+                            let status = match detection.state() {
+                                hardware_refresh::DetectionState::Ready => self.translations.hardware_encoder_count(
+                                    detection.encoders.as_ref().map_or(0, |encoders| encoders.len())),
+                                state => self.translations.hardware_detection_state(state).to_string(),
+                            };
+                            ui.weak(status);
+                            let button = ui.add_enabled(!detection.is_running(), egui::Button::new(self.translations.redetect_hardware()).small());
+                            let button = match detection.cooldown_remaining().filter(|_| detection.has_queued_request()) {
+                                Some(remaining) => button.on_hover_text(self.translations.redetect_queued(remaining.as_secs() + 1)),
+                                None => button,
+                            };
+                            if button.clicked() {
+                                redetect_hardware = true;
+                            }
+                        });
+                        let stats = &self.workflow_executor.session_stats;
+                        if stats.workflow_runs > 0 {
+                            ui.weak(self.translations.workflow_session_stats(
+                                stats.workflow_runs,
+                                stats.failed_runs,
+                                stats.nodes_executed,
+                                stats.total_wall_time_ms,
+                                &workflow_report::format_bytes(stats.artifact_bytes),
+                            ));
+                        }
                         ui.label(self.translations.developed_with_rust());
                         ui.label(self.translations.third_party_notice());
                         
@@ -628,6 +1278,35 @@ impl eframe::App for FFmpegGui {
                     });
                 });
         }
+        if redetect_hardware {
+            self.refresh_hardware_cache("re-detect button");
+        }
+
+        self.show_settings_import_dialog(ctx);
+        self.poll_workflow_run();
+        self.show_run_report_dialog(ctx);
+        self.show_workflow_plan_dialog(ctx);
+        self.show_diagnostics_dialog(ctx);
+        self.show_checksum_verify_dialog(ctx);
+        self.show_compatibility_matrix(ctx);
+        self.show_workflow_diff(ctx);
+        self.show_workflow_library(ctx);
+        self.show_folder_run_dialog(ctx);
+        self.show_watch_folder_window(ctx);
+        self.show_download_cache_window(ctx);
+        self.show_review_clip_dialog(ctx);
+        self.show_workflow_load_dialog(ctx);
+        self.show_drop_chooser(ctx);
+        self.show_data_batch_dialog(ctx);
+        self.show_encoder_choice_dialog(ctx);
+        self.show_parameter_issues_dialog(ctx);
+        if self.session_undo.show_toast(ctx, &self.translations) {
+            self.undo_session_action();
+        }
+        self.show_first_run_wizard(ctx);
+        self.show_queue_restore_dialog(ctx);
+        self.show_workflow_recovery_dialog(ctx);
+        self.autosave_workflows();
 
         if self.show_compatibility_warning {
             let window_title = if self.translations.language == crate::language::Language::Chinese {
@@ -743,15 +1422,20 @@ impl eframe::App for FFmpegGui {
             self.open_workflow_window(ctx);
         }
         
-        // Display persistent workflow window
+        // Display the workflow editor, in its own viewport unless that is unavailable or disabled
         if self.workflow_window_open {
-            self.show_persistent_workflow_window(ctx);
+            if self.workflow_editor_embedded() {
+                self.show_automation_editor_window(ctx);
+            } else {
+                self.show_persistent_workflow_window(ctx);
+            }
         }
         
-        // Original embedded workflow editor has been replaced with standalone window
-        // if self.show_automation_editor {
-        //     self.show_automation_editor_window(ctx);
-        // }
+        self.show_embedded_editor_notice_window(ctx);
+    }
+    
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_task_queue();
     }
 }
 
@@ -760,12 +1444,12 @@ impl FFmpegGui {
         match operation {
             OperationType::VideoConvert | OperationType::VideoCompress | 
             OperationType::VideoResize | OperationType::VideoCrop | 
-            OperationType::VideoRotate | OperationType::VideoFilter |
+            OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
             OperationType::ExtractVideo => "video".to_string(),
             
             OperationType::AudioConvert | OperationType::AudioCompress | 
             OperationType::AudioResample | OperationType::AudioVolume | 
-            OperationType::AudioTrim | OperationType::AudioMerge |
+            OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize |
             OperationType::ExtractAudio => "audio".to_string(),
             
             OperationType::VideoAudioMerge | OperationType::VideoAudioSplit => "video_audio".to_string(),
@@ -839,18 +1523,40 @@ impl FFmpegGui {
     }
 
     fn sync_tasks(&mut self) {
+        // Only re-index when the executor or UI reported a change; tasks are never cloned
+        let generation = tasks_generation();
+        if generation == self.tasks_generation_seen {
+            return;
+        }
         if let Ok(tasks_guard) = self.tasks.try_lock() {
-            self.tasks_for_ui = tasks_guard.clone();
+            self.task_view.refresh(&tasks_guard, generation);
+            self.tasks_generation_seen = generation;
         }
+        if self.task_view.counts.failed > 0 {
+            self.check_hardware_device_failures();
+        }
+    }
+    
+    /// Whether the UI needs frequent repaints (running tasks, workflow execution, pending detection, interaction).
+    /// Pending tasks only count while the queue may start them; a paused queue waits on the slow heartbeat.
+    fn needs_fast_repaint(&self) -> bool {
+        self.task_view.counts.running > 0
+            || (self.task_view.counts.pending > 0 && !self.queue_constraints().pause_queue)
+            || self.workflow_executor.execution_status == automation_flow::ExecutionStatus::Running
+            || self.hardware_detection.is_running()
+            || checksum::hashing_active()
+            || audio_preview::is_active()
+            || self.audio_content_probe.is_some()
+            || self.editor().dragging_node.is_some()
+            || self.editor().creating_connection
     }
     
     fn clear_completed_tasks(&mut self) {
-        if let Ok(mut tasks_guard) = self.tasks.try_lock() {
-            tasks_guard.retain(|t| t.status != TaskStatus::Completed && t.status != TaskStatus::Failed);
-        }
+        apply_task_actions(&self.tasks, &[TaskAction::ClearFinished]);
     }
     
     fn reset_project(&mut self) {
+        self.session_undo.push(session_undo::UndoAction::ResetProject, self.session_snapshot());
         self.current_operation = None;
         self.input_files.clear();
         self.output_file.clear();
@@ -860,20 +1566,45 @@ impl FFmpegGui {
         self.status_message = self.translations.project_reset().to_string();
     }
     
+    fn reset_settings(&mut self) {
+        self.session_undo.push(session_undo::UndoAction::ResetSettings, self.session_snapshot());
+        self.video_settings = VideoSettings::default();
+        self.audio_settings = AudioSettings::default();
+    }
+    
+    /// The state a project file stores and undo restores
+    fn session_snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            current_operation: self.current_operation.clone(),
+            input_files: self.input_files.clone(),
+            output_file: self.output_file.clone(),
+            video_settings: self.video_settings.clone(),
+            audio_settings: self.audio_settings.clone(),
+        }
+    }
+    
+    fn restore_session_snapshot(&mut self, snapshot: SessionSnapshot) {
+        self.current_operation = snapshot.current_operation;
+        self.input_files = snapshot.input_files;
+        self.output_file = snapshot.output_file;
+        self.video_settings = snapshot.video_settings;
+        self.audio_settings = snapshot.audio_settings;
+    }
+    
+    fn undo_session_action(&mut self) {
+        if let Some((action, snapshot)) = self.session_undo.pop() {
+            self.restore_session_snapshot(snapshot);
+            self.status_message = self.translations.undo_restored(action);
+        }
+    }
+    
     fn save_project_as(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("FF GUI Project", &["ffcfg"])
             .set_title("Save Project As")
             .save_file() {
                 
-            let config = ProjectConfig::from_app_state(
-                self.current_operation.clone(),
-                self.input_files.clone(),
-                self.output_file.clone(),
-                self.video_settings.clone(),
-                self.audio_settings.clone(),
-                None,
-            );
+            let config = ProjectConfig::from_snapshot(self.session_snapshot(), None);
             
             match config.save_to_file(&path.display().to_string()) {
                 Ok(_) => {
@@ -894,11 +1625,7 @@ impl FFmpegGui {
                 
             match ProjectConfig::load_from_file(&path.display().to_string()) {
                 Ok(config) => {
-                    self.current_operation = config.current_operation;
-                    self.input_files = config.input_files;
-                    self.output_file = config.output_file;
-                    self.video_settings = config.video_settings;
-                    self.audio_settings = config.audio_settings;
+                    self.restore_session_snapshot(config.into_snapshot());
                     self.status_message = self.translations.project_loaded().to_string();
                 },
                 Err(e) => {
@@ -908,1907 +1635,3765 @@ impl FFmpegGui {
         }
     }
     
-    fn show_file_selection(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
-        // For subtitle and watermark operations, show completely separate video file selector
-        match operation {
-            OperationType::AddSubtitle | OperationType::AddWatermark => {
-                // Show dedicated video file selector with drag-and-drop support
-                ui.group(|ui| {
-                    ui.label("📹 Video File (Required):");
-                    
-                    // Create drag-and-drop area for video file
-                    let video_area_height = 60.0;
-                    let (rect, response) = ui.allocate_exact_size(
-                        egui::vec2(ui.available_width(), video_area_height),
-                        egui::Sense::click()
-                    );
-                    
-                    // Handle drag and drop for video file
-                    let is_being_dragged = !ui.ctx().input(|i| i.raw.hovered_files.is_empty());
-                    let can_accept_drop = is_being_dragged && ui.rect_contains_pointer(rect);
-                    
-                    // Draw the drop area
-                    let bg_color = if can_accept_drop {
-                        egui::Color32::from_rgba_unmultiplied(100, 200, 100, 100)
-                    } else if self.dedicated_video_file.is_empty() {
-                        egui::Color32::from_rgba_unmultiplied(100, 100, 100, 50)
-                    } else {
-                        egui::Color32::from_rgba_unmultiplied(50, 150, 50, 50)
-                    };
-                    
-                    ui.painter().rect_filled(rect, 8.0, bg_color);
-                    ui.painter().rect_stroke(rect, 8.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
-                    
-                    // Handle click to open file dialog
-                    if response.clicked() {
-                        if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("Video files", &["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"])
-                            .pick_file() {
-                            self.dedicated_video_file = path.display().to_string();
-                        }
+    /// Render a single frame with the shifted subtitle burned in to check the alignment
+    fn show_subtitle_preview(&mut self, ui: &mut egui::Ui) {
+        let is_chinese = self.translations.language == Language::Chinese;
+        
+        if let Some(receiver) = &self.subtitle_preview_receiver {
+            if let Ok(result) = receiver.try_recv() {
+                match result {
+                    Ok(image) => {
+                        self.subtitle_preview_texture = Some(ui.ctx().load_texture("subtitle_preview", image, egui::TextureOptions::LINEAR));
+                        self.subtitle_preview_error = None;
                     }
-                    
-                    // Process dropped files for video
-                    if !ui.ctx().input(|i| i.raw.dropped_files.is_empty()) {
-                        let dropped_files = ui.ctx().input(|i| i.raw.dropped_files.clone());
-                        if let Some(file) = dropped_files.first() {
-                            if let Some(path) = &file.path {
-                                let ext = path.extension()
-                                    .and_then(|e| e.to_str())
-                                    .unwrap_or("")
-                                    .to_lowercase();
-                                if ["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"].contains(&ext.as_str()) {
-                                    self.dedicated_video_file = path.display().to_string();
-                                    // Auto-generate output file name based on video file
-                                    self.generate_output_filename_from_video();
+                    Err(e) => self.subtitle_preview_error = Some(e),
+                }
+                self.subtitle_preview_receiver = None;
+            }
+        }
+        
+        ui.group(|ui| {
+            ui.label(if is_chinese { "🔍 字幕对齐预览" } else { "🔍 Subtitle Alignment Preview" });
+            ui.horizontal(|ui| {
+                ui.label(if is_chinese { "时间点 (秒):" } else { "Timestamp (s):" });
+                ui.add(egui::DragValue::new(&mut self.subtitle_preview_time).speed(0.5).range(0.0..=86_400.0));
+                
+                let can_render = !self.dedicated_video_file.is_empty()
+                    && !self.video_settings.subtitle_file.is_empty()
+                    && self.subtitle_preview_receiver.is_none();
+                if ui.add_enabled(can_render, egui::Button::new(if is_chinese { "渲染预览帧" } else { "Render preview frame" })).clicked() {
+                    let video_file = self.dedicated_video_file.clone();
+                    let settings = self.video_settings.clone();
+                    let time = self.subtitle_preview_time;
+                    let ctx = ui.ctx().clone();
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    std::thread::spawn(move || {
+                        let result = subtitle_timing::prepare_shifted_subtitle(&settings.subtitle_file, settings.subtitle_offset_ms, settings.subtitle_stretch)
+                            .and_then(|subtitle| {
+                                let frame = subtitle_timing::render_preview_frame(&video_file, &subtitle, time);
+                                if subtitle != settings.subtitle_file {
+                                    let _ = std::fs::remove_file(&subtitle);
                                 }
-                            }
-                        }
-                    }
-                    
-                    // Draw text in the drop area
-                    let text = if self.dedicated_video_file.is_empty() {
-                        if can_accept_drop {
-                            "📹 Drop video file here"
-                        } else {
-                            "📹 Click to select video file or drag & drop here"
-                        }
-                    } else {
-                        // Show file name
-                        let file_name = std::path::Path::new(&self.dedicated_video_file)
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("Unknown");
-                        &format!("📹 {}", file_name)
-                    };
-                    
-                    ui.painter().text(
-                        rect.center(),
-                        egui::Align2::CENTER_CENTER,
-                        text,
-                        egui::FontId::default(),
-                        if self.dedicated_video_file.is_empty() { egui::Color32::GRAY } else { egui::Color32::WHITE }
-                    );
-                    
-                    ui.add_space(5.0);
-                    
-                    // Show controls and status
-                    ui.horizontal(|ui| {
-                        if !self.dedicated_video_file.is_empty() {
-                            if ui.button("🚮 Clear").clicked() {
-                                self.dedicated_video_file.clear();
-                            }
-                            if ui.button("📁 Change").clicked() {
-                                if let Some(path) = rfd::FileDialog::new()
-                                    .add_filter("Video files", &["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"])
-                                    .pick_file() {
-                                    self.dedicated_video_file = path.display().to_string();
-                                }
-                            }
-                        }
+                                frame
+                            })
+                            .map_err(|e| e.to_string());
+                        let _ = tx.send(result);
+                        ctx.request_repaint();
                     });
-                    
-                    // Show status for video file
-                    if self.dedicated_video_file.is_empty() {
-                        ui.colored_label(egui::Color32::from_rgb(255, 150, 100), 
-                            "⚠ Please select a video file first");
-                    } else {
-                        let is_video = self.dedicated_video_file.to_lowercase().ends_with(".mp4") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".avi") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".mkv") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".mov") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".webm") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".flv") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".wmv") ||
-                                      self.dedicated_video_file.to_lowercase().ends_with(".m4v");
-                        
-                        if is_video {
-                            ui.colored_label(egui::Color32::from_rgb(100, 200, 100), "✅ Video file ready");
-                        } else {
-                            ui.colored_label(egui::Color32::from_rgb(255, 150, 100), 
-                                "⚠ Selected file is not a video file");
-                        }
-                    }
-                });
-                
-                ui.add_space(10.0);
-                
-                // Show output file selector for these operations
-                OutputSelector::show(
-                    ui, 
-                    &mut self.output_file, 
-                    operation,
-                    Some(&self.video_settings),
-                    Some(&self.audio_settings),
-                    &self.translations
-                );
-                
-                // Don't show regular input files section for these operations
-                return;
-            }
-            _ => {
-                // Normal file selection for other operations
-                let allow_multiple = matches!(operation, 
-                    OperationType::AudioMerge | 
-                    OperationType::BatchConvert | 
-                    OperationType::VideoAudioMerge
-                );
-                
-                let label = match operation {
-                    OperationType::VideoAudioMerge => self.translations.input_files_video_audio(),
-                    OperationType::AudioMerge => self.translations.input_audio_files(),
-                    OperationType::BatchConvert => self.translations.batch_input_files(),
-                    _ => self.translations.input_files(),
-                };
-                
-                let old_files_len = self.input_files.len();
-                let old_first_file = self.input_files.first().cloned();
-                
-                FileSelector::show(ui, &mut self.input_files, allow_multiple, label, &self.translations, || {});
-                
-                // Check if files changed and auto-generate output filename
-                let files_changed = self.input_files.len() != old_files_len || 
-                                   self.input_files.first() != old_first_file.as_ref();
-                
-                if files_changed && !self.input_files.is_empty() {
-                    // Only auto-generate if output is empty or already auto-generated
-                    if self.output_file.is_empty() || self.is_auto_generated_filename(&self.output_file) {
-                        self.generate_output_filename_from_input();
-                    }
+                    self.subtitle_preview_receiver = Some(rx);
+                }
+                if self.subtitle_preview_receiver.is_some() {
+                    ui.spinner();
                 }
+            });
+            
+            if let Some(error) = &self.subtitle_preview_error {
+                ui.colored_label(egui::Color32::RED, error);
             }
+            if let Some(texture) = &self.subtitle_preview_texture {
+                ui.image((texture.id(), texture.size_vec2()));
+            }
+        });
+    }
+    
+    /// Limits the power policy puts on the queue right now
+    fn power_constraints(&self) -> power::PowerConstraints {
+        power::constraints_for(&self.power_policy, self.power_source, power::available_cores())
+    }
+    
+    /// Limits of the schedule profile active now, unless the schedule is being ignored
+    fn schedule_constraints(&self) -> power::PowerConstraints {
+        let now = chrono::Local::now();
+        if self.schedule_override_until.is_some_and(|until| now < until) {
+            return power::PowerConstraints::default();
+        }
+        schedule::active_profile(&self.schedule_policy, now.naive_local())
+            .map(schedule::ScheduleProfile::constraints)
+            .unwrap_or_default()
+    }
+    
+    /// Power and schedule limits together, as the executor applies them
+    fn queue_constraints(&self) -> power::PowerConstraints {
+        self.power_constraints().combine(self.schedule_constraints())
+    }
+    
+    fn apply_power_policy(&self) {
+        if let Some(executor) = &self.task_executor {
+            executor.set_power_constraints(self.queue_constraints());
+        }
+    }
+    
+    /// Re-evaluate the schedule on a timer; repaints are requested so this runs while the window is idle
+    fn poll_schedule(&mut self, ctx: &egui::Context) {
+        if !self.schedule_policy.enabled && self.active_schedule_profile.is_none() {
+            return;
+        }
+        if self.schedule_checked.is_none_or(|checked| checked.elapsed() >= schedule::CHECK_INTERVAL) {
+            self.refresh_schedule();
+        }
+        ctx.request_repaint_after(schedule::CHECK_INTERVAL);
+    }
+    
+    /// Find the active schedule profile and, when it changed, apply its limits and announce the transition
+    fn refresh_schedule(&mut self) {
+        self.schedule_checked = Some(std::time::Instant::now());
+        let now = chrono::Local::now();
+        if self.schedule_override_until.is_some_and(|until| now >= until) {
+            log_info!("Schedule override expired");
+            self.schedule_override_until = None;
+        }
+        let profile = if self.schedule_override_until.is_some() {
+            None
+        } else {
+            schedule::active_profile(&self.schedule_policy, now.naive_local())
+        };
+        let name = profile.map(|profile| profile.name.clone());
+        let paused = profile.is_some_and(|profile| profile.pause_queue);
+        if name == self.active_schedule_profile {
+            // Edits to the active profile still take effect
+            self.apply_power_policy();
+            return;
         }
         
-        if !self.input_files.is_empty() && self.input_files.len() == 1 {
-            let current_file = &self.input_files[0];
-            
-            // Only analyze file if it's different from the last analyzed file
-            if current_file != &self.last_analyzed_file {
-                // Use bundled FFmpeg worker for file analysis
-                let worker = ffmpeg_worker_simple::FFmpegWorker::new();
-                if let Ok(info) = worker.get_file_info(current_file) {
-                    let video_info = if info.video_streams.is_empty() { 
-                        "None".to_string() 
-                    } else { 
-                        format!("{} streams", info.video_streams.len()) 
-                    };
-                    let audio_info = if info.audio_streams.is_empty() { 
-                        "None".to_string() 
-                    } else { 
-                        format!("{} streams", info.audio_streams.len()) 
-                    };
-                    self.file_info = format!(
-                        "File: {}\nDuration: {:.2}s\nVideo: {}\nAudio: {}",
-                        info.filename,
-                        info.duration,
-                        video_info,
-                        audio_info
-                    );
-                    self.file_info_cache = self.file_info.clone();
-                    self.last_analyzed_file = current_file.clone();
-                    
-                    // Detect streams and resolution from info
-                    if let Some(video) = info.video_streams.first() {
-                        let width = video.width as u32;
-                        let height = video.height as u32;
-                        self.is_portrait_video = Some(height > width);
-                        self.detected_resolution = Some((width, height));
-                    }
-                } else {
-                    // If analysis fails, use cached info
-                    self.file_info = self.file_info_cache.clone();
-                }
-                
-                // Auto-generate output filename when input file is selected
-                // Only auto-generate if output is empty or already auto-generated
-                if self.output_file.is_empty() || self.is_auto_generated_filename(&self.output_file) {
-                    self.generate_output_filename_from_input();
-                }
+        match (&self.active_schedule_profile, &name) {
+            (_, Some(entered)) => {
+                log_info!("Schedule profile \"{}\" is now active ({:?})", entered, self.schedule_constraints());
+                self.status_message = self.translations.schedule_entered(entered, paused);
             }
+            (Some(left), None) => {
+                log_info!("Schedule profile \"{}\" ended", left);
+                self.status_message = self.translations.schedule_left(left);
+            }
+            (None, None) => {}
         }
-        
-        ui.add_space(10.0);
-        OutputSelector::show(
-            ui, 
-            &mut self.output_file, 
-            operation,
-            Some(&self.video_settings),
-            Some(&self.audio_settings),
-            &self.translations
-        );
+        self.active_schedule_profile = name;
+        self.apply_power_policy();
     }
     
-    fn show_settings_panel(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
-        // Show operation-specific settings
-        let ctx = ui.ctx().clone();
-        let cached_hw_encoders = self.get_cached_hardware_encoders();
-        operation_settings::OperationSettings::show(
-            &ctx,
-            ui, 
-            operation, 
-            &mut self.video_settings, 
-            &mut self.audio_settings, 
-            &self.translations,
-            self.detected_resolution,
-            self.is_portrait_video,
-            &cached_hw_encoders
-        );
-        
-        ui.add_space(10.0);
+    /// Pick up power state changes from the monitor thread, apply the policy and announce pausing or resuming
+    fn poll_power_source(&mut self) {
+        let previous = self.power_constraints();
+        let mut changed = false;
+        while let Ok(source) = self.power_receiver.try_recv() {
+            changed |= source != self.power_source;
+            self.power_source = source;
+        }
+        if !changed {
+            return;
+        }
         
-        // Show common encoding settings for operations that need them
-        let needs_video_settings = matches!(operation,
-            OperationType::VideoConvert | OperationType::VideoCompress | 
-            OperationType::VideoResize | OperationType::VideoCrop | 
-            OperationType::VideoRotate | OperationType::VideoFilter |
-            OperationType::VideoAudioMerge | OperationType::ExtractVideo
-        );
+        let constraints = self.power_constraints();
+        log_info!("Power source is now {:?}, constraints {:?}", self.power_source, constraints);
+        if constraints != previous {
+            self.apply_power_policy();
+            self.status_message = match (previous.pause_queue, constraints.pause_queue) {
+                (false, true) => self.translations.power_rule_queue_paused(),
+                (true, false) => self.translations.power_rule_queue_resumed(),
+                _ if constraints.is_constrained() => self.translations.power_rule_limits_applied(),
+                _ => self.translations.power_rule_limits_lifted(),
+            }.to_string();
+        }
+    }
+    
+    fn apply_max_tasks_per_destination(&self) {
+        if let Some(executor) = &self.task_executor {
+            executor.set_max_tasks_per_destination(self.max_tasks_per_destination);
+        }
+    }
+    
+    fn apply_max_concurrent_tasks(&self) {
+        if let Some(executor) = &self.task_executor {
+            executor.set_max_concurrent_tasks(self.max_concurrent_tasks);
+        }
+    }
+    
+    fn apply_keep_existing_outputs(&self) {
+        if let Some(executor) = &self.task_executor {
+            executor.set_keep_existing_outputs(self.keep_existing_outputs);
+        }
+    }
+    
+    fn current_preferences(&self) -> UserPreferences {
+        UserPreferences {
+            language: self.current_language.code().to_string(),
+            dark_mode: self.dark_mode,
+            max_tasks_per_destination: self.max_tasks_per_destination,
+            max_concurrent_tasks: self.max_concurrent_tasks,
+            suppressed_advisories: self.suppressed_advisories.clone(),
+            encoder_policy: self.encoder_policy,
+            default_output_dir: self.default_output_dir.clone(),
+            filename_template: self.filename_template.clone(),
+            force_embedded_workflow_editor: self.force_embedded_workflow_editor,
+            power_policy: self.power_policy,
+            output_favorites: self.output_favorites.clone(),
+            checksum_outputs: self.checksum_outputs,
+            checksum_sidecar: self.checksum_sidecar,
+            keep_source_times: self.keep_source_times,
+            write_creation_time: self.write_creation_time,
+            output_guards: self.output_guards.clone(),
+            drop_choices: self.drop_choices.clone(),
+            incremental_workflow_execution: self.workflow_executor.incremental,
+            keep_stopped_workflow_files: self.workflow_executor.keep_files_on_cancel,
+            review_clip: self.review_clip_settings.clone(),
+            schedule_policy: self.schedule_policy.clone(),
+            keep_scratch_files: self.keep_scratch_files,
+            keep_existing_outputs: self.keep_existing_outputs,
+            download_cache_cap_mb: self.download_cache_cap_mb,
+            workflow_autosave: self.workflow_autosave,
+            workflow_autosave_secs: self.workflow_autosave_secs,
+            watch_folder: self.watch_folder.clone(),
+            watch_folder_enabled: self.watch_folder_enabled,
+        }
+    }
+    
+    /// Write the preferences file; called whenever a persisted preference changes
+    fn save_preferences(&self) {
+        if safe_mode::is_skipped(safe_mode::Subsystem::Preferences) {
+            return;
+        }
+        if let Err(e) = self.current_preferences().save() {
+            log_warn!("Failed to save preferences: {}", e);
+        }
+    }
+    
+    /// Probe each merge input once; failed probes are remembered too so they are not retried every frame
+    fn merge_input_duration(&mut self, file: &str) -> Option<f64> {
+        *self.merge_input_durations.entry(file.to_string()).or_insert_with(|| {
+            probe_cache::probe_cache().get_file_info(file).ok().map(|info| info.duration)
+        })
+    }
+    
+    /// Forget everything detected about the previous input so nothing stale is used while the new probe runs
+    fn clear_detected_file_info(&mut self) {
+        self.file_info = String::new();
+        self.detected_duration = None;
+        self.detected_resolution = None;
+        self.is_portrait_video = None;
+        self.detected_programs.clear();
+        self.detected_media_info = None;
+        self.video_settings.input_program = None;
+        self.video_settings.source_alpha = false;
+        // Chapter edits belong to the previous input
+        self.detected_chapters.clear();
+        self.audio_content_probe = None;
+        self.audio_settings.content_type = None;
+        self.video_settings.chapter_edits = None;
+        self.audio_settings.chapter_edits = None;
+    }
+    
+    /// Apply file probe stages that finished since the last frame
+    fn poll_file_probe(&mut self) {
+        let Some(probe) = self.file_probe.as_mut() else {
+            return;
+        };
+        // The input changed without going through the file selector (e.g. a loaded project)
+        if self.input_files.len() != 1 || self.input_files[0] != probe.path() {
+            self.file_probe = None;
+            self.last_analyzed_file.clear();
+            return;
+        }
         
-        let needs_audio_settings = matches!(operation,
-            OperationType::AudioConvert | OperationType::AudioCompress | 
-            OperationType::AudioResample | OperationType::AudioVolume | 
-            OperationType::AudioTrim | OperationType::AudioMerge |
-            OperationType::VideoAudioMerge | OperationType::ExtractAudio
-        );
+        let completed = probe.poll();
+        if completed.is_empty() {
+            return;
+        }
+        let info = probe.info.clone();
+        if probe.is_finished() {
+            self.file_probe = None;
+        }
         
-        let cached_hw_encoders = self.get_cached_hardware_encoders();
+        self.file_info = info.summary();
+        self.detected_duration = info.duration();
+        if let Some(container) = &info.container {
+            self.detected_chapters = container.chapters.clone();
+        }
         
-        if *operation == OperationType::VideoConvert {
-            if needs_video_settings {
-                SettingsPanel::show_video_settings(ui, &mut self.video_settings, &self.translations, &cached_hw_encoders);
-            }
-            
-            if needs_audio_settings {
-                ui.add_space(10.0);
-                SettingsPanel::show_audio_settings(ui, &mut self.audio_settings, &self.translations);
-            }
-        } else {
-            ui.horizontal(|ui| {
-                if needs_video_settings {
-                    ui.vertical(|ui| {
-                        SettingsPanel::show_video_settings(ui, &mut self.video_settings, &self.translations, &cached_hw_encoders);
-                    });
+        if completed.contains(&progressive_probe::ProbeStage::Streams) {
+            if let Some(media) = &info.media {
+                // Multi-program captures: map the first program unless the user picks another
+                self.video_settings.input_program = if media.programs.len() > 1 {
+                    media.programs.first().map(|program| program.program_id)
+                } else {
+                    None
+                };
+                self.detected_programs = media.programs.clone();
+                self.video_settings.source_alpha = alpha_video::source_alpha_format(media).is_some();
+                self.detected_media_info = Some(media.clone());
+                if !media.audio_streams.is_empty() {
+                    self.start_audio_content_probe(info.path.clone());
                 }
                 
-                if needs_audio_settings {
-                    ui.vertical(|ui| {
-                        SettingsPanel::show_audio_settings(ui, &mut self.audio_settings, &self.translations);
-                    });
+                if let Some((width, height)) = info.resolution() {
+                    self.is_portrait_video = Some(height > width);
+                    self.detected_resolution = Some((width, height));
                 }
-            });
+            }
         }
-        
     }
     
-    
-    fn validate_operation_requirements(&self, operation: &OperationType) -> (bool, Option<String>) {
-        match operation {
-            OperationType::AddSubtitle => {
-                if self.dedicated_video_file.is_empty() {
-                    return (false, Some(self.translations.video_file_required().to_string()));
+    /// Guess voice or music for the bitrate advice from a short spectrum scan of the input
+    fn start_audio_content_probe(&mut self, path: String) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(audio_advice::probe_content(&path));
+        });
+        self.audio_content_probe = Some(rx);
+    }
+    
+    fn poll_audio_content_probe(&mut self) {
+        let Some(receiver) = &self.audio_content_probe else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(content) => {
+                self.audio_settings.content_type = content;
+                self.audio_content_probe = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => self.audio_content_probe = None,
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+        }
+    }
+    
+    fn set_language(&mut self, language: Language) {
+        self.current_language = language.clone();
+        self.translations = Translations::new(language);
+        self.status_message = self.translations.ready().to_string();
+    }
+    
+    /// Asks whether to queue the last session's unfinished tasks again; their inputs may have moved
+    fn show_queue_restore_dialog(&mut self, ctx: &egui::Context) {
+        if self.queue_restore.is_empty() {
+            return;
+        }
+        let mut restore = None;
+        egui::Window::new(self.translations.queue_restore_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(self.translations.restore_unfinished_tasks(self.queue_restore.len()));
+                let interrupted = self.queue_restore.iter().filter(|task| task.status == TaskStatus::Failed).count();
+                if interrupted > 0 {
+                    ui.weak(self.translations.interrupted_tasks(interrupted));
                 }
-                if self.video_settings.subtitle_file.is_empty() {
-                    return (false, Some(self.translations.subtitle_file_required().to_string()));
+                let missing = self.queue_restore.iter()
+                    .filter(|task| task.input_files.iter().any(|input| !download_cache::is_remote(input) && !std::path::Path::new(input).exists()))
+                    .count();
+                if missing > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 180, 0), self.translations.tasks_missing_inputs(missing));
                 }
-                if self.output_file.is_empty() {
-                    return (false, Some("Please select output file".to_string()));
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for task in &self.queue_restore {
+                        let name = task.label.clone().unwrap_or_else(|| task.output_file.clone());
+                        ui.label(format!("#{} {} · {}", task.id, task.operation.display_name(&self.translations), name));
+                    }
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(self.translations.queue_restore_restore()).clicked() {
+                        restore = Some(true);
+                    }
+                    if ui.button(self.translations.queue_restore_discard()).clicked() {
+                        restore = Some(false);
+                    }
+                });
+            });
+        match restore {
+            Some(true) => {
+                let tasks = std::mem::take(&mut self.queue_restore);
+                log_info!("Restoring {} task(s) from the last session", tasks.len());
+                if let Some(executor) = &self.task_executor {
+                    for task in tasks {
+                        executor.enqueue_task(task);
+                    }
                 }
-                // Add informative message about subtitle operation
-                let info = "Note: Subtitle operations are experimental in static library mode. If the operation fails, the system will fallback to safe file copy.";
-                (true, Some(info.to_string()))
             }
-            OperationType::AddWatermark => {
-                if self.dedicated_video_file.is_empty() {
-                    return (false, Some(self.translations.video_file_required().to_string()));
-                }
-                if self.video_settings.watermark_file.is_empty() {
-                    return (false, Some(self.translations.watermark_file_required().to_string()));
+            Some(false) => {
+                self.queue_restore.clear();
+                queue_store::discard();
+            }
+            None => {}
+        }
+    }
+    
+    /// Offers the unsaved workflows an earlier session left in its autosave
+    fn show_workflow_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let Some(recovery) = &self.workflow_recovery else {
+            return;
+        };
+        let mut recover = None;
+        egui::Window::new(self.translations.workflow_autosave_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(self.translations.recover_workflow_from(&recovery.saved_at.format("%Y-%m-%d %H:%M:%S").to_string()));
+                ui.add_space(8.0);
+                for unsaved in &recovery.workflows {
+                    match &unsaved.file_path {
+                        Some(path) => ui.label(format!("{} · {}", unsaved.workflow.name, path.display())),
+                        None => ui.label(&unsaved.workflow.name),
+                    };
                 }
-                if self.output_file.is_empty() {
-                    return (false, Some("Please select output file".to_string()));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(self.translations.workflow_autosave_recover()).clicked() {
+                        recover = Some(true);
+                    }
+                    if ui.button(self.translations.workflow_autosave_discard()).clicked() {
+                        recover = Some(false);
+                    }
+                });
+            });
+        match recover {
+            Some(true) => {
+                let Some(recovery) = self.workflow_recovery.take() else {
+                    return;
+                };
+                log_info!("Recovering {} unsaved workflow(s) from the autosave", recovery.workflows.len());
+                for unsaved in recovery.workflows {
+                    let tab = workflow_tabs::WorkflowEditorState::recovered(unsaved.workflow, unsaved.file_path);
+                    if self.editor().is_blank() {
+                        *self.editor_mut() = tab;
+                    } else {
+                        self.workflow_tabs.push(tab);
+                        self.active_workflow_tab = self.workflow_tabs.len() - 1;
+                    }
                 }
-                (true, None)
+                // The recovered tabs are unsaved; the next autosave replaces the old file with them
+                self.workflow_autosave_written = true;
+                self.workflow_autosave_due = true;
+                self.open_workflow_window_requested = true;
             }
-            _ => {
-                let basic_requirements = !self.input_files.is_empty() && !self.output_file.is_empty();
-                (basic_requirements, None)
+            Some(false) => {
+                self.workflow_recovery = None;
+                workflow_autosave::discard();
             }
+            None => {}
         }
     }
-
-    fn show_action_buttons(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
-        ui.add_space(10.0);
+    
+    /// Autosave the tabs with unsaved changes after an edit or once the interval passed, and remove
+    /// the autosave once everything is saved. The file of an earlier session is left alone until
+    /// the user decided about recovering it.
+    fn autosave_workflows(&mut self) {
+        if self.workflow_recovery.is_some() {
+            return;
+        }
+        let dirty: Vec<&workflow_tabs::WorkflowEditorState> = self.workflow_tabs.iter().filter(|tab| tab.is_dirty()).collect();
+        if dirty.is_empty() {
+            if self.workflow_autosave_written {
+                workflow_autosave::discard();
+                self.workflow_autosave_written = false;
+            }
+            self.workflow_autosave_due = false;
+            return;
+        }
+        let interval = std::time::Duration::from_secs(self.workflow_autosave_secs);
+        if !self.workflow_autosave || !(self.workflow_autosave_due || self.last_workflow_autosave.elapsed() >= interval) {
+            return;
+        }
+        let workflows = dirty.iter()
+            .map(|tab| workflow_autosave::UnsavedWorkflow { workflow: tab.workflow.clone(), file_path: tab.file_path.clone() })
+            .collect();
+        workflow_autosave::save_in_background(workflows);
+        self.workflow_autosave_written = true;
+        self.workflow_autosave_due = false;
+        self.last_workflow_autosave = std::time::Instant::now();
+    }
+    
+    /// Save unfinished tasks for the next start; tasks not yet restored are kept as they were
+    fn save_task_queue(&self) {
+        if !self.queue_restore.is_empty() {
+            return;
+        }
+        if let Ok(tasks) = self.tasks.lock() {
+            queue_store::save(&tasks);
+        }
+    }
+    
+    fn show_first_run_wizard(&mut self, ctx: &egui::Context) {
+        let Some(wizard) = self.first_run_wizard.as_mut() else {
+            return;
+        };
         
-        let (can_start, validation_message) = self.validate_operation_requirements(operation);
+        let task_snapshot = self.tasks.lock().map(|tasks| tasks.clone()).unwrap_or_default();
+        if let Some(source) = wizard.poll_self_test(&task_snapshot) {
+            let codec = first_run::self_test_codec(wizard.encoder_policy, self.hardware_detection.encoders.as_deref().unwrap_or(&[]));
+            match (wizard.self_test_task(self.next_task_id, &source, &codec), &self.task_executor) {
+                (Some(task), Some(executor)) => {
+                    log_info!("Queueing self-test task {} with {}", task.id, codec);
+                    executor.enqueue_task(task);
+                    wizard.self_test = first_run::SelfTest::Running { task_id: self.next_task_id, codec };
+                    self.next_task_id += 1;
+                }
+                _ => {
+                    wizard.self_test = first_run::SelfTest::Failed {
+                        error: "task executor not available".to_string(),
+                        hints: vec![first_run::SelfTestHint::Generic],
+                    };
+                }
+            }
+        }
+        if wizard.is_busy() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
         
-        ui.horizontal(|ui| {
-            let start_button = egui::Button::new(
-                egui::RichText::new(self.translations.start_processing())
-                    .color(egui::Color32::WHITE)
-                    .strong()
-            ).min_size(egui::vec2(120.0, 35.0));
-            
-            if can_start {
-                if ui.add(start_button.fill(egui::Color32::from_rgb(34, 139, 34))
-                    .rounding(egui::Rounding::same(6.0))).clicked() {
-                    self.start_processing(operation);
+        let encoders = self.hardware_detection.encoders.clone().unwrap_or_default();
+        let action = first_run::show_wizard(ctx, wizard, self.hardware_detection.hardware_info.as_ref(), &encoders, &self.translations);
+        match action {
+            Some(first_run::WizardAction::LanguageChanged(language)) => self.set_language(language),
+            Some(first_run::WizardAction::ThemeChanged(dark_mode)) => self.dark_mode = dark_mode,
+            Some(first_run::WizardAction::Finish) => {
+                if let Some(mut wizard) = self.first_run_wizard.take() {
+                    let mut preferences = self.current_preferences();
+                    wizard.apply_to(&mut preferences);
+                    self.encoder_policy = preferences.encoder_policy;
+                    self.default_output_dir = preferences.default_output_dir;
+                    self.filename_template = preferences.filename_template;
+                    wizard.cleanup();
                 }
-            } else {
-                ui.add_enabled(false, start_button.fill(egui::Color32::DARK_GRAY)
-                    .rounding(egui::Rounding::same(6.0)));
+                self.save_preferences();
+                self.status_message = self.translations.first_run_saved().to_string();
+            }
+            Some(first_run::WizardAction::Skip) => {
+                if let Some(mut wizard) = self.first_run_wizard.take() {
+                    wizard.cleanup();
+                }
+                // Keep the defaults but do not ask again on the next start
+                self.save_preferences();
+            }
+            None => {}
+        }
+    }
+    
+    /// Video settings for a new task with the encoder policy applied to an "auto" codec
+    fn video_settings_for_task(&mut self) -> VideoSettings {
+        let mut settings = self.video_settings.clone();
+        if let Some(codec) = self.encoder_choice_for_next_task.take() {
+            if codec != "auto" {
+                settings.use_hardware_acceleration = true;
+            }
+            settings.codec = codec;
+        } else if settings.codec == "auto"
+            && alpha_video::auto_codec_for_settings(&settings, &settings.container_format).is_none()
+            && (self.encoder_policy == EncoderPolicy::PreferHardware || self.queue_constraints().prefer_hardware) {
+            let encoders = self.hardware_detection.encoders.clone().unwrap_or_default();
+            if let Some(codec) = first_run::hardware_codec_for(&settings.container_format, &settings.quality_preset, &encoders) {
+                log_info!("Encoder policy prefers hardware, using {}", codec);
+                settings.codec = codec;
+                settings.use_hardware_acceleration = true;
+            }
+        }
+        settings
+    }
+    
+    /// Hardware codec to offer when the policy is "always ask" and the next task would use an auto codec
+    fn encoder_choice_needed(&self, operation: &OperationType) -> Option<String> {
+        if self.encoder_policy != EncoderPolicy::AlwaysAsk
+            || self.video_settings.codec != "auto"
+            || alpha_video::auto_codec_for_settings(&self.video_settings, &self.video_settings.container_format).is_some()
+            || self.get_operation_category(operation) != "video" {
+            return None;
+        }
+        let encoders = self.hardware_detection.encoders.as_deref().unwrap_or(&[]);
+        first_run::hardware_codec_for(&self.video_settings.container_format, &self.video_settings.quality_preset, encoders)
+    }
+    
+    fn show_encoder_choice_dialog(&mut self, ctx: &egui::Context) {
+        let Some((operation, hardware_codec)) = self.pending_encoder_choice.clone() else {
+            return;
+        };
+        let mut choice = None;
+        let mut cancelled = false;
+        egui::Window::new(self.translations.first_run_choose_encoder())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(self.translations.encoder_policy_description(EncoderPolicy::AlwaysAsk));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(format!("⚡ {}", hardware_codec)).clicked() {
+                        choice = Some(hardware_codec.clone());
+                    }
+                    if ui.button(self.translations.encoder_policy_name(EncoderPolicy::PreferQuality)).clicked() {
+                        choice = Some("auto".to_string());
+                    }
+                    if ui.button(self.translations.cancel()).clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        if let Some(codec) = choice {
+            self.pending_encoder_choice = None;
+            self.encoder_choice_for_next_task = Some(codec);
+            self.force_start_processing(&operation);
+        } else if cancelled {
+            self.pending_encoder_choice = None;
+        }
+    }
+    
+    fn show_parameter_issues_dialog(&mut self, ctx: &egui::Context) {
+        let Some((operation, issues)) = self.pending_parameter_issues.clone() else {
+            return;
+        };
+        let mut clamp = None;
+        let mut clamp_all = false;
+        let mut cancelled = false;
+        egui::Window::new(self.translations.parameter_limit_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(self.translations.parameter_limit_intro());
+                ui.add_space(8.0);
+                egui::Grid::new("parameter_issues").num_columns(4).spacing([12.0, 6.0]).show(ui, |ui| {
+                    for (index, issue) in issues.iter().enumerate() {
+                        ui.label(self.translations.parameter_field_name(issue.field));
+                        ui.colored_label(egui::Color32::from_rgb(230, 70, 70), &issue.value);
+                        ui.weak(self.translations.parameter_out_of_range(&issue.codec, &issue.valid_range));
+                        if ui.button(format!("{} {}", self.translations.parameter_limit_clamp(), parameter_limits::clamped_text(&issue.clamped))).clicked() {
+                            clamp = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(self.translations.parameter_limit_clamp_all_start()).clicked() {
+                        clamp_all = true;
+                    }
+                    if ui.button(self.translations.cancel()).clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        if let Some(index) = clamp {
+            parameter_limits::apply_clamp(&issues[index], &mut self.video_settings, &mut self.audio_settings);
+            let remaining = parameter_limits::validate(&operation, &self.video_settings, &self.audio_settings);
+            self.pending_parameter_issues = (!remaining.is_empty()).then_some((operation, remaining));
+        } else if clamp_all {
+            for issue in &issues {
+                parameter_limits::apply_clamp(issue, &mut self.video_settings, &mut self.audio_settings);
             }
+            self.pending_parameter_issues = None;
+            self.start_processing(&operation);
+        } else if cancelled {
+            self.pending_parameter_issues = None;
+        }
+    }
+    
+    fn export_settings_bundle(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("FF GUI Settings", &["ffsettings"])
+            .set_title("Export Settings Bundle")
+            .save_file() {
             
-            ui.add_space(10.0);
+            let mut bundle = SettingsBundle::new();
+            let preferences = self.current_preferences();
+            let encoding_defaults = EncodingDefaults {
+                video_settings: self.video_settings.clone(),
+                audio_settings: self.audio_settings.clone(),
+            };
             
-            let preview_button = egui::Button::new(
-                egui::RichText::new(self.translations.preview_command())
-                    .color(egui::Color32::WHITE)
-                    .strong()
-            ).min_size(egui::vec2(120.0, 35.0));
+            let watch_folder = WatchFolderSection {
+                config: self.watch_folder.clone(),
+                enabled: self.watch_folder_enabled,
+            };
+            let workflows = FolderSection::read(&workflow_library::library_dir());
+            let templates = FolderSection::read(&node_group::templates_dir());
             
-            if can_start {
-                if ui.add(preview_button.fill(egui::Color32::from_rgb(70, 130, 180))
-                    .rounding(egui::Rounding::same(6.0))).clicked() {
-                    self.show_command_preview(operation);
+            let result = bundle.add_section(BUNDLE_SECTION_PREFERENCES, &preferences)
+                .and_then(|_| bundle.add_section(BUNDLE_SECTION_ENCODING_DEFAULTS, &encoding_defaults))
+                .and_then(|_| bundle.add_section(BUNDLE_SECTION_WORKFLOWS, &workflows?))
+                .and_then(|_| bundle.add_section(BUNDLE_SECTION_TEMPLATES, &templates?))
+                .and_then(|_| bundle.add_section(BUNDLE_SECTION_WATCH_FOLDER, &watch_folder))
+                .and_then(|_| bundle.save_to_file(&path.display().to_string()));
+            
+            match result {
+                Ok(_) => {
+                    log_info!("Settings bundle exported to {}", path.display());
+                    self.status_message = self.translations.settings_bundle_exported().to_string();
+                },
+                Err(e) => {
+                    self.status_message = format!("{}: {}", self.translations.save_error(), e);
                 }
-            } else {
-                ui.add_enabled(false, preview_button.fill(egui::Color32::DARK_GRAY)
-                    .rounding(egui::Rounding::same(6.0)));
             }
+        }
+    }
+    
+    fn import_settings_bundle(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("FF GUI Settings", &["ffsettings"])
+            .set_title("Import Settings Bundle")
+            .pick_file() {
             
-            ui.add_space(10.0);
-            
-            let reset_button = egui::Button::new(
-                egui::RichText::new(self.translations.reset_settings())
-                    .color(egui::Color32::WHITE)
-                    .strong()
-            ).min_size(egui::vec2(100.0, 35.0))
-                .fill(egui::Color32::from_rgb(255, 140, 0))
-                .rounding(egui::Rounding::same(6.0));
-            
-            if ui.add(reset_button).clicked() {
-                self.video_settings = VideoSettings::default();
-                self.audio_settings = AudioSettings::default();
+            match SettingsBundle::load_from_file(&path.display().to_string()) {
+                Ok(bundle) => {
+                    let sections = bundle.sections.keys()
+                        .map(|name| (name.clone(), true))
+                        .collect();
+                    self.settings_import_report.clear();
+                    self.pending_settings_import = Some((bundle, sections));
+                },
+                Err(e) => {
+                    log_error!("Failed to load settings bundle: {}", e);
+                    self.status_message = format!("{}: {}", self.translations.load_error(), e);
+                }
             }
-        });
+        }
+    }
+    
+    /// Take over all preferences and save them
+    fn apply_preferences(&mut self, prefs: UserPreferences) -> Result<(), String> {
+        let language = Language::from_code(&prefs.language)
+            .ok_or_else(|| format!("unknown language '{}'", prefs.language))?;
+        self.current_language = language.clone();
+        self.translations = Translations::new(language);
+        self.dark_mode = prefs.dark_mode;
+        self.max_tasks_per_destination = prefs.max_tasks_per_destination;
+        self.apply_max_tasks_per_destination();
+        self.max_concurrent_tasks = prefs.max_concurrent_tasks;
+        self.apply_max_concurrent_tasks();
+        self.suppressed_advisories = prefs.suppressed_advisories;
+        self.encoder_policy = prefs.encoder_policy;
+        self.default_output_dir = prefs.default_output_dir;
+        self.filename_template = prefs.filename_template;
+        self.force_embedded_workflow_editor = prefs.force_embedded_workflow_editor;
+        self.power_policy = prefs.power_policy;
+        self.output_favorites = prefs.output_favorites;
+        self.checksum_outputs = prefs.checksum_outputs;
+        self.checksum_sidecar = prefs.checksum_sidecar;
+        self.keep_source_times = prefs.keep_source_times;
+        self.write_creation_time = prefs.write_creation_time;
+        self.output_guards = prefs.output_guards;
+        self.drop_choices = prefs.drop_choices;
+        self.workflow_executor.incremental = prefs.incremental_workflow_execution;
+        self.workflow_executor.keep_files_on_cancel = prefs.keep_stopped_workflow_files;
+        self.review_clip_settings = prefs.review_clip;
+        self.schedule_policy = prefs.schedule_policy;
+        self.keep_scratch_files = prefs.keep_scratch_files;
+        temp_files::registry().set_keep(self.keep_scratch_files);
+        self.keep_existing_outputs = prefs.keep_existing_outputs;
+        self.apply_keep_existing_outputs();
+        self.download_cache_cap_mb = prefs.download_cache_cap_mb;
+        download_cache::set_size_cap_mb(self.download_cache_cap_mb);
+        self.workflow_autosave = prefs.workflow_autosave;
+        self.workflow_autosave_secs = prefs.workflow_autosave_secs;
+        if prefs.watch_folder != self.watch_folder || prefs.watch_folder_enabled != self.watch_folder_enabled {
+            self.folder_watcher = None;
+            self.watch_folder_error = None;
+        }
+        self.watch_folder = prefs.watch_folder;
+        self.watch_folder_enabled = prefs.watch_folder_enabled;
+        self.refresh_schedule();
+        self.apply_power_policy();
+        self.save_preferences();
+        Ok(())
+    }
+    
+    /// Run a subsystem safe mode skipped at startup
+    fn re_enable_subsystem(&mut self, subsystem: safe_mode::Subsystem) {
+        safe_mode::re_enable(subsystem);
+        match subsystem {
+            safe_mode::Subsystem::HardwareDetection => self.refresh_hardware_cache("re-enabled in safe mode"),
+            safe_mode::Subsystem::FfmpegExtraction => {
+                if let Err(e) = bundled_ffmpeg::get_bundled_ffmpeg() {
+                    log_error!("Bundled FFmpeg initialization failed: {}", e);
+                    self.status_message = e.to_string();
+                }
+            }
+            safe_mode::Subsystem::Preferences => {
+                if let Some(Err(e)) = UserPreferences::load().map(|prefs| self.apply_preferences(prefs)) {
+                    log_warn!("Stored preferences not applied: {}", e);
+                }
+            }
+        }
+    }
+    
+    /// Apply the selected sections; each section is parsed fully before anything is assigned
+    fn apply_settings_import(&mut self, bundle: &SettingsBundle, sections: &[(String, bool)]) {
+        let mut report = Vec::new();
         
-        // Show validation message if exists
-        if let Some(message) = validation_message {
-            ui.add_space(5.0);
-            // Use different colors for errors vs warnings
-            let (color, _can_proceed) = if can_start {
-                (egui::Color32::from_rgb(255, 165, 0), true) // Orange for warnings
-            } else {
-                (egui::Color32::from_rgb(255, 100, 100), false) // Red for errors
+        for (name, selected) in sections {
+            if !selected {
+                continue;
+            }
+            let result = match name.as_str() {
+                // The watch folder has its own section and stays as it is unless that one is selected
+                BUNDLE_SECTION_PREFERENCES => bundle.get_section::<UserPreferences>(name).and_then(|mut prefs| {
+                    prefs.watch_folder = self.watch_folder.clone();
+                    prefs.watch_folder_enabled = self.watch_folder_enabled;
+                    self.apply_preferences(prefs)
+                }),
+                BUNDLE_SECTION_ENCODING_DEFAULTS => bundle.get_section::<EncodingDefaults>(name).map(|defaults| {
+                    self.video_settings = defaults.video_settings;
+                    self.audio_settings = defaults.audio_settings;
+                }),
+                BUNDLE_SECTION_WORKFLOWS => bundle.get_section::<FolderSection>(name).and_then(|section| {
+                    section.write(&workflow_library::library_dir(), |content| workflow_file::parse(content).map(|_| ()).map_err(|e| e.to_string()))
+                }).map(|()| {
+                    if let Some(library) = &mut self.workflow_library {
+                        library.refresh();
+                    }
+                }),
+                BUNDLE_SECTION_TEMPLATES => bundle.get_section::<FolderSection>(name).and_then(|section| {
+                    section.write(&node_group::templates_dir(), |content| serde_json::from_str::<automation_flow::NodeGroup>(content).map(|_| ()).map_err(|e| e.to_string()))
+                }),
+                BUNDLE_SECTION_WATCH_FOLDER => bundle.get_section::<WatchFolderSection>(name).map(|section| {
+                    // Dropping the watcher restarts it with the new definition
+                    if section.config != self.watch_folder || section.enabled != self.watch_folder_enabled {
+                        self.folder_watcher = None;
+                        self.watch_folder_error = None;
+                    }
+                    self.watch_folder = section.config;
+                    self.watch_folder_enabled = section.enabled;
+                    self.save_preferences();
+                }),
+                _ => Err("unknown section".to_string()),
             };
             
-            ui.colored_label(color, format!("⚠ {}", message));
+            let section_name = self.translations.settings_section_name(name);
+            match result {
+                Ok(()) => report.push(format!("✅ {}", section_name)),
+                Err(e) => {
+                    log_warn!("Settings bundle section '{}' not imported: {}", name, e);
+                    report.push(format!("❌ {}: {}", section_name, e));
+                }
+            }
         }
         
-        ui.add_space(10.0);
+        self.status_message = report.join("  ");
+        self.settings_import_report = report;
     }
     
-    fn start_processing(&mut self, operation: &OperationType) {
-        // Check compatibility before starting processing
-        if !self.check_compatibility_and_warn() {
-            // Store context so we can continue if user chooses to
-            self.compatibility_warning_context = CompatibilityWarningContext::StartProcessing(operation.clone());
-            return; // Show warning dialog, don't start processing
+    fn show_run_report_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_run_report {
+            return;
         }
-
-        self.force_start_processing(operation);
-    }
-
-    fn force_start_processing(&mut self, operation: &OperationType) {
-        // Prepare input files based on operation type
-        let input_files = match operation {
-            OperationType::AddSubtitle | OperationType::AddWatermark => {
-                // Use dedicated video file for these operations
-                vec![self.dedicated_video_file.clone()]
-            }
-            _ => {
-                // Use regular input files for other operations
-                self.input_files.clone()
+        let report = match &self.workflow_executor.last_run_report {
+            Some(report) => report,
+            None => {
+                self.show_run_report = false;
+                return;
             }
         };
         
-        // Generate unique output filename for each task
-        let unique_output_file = if self.is_auto_generated_filename(&self.output_file) {
-            // If it's an auto-generated filename, create a new unique filename
-            let path = std::path::Path::new(&self.output_file);
-            if let Some(parent) = path.parent() {
-                let extension = path.extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("mp4");
-                let timestamp = self.generate_timestamp();
-                parent.join(format!("output_{}.{}", timestamp, extension))
-                    .display().to_string()
-            } else {
-                self.output_file.clone()
-            }
-        } else {
-            // If it's a user-specified filename, add task ID to ensure uniqueness
-            let path = std::path::Path::new(&self.output_file);
-            let stem = path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("output");
-            let extension = path.extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("mp4");
-            
-            if let Some(parent) = path.parent() {
-                parent.join(format!("{}_{}.{}", stem, self.next_task_id, extension))
-                    .display().to_string()
-            } else {
-                format!("{}_{}.{}", stem, self.next_task_id, extension)
-            }
-        };
+        let mut open = true;
+        let mut export = false;
+        egui::Window::new(self.translations.run_report_title())
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                workflow_report::show_report(ui, report, &self.translations);
+                ui.add_space(10.0);
+                if ui.button(self.translations.run_report_export_json()).clicked() {
+                    export = true;
+                }
+            });
         
-        let task = ProcessingTask {
-            id: self.next_task_id,
-            operation: operation.clone(),
-            input_files,
-            output_file: unique_output_file,
-            video_settings: Some(self.video_settings.clone()),
-            audio_settings: Some(self.audio_settings.clone()),
-            progress: 0.0,
-            status: TaskStatus::Pending,
-            error_message: None,
-            start_time: None,
-            estimated_total_time: None,
-            completion_time: None,
+        if export {
+            self.export_run_report();
+        }
+        self.show_run_report = open;
+    }
+    
+    /// Dry-run the open workflow for the command preview window
+    fn preview_workflow_commands(&mut self) {
+        let hardware_encoders = self.get_cached_hardware_encoders();
+        if !hardware_encoders.is_empty() {
+            self.workflow_executor.update_hardware_cache(hardware_encoders);
+        }
+        self.workflow_preview = Some(self.workflow_executor.preview_workflow(&self.editor().workflow));
+    }
+    
+    fn show_workflow_plan_dialog(&mut self, ctx: &egui::Context) {
+        let Some(preview) = &self.workflow_preview else {
+            return;
         };
         
-        if let Ok(mut tasks_guard) = self.tasks.try_lock() {
-            log_info!("Adding task {} ({}) to queue", task.id, task.operation.display_name(&self.translations));
-            tasks_guard.push(task);
-            log_info!("Task queue now has {} tasks", tasks_guard.len());
-        } else {
-            log_error!("Failed to lock tasks queue for adding task");
+        let mut open = true;
+        let mut refresh = false;
+        // Steps name the nodes inside groups
+        let workflow = &self.editor().workflow.flattened();
+        egui::Window::new(self.translations.workflow_plan_title())
+            .open(&mut open)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                if ui.button(self.translations.workflow_plan_refresh()).clicked() {
+                    refresh = true;
+                }
+                match preview {
+                    Ok(steps) => workflow_plan::show_preview(ui, workflow, steps, &self.translations),
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+                    }
+                }
+            });
+        if !open {
+            self.workflow_preview = None;
+        } else if refresh {
+            self.preview_workflow_commands();
         }
-        
-        self.next_task_id += 1;
-        self.status_message = self.translations.task_added(&operation.display_name(&self.translations));
     }
     
-    fn check_compatibility_and_warn(&mut self) -> bool {
-        // Skip compatibility check for GIF operations
-        if let Some(ref operation) = self.current_operation {
-            if matches!(operation, OperationType::VideoToGif | OperationType::GifResize) {
-                return true;
-            }
+    fn show_diagnostics_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_diagnostics_dialog {
+            return;
         }
-
-        // Check if input file has audio/video streams when needed
-        if !self.input_files.is_empty() && self.input_files.len() == 1 {
-            if let Some(ref operation) = self.current_operation {
-                // Check stream availability based on operation type
-                let needs_video = matches!(operation,
-                    OperationType::VideoConvert | OperationType::VideoCompress |
-                    OperationType::VideoResize | OperationType::VideoCrop |
-                    OperationType::VideoRotate | OperationType::VideoFilter |
-                    OperationType::ExtractVideo | OperationType::VideoToGif
-                );
-                
-                let needs_audio = matches!(operation,
-                    OperationType::AudioConvert | OperationType::AudioCompress |
-                    OperationType::AudioResample | OperationType::AudioVolume |
-                    OperationType::AudioTrim | OperationType::ExtractAudio
-                );
-                
-                // Use cached detection results or detect streams
-                let (has_video, has_audio) = if let Some((_width, _height)) = self.detected_resolution {
-                    // Use cached resolution data - assume file has video if we have resolution
-                    (true, true) // Assume has audio for compatibility - could be improved
-                } else {
-                    // Fallback to detection if no cached data
-                    // Use bundled FFmpeg worker to detect streams
-                    let worker = ffmpeg_worker_simple::FFmpegWorker::new();
-                    if let Ok((video, audio, _)) = worker.detect_streams(&self.input_files[0]) {
-                        (video, audio)
-                    } else {
-                        // Last resort: parse file_info text
-                        let info_lower = self.file_info.to_lowercase();
-                        let has_video = info_lower.contains("video:") || info_lower.contains("stream #") && info_lower.contains("video");
-                        let has_audio = info_lower.contains("audio:") || info_lower.contains("stream #") && info_lower.contains("audio");
-                        (has_video, has_audio)
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new(self.translations.diagnostics_title())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label(self.translations.diagnostics_contents());
+                ui.add_space(5.0);
+                ui.checkbox(&mut self.diagnostics_include_tasks, self.translations.diagnostics_include_tasks());
+                ui.weak(self.translations.diagnostics_tasks_note());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(self.translations.diagnostics_save()).clicked() {
+                        save = true;
                     }
-                };
-                
-                if needs_video && !has_video {
-                    self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                        "所选文件不包含视频流。此操作需要视频输入。".to_string()
-                    } else {
-                        "The selected file does not contain a video stream. This operation requires video input.".to_string()
-                    };
-                    self.compatibility_recommended_codec = None;
-                    self.compatibility_recommended_format = None;
-                    self.show_compatibility_warning = true;
-                    return false;
-                }
-                
-                if needs_audio && !has_audio {
-                    self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                        "所选文件不包含音频流。此操作需要音频输入。".to_string()
-                    } else {
-                        "The selected file does not contain an audio stream. This operation requires audio input.".to_string()
-                    };
-                    self.compatibility_recommended_codec = None;
-                    self.compatibility_recommended_format = None;
-                    self.show_compatibility_warning = true;
-                    return false;
-                }
+                    if ui.button(self.translations.cancel()).clicked() {
+                        self.show_diagnostics_dialog = false;
+                    }
+                });
+            });
+        if !open {
+            self.show_diagnostics_dialog = false;
+        }
+        if save {
+            self.create_diagnostic_bundle();
+        }
+    }
+    
+    fn show_checksum_verify_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(dialog) = &mut self.checksum_verify {
+            if !dialog.show(ctx, &self.translations) {
+                self.checksum_verify = None;
             }
         }
-
-        // Determine the target container format
-        let container_format = if !self.video_settings.container_format.is_empty() && self.video_settings.container_format != "auto" {
-            &self.video_settings.container_format
-        } else if !self.audio_settings.format.is_empty() {
-            &self.audio_settings.format
-        } else {
-            ""
+    }
+    
+    fn show_compatibility_matrix(&mut self, ctx: &egui::Context) {
+        if let Some(window) = &mut self.compatibility_matrix {
+            if !window.show(ctx, &self.translations, &self.video_settings.container_format, &self.video_settings.codec, &self.audio_settings.codec) {
+                self.compatibility_matrix = None;
+            }
+        }
+    }
+    
+    fn show_download_cache_window(&mut self, ctx: &egui::Context) {
+        if !self.show_download_cache {
+            return;
+        }
+        let mut cap_changed = false;
+        self.show_download_cache = download_cache::show_window(ctx, &self.translations, &mut self.download_cache_cap_mb, &mut cap_changed);
+        if cap_changed {
+            self.save_preferences();
+        }
+    }
+    
+    fn show_workflow_diff(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.workflow_diff else {
+            return;
         };
-
-        if container_format.is_empty() {
-            return true; // No format specified, nothing to check
+        let editor_workflow = &self.workflow_tabs[self.active_workflow_tab].workflow;
+        let open = window.show(ctx, &self.translations, editor_workflow);
+        // The canvas may live in the workflow viewport
+        ctx.request_repaint_of(egui::ViewportId::from_hash_of("workflow_editor"));
+        if !open {
+            self.workflow_diff = None;
         }
-
-        // Check video codec compatibility
-        if !self.video_settings.codec.is_empty() && self.video_settings.codec != "auto" {
-            if let Err(_) = CodecManager::validate_codec_format_compatibility(
-                &self.video_settings.codec, 
-                container_format, 
-                false
-            ) {
-                let recommended_codec = CodecManager::get_best_video_codec_for_format(container_format);
-                self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                    format!("视频编码器 '{}' 与容器格式 '{}' 不兼容。这可能导致编码失败。", self.video_settings.codec, container_format)
-                } else {
-                    format!("Video codec '{}' is not compatible with container format '{}'. This may cause encoding to fail.", self.video_settings.codec, container_format)
-                };
-                self.compatibility_recommended_codec = Some(recommended_codec);
-                self.compatibility_recommended_format = None;
-                self.show_compatibility_warning = true;
-                return false;
+    }
+    
+    /// Start watching with the workflow file of the watch folder settings
+    fn start_folder_watcher(&mut self) {
+        self.folder_watcher = None;
+        let path = self.watch_folder.workflow_path.clone();
+        let workflow = std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {}", path, e))
+            .and_then(|content| workflow_file::parse(&content).map(|(workflow, _)| workflow).map_err(|e| format!("{}: {}", path, e)));
+        let hardware_encoders = self.get_cached_hardware_encoders();
+        if !hardware_encoders.is_empty() {
+            self.workflow_executor.update_hardware_cache(hardware_encoders);
+        }
+        match workflow.and_then(|workflow| watch_folder::FolderWatcher::start(self.watch_folder.clone(), &workflow, &self.workflow_executor)) {
+            Ok(watcher) => {
+                self.folder_watcher = Some(watcher);
+                self.watch_folder_error = None;
+            }
+            Err(e) => {
+                log_warn!("Watch folder not started: {}", e);
+                self.watch_folder_error = Some(e);
             }
         }
-
-        // Check audio codec compatibility
-        // IMPORTANT: Also check when audio codec is "auto" but would result in incompatible codec
-        let audio_codec_to_check = if self.audio_settings.codec == "auto" || self.audio_settings.codec.is_empty() {
-            // Predict what codec would be used for "auto"
-            // Check if the default audio codec for this format would be incompatible
-            let default_audio_codec = match container_format {
-                "mp4" | "mov" => "aac",
-                "webm" => "libopus", // WebM should default to Opus or Vorbis, not AAC
-                "ogg" => "libvorbis",
-                "mkv" => "aac", // MKV supports many codecs, AAC is common
-                _ => "aac"
-            };
-            
-            // Special case: if converting to WebM and audio codec is auto, check if source would use AAC
-            if container_format == "webm" && (self.audio_settings.codec == "auto" || self.audio_settings.codec.is_empty()) {
-                // WebM doesn't support AAC, warn user
-                self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                    format!("WebM格式不支持AAC音频编码器。音频将自动转换为{}编码器。", default_audio_codec)
-                } else {
-                    format!("WebM format does not support AAC audio codec. The audio will be automatically converted to {} codec.", default_audio_codec)
-                };
-                self.compatibility_recommended_codec = Some(default_audio_codec.to_string());
-                self.compatibility_recommended_format = None;
-                self.show_compatibility_warning = true;
-                return false;
+    }
+    
+    /// Watch folder indicator in the status bar: state, last file and errors, with pause/resume
+    fn show_watch_folder_status(&mut self, ui: &mut egui::Ui) {
+        // Started on the first frame after launch, and again after its settings were applied
+        if self.folder_watcher.is_none() && self.watch_folder_error.is_none() {
+            self.start_folder_watcher();
+        }
+        let Some(watcher) = &self.folder_watcher else {
+            let error = self.watch_folder_error.clone().unwrap_or_default();
+            if ui.colored_label(egui::Color32::from_rgb(255, 100, 100), self.translations.watch_folder_failed())
+                .on_hover_text(error)
+                .clicked()
+            {
+                self.show_watch_folder = true;
             }
-            default_audio_codec.to_string()
-        } else {
-            self.audio_settings.codec.clone()
+            return;
         };
-
-        if !audio_codec_to_check.is_empty() && audio_codec_to_check != "auto" {
-            if let Err(_) = CodecManager::validate_codec_format_compatibility(
-                &audio_codec_to_check, 
-                container_format, 
-                true
-            ) {
-                let recommended_codec = CodecManager::get_best_audio_codec_for_format(container_format);
-                self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                    format!("音频编码器 '{}' 与容器格式 '{}' 不兼容。这可能导致编码失败。", audio_codec_to_check, container_format)
-                } else {
-                    format!("Audio codec '{}' is not compatible with container format '{}'. This may cause encoding to fail.", audio_codec_to_check, container_format)
+        let status = watcher.status();
+        let folder = std::path::Path::new(&watcher.config.watch_dir).file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| watcher.config.watch_dir.clone());
+        let text = match (&status.running, watcher.is_paused()) {
+            (_, true) => format!("👁 ⏸ {}", folder),
+            (Some(running), false) => format!("👁 ⟳ {}", running),
+            (None, false) => format!("👁 {}", folder),
+        };
+        let mut hover = self.translations.watch_folder_status(&watcher.config.watch_dir, status.processed,
+            status.last_trigger.as_ref().map(|(at, name)| (at.format("%H:%M:%S").to_string(), name.as_str())));
+        if let Some(error) = &status.last_error {
+            hover = format!("{}\n⚠ {}", hover, error);
+        }
+        let color = if status.last_error.is_some() { egui::Color32::from_rgb(255, 165, 0) } else { ui.visuals().text_color() };
+        if ui.add(egui::Label::new(egui::RichText::new(text).color(color)).sense(egui::Sense::click()))
+            .on_hover_text(hover)
+            .clicked()
+        {
+            self.show_watch_folder = true;
+        }
+        let paused = watcher.is_paused();
+        if ui.small_button(if paused { self.translations.watch_folder_resume() } else { self.translations.watch_folder_pause() }).clicked() {
+            watcher.set_paused(!paused);
+        }
+        // The watcher thread updates the status on its own
+        ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+    }
+    
+    fn show_watch_folder_window(&mut self, ctx: &egui::Context) {
+        if !self.show_watch_folder {
+            return;
+        }
+        let mut open = true;
+        let focused_workflow = self.editor().file_path.clone();
+        egui::Window::new(self.translations.watch_folder_title())
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(self.translations.watch_folder_hint());
+                ui.add_space(5.0);
+                if watch_folder::show_editor(ui, &mut self.watch_folder, focused_workflow.as_deref(), &self.translations) {
+                    self.save_preferences();
+                }
+                if self.folder_watcher.as_ref().is_some_and(|watcher| watcher.config != self.watch_folder) {
+                    ui.weak(self.translations.watch_folder_restart_hint());
+                }
+                if let Some(error) = &self.watch_folder_error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                }
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if self.watch_folder_enabled {
+                        if ui.button(self.translations.watch_folder_restart()).clicked() {
+                            self.start_folder_watcher();
+                        }
+                        if ui.button(self.translations.watch_folder_stop()).clicked() {
+                            self.watch_folder_enabled = false;
+                            self.folder_watcher = None;
+                            self.watch_folder_error = None;
+                            self.save_preferences();
+                        }
+                    } else if ui.button(self.translations.watch_folder_start()).clicked() {
+                        self.watch_folder_enabled = true;
+                        self.start_folder_watcher();
+                        self.save_preferences();
+                    }
+                });
+            });
+        if !open {
+            self.show_watch_folder = false;
+        }
+    }
+    
+    /// Runs the focused workflow once per file of a folder
+    fn show_folder_run_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.folder_run else {
+            return;
+        };
+        let workflow = &self.workflow_tabs[self.active_workflow_tab].workflow;
+        match dialog.show(ctx, workflow, self.max_concurrent_tasks, &self.translations) {
+            folder_run::FolderRunAction::None => {}
+            folder_run::FolderRunAction::Close => self.folder_run = None,
+            folder_run::FolderRunAction::Start(files) => {
+                let hardware_encoders = self.get_cached_hardware_encoders();
+                if !hardware_encoders.is_empty() {
+                    self.workflow_executor.update_hardware_cache(hardware_encoders);
+                }
+                let Some(dialog) = &mut self.folder_run else {
+                    return;
                 };
-                self.compatibility_recommended_codec = Some(recommended_codec);
-                self.compatibility_recommended_format = None;
-                self.show_compatibility_warning = true;
-                return false;
+                let concurrency = if dialog.settings.parallel { self.max_concurrent_tasks } else { 1 };
+                let workflow = &self.workflow_tabs[self.active_workflow_tab].workflow;
+                dialog.run = Some(folder_run::FolderRun::start(workflow, files, &dialog.settings, &self.workflow_executor, concurrency));
             }
         }
-
-        // Check subtitle compatibility for AddSubtitle operations
-        if let Some(ref operation) = self.current_operation {
-            if matches!(operation, OperationType::AddSubtitle) {
-                // Check if we have the required files
-                if !self.dedicated_video_file.is_empty() && !self.video_settings.subtitle_file.is_empty() {
-                    // Get output file extension
-                    let output_ext = std::path::Path::new(&self.output_file)
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .unwrap_or("")
-                        .to_lowercase();
-                    
-                    // Get subtitle file extension  
-                    let subtitle_ext = std::path::Path::new(&self.video_settings.subtitle_file)
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .unwrap_or("")
-                        .to_lowercase();
-                    
-                    // Check MP4 + SRT/VTT/ASS soft subtitle incompatibility
-                    if output_ext == "mp4" && 
-                       (subtitle_ext == "srt" || subtitle_ext == "vtt" || subtitle_ext == "ass") &&
-                       self.video_settings.subtitle_mode == "soft" {
-                        
-                        self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
-                            format!("MP4容器格式不支持{}软字幕。建议更改输出格式为MKV以获得更好的兼容性。", 
-                                subtitle_ext.to_uppercase())
-                        } else {
-                            format!("MP4 container format does not support {} soft subtitles. Recommend changing output format to MKV for better compatibility.", 
-                                subtitle_ext.to_uppercase())
-                        };
-                        self.compatibility_recommended_codec = None;
-                        self.compatibility_recommended_format = Some("mkv".to_string());
-                        self.show_compatibility_warning = true;
-                        return false;
+    }
+    
+    fn show_workflow_library(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.workflow_library else {
+            return;
+        };
+        match window.show(ctx, &self.translations) {
+            workflow_library::LibraryAction::None => {}
+            workflow_library::LibraryAction::Close => self.workflow_library = None,
+            workflow_library::LibraryAction::Open(path) => {
+                self.open_workflow_file(path);
+                self.open_workflow_window(ctx);
+            }
+            workflow_library::LibraryAction::Renamed { from, to, name } => {
+                for tab in self.workflow_tabs.iter_mut().filter(|tab| tab.file_path.as_ref() == Some(&from)) {
+                    // A tab without edits follows the new name; one with edits keeps them unsaved
+                    if tab.is_dirty() {
+                        tab.file_path = Some(to.clone());
+                    } else {
+                        tab.workflow.name = name.clone();
+                        tab.mark_saved(to.clone());
                     }
                 }
             }
+            workflow_library::LibraryAction::Deleted(path) => {
+                for tab in self.workflow_tabs.iter_mut().filter(|tab| tab.file_path.as_ref() == Some(&path)) {
+                    tab.file_path = None;
+                }
+            }
         }
-
-        true // No compatibility issues found
     }
-
-    fn check_and_reset_incompatible_codecs(&mut self) {
-        let current_format = &self.video_settings.container_format;
-        
-        // Only check if format has actually changed
-        if self.last_container_format.is_empty() {
-            self.last_container_format = current_format.clone();
+    
+    fn show_workflow_load_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(dialog) = &self.workflow_load_dialog {
+            if !dialog.show(ctx, &self.translations) {
+                self.workflow_load_dialog = None;
+            }
+        }
+    }
+    
+    fn show_review_clip_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.review_clip else {
             return;
-        }
-        
-        if &self.last_container_format != current_format {
-            log_info!("Container format changed from {} to {}, checking codec compatibility...", 
-                     self.last_container_format, current_format);
-            
-            // Check if current audio codec is compatible with new format
-            if !self.audio_settings.codec.is_empty() && self.audio_settings.codec != "auto" {
-                if let Err(_) = crate::codec_manager::CodecManager::validate_codec_format_compatibility(
-                    &self.audio_settings.codec, 
-                    current_format, 
-                    true
-                ) {
-                    log_warn!("Audio codec '{}' is incompatible with '{}', selecting best audio codec", 
-                             self.audio_settings.codec, current_format);
-                    
-                    // Select the best audio codec for this format
-                    let recommended_audio_codec = crate::codec_manager::CodecManager::get_best_audio_codec_for_format(current_format);
-                    self.audio_settings.codec = recommended_audio_codec.clone();
-                    
-                    // Show a brief status message with translation support
-                    self.status_message = if self.translations.language == crate::language::Language::Chinese {
-                        format!("音频编码器已自动调整为{}（兼容{}格式）", recommended_audio_codec, current_format)
-                    } else {
-                        format!("Audio codec automatically adjusted to {} for {} format compatibility", recommended_audio_codec, current_format)
-                    };
-                }
-            }
-            
-            // Check if current video codec is compatible with new format
-            if !self.video_settings.codec.is_empty() && self.video_settings.codec != "auto" {
-                if let Err(_) = crate::codec_manager::CodecManager::validate_codec_format_compatibility(
-                    &self.video_settings.codec, 
-                    current_format, 
-                    false
-                ) {
-                    log_warn!("Video codec '{}' is incompatible with '{}', selecting best video codec", 
-                             self.video_settings.codec, current_format);
-                    
-                    // Use smart encoder recommendation if hardware encoders are available
-                    let (recommended_video_codec, _reason) = if let Some(ref hardware_encoders) = self.cached_hardware_encoders {
-                        crate::codec_manager::CodecManager::get_smart_encoder_recommendation(
-                            current_format,
-                            "Balanced", // Default quality preset
-                            false, // Not speed priority
-                            hardware_encoders,
-                            self.translations.language == crate::language::Language::Chinese
-                        )
-                    } else {
-                        (crate::codec_manager::CodecManager::get_best_video_codec_for_format(current_format), String::new())
-                    };
-                    
-                    self.video_settings.codec = recommended_video_codec.clone();
-                    
-                    // Show a brief status message with translation support
-                    self.status_message = if self.translations.language == crate::language::Language::Chinese {
-                        format!("视频编码器已自动调整为{}（兼容{}格式）", recommended_video_codec, current_format)
-                    } else {
-                        format!("Video codec automatically adjusted to {} for {} format compatibility", recommended_video_codec, current_format)
-                    };
-                }
-            }
-            
-            // Update the tracked format
-            self.last_container_format = current_format.clone();
+        };
+        match dialog.show(ctx, &self.translations) {
+            review_clip::ReviewClipAction::None => {}
+            review_clip::ReviewClipAction::Close => self.review_clip = None,
+            review_clip::ReviewClipAction::Queue(start, end) => {
+                if let Some(dialog) = self.review_clip.take() {
+                    self.queue_review_clip(&dialog.input, dialog.settings, start, end);
+                }
+            }
         }
     }
-
-    fn apply_compatibility_recommendation(&mut self) {
-        if let Some(ref codec) = self.compatibility_recommended_codec {
-            // Check if we're dealing with video or audio based on the current codec being checked
-            // Support both English and Chinese messages
-            if self.compatibility_warning_message.contains("Video codec") || 
-               self.compatibility_warning_message.contains("视频编码器") {
-                self.video_settings.codec = codec.clone();
-            } else if self.compatibility_warning_message.contains("Audio codec") || 
-                      self.compatibility_warning_message.contains("音频编码器") ||
-                      self.compatibility_warning_message.contains("WebM") {
-                // WebM format warning is about audio codec compatibility
-                self.audio_settings.codec = codec.clone();
-            }
-        }
-
-        if let Some(ref format) = self.compatibility_recommended_format {
-            // Apply recommended format
-            if self.compatibility_warning_message.contains("Video") || 
-               self.compatibility_warning_message.contains("视频") {
-                self.video_settings.container_format = format.clone();
-            } else if self.compatibility_warning_message.contains("Audio") || 
-                      self.compatibility_warning_message.contains("音频") {
-                self.audio_settings.format = format.clone();
-            } else if self.compatibility_warning_message.contains("MP4") && 
-                      (self.compatibility_warning_message.contains("subtitle") || 
-                       self.compatibility_warning_message.contains("字幕")) {
-                // Handle subtitle compatibility format change
-                self.video_settings.container_format = format.clone();
-                
-                // Also update the output file extension if it ends with .mp4
-                if self.output_file.ends_with(".mp4") {
-                    self.output_file = self.output_file.replace(".mp4", &format!(".{}", format));
-                }
-            }
-        }
-        
-        // Update status message to show the recommendation was applied
-        self.status_message = if self.translations.language == crate::language::Language::Chinese {
-            "已应用推荐设置".to_string()
+    
+    /// Low-priority task writing `{name}_review_{start}-{end}.mp4`; the options become the new defaults
+    fn queue_review_clip(&mut self, input: &str, settings: review_clip::ReviewClipSettings, start: f64, end: f64) {
+        let folder = if self.default_output_dir.is_empty() {
+            std::path::Path::new(input).parent().map(|parent| parent.to_path_buf()).unwrap_or_default()
         } else {
-            "Applied recommended settings".to_string()
+            std::path::PathBuf::from(&self.default_output_dir)
         };
-    }
-
-    fn show_command_preview(&mut self, operation: &OperationType) {
-        // Check compatibility before generating preview
-        if !self.check_compatibility_and_warn() {
-            // Store context so we can continue if user chooses to
-            self.compatibility_warning_context = CompatibilityWarningContext::PreviewCommand(operation.clone());
-            return; // Show warning dialog, don't generate preview
-        }
-
-        self.force_show_command_preview(operation);
-    }
-
-    fn generate_output_filename_from_video(&mut self) {
-        if !self.dedicated_video_file.is_empty() {
-            let video_path = std::path::Path::new(&self.dedicated_video_file);
-            if let Some(parent) = video_path.parent() {
-                // Generate timestamp in YYYYMMDD_HHMMSS format
-                let timestamp = self.generate_timestamp();
-                
-                // Get appropriate extension based on current operation and settings
-                let extension = self.get_output_extension_for_current_operation();
-                
-                // Create output filename with timestamp
-                let output_filename = format!("output_{}.{}", timestamp, extension);
-                self.output_file = parent.join(output_filename).display().to_string();
+        let task = ProcessingTask {
+            id: self.next_task_id,
+            operation: OperationType::ReviewClip,
+            input_files: vec![input.to_string()],
+            output_file: review_clip::output_path(input, &folder, start, end),
+            video_settings: Some(review_clip::task_video_settings(&settings, start, end)),
+            audio_settings: None,
+            progress: 0.0,
+            status: TaskStatus::Pending,
+            error_message: None,
+            start_time: None,
+            paused_at: None,
+            estimated_total_time: None,
+            progress_unknown: false,
+            completion_time: None,
+            environment: None,
+            size_outcome: None,
+            executed_commands: Vec::new(),
+            label: None,
+            cancel_requested: false,
+            delete_partial_output: false,
+            checksum: self.new_task_checksum(),
+            low_priority: true,
+            archive: None,
+            timestamps: self.new_task_timestamps(),
+            guards: self.new_task_guards(),
+            log: Default::default(),
+        };
+        log_info!("Queueing review clip task {} ({:.3}s - {:.3}s)", task.id, start, end);
+        match &self.task_executor {
+            Some(executor) => {
+                executor.enqueue_task(task);
+                self.next_task_id += 1;
+                self.status_message = self.translations.review_clip_queued().to_string();
             }
+            None => log_error!("Task executor not available, cannot add task"),
+        }
+        if settings != self.review_clip_settings {
+            self.review_clip_settings = settings;
+            self.save_preferences();
         }
     }
     
-    fn generate_output_filename_from_input(&mut self) {
-        if !self.input_files.is_empty() {
-            let input_path = std::path::Path::new(&self.input_files[0]);
-            if let Some(parent) = input_path.parent() {
-                // Generate timestamp in YYYYMMDD_HHMMSS format
-                let timestamp = self.generate_timestamp();
-                
-                // Get appropriate extension based on current operation and settings
-                let extension = self.get_output_extension_for_current_operation();
-                
-                // Create output filename with timestamp
-                let output_filename = format!("output_{}.{}", timestamp, extension);
-                self.output_file = parent.join(output_filename).display().to_string();
-            }
-        }
+    /// Post-completion checksum settings a new task starts with
+    fn new_task_checksum(&self) -> checksum::TaskChecksum {
+        checksum::TaskChecksum::new(self.checksum_outputs, self.checksum_outputs && self.checksum_sidecar)
     }
     
-    fn generate_timestamp(&self) -> String {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default();
-        
-        // Use millisecond timestamp to ensure uniqueness
-        let millis = now.as_millis();
-        
-        // Add a random suffix to further ensure uniqueness
-        let random_suffix = uuid::Uuid::new_v4().as_u128() % 1000;
-        
-        format!("{}_{}", millis, random_suffix)
+    /// Post-completion timestamp options a new task starts with
+    fn new_task_timestamps(&self) -> file_times::TaskTimestamps {
+        file_times::TaskTimestamps::new(self.keep_source_times, self.write_creation_time)
     }
     
-    fn is_auto_generated_filename(&self, filename: &str) -> bool {
-        // Check if filename looks like our auto-generated format: output_timestamp.ext
-        if let Some(file_name) = std::path::Path::new(filename).file_name().and_then(|n| n.to_str()) {
-            file_name.starts_with("output_") && file_name.contains('.')
-        } else {
-            false
+    /// Output guards a new task runs under: the override set next to the Start button, else the global ones
+    fn new_task_guards(&self) -> output_guard::TaskGuards {
+        output_guard::TaskGuards::new(self.guard_override.clone().unwrap_or_else(|| self.output_guards.clone()))
+    }
+    
+    fn show_data_batch_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.data_batch else {
+            return;
+        };
+        match dialog.show(ctx, &self.video_settings, &self.translations) {
+            data_batch::DataBatchAction::None => {}
+            data_batch::DataBatchAction::Close => self.data_batch = None,
+            data_batch::DataBatchAction::Queue(rows) => {
+                self.data_batch = None;
+                self.queue_data_batch(rows);
+            }
         }
     }
     
-    fn get_output_extension_for_current_operation(&self) -> String {
-        match &self.current_operation {
-            Some(operation) => {
-                match operation {
-                    OperationType::VideoConvert | OperationType::VideoCompress | 
-                    OperationType::VideoResize | OperationType::VideoCrop | 
-                    OperationType::VideoRotate | OperationType::VideoFilter |
-                    OperationType::AddSubtitle | OperationType::AddWatermark => {
-                        self.video_settings.container_format.clone()
-                    },
-                    OperationType::AudioConvert | OperationType::AudioCompress | 
-                    OperationType::AudioResample | OperationType::AudioVolume | 
-                    OperationType::AudioTrim | OperationType::AudioMerge => {
-                        self.audio_settings.format.clone()
-                    },
-                    OperationType::FrameExtract => {
-                        self.video_settings.frame_format.clone()
-                    },
-                    OperationType::VideoToGif | OperationType::GifResize => {
-                        "gif".to_string()
-                    },
-                    _ => "mp4".to_string()
-                }
-            },
-            None => "mp4".to_string()
+    /// One AddWatermark task per CSV row with the row's text, image and position
+    fn queue_data_batch(&mut self, rows: Vec<data_batch::ResolvedRow>) {
+        if self.task_executor.is_none() {
+            log_error!("Task executor not available, cannot add task");
+            return;
+        }
+        let base_settings = self.video_settings_for_task();
+        let count = rows.len();
+        for row in rows {
+            let output_file = match &row.output {
+                Some(output) => output.clone(),
+                None => self.auto_output_path(&row.input, &base_settings.container_format),
+            };
+            let task = ProcessingTask {
+                id: self.next_task_id,
+                operation: OperationType::AddWatermark,
+                input_files: vec![row.input.clone()],
+                output_file,
+                video_settings: Some(row.apply(&base_settings)),
+                audio_settings: Some(self.audio_settings.clone()),
+                progress: 0.0,
+                status: TaskStatus::Pending,
+                error_message: None,
+                start_time: None,
+                paused_at: None,
+                estimated_total_time: None,
+                progress_unknown: false,
+                completion_time: None,
+                environment: None,
+                size_outcome: None,
+                executed_commands: Vec::new(),
+                label: None,
+                cancel_requested: false,
+                delete_partial_output: false,
+                checksum: self.new_task_checksum(),
+                low_priority: false,
+                archive: None,
+                timestamps: self.new_task_timestamps(),
+                guards: self.new_task_guards(),
+                log: Default::default(),
+            };
+            log_info!("Queueing task {} for CSV row {}", task.id, row.row);
+            if let Some(executor) = &self.task_executor {
+                executor.enqueue_task(task);
+            }
+            self.next_task_id += 1;
         }
+        self.status_message = self.translations.data_batch_queued(count);
     }
-
-    fn force_show_command_preview(&mut self, operation: &OperationType) {
-        // Prepare input files based on operation type
-        let input_files = match operation {
-            OperationType::AddSubtitle | OperationType::AddWatermark => {
-                // Use dedicated video file for these operations
-                vec![self.dedicated_video_file.clone()]
+    
+    fn create_diagnostic_bundle(&mut self) {
+        let tasks = self.diagnostics_include_tasks
+            .then(|| self.tasks.lock().map(|tasks| tasks.clone()).unwrap_or_default());
+        let bundle = diagnostics::DiagnosticBundle::collect(
+            self.hardware_detection.hardware_info.as_ref(),
+            self.hardware_detection.encoders.as_deref().unwrap_or(&[]),
+            tasks.as_deref(),
+        );
+        match diagnostics::save_bundle(&bundle) {
+            Ok(Some(path)) => {
+                log_info!("Diagnostic bundle saved to {}", path.display());
+                self.status_message = self.translations.diagnostics_saved().to_string();
+                self.show_diagnostics_dialog = false;
             }
-            _ => {
-                // Use regular input files for other operations
-                self.input_files.clone()
+            Ok(None) => {}
+            Err(e) => {
+                log_error!("Failed to save diagnostic bundle: {}", e);
+                self.status_message = format!("{}: {}", self.translations.save_error(), e);
             }
+        }
+    }
+    
+    fn export_run_report(&mut self) {
+        let report = match &self.workflow_executor.last_run_report {
+            Some(report) => report,
+            None => return,
         };
-        
-        let task = ProcessingTask {
-            id: 0,
-            operation: operation.clone(),
-            input_files,
-            output_file: self.output_file.clone(),
-            video_settings: Some(self.video_settings.clone()),
-            audio_settings: Some(self.audio_settings.clone()),
-            progress: 0.0,
-            status: TaskStatus::Pending,
-            error_message: None,
-            start_time: None,
-            estimated_total_time: None,
-            completion_time: None,
-        };
-        
-        match TaskExecutor::preview_command(&task) {
-            Ok(command) => {
-                self.command_preview = command;
-                self.status_message = self.translations.command_generated();
-            }
-            Err(e) => {
-                self.command_preview = format!("{}: {}", self.translations.command_generation_error(), e);
-                self.status_message = self.translations.command_generation_failed();
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_title("Export Run Report")
+            .set_file_name(format!("{}_run_report.json", report.workflow_name.replace(' ', "_")))
+            .save_file() {
+            
+            let result = report.to_json()
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+            match result {
+                Ok(_) => {
+                    log_info!("Run report exported to {}", path.display());
+                    self.status_message = self.translations.run_report_exported().to_string();
+                },
+                Err(e) => {
+                    self.status_message = format!("{}: {}", self.translations.save_error(), e);
+                }
             }
         }
     }
     
-
-    fn open_workflow_window(&mut self, _ctx: &egui::Context) {
-        log_debug!("Opening workflow window...");
-        
-        // Ensure there is a workflow to display
-        if self.current_workflow.is_none() {
-            log_debug!("Creating new workflow...");
-            self.current_workflow = Some(automation_flow::AutomationWorkflow::new("New Workflow".to_string()));
+    /// Write the open workflow's commands as a shell script; the extension picked decides the shell
+    fn export_workflow_script(&mut self) {
+        let mut dialog = rfd::FileDialog::new().set_title(self.translations.workflow_script_title());
+        let native = shell_quote::Shell::native();
+        for shell in std::iter::once(native).chain(shell_quote::Shell::all().into_iter().filter(|shell| *shell != native)) {
+            dialog = dialog.add_filter(shell.display_name(), &[workflow_script::extension(shell)]);
         }
+        let file_name = format!("{}.{}", self.editor().workflow.name.replace(' ', "_"), workflow_script::extension(native));
+        let Some(path) = dialog.set_file_name(file_name).save_file() else {
+            return;
+        };
         
-        // Set window open state
-        self.workflow_window_open = true;
-        self.show_automation_editor = true;
-        
-        log_debug!("Workflow window state set to open");
+        let hardware_encoders = self.get_cached_hardware_encoders();
+        if !hardware_encoders.is_empty() {
+            self.workflow_executor.update_hardware_cache(hardware_encoders);
+        }
+        let workflow = &self.editor().workflow;
+        let result = workflow_script::export(workflow, &self.workflow_executor, workflow_script::shell_for_path(&path), &self.translations)
+            .map_err(|refusal| refusal.message(workflow, &self.translations))
+            .and_then(|script| workflow_script::save(&path, &script).map_err(|e| format!("{}: {}", self.translations.save_error(), e)));
+        self.status_message = match result {
+            Ok(()) => self.translations.workflow_script_exported().to_string(),
+            Err(e) => {
+                log_warn!("{}", e);
+                e
+            }
+        };
     }
     
-    fn show_persistent_workflow_window(&mut self, ctx: &egui::Context) {
-        if !self.workflow_window_open {
+    fn show_settings_import_dialog(&mut self, ctx: &egui::Context) {
+        if self.pending_settings_import.is_none() && self.settings_import_report.is_empty() {
             return;
         }
         
-        let viewport_id = egui::ViewportId::from_hash_of("workflow_editor");
+        let mut apply = false;
+        let mut close = false;
         
-        let workflow_name = if let Some(ref workflow) = self.current_workflow {
-            format!("🔧 Automation Workflow Editor - {}", workflow.name)
-        } else {
-            "🔧 Automation Workflow Editor - No Workflow".to_string()
-        };
-        
-        let viewport_builder = egui::ViewportBuilder::default()
-            .with_title(workflow_name)
-            .with_inner_size([1200.0, 800.0])
-            .with_min_inner_size([800.0, 600.0])
-            .with_position([100.0, 100.0]);  // Set initial position to avoid completely covering main window
-        
-        let mut should_close = false;
-        
-        ctx.show_viewport_immediate(
-            viewport_id,
-            viewport_builder,
-            |ctx, _class| {
-                egui::CentralPanel::default().show(ctx, |ui| {
-                    // Toolbar
+        egui::Window::new(self.translations.import_settings_bundle())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if let Some((bundle, sections)) = &mut self.pending_settings_import {
+                    ui.label(format!("v{}", bundle.version));
+                    ui.label(self.translations.settings_bundle_select_sections());
+                    for (name, selected) in sections.iter_mut() {
+                        ui.checkbox(selected, self.translations.settings_section_name(name));
+                    }
+                    ui.add_space(10.0);
                     ui.horizontal(|ui| {
-                        if ui.button("🆕 New Workflow").clicked() {
-                            self.save_workflow_state();
-                            self.current_workflow = Some(automation_flow::AutomationWorkflow::new("New Workflow".to_string()));
-                        }
-                        
-                        if ui.button("💾 Save").clicked() {
-                            self.save_workflow();
-                        }
-                        
-                        if ui.button("📁 Load").clicked() {
-                            self.load_workflow();
-                        }
-                        
-                        ui.separator();
-                        
-                        let can_undo = self.history_index > 0;
-                        let can_redo = self.history_index < (self.workflow_history.len() as isize - 1);
-                        
-                        if ui.add_enabled(can_undo, egui::Button::new("Undo")).clicked() {
-                            self.undo_workflow();
-                        }
-                        
-                        if ui.add_enabled(can_redo, egui::Button::new("Redo")).clicked() {
-                            self.redo_workflow();
-                        }
-                        
-                        ui.separator();
-                        
-                        let has_selection = self.selected_node.is_some();
-                        if ui.add_enabled(has_selection, egui::Button::new("🚮 Delete")).clicked() {
-                            self.delete_selected_node();
+                        if ui.button(self.translations.import()).clicked() {
+                            apply = true;
                         }
-                        
-                        ui.separator();
-                        
-                        if ui.button("🚀 Execute").clicked() {
-                            self.execute_workflow();
-                        }
-                        
-                        ui.separator();
-                        
-                        if ui.button("❌ Close").clicked() {
-                            should_close = true;
+                        if ui.button(self.translations.cancel()).clicked() {
+                            close = true;
                         }
-                        
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label("💡 Press Ctrl+W to toggle this window");
-                        });
                     });
+                } else {
+                    for line in &self.settings_import_report {
+                        ui.label(line);
+                    }
+                    ui.add_space(10.0);
+                    if ui.button(self.translations.close()).clicked() {
+                        close = true;
+                    }
+                }
+            });
+        
+        if apply {
+            if let Some((bundle, sections)) = self.pending_settings_import.take() {
+                self.apply_settings_import(&bundle, &sections);
+            }
+        }
+        if close {
+            self.pending_settings_import = None;
+            self.settings_import_report.clear();
+        }
+    }
+    
+    fn show_file_selection(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
+        // For subtitle and watermark operations, show completely separate video file selector
+        match operation {
+            OperationType::AddSubtitle | OperationType::AddWatermark => {
+                // Show dedicated video file selector with drag-and-drop support
+                ui.group(|ui| {
+                    ui.label("📹 Video File (Required):");
                     
-                    ui.separator();
-                    
-                    // Add node menu - comprehensive version
-                    ui.horizontal_wrapped(|ui| {
-                        ui.label("Add Nodes:");
-                        
-                        ui.menu_button("📁 Input/Output", |ui| {
-                            if ui.button(automation_flow::NodeType::InputFile.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::InputFile, egui::pos2(100.0, 100.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::OutputFile.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::OutputFile, egui::pos2(600.0, 100.0));
-                                ui.close_menu();
-                            }
-                        });
-                        
-                        ui.menu_button("🎵 Audio Processing", |ui| {
-                            ui.vertical(|ui| {
-                                ui.label("Basic Operations:");
-                                if ui.small_button(automation_flow::NodeType::ExtractAudio.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::ExtractAudio, egui::pos2(200.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioConvert.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioConvert, egui::pos2(200.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioCompress.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioCompress, egui::pos2(200.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioResample.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioResample, egui::pos2(200.0, 300.0));
-                                    ui.close_menu();
-                                }
-                                ui.separator();
-                                ui.label("Audio Effects:");
-                                if ui.small_button(automation_flow::NodeType::AudioVolume.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioVolume, egui::pos2(200.0, 350.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioTrim.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioTrim, egui::pos2(200.0, 400.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioMerge.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioMerge, egui::pos2(200.0, 450.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioNormalize.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioNormalize, egui::pos2(200.0, 500.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioDeNoise.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioDeNoise, egui::pos2(200.0, 550.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioEqualizer.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioEqualizer, egui::pos2(200.0, 600.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioFade.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioFade, egui::pos2(200.0, 650.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioEcho.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioEcho, egui::pos2(200.0, 700.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::AudioSpeed.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioSpeed, egui::pos2(200.0, 750.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                        
-                        ui.menu_button("📺 Video Processing", |ui| {
-                            ui.vertical(|ui| {
-                                ui.label("Basic Operations:");
-                                if ui.small_button(automation_flow::NodeType::ExtractVideo.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::ExtractVideo, egui::pos2(300.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoConvert.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoConvert, egui::pos2(300.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoCompress.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoCompress, egui::pos2(300.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoRecode.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoRecode, egui::pos2(300.0, 300.0));
-                                    ui.close_menu();
-                                }
-                                ui.separator();
-                                ui.label("Video Transformations:");
-                                if ui.small_button(automation_flow::NodeType::VideoResize.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoResize, egui::pos2(300.0, 350.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoCrop.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoCrop, egui::pos2(300.0, 400.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoRotate.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoRotate, egui::pos2(300.0, 450.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoFilter.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoFilter, egui::pos2(300.0, 500.0));
-                                    ui.close_menu();
-                                }
-                                ui.separator();
-                                ui.label("Video Effects:");
-                                if ui.small_button(automation_flow::NodeType::FrameExtract.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::FrameExtract, egui::pos2(300.0, 550.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoFPS.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoFPS, egui::pos2(300.0, 600.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoStabilize.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoStabilize, egui::pos2(300.0, 650.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoDeinterlace.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoDeinterlace, egui::pos2(300.0, 700.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoColorCorrect.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoColorCorrect, egui::pos2(300.0, 750.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoBrightness.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoBrightness, egui::pos2(300.0, 800.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoSaturation.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoSaturation, egui::pos2(300.0, 850.0));
-                                    ui.close_menu();
-                                }
-                                if ui.small_button(automation_flow::NodeType::VideoGamma.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoGamma, egui::pos2(300.0, 900.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                        
-                        ui.menu_button("🎭 Audio/Video Operations", |ui| {
-                            ui.vertical(|ui| {
-                                if ui.button(automation_flow::NodeType::Combine.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::Combine, egui::pos2(400.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::SplitAudioVideo.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::SplitAudioVideo, egui::pos2(400.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoOverlay.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoOverlay, egui::pos2(400.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoPiP.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoPiP, egui::pos2(400.0, 300.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoSideBySide.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoSideBySide, egui::pos2(400.0, 350.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::AudioVideoSync.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AudioVideoSync, egui::pos2(400.0, 400.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                        
-                        ui.menu_button("🎨 Text & Graphics", |ui| {
-                            ui.vertical(|ui| {
-                                if ui.button(automation_flow::NodeType::AddSubtitle.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AddSubtitle, egui::pos2(500.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::AddWatermark.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AddWatermark, egui::pos2(500.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::AddText.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AddText, egui::pos2(500.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::AddLogo.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AddLogo, egui::pos2(500.0, 300.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::AddTimecode.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::AddTimecode, egui::pos2(500.0, 350.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                        
-                        ui.menu_button("🔄 Format Conversion", |ui| {
-                            ui.vertical(|ui| {
-                                if ui.button(automation_flow::NodeType::VideoToGif.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoToGif, egui::pos2(600.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::GifResize.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::GifResize, egui::pos2(600.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoToImages.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoToImages, egui::pos2(600.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::ImagesToVideo.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::ImagesToVideo, egui::pos2(600.0, 300.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                        
-                        ui.menu_button("📦 Batch & Advanced", |ui| {
-                            ui.vertical(|ui| {
-                                if ui.button(automation_flow::NodeType::BatchConvert.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::BatchConvert, egui::pos2(700.0, 150.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::BatchProcess.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::BatchProcess, egui::pos2(700.0, 200.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::MultiPassEncode.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::MultiPassEncode, egui::pos2(700.0, 250.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::QualityAnalysis.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::QualityAnalysis, egui::pos2(700.0, 300.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::FormatValidation.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::FormatValidation, egui::pos2(700.0, 350.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::StreamPrep.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::StreamPrep, egui::pos2(700.0, 400.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoEncrypt.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoEncrypt, egui::pos2(700.0, 450.0));
-                                    ui.close_menu();
-                                }
-                                if ui.button(automation_flow::NodeType::VideoDecrypt.display_name()).clicked() {
-                                    self.add_automation_node(automation_flow::NodeType::VideoDecrypt, egui::pos2(700.0, 500.0));
-                                    ui.close_menu();
-                                }
-                            });
-                        });
-                    });
+                    // Create drag-and-drop area for video file
+                    let video_area_height = 60.0;
+                    let (rect, response) = ui.allocate_exact_size(
+                        egui::vec2(ui.available_width(), video_area_height),
+                        egui::Sense::click()
+                    );
                     
-                    ui.separator();
+                    // Handle drag and drop for video file
+                    let is_being_dragged = !ui.ctx().input(|i| i.raw.hovered_files.is_empty());
+                    let can_accept_drop = is_being_dragged && ui.rect_contains_pointer(rect);
                     
-                    // Main canvas area - reserve space for UI elements below
-                    let mut canvas_rect = ui.available_rect_before_wrap();
-                    canvas_rect.max.y -= 80.0;  // Reserve 80 pixels for status bar/hints below
-                    let canvas_response = ui.allocate_rect(canvas_rect, egui::Sense::click_and_drag());
+                    // Draw the drop area
+                    let bg_color = if can_accept_drop {
+                        egui::Color32::from_rgba_unmultiplied(100, 200, 100, 100)
+                    } else if self.dedicated_video_file.is_empty() {
+                        egui::Color32::from_rgba_unmultiplied(100, 100, 100, 50)
+                    } else {
+                        egui::Color32::from_rgba_unmultiplied(50, 150, 50, 50)
+                    };
                     
-                    // Draw grid
-                    self.draw_grid(ui, canvas_rect);
+                    ui.painter().rect_filled(rect, 8.0, bg_color);
+                    ui.painter().rect_stroke(rect, 8.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
                     
-                    // Handle canvas interaction
-                    self.handle_canvas_interaction(ui, canvas_rect, &canvas_response);
+                    // Handle click to open file dialog
+                    if response.clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Video files", &["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"])
+                            .pick_file() {
+                            self.dedicated_video_file = path.display().to_string();
+                        }
+                    }
                     
-                    // Handle keyboard shortcuts
-                    ctx.input(|i| {
-                        if i.key_pressed(egui::Key::Escape) {
-                            if self.creating_connection {
-                                self.creating_connection = false;
-                                self.connection_source_node = None;
-                                self.connection_source_port = None;
-                                log_debug!("Connection cancelled by ESC key");
+                    // Process dropped files for video
+                    if !ui.ctx().input(|i| i.raw.dropped_files.is_empty()) {
+                        let dropped_files = ui.ctx().input(|i| i.raw.dropped_files.clone());
+                        if let Some(file) = dropped_files.first() {
+                            if let Some(path) = &file.path {
+                                let ext = path.extension()
+                                    .and_then(|e| e.to_str())
+                                    .unwrap_or("")
+                                    .to_lowercase();
+                                if ["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"].contains(&ext.as_str()) {
+                                    self.dedicated_video_file = path.display().to_string();
+                                    // Auto-generate output file name based on video file
+                                    self.generate_output_filename_from_video();
+                                }
                             }
                         }
-                        
-                        if i.key_pressed(egui::Key::Delete) {
-                            self.delete_selected_node();
-                        }
-                        
-                        if i.modifiers.ctrl && i.key_pressed(egui::Key::Z) {
-                            self.undo_workflow();
-                        }
-                        
-                        if (i.modifiers.ctrl && i.key_pressed(egui::Key::Y)) || 
-                           (i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::Z)) {
-                            self.redo_workflow();
-                        }
-                    });
+                    }
                     
-                    // Draw nodes
-                    let workflow_nodes = if let Some(ref workflow) = self.current_workflow {
-                        workflow.nodes.values().cloned().collect::<Vec<_>>()
+                    // Draw text in the drop area
+                    let text = if self.dedicated_video_file.is_empty() {
+                        if can_accept_drop {
+                            "📹 Drop video file here"
+                        } else {
+                            "📹 Click to select video file or drag & drop here"
+                        }
                     } else {
-                        Vec::new()
+                        // Show file name
+                        let file_name = std::path::Path::new(&self.dedicated_video_file)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("Unknown");
+                        &format!("📹 {}", file_name)
                     };
                     
-                    for node in workflow_nodes {
-                        self.draw_single_node_with_canvas(ui, &node, canvas_rect);
-                    }
+                    ui.painter().text(
+                        rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        text,
+                        egui::FontId::default(),
+                        if self.dedicated_video_file.is_empty() { egui::Color32::GRAY } else { egui::Color32::WHITE }
+                    );
                     
-                    // Draw connection lines
-                    if let Some(ref workflow) = self.current_workflow {
-                        let workflow_clone = workflow.clone();
-                        self.draw_connections_with_canvas(ui, &workflow_clone, canvas_rect);
-                    }
+                    ui.add_space(5.0);
                     
-                    // Status bar - display important hint information
-                    ui.separator();
+                    // Show controls and status
                     ui.horizontal(|ui| {
-                        ui.label("💡 Tips:");
-                        if self.creating_connection {
-                            ui.label("Click input port (left side) to complete connection | ESC to cancel");
-                        } else {
-                            ui.label("Click output ports (right) to start connections | Del=Delete | Ctrl+Z=Undo");
-                        }
-                        
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if let Some(ref workflow) = self.current_workflow {
-                                ui.label(format!("Nodes: {} | Connections: {}", 
-                                    workflow.nodes.len(), 
-                                    workflow.connections.len()));
+                        if !self.dedicated_video_file.is_empty() {
+                            if ui.button("🚮 Clear").clicked() {
+                                self.dedicated_video_file.clear();
                             }
-                        });
-                    });
-                    
-                    // Bottom information and properties panel
-                    egui::ScrollArea::vertical()
-                        .max_height(200.0)
-                        .show(ui, |ui| {
-                            ui.separator();
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("Shortcuts:");
-                                ui.label("Del=Delete | Ctrl+Z=Undo | Ctrl+Y=Redo | ESC=Cancel");
-                            });
-                            
-                            ui.horizontal(|ui| {
-                                ui.label("Instructions:");
-                                ui.label("Click output ports (right side) to start connections, click input ports (left side) to complete them.");
-                            });
-                            
-                            // Node property editing
-                            if let Some(ref selected_id) = self.selected_node.clone() {
-                                // Ensure we have the latest hardware encoders BEFORE borrowing workflow
-                                let current_hw_encoders = self.get_cached_hardware_encoders();
-                                
-                                if let Some(ref mut workflow) = self.current_workflow {
-                                    if let Some(ref mut node) = workflow.nodes.get_mut(selected_id) {
-                                        ui.separator();
-                                        ui.heading("Node Properties");
-                                        ui.label(format!("Type: {}", node.node_type.display_name()));
-                                        
-                                        // Use comprehensive parameter UI
-                                        node.show_comprehensive_parameters_ui(ui, &self.translations, &current_hw_encoders);
-                                        
-                                        // Auto-fill output filenames when input files change
-                                        if node.node_type == automation_flow::NodeType::InputFile {
-                                            if let Some(ref mut workflow) = self.current_workflow {
-                                                let workflow_clone = workflow.clone();
-                                                let mut changed = false;
-                                                for (_, output_node) in workflow.nodes.iter_mut() {
-                                                    if output_node.node_type == automation_flow::NodeType::OutputFile {
-                                                        if output_node.auto_fill_output_from_input(&workflow_clone) {
-                                                            changed = true;
-                                                        }
-                                                    }
-                                                }
-                                                if changed {
-                                                    self.save_workflow_state();
-                                                }
-                                            }
-                                        }
-                                    }
+                            if ui.button("📁 Change").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("Video files", &["mp4", "avi", "mkv", "mov", "webm", "flv", "wmv", "m4v"])
+                                    .pick_file() {
+                                    self.dedicated_video_file = path.display().to_string();
                                 }
                             }
-                        });
-                });
-                
-                // Check system window close request
-                if ctx.input(|i| i.viewport().close_requested()) {
-                    should_close = true;
-                }
-            },
+                        }
+                    });
+                    
+                    // Show status for video file
+                    if self.dedicated_video_file.is_empty() {
+                        ui.colored_label(egui::Color32::from_rgb(255, 150, 100), 
+                            "⚠ Please select a video file first");
+                    } else {
+                        let is_video = self.dedicated_video_file.to_lowercase().ends_with(".mp4") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".avi") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".mkv") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".mov") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".webm") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".flv") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".wmv") ||
+                                      self.dedicated_video_file.to_lowercase().ends_with(".m4v");
+                        
+                        if is_video {
+                            ui.colored_label(egui::Color32::from_rgb(100, 200, 100), "✅ Video file ready");
+                        } else {
+                            ui.colored_label(egui::Color32::from_rgb(255, 150, 100), 
+                                "⚠ Selected file is not a video file");
+                        }
+                    }
+                });
+                
+                ui.add_space(10.0);
+                
+                // Show output file selector for these operations
+                OutputSelector::show(
+                    ui, 
+                    &mut self.output_file, 
+                    operation,
+                    Some(&self.video_settings),
+                    Some(&self.audio_settings),
+                    &self.translations
+                );
+                self.show_output_favorites(ui, operation);
+                
+                // Don't show regular input files section for these operations
+                return;
+            }
+            _ => {
+                // Normal file selection for other operations
+                let allow_multiple = matches!(operation, 
+                    OperationType::AudioMerge | 
+                    OperationType::BatchConvert | 
+                    OperationType::VideoAudioMerge
+                );
+                
+                let label = match operation {
+                    OperationType::VideoAudioMerge => self.translations.input_files_video_audio(),
+                    OperationType::AudioMerge => self.translations.input_audio_files(),
+                    OperationType::BatchConvert => self.translations.batch_input_files(),
+                    _ => self.translations.input_files(),
+                };
+                
+                let old_files_len = self.input_files.len();
+                let old_first_file = self.input_files.first().cloned();
+                let before = (old_files_len > 0).then(|| self.session_snapshot());
+                
+                FileSelector::show(ui, &mut self.input_files, allow_multiple, label, &self.translations, || {});
+                if let Some(before) = before.filter(|_| self.input_files.is_empty()) {
+                    self.session_undo.push(session_undo::UndoAction::ClearInputFiles, before);
+                }
+                
+                if *operation == OperationType::AudioMerge && self.audio_settings.merge_mode == audio_mix::MODE_MIX && !self.input_files.is_empty() {
+                    let inputs: Vec<(String, Option<f64>)> = self.input_files.clone().into_iter()
+                        .map(|file| {
+                            let duration = self.merge_input_duration(&file);
+                            (file, duration)
+                        })
+                        .collect();
+                    audio_mix::show_input_gains(ui, &inputs, &mut self.audio_settings.mix, &self.translations);
+                }
+                
+                // Check if files changed and auto-generate output filename
+                let files_changed = self.input_files.len() != old_files_len || 
+                                   self.input_files.first() != old_first_file.as_ref();
+                
+                if files_changed && !self.input_files.is_empty() {
+                    // Only auto-generate if output is empty or already auto-generated
+                    if self.output_file.is_empty() || self.is_auto_generated_filename(&self.output_file) {
+                        self.generate_output_filename_from_input();
+                    }
+                }
+            }
+        }
+        
+        if !self.input_files.is_empty() && self.input_files.len() == 1 {
+            let current_file = &self.input_files[0];
+            
+            // Only analyze file if it's different from the last analyzed file
+            if current_file != &self.last_analyzed_file {
+                // Replacing the probe cancels whatever stages of the previous file are still running
+                self.file_probe = Some(progressive_probe::ProgressiveProbe::start(current_file, ui.ctx()));
+                self.last_analyzed_file = current_file.clone();
+                self.clear_detected_file_info();
+                self.poll_file_probe();
+                
+                // Auto-generate output filename when input file is selected
+                // Only auto-generate if output is empty or already auto-generated
+                if self.output_file.is_empty() || self.is_auto_generated_filename(&self.output_file) {
+                    self.generate_output_filename_from_input();
+                }
+            }
+        }
+        
+        ui.add_space(10.0);
+        if *operation == OperationType::FrameExtract {
+            self.show_frame_extract_output(ui);
+            return;
+        }
+        let before = (!self.output_file.is_empty()).then(|| self.session_snapshot());
+        OutputSelector::show(
+            ui, 
+            &mut self.output_file, 
+            operation,
+            Some(&self.video_settings),
+            Some(&self.audio_settings),
+            &self.translations
+        );
+        if let Some(before) = before.filter(|_| self.output_file.is_empty()) {
+            self.session_undo.push(session_undo::UndoAction::ClearOutputFile, before);
+        }
+        self.show_output_favorites(ui, operation);
+        self.show_output_extension_conflict(ui, operation);
+    }
+    
+    /// Frame extraction writes a numbered series into a folder instead of one output file
+    fn show_frame_extract_output(&mut self, ui: &mut egui::Ui) {
+        if let Some(input) = self.input_files.first() {
+            let default_dir = frame_extract::default_output_dir(input, &self.default_output_dir);
+            let settings = &mut self.video_settings;
+            if settings.frame_output_dir.is_empty() || settings.frame_output_dir == self.last_auto_frame_dir {
+                settings.frame_output_dir = default_dir.clone();
+                self.last_auto_frame_dir = default_dir;
+            }
+        }
+        frame_extract::show_output_settings(ui, &mut self.video_settings, &self.translations);
+        self.output_file = frame_extract::output_pattern(&self.video_settings);
+    }
+    
+    fn show_settings_panel(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
+        if self.detected_programs.len() > 1 {
+            self.show_program_selector(ui);
+            ui.add_space(10.0);
+        }
+        
+        // Show operation-specific settings
+        let ctx = ui.ctx().clone();
+        let cached_hw_encoders = self.get_cached_hardware_encoders();
+        let effective = self.effective_codecs(operation);
+        operation_settings::OperationSettings::show(
+            &ctx,
+            ui, 
+            operation, 
+            &mut self.video_settings, 
+            &mut self.audio_settings, 
+            &self.translations,
+            &operation_settings::SettingsContext {
+                detected_resolution: self.detected_resolution,
+                is_portrait: self.is_portrait_video,
+                cached_hw_encoders: &cached_hw_encoders,
+                effective: &effective,
+            }
+        );
+        
+        // Advisories only make sense for operations that re-encode the whole input
+        let advisory_operation = matches!(operation,
+            OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize |
+            OperationType::AudioConvert | OperationType::AudioCompress | OperationType::ExtractAudio
+        );
+        if advisory_operation && self.input_files.len() == 1 {
+            if let Some(info) = &self.detected_media_info {
+                ui.add_space(10.0);
+                encode_advisor::show_advisories(
+                    ui,
+                    info,
+                    &mut self.video_settings,
+                    &mut self.audio_settings,
+                    &mut self.suppressed_advisories,
+                    &self.translations,
+                );
+            }
+        }
+        
+        let encodes_video = matches!(operation,
+            OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize |
+            OperationType::VideoCrop | OperationType::VideoRotate | OperationType::VideoFilter
+        );
+        if encodes_video && self.input_files.len() == 1 {
+            if let Some(info) = &self.detected_media_info {
+                ui.add_space(10.0);
+                alpha_video::show_alpha_panel(ui, info, &mut self.video_settings, &self.translations);
+            }
+        }
+        
+        if *operation == OperationType::VideoCompress && self.input_files.len() == 1 {
+            if let Some(info) = &self.detected_media_info {
+                ui.add_space(10.0);
+                size_target::show_planner(ui, info, &mut self.video_settings, &mut self.audio_settings, &self.translations);
+            }
+        }
+        
+        // Chapter edits apply to a single input only
+        if self.input_files.len() == 1 {
+            match operation {
+                OperationType::VideoConvert => {
+                    ui.add_space(10.0);
+                    let settings = &mut self.video_settings;
+                    chapters::show_editor(ui, &mut settings.preserve_chapters, &mut settings.chapter_edits,
+                        &self.detected_chapters, &settings.container_format, &self.translations);
+                }
+                OperationType::AudioConvert => {
+                    ui.add_space(10.0);
+                    let settings = &mut self.audio_settings;
+                    chapters::show_editor(ui, &mut settings.preserve_chapters, &mut settings.chapter_edits,
+                        &self.detected_chapters, &settings.format, &self.translations);
+                }
+                _ => {}
+            }
+        } else {
+            self.video_settings.chapter_edits = None;
+            self.audio_settings.chapter_edits = None;
+        }
+        
+        if *operation == OperationType::AddSubtitle {
+            ui.add_space(10.0);
+            self.show_subtitle_preview(ui);
+        }
+        
+        if *operation == OperationType::AddWatermark {
+            ui.add_space(10.0);
+            if ui.button(self.translations.data_batch_button()).clicked() {
+                self.data_batch = Some(data_batch::DataBatchDialog::default());
+            }
+        }
+        
+        if *operation == OperationType::CutAndJoin {
+            ui.add_space(10.0);
+            let input_file = self.input_files.first().cloned().unwrap_or_default();
+            timeline::TimelineStrip::show(
+                ui,
+                &mut self.timeline_state,
+                &input_file,
+                self.detected_duration.unwrap_or(0.0),
+                &mut self.video_settings.cut_segments,
+                &self.translations,
+            );
+        }
+        
+        ui.add_space(10.0);
+        
+        // Show common encoding settings for operations that need them
+        let needs_video_settings = matches!(operation,
+            OperationType::VideoConvert | OperationType::VideoCompress | 
+            OperationType::VideoResize | OperationType::VideoCrop | 
+            OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
+            OperationType::VideoAudioMerge | OperationType::ExtractVideo
+        );
+        
+        let needs_audio_settings = matches!(operation,
+            OperationType::AudioConvert | OperationType::AudioCompress | 
+            OperationType::AudioResample | OperationType::AudioVolume | 
+            OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize |
+            OperationType::VideoAudioMerge | OperationType::ExtractAudio
+        );
+        
+        let cached_hw_encoders = self.get_cached_hardware_encoders();
+        
+        if *operation == OperationType::VideoConvert {
+            if needs_video_settings {
+                SettingsPanel::show_video_settings(ui, &mut self.video_settings, &self.translations, &cached_hw_encoders);
+            }
+            
+            if needs_audio_settings {
+                ui.add_space(10.0);
+                SettingsPanel::show_audio_settings(ui, &mut self.audio_settings, &self.translations);
+            }
+        } else {
+            ui.horizontal(|ui| {
+                if needs_video_settings {
+                    ui.vertical(|ui| {
+                        SettingsPanel::show_video_settings(ui, &mut self.video_settings, &self.translations, &cached_hw_encoders);
+                    });
+                }
+                
+                if needs_audio_settings {
+                    ui.vertical(|ui| {
+                        SettingsPanel::show_audio_settings(ui, &mut self.audio_settings, &self.translations);
+                    });
+                }
+            });
+        }
+        
+        help_overlay::paint(ui.ctx(), help_overlay::PANEL_SETTINGS, &self.translations);
+    }
+    
+    
+    /// Program picker for transport streams carrying several channels
+    fn show_program_selector(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(self.translations.input_program());
+                let selected_text = self.detected_programs.iter()
+                    .find(|program| Some(program.program_id) == self.video_settings.input_program)
+                    .map(|program| program.label())
+                    .unwrap_or_else(|| self.translations.all_programs().to_string());
+                egui::ComboBox::from_id_salt("input_program")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for program in &self.detected_programs {
+                            ui.selectable_value(&mut self.video_settings.input_program, Some(program.program_id), program.label());
+                        }
+                        ui.selectable_value(&mut self.video_settings.input_program, None, self.translations.all_programs());
+                    });
+            });
+            ui.weak(self.translations.input_program_hint());
+        });
+    }
+    
+    fn validate_operation_requirements(&self, operation: &OperationType) -> (bool, Option<String>) {
+        match operation {
+            OperationType::AddSubtitle => {
+                if self.dedicated_video_file.is_empty() {
+                    return (false, Some(self.translations.video_file_required().to_string()));
+                }
+                if self.video_settings.subtitle_file.is_empty() {
+                    return (false, Some(self.translations.subtitle_file_required().to_string()));
+                }
+                if self.output_file.is_empty() {
+                    return (false, Some("Please select output file".to_string()));
+                }
+                // Add informative message about subtitle operation
+                let info = "Note: Subtitle operations are experimental in static library mode. If the operation fails, the system will fallback to safe file copy.";
+                (true, Some(info.to_string()))
+            }
+            OperationType::AddWatermark => {
+                if self.dedicated_video_file.is_empty() {
+                    return (false, Some(self.translations.video_file_required().to_string()));
+                }
+                if self.video_settings.watermark_file.is_empty() && self.video_settings.watermark_text.trim().is_empty() {
+                    return (false, Some(self.translations.watermark_file_required().to_string()));
+                }
+                if self.output_file.is_empty() {
+                    return (false, Some("Please select output file".to_string()));
+                }
+                (true, None)
+            }
+            OperationType::CutAndJoin => {
+                if self.input_files.is_empty() || self.output_file.is_empty() {
+                    return (false, None);
+                }
+                match cut_join::normalize_segments(&self.video_settings.cut_segments, self.detected_duration) {
+                    Ok(_) => (true, None),
+                    Err(e) => (false, Some(e.to_string())),
+                }
+            }
+            OperationType::FrameRateConvert => {
+                if self.input_files.is_empty() || self.output_file.is_empty() {
+                    return (false, None);
+                }
+                match self.video_settings.fps_conversion.validate() {
+                    Ok(()) => (true, None),
+                    Err(e) => (false, Some(e.to_string())),
+                }
+            }
+            _ => {
+                let basic_requirements = !self.input_files.is_empty() && !self.output_file.is_empty();
+                (basic_requirements, None)
+            }
+        }
+    }
+
+    fn show_action_buttons(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
+        ui.add_space(10.0);
+        
+        let (can_start, validation_message) = self.validate_operation_requirements(operation);
+        
+        ui.horizontal(|ui| {
+            let start_button = egui::Button::new(
+                egui::RichText::new(self.translations.start_processing())
+                    .color(egui::Color32::WHITE)
+                    .strong()
+            ).min_size(egui::vec2(120.0, 35.0));
+            
+            if can_start {
+                if ui.add(start_button.fill(egui::Color32::from_rgb(34, 139, 34))
+                    .rounding(egui::Rounding::same(6.0))).clicked() {
+                    // Debounce rapid double clicks so the same task isn't queued twice
+                    let debounced = self.last_start_click
+                        .map(|t| t.elapsed() < std::time::Duration::from_millis(500))
+                        .unwrap_or(false);
+                    if !debounced {
+                        self.last_start_click = Some(std::time::Instant::now());
+                        self.start_processing(operation);
+                    }
+                }
+            } else {
+                ui.add_enabled(false, start_button.fill(egui::Color32::DARK_GRAY)
+                    .rounding(egui::Rounding::same(6.0)));
+            }
+            
+            ui.add_space(10.0);
+            
+            let preview_button = egui::Button::new(
+                egui::RichText::new(self.translations.preview_command())
+                    .color(egui::Color32::WHITE)
+                    .strong()
+            ).min_size(egui::vec2(120.0, 35.0));
+            
+            if can_start {
+                if ui.add(preview_button.fill(egui::Color32::from_rgb(70, 130, 180))
+                    .rounding(egui::Rounding::same(6.0))).clicked() {
+                    self.show_command_preview(operation);
+                }
+            } else {
+                ui.add_enabled(false, preview_button.fill(egui::Color32::DARK_GRAY)
+                    .rounding(egui::Rounding::same(6.0)));
+            }
+            
+            ui.add_space(10.0);
+            
+            let reset_button = egui::Button::new(
+                egui::RichText::new(self.translations.reset_settings())
+                    .color(egui::Color32::WHITE)
+                    .strong()
+            ).min_size(egui::vec2(100.0, 35.0))
+                .fill(egui::Color32::from_rgb(255, 140, 0))
+                .rounding(egui::Rounding::same(6.0));
+            
+            if ui.add(reset_button).clicked() {
+                self.reset_settings();
+            }
+        });
+        
+        // Show validation message if exists
+        if let Some(message) = validation_message {
+            ui.add_space(5.0);
+            // Use different colors for errors vs warnings
+            let (color, _can_proceed) = if can_start {
+                (egui::Color32::from_rgb(255, 165, 0), true) // Orange for warnings
+            } else {
+                (egui::Color32::from_rgb(255, 100, 100), false) // Red for errors
+            };
+            
+            ui.colored_label(color, format!("⚠ {}", message));
+        }
+        
+        ui.add_space(5.0);
+        egui::CollapsingHeader::new(self.translations.output_guard_advanced())
+            .id_salt("start_advanced")
+            .show(ui, |ui| {
+                let mut overriding = self.guard_override.is_some();
+                if ui.checkbox(&mut overriding, self.translations.output_guard_override())
+                    .on_hover_text(self.translations.output_guard_override_hint())
+                    .changed()
+                {
+                    self.guard_override = overriding.then(|| self.output_guards.clone());
+                }
+                match self.guard_override.as_mut() {
+                    Some(guards) => {
+                        output_guard::show_editor(ui, guards, &self.translations);
+                    }
+                    None if self.output_guards.is_active() => {
+                        ui.weak(format!("{} {}", self.translations.output_guard_global(), self.output_guards.summary()));
+                    }
+                    None => {
+                        ui.weak(self.translations.output_guard_none());
+                    }
+                }
+            });
+        
+        ui.add_space(10.0);
+    }
+    
+    fn start_processing(&mut self, operation: &OperationType) {
+        // The inline choice under the output selector has to be made first
+        if self.output_extension_conflict(operation).is_some() && !self.output_extension_kept(operation) {
+            self.status_message = self.translations.resolve_extension_mismatch().to_string();
+            return;
+        }
+
+        if *operation == OperationType::FrameExtract {
+            if let Err(e) = frame_extract::prepare_output_dir(&self.video_settings.frame_output_dir) {
+                self.status_message = format!("{}: {}", self.translations.frame_extract_output_dir_error(), e);
+                return;
+            }
+        }
+
+        // Values ffmpeg would reject mid-task
+        let issues = parameter_limits::validate(operation, &self.video_settings, &self.audio_settings);
+        if !issues.is_empty() {
+            self.status_message = self.translations.parameter_limit_blocked().to_string();
+            self.pending_parameter_issues = Some((operation.clone(), issues));
+            return;
+        }
+
+        // Check compatibility before starting processing
+        if !self.check_compatibility_and_warn() {
+            // Store context so we can continue if user chooses to
+            self.compatibility_warning_context = CompatibilityWarningContext::StartProcessing(operation.clone());
+            return; // Show warning dialog, don't start processing
+        }
+
+        if let Some(hardware_codec) = self.encoder_choice_needed(operation) {
+            self.pending_encoder_choice = Some((operation.clone(), hardware_codec));
+            return;
+        }
+
+        self.force_start_processing(operation);
+    }
+
+    fn force_start_processing(&mut self, operation: &OperationType) {
+        // Prepare input files based on operation type
+        let input_files = match operation {
+            OperationType::AddSubtitle | OperationType::AddWatermark => {
+                // Use dedicated video file for these operations
+                vec![self.dedicated_video_file.clone()]
+            }
+            _ => {
+                // Use regular input files for other operations
+                self.input_files.clone()
+            }
+        };
+        
+        // Generate unique output filename for each task
+        let unique_output_file = if *operation == OperationType::FrameExtract {
+            // A name pattern inside the chosen folder, numbered by ffmpeg
+            self.output_file.clone()
+        } else if self.is_auto_generated_filename(&self.output_file) {
+            // If it's an auto-generated filename, create a new unique filename
+            let path = std::path::Path::new(&self.output_file);
+            let extension = path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("mp4")
+                .to_string();
+            match input_files.first() {
+                Some(input) => self.auto_output_path(input, &extension),
+                None => self.output_file.clone(),
+            }
+        } else {
+            // If it's a user-specified filename, add task ID to ensure uniqueness
+            let path = std::path::Path::new(&self.output_file);
+            let stem = path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output");
+            let extension = path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("mp4");
+            
+            if let Some(parent) = path.parent() {
+                parent.join(format!("{}_{}.{}", stem, self.next_task_id, extension))
+                    .display().to_string()
+            } else {
+                format!("{}_{}.{}", stem, self.next_task_id, extension)
+            }
+        };
+        
+        let task = ProcessingTask {
+            id: self.next_task_id,
+            operation: operation.clone(),
+            input_files,
+            output_file: unique_output_file,
+            video_settings: Some(self.video_settings_for_task()),
+            audio_settings: Some(self.audio_settings.clone()),
+            progress: 0.0,
+            status: TaskStatus::Pending,
+            error_message: None,
+            start_time: None,
+            paused_at: None,
+            estimated_total_time: None,
+            progress_unknown: false,
+            completion_time: None,
+            environment: None,
+            size_outcome: None,
+            executed_commands: Vec::new(),
+            label: None,
+            cancel_requested: false,
+            delete_partial_output: false,
+            checksum: self.new_task_checksum(),
+            low_priority: false,
+            archive: None,
+            timestamps: self.new_task_timestamps(),
+            guards: self.new_task_guards(),
+            log: Default::default(),
+        };
+        
+        log_info!("Queueing task {} ({})", task.id, task.operation.display_name(&self.translations));
+        let renamed = if let Some(executor) = &self.task_executor {
+            executor.enqueue_task(task)
+        } else {
+            log_error!("Task executor not available, cannot add task");
+            return;
+        };
+        
+        self.next_task_id += 1;
+        self.status_message = match renamed {
+            Some(path) => self.translations.output_path_renamed(&operation.display_name(&self.translations), &path),
+            None => self.translations.task_added(&operation.display_name(&self.translations)),
+        };
+    }
+    
+    /// Typed output extension against the container the operation writes
+    fn output_extension_conflict(&self, operation: &OperationType) -> Option<output_extension::ExtensionConflict> {
+        let extension = std::path::Path::new(&self.output_file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("");
+        let container = match output_extension::container_source(operation)? {
+            output_extension::ContainerSource::Video => self.video_settings.container_format.as_str(),
+            output_extension::ContainerSource::Audio => self.audio_settings.format.as_str(),
+            output_extension::ContainerSource::Fixed(container) => container,
+        };
+        output_extension::reconcile(extension, container, operation)
+    }
+
+    /// The user chose to keep the mismatched extension and force the container with -f
+    fn output_extension_kept(&self, operation: &OperationType) -> bool {
+        match output_extension::container_source(operation) {
+            Some(output_extension::ContainerSource::Video) => self.video_settings.keep_output_extension,
+            Some(output_extension::ContainerSource::Audio) => self.audio_settings.keep_output_extension,
+            _ => false,
+        }
+    }
+
+    fn apply_extension_fix(&mut self, operation: &OperationType, conflict: &output_extension::ExtensionConflict, fix: output_extension::ExtensionFix) {
+        let source = output_extension::container_source(operation);
+        match fix {
+            output_extension::ExtensionFix::ChangeContainer => match source {
+                Some(output_extension::ContainerSource::Video) => self.video_settings.container_format = conflict.extension.clone(),
+                Some(output_extension::ContainerSource::Audio) => self.audio_settings.format = conflict.extension.clone(),
+                _ => {}
+            },
+            output_extension::ExtensionFix::RenameOutput => {
+                let auto_generated = self.is_auto_generated_filename(&self.output_file);
+                self.output_file = output_extension::with_extension(&self.output_file, &conflict.container);
+                if auto_generated {
+                    self.last_auto_output_file = self.output_file.clone();
+                }
+            }
+            output_extension::ExtensionFix::KeepBoth => match source {
+                Some(output_extension::ContainerSource::Video) => self.video_settings.keep_output_extension = true,
+                Some(output_extension::ContainerSource::Audio) => self.audio_settings.keep_output_extension = true,
+                _ => {}
+            },
+        }
+    }
+
+    /// Inline choice when the output extension and the container disagree, also after the container changes
+    fn show_output_extension_conflict(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
+        if self.output_file.is_empty() {
+            return;
+        }
+        let Some(conflict) = self.output_extension_conflict(operation) else {
+            return;
+        };
+
+        // A name the app generated just follows the container
+        if self.is_auto_generated_filename(&self.output_file) && conflict.fixes.contains(&output_extension::ExtensionFix::RenameOutput) {
+            self.apply_extension_fix(operation, &conflict, output_extension::ExtensionFix::RenameOutput);
+            return;
+        }
+
+        if self.output_extension_kept(operation) {
+            ui.horizontal(|ui| {
+                ui.weak(self.translations.extension_kept(&conflict.extension, &conflict.container));
+                if ui.small_button("✖").on_hover_text(self.translations.extension_kept_undo()).clicked() {
+                    self.video_settings.keep_output_extension = false;
+                    self.audio_settings.keep_output_extension = false;
+                }
+            });
+            return;
+        }
+
+        let mut chosen = None;
+        ui.group(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(255, 150, 100), self.translations.extension_mismatch(&conflict.extension, &conflict.container));
+            ui.horizontal_wrapped(|ui| {
+                for &fix in &conflict.fixes {
+                    if ui.button(self.translations.extension_fix(fix, &conflict.extension, &conflict.container)).clicked() {
+                        chosen = Some(fix);
+                    }
+                }
+            });
+        });
+        if let Some(fix) = chosen {
+            self.apply_extension_fix(operation, &conflict, fix);
+        }
+    }
+
+    fn check_compatibility_and_warn(&mut self) -> bool {
+        // Skip compatibility check for GIF operations
+        if let Some(ref operation) = self.current_operation {
+            if matches!(operation, OperationType::VideoToGif | OperationType::GifResize) {
+                return true;
+            }
+        }
+
+        // Check if input file has audio/video streams when needed
+        if !self.input_files.is_empty() && self.input_files.len() == 1 {
+            if let Some(ref operation) = self.current_operation {
+                // Check stream availability based on operation type
+                let needs_video = matches!(operation,
+                    OperationType::VideoConvert | OperationType::VideoCompress |
+                    OperationType::VideoResize | OperationType::VideoCrop |
+                    OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
+                    OperationType::ExtractVideo | OperationType::VideoToGif
+                );
+                
+                let needs_audio = matches!(operation,
+                    OperationType::AudioConvert | OperationType::AudioCompress |
+                    OperationType::AudioResample | OperationType::AudioVolume |
+                    OperationType::AudioTrim | OperationType::AudioNormalize | OperationType::ExtractAudio
+                );
+                
+                // Stream presence from the file probe; before its streams stage finishes ask ffprobe directly,
+                // and if that fails too the streams are unknown and the check is skipped
+                let streams = match &self.detected_media_info {
+                    Some(info) => Some((!info.video_streams.is_empty(), !info.audio_streams.is_empty())),
+                    None => probe_cache::probe_cache().detect_streams(&self.input_files[0]).ok()
+                        .map(|(video, audio, _)| (video, audio)),
+                };
+                let (has_video, has_audio) = streams.unwrap_or((true, true));
+                
+                if needs_video && !has_video {
+                    self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
+                        "所选文件不包含视频流。此操作需要视频输入。".to_string()
+                    } else {
+                        "The selected file does not contain a video stream. This operation requires video input.".to_string()
+                    };
+                    self.compatibility_recommended_codec = None;
+                    self.compatibility_recommended_format = None;
+                    self.show_compatibility_warning = true;
+                    return false;
+                }
+                
+                if needs_audio && !has_audio {
+                    self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
+                        "所选文件不包含音频流。此操作需要音频输入。".to_string()
+                    } else {
+                        "The selected file does not contain an audio stream. This operation requires audio input.".to_string()
+                    };
+                    self.compatibility_recommended_codec = None;
+                    self.compatibility_recommended_format = None;
+                    self.show_compatibility_warning = true;
+                    return false;
+                }
+            }
+        }
+
+        // Validate the codecs "auto" really resolves to, for the container the operation writes
+        let Some(operation) = self.current_operation.clone() else {
+            return true;
+        };
+        let container = self.target_container(&operation);
+        let container_format = container.as_str();
+        if container_format.is_empty() {
+            return true; // No format specified, nothing to check
+        }
+        let effective = self.effective_codecs(&operation);
+
+        // Check video codec compatibility
+        if let Some(video_codec) = effective.video.filter(|codec| codec != "copy") {
+            if CodecManager::validate_codec_format_compatibility(&video_codec, container_format, false).is_err() {
+                let recommended_codec = CodecManager::get_best_video_codec_for_format(container_format);
+                self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
+                    format!("视频编码器 '{}' 与容器格式 '{}' 不兼容。这可能导致编码失败。", video_codec, container_format)
+                } else {
+                    format!("Video codec '{}' is not compatible with container format '{}'. This may cause encoding to fail.", video_codec, container_format)
+                };
+                self.compatibility_recommended_codec = Some(recommended_codec);
+                self.compatibility_recommended_format = None;
+                self.show_compatibility_warning = true;
+                return false;
+            }
+        }
+
+        // Check audio codec compatibility
+        if let Some(audio_codec_to_check) = effective.audio.filter(|codec| codec != "copy") {
+            if CodecManager::validate_codec_format_compatibility(&audio_codec_to_check, container_format, true).is_err() {
+                let recommended_codec = CodecManager::get_best_audio_codec_for_format(container_format);
+                self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
+                    format!("音频编码器 '{}' 与容器格式 '{}' 不兼容。这可能导致编码失败。", audio_codec_to_check, container_format)
+                } else {
+                    format!("Audio codec '{}' is not compatible with container format '{}'. This may cause encoding to fail.", audio_codec_to_check, container_format)
+                };
+                self.compatibility_recommended_codec = Some(recommended_codec);
+                self.compatibility_recommended_format = None;
+                self.show_compatibility_warning = true;
+                return false;
+            }
+        }
+
+        // Check subtitle compatibility for AddSubtitle operations
+        if let Some(ref operation) = self.current_operation {
+            if matches!(operation, OperationType::AddSubtitle) {
+                // Check if we have the required files
+                if !self.dedicated_video_file.is_empty() && !self.video_settings.subtitle_file.is_empty() {
+                    // Get output file extension
+                    let output_ext = std::path::Path::new(&self.output_file)
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("")
+                        .to_lowercase();
+                    
+                    // Get subtitle file extension  
+                    let subtitle_ext = std::path::Path::new(&self.video_settings.subtitle_file)
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("")
+                        .to_lowercase();
+                    
+                    // Check MP4 + SRT/VTT/ASS soft subtitle incompatibility
+                    if output_ext == "mp4" && 
+                       (subtitle_ext == "srt" || subtitle_ext == "vtt" || subtitle_ext == "ass") &&
+                       self.video_settings.subtitle_mode == "soft" {
+                        
+                        self.compatibility_warning_message = if self.translations.language == crate::language::Language::Chinese {
+                            format!("MP4容器格式不支持{}软字幕。建议更改输出格式为MKV以获得更好的兼容性。", 
+                                subtitle_ext.to_uppercase())
+                        } else {
+                            format!("MP4 container format does not support {} soft subtitles. Recommend changing output format to MKV for better compatibility.", 
+                                subtitle_ext.to_uppercase())
+                        };
+                        self.compatibility_recommended_codec = None;
+                        self.compatibility_recommended_format = Some("mkv".to_string());
+                        self.show_compatibility_warning = true;
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true // No compatibility issues found
+    }
+
+    fn check_and_reset_incompatible_codecs(&mut self) {
+        let current_format = &self.video_settings.container_format;
+        
+        // Only check if format has actually changed
+        if self.last_container_format.is_empty() {
+            self.last_container_format = current_format.clone();
+            return;
+        }
+        
+        if &self.last_container_format != current_format {
+            log_info!("Container format changed from {} to {}, checking codec compatibility...", 
+                     self.last_container_format, current_format);
+            
+            // Check if current audio codec is compatible with new format
+            if !self.audio_settings.codec.is_empty() && self.audio_settings.codec != "auto" {
+                if let Err(_) = crate::codec_manager::CodecManager::validate_codec_format_compatibility(
+                    &self.audio_settings.codec, 
+                    current_format, 
+                    true
+                ) {
+                    log_warn!("Audio codec '{}' is incompatible with '{}', selecting best audio codec", 
+                             self.audio_settings.codec, current_format);
+                    
+                    // Select the best audio codec for this format
+                    let recommended_audio_codec = crate::codec_manager::CodecManager::get_best_audio_codec_for_format(current_format);
+                    self.audio_settings.codec = recommended_audio_codec.clone();
+                    
+                    // Show a brief status message with translation support
+                    self.status_message = if self.translations.language == crate::language::Language::Chinese {
+                        format!("音频编码器已自动调整为{}（兼容{}格式）", recommended_audio_codec, current_format)
+                    } else {
+                        format!("Audio codec automatically adjusted to {} for {} format compatibility", recommended_audio_codec, current_format)
+                    };
+                }
+            }
+            
+            // Check if current video codec is compatible with new format
+            if !self.video_settings.codec.is_empty() && self.video_settings.codec != "auto" {
+                if let Err(_) = crate::codec_manager::CodecManager::validate_codec_format_compatibility(
+                    &self.video_settings.codec, 
+                    current_format, 
+                    false
+                ) {
+                    log_warn!("Video codec '{}' is incompatible with '{}', selecting best video codec", 
+                             self.video_settings.codec, current_format);
+                    
+                    // Use smart encoder recommendation if hardware encoders are available
+                    let (recommended_video_codec, _reason) = if let Some(ref hardware_encoders) = self.hardware_detection.encoders {
+                        crate::codec_manager::CodecManager::get_smart_encoder_recommendation(
+                            current_format,
+                            "Balanced", // Default quality preset
+                            false, // Not speed priority
+                            hardware_encoders,
+                            self.translations.language == crate::language::Language::Chinese
+                        )
+                    } else {
+                        (crate::codec_manager::CodecManager::get_best_video_codec_for_format(current_format), String::new())
+                    };
+                    
+                    self.video_settings.codec = recommended_video_codec.clone();
+                    
+                    // Show a brief status message with translation support
+                    self.status_message = if self.translations.language == crate::language::Language::Chinese {
+                        format!("视频编码器已自动调整为{}（兼容{}格式）", recommended_video_codec, current_format)
+                    } else {
+                        format!("Video codec automatically adjusted to {} for {} format compatibility", recommended_video_codec, current_format)
+                    };
+                }
+            }
+            
+            // Update the tracked format
+            self.last_container_format = current_format.clone();
+        }
+    }
+
+    fn apply_compatibility_recommendation(&mut self) {
+        self.session_undo.push(session_undo::UndoAction::ApplyRecommendation, self.session_snapshot());
+        if let Some(ref codec) = self.compatibility_recommended_codec {
+            // Check if we're dealing with video or audio based on the current codec being checked
+            // Support both English and Chinese messages
+            if self.compatibility_warning_message.contains("Video codec") || 
+               self.compatibility_warning_message.contains("视频编码器") {
+                self.video_settings.codec = codec.clone();
+            } else if self.compatibility_warning_message.contains("Audio codec") || 
+                      self.compatibility_warning_message.contains("音频编码器") ||
+                      self.compatibility_warning_message.contains("WebM") {
+                // WebM format warning is about audio codec compatibility
+                self.audio_settings.codec = codec.clone();
+            }
+        }
+
+        if let Some(ref format) = self.compatibility_recommended_format {
+            // Apply recommended format
+            if self.compatibility_warning_message.contains("Video") || 
+               self.compatibility_warning_message.contains("视频") {
+                self.video_settings.container_format = format.clone();
+            } else if self.compatibility_warning_message.contains("Audio") || 
+                      self.compatibility_warning_message.contains("音频") {
+                self.audio_settings.format = format.clone();
+            } else if self.compatibility_warning_message.contains("MP4") && 
+                      (self.compatibility_warning_message.contains("subtitle") || 
+                       self.compatibility_warning_message.contains("字幕")) {
+                // Handle subtitle compatibility format change
+                self.video_settings.container_format = format.clone();
+                
+                // Also update the output file extension if it ends with .mp4
+                if self.output_file.ends_with(".mp4") {
+                    self.output_file = self.output_file.replace(".mp4", &format!(".{}", format));
+                }
+            }
+        }
+        
+        // Update status message to show the recommendation was applied
+        self.status_message = if self.translations.language == crate::language::Language::Chinese {
+            "已应用推荐设置".to_string()
+        } else {
+            "Applied recommended settings".to_string()
+        };
+    }
+
+    fn show_command_preview(&mut self, operation: &OperationType) {
+        // Check compatibility before generating preview
+        if !self.check_compatibility_and_warn() {
+            // Store context so we can continue if user chooses to
+            self.compatibility_warning_context = CompatibilityWarningContext::PreviewCommand(operation.clone());
+            return; // Show warning dialog, don't generate preview
+        }
+
+        self.force_show_command_preview(operation);
+    }
+
+    fn generate_output_filename_from_video(&mut self) {
+        if !self.dedicated_video_file.is_empty() {
+            // Get appropriate extension based on current operation and settings
+            let extension = self.get_output_extension_for_current_operation();
+            self.output_file = self.auto_output_path(&self.dedicated_video_file.clone(), &extension);
+        }
+    }
+    
+    fn generate_output_filename_from_input(&mut self) {
+        if !self.input_files.is_empty() {
+            // Get appropriate extension based on current operation and settings
+            let extension = self.get_output_extension_for_current_operation();
+            self.output_file = self.auto_output_path(&self.input_files[0].clone(), &extension);
+        }
+    }
+    
+    /// Favorites row under the output selector; picking a folder keeps the current file name
+    fn show_output_favorites(&mut self, ui: &mut egui::Ui, operation: &OperationType) {
+        let category = self.get_operation_category(operation);
+        match output_favorites::show(ui, &mut self.output_favorites, &self.output_file, &category, &self.translations) {
+            output_favorites::FavoritesAction::None => {}
+            output_favorites::FavoritesAction::Changed => self.save_preferences(),
+            output_favorites::FavoritesAction::UseFolder(folder) => {
+                if self.output_file.is_empty() {
+                    self.generate_output_filename_from_input();
+                }
+                let file_name = std::path::Path::new(&self.output_file)
+                    .file_name()
+                    .map(|name| name.to_os_string())
+                    .unwrap_or_else(|| format!("output.{}", self.get_output_extension_for_current_operation()).into());
+                self.output_file = std::path::Path::new(&folder).join(file_name).display().to_string();
+            }
+        }
+    }
+    
+    /// Output path from the filename template, in the category's default favorite folder,
+    /// the default output folder or next to the input
+    fn auto_output_path(&mut self, input: &str, extension: &str) -> String {
+        let input_path = std::path::Path::new(input);
+        let category_folder = self.current_operation.as_ref()
+            .and_then(|operation| self.output_favorites.default_for(&self.get_operation_category(operation)))
+            .filter(|folder| output_favorites::folder_available(folder) != Some(false))
+            .map(str::to_string);
+        let folder = if let Some(category_folder) = category_folder {
+            std::path::PathBuf::from(category_folder)
+        } else if self.default_output_dir.is_empty() {
+            // A remote input has no folder; its output goes to the working directory
+            input_path.parent().filter(|_| !download_cache::is_remote(input)).map(|parent| parent.to_path_buf()).unwrap_or_default()
+        } else {
+            std::path::PathBuf::from(&self.default_output_dir)
+        };
+        let remote_name = download_cache::is_remote(input).then(|| download_cache::remote_file_name(input));
+        let stem_path = remote_name.as_deref().map(std::path::Path::new).unwrap_or(input_path);
+        let stem = stem_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("output");
+        let timestamp = self.generate_timestamp();
+        let file_name = first_run::render_filename_template(&self.filename_template, stem, &timestamp);
+        self.last_auto_output_file = folder.join(format!("{}.{}", file_name, extension)).display().to_string();
+        self.last_auto_output_file.clone()
+    }
+    
+    fn generate_timestamp(&self) -> String {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        
+        // Use millisecond timestamp to ensure uniqueness
+        let millis = now.as_millis();
+        
+        // Add a random suffix to further ensure uniqueness
+        let random_suffix = uuid::Uuid::new_v4().as_u128() % 1000;
+        
+        format!("{}_{}", millis, random_suffix)
+    }
+    
+    fn is_auto_generated_filename(&self, filename: &str) -> bool {
+        if !self.last_auto_output_file.is_empty() && filename == self.last_auto_output_file {
+            return true;
+        }
+        // Check if filename looks like our auto-generated format: output_timestamp.ext
+        if let Some(file_name) = std::path::Path::new(filename).file_name().and_then(|n| n.to_str()) {
+            file_name.starts_with("output_") && file_name.contains('.')
+        } else {
+            false
+        }
+    }
+    
+    /// Container `operation` writes: the chosen format, otherwise the output file's extension
+    fn target_container(&self, operation: &OperationType) -> String {
+        let chosen = if self.get_operation_category(operation) == "audio" {
+            &self.audio_settings.format
+        } else {
+            &self.video_settings.container_format
+        };
+        if !chosen.is_empty() && chosen != "auto" {
+            return chosen.to_lowercase();
+        }
+        std::path::Path::new(&self.output_file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase()
+    }
+    
+    /// What "auto" codecs of the current settings become, including the encoder policy's hardware choice
+    fn effective_codecs(&self, operation: &OperationType) -> effective_codecs::EffectiveCodecs {
+        let prefer_hardware = self.encoder_policy == EncoderPolicy::PreferHardware || self.queue_constraints().prefer_hardware;
+        effective_codecs::resolve_effective_codecs(
+            operation,
+            &self.target_container(operation),
+            &self.video_settings,
+            &self.audio_settings,
+            self.hardware_detection.encoders.as_deref().unwrap_or(&[]),
+            prefer_hardware,
+        )
+    }
+    
+    fn get_output_extension_for_current_operation(&self) -> String {
+        match &self.current_operation {
+            Some(operation) => {
+                match operation {
+                    OperationType::VideoConvert | OperationType::VideoCompress | 
+                    OperationType::VideoResize | OperationType::VideoCrop | 
+                    OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
+                    OperationType::AddSubtitle | OperationType::AddWatermark => {
+                        self.video_settings.container_format.clone()
+                    },
+                    OperationType::AudioConvert | OperationType::AudioCompress | 
+                    OperationType::AudioResample | OperationType::AudioVolume | 
+                    OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize => {
+                        self.audio_settings.format.clone()
+                    },
+                    OperationType::FrameExtract => {
+                        self.video_settings.frame_format.clone()
+                    },
+                    OperationType::VideoToGif | OperationType::GifResize => {
+                        "gif".to_string()
+                    },
+                    _ => "mp4".to_string()
+                }
+            },
+            None => "mp4".to_string()
+        }
+    }
+
+    fn force_show_command_preview(&mut self, operation: &OperationType) {
+        // Prepare input files based on operation type
+        let input_files = match operation {
+            OperationType::AddSubtitle | OperationType::AddWatermark => {
+                // Use dedicated video file for these operations
+                vec![self.dedicated_video_file.clone()]
+            }
+            _ => {
+                // Use regular input files for other operations
+                self.input_files.clone()
+            }
+        };
+        
+        let task = ProcessingTask {
+            id: 0,
+            operation: operation.clone(),
+            input_files,
+            output_file: self.output_file.clone(),
+            video_settings: Some(self.video_settings.clone()),
+            audio_settings: Some(self.audio_settings.clone()),
+            progress: 0.0,
+            status: TaskStatus::Pending,
+            error_message: None,
+            start_time: None,
+            paused_at: None,
+            estimated_total_time: None,
+            progress_unknown: false,
+            completion_time: None,
+            environment: None,
+            size_outcome: None,
+            executed_commands: Vec::new(),
+            label: None,
+            cancel_requested: false,
+            delete_partial_output: false,
+            checksum: Default::default(),
+            low_priority: false,
+            archive: None,
+            timestamps: Default::default(),
+            guards: Default::default(),
+            log: Default::default(),
+        };
+        
+        match TaskExecutor::preview_command(&task) {
+            Ok(command) => {
+                self.command_preview = command;
+                self.status_message = self.translations.command_generated();
+            }
+            Err(e) => {
+                self.command_preview = format!("{}: {}", self.translations.command_generation_error(), e);
+                self.status_message = self.translations.command_generation_failed();
+            }
+        }
+    }
+    
+
+    fn open_workflow_window(&mut self, _ctx: &egui::Context) {
+        log_debug!("Opening workflow window...");
+        
+        // Set window open state
+        self.workflow_window_open = true;
+        self.show_automation_editor = true;
+        
+        log_debug!("Workflow window state set to open");
+    }
+    
+    /// Editor state of the focused workflow tab
+    fn editor(&self) -> &workflow_tabs::WorkflowEditorState {
+        &self.workflow_tabs[self.active_workflow_tab]
+    }
+    
+    fn editor_mut(&mut self) -> &mut workflow_tabs::WorkflowEditorState {
+        &mut self.workflow_tabs[self.active_workflow_tab]
+    }
+    
+    fn new_workflow_tab(&mut self) {
+        self.workflow_tabs.push(workflow_tabs::WorkflowEditorState::new(automation_flow::AutomationWorkflow::new("New Workflow".to_string())));
+        self.active_workflow_tab = self.workflow_tabs.len() - 1;
+    }
+    
+    /// Close a tab right away, or ask first when it has unsaved changes
+    fn request_close_workflow_tab(&mut self, index: usize) {
+        if self.workflow_tabs[index].is_dirty() {
+            self.pending_workflow_tab_close = Some(index);
+        } else {
+            self.close_workflow_tab(index);
+        }
+    }
+    
+    fn close_workflow_tab(&mut self, index: usize) {
+        if index >= self.workflow_tabs.len() {
+            return;
+        }
+        self.workflow_tabs.remove(index);
+        if self.workflow_tabs.is_empty() {
+            self.workflow_tabs.push(workflow_tabs::WorkflowEditorState::new(automation_flow::AutomationWorkflow::new("New Workflow".to_string())));
+        }
+        if self.active_workflow_tab > index || self.active_workflow_tab >= self.workflow_tabs.len() {
+            self.active_workflow_tab = self.active_workflow_tab.saturating_sub(1);
+        }
+        log_debug!("Closed workflow tab {}, {} left", index, self.workflow_tabs.len());
+    }
+    
+    fn show_workflow_tab_bar(&mut self, ui: &mut egui::Ui) {
+        match workflow_tabs::show_tab_bar(ui, &self.workflow_tabs, self.active_workflow_tab, &self.translations) {
+            Some(workflow_tabs::TabAction::Select(index)) => self.active_workflow_tab = index,
+            Some(workflow_tabs::TabAction::Close(index)) => self.request_close_workflow_tab(index),
+            Some(workflow_tabs::TabAction::New) => self.new_workflow_tab(),
+            None => {}
+        }
+    }
+    
+    fn show_close_workflow_tab_dialog(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.pending_workflow_tab_close else {
+            return;
+        };
+        if index >= self.workflow_tabs.len() {
+            self.pending_workflow_tab_close = None;
+            return;
+        }
+        
+        let message = self.translations.unsaved_workflow_message(&self.workflow_tabs[index].workflow.name);
+        egui::Window::new(self.translations.unsaved_workflow_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(message);
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(self.translations.save_workflow_and_close()).clicked() {
+                        // Saving always acts on the focused tab
+                        self.active_workflow_tab = index;
+                        if self.save_workflow() {
+                            self.close_workflow_tab(index);
+                        }
+                        self.pending_workflow_tab_close = None;
+                    }
+                    if ui.button(self.translations.discard_workflow_changes()).clicked() {
+                        self.close_workflow_tab(index);
+                        self.pending_workflow_tab_close = None;
+                    }
+                    if ui.button(self.translations.cancel()).clicked() {
+                        self.pending_workflow_tab_close = None;
+                    }
+                });
+            });
+    }
+    
+    fn show_persistent_workflow_window(&mut self, ctx: &egui::Context) {
+        if !self.workflow_window_open {
+            return;
+        }
+        
+        // Backends without multi-viewport support would paint the CentralPanel over the main window
+        if ctx.embed_viewports() {
+            self.fall_back_to_embedded_editor("backend does not support multiple viewports");
+            self.show_automation_editor_window(ctx);
+            return;
+        }
+        
+        let viewport_id = egui::ViewportId::from_hash_of("workflow_editor");
+        
+        // Only the focused tab can change between frames
+        self.editor_mut().refresh_dirty();
+        let workflow_name = format!("{} - {}", self.translations.workflow_editor_title(), self.editor().title());
+        
+        let target = *self.workflow_viewport_target.get_or_insert_with(|| {
+            Self::workflow_viewport_initial_rect(ctx, self.workflow_viewport_rect)
+        });
+        // Builder geometry is in points of the main window; the first frame corrects for a monitor with another scale
+        let main_ppp = ctx.pixels_per_point();
+        let viewport_builder = egui::ViewportBuilder::default()
+            .with_title(workflow_name)
+            .with_inner_size(target.size() / main_ppp)
+            .with_min_inner_size([800.0, 600.0])
+            .with_position(target.min / main_ppp);
+        
+        let mut should_close = false;
+        // The backend skips the callback when it fails to create the native window
+        let mut viewport_shown = false;
+        let mut viewport_embedded = false;
+        
+        ctx.show_viewport_immediate(
+            viewport_id,
+            viewport_builder,
+            |ctx, class| {
+                viewport_shown = true;
+                if class == egui::ViewportClass::Embedded {
+                    viewport_embedded = true;
+                    return;
+                }
+                self.track_workflow_viewport_geometry(ctx, target);
+                
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if self.show_workflow_editor_contents(ctx, ui) {
+                        should_close = true;
+                    }
+                });
+                
+                self.show_close_workflow_tab_dialog(ctx);
+                
+                // Check system window close request
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    should_close = true;
+                }
+            },
         );
         
-        // Handle closing outside viewport callback
-        if should_close {
-            self.workflow_window_open = false;
-            self.show_automation_editor = false;
-            log_debug!("Workflow window closed");
-        }
+        if !viewport_shown || viewport_embedded {
+            self.fall_back_to_embedded_editor(if viewport_embedded {
+                "viewport was embedded by the backend"
+            } else {
+                "viewport window could not be created"
+            });
+            self.show_automation_editor_window(ctx);
+            return;
+        }
+        
+        // Handle closing outside viewport callback
+        if should_close {
+            self.workflow_window_open = false;
+            self.show_automation_editor = false;
+            self.workflow_viewport_target = None;
+            self.workflow_viewport_placed = false;
+            log_debug!("Workflow window closed");
+        }
+    }
+    
+    /// Opening geometry of the workflow viewport in physical pixels, on the main window's monitor
+    fn workflow_viewport_initial_rect(ctx: &egui::Context, remembered: Option<egui::Rect>) -> egui::Rect {
+        let ppp = ctx.pixels_per_point();
+        let size = egui::vec2(1200.0, 800.0) * ppp;
+        let min_size = egui::vec2(800.0, 600.0) * ppp;
+        let (outer_rect, monitor_size) = ctx.input(|i| (i.viewport().outer_rect, i.viewport().monitor_size));
+        match outer_rect {
+            Some(main_window) => viewport_geometry::initial_rect(
+                remembered,
+                viewport_geometry::to_physical(main_window, ppp),
+                monitor_size.unwrap_or(egui::Vec2::ZERO) * ppp,
+                size,
+                min_size,
+                ppp,
+            ),
+            // Backends that do not report window positions: trust the remembered geometry
+            None => remembered.unwrap_or(egui::Rect::from_min_size(
+                egui::pos2(100.0, 100.0) * ppp,
+                size,
+            )),
+        }
+    }
+    
+    /// Runs inside the workflow viewport: moves it where it was meant to open once its own scale is
+    /// known, follows scale changes when it is dragged to another monitor, and remembers its geometry
+    fn track_workflow_viewport_geometry(&mut self, ctx: &egui::Context, target: egui::Rect) {
+        let ppp = ctx.pixels_per_point();
+        let (outer_rect, inner_rect, native_ppp) = ctx.input(|i| {
+            (i.viewport().outer_rect, i.viewport().inner_rect, i.viewport().native_pixels_per_point)
+        });
+        
+        if let Some(native_ppp) = native_ppp {
+            if self.workflow_viewport_ppp.is_some_and(|previous| previous != native_ppp) {
+                log_info!("Workflow window scale changed to {:.2}", native_ppp);
+                ctx.request_repaint();
+            }
+            self.workflow_viewport_ppp = Some(native_ppp);
+        }
+        
+        let (Some(outer_rect), Some(inner_rect)) = (outer_rect, inner_rect) else {
+            return;
+        };
+        if !self.workflow_viewport_placed {
+            self.workflow_viewport_placed = true;
+            // The window was created with the primary monitor's scale, which may not be this monitor's
+            let wanted = viewport_geometry::to_points(target, ppp);
+            if (outer_rect.min - wanted.min).length() > 1.0 {
+                log_debug!("Moving workflow window to {:?} at scale {:.2}", wanted.min, ppp);
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(wanted.min));
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(wanted.size()));
+                return;
+            }
+        }
+        self.workflow_viewport_rect = Some(viewport_geometry::to_physical(
+            egui::Rect::from_min_size(outer_rect.min, inner_rect.size()),
+            ppp,
+        ));
+    }
+    
+    /// Switch the workflow editor to the in-window variant for the rest of the session and queue the one-time notice
+    fn fall_back_to_embedded_editor(&mut self, reason: &str) {
+        if self.workflow_viewport_failed {
+            return;
+        }
+        log_warn!("Workflow editor falls back to the embedded window: {}", reason);
+        self.workflow_viewport_failed = true;
+        self.show_embedded_editor_notice = true;
+        self.status_message = self.translations.embedded_editor_notice_title().to_string();
+    }
+    
+    /// Explains once why the workflow editor opened inside the main window
+    fn show_embedded_editor_notice_window(&mut self, ctx: &egui::Context) {
+        if !self.show_embedded_editor_notice {
+            return;
+        }
+        
+        egui::Window::new(self.translations.embedded_editor_notice_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label(self.translations.embedded_editor_notice_text());
+                ui.add_space(8.0);
+                if ui.button(self.translations.close()).clicked() {
+                    self.show_embedded_editor_notice = false;
+                }
+            });
+    }
+    
+    /// The embedded variant is used when the user asked for it or the separate viewport failed this session
+    fn workflow_editor_embedded(&self) -> bool {
+        self.force_embedded_workflow_editor || self.workflow_viewport_failed
+    }
+
+    /// Workflow editor as a window inside the main window, used when a separate viewport is unavailable or not wanted
+    fn show_automation_editor_window(&mut self, ctx: &egui::Context) {
+        let mut window_open = true;
+        let mut close_clicked = false;
+        self.editor_mut().refresh_dirty();
+        let workflow_name = format!("{} - {}", self.translations.workflow_editor_title(), self.editor().title());
+        
+        let mut window = egui::Window::new(workflow_name)
+            .id(egui::Id::new("embedded_workflow_editor"))
+            .default_size(self.workflow_window_size.unwrap_or(egui::vec2(1200.0, 800.0)))
+            .min_size([800.0, 600.0])
+            .max_size([1600.0, 1200.0])
+            .resizable(true)
+            .collapsible(false)
+            .scroll([false, false])
+            .open(&mut window_open);
+            
+        if let Some(pos) = self.workflow_window_pos {
+            window = window.default_pos(pos);
+        }
+        
+        let response = window
+            .show(ctx, |ui| {
+                close_clicked = self.show_workflow_editor_contents(ctx, ui);
+            });
+        
+        self.show_close_workflow_tab_dialog(ctx);
+        
+        // Save window state and handle window close events
+        if let Some(response) = response {
+            let rect = response.response.rect;
+            self.workflow_window_pos = Some(rect.min);
+            self.workflow_window_size = Some(rect.size());
+        }
+        
+        if !window_open || close_clicked {
+            self.workflow_window_open = false;
+            self.show_automation_editor = false;
+            log_debug!("Embedded workflow editor closed");
+        }
+    }
+    
+    /// Editor body shared by the separate viewport and the embedded fallback window; returns true when Close was clicked
+    fn show_workflow_editor_contents(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> bool {
+        let close_clicked = self.show_workflow_toolbar(ui);
+        
+        self.show_workflow_tab_bar(ui);
+        
+        ui.separator();
+        
+        self.show_workflow_node_menus(ui);
+        
+        ui.separator();
+        
+        self.show_workflow_canvas(ctx, ui);
+        
+        self.show_workflow_properties(ui);
+        
+        help_overlay::paint(ctx, help_overlay::PANEL_WORKFLOW, &self.translations);
+        
+        close_clicked
+    }
+    
+    fn show_workflow_toolbar(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut close_clicked = false;
+        
+        ui.horizontal(|ui| {
+            let file_buttons = ui.horizontal(|ui| {
+                if ui.button(self.translations.workflow_editor_new()).clicked() {
+                    self.new_workflow_tab();
+                }
+                
+                if ui.button(self.translations.workflow_editor_save()).clicked() {
+                    self.save_workflow();
+                }
+                
+                if ui.button(self.translations.workflow_editor_load()).clicked() {
+                    self.load_workflow();
+                }
+                
+                if ui.button(self.translations.workflow_library_save_to_library()).clicked() {
+                    self.save_workflow_to_library();
+                }
+                
+                if ui.button(self.translations.workflow_library_library()).clicked() {
+                    self.workflow_library = Some(workflow_library::LibraryWindow::new());
+                }
+            }).response;
+            help_overlay::callout(ui, &file_buttons, help_overlay::PANEL_WORKFLOW, "workflow.file", &self.translations);
+            
+            ui.separator();
+            
+            let description_hint = self.translations.workflow_library_description_hint();
+            ui.add(egui::TextEdit::singleline(&mut self.editor_mut().workflow.description)
+                .hint_text(description_hint)
+                .desired_width(200.0));
+            
+            ui.separator();
+            
+            let can_undo = self.editor().can_undo();
+            let can_redo = self.editor().can_redo();
+            
+            let history_buttons = ui.horizontal(|ui| {
+                if ui.add_enabled(can_undo, egui::Button::new(self.translations.workflow_editor_undo())).clicked() {
+                    self.undo_workflow();
+                }
+                
+                if ui.add_enabled(can_redo, egui::Button::new(self.translations.workflow_editor_redo())).clicked() {
+                    self.redo_workflow();
+                }
+            }).response;
+            help_overlay::callout(ui, &history_buttons, help_overlay::PANEL_WORKFLOW, "workflow.history", &self.translations);
+            
+            ui.separator();
+            
+            let has_selection = !self.editor().selected_nodes.is_empty();
+            let delete_button = ui.add_enabled(has_selection, egui::Button::new(self.translations.workflow_editor_delete()));
+            if delete_button.clicked() {
+                self.delete_selected_node();
+            }
+            help_overlay::callout(ui, &delete_button, help_overlay::PANEL_WORKFLOW, "workflow.delete", &self.translations);
+            
+            if ui.button(self.translations.workflow_editor_fit_view())
+                .on_hover_text(self.translations.workflow_editor_fit_view_hint())
+                .clicked()
+            {
+                self.editor_mut().fit_pending = true;
+            }
+            
+            ui.separator();
+            
+            let execute_button = ui.add_enabled(self.workflow_run.is_none(), egui::Button::new(self.translations.workflow_editor_execute()));
+            if execute_button.clicked() {
+                self.execute_workflow(false);
+            }
+            help_overlay::callout(ui, &execute_button, help_overlay::PANEL_WORKFLOW, "workflow.execute", &self.translations);
+            
+            if ui.add_enabled(self.workflow_run.is_some(), egui::Button::new(self.translations.workflow_editor_stop()))
+                .on_hover_text(self.translations.workflow_editor_stop_hint())
+                .clicked()
+            {
+                self.workflow_executor.cancel();
+                self.status_message = "Stopping workflow execution".to_string();
+            }
+            if ui.checkbox(&mut self.workflow_executor.keep_files_on_cancel, self.translations.workflow_editor_keep_on_stop())
+                .on_hover_text(self.translations.workflow_editor_keep_on_stop_hint())
+                .changed()
+            {
+                self.save_preferences();
+            }
+            
+            if ui.button(self.translations.workflow_editor_plan())
+                .on_hover_text(self.translations.workflow_editor_plan_hint())
+                .clicked()
+            {
+                self.preview_workflow_commands();
+            }
+            
+            if ui.checkbox(&mut self.workflow_executor.incremental, self.translations.workflow_editor_incremental())
+                .on_hover_text(self.translations.workflow_editor_incremental_hint())
+                .changed()
+            {
+                self.save_preferences();
+            }
+            if self.workflow_executor.incremental
+                && ui.add_enabled(self.workflow_run.is_none(), egui::Button::new(self.translations.workflow_editor_force_rerun())).clicked()
+            {
+                self.execute_workflow(true);
+            }
+            
+            ui.separator();
+            
+            if ui.button(self.translations.workflow_editor_close()).clicked() {
+                close_clicked = true;
+            }
+            
+            help_overlay::toggle_button(ui, help_overlay::PANEL_WORKFLOW, &self.translations);
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(self.translations.workflow_editor_toggle_hint());
+            });
+        });
+        
+        close_clicked
     }
-
-    fn show_automation_editor_window(&mut self, ctx: &egui::Context) {
-        let mut window_open = true;
-        let workflow_name = if let Some(ref workflow) = self.current_workflow {
-            format!("🔧 Automation Workflow Editor - {}", workflow.name)
-        } else {
-            "🔧 Automation Workflow Editor - No Workflow".to_string()
-        };
-        
-        let mut window = egui::Window::new(workflow_name)
-            .default_size(self.workflow_window_size.unwrap_or(egui::vec2(1200.0, 800.0)))
-            .min_size([800.0, 600.0])
-            .max_size([1600.0, 1200.0])
-            .resizable(true)
-            .collapsible(false)
-            .scroll([false, false])
-            .open(&mut window_open);
+    
+    fn show_workflow_node_menus(&mut self, ui: &mut egui::Ui) {
+        // Add node menu - comprehensive version
+        let add_nodes = ui.horizontal_wrapped(|ui| {
+            ui.label(self.translations.workflow_editor_add_nodes());
             
-        if let Some(pos) = self.workflow_window_pos {
-            window = window.default_pos(pos);
-        }
-        
-        let response = window
-            .show(ctx, |ui| {
-                ui.horizontal(|ui| {
-                    if ui.button("🆕 New Workflow").clicked() {
-                        self.save_workflow_state();
-                        self.current_workflow = Some(automation_flow::AutomationWorkflow::new("New Workflow".to_string()));
+            ui.menu_button(self.translations.workflow_editor_menu_io(), |ui| {
+                if ui.button(self.translations.node_type_name(&automation_flow::NodeType::InputFile)).clicked() {
+                    self.add_automation_node(automation_flow::NodeType::InputFile, egui::pos2(100.0, 100.0));
+                    ui.close_menu();
+                }
+                if ui.button(self.translations.node_type_name(&automation_flow::NodeType::OutputFile)).clicked() {
+                    self.add_automation_node(automation_flow::NodeType::OutputFile, egui::pos2(600.0, 100.0));
+                    ui.close_menu();
+                }
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_audio(), |ui| {
+                ui.vertical(|ui| {
+                    ui.label(self.translations.workflow_editor_basic_operations());
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::ExtractAudio)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::ExtractAudio, egui::pos2(200.0, 150.0));
+                        ui.close_menu();
                     }
-                    
-                    if ui.button("💾 Save").clicked() {
-                        self.save_workflow();
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioConvert)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioConvert, egui::pos2(200.0, 200.0));
+                        ui.close_menu();
                     }
-                    
-                    if ui.button("📁 Load").clicked() {
-                        self.load_workflow();
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioCompress)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioCompress, egui::pos2(200.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioResample)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioResample, egui::pos2(200.0, 300.0));
+                        ui.close_menu();
                     }
-                    
                     ui.separator();
-                    
-                    let can_undo = self.history_index > 0;
-                    let can_redo = self.history_index < (self.workflow_history.len() as isize - 1);
-                    
-                    if ui.add_enabled(can_undo, egui::Button::new("Undo")).clicked() {
-                        self.undo_workflow();
+                    ui.label(self.translations.workflow_editor_audio_effects());
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioVolume)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioVolume, egui::pos2(200.0, 350.0));
+                        ui.close_menu();
                     }
-                    
-                    if ui.add_enabled(can_redo, egui::Button::new("Redo")).clicked() {
-                        self.redo_workflow();
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioTrim)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioTrim, egui::pos2(200.0, 400.0));
+                        ui.close_menu();
                     }
-                    
-                    ui.separator();
-                    
-                    let has_selection = self.selected_node.is_some();
-                    if ui.add_enabled(has_selection, egui::Button::new("🚮 Delete")).clicked() {
-                        self.delete_selected_node();
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioMerge)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioMerge, egui::pos2(200.0, 450.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioNormalize)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioNormalize, egui::pos2(200.0, 500.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioDeNoise)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioDeNoise, egui::pos2(200.0, 550.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioEqualizer)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioEqualizer, egui::pos2(200.0, 600.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioFade)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioFade, egui::pos2(200.0, 650.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioEcho)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioEcho, egui::pos2(200.0, 700.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::AudioSpeed)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioSpeed, egui::pos2(200.0, 750.0));
+                        ui.close_menu();
+                    }
+                });
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_video(), |ui| {
+                ui.vertical(|ui| {
+                    ui.label(self.translations.workflow_editor_basic_operations());
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::ExtractVideo)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::ExtractVideo, egui::pos2(300.0, 150.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoConvert)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoConvert, egui::pos2(300.0, 200.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoCompress)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoCompress, egui::pos2(300.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoRecode)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoRecode, egui::pos2(300.0, 300.0));
+                        ui.close_menu();
                     }
-                    
                     ui.separator();
-                    
-                    if ui.button("🚀 Execute").clicked() {
-                        self.execute_workflow();
+                    ui.label(self.translations.workflow_editor_video_transformations());
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoResize)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoResize, egui::pos2(300.0, 350.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoCrop)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoCrop, egui::pos2(300.0, 400.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoRotate)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoRotate, egui::pos2(300.0, 450.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoFilter)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoFilter, egui::pos2(300.0, 500.0));
+                        ui.close_menu();
                     }
-                    
                     ui.separator();
-                    
-                    if ui.button("❌ Close Editor").clicked() {
-                        self.show_automation_editor = false;
+                    ui.label(self.translations.workflow_editor_video_effects());
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::FrameExtract)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::FrameExtract, egui::pos2(300.0, 550.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoFPS)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoFPS, egui::pos2(300.0, 600.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoStabilize)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoStabilize, egui::pos2(300.0, 650.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoDeinterlace)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoDeinterlace, egui::pos2(300.0, 700.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoColorCorrect)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoColorCorrect, egui::pos2(300.0, 750.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoBrightness)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoBrightness, egui::pos2(300.0, 800.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoSaturation)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoSaturation, egui::pos2(300.0, 850.0));
+                        ui.close_menu();
+                    }
+                    if ui.small_button(self.translations.node_type_name(&automation_flow::NodeType::VideoGamma)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoGamma, egui::pos2(300.0, 900.0));
+                        ui.close_menu();
                     }
-                    
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.label("💡 Press Ctrl+W to toggle this window");
-                    });
                 });
-                
-                ui.separator();
-                
-                ui.horizontal_wrapped(|ui| {
-                    ui.label("Add Nodes:");
-                    
-                    ui.menu_button("📁 Input/Output", |ui| {
-                        if ui.button(automation_flow::NodeType::InputFile.display_name()).clicked() {
-                            self.add_automation_node(automation_flow::NodeType::InputFile, egui::pos2(100.0, 100.0));
-                            ui.close_menu();
-                        }
-                        if ui.button(automation_flow::NodeType::OutputFile.display_name()).clicked() {
-                            self.add_automation_node(automation_flow::NodeType::OutputFile, egui::pos2(600.0, 100.0));
-                            ui.close_menu();
-                        }
-                    });
-                    
-                    ui.menu_button("🎵 Audio Processing", |ui| {
-                        ui.vertical(|ui| {
-                            ui.label("Basic Operations:");
-                            if ui.small_button(automation_flow::NodeType::ExtractAudio.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::ExtractAudio, egui::pos2(200.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioConvert.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioConvert, egui::pos2(200.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioCompress.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioCompress, egui::pos2(200.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioResample.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioResample, egui::pos2(200.0, 300.0));
-                                ui.close_menu();
-                            }
-                            ui.separator();
-                            ui.label("Audio Effects:");
-                            if ui.small_button(automation_flow::NodeType::AudioVolume.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioVolume, egui::pos2(200.0, 350.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioTrim.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioTrim, egui::pos2(200.0, 400.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioMerge.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioMerge, egui::pos2(200.0, 450.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioNormalize.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioNormalize, egui::pos2(200.0, 500.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioDeNoise.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioDeNoise, egui::pos2(200.0, 550.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioEqualizer.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioEqualizer, egui::pos2(200.0, 600.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioFade.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioFade, egui::pos2(200.0, 650.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioEcho.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioEcho, egui::pos2(200.0, 700.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::AudioSpeed.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioSpeed, egui::pos2(200.0, 750.0));
-                                ui.close_menu();
-                            }
-                        });
-                    });
-                    
-                    ui.menu_button("📺 Video Processing", |ui| {
-                        ui.vertical(|ui| {
-                            ui.label("Basic Operations:");
-                            if ui.small_button(automation_flow::NodeType::ExtractVideo.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::ExtractVideo, egui::pos2(300.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoConvert.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoConvert, egui::pos2(300.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoCompress.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoCompress, egui::pos2(300.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoRecode.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoRecode, egui::pos2(300.0, 300.0));
-                                ui.close_menu();
-                            }
-                            ui.separator();
-                            ui.label("Video Transformations:");
-                            if ui.small_button(automation_flow::NodeType::VideoResize.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoResize, egui::pos2(300.0, 350.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoCrop.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoCrop, egui::pos2(300.0, 400.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoRotate.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoRotate, egui::pos2(300.0, 450.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoFilter.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoFilter, egui::pos2(300.0, 500.0));
-                                ui.close_menu();
-                            }
-                            ui.separator();
-                            ui.label("Video Effects:");
-                            if ui.small_button(automation_flow::NodeType::FrameExtract.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::FrameExtract, egui::pos2(300.0, 550.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoFPS.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoFPS, egui::pos2(300.0, 600.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoStabilize.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoStabilize, egui::pos2(300.0, 650.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoDeinterlace.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoDeinterlace, egui::pos2(300.0, 700.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoColorCorrect.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoColorCorrect, egui::pos2(300.0, 750.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoBrightness.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoBrightness, egui::pos2(300.0, 800.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoSaturation.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoSaturation, egui::pos2(300.0, 850.0));
-                                ui.close_menu();
-                            }
-                            if ui.small_button(automation_flow::NodeType::VideoGamma.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoGamma, egui::pos2(300.0, 900.0));
-                                ui.close_menu();
-                            }
-                        });
-                    });
-                    
-                    ui.menu_button("🎭 Audio/Video Operations", |ui| {
-                        ui.vertical(|ui| {
-                            if ui.button(automation_flow::NodeType::Combine.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::Combine, egui::pos2(400.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::SplitAudioVideo.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::SplitAudioVideo, egui::pos2(400.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::VideoOverlay.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoOverlay, egui::pos2(400.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::VideoPiP.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoPiP, egui::pos2(400.0, 300.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::VideoSideBySide.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoSideBySide, egui::pos2(400.0, 350.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::AudioVideoSync.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AudioVideoSync, egui::pos2(400.0, 400.0));
-                                ui.close_menu();
-                            }
-                        });
-                    });
-                    
-                    ui.menu_button("🎨 Text & Graphics", |ui| {
-                        ui.vertical(|ui| {
-                            if ui.button(automation_flow::NodeType::AddSubtitle.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AddSubtitle, egui::pos2(500.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::AddWatermark.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AddWatermark, egui::pos2(500.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::AddText.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AddText, egui::pos2(500.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::AddLogo.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AddLogo, egui::pos2(500.0, 300.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::AddTimecode.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::AddTimecode, egui::pos2(500.0, 350.0));
-                                ui.close_menu();
-                            }
-                        });
-                    });
-                    
-                    ui.menu_button("🔄 Format Conversion", |ui| {
-                        ui.vertical(|ui| {
-                            if ui.button(automation_flow::NodeType::VideoToGif.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoToGif, egui::pos2(600.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::GifResize.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::GifResize, egui::pos2(600.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::VideoToImages.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoToImages, egui::pos2(600.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::ImagesToVideo.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::ImagesToVideo, egui::pos2(600.0, 300.0));
-                                ui.close_menu();
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_av(), |ui| {
+                ui.vertical(|ui| {
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::Combine)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::Combine, egui::pos2(400.0, 150.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::SplitAudioVideo)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::SplitAudioVideo, egui::pos2(400.0, 200.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoOverlay)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoOverlay, egui::pos2(400.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoPiP)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoPiP, egui::pos2(400.0, 300.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoSideBySide)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoSideBySide, egui::pos2(400.0, 350.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AudioVideoSync)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AudioVideoSync, egui::pos2(400.0, 400.0));
+                        ui.close_menu();
+                    }
+                });
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_text(), |ui| {
+                ui.vertical(|ui| {
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AddSubtitle)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AddSubtitle, egui::pos2(500.0, 150.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AddWatermark)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AddWatermark, egui::pos2(500.0, 200.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AddText)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AddText, egui::pos2(500.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AddLogo)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AddLogo, egui::pos2(500.0, 300.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::AddTimecode)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::AddTimecode, egui::pos2(500.0, 350.0));
+                        ui.close_menu();
+                    }
+                });
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_format(), |ui| {
+                ui.vertical(|ui| {
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoToGif)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoToGif, egui::pos2(600.0, 150.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::GifResize)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::GifResize, egui::pos2(600.0, 200.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoToImages)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoToImages, egui::pos2(600.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::ImagesToVideo)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::ImagesToVideo, egui::pos2(600.0, 300.0));
+                        ui.close_menu();
+                    }
+                });
+            });
+            
+            ui.menu_button(self.translations.workflow_editor_menu_batch(), |ui| {
+                ui.vertical(|ui| {
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::BatchConvert)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::BatchConvert, egui::pos2(700.0, 150.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::BatchProcess)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::BatchProcess, egui::pos2(700.0, 200.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::MultiPassEncode)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::MultiPassEncode, egui::pos2(700.0, 250.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::QualityAnalysis)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::QualityAnalysis, egui::pos2(700.0, 300.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::FormatValidation)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::FormatValidation, egui::pos2(700.0, 350.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::StreamPrep)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::StreamPrep, egui::pos2(700.0, 400.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoEncrypt)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoEncrypt, egui::pos2(700.0, 450.0));
+                        ui.close_menu();
+                    }
+                    if ui.button(self.translations.node_type_name(&automation_flow::NodeType::VideoDecrypt)).clicked() {
+                        self.add_automation_node(automation_flow::NodeType::VideoDecrypt, egui::pos2(700.0, 500.0));
+                        ui.close_menu();
+                    }
+                });
+            });
+            
+            ui.menu_button(self.translations.node_group_templates(), |ui| {
+                let templates = node_group::scan();
+                if templates.is_empty() {
+                    ui.weak(self.translations.node_group_no_templates());
+                }
+                for template in &templates {
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("📦 {}", template.group.name)).clicked() {
+                            self.insert_group_template(&template.group, egui::pos2(300.0, 200.0));
+                            ui.close_menu();
+                        }
+                        if ui.small_button("🗑").on_hover_text(self.translations.node_group_delete_template()).clicked() {
+                            if let Err(e) = node_group::delete(&template.path) {
+                                log_error!("Failed to delete template: {}", e);
+                                self.status_message = e;
                             }
-                        });
+                        }
                     });
-                    
-                    ui.menu_button("📦 Batch & Advanced", |ui| {
-                        ui.vertical(|ui| {
-                            if ui.button(automation_flow::NodeType::BatchConvert.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::BatchConvert, egui::pos2(700.0, 150.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::BatchProcess.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::BatchProcess, egui::pos2(700.0, 200.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::MultiPassEncode.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::MultiPassEncode, egui::pos2(700.0, 250.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::QualityAnalysis.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::QualityAnalysis, egui::pos2(700.0, 300.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::FormatValidation.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::FormatValidation, egui::pos2(700.0, 350.0));
-                                ui.close_menu();
-                            }
-                            if ui.button(automation_flow::NodeType::StreamPrep.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::StreamPrep, egui::pos2(700.0, 400.0));
-                                ui.close_menu();
+                }
+                ui.separator();
+                let group_button = ui.add_enabled(self.editor().selected_nodes.len() >= 2, egui::Button::new(self.translations.node_group_group()))
+                    .on_hover_text(self.translations.node_group_group_hint())
+                    .on_disabled_hover_text(self.translations.node_group_group_hint());
+                if group_button.clicked() {
+                    self.group_selected_nodes();
+                    ui.close_menu();
+                }
+                if ui.button(self.translations.node_group_open_folder()).clicked() {
+                    let dir = node_group::templates_dir();
+                    match std::fs::create_dir_all(&dir) {
+                        Ok(()) => workflow_library::open_folder(&dir),
+                        Err(e) => log_error!("Failed to create {}: {}", dir.display(), e),
+                    }
+                    ui.close_menu();
+                }
+            });
+        }).response;
+        help_overlay::callout(ui, &add_nodes, help_overlay::PANEL_WORKFLOW, "workflow.add_nodes", &self.translations);
+    }
+    
+    fn show_workflow_canvas(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        // Main canvas area - reserve space for UI elements below
+        let mut canvas_rect = ui.available_rect_before_wrap();
+        canvas_rect.max.y -= 80.0;  // Reserve 80 pixels for status bar/hints below
+        let canvas_response = ui.allocate_rect(canvas_rect, egui::Sense::click_and_drag());
+        help_overlay::callout(ui, &canvas_response, help_overlay::PANEL_WORKFLOW, "workflow.canvas", &self.translations);
+        
+        if self.editor().fit_pending {
+            let view = self.editor().canvas_view(canvas_rect);
+            let editor = self.editor_mut();
+            editor.fit_to_view(&view);
+            editor.fit_pending = false;
+        }
+        
+        // Handle canvas interaction; panning and zooming take effect this frame
+        self.handle_canvas_interaction(ui, canvas_rect, &canvas_response);
+        let view = self.editor().canvas_view(canvas_rect);
+        
+        // Draw grid
+        self.draw_grid(ui, &view);
+        
+        // Handle keyboard shortcuts
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::Escape) && self.editor().creating_connection {
+                self.editor_mut().cancel_connection();
+                log_debug!("Connection cancelled by ESC key");
+            }
+            
+            if i.key_pressed(egui::Key::Delete) {
+                self.delete_selected_node();
+            }
+            
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::Z) {
+                self.undo_workflow();
+            }
+            
+            if (i.modifiers.ctrl && i.key_pressed(egui::Key::Y)) || 
+               (i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::Z)) {
+                self.redo_workflow();
+            }
+        });
+        
+        // Draw nodes
+        let workflow_nodes = self.editor().workflow.nodes.values().cloned().collect::<Vec<_>>();
+        
+        for node in workflow_nodes {
+            self.draw_single_node_with_canvas(ui, &node, &view);
+        }
+        
+        // Outlines of nodes removed since the compared version, where they used to be
+        if let Some(diff) = self.workflow_diff.as_ref().and_then(|window| window.canvas_highlight()) {
+            let painter = ui.painter();
+            for old_rect in diff.nodes.iter().filter_map(|node| node.old_rect) {
+                let rect = view.node_rect(painter, old_rect.min, old_rect.size());
+                painter.rect_stroke(rect, egui::Rounding::same(view.scale(8.0)), egui::Stroke::new(2.0, workflow_diff::ChangeKind::Removed.color()));
+            }
+        }
+        
+        // Draw connection lines
+        let workflow_clone = self.editor().workflow.clone();
+        self.draw_connections_with_canvas(ui, &workflow_clone, &view);
+        self.show_connection_menu(ui, &workflow_clone);
+        
+        if let Some(marquee) = self.marquee_rect(ui) {
+            let color = egui::Color32::from_rgb(100, 150, 255);
+            ui.painter().rect_filled(marquee, egui::Rounding::ZERO, color.gamma_multiply(0.15));
+            ui.painter().rect_stroke(marquee, egui::Rounding::ZERO, egui::Stroke::new(1.0, color));
+        }
+    }
+    
+    /// "From node: port → to node: port" for menus
+    fn connection_description(&self, workflow: &automation_flow::AutomationWorkflow, connection: &automation_flow::NodeConnection) -> String {
+        let end = |node_id: &str, port: usize, output: bool| match workflow.nodes.get(node_id) {
+            Some(node) => {
+                let ports = if output { &node.output_ports } else { &node.input_ports };
+                let port = ports.get(port).map(|port| self.translations.port_name(&port.name)).unwrap_or_default();
+                format!("{}: {}", self.translations.node_type_name(&node.node_type), port)
+            }
+            None => node_id.to_string(),
+        };
+        format!("{} → {}", end(&connection.from_node, connection.from_port, true), end(&connection.to_node, connection.to_port, false))
+    }
+    
+    /// Menu over right-clicked connection lines: pick one when several overlap, then delete it,
+    /// insert a node on it or outline its endpoints
+    fn show_connection_menu(&mut self, ui: &mut egui::Ui, workflow: &automation_flow::AutomationWorkflow) {
+        enum MenuAction {
+            Choose(String),
+            Delete(String),
+            Insert(String, automation_flow::NodeType),
+            ShowEndpoints(String),
+        }
+        
+        let Some(menu) = self.editor().connection_menu.as_ref() else { return };
+        let position = menu.position;
+        let candidates: Vec<&automation_flow::NodeConnection> = menu.connections.iter()
+            .filter_map(|id| workflow.connections.get(id))
+            .collect();
+        let chosen = menu.chosen.as_ref().and_then(|id| workflow.connections.get(id));
+        if candidates.is_empty() {
+            // Removed by undo or an edit elsewhere
+            self.editor_mut().connection_menu = None;
+            return;
+        }
+        
+        let mut action = None;
+        let mut hovered = None;
+        let area = egui::Area::new(ui.id().with("connection_menu"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(position)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::menu(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(360.0);
+                    match chosen {
+                        None => {
+                            ui.label(self.translations.workflow_editor_connection_pick());
+                            ui.separator();
+                            for connection in &candidates {
+                                let button = ui.button(self.connection_description(workflow, connection));
+                                if button.hovered() {
+                                    hovered = Some(connection.id.clone());
+                                }
+                                if button.clicked() {
+                                    action = Some(MenuAction::Choose(connection.id.clone()));
+                                }
                             }
-                            if ui.button(automation_flow::NodeType::VideoEncrypt.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoEncrypt, egui::pos2(700.0, 450.0));
-                                ui.close_menu();
+                        }
+                        Some(connection) => {
+                            ui.label(egui::RichText::new(self.connection_description(workflow, connection)).strong());
+                            ui.separator();
+                            if ui.button(self.translations.workflow_editor_delete_connection()).clicked() {
+                                action = Some(MenuAction::Delete(connection.id.clone()));
                             }
-                            if ui.button(automation_flow::NodeType::VideoDecrypt.display_name()).clicked() {
-                                self.add_automation_node(automation_flow::NodeType::VideoDecrypt, egui::pos2(700.0, 500.0));
-                                ui.close_menu();
+                            egui::CollapsingHeader::new(self.translations.workflow_editor_insert_node())
+                                .id_salt("connection_menu_insert")
+                                .show(ui, |ui| {
+                                    let insertable: Vec<&automation_flow::NodeType> = automation_flow::NodeType::PROCESSING.iter()
+                                        .filter(|node_type| {
+                                            let candidate = automation_flow::AutomationNode::new(String::new(), (*node_type).clone(), egui::Pos2::ZERO);
+                                            workflow.insertion_ports(&connection.id, &candidate).is_some()
+                                        })
+                                        .collect();
+                                    if insertable.is_empty() {
+                                        ui.weak(self.translations.workflow_editor_no_insertable());
+                                    }
+                                    egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                                        for node_type in insertable {
+                                            if ui.small_button(self.translations.node_type_name(node_type)).clicked() {
+                                                action = Some(MenuAction::Insert(connection.id.clone(), node_type.clone()));
+                                            }
+                                        }
+                                    });
+                                });
+                            if ui.button(self.translations.workflow_editor_show_endpoints()).clicked() {
+                                action = Some(MenuAction::ShowEndpoints(connection.id.clone()));
                             }
-                        });
-                    });
+                        }
+                    }
                 });
-                
-                ui.separator();
-                
-                let available_rect = ui.available_rect_before_wrap();
-                let (canvas_rect, response) = ui.allocate_exact_size(available_rect.size(), egui::Sense::click_and_drag());
-                
-                ui.painter().rect_filled(
-                    canvas_rect,
-                    egui::Rounding::same(0.0),
-                    egui::Color32::from_gray(45)
-                );
-                
-                let grid_spacing = 25.0;
-                let grid_color = egui::Color32::from_gray(60);
-                
-                let mut x = canvas_rect.min.x;
-                while x <= canvas_rect.max.x {
-                    ui.painter().line_segment(
-                        [egui::pos2(x, canvas_rect.min.y), egui::pos2(x, canvas_rect.max.y)],
-                        egui::Stroke::new(0.5, grid_color)
-                    );
-                    x += grid_spacing;
+            });
+        
+        match action {
+            Some(MenuAction::Choose(id)) => {
+                if let Some(menu) = self.editor_mut().connection_menu.as_mut() {
+                    menu.chosen = Some(id);
                 }
-                
-                let mut y = canvas_rect.min.y;
-                while y <= canvas_rect.max.y {
-                    ui.painter().line_segment(
-                        [egui::pos2(canvas_rect.min.x, y), egui::pos2(canvas_rect.max.x, y)],
-                        egui::Stroke::new(0.5, grid_color)
-                    );
-                    y += grid_spacing;
+            }
+            Some(MenuAction::Delete(id)) => {
+                self.save_workflow_state();
+                let editor = self.editor_mut();
+                editor.workflow.remove_connection(&id);
+                editor.connection_menu = None;
+                if editor.highlighted_connection.as_ref() == Some(&id) {
+                    editor.highlighted_connection = None;
                 }
-                
-                self.handle_canvas_interaction(ui, canvas_rect, &response);
-                
-                let workflow_nodes = if let Some(ref workflow) = self.current_workflow {
-                    workflow.nodes.values().cloned().collect::<Vec<_>>()
-                } else {
-                    Vec::new()
-                };
-                
-                for node in workflow_nodes {
-                    self.draw_single_node_with_canvas(ui, &node, canvas_rect);
+                log_debug!("Deleted connection: {}", id);
+            }
+            Some(MenuAction::Insert(id, node_type)) => {
+                self.editor_mut().connection_menu = None;
+                let node_id = automation_flow::new_node_id();
+                let mut node = automation_flow::AutomationNode::new(node_id.clone(), node_type, egui::Pos2::ZERO);
+                if let Some(connection) = workflow.connections.get(&id) {
+                    if let (Some(from_node), Some(to_node)) = (workflow.nodes.get(&connection.from_node), workflow.nodes.get(&connection.to_node)) {
+                        let from_pos = from_node.position + viewport_geometry::port_offset(from_node.size, connection.from_port, true);
+                        let to_pos = to_node.position + viewport_geometry::port_offset(to_node.size, connection.to_port, false);
+                        node.position = viewport_geometry::connection_point(from_pos, to_pos, 0.5) - node.size / 2.0;
+                    }
                 }
-                
-                if let Some(ref workflow) = self.current_workflow {
-                    let workflow_clone = workflow.clone();
-                    self.draw_connections_with_canvas(ui, &workflow_clone, canvas_rect);
+                self.save_workflow_state();
+                let editor = self.editor_mut();
+                match editor.workflow.insert_node_on_connection(&id, node) {
+                    Ok(()) => {
+                        editor.select_only(&node_id);
+                        log_debug!("Inserted node {} on connection {}", node_id, id);
+                    }
+                    Err(e) => log_error!("❌ Insert failed: {}", e),
+                }
+            }
+            Some(MenuAction::ShowEndpoints(id)) => {
+                let editor = self.editor_mut();
+                editor.highlighted_connection = Some(id);
+                editor.connection_menu = None;
+            }
+            None => {
+                let escape = ui.ctx().input(|i| i.key_pressed(egui::Key::Escape));
+                let clicked_away = ui.ctx().input(|i| i.pointer.primary_clicked()) && area.response.clicked_elsewhere();
+                if let Some(menu) = self.editor_mut().connection_menu.as_mut() {
+                    menu.hovered = hovered;
                 }
+                if escape || clicked_away {
+                    self.editor_mut().connection_menu = None;
+                }
+            }
+        }
+    }
+    
+    /// Status bar plus the shortcuts, instructions and selected node's parameters below the canvas
+    fn show_workflow_properties(&mut self, ui: &mut egui::Ui) {
+        // Status bar - display important hint information
+        ui.separator();
+        let status_bar = ui.horizontal(|ui| {
+            ui.label(self.translations.workflow_editor_tips());
+            if self.editor().creating_connection {
+                ui.label(self.translations.workflow_editor_tip_connecting());
+            } else {
+                ui.label(self.translations.workflow_editor_tip_idle());
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let workflow = &self.editor().workflow;
+                ui.label(self.translations.workflow_counts(workflow.nodes.len(), workflow.connections.len()));
+            });
+        }).response;
+        help_overlay::callout(ui, &status_bar, help_overlay::PANEL_WORKFLOW, "workflow.status", &self.translations);
+        
+        // Bottom information and properties panel
+        let properties_area = egui::ScrollArea::vertical()
+            .max_height(200.0)
+            .show(ui, |ui| {
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label(self.translations.workflow_editor_shortcuts());
+                    ui.label(self.translations.workflow_editor_shortcut_list());
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label(self.translations.workflow_editor_instructions());
+                    ui.label(self.translations.workflow_editor_instruction_text());
+                });
                 
-                egui::ScrollArea::vertical()
-                    .max_height(200.0)
-                    .show(ui, |ui| {
+                // Node property editing
+                if let Some(ref selected_id) = self.editor().single_selected() {
+                    // Ensure we have the latest hardware encoders BEFORE borrowing workflow
+                    let current_hw_encoders = self.get_cached_hardware_encoders();
+                    
+                    let tab = self.active_workflow_tab;
+                    let run_error = self.editor().workflow.nodes.get(selected_id).and_then(|node| self.node_run_state(node))
+                        .filter(|state| state.status == automation_flow::NodeRunStatus::Failed)
+                        .and_then(|state| state.error);
+                    if let Some(ref mut node) = self.workflow_tabs[tab].workflow.nodes.get_mut(selected_id) {
                         ui.separator();
+                        ui.heading(self.translations.workflow_editor_node_properties());
+                        ui.label(self.translations.workflow_node_type(&self.translations.node_type_name(&node.node_type)));
+                        if let Some(error) = run_error {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}: {}", self.translations.workflow_editor_run_failed(), error));
+                        }
                         
-                        ui.horizontal(|ui| {
-                            ui.label("Shortcuts:");
-                            ui.label("Del=Delete | Ctrl+Z=Undo | Ctrl+Y=Redo | Ctrl+A=Select All | ESC=Cancel");
-                        });
-                        
-                        ui.horizontal(|ui| {
-                            ui.label("Instructions:");
-                            ui.label("Click output ports (right side) to start connections, click input ports (left side) to complete them.");
-                        });
+                        // Use comprehensive parameter UI
+                        node.show_comprehensive_parameters_ui(ui, &self.translations, &current_hw_encoders);
                         
-                        if let Some(ref selected_id) = self.selected_node.clone() {
-                            // Ensure we have the latest hardware encoders BEFORE borrowing workflow
-                            let current_hw_encoders = self.get_cached_hardware_encoders();
-                            
-                            if let Some(ref mut workflow) = self.current_workflow {
-                                if let Some(ref mut node) = workflow.nodes.get_mut(selected_id) {
-                                    ui.separator();
-                                    ui.heading("Node Properties");
-                                    ui.label(format!("Type: {}", node.node_type.display_name()));
-                                    
-                                    // Use comprehensive parameter UI
-                                    node.show_comprehensive_parameters_ui(ui, &self.translations, &current_hw_encoders);
-                                    
-                                    // Auto-fill output filenames when input files change
-                                    if node.node_type == automation_flow::NodeType::InputFile {
-                                        let workflow_clone = workflow.clone();
-                                        let mut changed = false;
-                                        for (_, output_node) in workflow.nodes.iter_mut() {
-                                            if output_node.node_type == automation_flow::NodeType::OutputFile {
-                                                if output_node.auto_fill_output_from_input(&workflow_clone) {
-                                                    changed = true;
-                                                }
-                                            }
-                                        }
-                                        if changed {
-                                            self.save_workflow_state();
-                                        }
-                                    }
+                        // Auto-fill output filenames when input files change
+                        if node.node_type == automation_flow::NodeType::InputFile {
+                            let workflow = &mut self.workflow_tabs[tab].workflow;
+                            let workflow_clone = workflow.clone();
+                            let mut changed = false;
+                            for (_, output_node) in workflow.nodes.iter_mut() {
+                                if output_node.node_type == automation_flow::NodeType::OutputFile
+                                    && output_node.auto_fill_output_from_input(&workflow_clone)
+                                {
+                                    changed = true;
                                 }
                             }
-                        } else {
-                            ui.separator();
-                            ui.label("Select a node to edit its properties");
+                            if changed {
+                                self.save_workflow_state();
+                            }
                         }
-                    });
+                    }
+                }
             });
-        
-        // Save window state and handle window close events
-        if let Some(response) = response {
-            let rect = response.response.rect;
-            self.workflow_window_pos = Some(rect.min);
-            self.workflow_window_size = Some(rect.size());
-        }
-        
-        if !window_open {
-            self.show_automation_editor = false;
+        if self.editor().single_selected().is_some() {
+            let properties = ui.interact(properties_area.inner_rect, ui.id().with("workflow_properties_help"), egui::Sense::hover());
+            help_overlay::callout(ui, &properties, help_overlay::PANEL_WORKFLOW, "workflow.properties", &self.translations);
         }
     }
     
     fn add_automation_node(&mut self, node_type: automation_flow::NodeType, position: egui::Pos2) {
         self.save_workflow_state();
         
-        if let Some(ref mut workflow) = self.current_workflow {
-            let node_id = format!("node_{}", uuid::Uuid::new_v4().to_string()[..8].to_string());
-            let node = automation_flow::AutomationNode::new(node_id, node_type, position);
-            workflow.add_node(node);
-        }
+        let node_id = automation_flow::new_node_id();
+        let node = automation_flow::AutomationNode::new(node_id, node_type, position);
+        self.editor_mut().workflow.add_node(node);
     }
     
-    fn draw_grid(&self, ui: &mut egui::Ui, rect: egui::Rect) {
+    fn draw_grid(&self, ui: &mut egui::Ui, view: &viewport_geometry::CanvasView) {
         let painter = ui.painter();
-        let grid_size = 20.0 * self.workflow_canvas_zoom;
+        let rect = view.rect;
+        let grid_size = view.scale(20.0);
         
-        let start_x = (rect.min.x / grid_size).floor() * grid_size;
-        let start_y = (rect.min.y / grid_size).floor() * grid_size;
+        // Lines stay on workflow coordinates that are multiples of 20 as the canvas pans
+        let origin = view.to_screen(egui::Pos2::ZERO);
+        let start_x = rect.min.x + (origin.x - rect.min.x).rem_euclid(grid_size);
+        let start_y = rect.min.y + (origin.y - rect.min.y).rem_euclid(grid_size);
         
         let mut x = start_x;
         while x <= rect.max.x {
@@ -2829,64 +5414,135 @@ impl FFmpegGui {
         }
     }
     
-    fn draw_connections_with_canvas(&mut self, ui: &mut egui::Ui, workflow: &automation_flow::AutomationWorkflow, canvas_rect: egui::Rect) {
+    fn draw_connections_with_canvas(&mut self, ui: &mut egui::Ui, workflow: &automation_flow::AutomationWorkflow, view: &viewport_geometry::CanvasView) {
         let painter = ui.painter();
+        let canvas_rect = view.rect;
+        let latest_pointer = ui.ctx().input(|i| i.pointer.latest_pos());
+        let rerouting = self.editor().rerouting.clone();
         
-        let mut connections_to_delete = Vec::new();
+        let mut curves = Vec::new();
         for connection in workflow.connections.values() {
             if let (Some(from_node), Some(to_node)) = (
                 workflow.nodes.get(&connection.from_node),
                 workflow.nodes.get(&connection.to_node)
             ) {
-                let from_pos = egui::pos2(
-                    canvas_rect.min.x + from_node.position.x + from_node.size.x + 5.0,
-                    canvas_rect.min.y + from_node.position.y + 35.0 + (connection.from_port as f32 * 25.0)
-                );
-                let to_pos = egui::pos2(
-                    canvas_rect.min.x + to_node.position.x - 5.0,
-                    canvas_rect.min.y + to_node.position.y + 35.0 + (connection.to_port as f32 * 25.0)
-                );
+                let mut from_pos = view.port_position(view.node_rect(painter, from_node.position, from_node.size), from_node.size, connection.from_port, true);
+                let mut to_pos = view.port_position(view.node_rect(painter, to_node.position, to_node.size), to_node.size, connection.to_port, false);
+                // The end being rerouted follows the pointer
+                match (&rerouting, latest_pointer) {
+                    (Some((id, automation_flow::ConnectionEnd::Source)), Some(pointer)) if *id == connection.id => from_pos = pointer,
+                    (Some((id, automation_flow::ConnectionEnd::Target)), Some(pointer)) if *id == connection.id => to_pos = pointer,
+                    _ => {}
+                }
                 
                 self.draw_connection_line(&painter, from_pos, to_pos, connection.data_type.get_color());
-                
-                if self.is_point_near_connection_line(from_pos, to_pos, ui.ctx().input(|i| i.pointer.latest_pos().unwrap_or_default())) {
-                    if ui.ctx().input(|i| i.pointer.secondary_clicked()) {
-                        connections_to_delete.push(connection.id.clone());
-                    }
-                    
-                    if ui.ctx().input(|i| i.pointer.latest_pos().map_or(false, |pos| 
-                        self.is_point_near_connection_line(from_pos, to_pos, pos))) {
-                        let mid_point = egui::pos2((from_pos.x + to_pos.x) / 2.0, (from_pos.y + to_pos.y) / 2.0);
-                        painter.circle_filled(
-                            mid_point,
-                            4.0,
-                            egui::Color32::from_rgb(255, 100, 100)
-                        );
+                curves.push((connection.id.clone(), from_pos, to_pos));
+            }
+        }
+        
+        // A lone node without connections dragged over a connection it can carry is spliced into
+        // it when dropped
+        let editor = self.editor();
+        let splice_target = editor.dragging_node.as_ref()
+            .filter(|id| editor.selected_nodes.len() == 1
+                && !workflow.connections.values().any(|connection| connection.from_node == **id || connection.to_node == **id))
+            .and_then(|id| workflow.nodes.get(id))
+            .zip(latest_pointer)
+            .and_then(|(node, pointer)| {
+                viewport_geometry::connections_near(curves.iter().cloned(), pointer, viewport_geometry::SPLICE_HIT_RADIUS)
+                    .into_iter()
+                    .find(|id| workflow.insertion_ports(id, node).is_some())
+            });
+        if let Some((_, from_pos, to_pos)) = splice_target.as_ref().and_then(|id| curves.iter().find(|(curve, _, _)| curve == id)) {
+            painter.add(egui::Shape::line(viewport_geometry::connection_points(*from_pos, *to_pos), egui::Stroke::new(6.0, egui::Color32::from_rgb(255, 220, 80).gamma_multiply(0.6))));
+        }
+        self.editor_mut().splice_target = splice_target;
+        
+        // Grab handles just outside both ends of each connection; dragging one onto another port
+        // moves that end there
+        let mut reroute_drop = None;
+        for (id, from_pos, to_pos) in &curves {
+            for (end, port_pos, direction) in [(automation_flow::ConnectionEnd::Source, *from_pos, 1.0), (automation_flow::ConnectionEnd::Target, *to_pos, -1.0)] {
+                let moving = rerouting.as_ref().is_some_and(|(rerouted, rerouted_end)| rerouted == id && *rerouted_end == end);
+                let handle = if moving { port_pos } else { port_pos + egui::vec2(direction * view.scale(16.0), 0.0) };
+                let rect = egui::Rect::from_center_size(handle, egui::Vec2::splat(view.scale(12.0)));
+                let response = ui.interact(rect, egui::Id::new(("connection_end", id, end)), egui::Sense::drag());
+                if response.hovered() || moving {
+                    let color = workflow.connections.get(id).map(|connection| connection.data_type.get_color()).unwrap_or(egui::Color32::WHITE);
+                    painter.circle_filled(handle, view.scale(5.0), color);
+                    painter.circle_stroke(handle, view.scale(5.0), egui::Stroke::new(1.0, egui::Color32::WHITE));
+                }
+                if response.drag_started_by(egui::PointerButton::Primary) && !self.editor().creating_connection && !self.editor().panning {
+                    self.editor_mut().rerouting = Some((id.clone(), end));
+                }
+                if response.drag_stopped() && moving {
+                    reroute_drop = Some((id.clone(), end));
+                }
+            }
+        }
+        if let Some((connection_id, end)) = reroute_drop {
+            self.editor_mut().rerouting = None;
+            let target = latest_pointer.and_then(|pointer| self.port_at(painter, view, pointer, end == automation_flow::ConnectionEnd::Source));
+            if let Some((node_id, port)) = target {
+                self.save_workflow_state();
+                match self.editor_mut().workflow.reroute_connection(&connection_id, end, &node_id, port) {
+                    Ok(()) => log_debug!("Rerouted connection {} to node {} port {}", connection_id, node_id, port),
+                    Err(e) => {
+                        log_error!("❌ Reroute failed: {}", e);
+                        self.status_message = e;
                     }
                 }
             }
         }
         
-        if let Some(ref mut workflow_mut) = self.current_workflow {
-            for conn_id in connections_to_delete {
-                workflow_mut.remove_connection(&conn_id);
+        // Nodes take their own right-clicks, so only the open canvas counts
+        let over_node = |pos: egui::Pos2| workflow.nodes.values().any(|node| view.node_rect(painter, node.position, node.size).contains(pos));
+        let pointer = ui.ctx().input(|i| i.pointer.latest_pos())
+            .filter(|pos| canvas_rect.contains(*pos) && !over_node(*pos));
+        if let Some(pointer) = pointer {
+            let near = viewport_geometry::connections_near(curves.iter().cloned(), pointer, viewport_geometry::CONNECTION_HIT_RADIUS);
+            if let Some((_, from_pos, to_pos)) = near.first().and_then(|id| curves.iter().find(|(curve, _, _)| curve == id)) {
+                painter.circle_filled(
+                    viewport_geometry::connection_point(*from_pos, *to_pos, 0.5),
+                    4.0,
+                    egui::Color32::from_rgb(255, 100, 100)
+                );
+            }
+            if ui.ctx().input(|i| i.pointer.secondary_clicked()) {
+                self.editor_mut().connection_menu = (!near.is_empty()).then(|| workflow_tabs::ConnectionMenu::new(pointer, near));
+            }
+        }
+        
+        // Endpoints of the connection picked or hovered in the menu, or of the one chosen by "Show endpoints"
+        let editor = self.editor();
+        let highlighted = editor.connection_menu.as_ref()
+            .and_then(|menu| menu.chosen.as_ref().or(menu.hovered.as_ref()))
+            .or(editor.highlighted_connection.as_ref());
+        if let Some((_, from_pos, to_pos)) = highlighted.and_then(|id| curves.iter().find(|(curve, _, _)| curve == id)) {
+            let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 220, 80));
+            painter.add(egui::Shape::line(viewport_geometry::connection_points(*from_pos, *to_pos), egui::Stroke::new(5.0, stroke.color.gamma_multiply(0.4))));
+            for endpoint in [from_pos, to_pos] {
+                painter.circle_stroke(*endpoint, 10.0, stroke);
+            }
+            if let Some(connection) = highlighted.and_then(|id| workflow.connections.get(id)) {
+                for node in [&connection.from_node, &connection.to_node].into_iter().filter_map(|id| workflow.nodes.get(id)) {
+                    painter.rect_stroke(view.node_rect(painter, node.position, node.size).expand(3.0), egui::Rounding::same(view.scale(8.0)), stroke);
+                }
             }
         }
         
-        if self.creating_connection {
+        let editor = self.editor();
+        if editor.creating_connection {
             if let (Some(ref source_node_id), Some(source_port_idx)) = 
-                (&self.connection_source_node, self.connection_source_port) {
+                (&editor.connection_source_node, editor.connection_source_port) {
                 if let Some(source_node) = workflow.nodes.get(source_node_id) {
-                    let from_pos = egui::pos2(
-                        canvas_rect.min.x + source_node.position.x + source_node.size.x + 5.0,
-                        canvas_rect.min.y + source_node.position.y + 35.0 + (source_port_idx as f32 * 25.0)
-                    );
+                    let from_pos = view.port_position(view.node_rect(painter, source_node.position, source_node.size), source_node.size, source_port_idx, true);
                     
                     let port_data_type = &source_node.output_ports[source_port_idx].data_type;
-                    self.draw_connection_line(&painter, from_pos, self.connection_target_pos, port_data_type.get_color());
+                    self.draw_connection_line(painter, from_pos, editor.connection_target_pos, port_data_type.get_color());
                     
                     painter.circle_filled(
-                        self.connection_target_pos,
+                        editor.connection_target_pos,
                         4.0,
                         port_data_type.get_color()
                     );
@@ -2898,7 +5554,6 @@ impl FFmpegGui {
     fn draw_connections(&mut self, ui: &mut egui::Ui, workflow: &automation_flow::AutomationWorkflow) {
         let painter = ui.painter();
         
-        let mut connections_to_delete = Vec::new();
         for connection in workflow.connections.values() {
             if let (Some(from_node), Some(to_node)) = (
                 workflow.nodes.get(&connection.from_node),
@@ -2910,41 +5565,30 @@ impl FFmpegGui {
                 ) {
                     self.draw_connection_line(&painter, from_pos, to_pos, connection.data_type.get_color());
                     
-                    if self.is_point_near_connection_line(from_pos, to_pos, ui.ctx().input(|i| i.pointer.latest_pos().unwrap_or_default())) {
-                        if ui.ctx().input(|i| i.pointer.secondary_clicked()) {
-                            connections_to_delete.push(connection.id.clone());
-                        }
-                        
-                        if ui.ctx().input(|i| i.pointer.latest_pos().map_or(false, |pos| 
-                            self.is_point_near_connection_line(from_pos, to_pos, pos))) {
-                            let mid_point = egui::pos2((from_pos.x + to_pos.x) / 2.0, (from_pos.y + to_pos.y) / 2.0);
-                            painter.circle_filled(
-                                mid_point,
-                                4.0,
-                                egui::Color32::from_rgb(255, 100, 100)
-                            );
-                        }
+                    let hovered = ui.ctx().input(|i| i.pointer.latest_pos()).is_some_and(|pos|
+                        viewport_geometry::distance_to_connection(from_pos, to_pos, pos) <= viewport_geometry::CONNECTION_HIT_RADIUS);
+                    if hovered {
+                        painter.circle_filled(
+                            viewport_geometry::connection_point(from_pos, to_pos, 0.5),
+                            4.0,
+                            egui::Color32::from_rgb(255, 100, 100)
+                        );
                     }
                 }
             }
         }
         
-        if let Some(ref mut workflow_mut) = self.current_workflow {
-            for conn_id in connections_to_delete {
-                workflow_mut.remove_connection(&conn_id);
-            }
-        }
-        
-        if self.creating_connection {
+        let editor = self.editor();
+        if editor.creating_connection {
             if let (Some(ref source_node_id), Some(source_port_idx)) = 
-                (&self.connection_source_node, self.connection_source_port) {
+                (&editor.connection_source_node, editor.connection_source_port) {
                 if let Some(source_node) = workflow.nodes.get(source_node_id) {
                     if let Some(from_pos) = source_node.get_output_port_position(source_port_idx) {
                         let port_data_type = &source_node.output_ports[source_port_idx].data_type;
-                        self.draw_connection_line(&painter, from_pos, self.connection_target_pos, port_data_type.get_color());
+                        self.draw_connection_line(painter, from_pos, editor.connection_target_pos, port_data_type.get_color());
                         
                         painter.circle_filled(
-                            self.connection_target_pos,
+                            editor.connection_target_pos,
                             4.0,
                             port_data_type.get_color()
                         );
@@ -2954,41 +5598,37 @@ impl FFmpegGui {
         }
     }
     
+    /// Node and index of the output port (or input port) under `pointer`
+    fn port_at(&self, painter: &egui::Painter, view: &viewport_geometry::CanvasView, pointer: egui::Pos2, output: bool) -> Option<(String, usize)> {
+        self.editor().workflow.nodes.values().find_map(|node| {
+            let node_rect = view.node_rect(painter, node.position, node.size);
+            let ports = if output { node.output_ports.len() } else { node.input_ports.len() };
+            (0..ports)
+                .find(|port| egui::Rect::from_center_size(view.port_position(node_rect, node.size, *port, output), egui::Vec2::splat(view.scale(20.0))).contains(pointer))
+                .map(|port| (node.id.clone(), port))
+        })
+    }
+    
     fn draw_connection_line(&self, painter: &egui::Painter, from: egui::Pos2, to: egui::Pos2, color: egui::Color32) {
-
-        let dx = to.x - from.x;
-        let control_offset = dx.abs().min(100.0).max(30.0);
-        
-        let control1 = egui::pos2(from.x + control_offset, from.y);
-        let control2 = egui::pos2(to.x - control_offset, to.y);
-        
-        let mut points = Vec::new();
-        let num_segments = 20;
-        
-        for i in 0..=num_segments {
-            let t = i as f32 / num_segments as f32;
-            let point = cubic_bezier(from, control1, control2, to, t);
-            points.push(point);
-        }
-        
-        for i in 0..points.len() - 1 {
-            painter.line_segment(
-                [points[i], points[i + 1]],
-                egui::Stroke::new(3.0, color)
-            );
-        }
+        painter.add(egui::Shape::line(viewport_geometry::connection_points(from, to), egui::Stroke::new(3.0, color)));
     }
     
     
-    fn draw_single_node_with_canvas(&mut self, ui: &mut egui::Ui, node: &automation_flow::AutomationNode, canvas_rect: egui::Rect) {
+    /// Latest run state of `node`; a group shows the combined state of the nodes inside it
+    fn node_run_state(&self, node: &automation_flow::AutomationNode) -> Option<automation_flow::NodeRunState> {
+        let workflow_id = &self.editor().workflow.id;
+        match &node.group {
+            Some(group) => self.workflow_executor.run_state.get_combined(workflow_id, &group.leaf_ids()),
+            None => self.workflow_executor.run_state.get(workflow_id, &node.id),
+        }
+    }
+    
+    fn draw_single_node_with_canvas(&mut self, ui: &mut egui::Ui, node: &automation_flow::AutomationNode, view: &viewport_geometry::CanvasView) {
         let painter = ui.painter();
-        let node_screen_pos = egui::pos2(
-            canvas_rect.min.x + node.position.x,
-            canvas_rect.min.y + node.position.y
-        );
-        let node_rect = egui::Rect::from_min_size(node_screen_pos, node.size);
+        let node_rect = view.node_rect(painter, node.position, node.size);
+        let rounding = egui::Rounding::same(view.scale(8.0));
         
-        let is_selected = self.selected_node.as_ref() == Some(&node.id);
+        let is_selected = self.editor().is_selected(&node.id);
         let mut bg_color = if is_selected {
             node.node_type.get_color().gamma_multiply(1.3)
         } else {
@@ -3015,7 +5655,7 @@ impl FFmpegGui {
         
         painter.rect_filled(
             node_rect,
-            egui::Rounding::same(8.0),
+            rounding,
             bg_color
         );
         
@@ -3045,15 +5685,50 @@ impl FFmpegGui {
         
         painter.rect_stroke(
             node_rect,
-            egui::Rounding::same(8.0),
+            rounding,
             border_stroke
         );
         
+        if let Some(color) = self.workflow_diff.as_ref().and_then(|window| window.canvas_highlight()).and_then(|diff| diff.highlight(&node.id)) {
+            painter.rect_stroke(node_rect.expand(4.0), egui::Rounding::same(view.scale(10.0)), egui::Stroke::new(3.0, color));
+        }
+        
+        // Status of the node in the latest run: ring, corner badge and, while running, a progress bar
+        if let Some(state) = self.node_run_state(node) {
+            let (color, badge) = match state.status {
+                automation_flow::NodeRunStatus::Pending => (egui::Color32::from_gray(150), "…"),
+                automation_flow::NodeRunStatus::Running => (egui::Color32::from_rgb(100, 150, 255), "▶"),
+                automation_flow::NodeRunStatus::Done => (egui::Color32::from_rgb(100, 200, 100), "✔"),
+                automation_flow::NodeRunStatus::Failed => (egui::Color32::from_rgb(255, 100, 100), "✖"),
+                automation_flow::NodeRunStatus::Cancelled => (egui::Color32::from_rgb(230, 160, 60), "⏹"),
+                automation_flow::NodeRunStatus::Skipped => (egui::Color32::from_gray(110), "–"),
+            };
+            if !matches!(state.status, automation_flow::NodeRunStatus::Pending | automation_flow::NodeRunStatus::Skipped) {
+                painter.rect_stroke(node_rect.expand(2.0), egui::Rounding::same(view.scale(9.0)), egui::Stroke::new(2.5, color));
+            }
+            let badge_center = node_rect.right_top() + egui::vec2(-10.0, 10.0) * view.zoom;
+            painter.circle_filled(badge_center, view.scale(8.0), color);
+            painter.text(badge_center, egui::Align2::CENTER_CENTER, badge, egui::FontId::proportional(view.scale(10.0)), egui::Color32::WHITE);
+            if state.status == automation_flow::NodeRunStatus::Running {
+                let track = egui::Rect::from_min_max(
+                    egui::pos2(node_rect.min.x + view.scale(6.0), node_rect.max.y - view.scale(8.0)),
+                    egui::pos2(node_rect.max.x - view.scale(6.0), node_rect.max.y - view.scale(4.0)),
+                );
+                painter.rect_filled(track, egui::Rounding::same(2.0), egui::Color32::from_black_alpha(120));
+                let filled = egui::Rect::from_min_size(track.min, egui::vec2(track.width() * state.progress, track.height()));
+                painter.rect_filled(filled, egui::Rounding::same(2.0), color);
+            }
+        }
+        
+        let title = match &node.group {
+            Some(group) => format!("📦 {}", group.name),
+            None => self.translations.node_type_name(&node.node_type),
+        };
         painter.text(
-            egui::pos2(node_rect.center().x, node_rect.min.y + 15.0),
+            egui::pos2(node_rect.center().x, node_rect.min.y + view.scale(15.0)),
             egui::Align2::CENTER_TOP,
-            node.node_type.display_name(),
-            egui::FontId::default(),
+            title,
+            egui::FontId::proportional(view.scale(14.0)),
             egui::Color32::WHITE
         );
         
@@ -3073,14 +5748,14 @@ impl FFmpegGui {
                     "File loaded".to_string()
                 }
             } else {
-                "📥 Drop file here".to_string()
+                self.translations.workflow_editor_drop_file().to_string()
             };
             
             painter.text(
-                egui::pos2(node_rect.center().x, node_rect.min.y + 35.0),
+                egui::pos2(node_rect.center().x, node_rect.min.y + view.scale(35.0)),
                 egui::Align2::CENTER_TOP,
                 status_text,
-                egui::FontId::proportional(10.0),
+                egui::FontId::proportional(view.scale(10.0)),
                 if has_file { egui::Color32::from_rgb(200, 255, 200) } else { egui::Color32::from_rgb(255, 200, 200) }
             );
         } else if node.node_type == automation_flow::NodeType::OutputFile {
@@ -3096,44 +5771,65 @@ impl FFmpegGui {
                         .to_string_lossy();
                     format!("💾 {}", filename)
                 } else {
-                    "💾 Path set".to_string()
+                    self.translations.workflow_editor_path_set().to_string()
                 }
             } else {
-                "📥 Drop file here".to_string()
+                self.translations.workflow_editor_drop_file().to_string()
             };
             
             painter.text(
-                egui::pos2(node_rect.center().x, node_rect.min.y + 35.0),
+                egui::pos2(node_rect.center().x, node_rect.min.y + view.scale(35.0)),
                 egui::Align2::CENTER_TOP,
                 status_text,
-                egui::FontId::proportional(10.0),
+                egui::FontId::proportional(view.scale(10.0)),
                 if has_path { egui::Color32::from_rgb(200, 255, 200) } else { egui::Color32::from_rgb(255, 200, 200) }
             );
         }
         
         let node_response = ui.interact(node_rect, egui::Id::new(format!("node_{}", node.id)), egui::Sense::click_and_drag());
         if node_response.clicked() {
-            self.selected_node = Some(node.id.clone());
+            if ui.input(|i| i.modifiers.command) {
+                self.editor_mut().toggle_selected(&node.id);
+            } else {
+                self.editor_mut().select_only(&node.id);
+            }
             log_debug!("Node {} selected", node.id);
         }
         
         node_response.context_menu(|ui| {
-            ui.label(format!("Node: {}", node.node_type.display_name()));
+            ui.label(self.translations.workflow_node_context(&self.translations.node_type_name(&node.node_type)));
             ui.separator();
             
+            if node.group.is_some() {
+                if ui.button(self.translations.node_group_ungroup()).clicked() {
+                    self.ungroup_node(&node.id);
+                    ui.close_menu();
+                }
+                if ui.button(self.translations.node_group_save_template()).clicked() {
+                    self.save_group_template(&node.id);
+                    ui.close_menu();
+                }
+                ui.separator();
+            } else if self.editor().selected_nodes.len() >= 2 && self.editor().is_selected(&node.id) {
+                if ui.button(self.translations.node_group_group()).clicked() {
+                    self.group_selected_nodes();
+                    ui.close_menu();
+                }
+                ui.separator();
+            }
+            
             if node.node_type == automation_flow::NodeType::InputFile {
-                if ui.button("📁 Browse File...").clicked() {
+                if ui.button(self.translations.workflow_editor_browse_file()).clicked() {
                     if let Some(path) = rfd::FileDialog::new()
                         .add_filter("Media Files", &["mp4", "avi", "mov", "mkv", "mp3", "wav", "flac", "aac"])
                         .add_filter("All Files", &["*"])
                         .pick_file()
                     {
-                        if let Some(ref mut workflow) = self.current_workflow {
-                            if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
-                                if let Some(param) = node_mut.parameters.get_mut("file_path") {
-                                    param.value = path.display().to_string();
-                                    log_debug!("Set input file path: {}", param.value);
-                                }
+                        let workflow = &mut self.editor_mut().workflow;
+                        if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
+                            if let Some(param) = node_mut.parameters.get_mut("file_path") {
+                                param.value = path.display().to_string();
+                                log_debug!("Set input file path: {}", param.value);
                             }
                         }
                     }
@@ -3143,19 +5839,18 @@ impl FFmpegGui {
             }
             
             if node.node_type == automation_flow::NodeType::OutputFile {
-                if ui.button("💾 Save As...").clicked() {
+                if ui.button(self.translations.workflow_editor_save_as()).clicked() {
                     if let Some(path) = rfd::FileDialog::new()
                         .add_filter("Video Files", &["mp4", "mkv", "avi", "mov", "webm"])
                         .add_filter("Audio Files", &["mp3", "wav", "flac", "aac", "ogg"])
                         .add_filter("All Files", &["*"])
                         .save_file()
                     {
-                        if let Some(ref mut workflow) = self.current_workflow {
-                            if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
-                                if let Some(param) = node_mut.parameters.get_mut("output_path") {
-                                    param.value = path.display().to_string();
-                                    log_debug!("Set output file path: {}", param.value);
-                                }
+                        let workflow = &mut self.editor_mut().workflow;
+                        if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
+                            if let Some(param) = node_mut.parameters.get_mut("output_path") {
+                                param.value = path.display().to_string();
+                                log_debug!("Set output file path: {}", param.value);
                             }
                         }
                     }
@@ -3164,14 +5859,16 @@ impl FFmpegGui {
                 ui.separator();
             }
             
-            if ui.button("🚮 Delete Node").clicked() {
-                self.selected_node = Some(node.id.clone());
+            if ui.button(self.translations.workflow_editor_delete_node()).clicked() {
+                if !self.editor().is_selected(&node.id) {
+                    self.editor_mut().select_only(&node.id);
+                }
                 self.delete_selected_node();
                 ui.close_menu();
             }
             
             ui.separator();
-            ui.label("Parameters:");
+            ui.label(self.translations.workflow_editor_parameters());
             
             egui::ScrollArea::vertical()
                 .max_height(300.0)
@@ -3180,43 +5877,62 @@ impl FFmpegGui {
                     let current_hw_encoders = self.get_cached_hardware_encoders();
                     
                     // Use comprehensive parameter UI for context menu
-                    if let Some(ref mut workflow) = self.current_workflow {
-                        if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
-                            node_mut.show_comprehensive_parameters_ui(
-                                ui, 
-                                &self.translations, 
-                                &current_hw_encoders
-                            );
-                            
-                            // Auto-fill output filenames when input files change
-                            if node_mut.node_type == automation_flow::NodeType::InputFile {
-                                // Trigger auto-fill for all connected output nodes
-                                let workflow_clone = workflow.clone();
-                                let mut changed = false;
-                                for (_, output_node) in workflow.nodes.iter_mut() {
-                                    if output_node.node_type == automation_flow::NodeType::OutputFile {
-                                        if output_node.auto_fill_output_from_input(&workflow_clone) {
-                                            changed = true;
-                                        }
-                                    }
-                                }
-                                if changed {
-                                    self.save_workflow_state();
+                    let workflow = &mut self.workflow_tabs[self.active_workflow_tab].workflow;
+                    if let Some(node_mut) = workflow.nodes.get_mut(&node.id) {
+                        node_mut.show_comprehensive_parameters_ui(
+                            ui, 
+                            &self.translations, 
+                            &current_hw_encoders
+                        );
+                        
+                        // Auto-fill output filenames when input files change
+                        if node_mut.node_type == automation_flow::NodeType::InputFile {
+                            // Trigger auto-fill for all connected output nodes
+                            let workflow_clone = workflow.clone();
+                            let mut changed = false;
+                            for (_, output_node) in workflow.nodes.iter_mut() {
+                                if output_node.node_type == automation_flow::NodeType::OutputFile
+                                    && output_node.auto_fill_output_from_input(&workflow_clone)
+                                {
+                                    changed = true;
                                 }
                             }
+                            if changed {
+                                self.save_workflow_state();
+                            }
                         }
                     }
                 });
         });
         
-        if node_response.drag_started() {
+        if node_response.drag_started_by(egui::PointerButton::Primary) && !self.editor().panning {
             self.save_workflow_state();
+            let editor = self.editor_mut();
+            // Dragging a node outside the selection moves that node alone
+            if !editor.is_selected(&node.id) {
+                editor.select_only(&node.id);
+            }
+            editor.dragging_node = Some(node.id.clone());
         }
         
-        if node_response.dragged() {
-            if let Some(ref mut workflow) = self.current_workflow {
-                if let Some(ref mut node_mut) = workflow.nodes.get_mut(&node.id) {
-                    node_mut.position += node_response.drag_delta();
+        if node_response.dragged_by(egui::PointerButton::Primary) && self.editor().dragging_node.is_some() {
+            let delta = node_response.drag_delta() / view.zoom;
+            let editor = self.editor_mut();
+            for node_id in &editor.selected_nodes {
+                if let Some(node_mut) = editor.workflow.nodes.get_mut(node_id) {
+                    node_mut.position += delta;
+                }
+            }
+        }
+        
+        if node_response.drag_stopped() {
+            let editor = self.editor_mut();
+            editor.dragging_node = None;
+            // Spliced within the drag's undo step
+            if let Some(connection_id) = editor.splice_target.take() {
+                match editor.workflow.splice_node(&connection_id, &node.id) {
+                    Ok(()) => log_debug!("Spliced node {} into connection {}", node.id, connection_id),
+                    Err(e) => log_warn!("Cannot splice node {}: {}", node.id, e),
                 }
             }
         }
@@ -3224,13 +5940,10 @@ impl FFmpegGui {
         let mut port_interactions = Vec::new();
         
         for (i, port) in node.input_ports.iter().enumerate() {
-            let port_pos = egui::pos2(
-                node_screen_pos.x - 5.0,
-                node_screen_pos.y + 35.0 + (i as f32 * 25.0)
-            );
+            let port_pos = view.port_position(node_rect, node.size, i, false);
             
-            let is_potential_target = self.creating_connection && 
-                self.connection_source_node.as_ref() != Some(&node.id);
+            let is_potential_target = self.editor().creating_connection && 
+                self.editor().connection_source_node.as_ref() != Some(&node.id);
 
             let port_color = if is_potential_target {
                 port.data_type.get_color().gamma_multiply(1.5)
@@ -3240,29 +5953,29 @@ impl FFmpegGui {
             
             painter.circle_filled(
                 port_pos,
-                6.0,
+                view.scale(6.0),
                 port_color
             );
             
 
             painter.circle_stroke(
                 port_pos,
-                6.0,
+                view.scale(6.0),
                 egui::Stroke::new(1.0, egui::Color32::WHITE)
             );
             
             painter.text(
-                egui::pos2(port_pos.x + 15.0, port_pos.y),
+                egui::pos2(port_pos.x + view.scale(15.0), port_pos.y),
                 egui::Align2::LEFT_CENTER,
-                &port.name,
-                egui::FontId::monospace(10.0),
+                self.translations.port_name(&port.name),
+                egui::FontId::monospace(view.scale(10.0)),
                 egui::Color32::WHITE
             );
             
-            let port_rect = egui::Rect::from_center_size(port_pos, egui::vec2(20.0, 20.0));
+            let port_rect = egui::Rect::from_center_size(port_pos, egui::Vec2::splat(view.scale(20.0)));
             let response = ui.interact(port_rect, egui::Id::new(format!("input_port_{}_{}", node.id, i)), egui::Sense::click());
             
-            if response.clicked() && self.creating_connection {
+            if response.clicked() && self.editor().creating_connection {
                 port_interactions.push((node.id.clone(), i, port.data_type.clone()));
                 log_debug!("Clicked input port {} on node {}", i, node.id);
             }
@@ -3270,50 +5983,49 @@ impl FFmpegGui {
         
         for (target_node_id, target_port_idx, target_data_type) in port_interactions {
             if let (Some(ref source_node_id), Some(source_port_idx)) = 
-                (&self.connection_source_node, self.connection_source_port) {
+                (self.editor().connection_source_node.clone(), self.editor().connection_source_port) {
                 
                 log_debug!("Attempting to connect {} port {} -> {} port {}", 
                     source_node_id, source_port_idx, target_node_id, target_port_idx);
                 
                 if source_node_id != &target_node_id {
-                    if let Some(ref mut workflow) = self.current_workflow {
-                        if let Some(source_node) = workflow.nodes.get(source_node_id) {
-                            if source_port_idx < source_node.output_ports.len() {
-                                let source_data_type = &source_node.output_ports[source_port_idx].data_type;
-                                
-                                log_debug!("Data types: {:?} -> {:?}", source_data_type, target_data_type);
+                    let workflow = &mut self.editor_mut().workflow;
+                    if let Some(source_node) = workflow.nodes.get(source_node_id) {
+                        if source_port_idx < source_node.output_ports.len() {
+                            let source_data_type = &source_node.output_ports[source_port_idx].data_type;
+                            
+                            log_debug!("Data types: {:?} -> {:?}", source_data_type, target_data_type);
+                            
+                            if automation_flow::AutomationWorkflow::are_types_compatible(source_data_type, &target_data_type) {
+                                let connection = automation_flow::NodeConnection {
+                                    id: automation_flow::new_connection_id(),
+                                    from_node: source_node_id.clone(),
+                                    from_port: source_port_idx,
+                                    to_node: target_node_id,
+                                    to_port: target_port_idx,
+                                    data_type: source_data_type.clone(),
+                                };
                                 
-                                if automation_flow::AutomationWorkflow::are_types_compatible(source_data_type, &target_data_type) {
-                                    let connection = automation_flow::NodeConnection {
-                                        id: format!("conn_{}", uuid::Uuid::new_v4().to_string()[..8].to_string()),
-                                        from_node: source_node_id.clone(),
-                                        from_port: source_port_idx,
-                                        to_node: target_node_id,
-                                        to_port: target_port_idx,
-                                        data_type: source_data_type.clone(),
-                                    };
-                                    
-                                    match workflow.add_connection(connection) {
-                                        Ok(_) => {
-                                            log_debug!("Connection created successfully");
-                                            
-                                            // Trigger auto-fill for all output nodes when connections are made
-                                            let workflow_clone = workflow.clone();
-                                            for (_, output_node) in workflow.nodes.iter_mut() {
-                                                if output_node.node_type == automation_flow::NodeType::OutputFile {
-                                                    output_node.auto_fill_output_from_input(&workflow_clone);
-                                                }
+                                match workflow.add_connection(connection) {
+                                    Ok(_) => {
+                                        log_debug!("Connection created successfully");
+                                        
+                                        // Trigger auto-fill for all output nodes when connections are made
+                                        let workflow_clone = workflow.clone();
+                                        for (_, output_node) in workflow.nodes.iter_mut() {
+                                            if output_node.node_type == automation_flow::NodeType::OutputFile {
+                                                output_node.auto_fill_output_from_input(&workflow_clone);
                                             }
-                                            
-                                            self.save_workflow_state();
-                                        },
-                                        Err(e) => {
-                                            log_error!("❌ Connection failed: {}", e);
                                         }
+                                        
+                                        self.save_workflow_state();
+                                    },
+                                    Err(e) => {
+                                        log_error!("❌ Connection failed: {}", e);
                                     }
-                                } else {
-                                    log_error!("❌ Data type mismatch: {:?} != {:?}", source_data_type, target_data_type);
                                 }
+                            } else {
+                                log_error!("❌ Data type mismatch: {:?} != {:?}", source_data_type, target_data_type);
                             }
                         }
                     }
@@ -3322,48 +6034,43 @@ impl FFmpegGui {
                 }
             }
             
-            self.creating_connection = false;
-            self.connection_source_node = None;
-            self.connection_source_port = None;
+            self.editor_mut().cancel_connection();
             log_debug!("Connection state reset");
         }
         
         for (i, port) in node.output_ports.iter().enumerate() {
-            let port_pos = egui::pos2(
-                node_screen_pos.x + node.size.x + 5.0,
-                node_screen_pos.y + 35.0 + (i as f32 * 25.0)
-            );
+            let port_pos = view.port_position(node_rect, node.size, i, true);
             
             painter.circle_filled(
                 port_pos,
-                6.0,
+                view.scale(6.0),
                 port.data_type.get_color()
             );
             
             painter.circle_stroke(
                 port_pos,
-                6.0,
+                view.scale(6.0),
                 egui::Stroke::new(1.0, egui::Color32::WHITE)
             );
             
             painter.text(
-                egui::pos2(port_pos.x - 15.0, port_pos.y),
+                egui::pos2(port_pos.x - view.scale(15.0), port_pos.y),
                 egui::Align2::RIGHT_CENTER,
-                &port.name,
-                egui::FontId::monospace(10.0),
+                self.translations.port_name(&port.name),
+                egui::FontId::monospace(view.scale(10.0)),
                 egui::Color32::WHITE
             );
             
-            let port_rect = egui::Rect::from_center_size(port_pos, egui::vec2(20.0, 20.0));
+            let port_rect = egui::Rect::from_center_size(port_pos, egui::Vec2::splat(view.scale(20.0)));
             let response = ui.interact(port_rect, egui::Id::new(format!("output_port_{}_{}", node.id, i)), egui::Sense::click());
             
-            if response.clicked() && !self.creating_connection {
-                self.creating_connection = true;
-                self.connection_source_node = Some(node.id.clone());
-                self.connection_source_port = Some(i);
-                self.connection_target_pos = port_pos;
+            if response.clicked() && !self.editor().creating_connection {
+                self.editor_mut().creating_connection = true;
+                self.editor_mut().connection_source_node = Some(node.id.clone());
+                self.editor_mut().connection_source_port = Some(i);
+                self.editor_mut().connection_target_pos = port_pos;
                 log_debug!("Started connection from node {} port {}", node.id, i);
-            } else if response.clicked() && self.creating_connection {
+            } else if response.clicked() && self.editor().creating_connection {
                 log_warn!("Cannot start new connection while one is in progress");
             }
         }
@@ -3373,7 +6080,7 @@ impl FFmpegGui {
         let painter = ui.painter();
         let node_rect = egui::Rect::from_min_size(node.position, node.size);
         
-        let is_selected = self.selected_node.as_ref() == Some(&node.id);
+        let is_selected = self.editor().is_selected(&node.id);
         let bg_color = if is_selected {
             node.node_type.get_color().gamma_multiply(1.3)
         } else {
@@ -3395,14 +6102,14 @@ impl FFmpegGui {
         painter.text(
             egui::pos2(node_rect.center().x, node_rect.min.y + 15.0),
             egui::Align2::CENTER_TOP,
-            node.node_type.display_name(),
+            self.translations.node_type_name(&node.node_type),
             egui::FontId::default(),
             egui::Color32::WHITE
         );
         
         let node_response = ui.interact(node_rect, egui::Id::new(format!("node_{}", node.id)), egui::Sense::click_and_drag());
         if node_response.clicked() {
-            self.selected_node = Some(node.id.clone());
+            self.editor_mut().select_only(&node.id);
             log_debug!("Node {} selected", node.id);
         }
         
@@ -3411,10 +6118,9 @@ impl FFmpegGui {
         }
         
         if node_response.dragged() {
-            if let Some(ref mut workflow) = self.current_workflow {
-                if let Some(ref mut node_mut) = workflow.nodes.get_mut(&node.id) {
-                    node_mut.position += node_response.drag_delta();
-                }
+            let workflow = &mut self.editor_mut().workflow;
+            if let Some(ref mut node_mut) = workflow.nodes.get_mut(&node.id) {
+                node_mut.position += node_response.drag_delta();
             }
         }
         
@@ -3422,8 +6128,8 @@ impl FFmpegGui {
         
         for (i, port) in node.input_ports.iter().enumerate() {
             if let Some(port_pos) = node.get_input_port_position(i) {
-                let is_potential_target = self.creating_connection && 
-                    self.connection_source_node.as_ref() != Some(&node.id);
+                let is_potential_target = self.editor().creating_connection && 
+                    self.editor().connection_source_node.as_ref() != Some(&node.id);
                 
                 let port_color = if is_potential_target {
                     port.data_type.get_color().gamma_multiply(1.5)
@@ -3446,7 +6152,7 @@ impl FFmpegGui {
                 painter.text(
                     egui::pos2(port_pos.x + 15.0, port_pos.y),
                     egui::Align2::LEFT_CENTER,
-                    &port.name,
+                    self.translations.port_name(&port.name),
                     egui::FontId::monospace(10.0),
                     egui::Color32::WHITE
                 );
@@ -3454,7 +6160,7 @@ impl FFmpegGui {
                 let port_rect = egui::Rect::from_center_size(port_pos, egui::vec2(20.0, 20.0));
                 let response = ui.interact(port_rect, egui::Id::new(format!("input_port_{}_{}", node.id, i)), egui::Sense::click());
                 
-                if response.clicked() && self.creating_connection {
+                if response.clicked() && self.editor().creating_connection {
                     port_interactions.push((node.id.clone(), i, port.data_type.clone()));
                     log_debug!("Clicked input port {} on node {}", i, node.id);
                 }
@@ -3463,50 +6169,49 @@ impl FFmpegGui {
         
         for (target_node_id, target_port_idx, target_data_type) in port_interactions {
             if let (Some(ref source_node_id), Some(source_port_idx)) = 
-                (&self.connection_source_node, self.connection_source_port) {
+                (self.editor().connection_source_node.clone(), self.editor().connection_source_port) {
                 
                 log_debug!("Attempting to connect {} port {} -> {} port {}", 
                     source_node_id, source_port_idx, target_node_id, target_port_idx);
                 
                 if source_node_id != &target_node_id {
-                    if let Some(ref mut workflow) = self.current_workflow {
-                        if let Some(source_node) = workflow.nodes.get(source_node_id) {
-                            if source_port_idx < source_node.output_ports.len() {
-                                let source_data_type = &source_node.output_ports[source_port_idx].data_type;
-                                
-                                log_debug!("Data types: {:?} -> {:?}", source_data_type, target_data_type);
+                    let workflow = &mut self.editor_mut().workflow;
+                    if let Some(source_node) = workflow.nodes.get(source_node_id) {
+                        if source_port_idx < source_node.output_ports.len() {
+                            let source_data_type = &source_node.output_ports[source_port_idx].data_type;
+                            
+                            log_debug!("Data types: {:?} -> {:?}", source_data_type, target_data_type);
+                            
+                            if source_data_type == &target_data_type {
+                                let connection = automation_flow::NodeConnection {
+                                    id: automation_flow::new_connection_id(),
+                                    from_node: source_node_id.clone(),
+                                    from_port: source_port_idx,
+                                    to_node: target_node_id,
+                                    to_port: target_port_idx,
+                                    data_type: source_data_type.clone(),
+                                };
                                 
-                                if source_data_type == &target_data_type {
-                                    let connection = automation_flow::NodeConnection {
-                                        id: format!("conn_{}", uuid::Uuid::new_v4().to_string()[..8].to_string()),
-                                        from_node: source_node_id.clone(),
-                                        from_port: source_port_idx,
-                                        to_node: target_node_id,
-                                        to_port: target_port_idx,
-                                        data_type: source_data_type.clone(),
-                                    };
-                                    
-                                    match workflow.add_connection(connection) {
-                                        Ok(_) => {
-                                            log_debug!("Connection created successfully");
-                                            
-                                            // Trigger auto-fill for all output nodes when connections are made
-                                            let workflow_clone = workflow.clone();
-                                            for (_, output_node) in workflow.nodes.iter_mut() {
-                                                if output_node.node_type == automation_flow::NodeType::OutputFile {
-                                                    output_node.auto_fill_output_from_input(&workflow_clone);
-                                                }
+                                match workflow.add_connection(connection) {
+                                    Ok(_) => {
+                                        log_debug!("Connection created successfully");
+                                        
+                                        // Trigger auto-fill for all output nodes when connections are made
+                                        let workflow_clone = workflow.clone();
+                                        for (_, output_node) in workflow.nodes.iter_mut() {
+                                            if output_node.node_type == automation_flow::NodeType::OutputFile {
+                                                output_node.auto_fill_output_from_input(&workflow_clone);
                                             }
-                                            
-                                            self.save_workflow_state();
-                                        },
-                                        Err(e) => {
-                                            log_error!("❌ Connection failed: {}", e);
                                         }
+                                        
+                                        self.save_workflow_state();
+                                    },
+                                    Err(e) => {
+                                        log_error!("❌ Connection failed: {}", e);
                                     }
-                                } else {
-                                    log_error!("❌ Data type mismatch: {:?} != {:?}", source_data_type, target_data_type);
                                 }
+                            } else {
+                                log_error!("❌ Data type mismatch: {:?} != {:?}", source_data_type, target_data_type);
                             }
                         }
                     }
@@ -3515,9 +6220,7 @@ impl FFmpegGui {
                 }
             }
             
-            self.creating_connection = false;
-            self.connection_source_node = None;
-            self.connection_source_port = None;
+            self.editor_mut().cancel_connection();
             log_debug!("Connection state reset");
         }
         
@@ -3539,7 +6242,7 @@ impl FFmpegGui {
                 painter.text(
                     egui::pos2(port_pos.x - 15.0, port_pos.y),
                     egui::Align2::RIGHT_CENTER,
-                    &port.name,
+                    self.translations.port_name(&port.name),
                     egui::FontId::monospace(10.0),
                     egui::Color32::WHITE
                 );
@@ -3547,13 +6250,13 @@ impl FFmpegGui {
                 let port_rect = egui::Rect::from_center_size(port_pos, egui::vec2(20.0, 20.0));
                 let response = ui.interact(port_rect, egui::Id::new(format!("output_port_{}_{}", node.id, i)), egui::Sense::click());
                 
-                if response.clicked() && !self.creating_connection {
-                    self.creating_connection = true;
-                    self.connection_source_node = Some(node.id.clone());
-                    self.connection_source_port = Some(i);
-                    self.connection_target_pos = port_pos;
+                if response.clicked() && !self.editor().creating_connection {
+                    self.editor_mut().creating_connection = true;
+                    self.editor_mut().connection_source_node = Some(node.id.clone());
+                    self.editor_mut().connection_source_port = Some(i);
+                    self.editor_mut().connection_target_pos = port_pos;
                     log_debug!("Started connection from node {} port {}", node.id, i);
-                } else if response.clicked() && self.creating_connection {
+                } else if response.clicked() && self.editor().creating_connection {
                     log_warn!("Cannot start new connection while one is in progress");
                 }
             }
@@ -3561,41 +6264,92 @@ impl FFmpegGui {
         
     }
     
-    fn handle_canvas_interaction(&mut self, ui: &mut egui::Ui, _canvas_rect: egui::Rect, response: &egui::Response) {
-        if self.creating_connection {
+    fn handle_canvas_interaction(&mut self, ui: &mut egui::Ui, canvas_rect: egui::Rect, response: &egui::Response) {
+        if self.editor().creating_connection {
             if let Some(pointer_pos) = ui.ctx().input(|i| i.pointer.latest_pos()) {
-                self.connection_target_pos = pointer_pos;
+                self.editor_mut().connection_target_pos = pointer_pos;
             }
         }
         
         if response.clicked() {
-            if self.creating_connection {
-                self.creating_connection = false;
-                self.connection_source_node = None;
-                self.connection_source_port = None;
+            if self.editor().creating_connection {
+                self.editor_mut().cancel_connection();
                 log_debug!("Connection cancelled by clicking empty area");
             } else {
-                self.selected_node = None;
+                self.editor_mut().selected_nodes.clear();
+                self.editor_mut().highlighted_connection = None;
+            }
+        }
+        
+        // Middle-button drag or Space+drag pans; a pan started on the canvas goes on outside it
+        let typing = ui.ctx().wants_keyboard_input();
+        let (pointer, pan_held, pointer_delta, zoom_factor) = ui.input(|i| (
+            i.pointer.latest_pos(),
+            i.pointer.middle_down() || (!typing && i.key_down(egui::Key::Space) && i.pointer.primary_down()),
+            i.pointer.delta(),
+            i.zoom_delta(),
+        ));
+        let over_canvas = pointer.is_some_and(|pos| canvas_rect.contains(pos)) && ui.ui_contains_pointer();
+        let panning = pan_held && (self.editor().panning || over_canvas);
+        let editor = self.editor_mut();
+        editor.panning = panning;
+        if panning {
+            editor.canvas_offset += pointer_delta;
+            editor.marquee_start = None;
+        }
+        
+        // Ctrl+scroll zooms around the pointer
+        if let Some(anchor) = pointer.filter(|_| over_canvas && zoom_factor != 1.0) {
+            let view = editor.canvas_view(canvas_rect);
+            (editor.canvas_offset, editor.canvas_zoom) = view.zoomed_at(anchor, zoom_factor);
+        }
+        
+        // Dragging on empty canvas draws a selection rectangle; with Ctrl it adds to the selection
+        if response.drag_started_by(egui::PointerButton::Primary) && !self.editor().creating_connection && !panning {
+            self.editor_mut().marquee_start = response.interact_pointer_pos();
+        }
+        if response.drag_stopped() {
+            if let Some(marquee) = self.marquee_rect(ui) {
+                let add = ui.input(|i| i.modifiers.command);
+                let editor = self.editor_mut();
+                let view = editor.canvas_view(canvas_rect);
+                editor.select_in_rect(egui::Rect::from_two_pos(view.to_workflow(marquee.min), view.to_workflow(marquee.max)), add);
+                log_debug!("Selected {} nodes with the selection rectangle", editor.selected_nodes.len());
             }
+            self.editor_mut().marquee_start = None;
         }
         
         if response.secondary_clicked() {
-            if self.creating_connection {
-                self.creating_connection = false;
-                self.connection_source_node = None;
-                self.connection_source_port = None;
+            if self.editor().creating_connection {
+                self.editor_mut().cancel_connection();
                 log_debug!("Connection cancelled by right click");
             }
         }
         
+        // Ctrl+C and Ctrl+V arrive as clipboard events; text fields being edited keep them
+        if !ui.ctx().wants_keyboard_input() {
+            let (copy, pasted) = ui.ctx().input(|i| (
+                i.events.iter().any(|event| matches!(event, egui::Event::Copy)),
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                }),
+            ));
+            if copy {
+                self.copy_selected_node(ui.ctx());
+            }
+            if let Some(text) = pasted {
+                self.paste_node(&text, egui::vec2(30.0, 30.0));
+            }
+        }
+        
         ui.ctx().input(|i| {
             if i.key_pressed(egui::Key::Escape) {
-                if self.creating_connection {
-                    self.creating_connection = false;
-                    self.connection_source_node = None;
-                    self.connection_source_port = None;
+                if self.editor().creating_connection {
+                    self.editor_mut().cancel_connection();
                     log_debug!("Connection cancelled by ESC key");
                 }
+                self.editor_mut().rerouting = None;
             }
             
             if i.key_pressed(egui::Key::Delete) {
@@ -3614,228 +6368,374 @@ impl FFmpegGui {
             if i.modifiers.ctrl && i.key_pressed(egui::Key::A) {
                 self.select_all_nodes();
             }
+
         });
     }
     
-    fn is_point_near_connection_line(&self, line_start: egui::Pos2, line_end: egui::Pos2, point: egui::Pos2) -> bool {
-        let line_length_sq = (line_end.x - line_start.x).powi(2) + (line_end.y - line_start.y).powi(2);
-        
-        if line_length_sq == 0.0 {
-            let dist_sq = (point.x - line_start.x).powi(2) + (point.y - line_start.y).powi(2);
-            return dist_sq <= 100.0; 
-        }
-        
-        let t = ((point.x - line_start.x) * (line_end.x - line_start.x) + 
-                (point.y - line_start.y) * (line_end.y - line_start.y)) / line_length_sq;
-        
-        let t = t.max(0.0).min(1.0);
-        
-        let closest_point = egui::pos2(
-            line_start.x + t * (line_end.x - line_start.x),
-            line_start.y + t * (line_end.y - line_start.y)
-        );
-        
-        let dist_sq = (point.x - closest_point.x).powi(2) + (point.y - closest_point.y).powi(2);
-        dist_sq <= 100.0 
+    /// Selection rectangle being dragged, in screen coordinates
+    fn marquee_rect(&self, ui: &egui::Ui) -> Option<egui::Rect> {
+        let start = self.editor().marquee_start?;
+        let pointer = ui.ctx().input(|i| i.pointer.latest_pos())?;
+        Some(egui::Rect::from_two_pos(start, pointer))
     }
     
-    fn save_workflow(&self) {
-        if let Some(ref workflow) = self.current_workflow {
-            match rfd::FileDialog::new()
-                .add_filter("Workflow Files", &["json"])
-                .set_file_name(&workflow.name)
-                .save_file()
-            {
-                Some(path) => {
-                    match serde_json::to_string_pretty(workflow) {
-                        Ok(json) => {
-                            if let Err(e) = std::fs::write(&path, json) {
-                                log_error!("Failed to save workflow: {}", e);
-                            } else {
-                                log_info!("Workflow saved successfully");
-                            }
-                        }
-                        Err(e) => {
-                            log_error!("Failed to serialize workflow: {}", e);
-                        }
+    /// Save the focused tab's workflow; returns true when it was written
+    fn save_workflow(&mut self) -> bool {
+        let editor = self.editor_mut();
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("Workflow Files", &["json"])
+            .set_file_name(&editor.workflow.name);
+        if let Some(dir) = editor.file_path.as_ref().and_then(|p| p.parent()) {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(path) = dialog.save_file() {
+            match workflow_file::to_json(&editor.workflow) {
+                Ok(json) => {
+                    if let Err(e) = std::fs::write(&path, json) {
+                        log_error!("Failed to save workflow: {}", e);
+                    } else {
+                        log_info!("Workflow saved successfully");
+                        editor.mark_saved(path);
+                        return true;
                     }
                 }
-                None => {}
+                Err(e) => {
+                    log_error!("Failed to serialize workflow: {}", e);
+                }
+            }
+        }
+        false
+    }
+    
+    /// Save the focused tab's workflow into the workflow library
+    fn save_workflow_to_library(&mut self) {
+        let editor = self.editor_mut();
+        match workflow_library::save(&editor.workflow, editor.file_path.as_deref()) {
+            Ok(path) => {
+                editor.mark_saved(path);
+                if let Some(window) = &mut self.workflow_library {
+                    window.refresh();
+                }
+            }
+            Err(e) => {
+                log_error!("Failed to save workflow to library: {}", e);
+                self.status_message = format!("{}: {}", self.translations.workflow_library_save_failed(), e);
             }
         }
     }
     
     fn load_workflow(&mut self) {
-        match rfd::FileDialog::new()
+        if let Some(path) = rfd::FileDialog::new()
             .add_filter("Workflow Files", &["json"])
             .pick_file()
         {
-            Some(path) => {
-                match std::fs::read_to_string(&path) {
-                    Ok(content) => {
-                        match serde_json::from_str::<automation_flow::AutomationWorkflow>(&content) {
-                            Ok(workflow) => {
-                                self.current_workflow = Some(workflow);
-                                log_info!("Workflow loaded successfully");
-                            }
-                            Err(e) => {
-                                log_error!("Failed to parse workflow: {}", e);
-                            }
+            self.open_workflow_file(path);
+        }
+    }
+    
+    fn open_workflow_file(&mut self, path: std::path::PathBuf) {
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                match workflow_file::parse(&content) {
+                    Ok((workflow, issues)) => {
+                        let tab = workflow_tabs::WorkflowEditorState::from_file(workflow, path.clone());
+                        // Reuse an untouched empty tab instead of piling up blank ones
+                        if self.editor().is_blank() {
+                            *self.editor_mut() = tab;
+                        } else {
+                            self.workflow_tabs.push(tab);
+                            self.active_workflow_tab = self.workflow_tabs.len() - 1;
+                        }
+                        log_info!("Workflow loaded with {} issue(s)", issues.len());
+                        if !issues.is_empty() {
+                            self.workflow_load_dialog = Some(workflow_file::LoadDialog::new(file_name, Ok(issues)));
                         }
                     }
                     Err(e) => {
-                        log_error!("Failed to read workflow file: {}", e);
+                        log_error!("Failed to parse workflow: {}", e);
+                        self.workflow_load_dialog = Some(workflow_file::LoadDialog::new(file_name, Err(e)));
                     }
                 }
             }
-            None => {}
+            Err(e) => {
+                log_error!("Failed to read workflow file: {}", e);
+            }
         }
     }
     
-    fn execute_workflow(&mut self) {
-        log_debug!("Execute button clicked");
-        
-        // Clone workflow early to avoid borrowing issues
-        let workflow_clone = self.current_workflow.clone();
-        
-        if let Some(workflow) = workflow_clone {
-            log_info!("Workflow exists with {} nodes", workflow.nodes.len());
-            
-            if workflow.nodes.is_empty() {
-                self.status_message = "No nodes in workflow".to_string();
-                log_warn!("No nodes in workflow");
-                return;
+    /// Compare the focused tab with the file it was loaded from or last saved to
+    fn compare_workflow_with_saved(&mut self) {
+        let Some(path) = self.editor().file_path.clone() else {
+            return;
+        };
+        match workflow_diff::load(&path) {
+            Ok(saved) => {
+                let label = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                self.workflow_diff = Some(workflow_diff::DiffWindow::with_saved(saved, label));
             }
-            
-            let has_input = workflow.nodes.values().any(|n| n.node_type == automation_flow::NodeType::InputFile);
-            let has_output = workflow.nodes.values().any(|n| n.node_type == automation_flow::NodeType::OutputFile);
-            
-            if !has_input {
-                self.status_message = "No input file node found".to_string();
-                log_error!("No input file node found");
-                return;
+            Err(e) => {
+                log_error!("Failed to load saved workflow {} for comparison: {}", path.display(), e);
+                self.status_message = format!("{}: {}", self.translations.workflow_diff_load_failed(), e);
             }
-            
-            if !has_output {
-                self.status_message = "No output file node found".to_string();
-                log_error!("No output file node found");
+        }
+    }
+    
+    fn compare_workflow_files(&mut self) {
+        let mut picked = Vec::new();
+        for title in [self.translations.workflow_diff_pick_old(), self.translations.workflow_diff_pick_new()] {
+            let Some(path) = rfd::FileDialog::new()
+                .set_title(title)
+                .add_filter("Workflow Files", &["json"])
+                .pick_file() else {
                 return;
-            }
-            
-            // Ensure workflow executor has latest hardware encoders
-            let hardware_encoders = self.get_cached_hardware_encoders();
-            if !hardware_encoders.is_empty() {
-                self.workflow_executor.update_hardware_cache(hardware_encoders);
-                log_info!("🚀 Workflow execution with hardware acceleration enabled");
-            } else {
-                log_info!("⚙ Workflow execution with software-only encoding");
-            }
-            
-            log_info!("Starting workflow execution...");
-            match self.workflow_executor.execute_workflow(workflow) {
-                Ok(_) => {
-                    self.status_message = "Workflow execution started successfully".to_string();
-                    log_debug!("Workflow execution started successfully");
-                }
+            };
+            match workflow_diff::load(&path) {
+                Ok(workflow) => picked.push((workflow, path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default())),
                 Err(e) => {
-                    self.status_message = format!("Workflow execution failed: {}", e);
-                    log_error!("❌ Workflow execution failed: {}", e);
+                    log_error!("Failed to load workflow {} for comparison: {}", path.display(), e);
+                    self.status_message = format!("{}: {}", self.translations.workflow_diff_load_failed(), e);
+                    return;
                 }
             }
+        }
+        let new = picked.pop().expect("two files picked");
+        let old = picked.pop().expect("two files picked");
+        self.workflow_diff = Some(workflow_diff::DiffWindow::with_files(old, new));
+    }
+    
+    /// `force_full` runs every node even when incremental execution would skip it
+    fn execute_workflow(&mut self, force_full: bool) {
+        log_debug!("Execute button clicked");
+        
+        // Clone workflow early to avoid borrowing issues
+        let workflow = self.editor().workflow.clone();
+        log_info!("Workflow exists with {} nodes", workflow.nodes.len());
+        
+        if self.workflow_run.is_some() {
+            self.status_message = "A workflow is already running".to_string();
+            return;
+        }
+        
+        if workflow.nodes.is_empty() {
+            self.status_message = "No nodes in workflow".to_string();
+            log_warn!("No nodes in workflow");
+            return;
+        }
+        
+        let flat = workflow.flattened();
+        let has_input = flat.nodes.values().any(|n| n.node_type == automation_flow::NodeType::InputFile);
+        let has_output = flat.nodes.values().any(|n| n.node_type == automation_flow::NodeType::OutputFile);
+        
+        if !has_input {
+            self.status_message = "No input file node found".to_string();
+            log_error!("No input file node found");
+            return;
+        }
+        
+        if !has_output {
+            self.status_message = "No output file node found".to_string();
+            log_error!("No output file node found");
+            return;
+        }
+        
+        // Ensure workflow executor has latest hardware encoders
+        let hardware_encoders = self.get_cached_hardware_encoders();
+        if !hardware_encoders.is_empty() {
+            self.workflow_executor.update_hardware_cache(hardware_encoders);
+            log_info!("🚀 Workflow execution with hardware acceleration enabled");
         } else {
-            self.status_message = "No workflow loaded".to_string();
-            log_warn!("No workflow loaded");
+            log_info!("⚙ Workflow execution with software-only encoding");
         }
+        
+        log_info!("Starting workflow execution...");
+        self.workflow_run = Some(self.workflow_executor.spawn_run(workflow, force_full));
+        self.status_message = "Workflow execution started".to_string();
     }
     
-    fn save_workflow_state(&mut self) {
-        if let Some(ref workflow) = self.current_workflow {
-            if self.history_index >= 0 && (self.history_index as usize) < self.workflow_history.len() - 1 {
-                self.workflow_history.truncate((self.history_index + 1) as usize);
+    /// Collect the workflow run once its thread has finished
+    fn poll_workflow_run(&mut self) {
+        if !self.workflow_run.as_ref().is_some_and(|handle| handle.is_finished()) {
+            return;
+        }
+        let Some(handle) = self.workflow_run.take() else {
+            return;
+        };
+        match handle.join() {
+            Ok((runner, result)) => {
+                self.workflow_executor.finish_background_run(runner);
+                match result {
+                    Ok(_) if self.workflow_executor.execution_status == automation_flow::ExecutionStatus::Cancelled => {
+                        self.status_message = "Workflow execution stopped".to_string();
+                    }
+                    Ok(_) => {
+                        self.status_message = "Workflow execution completed".to_string();
+                        log_debug!("Workflow execution completed");
+                    }
+                    Err(e) => {
+                        self.status_message = format!("Workflow execution failed: {}", e);
+                        log_error!("❌ Workflow execution failed: {}", e);
+                    }
+                }
             }
-            
-            self.workflow_history.push(workflow.clone());
-            self.history_index = self.workflow_history.len() as isize - 1;
-            
-            if self.workflow_history.len() > self.max_history_size {
-                self.workflow_history.remove(0);
-                self.history_index -= 1;
+            Err(_) => {
+                self.workflow_executor.execution_status = automation_flow::ExecutionStatus::Failed("Workflow thread panicked".to_string());
+                self.status_message = "Workflow execution failed: the workflow thread panicked".to_string();
+                log_error!("❌ Workflow thread panicked");
             }
-            
-            log_debug!("Saved workflow state. History size: {}, Index: {}", 
-                self.workflow_history.len(), self.history_index);
         }
     }
     
+    fn save_workflow_state(&mut self) {
+        let max_history_size = self.max_history_size;
+        let editor = self.editor_mut();
+        editor.save_state(max_history_size);
+        
+        log_debug!("Saved workflow state. History size: {}, Index: {}", 
+            editor.history.len(), editor.history_index);
+        // The edit lands after this snapshot; the autosave picks it up on the next frame
+        self.workflow_autosave_due = true;
+    }
+    
     fn undo_workflow(&mut self) {
-        if self.history_index > 0 {
-            self.history_index -= 1;
-            if let Some(workflow) = self.workflow_history.get(self.history_index as usize) {
-                self.current_workflow = Some(workflow.clone());
-                log_debug!("Undo: History index now {}", self.history_index);
-            }
+        if self.editor_mut().undo() {
+            log_debug!("Undo: History index now {}", self.editor().history_index);
         } else {
             log_debug!("No more undo steps available");
         }
     }
     
     fn redo_workflow(&mut self) {
-        if self.history_index < (self.workflow_history.len() as isize - 1) {
-            self.history_index += 1;
-            if let Some(workflow) = self.workflow_history.get(self.history_index as usize) {
-                self.current_workflow = Some(workflow.clone());
-                log_debug!("Redo: History index now {}", self.history_index);
-            }
+        if self.editor_mut().redo() {
+            log_debug!("Redo: History index now {}", self.editor().history_index);
         } else {
             log_debug!("No more redo steps available");
         }
     }
     
+    /// Delete every selected node with its connections, as one undo step
     fn delete_selected_node(&mut self) {
-        if let Some(ref selected_id) = self.selected_node.clone() {
-            self.save_workflow_state();
-            
-            if let Some(ref mut workflow) = self.current_workflow {
-                workflow.remove_node(selected_id);
-                self.selected_node = None;
-                log_debug!("Deleted node: {}", selected_id);
-            }
+        if self.editor().selected_nodes.is_empty() {
+            return;
         }
+        self.save_workflow_state();
+        
+        let editor = self.editor_mut();
+        let selected = std::mem::take(&mut editor.selected_nodes);
+        editor.workflow.remove_nodes(&selected);
+        log_debug!("Deleted {} nodes", selected.len());
     }
     
-    fn copy_selected_node(&mut self) -> Option<automation_flow::AutomationNode> {
-        if let Some(ref selected_id) = self.selected_node {
-            if let Some(ref workflow) = self.current_workflow {
-                return workflow.nodes.get(selected_id).cloned();
+    /// Copy the selected nodes and the connections between them to the clipboard as JSON, so they
+    /// can be pasted into another tab or another window of the app
+    fn copy_selected_node(&self, ctx: &egui::Context) {
+        let editor = self.editor();
+        if editor.selected_nodes.is_empty() {
+            return;
+        }
+        let copied = editor.workflow.copy_nodes(&editor.selected_nodes);
+        match serde_json::to_string(&copied) {
+            Ok(json) => {
+                log_debug!("Copied {} nodes", copied.nodes.len());
+                ctx.copy_text(json);
             }
+            Err(e) => log_error!("Failed to copy nodes: {}", e),
         }
-        None
     }
     
-    fn paste_node(&mut self, node: &automation_flow::AutomationNode, offset: egui::Vec2) {
-        // Save state first
+    /// Paste nodes copied by `copy_selected_node` with fresh ids, shifted by `offset`; the pasted
+    /// nodes become the selection. Other clipboard text is ignored.
+    fn paste_node(&mut self, text: &str, offset: egui::Vec2) {
+        let Ok(copied) = serde_json::from_str::<automation_flow::NodeGroup>(text) else {
+            return;
+        };
+        if copied.nodes.is_empty() {
+            return;
+        }
         self.save_workflow_state();
         
-        if let Some(ref mut workflow) = self.current_workflow {
-            let mut new_node = node.clone();
-            new_node.id = format!("node_{}", uuid::Uuid::new_v4().to_string()[..8].to_string());
-            let new_position = new_node.position + offset;
-            new_node.position = new_position;
-            
-            log_debug!("Pasted node at position {:?}", new_position);
-            workflow.add_node(new_node);
-        }
+        let editor = self.editor_mut();
+        let pasted = editor.workflow.paste_nodes(&copied, offset);
+        log_debug!("Pasted {} nodes", pasted.len());
+        editor.selected_nodes = pasted.into_iter().collect();
     }
     
     fn select_all_nodes(&mut self) {
-        if let Some(ref workflow) = self.current_workflow {
-            if let Some(first_node_id) = workflow.nodes.keys().next().cloned() {
-                self.selected_node = Some(first_node_id);
+        let editor = self.editor_mut();
+        editor.selected_nodes = editor.workflow.nodes.keys().cloned().collect();
+    }
+    
+    /// Collapse the Ctrl+clicked nodes into one group node
+    fn group_selected_nodes(&mut self) {
+        let ids: Vec<String> = self.editor().selected_nodes.iter()
+            .filter(|id| self.editor().workflow.nodes.contains_key(*id))
+            .cloned()
+            .collect();
+        if ids.len() < 2 {
+            return;
+        }
+        self.save_workflow_state();
+        let name = self.translations.node_group_default_name().to_string();
+        let editor = self.editor_mut();
+        match editor.workflow.group_nodes(&ids, &name) {
+            Ok(group_id) => {
+                log_info!("Grouped {} nodes into {}", ids.len(), group_id);
+                editor.select_only(&group_id);
+            }
+            Err(e) => {
+                log_warn!("Cannot group nodes: {}", e);
+                self.status_message = e;
+            }
+        }
+    }
+    
+    fn ungroup_node(&mut self, group_id: &str) {
+        self.save_workflow_state();
+        let editor = self.editor_mut();
+        match editor.workflow.ungroup_node(group_id) {
+            Ok(restored) => {
+                log_info!("Ungrouped {} into {} nodes", group_id, restored.len());
+                editor.selected_nodes = restored.into_iter().collect();
+            }
+            Err(e) => {
+                log_warn!("Cannot ungroup {}: {}", group_id, e);
+                self.status_message = e;
+            }
+        }
+    }
+    
+    fn save_group_template(&mut self, group_id: &str) {
+        let Some(group) = self.editor().workflow.nodes.get(group_id).and_then(|node| node.group.clone()) else {
+            return;
+        };
+        match node_group::save(&group) {
+            Ok(path) => self.status_message = format!("{} {}", self.translations.node_group_template_saved(), path.display()),
+            Err(e) => {
+                log_error!("Failed to save template: {}", e);
+                self.status_message = e;
             }
         }
     }
     
+    /// Add a copy of a template's group with ids of its own
+    fn insert_group_template(&mut self, group: &automation_flow::NodeGroup, position: egui::Pos2) {
+        self.save_workflow_state();
+        let node = automation_flow::AutomationNode::new_group(automation_flow::new_node_id(), group.with_fresh_ids(), position);
+        self.editor_mut().workflow.add_node(node);
+    }
+    
     fn handle_drag_and_drop(&mut self, ctx: &egui::Context) {
+        // With no operation selected, offer operations that fit the dropped files
+        if self.current_operation.is_none() && !self.show_automation_editor {
+            let dropped: Vec<String> = ctx.input(|i| i.raw.dropped_files.iter()
+                .filter_map(|file| file.path.as_ref().map(|path| path.to_string_lossy().to_string()))
+                .collect());
+            if let Some(suggestion) = drop_suggestion::suggest(&dropped) {
+                log_debug!("Suggesting {:?} for {} dropped files", suggestion.operations, suggestion.files.len());
+                self.drop_chooser = Some(drop_suggestion::DropChooser::new(suggestion, &self.drop_choices));
+                return;
+            }
+        }
         ctx.input(|i| {
             if !i.raw.dropped_files.is_empty() {
                 for file in &i.raw.dropped_files {
@@ -3878,6 +6778,64 @@ impl FFmpegGui {
         });
     }
     
+    fn show_drop_chooser(&mut self, ctx: &egui::Context) {
+        let Some(chooser) = &mut self.drop_chooser else {
+            return;
+        };
+        match chooser.show(ctx, &self.translations) {
+            drop_suggestion::ChooserAction::None => {}
+            drop_suggestion::ChooserAction::Dismiss => self.drop_chooser = None,
+            drop_suggestion::ChooserAction::Choose(operation) => {
+                let suggestion = chooser.suggestion.clone();
+                self.drop_chooser = None;
+                self.apply_drop_choice(operation, &suggestion);
+            }
+        }
+    }
+    
+    /// Select the chosen operation and put the dropped files where that operation reads its inputs
+    fn apply_drop_choice(&mut self, operation: OperationType, suggestion: &drop_suggestion::DropSuggestion) {
+        self.drop_choices.insert(suggestion.kind.key().to_string(), operation.clone());
+        self.save_preferences();
+        
+        self.current_operation = Some(operation.clone());
+        self.switch_operation_category(&operation);
+        
+        match operation {
+            OperationType::AddWatermark => {
+                if let Some(video) = suggestion.video() {
+                    self.dedicated_video_file = video.to_string();
+                }
+                if let Some(image) = suggestion.image() {
+                    self.video_settings.watermark_file = image.to_string();
+                }
+            }
+            OperationType::AddSubtitle => {
+                if let Some(video) = suggestion.video() {
+                    self.dedicated_video_file = video.to_string();
+                }
+                if let Some(subtitle) = suggestion.subtitle() {
+                    self.video_settings.subtitle_file = subtitle.to_string();
+                }
+            }
+            OperationType::AudioMerge | OperationType::BatchConvert | OperationType::VideoAudioMerge => {
+                self.input_files = suggestion.files.clone();
+            }
+            _ => {
+                self.input_files = suggestion.files.iter().take(1).cloned().collect();
+            }
+        }
+        // Same rule as picking files by hand: a name the user typed is kept
+        if self.output_file.is_empty() || self.is_auto_generated_filename(&self.output_file) {
+            if matches!(operation, OperationType::AddWatermark | OperationType::AddSubtitle) {
+                self.generate_output_filename_from_video();
+            } else {
+                self.generate_output_filename_from_input();
+            }
+        }
+        log_info!("Drop chooser: {:?} with {} files", operation, suggestion.files.len());
+    }
+    
     fn is_media_file(&self, file_path: &str) -> bool {
         let path = std::path::Path::new(file_path);
         if let Some(extension) = path.extension() {
@@ -3894,88 +6852,82 @@ impl FFmpegGui {
     }
     
     fn assign_file_to_automation_node(&mut self, file_path: &str) {
-        if let Some(ref mut workflow) = self.current_workflow {
-            // Find both input and output file nodes
-            let mut input_nodes: Vec<String> = workflow.nodes.iter()
-                .filter(|(_, node)| node.node_type == automation_flow::NodeType::InputFile)
-                .map(|(id, _)| id.clone())
-                .collect();
-            
-            let mut output_nodes: Vec<String> = workflow.nodes.iter()
-                .filter(|(_, node)| node.node_type == automation_flow::NodeType::OutputFile)
-                .map(|(id, _)| id.clone())
-                .collect();
-            
-            // Only show warning if workflow has other nodes but no file nodes
-            // (Empty workflow should not trigger this warning)
-            if input_nodes.is_empty() && output_nodes.is_empty() && !workflow.nodes.is_empty() {
-                self.status_message = "No Input or Output File nodes found. Add a file node first.".to_string();
-                log_debug!("Cannot assign file: no file nodes in workflow with {} nodes", workflow.nodes.len());
-                return;
-            }
-            
-            // If workflow is empty, silently ignore (user hasn't started building workflow yet)
-            if workflow.nodes.is_empty() {
-                return;
-            }
-            
-            // Determine target node - prioritize selected node if it's a file node
-            let (target_node_id, is_output_node) = if let Some(ref selected_id) = self.selected_node {
-                if input_nodes.contains(selected_id) {
-                    (selected_id.clone(), false)
-                } else if output_nodes.contains(selected_id) {
-                    (selected_id.clone(), true)
-                } else if !input_nodes.is_empty() {
-                    (input_nodes.remove(0), false)
-                } else {
-                    (output_nodes.remove(0), true)
-                }
+        let selected_node = self.editor().single_selected();
+        let workflow = &mut self.workflow_tabs[self.active_workflow_tab].workflow;
+        // Find both input and output file nodes
+        let mut input_nodes: Vec<String> = workflow.nodes.iter()
+            .filter(|(_, node)| node.node_type == automation_flow::NodeType::InputFile)
+            .map(|(id, _)| id.clone())
+            .collect();
+        
+        let mut output_nodes: Vec<String> = workflow.nodes.iter()
+            .filter(|(_, node)| node.node_type == automation_flow::NodeType::OutputFile)
+            .map(|(id, _)| id.clone())
+            .collect();
+        
+        // Only show warning if workflow has other nodes but no file nodes
+        // (Empty workflow should not trigger this warning)
+        if input_nodes.is_empty() && output_nodes.is_empty() && !workflow.nodes.is_empty() {
+            self.status_message = "No Input or Output File nodes found. Add a file node first.".to_string();
+            log_debug!("Cannot assign file: no file nodes in workflow with {} nodes", workflow.nodes.len());
+            return;
+        }
+        
+        // If workflow is empty, silently ignore (user hasn't started building workflow yet)
+        if workflow.nodes.is_empty() {
+            return;
+        }
+        
+        // Determine target node - prioritize selected node if it's a file node
+        let (target_node_id, is_output_node) = if let Some(ref selected_id) = selected_node {
+            if input_nodes.contains(selected_id) {
+                (selected_id.clone(), false)
+            } else if output_nodes.contains(selected_id) {
+                (selected_id.clone(), true)
             } else if !input_nodes.is_empty() {
                 (input_nodes.remove(0), false)
             } else {
                 (output_nodes.remove(0), true)
-            };
-            
-            if let Some(node) = workflow.nodes.get_mut(&target_node_id) {
-                let param_name = if is_output_node { "output_path" } else { "file_path" };
-                let node_type_name = if is_output_node { "Output" } else { "Input" };
-                
-                if let Some(param) = node.parameters.get_mut(param_name) {
-                    param.value = file_path.to_string();
-                    self.status_message = format!("File assigned to {} node: {}", 
-                        node_type_name,
-                        std::path::Path::new(file_path).file_name()
-                            .unwrap_or_default().to_string_lossy());
-                    log_debug!("Assigned file {} to {} node {}", file_path, node_type_name, target_node_id);
-                }
             }
+        } else if !input_nodes.is_empty() {
+            (input_nodes.remove(0), false)
         } else {
-            self.status_message = "No workflow loaded. Switch to automation editor first.".to_string();
+            (output_nodes.remove(0), true)
+        };
+        
+        if let Some(node) = workflow.nodes.get_mut(&target_node_id) {
+            let param_name = if is_output_node { "output_path" } else { "file_path" };
+            let node_type_name = if is_output_node { "Output" } else { "Input" };
+            
+            if let Some(param) = node.parameters.get_mut(param_name) {
+                param.value = file_path.to_string();
+                self.status_message = format!("File assigned to {} node: {}", 
+                    node_type_name,
+                    std::path::Path::new(file_path).file_name()
+                        .unwrap_or_default().to_string_lossy());
+                log_debug!("Assigned file {} to {} node {}", file_path, node_type_name, target_node_id);
+            }
         }
     }
 }
 
-fn cubic_bezier(p0: egui::Pos2, p1: egui::Pos2, p2: egui::Pos2, p3: egui::Pos2, t: f32) -> egui::Pos2 {
-    let t2 = t * t;
-    let t3 = t2 * t;
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
-    let mt3 = mt2 * mt;
-    
-    egui::pos2(
-        mt3 * p0.x + 3.0 * mt2 * t * p1.x + 3.0 * mt * t2 * p2.x + t3 * p3.x,
-        mt3 * p0.y + 3.0 * mt2 * t * p1.y + 3.0 * mt * t2 * p2.y + t3 * p3.y,
-    )
-}
-
 fn main() -> Result<()> {
+    logging::init_file_logging();
+    if let Some(trigger) = safe_mode::requested(std::env::args().skip(1), safe_mode::shift_held_at_launch()) {
+        safe_mode::activate(trigger);
+    }
+    
     std::env::set_var("AV_LOG_FORCE_LEVEL", "quiet");
     std::env::set_var("AV_LOG_FORCE_NOCOLOR", "1");
     std::env::set_var("FFMPEG_HIDE_BANNER", "1");
     std::env::set_var("AV_LOG_SKIP_REPEATED", "1");
     
-    // Initialize bundled FFmpeg (will be initialized on first use)
-    let _ = bundled_ffmpeg::get_bundled_ffmpeg()?;
+    // Unpack the bundled FFmpeg now; in safe mode that waits for its first use
+    safe_mode::guarded(safe_mode::Subsystem::FfmpegExtraction, bundled_ffmpeg::get_bundled_ffmpeg).transpose()?;
+    
+    if let Some(code) = headless::run_from_args(std::env::args().skip(1).collect()) {
+        std::process::exit(code);
+    }
     
     // Load the  
     let icon_data = load_custom_icon();
@@ -4086,6 +7038,8 @@ fn setup_fallback_fonts(ctx: &egui::Context) {
 
 impl Drop for FFmpegGui {
     fn drop(&mut self) {
+        // Before ffmpeg is stopped, which would fail the running tasks
+        self.save_task_queue();
         if let Some(executor) = &self.task_executor {
             executor.terminate_all_ffmpeg_processes();
         }
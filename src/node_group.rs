@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use crate::automation_flow::NodeGroup;
+use crate::language::Translations;
+
+/// Folder of saved group templates, next to the preferences file
+pub fn templates_dir() -> PathBuf {
+    crate::app_state::UserPreferences::file_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("templates")))
+        .unwrap_or_else(|| std::env::temp_dir().join("ffmpeg_gui_templates"))
+}
+
+/// One readable template file
+pub struct Template {
+    pub path: PathBuf,
+    pub group: NodeGroup,
+}
+
+/// Every template in the templates folder, by name. Files that are not templates are skipped.
+pub fn scan() -> Vec<Template> {
+    let Ok(read_dir) = std::fs::read_dir(templates_dir()) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<Template> = read_dir
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .filter_map(|path| match load(&path) {
+            Ok(group) => Some(Template { path, group }),
+            Err(e) => {
+                log_warn!("Skipping template {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    templates.sort_by(|a, b| a.group.name.to_lowercase().cmp(&b.group.name.to_lowercase()).then_with(|| a.path.cmp(&b.path)));
+    templates
+}
+
+fn load(path: &Path) -> Result<NodeGroup, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Write `group` as a new template file named after it
+pub fn save(group: &NodeGroup) -> Result<PathBuf, String> {
+    let dir = templates_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = crate::workflow_library::unique_path(&dir, &group.name, None);
+    let json = serde_json::to_string_pretty(group).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    log_info!("Group '{}' saved as template {}", group.name, path.display());
+    Ok(path)
+}
+
+pub fn delete(path: &Path) -> Result<(), String> {
+    std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    log_info!("Deleted template {}", path.display());
+    Ok(())
+}
+
+/// Name and contents of a group node. Returns true when the name changed.
+pub fn show_editor(ui: &mut egui::Ui, group: &mut NodeGroup, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.node_group_name());
+        changed |= ui.text_edit_singleline(&mut group.name).changed();
+    });
+
+    let mut nodes: Vec<_> = group.nodes.values().collect();
+    nodes.sort_by(|a, b| a.position.y.total_cmp(&b.position.y).then(a.position.x.total_cmp(&b.position.x)));
+    ui.label(translations.node_group_counts(nodes.len(), group.inputs.len(), group.outputs.len()));
+    for node in nodes {
+        let name = match &node.group {
+            Some(inner) => format!("{} ({})", translations.node_type_name(&node.node_type), inner.name),
+            None => translations.node_type_name(&node.node_type),
+        };
+        ui.weak(format!("• {}", name));
+    }
+    ui.weak(translations.node_group_hint());
+
+    changed
+}
@@ -2,28 +2,41 @@ use crate::app_state::{OperationType, VideoSettings, AudioSettings};
 use crate::language::Translations;
 use crate::comprehensive_codec_registry::CodecType;
 use crate::comprehensive_ui_components::{ComprehensiveUIComponents, FormatPurpose};
+use crate::effective_codecs::{show_auto_resolution, EffectiveCodecs};
 use egui;
 
 pub struct OperationSettings;
 
+/// What the settings panels know about the input and the machine
+pub struct SettingsContext<'a> {
+    pub detected_resolution: Option<(u32, u32)>,
+    pub is_portrait: Option<bool>,
+    pub cached_hw_encoders: &'a [String],
+    pub effective: &'a EffectiveCodecs,
+}
+
 impl OperationSettings {
-    pub fn show(ctx: &egui::Context, ui: &mut egui::Ui, operation: &OperationType, video_settings: &mut VideoSettings, audio_settings: &mut AudioSettings, translations: &Translations, detected_resolution: Option<(u32, u32)>, is_portrait: Option<bool>, cached_hw_encoders: &[String]) {
+    pub fn show(ctx: &egui::Context, ui: &mut egui::Ui, operation: &OperationType, video_settings: &mut VideoSettings, audio_settings: &mut AudioSettings, translations: &Translations, context: &SettingsContext) {
+        let SettingsContext { detected_resolution, is_portrait, cached_hw_encoders, effective } = *context;
         match operation {
             // Video operations
-            OperationType::VideoConvert => Self::show_video_convert(ui, video_settings, translations, cached_hw_encoders),
+            OperationType::VideoConvert => Self::show_video_convert(ui, video_settings, translations, cached_hw_encoders, effective),
             OperationType::VideoCompress => Self::show_video_compress(ui, video_settings, translations, cached_hw_encoders),
             OperationType::VideoResize => Self::show_video_resize(ui, video_settings, translations, detected_resolution, is_portrait),
             OperationType::VideoCrop => Self::show_video_crop(ui, video_settings, translations),
             OperationType::VideoRotate => Self::show_video_rotate(ui, video_settings, translations),
-            OperationType::VideoFilter => Self::show_video_filter(ui, video_settings, translations),
+            OperationType::VideoFilter => Self::show_video_filter(ui, video_settings, translations, detected_resolution),
+            OperationType::CutAndJoin => Self::show_cut_and_join(ui, video_settings, translations),
+            OperationType::FrameRateConvert => Self::show_frame_rate_convert(ui, video_settings, translations),
             
             // Audio operations
-            OperationType::AudioConvert => Self::show_audio_convert(ui, audio_settings, translations, cached_hw_encoders),
+            OperationType::AudioConvert => Self::show_audio_convert(ui, audio_settings, translations, cached_hw_encoders, effective),
             OperationType::AudioCompress => Self::show_audio_compress(ui, audio_settings, translations),
             OperationType::AudioResample => Self::show_audio_resample(ui, audio_settings, translations),
             OperationType::AudioVolume => Self::show_audio_volume(ui, audio_settings, translations),
             OperationType::AudioTrim => Self::show_audio_trim(ui, audio_settings, translations),
             OperationType::AudioMerge => Self::show_audio_merge(ui, audio_settings, translations),
+            OperationType::AudioNormalize => Self::show_audio_normalize(ui, audio_settings, translations),
             
             // Combined operations
             OperationType::VideoAudioMerge => Self::show_video_audio_merge(ui, video_settings, audio_settings, translations),
@@ -38,11 +51,13 @@ impl OperationSettings {
             OperationType::FrameExtract => Self::show_frame_extract(ui, video_settings, translations),
             OperationType::VideoToGif => Self::show_video_to_gif(ui, video_settings, translations),
             OperationType::GifResize => Self::show_gif_resize(ui, video_settings, translations),
+            // Configured in its own dialog from the file info panel
+            OperationType::ReviewClip => {}
         }
     }
     
     // Video Convert - Format conversion with codec selection
-    fn show_video_convert(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations, cached_hw_encoders: &[String]) {
+    fn show_video_convert(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations, cached_hw_encoders: &[String], effective: &EffectiveCodecs) {
         ui.group(|ui| {
             ui.heading(if translations.language == crate::language::Language::Chinese {
                 "🎬 视频格式转换设置"
@@ -79,14 +94,19 @@ impl OperationSettings {
                     });
                 });
                 
-                ComprehensiveUIComponents::show_comprehensive_codec_selector(
-                    ui,
-                    CodecType::Video,
-                    &mut settings.codec,
-                    &settings.container_format,
-                    translations,
-                    cached_hw_encoders
-                );
+                if settings.source_alpha && settings.alpha_background.is_none() {
+                    crate::alpha_video::show_alpha_codec_selector(ui, settings, translations);
+                } else {
+                    ComprehensiveUIComponents::show_comprehensive_codec_selector(
+                        ui,
+                        CodecType::Video,
+                        &mut settings.codec,
+                        &settings.container_format,
+                        translations,
+                        cached_hw_encoders
+                    );
+                }
+                show_auto_resolution(ui, &settings.codec, effective.video.as_deref(), translations);
                 
                 // Show advanced codec settings
                 let codec_name = settings.codec.clone();
@@ -195,27 +215,59 @@ impl OperationSettings {
             
             ui.add_space(10.0);
             
-            ui.horizontal(|ui| {
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "目标文件大小 (MB):"
-                } else {
-                    "Target File Size (MB):"
-                });
-                ui.add(egui::DragValue::new(&mut settings.target_size_mb)
-                    .range(1..=10000)
-                    .suffix(" MB"));
-            });
+            let mut size_target = settings.target_size_mb > 0;
+            if ui.checkbox(&mut size_target, translations.size_target_enable()).changed() {
+                settings.target_size_mb = if size_target { 50 } else { 0 };
+                settings.two_pass = false;
+            }
             
             if settings.target_size_mb > 0 {
+                ui.horizontal(|ui| {
+                    ui.label(if translations.language == crate::language::Language::Chinese {
+                        "目标文件大小 (MB):"
+                    } else {
+                        "Target File Size (MB):"
+                    });
+                    ui.add(egui::DragValue::new(&mut settings.target_size_mb)
+                        .range(1..=10000)
+                        .suffix(" MB"));
+                });
                 ui.label(if translations.language == crate::language::Language::Chinese {
                     "⚠ 启用目标文件大小会进行两遍编码"
                 } else {
                     "⚠ Target size enables two-pass encoding"
                 });
+            } else {
+                ui.add_space(10.0);
+                if ui.checkbox(&mut settings.two_pass, translations.two_pass_bitrate_enable())
+                    .on_hover_text(translations.two_pass_bitrate_hint())
+                    .changed() && settings.two_pass && crate::encode_advisor::bitrate_kbps(&settings.bitrate).is_none()
+                {
+                    settings.bitrate = "2000k".to_string();
+                }
+                if settings.two_pass {
+                    ui.horizontal(|ui| {
+                        ui.label(translations.two_pass_bitrate_label());
+                        let mut kbps = crate::encode_advisor::bitrate_kbps(&settings.bitrate).unwrap_or(2000);
+                        if ui.add(egui::DragValue::new(&mut kbps).range(100..=100_000).speed(10.0).suffix(" kbps")).changed() {
+                            settings.bitrate = format!("{}k", kbps);
+                        }
+                    });
+                }
             }
         });
     }
     
+    // Frame Rate Conversion - fps filter, motion interpolation or speed change
+    fn show_frame_rate_convert(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+        ui.group(|ui| {
+            ui.heading(format!("⚡ {}", translations.frame_rate_convert()));
+            ui.separator();
+            
+            crate::fps_conversion::show_editor(ui, &mut settings.fps_conversion, translations);
+        });
+    }
+    
     // Video Resize - Resolution adjustment
     fn show_video_resize(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations, detected_resolution: Option<(u32, u32)>, is_portrait: Option<bool>) {
         ui.group(|ui| {
@@ -320,7 +372,8 @@ impl OperationSettings {
             ui.horizontal(|ui| {
                 ui.label(translations.custom_width());
                 let mut width = settings.width.unwrap_or(0) as i32;
-                if ui.add(egui::DragValue::new(&mut width).range(0..=7680)).changed() {
+                let width_response = ui.add(egui::DragValue::new(&mut width).range(0..=7680));
+                if width_response.changed() {
                     settings.width = if width > 0 { Some(width as u32) } else { None };
                     // Sync with resolution field
                     if let (Some(w), Some(h)) = (settings.width, settings.height) {
@@ -330,13 +383,25 @@ impl OperationSettings {
                 
                 ui.label(translations.height());
                 let mut height = settings.height.unwrap_or(0) as i32;
-                if ui.add(egui::DragValue::new(&mut height).range(0..=4320)).changed() {
+                let height_response = ui.add(egui::DragValue::new(&mut height).range(0..=4320));
+                if height_response.changed() {
                     settings.height = if height > 0 { Some(height as u32) } else { None };
                     // Sync with resolution field
                     if let (Some(w), Some(h)) = (settings.width, settings.height) {
                         settings.resolution = (w, h);
                     }
                 }
+                
+                // Odd sizes and sizes beyond the encoder's largest frame
+                let issues = crate::parameter_limits::validate_video(settings);
+                if let Some(issue) = crate::parameter_limits::issue_for(&issues, crate::parameter_limits::Field::Size) {
+                    if crate::parameter_limits::show_field_issue(ui, &(width_response | height_response), issue, translations) {
+                        crate::parameter_limits::apply_video_clamp(issue, settings);
+                        if let (Some(w), Some(h)) = (settings.width, settings.height) {
+                            settings.resolution = (w, h);
+                        }
+                    }
+                }
             });
             
             ui.checkbox(&mut settings.maintain_aspect_ratio, 
@@ -483,7 +548,69 @@ impl OperationSettings {
     }
     
     // Video Filter - Various video filters
-    fn show_video_filter(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+    // Subtitle timing shift / re-sync
+    fn show_subtitle_timing(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+        let is_chinese = translations.language == crate::language::Language::Chinese;
+        ui.collapsing(if is_chinese { "⏱ 字幕时间调整" } else { "⏱ Subtitle Timing" }, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(if is_chinese { "偏移:" } else { "Offset:" });
+                let mut offset_seconds = settings.subtitle_offset_ms as f64 / 1000.0;
+                if crate::locale_format::duration_edit(ui, "subtitle_offset", &mut offset_seconds, translations).changed() {
+                    settings.subtitle_offset_ms = (offset_seconds * 1000.0).round() as i64;
+                }
+                for step in [-1000, -100, 100, 1000] {
+                    if ui.small_button(format!("{:+} ms", step)).clicked() {
+                        settings.subtitle_offset_ms += step;
+                    }
+                }
+            });
+            
+            ui.horizontal(|ui| {
+                ui.label(if is_chinese { "时间拉伸:" } else { "Stretch:" });
+                ui.add(egui::DragValue::new(&mut settings.subtitle_stretch).speed(0.0001).range(0.5..=2.0).max_decimals(6)
+                    .custom_parser(|text| crate::locale_format::parse_decimal_in_range(text, 0.5, 2.0).ok()));
+                egui::ComboBox::from_id_salt("subtitle_stretch_presets")
+                    .selected_text(if is_chinese { "帧率预设" } else { "Framerate presets" })
+                    .show_ui(ui, |ui| {
+                        for (label, factor) in crate::subtitle_timing::stretch_presets() {
+                            if ui.selectable_label((settings.subtitle_stretch - factor).abs() < 1e-6, label).clicked() {
+                                settings.subtitle_stretch = factor;
+                            }
+                        }
+                    });
+            });
+            
+            if ui.button(if is_chinese { "重置" } else { "Reset" }).clicked() {
+                settings.subtitle_offset_ms = 0;
+                settings.subtitle_stretch = 1.0;
+            }
+        });
+    }
+    
+    // Cut and Join - segment selection is done on the timeline strip
+    fn show_cut_and_join(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+        let is_chinese = translations.language == crate::language::Language::Chinese;
+        ui.group(|ui| {
+            ui.heading(if is_chinese { "🎞 剪切拼接设置" } else { "🎞 Cut & Join Settings" });
+            ui.separator();
+            
+            ui.horizontal(|ui| {
+                ui.label(if is_chinese { "模式:" } else { "Mode:" });
+                ui.radio_value(&mut settings.cut_reencode, false, if is_chinese { "流复制 (快速)" } else { "Stream copy (fast)" });
+                ui.radio_value(&mut settings.cut_reencode, true, if is_chinese { "重新编码 (帧精确)" } else { "Re-encode (frame accurate)" });
+            });
+            
+            if !settings.cut_reencode {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), if is_chinese {
+                    "⚠ 流复制只能在关键帧处切割，片段边界可能偏移最多一个GOP"
+                } else {
+                    "⚠ Stream copy cuts snap to keyframes, boundaries may shift by up to one GOP"
+                });
+            }
+        });
+    }
+    
+    fn show_video_filter(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations, detected_resolution: Option<(u32, u32)>) {
         ui.group(|ui| {
             ui.heading(if translations.language == crate::language::Language::Chinese {
                 "🎨 视频滤镜设置"
@@ -492,64 +619,38 @@ impl OperationSettings {
             });
             ui.separator();
             
-            ui.label(if translations.language == crate::language::Language::Chinese {
-                "选择滤镜:"
-            } else {
-                "Select Filter:"
-            });
-            
-            ui.checkbox(&mut settings.denoise, 
-                if translations.language == crate::language::Language::Chinese {
-                    "降噪"
-                } else {
-                    "Denoise"
-                });
-            ui.checkbox(&mut settings.deinterlace, 
-                if translations.language == crate::language::Language::Chinese {
-                    "去隔行"
-                } else {
-                    "Deinterlace"
+            // Projects from before the stack still carry the fixed trio; offer to move it into the stack
+            if settings.filter_stack.is_empty() && crate::filter_stack::has_legacy_filters(settings) {
+                ui.horizontal(|ui| {
+                    ui.label(translations.legacy_filters_hint());
+                    if ui.button(translations.convert_to_filter_stack()).clicked() {
+                        settings.filter_stack = crate::filter_stack::from_legacy(settings);
+                        settings.denoise = false;
+                        settings.deinterlace = false;
+                        settings.brightness = 0.0;
+                        settings.contrast = 1.0;
+                        settings.saturation = 1.0;
+                    }
                 });
+                ui.add_space(6.0);
+            }
+            
+            ui.label(translations.filter_stack_title());
+            crate::filter_stack::show_editor(ui, &mut settings.filter_stack, translations, detected_resolution);
+            
+            ui.add_space(10.0);
+            
             ui.checkbox(&mut settings.stabilize, 
                 if translations.language == crate::language::Language::Chinese {
                     "视频稳定"
                 } else {
                     "Stabilize"
                 });
-            
-            ui.add_space(10.0);
-            
-            ui.horizontal(|ui| {
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "亮度:"
-                } else {
-                    "Brightness:"
-                });
-                ui.add(egui::Slider::new(&mut settings.brightness, -1.0..=1.0));
-            });
-            
-            ui.horizontal(|ui| {
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "对比度:"
-                } else {
-                    "Contrast:"
-                });
-                ui.add(egui::Slider::new(&mut settings.contrast, -2.0..=2.0));
-            });
-            
-            ui.horizontal(|ui| {
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "饱和度:"
-                } else {
-                    "Saturation:"
-                });
-                ui.add(egui::Slider::new(&mut settings.saturation, 0.0..=3.0));
-            });
         });
     }
     
     // Audio Convert - Audio format conversion
-    fn show_audio_convert(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations, cached_hw_encoders: &[String]) {
+    fn show_audio_convert(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations, cached_hw_encoders: &[String], effective: &EffectiveCodecs) {
         ui.group(|ui| {
             ui.heading(if translations.language == crate::language::Language::Chinese {
                 "🎵 音频格式转换"
@@ -593,6 +694,7 @@ impl OperationSettings {
                 translations,
                 cached_hw_encoders
             );
+            show_auto_resolution(ui, &settings.codec, effective.audio.as_deref(), translations);
             
             ui.add_space(10.0);
             
@@ -612,6 +714,13 @@ impl OperationSettings {
                 } else {
                     "Keep Original Audio Stream"
                 });
+            
+            ui.add_space(10.0);
+            
+            // Fades re-encode, so they do not go with a copied stream
+            ui.add_enabled_ui(!settings.copy_audio, |ui| {
+                crate::audio_fade::show_editor(ui, &mut settings.fade, translations);
+            });
         });
     }
     
@@ -738,6 +847,19 @@ impl OperationSettings {
         });
     }
     
+    // Audio Normalize - Two-pass loudnorm towards a target loudness
+    fn show_audio_normalize(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations) {
+        ui.group(|ui| {
+            ui.heading(format!("📊 {}", translations.audio_normalize()));
+            ui.separator();
+            
+            crate::loudness::show_editor(ui, &mut settings.loudness, translations);
+            if let Err(e) = settings.loudness.validate() {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+            }
+        });
+    }
+    
     // Audio Trim - Cut audio segments
     fn show_audio_trim(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations) {
         ui.group(|ui| {
@@ -754,8 +876,7 @@ impl OperationSettings {
                 } else {
                     "Start Time:"
                 });
-                ui.text_edit_singleline(&mut settings.start_time);
-                ui.label("(HH:MM:SS)");
+                crate::locale_format::optional_duration_edit(ui, "audio_trim_start_time", &mut settings.start_time, translations);
             });
             
             ui.horizontal(|ui| {
@@ -764,24 +885,12 @@ impl OperationSettings {
                 } else {
                     "End Time:"
                 });
-                ui.text_edit_singleline(&mut settings.end_time);
-                ui.label("(HH:MM:SS)");
+                crate::locale_format::optional_duration_edit(ui, "audio_trim_end_time", &mut settings.end_time, translations);
             });
             
             ui.add_space(10.0);
             
-            ui.checkbox(&mut settings.fade_in, 
-                if translations.language == crate::language::Language::Chinese {
-                    "淡入效果"
-                } else {
-                    "Fade In"
-                });
-            ui.checkbox(&mut settings.fade_out, 
-                if translations.language == crate::language::Language::Chinese {
-                    "淡出效果"
-                } else {
-                    "Fade Out"
-                });
+            crate::audio_fade::show_editor(ui, &mut settings.fade, translations);
         });
     }
     
@@ -795,41 +904,7 @@ impl OperationSettings {
             });
             ui.separator();
             
-            ui.horizontal(|ui| {
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "合并模式:"
-                } else {
-                    "Merge Mode:"
-                });
-                ui.radio_value(&mut settings.merge_mode, "concat".to_string(), 
-                    if translations.language == crate::language::Language::Chinese { "顺序连接" } else { "Concatenate" });
-                ui.radio_value(&mut settings.merge_mode, "mix".to_string(), 
-                    if translations.language == crate::language::Language::Chinese { "混音" } else { "Mix" });
-            });
-            
-            ui.add_space(10.0);
-            
-            if settings.merge_mode == "concat" {
-                ui.checkbox(&mut settings.add_silence, 
-                    if translations.language == crate::language::Language::Chinese {
-                        "在音频之间添加静音"
-                    } else {
-                        "Add Silence Between Tracks"
-                    });
-                
-                if settings.add_silence {
-                    ui.horizontal(|ui| {
-                        ui.label(if translations.language == crate::language::Language::Chinese {
-                            "静音时长 (秒):"
-                        } else {
-                            "Silence Duration (seconds):"
-                        });
-                        ui.add(egui::DragValue::new(&mut settings.silence_duration)
-                            .range(0.0..=10.0)
-                            .suffix(" s"));
-                    });
-                }
-            }
+            crate::audio_mix::show_merge_editor(ui, settings, translations);
         });
     }
     
@@ -869,6 +944,10 @@ impl OperationSettings {
                     .speed(0.1)
                     .suffix(" s"));
             });
+            
+            ui.checkbox(&mut audio_settings.copy_audio, translations.merge_copy_audio())
+                .on_hover_text(translations.merge_copy_hint());
+            ui.checkbox(&mut audio_settings.merge_shortest, translations.merge_shortest());
         });
     }
     
@@ -988,7 +1067,7 @@ impl OperationSettings {
                             "Audio Bitrate:"
                         });
                         egui::ComboBox::from_id_salt("audio_split_bitrate")
-                            .selected_text(&audio_settings.bitrate)
+                            .selected_text(crate::locale_format::format_bitrate(&audio_settings.bitrate, &translations.language))
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(&mut audio_settings.bitrate, "96k".to_string(), "96 kbps");
                                 ui.selectable_value(&mut audio_settings.bitrate, "128k".to_string(), "128 kbps");
@@ -1218,27 +1297,20 @@ impl OperationSettings {
                         }
                     },
                     "filter" => {
-                        let filters = [
-                            (video_settings.denoise, if translations.language == crate::language::Language::Chinese { "降噪" } else { "Denoise" }),
-                            (video_settings.deinterlace, if translations.language == crate::language::Language::Chinese { "去隔行" } else { "Deinterlace" }),
-                            (video_settings.stabilize, if translations.language == crate::language::Language::Chinese { "视频稳定" } else { "Stabilize" }),
-                        ].iter().filter(|(enabled, _)| *enabled).map(|(_, name)| *name).collect::<Vec<_>>();
+                        let mut filters: Vec<&str> = crate::filter_stack::effective_stack(video_settings).iter()
+                            .filter(|entry| entry.enabled)
+                            .map(|entry| translations.filter_stage_name(&entry.stage))
+                            .collect();
+                        if video_settings.stabilize {
+                            filters.push(if translations.language == crate::language::Language::Chinese { "视频稳定" } else { "Stabilize" });
+                        }
                         
                         if !filters.is_empty() {
                             ui.label(format!("🎨 {}: {}", 
                                 if translations.language == crate::language::Language::Chinese { "滤镜" } else { "Filters" },
-                                filters.join(", ")
+                                filters.join(" → ")
                             ));
-                        }
-                        
-                        if video_settings.brightness != 0.0 || video_settings.contrast != 1.0 || video_settings.saturation != 1.0 {
-                            ui.label(format!("⚡ {} B:{:.1} C:{:.1} S:{:.1}", 
-                                if translations.language == crate::language::Language::Chinese { "色彩调整:" } else { "Color Adjust:" },
-                                video_settings.brightness, video_settings.contrast, video_settings.saturation
-                            ));
-                        }
-                        
-                        if filters.is_empty() && video_settings.brightness == 0.0 && video_settings.contrast == 1.0 && video_settings.saturation == 1.0 {
+                        } else {
                             ui.label(if translations.language == crate::language::Language::Chinese {
                                 "⚠ 请先在\"视频滤镜\"中设置滤镜参数"
                             } else {
@@ -1400,6 +1472,9 @@ impl OperationSettings {
                 ui.radio_value(&mut settings.subtitle_mode, "hard".to_string(), translations.hard_subtitle());
             });
             
+            ui.add_space(10.0);
+            Self::show_subtitle_timing(ui, settings, translations);
+            
             if settings.subtitle_mode == "hard" {
                 ui.add_space(5.0);
                 ui.label(if translations.language == crate::language::Language::Chinese {
@@ -1430,6 +1505,7 @@ impl OperationSettings {
                                 }
                             });
                     });
+                    crate::font_resolver::show_font_row(ui, &mut settings.subtitle_font_family, translations);
                     
                     // Font size
                     ui.horizontal(|ui| {
@@ -1633,8 +1709,33 @@ impl OperationSettings {
                         ui.selectable_value(&mut settings.watermark_position, "bottom-left".to_string(), translations.bottom_left());
                         ui.selectable_value(&mut settings.watermark_position, "bottom-right".to_string(), translations.bottom_right());
                         ui.selectable_value(&mut settings.watermark_position, "center".to_string(), translations.center());
+                        ui.selectable_value(&mut settings.watermark_position, "custom".to_string(), translations.watermark_custom_position());
                     });
             });
+            if settings.watermark_position == "custom" {
+                ui.horizontal(|ui| {
+                    ui.label("X:");
+                    ui.add(egui::DragValue::new(&mut settings.watermark_x).range(0..=7680));
+                    ui.label("Y:");
+                    ui.add(egui::DragValue::new(&mut settings.watermark_y).range(0..=4320));
+                });
+            }
+            
+            ui.add_space(5.0);
+            
+            ui.horizontal(|ui| {
+                ui.label(translations.watermark_text());
+                ui.add(egui::TextEdit::singleline(&mut settings.watermark_text)
+                    .hint_text(translations.watermark_text_hint())
+                    .desired_width(220.0));
+            });
+            if !settings.watermark_text.trim().is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label(translations.watermark_font_size());
+                    ui.add(egui::DragValue::new(&mut settings.watermark_font_size).range(8..=400));
+                });
+                crate::font_resolver::show_font_row(ui, &mut settings.watermark_font, translations);
+            }
             
             ui.add_space(5.0);
             
@@ -1673,17 +1774,23 @@ impl OperationSettings {
             });
             ui.separator();
             
+            // The old "time range" mode extracted one frame per second; the range is now its own setting
+            if settings.frame_extract_mode == "time" {
+                settings.frame_extract_mode = crate::frame_extract::MODE_EVERY_N_SECONDS.to_string();
+                settings.frame_interval_seconds = 1.0;
+            }
+            
             ui.horizontal(|ui| {
                 ui.label(translations.extract_mode());
-                ui.radio_value(&mut settings.frame_extract_mode, "all".to_string(), translations.all_frames());
-                ui.radio_value(&mut settings.frame_extract_mode, "interval".to_string(), translations.every_n_frames());
-                ui.radio_value(&mut settings.frame_extract_mode, "time".to_string(), translations.time_range());
+                ui.radio_value(&mut settings.frame_extract_mode, crate::frame_extract::MODE_ALL.to_string(), translations.all_frames());
+                ui.radio_value(&mut settings.frame_extract_mode, crate::frame_extract::MODE_EVERY_N_FRAMES.to_string(), translations.every_n_frames());
+                ui.radio_value(&mut settings.frame_extract_mode, crate::frame_extract::MODE_EVERY_N_SECONDS.to_string(), translations.frame_extract_every_n_seconds());
             });
             
             ui.add_space(10.0);
             
             match settings.frame_extract_mode.as_str() {
-                "interval" => {
+                crate::frame_extract::MODE_EVERY_N_FRAMES => {
                     ui.horizontal(|ui| {
                         ui.label(translations.frame_interval());
                         ui.add(egui::DragValue::new(&mut settings.frame_interval)
@@ -1696,21 +1803,18 @@ impl OperationSettings {
                         "Extract one frame every N frames"
                     });
                 }
-                "time" => {
-                    ui.horizontal(|ui| {
-                        ui.label(translations.start_time());
-                        ui.text_edit_singleline(&mut settings.frame_start_time);
-                        ui.label("(HH:MM:SS)");
-                    });
+                crate::frame_extract::MODE_EVERY_N_SECONDS => {
                     ui.horizontal(|ui| {
-                        ui.label(translations.end_time());
-                        ui.text_edit_singleline(&mut settings.frame_end_time);
-                        ui.label("(HH:MM:SS, 可选)");
+                        ui.label(translations.frame_extract_seconds_interval());
+                        ui.add(egui::DragValue::new(&mut settings.frame_interval_seconds)
+                            .range(0.04..=3600.0)
+                            .speed(0.1)
+                            .suffix(" s"));
                     });
                     ui.label(if translations.language == crate::language::Language::Chinese {
-                        "从指定时间范围提取帧"
+                        "每隔N秒提取一张图片"
                     } else {
-                        "Extract frames from specified time range"
+                        "Extract one frame every N seconds"
                     });
                 }
                 _ => {
@@ -1724,6 +1828,17 @@ impl OperationSettings {
             
             ui.add_space(10.0);
             
+            ui.label(translations.frame_extract_range());
+            ui.horizontal(|ui| {
+                ui.label(translations.start_time());
+                crate::locale_format::optional_duration_edit(ui, "frame_start_time", &mut settings.frame_start_time, translations);
+                ui.label(translations.end_time());
+                crate::locale_format::optional_duration_edit(ui, "frame_end_time", &mut settings.frame_end_time, translations);
+            });
+            ui.label(translations.frame_extract_range_hint());
+            
+            ui.add_space(10.0);
+            
             ui.horizontal(|ui| {
                 ui.label(translations.output_format());
                 ui.radio_value(&mut settings.frame_format, "png".to_string(), "PNG");
@@ -1731,28 +1846,40 @@ impl OperationSettings {
                 ui.radio_value(&mut settings.frame_format, "bmp".to_string(), "BMP");
             });
             
-            if settings.frame_format == "jpg" {
-                ui.add_space(5.0);
-                ui.horizontal(|ui| {
-                    ui.label(translations.image_quality());
-                    let quality_value = settings.frame_quality;
-                    ui.add(egui::Slider::new(&mut settings.frame_quality, 1..=31)
-                        .text(format!("{}", quality_value))
-                        .step_by(1.0));
-                });
-                ui.label(if translations.language == crate::language::Language::Chinese {
-                    "数值越小质量越高 (1=最高质量, 31=最低质量)"
-                } else {
-                    "Lower values = higher quality (1=highest, 31=lowest)"
-                });
+            match settings.frame_format.as_str() {
+                "jpg" => {
+                    ui.add_space(5.0);
+                    ui.horizontal(|ui| {
+                        ui.label(translations.image_quality());
+                        let quality_value = settings.frame_quality;
+                        ui.add(egui::Slider::new(&mut settings.frame_quality, 1..=31)
+                            .text(format!("{}", quality_value))
+                            .step_by(1.0));
+                    });
+                    ui.label(if translations.language == crate::language::Language::Chinese {
+                        "数值越小质量越高 (1=最高质量, 31=最低质量)"
+                    } else {
+                        "Lower values = higher quality (1=highest, 31=lowest)"
+                    });
+                }
+                "png" => {
+                    ui.add_space(5.0);
+                    ui.horizontal(|ui| {
+                        ui.label(translations.frame_extract_png_compression());
+                        ui.add(egui::Slider::new(&mut settings.frame_png_compression, 0..=9));
+                    });
+                    ui.label(translations.frame_extract_png_compression_hint());
+                }
+                _ => {}
             }
             
-            ui.add_space(10.0);
-            ui.label(if translations.language == crate::language::Language::Chinese {
-                "💡 输出文件将保存为: frame_001.png, frame_002.png..."
+            ui.add_space(5.0);
+            ui.checkbox(&mut settings.accurate_color_conversion, if translations.language == crate::language::Language::Chinese {
+                "精确色彩转换 (根据源的色彩范围/矩阵)"
             } else {
-                "💡 Output files will be saved as: frame_001.png, frame_002.png..."
+                "Accurate color conversion (from source range/matrix)"
             });
+
         });
     }
     
@@ -0,0 +1,201 @@
+use crate::app_state::OperationType;
+use crate::comprehensive_codec_registry::ComprehensiveCodecRegistry;
+
+/// Where an operation takes its output container from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerSource {
+    /// VideoSettings::container_format
+    Video,
+    /// AudioSettings::format
+    Audio,
+    /// The operation always writes this container
+    Fixed(&'static str),
+}
+
+/// Operations whose executor honors the selected container; the others derive the extension from the codec
+pub fn container_source(operation: &OperationType) -> Option<ContainerSource> {
+    match operation {
+        OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize => Some(ContainerSource::Video),
+        OperationType::AudioConvert | OperationType::AudioCompress |
+        OperationType::AudioResample | OperationType::AudioVolume | OperationType::AudioNormalize => Some(ContainerSource::Audio),
+        OperationType::VideoToGif | OperationType::GifResize => Some(ContainerSource::Fixed("gif")),
+        _ => None,
+    }
+}
+
+/// Ways to settle an output name whose extension disagrees with the container
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtensionFix {
+    /// Switch the container to the typed extension
+    ChangeContainer,
+    /// Replace the extension with the container's
+    RenameOutput,
+    /// Keep the name and force the muxer with -f
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionConflict {
+    pub extension: String,
+    pub container: String,
+    /// Fixes that make sense for this combination, in the order they are offered
+    pub fixes: Vec<ExtensionFix>,
+}
+
+/// Extensions naming the same container
+fn same_container(extension: &str, container: &str) -> bool {
+    fn canonical(name: &str) -> &str {
+        match name {
+            "jpeg" => "jpg",
+            "mpeg" => "mpg",
+            "mts" => "m2ts",
+            "tif" => "tiff",
+            _ => name,
+        }
+    }
+    canonical(extension) == canonical(container)
+}
+
+/// Compare the typed output extension with the container the operation will write.
+/// `container` is the selected container format; it is ignored for operations with a fixed container.
+pub fn reconcile(extension: &str, container: &str, operation: &OperationType) -> Option<ExtensionConflict> {
+    let source = container_source(operation)?;
+    let container = match source {
+        ContainerSource::Fixed(fixed) => fixed.to_string(),
+        _ => container.to_lowercase(),
+    };
+    let extension = extension.to_lowercase();
+
+    // "auto" lets the extension pick the container, and a missing extension is filled in by the executor
+    if extension.is_empty() || container.is_empty() || container == "auto" || container == "original" {
+        return None;
+    }
+    if same_container(&extension, &container) {
+        return None;
+    }
+
+    let mut fixes = Vec::new();
+    let known_container = ComprehensiveCodecRegistry::get_container_formats().contains_key(&extension);
+    if known_container && !matches!(source, ContainerSource::Fixed(_)) {
+        fixes.push(ExtensionFix::ChangeContainer);
+    }
+    fixes.push(ExtensionFix::RenameOutput);
+    if !matches!(source, ContainerSource::Fixed(_)) && muxer_name(&container).is_some() {
+        fixes.push(ExtensionFix::KeepBoth);
+    }
+
+    Some(ExtensionConflict { extension, container, fixes })
+}
+
+/// `output_file` with its extension replaced by `extension`
+pub fn with_extension(output_file: &str, extension: &str) -> String {
+    std::path::Path::new(output_file).with_extension(extension).display().to_string()
+}
+
+/// ffmpeg muxer for a container extension, passed with -f when the file name does not say it
+pub fn muxer_name(container: &str) -> Option<&'static str> {
+    Some(match container {
+        "mp4" | "m4v" => "mp4",
+        "mkv" => "matroska",
+        "webm" => "webm",
+        "avi" => "avi",
+        "mov" => "mov",
+        "ts" | "m2ts" | "mts" => "mpegts",
+        "flv" => "flv",
+        "3gp" => "3gp",
+        "wmv" | "wma" => "asf",
+        "mp3" => "mp3",
+        "aac" => "adts",
+        "m4a" => "ipod",
+        "flac" => "flac",
+        "wav" => "wav",
+        "ogg" => "ogg",
+        "opus" => "opus",
+        "gif" => "gif",
+        _ => return None,
+    })
+}
+
+/// -f flag forcing `container` when the output keeps a different extension
+pub fn forced_format_args(container: &str, keep_output_extension: bool) -> Vec<String> {
+    match muxer_name(&container.to_lowercase()) {
+        Some(muxer) if keep_output_extension => vec!["-f".to_string(), muxer.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ExtensionFix::{ChangeContainer, KeepBoth, RenameOutput};
+
+    #[test]
+    fn reconcile_offers_the_fixes_that_fit_each_combination() {
+        let cases: &[(&str, &str, OperationType, Option<&[ExtensionFix]>)] = &[
+            // Matching names, aliases and containers the executor fills in itself
+            ("mp4", "mp4", OperationType::VideoConvert, None),
+            ("MP4", "mp4", OperationType::VideoCompress, None),
+            ("jpeg", "jpg", OperationType::VideoConvert, None),
+            ("jpg", "jpeg", OperationType::VideoConvert, None),
+            ("mts", "m2ts", OperationType::VideoResize, None),
+            ("m2ts", "mts", OperationType::VideoResize, None),
+            ("mkv", "auto", OperationType::VideoConvert, None),
+            ("mkv", "original", OperationType::VideoConvert, None),
+            ("mp3", "AUTO", OperationType::AudioConvert, None),
+            ("", "mp4", OperationType::VideoConvert, None),
+            ("mkv", "", OperationType::VideoConvert, None),
+            // Video containers
+            ("mkv", "mp4", OperationType::VideoConvert, Some(&[ChangeContainer, RenameOutput, KeepBoth])),
+            ("txt", "mkv", OperationType::VideoCompress, Some(&[RenameOutput, KeepBoth])),
+            // Audio-only operations take the container from the audio settings
+            ("mp3", "wav", OperationType::AudioConvert, Some(&[ChangeContainer, RenameOutput, KeepBoth])),
+            ("m4a", "flac", OperationType::AudioNormalize, Some(&[ChangeContainer, RenameOutput, KeepBoth])),
+            ("mkv", "amr", OperationType::AudioVolume, Some(&[ChangeContainer, RenameOutput])),
+            ("xyz", "amr", OperationType::AudioResample, Some(&[RenameOutput])),
+            // GIF operations always write GIF whatever container is selected
+            ("gif", "mp4", OperationType::VideoToGif, None),
+            ("mp4", "mkv", OperationType::VideoToGif, Some(&[RenameOutput])),
+            ("webm", "gif", OperationType::GifResize, Some(&[RenameOutput])),
+            // Operations that derive the extension from the codec are never reconciled
+            ("mkv", "mp4", OperationType::ExtractAudio, None),
+            ("mkv", "mp4", OperationType::VideoCrop, None),
+            ("mkv", "mp4", OperationType::CutAndJoin, None),
+        ];
+        for (extension, container, operation, expected) in cases {
+            let conflict = reconcile(extension, container, operation);
+            assert_eq!(
+                conflict.as_ref().map(|conflict| conflict.fixes.as_slice()),
+                *expected,
+                "{} in {} for {:?}", extension, container, operation
+            );
+        }
+    }
+
+    #[test]
+    fn conflicts_name_the_lowercased_extension_and_the_container_written() {
+        let conflict = reconcile("MKV", "MP4", &OperationType::VideoConvert).unwrap();
+        assert_eq!(conflict.extension, "mkv");
+        assert_eq!(conflict.container, "mp4");
+
+        let conflict = reconcile("mp4", "mkv", &OperationType::VideoToGif).unwrap();
+        assert_eq!(conflict.container, "gif");
+    }
+
+    #[test]
+    fn forced_format_args_name_the_muxer_only_when_the_extension_is_kept() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(forced_format_args("mkv", true), args(&["-f", "matroska"]));
+        assert_eq!(forced_format_args("MKV", true), args(&["-f", "matroska"]));
+        assert_eq!(forced_format_args("mts", true), args(&["-f", "mpegts"]));
+        assert_eq!(forced_format_args("m4a", true), args(&["-f", "ipod"]));
+        assert_eq!(forced_format_args("aac", true), args(&["-f", "adts"]));
+        assert!(forced_format_args("mkv", false).is_empty());
+        assert!(forced_format_args("amr", true).is_empty());
+    }
+
+    #[test]
+    fn with_extension_replaces_only_the_last_extension() {
+        assert_eq!(with_extension("/out/clip.final.mkv", "mp4"), "/out/clip.final.mp4");
+        assert_eq!(with_extension("/out/clip", "mp4"), "/out/clip.mp4");
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use lazy_static::lazy_static;
+
+use crate::language::Translations;
+
+/// Operation categories that can have a default folder, with their labels
+fn default_categories(translations: &Translations) -> [(&'static str, &'static str); 3] {
+    [
+        ("video", translations.output_favorites_video()),
+        ("audio", translations.output_favorites_audio()),
+        ("batch", translations.output_favorites_batch()),
+    ]
+}
+
+/// How long a folder's availability is trusted before it is checked again
+const AVAILABILITY_REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+lazy_static! {
+    static ref AVAILABILITY: Mutex<HashMap<String, (Instant, bool)>> = Mutex::new(HashMap::new());
+    static ref CHECKING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Starred output folders, in the user's order, with an optional default folder per operation category
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OutputFavorites {
+    pub folders: Vec<String>,
+    /// Category ("video", "audio", "batch") -> starred folder new outputs of that category go to
+    #[serde(default)]
+    pub category_defaults: BTreeMap<String, String>,
+}
+
+impl OutputFavorites {
+    pub fn is_starred(&self, folder: &str) -> bool {
+        self.folders.iter().any(|starred| same_folder(starred, folder))
+    }
+
+    /// Star or unstar a folder; unstarring also drops it as a category default
+    pub fn toggle(&mut self, folder: &str) {
+        if self.is_starred(folder) {
+            self.folders.retain(|starred| !same_folder(starred, folder));
+            self.category_defaults.retain(|_, default| !same_folder(default, folder));
+        } else {
+            self.folders.push(folder.trim_end_matches(['/', '\\']).to_string());
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.folders.len() {
+            let folder = self.folders.remove(index);
+            self.category_defaults.retain(|_, default| !same_folder(default, &folder));
+        }
+    }
+
+    /// Swap the folder at `index` with its neighbour above (`up`) or below
+    pub fn move_folder(&mut self, index: usize, up: bool) {
+        let other = if up { index.checked_sub(1) } else { Some(index + 1) };
+        if let Some(other) = other.filter(|&other| other < self.folders.len() && index < self.folders.len()) {
+            self.folders.swap(index, other);
+        }
+    }
+
+    /// Starred default folder of a category
+    pub fn default_for(&self, category: &str) -> Option<&str> {
+        self.category_defaults.get(category)
+            .filter(|folder| self.is_starred(folder))
+            .map(String::as_str)
+    }
+
+    /// Make `folder` the category's default, or clear it if it already is
+    pub fn toggle_default(&mut self, category: &str, folder: &str) {
+        if self.default_for(category).is_some_and(|default| same_folder(default, folder)) {
+            self.category_defaults.remove(category);
+        } else {
+            self.category_defaults.insert(category.to_string(), folder.to_string());
+        }
+    }
+}
+
+fn same_folder(a: &str, b: &str) -> bool {
+    let trim = |path: &str| path.trim_end_matches(['/', '\\']).to_string();
+    if cfg!(windows) {
+        trim(a).eq_ignore_ascii_case(&trim(b))
+    } else {
+        trim(a) == trim(b)
+    }
+}
+
+/// Folder with the home directory as "~" and only the last two components when it is long
+pub fn short_path(folder: &str) -> String {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).unwrap_or_default();
+    let folder = match folder.strip_prefix(home.as_str()) {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with(['/', '\\'])) => format!("~{}", rest),
+        _ => folder.to_string(),
+    };
+    if folder.chars().count() <= 40 {
+        return folder;
+    }
+    let separator = if folder.contains('\\') { '\\' } else { '/' };
+    let parts: Vec<&str> = folder.split(separator).filter(|part| !part.is_empty()).collect();
+    match parts.as_slice() {
+        [.., parent, name] => format!("…{}{}{}{}", separator, parent, separator, name),
+        _ => folder,
+    }
+}
+
+/// Whether the folder exists, as of the last check; None until the first check finishes.
+/// Checks run on a background thread so an offline network share cannot stall the UI.
+pub fn folder_available(folder: &str) -> Option<bool> {
+    let cached = AVAILABILITY.lock().ok().and_then(|cache| cache.get(folder).copied());
+    if cached.is_none_or(|(checked_at, _)| checked_at.elapsed() >= AVAILABILITY_REFRESH_INTERVAL) {
+        let started = CHECKING.lock().map(|mut checking| checking.insert(folder.to_string())).unwrap_or(false);
+        if started {
+            let folder = folder.to_string();
+            std::thread::spawn(move || {
+                let available = Path::new(&folder).is_dir();
+                if let Ok(mut cache) = AVAILABILITY.lock() {
+                    cache.insert(folder.clone(), (Instant::now(), available));
+                }
+                if let Ok(mut checking) = CHECKING.lock() {
+                    checking.remove(&folder);
+                }
+            });
+        }
+    }
+    cached.map(|(_, available)| available)
+}
+
+pub enum FavoritesAction {
+    None,
+    /// Favorites were edited and should be saved
+    Changed,
+    /// Put the output into this folder
+    UseFolder(String),
+}
+
+/// Star button, favorites dropdown and management menu shown under the output selector
+pub fn show(
+    ui: &mut egui::Ui,
+    favorites: &mut OutputFavorites,
+    output_file: &str,
+    category: &str,
+    translations: &Translations,
+) -> FavoritesAction {
+    let mut action = FavoritesAction::None;
+    let current_folder = Path::new(output_file).parent()
+        .map(|parent| parent.display().to_string())
+        .filter(|parent| !parent.is_empty());
+
+    ui.horizontal(|ui| {
+        let starred = current_folder.as_deref().is_some_and(|folder| favorites.is_starred(folder));
+        let star = ui.add_enabled(current_folder.is_some(), egui::Button::new(if starred { "★" } else { "☆" }))
+            .on_hover_text(if starred { translations.output_favorites_unstar() } else { translations.output_favorites_star() });
+        if star.clicked() {
+            if let Some(folder) = &current_folder {
+                favorites.toggle(folder);
+                action = FavoritesAction::Changed;
+            }
+        }
+
+        egui::ComboBox::from_id_salt("output_favorites")
+            .selected_text(translations.output_favorites_favorites())
+            .width(220.0)
+            .show_ui(ui, |ui| {
+                if favorites.folders.is_empty() {
+                    ui.weak(translations.output_favorites_empty());
+                }
+                for folder in &favorites.folders {
+                    let offline = folder_available(folder) == Some(false);
+                    let text = if offline {
+                        egui::RichText::new(format!("⚠ {}", short_path(folder))).weak()
+                    } else {
+                        egui::RichText::new(short_path(folder))
+                    };
+                    let hover = if offline {
+                        format!("{}\n{}", folder, translations.output_favorites_offline())
+                    } else {
+                        folder.clone()
+                    };
+                    let selected = current_folder.as_deref().is_some_and(|current| same_folder(current, folder));
+                    if ui.selectable_label(selected, text).on_hover_text(hover).clicked() {
+                        action = FavoritesAction::UseFolder(folder.clone());
+                    }
+                }
+            });
+
+        if let Some(default) = favorites.default_for(category) {
+            ui.weak(format!("{} {}", translations.output_favorites_default(), short_path(default)))
+                .on_hover_text(default);
+        }
+
+        ui.menu_button("⚙", |ui| {
+            if show_manager(ui, favorites, translations) {
+                action = FavoritesAction::Changed;
+            }
+        });
+    });
+    action
+}
+
+/// Reorder and remove favorites and pick the category defaults; returns whether anything changed
+fn show_manager(ui: &mut egui::Ui, favorites: &mut OutputFavorites, translations: &Translations) -> bool {
+    ui.set_min_width(360.0);
+    ui.strong(translations.output_favorites_manage());
+    if favorites.folders.is_empty() {
+        ui.weak(translations.output_favorites_empty());
+        return false;
+    }
+    ui.weak(translations.output_favorites_default_hint());
+    let mut changed = false;
+    let mut remove = None;
+    let mut swap = None;
+    let count = favorites.folders.len();
+    egui::Grid::new("output_favorites_manager").num_columns(3).show(ui, |ui| {
+        for index in 0..count {
+            let folder = favorites.folders[index].clone();
+            if folder_available(&folder) == Some(false) {
+                ui.weak(format!("⚠ {}", short_path(&folder)))
+                    .on_hover_text(format!("{}\n{}", folder, translations.output_favorites_offline()));
+            } else {
+                ui.label(short_path(&folder)).on_hover_text(folder.as_str());
+            }
+            ui.horizontal(|ui| {
+                for (category, label) in default_categories(translations) {
+                    let is_default = favorites.default_for(category).is_some_and(|default| same_folder(default, &folder));
+                    if ui.selectable_label(is_default, label).clicked() {
+                        favorites.toggle_default(category, &folder);
+                        changed = true;
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.add_enabled(index > 0, egui::Button::new("⬆").small()).clicked() {
+                    swap = Some((index, true));
+                }
+                if ui.add_enabled(index + 1 < count, egui::Button::new("⬇").small()).clicked() {
+                    swap = Some((index, false));
+                }
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(index);
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if let Some((index, up)) = swap {
+        favorites.move_folder(index, up);
+        changed = true;
+    }
+    if let Some(index) = remove {
+        favorites.remove(index);
+        changed = true;
+    }
+    changed
+}
@@ -0,0 +1,304 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::language::Translations;
+
+/// How often a running task's output size is checked; statting every progress line would hammer
+/// network shares
+pub const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// An output this close to the duration limit counts as cut off by it
+const DURATION_TOLERANCE_SECS: f64 = 0.5;
+/// Past the limit by this much, -t evidently did not apply and the process is stopped
+const DURATION_OVERRUN_SECS: f64 = 5.0;
+/// Values a guard starts at when switched on
+const DEFAULT_MAX_DURATION_SECS: u64 = 4 * 3600;
+const DEFAULT_MAX_SIZE_MB: u64 = 20 * 1024;
+
+/// Limits on what a single task may write. Live inputs, looped inputs and watch folders can
+/// otherwise produce tasks that never end or fill the disk.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputGuards {
+    /// Passed to ffmpeg as -t
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// Checked against the output file every POLL_INTERVAL while ffmpeg runs
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+}
+
+impl OutputGuards {
+    pub fn is_active(&self) -> bool {
+        self.max_duration_secs.is_some() || self.max_size_mb.is_some()
+    }
+
+    fn max_size_bytes(&self) -> Option<u64> {
+        self.max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    /// Short form for the task card, e.g. "≤ 4:00:00 · ≤ 20480 MB"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(secs) = self.max_duration_secs {
+            parts.push(format!("≤ {}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60));
+        }
+        if let Some(mb) = self.max_size_mb {
+            parts.push(format!("≤ {} MB", mb));
+        }
+        parts.join(" · ")
+    }
+
+    /// The guard that `output_bytes` written and `reached_secs` of output time exceed, if any
+    pub fn check(&self, output_bytes: u64, reached_secs: Option<f64>) -> Option<GuardTrip> {
+        if let Some(limit) = self.max_size_bytes().filter(|limit| output_bytes > *limit) {
+            return Some(GuardTrip::Size { limit_mb: limit / (1024 * 1024), written_bytes: output_bytes });
+        }
+        match (self.max_duration_secs, reached_secs) {
+            (Some(limit), Some(reached)) if reached > limit as f64 + DURATION_OVERRUN_SECS => {
+                Some(GuardTrip::Duration { limit_secs: limit })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a run that ended normally was cut off by the duration limit rather than reaching
+    /// the end of its input. An unknown input duration (live streams) counts as longer.
+    pub fn cut_by_duration(&self, reached_secs: Option<f64>, input_secs: Option<f64>) -> Option<GuardTrip> {
+        let limit = self.max_duration_secs?;
+        let reached = reached_secs?;
+        let at_limit = reached >= limit as f64 - DURATION_TOLERANCE_SECS;
+        let input_longer = input_secs.is_none_or(|input| input > limit as f64 + DURATION_TOLERANCE_SECS);
+        (at_limit && input_longer).then_some(GuardTrip::Duration { limit_secs: limit })
+    }
+}
+
+/// Why a guard stopped a task; the task is marked Failed with this as its message
+#[derive(Clone, Debug, PartialEq)]
+pub enum GuardTrip {
+    Duration { limit_secs: u64 },
+    Size { limit_mb: u64, written_bytes: u64 },
+}
+
+impl std::fmt::Display for GuardTrip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuardTrip::Duration { limit_secs } => write!(
+                f, "Guard tripped: the output reached the maximum duration of {} s and was stopped there", limit_secs
+            ),
+            GuardTrip::Size { limit_mb, written_bytes } => write!(
+                f, "Guard tripped: the output grew to {} MB, over the maximum of {} MB, and ffmpeg was stopped",
+                written_bytes / (1024 * 1024), limit_mb
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GuardTrip {}
+
+/// The guards a task runs under and, once it has stopped, the one that tripped
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TaskGuards {
+    pub limits: OutputGuards,
+    #[serde(skip)]
+    pub tripped: Option<GuardTrip>,
+}
+
+impl TaskGuards {
+    pub fn new(limits: OutputGuards) -> Self {
+        Self { limits, tripped: None }
+    }
+}
+
+thread_local! {
+    /// Guards of the task running on this thread; each running task has its own thread
+    static ACTIVE: RefCell<Option<OutputGuards>> = const { RefCell::new(None) };
+}
+
+pub fn set_active(guards: Option<OutputGuards>) {
+    ACTIVE.with(|active| *active.borrow_mut() = guards.filter(OutputGuards::is_active));
+}
+
+pub fn active() -> Option<OutputGuards> {
+    ACTIVE.with(|active| active.borrow().clone())
+}
+
+/// `args` with `-t` placed after the last input, where it limits the output
+pub fn with_duration_limit(args: &[String], limit_secs: u64) -> Vec<String> {
+    let at = args.iter().rposition(|arg| arg == "-i").map_or(0, |i| (i + 2).min(args.len()));
+    let mut limited = args[..at].to_vec();
+    limited.extend(["-t".to_string(), limit_secs.to_string()]);
+    limited.extend_from_slice(&args[at..]);
+    limited
+}
+
+/// Arguments after the last input that may name an output file. Which of them exist is only
+/// known once ffmpeg starts writing, so the size check takes the largest existing one.
+pub fn output_candidates(args: &[String]) -> Vec<PathBuf> {
+    let start = args.iter().rposition(|arg| arg == "-i").map_or(0, |i| i + 2);
+    args.iter().skip(start)
+        .filter(|arg| !arg.starts_with('-') && Path::new(arg).extension().is_some())
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub fn written_bytes(candidates: &[PathBuf]) -> u64 {
+    candidates.iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Checkboxes and values for both guards; returns true when anything changed
+pub fn show_editor(ui: &mut egui::Ui, guards: &mut OutputGuards, translations: &Translations) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        let mut enabled = guards.max_duration_secs.is_some();
+        if ui.checkbox(&mut enabled, translations.output_guard_max_duration())
+            .on_hover_text(translations.output_guard_max_duration_hint())
+            .changed()
+        {
+            guards.max_duration_secs = enabled.then_some(DEFAULT_MAX_DURATION_SECS);
+            changed = true;
+        }
+        if let Some(secs) = guards.max_duration_secs.as_mut() {
+            let mut minutes = *secs / 60;
+            if ui.add(egui::DragValue::new(&mut minutes).range(1..=10_000).suffix(" min")).changed() {
+                *secs = minutes * 60;
+                changed = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        let mut enabled = guards.max_size_mb.is_some();
+        if ui.checkbox(&mut enabled, translations.output_guard_max_size())
+            .on_hover_text(translations.output_guard_max_size_hint())
+            .changed()
+        {
+            guards.max_size_mb = enabled.then_some(DEFAULT_MAX_SIZE_MB);
+            changed = true;
+        }
+        if let Some(mb) = guards.max_size_mb.as_mut() {
+            changed |= ui.add(egui::DragValue::new(mb).range(1..=10_000_000).speed(10.0).suffix(" MB")).changed();
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    const MB: u64 = 1024 * 1024;
+
+    fn guards(duration: Option<u64>, size: Option<u64>) -> OutputGuards {
+        OutputGuards { max_duration_secs: duration, max_size_mb: size }
+    }
+
+    #[test]
+    fn size_guard_trips_only_past_the_limit() {
+        let size = guards(None, Some(100));
+        assert_eq!(size.check(0, None), None);
+        assert_eq!(size.check(100 * MB, Some(1e9)), None);
+        assert_eq!(size.check(100 * MB + 1, None), Some(GuardTrip::Size { limit_mb: 100, written_bytes: 100 * MB + 1 }));
+        // A limit too large to express in bytes never trips
+        assert_eq!(guards(None, Some(u64::MAX)).check(u64::MAX, None), None);
+    }
+
+    #[test]
+    fn duration_guard_stops_only_an_overrun_that_t_did_not_end() {
+        let duration = guards(Some(60), None);
+        assert_eq!(duration.check(u64::MAX, None), None, "no size limit, no time yet");
+        assert_eq!(duration.check(0, Some(60.0)), None);
+        assert_eq!(duration.check(0, Some(60.0 + DURATION_OVERRUN_SECS)), None);
+        assert_eq!(duration.check(0, Some(65.1)), Some(GuardTrip::Duration { limit_secs: 60 }));
+
+        // Both over: the size is reported, being what fills the disk
+        let both = guards(Some(60), Some(1));
+        assert!(matches!(both.check(2 * MB, Some(600.0)), Some(GuardTrip::Size { .. })));
+        assert_eq!(both.check(MB, Some(600.0)), Some(GuardTrip::Duration { limit_secs: 60 }));
+    }
+
+    #[test]
+    fn a_normal_end_at_the_limit_counts_as_cut_off_unless_the_input_ended_there() {
+        let duration = guards(Some(60), None);
+        let cut = Some(GuardTrip::Duration { limit_secs: 60 });
+        // Live input of unknown length stopped by -t
+        assert_eq!(duration.cut_by_duration(Some(60.0), None), cut);
+        assert_eq!(duration.cut_by_duration(Some(59.6), Some(3600.0)), cut);
+        // Ended short of the limit, or the input is no longer than the limit
+        assert_eq!(duration.cut_by_duration(Some(42.0), None), None);
+        assert_eq!(duration.cut_by_duration(Some(60.0), Some(60.4)), None);
+        assert_eq!(duration.cut_by_duration(None, None), None);
+        assert_eq!(guards(None, Some(5)).cut_by_duration(Some(1e6), None), None);
+    }
+
+    #[test]
+    fn trips_explain_themselves_in_the_task_error() {
+        assert_eq!(
+            GuardTrip::Duration { limit_secs: 14400 }.to_string(),
+            "Guard tripped: the output reached the maximum duration of 14400 s and was stopped there"
+        );
+        assert_eq!(
+            GuardTrip::Size { limit_mb: 100, written_bytes: 150 * MB + 5 }.to_string(),
+            "Guard tripped: the output grew to 150 MB, over the maximum of 100 MB, and ffmpeg was stopped"
+        );
+        let error = anyhow::Error::new(GuardTrip::Duration { limit_secs: 1 });
+        assert_eq!(error.downcast_ref::<GuardTrip>(), Some(&GuardTrip::Duration { limit_secs: 1 }));
+    }
+
+    #[test]
+    fn summary_and_activity() {
+        assert!(!OutputGuards::default().is_active());
+        assert_eq!(OutputGuards::default().summary(), "");
+        assert_eq!(guards(Some(4 * 3600 + 65), None).summary(), "≤ 4:01:05");
+        assert_eq!(guards(Some(90), Some(20480)).summary(), "≤ 0:01:30 · ≤ 20480 MB");
+        assert!(guards(None, Some(1)).is_active());
+
+        // Preferences from before the guards existed load with both off
+        let loaded: OutputGuards = serde_json::from_str("{}").unwrap();
+        assert_eq!(loaded, OutputGuards::default());
+    }
+
+    #[test]
+    fn active_guards_are_per_thread_and_only_when_set() {
+        set_active(Some(OutputGuards::default()));
+        assert_eq!(active(), None, "guards that limit nothing are not installed");
+        set_active(Some(guards(Some(60), None)));
+        assert_eq!(active(), Some(guards(Some(60), None)));
+        std::thread::spawn(|| assert_eq!(active(), None)).join().unwrap();
+        set_active(None);
+        assert_eq!(active(), None);
+    }
+
+    #[test]
+    fn duration_limit_goes_after_the_last_input() {
+        let command = args(&["-y", "-i", "a.mp4", "-i", "b.wav", "-c:v", "libx264", "out.mp4"]);
+        assert_eq!(with_duration_limit(&command, 60), args(&["-y", "-i", "a.mp4", "-i", "b.wav", "-t", "60", "-c:v", "libx264", "out.mp4"]));
+        assert_eq!(with_duration_limit(&args(&["-f", "lavfi", "-i"]), 5), args(&["-f", "lavfi", "-i", "-t", "5"]));
+        assert_eq!(with_duration_limit(&args(&["out.mp4"]), 5), args(&["-t", "5", "out.mp4"]));
+    }
+
+    #[test]
+    fn size_is_read_from_the_largest_existing_output() {
+        let command = args(&["-i", "in.mkv", "-map", "0", "-f", "tee", "-metadata", "title=x", "a.mp4", "-c", "copy", "b.mkv"]);
+        assert_eq!(output_candidates(&command), vec![PathBuf::from("a.mp4"), PathBuf::from("b.mkv")]);
+
+        let dir = crate::test_support::temp_dir("guard");
+        std::fs::write(dir.join("small.mp4"), vec![0u8; 10]).unwrap();
+        std::fs::write(dir.join("large.mkv"), vec![0u8; 300]).unwrap();
+        std::fs::create_dir_all(dir.join("folder.mp4")).unwrap();
+        let candidates = [dir.join("small.mp4"), dir.join("large.mkv"), dir.join("missing.mp4"), dir.join("folder.mp4")];
+        assert_eq!(written_bytes(&candidates), 300);
+        assert_eq!(written_bytes(&candidates[2..]), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
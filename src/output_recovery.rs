@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::app_state::{OperationType, ProcessingTask};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+/// ffmpeg messages of a failed write to the output. Plain I/O errors only count when they name the
+/// output file, so a failing read from the input is not mistaken for a lost destination.
+const MUXER_WRITE_SIGNATURES: &[&str] = &[
+    "no space left on device",
+    "av_interleaved_write_frame()",
+    "error muxing a packet",
+    "error submitting a packet to the muxer",
+    "error writing trailer",
+    "error closing file",
+];
+const IO_ERROR_SIGNATURES: &[&str] = &[
+    "input/output error",
+    "i/o error",
+    "broken pipe",
+    "stale file handle",
+    "the specified network name is no longer available",
+];
+
+/// How often the destination of a task waiting for recovery is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The resumed tail starts this far before the end of the partial, whose last GOP may be cut short
+const RESUME_MARGIN_SECS: f64 = 2.0;
+/// Below this much kept output a restart costs about as much as a resume
+const MIN_RESUME_SECS: f64 = 5.0;
+
+/// Containers whose partial outputs the concat demuxer can join with a new tail
+const CONCAT_CONTAINERS: &[&str] = &["mp4", "m4v", "mov", "mkv", "webm", "ts", "mts", "m2ts", "mpg", "mpeg"];
+
+/// Whether a failed task lost its output destination mid-encode (unplugged drive, dropped NAS,
+/// full disk) rather than failing on its input or settings
+pub fn is_output_failure(error: &str, output_file: &str) -> bool {
+    let error = error.to_lowercase();
+    let file_name = Path::new(output_file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let write_failure = error.split(';').any(|line| {
+        MUXER_WRITE_SIGNATURES.iter().any(|signature| line.contains(signature))
+            || (!file_name.is_empty()
+                && line.contains(&file_name)
+                && IO_ERROR_SIGNATURES.iter().any(|signature| line.contains(signature)))
+    });
+    write_failure || !destination_reachable(output_file)
+}
+
+/// The output's parent directory exists and can be listed
+pub fn destination_reachable(output_file: &str) -> bool {
+    let parent = match Path::new(output_file).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::read_dir(parent).is_ok()
+}
+
+/// What a task waiting for recovery does next
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecoveryChoice {
+    /// Encode everything again to the same path
+    Restart,
+    /// Keep the partial output up to this many seconds and encode only the rest
+    Resume(f64),
+    /// Encode everything again to another path
+    Redirect(String),
+}
+
+/// Why a task cannot resume, checked before its partial output is even looked at
+pub fn resume_support(task: &ProcessingTask) -> Result<(), String> {
+    if !matches!(task.operation, OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize) {
+        return Err("only conversions can resume".to_string());
+    }
+    let settings = task.video_settings.as_ref().ok_or("no video settings")?;
+    let container = Path::new(&task.output_file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or(&settings.container_format)
+        .to_lowercase();
+    if !CONCAT_CONTAINERS.contains(&container.as_str()) {
+        return Err(format!("{} outputs cannot be joined", container));
+    }
+    // A stream-copied tail starts at the keyframe before the resume point and would repeat frames
+    if settings.copy_video || settings.codec == "copy" {
+        return Err("stream copy cannot resume".to_string());
+    }
+    // The bitrate of a second pass or a size target is planned for the whole file
+    if settings.two_pass || settings.target_size_mb > 0 {
+        return Err("two-pass and target-size encodes cannot resume".to_string());
+    }
+    Ok(())
+}
+
+/// What ffprobe and a decode of the last seconds found in a partial output
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialOutput {
+    pub duration: f64,
+    pub has_video: bool,
+}
+
+/// Probe the partial output and decode its end; fails for truncated files ffmpeg cannot read, such
+/// as an MP4 whose index was never written
+pub fn validate_partial(path: &str) -> Result<PartialOutput, String> {
+    validate_partial_with(
+        path,
+        |args| get_bundled_ffmpeg().and_then(|ffmpeg| ffmpeg.run_ffprobe(args)).map(|output| output.stdout).map_err(|e| e.to_string()),
+        |args| get_bundled_ffmpeg().and_then(|ffmpeg| ffmpeg.run_ffmpeg(args))
+            .map(|output| String::from_utf8_lossy(&output.stderr).trim().to_string())
+            .map_err(|e| e.to_string()),
+    )
+}
+
+/// `validate_partial` with the ffprobe run (returning stdout) and the decode run (returning stderr)
+/// passed in; a run that fails is an error
+fn validate_partial_with(
+    path: &str,
+    probe: impl FnOnce(&[&str]) -> Result<Vec<u8>, String>,
+    decode: impl FnOnce(&[&str]) -> Result<String, String>,
+) -> Result<PartialOutput, String> {
+    if !Path::new(path).is_file() {
+        return Err("no partial output".to_string());
+    }
+    let stdout = probe(&[
+        "-v", "error",
+        "-show_entries", "format=duration:stream=codec_type",
+        "-of", "json",
+        path,
+    ])?;
+    let json: serde_json::Value = serde_json::from_slice(&stdout).map_err(|e| e.to_string())?;
+    let duration = json["format"]["duration"].as_str()
+        .and_then(|duration| duration.parse::<f64>().ok())
+        .filter(|duration| *duration > 0.0)
+        .ok_or("partial output has no duration")?;
+    let has_video = json["streams"].as_array()
+        .is_some_and(|streams| streams.iter().any(|stream| stream["codec_type"] == "video"));
+
+    // The head kept for the resume must decode up to its end
+    let tail_start = format!("{:.3}", (duration - RESUME_MARGIN_SECS * 2.0).max(0.0));
+    let errors = decode(&["-v", "error", "-ss", &tail_start, "-i", path, "-f", "null", "-"])
+        .map_err(|e| format!("partial output does not decode to its end: {}", e.lines().next().unwrap_or("")))?;
+    if !errors.is_empty() {
+        return Err(format!("partial output does not decode to its end: {}", errors.lines().next().unwrap_or("")));
+    }
+    Ok(PartialOutput { duration, has_video })
+}
+
+/// Seconds of the partial output to keep, leaving a margin before its possibly cut-off end
+pub fn plan_resume(partial: &PartialOutput) -> Result<f64, String> {
+    if !partial.has_video {
+        return Err("partial output has no video".to_string());
+    }
+    let resume_at = partial.duration - RESUME_MARGIN_SECS;
+    if resume_at < MIN_RESUME_SECS {
+        return Err("too little output to keep".to_string());
+    }
+    Ok((resume_at * 1000.0).floor() / 1000.0)
+}
+
+/// Commands of a resume: copy the head of the partial aside, encode the tail (built by the caller
+/// from the task's settings into `tail_file`) and join both over the partial
+#[derive(Debug, Clone)]
+pub struct ResumeCommands {
+    pub head: Vec<String>,
+    pub tail_file: String,
+    /// Concat demuxer list (path, contents), written before `join` runs
+    pub concat_list: (String, String),
+    pub join: Vec<String>,
+}
+
+pub fn resume_commands(output_file: &str, resume_at: f64, temp_dir: &str) -> ResumeCommands {
+    let extension = Path::new(output_file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mkv");
+    let temp = |name: &str| Path::new(temp_dir).join(name).display().to_string();
+    let head_file = temp(&format!("resume_head.{}", extension));
+    let tail_file = temp(&format!("resume_tail.{}", extension));
+    let list_path = temp("resume.txt");
+    let list_contents = [&head_file, &tail_file].iter()
+        .map(|file| format!("file '{}'\n", file.replace('\'', "'\\''")))
+        .collect();
+    let owned = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    ResumeCommands {
+        head: owned(&["-i", output_file, "-t", &format!("{:.3}", resume_at), "-map", "0", "-c", "copy", "-y", &head_file]),
+        tail_file,
+        join: owned(&["-f", "concat", "-safe", "0", "-i", &list_path, "-map", "0", "-c", "copy", "-y", output_file]),
+        concat_list: (list_path, list_contents),
+    }
+}
+
+/// Put `-ss` in front of the input so the tail encode starts at the resume point
+pub fn seek_input(args: &mut Vec<String>, resume_at: f64) {
+    let position = args.iter().position(|arg| arg == "-i").unwrap_or(0);
+    args.splice(position..position, ["-ss".to_string(), format!("{:.3}", resume_at)]);
+}
+
+/// State of a task waiting for its destination, as seen by the last poll
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryStatus {
+    pub reachable: bool,
+    /// Resume point once the destination is back and the partial was checked; the error says why not
+    pub resume: Option<Result<f64, String>>,
+}
+
+lazy_static::lazy_static! {
+    /// Tasks in NeedsOutputRecovery by id; their watcher stops when the entry is removed
+    static ref WATCHED: Mutex<HashMap<usize, RecoveryStatus>> = Mutex::new(HashMap::new());
+}
+
+/// Resume check due at a poll: the destination just came back, so the partial is looked at again
+fn resume_check(
+    support: &Result<(), String>,
+    reachable: bool,
+    was_reachable: bool,
+    check: impl FnOnce() -> Result<f64, String>,
+) -> Option<Result<f64, String>> {
+    if !reachable || was_reachable {
+        return None;
+    }
+    Some(match support {
+        Err(reason) => Err(reason.clone()),
+        Ok(()) => check(),
+    })
+}
+
+/// Fold one poll into the watched status; true when the task row needs a redraw
+fn apply_poll(status: &mut RecoveryStatus, reachable: bool, resume: Option<Result<f64, String>>) -> bool {
+    if reachable == status.reachable && resume.is_none() {
+        return false;
+    }
+    status.reachable = reachable;
+    if !reachable {
+        status.resume = None;
+    } else if let Some(resume) = resume {
+        status.resume = Some(resume);
+    }
+    true
+}
+
+/// Poll the task's destination until a recovery choice is made; the partial output is validated
+/// each time the destination comes back
+pub fn watch(task_id: usize, output_file: String, support: Result<(), String>) {
+    WATCHED.lock().unwrap().insert(task_id, RecoveryStatus { reachable: false, resume: None });
+    std::thread::spawn(move || {
+        let mut was_reachable = false;
+        loop {
+            let reachable = destination_reachable(&output_file);
+            let resume = resume_check(&support, reachable, was_reachable, || {
+                validate_partial(&output_file).and_then(|partial| plan_resume(&partial))
+            });
+            {
+                let mut watched = WATCHED.lock().unwrap();
+                let Some(status) = watched.get_mut(&task_id) else {
+                    break;
+                };
+                if let Some(resume) = &resume {
+                    log_info!("Task {}: destination of {} is reachable again (resume: {:?})", task_id, output_file, resume);
+                }
+                if apply_poll(status, reachable, resume) {
+                    crate::task_executor::mark_tasks_dirty();
+                }
+            }
+            was_reachable = reachable;
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+pub fn status(task_id: usize) -> Option<RecoveryStatus> {
+    WATCHED.lock().unwrap().get(&task_id).cloned()
+}
+
+/// Stop watching a task once a recovery choice was made
+pub fn forget(task_id: usize) {
+    WATCHED.lock().unwrap().remove(&task_id);
+}
+
+/// Delete the partial output left behind after redirecting a task. On a destination that is still
+/// away it is removed once the destination comes back, so the stale file does not reappear.
+pub fn discard_partial(output_file: &str) {
+    let output_file = output_file.to_string();
+    std::thread::spawn(move || {
+        while !destination_reachable(&output_file) {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        if Path::new(&output_file).is_file() {
+            match std::fs::remove_file(&output_file) {
+                Ok(()) => log_info!("Removed partial output {}", output_file),
+                Err(e) => log_warn!("Could not remove partial output {}: {}", output_file, e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    const PROBE_JSON: &str = r#"{"streams":[{"codec_type":"video"},{"codec_type":"audio"}],"format":{"duration":"42.500000"}}"#;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn convert_task(output: &str) -> ProcessingTask {
+        let mut task = ProcessingTask::new(OperationType::VideoConvert, vec!["in.mp4".to_string()], output.to_string());
+        task.video_settings = Some(crate::app_state::VideoSettings::default());
+        task
+    }
+
+    fn partial_file() -> (std::path::PathBuf, String) {
+        let dir = crate::test_support::temp_dir("output_recovery");
+        let path = dir.join("partial.mkv");
+        std::fs::write(&path, b"partial").unwrap();
+        (dir, path.display().to_string())
+    }
+
+    #[test]
+    fn output_failures_are_told_apart_from_input_failures() {
+        let (dir, output) = partial_file();
+        assert!(is_output_failure("av_interleaved_write_frame(): No space left on device", &output));
+        assert!(is_output_failure("Error opening input; partial.mkv: Input/output error", &output));
+        assert!(!is_output_failure("in.mp4: Input/output error", &output));
+        assert!(!is_output_failure("Invalid data found when processing input", &output));
+        // A destination that disappeared counts whatever ffmpeg said
+        let gone = dir.join("unplugged").join("out.mkv").display().to_string();
+        assert!(is_output_failure("Conversion failed!", &gone));
+        assert!(!destination_reachable(&gone));
+        assert!(destination_reachable(&output));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn only_re_encoded_joinable_conversions_can_resume() {
+        assert_eq!(resume_support(&convert_task("/out/a.mp4")), Ok(()));
+        assert_eq!(resume_support(&convert_task("/out/a.MKV")), Ok(()));
+
+        let mut task = convert_task("/out/a.mp4");
+        task.operation = OperationType::AudioConvert;
+        assert!(resume_support(&task).is_err());
+
+        let mut task = convert_task("/out/a.gif");
+        assert_eq!(resume_support(&task), Err("gif outputs cannot be joined".to_string()));
+        // Without an extension the container setting decides
+        task.output_file = "/out/a".to_string();
+        assert_eq!(resume_support(&task), Ok(()));
+
+        for change in [
+            |settings: &mut crate::app_state::VideoSettings| settings.copy_video = true,
+            |settings: &mut crate::app_state::VideoSettings| settings.codec = "copy".to_string(),
+            |settings: &mut crate::app_state::VideoSettings| settings.two_pass = true,
+            |settings: &mut crate::app_state::VideoSettings| settings.target_size_mb = 50,
+        ] {
+            let mut task = convert_task("/out/a.mp4");
+            change(task.video_settings.as_mut().unwrap());
+            assert!(resume_support(&task).is_err());
+        }
+        let mut task = convert_task("/out/a.mp4");
+        task.video_settings = None;
+        assert!(resume_support(&task).is_err());
+    }
+
+    #[test]
+    fn validation_needs_a_partial_file() {
+        let (dir, _) = partial_file();
+        let probed = RefCell::new(false);
+        let probe = |_: &[&str]| -> Result<Vec<u8>, String> {
+            *probed.borrow_mut() = true;
+            Ok(PROBE_JSON.as_bytes().to_vec())
+        };
+        let missing = dir.join("missing.mkv").display().to_string();
+        assert_eq!(validate_partial_with(&missing, probe, |_| Ok(String::new())), Err("no partial output".to_string()));
+        assert_eq!(validate_partial_with(&dir.display().to_string(), probe, |_| Ok(String::new())), Err("no partial output".to_string()));
+        assert!(!*probed.borrow());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validation_probes_the_partial_and_decodes_its_end() {
+        let (dir, path) = partial_file();
+        let decoded = RefCell::new(Vec::new());
+        let partial = validate_partial_with(
+            &path,
+            |args| {
+                assert_eq!(args.last(), Some(&path.as_str()));
+                Ok(PROBE_JSON.as_bytes().to_vec())
+            },
+            |args| {
+                *decoded.borrow_mut() = strings(args);
+                Ok(String::new())
+            },
+        );
+        assert_eq!(partial, Ok(PartialOutput { duration: 42.5, has_video: true }));
+        assert_eq!(*decoded.borrow(), strings(&["-v", "error", "-ss", "38.500", "-i", &path, "-f", "null", "-"]));
+
+        let audio_only = r#"{"streams":[{"codec_type":"audio"}],"format":{"duration":"3.0"}}"#;
+        let partial = validate_partial_with(&path, |_| Ok(audio_only.as_bytes().to_vec()), |args| {
+            assert_eq!(args[3], "0.000");
+            Ok(String::new())
+        });
+        assert_eq!(partial, Ok(PartialOutput { duration: 3.0, has_video: false }));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validation_rejects_unreadable_and_truncated_partials() {
+        let (dir, path) = partial_file();
+        let ok = |_: &[&str]| Ok(String::new());
+        let probe = |json: &'static str| move |_: &[&str]| Ok(json.as_bytes().to_vec());
+
+        assert_eq!(
+            validate_partial_with(&path, |_| Err("moov atom not found".to_string()), ok),
+            Err("moov atom not found".to_string())
+        );
+        assert!(validate_partial_with(&path, probe("not json"), ok).is_err());
+        assert_eq!(
+            validate_partial_with(&path, probe(r#"{"streams":[],"format":{}}"#), ok),
+            Err("partial output has no duration".to_string())
+        );
+        assert_eq!(
+            validate_partial_with(&path, probe(r#"{"format":{"duration":"0.000"}}"#), ok),
+            Err("partial output has no duration".to_string())
+        );
+        assert_eq!(
+            validate_partial_with(&path, probe(PROBE_JSON), |_| Ok("[h264] error while decoding MB 3 4\nmore".to_string())),
+            Err("partial output does not decode to its end: [h264] error while decoding MB 3 4".to_string())
+        );
+        assert_eq!(
+            validate_partial_with(&path, probe(PROBE_JSON), |_| Err("FFmpeg failed: truncated".to_string())),
+            Err("partial output does not decode to its end: FFmpeg failed: truncated".to_string())
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resume_plan_keeps_the_head_before_the_margin() {
+        assert_eq!(plan_resume(&PartialOutput { duration: 42.5, has_video: true }), Ok(40.5));
+        assert_eq!(plan_resume(&PartialOutput { duration: 100.12345, has_video: true }), Ok(98.123));
+        assert_eq!(plan_resume(&PartialOutput { duration: 7.0, has_video: true }), Ok(5.0));
+        assert_eq!(plan_resume(&PartialOutput { duration: 6.9, has_video: true }), Err("too little output to keep".to_string()));
+        assert_eq!(plan_resume(&PartialOutput { duration: 600.0, has_video: false }), Err("partial output has no video".to_string()));
+    }
+
+    #[test]
+    fn resume_commands_copy_the_head_and_join_it_with_the_tail() {
+        let commands = resume_commands("/media/it's.mkv", 40.5, "/tmp/resume");
+        assert_eq!(commands.head, strings(&[
+            "-i", "/media/it's.mkv", "-t", "40.500", "-map", "0", "-c", "copy", "-y", "/tmp/resume/resume_head.mkv",
+        ]));
+        assert_eq!(commands.tail_file, "/tmp/resume/resume_tail.mkv");
+        assert_eq!(commands.concat_list, (
+            "/tmp/resume/resume.txt".to_string(),
+            "file '/tmp/resume/resume_head.mkv'\nfile '/tmp/resume/resume_tail.mkv'\n".to_string(),
+        ));
+        assert_eq!(commands.join, strings(&[
+            "-f", "concat", "-safe", "0", "-i", "/tmp/resume/resume.txt", "-map", "0", "-c", "copy", "-y", "/media/it's.mkv",
+        ]));
+
+        let quoted = resume_commands("/media/out.mp4", 10.0, "/tmp/it's");
+        assert_eq!(quoted.concat_list.1, "file '/tmp/it'\\''s/resume_head.mp4'\nfile '/tmp/it'\\''s/resume_tail.mp4'\n");
+    }
+
+    #[test]
+    fn tail_encode_seeks_before_its_input() {
+        let mut args = strings(&["-hide_banner", "-i", "in.mp4", "-c:v", "libx264", "tail.mkv"]);
+        seek_input(&mut args, 40.5);
+        assert_eq!(args, strings(&["-hide_banner", "-ss", "40.500", "-i", "in.mp4", "-c:v", "libx264", "tail.mkv"]));
+    }
+
+    #[test]
+    fn the_partial_is_checked_once_each_time_the_destination_returns() {
+        let supported: Result<(), String> = Ok(());
+        let checks = RefCell::new(0);
+        let check = || {
+            *checks.borrow_mut() += 1;
+            Ok(40.5)
+        };
+        assert_eq!(resume_check(&supported, false, false, check), None);
+        assert_eq!(resume_check(&supported, true, false, check), Some(Ok(40.5)));
+        assert_eq!(resume_check(&supported, true, true, check), None);
+        assert_eq!(resume_check(&supported, false, true, check), None);
+        assert_eq!(*checks.borrow(), 1);
+
+        let unsupported = Err("stream copy cannot resume".to_string());
+        assert_eq!(resume_check(&unsupported, true, false, check), Some(Err("stream copy cannot resume".to_string())));
+        assert_eq!(*checks.borrow(), 1);
+    }
+
+    #[test]
+    fn polls_update_the_status_only_when_something_changed() {
+        let mut status = RecoveryStatus { reachable: false, resume: None };
+        assert!(!apply_poll(&mut status, false, None));
+
+        assert!(apply_poll(&mut status, true, Some(Ok(40.5))));
+        assert_eq!(status, RecoveryStatus { reachable: true, resume: Some(Ok(40.5)) });
+        // Later polls while it stays reachable keep the result
+        assert!(!apply_poll(&mut status, true, None));
+        assert_eq!(status.resume, Some(Ok(40.5)));
+
+        // Gone again: the old result no longer says anything about the partial
+        assert!(apply_poll(&mut status, false, None));
+        assert_eq!(status, RecoveryStatus { reachable: false, resume: None });
+
+        assert!(apply_poll(&mut status, true, Some(Err("too little output to keep".to_string()))));
+        assert_eq!(status.resume, Some(Err("too little output to keep".to_string())));
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Where the audio of a two-input composition comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayAudio {
+    /// The main (first) video's audio only
+    Main,
+    /// Both inputs mixed with amix
+    Mix,
+}
+
+impl OverlayAudio {
+    pub fn all() -> [OverlayAudio; 2] {
+        [OverlayAudio::Main, OverlayAudio::Mix]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            OverlayAudio::Main => "main",
+            OverlayAudio::Mix => "mix",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<OverlayAudio> {
+        OverlayAudio::all().into_iter().find(|audio| audio.key() == key.trim())
+    }
+}
+
+/// Corner or center a picture-in-picture is placed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl PipAnchor {
+    pub fn all() -> [PipAnchor; 5] {
+        [PipAnchor::TopLeft, PipAnchor::TopRight, PipAnchor::BottomLeft, PipAnchor::BottomRight, PipAnchor::Center]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            PipAnchor::TopLeft => "top_left",
+            PipAnchor::TopRight => "top_right",
+            PipAnchor::BottomLeft => "bottom_left",
+            PipAnchor::BottomRight => "bottom_right",
+            PipAnchor::Center => "center",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<PipAnchor> {
+        PipAnchor::all().into_iter().find(|anchor| anchor.key() == key.trim())
+    }
+
+    /// overlay x and y expressions keeping `margin` pixels from the anchored edges
+    fn position(&self, margin: u32) -> (String, String) {
+        let left = margin.to_string();
+        let right = format!("main_w-overlay_w-{}", margin);
+        let top = margin.to_string();
+        let bottom = format!("main_h-overlay_h-{}", margin);
+        match self {
+            PipAnchor::TopLeft => (left, top),
+            PipAnchor::TopRight => (right, top),
+            PipAnchor::BottomLeft => (left, bottom),
+            PipAnchor::BottomRight => (right, bottom),
+            PipAnchor::Center => ("(main_w-overlay_w)/2".to_string(), "(main_h-overlay_h)/2".to_string()),
+        }
+    }
+}
+
+/// Second video laid over the main one at an x/y position, optionally translucent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// overlay x expression, e.g. "10" or "main_w-overlay_w-10"
+    pub x: String,
+    pub y: String,
+    /// 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+    pub audio: OverlayAudio,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self { x: "10".to_string(), y: "10".to_string(), opacity: 1.0, audio: OverlayAudio::Main }
+    }
+}
+
+/// Second video shrunk and placed in a corner or the center of the main one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipSettings {
+    /// Width of the inset as a percentage of the main video's width
+    pub scale_percent: f32,
+    pub anchor: PipAnchor,
+    /// Distance from the anchored edges in pixels
+    pub margin: u32,
+    pub audio: OverlayAudio,
+}
+
+impl Default for PipSettings {
+    fn default() -> Self {
+        Self { scale_percent: 30.0, anchor: PipAnchor::BottomRight, margin: 20, audio: OverlayAudio::Main }
+    }
+}
+
+/// A position or timeline expression as a filter option value; plain numbers stay readable
+pub fn filter_expression(value: &str) -> String {
+    let value = value.trim();
+    if value.chars().any(|c| matches!(c, ',' | ':' | ';' | '[' | ']' | '\'' | '\\' | ' ')) {
+        crate::comprehensive_command_builder::ComprehensiveCommandBuilder::quote_filter_value(value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// A finished second input disappears instead of freezing on its last frame
+fn overlay_filter(x: &str, y: &str) -> String {
+    format!("overlay=x={}:y={}:eof_action=pass", filter_expression(x), filter_expression(y))
+}
+
+impl OverlaySettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.x.trim().is_empty() || self.y.trim().is_empty() {
+            return Err(anyhow!("The overlay position needs both an x and a y expression"));
+        }
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err(anyhow!("Opacity must be between 0 and 1, got {}", self.opacity));
+        }
+        Ok(())
+    }
+
+    /// filter_complex for inputs 0 (main) and 1 (overlay), labelled [v]
+    pub fn video_graph(&self) -> Result<String> {
+        self.validate()?;
+        let overlay = overlay_filter(&self.x, &self.y);
+        Ok(if self.opacity < 1.0 {
+            format!("[1:v]format=rgba,colorchannelmixer=aa={}[top];[0:v][top]{}[v]", self.opacity, overlay)
+        } else {
+            format!("[0:v][1:v]{}[v]", overlay)
+        })
+    }
+}
+
+impl PipSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(1.0..=100.0).contains(&self.scale_percent) {
+            return Err(anyhow!("The picture-in-picture size must be between 1 and 100%, got {}", self.scale_percent));
+        }
+        Ok(())
+    }
+
+    /// filter_complex for inputs 0 (main) and 1 (inset), labelled [v]. With the main video's width
+    /// known the inset gets an exact even width, otherwise it is scaled relative to itself.
+    pub fn video_graph(&self, main_width: Option<u32>) -> Result<String> {
+        self.validate()?;
+        let width = match main_width.filter(|width| *width > 0) {
+            Some(main_width) => (((main_width as f32 * self.scale_percent / 100.0) / 2.0).round() as u32 * 2).max(2).to_string(),
+            None => format!("iw*{}", self.scale_percent / 100.0),
+        };
+        let (x, y) = self.anchor.position(self.margin);
+        Ok(format!("[1:v]scale={}:-2[pip];[0:v][pip]{}[v]", width, overlay_filter(&x, &y)))
+    }
+}
+
+/// -filter_complex and -map arguments for a two-input composition with video graph `video_graph`.
+/// Mixing falls back to whichever input has audio; without any the output is silent.
+pub fn composition_args(video_graph: String, audio: OverlayAudio, main_has_audio: bool, second_has_audio: bool) -> Vec<String> {
+    let mut graph = video_graph;
+    let audio_map = match (audio, main_has_audio, second_has_audio) {
+        (OverlayAudio::Mix, true, true) => {
+            graph.push_str(";[0:a][1:a]amix=inputs=2:duration=first:dropout_transition=0[a]");
+            Some("[a]")
+        }
+        (_, true, _) => Some("0:a"),
+        (OverlayAudio::Mix, false, true) => Some("1:a"),
+        _ => None,
+    };
+    let mut args = vec!["-filter_complex".to_string(), graph, "-map".to_string(), "[v]".to_string()];
+    if let Some(audio_map) = audio_map {
+        args.extend(["-map".to_string(), audio_map.to_string()]);
+    }
+    args
+}
+
+fn show_audio_choice(ui: &mut egui::Ui, audio: &mut OverlayAudio, translations: &Translations) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(translations.overlay_audio());
+        for choice in OverlayAudio::all() {
+            let label = match choice {
+                OverlayAudio::Main => translations.overlay_main(),
+                OverlayAudio::Mix => translations.overlay_mix(),
+            };
+            changed |= ui.radio_value(audio, choice, label).changed();
+        }
+    });
+    changed
+}
+
+/// Position expressions, opacity and audio. Returns true when the settings changed.
+pub fn show_overlay_editor(ui: &mut egui::Ui, settings: &mut OverlaySettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("x:");
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.x).desired_width(140.0)).changed();
+        ui.label("y:");
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.y).desired_width(140.0)).changed();
+    }).response.on_hover_text(translations.overlay_position_hint());
+    ui.horizontal(|ui| {
+        ui.label(translations.overlay_opacity());
+        changed |= ui.add(egui::Slider::new(&mut settings.opacity, 0.0..=1.0)).changed();
+    });
+    changed |= show_audio_choice(ui, &mut settings.audio, translations);
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
+
+/// Inset size, anchor, margin and audio. Returns true when the settings changed.
+pub fn show_pip_editor(ui: &mut egui::Ui, settings: &mut PipSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.overlay_pip_scale());
+        changed |= ui.add(egui::Slider::new(&mut settings.scale_percent, 5.0..=100.0).suffix(" %")).changed();
+    });
+    ui.horizontal_wrapped(|ui| {
+        ui.label(translations.overlay_anchor());
+        for anchor in PipAnchor::all() {
+            let label = match anchor {
+                PipAnchor::TopLeft => translations.overlay_top_left(),
+                PipAnchor::TopRight => translations.overlay_top_right(),
+                PipAnchor::BottomLeft => translations.overlay_bottom_left(),
+                PipAnchor::BottomRight => translations.overlay_bottom_right(),
+                PipAnchor::Center => translations.overlay_center(),
+            };
+            changed |= ui.radio_value(&mut settings.anchor, anchor, label).changed();
+        }
+    });
+    ui.add_enabled_ui(settings.anchor != PipAnchor::Center, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(translations.overlay_margin());
+            changed |= ui.add(egui::DragValue::new(&mut settings.margin).range(0..=500).suffix(" px")).changed();
+        });
+    });
+    changed |= show_audio_choice(ui, &mut settings.audio, translations);
+
+    changed
+}
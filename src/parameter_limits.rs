@@ -0,0 +1,428 @@
+use crate::app_state::{AudioSettings, OperationType, VideoSettings};
+use crate::comprehensive_codec_registry::{ComprehensiveCodecRegistry, EncoderLimits};
+use crate::language::Translations;
+
+/// Settings field a range check applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// VideoSettings::crf
+    Crf,
+    /// VideoSettings::quality, passed as -crf by the simple executors
+    Quality,
+    VideoBitrate,
+    Framerate,
+    /// VideoSettings::width / height (resize and the command builder)
+    Size,
+    /// VideoSettings::resolution (-s in the simple executors)
+    Resolution,
+    AudioBitrate,
+}
+
+/// Nearest valid value for a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClampValue {
+    Number(i32),
+    Text(String),
+    Size(u32, u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterIssue {
+    pub field: Field,
+    pub codec: String,
+    /// Value as entered
+    pub value: String,
+    /// Valid range for display, e.g. "0 – 51"
+    pub valid_range: String,
+    pub clamped: ClampValue,
+}
+
+/// Frame rate names ffmpeg accepts for -r
+const NAMED_FRAME_RATES: &[&str] = &["ntsc", "pal", "qntsc", "qpal", "sntsc", "spal", "film", "ntsc-film"];
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", (value * 1000.0).round() / 1000.0)
+    }
+}
+
+/// Clamped quality when `value` lies outside `range`
+pub fn check_quality(value: i32, range: (i32, i32)) -> Option<i32> {
+    (value < range.0 || value > range.1).then(|| value.clamp(range.0, range.1))
+}
+
+/// Bitrate in kbps; None for "auto" or an empty field. Text that is not a positive bitrate counts as 0.
+pub fn bitrate_kbps(bitrate: &str) -> Option<f64> {
+    let text = bitrate.trim();
+    if text.is_empty() || text.eq_ignore_ascii_case("auto") {
+        return None;
+    }
+    Some(crate::locale_format::parse_bitrate(text).ok()
+        .and_then(|parsed| parsed.trim_end_matches('k').parse::<f64>().ok())
+        .unwrap_or(0.0))
+}
+
+/// Clamped bitrate in kbps when `bitrate` lies outside `range`
+pub fn check_bitrate(bitrate: &str, range: (f64, f64)) -> Option<f64> {
+    let kbps = bitrate_kbps(bitrate)?;
+    (kbps < range.0 || kbps > range.1).then(|| kbps.clamp(range.0, range.1))
+}
+
+/// Frame rate in fps: a number, a fraction ("30000/1001") or a named rate. None for "auto" or empty.
+/// Unparsable text counts as 0.
+pub fn frame_rate(fps: &str) -> Option<f64> {
+    let text = fps.trim();
+    if text.is_empty() || text.eq_ignore_ascii_case("auto") || NAMED_FRAME_RATES.contains(&text.to_lowercase().as_str()) {
+        return None;
+    }
+    let rate = match text.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = crate::locale_format::parse_decimal(numerator).ok();
+            let denominator = crate::locale_format::parse_decimal(denominator).ok().filter(|d| *d > 0.0);
+            numerator.zip(denominator).map(|(n, d)| n / d)
+        }
+        None => crate::locale_format::parse_decimal(text).ok(),
+    };
+    Some(rate.unwrap_or(0.0))
+}
+
+/// Clamped frame rate when `fps` lies outside 1..=max_fps
+pub fn check_frame_rate(fps: &str, max_fps: f64) -> Option<f64> {
+    let rate = frame_rate(fps)?;
+    (rate < 1.0 || rate > max_fps).then(|| rate.clamp(1.0, max_fps))
+}
+
+/// Largest size within the encoder limits keeping the aspect ratio, when `width` x `height` does not fit.
+/// A dimension of 0 is left for the scaler to pick and stays 0.
+pub fn check_size(width: u32, height: u32, limits: &EncoderLimits) -> Option<(u32, u32)> {
+    let mut scale: f64 = 1.0;
+    if width > limits.max_width {
+        scale = scale.min(limits.max_width as f64 / width as f64);
+    }
+    if height > limits.max_height {
+        scale = scale.min(limits.max_height as f64 / height as f64);
+    }
+    let fit = |dimension: u32| -> u32 {
+        if dimension == 0 {
+            return 0;
+        }
+        let scaled = ((dimension as f64 * scale).floor() as u32).max(1);
+        if limits.even_dimensions { (scaled & !1).max(2) } else { scaled }
+    };
+    let clamped = (fit(width), fit(height));
+    (clamped != (width, height)).then_some(clamped)
+}
+
+fn size_range(limits: &EncoderLimits) -> String {
+    if limits.even_dimensions {
+        format!("≤ {}×{}, even", limits.max_width, limits.max_height)
+    } else {
+        format!("≤ {}×{}", limits.max_width, limits.max_height)
+    }
+}
+
+/// Operations that encode video with the video settings
+fn encodes_video(operation: &OperationType) -> bool {
+    matches!(operation,
+        OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize |
+        OperationType::VideoCrop | OperationType::VideoRotate | OperationType::VideoFilter |
+        OperationType::CutAndJoin | OperationType::BatchConvert | OperationType::VideoAudioMerge |
+        OperationType::AddSubtitle | OperationType::AddWatermark | OperationType::FrameRateConvert)
+}
+
+/// Operations that encode audio with the audio settings
+fn encodes_audio(operation: &OperationType) -> bool {
+    matches!(operation,
+        OperationType::AudioConvert | OperationType::AudioCompress | OperationType::AudioResample |
+        OperationType::AudioVolume | OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize |
+        OperationType::ExtractAudio | OperationType::VideoAudioSplit)
+}
+
+/// Out-of-range video encoder parameters
+pub fn validate_video(settings: &VideoSettings) -> Vec<ParameterIssue> {
+    let codec = settings.codec.as_str();
+    if codec == "copy" {
+        return Vec::new();
+    }
+    let limits = ComprehensiveCodecRegistry::get_encoder_limits(codec);
+    let issue = |field: Field, value: String, valid_range: String, clamped: ClampValue| ParameterIssue {
+        field,
+        codec: codec.to_string(),
+        value,
+        valid_range,
+        clamped,
+    };
+    let mut issues = Vec::new();
+
+    if let Some(range) = limits.quality_range {
+        let valid_range = format!("{} – {}", range.0, range.1);
+        if let Some(clamped) = check_quality(settings.crf, range).filter(|_| !settings.two_pass) {
+            issues.push(issue(Field::Crf, settings.crf.to_string(), valid_range.clone(), ClampValue::Number(clamped)));
+        }
+        // 0 leaves the quality to the encoder
+        if let Some(clamped) = check_quality(settings.quality, range).filter(|_| settings.quality != 0) {
+            issues.push(issue(Field::Quality, settings.quality.to_string(), valid_range, ClampValue::Number(clamped)));
+        }
+    }
+    if let Some(kbps) = check_bitrate(&settings.bitrate, limits.bitrate_kbps) {
+        issues.push(issue(Field::VideoBitrate, settings.bitrate.clone(),
+            format!("{} – {} kbps", format_number(limits.bitrate_kbps.0), format_number(limits.bitrate_kbps.1)),
+            ClampValue::Text(format!("{}k", kbps.round() as u64))));
+    }
+    if let Some(rate) = check_frame_rate(&settings.fps, limits.max_fps) {
+        issues.push(issue(Field::Framerate, settings.fps.clone(), format!("1 – {} fps", format_number(limits.max_fps)),
+            ClampValue::Text(format_number(rate))));
+    }
+    let (width, height) = (settings.width.unwrap_or(0), settings.height.unwrap_or(0));
+    if let Some((w, h)) = check_size(width, height, &limits) {
+        issues.push(issue(Field::Size, format!("{}×{}", width, height), size_range(&limits), ClampValue::Size(w, h)));
+    }
+    let (width, height) = settings.resolution;
+    // A zero in either dimension keeps the original resolution
+    if let Some((w, h)) = check_size(width, height, &limits).filter(|_| width > 0 && height > 0) {
+        issues.push(issue(Field::Resolution, format!("{}×{}", width, height), size_range(&limits), ClampValue::Size(w, h)));
+    }
+    issues
+}
+
+/// Out-of-range audio encoder parameters
+pub fn validate_audio(settings: &AudioSettings) -> Vec<ParameterIssue> {
+    if settings.codec == "copy" {
+        return Vec::new();
+    }
+    let limits = ComprehensiveCodecRegistry::get_audio_encoder_limits(&settings.codec);
+    check_bitrate(&settings.bitrate, limits.bitrate_kbps)
+        .map(|kbps| ParameterIssue {
+            field: Field::AudioBitrate,
+            codec: settings.codec.clone(),
+            value: settings.bitrate.clone(),
+            valid_range: format!("{} – {} kbps", format_number(limits.bitrate_kbps.0), format_number(limits.bitrate_kbps.1)),
+            clamped: ClampValue::Text(format!("{}k", kbps.round() as u64)),
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Everything out of range for what `operation` encodes
+pub fn validate(operation: &OperationType, video: &VideoSettings, audio: &AudioSettings) -> Vec<ParameterIssue> {
+    let mut issues = Vec::new();
+    if encodes_video(operation) {
+        issues.extend(validate_video(video));
+    }
+    if encodes_audio(operation) || encodes_video(operation) {
+        issues.extend(validate_audio(audio));
+    }
+    issues
+}
+
+/// Write the clamped value back into the video settings; audio fields are left alone
+pub fn apply_video_clamp(issue: &ParameterIssue, video: &mut VideoSettings) {
+    match (issue.field, &issue.clamped) {
+        (Field::Crf, ClampValue::Number(value)) => video.crf = *value,
+        (Field::Quality, ClampValue::Number(value)) => video.quality = *value,
+        (Field::VideoBitrate, ClampValue::Text(value)) => video.bitrate = value.clone(),
+        (Field::Framerate, ClampValue::Text(value)) => video.fps = value.clone(),
+        (Field::Size, ClampValue::Size(width, height)) => {
+            video.width = (*width > 0).then_some(*width);
+            video.height = (*height > 0).then_some(*height);
+        }
+        (Field::Resolution, ClampValue::Size(width, height)) => video.resolution = (*width, *height),
+        _ => {}
+    }
+}
+
+/// Write the clamped value back into the settings
+pub fn apply_clamp(issue: &ParameterIssue, video: &mut VideoSettings, audio: &mut AudioSettings) {
+    match (issue.field, &issue.clamped) {
+        (Field::AudioBitrate, ClampValue::Text(value)) => audio.bitrate = value.clone(),
+        _ => apply_video_clamp(issue, video),
+    }
+}
+
+pub fn clamped_text(clamped: &ClampValue) -> String {
+    match clamped {
+        ClampValue::Number(value) => value.to_string(),
+        ClampValue::Text(value) => value.clone(),
+        ClampValue::Size(width, height) => format!("{}×{}", width, height),
+    }
+}
+
+/// Issue for `field` in a list from validate_video/validate_audio
+pub fn issue_for(issues: &[ParameterIssue], field: Field) -> Option<&ParameterIssue> {
+    issues.iter().find(|issue| issue.field == field)
+}
+
+/// Red outline with the valid range as tooltip on the widget of an out-of-range field, and a quick-fix
+/// button next to it. Returns true when the clamp button was clicked.
+pub fn show_field_issue(ui: &mut egui::Ui, response: &egui::Response, issue: &ParameterIssue, translations: &Translations) -> bool {
+    ui.painter().rect_stroke(response.rect.expand(1.0), 2.0, egui::Stroke::new(1.5, egui::Color32::from_rgb(230, 70, 70)));
+    let tooltip = translations.parameter_out_of_range(&issue.codec, &issue.valid_range);
+    response.clone().on_hover_text(&tooltip);
+    ui.small_button(format!("{} {}", translations.parameter_limit_clamp(), clamped_text(&issue.clamped)))
+        .on_hover_text(tooltip)
+        .clicked()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(codec: &str) -> VideoSettings {
+        VideoSettings {
+            codec: codec.to_string(),
+            crf: 23,
+            quality: 0,
+            bitrate: "auto".to_string(),
+            fps: "auto".to_string(),
+            width: None,
+            height: None,
+            resolution: (0, 0),
+            two_pass: false,
+            ..Default::default()
+        }
+    }
+
+    fn limits(codec: &str) -> EncoderLimits {
+        ComprehensiveCodecRegistry::get_encoder_limits(codec)
+    }
+
+    #[test]
+    fn range_tables_follow_the_encoder() {
+        assert_eq!(limits("libx264").quality_range, Some((0, 51)));
+        assert_eq!((limits("libx264").max_width, limits("libx264").max_height), (8192, 4320));
+        // UI and preset names map to the registry rows
+        assert_eq!(limits("h264"), limits("libx264"));
+        assert_eq!(limits("vp9"), limits("libvpx-vp9"));
+        assert_eq!(limits("av1"), limits("libaom-av1"));
+        assert_eq!(limits("libvpx").quality_range, Some((4, 63)));
+        assert!(!limits("libvpx-vp9").even_dimensions);
+        assert_eq!(limits("prores_ks").quality_range, None);
+
+        let unknown = limits("mjpeg");
+        assert_eq!(unknown.quality_range, None);
+        assert_eq!((unknown.max_width, unknown.max_fps), (16384, 240.0));
+
+        let audio = |codec| ComprehensiveCodecRegistry::get_audio_encoder_limits(codec).bitrate_kbps;
+        assert_eq!(audio("mp3"), (8.0, 320.0));
+        assert_eq!(audio("opus"), (6.0, 510.0));
+        assert_eq!(audio("pcm_s16le"), (6.0, 1536.0));
+    }
+
+    #[test]
+    fn hardware_encoders_have_their_own_rows() {
+        assert_eq!(limits("h264_nvenc").quality_range, Some((0, 51)));
+        assert_eq!((limits("h264_nvenc").max_width, limits("h264_nvenc").max_height), (4096, 4096));
+        assert_eq!((limits("hevc_nvenc").max_width, limits("hevc_nvenc").max_height), (8192, 8192));
+        assert_eq!(limits("h264_qsv").quality_range, Some((1, 51)));
+        assert_eq!(limits("h264_amf").max_height, 2160);
+        assert_eq!(limits("av1_amf").quality_range, Some((1, 63)));
+        assert_eq!(limits("h264_videotoolbox").quality_range, Some((1, 100)));
+        assert!(["h264_nvenc", "hevc_qsv", "h264_vaapi", "hevc_videotoolbox"].iter().all(|codec| limits(codec).even_dimensions));
+
+        // 4K DCI fits NVENC HEVC but not NVENC H.264
+        let mut settings = video("hevc_nvenc");
+        settings.width = Some(6000);
+        settings.height = Some(3000);
+        assert!(validate_video(&settings).is_empty());
+        settings.codec = "h264_nvenc".to_string();
+        let issues = validate_video(&settings);
+        assert_eq!(issue_for(&issues, Field::Size).unwrap().clamped, ClampValue::Size(4096, 2048));
+        assert_eq!(issues[0].valid_range, "≤ 4096×4096, even");
+
+        // A quality of 0 is out of range for QSV crf but means "encoder default" in the quality field
+        let mut settings = video("h264_qsv");
+        settings.crf = 0;
+        let issues = validate_video(&settings);
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].field, &issues[0].clamped), (Field::Crf, &ClampValue::Number(1)));
+        settings.crf = 20;
+        settings.quality = 80;
+        assert_eq!(validate_video(&settings)[0].clamped, ClampValue::Number(51));
+        settings.codec = "h264_videotoolbox".to_string();
+        assert!(validate_video(&settings).is_empty());
+    }
+
+    #[test]
+    fn quality_bitrate_and_frame_rate_clamping() {
+        assert_eq!(check_quality(52, (0, 51)), Some(51));
+        assert_eq!(check_quality(-1, (0, 51)), Some(0));
+        assert_eq!(check_quality(51, (0, 51)), None);
+
+        assert_eq!(bitrate_kbps("auto"), None);
+        assert_eq!(bitrate_kbps(" "), None);
+        assert_eq!(bitrate_kbps("2,5M"), Some(2500.0));
+        assert_eq!(bitrate_kbps("fast"), Some(0.0));
+        assert_eq!(check_bitrate("512k", (8.0, 320.0)), Some(320.0));
+        assert_eq!(check_bitrate("4", (8.0, 320.0)), Some(8.0));
+        assert_eq!(check_bitrate("320", (8.0, 320.0)), None);
+
+        assert_eq!(frame_rate("30000/1001").map(|rate| (rate * 1000.0).round()), Some(29970.0));
+        assert_eq!(frame_rate("ntsc"), None);
+        assert_eq!(frame_rate("Auto"), None);
+        assert_eq!(frame_rate("25/0"), Some(0.0));
+        assert_eq!(frame_rate("59,94"), Some(59.94));
+        assert_eq!(check_frame_rate("300", 240.0), Some(240.0));
+        assert_eq!(check_frame_rate("0.5", 240.0), Some(1.0));
+        assert_eq!(check_frame_rate("60", 240.0), None);
+    }
+
+    #[test]
+    fn sizes_keep_the_aspect_ratio_and_even_dimensions() {
+        let x264 = limits("libx264");
+        assert_eq!(check_size(1920, 1080, &x264), None);
+        assert_eq!(check_size(16384, 8640, &x264), Some((8192, 4320)));
+        // Both limits apply; the tighter one wins
+        assert_eq!(check_size(10000, 10000, &x264), Some((4320, 4320)));
+        // Odd sizes are rounded down for 4:2:0 encoders only
+        assert_eq!(check_size(1279, 719, &x264), Some((1278, 718)));
+        assert_eq!(check_size(1279, 719, &limits("libvpx-vp9")), None);
+        // A zero dimension is left to the scaler
+        assert_eq!(check_size(10000, 0, &x264), Some((8192, 0)));
+        assert_eq!(check_size(1, 1, &x264), Some((2, 2)));
+    }
+
+    #[test]
+    fn validation_covers_what_the_operation_encodes() {
+        let mut settings = video("libx264");
+        settings.crf = 60;
+        settings.bitrate = "2000000k".to_string();
+        settings.fps = "500".to_string();
+        settings.resolution = (9000, 5000);
+        let mut audio = AudioSettings { codec: "mp3".to_string(), bitrate: "500k".to_string(), ..Default::default() };
+
+        let issues = validate(&OperationType::VideoConvert, &settings, &audio);
+        let fields: Vec<Field> = issues.iter().map(|issue| issue.field).collect();
+        assert_eq!(fields, [Field::Crf, Field::VideoBitrate, Field::Framerate, Field::Resolution, Field::AudioBitrate]);
+        assert_eq!(issue_for(&issues, Field::VideoBitrate).unwrap().valid_range, "16 – 1000000 kbps");
+        assert_eq!(issue_for(&issues, Field::Resolution).unwrap().clamped, ClampValue::Size(7776, 4320));
+        assert_eq!(issue_for(&issues, Field::AudioBitrate).unwrap().clamped, ClampValue::Text("320k".to_string()));
+
+        assert_eq!(validate(&OperationType::AudioConvert, &settings, &audio).len(), 1);
+        assert!(validate(&OperationType::FrameExtract, &settings, &audio).is_empty());
+        // Two-pass encodes ignore the crf, stream copy ignores everything
+        settings.two_pass = true;
+        assert!(issue_for(&validate_video(&settings), Field::Crf).is_none());
+        settings.codec = "copy".to_string();
+        audio.codec = "copy".to_string();
+        assert!(validate(&OperationType::VideoConvert, &settings, &audio).is_empty());
+
+        for issue in &issues {
+            apply_clamp(issue, &mut settings, &mut audio);
+        }
+        assert_eq!((settings.crf, settings.bitrate.as_str(), settings.fps.as_str()), (51, "1000000k", "240"));
+        assert_eq!(settings.resolution, (7776, 4320));
+        assert_eq!(audio.bitrate, "320k");
+    }
+
+    #[test]
+    fn clamping_a_size_writes_back_zero_as_unset() {
+        let mut settings = video("libx264");
+        settings.width = Some(12000);
+        let issue = issue_for(&validate_video(&settings), Field::Size).unwrap().clone();
+        assert_eq!(clamped_text(&issue.clamped), "8192×0");
+        apply_video_clamp(&issue, &mut settings);
+        assert_eq!((settings.width, settings.height), (Some(8192), None));
+    }
+}
@@ -0,0 +1,292 @@
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// How often the monitor thread re-reads the power state
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// No battery found or the platform does not report it; treated like AC
+    Unknown,
+}
+
+/// Power-aware encoding rules, applied while running on battery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PowerPolicy {
+    pub enabled: bool,
+    /// Start ffmpeg below normal priority; a task already running is lowered too
+    pub lower_priority: bool,
+    /// Cap encoder threads at half the cores
+    pub cap_threads: bool,
+    /// Resolve an "auto" video codec to a hardware encoder
+    pub prefer_hardware: bool,
+    /// Start no new task until AC power returns
+    pub pause_queue: bool,
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lower_priority: true,
+            cap_threads: true,
+            prefer_hardware: true,
+            pause_queue: false,
+        }
+    }
+}
+
+/// What the task executor has to honor right now
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerConstraints {
+    pub lower_priority: bool,
+    /// 0 means no cap
+    pub max_threads: usize,
+    pub prefer_hardware: bool,
+    pub pause_queue: bool,
+    /// Tasks set to a hardware encoder use the matching software encoder
+    pub software_only: bool,
+}
+
+impl PowerConstraints {
+    pub fn is_constrained(&self) -> bool {
+        *self != PowerConstraints::default()
+    }
+
+    /// Both sets of limits at once: the stricter thread cap, and software-only wins over preferring hardware
+    pub fn combine(self, other: PowerConstraints) -> PowerConstraints {
+        let max_threads = match (self.max_threads, other.max_threads) {
+            (0, cap) | (cap, 0) => cap,
+            (a, b) => a.min(b),
+        };
+        let software_only = self.software_only || other.software_only;
+        PowerConstraints {
+            lower_priority: self.lower_priority || other.lower_priority,
+            max_threads,
+            prefer_hardware: (self.prefer_hardware || other.prefer_hardware) && !software_only,
+            pause_queue: self.pause_queue || other.pause_queue,
+            software_only,
+        }
+    }
+}
+
+/// Constraints for a power state; only a known battery state constrains anything
+pub fn constraints_for(policy: &PowerPolicy, source: PowerSource, cores: usize) -> PowerConstraints {
+    if !policy.enabled || source != PowerSource::Battery {
+        return PowerConstraints::default();
+    }
+    PowerConstraints {
+        lower_priority: policy.lower_priority,
+        max_threads: if policy.cap_threads { (cores / 2).max(1) } else { 0 },
+        prefer_hardware: policy.prefer_hardware,
+        pause_queue: policy.pause_queue,
+        software_only: false,
+    }
+}
+
+/// Logical cores, used for the thread cap
+pub fn available_cores() -> usize {
+    std::thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1)
+}
+
+/// Poll the power state on a background thread; the receiver gets the first state and then every change
+pub fn start_monitor() -> Receiver<PowerSource> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let source = detect_power_source();
+            if last != Some(source) {
+                if sender.send(source).is_err() {
+                    break;
+                }
+                last = Some(source);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+    receiver
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_power_source() -> PowerSource {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Unknown;
+    };
+    let read = |path: &std::path::Path, name: &str| std::fs::read_to_string(path.join(name)).map(|value| value.trim().to_string()).unwrap_or_default();
+
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_str() {
+            // A connected charger settles it, even with the battery still reporting "Discharging" briefly
+            "Mains" | "USB" if read(&path, "online") == "1" => return PowerSource::Ac,
+            "Battery" if read(&path, "scope") != "Device" => {
+                has_battery = true;
+                if read(&path, "status") == "Discharging" {
+                    return PowerSource::Battery;
+                }
+            }
+            _ => {}
+        }
+    }
+    if has_battery { PowerSource::Ac } else { PowerSource::Unknown }
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect_power_source() -> PowerSource {
+    let Ok(output) = std::process::Command::new("pmset").args(["-g", "batt"]).output() else {
+        return PowerSource::Unknown;
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if text.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_power_source() -> PowerSource {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    // Win32_Battery.BatteryStatus: 1 = discharging, 2 = on AC; desktops have no instance at all
+    let Ok(output) = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", "(Get-CimInstance Win32_Battery).BatteryStatus"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output() else {
+        return PowerSource::Unknown;
+    };
+    match String::from_utf8_lossy(&output.stdout).lines().next().map(str::trim) {
+        Some("1") => PowerSource::Battery,
+        Some("") | None => PowerSource::Unknown,
+        Some(_) => PowerSource::Ac,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn detect_power_source() -> PowerSource {
+    PowerSource::Unknown
+}
+
+/// Lower a running process to below-normal priority. It is not raised again when AC returns,
+/// since unprivileged users cannot raise a niced process back on Unix.
+pub fn lower_process_priority(pid: u32) {
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &format!("(Get-Process -Id {}).PriorityClass = 'BelowNormal'", pid)])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+    };
+    #[cfg(not(target_os = "windows"))]
+    let result = std::process::Command::new("renice")
+        .args(["-n", "10", "-p", &pid.to_string()])
+        .output();
+
+    if let Err(e) = result {
+        log_warn!("Failed to lower priority of process {}: {}", pid, e);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> PowerPolicy {
+        PowerPolicy { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn only_a_known_battery_state_constrains() {
+        for source in [PowerSource::Ac, PowerSource::Unknown] {
+            assert_eq!(constraints_for(&enabled(), source, 8), PowerConstraints::default());
+        }
+        assert_eq!(constraints_for(&PowerPolicy::default(), PowerSource::Battery, 8), PowerConstraints::default());
+
+        let constraints = constraints_for(&enabled(), PowerSource::Battery, 8);
+        assert_eq!(constraints, PowerConstraints {
+            lower_priority: true,
+            max_threads: 4,
+            prefer_hardware: true,
+            pause_queue: false,
+            software_only: false,
+        });
+        assert!(constraints.is_constrained());
+        assert!(!PowerConstraints::default().is_constrained());
+    }
+
+    #[test]
+    fn each_rule_maps_to_its_constraint() {
+        let policy = PowerPolicy { enabled: true, lower_priority: false, cap_threads: false, prefer_hardware: false, pause_queue: true };
+        assert_eq!(constraints_for(&policy, PowerSource::Battery, 8), PowerConstraints { pause_queue: true, ..Default::default() });
+
+        let all_off = PowerPolicy { pause_queue: false, ..policy };
+        let constraints = constraints_for(&all_off, PowerSource::Battery, 8);
+        assert_eq!(constraints, PowerConstraints::default());
+        assert!(!constraints.is_constrained());
+    }
+
+    #[test]
+    fn thread_cap_is_half_the_cores_and_at_least_one() {
+        let cap = |cores| constraints_for(&enabled(), PowerSource::Battery, cores).max_threads;
+        assert_eq!(cap(16), 8);
+        assert_eq!(cap(3), 1);
+        assert_eq!(cap(1), 1);
+        assert_eq!(cap(0), 1);
+    }
+
+    #[test]
+    fn combining_keeps_the_stricter_limits() {
+        let battery = constraints_for(&enabled(), PowerSource::Battery, 8);
+        let software = PowerConstraints { max_threads: 2, software_only: true, ..Default::default() };
+
+        let combined = battery.combine(software);
+        assert_eq!(combined, PowerConstraints {
+            lower_priority: true,
+            max_threads: 2,
+            prefer_hardware: false,
+            pause_queue: false,
+            software_only: true,
+        });
+        assert_eq!(software.combine(battery), combined);
+
+        // No cap on one side leaves the other's cap
+        let uncapped = PowerConstraints { pause_queue: true, ..Default::default() };
+        assert_eq!(battery.combine(uncapped).max_threads, 4);
+        assert!(battery.combine(uncapped).pause_queue);
+        assert_eq!(battery.combine(PowerConstraints::default()), battery);
+    }
+
+    #[test]
+    fn thread_cap_reaches_the_command() {
+        use crate::app_state::VideoSettings;
+        use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+        let build = |max_threads| {
+            let video = VideoSettings { max_threads, ..Default::default() };
+            ComprehensiveCommandBuilder::build_video_conversion_command("in.mov", "out.mp4", &video, None).unwrap()
+        };
+        assert!(build(4).windows(2).any(|pair| pair == ["-threads", "4"]));
+        assert!(!build(0).iter().any(|arg| arg == "-threads"));
+    }
+
+    #[test]
+    fn policy_defaults_to_off_with_every_rule_but_pausing() {
+        let policy: PowerPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(policy, PowerPolicy::default());
+        assert!(!policy.enabled && !policy.pause_queue);
+        assert!(policy.lower_priority && policy.cap_threads && policy.prefer_hardware);
+
+        let json = serde_json::to_string(&enabled()).unwrap();
+        assert_eq!(serde_json::from_str::<PowerPolicy>(&json).unwrap(), enabled());
+    }
+}
@@ -0,0 +1,442 @@
+use eframe::egui;
+
+use crate::app_state::{AudioSettings, VideoSettings};
+use crate::encode_advisor::{bitrate_kbps, codec_family, configured_resolution, source_audio_kbps, source_video_kbps};
+use crate::ffmpeg_worker_simple::MediaInfo;
+use crate::language::Translations;
+use crate::preset_manager::EncodingPreset;
+use crate::size_target::{estimated_size_mb, DEFAULT_AUDIO_KBPS};
+
+/// Estimates without an input are given for this much video
+pub const HOUR_SECS: f64 = 3600.0;
+/// Frame rate and size assumed when neither the preset nor the input fixes them
+const DEFAULT_FPS: f64 = 30.0;
+const DEFAULT_RESOLUTION: (u32, u32) = (1920, 1080);
+/// Bits per pixel per frame of libx264 at CRF 23; other codecs scale it by their efficiency
+const H264_BITS_PER_PIXEL_AT_CRF_23: f64 = 0.07;
+/// Frames per second a software H.264 encoder manages at 1080p with the "medium" preset
+const H264_MEDIUM_FPS_1080P: f64 = 120.0;
+const PIXELS_1080P: f64 = 1920.0 * 1080.0;
+
+/// What the estimates know about the input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SourceFacts {
+    pub duration_secs: f64,
+    pub resolution: Option<(u32, u32)>,
+    pub fps: Option<f64>,
+    pub video_kbps: Option<u64>,
+    pub audio_kbps: Option<u64>,
+    pub has_audio: bool,
+}
+
+impl SourceFacts {
+    pub fn from_media_info(info: &MediaInfo) -> Option<Self> {
+        if !info.duration.is_finite() || info.duration <= 0.0 {
+            return None;
+        }
+        let video = info.video_streams.first();
+        Some(Self {
+            duration_secs: info.duration,
+            resolution: video.map(|video| video.display_size()).filter(|(width, height)| *width > 0 && *height > 0),
+            fps: video.map(|video| video.fps).filter(|fps| *fps > 0.0),
+            video_kbps: source_video_kbps(info),
+            audio_kbps: source_audio_kbps(info),
+            has_audio: !info.audio_streams.is_empty(),
+        })
+    }
+}
+
+/// Stereo 16-bit PCM at 48 kHz
+const PCM_KBPS: f64 = 1536.0;
+
+/// Bitrate per pixel relative to H.264 at the same visual quality
+fn codec_efficiency(codec: &str) -> f64 {
+    match codec_family(codec) {
+        Some("hevc") => 0.6,
+        Some("av1") => 0.5,
+        Some("vp9") => 0.65,
+        Some("vp8") => 1.2,
+        Some("mpeg4") | Some("mpeg2") => 1.6,
+        Some("mjpeg") => 8.0,
+        _ => 1.0,
+    }
+}
+
+/// ProRes has a fixed data rate per profile: 45, 102, 147, 220 and 330 Mbit/s at 1080p29.97
+fn prores_bits_per_pixel(profile: &str) -> f64 {
+    match profile.to_lowercase().as_str() {
+        "proxy" | "0" => 0.72,
+        "lt" | "1" => 1.64,
+        "hq" | "3" => 3.54,
+        "4444" | "4444xq" | "4" | "5" => 5.31,
+        _ => 2.37,
+    }
+}
+
+/// Hardware encoders are named after their API; their speed hardly depends on the preset
+fn is_hardware_encoder(codec: &str) -> bool {
+    ["_nvenc", "_qsv", "_amf", "_videotoolbox", "_vaapi", "_mf"].iter().any(|api| codec.ends_with(api))
+}
+
+/// Encoder speed at 1080p relative to libx264 "medium"
+fn codec_speed(codec: &str) -> f64 {
+    let codec = codec.to_lowercase();
+    if is_hardware_encoder(&codec) {
+        return 3.5;
+    }
+    if codec.contains("prores") {
+        return 2.0;
+    }
+    match codec.as_str() {
+        "libx265" => 0.25,
+        "libsvtav1" => 0.2,
+        "libaom-av1" => 0.02,
+        "librav1e" => 0.08,
+        "libvpx-vp9" => 0.1,
+        "libvpx" => 0.4,
+        "mpeg4" | "libxvid" | "mpeg2video" | "mjpeg" => 3.0,
+        _ => 1.0,
+    }
+}
+
+/// Speed of an x264-style preset relative to "medium"
+fn preset_speed(preset: &str) -> f64 {
+    match preset {
+        "ultrafast" => 4.0,
+        "superfast" => 3.0,
+        "veryfast" => 2.2,
+        "faster" => 1.6,
+        "fast" => 1.3,
+        "slow" => 0.5,
+        "slower" => 0.25,
+        "veryslow" => 0.12,
+        "placebo" => 0.04,
+        _ => 1.0,
+    }
+}
+
+/// Frames per second the encoder is expected to reach for `settings` at `resolution`
+pub fn encode_fps(settings: &VideoSettings, resolution: (u32, u32)) -> f64 {
+    let pixels = (resolution.0 as f64 * resolution.1 as f64).max(1.0);
+    let preset = if is_hardware_encoder(&settings.codec) { 1.0 } else { preset_speed(&settings.preset) };
+    H264_MEDIUM_FPS_1080P * codec_speed(&settings.codec) * preset * PIXELS_1080P / pixels
+}
+
+/// Video bitrate of `settings`: the explicit bitrate, else a CRF estimate from bits per pixel. Each
+/// 6 CRF steps halve the bitrate.
+pub fn video_kbps(settings: &VideoSettings, resolution: (u32, u32), fps: f64) -> f64 {
+    if let Some(kbps) = bitrate_kbps(&settings.bitrate) {
+        return kbps as f64;
+    }
+    let bits_per_pixel = if settings.codec.contains("prores") {
+        prores_bits_per_pixel(&settings.profile)
+    } else {
+        H264_BITS_PER_PIXEL_AT_CRF_23
+            * codec_efficiency(&settings.codec)
+            * 2f64.powf((23 - settings.quality) as f64 / 6.0)
+    };
+    resolution.0 as f64 * resolution.1 as f64 * fps * bits_per_pixel / 1000.0
+}
+
+fn audio_kbps(settings: &AudioSettings, source: Option<&SourceFacts>) -> f64 {
+    if source.is_some_and(|source| !source.has_audio) {
+        return 0.0;
+    }
+    if settings.codec.starts_with("pcm_") {
+        return PCM_KBPS;
+    }
+    let kbps = if settings.copy_audio {
+        source.and_then(|source| source.audio_kbps)
+    } else {
+        bitrate_kbps(&settings.bitrate)
+    };
+    kbps.unwrap_or(DEFAULT_AUDIO_KBPS as u64) as f64
+}
+
+/// One preset in the comparison table
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonRow {
+    pub index: usize,
+    pub name: String,
+    pub codec: String,
+    pub resolution: (u32, u32),
+    pub total_kbps: f64,
+    /// For the input's duration, or per hour of video without an input
+    pub size_mb: f64,
+    pub encode_secs: f64,
+    pub selected: bool,
+}
+
+/// Estimates for each preset against the input, or per hour of 1080p30 video when there is none
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonTable {
+    pub rows: Vec<ComparisonRow>,
+    pub per_hour: bool,
+}
+
+/// Whether the current settings are those of `preset`
+fn is_selected(preset: &EncodingPreset, current: &VideoSettings) -> bool {
+    let video = &preset.video_settings;
+    video.codec == current.codec
+        && video.quality == current.quality
+        && video.preset == current.preset
+        && video.bitrate == current.bitrate
+        && video.resolution == current.resolution
+        && video.fps == current.fps
+}
+
+pub fn build_table(presets: &[EncodingPreset], source: Option<&SourceFacts>, current: &VideoSettings) -> ComparisonTable {
+    let duration = source.map_or(HOUR_SECS, |source| source.duration_secs);
+    let rows = presets.iter().enumerate().map(|(index, preset)| {
+        let video = &preset.video_settings;
+        let resolution = configured_resolution(video)
+            .or(source.and_then(|source| source.resolution))
+            .unwrap_or(DEFAULT_RESOLUTION);
+        let fps = video.fps.parse::<f64>().ok().filter(|fps| *fps > 0.0)
+            .or(source.and_then(|source| source.fps))
+            .unwrap_or(DEFAULT_FPS);
+        let copy = video.copy_video || video.codec == "copy";
+        let video_kbps = match source.and_then(|source| source.video_kbps).filter(|_| copy) {
+            Some(kbps) => kbps as f64,
+            None => video_kbps(video, resolution, fps),
+        };
+        let total_kbps = video_kbps + audio_kbps(&preset.audio_settings, source);
+        let encode_secs = if copy { 0.0 } else { duration * fps / encode_fps(video, resolution) };
+        ComparisonRow {
+            index,
+            name: preset.name.clone(),
+            codec: video.codec.clone(),
+            resolution,
+            total_kbps,
+            size_mb: estimated_size_mb(total_kbps, duration),
+            encode_secs,
+            selected: is_selected(preset, current),
+        }
+    }).collect();
+    ComparisonTable { rows, per_hour: source.is_none() }
+}
+
+fn format_size(size_mb: f64) -> String {
+    if size_mb >= 1024.0 {
+        format!("{:.1} GB", size_mb / 1024.0)
+    } else {
+        format!("{:.0} MB", size_mb.max(1.0))
+    }
+}
+
+/// Table of the presets; returns the index of the preset clicked
+pub fn show_table(ui: &mut egui::Ui, table: &ComparisonTable, translations: &Translations) -> Option<usize> {
+    let mut clicked = None;
+    if table.per_hour {
+        ui.label(translations.preset_comparison_no_input());
+    }
+    egui::Grid::new("preset_comparison_grid").striped(true).spacing([12.0, 4.0]).show(ui, |ui| {
+        ui.strong(translations.preset_comparison_preset());
+        ui.strong(translations.preset_comparison_codec());
+        ui.strong(translations.preset_comparison_resolution());
+        ui.strong(if table.per_hour { translations.preset_comparison_size_per_hour() } else { translations.preset_comparison_size() });
+        ui.strong(if table.per_hour { translations.preset_comparison_time_per_hour() } else { translations.preset_comparison_time() });
+        ui.end_row();
+        for row in &table.rows {
+            let name = if row.selected { egui::RichText::new(format!("▶ {}", row.name)).strong() } else { egui::RichText::new(&row.name) };
+            let response = ui.selectable_label(row.selected, name)
+                .on_hover_text(format!("≈ {:.0} kbps · {}", row.total_kbps, translations.preset_comparison_apply_hint()));
+            if response.clicked() {
+                clicked = Some(row.index);
+            }
+            ui.label(&row.codec);
+            ui.label(format!("{}×{}", row.resolution.0, row.resolution.1));
+            ui.label(format!("≈ {}", format_size(row.size_mb)));
+            ui.label(format!("≈ {}", crate::locale_format::format_duration(row.encode_secs.round(), &translations.language)));
+            ui.end_row();
+        }
+    });
+    ui.separator();
+    ui.label(egui::RichText::new(translations.preset_comparison_formula()).small().weak());
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset_manager::PresetCategory;
+
+    /// Ten minutes of 720p25 with 3 Mbit/s of video and 128 kbit/s AAC; the video stream has no
+    /// bitrate of its own, as in most MKV files, so it comes from the container's
+    fn file_info() -> MediaInfo {
+        serde_json::from_value(serde_json::json!({
+            "filename": "ladder.mkv", "duration": 600.0, "bit_rate": 3_128_000,
+            "video_streams": [{ "index": 0, "codec": "h264", "width": 1280, "height": 720, "fps": 25.0 }],
+            "audio_streams": [{ "index": 1, "codec": "aac", "sample_rate": 48000, "channels": 2, "bit_rate": 128_000 }],
+        })).unwrap()
+    }
+
+    fn preset(name: &str, video: VideoSettings, audio: AudioSettings) -> EncodingPreset {
+        EncodingPreset {
+            name: name.to_string(),
+            description: String::new(),
+            category: PresetCategory::Custom,
+            video_settings: video,
+            audio_settings: audio,
+            recommended_formats: vec![],
+        }
+    }
+
+    fn aac(bitrate: &str) -> AudioSettings {
+        AudioSettings { codec: "aac".to_string(), bitrate: bitrate.to_string(), ..Default::default() }
+    }
+
+    /// Source-size x264, 1080p30 x265, a remux, a fixed-rate NVENC encode and ProRes HQ with PCM
+    fn ladder() -> Vec<EncodingPreset> {
+        vec![
+            preset("x264 CRF 23", VideoSettings { codec: "libx264".into(), preset: "medium".into(), ..Default::default() }, aac("128k")),
+            preset("x265 1080p", VideoSettings {
+                codec: "libx265".into(), quality: 29, preset: "slow".into(), width: Some(1920), height: Some(1080), fps: "30".into(),
+                ..Default::default()
+            }, aac("96k")),
+            preset("Remux", VideoSettings { codec: "copy".into(), copy_video: true, ..Default::default() },
+                AudioSettings { copy_audio: true, ..Default::default() }),
+            preset("NVENC 5M", VideoSettings { codec: "h264_nvenc".into(), bitrate: "5M".into(), preset: "slow".into(), ..Default::default() }, aac("auto")),
+            preset("ProRes HQ", VideoSettings { codec: "prores_ks".into(), profile: "hq".into(), ..Default::default() },
+                AudioSettings { codec: "pcm_s16le".into(), ..Default::default() }),
+        ]
+    }
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= expected.abs() * 1e-9 + 1e-9
+    }
+
+    fn row<'a>(table: &'a ComparisonTable, name: &str) -> &'a ComparisonRow {
+        table.rows.iter().find(|row| row.name == name).unwrap()
+    }
+
+    #[test]
+    fn source_facts_come_from_the_file_info() {
+        let facts = SourceFacts::from_media_info(&file_info()).unwrap();
+        assert_eq!(facts, SourceFacts {
+            duration_secs: 600.0,
+            resolution: Some((1280, 720)),
+            fps: Some(25.0),
+            video_kbps: Some(3000),
+            audio_kbps: Some(128),
+            has_audio: true,
+        });
+
+        let mut broken = file_info();
+        broken.duration = f64::NAN;
+        assert_eq!(SourceFacts::from_media_info(&broken), None);
+        broken.duration = 0.0;
+        assert_eq!(SourceFacts::from_media_info(&broken), None);
+    }
+
+    #[test]
+    fn rows_follow_the_preset_order_with_resolution_and_rate_fallbacks() {
+        let facts = SourceFacts::from_media_info(&file_info()).unwrap();
+        let table = build_table(&ladder(), Some(&facts), &VideoSettings::default());
+        assert!(!table.per_hour);
+        let summary: Vec<(usize, &str, &str, (u32, u32))> = table.rows.iter()
+            .map(|row| (row.index, row.name.as_str(), row.codec.as_str(), row.resolution))
+            .collect();
+        assert_eq!(summary, vec![
+            (0, "x264 CRF 23", "libx264", (1280, 720)),
+            (1, "x265 1080p", "libx265", (1920, 1080)),
+            (2, "Remux", "copy", (1280, 720)),
+            (3, "NVENC 5M", "h264_nvenc", (1280, 720)),
+            (4, "ProRes HQ", "prores_ks", (1280, 720)),
+        ]);
+        assert!(table.rows.iter().all(|row| !row.selected));
+    }
+
+    #[test]
+    fn crf_rows_estimate_from_bits_per_pixel_and_encoder_speed() {
+        let facts = SourceFacts::from_media_info(&file_info()).unwrap();
+        let table = build_table(&ladder(), Some(&facts), &VideoSettings::default());
+
+        // 1280x720 at 25 fps and 0.07 bits per pixel, plus 128k audio
+        let x264 = row(&table, "x264 CRF 23");
+        assert!(close(x264.total_kbps, 1612.8 + 128.0), "{}", x264.total_kbps);
+        assert!(close(x264.size_mb, estimated_size_mb(1740.8, 600.0)));
+        assert!((x264.size_mb - 127.05).abs() < 0.01, "{}", x264.size_mb);
+        // 120 fps at 1080p is 270 fps at 720p: 15000 frames in 55.6 s
+        assert!(close(x264.encode_secs, 15000.0 / 270.0), "{}", x264.encode_secs);
+
+        // x265 at CRF 29 is 0.6 times the H.264 rate, halved by the six CRF steps, at 1080p30
+        let x265 = row(&table, "x265 1080p");
+        assert!(close(x265.total_kbps, 1920.0 * 1080.0 * 30.0 * 0.07 * 0.6 * 0.5 / 1000.0 + 96.0), "{}", x265.total_kbps);
+        // 0.25 of x264's speed and half again for "slow": 15 fps for 18000 frames
+        assert!(close(x265.encode_secs, 1200.0), "{}", x265.encode_secs);
+    }
+
+    #[test]
+    fn copy_fixed_rate_and_intra_rows() {
+        let facts = SourceFacts::from_media_info(&file_info()).unwrap();
+        let table = build_table(&ladder(), Some(&facts), &VideoSettings::default());
+
+        // Remuxing keeps the source's own rates and costs no encode time
+        let remux = row(&table, "Remux");
+        assert!(close(remux.total_kbps, 3128.0));
+        assert_eq!(remux.encode_secs, 0.0);
+
+        // The explicit bitrate wins; hardware speed ignores the preset; "auto" audio is the default rate
+        let nvenc = row(&table, "NVENC 5M");
+        assert!(close(nvenc.total_kbps, 5000.0 + DEFAULT_AUDIO_KBPS as f64));
+        assert!(close(nvenc.encode_secs, 15000.0 / (120.0 * 3.5 * 2.25)), "{}", nvenc.encode_secs);
+
+        let prores = row(&table, "ProRes HQ");
+        assert!(close(prores.total_kbps, 1280.0 * 720.0 * 25.0 * 3.54 / 1000.0 + PCM_KBPS));
+        assert!(close(prores.encode_secs, 15000.0 / 540.0));
+
+        let sizes: Vec<f64> = table.rows.iter().map(|row| row.size_mb).collect();
+        let largest = sizes.iter().cloned().fold(0.0, f64::max);
+        assert_eq!(largest, prores.size_mb);
+        assert!(remux.size_mb > row(&table, "x264 CRF 23").size_mb);
+    }
+
+    #[test]
+    fn current_settings_mark_their_preset() {
+        let presets = ladder();
+        let facts = SourceFacts::from_media_info(&file_info()).unwrap();
+        let current = presets[1].video_settings.clone();
+        let table = build_table(&presets, Some(&facts), &current);
+        let selected: Vec<&str> = table.rows.iter().filter(|row| row.selected).map(|row| row.name.as_str()).collect();
+        assert_eq!(selected, vec!["x265 1080p"]);
+
+        // A changed CRF is no longer that preset
+        let tweaked = VideoSettings { quality: 30, ..current };
+        assert!(build_table(&presets, Some(&facts), &tweaked).rows.iter().all(|row| !row.selected));
+    }
+
+    #[test]
+    fn without_an_input_rows_are_per_hour_of_1080p30() {
+        let table = build_table(&ladder(), None, &VideoSettings::default());
+        assert!(table.per_hour);
+        let x264 = row(&table, "x264 CRF 23");
+        assert_eq!(x264.resolution, DEFAULT_RESOLUTION);
+        assert!(close(x264.total_kbps, 1920.0 * 1080.0 * 30.0 * 0.07 / 1000.0 + 128.0));
+        assert!(close(x264.size_mb, estimated_size_mb(x264.total_kbps, HOUR_SECS)));
+        assert!(close(x264.encode_secs, HOUR_SECS * 30.0 / 120.0));
+        // Nothing to copy from: the remux row falls back to an estimate rather than claiming zero
+        assert!(row(&table, "Remux").total_kbps > 0.0);
+    }
+
+    #[test]
+    fn silent_sources_add_no_audio() {
+        let mut info = file_info();
+        info.audio_streams.clear();
+        info.bit_rate = Some(3_000_000);
+        let facts = SourceFacts::from_media_info(&info).unwrap();
+        assert!(!facts.has_audio);
+        let table = build_table(&ladder(), Some(&facts), &VideoSettings::default());
+        assert!(close(row(&table, "x264 CRF 23").total_kbps, 1612.8));
+        assert!(close(row(&table, "ProRes HQ").total_kbps, 1280.0 * 720.0 * 25.0 * 3.54 / 1000.0));
+        assert!(close(row(&table, "Remux").total_kbps, 3000.0));
+    }
+
+    #[test]
+    fn sizes_are_shown_in_megabytes_up_to_a_gigabyte() {
+        assert_eq!(format_size(0.2), "1 MB");
+        assert_eq!(format_size(127.05), "127 MB");
+        assert_eq!(format_size(1023.4), "1023 MB");
+        assert_eq!(format_size(1536.0), "1.5 GB");
+    }
+}
@@ -21,6 +21,7 @@ pub enum PresetCategory {
     MobileOptimized,
     Archive,
     Streaming,
+    SocialMedia,
     Custom,
 }
 
@@ -69,6 +70,13 @@ impl PresetCategory {
                     "Streaming"
                 }
             },
+            PresetCategory::SocialMedia => {
+                if translations.language == crate::language::Language::Chinese {
+                    "社交媒体"
+                } else {
+                    "Social Media"
+                }
+            },
             PresetCategory::Custom => {
                 if translations.language == crate::language::Language::Chinese {
                     "自定义"
@@ -84,7 +92,7 @@ pub struct PresetManager;
 
 impl PresetManager {
     pub fn get_builtin_presets() -> Vec<EncodingPreset> {
-        vec![
+        let mut presets = vec![
             EncodingPreset {
                 name: "YouTube 1080p".to_string(),
                 description: "Optimized settings for YouTube 1080p uploads".to_string(),
@@ -354,7 +362,10 @@ impl PresetManager {
                 },
                 recommended_formats: vec!["mp4".to_string(), "flv".to_string()],
             },
-        ]
+        ];
+        // Without a source to look at, social presets pad so nothing is cut off
+        presets.extend(crate::social_presets::PRESETS.iter().map(|preset| preset.encoding_preset(FrameFit::Pad)));
+        presets
     }
 
     pub fn get_presets_by_category(category: &PresetCategory) -> Vec<EncodingPreset> {
@@ -372,6 +383,7 @@ impl PresetManager {
             PresetCategory::MobileOptimized,
             PresetCategory::Archive,
             PresetCategory::Streaming,
+            PresetCategory::SocialMedia,
             PresetCategory::Custom,
         ]
     }
@@ -0,0 +1,263 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use anyhow::Result;
+use lazy_static::lazy_static;
+use crate::ffmpeg_worker_simple::{FFmpegWorker, MediaInfo};
+
+/// Number of probed files kept in memory
+const PROBE_CACHE_CAPACITY: usize = 64;
+
+lazy_static! {
+    static ref PROBE_CACHE: Arc<ProbeCache> = Arc::new(ProbeCache::new(PROBE_CACHE_CAPACITY));
+}
+
+/// Shared probe cache used by the UI, compatibility checks and the workflow validator
+pub fn probe_cache() -> Arc<ProbeCache> {
+    PROBE_CACHE.clone()
+}
+
+/// (has_video, has_audio, resolution of the first video stream)
+pub type StreamSummary = (bool, bool, Option<(u32, u32)>);
+
+/// A file is identified by its canonical path plus size and modification time,
+/// so an overwritten file never returns stale results
+#[derive(Debug, Clone, PartialEq)]
+struct ProbeKey {
+    path: PathBuf,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl ProbeKey {
+    fn for_path(path: &str) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(Self {
+            path: canonical_path(path),
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+fn canonical_path(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+/// Small LRU of full ffprobe results
+pub struct ProbeCache {
+    // Most recently used entry at the back
+    entries: Mutex<VecDeque<(ProbeKey, MediaInfo)>>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ProbeCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Full media info, probing with ffprobe only on a cache miss
+    pub fn get_file_info(&self, path: &str) -> Result<MediaInfo> {
+        self.get_or_probe(path, |path| FFmpegWorker::new().get_file_info(path))
+    }
+
+    pub fn detect_streams(&self, path: &str) -> Result<StreamSummary> {
+        let info = self.get_file_info(path)?;
+        let resolution = info.video_streams.first().map(|video| (video.width, video.height));
+        Ok((!info.video_streams.is_empty(), !info.audio_streams.is_empty(), resolution))
+    }
+
+    fn get_or_probe(&self, path: &str, probe: impl FnOnce(&str) -> Result<MediaInfo>) -> Result<MediaInfo> {
+        let key = match ProbeKey::for_path(path) {
+            Ok(key) => key,
+            // Not a local file (or not readable yet) - probe directly without caching
+            Err(_) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return probe(path);
+            }
+        };
+
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(index) = entries.iter().position(|(cached, _)| *cached == key) {
+                if let Some(entry) = entries.remove(index) {
+                    let info = entry.1.clone();
+                    entries.push_back(entry);
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(info);
+                }
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let info = probe(path)?;
+
+        if let Ok(mut entries) = self.entries.lock() {
+            // Drop results for older versions of the same file
+            entries.retain(|(cached, _)| cached.path != key.path);
+            entries.push_back((key, info.clone()));
+            while entries.len() > self.capacity {
+                entries.pop_front();
+            }
+        }
+        Ok(info)
+    }
+
+    /// Forget everything cached for `path`; called when a task writes to it
+    pub fn invalidate(&self, path: &str) {
+        let path = canonical_path(path);
+        if let Ok(mut entries) = self.entries.lock() {
+            let before = entries.len();
+            entries.retain(|(cached, _)| cached.path != path);
+            if entries.len() != before {
+                log_debug!("Probe cache invalidated for {}", path.display());
+            }
+        }
+    }
+
+    /// (hits, misses, cached files)
+    pub fn stats(&self) -> (u64, u64, usize) {
+        let len = self.entries.lock().map(|entries| entries.len()).unwrap_or(0);
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed), len)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    fn info(duration: f64) -> MediaInfo {
+        MediaInfo {
+            filename: String::new(),
+            duration,
+            video_streams: Vec::new(),
+            audio_streams: Vec::new(),
+            format_name: String::new(),
+            programs: Vec::new(),
+            bit_rate: None,
+        }
+    }
+
+    fn file(dir: &TempDir, name: &str, contents: &[u8]) -> String {
+        let path = dir.0.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    /// Probe through the cache, counting how often ffprobe would have run
+    fn probe(cache: &ProbeCache, path: &str, probes: &Cell<u32>, duration: f64) -> f64 {
+        cache.get_or_probe(path, |_| {
+            probes.set(probes.get() + 1);
+            Ok(info(duration))
+        }).unwrap().duration
+    }
+
+    #[test]
+    fn unchanged_files_are_probed_once() {
+        let dir = TempDir::new("probe_hit");
+        let path = file(&dir, "a.mp4", b"aaaa");
+        let cache = ProbeCache::new(4);
+        let probes = Cell::new(0);
+        assert_eq!(probe(&cache, &path, &probes, 1.0), 1.0);
+        assert_eq!(probe(&cache, &path, &probes, 2.0), 1.0);
+        // Another spelling of the same file shares the entry
+        let spelled = dir.0.join(".").join("a.mp4").display().to_string();
+        assert_eq!(probe(&cache, &spelled, &probes, 3.0), 1.0);
+        assert_eq!(probes.get(), 1);
+        assert_eq!(cache.stats(), (2, 1, 1));
+    }
+
+    #[test]
+    fn overwriting_a_file_invalidates_its_entry() {
+        let dir = TempDir::new("probe_overwrite");
+        let path = file(&dir, "a.mp4", b"short");
+        let cache = ProbeCache::new(4);
+        let probes = Cell::new(0);
+        assert_eq!(probe(&cache, &path, &probes, 1.0), 1.0);
+
+        // New size
+        std::fs::write(&path, b"a longer file").unwrap();
+        assert_eq!(probe(&cache, &path, &probes, 2.0), 2.0);
+
+        // Same size, new modification time
+        std::fs::write(&path, b"same length!!").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        drop(file);
+        assert_eq!(probe(&cache, &path, &probes, 3.0), 3.0);
+        assert_eq!(probe(&cache, &path, &probes, 4.0), 3.0);
+
+        assert_eq!(probes.get(), 3);
+        // Results for the older versions were dropped, not kept next to the new one
+        assert_eq!(cache.stats().2, 1);
+    }
+
+    #[test]
+    fn explicit_invalidation() {
+        let dir = TempDir::new("probe_invalidate");
+        let a = file(&dir, "a.mp4", b"a");
+        let b = file(&dir, "b.mp4", b"b");
+        let cache = ProbeCache::new(4);
+        let probes = Cell::new(0);
+        probe(&cache, &a, &probes, 1.0);
+        probe(&cache, &b, &probes, 1.0);
+
+        cache.invalidate(&a);
+        assert_eq!(cache.stats().2, 1);
+        assert_eq!(probe(&cache, &a, &probes, 2.0), 2.0);
+        assert_eq!(probe(&cache, &b, &probes, 2.0), 1.0);
+        assert_eq!(probes.get(), 3);
+
+        // Unknown and missing paths are ignored
+        cache.invalidate(&dir.0.join("missing.mp4").display().to_string());
+        assert_eq!(cache.stats().2, 2);
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let dir = TempDir::new("probe_lru");
+        let paths: Vec<String> = (0..3).map(|i| file(&dir, &format!("{i}.mp4"), b"x")).collect();
+        let cache = ProbeCache::new(2);
+        let probes = Cell::new(0);
+        probe(&cache, &paths[0], &probes, 0.0);
+        probe(&cache, &paths[1], &probes, 1.0);
+        // Touch 0 so 1 is the oldest when 2 comes in
+        probe(&cache, &paths[0], &probes, 9.0);
+        probe(&cache, &paths[2], &probes, 2.0);
+        assert_eq!(probes.get(), 3);
+
+        assert_eq!(probe(&cache, &paths[0], &probes, 9.0), 0.0);
+        assert_eq!(probes.get(), 3);
+        assert_eq!(probe(&cache, &paths[1], &probes, 5.0), 5.0);
+        assert_eq!(probes.get(), 4);
+        assert_eq!(cache.stats().2, 2);
+    }
+
+    #[test]
+    fn missing_files_and_failures_are_not_cached() {
+        let dir = TempDir::new("probe_missing");
+        let cache = ProbeCache::new(4);
+        let probes = Cell::new(0);
+        let missing = dir.0.join("later.mp4").display().to_string();
+        assert_eq!(probe(&cache, &missing, &probes, 1.0), 1.0);
+        assert_eq!(probe(&cache, &missing, &probes, 2.0), 2.0);
+        assert_eq!(cache.stats(), (0, 2, 0));
+
+        let path = file(&dir, "broken.mp4", b"x");
+        assert!(cache.get_or_probe(&path, |_| Err(anyhow::anyhow!("ffprobe failed"))).is_err());
+        assert_eq!(cache.stats().2, 0);
+        assert_eq!(probe(&cache, &path, &probes, 3.0), 3.0);
+    }
+}
@@ -0,0 +1,518 @@
+use std::io::Read;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+use crate::color_conversion::ColorMetadata;
+use crate::ffmpeg_worker_simple::MediaInfo;
+
+/// Seconds of video scanned for keyframes and frame durations
+const DEEP_SCAN_SECONDS: u32 = 60;
+/// Frame durations differing by more than this fraction mark the stream as variable frame rate
+const VFR_TOLERANCE: f64 = 0.05;
+
+/// Probe stages in delivery order; each runs on its own thread and can be cancelled on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeStage {
+    /// Format, size, duration estimate: one quick ffprobe -show_format
+    Container,
+    /// Full stream list via the probe cache
+    Streams,
+    /// Keyframe interval, VFR detection and full color metadata from a packet scan
+    Deep,
+}
+
+impl ProbeStage {
+    pub fn all() -> [ProbeStage; 3] {
+        [ProbeStage::Container, ProbeStage::Streams, ProbeStage::Deep]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContainerInfo {
+    pub format_name: String,
+    pub size_bytes: Option<u64>,
+    /// Container duration; may be an estimate for streams without an index
+    pub duration: Option<f64>,
+    pub bit_rate: Option<u64>,
+    pub chapters: Vec<crate::chapters::Chapter>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeepInfo {
+    /// Average seconds between keyframes in the scanned part
+    pub keyframe_interval: Option<f64>,
+    pub variable_frame_rate: Option<bool>,
+    pub color: Option<ColorMetadata>,
+}
+
+/// File info filled in stage by stage; a None field is unknown, not empty
+#[derive(Debug, Clone, Default)]
+pub struct FileInfo {
+    pub path: String,
+    pub container: Option<ContainerInfo>,
+    pub media: Option<MediaInfo>,
+    pub deep: Option<DeepInfo>,
+    /// Stages that failed with their error
+    pub errors: Vec<(ProbeStage, String)>,
+}
+
+impl FileInfo {
+    /// Stream-level duration once known, the container estimate before that
+    pub fn duration(&self) -> Option<f64> {
+        self.media.as_ref().map(|media| media.duration)
+            .or_else(|| self.container.as_ref().and_then(|container| container.duration))
+            .filter(|duration| *duration > 0.0)
+    }
+
+    pub fn has_video(&self) -> Option<bool> {
+        self.media.as_ref().map(|media| !media.video_streams.is_empty())
+    }
+
+    /// Displayed size of the first video stream, with its rotation applied
+    pub fn resolution(&self) -> Option<(u32, u32)> {
+        self.media.as_ref()
+            .and_then(|media| media.video_streams.first())
+            .map(|video| video.display_size())
+    }
+
+    fn failed(&self, stage: ProbeStage) -> bool {
+        self.errors.iter().any(|(failed, _)| *failed == stage)
+    }
+
+    /// Text for the file info panel; stages still running show as "…"
+    pub fn summary(&self) -> String {
+        let pending = |stage: ProbeStage| if self.failed(stage) { "?".to_string() } else { "…".to_string() };
+        let filename = std::path::Path::new(&self.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.clone());
+
+        let mut lines = vec![format!("File: {}", filename)];
+        match &self.container {
+            Some(container) => {
+                let size = container.size_bytes
+                    .map(|bytes| format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0))
+                    .unwrap_or_else(|| "?".to_string());
+                lines.push(format!("Format: {} ({})", container.format_name, size));
+                if !container.chapters.is_empty() {
+                    lines.push(format!("Chapters: {}", container.chapters.len()));
+                }
+            }
+            None => lines.push(format!("Format: {}", pending(ProbeStage::Container))),
+        }
+        lines.push(match self.duration() {
+            Some(duration) if self.media.is_some() => format!("Duration: {:.2}s", duration),
+            Some(duration) => format!("Duration: ~{:.2}s", duration),
+            None => format!("Duration: {}", pending(ProbeStage::Streams)),
+        });
+
+        match &self.media {
+            Some(media) => {
+                let video_info = if let Some(video) = media.video_streams.first() {
+                    let color = self.deep.as_ref().and_then(|deep| deep.color.clone()).unwrap_or_else(|| ColorMetadata {
+                        range: video.color_range.clone(),
+                        matrix: video.color_space.clone(),
+                        ..Default::default()
+                    });
+                    let alpha = crate::alpha_video::source_alpha_format(media)
+                        .map(|pix_fmt| format!(", alpha: {}", pix_fmt))
+                        .unwrap_or_default();
+                    format!("{} streams (color: {}{})", media.video_streams.len(), color.summary(), alpha)
+                } else {
+                    "None".to_string()
+                };
+                let audio_info = if media.audio_streams.is_empty() {
+                    "None".to_string()
+                } else {
+                    format!("{} streams", media.audio_streams.len())
+                };
+                lines.push(format!("Video: {}", video_info));
+                if let Some(stream) = media.video_streams.first() {
+                    let describe = |rate: Option<crate::frame_rate::FrameRate>| rate.map(crate::frame_rate::describe).unwrap_or_else(|| "?".to_string());
+                    lines.push(format!("Frame rate: {} average | {} real", describe(stream.avg_frame_rate), describe(stream.r_frame_rate)));
+                }
+                lines.push(format!("Audio: {}", audio_info));
+            }
+            None => {
+                lines.push(format!("Video: {}", pending(ProbeStage::Streams)));
+                lines.push(format!("Audio: {}", pending(ProbeStage::Streams)));
+            }
+        }
+
+        // Deep analysis only applies to video
+        if self.has_video() != Some(false) {
+            match &self.deep {
+                Some(deep) => {
+                    let keyframes = deep.keyframe_interval
+                        .map(|interval| format!("{:.2}s", interval))
+                        .unwrap_or_else(|| "?".to_string());
+                    let frame_rate = match deep.variable_frame_rate {
+                        Some(true) => "variable",
+                        Some(false) => "constant",
+                        None => "?",
+                    };
+                    lines.push(format!("Keyframes: every {} | Frame rate: {}", keyframes, frame_rate));
+                }
+                None => lines.push(format!("Keyframes: {}", pending(ProbeStage::Deep))),
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+enum StageResult {
+    Container(ContainerInfo),
+    Streams(Box<MediaInfo>),
+    Deep(DeepInfo),
+    Failed(ProbeStage, String),
+}
+
+/// Running staged probe of one file. Dropping it cancels every stage that has not finished.
+pub struct ProgressiveProbe {
+    pub info: FileInfo,
+    cancel: [Arc<AtomicBool>; 3],
+    receiver: Receiver<StageResult>,
+    finished: [bool; 3],
+}
+
+impl ProgressiveProbe {
+    pub fn start(path: &str, ctx: &egui::Context) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let cancel = [
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        ];
+        for stage in ProbeStage::all() {
+            spawn_stage(stage, path.to_string(), cancel[stage.index()].clone(), sender.clone(), ctx.clone());
+        }
+        Self {
+            info: FileInfo { path: path.to_string(), ..Default::default() },
+            cancel,
+            receiver,
+            finished: [false; 3],
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.info.path
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.iter().all(|finished| *finished)
+    }
+
+    /// Stop one stage; its result is dropped even if the ffprobe run already finished
+    pub fn cancel_stage(&mut self, stage: ProbeStage) {
+        self.cancel[stage.index()].store(true, Ordering::Relaxed);
+        self.finished[stage.index()] = true;
+    }
+
+    pub fn cancel(&mut self) {
+        for stage in ProbeStage::all() {
+            self.cancel_stage(stage);
+        }
+    }
+
+    /// Apply stage results that arrived since the last call; returns the stages that completed
+    pub fn poll(&mut self) -> Vec<ProbeStage> {
+        let mut completed = Vec::new();
+        while let Ok(result) = self.receiver.try_recv() {
+            let stage = match &result {
+                StageResult::Container(_) => ProbeStage::Container,
+                StageResult::Streams(_) => ProbeStage::Streams,
+                StageResult::Deep(_) => ProbeStage::Deep,
+                StageResult::Failed(stage, _) => *stage,
+            };
+            if self.cancel[stage.index()].load(Ordering::Relaxed) {
+                continue;
+            }
+            self.finished[stage.index()] = true;
+            match result {
+                StageResult::Container(container) => self.info.container = Some(container),
+                StageResult::Streams(media) => self.info.media = Some(*media),
+                StageResult::Deep(deep) => self.info.deep = Some(deep),
+                StageResult::Failed(stage, error) => {
+                    log_warn!("{:?} probe of {} failed: {}", stage, self.info.path, error);
+                    self.info.errors.push((stage, error));
+                }
+            }
+            completed.push(stage);
+        }
+        completed
+    }
+}
+
+impl Drop for ProgressiveProbe {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn spawn_stage(stage: ProbeStage, path: String, cancel: Arc<AtomicBool>, sender: Sender<StageResult>, ctx: egui::Context) {
+    std::thread::spawn(move || {
+        let result = match stage {
+            ProbeStage::Container => probe_container(&path, &cancel).map(StageResult::Container),
+            ProbeStage::Streams => crate::probe_cache::probe_cache().get_file_info(&path)
+                .map(|media| StageResult::Streams(Box::new(media))),
+            ProbeStage::Deep => probe_deep(&path, &cancel).map(StageResult::Deep),
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let _ = sender.send(result.unwrap_or_else(|e| StageResult::Failed(stage, e.to_string())));
+        ctx.request_repaint();
+    });
+}
+
+/// Run ffprobe, killing it as soon as `cancel` is set
+fn run_cancellable_ffprobe(args: &[&str], cancel: &AtomicBool) -> Result<Vec<u8>> {
+    let mut child = get_bundled_ffmpeg()?.probe_command()
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to execute FFprobe: {}", e))?;
+
+    // Drain stdout on a separate thread so a full pipe cannot stall the probe
+    let mut stdout = child.stdout.take().ok_or_else(|| anyhow!("FFprobe stdout unavailable"))?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("Cancelled"));
+        }
+        if let Some(status) = child.try_wait()? {
+            let output = reader.join().map_err(|_| anyhow!("FFprobe output reader panicked"))?;
+            if !status.success() {
+                return Err(anyhow!("FFprobe exited with {}", status));
+            }
+            return Ok(output);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+}
+
+fn probe_container(path: &str, cancel: &AtomicBool) -> Result<ContainerInfo> {
+    let output = run_cancellable_ffprobe(&["-v", "error", "-show_format", "-show_chapters", "-of", "json", path], cancel)?;
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    let format = json["format"].as_object().ok_or_else(|| anyhow!("No format information found"))?;
+    // ffprobe prints numbers in the format section as strings
+    let number = |key: &str| format.get(key).and_then(|value| value.as_str()).and_then(|s| s.parse::<f64>().ok());
+    Ok(ContainerInfo {
+        format_name: format.get("format_name").and_then(|f| f.as_str()).unwrap_or("").to_string(),
+        size_bytes: number("size").map(|size| size as u64)
+            .or_else(|| std::fs::metadata(path).ok().map(|metadata| metadata.len())),
+        duration: number("duration"),
+        bit_rate: number("bit_rate").map(|rate| rate as u64).filter(|rate| *rate > 0),
+        chapters: crate::chapters::from_ffprobe_json(&json),
+    })
+}
+
+fn probe_deep(path: &str, cancel: &AtomicBool) -> Result<DeepInfo> {
+    let interval = format!("%+{}", DEEP_SCAN_SECONDS);
+    let output = run_cancellable_ffprobe(&[
+        "-v", "error",
+        "-select_streams", "v:0",
+        "-read_intervals", &interval,
+        "-show_entries", "packet=pts_time,duration_time,flags",
+        "-of", "json",
+        path,
+    ], cancel)?;
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+    let packets = json["packets"].as_array().cloned().unwrap_or_default();
+    let time = |packet: &serde_json::Value, key: &str| packet[key].as_str().and_then(|s| s.parse::<f64>().ok());
+
+    let mut keyframes: Vec<f64> = packets.iter()
+        .filter(|packet| packet["flags"].as_str().is_some_and(|flags| flags.starts_with('K')))
+        .filter_map(|packet| time(packet, "pts_time"))
+        .collect();
+    keyframes.sort_by(|a, b| a.total_cmp(b));
+    let keyframe_interval = match (keyframes.first(), keyframes.last()) {
+        (Some(first), Some(last)) if keyframes.len() > 1 => Some((last - first) / (keyframes.len() - 1) as f64),
+        _ => None,
+    };
+
+    let durations: Vec<f64> = packets.iter()
+        .filter_map(|packet| time(packet, "duration_time"))
+        .filter(|duration| *duration > 0.0)
+        .collect();
+    let variable_frame_rate = match (
+        durations.iter().copied().reduce(f64::min),
+        durations.iter().copied().reduce(f64::max),
+    ) {
+        (Some(shortest), Some(longest)) => Some((longest - shortest) / longest > VFR_TOLERANCE),
+        _ => None,
+    };
+
+    if cancel.load(Ordering::Relaxed) {
+        return Err(anyhow!("Cancelled"));
+    }
+    Ok(DeepInfo {
+        keyframe_interval,
+        variable_frame_rate,
+        color: crate::color_conversion::probe_color_metadata(path),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Probe whose stage results are fed by the test instead of ffprobe threads
+    fn staged(path: &str) -> (ProgressiveProbe, Sender<StageResult>) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let probe = ProgressiveProbe {
+            info: FileInfo { path: path.to_string(), ..Default::default() },
+            cancel: Default::default(),
+            receiver,
+            finished: [false; 3],
+        };
+        (probe, sender)
+    }
+
+    fn media(duration: f64, with_video: bool) -> MediaInfo {
+        let video = if with_video {
+            serde_json::json!([{
+                "index": 0, "codec": "h264", "width": 1920, "height": 1080, "fps": 25.0,
+                "color_range": "tv", "color_space": "bt709", "pix_fmt": "yuv420p", "rotation": -90,
+            }])
+        } else {
+            serde_json::json!([])
+        };
+        serde_json::from_value(serde_json::json!({
+            "filename": "clip.mp4", "duration": duration, "video_streams": video,
+            "audio_streams": [{ "index": 1, "codec": "aac", "sample_rate": 48000, "channels": 2 }],
+        })).unwrap()
+    }
+
+    fn container(duration: Option<f64>) -> ContainerInfo {
+        ContainerInfo {
+            format_name: "mov,mp4,m4a,3gp,3g2,mj2".to_string(),
+            size_bytes: Some(3 * 1024 * 1024),
+            duration,
+            ..Default::default()
+        }
+    }
+
+    fn line<'a>(summary: &'a str, prefix: &str) -> &'a str {
+        summary.lines().find(|line| line.starts_with(prefix)).unwrap_or_else(|| panic!("no {prefix} line in {summary}"))
+    }
+
+    #[test]
+    fn stages_fill_in_as_they_arrive_in_any_order() {
+        let (mut probe, sender) = staged("/media/clips/clip.mp4");
+        assert!(probe.poll().is_empty());
+        let summary = probe.info.summary();
+        assert_eq!(line(&summary, "File:"), "File: clip.mp4");
+        assert_eq!(line(&summary, "Format:"), "Format: …");
+        assert_eq!(line(&summary, "Duration:"), "Duration: …");
+        assert_eq!(line(&summary, "Keyframes:"), "Keyframes: …");
+        assert_eq!(probe.info.has_video(), None);
+
+        // The deep scan can beat the stream probe; it shows up without waiting for the others
+        sender.send(StageResult::Deep(DeepInfo { keyframe_interval: Some(2.0), variable_frame_rate: Some(false), color: None })).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Deep]);
+        assert_eq!(line(&probe.info.summary(), "Keyframes:"), "Keyframes: every 2.00s | Frame rate: constant");
+        assert!(!probe.is_finished());
+
+        // The container duration is shown as an estimate until the streams are known
+        sender.send(StageResult::Container(container(Some(12.5)))).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Container]);
+        let summary = probe.info.summary();
+        assert_eq!(line(&summary, "Format:"), "Format: mov,mp4,m4a,3gp,3g2,mj2 (3.0 MB)");
+        assert_eq!(line(&summary, "Duration:"), "Duration: ~12.50s");
+        assert_eq!(line(&summary, "Video:"), "Video: …");
+
+        sender.send(StageResult::Streams(Box::new(media(12.48, true)))).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Streams]);
+        let summary = probe.info.summary();
+        assert_eq!(line(&summary, "Duration:"), "Duration: 12.48s");
+        assert_eq!(line(&summary, "Video:"), "Video: 1 streams (color: bt709 / limited)");
+        assert_eq!(line(&summary, "Audio:"), "Audio: 1 streams");
+        assert_eq!(probe.info.has_video(), Some(true));
+        assert_eq!(probe.info.resolution(), Some((1080, 1920)));
+        assert!(probe.is_finished());
+        assert!(probe.info.errors.is_empty());
+    }
+
+    #[test]
+    fn several_results_are_applied_in_one_poll() {
+        let (mut probe, sender) = staged("clip.mp4");
+        sender.send(StageResult::Streams(Box::new(media(3.0, true)))).unwrap();
+        sender.send(StageResult::Container(container(None))).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Streams, ProbeStage::Container]);
+        assert!(probe.poll().is_empty());
+        assert!(!probe.is_finished());
+    }
+
+    #[test]
+    fn failed_stages_show_as_unknown() {
+        let (mut probe, sender) = staged("broken.mp4");
+        sender.send(StageResult::Container(container(Some(4.0)))).unwrap();
+        sender.send(StageResult::Failed(ProbeStage::Streams, "Invalid data found".to_string())).unwrap();
+        sender.send(StageResult::Failed(ProbeStage::Deep, "Cancelled".to_string())).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Container, ProbeStage::Streams, ProbeStage::Deep]);
+        assert!(probe.is_finished());
+        assert_eq!(probe.info.errors, [
+            (ProbeStage::Streams, "Invalid data found".to_string()),
+            (ProbeStage::Deep, "Cancelled".to_string()),
+        ]);
+
+        let summary = probe.info.summary();
+        assert_eq!(line(&summary, "Duration:"), "Duration: ~4.00s");
+        assert_eq!(line(&summary, "Video:"), "Video: ?");
+        assert_eq!(line(&summary, "Audio:"), "Audio: ?");
+        assert_eq!(line(&summary, "Keyframes:"), "Keyframes: ?");
+    }
+
+    #[test]
+    fn cancelled_stages_drop_late_results() {
+        let (mut probe, sender) = staged("clip.mp4");
+        probe.cancel_stage(ProbeStage::Deep);
+        sender.send(StageResult::Deep(DeepInfo { keyframe_interval: Some(1.0), ..Default::default() })).unwrap();
+        sender.send(StageResult::Container(container(Some(3.0)))).unwrap();
+        assert_eq!(probe.poll(), [ProbeStage::Container]);
+        assert!(probe.info.deep.is_none());
+        assert!(!probe.is_finished());
+
+        probe.cancel();
+        assert!(probe.is_finished());
+        sender.send(StageResult::Streams(Box::new(media(3.0, true)))).unwrap();
+        assert!(probe.poll().is_empty());
+        assert!(probe.info.media.is_none());
+        assert!(probe.cancel.iter().all(|flag| flag.load(Ordering::Relaxed)));
+    }
+
+    #[test]
+    fn audio_only_files_skip_the_keyframe_line() {
+        let info = FileInfo {
+            path: "song.m4a".to_string(),
+            container: Some(container(Some(0.0))),
+            media: Some(media(0.0, false)),
+            ..Default::default()
+        };
+        let summary = info.summary();
+        assert_eq!(info.has_video(), Some(false));
+        assert_eq!(info.resolution(), None);
+        assert_eq!(line(&summary, "Video:"), "Video: None");
+        // A zero duration is unknown, not empty
+        assert_eq!(info.duration(), None);
+        assert_eq!(line(&summary, "Duration:"), "Duration: …");
+        assert!(!summary.contains("Keyframes"));
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Per-frame values of one metric, by 0-based frame number
+pub type FrameScores = BTreeMap<u64, f64>;
+
+/// Objective quality metrics comparing an encode against its reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityMetric {
+    Psnr,
+    Ssim,
+    /// Needs an ffmpeg built with libvmaf
+    Vmaf,
+}
+
+impl QualityMetric {
+    pub fn all() -> [QualityMetric; 3] {
+        [QualityMetric::Psnr, QualityMetric::Ssim, QualityMetric::Vmaf]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            QualityMetric::Psnr => "psnr",
+            QualityMetric::Ssim => "ssim",
+            QualityMetric::Vmaf => "vmaf",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<QualityMetric> {
+        QualityMetric::all().into_iter().find(|metric| metric.key() == key.trim().to_lowercase())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QualityMetric::Psnr => "PSNR",
+            QualityMetric::Ssim => "SSIM",
+            QualityMetric::Vmaf => "VMAF",
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        match self {
+            QualityMetric::Vmaf => crate::bundled_ffmpeg::has_filter("libvmaf"),
+            _ => true,
+        }
+    }
+
+    /// Filter comparing main input 0 against reference input 1, writing per-frame values to `stats_file`
+    fn filter(&self, stats_file: &str) -> String {
+        let path = crate::font_resolver::filter_path(Path::new(stats_file));
+        match self {
+            QualityMetric::Psnr => format!("psnr=stats_file={}", path),
+            QualityMetric::Ssim => format!("ssim=stats_file={}", path),
+            QualityMetric::Vmaf => format!("libvmaf=log_fmt=csv:log_path={}", path),
+        }
+    }
+
+    /// The average from the summary line the filter logs when it finishes, e.g.
+    /// "[Parsed_psnr_4 @ 0x…] PSNR y:41.2 u:45.0 v:45.3 average:42.1 min:38.0 max:48.9"
+    fn parse_summary(&self, line: &str) -> Option<f64> {
+        let (marker, field) = match self {
+            QualityMetric::Psnr => ("] PSNR ", "average:"),
+            QualityMetric::Ssim => ("] SSIM ", "All:"),
+            QualityMetric::Vmaf => ("VMAF score", ":"),
+        };
+        let rest = &line[line.find(marker)? + marker.len()..];
+        let value = &rest[rest.find(field)? + field.len()..];
+        value.split_whitespace().next()?.parse().ok()
+    }
+
+    /// Value of one line of the metric's stats file, keyed by 0-based frame number
+    fn parse_stats_line(&self, line: &str, vmaf_column: Option<usize>) -> Option<(u64, f64)> {
+        let field = |name: &str| line.split_whitespace()
+            .find_map(|part| part.strip_prefix(name))
+            .and_then(|value| value.parse::<f64>().ok());
+        match self {
+            // "n:1 mse_avg:4.32 mse_y:… psnr_avg:41.77 psnr_y:…"
+            QualityMetric::Psnr => Some(((field("n:")? as u64).saturating_sub(1), field("psnr_avg:")?)),
+            // "n:1 Y:0.991 U:0.995 V:0.995 All:0.992 (21.2)"
+            QualityMetric::Ssim => Some(((field("n:")? as u64).saturating_sub(1), field("All:")?)),
+            // "Frame,integer_adm2,…,vmaf"
+            QualityMetric::Vmaf => {
+                let columns: Vec<&str> = line.split(',').collect();
+                Some((columns.first()?.trim().parse().ok()?, columns.get(vmaf_column?)?.trim().parse().ok()?))
+            }
+        }
+    }
+
+    /// Per-frame values from the metric's stats file
+    fn read_stats(&self, stats_file: &str) -> FrameScores {
+        let Ok(contents) = std::fs::read_to_string(stats_file) else {
+            return FrameScores::new();
+        };
+        let mut lines = contents.lines();
+        let vmaf_column = match self {
+            QualityMetric::Vmaf => lines.next().and_then(|header| header.split(',').position(|column| column.trim() == "vmaf")),
+            _ => None,
+        };
+        lines.filter_map(|line| self.parse_stats_line(line, vmaf_column)).collect()
+    }
+}
+
+/// Metrics and per-frame export of a QualityAnalysis node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualitySettings {
+    pub metrics: Vec<QualityMetric>,
+    /// CSV with one row per frame and a column per metric; empty for none
+    pub csv_path: String,
+}
+
+impl Default for QualitySettings {
+    fn default() -> Self {
+        Self { metrics: vec![QualityMetric::Psnr, QualityMetric::Ssim], csv_path: String::new() }
+    }
+}
+
+impl QualitySettings {
+    /// "psnr,ssim,vmaf" as stored in the node parameter
+    pub fn metrics_param(&self) -> String {
+        self.metrics.iter().map(|metric| metric.key()).collect::<Vec<_>>().join(",")
+    }
+
+    pub fn parse_metrics(value: &str) -> Vec<QualityMetric> {
+        let mut metrics: Vec<QualityMetric> = value.split(',').filter_map(QualityMetric::from_key).collect();
+        metrics.sort();
+        metrics.dedup();
+        metrics
+    }
+}
+
+/// Outcome of one comparison, kept for the node panel and written as the node's report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    pub reference: String,
+    pub distorted: String,
+    pub scores: Vec<(QualityMetric, f64)>,
+    /// Selected metrics this ffmpeg cannot compute
+    pub unavailable: Vec<QualityMetric>,
+    /// Distorted size, reference size: the distorted video was scaled to the reference's size
+    pub scaled: Option<((u32, u32), (u32, u32))>,
+    pub frames: usize,
+    pub csv_path: Option<String>,
+}
+
+impl QualityReport {
+    /// Report lines for the log and the report file
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Reference: {}", self.reference),
+            format!("Distorted: {}", self.distorted),
+        ];
+        if let Some(((width, height), (reference_width, reference_height))) = self.scaled {
+            lines.push(format!(
+                "Distorted video scaled from {}x{} to the reference's {}x{} before comparing",
+                width, height, reference_width, reference_height
+            ));
+        }
+        for (metric, score) in &self.scores {
+            lines.push(format!("{}: {}", metric.name(), format_score(*metric, *score)));
+        }
+        for metric in &self.unavailable {
+            lines.push(format!("{}: not computed, this ffmpeg has no lib{} filter", metric.name(), metric.key()));
+        }
+        lines.push(format!("Frames compared: {}", self.frames));
+        if let Some(csv_path) = &self.csv_path {
+            lines.push(format!("Per-frame scores: {}", csv_path));
+        }
+        lines
+    }
+}
+
+/// PSNR in dB, SSIM as 0–1, VMAF as 0–100
+pub fn format_score(metric: QualityMetric, score: f64) -> String {
+    match metric {
+        _ if score.is_infinite() => "∞ (identical)".to_string(),
+        QualityMetric::Psnr => format!("{:.2} dB", score),
+        QualityMetric::Ssim => format!("{:.4}", score),
+        QualityMetric::Vmaf => format!("{:.2}", score),
+    }
+}
+
+/// Arguments comparing `distorted` (input 0) against `reference` (input 1). Both start at zero;
+/// the distorted video is scaled to `scale_to` first when the sizes differ. Each metric writes its
+/// per-frame values to the file paired with it.
+pub fn comparison_args(distorted: &str, reference: &str, scale_to: Option<(u32, u32)>, stats_files: &[(QualityMetric, String)]) -> Vec<String> {
+    let count = stats_files.len();
+    let branches = |prefix: &str| -> String {
+        if count == 1 {
+            format!("[{}0]", prefix)
+        } else {
+            format!("split={}{}", count, (0..count).map(|index| format!("[{}{}]", prefix, index)).collect::<String>())
+        }
+    };
+    let scale = scale_to.map(|(width, height)| format!("scale={}:{}:flags=bicubic,", width, height)).unwrap_or_default();
+    let separator = |branch: &str| if branch.starts_with('[') { "" } else { "," };
+    let (distorted_branches, reference_branches) = (branches("d"), branches("r"));
+    let mut graph = vec![
+        format!("[0:v]{}setpts=PTS-STARTPTS{}{}", scale, separator(&distorted_branches), distorted_branches),
+        format!("[1:v]setpts=PTS-STARTPTS{}{}", separator(&reference_branches), reference_branches),
+    ];
+    let mut args: Vec<String> = ["-hide_banner", "-nostdin", "-i", distorted, "-i", reference].map(String::from).to_vec();
+    let mut maps = Vec::new();
+    for (index, (metric, stats_file)) in stats_files.iter().enumerate() {
+        graph.push(format!("[d{}][r{}]{}[q{}]", index, index, metric.filter(stats_file), index));
+        maps.extend(["-map".to_string(), format!("[q{}]", index)]);
+    }
+    args.extend(["-filter_complex".to_string(), graph.join(";")]);
+    args.extend(maps);
+    args.extend(["-f", "null", "-"].map(String::from));
+    args
+}
+
+/// Averages from the filters' summary lines in the ffmpeg log, falling back to the mean of the
+/// per-frame values where a summary is missing. Also returns the per-frame values of each metric.
+pub fn collect_scores(log_tail: &[String], stats_files: &[(QualityMetric, String)]) -> (Vec<(QualityMetric, f64)>, Vec<FrameScores>) {
+    let per_frame: Vec<FrameScores> = stats_files.iter().map(|(metric, file)| metric.read_stats(file)).collect();
+    let scores = stats_files.iter().zip(&per_frame)
+        .filter_map(|((metric, _), frames)| {
+            let summary = log_tail.iter().rev().find_map(|line| metric.parse_summary(line));
+            let mean = (!frames.is_empty()).then(|| frames.values().sum::<f64>() / frames.len() as f64);
+            summary.or(mean).map(|score| (*metric, score))
+        })
+        .collect();
+    (scores, per_frame)
+}
+
+/// Write the per-frame values as "frame,psnr,ssim,vmaf" rows; returns the number of rows
+pub fn write_csv(path: &str, metrics: &[QualityMetric], per_frame: &[FrameScores]) -> Result<usize> {
+    let frames: std::collections::BTreeSet<u64> = per_frame.iter().flat_map(|frames| frames.keys().copied()).collect();
+    let mut csv = std::iter::once("frame".to_string())
+        .chain(metrics.iter().map(|metric| metric.key().to_string()))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+    for frame in &frames {
+        let values: Vec<String> = per_frame.iter()
+            .map(|frames| frames.get(frame).map(|value| value.to_string()).unwrap_or_default())
+            .collect();
+        csv.push_str(&format!("{},{}\n", frame, values.join(",")));
+    }
+    if let Some(parent) = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, csv).map_err(|e| anyhow!("Could not write {}: {}", path, e))?;
+    Ok(frames.len())
+}
+
+/// Check the selection before running: at least one metric, and only ones this ffmpeg has
+pub fn split_available(metrics: &[QualityMetric]) -> Result<(Vec<QualityMetric>, Vec<QualityMetric>)> {
+    if metrics.is_empty() {
+        return Err(anyhow!("No metric selected"));
+    }
+    let (available, unavailable): (Vec<QualityMetric>, Vec<QualityMetric>) = metrics.iter().partition(|metric| metric.is_available());
+    if available.is_empty() {
+        return Err(anyhow!("This ffmpeg cannot compute {}", unavailable.iter().map(|metric| metric.name()).collect::<Vec<_>>().join(", ")));
+    }
+    Ok((available, unavailable))
+}
+
+/// Latest report of each QualityAnalysis node this session, by node id
+static LAST_REPORTS: Mutex<BTreeMap<String, QualityReport>> = Mutex::new(BTreeMap::new());
+
+pub fn record_report(node_id: &str, report: QualityReport) {
+    LAST_REPORTS.lock().unwrap().insert(node_id.to_string(), report);
+}
+
+pub fn last_report(node_id: &str) -> Option<QualityReport> {
+    LAST_REPORTS.lock().unwrap().get(node_id).cloned()
+}
+
+/// Metric choice, CSV export and the node's latest scores. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut QualitySettings, last_report: Option<&QualityReport>, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.quality_metrics());
+        for metric in QualityMetric::all() {
+            let mut selected = settings.metrics.contains(&metric);
+            let available = metric.is_available();
+            let response = ui.add_enabled(available, egui::Checkbox::new(&mut selected, metric.name()));
+            if response.changed() {
+                settings.metrics.retain(|other| *other != metric);
+                if selected {
+                    settings.metrics.push(metric);
+                    settings.metrics.sort();
+                }
+                changed = true;
+            }
+            if !available {
+                response.on_disabled_hover_text(translations.quality_vmaf_unavailable());
+            }
+        }
+    });
+    if settings.metrics.is_empty() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", translations.quality_no_metric()));
+    }
+    ui.horizontal(|ui| {
+        ui.label(translations.quality_csv_path());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.csv_path)
+            .hint_text(translations.quality_csv_hint())
+            .desired_width(180.0)).changed();
+        if ui.small_button("📂").clicked() {
+            if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).save_file() {
+                settings.csv_path = path.display().to_string();
+                changed = true;
+            }
+        }
+    });
+    ui.weak(translations.quality_inputs_hint());
+
+    ui.separator();
+    ui.label(egui::RichText::new(translations.quality_last_report()).strong());
+    match last_report {
+        Some(report) => {
+            for line in report.lines().iter().skip(2) {
+                ui.label(line);
+            }
+        }
+        None => {
+            ui.weak(translations.quality_no_report());
+        }
+    }
+
+    changed
+}
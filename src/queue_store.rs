@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use crate::app_state::{ProcessingTask, TaskStatus, UserPreferences};
+
+/// Error message of a task that was running when the application closed
+pub const INTERRUPTED_MESSAGE: &str = "Interrupted: the application closed while this task was running";
+
+/// Unfinished tasks as saved on exit
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct StoredQueue {
+    /// Id the next new task would have got; restored so new ids do not collide with saved ones
+    next_task_id: usize,
+    tasks: Vec<ProcessingTask>,
+}
+
+/// Next to the preferences file in the per-user config directory
+pub fn file_path() -> Option<PathBuf> {
+    Some(UserPreferences::file_path()?.with_file_name("task_queue.json"))
+}
+
+/// Tasks worth offering again: queued ones, and ones cut off while running
+fn is_unfinished(task: &ProcessingTask) -> bool {
+    matches!(task.status, TaskStatus::Pending | TaskStatus::Running | TaskStatus::Paused | TaskStatus::NeedsOutputRecovery)
+}
+
+/// Save the unfinished tasks of `tasks`; without any the file is removed
+pub fn save(tasks: &[ProcessingTask]) {
+    let Some(path) = file_path() else {
+        return;
+    };
+    let unfinished: Vec<ProcessingTask> = tasks.iter().filter(|task| is_unfinished(task)).cloned().collect();
+    if unfinished.is_empty() {
+        discard();
+        return;
+    }
+    let stored = StoredQueue { next_task_id: ProcessingTask::next_id(), tasks: unfinished };
+    let result = path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&stored).map_err(|e| e.to_string()))
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => log_info!("Saved {} unfinished task(s) to {}", stored.tasks.len(), path.display()),
+        Err(e) => log_warn!("Could not save the task queue to {}: {}", path.display(), e),
+    }
+}
+
+/// Tasks saved by the last session, ready to queue again: tasks that were running come back as
+/// Failed with INTERRUPTED_MESSAGE. Reserves the saved ids even when the user declines the restore.
+pub fn load() -> Vec<ProcessingTask> {
+    let Some(json) = file_path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let stored: StoredQueue = match serde_json::from_str(&json) {
+        Ok(stored) => stored,
+        Err(e) => {
+            log_warn!("Ignoring unreadable task queue file: {}", e);
+            return Vec::new();
+        }
+    };
+    let highest_id = stored.tasks.iter().map(|task| task.id).max().unwrap_or(0);
+    ProcessingTask::reserve_ids_below(stored.next_task_id.max(highest_id + 1));
+    stored.tasks.into_iter()
+        .map(|mut task| {
+            if task.status != TaskStatus::Pending {
+                task.status = TaskStatus::Failed;
+                task.error_message = Some(INTERRUPTED_MESSAGE.to_string());
+            }
+            task
+        })
+        .collect()
+}
+
+/// Forget the saved queue, e.g. once the user declined restoring it
+pub fn discard() {
+    if let Some(path) = file_path().filter(|path| path.exists()) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log_warn!("Could not remove {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Software encoders offered for renditions; cached hardware encoders are listed after them
+pub const RENDITION_CODECS: [&str; 4] = ["libx264", "libx265", "libvpx-vp9", "libsvtav1"];
+
+/// Containers renditions can be written to
+pub const RENDITION_CONTAINERS: [&str; 4] = ["mp4", "mkv", "mov", "webm"];
+
+/// One output of a MultiResOutput node: a height and the bitrates to encode it at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rendition {
+    pub height: u32,
+    /// Bitrates as typed, e.g. "5000k" or "2.5M"
+    pub video_bitrate: String,
+    pub audio_bitrate: String,
+}
+
+impl Rendition {
+    pub fn new(height: u32, video_bitrate: &str, audio_bitrate: &str) -> Self {
+        Self { height, video_bitrate: video_bitrate.to_string(), audio_bitrate: audio_bitrate.to_string() }
+    }
+
+    /// "height:video_bitrate:audio_bitrate", as stored in the node parameter
+    fn param(&self) -> String {
+        format!("{}:{}:{}", self.height, self.video_bitrate.trim(), self.audio_bitrate.trim())
+    }
+
+    fn parse(entry: &str) -> Option<Rendition> {
+        let mut fields = entry.split(':').map(str::trim);
+        let height = fields.next()?.trim_end_matches('p').parse().ok()?;
+        let video_bitrate = fields.next().unwrap_or_default();
+        let audio_bitrate = fields.next().unwrap_or_default();
+        Some(Rendition::new(height, video_bitrate, audio_bitrate))
+    }
+}
+
+/// Renditions encoded from one input in a single ffmpeg run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenditionSettings {
+    pub renditions: Vec<Rendition>,
+    /// Encoder for every rendition, software or hardware
+    pub video_codec: String,
+    pub container: String,
+    /// Output files are named <basename>_<height>p.<container>
+    pub basename: String,
+    /// Folder for the outputs; empty uses the workflow's scratch folder
+    pub output_dir: String,
+}
+
+impl Default for RenditionSettings {
+    fn default() -> Self {
+        Self {
+            renditions: vec![
+                Rendition::new(1080, "5000k", "192k"),
+                Rendition::new(720, "2500k", "128k"),
+                Rendition::new(480, "1000k", "96k"),
+            ],
+            video_codec: "libx264".to_string(),
+            container: "mp4".to_string(),
+            basename: "output".to_string(),
+            output_dir: String::new(),
+        }
+    }
+}
+
+/// The renditions node parameter, "1080:5000k:192k;720:2500k:128k"
+pub fn renditions_param(renditions: &[Rendition]) -> String {
+    renditions.iter().map(Rendition::param).collect::<Vec<_>>().join(";")
+}
+
+/// Renditions from the node parameter; malformed entries are dropped
+pub fn parse_renditions(value: &str) -> Vec<Rendition> {
+    value.split(';').filter(|entry| !entry.trim().is_empty()).filter_map(Rendition::parse).collect()
+}
+
+/// Audio encoder for a container: Opus where WebM requires it, AAC elsewhere
+pub fn audio_codec(container: &str) -> &'static str {
+    if container == "webm" { "libopus" } else { "aac" }
+}
+
+/// Bitrate in ffmpeg form ("2500k"), None unless it is a positive bitrate
+fn ffmpeg_bitrate(bitrate: &str) -> Option<String> {
+    crate::locale_format::parse_bitrate(bitrate).ok().filter(|parsed| parsed != "auto")
+}
+
+impl RenditionSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.renditions.is_empty() {
+            return Err(anyhow!("Add at least one rendition"));
+        }
+        if self.video_codec.trim().is_empty() {
+            return Err(anyhow!("No video codec selected"));
+        }
+        let basename = self.basename.trim();
+        if basename.is_empty() || basename.contains(['/', '\\']) {
+            return Err(anyhow!("The base name must be a file name without folders"));
+        }
+        if self.container == "webm"
+            && !matches!(crate::encode_advisor::codec_family(&self.video_codec), Some("vp8" | "vp9" | "av1")) {
+            return Err(anyhow!("WebM holds VP8, VP9 or AV1 video, not {}", self.video_codec));
+        }
+        let mut heights = Vec::new();
+        for rendition in &self.renditions {
+            if rendition.height < 2 || rendition.height % 2 != 0 {
+                return Err(anyhow!("Heights must be even and at least 2, got {}", rendition.height));
+            }
+            if heights.contains(&rendition.height) {
+                return Err(anyhow!("Two renditions are {}p and would write the same file", rendition.height));
+            }
+            heights.push(rendition.height);
+            for bitrate in [&rendition.video_bitrate, &rendition.audio_bitrate] {
+                if ffmpeg_bitrate(bitrate).is_none() {
+                    return Err(anyhow!("{}p: \"{}\" is not a bitrate", rendition.height, bitrate));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the rendition at `height` is written
+    pub fn output_path(&self, output_dir: &str, height: u32) -> PathBuf {
+        Path::new(output_dir).join(format!("{}_{}p.{}", self.basename.trim(), height, self.container))
+    }
+
+    /// Heights above the source's, which can only be reached by upscaling
+    pub fn upscaled(&self, source_height: u32) -> Vec<u32> {
+        self.renditions.iter().map(|rendition| rendition.height).filter(|height| *height > source_height).collect()
+    }
+
+    /// One ffmpeg run decoding the input once: the video is split, each branch scaled to its
+    /// height (width following the aspect ratio) and encoded into its own output file.
+    pub fn args(&self, input_file: &str, output_dir: &str) -> Result<Vec<String>> {
+        self.validate()?;
+        let count = self.renditions.len();
+        let branches: String = (0..count).map(|i| format!("[s{}]", i)).collect();
+        let mut graph = format!("[0:v]split={}{}", count, branches);
+        for (i, rendition) in self.renditions.iter().enumerate() {
+            graph.push_str(&format!(";[s{}]scale=-2:{},format=yuv420p[v{}]", i, rendition.height, i));
+        }
+
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin", "-i", input_file, "-filter_complex", &graph]
+            .map(String::from).to_vec();
+        for (i, rendition) in self.renditions.iter().enumerate() {
+            let video_bitrate = ffmpeg_bitrate(&rendition.video_bitrate).unwrap_or_default();
+            let audio_bitrate = ffmpeg_bitrate(&rendition.audio_bitrate).unwrap_or_default();
+            args.extend([
+                "-map".to_string(), format!("[v{}]", i),
+                "-map".to_string(), "0:a:0?".to_string(),
+                "-c:v".to_string(), self.video_codec.trim().to_string(),
+                "-b:v".to_string(), video_bitrate,
+                "-c:a".to_string(), audio_codec(&self.container).to_string(),
+                "-b:a".to_string(), audio_bitrate,
+            ]);
+            if matches!(self.container.as_str(), "mp4" | "mov") {
+                args.extend(["-movflags".to_string(), "+faststart".to_string()]);
+            }
+            args.extend(["-y".to_string(), self.output_path(output_dir, rendition.height).display().to_string()]);
+        }
+        Ok(args)
+    }
+}
+
+/// Height for a newly added rendition: half the smallest one, rounded to even
+fn next_height(renditions: &[Rendition]) -> u32 {
+    match renditions.iter().map(|rendition| rendition.height).min() {
+        Some(smallest) => ((smallest / 4) * 2).max(2),
+        None => 720,
+    }
+}
+
+/// Rendition rows with add/remove, codec, container, base name and folder. Returns true when the
+/// settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut RenditionSettings, cached_hw_encoders: &[String], translations: &Translations) -> bool {
+    let mut changed = false;
+
+    let mut removed = None;
+    egui::Grid::new("multi_res_renditions").num_columns(4).spacing([8.0, 4.0]).show(ui, |ui| {
+        ui.strong(translations.renditions_height());
+        ui.strong(translations.renditions_video_bitrate());
+        ui.strong(translations.renditions_audio_bitrate());
+        ui.end_row();
+        for (i, rendition) in settings.renditions.iter_mut().enumerate() {
+            changed |= ui.add(egui::DragValue::new(&mut rendition.height).range(2..=4320).speed(2).suffix("p")).changed();
+            changed |= ui.add(egui::TextEdit::singleline(&mut rendition.video_bitrate).desired_width(70.0)).changed();
+            changed |= ui.add(egui::TextEdit::singleline(&mut rendition.audio_bitrate).desired_width(60.0)).changed();
+            if ui.small_button("🗑").on_hover_text(translations.renditions_remove()).clicked() {
+                removed = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = removed {
+        settings.renditions.remove(i);
+        changed = true;
+    }
+    if ui.button(translations.renditions_add()).clicked() {
+        let height = next_height(&settings.renditions);
+        settings.renditions.push(Rendition::new(height, "800k", "96k"));
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(translations.renditions_codec());
+        egui::ComboBox::from_id_salt("multi_res_codec")
+            .selected_text(&settings.video_codec)
+            .show_ui(ui, |ui| {
+                for codec in RENDITION_CODECS {
+                    changed |= ui.selectable_value(&mut settings.video_codec, codec.to_string(), codec).changed();
+                }
+                let hardware: Vec<&String> = cached_hw_encoders.iter()
+                    .filter(|encoder| crate::encode_advisor::codec_family(encoder).is_some_and(|family| matches!(family, "h264" | "hevc" | "av1" | "vp9")))
+                    .collect();
+                if !hardware.is_empty() {
+                    ui.separator();
+                    for encoder in hardware {
+                        changed |= ui.selectable_value(&mut settings.video_codec, encoder.clone(), format!("🚀 {}", encoder)).changed();
+                    }
+                }
+            });
+        ui.label(translations.renditions_container());
+        egui::ComboBox::from_id_salt("multi_res_container")
+            .selected_text(&settings.container)
+            .show_ui(ui, |ui| {
+                for container in RENDITION_CONTAINERS {
+                    changed |= ui.selectable_value(&mut settings.container, container.to_string(), container).changed();
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(translations.renditions_basename());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.basename).desired_width(140.0)).changed();
+        ui.weak(format!("_<height>p.{}", settings.container));
+    });
+    changed |= crate::image_sequence::show_folder_row(ui, translations.frame_extract_output_dir(), &mut settings.output_dir,
+        translations.image_sequence_scratch_dir(), translations);
+
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
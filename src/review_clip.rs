@@ -0,0 +1,277 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{CutSegment, VideoSettings};
+use crate::language::Translations;
+
+/// Default length of a new review clip
+const DEFAULT_CLIP_SECONDS: f64 = 30.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewPreset {
+    P480,
+    #[default]
+    P720,
+}
+
+impl ReviewPreset {
+    pub fn all() -> [ReviewPreset; 2] {
+        [ReviewPreset::P480, ReviewPreset::P720]
+    }
+
+    /// Output height; smaller sources are not upscaled
+    pub fn height(&self) -> u32 {
+        match self {
+            ReviewPreset::P480 => 480,
+            ReviewPreset::P720 => 720,
+        }
+    }
+
+    /// Bitrate cap in kbit/s so a clip stays small enough to send over chat
+    pub fn max_bitrate_kbps(&self) -> u32 {
+        match self {
+            ReviewPreset::P480 => 1000,
+            ReviewPreset::P720 => 2500,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}p (≤ {:.1} Mbit/s)", self.height(), self.max_bitrate_kbps() as f32 / 1000.0)
+    }
+}
+
+/// Review clip options remembered in the preferences; the range is picked per clip
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewClipSettings {
+    pub preset: ReviewPreset,
+    /// Burn the source timecode into the top-left corner
+    pub burn_timecode: bool,
+    pub watermark: bool,
+    pub watermark_text: String,
+}
+
+impl Default for ReviewClipSettings {
+    fn default() -> Self {
+        Self {
+            preset: ReviewPreset::default(),
+            burn_timecode: true,
+            watermark: true,
+            watermark_text: "DRAFT – do not distribute".to_string(),
+        }
+    }
+}
+
+/// Time for a file name: 1m05s, 1h02m05s
+fn file_stamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}h{:02}m{:02}s", hours, minutes, secs)
+    } else {
+        format!("{}m{:02}s", minutes, secs)
+    }
+}
+
+/// `{name}_review_{start}-{end}.mp4` next to `folder`
+pub fn output_path(input: &str, folder: &std::path::Path, start: f64, end: f64) -> String {
+    let stem = std::path::Path::new(input).file_stem().and_then(|stem| stem.to_str()).unwrap_or("output");
+    folder.join(format!("{}_review_{}-{}.mp4", stem, file_stamp(start), file_stamp(end))).display().to_string()
+}
+
+/// Video settings of a review clip task: the range travels as the single cut segment and the
+/// watermark as the drawtext text, so the command builder reuses the trim and drawtext code
+pub fn task_video_settings(settings: &ReviewClipSettings, start: f64, end: f64) -> VideoSettings {
+    VideoSettings {
+        codec: "libx264".to_string(),
+        container_format: "mp4".to_string(),
+        container_options: std::collections::BTreeMap::from([("faststart".to_string(), true)]),
+        cut_segments: vec![CutSegment { start, end }],
+        watermark_text: if settings.watermark { settings.watermark_text.trim().to_string() } else { String::new() },
+        watermark_position: "center".to_string(),
+        watermark_opacity: 0.4,
+        watermark_font_size: settings.preset.height() / 12,
+        review_clip: Some(settings.clone()),
+        ..Default::default()
+    }
+}
+
+pub enum ReviewClipAction {
+    None,
+    Close,
+    /// Queue a clip of (start, end) seconds
+    Queue(f64, f64),
+}
+
+/// Range and options for a review clip of the analyzed input
+pub struct ReviewClipDialog {
+    pub input: String,
+    duration: f64,
+    start: f64,
+    end: f64,
+    pub settings: ReviewClipSettings,
+}
+
+impl ReviewClipDialog {
+    pub fn new(input: &str, duration: f64, settings: &ReviewClipSettings) -> Self {
+        Self {
+            input: input.to_string(),
+            duration,
+            start: 0.0,
+            end: duration.min(DEFAULT_CLIP_SECONDS),
+            settings: settings.clone(),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations) -> ReviewClipAction {
+        let mut action = ReviewClipAction::None;
+        let mut open = true;
+        egui::Window::new(translations.review_clip_title())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let name = std::path::Path::new(&self.input).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                ui.weak(name);
+                ui.add_space(4.0);
+
+                egui::Grid::new("review_clip_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(translations.review_clip_start());
+                    if ui.add(crate::timeline::time_field(&mut self.start, self.duration)).changed() {
+                        self.end = self.end.max(self.start + crate::timeline::MIN_SEGMENT_SECONDS).min(self.duration);
+                    }
+                    ui.end_row();
+
+                    ui.label(translations.review_clip_end());
+                    if ui.add(crate::timeline::time_field(&mut self.end, self.duration)).changed() {
+                        self.start = self.start.min(self.end - crate::timeline::MIN_SEGMENT_SECONDS).max(0.0);
+                    }
+                    ui.end_row();
+
+                    ui.label(translations.review_clip_preset());
+                    egui::ComboBox::from_id_salt("review_clip_preset")
+                        .selected_text(self.settings.preset.label())
+                        .show_ui(ui, |ui| {
+                            for preset in ReviewPreset::all() {
+                                ui.selectable_value(&mut self.settings.preset, preset, preset.label());
+                            }
+                        });
+                    ui.end_row();
+                });
+
+                ui.checkbox(&mut self.settings.burn_timecode, translations.review_clip_timecode());
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.settings.watermark, translations.review_clip_watermark());
+                    ui.add_enabled(self.settings.watermark, egui::TextEdit::singleline(&mut self.settings.watermark_text).desired_width(200.0));
+                });
+
+                ui.add_space(4.0);
+                ui.weak(format!("{} {}", translations.review_clip_length(), file_stamp(self.end - self.start)));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let valid = self.end - self.start >= crate::timeline::MIN_SEGMENT_SECONDS;
+                    if ui.add_enabled(valid, egui::Button::new(translations.review_clip_queue())).clicked() {
+                        action = ReviewClipAction::Queue(self.start, self.end);
+                    }
+                    if ui.button(translations.cancel()).clicked() {
+                        action = ReviewClipAction::Close;
+                    }
+                });
+            });
+        if !open {
+            action = ReviewClipAction::Close;
+        }
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+    fn command(settings: &ReviewClipSettings, start: f64, end: f64, has_audio: bool) -> Vec<String> {
+        let video = task_video_settings(settings, start, end);
+        ComprehensiveCommandBuilder::build_review_clip_command("in.mov", "out.mp4", &video, has_audio).unwrap()
+    }
+
+    fn value_of<'a>(args: &'a [String], flag: &str) -> &'a str {
+        let index = args.iter().position(|arg| arg == flag).unwrap_or_else(|| panic!("no {flag} in {args:?}"));
+        &args[index + 1]
+    }
+
+    #[test]
+    fn trimmed_overlaid_faststart_command() {
+        let args = command(&ReviewClipSettings::default(), 65.5, 95.5, true);
+        // Input seek before -i, then the clip length
+        assert_eq!(args[..6], ["-ss", "65.500", "-i", "in.mov", "-t", "30.000"]);
+
+        let filters = value_of(&args, "-vf");
+        assert!(filters.starts_with("scale=-2:'min(720,ih)',drawtext=text=''\\''%{pts:hms:65.500}'\\''':fontsize=30:"), "{filters}");
+        assert!(filters.contains(",drawtext=text=''\\''DRAFT – do not distribute'\\''':expansion=none:fontsize=60:"), "{filters}");
+        assert!(filters.contains(":x=(w-tw)/2:y=(h-th)/2"), "{filters}");
+        assert!(filters.ends_with(",format=yuv420p"), "{filters}");
+        // The timecode is drawn first, so the watermark lies over it
+        assert!(filters.find("pts:hms").unwrap() < filters.find("DRAFT").unwrap());
+
+        assert_eq!(value_of(&args, "-c:v"), "libx264");
+        assert_eq!(value_of(&args, "-maxrate"), "2500k");
+        assert_eq!(value_of(&args, "-bufsize"), "5000k");
+        assert_eq!(value_of(&args, "-c:a"), "aac");
+        assert_eq!(value_of(&args, "-map_metadata"), "-1");
+        assert_eq!(value_of(&args, "-movflags"), "+faststart");
+        assert_eq!(args[args.len() - 2..], ["-y", "out.mp4"]);
+    }
+
+    #[test]
+    fn overlays_and_audio_are_optional() {
+        let settings = ReviewClipSettings {
+            preset: ReviewPreset::P480,
+            burn_timecode: false,
+            watermark: false,
+            watermark_text: "unused".to_string(),
+        };
+        let args = command(&settings, 0.0, 12.25, false);
+        assert_eq!(args[..6], ["-ss", "0.000", "-i", "in.mov", "-t", "12.250"]);
+        assert_eq!(value_of(&args, "-vf"), "scale=-2:'min(480,ih)',format=yuv420p");
+        assert_eq!(value_of(&args, "-maxrate"), "1000k");
+        assert!(args.iter().any(|arg| arg == "-an"));
+        assert!(!args.iter().any(|arg| arg == "-c:a"));
+        assert_eq!(value_of(&args, "-movflags"), "+faststart");
+
+        // A blank watermark text draws nothing either
+        let blank = ReviewClipSettings { watermark: true, watermark_text: "  ".to_string(), ..settings };
+        assert_eq!(value_of(&command(&blank, 0.0, 5.0, false), "-vf"), "scale=-2:'min(480,ih)',format=yuv420p");
+    }
+
+    #[test]
+    fn missing_ranges_and_settings_are_rejected() {
+        let mut video = task_video_settings(&ReviewClipSettings::default(), 10.0, 20.0);
+        video.cut_segments.clear();
+        assert!(ComprehensiveCommandBuilder::build_review_clip_command("in.mov", "out.mp4", &video, true).is_err());
+        let mut video = task_video_settings(&ReviewClipSettings::default(), 10.0, 20.0);
+        video.review_clip = None;
+        assert!(ComprehensiveCommandBuilder::build_review_clip_command("in.mov", "out.mp4", &video, true).is_err());
+    }
+
+    #[test]
+    fn output_names_carry_the_range() {
+        let folder = std::path::Path::new("/exports");
+        assert_eq!(output_path("/media/take 3.mov", folder, 65.9, 95.5), folder.join("take 3_review_1m05s-1m35s.mp4").display().to_string());
+        assert_eq!(output_path("clip.mkv", folder, 3725.0, 3730.0), folder.join("clip_review_1h02m05s-1h02m10s.mp4").display().to_string());
+    }
+
+    #[test]
+    fn presets_and_settings() {
+        assert_eq!(ReviewPreset::P720.label(), "720p (≤ 2.5 Mbit/s)");
+        assert_eq!(ReviewPreset::P480.label(), "480p (≤ 1.0 Mbit/s)");
+
+        let video = task_video_settings(&ReviewClipSettings::default(), 1.0, 2.0);
+        assert_eq!(video.watermark_font_size, 60);
+        assert_eq!(video.container_options.get("faststart"), Some(&true));
+        assert_eq!(video.review_clip, Some(ReviewClipSettings::default()));
+
+        let restored: ReviewClipSettings = serde_json::from_str(r#"{"preset":"P480"}"#).unwrap();
+        assert_eq!(restored, ReviewClipSettings { preset: ReviewPreset::P480, ..Default::default() });
+    }
+}
@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+thread_local! {
+    /// Token of the workflow run executing on this thread; a run stays on its thread start to finish
+    static CURRENT_TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    /// ffmpeg processes the run is waiting on
+    pids: Mutex<Vec<u32>>,
+}
+
+/// Stop request for a workflow run, shared between the UI and the run's thread. Processes the run
+/// starts are registered with it, so a stop ends the running ffmpeg instead of waiting for it.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+impl CancelToken {
+    fn pids(&self) -> std::sync::MutexGuard<'_, Vec<u32>> {
+        self.0.pids.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Stop the run: kill its processes; the run notices the flag before starting another node
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        for pid in self.pids().iter() {
+            crate::task_executor::kill_process(*pid);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Keeps a process registered with its run's token until dropped
+pub struct ProcessWatch {
+    token: CancelToken,
+    pid: u32,
+}
+
+impl Drop for ProcessWatch {
+    fn drop(&mut self) {
+        self.token.pids().retain(|pid| *pid != self.pid);
+    }
+}
+
+/// Run `run` with the processes it starts on this thread stopped by `token`
+pub fn with_token<T>(token: &CancelToken, run: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_TOKEN.replace(Some(token.clone()));
+    let result = run();
+    CURRENT_TOKEN.set(previous);
+    result
+}
+
+/// Register a process just started on this thread with the run's token, if a run is executing
+/// here. A run stopped in the meantime kills it right away.
+pub fn watch_process(pid: u32) -> Option<ProcessWatch> {
+    let token = CURRENT_TOKEN.with(|current| current.borrow().clone())?;
+    token.pids().push(pid);
+    if token.is_cancelled() {
+        crate::task_executor::kill_process(pid);
+    }
+    Some(ProcessWatch { token, pid })
+}
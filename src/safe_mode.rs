@@ -0,0 +1,284 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use eframe::egui;
+
+use crate::language::Translations;
+
+/// Command line flag that starts the app in safe mode
+pub const CLI_FLAG: &str = "--safe-mode";
+
+/// Startup work safe mode skips, each behind one guarded init function
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subsystem {
+    /// Background probe of GPUs and hardware encoders; without it only software encoders are used
+    HardwareDetection,
+    /// Unpacking the bundled ffmpeg before the window opens; otherwise done on first use
+    FfmpegExtraction,
+    /// Reading and writing the preferences file; defaults are used and nothing is saved
+    Preferences,
+}
+
+impl Subsystem {
+    pub fn all() -> [Subsystem; 3] {
+        [Subsystem::HardwareDetection, Subsystem::FfmpegExtraction, Subsystem::Preferences]
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Subsystem::HardwareDetection => "hardware_detection",
+            Subsystem::FfmpegExtraction => "ffmpeg_extraction",
+            Subsystem::Preferences => "preferences",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            Subsystem::HardwareDetection => 1,
+            Subsystem::FfmpegExtraction => 2,
+            Subsystem::Preferences => 4,
+        }
+    }
+}
+
+/// How safe mode was requested
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+    CliFlag,
+    ShiftHeld,
+}
+
+/// Which subsystems are skipped and why; the app keeps one for the whole process
+pub struct SafeMode {
+    /// Subsystems currently skipped, as Subsystem bits
+    skipped: AtomicU8,
+    trigger: Mutex<Option<Trigger>>,
+}
+
+impl SafeMode {
+    pub const fn new() -> Self {
+        Self { skipped: AtomicU8::new(0), trigger: Mutex::new(None) }
+    }
+
+    /// Skip every subsystem
+    pub fn activate(&self, trigger: Trigger) {
+        *self.trigger.lock().unwrap() = Some(trigger);
+        self.skipped.store(Subsystem::all().iter().fold(0, |bits, subsystem| bits | subsystem.bit()), Ordering::Relaxed);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.trigger.lock().unwrap().is_some()
+    }
+
+    pub fn is_skipped(&self, subsystem: Subsystem) -> bool {
+        self.skipped.load(Ordering::Relaxed) & subsystem.bit() != 0
+    }
+
+    /// Stop skipping `subsystem`; the caller runs its init right after
+    pub fn re_enable(&self, subsystem: Subsystem) {
+        self.skipped.fetch_and(!subsystem.bit(), Ordering::Relaxed);
+        log_info!("Safe mode: {} re-enabled", subsystem.key());
+    }
+
+    /// Run `init` unless `subsystem` is skipped; None when skipped
+    pub fn guarded<T>(&self, subsystem: Subsystem, init: impl FnOnce() -> T) -> Option<T> {
+        if self.is_skipped(subsystem) {
+            log_info!("Safe mode: skipping {}", subsystem.key());
+            return None;
+        }
+        Some(init())
+    }
+
+    /// One line for environment snapshots and diagnostic bundles; None outside safe mode
+    pub fn describe(&self) -> Option<String> {
+        let trigger = (*self.trigger.lock().unwrap())?;
+        let skipped: Vec<&str> = Subsystem::all().into_iter()
+            .filter(|subsystem| self.is_skipped(*subsystem))
+            .map(Subsystem::key)
+            .collect();
+        let trigger = match trigger {
+            Trigger::CliFlag => CLI_FLAG,
+            Trigger::ShiftHeld => "Shift held at launch",
+        };
+        Some(if skipped.is_empty() {
+            format!("on ({}), everything re-enabled", trigger)
+        } else {
+            format!("on ({}), skipping {}", trigger, skipped.join(", "))
+        })
+    }
+}
+
+static STATE: SafeMode = SafeMode::new();
+
+/// Safe mode trigger from the command line arguments (without the program name) and the state
+/// of the Shift key at launch
+pub fn requested<I: IntoIterator<Item = String>>(args: I, shift_held: bool) -> Option<Trigger> {
+    if args.into_iter().any(|arg| arg == CLI_FLAG) {
+        Some(Trigger::CliFlag)
+    } else if shift_held {
+        Some(Trigger::ShiftHeld)
+    } else {
+        None
+    }
+}
+
+/// Skip every subsystem and log verbosely; called once at startup before anything is initialized
+pub fn activate(trigger: Trigger) {
+    STATE.activate(trigger);
+    crate::logging::set_verbose(true);
+    log_warn!("Safe mode ({:?}): hardware detection, early ffmpeg extraction and preferences are disabled", trigger);
+}
+
+pub fn is_active() -> bool {
+    STATE.is_active()
+}
+
+pub fn is_skipped(subsystem: Subsystem) -> bool {
+    STATE.is_skipped(subsystem)
+}
+
+pub fn re_enable(subsystem: Subsystem) {
+    STATE.re_enable(subsystem)
+}
+
+pub fn guarded<T>(subsystem: Subsystem, init: impl FnOnce() -> T) -> Option<T> {
+    STATE.guarded(subsystem, init)
+}
+
+pub fn describe() -> Option<String> {
+    STATE.describe()
+}
+
+/// Whether Shift is down while the app starts, read before any window exists
+#[cfg(target_os = "windows")]
+pub fn shift_held_at_launch() -> bool {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetAsyncKeyState(key: i32) -> i16;
+    }
+    const VK_SHIFT: i32 = 0x10;
+    // The high bit is set while the key is down
+    unsafe { GetAsyncKeyState(VK_SHIFT) < 0 }
+}
+
+#[cfg(target_os = "macos")]
+pub fn shift_held_at_launch() -> bool {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    }
+    const COMBINED_SESSION_STATE: i32 = 0;
+    const SHIFT_MASK: u64 = 0x0002_0000;
+    unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) & SHIFT_MASK != 0 }
+}
+
+/// Keyboard state is not readable without a window on X11 and Wayland; --safe-mode works everywhere
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn shift_held_at_launch() -> bool {
+    false
+}
+
+/// Banner under the menu bar while safe mode is on, with a button per skipped subsystem.
+/// Returns the subsystem the user re-enabled.
+pub fn show_banner(ctx: &egui::Context, translations: &Translations) -> Option<Subsystem> {
+    if !is_active() {
+        return None;
+    }
+    let mut re_enabled = None;
+    egui::TopBottomPanel::top("safe_mode_banner").show(ctx, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(255, 180, 0), translations.safe_mode_banner());
+            ui.label(translations.safe_mode_explanation());
+            for subsystem in Subsystem::all().into_iter().filter(|subsystem| is_skipped(*subsystem)) {
+                let label = match subsystem {
+                    Subsystem::HardwareDetection => translations.safe_mode_hardware_detection(),
+                    Subsystem::FfmpegExtraction => translations.safe_mode_ffmpeg_extraction(),
+                    Subsystem::Preferences => translations.safe_mode_preferences(),
+                };
+                if ui.button(label).clicked() {
+                    re_enabled = Some(subsystem);
+                }
+            }
+        });
+    });
+    re_enabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flag_or_shift_requests_safe_mode() {
+        assert_eq!(requested(args(&[]), false), None);
+        assert_eq!(requested(args(&["--verbose", "clip.mp4"]), false), None);
+        assert_eq!(requested(args(&["clip.mp4", CLI_FLAG]), false), Some(Trigger::CliFlag));
+        // The flag wins, so the snapshot names the reproducible trigger
+        assert_eq!(requested(args(&[CLI_FLAG]), true), Some(Trigger::CliFlag));
+        assert_eq!(requested(args(&["clip.mp4"]), true), Some(Trigger::ShiftHeld));
+        // Exact match only
+        assert_eq!(requested(args(&["--safe-mode=1", "--safe"]), false), None);
+    }
+
+    #[test]
+    fn inactive_safe_mode_runs_every_init() {
+        let state = SafeMode::new();
+        assert!(!state.is_active());
+        assert_eq!(state.describe(), None);
+        for subsystem in Subsystem::all() {
+            assert!(!state.is_skipped(subsystem));
+            assert_eq!(state.guarded(subsystem, || subsystem.key()), Some(subsystem.key()));
+        }
+    }
+
+    #[test]
+    fn activation_skips_every_init_until_re_enabled() {
+        let state = SafeMode::new();
+        state.activate(Trigger::CliFlag);
+        assert!(state.is_active());
+        let mut ran = Vec::new();
+        for subsystem in Subsystem::all() {
+            assert!(state.is_skipped(subsystem));
+            assert_eq!(state.guarded(subsystem, || ran.push(subsystem)), None);
+        }
+        assert!(ran.is_empty());
+        assert_eq!(state.describe().as_deref(), Some("on (--safe-mode), skipping hardware_detection, ffmpeg_extraction, preferences"));
+
+        state.re_enable(Subsystem::FfmpegExtraction);
+        assert!(!state.is_skipped(Subsystem::FfmpegExtraction));
+        assert!(state.is_skipped(Subsystem::HardwareDetection) && state.is_skipped(Subsystem::Preferences));
+        assert_eq!(state.guarded(Subsystem::FfmpegExtraction, || 7), Some(7));
+        assert_eq!(state.describe().as_deref(), Some("on (--safe-mode), skipping hardware_detection, preferences"));
+
+        // Re-enabling twice is harmless; once all are back safe mode is still reported as on
+        state.re_enable(Subsystem::FfmpegExtraction);
+        state.re_enable(Subsystem::HardwareDetection);
+        state.re_enable(Subsystem::Preferences);
+        assert!(state.is_active());
+        assert_eq!(state.describe().as_deref(), Some("on (--safe-mode), everything re-enabled"));
+    }
+
+    #[test]
+    fn shift_trigger_is_described_in_words() {
+        let state = SafeMode::new();
+        state.activate(Trigger::ShiftHeld);
+        state.re_enable(Subsystem::Preferences);
+        assert_eq!(state.describe().as_deref(), Some("on (Shift held at launch), skipping hardware_detection, ffmpeg_extraction"));
+    }
+
+    #[test]
+    fn subsystem_bits_and_keys_are_distinct() {
+        let all = Subsystem::all();
+        let bits = all.iter().fold(0u8, |bits, subsystem| {
+            assert_eq!(bits & subsystem.bit(), 0, "{:?} shares a bit", subsystem);
+            bits | subsystem.bit()
+        });
+        assert_eq!(bits.count_ones() as usize, all.len());
+        let keys: std::collections::BTreeSet<&str> = all.iter().map(|subsystem| subsystem.key()).collect();
+        assert_eq!(keys.len(), all.len());
+    }
+}
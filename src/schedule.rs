@@ -0,0 +1,288 @@
+use chrono::{Datelike, NaiveDateTime, Timelike, Weekday};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::language::Translations;
+use crate::power::PowerConstraints;
+
+/// How often the active profile is re-evaluated
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Length of the "ignore schedule" override from the bottom panel
+pub const OVERRIDE_DURATION: chrono::Duration = chrono::Duration::hours(2);
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Throttling limits applied during a weekly time window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleProfile {
+    pub name: String,
+    /// Monday first; a window past midnight belongs to the day it starts on
+    pub days: [bool; 7],
+    /// Minutes after local midnight; `end` before `start` spans midnight, equal means all day
+    pub start: u16,
+    pub end: u16,
+    /// Encoder thread cap, 0 means no cap
+    pub max_threads: usize,
+    pub lower_priority: bool,
+    /// Off: tasks set to a hardware encoder use the software encoder instead
+    pub allow_hardware: bool,
+    /// Hold the queue after the running task finishes
+    pub pause_queue: bool,
+}
+
+impl Default for ScheduleProfile {
+    fn default() -> Self {
+        Self {
+            name: "Office hours".to_string(),
+            days: [true, true, true, true, true, false, false],
+            start: 9 * 60,
+            end: 18 * 60,
+            max_threads: 2,
+            lower_priority: true,
+            allow_hardware: true,
+            pause_queue: false,
+        }
+    }
+}
+
+impl ScheduleProfile {
+    fn runs_on(&self, day: Weekday) -> bool {
+        self.days[day.num_days_from_monday() as usize]
+    }
+
+    /// Whether wall-clock time `now` falls in the window
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        let minute = (now.hour() * 60 + now.minute()) as u16;
+        let today = now.weekday();
+        if self.start == self.end {
+            return self.runs_on(today);
+        }
+        if self.start < self.end {
+            return self.runs_on(today) && minute >= self.start && minute < self.end;
+        }
+        // Spans midnight: the evening part is today's window, the early morning part yesterday's
+        (self.runs_on(today) && minute >= self.start) || (self.runs_on(today.pred()) && minute < self.end)
+    }
+
+    pub fn constraints(&self) -> PowerConstraints {
+        PowerConstraints {
+            lower_priority: self.lower_priority,
+            max_threads: self.max_threads,
+            prefer_hardware: false,
+            pause_queue: self.pause_queue,
+            software_only: !self.allow_hardware,
+        }
+    }
+}
+
+/// Time-of-day throttling, saved in the preferences
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulePolicy {
+    pub enabled: bool,
+    /// The first matching profile wins
+    pub profiles: Vec<ScheduleProfile>,
+}
+
+/// Profile in effect at local wall-clock time `now`. Windows are wall-clock times, so across a
+/// DST change a window starts and ends at the same clock reading: one falling in the skipped hour
+/// does not run that night and one in the repeated hour runs through both passes.
+pub fn active_profile(policy: &SchedulePolicy, now: NaiveDateTime) -> Option<&ScheduleProfile> {
+    if !policy.enabled {
+        return None;
+    }
+    policy.profiles.iter().find(|profile| profile.is_active(now))
+}
+
+/// 09:00
+pub fn format_minutes(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn parse_minutes(text: &str) -> Option<f64> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes) = (hours.trim().parse::<u16>().ok()?, minutes.trim().parse::<u16>().ok()?);
+    (hours < 24 && minutes < 60).then_some((hours * 60 + minutes) as f64)
+}
+
+fn time_field(value: &mut u16) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .range(0..=MINUTES_PER_DAY - 1)
+        .speed(5.0)
+        .custom_formatter(|minutes, _| format_minutes(minutes as u16))
+        .custom_parser(parse_minutes)
+}
+
+/// Profile list for the settings menu; returns true when something changed
+pub fn show_editor(ui: &mut egui::Ui, policy: &mut SchedulePolicy, translations: &Translations) -> bool {
+    let mut changed = ui.checkbox(&mut policy.enabled, translations.schedule_enabled()).changed();
+    let mut remove = None;
+    ui.add_enabled_ui(policy.enabled, |ui| {
+        for (index, profile) in policy.profiles.iter_mut().enumerate() {
+            ui.separator();
+            ui.push_id(index, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.add(egui::TextEdit::singleline(&mut profile.name).desired_width(140.0)).changed();
+                    if ui.small_button("🗑").on_hover_text(translations.schedule_remove()).clicked() {
+                        remove = Some(index);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    for (day, enabled) in profile.days.iter_mut().enumerate() {
+                        changed |= ui.toggle_value(enabled, translations.weekday_short(day)).changed();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    changed |= ui.add(time_field(&mut profile.start)).changed();
+                    ui.label("–");
+                    changed |= ui.add(time_field(&mut profile.end)).changed();
+                }).response.on_hover_text(translations.schedule_window_hint());
+                ui.horizontal(|ui| {
+                    ui.label(translations.schedule_max_threads());
+                    changed |= ui.add(egui::DragValue::new(&mut profile.max_threads).range(0..=256))
+                        .on_hover_text(translations.schedule_max_threads_hint())
+                        .changed();
+                });
+                changed |= ui.checkbox(&mut profile.lower_priority, translations.schedule_lower_priority()).changed();
+                changed |= ui.checkbox(&mut profile.allow_hardware, translations.schedule_allow_hardware()).changed();
+                changed |= ui.checkbox(&mut profile.pause_queue, translations.schedule_pause_queue())
+                    .on_hover_text(translations.schedule_pause_queue_hint())
+                    .changed();
+            });
+        }
+        ui.separator();
+        if ui.button(translations.schedule_add()).clicked() {
+            policy.profiles.push(ScheduleProfile::default());
+            changed = true;
+        }
+    });
+    if let Some(index) = remove {
+        policy.profiles.remove(index);
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// 2024-03-04 is a Monday, so `day` 0..7 runs Monday to Sunday
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 4 + day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn profile(name: &str, days: [bool; 7], start: u16, end: u16) -> ScheduleProfile {
+        ScheduleProfile { name: name.to_string(), days, start, end, ..Default::default() }
+    }
+
+    const WEEKDAYS: [bool; 7] = [true, true, true, true, true, false, false];
+    const MONDAY_ONLY: [bool; 7] = [true, false, false, false, false, false, false];
+
+    #[test]
+    fn daytime_window_includes_start_and_excludes_end() {
+        let office = ScheduleProfile::default();
+        assert!(!office.is_active(at(0, 8, 59)));
+        assert!(office.is_active(at(0, 9, 0)));
+        assert!(office.is_active(at(4, 17, 59)));
+        assert!(!office.is_active(at(4, 18, 0)));
+        // Saturday is off
+        assert!(!office.is_active(at(5, 12, 0)));
+    }
+
+    #[test]
+    fn window_past_midnight_belongs_to_the_day_it_starts() {
+        let night = profile("Night", MONDAY_ONLY, 22 * 60, 6 * 60);
+        assert!(!night.is_active(at(0, 21, 59)));
+        assert!(night.is_active(at(0, 22, 0)));
+        assert!(night.is_active(at(0, 23, 59)));
+        // Tuesday morning is the tail of Monday's window
+        assert!(night.is_active(at(1, 0, 0)));
+        assert!(night.is_active(at(1, 5, 59)));
+        assert!(!night.is_active(at(1, 6, 0)));
+        assert!(!night.is_active(at(1, 22, 0)));
+        // Monday morning would be Sunday's tail, and Sunday is off
+        assert!(!night.is_active(at(0, 1, 0)));
+    }
+
+    #[test]
+    fn sunday_night_spills_into_monday() {
+        let sunday = profile("Sunday", [false, false, false, false, false, false, true], 23 * 60, 2 * 60);
+        assert!(sunday.is_active(at(6, 23, 30)));
+        // Monday's pred() wraps to Sunday
+        assert!(sunday.is_active(at(7, 1, 30)));
+        assert!(!sunday.is_active(at(7, 2, 0)));
+    }
+
+    #[test]
+    fn equal_start_and_end_is_all_day() {
+        let all_day = profile("All day", MONDAY_ONLY, 8 * 60, 8 * 60);
+        assert!(all_day.is_active(at(0, 0, 0)));
+        assert!(all_day.is_active(at(0, 7, 59)));
+        assert!(all_day.is_active(at(0, 23, 59)));
+        assert!(!all_day.is_active(at(1, 0, 0)));
+        let midnight = profile("Midnight", MONDAY_ONLY, 0, 0);
+        assert!(midnight.is_active(at(0, 12, 0)));
+        assert!(!midnight.is_active(at(6, 12, 0)));
+    }
+
+    #[test]
+    fn first_matching_profile_wins() {
+        let mut policy = SchedulePolicy {
+            enabled: true,
+            profiles: vec![
+                profile("Lunch", WEEKDAYS, 12 * 60, 13 * 60),
+                ScheduleProfile::default(),
+                profile("Weekend", [true; 7], 0, 0),
+            ],
+        };
+        let name = |policy: &SchedulePolicy, now| active_profile(policy, now).map(|profile| profile.name.clone());
+        assert_eq!(name(&policy, at(0, 12, 30)).as_deref(), Some("Lunch"));
+        assert_eq!(name(&policy, at(0, 10, 0)).as_deref(), Some("Office hours"));
+        assert_eq!(name(&policy, at(0, 20, 0)).as_deref(), Some("Weekend"));
+        assert_eq!(name(&policy, at(5, 12, 30)).as_deref(), Some("Weekend"));
+
+        policy.profiles.swap(0, 2);
+        assert_eq!(name(&policy, at(0, 12, 30)).as_deref(), Some("Weekend"));
+
+        policy.enabled = false;
+        assert_eq!(name(&policy, at(0, 12, 30)), None);
+        assert_eq!(active_profile(&SchedulePolicy { enabled: true, profiles: Vec::new() }, at(0, 12, 0)), None);
+    }
+
+    #[test]
+    fn windows_follow_the_wall_clock_across_dst_changes() {
+        // Spring forward (US, 2024-03-10): clocks jump from 01:59 to 03:00, so a window inside the
+        // skipped hour never sees a matching reading that night
+        let skipped = profile("Skipped", [true; 7], 2 * 60, 3 * 60);
+        assert!(!skipped.is_active(at(6, 1, 59)));
+        assert!(!skipped.is_active(at(6, 3, 0)));
+        // A window reaching past the gap ends at its clock reading, an hour of real time early
+        let night = profile("Night", [true; 7], 60, 4 * 60);
+        assert!(night.is_active(at(6, 1, 59)));
+        assert!(night.is_active(at(6, 3, 0)));
+        assert!(!night.is_active(at(6, 4, 0)));
+
+        // Fall back (2024-11-03): 01:00-01:59 happens twice and both passes read the same local
+        // time, so the window is active through both
+        let repeated = profile("Repeated", [true; 7], 60, 2 * 60);
+        let both_passes = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap().and_hms_opt(1, 30, 0).unwrap();
+        assert!(repeated.is_active(both_passes));
+        assert!(!repeated.is_active(both_passes + chrono::Duration::minutes(30)));
+    }
+
+    #[test]
+    fn minutes_format_and_parse() {
+        assert_eq!(format_minutes(0), "00:00");
+        assert_eq!(format_minutes(9 * 60 + 5), "09:05");
+        assert_eq!(format_minutes(MINUTES_PER_DAY - 1), "23:59");
+        assert_eq!(parse_minutes(" 7:30 "), Some(450.0));
+        assert_eq!(parse_minutes("24:00"), None);
+        assert_eq!(parse_minutes("12:60"), None);
+        assert_eq!(parse_minutes("noon"), None);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::app_state::SessionSnapshot;
+use crate::language::Translations;
+
+/// Older entries are dropped beyond this
+const MAX_ENTRIES: usize = 20;
+/// How long the "Undo: ..." toast stays after a destructive action
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Main-window actions that replace user state and can be undone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoAction {
+    ResetProject,
+    ResetSettings,
+    ClearInputFiles,
+    ClearOutputFile,
+    ApplyPreset,
+    ApplyRecommendation,
+}
+
+/// Undo stack for the main window; the workflow editor keeps its own history
+#[derive(Default)]
+pub struct SessionUndo {
+    /// State before each action, newest last
+    entries: VecDeque<(UndoAction, SessionSnapshot)>,
+    toast: Option<(UndoAction, Instant)>,
+}
+
+impl SessionUndo {
+    /// Remember the state from before `action` and announce it with the toast
+    pub fn push(&mut self, action: UndoAction, before: SessionSnapshot) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((action, before));
+        self.toast = Some((action, Instant::now()));
+    }
+
+    pub fn pop(&mut self) -> Option<(UndoAction, SessionSnapshot)> {
+        self.toast = None;
+        self.entries.pop_back()
+    }
+
+    /// Toast offering to undo the latest action; returns true when its button was clicked
+    pub fn show_toast(&mut self, ctx: &egui::Context, translations: &Translations) -> bool {
+        let Some((action, shown_at)) = self.toast else {
+            return false;
+        };
+        let elapsed = shown_at.elapsed();
+        if elapsed >= TOAST_DURATION {
+            self.toast = None;
+            return false;
+        }
+        // Wake up to hide the toast even when nothing else repaints
+        ctx.request_repaint_after(TOAST_DURATION - elapsed);
+
+        let mut clicked = false;
+        egui::Area::new(egui::Id::new("session_undo_toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -48.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("↶ {}", translations.undo_action(action))).clicked() {
+                            clicked = true;
+                        }
+                        ui.weak("Ctrl+Z");
+                        if ui.small_button("✖").clicked() {
+                            self.toast = None;
+                        }
+                    });
+                });
+            });
+        clicked
+    }
+}
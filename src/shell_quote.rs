@@ -0,0 +1,249 @@
+/// Shell dialect a copied or exported command is quoted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// sh/bash/zsh: single quotes
+    Posix,
+    /// PowerShell: single quotes with doubled inner quotes, `&` to call a quoted program
+    PowerShell,
+}
+
+impl Shell {
+    pub fn all() -> [Shell; 2] {
+        [Shell::Posix, Shell::PowerShell]
+    }
+
+    /// Shell of the platform the app runs on
+    pub fn native() -> Shell {
+        if cfg!(target_os = "windows") {
+            Shell::PowerShell
+        } else {
+            Shell::Posix
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Shell::Posix => "sh / bash",
+            Shell::PowerShell => "PowerShell",
+        }
+    }
+
+    /// Separator between consecutive commands on one line
+    pub fn separator(&self) -> &'static str {
+        match self {
+            Shell::Posix => " && ",
+            // Windows PowerShell 5 has no `&&`
+            Shell::PowerShell => "; ",
+        }
+    }
+}
+
+/// Characters that never need quoting in either shell
+fn is_plain(arg: &str) -> bool {
+    !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=+".contains(c))
+}
+
+/// Quote one argument so the shell passes it through unchanged, including spaces, quotes and non-ASCII text
+pub fn quote_arg(arg: &str, shell: Shell) -> String {
+    if is_plain(arg) {
+        return arg.to_string();
+    }
+    match shell {
+        Shell::Posix => format!("'{}'", arg.replace('\'', r"'\''")),
+        // PowerShell also treats the typographic single quotes as quote characters
+        Shell::PowerShell => format!("'{}'", arg
+            .replace('\'', "''")
+            .replace('\u{2018}', "\u{2018}\u{2018}")
+            .replace('\u{2019}', "\u{2019}\u{2019}")),
+    }
+}
+
+/// One command line; the program is taken as a file name so the command also runs where ffmpeg is on PATH
+pub fn command_line(argv: &[String], shell: Shell) -> String {
+    let Some((program, args)) = argv.split_first() else {
+        return String::new();
+    };
+    let program = std::path::Path::new(program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| program.clone());
+    let quoted_program = quote_arg(&program, shell);
+    let mut parts = vec![match shell {
+        Shell::PowerShell if quoted_program != program => format!("& {}", quoted_program),
+        _ => quoted_program,
+    }];
+    parts.extend(args.iter().map(|arg| quote_arg(arg, shell)));
+    parts.join(" ")
+}
+
+/// Several commands on one line, run in order
+pub fn join_commands(commands: &[Vec<String>], shell: Shell) -> String {
+    commands.iter()
+        .map(|argv| command_line(argv, shell))
+        .collect::<Vec<_>>()
+        .join(shell.separator())
+}
+
+/// argv of each command in a command preview, one per line or chained with `&&`; comment and
+/// blank lines are skipped
+pub fn split_preview(preview: &str) -> Vec<Vec<String>> {
+    preview.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(split_command_line)
+        .filter(|argv| !argv.is_empty())
+        .collect()
+}
+
+/// Split a preview line built with double-quoted arguments back into the argv of each command
+/// on it; an unquoted `&&` separates commands
+fn split_command_line(line: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    let mut quoted = false;
+    let mut end_arg = |args: &mut Vec<String>, current: &mut String, quoted: bool| {
+        if !quoted && current == "&&" {
+            commands.push(std::mem::take(args));
+            current.clear();
+        } else {
+            args.push(std::mem::take(current));
+        }
+    };
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+                quoted = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    end_arg(&mut args, &mut current, quoted);
+                    has_arg = false;
+                    quoted = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        end_arg(&mut args, &mut current, quoted);
+    }
+    commands.push(args);
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Preview line the way the command preview writes it: arguments with spaces or quotes
+    /// in double quotes, empty arguments as `""`
+    fn preview_line(argv: &[String]) -> String {
+        argv.iter()
+            .map(|arg| if arg.is_empty() || arg.contains(' ') || arg == "&&" {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn plain_arguments_stay_unquoted() {
+        for shell in Shell::all() {
+            assert_eq!(quote_arg("-c:v", shell), "-c:v");
+            assert_eq!(quote_arg("/tmp/out_1.mp4", shell), "/tmp/out_1.mp4");
+            assert_eq!(quote_arg("scale=1280:-2", shell), "scale=1280:-2");
+        }
+    }
+
+    #[test]
+    fn posix_quotes_spaces_quotes_and_non_ascii() {
+        let shell = Shell::Posix;
+        assert_eq!(quote_arg("my file.mp4", shell), "'my file.mp4'");
+        assert_eq!(quote_arg("it's.mp4", shell), r"'it'\''s.mp4'");
+        assert_eq!(quote_arg("say \"hi\".mp4", shell), "'say \"hi\".mp4'");
+        assert_eq!(quote_arg("\u{2018}live\u{2019}.mp4", shell), "'\u{2018}live\u{2019}.mp4'");
+        assert_eq!(quote_arg("動画 テスト.mp4", shell), "'動画 テスト.mp4'");
+        assert_eq!(quote_arg("", shell), "''");
+    }
+
+    #[test]
+    fn powershell_doubles_straight_and_typographic_single_quotes() {
+        let shell = Shell::PowerShell;
+        assert_eq!(quote_arg("my file.mp4", shell), "'my file.mp4'");
+        assert_eq!(quote_arg("it's.mp4", shell), "'it''s.mp4'");
+        assert_eq!(quote_arg("say \"hi\".mp4", shell), "'say \"hi\".mp4'");
+        assert_eq!(
+            quote_arg("\u{2018}live\u{2019}.mp4", shell),
+            "'\u{2018}\u{2018}live\u{2019}\u{2019}.mp4'"
+        );
+        assert_eq!(quote_arg("動画 テスト.mp4", shell), "'動画 テスト.mp4'");
+        assert_eq!(quote_arg("", shell), "''");
+    }
+
+    #[test]
+    fn command_line_takes_the_program_by_name() {
+        let command = argv(&["/usr/local/bin/ffmpeg", "-i", "my file.mp4", "out.mkv"]);
+        assert_eq!(command_line(&command, Shell::Posix), "ffmpeg -i 'my file.mp4' out.mkv");
+        assert_eq!(command_line(&command, Shell::PowerShell), "ffmpeg -i 'my file.mp4' out.mkv");
+        assert_eq!(command_line(&[], Shell::Posix), "");
+    }
+
+    #[test]
+    fn powershell_calls_a_quoted_program_with_ampersand() {
+        let command = argv(&["/opt/my tools/ff mpeg", "-i", "it's.mp4"]);
+        assert_eq!(command_line(&command, Shell::Posix), r"'ff mpeg' -i 'it'\''s.mp4'");
+        assert_eq!(command_line(&command, Shell::PowerShell), "& 'ff mpeg' -i 'it''s.mp4'");
+    }
+
+    #[test]
+    fn join_commands_uses_the_shell_separator() {
+        let commands = vec![argv(&["ffmpeg", "-i", "a.mp4", "b.mkv"]), argv(&["ffprobe", "b.mkv"])];
+        assert_eq!(join_commands(&commands, Shell::Posix), "ffmpeg -i a.mp4 b.mkv && ffprobe b.mkv");
+        assert_eq!(join_commands(&commands, Shell::PowerShell), "ffmpeg -i a.mp4 b.mkv; ffprobe b.mkv");
+    }
+
+    #[test]
+    fn split_preview_splits_chained_commands_and_skips_comments() {
+        let preview = "# pass 1\n\nffmpeg -i \"my file.mp4\" -pass 1 -f null - && ffmpeg -i \"my file.mp4\" -pass 2 out.mp4\n  # done\n";
+        assert_eq!(split_preview(preview), vec![
+            argv(&["ffmpeg", "-i", "my file.mp4", "-pass", "1", "-f", "null", "-"]),
+            argv(&["ffmpeg", "-i", "my file.mp4", "-pass", "2", "out.mp4"]),
+        ]);
+    }
+
+    #[test]
+    fn split_preview_keeps_quoted_ampersands_and_empty_arguments() {
+        assert_eq!(
+            split_preview("ffmpeg -metadata \"title=&&\" -i \"&&\" \"\" out.mp4"),
+            vec![argv(&["ffmpeg", "-metadata", "title=&&", "-i", "&&", "", "out.mp4"])]
+        );
+    }
+
+    #[test]
+    fn split_preview_round_trips_preview_lines() {
+        let commands = vec![
+            argv(&["ffmpeg", "-i", "動画 テスト.mp4", "-metadata", "comment=", "out.mp4"]),
+            argv(&["ffmpeg", "-i", "&&", "-metadata", "title=it's \u{2018}live\u{2019}", "b.mkv"]),
+            argv(&["ffprobe", "", "out.mp4"]),
+        ];
+        let one_per_line = commands.iter().map(|c| preview_line(c)).collect::<Vec<_>>().join("\n");
+        let chained = commands.iter().map(|c| preview_line(c)).collect::<Vec<_>>().join(" && ");
+        assert_eq!(split_preview(&one_per_line), commands);
+        assert_eq!(split_preview(&chained), commands);
+    }
+}
@@ -0,0 +1,294 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::app_state::{VideoSettings, AudioSettings};
+use crate::encode_advisor::{bitrate_kbps, configured_resolution, source_audio_kbps};
+use crate::ffmpeg_worker_simple::MediaInfo;
+use crate::language::Translations;
+
+/// Audio bitrate assumed when the audio settings leave it on "auto"
+pub const DEFAULT_AUDIO_KBPS: u32 = 128;
+/// Share of the target size reserved for container overhead (headers, index, interleaving)
+const CONTAINER_OVERHEAD: f64 = 0.02;
+/// Below this many bits per pixel per frame the picture falls apart for any common codec
+const MIN_BITS_PER_PIXEL: f64 = 0.02;
+/// Bits per pixel aimed for when suggesting a smaller resolution
+const COMFORTABLE_BITS_PER_PIXEL: f64 = 0.05;
+
+/// Bitrates that make the output land on a target size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizePlan {
+    pub total_kbps: u32,
+    pub video_kbps: u32,
+    /// None when the input has no audio
+    pub audio_kbps: Option<u32>,
+}
+
+/// Split the bitrate budget of `target_mb` (MiB) over `duration_secs` between audio and video
+pub fn plan(target_mb: f64, duration_secs: f64, audio_kbps: Option<u32>) -> Result<SizePlan> {
+    if target_mb <= 0.0 {
+        return Err(anyhow!("Target size must be above 0 MB"));
+    }
+    if !duration_secs.is_finite() || duration_secs <= 0.0 {
+        return Err(anyhow!("Input duration is unknown, cannot derive a bitrate from the target size"));
+    }
+
+    let usable_bits = target_mb * 1024.0 * 1024.0 * 8.0 * (1.0 - CONTAINER_OVERHEAD);
+    let total_kbps = (usable_bits / duration_secs / 1000.0).floor();
+    let video_kbps = total_kbps - audio_kbps.unwrap_or(0) as f64;
+    if video_kbps < 1.0 {
+        return Err(anyhow!("{} MB cannot hold {:.1} s of audio at {} kbps, let alone video",
+            target_mb, duration_secs, audio_kbps.unwrap_or(0)));
+    }
+
+    Ok(SizePlan {
+        total_kbps: total_kbps.min(u32::MAX as f64) as u32,
+        video_kbps: video_kbps.min(u32::MAX as f64) as u32,
+        audio_kbps,
+    })
+}
+
+/// Size in MiB an output of `total_kbps` over `duration_secs` comes out at; the inverse of `plan`
+pub fn estimated_size_mb(total_kbps: f64, duration_secs: f64) -> f64 {
+    total_kbps * 1000.0 * duration_secs / 8.0 / (1024.0 * 1024.0) / (1.0 - CONTAINER_OVERHEAD)
+}
+
+/// Audio bitrate the output will use: the configured one, the source's when copying, or the default
+pub fn planned_audio_kbps(info: &MediaInfo, audio: Option<&AudioSettings>) -> Option<u32> {
+    if info.audio_streams.is_empty() {
+        return None;
+    }
+    let kbps = match audio {
+        Some(audio) if audio.copy_audio => source_audio_kbps(info),
+        Some(audio) => bitrate_kbps(&audio.bitrate),
+        None => None,
+    };
+    Some(kbps.map(|kbps| kbps as u32).unwrap_or(DEFAULT_AUDIO_KBPS))
+}
+
+pub fn plan_for(info: &MediaInfo, video: &VideoSettings, audio: Option<&AudioSettings>) -> Result<SizePlan> {
+    plan(video.target_size_mb as f64, info.duration, planned_audio_kbps(info, audio))
+}
+
+/// Write the plan into the settings: video and audio bitrate with two-pass encoding
+pub fn apply_plan(plan: &SizePlan, video: &mut VideoSettings, audio: Option<&mut AudioSettings>) {
+    video.bitrate = format!("{}k", plan.video_kbps);
+    video.two_pass = true;
+    if let (Some(audio), Some(audio_kbps)) = (audio, plan.audio_kbps) {
+        if !audio.copy_audio {
+            audio.bitrate = format!("{}k", audio_kbps);
+        }
+    }
+}
+
+/// Resolution the video bitrate can still carry, when the output resolution is too large for it
+pub fn feasibility(video_kbps: u32, resolution: (u32, u32), fps: f64) -> Option<(u32, u32)> {
+    let (width, height) = resolution;
+    if width == 0 || height == 0 || fps <= 0.0 {
+        return None;
+    }
+    let pixels_per_second = width as f64 * height as f64 * fps;
+    let bits_per_pixel = video_kbps as f64 * 1000.0 / pixels_per_second;
+    if bits_per_pixel >= MIN_BITS_PER_PIXEL {
+        return None;
+    }
+    let scale = (bits_per_pixel / COMFORTABLE_BITS_PER_PIXEL).sqrt();
+    // Encoders want even dimensions
+    let even = |side: u32| ((side as f64 * scale) as u32 / 2 * 2).max(2);
+    Some((even(width), even(height)))
+}
+
+/// Output size measured against the target after the task finished
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizeOutcome {
+    pub target_mb: f64,
+    pub actual_mb: f64,
+}
+
+impl SizeOutcome {
+    pub fn measure(output_file: &str, target_mb: i32) -> Option<Self> {
+        let bytes = std::fs::metadata(output_file).ok()?.len();
+        Some(Self {
+            target_mb: target_mb as f64,
+            actual_mb: bytes as f64 / 1024.0 / 1024.0,
+        })
+    }
+
+    /// Signed deviation from the target in percent
+    pub fn deviation_percent(&self) -> f64 {
+        (self.actual_mb - self.target_mb) / self.target_mb * 100.0
+    }
+}
+
+/// Planner shown under the compression settings when a target size is set
+pub fn show_planner(
+    ui: &mut egui::Ui,
+    info: &MediaInfo,
+    video: &mut VideoSettings,
+    audio: &mut AudioSettings,
+    translations: &Translations,
+) {
+    if video.target_size_mb <= 0 {
+        return;
+    }
+
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(translations.size_target_planner()).strong());
+
+        let plan = match plan_for(info, video, Some(audio)) {
+            Ok(plan) => plan,
+            Err(e) => {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+                return;
+            }
+        };
+        ui.label(translations.size_target_split(plan.total_kbps, plan.video_kbps, plan.audio_kbps));
+
+        let resolution = configured_resolution(video)
+            .or_else(|| info.video_streams.first().map(|stream| (stream.width, stream.height)));
+        let fps = crate::frame_rate::FrameRate::parse(&video.fps).map(|rate| rate.as_f64())
+            .or_else(|| info.video_streams.first().map(|stream| stream.fps))
+            .unwrap_or(0.0);
+        if let Some(suggested) = resolution.and_then(|resolution| feasibility(plan.video_kbps, resolution, fps)) {
+            ui.colored_label(egui::Color32::from_rgb(255, 180, 0), format!("⚠ {}", translations.size_target_too_low(plan.video_kbps, suggested)));
+            if ui.button(translations.size_target_reduce_resolution(suggested)).clicked() {
+                video.width = Some(suggested.0);
+                video.height = Some(suggested.1);
+            }
+        }
+
+        ui.checkbox(&mut video.size_target_confirm, translations.size_target_confirm());
+        if video.size_target_confirm {
+            let applied = video.two_pass && bitrate_kbps(&video.bitrate) == Some(plan.video_kbps as u64);
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!applied, egui::Button::new(translations.size_target_apply())).clicked() {
+                    apply_plan(&plan, video, Some(audio));
+                }
+                if !applied {
+                    ui.weak(translations.size_target_not_applied());
+                }
+            });
+        } else {
+            ui.weak(translations.size_target_automatic());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(duration: f64, audio_bit_rates: &[Option<u64>]) -> MediaInfo {
+        let audio_streams: Vec<serde_json::Value> = audio_bit_rates.iter().enumerate()
+            .map(|(index, bit_rate)| serde_json::json!({
+                "index": index + 1, "codec": "aac", "sample_rate": 48000, "channels": 2, "bit_rate": bit_rate,
+            }))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "filename": "input.mp4",
+            "duration": duration,
+            "video_streams": [{ "index": 0, "codec": "h264", "width": 1920, "height": 1080, "fps": 30.0 }],
+            "audio_streams": audio_streams,
+        })).unwrap()
+    }
+
+    #[test]
+    fn plan_splits_the_budget_between_audio_and_video() {
+        // 10 MiB less 2 % overhead over a minute
+        let plan = plan(10.0, 60.0, Some(128)).unwrap();
+        assert_eq!(plan, SizePlan { total_kbps: 1370, video_kbps: 1242, audio_kbps: Some(128) });
+        let size = estimated_size_mb(plan.total_kbps as f64, 60.0);
+        assert!(size <= 10.0 && size > 9.99, "{size}");
+    }
+
+    #[test]
+    fn inputs_without_audio_give_video_the_whole_budget() {
+        let info = media(60.0, &[]);
+        assert_eq!(planned_audio_kbps(&info, Some(&AudioSettings::default())), None);
+        assert_eq!(planned_audio_kbps(&info, None), None);
+
+        let video = VideoSettings { target_size_mb: 10, ..Default::default() };
+        let plan = plan_for(&info, &video, Some(&AudioSettings::default())).unwrap();
+        assert_eq!(plan, SizePlan { total_kbps: 1370, video_kbps: 1370, audio_kbps: None });
+
+        // Applying leaves the audio settings alone
+        let mut video = video;
+        let mut audio = AudioSettings { bitrate: "96k".to_string(), ..Default::default() };
+        apply_plan(&plan, &mut video, Some(&mut audio));
+        assert_eq!(video.bitrate, "1370k");
+        assert!(video.two_pass);
+        assert_eq!(audio.bitrate, "96k");
+    }
+
+    #[test]
+    fn audio_bitrate_comes_from_settings_source_or_default() {
+        let info = media(60.0, &[Some(160_000)]);
+        let configured = AudioSettings { bitrate: "192k".to_string(), copy_audio: false, ..Default::default() };
+        assert_eq!(planned_audio_kbps(&info, Some(&configured)), Some(192));
+        let copied = AudioSettings { copy_audio: true, ..configured.clone() };
+        assert_eq!(planned_audio_kbps(&info, Some(&copied)), Some(160));
+        let automatic = AudioSettings { bitrate: "auto".to_string(), copy_audio: false, ..Default::default() };
+        assert_eq!(planned_audio_kbps(&info, Some(&automatic)), Some(DEFAULT_AUDIO_KBPS));
+        assert_eq!(planned_audio_kbps(&info, None), Some(DEFAULT_AUDIO_KBPS));
+        // A copied stream whose bitrate is unknown is planned at the default
+        assert_eq!(planned_audio_kbps(&media(60.0, &[None]), Some(&copied)), Some(DEFAULT_AUDIO_KBPS));
+
+        let mut video = VideoSettings::default();
+        let mut audio = copied;
+        let plan = plan(10.0, 60.0, Some(160)).unwrap();
+        apply_plan(&plan, &mut video, Some(&mut audio));
+        assert_eq!(audio.bitrate, "192k", "a copied stream keeps its settings");
+        let mut audio = configured;
+        apply_plan(&plan, &mut video, Some(&mut audio));
+        assert_eq!(audio.bitrate, "160k");
+    }
+
+    #[test]
+    fn very_short_clips() {
+        // A single frame gets a huge but finite bitrate
+        let single_frame = plan(1.0, 0.04, Some(128)).unwrap();
+        assert_eq!(single_frame.total_kbps, 205_520);
+        assert_eq!(single_frame.video_kbps, 205_392);
+
+        // A tiny target over a short clip cannot fit the audio...
+        let error = plan(0.001, 1.0, Some(128)).unwrap_err();
+        assert!(error.to_string().contains("cannot hold"), "{error}");
+        // ...but still fits a few kbps of video without it
+        assert_eq!(plan(0.001, 1.0, None).unwrap().video_kbps, 8);
+        // Less than one kbps is never planned
+        assert!(plan(0.0001, 1.0, None).is_err());
+    }
+
+    #[test]
+    fn unknown_durations_and_targets_are_rejected() {
+        for duration in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(plan(10.0, duration, None).is_err(), "{duration}");
+        }
+        assert!(plan(0.0, 60.0, None).is_err());
+        assert!(plan(-5.0, 60.0, None).is_err());
+
+        let video = VideoSettings { target_size_mb: 10, ..Default::default() };
+        assert!(plan_for(&media(0.0, &[]), &video, None).is_err());
+    }
+
+    #[test]
+    fn feasibility_suggests_an_even_smaller_resolution() {
+        assert_eq!(feasibility(5000, (1920, 1080), 30.0), None);
+        let (width, height) = feasibility(500, (1920, 1080), 30.0).unwrap();
+        assert!(width < 1920 && height < 1080);
+        assert_eq!((width % 2, height % 2), (0, 0));
+        assert!((width as f64 / height as f64 - 16.0 / 9.0).abs() < 0.02, "{width}x{height}");
+        // The suggestion leaves room above the floor
+        assert!(500_000.0 / (width as f64 * height as f64 * 30.0) >= MIN_BITS_PER_PIXEL);
+
+        assert_eq!(feasibility(1, (1920, 1080), 30.0).map(|(w, h)| w >= 2 && h >= 2), Some(true));
+        assert_eq!(feasibility(500, (0, 1080), 30.0), None);
+        assert_eq!(feasibility(500, (1920, 1080), 0.0), None);
+    }
+
+    #[test]
+    fn outcome_deviation() {
+        let outcome = SizeOutcome { target_mb: 10.0, actual_mb: 9.5 };
+        assert!((outcome.deviation_percent() + 5.0).abs() < 1e-9);
+        assert!(SizeOutcome::measure("/nonexistent/output.mp4", 10).is_none());
+    }
+}
@@ -0,0 +1,456 @@
+use std::sync::mpsc::{Receiver, Sender};
+
+use eframe::egui;
+
+use crate::app_state::{AudioSettings, FrameFit, VideoSettings};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+use crate::language::Translations;
+use crate::preset_manager::{EncodingPreset, PresetCategory};
+
+/// Share of the source picture a crop has to keep; below it the source is padded instead
+const MIN_CROP_KEEP: f32 = 0.75;
+const PREVIEW_HEIGHT: u32 = 160;
+
+/// Upload target of one platform
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SocialPreset {
+    pub name: &'static str,
+    pub size: (u32, u32),
+    /// Peak video bitrate (-maxrate) and rate control buffer (-bufsize)
+    pub max_bitrate: &'static str,
+    pub buffer_size: &'static str,
+    pub audio_bitrate: &'static str,
+    /// Longest video the platform accepts, in seconds
+    pub max_duration_secs: Option<u32>,
+}
+
+pub const PRESETS: &[SocialPreset] = &[
+    SocialPreset {
+        name: "TikTok 9:16",
+        size: (1080, 1920),
+        max_bitrate: "8M",
+        buffer_size: "16M",
+        audio_bitrate: "128k",
+        max_duration_secs: Some(600),
+    },
+    SocialPreset {
+        name: "Instagram Reels 9:16",
+        size: (1080, 1920),
+        max_bitrate: "6M",
+        buffer_size: "12M",
+        audio_bitrate: "128k",
+        max_duration_secs: Some(90),
+    },
+    SocialPreset {
+        name: "YouTube Shorts 9:16",
+        size: (1080, 1920),
+        max_bitrate: "10M",
+        buffer_size: "20M",
+        audio_bitrate: "192k",
+        max_duration_secs: Some(60),
+    },
+    SocialPreset {
+        name: "Instagram 1:1",
+        size: (1080, 1080),
+        max_bitrate: "5M",
+        buffer_size: "10M",
+        audio_bitrate: "128k",
+        max_duration_secs: Some(60),
+    },
+    SocialPreset {
+        name: "YouTube 16:9",
+        size: (1920, 1080),
+        max_bitrate: "12M",
+        buffer_size: "24M",
+        audio_bitrate: "192k",
+        max_duration_secs: Some(900),
+    },
+];
+
+/// Share of the source picture left after cropping it to the target aspect ratio
+pub fn crop_keep(source: (u32, u32), target: (u32, u32)) -> f32 {
+    if source.0 == 0 || source.1 == 0 || target.0 == 0 || target.1 == 0 {
+        return 1.0;
+    }
+    let source_aspect = source.0 as f32 / source.1 as f32;
+    let target_aspect = target.0 as f32 / target.1 as f32;
+    source_aspect.min(target_aspect) / source_aspect.max(target_aspect)
+}
+
+impl SocialPreset {
+    /// Crop when little of the source is lost (4:3 into 16:9, a near-vertical phone clip into 9:16),
+    /// pad when cropping would cut away most of it (landscape or square into 9:16)
+    pub fn auto_fit(&self, source: (u32, u32)) -> FrameFit {
+        if crop_keep(source, self.size) >= MIN_CROP_KEEP {
+            FrameFit::Crop
+        } else {
+            FrameFit::Pad
+        }
+    }
+
+    /// Source duration over the platform limit, as the limit in seconds
+    pub fn exceeded_limit(&self, duration: f64) -> Option<u32> {
+        self.max_duration_secs.filter(|limit| duration > *limit as f64)
+    }
+
+    /// H.264 high profile with the platform's bitrate cap at the exact upload size. The frames are
+    /// auto-rotated while decoding, so the rotate tag of phone recordings is cleared on the output
+    /// to keep players from turning the upright picture a second time.
+    pub fn video_settings(&self, fit: FrameFit) -> VideoSettings {
+        let (width, height) = self.size;
+        VideoSettings {
+            codec: "libx264".to_string(),
+            preset: "medium".to_string(),
+            profile: "high".to_string(),
+            pixel_format: "yuv420p".to_string(),
+            quality: 21,
+            crf: 21,
+            resolution: self.size,
+            width: Some(width),
+            height: Some(height),
+            frame_fit: Some(fit),
+            container_format: "mp4".to_string(),
+            container_options: [("faststart".to_string(), true), ("fragmented".to_string(), false)].into_iter().collect(),
+            custom_args: format!("-maxrate {} -bufsize {} -metadata:s:v:0 rotate=0", self.max_bitrate, self.buffer_size),
+            ..Default::default()
+        }
+    }
+
+    pub fn audio_settings(&self) -> AudioSettings {
+        AudioSettings {
+            codec: "aac".to_string(),
+            bitrate: self.audio_bitrate.to_string(),
+            sample_rate: "48000".to_string(),
+            channels: "2".to_string(),
+            quality: "auto".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn encoding_preset(&self, fit: FrameFit) -> EncodingPreset {
+        let limit = match self.max_duration_secs {
+            Some(limit) => format!(", up to {}", format_limit(limit)),
+            None => String::new(),
+        };
+        EncodingPreset {
+            name: self.name.to_string(),
+            description: format!("{}x{} H.264 capped at {}{}", self.size.0, self.size.1, self.max_bitrate, limit),
+            category: PresetCategory::SocialMedia,
+            video_settings: self.video_settings(fit),
+            audio_settings: self.audio_settings(),
+            recommended_formats: vec!["mp4".to_string()],
+        }
+    }
+}
+
+/// "60s", "90s", "10 min"
+pub fn format_limit(secs: u32) -> String {
+    if secs > 90 && secs.is_multiple_of(60) {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn fit_label(fit: FrameFit, translations: &Translations) -> &'static str {
+    match fit {
+        FrameFit::Crop => translations.social_presets_crop(),
+        FrameFit::Pad => translations.social_presets_pad(),
+    }
+}
+
+/// Input file, preset index and fit a preview frame was rendered for
+type PreviewKey = (String, usize, FrameFit);
+
+/// Social media export section: platform choice, crop-or-pad decision with override and a
+/// preview frame of the result
+pub struct SocialExportPanel {
+    selected: usize,
+    /// The other fit than the automatic one was chosen
+    overridden: bool,
+    preview: Option<(PreviewKey, egui::TextureHandle)>,
+    /// Last preview asked for; a failed render is not retried until the key changes
+    requested: Option<PreviewKey>,
+    in_flight: bool,
+    sender: Sender<(PreviewKey, Option<egui::ColorImage>)>,
+    receiver: Receiver<(PreviewKey, Option<egui::ColorImage>)>,
+}
+
+impl Default for SocialExportPanel {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            selected: 0,
+            overridden: false,
+            preview: None,
+            requested: None,
+            in_flight: false,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl SocialExportPanel {
+    /// Returns true when the preset was applied to the settings
+    #[allow(clippy::too_many_arguments)]
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        input_file: Option<&str>,
+        detected_resolution: Option<(u32, u32)>,
+        is_portrait: Option<bool>,
+        detected_duration: Option<f64>,
+        video_settings: &mut VideoSettings,
+        audio_settings: &mut AudioSettings,
+        translations: &Translations,
+    ) -> bool {
+        ui.label(translations.social_presets_hint());
+        ui.horizontal_wrapped(|ui| {
+            for (index, preset) in PRESETS.iter().enumerate() {
+                if ui.selectable_value(&mut self.selected, index, preset.name).changed() {
+                    self.overridden = false;
+                }
+            }
+        });
+        let preset = &PRESETS[self.selected.min(PRESETS.len() - 1)];
+        ui.label(format!("{}x{}, H.264 high ≤ {}, AAC {}, faststart",
+            preset.size.0, preset.size.1, preset.max_bitrate, preset.audio_bitrate));
+
+        let fit = match detected_resolution {
+            Some(source) => {
+                let orientation = if source.0 == source.1 {
+                    translations.social_presets_square()
+                } else if is_portrait == Some(true) {
+                    translations.social_presets_portrait()
+                } else {
+                    translations.social_presets_landscape()
+                };
+                ui.label(format!("{} {}x{} {}", translations.social_presets_source(), source.0, source.1, orientation));
+
+                let auto = preset.auto_fit(source);
+                let other = match auto {
+                    FrameFit::Crop => FrameFit::Pad,
+                    FrameFit::Pad => FrameFit::Crop,
+                };
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} {} ({} {:.0}%)",
+                        translations.social_presets_auto(),
+                        fit_label(auto, translations),
+                        translations.social_presets_crop_keeps(),
+                        crop_keep(source, preset.size) * 100.0));
+                    ui.checkbox(&mut self.overridden, format!("{} {}",
+                        translations.social_presets_use_instead(), fit_label(other, translations)));
+                });
+                if self.overridden { other } else { auto }
+            }
+            None => {
+                ui.label(translations.social_presets_no_source());
+                FrameFit::Pad
+            }
+        };
+
+        if let Some(limit) = detected_duration.and_then(|duration| preset.exceeded_limit(duration)) {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠ {} {} ({:.0}s)",
+                translations.social_presets_too_long(), format_limit(limit), detected_duration.unwrap_or_default()));
+        }
+
+        if let (Some(input_file), Some(_)) = (input_file, detected_resolution) {
+            self.show_preview(ui, (input_file.to_string(), self.selected, fit), preset, detected_duration, translations);
+        }
+
+        let applied = ui.button(format!("{} {}", translations.social_presets_apply(), preset.name)).clicked();
+        if applied {
+            crate::preset_manager::PresetManager::apply_preset_to_settings(&preset.encoding_preset(fit), video_settings, audio_settings);
+        }
+        applied
+    }
+
+    /// Frame of the source cropped or padded to the preset, rendered once per input, preset and fit
+    fn show_preview(&mut self, ui: &mut egui::Ui, key: PreviewKey, preset: &SocialPreset, duration: Option<f64>, translations: &Translations) {
+        while let Ok((done, image)) = self.receiver.try_recv() {
+            if self.requested.as_ref() == Some(&done) {
+                self.in_flight = false;
+            }
+            if let Some(image) = image {
+                let texture = ui.ctx().load_texture("social_preset_preview", image, egui::TextureOptions::LINEAR);
+                self.preview = Some((done, texture));
+            }
+        }
+
+        let current = self.preview.as_ref().is_some_and(|(shown, _)| *shown == key);
+        if !current && self.requested.as_ref() != Some(&key) {
+            self.requested = Some(key.clone());
+            self.in_flight = true;
+            let sender = self.sender.clone();
+            let ctx = ui.ctx().clone();
+            let filter = ComprehensiveCommandBuilder::scale_filter(preset.size.0, preset.size.1, Some(key.2));
+            // A second in, clear of fade-ins, unless the clip is shorter than that
+            let time = duration.map(|duration| (duration / 2.0).min(1.0)).unwrap_or(0.0);
+            std::thread::spawn(move || {
+                let image = extract_preview(&key.0, time, &filter);
+                let _ = sender.send((key, image));
+                ctx.request_repaint();
+            });
+        }
+
+        match &self.preview {
+            Some((_, texture)) if current => {
+                ui.add(egui::Image::new(texture).max_height(PREVIEW_HEIGHT as f32));
+            }
+            _ if self.in_flight => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(translations.social_presets_rendering());
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn extract_preview(input_file: &str, time: f64, fit_filter: &str) -> Option<egui::ColorImage> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    let time_arg = format!("{:.3}", time);
+    let filter = format!("{},scale=-2:{}", fit_filter, PREVIEW_HEIGHT);
+    let output = ffmpeg.run_ffmpeg(&[
+        "-v", "error", "-ss", &time_arg, "-i", input_file,
+        "-frames:v", "1", "-vf", &filter,
+        "-f", "image2pipe", "-vcodec", "png", "-",
+    ]).ok()?;
+    let image = image::load_from_memory(&output.stdout).ok()?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    log_debug!("Rendered social preset preview at {:.3}s with {}", time, fit_filter);
+    Some(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffmpeg_worker_simple::VideoStreamInfo;
+
+    const LANDSCAPE: (u32, u32) = (1920, 1080);
+    const PORTRAIT: (u32, u32) = (1080, 1920);
+    const SQUARE: (u32, u32) = (1080, 1080);
+
+    fn preset(name: &str) -> &'static SocialPreset {
+        PRESETS.iter().find(|preset| preset.name == name).unwrap()
+    }
+
+    fn command(preset: &SocialPreset, fit: FrameFit) -> Vec<String> {
+        ComprehensiveCommandBuilder::build_video_conversion_command("clip.mov", "clip.mp4", &preset.video_settings(fit), Some(&preset.audio_settings())).unwrap()
+    }
+
+    fn value_of<'a>(args: &'a [String], flag: &str) -> &'a str {
+        let at = args.iter().position(|arg| arg == flag).unwrap_or_else(|| panic!("no {flag} in {args:?}"));
+        &args[at + 1]
+    }
+
+    /// A phone recording stored landscape with a quarter-turn display matrix
+    fn rotated_phone_stream(rotation: i32) -> VideoStreamInfo {
+        serde_json::from_value(serde_json::json!({
+            "index": 0, "codec": "hevc", "width": 1920, "height": 1080, "fps": 30.0,
+            "color_range": "tv", "color_space": "bt709", "pix_fmt": "yuv420p", "rotation": rotation,
+        })).unwrap()
+    }
+
+    #[test]
+    fn landscape_sources_pad_into_vertical_and_square_and_crop_into_widescreen() {
+        assert_eq!(preset("TikTok 9:16").auto_fit(LANDSCAPE), FrameFit::Pad);
+        assert_eq!(preset("YouTube Shorts 9:16").auto_fit(LANDSCAPE), FrameFit::Pad);
+        assert_eq!(preset("Instagram 1:1").auto_fit(LANDSCAPE), FrameFit::Pad);
+        assert_eq!(preset("YouTube 16:9").auto_fit(LANDSCAPE), FrameFit::Crop);
+        // 4:3 loses a quarter to a 16:9 crop, still worth it; a cinema scope frame into 1:1 is not
+        assert_eq!(preset("YouTube 16:9").auto_fit((1440, 1080)), FrameFit::Crop);
+        assert_eq!(preset("Instagram 1:1").auto_fit((2560, 1080)), FrameFit::Pad);
+    }
+
+    #[test]
+    fn portrait_sources_crop_into_vertical_and_pad_elsewhere() {
+        assert_eq!(preset("Instagram Reels 9:16").auto_fit(PORTRAIT), FrameFit::Crop);
+        // A 2:3 phone photo slideshow still fills 9:16 with a small crop
+        assert_eq!(preset("Instagram Reels 9:16").auto_fit((720, 1080)), FrameFit::Crop);
+        assert_eq!(preset("Instagram 1:1").auto_fit(PORTRAIT), FrameFit::Pad);
+        assert_eq!(preset("YouTube 16:9").auto_fit(PORTRAIT), FrameFit::Pad);
+    }
+
+    #[test]
+    fn square_sources_crop_only_into_square() {
+        assert_eq!(preset("Instagram 1:1").auto_fit(SQUARE), FrameFit::Crop);
+        assert_eq!(preset("TikTok 9:16").auto_fit(SQUARE), FrameFit::Pad);
+        assert_eq!(preset("YouTube 16:9").auto_fit(SQUARE), FrameFit::Pad);
+        assert!((crop_keep(SQUARE, PORTRAIT) - 0.5625).abs() < 1e-6);
+        assert!((crop_keep(SQUARE, LANDSCAPE) - crop_keep(LANDSCAPE, SQUARE)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotated_phone_recordings_fit_by_their_display_size() {
+        for rotation in [90, 270] {
+            let stream = rotated_phone_stream(rotation);
+            assert_eq!(stream.display_size(), PORTRAIT, "{rotation}");
+            assert_eq!(preset("TikTok 9:16").auto_fit(stream.display_size()), FrameFit::Crop);
+        }
+        let upside_down = rotated_phone_stream(180);
+        assert_eq!(upside_down.display_size(), LANDSCAPE);
+        assert_eq!(preset("TikTok 9:16").auto_fit(upside_down.display_size()), FrameFit::Pad);
+    }
+
+    #[test]
+    fn unknown_sizes_keep_the_whole_picture() {
+        assert_eq!(crop_keep((0, 0), PORTRAIT), 1.0);
+        assert_eq!(crop_keep(LANDSCAPE, (0, 1920)), 1.0);
+    }
+
+    #[test]
+    fn landscape_into_vertical_pads_to_the_exact_upload_size() {
+        let tiktok = preset("TikTok 9:16");
+        let args = command(tiktok, tiktok.auto_fit(LANDSCAPE));
+        let filters = value_of(&args, "-vf");
+        assert!(filters.contains("scale=1080:1920:force_original_aspect_ratio=decrease,pad=1080:1920:(ow-iw)/2:(oh-ih)/2,setsar=1"), "{filters}");
+        assert_eq!(value_of(&args, "-c:v"), "libx264");
+        assert_eq!(value_of(&args, "-profile:v"), "high");
+        assert_eq!(value_of(&args, "-pix_fmt"), "yuv420p");
+        assert_eq!(value_of(&args, "-c:a"), "aac");
+        assert_eq!(value_of(&args, "-b:a"), "128k");
+        assert!(value_of(&args, "-movflags").contains("faststart"));
+        // The executor appends the custom args after the built command
+        assert_eq!(tiktok.video_settings(FrameFit::Pad).custom_args, "-maxrate 8M -bufsize 16M -metadata:s:v:0 rotate=0");
+    }
+
+    #[test]
+    fn square_and_widescreen_crop_to_the_exact_upload_size() {
+        let square = preset("Instagram 1:1");
+        let filters = value_of(&command(square, square.auto_fit(SQUARE)), "-vf").to_string();
+        assert!(filters.contains("scale=1080:1080:force_original_aspect_ratio=increase,crop=1080:1080,setsar=1"), "{filters}");
+
+        let youtube = preset("YouTube 16:9");
+        let args = command(youtube, youtube.auto_fit(LANDSCAPE));
+        assert!(value_of(&args, "-vf").contains("crop=1920:1080"));
+        assert!(youtube.video_settings(FrameFit::Crop).custom_args.starts_with("-maxrate 12M -bufsize 24M"));
+        assert_eq!(value_of(&args, "-b:a"), "192k");
+    }
+
+    #[test]
+    fn duration_limits_and_their_labels() {
+        let reels = preset("Instagram Reels 9:16");
+        assert_eq!(reels.exceeded_limit(90.0), None);
+        assert_eq!(reels.exceeded_limit(90.5), Some(90));
+        assert_eq!(format_limit(60), "60s");
+        assert_eq!(format_limit(90), "90s");
+        assert_eq!(format_limit(600), "10 min");
+        assert_eq!(format_limit(150), "150s");
+        assert_eq!(preset("TikTok 9:16").encoding_preset(FrameFit::Pad).description, "1080x1920 H.264 capped at 8M, up to 10 min");
+    }
+
+    #[test]
+    fn every_preset_is_offered_as_a_padding_builtin() {
+        let builtin = crate::preset_manager::PresetManager::get_builtin_presets();
+        for social in PRESETS {
+            let found = builtin.iter().find(|preset| preset.name == social.name).unwrap_or_else(|| panic!("{} missing", social.name));
+            assert!(matches!(found.category, PresetCategory::SocialMedia));
+            assert_eq!(found.video_settings.frame_fit, Some(FrameFit::Pad));
+            assert_eq!((found.video_settings.width, found.video_settings.height), (Some(social.size.0), Some(social.size.1)));
+        }
+    }
+}
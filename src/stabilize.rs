@@ -0,0 +1,72 @@
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// vid.stab stabilization: vidstabdetect writes the camera motion to a transforms file in a first
+/// pass, vidstabtransform smooths it out in a second pass
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StabilizeSettings {
+    /// Frames before and after each frame averaged into the camera path (0 = static camera)
+    pub smoothing: u32,
+    /// Extra zoom in percent; negative values zoom out
+    pub zoom: f32,
+    /// Sharpen the result, countering the blur of the transform's interpolation
+    pub optblur: bool,
+}
+
+impl Default for StabilizeSettings {
+    fn default() -> Self {
+        Self { smoothing: 10, zoom: 0.0, optblur: false }
+    }
+}
+
+impl StabilizeSettings {
+    /// First pass: analyse only, writing the transforms to `transforms_file`
+    pub fn detect_args(input_file: &str, transforms_file: &str) -> Vec<String> {
+        let null_device = if cfg!(target_os = "windows") { "NUL" } else { "/dev/null" };
+        let filter = format!("vidstabdetect=result={}", crate::font_resolver::filter_path(std::path::Path::new(transforms_file)));
+        ["-hide_banner", "-nostdin", "-i", input_file, "-an", "-vf", &filter, "-f", "null", "-y", null_device]
+            .map(String::from)
+            .to_vec()
+    }
+
+    /// Second pass: apply the smoothed transforms, keeping the audio as it is
+    pub fn transform_args(&self, input_file: &str, output_file: &str, transforms_file: &str) -> Vec<String> {
+        let mut filter = format!(
+            "vidstabtransform=input={}:smoothing={}:zoom={}",
+            crate::font_resolver::filter_path(std::path::Path::new(transforms_file)), self.smoothing, self.zoom
+        );
+        if self.optblur {
+            filter.push_str(",unsharp=5:5:0.8:3:3:0.4");
+        }
+        ["-hide_banner", "-nostdin", "-i", input_file, "-vf", &filter, "-c:a", "copy", "-y", output_file]
+            .map(String::from)
+            .to_vec()
+    }
+}
+
+/// Whether the bundled ffmpeg has both vid.stab filters; builds without libvidstab lack them
+pub fn vidstab_available() -> bool {
+    crate::bundled_ffmpeg::has_filter("vidstabdetect") && crate::bundled_ffmpeg::has_filter("vidstabtransform")
+}
+
+/// Smoothing, zoom and sharpening. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut StabilizeSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    if !vidstab_available() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), translations.stabilize_unavailable());
+    }
+    ui.horizontal(|ui| {
+        ui.label(translations.stabilize_smoothing());
+        changed |= ui.add(egui::DragValue::new(&mut settings.smoothing).range(0..=100)).changed();
+    }).response.on_hover_text(translations.stabilize_smoothing_hint());
+    ui.horizontal(|ui| {
+        ui.label(translations.stabilize_zoom());
+        changed |= ui.add(egui::DragValue::new(&mut settings.zoom).range(-50.0..=50.0).speed(0.5).suffix(" %")).changed();
+    });
+    changed |= ui.checkbox(&mut settings.optblur, translations.stabilize_optblur()).changed();
+    ui.label(egui::RichText::new(translations.stabilize_two_pass_hint()).weak());
+
+    changed
+}
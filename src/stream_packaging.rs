@@ -0,0 +1,426 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Video choices: auto copies H.264/HEVC sources and encodes anything else to H.264
+pub const STREAM_VIDEO_CODECS: [&str; 4] = ["auto", "copy", "libx264", "libx265"];
+
+/// Audio choices: auto copies AAC sources and encodes anything else to AAC
+pub const STREAM_AUDIO_CODECS: [&str; 3] = ["auto", "copy", "aac"];
+
+/// Files of a MultiResOutput folder that are packaged as variants
+const VARIANT_EXTENSIONS: [&str; 5] = ["mp4", "mkv", "mov", "webm", "ts"];
+
+/// Audio bitrate when audio is encoded
+const AUDIO_BITRATE: &str = "128k";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    Hls,
+    Dash,
+}
+
+impl StreamFormat {
+    pub fn all() -> [StreamFormat; 2] {
+        [StreamFormat::Hls, StreamFormat::Dash]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            StreamFormat::Hls => "hls",
+            StreamFormat::Dash => "dash",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<StreamFormat> {
+        StreamFormat::all().into_iter().find(|format| format.key() == key.trim())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamFormat::Hls => "HLS",
+            StreamFormat::Dash => "DASH",
+        }
+    }
+}
+
+/// HLS playlist type: VOD playlists are complete, EVENT playlists only ever grow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistType {
+    Vod,
+    Event,
+}
+
+impl PlaylistType {
+    pub fn all() -> [PlaylistType; 2] {
+        [PlaylistType::Vod, PlaylistType::Event]
+    }
+
+    /// Stable key used in node parameters and as the -hls_playlist_type value
+    pub fn key(&self) -> &'static str {
+        match self {
+            PlaylistType::Vod => "vod",
+            PlaylistType::Event => "event",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<PlaylistType> {
+        PlaylistType::all().into_iter().find(|playlist| playlist.key() == key.trim())
+    }
+}
+
+/// One input to package, with what the probe found in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSource {
+    pub path: String,
+    /// Codec names of the first video and audio streams; None when there is none or the probe failed
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub height: u32,
+    pub video_kbps: Option<u64>,
+}
+
+impl StreamSource {
+    pub fn probe(path: &str) -> StreamSource {
+        let info = crate::probe_cache::probe_cache().get_file_info(path).ok();
+        let video = info.as_ref().and_then(|info| info.video_streams.first());
+        StreamSource {
+            path: path.to_string(),
+            video_codec: video.map(|stream| stream.codec.clone()),
+            audio_codec: info.as_ref().and_then(|info| info.audio_streams.first()).map(|stream| stream.codec.clone()),
+            height: video.map(|stream| stream.height).unwrap_or(0),
+            video_kbps: info.as_ref().and_then(crate::encode_advisor::source_video_kbps),
+        }
+    }
+
+    /// Without a probe the audio is assumed present and mapped optionally
+    fn has_audio(&self) -> bool {
+        self.audio_codec.is_some() || self.video_codec.is_none()
+    }
+}
+
+/// Video files in a MultiResOutput folder, largest first once probed
+pub fn variant_sources(directory: &str) -> Result<Vec<StreamSource>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(directory)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str())
+            .is_some_and(|ext| VARIANT_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+        .collect();
+    if files.is_empty() {
+        return Err(anyhow!("{} holds no video files to package", directory));
+    }
+    files.sort();
+    let mut sources: Vec<StreamSource> = files.iter().map(|file| StreamSource::probe(&file.display().to_string())).collect();
+    sources.sort_by_key(|source| std::cmp::Reverse(source.height));
+    Ok(sources)
+}
+
+/// Streaming output of a StreamPrep node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamSettings {
+    pub format: StreamFormat,
+    pub segment_seconds: f64,
+    pub playlist_type: PlaylistType,
+    /// DASH fragment length within a segment
+    pub fragment_seconds: f64,
+    pub video_codec: String,
+    pub audio_codec: String,
+    /// Playlist or manifest name without extension; segments are named after it
+    pub name: String,
+    /// Folder for the playlists and segments; empty uses the workflow's scratch folder
+    pub output_dir: String,
+}
+
+impl Default for StreamSettings {
+    fn default() -> Self {
+        Self {
+            format: StreamFormat::Hls,
+            segment_seconds: 6.0,
+            playlist_type: PlaylistType::Vod,
+            fragment_seconds: 2.0,
+            video_codec: "auto".to_string(),
+            audio_codec: "auto".to_string(),
+            name: "stream".to_string(),
+            output_dir: String::new(),
+        }
+    }
+}
+
+impl StreamSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.segment_seconds.is_finite() && self.segment_seconds >= 1.0) {
+            return Err(anyhow!("Segments must be at least 1 second long"));
+        }
+        if self.format == StreamFormat::Dash
+            && !(self.fragment_seconds > 0.0 && self.fragment_seconds <= self.segment_seconds) {
+            return Err(anyhow!("Fragments must be longer than 0 and no longer than a segment ({}s)", self.segment_seconds));
+        }
+        let name = self.name.trim();
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(anyhow!("The playlist name must be a file name without folders"));
+        }
+        Ok(())
+    }
+
+    /// The encoder for the video of `source`, "copy" when it is kept as is
+    fn video_encoder(&self, source: &StreamSource) -> String {
+        match self.video_codec.as_str() {
+            "auto" => match source.video_codec.as_deref().and_then(crate::encode_advisor::codec_family) {
+                Some("h264" | "hevc") => "copy".to_string(),
+                _ => "libx264".to_string(),
+            },
+            codec => codec.to_string(),
+        }
+    }
+
+    fn audio_encoder(&self, source: &StreamSource) -> String {
+        match self.audio_codec.as_str() {
+            "auto" => match source.audio_codec.as_deref().and_then(crate::encode_advisor::codec_family) {
+                Some("aac") => "copy".to_string(),
+                _ => "aac".to_string(),
+            },
+            codec => codec.to_string(),
+        }
+    }
+
+    /// Video codec family the output of `source` ends up with, None when unknown
+    fn output_video_family(&self, source: &StreamSource) -> Option<&'static str> {
+        match self.video_encoder(source).as_str() {
+            "copy" => source.video_codec.as_deref().and_then(crate::encode_advisor::codec_family),
+            encoder => crate::encode_advisor::codec_family(encoder),
+        }
+    }
+
+    fn output_audio_family(&self, source: &StreamSource) -> Option<&'static str> {
+        match self.audio_encoder(source).as_str() {
+            "copy" => source.audio_codec.as_deref().and_then(crate::encode_advisor::codec_family),
+            encoder => crate::encode_advisor::codec_family(encoder),
+        }
+    }
+
+    /// Streams that players may refuse: video other than H.264/HEVC and audio other than AAC
+    pub fn compatibility_warnings(&self, sources: &[StreamSource]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for source in sources {
+            let file = Path::new(&source.path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            if let Some(codec) = source.video_codec.as_deref() {
+                if !matches!(self.output_video_family(source), Some("h264" | "hevc")) {
+                    warnings.push(format!("{}: {} video is copied, but {} players expect H.264 or HEVC", file, codec, self.format.name()));
+                }
+            }
+            if let Some(codec) = source.audio_codec.as_deref() {
+                if self.output_audio_family(source) != Some("aac") {
+                    warnings.push(format!("{}: {} audio is copied, but {} players expect AAC", file, codec, self.format.name()));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// The playlist (HLS, the master playlist for several variants) or manifest (DASH) to play
+    pub fn manifest_path(&self, output_dir: &str) -> PathBuf {
+        let extension = match self.format {
+            StreamFormat::Hls => "m3u8",
+            StreamFormat::Dash => "mpd",
+        };
+        Path::new(output_dir).join(format!("{}.{}", self.name.trim(), extension))
+    }
+
+    /// Inputs, stream maps and codec options shared by both formats. Every source contributes its
+    /// video; audio comes from every source for HLS variants and from the first one for DASH.
+    fn encoding_args(&self, sources: &[StreamSource], audio_sources: &[usize]) -> Vec<String> {
+        let mut args: Vec<String> = ["-hide_banner", "-nostdin"].map(String::from).to_vec();
+        for source in sources {
+            args.extend(["-i".to_string(), source.path.clone()]);
+        }
+        for (i, _) in sources.iter().enumerate() {
+            args.extend(["-map".to_string(), format!("{}:v:0", i)]);
+            if audio_sources.contains(&i) {
+                args.extend(["-map".to_string(), format!("{}:a:0?", i)]);
+            }
+        }
+
+        let keyframes = format!("expr:gte(t,n_forced*{})", self.segment_seconds);
+        for (i, source) in sources.iter().enumerate() {
+            let encoder = self.video_encoder(source);
+            args.extend([format!("-c:v:{}", i), encoder.clone()]);
+            if encoder != "copy" {
+                // Segments can only start on a keyframe
+                args.extend([format!("-force_key_frames:v:{}", i), keyframes.clone()]);
+                args.extend([format!("-pix_fmt:v:{}", i), "yuv420p".to_string()]);
+                // Master playlists need every variant's bandwidth
+                if sources.len() > 1 {
+                    if let Some(kbps) = source.video_kbps {
+                        args.extend([format!("-b:v:{}", i), format!("{}k", kbps)]);
+                    }
+                }
+            }
+            if self.output_video_family(source) == Some("hevc") {
+                args.extend([format!("-tag:v:{}", i), "hvc1".to_string()]);
+            }
+        }
+        for (k, i) in audio_sources.iter().enumerate() {
+            let encoder = self.audio_encoder(&sources[*i]);
+            args.extend([format!("-c:a:{}", k), encoder.clone()]);
+            if encoder != "copy" {
+                args.extend([format!("-b:a:{}", k), AUDIO_BITRATE.to_string()]);
+            }
+        }
+        args
+    }
+
+    /// One ffmpeg run writing the playlists or manifest and all segments into `output_dir`.
+    /// Several sources become variants of one adaptive stream.
+    pub fn args(&self, sources: &[StreamSource], output_dir: &str) -> Result<Vec<String>> {
+        self.validate()?;
+        if sources.is_empty() {
+            return Err(anyhow!("There is nothing to package"));
+        }
+        let name = self.name.trim();
+        let dir = Path::new(output_dir);
+        let path = |file: String| dir.join(file).display().to_string();
+
+        match self.format {
+            StreamFormat::Hls => {
+                let audio_sources: Vec<usize> = (0..sources.len()).filter(|i| sources[*i].has_audio()).collect();
+                let mut args = self.encoding_args(sources, &audio_sources);
+                let fmp4 = sources.iter().any(|source| self.output_video_family(source) == Some("hevc"));
+                let segment_extension = if fmp4 { "m4s" } else { "ts" };
+                args.extend([
+                    "-f".to_string(), "hls".to_string(),
+                    "-hls_time".to_string(), self.segment_seconds.to_string(),
+                    "-hls_playlist_type".to_string(), self.playlist_type.key().to_string(),
+                    "-hls_flags".to_string(), "independent_segments".to_string(),
+                ]);
+                // HEVC segments go in fragmented MP4, which Apple players and hls.js require for it
+                if fmp4 {
+                    args.extend(["-hls_segment_type".to_string(), "fmp4".to_string()]);
+                }
+                if sources.len() == 1 {
+                    if fmp4 {
+                        args.extend(["-hls_fmp4_init_filename".to_string(), format!("{}_init.mp4", name)]);
+                    }
+                    args.extend([
+                        "-hls_segment_filename".to_string(), path(format!("{}_%05d.{}", name, segment_extension)),
+                        "-y".to_string(), self.manifest_path(output_dir).display().to_string(),
+                    ]);
+                } else {
+                    let mut audio_index = 0;
+                    let stream_map: Vec<String> = (0..sources.len()).map(|i| {
+                        if audio_sources.contains(&i) {
+                            audio_index += 1;
+                            format!("v:{},a:{}", i, audio_index - 1)
+                        } else {
+                            format!("v:{}", i)
+                        }
+                    }).collect();
+                    if fmp4 {
+                        args.extend(["-hls_fmp4_init_filename".to_string(), format!("{}_%v_init.mp4", name)]);
+                    }
+                    args.extend([
+                        "-var_stream_map".to_string(), stream_map.join(" "),
+                        "-master_pl_name".to_string(), format!("{}.m3u8", name),
+                        "-hls_segment_filename".to_string(), path(format!("{}_%v_%05d.{}", name, segment_extension)),
+                        "-y".to_string(), path(format!("{}_%v.m3u8", name)),
+                    ]);
+                }
+                Ok(args)
+            }
+            StreamFormat::Dash => {
+                let audio_sources: Vec<usize> = sources.iter().position(StreamSource::has_audio).into_iter().collect();
+                let mut args = self.encoding_args(sources, &audio_sources);
+                let adaptation_sets = if audio_sources.is_empty() { "id=0,streams=v" } else { "id=0,streams=v id=1,streams=a" };
+                args.extend([
+                    "-f".to_string(), "dash".to_string(),
+                    "-seg_duration".to_string(), self.segment_seconds.to_string(),
+                    "-frag_type".to_string(), "duration".to_string(),
+                    "-frag_duration".to_string(), self.fragment_seconds.to_string(),
+                    "-use_template".to_string(), "1".to_string(),
+                    "-use_timeline".to_string(), "1".to_string(),
+                    "-adaptation_sets".to_string(), adaptation_sets.to_string(),
+                    "-init_seg_name".to_string(), format!("{}_init_$RepresentationID$.$ext$", name),
+                    "-media_seg_name".to_string(), format!("{}_$RepresentationID$_$Number%05d$.$ext$", name),
+                    "-y".to_string(), self.manifest_path(output_dir).display().to_string(),
+                ]);
+                Ok(args)
+            }
+        }
+    }
+}
+
+/// Format, segment timing, codecs, name and folder. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut StreamSettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_format());
+        for format in StreamFormat::all() {
+            changed |= ui.radio_value(&mut settings.format, format, format.name()).changed();
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_segment());
+        changed |= ui.add(egui::DragValue::new(&mut settings.segment_seconds).range(1.0..=60.0).speed(0.5).suffix(" s")).changed();
+        match settings.format {
+            StreamFormat::Hls => {
+                ui.label(translations.stream_packaging_playlist_type());
+                for playlist in PlaylistType::all() {
+                    let label = match playlist {
+                        PlaylistType::Vod => translations.stream_packaging_vod(),
+                        PlaylistType::Event => translations.stream_packaging_event(),
+                    };
+                    changed |= ui.radio_value(&mut settings.playlist_type, playlist, label).changed();
+                }
+            }
+            StreamFormat::Dash => {
+                ui.label(translations.stream_packaging_fragment());
+                changed |= ui.add(egui::DragValue::new(&mut settings.fragment_seconds).range(0.1..=60.0).speed(0.1).suffix(" s")).changed();
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_video_codec());
+        egui::ComboBox::from_id_salt("stream_prep_video_codec")
+            .selected_text(&settings.video_codec)
+            .show_ui(ui, |ui| {
+                for codec in STREAM_VIDEO_CODECS {
+                    changed |= ui.selectable_value(&mut settings.video_codec, codec.to_string(), codec).changed();
+                }
+            });
+        ui.label(translations.stream_packaging_audio_codec());
+        egui::ComboBox::from_id_salt("stream_prep_audio_codec")
+            .selected_text(&settings.audio_codec)
+            .show_ui(ui, |ui| {
+                for codec in STREAM_AUDIO_CODECS {
+                    changed |= ui.selectable_value(&mut settings.audio_codec, codec.to_string(), codec).changed();
+                }
+            });
+    }).response.on_hover_text(translations.stream_packaging_codec_hint());
+    if settings.video_codec == "copy" || settings.audio_codec == "copy" {
+        ui.colored_label(egui::Color32::from_rgb(255, 200, 100), translations.stream_packaging_copy_warning());
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(translations.stream_packaging_name());
+        changed |= ui.add(egui::TextEdit::singleline(&mut settings.name).desired_width(120.0)).changed();
+        ui.weak(settings.manifest_path("").display().to_string());
+    });
+    changed |= crate::image_sequence::show_folder_row(ui, translations.frame_extract_output_dir(), &mut settings.output_dir,
+        translations.image_sequence_scratch_dir(), translations);
+    ui.weak(translations.stream_packaging_variants_hint());
+
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
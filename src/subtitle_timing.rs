@@ -0,0 +1,298 @@
+use anyhow::Result;
+
+/// Shift and stretch every timestamp in an SRT, WebVTT or ASS document.
+/// New time = old time * stretch + offset, clamped at zero. BOMs and CRLF line
+/// endings are tolerated and cues don't need to be separated by blank lines,
+/// since timing lines are recognised by their own content. Other lines pass through unchanged.
+pub fn shift_subtitle_text(content: &str, offset_ms: i64, stretch: f64) -> String {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut output = String::with_capacity(content.len());
+
+    for raw_line in content.split('\n') {
+        let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let shifted = if line.contains("-->") {
+            shift_cue_timing_line(line, offset_ms, stretch)
+        } else if line.starts_with("Dialogue:") {
+            shift_ass_dialogue_line(line, offset_ms, stretch)
+        } else {
+            None
+        };
+        output.push_str(shifted.as_deref().unwrap_or(line));
+        output.push('\n');
+    }
+
+    // Every item got a newline appended, including the last one
+    output.pop();
+    output
+}
+
+/// Write a shifted copy of the subtitle file to the temp directory.
+/// Returns the original path when no adjustment is requested.
+pub fn prepare_shifted_subtitle(subtitle_file: &str, offset_ms: i64, stretch: f64) -> Result<String> {
+    if offset_ms == 0 && (stretch - 1.0).abs() < f64::EPSILON {
+        return Ok(subtitle_file.to_string());
+    }
+    if stretch <= 0.0 {
+        return Err(anyhow::anyhow!("Invalid subtitle stretch factor: {}", stretch));
+    }
+
+    let bytes = std::fs::read(subtitle_file)?;
+    let content = String::from_utf8_lossy(&bytes);
+    let shifted = shift_subtitle_text(&content, offset_ms, stretch);
+
+    let path = std::path::Path::new(subtitle_file);
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("srt");
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("subtitle");
+    let output = crate::temp_files::scratch_path(&format!("{}_shifted.{}", stem, extension))
+        .display().to_string();
+    std::fs::write(&output, shifted)?;
+    log_info!("Subtitle timing shifted by {}ms (x{:.4}) -> {}", offset_ms, stretch, output);
+    Ok(output)
+}
+
+/// Common framerate stretch presets: (label, factor)
+pub fn stretch_presets() -> Vec<(&'static str, f64)> {
+    vec![
+        ("1.0", 1.0),
+        ("23.976 → 25", 23.976 / 25.0),
+        ("25 → 23.976", 25.0 / 23.976),
+        ("24 → 25", 24.0 / 25.0),
+        ("25 → 24", 25.0 / 24.0),
+    ]
+}
+
+fn apply(ms: i64, offset_ms: i64, stretch: f64) -> i64 {
+    ((ms as f64 * stretch).round() as i64 + offset_ms).max(0)
+}
+
+/// "00:00:01,000 --> 00:00:04,000 [cue settings]"
+fn shift_cue_timing_line(line: &str, offset_ms: i64, stretch: f64) -> Option<String> {
+    let (left, right) = line.split_once("-->")?;
+    let start = left.trim();
+    let right = right.trim_start();
+    let end_len = right.find(char::is_whitespace).unwrap_or(right.len());
+    let (end, settings) = right.split_at(end_len);
+
+    let start_ms = parse_cue_timestamp(start)?;
+    let end_ms = parse_cue_timestamp(end)?;
+    Some(format!(
+        "{} --> {}{}",
+        format_cue_timestamp(apply(start_ms, offset_ms, stretch), start),
+        format_cue_timestamp(apply(end_ms, offset_ms, stretch), end),
+        settings
+    ))
+}
+
+/// Accepts HH:MM:SS,mmm (SRT), HH:MM:SS.mmm and MM:SS.mmm (WebVTT)
+fn parse_cue_timestamp(timestamp: &str) -> Option<i64> {
+    let (clock, fraction) = timestamp.rsplit_once([',', '.'])?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (h.trim().parse::<i64>().ok()?, m.parse::<i64>().ok()?, s.parse::<i64>().ok()?),
+        [m, s] => (0, m.trim().parse::<i64>().ok()?, s.parse::<i64>().ok()?),
+        _ => return None,
+    };
+    // Some files use fewer than three fraction digits
+    let fraction = fraction.trim();
+    let millis = match fraction.len() {
+        1 => fraction.parse::<i64>().ok()? * 100,
+        2 => fraction.parse::<i64>().ok()? * 10,
+        3 => fraction.parse::<i64>().ok()?,
+        _ => fraction.get(..3)?.parse::<i64>().ok()?,
+    };
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+/// Format like the original timestamp (same decimal separator, hours kept if present)
+fn format_cue_timestamp(ms: i64, original: &str) -> String {
+    let separator = if original.contains(',') { ',' } else { '.' };
+    let hours = ms / 3_600_000;
+    let minutes = (ms % 3_600_000) / 60_000;
+    let seconds = (ms % 60_000) / 1000;
+    let millis = ms % 1000;
+    if original.matches(':').count() >= 2 || hours > 0 {
+        format!("{:02}:{:02}:{:02}{}{:03}", hours, minutes, seconds, separator, millis)
+    } else {
+        format!("{:02}:{:02}{}{:03}", minutes, seconds, separator, millis)
+    }
+}
+
+/// "Dialogue: 0,0:00:01.00,0:00:04.00,Default,..."
+fn shift_ass_dialogue_line(line: &str, offset_ms: i64, stretch: f64) -> Option<String> {
+    let mut fields: Vec<String> = line.splitn(4, ',').map(|s| s.to_string()).collect();
+    if fields.len() < 3 {
+        return None;
+    }
+    for field in fields.iter_mut().skip(1).take(2) {
+        let ms = parse_ass_timestamp(field)?;
+        *field = format_ass_timestamp(apply(ms, offset_ms, stretch));
+    }
+    Some(fields.join(","))
+}
+
+/// ASS uses H:MM:SS.cc (centiseconds)
+fn parse_ass_timestamp(timestamp: &str) -> Option<i64> {
+    let (clock, centis) = timestamp.trim().split_once('.')?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let hours = parts[0].parse::<i64>().ok()?;
+    let minutes = parts[1].parse::<i64>().ok()?;
+    let seconds = parts[2].parse::<i64>().ok()?;
+    let centis = centis.get(..2).unwrap_or(centis).parse::<i64>().ok()?;
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + centis * 10)
+}
+
+fn format_ass_timestamp(ms: i64) -> String {
+    let centis = (ms + 5) / 10;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        centis / 360_000,
+        (centis % 360_000) / 6000,
+        (centis % 6000) / 100,
+        centis % 100
+    )
+}
+
+/// Render one frame of `video_file` at `time` with the subtitle burned in, for checking the alignment
+pub fn render_preview_frame(video_file: &str, subtitle_file: &str, time: f64) -> Result<egui::ColorImage> {
+    let ffmpeg = crate::bundled_ffmpeg::get_bundled_ffmpeg()?;
+    let escaped = subtitle_file.replace('\\', "\\\\").replace(':', "\\:").replace('\'', "\\'");
+    let filter = format!("subtitles='{}',scale=480:-2", escaped);
+    let time_arg = format!("{:.3}", time);
+    // Seek after the input so the subtitle filter sees the original timestamps
+    let output = ffmpeg.run_ffmpeg(&[
+        "-v", "error", "-i", video_file, "-ss", &time_arg,
+        "-vf", &filter, "-frames:v", "1",
+        "-f", "image2pipe", "-vcodec", "png", "-",
+    ])?;
+    let image = image::load_from_memory(&output.stdout)?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\n00:00:01,000 --> 00:00:04,000\nHello\n\n2\n00:00:05,500 --> 00:00:07,250\nWorld\n";
+
+    #[test]
+    fn srt_offset_and_stretch() {
+        assert_eq!(
+            shift_subtitle_text(SRT, 1500, 1.0),
+            "1\n00:00:02,500 --> 00:00:05,500\nHello\n\n2\n00:00:07,000 --> 00:00:08,750\nWorld\n",
+        );
+        assert_eq!(
+            shift_subtitle_text(SRT, 0, 2.0),
+            "1\n00:00:02,000 --> 00:00:08,000\nHello\n\n2\n00:00:11,000 --> 00:00:14,500\nWorld\n",
+        );
+        // Stretch applies before the offset
+        assert!(shift_subtitle_text(SRT, 1000, 2.0).contains("00:00:03,000 --> 00:00:09,000"));
+        // Unchanged timing gives back the same document
+        assert_eq!(shift_subtitle_text(SRT, 0, 1.0), SRT);
+    }
+
+    #[test]
+    fn negative_results_clamp_at_zero() {
+        assert!(shift_subtitle_text(SRT, -2000, 1.0).contains("00:00:00,000 --> 00:00:02,000"));
+        assert!(shift_subtitle_text(SRT, -3_600_000, 1.0).contains("00:00:00,000 --> 00:00:00,000"));
+    }
+
+    #[test]
+    fn bom_and_crlf_are_tolerated() {
+        let crlf = format!("\u{feff}{}", SRT.replace('\n', "\r\n"));
+        let shifted = shift_subtitle_text(&crlf, 1000, 1.0);
+        assert!(!shifted.starts_with('\u{feff}'));
+        assert!(!shifted.contains('\r'));
+        assert_eq!(shifted, shift_subtitle_text(SRT, 1000, 1.0));
+        // A BOM right before the first timing line does not hide it
+        assert_eq!(shift_subtitle_text("\u{feff}00:00:01,000 --> 00:00:02,000", 1000, 1.0), "00:00:02,000 --> 00:00:03,000");
+    }
+
+    #[test]
+    fn cues_without_blank_lines_or_with_short_fractions() {
+        let packed = "1\n00:00:01,000 --> 00:00:02,000\nA\n2\n00:00:03,000 --> 00:00:04,000\nB";
+        assert_eq!(
+            shift_subtitle_text(packed, 500, 1.0),
+            "1\n00:00:01,500 --> 00:00:02,500\nA\n2\n00:00:03,500 --> 00:00:04,500\nB",
+        );
+        assert_eq!(shift_subtitle_text("00:00:01,5 --> 00:00:02,25", 0, 1.0), "00:00:01,500 --> 00:00:02,250");
+        assert_eq!(shift_subtitle_text("00:00:01,5000 --> 00:00:02,000", 0, 1.0), "00:00:01,500 --> 00:00:02,000");
+        // Extra spacing around the arrow is normalized
+        assert_eq!(shift_subtitle_text("00:00:01,000-->00:00:02,000", 0, 1.0), "00:00:01,000 --> 00:00:02,000");
+    }
+
+    #[test]
+    fn malformed_cues_pass_through() {
+        let malformed = [
+            "00:00:01,000 --> later",
+            "00:00:01 --> 00:00:02",
+            "1:2:3:4,000 --> 00:00:02,000",
+            "aa:bb:cc,ddd --> 00:00:02,000",
+            "00:00:01,000 -->",
+            "She said --> go",
+            "-->",
+        ];
+        for line in malformed {
+            assert_eq!(shift_subtitle_text(line, 1000, 1.0), line);
+        }
+        // A broken cue does not stop the rest of the file from being shifted
+        let mixed = "00:00:01,000 --> later\nText\n00:00:03,000 --> 00:00:04,000\nMore";
+        assert_eq!(shift_subtitle_text(mixed, 1000, 1.0), "00:00:01,000 --> later\nText\n00:00:04,000 --> 00:00:05,000\nMore");
+    }
+
+    #[test]
+    fn webvtt_keeps_its_format_and_cue_settings() {
+        let vtt = "WEBVTT\n\n00:01.000 --> 00:04.000 align:start position:10%\nHi\n\n01:00:00.000 --> 01:00:01.000\nLater";
+        assert_eq!(
+            shift_subtitle_text(vtt, 500, 1.0),
+            "WEBVTT\n\n00:01.500 --> 00:04.500 align:start position:10%\nHi\n\n01:00:00.500 --> 01:00:01.500\nLater",
+        );
+        // Short timestamps grow an hour field once they pass an hour
+        assert_eq!(shift_subtitle_text("59:59.500 --> 59:59.900", 1000, 1.0), "01:00:00.500 --> 01:00:00.900");
+    }
+
+    #[test]
+    fn ass_dialogue_lines() {
+        let ass = "[Events]\nFormat: Layer, Start, End, Style, Text\nDialogue: 0,0:00:01.00,0:00:04.50,Default,,0,0,0,,Hi, there";
+        assert_eq!(
+            shift_subtitle_text(ass, 1250, 1.0),
+            "[Events]\nFormat: Layer, Start, End, Style, Text\nDialogue: 0,0:00:02.25,0:00:05.75,Default,,0,0,0,,Hi, there",
+        );
+        for line in ["Dialogue: 0,bad,0:00:01.00,Default,,Text", "Dialogue: 0", "Dialogue: 0,0:00:01,0:00:02.00,x"] {
+            assert_eq!(shift_subtitle_text(line, 1000, 1.0), line);
+        }
+    }
+
+    #[test]
+    fn prepared_copy_is_written_only_when_needed() {
+        let dir = crate::test_support::temp_dir("subtitle");
+        let source = dir.join("movie.srt");
+        std::fs::write(&source, SRT).unwrap();
+        let source = source.display().to_string();
+
+        assert_eq!(prepare_shifted_subtitle(&source, 0, 1.0).unwrap(), source);
+        assert!(prepare_shifted_subtitle(&source, 0, 0.0).is_err());
+        assert!(prepare_shifted_subtitle(&dir.join("missing.srt").display().to_string(), 100, 1.0).is_err());
+
+        let shifted = prepare_shifted_subtitle(&source, 1500, 1.0).unwrap();
+        assert_ne!(shifted, source);
+        assert!(shifted.ends_with("movie_shifted.srt"));
+        assert_eq!(std::fs::read_to_string(&shifted).unwrap(), shift_subtitle_text(SRT, 1500, 1.0));
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), SRT);
+
+        let _ = std::fs::remove_file(&shifted);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stretch_presets_convert_between_frame_rates() {
+        let presets = stretch_presets();
+        assert_eq!(presets[0], ("1.0", 1.0));
+        for (label, factor) in &presets {
+            assert!(*factor > 0.9 && *factor < 1.1, "{label}");
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use std::process::Command;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::Duration;
 use crate::app_state::*;
@@ -7,6 +10,8 @@ use crate::codec_manager::*;
 use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
 use crate::comprehensive_codec_registry::CodecType;
 use crate::bundled_ffmpeg::{get_bundled_ffmpeg};
+use crate::destination::destination_volume;
+use crate::effective_codecs::{resolve_audio_codec, resolve_video_codec};
 use anyhow::Result;
 use crate::{log_debug, log_info, log_warn, log_error};
 
@@ -22,9 +27,488 @@ fn create_ffprobe_command() -> Result<Command> {
     Ok(bundled_ffmpeg.probe_command())
 }
 
+thread_local! {
+    /// ffmpeg runs of the task executing on this thread; each running task has its own thread
+    static COMMAND_LOG: RefCell<Vec<Vec<String>>> = const { RefCell::new(Vec::new()) };
+    /// Start ffmpeg below normal priority, set from the power constraints when a task starts
+    static LOW_PRIORITY_FFMPEG: Cell<bool> = const { Cell::new(false) };
+    /// Part of the task's progress bar the next ffmpeg run fills, as (start, share); tasks that
+    /// run ffmpeg more than once, like two-pass encodes, split the bar between the runs
+    static PROGRESS_SPAN: Cell<(f32, f32)> = const { Cell::new((0.0, 1.0)) };
+    /// Last stderr lines of the most recent successful ffmpeg run, for runs that print results
+    /// there (loudness measurement)
+    static LAST_STDERR: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Run `run` with its ffmpeg runs filling `share` of the progress bar from `start`
+fn with_progress_span<T>(start: f32, share: f32, run: impl FnOnce() -> T) -> T {
+    let previous = PROGRESS_SPAN.replace((start, share));
+    let result = run();
+    PROGRESS_SPAN.set(previous);
+    result
+}
+
+/// Remember a command's argv so the task can later be copied as a shell command
+fn record_command(cmd: &Command) {
+    let mut argv = vec![cmd.get_program().to_string_lossy().to_string()];
+    argv.extend(cmd.get_args().map(|arg| arg.to_string_lossy().to_string()));
+    COMMAND_LOG.with(|log| log.borrow_mut().push(argv));
+}
+
+/// Commands recorded since the last call
+fn take_command_log() -> Vec<Vec<String>> {
+    COMMAND_LOG.with(|log| std::mem::take(&mut *log.borrow_mut()))
+}
+
+fn take_last_stderr() -> Vec<String> {
+    LAST_STDERR.with(|tail| std::mem::take(&mut *tail.borrow_mut()))
+}
+
+/// An ffmpeg process the progress runner is waiting on
+#[derive(Clone, Copy, Debug)]
+struct RunningFfmpeg {
+    /// Queue task it runs for; None for workflow runs
+    task_id: Option<usize>,
+    /// Suspended by a pause
+    paused: bool,
+}
+
+lazy_static::lazy_static! {
+    /// ffmpeg processes the progress runners are waiting on, by pid
+    static ref RUNNING_FFMPEG: Mutex<HashMap<u32, RunningFfmpeg>> = Mutex::new(HashMap::new());
+}
+
+/// Removes a pid from RUNNING_FFMPEG however the run returns
+struct RunningPidGuard(u32);
+
+impl RunningPidGuard {
+    fn register(pid: u32, task_id: Option<usize>) -> Self {
+        RUNNING_FFMPEG.lock().unwrap().insert(pid, RunningFfmpeg { task_id, paused: false });
+        Self(pid)
+    }
+}
+
+impl Drop for RunningPidGuard {
+    fn drop(&mut self) {
+        RUNNING_FFMPEG.lock().unwrap().remove(&self.0);
+    }
+}
+
+/// Pids of the running ffmpeg processes matching `filter`. Taken under the lock and returned
+/// without it, so the slow kill and suspend commands never run while it is held.
+fn running_pids(filter: impl Fn(&RunningFfmpeg) -> bool) -> Vec<u32> {
+    RUNNING_FFMPEG.lock().unwrap().iter().filter(|(_, process)| filter(process)).map(|(pid, _)| *pid).collect()
+}
+
+/// Suspend (`suspend`) or resume a process: SIGSTOP/SIGCONT on Unix, NtSuspendProcess and
+/// NtResumeProcess on Windows. A suspended process keeps its memory and open files.
+fn signal_process(pid: u32, suspend: bool) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    let output = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let call = if suspend { "NtSuspendProcess" } else { "NtResumeProcess" };
+        let script = format!(
+            "$nt = Add-Type -Name Nt -Namespace FFGui -PassThru -MemberDefinition '[DllImport(\"ntdll.dll\")] public static extern int {call}(IntPtr handle);'; \
+             exit $nt::{call}((Get-Process -Id {pid}).Handle)"
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()?
+    };
+    #[cfg(not(target_os = "windows"))]
+    let output = Command::new("kill")
+        .args([if suspend { "-STOP" } else { "-CONT" }, &pid.to_string()])
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Suspend or resume the ffmpeg process of a running task. Between two ffmpeg runs of a task
+/// there is nothing to signal; the next run then starts suspended.
+fn set_ffmpeg_suspended(task_id: usize, suspend: bool) -> Result<()> {
+    for pid in running_pids(|process| process.task_id == Some(task_id)) {
+        signal_process(pid, suspend)?;
+        // The process may have exited while it was signalled
+        if let Some(process) = RUNNING_FFMPEG.lock().unwrap().get_mut(&pid) {
+            process.paused = suspend;
+        }
+    }
+    Ok(())
+}
+
+/// Bumped whenever the shared task list changes, so the UI only re-clones it when needed
+static TASKS_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Mark the shared task list as changed
+pub fn mark_tasks_dirty() {
+    TASKS_GENERATION.fetch_add(1, Ordering::Release);
+}
+
+/// Current generation of the shared task list
+pub fn tasks_generation() -> u64 {
+    TASKS_GENERATION.load(Ordering::Acquire)
+}
+
+/// A change the UI asks for on the shared queue. Actions address tasks by id and never replace the
+/// list, so progress and status written by the executor in the meantime are kept. The executor is
+/// the only writer of progress and of how a run ends; actions only move a task between states the
+/// executor never sets itself (Running and Paused, NeedsOutputRecovery back to Pending) or take it
+/// off the queue before it starts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskAction {
+    /// Drop a task that is not running
+    Remove(usize),
+    /// Stop a running or paused task, optionally deleting its partial output; a pending task
+    /// just leaves the queue
+    Cancel { id: usize, delete_partial: bool },
+    /// Suspend a running task's ffmpeg process
+    Pause(usize),
+    /// Continue a paused task
+    Resume(usize),
+    /// Swap a pending task with the pending task before it (`up`) or after it
+    Move { id: usize, up: bool },
+    /// Name shown in the task list; an empty label restores the output file name
+    Relabel { id: usize, label: String },
+    /// Post-completion checksum and sidecar of a pending task
+    SetChecksum { id: usize, requested: bool, write_sidecar: bool },
+    /// Stop hashing a completed task's output
+    CancelChecksum(usize),
+    /// Archive a pending task's output is packaged into; None packages nothing
+    SetArchive { id: usize, path: Option<String> },
+    /// Post-completion copy of the source's file times and recording date for a pending task
+    SetTimestamps { id: usize, copy_times: bool, write_creation_time: bool },
+    /// Drop completed and failed tasks
+    ClearFinished,
+    /// Replace a failed task with a fresh pending copy of it
+    Retry(usize),
+    /// Retry every failed task
+    RetryAllFailed,
+    /// Restart, resume or redirect a task whose output destination went away
+    RecoverOutput { id: usize, choice: crate::output_recovery::RecoveryChoice },
+}
+
+impl TaskAction {
+    /// Apply to the queue; returns whether anything changed. Targets that finished, started or
+    /// disappeared since the action was issued are left alone.
+    pub fn apply(&self, tasks: &mut Vec<ProcessingTask>) -> bool {
+        match self {
+            TaskAction::Remove(id) => {
+                let before = tasks.len();
+                if let Some(task) = tasks.iter().find(|t| t.id == *id && !t.is_started()) {
+                    task.checksum.cancel();
+                    crate::output_recovery::forget(task.id);
+                }
+                tasks.retain(|t| t.id != *id || t.is_started());
+                tasks.len() != before
+            }
+            TaskAction::Cancel { id, delete_partial } => match tasks.iter().position(|t| t.id == *id) {
+                Some(position) if tasks[position].status == TaskStatus::Pending => {
+                    let task = tasks.remove(position);
+                    task.checksum.cancel();
+                    true
+                }
+                Some(position) if tasks[position].is_started() && !tasks[position].cancel_requested => {
+                    tasks[position].cancel_requested = true;
+                    tasks[position].delete_partial_output = *delete_partial;
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::Pause(id) => match tasks.iter_mut().find(|t| t.id == *id) {
+                Some(task) if task.status == TaskStatus::Running && !task.cancel_requested => {
+                    task.status = TaskStatus::Paused;
+                    task.paused_at = Some(std::time::Instant::now());
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::Resume(id) => match tasks.iter_mut().find(|t| t.id == *id) {
+                Some(task) if task.status == TaskStatus::Paused => {
+                    // Elapsed time and the ETA leave the pause out
+                    if let (Some(start), Some(paused_at)) = (task.start_time, task.paused_at.take()) {
+                        task.start_time = Some(start + paused_at.elapsed());
+                    }
+                    task.status = TaskStatus::Running;
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::Move { id, up } => {
+                let pending: Vec<usize> = tasks.iter()
+                    .enumerate()
+                    .filter(|(_, t)| t.status == TaskStatus::Pending)
+                    .map(|(position, _)| position)
+                    .collect();
+                let Some(slot) = pending.iter().position(|&position| tasks[position].id == *id) else {
+                    return false;
+                };
+                let neighbour = if *up { slot.checked_sub(1) } else { Some(slot + 1) };
+                match neighbour.and_then(|other| pending.get(other)) {
+                    Some(&other) => {
+                        tasks.swap(pending[slot], other);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            TaskAction::Relabel { id, label } => {
+                let label = Some(label.trim()).filter(|label| !label.is_empty()).map(str::to_string);
+                match tasks.iter_mut().find(|t| t.id == *id) {
+                    Some(task) if task.label != label => {
+                        task.label = label;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            TaskAction::SetChecksum { id, requested, write_sidecar } => match tasks.iter_mut().find(|t| t.id == *id) {
+                Some(task) if task.status == TaskStatus::Pending => {
+                    task.checksum.requested = *requested;
+                    task.checksum.write_sidecar = *requested && *write_sidecar;
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::CancelChecksum(id) => tasks.iter().find(|t| t.id == *id).is_some_and(|task| task.checksum.cancel()),
+            TaskAction::SetTimestamps { id, copy_times, write_creation_time } => match tasks.iter_mut().find(|t| t.id == *id) {
+                Some(task) if task.status == TaskStatus::Pending => {
+                    task.timestamps.copy_times = *copy_times;
+                    task.timestamps.write_creation_time = *write_creation_time;
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::SetArchive { id, path } => match tasks.iter_mut().find(|t| t.id == *id) {
+                Some(task) if task.status == TaskStatus::Pending && task.archive != *path => {
+                    task.archive = path.clone();
+                    true
+                }
+                _ => false,
+            },
+            TaskAction::ClearFinished => {
+                let before = tasks.len();
+                // Tasks still hashing stay until their checksum is in
+                tasks.retain(|t| !matches!(t.status, TaskStatus::Completed | TaskStatus::Failed) || t.checksum.is_hashing());
+                tasks.len() != before
+            }
+            TaskAction::Retry(id) => Self::retry_failed(tasks, |task| task.id == *id),
+            TaskAction::RetryAllFailed => Self::retry_failed(tasks, |_| true),
+            TaskAction::RecoverOutput { id, choice } => {
+                use crate::output_recovery::RecoveryChoice;
+                let Some(task) = tasks.iter_mut().find(|t| t.id == *id && t.status == TaskStatus::NeedsOutputRecovery) else {
+                    return false;
+                };
+                crate::output_recovery::forget(*id);
+                let resume_at = match choice {
+                    RecoveryChoice::Resume(resume_at) => Some(*resume_at),
+                    RecoveryChoice::Restart => None,
+                    RecoveryChoice::Redirect(output_file) => {
+                        crate::output_recovery::discard_partial(&task.output_file);
+                        task.output_file = output_file.clone();
+                        None
+                    }
+                };
+                if let Some(settings) = task.video_settings.as_mut() {
+                    settings.resume_at = resume_at;
+                }
+                task.status = TaskStatus::Pending;
+                task.progress = 0.0;
+                task.error_message = None;
+                task.executed_commands.clear();
+                task.log.clear();
+                true
+            }
+        }
+    }
+
+    /// Replace the failed tasks matching `selected` with pending copies at the end of the queue.
+    /// A copy whose output a partial run left behind, or another task claims, writes to a new name.
+    fn retry_failed(tasks: &mut Vec<ProcessingTask>, selected: impl Fn(&ProcessingTask) -> bool) -> bool {
+        let (failed, kept): (Vec<ProcessingTask>, Vec<ProcessingTask>) = std::mem::take(tasks)
+            .into_iter()
+            .partition(|task| task.status == TaskStatus::Failed && selected(task));
+        *tasks = kept;
+        for task in &failed {
+            let mut retry = task.retry();
+            let claimed = TaskExecutor::claimed_output_paths(tasks);
+            retry.output_file = TaskExecutor::free_output_path(&retry.output_file, |candidate| {
+                claimed.contains(&output_path_key(candidate)) || std::path::Path::new(candidate).exists()
+            });
+            log_info!("Retrying task {} as task {} writing {}", task.id, retry.id, retry.output_file);
+            tasks.push(retry);
+        }
+        !failed.is_empty()
+    }
+}
+
+/// `path` as compared for output claims: absolute, with `.`, `..` and links in its folder resolved,
+/// and lowercase where file names are case-insensitive (Windows and macOS), so spellings of one
+/// file compare equal. A folder that does not exist yet is normalized by its components alone.
+fn output_path_key(path: &str) -> String {
+    let path = std::path::Path::new(path);
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = std::path::PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    let resolved = match (normalized.parent(), normalized.file_name()) {
+        (Some(parent), Some(name)) => std::fs::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or(normalized),
+        _ => normalized,
+    };
+    let key = resolved.display().to_string();
+    if cfg!(any(windows, target_os = "macos")) {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+/// Apply UI actions to the shared queue under one lock. Cancelling a running task kills its ffmpeg
+/// process; pausing and resuming signal it, and are undone when that fails. The processes are
+/// signalled after the lock is released, so the executor and the UI are not held up meanwhile.
+pub fn apply_task_actions(tasks: &Mutex<Vec<ProcessingTask>>, actions: &[TaskAction]) {
+    if actions.is_empty() {
+        return;
+    }
+    let Ok(mut tasks_guard) = tasks.lock() else {
+        return;
+    };
+    let mut changed = false;
+    let mut signals = Vec::new();
+    for action in actions {
+        if action.apply(&mut tasks_guard) {
+            changed = true;
+            match action {
+                TaskAction::Cancel { id, .. } if tasks_guard.iter().any(|t| t.id == *id && t.is_started()) => signals.push(action),
+                TaskAction::Pause(_) | TaskAction::Resume(_) => signals.push(action),
+                _ => {}
+            }
+        }
+    }
+    drop(tasks_guard);
+    if changed {
+        mark_tasks_dirty();
+    }
+
+    for action in signals {
+        match action {
+            TaskAction::Cancel { id, .. } => {
+                log_info!("🛑 Cancelling task {}", id);
+                kill_task_ffmpeg(*id);
+            }
+            TaskAction::Pause(id) | TaskAction::Resume(id) => {
+                let pause = matches!(action, TaskAction::Pause(_));
+                if let Err(e) = set_ffmpeg_suspended(*id, pause) {
+                    log_error!("Could not {} task {}: {}", if pause { "pause" } else { "resume" }, id, e);
+                    let undo = if pause { TaskAction::Resume(*id) } else { TaskAction::Pause(*id) };
+                    if tasks.lock().is_ok_and(|mut tasks_guard| undo.apply(&mut tasks_guard)) {
+                        mark_tasks_dirty();
+                    }
+                } else {
+                    log_info!("Task {} {}", id, if pause { "paused" } else { "resumed" });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Kill the ffmpeg processes of one task; other running tasks go on. Suspended processes can be
+/// killed as they are.
+fn kill_task_ffmpeg(task_id: usize) {
+    for pid in running_pids(|process| process.task_id == Some(task_id)) {
+        kill_process(pid);
+    }
+}
+
+/// Kill one process by pid, from any thread
+pub(crate) fn kill_process(pid: u32) {
+    let pid = pid.to_string();
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        Command::new("taskkill").args(["/F", "/PID", &pid]).creation_flags(CREATE_NO_WINDOW).output()
+    };
+    #[cfg(not(target_os = "windows"))]
+    let result = Command::new("kill").args(["-KILL", &pid]).output();
+    if let Err(e) = result {
+        log_warn!("Could not kill ffmpeg process {}: {}", pid, e);
+    }
+}
+
+/// Kill every ffmpeg process, including ones started outside the progress runner. A paused
+/// process is continued afterwards so it can act on the signal and exit.
+pub fn terminate_ffmpeg_processes() {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/F", "/IM", "ffmpeg.exe"])
+            .output();
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = Command::new("pkill")
+            .args(["-f", "ffmpeg"])
+            .output();
+    }
+    
+    for pid in running_pids(|process| process.paused) {
+        let _ = signal_process(pid, false);
+        if let Some(process) = RUNNING_FFMPEG.lock().unwrap().get_mut(&pid) {
+            process.paused = false;
+        }
+    }
+}
+
+/// How long an output written by our own tasks is ignored by watch folders
+const RECENT_OUTPUT_GRACE: Duration = Duration::from_secs(120);
+
+lazy_static::lazy_static! {
+    static ref RECENT_OUTPUTS: Mutex<HashMap<String, std::time::Instant>> = Mutex::new(HashMap::new());
+}
+
+/// Remember an output path written by the executor or a watch-folder workflow
+pub fn record_written_output(path: &str) {
+    if let Ok(mut outputs) = RECENT_OUTPUTS.lock() {
+        outputs.retain(|_, written| written.elapsed() < RECENT_OUTPUT_GRACE);
+        outputs.insert(path.to_string(), std::time::Instant::now());
+    }
+}
+
+/// Whether a path was written by one of our tasks within the grace period
+pub fn was_recently_written(path: &str) -> bool {
+    RECENT_OUTPUTS.lock()
+        .map(|outputs| outputs.get(path).map(|written| written.elapsed() < RECENT_OUTPUT_GRACE).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+/// Tasks run at once unless the user allows more
+pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 1;
+/// Upper end of the concurrency setting
+pub const MAX_CONCURRENT_TASKS_LIMIT: usize = 8;
+
 pub struct TaskExecutor {
     tasks: Arc<Mutex<Vec<ProcessingTask>>>,
     running: Arc<Mutex<bool>>,
+    max_tasks_per_destination: Arc<AtomicUsize>,
+    max_concurrent_tasks: Arc<AtomicUsize>,
+    power_constraints: Arc<Mutex<crate::power::PowerConstraints>>,
+    keep_existing_outputs: Arc<AtomicBool>,
 }
 
 impl TaskExecutor {
@@ -48,13 +532,16 @@ impl TaskExecutor {
                 // Update task progress
                 if let Ok(mut tasks_guard) = tasks.try_lock() {
                     if let Some(task) = tasks_guard.iter_mut().find(|t| t.id == task_id) {
-                        if task.status == TaskStatus::Cancelled {
+                        if task.cancel_requested {
                             break;
                         }
-                        if task.status != TaskStatus::Running {
-                            break; // Task completed, stop simulation
+                        match task.status {
+                            TaskStatus::Running => {}
+                            TaskStatus::Paused => continue,
+                            _ => break, // Task completed, stop simulation
                         }
                         task.progress = progress;
+                        mark_tasks_dirty();
                     }
                 } else {
                     continue;
@@ -81,6 +568,7 @@ impl TaskExecutor {
             // Medium operations (10-30 seconds base)  
             OperationType::AudioConvert => 15,
             OperationType::AudioMerge => 12,
+            OperationType::AudioNormalize => 25,
             OperationType::VideoAudioMerge => 18,
             OperationType::AudioResample => 20,
             
@@ -91,12 +579,15 @@ impl TaskExecutor {
             OperationType::VideoCrop => 40,
             OperationType::VideoRotate => 30,
             OperationType::VideoFilter => 50,
+            OperationType::CutAndJoin => 20,
+            OperationType::FrameRateConvert => 60,
             OperationType::AddSubtitle => 55,
             OperationType::AddWatermark => 50,
             OperationType::VideoToGif => 80,
             OperationType::FrameExtract => 25,
             OperationType::BatchConvert => 30,
             OperationType::GifResize => 25,
+            OperationType::ReviewClip => 20,
             OperationType::AudioCompress => 25,
         };
         
@@ -109,6 +600,20 @@ impl TaskExecutor {
         }
     }
     
+    /// Output path and container of a video conversion; a kept mismatched extension is written with the selected container
+    fn video_output_path_and_container(output_file: &str, video_codec: &str, video_settings: &VideoSettings) -> (String, String) {
+        if video_settings.keep_output_extension && crate::output_extension::muxer_name(&video_settings.container_format.to_lowercase()).is_some() {
+            return (output_file.to_string(), video_settings.container_format.to_lowercase());
+        }
+        let normalized_output = Self::normalize_output_path_with_container(output_file, video_codec, &video_settings.container_format, false);
+        let final_output_ext = std::path::Path::new(&normalized_output)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        (normalized_output, final_output_ext)
+    }
+
     fn normalize_output_path_with_container(output_path: &str, codec: &str, container_format: &str, is_audio: bool) -> String {
         let path = std::path::Path::new(output_path);
         
@@ -159,15 +664,90 @@ impl TaskExecutor {
     
     /// Execute FFmpeg command, suppress AAC warnings but retain error information
     fn execute_ffmpeg_command_with_progress(
-        mut cmd: Command, 
+        cmd: Command, 
         tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>,
         task_id: Option<usize>
+    ) -> Result<()> {
+        Self::run_ffmpeg_with_progress(cmd, tasks, task_id, None)
+    }
+
+    /// `cmd` with its arguments replaced by `args`
+    fn with_args(cmd: &Command, args: &[String]) -> Command {
+        let mut rebuilt = Command::new(cmd.get_program());
+        rebuilt.args(args);
+        for (key, value) in cmd.get_envs() {
+            match value {
+                Some(value) => rebuilt.env(key, value),
+                None => rebuilt.env_remove(key),
+            };
+        }
+        if let Some(dir) = cmd.get_current_dir() {
+            rebuilt.current_dir(dir);
+        }
+        rebuilt
+    }
+    
+    /// Progress from `reached` seconds of output out of `total`, and the time left at ffmpeg's
+    /// reported `speed`. Without a total the progress stays unknown and the list shows a spinner.
+    /// The run fills the `(start, share)` span of the bar; later runs are assumed to take as long.
+    fn report_progress(task: &mut ProcessingTask, reached: f32, total: Option<f32>, speed: Option<f32>, span: (f32, f32)) {
+        if task.cancel_requested {
+            return;
+        }
+        let (start, share) = span;
+        match total.filter(|total| *total > 0.0) {
+            Some(total) => {
+                task.progress = (start + share * (reached / total).clamp(0.0, 1.0)).clamp(0.0, 0.99);
+                task.progress_unknown = false;
+                let later_runs = (1.0 - start - share).max(0.0) / share.max(f32::EPSILON);
+                let remaining = speed.map(|speed| Duration::from_secs_f32(((total - reached).max(0.0) + later_runs * total) / speed));
+                task.estimated_total_time = remaining.map(|remaining| task.active_elapsed().unwrap_or_default() + remaining);
+            }
+            None => {
+                task.progress_unknown = true;
+                task.estimated_total_time = None;
+            }
+        }
+        mark_tasks_dirty();
+    }
+    
+    /// Like execute_ffmpeg_command_with_progress; with `expected_frames`, progress comes from the
+    /// "frame=" count of `-progress pipe:2` instead of the output time
+    fn run_ffmpeg_with_progress(
+        mut cmd: Command, 
+        tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>,
+        task_id: Option<usize>,
+        expected_frames: Option<u64>
     ) -> Result<()> {
         use std::io::{BufRead, BufReader};
         use std::process::Stdio;
         
+        // Output guards of the running task: -t for the duration, periodic size checks below
+        let guards = crate::output_guard::active();
+        let mut args: Vec<String> = cmd.get_args().map(|arg| arg.to_string_lossy().to_string()).collect();
+        // Outputs of watch-folder runs carry the tag the watcher skips
+        if let Some(tagged) = crate::watch_folder::tagged_output_args(&args) {
+            cmd = Self::with_args(&cmd, &tagged);
+            args = tagged;
+        }
+        if let Some(limit) = guards.as_ref().and_then(|guards| guards.max_duration_secs) {
+            cmd = Self::with_args(&cmd, &crate::output_guard::with_duration_limit(&args, limit));
+        }
+        let output_candidates = crate::output_guard::output_candidates(&args);
+        let span = PROGRESS_SPAN.get();
+        
         // Print the actual command being executed for debugging
         log_debug!("Executing FFmpeg command: {:?}", cmd);
+        record_command(&cmd);
+        
+        // Machine-readable progress on stdout, unless the command reports progress itself or
+        // writes its output there
+        let progress_pipe = !args.iter().any(|arg| arg == "-progress" || arg == "-" || arg.starts_with("pipe:"));
+        if progress_pipe {
+            let mut progress_args = vec!["-progress".to_string(), "pipe:1".to_string()];
+            progress_args.extend(cmd.get_args().map(|arg| arg.to_string_lossy().to_string()));
+            cmd = Self::with_args(&cmd, &progress_args);
+        }
         
         // Set output capture for real-time progress reading
         cmd.stdout(Stdio::piped());
@@ -187,6 +767,28 @@ impl TaskExecutor {
         cmd.env("FFMPEG_HIDE_BANNER", "1");
         
         let mut child = cmd.spawn()?;
+        let _running_pid = RunningPidGuard::register(child.id(), task_id);
+        let _cancel_watch = crate::run_cancel::watch_process(child.id());
+        // A task paused between two of its ffmpeg runs starts the next one suspended
+        if let (Some(tasks), Some(id)) = (&tasks, task_id) {
+            if tasks.lock().is_ok_and(|tasks_guard| tasks_guard.iter().any(|t| t.id == id && t.status == TaskStatus::Paused)) {
+                if let Err(e) = set_ffmpeg_suspended(id, true) {
+                    log_warn!("Could not start ffmpeg paused: {}", e);
+                }
+            }
+        }
+        if LOW_PRIORITY_FFMPEG.get() {
+            crate::power::lower_process_priority(child.id());
+        }
+        
+        // TS/VOB containers often report a wrong Duration line; prefer the analyzed duration
+        let corrected_duration = match (&tasks, task_id) {
+            (Some(tasks), Some(id)) => tasks.lock().ok()
+                .and_then(|tasks_guard| tasks_guard.iter().find(|t| t.id == id).and_then(|t| t.input_files.first().cloned()))
+                .and_then(|input| crate::transport_stream::corrected_duration(&input))
+                .map(|duration| duration as f32),
+            _ => None,
+        };
         
         // Get stderr for real-time progress reading
         if let Some(stderr) = child.stderr.take() {
@@ -194,18 +796,28 @@ impl TaskExecutor {
             let tasks_clone = tasks.clone();
             let error_lines = Arc::new(Mutex::new(Vec::new()));
             let error_lines_clone = error_lines.clone();
+            // Output time reached and input duration, for the duration guard
+            let timing: Arc<Mutex<(Option<f32>, Option<f32>)>> = Arc::new(Mutex::new((None, corrected_duration)));
+            let timing_clone = timing.clone();
             
             // Read FFmpeg output and update progress in new thread
             let handle = thread::spawn(move || {
-                let mut total_duration: Option<f32> = None;
+                let mut total_duration: Option<f32> = corrected_duration;
+                // Log lines not yet handed to the task
+                let mut pending_log = crate::task_log::TaskLog::default();
                 
                 for line in reader.lines() {
                     if let Ok(line) = line {
+                        if let (Some(tasks), Some(id)) = (&tasks_clone, task_id) {
+                            pending_log.push(line.clone());
+                            crate::task_log::flush(tasks, id, &mut pending_log, false);
+                        }
+                        
                         // Also check cancellation status in progress thread
                         if let (Some(tasks), Some(id)) = (&tasks_clone, task_id) {
                             if let Ok(tasks_guard) = tasks.try_lock() {
                                 if let Some(task) = tasks_guard.iter().find(|t| t.id == id) {
-                                    if task.status == TaskStatus::Cancelled {
+                                    if task.cancel_requested {
                                         log_debug!("Task {} cancelled in progress thread", id);
                                         break; // Exit reading loop
                                     }
@@ -226,44 +838,110 @@ impl TaskExecutor {
                         if line.contains("Duration:") && total_duration.is_none() {
                             if let Some(duration_str) = Self::extract_duration(&line) {
                                 total_duration = Self::parse_time_to_seconds(&duration_str);
+                                if let Ok(mut timing) = timing_clone.lock() {
+                                    timing.1 = total_duration;
+                                }
                             }
                         }
                         
-                        // Parse time progress line
-                        if line.contains("time=") {
-                            if let Some(time_str) = Self::extract_time_progress(&line) {
-                                if let Some(current_seconds) = Self::parse_time_to_seconds(&time_str) {
-                                    if let Some(total) = total_duration {
-                                        let progress = (current_seconds / total).min(0.99);
-                                        
-                                        // Update task progress
-                                        if let (Some(tasks), Some(id)) = (&tasks_clone, task_id) {
-                                            if let Ok(mut tasks_guard) = tasks.try_lock() {
-                                                if let Some(task) = tasks_guard.iter_mut().find(|t| t.id == id) {
-                                                    if task.status == TaskStatus::Cancelled {
-                                                        log_debug!("Task {} cancelled during progress update", id);
-                                                        break; // Exit loop, stop processing
-                                                    }
-                                                    
-                                                    task.progress = progress;
-                                                }
-                                            }
+                        if let Some(reached) = Self::extract_time_progress(&line).and_then(|time_str| Self::parse_time_to_seconds(&time_str)) {
+                            if let Ok(mut timing) = timing_clone.lock() {
+                                *timing = (Some(timing.0.map_or(reached, |previous| previous.max(reached))), total_duration);
+                            }
+                        }
+                        
+                        // Parse frame count or time progress line
+                        let progress = match expected_frames {
+                            Some(expected) => line.strip_prefix("frame=")
+                                .and_then(|count| count.trim().parse::<u64>().ok())
+                                .map(|count| (count as f32 / expected.max(1) as f32).min(0.99)),
+                            // With the progress pipe, the stdout reader reports progress instead
+                            None if line.contains("time=") && !progress_pipe => Self::extract_time_progress(&line)
+                                .and_then(|time_str| Self::parse_time_to_seconds(&time_str))
+                                .zip(total_duration)
+                                .map(|(current_seconds, total)| (current_seconds / total).min(0.99)),
+                            None => None,
+                        };
+                        if let Some(progress) = progress {
+                            // Update task progress
+                            if let (Some(tasks), Some(id)) = (&tasks_clone, task_id) {
+                                if let Ok(mut tasks_guard) = tasks.try_lock() {
+                                    if let Some(task) = tasks_guard.iter_mut().find(|t| t.id == id) {
+                                        if task.cancel_requested {
+                                            log_debug!("Task {} cancelled during progress update", id);
+                                            break; // Exit loop, stop processing
                                         }
+                                        
+                                        task.progress = (span.0 + span.1 * progress).min(0.99);
+                                        mark_tasks_dirty();
                                     }
                                 }
                             }
                         }
                     }
                 }
+                if let (Some(tasks), Some(id)) = (&tasks_clone, task_id) {
+                    crate::task_log::flush(tasks, id, &mut pending_log, true);
+                }
+            });
+            
+            // `-progress` blocks end with a progress= line; each one updates the task
+            let progress_handle = child.stdout.take().filter(|_| progress_pipe).map(|stdout| {
+                let tasks = tasks.clone();
+                let timing = timing.clone();
+                thread::spawn(move || {
+                    let mut speed = None;
+                    for line in BufReader::new(stdout).lines().map_while(std::result::Result::ok) {
+                        let Some((key, value)) = line.split_once('=') else {
+                            continue;
+                        };
+                        let value = value.trim();
+                        match key.trim() {
+                            // Both are in microseconds; out_time_ms is misnamed
+                            "out_time_us" | "out_time_ms" => {
+                                if let Ok(micros) = value.parse::<i64>() {
+                                    if let Ok(mut timing) = timing.lock() {
+                                        let reached = micros.max(0) as f32 / 1_000_000.0;
+                                        timing.0 = Some(timing.0.map_or(reached, |previous| previous.max(reached)));
+                                    }
+                                }
+                            }
+                            "speed" => speed = value.trim_end_matches('x').trim().parse::<f32>().ok().filter(|speed| *speed > 0.0),
+                            "progress" => {
+                                let (reached, total) = timing.lock().map(|timing| *timing).unwrap_or_default();
+                                if let (Some(tasks), Some(id), Some(reached)) = (&tasks, task_id, reached) {
+                                    if let Some(task) = tasks.lock().unwrap().iter_mut().find(|t| t.id == id) {
+                                        Self::report_progress(task, reached, total, speed, span);
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                })
             });
             
             // Wait for process completion while checking cancellation status
+            let mut last_guard_check = std::time::Instant::now();
             let status = loop {
+                // Output guards, polled rather than checked on every progress line
+                if let Some(guards) = guards.as_ref().filter(|_| last_guard_check.elapsed() >= crate::output_guard::POLL_INTERVAL) {
+                    last_guard_check = std::time::Instant::now();
+                    let reached = timing.lock().ok().and_then(|timing| timing.0).map(f64::from);
+                    if let Some(trip) = guards.check(crate::output_guard::written_bytes(&output_candidates), reached) {
+                        log_warn!("{}", trip);
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        handle.join().unwrap_or_default();
+                        return Err(anyhow::Error::new(trip));
+                    }
+                }
+                
                 // Check if task was cancelled
                 if let (Some(tasks), Some(id)) = (&tasks, task_id) {
                     if let Ok(tasks_guard) = tasks.try_lock() {
                         if let Some(task) = tasks_guard.iter().find(|t| t.id == id) {
-                            if task.status == TaskStatus::Cancelled {
+                            if task.cancel_requested {
                                 log_debug!("Task {} was cancelled, terminating FFmpeg process", id);
                                 // Force terminate FFmpeg process
                                 let _ = child.kill();
@@ -290,6 +968,9 @@ impl TaskExecutor {
                 }
             };
             handle.join().unwrap_or_default();
+            if let Some(progress_handle) = progress_handle {
+                progress_handle.join().unwrap_or_default();
+            }
             
             if !status.success() {
                 // Get error output with improved formatting
@@ -303,7 +984,8 @@ impl TaskExecutor {
                                 let line_lower = line.to_lowercase();
                                 line_lower.contains("error") || line_lower.contains("failed") || 
                                 line_lower.contains("impossible") || line_lower.contains("not implemented") ||
-                                line_lower.contains("invalid") || line_lower.contains("no such")
+                                line_lower.contains("invalid") || line_lower.contains("no such") ||
+                                line_lower.contains("no space left")
                             })
                             .take(3) // Limit to first 3 critical errors
                             .map(|line| {
@@ -332,6 +1014,28 @@ impl TaskExecutor {
                 
                 return Err(anyhow::anyhow!("FFmpeg execution failed: {}", error_output));
             }
+            
+            if let Ok(lines) = error_lines.lock() {
+                LAST_STDERR.with(|tail| *tail.borrow_mut() = lines.clone());
+            }
+            
+            // -t ends the run normally; an output cut off at the limit still fails the task
+            if let Some(guards) = guards.as_ref().filter(|guards| guards.max_duration_secs.is_some()) {
+                let (reached, input) = timing.lock().map(|timing| *timing).unwrap_or_default();
+                // Runs with -nostats print no time; the output's own duration tells instead
+                let reached = reached.map(f64::from).or_else(|| output_candidates.iter()
+                    .filter(|path| path.is_file())
+                    .find_map(|path| {
+                        let path = path.display().to_string();
+                        let duration = crate::probe_cache::probe_cache().get_file_info(&path).ok().map(|info| info.duration);
+                        crate::probe_cache::probe_cache().invalidate(&path);
+                        duration
+                    }));
+                if let Some(trip) = guards.cut_by_duration(reached, input.map(f64::from)) {
+                    log_warn!("{}", trip);
+                    return Err(anyhow::Error::new(trip));
+                }
+            }
         } else {
             // If stderr cannot be obtained, fallback to waiting for process completion
             let output = child.wait_with_output()?;
@@ -383,6 +1087,11 @@ impl TaskExecutor {
     }
     /// Generate preview FFmpeg command without execution
     pub fn preview_command(task: &ProcessingTask) -> Result<String> {
+        if Self::needs_fade_duration(task) {
+            let mut task = task.clone();
+            Self::resolve_fade_duration(&mut task)?;
+            return Self::preview_command(&task);
+        }
         match task.operation {
             OperationType::VideoConvert => Self::preview_video_convert(task),
             OperationType::AudioConvert => Self::preview_audio_convert(task),
@@ -398,26 +1107,170 @@ impl TaskExecutor {
             OperationType::VideoCrop => Self::preview_video_crop(task),
             OperationType::VideoRotate => Self::preview_video_rotate(task),
             OperationType::VideoFilter => Self::preview_video_filter(task),
+            OperationType::CutAndJoin => Self::preview_cut_and_join(task),
+            OperationType::FrameRateConvert => Self::preview_frame_rate_convert(task),
             OperationType::AudioTrim => Self::preview_audio_trim(task),
             OperationType::AudioMerge => Self::preview_audio_merge(task),
+            OperationType::AudioNormalize => Self::preview_audio_normalize(task),
             OperationType::BatchConvert => Self::preview_batch_convert(task),
             OperationType::AddSubtitle => Self::preview_add_subtitle(task),
             OperationType::AddWatermark => Self::preview_add_watermark(task),
             OperationType::FrameExtract => Self::preview_frame_extract(task),
             OperationType::VideoToGif => Self::preview_video_to_gif(task),
             OperationType::GifResize => Self::preview_gif_resize(task),
+            OperationType::ReviewClip => Self::preview_review_clip(task),
         }
     }
+    /// Output paths claimed by Pending/Running/Paused tasks, as `output_path_key`s; terminal
+    /// tasks release their claim
+    fn claimed_output_paths(tasks: &[ProcessingTask]) -> HashSet<String> {
+        tasks.iter()
+            .filter(|t| t.status == TaskStatus::Pending || t.is_started())
+            .map(|t| output_path_key(&t.output_file))
+            .collect()
+    }
+
+    /// Pick a path that doesn't collide with any claimed output by appending a counter to the stem.
+    /// With `keep_existing` an existing file is not replaced either.
+    fn unclaimed_output_path(path: &str, claimed: &HashSet<String>, keep_existing: bool) -> String {
+        Self::free_output_path(path, |candidate| {
+            claimed.contains(&output_path_key(candidate)) || (keep_existing && std::path::Path::new(candidate).exists())
+        })
+    }
+
+    /// `path`, or the first `<stem>_<n>` variant of it that is not `taken`
+    fn free_output_path(path: &str, taken: impl Fn(&str) -> bool) -> String {
+        if !taken(path) {
+            return path.to_string();
+        }
+        let original = std::path::Path::new(path);
+        let stem = original.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output");
+        let extension = original.extension().and_then(|ext| ext.to_str());
+        let mut counter = 1;
+        loop {
+            let file_name = match extension {
+                Some(ext) => format!("{}_{}.{}", stem, counter, ext),
+                None => format!("{}_{}", stem, counter),
+            };
+            let candidate = original.with_file_name(file_name).display().to_string();
+            if !taken(&candidate) {
+                return candidate;
+            }
+            counter += 1;
+        }
+    }
+
+    /// Add a task to the queue, renaming its output if another queued or running task already claims it,
+    /// or, when existing outputs are kept, if the file exists. A claimed path is renamed whatever the
+    /// overwrite setting, since two tasks writing one file at once would interleave their writes.
+    /// Returns the renamed output path if a collision was resolved.
+    pub fn enqueue_task(&self, mut task: ProcessingTask) -> Option<String> {
+        let mut tasks_guard = self.tasks.lock().unwrap();
+        let claimed = Self::claimed_output_paths(&tasks_guard);
+        let unclaimed = Self::unclaimed_output_path(&task.output_file, &claimed, self.keep_existing_outputs.load(Ordering::Relaxed));
+        let renamed = if unclaimed != task.output_file {
+            log_warn!("Output path {} is claimed by another task or exists, using {}", task.output_file, unclaimed);
+            task.output_file = unclaimed.clone();
+            Some(unclaimed)
+        } else {
+            None
+        };
+        
+        log_info!("Adding task {} to queue", task.id);
+        tasks_guard.push(task);
+        mark_tasks_dirty();
+        log_info!("Task queue now has {} tasks", tasks_guard.len());
+        renamed
+    }
+
     pub fn new(tasks: Arc<Mutex<Vec<ProcessingTask>>>) -> Self {
         Self {
             tasks,
             running: Arc::new(Mutex::new(false)),
+            max_tasks_per_destination: Arc::new(AtomicUsize::new(0)),
+            max_concurrent_tasks: Arc::new(AtomicUsize::new(DEFAULT_MAX_CONCURRENT_TASKS)),
+            power_constraints: Arc::new(Mutex::new(crate::power::PowerConstraints::default())),
+            keep_existing_outputs: Arc::new(AtomicBool::new(false)),
         }
     }
+    
+    /// Limit simultaneous tasks writing to the same volume (0 = unlimited)
+    pub fn set_max_tasks_per_destination(&self, limit: usize) {
+        self.max_tasks_per_destination.store(limit, Ordering::Relaxed);
+    }
+    
+    /// Give new tasks a free name instead of replacing an existing output file
+    pub fn set_keep_existing_outputs(&self, keep: bool) {
+        self.keep_existing_outputs.store(keep, Ordering::Relaxed);
+    }
+    
+    /// Limit tasks running at once (at least 1); tasks already running are not stopped
+    pub fn set_max_concurrent_tasks(&self, limit: usize) {
+        self.max_concurrent_tasks.store(limit.max(1), Ordering::Relaxed);
+    }
+    
+    /// Power-aware limits; they apply to tasks started from now on, a running task only gets its priority lowered
+    pub fn set_power_constraints(&self, constraints: crate::power::PowerConstraints) {
+        *self.power_constraints.lock().unwrap() = constraints;
+        if constraints.lower_priority {
+            for pid in running_pids(|_| true) {
+                crate::power::lower_process_priority(pid);
+            }
+        }
+    }
+    
+    /// Mark pending tasks with a cancel request as Cancelled; returns whether any were
+    fn settle_cancelled_pending(tasks: &mut [ProcessingTask]) -> bool {
+        let mut settled = false;
+        for task in tasks.iter_mut().filter(|t| t.status == TaskStatus::Pending && t.cancel_requested) {
+            task.status = TaskStatus::Cancelled;
+            settled = true;
+        }
+        settled
+    }
+    
+    /// Index of the first pending task whose destination volume still has a free slot;
+    /// low-priority tasks only when no normal task qualifies
+    fn next_schedulable_task(tasks: &[ProcessingTask], max_per_destination: usize) -> Option<usize> {
+        let mut running_per_volume: HashMap<String, usize> = HashMap::new();
+        if max_per_destination > 0 {
+            for task in tasks.iter().filter(|t| t.is_started()) {
+                *running_per_volume.entry(destination_volume(&task.output_file)).or_insert(0) += 1;
+            }
+        }
+        
+        let schedulable = |task: &ProcessingTask| {
+            task.status == TaskStatus::Pending
+                && (max_per_destination == 0
+                    || running_per_volume.get(&destination_volume(&task.output_file)).copied().unwrap_or(0) < max_per_destination)
+        };
+        tasks.iter().position(|task| schedulable(task) && !task.low_priority)
+            .or_else(|| tasks.iter().position(schedulable))
+    }
+
+    /// Mark the next schedulable task Running and return a copy of it, unless `max_concurrent`
+    /// tasks are started already
+    fn claim_next_task(tasks: &mut [ProcessingTask], max_concurrent: usize, max_per_destination: usize) -> Option<ProcessingTask> {
+        let started = tasks.iter().filter(|t| t.is_started()).count();
+        if started >= max_concurrent.max(1) {
+            return None;
+        }
+        let index = Self::next_schedulable_task(tasks, max_per_destination)?;
+        tasks[index].status = TaskStatus::Running;
+        tasks[index].progress = 0.0;
+        tasks[index].start_time = Some(std::time::Instant::now());
+        mark_tasks_dirty();
+        Some(tasks[index].clone())
+    }
 
     pub fn start(&self) {
         let tasks = self.tasks.clone();
         let running = self.running.clone();
+        let max_tasks_per_destination = self.max_tasks_per_destination.clone();
+        let max_concurrent_tasks = self.max_concurrent_tasks.clone();
+        let power_constraints = self.power_constraints.clone();
         
         // Set running status
         *running.lock().unwrap() = true;
@@ -429,62 +1282,33 @@ impl TaskExecutor {
                     break;
                 }
                 
-                // Find pending tasks
-                let task_to_process = {
+                // Pending tasks the user cancelled never start
+                {
                     let mut tasks_guard = tasks.lock().unwrap();
-                    let pending_task_index = tasks_guard.iter()
-                        .position(|task| task.status == TaskStatus::Pending);
-                    
-                    if let Some(index) = pending_task_index {
-                        // Set task status to running
-                        tasks_guard[index].status = TaskStatus::Running;
-                        tasks_guard[index].progress = 0.0;
-                        tasks_guard[index].start_time = Some(std::time::Instant::now());
-                        
-                        
-                        Some(tasks_guard[index].clone())
-                    } else {
-                        None
+                    if Self::settle_cancelled_pending(&mut tasks_guard) {
+                        mark_tasks_dirty();
                     }
-                };
+                }
                 
-                if let Some(mut task) = task_to_process {
-                    let task_id = task.id;
-                    
-                    // Check if task was cancelled before execution
-                    {
-                        let tasks_guard = tasks.lock().unwrap();
-                        if let Some(task_in_list) = tasks_guard.iter().find(|t| t.id == task_id) {
-                            if task_in_list.status == TaskStatus::Cancelled {
-                                log_debug!("Task {} was cancelled before execution", task_id);
-                                continue;
-                            }
-                        }
-                    }
-                    
-                    // Execute task with progress update
-                    let tasks_for_progress = tasks.clone();
-                    let result = Self::execute_task_with_progress(&mut task, tasks_for_progress);
-                    
-                    // Update task status
-                    let mut tasks_guard = tasks.lock().unwrap();
-                    if let Some(task_in_list) = tasks_guard.iter_mut().find(|t| t.id == task_id) {
-                        match result {
-                            Ok(()) => {
-                                task_in_list.status = TaskStatus::Completed;
-                                task_in_list.progress = 1.0;
-                                task_in_list.error_message = None;
-                                // Store completion time when task completes
-                                if let Some(start_time) = task_in_list.start_time {
-                                    task_in_list.completion_time = Some(start_time.elapsed());
-                                }
-                            }
-                            Err(e) => {
-                                task_in_list.status = TaskStatus::Failed;
-                                task_in_list.error_message = Some(e.to_string());
-                            }
-                        }
-                    }
+                // On battery with the pause rule, or in a paused schedule window, nothing new starts
+                let constraints = *power_constraints.lock().unwrap();
+                if constraints.pause_queue {
+                    thread::sleep(Duration::from_millis(500));
+                    continue;
+                }
+                
+                // Find pending tasks; the limit is read on every pickup, so a change applies to the next one
+                let task_to_process = Self::claim_next_task(
+                    &mut tasks.lock().unwrap(),
+                    max_concurrent_tasks.load(Ordering::Relaxed),
+                    max_tasks_per_destination.load(Ordering::Relaxed),
+                );
+                
+                // Each task runs on its own thread; the loop goes on to fill the remaining slots
+                if let Some(task) = task_to_process {
+                    let tasks = tasks.clone();
+                    thread::spawn(move || Self::run_task(tasks, task, constraints));
+                    continue;
                 }
                 
                 // Brief sleep to avoid excessive CPU usage
@@ -493,28 +1317,141 @@ impl TaskExecutor {
         });
     }
     
+    /// Run one task that the scheduler marked Running, on its own thread, and record how it ended
+    fn run_task(tasks: Arc<Mutex<Vec<ProcessingTask>>>, mut task: ProcessingTask, constraints: crate::power::PowerConstraints) {
+        let task_id = task.id;
+        crate::audio_preview::stop_if_playing(&task.output_file);
+        
+        LOW_PRIORITY_FFMPEG.set(constraints.lower_priority || task.low_priority);
+        if let Some(video_settings) = task.video_settings.as_mut() {
+            video_settings.max_threads = constraints.max_threads;
+            if let Some(software) = crate::hardware_refresh::software_encoder(&video_settings.codec).filter(|_| constraints.software_only) {
+                log_info!("Task {}: hardware encoders are not allowed right now, using {} instead of {}", task_id, software, video_settings.codec);
+                video_settings.codec = software.to_string();
+                video_settings.use_hardware_acceleration = false;
+                video_settings.preset = "auto".to_string();
+            }
+        }
+        
+        // Record the environment outside the lock; the ffmpeg version probe is cached per binary
+        let environment = crate::environment::EnvironmentSnapshot::capture(&task);
+        task.environment = Some(environment.clone());
+        if let Some(task_in_list) = tasks.lock().unwrap().iter_mut().find(|t| t.id == task_id) {
+            task_in_list.environment = Some(environment);
+            mark_tasks_dirty();
+        }
+        
+        // Check if task was cancelled before execution
+        {
+            let mut tasks_guard = tasks.lock().unwrap();
+            if let Some(task_in_list) = tasks_guard.iter_mut().find(|t| t.id == task_id) {
+                if task_in_list.cancel_requested {
+                    log_debug!("Task {} was cancelled before execution", task_id);
+                    task_in_list.status = TaskStatus::Cancelled;
+                    mark_tasks_dirty();
+                    return;
+                }
+            }
+        }
+        
+        // Execute task with progress update
+        let tasks_for_progress = tasks.clone();
+        take_command_log();
+        crate::output_guard::set_active(Some(task.guards.limits.clone()));
+        let mut result = Self::execute_task_with_progress(&mut task, tasks_for_progress);
+        crate::output_guard::set_active(None);
+        // Batch conversions apply the timestamps per file as each one finishes
+        if result.is_ok() && task.timestamps.requested() && task.operation != OperationType::BatchConvert {
+            if let Some(input) = task.input_files.first() {
+                let warnings = crate::file_times::apply(std::path::Path::new(input), std::path::Path::new(&task.output_file), &task.timestamps);
+                task.timestamps.warnings.extend(warnings);
+            }
+        }
+        // Packaging runs while the task still shows as running, with its own progress
+        if let (Ok(()), Some(archive_path)) = (&result, task.archive.as_deref()) {
+            if let Err(e) = crate::archive::package_task(&task, archive_path) {
+                result = Err(anyhow::anyhow!("Packaging {} failed: {}", archive_path, e));
+            }
+        }
+        
+        // Update task status
+        let mut tasks_guard = tasks.lock().unwrap();
+        if let Some(task_in_list) = tasks_guard.iter_mut().find(|t| t.id == task_id) {
+            task_in_list.paused_at = None;
+            task_in_list.executed_commands = take_command_log();
+            task_in_list.timestamps.warnings = std::mem::take(&mut task.timestamps.warnings);
+            Self::finish_task(&tasks, task_in_list, result);
+        }
+        mark_tasks_dirty();
+    }
+    
+    /// Status of a task whose run ended with `result`
+    fn finish_task(tasks: &Arc<Mutex<Vec<ProcessingTask>>>, task_in_list: &mut ProcessingTask, result: Result<()>) {
+        let task_id = task_in_list.id;
+        match result {
+            _ if task_in_list.cancel_requested => {
+                task_in_list.status = TaskStatus::Cancelled;
+                if task_in_list.delete_partial_output {
+                    Self::delete_partial_output(&task_in_list.output_file);
+                }
+            }
+            Ok(()) => {
+                record_written_output(&task_in_list.output_file);
+                crate::probe_cache::probe_cache().invalidate(&task_in_list.output_file);
+                let target_size_mb = task_in_list.video_settings.as_ref().map_or(0, |video| video.target_size_mb);
+                if target_size_mb > 0 {
+                    task_in_list.size_outcome = crate::size_target::SizeOutcome::measure(&task_in_list.output_file, target_size_mb);
+                }
+                task_in_list.status = TaskStatus::Completed;
+                task_in_list.progress = 1.0;
+                task_in_list.error_message = None;
+                // Store completion time when task completes
+                if let Some(start_time) = task_in_list.start_time {
+                    task_in_list.completion_time = Some(start_time.elapsed());
+                }
+                // The task is done; the checksum follows as a sub-status
+                if task_in_list.checksum.requested {
+                    crate::checksum::start_task_checksum(tasks, task_in_list);
+                }
+            }
+            // A guard stopped the run on purpose; resuming it would only trip the guard again
+            Err(e) if e.downcast_ref::<crate::output_guard::GuardTrip>().is_none()
+                && crate::output_recovery::is_output_failure(&e.to_string(), &task_in_list.output_file) => {
+                log_warn!("Task {} lost its output destination: {}", task_id, e);
+                task_in_list.status = TaskStatus::NeedsOutputRecovery;
+                task_in_list.error_message = Some(e.to_string());
+                crate::output_recovery::watch(task_id, task_in_list.output_file.clone(), crate::output_recovery::resume_support(task_in_list));
+            }
+            Err(e) => {
+                task_in_list.guards.tripped = e.downcast_ref::<crate::output_guard::GuardTrip>().cloned();
+                task_in_list.status = TaskStatus::Failed;
+                task_in_list.error_message = Some(e.to_string());
+            }
+        }
+    }
+    
     pub fn stop(&self) {
         *self.running.lock().unwrap() = false;
         self.terminate_all_ffmpeg_processes();
     }
     
-    /// Terminate all FFmpeg processes
-    pub fn terminate_all_ffmpeg_processes(&self) {
-        #[cfg(target_os = "windows")]
-        {
-            let _ = Command::new("taskkill")
-                .args(&["/F", "/IM", "ffmpeg.exe"])
-                .output();
+    /// Remove the partial output of a cancelled task
+    fn delete_partial_output(output_file: &str) {
+        let path = std::path::Path::new(output_file);
+        if !path.is_file() {
+            return;
         }
-        
-        #[cfg(not(target_os = "windows"))]
-        {
-            let _ = Command::new("pkill")
-                .args(&["-f", "ffmpeg"])
-                .output();
+        match std::fs::remove_file(path) {
+            Ok(()) => log_info!("Deleted partial output {}", output_file),
+            Err(e) => log_warn!("Could not delete partial output {}: {}", output_file, e),
         }
     }
     
+    /// Terminate all FFmpeg processes
+    pub fn terminate_all_ffmpeg_processes(&self) {
+        terminate_ffmpeg_processes();
+    }
+    
     /// Public method to execute a single task directly (for workflow use)
     pub fn execute_task(task: &mut ProcessingTask) -> Result<()> {
         // Create dummy params for the internal method
@@ -522,7 +1459,8 @@ impl TaskExecutor {
         Self::execute_task_with_shared_progress(task, dummy_tasks, task.id)
     }
 
-    fn execute_task_with_progress(task: &mut ProcessingTask, tasks: Arc<Mutex<Vec<ProcessingTask>>>) -> Result<()> {
+    /// Run `task` here and now, writing its progress into its copy in `tasks`
+    pub fn execute_task_with_progress(task: &mut ProcessingTask, tasks: Arc<Mutex<Vec<ProcessingTask>>>) -> Result<()> {
         // No longer start old time estimation progress thread, directly use FFmpeg real-time progress
         let task_id = task.id;
         
@@ -535,16 +1473,27 @@ impl TaskExecutor {
         tasks: Arc<Mutex<Vec<ProcessingTask>>>,
         task_id: usize
     ) -> Result<()> {
+        // Auxiliary files of this run go in a private directory, removed when the run ends
+        let _scratch = crate::temp_files::registry().enter_task(task_id);
+        
         // Check if task was cancelled before starting
         {
             let tasks_guard = tasks.lock().unwrap();
             if let Some(task_in_list) = tasks_guard.iter().find(|t| t.id == task_id) {
-                if task_in_list.status == TaskStatus::Cancelled {
+                if task_in_list.cancel_requested {
                     return Err(anyhow::anyhow!("Task was cancelled"));
                 }
             }
         }
         
+        // Remote inputs are fetched into the download cache first; the queued task keeps the URL
+        let cancelled = || tasks.lock().unwrap().iter().any(|t| t.id == task_id && t.cancel_requested);
+        crate::download_cache::localize_inputs(&mut task.input_files, task_id, &cancelled)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if Self::needs_fade_duration(task) {
+            Self::resolve_fade_duration(task)?;
+        }
+        
         // Pass tasks and task_id to enable real FFmpeg progress tracking
         match task.operation {
             OperationType::VideoConvert | OperationType::VideoCompress | OperationType::VideoResize => {
@@ -560,14 +1509,18 @@ impl TaskExecutor {
             OperationType::VideoCrop => Self::execute_video_crop_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::VideoRotate => Self::execute_video_rotate_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::VideoFilter => Self::execute_video_filter_with_progress(task, Some(tasks), Some(task_id)),
+            OperationType::CutAndJoin => Self::execute_cut_and_join_with_progress(task, Some(tasks), Some(task_id)),
+            OperationType::FrameRateConvert => Self::execute_frame_rate_convert(task, Some(tasks), Some(task_id)),
             OperationType::AudioTrim => Self::execute_audio_trim_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::AudioMerge => Self::execute_audio_merge_with_progress(task, Some(tasks), Some(task_id)),
+            OperationType::AudioNormalize => Self::execute_audio_normalize(task, Some(tasks), Some(task_id)),
             OperationType::BatchConvert => Self::execute_batch_convert_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::AddSubtitle => Self::execute_add_subtitle_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::AddWatermark => Self::execute_add_watermark_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::FrameExtract => Self::execute_frame_extract_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::VideoToGif => Self::execute_video_to_gif_with_progress(task, Some(tasks), Some(task_id)),
             OperationType::GifResize => Self::execute_gif_resize_with_progress(task, Some(tasks), Some(task_id)),
+            OperationType::ReviewClip => Self::execute_review_clip(task, Some(tasks), Some(task_id)),
         }
     }
 
@@ -638,6 +1591,10 @@ impl TaskExecutor {
         Self::execute_video_filter(task)
     }
     
+    fn execute_cut_and_join_with_progress(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        Self::execute_cut_and_join(task, tasks, task_id)
+    }
+    
     fn execute_audio_trim_with_progress(task: &mut ProcessingTask, _tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, _task_id: Option<usize>) -> Result<()> {
         Self::execute_audio_trim(task)
     }
@@ -667,8 +1624,8 @@ impl TaskExecutor {
         Self::execute_add_watermark(task)
     }
     
-    fn execute_frame_extract_with_progress(task: &mut ProcessingTask, _tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, _task_id: Option<usize>) -> Result<()> {
-        Self::execute_frame_extract(task)
+    fn execute_frame_extract_with_progress(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        Self::execute_frame_extract(task, tasks, task_id)
     }
     
     fn execute_video_to_gif_with_progress(task: &mut ProcessingTask, _tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, _task_id: Option<usize>) -> Result<()> {
@@ -705,11 +1662,7 @@ impl TaskExecutor {
             &output_ext
         };
         
-        let mut codec = if audio_settings.codec == "auto" {
-            CodecManager::get_best_audio_codec_for_format(target_format)
-        } else {
-            audio_settings.codec.clone()
-        };
+        let mut codec = resolve_audio_codec(audio_settings, target_format);
 
         // Validate encoder and format compatibility, silent fix
         if CodecManager::validate_codec_format_compatibility(&codec, target_format, true).is_err() {
@@ -746,29 +1699,111 @@ impl TaskExecutor {
             }
         }
         
-        // Build comprehensive FFmpeg command using the comprehensive command builder
-        let ffmpeg_args = ComprehensiveCommandBuilder::build_audio_conversion_command(
-            input_file,
-            output_file,
-            &updated_audio_settings
-        )?;
-        
-        // Create FFmpeg command
-        let mut cmd = create_ffmpeg_command()?;
-        
-        // Add all the comprehensive FFmpeg arguments
-        for arg in ffmpeg_args {
-            cmd.arg(arg);
+        // Edited chapters go in through an FFMETADATA file
+        updated_audio_settings.chapter_metadata_file = crate::chapters::write_metadata_file(
+            updated_audio_settings.preserve_chapters, updated_audio_settings.chapter_edits.as_deref())?;
+        let result = (|| -> Result<()> {
+            // Build comprehensive FFmpeg command using the comprehensive command builder
+            let ffmpeg_args = ComprehensiveCommandBuilder::build_audio_conversion_command(
+                input_file,
+                output_file,
+                &updated_audio_settings
+            )?;
+            
+            // Create FFmpeg command
+            let mut cmd = create_ffmpeg_command()?;
+            
+            // Add all the comprehensive FFmpeg arguments
+            for arg in ffmpeg_args {
+                cmd.arg(arg);
+            }
+            
+            // Execute the command
+            record_command(&cmd);
+            let output = cmd.output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow::anyhow!("FFmpeg audio conversion failed: {}", stderr));
+            }
+            
+            Ok(())
+        })();
+        if let Some(metadata_file) = &updated_audio_settings.chapter_metadata_file {
+            let _ = std::fs::remove_file(metadata_file);
         }
-        
-        // Execute the command
-        let output = cmd.output()?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("FFmpeg audio conversion failed: {}", stderr));
+        result
+    }
+
+    /// Audio settings for the second loudnorm pass: the codec resolved for the output format, no
+    /// one-pass loudnorm of its own, and the source sample rate since loudnorm resamples to 192 kHz
+    fn loudness_output_settings(input_file: &str, output_file: &str, audio_settings: &AudioSettings) -> AudioSettings {
+        let output_ext = std::path::Path::new(output_file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let target_format = if !audio_settings.format.is_empty() && audio_settings.format != "auto" {
+            audio_settings.format.clone()
+        } else {
+            output_ext
+        };
+        let mut codec = resolve_audio_codec(audio_settings, &target_format);
+        if CodecManager::validate_codec_format_compatibility(&codec, &target_format, true).is_err() {
+            codec = CodecManager::get_best_audio_codec_for_format(&target_format);
+        }
+        
+        let mut settings = audio_settings.clone();
+        settings.codec = codec;
+        settings.format = target_format;
+        settings.copy_audio = false;
+        settings.normalize = false;
+        if settings.sample_rate == "auto" || settings.sample_rate.is_empty() {
+            let source_rate = crate::probe_cache::probe_cache().get_file_info(input_file).ok()
+                .and_then(|info| info.audio_streams.first().map(|stream| stream.sample_rate))
+                .filter(|rate| *rate > 0);
+            settings.sample_rate = source_rate.unwrap_or(48_000).to_string();
+        }
+        settings
+    }
+    
+    /// Two-pass loudness normalization: measure the input, then normalize it with the measurement.
+    /// Each pass fills half of the progress bar.
+    fn execute_audio_normalize(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        if task.input_files.is_empty() {
+            return Err(anyhow::anyhow!("No input files specified"));
         }
+        let input_file = &task.input_files[0];
+        let audio_settings = task.audio_settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No audio settings specified"))?;
+        let target = audio_settings.loudness;
+        target.validate()?;
+        let output_settings = Self::loudness_output_settings(input_file, &task.output_file, audio_settings);
+        
+        let make_command = |ffmpeg_args: Vec<String>| -> Result<Command> {
+            let mut cmd = create_ffmpeg_command()?;
+            cmd.arg("-v").arg("info");
+            cmd.arg("-hide_banner");
+            cmd.arg("-nostdin");
+            cmd.args(ffmpeg_args);
+            Ok(cmd)
+        };
         
-        Ok(())
+        log_info!("Loudness normalization, pass 1 of 2: measuring {}", input_file);
+        let measure_args = ComprehensiveCommandBuilder::build_loudness_measure_command(input_file, &output_settings);
+        with_progress_span(0.0, 0.5, || {
+            Self::execute_ffmpeg_command_with_progress(make_command(measure_args)?, tasks.clone(), task_id)
+        })?;
+        let measured = crate::loudness::parse_measurement(&take_last_stderr())?;
+        log_info!("Measured {:.1} LUFS, true peak {:.1} dBTP, range {:.1} LU", measured.integrated_lufs, measured.true_peak_db, measured.range_lu);
+        if measured.within_tolerance(&target) {
+            log_info!("Loudness is already within {} LU of the {} LUFS target", crate::loudness::TOLERANCE_LU, target.integrated_lufs);
+        }
+        
+        log_info!("Loudness normalization, pass 2 of 2: normalizing to {} LUFS", target.integrated_lufs);
+        let normalize_args = ComprehensiveCommandBuilder::build_loudness_normalize_command(input_file, &task.output_file, &output_settings, Some(&measured))?;
+        with_progress_span(0.5, 0.5, || {
+            Self::execute_ffmpeg_command_with_progress(make_command(normalize_args)?, tasks, task_id)
+        })
     }
 
     fn execute_video_convert_with_progress(
@@ -793,22 +1828,11 @@ impl TaskExecutor {
             .unwrap_or("")
             .to_lowercase();
 
-        // Smart video codec selection
-        let mut video_codec = if video_settings.codec == "auto" {
-            CodecManager::get_best_video_codec_for_format(&output_ext)
-        } else {
-            video_settings.codec.clone()
-        };
+        // Smart video codec selection; transparent sources get an alpha-preserving encoder
+        let mut video_codec = resolve_video_codec(video_settings, &output_ext);
 
         // Normalize output file path, prioritize user-specified container format
-        let normalized_output = Self::normalize_output_path_with_container(output_file, &video_codec, &video_settings.container_format, false);
-        
-        // Get final output format (after normalization)
-        let final_output_ext = std::path::Path::new(&normalized_output)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        let (normalized_output, final_output_ext) = Self::video_output_path_and_container(output_file, &video_codec, video_settings);
         
         // Validate video codec and format compatibility, silent fix (using final format)
         if CodecManager::validate_codec_format_compatibility(&video_codec, &final_output_ext, false).is_err() {
@@ -816,16 +1840,9 @@ impl TaskExecutor {
         }
 
         // Smart audio codec selection (using final format)
-        let audio_codec = if let Some(audio_settings) = &task.audio_settings {
-            if audio_settings.codec == "auto" {
-                CodecManager::get_best_audio_codec_for_format(&final_output_ext)
-            } else if audio_settings.codec == "copy" {
-                "copy".to_string()
-            } else {
-                audio_settings.codec.clone()
-            }
-        } else {
-            "copy".to_string()
+        let audio_codec = match &task.audio_settings {
+            Some(audio_settings) => resolve_audio_codec(audio_settings, &final_output_ext),
+            None => "copy".to_string(),
         };
 
 
@@ -844,6 +1861,45 @@ impl TaskExecutor {
         );
     }
 
+    /// Whether the task runs the audio filter chain with a fade-out that has not been placed yet
+    fn needs_fade_duration(task: &ProcessingTask) -> bool {
+        matches!(task.operation,
+            OperationType::AudioConvert | OperationType::AudioCompress | OperationType::AudioResample |
+            OperationType::AudioVolume | OperationType::AudioTrim | OperationType::AudioNormalize)
+            && task.audio_settings.as_ref().is_some_and(|audio| audio.fade.needs_duration() && audio.fade_duration.is_none())
+    }
+
+    /// Probe the input for the length of the audio the filters see (the trimmed range, if any),
+    /// which the fade-out counts back from
+    fn resolve_fade_duration(task: &mut ProcessingTask) -> Result<()> {
+        let input_file = task.input_files.first()
+            .ok_or_else(|| anyhow::anyhow!("No input files specified"))?;
+        let total = crate::probe_cache::probe_cache().get_file_info(input_file).ok()
+            .map(|info| info.duration)
+            .filter(|duration| duration.is_finite() && *duration > 0.0)
+            .ok_or_else(|| anyhow::anyhow!("Cannot fade out: the duration of {} could not be determined", input_file))?;
+        let Some(audio_settings) = task.audio_settings.as_mut() else {
+            return Ok(());
+        };
+        let parse = |value: &str| -> Result<Option<f64>> {
+            if value.trim().is_empty() {
+                return Ok(None);
+            }
+            crate::locale_format::parse_duration(value)
+                .map(Some)
+                .map_err(|_| anyhow::anyhow!("Invalid trim time '{}'", value))
+        };
+        let start = parse(&audio_settings.start_time)?.unwrap_or(0.0);
+        let end = parse(&audio_settings.end_time)?.map_or(total, |end| end.min(total));
+        let duration = end - start;
+        if duration <= 0.0 {
+            return Err(anyhow::anyhow!("Cannot fade out: the trimmed range of {} is empty", input_file));
+        }
+        log_debug!("Fade-out placed from a {:.3}s input", duration);
+        audio_settings.fade_duration = Some(duration);
+        Ok(())
+    }
+
     /// Detect if file has audio stream
     fn has_audio_stream(input_file: &str) -> bool {
         // Use ffprobe to detect audio stream
@@ -891,7 +1947,7 @@ impl TaskExecutor {
         updated_video_settings.container_format = output_ext.to_string();
         
         // Create updated audio settings if provided
-        let updated_audio_settings = audio_settings.map(|as_ref| {
+        let mut updated_audio_settings = audio_settings.map(|as_ref| {
             let mut audio_copy = as_ref.clone();
             audio_copy.codec = audio_codec.to_string();
             audio_copy.format = output_ext.to_string();
@@ -916,43 +1972,143 @@ impl TaskExecutor {
             }
         }
         
-        // Build comprehensive FFmpeg command using the comprehensive command builder
-        let ffmpeg_args = ComprehensiveCommandBuilder::build_video_conversion_command(
-            input_file,
-            output_file,
-            &updated_video_settings,
-            updated_audio_settings.as_ref()
-        )?;
-        
-        // Create FFmpeg command with hardware acceleration if needed
-        let mut cmd = create_ffmpeg_command()?;
+        // Target size: derive the bitrates from the probed duration, unless the user approves them in the settings panel
+        if updated_video_settings.target_size_mb > 0 && !updated_video_settings.size_target_confirm {
+            let info = crate::probe_cache::probe_cache().get_file_info(input_file)?;
+            let plan = crate::size_target::plan_for(&info, &updated_video_settings, updated_audio_settings.as_ref())?;
+            log_info!("Target size {} MB: video {} kbps, audio {:?} kbps",
+                updated_video_settings.target_size_mb, plan.video_kbps, plan.audio_kbps);
+            crate::size_target::apply_plan(&plan, &mut updated_video_settings, updated_audio_settings.as_mut());
+        }
         
-        // Add hardware acceleration settings before input (with input codec validation)
-        Self::add_hardware_acceleration_args_with_validation(&mut cmd, input_file, &updated_video_settings.codec, updated_video_settings.use_hardware_acceleration);
+        // Edited chapters go in through an FFMETADATA file, removed again once the encode is done
+        updated_video_settings.chapter_metadata_file = crate::chapters::write_metadata_file(
+            updated_video_settings.preserve_chapters, updated_video_settings.chapter_edits.as_deref())?;
+        let remove_metadata_file = |settings: &VideoSettings| {
+            if let Some(metadata_file) = &settings.chapter_metadata_file {
+                let _ = std::fs::remove_file(metadata_file);
+            }
+        };
         
-        // Add progress tracking arguments
-        cmd.arg("-v").arg("info");
-        cmd.arg("-hide_banner");
-        cmd.arg("-stats");
-        cmd.arg("-nostdin");
-        cmd.arg("-progress").arg("pipe:2");
-        cmd.env("AV_LOG_FORCE_NOCOLOR", "1");
+        let make_command = |ffmpeg_args: Vec<String>| -> Result<Command> {
+            // Create FFmpeg command with hardware acceleration if needed
+            let mut cmd = create_ffmpeg_command()?;
+            
+            // Add hardware acceleration settings before input (with input codec validation)
+            Self::add_hardware_acceleration_args_with_validation(&mut cmd, input_file, &updated_video_settings.codec, updated_video_settings.use_hardware_acceleration);
+            
+            // Add progress tracking arguments
+            cmd.arg("-v").arg("info");
+            cmd.arg("-hide_banner");
+            cmd.arg("-stats");
+            cmd.arg("-nostdin");
+            cmd.arg("-progress").arg("pipe:2");
+            cmd.env("AV_LOG_FORCE_NOCOLOR", "1");
+            
+            // Add all the comprehensive FFmpeg arguments
+            for arg in ffmpeg_args {
+                cmd.arg(arg);
+            }
+            
+            // Add custom arguments if specified
+            if !updated_video_settings.custom_args.is_empty() {
+                let custom_args: Vec<&str> = updated_video_settings.custom_args.split_whitespace().collect();
+                for arg in custom_args {
+                    cmd.arg(arg);
+                }
+            }
+            Ok(cmd)
+        };
         
-        // Add all the comprehensive FFmpeg arguments
-        for arg in ffmpeg_args {
-            cmd.arg(arg);
+        // Resume after the destination dropped out: keep the partial's head, encode only the tail and join them
+        if let Some(resume_at) = updated_video_settings.resume_at {
+            let temp_dir = crate::temp_files::scratch_path("resume");
+            std::fs::create_dir_all(&temp_dir)?;
+            let resume = crate::output_recovery::resume_commands(output_file, resume_at, &temp_dir.display().to_string());
+            log_info!("Resuming {} from {:.3}s", output_file, resume_at);
+            
+            let run_step = |args: &[String]| -> Result<()> {
+                let mut cmd = create_ffmpeg_command()?;
+                cmd.arg("-v").arg("error");
+                cmd.arg("-hide_banner");
+                cmd.arg("-nostdin");
+                cmd.args(args);
+                Self::execute_ffmpeg_command_with_progress(cmd, None, None)
+            };
+            let result = run_step(&resume.head)
+                .and_then(|_| ComprehensiveCommandBuilder::build_video_conversion_command(
+                    input_file,
+                    &resume.tail_file,
+                    &updated_video_settings,
+                    updated_audio_settings.as_ref()
+                ))
+                .and_then(|mut tail_args| {
+                    crate::output_recovery::seek_input(&mut tail_args, resume_at);
+                    Self::execute_ffmpeg_command_with_progress(make_command(tail_args)?, tasks, task_id)
+                })
+                .and_then(|_| {
+                    std::fs::write(&resume.concat_list.0, &resume.concat_list.1)?;
+                    run_step(&resume.join)
+                });
+            remove_metadata_file(&updated_video_settings);
+            return result;
+        }
+        
+        // Hardware encoders have their own multipass modes and reject -pass
+        if updated_video_settings.two_pass && !Self::is_hardware_encoder_codec(&updated_video_settings.codec) {
+            let passlog = crate::temp_files::scratch_path("ffmpeg2pass").display().to_string();
+            let (first_pass, second_pass) = ComprehensiveCommandBuilder::build_two_pass_commands(
+                input_file,
+                output_file,
+                &updated_video_settings,
+                updated_audio_settings.as_ref(),
+                &passlog,
+            )?;
+            
+            // Each pass fills half of the progress bar
+            log_info!("Two-pass encode, pass 1 of 2");
+            let result = with_progress_span(0.0, 0.5, || {
+                Self::execute_ffmpeg_command_with_progress(make_command(first_pass)?, tasks.clone(), task_id)
+            }).and_then(|_| {
+                log_info!("Two-pass encode, pass 2 of 2");
+                with_progress_span(0.5, 0.5, || {
+                    Self::execute_ffmpeg_command_with_progress(make_command(second_pass)?, tasks, task_id)
+                })
+            });
+            Self::remove_pass_logs(&passlog);
+            remove_metadata_file(&updated_video_settings);
+            return result;
         }
         
-        // Add custom arguments if specified
-        if !updated_video_settings.custom_args.is_empty() {
-            let custom_args: Vec<&str> = updated_video_settings.custom_args.split_whitespace().collect();
-            for arg in custom_args {
-                cmd.arg(arg);
+        // Build comprehensive FFmpeg command using the comprehensive command builder
+        let result = ComprehensiveCommandBuilder::build_video_conversion_command(
+            input_file,
+            output_file,
+            &updated_video_settings,
+            updated_audio_settings.as_ref()
+        ).and_then(|ffmpeg_args| {
+            // Execute the command with progress tracking
+            Self::execute_ffmpeg_command_with_progress(make_command(ffmpeg_args)?, tasks, task_id)
+        });
+        remove_metadata_file(&updated_video_settings);
+        result
+    }
+
+    /// Delete the statistics files of a two-pass encode: the encoder appends `-0.log`,
+    /// `-0.log.mbtree` and the like to the `passlog` prefix
+    fn remove_pass_logs(passlog: &str) {
+        let prefix = std::path::Path::new(passlog);
+        let (Some(dir), Some(name)) = (prefix.parent(), prefix.file_name().map(|name| name.to_string_lossy().to_string())) else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten().filter(|entry| entry.file_name().to_string_lossy().starts_with(&name)) {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                log_warn!("Could not remove pass log {}: {}", entry.path().display(), e);
             }
         }
-        
-        // Execute the command with progress tracking
-        Self::execute_ffmpeg_command_with_progress(cmd, tasks, task_id)
     }
 
     /// Check if a codec is a hardware encoder
@@ -1186,7 +2342,10 @@ impl TaskExecutor {
     }
 
 
-    fn execute_video_audio_merge(task: &mut ProcessingTask) -> Result<()> {
+    /// Arguments of a video/audio merge: the video of the first input (or the second, if only it
+    /// has one) with the audio of the second (or the first). Each stream is copied when the settings
+    /// allow it and the output container accepts its codec, and encoded otherwise.
+    fn build_video_audio_merge_args(task: &ProcessingTask) -> Result<Vec<String>> {
         if task.input_files.len() < 2 {
             return Err(anyhow::anyhow!("Need at least 2 files for video/audio merge"));
         }
@@ -1202,103 +2361,97 @@ impl TaskExecutor {
             .unwrap_or("")
             .to_lowercase();
 
+        let first = crate::probe_cache::probe_cache().get_file_info(video_file)?;
+        let second = crate::probe_cache::probe_cache().get_file_info(audio_file)?;
+        log_debug!("First file streams - Video: {}, Audio: {}", first.video_streams.len(), first.audio_streams.len());
+        log_debug!("Second file streams - Video: {}, Audio: {}", second.video_streams.len(), second.audio_streams.len());
+
+        // Map video stream (prefer first file, fallback to second)
+        let (video_map, video_stream) = match (first.video_streams.first(), second.video_streams.first()) {
+            (Some(stream), _) => ("0:v", stream),
+            (None, Some(stream)) => ("1:v", stream),
+            (None, None) => return Err(anyhow::anyhow!("No video stream found in either input file")),
+        };
+        // Map audio stream (prefer second file, fallback to first)
+        let (audio_map, audio_stream) = match (second.audio_streams.first(), first.audio_streams.first()) {
+            (Some(stream), _) => ("1:a", stream),
+            (None, Some(stream)) => ("0:a", stream),
+            (None, None) => return Err(anyhow::anyhow!("No audio stream found in either input file")),
+        };
+
+        let copy_fits = |stream_codec: &str| {
+            CodecManager::validate_codec_format_compatibility(CodecManager::encoder_for_stream_codec(stream_codec), &output_ext, false).is_ok()
+        };
+
+        // The video is copied (usually no re-encoding needed) unless the container cannot hold it
+        let video_codec = if copy_fits(&video_stream.codec) {
+            "copy".to_string()
+        } else {
+            let codec = CodecManager::get_best_video_codec_for_format(&output_ext);
+            log_info!("{} video cannot be copied into {}, encoding with {}", video_stream.codec, output_ext, codec);
+            codec
+        };
+
         // Smart audio codec selection
-        let audio_codec = if let Some(audio_settings) = &task.audio_settings {
-            if audio_settings.codec == "auto" {
-                CodecManager::get_best_audio_codec_for_format(&output_ext)
-            } else {
-                audio_settings.codec.clone()
-            }
+        let copy_audio = task.audio_settings.as_ref().is_some_and(|audio_settings| audio_settings.copy_audio);
+        let audio_codec = if copy_audio && copy_fits(&audio_stream.codec) {
+            "copy".to_string()
         } else {
-            CodecManager::get_best_audio_codec_for_format(&output_ext)
+            if copy_audio {
+                log_info!("{} audio cannot be copied into {}, encoding it", audio_stream.codec, output_ext);
+            }
+            match &task.audio_settings {
+                Some(audio_settings) if audio_settings.codec != "auto" && audio_settings.codec != "copy" => audio_settings.codec.clone(),
+                _ => CodecManager::get_best_audio_codec_for_format(&output_ext),
+            }
         };
 
-        // Always use bundled FFmpeg approach
+        let mut args = vec![
+            "-i".to_string(), video_file.clone(),
+            "-i".to_string(), audio_file.clone(),
+            "-map".to_string(), video_map.to_string(),
+            "-map".to_string(), audio_map.to_string(),
+            "-c:v".to_string(), video_codec,
+            "-c:a".to_string(), audio_codec.clone(),
+        ];
 
-        // Fallback to external FFmpeg command
-        let mut cmd = create_ffmpeg_command()?;
-        cmd.arg("-i").arg(video_file);
-        cmd.arg("-i").arg(audio_file);
-        cmd.arg("-y"); // Overwrite output file
-        cmd.arg("-v").arg("error"); // Show error level information
-        cmd.arg("-hide_banner"); // Hide banner information
-        cmd.arg("-nostats"); // Disable statistics
-        cmd.arg("-nostdin"); // Disable interactive input
-        
-        // Set environment variables
-        cmd.env("AV_LOG_FORCE_NOCOLOR", "1");
-        
-        // Copy video stream (usually no re-encoding needed)
-        cmd.arg("-c:v").arg("copy");
-        
-        // Set audio encoder
-        cmd.arg("-c:a").arg(&audio_codec);
-        
         // Audio parameters
-        if let Some(audio_settings) = &task.audio_settings {
+        if let Some(audio_settings) = task.audio_settings.as_ref().filter(|_| audio_codec != "copy") {
             if audio_settings.bitrate != "auto" && !audio_settings.bitrate.is_empty() {
-                cmd.arg("-b:a").arg(&audio_settings.bitrate);
+                args.extend(["-b:a".to_string(), audio_settings.bitrate.clone()]);
             } else {
                 // Use default bitrate
-                let default_bitrate = match audio_codec.as_str() {
-                    "libmp3lame" => "128k",
-                    "aac" => "128k",
-                    "libvorbis" => "128k",
-                    "libopus" => "128k",
-                    _ => "128k"
-                };
-                cmd.arg("-b:a").arg(default_bitrate);
+                args.extend(["-b:a".to_string(), "128k".to_string()]);
             }
-            
             if audio_settings.sample_rate != "auto" && !audio_settings.sample_rate.is_empty() {
-                cmd.arg("-ar").arg(&audio_settings.sample_rate);
+                args.extend(["-ar".to_string(), audio_settings.sample_rate.clone()]);
             }
-            
             if audio_settings.channels != "auto" && !audio_settings.channels.is_empty() {
-                cmd.arg("-ac").arg(&audio_settings.channels);
+                args.extend(["-ac".to_string(), audio_settings.channels.clone()]);
             }
         }
-        
-        // Detect streams and map them intelligently
-        // Use FFprobe to detect available streams
-        let bundled_ffmpeg = get_bundled_ffmpeg()?;
-        
-        // Check first file for video/audio streams
-        let first_file_args = vec!["-v", "quiet", "-show_entries", "stream=codec_type", "-of", "csv=p=0", video_file];
-        let first_output = bundled_ffmpeg.run_ffprobe(&first_file_args)?;
-        let first_streams = String::from_utf8_lossy(&first_output.stdout);
-        let first_has_video = first_streams.lines().any(|line| line.trim() == "video");
-        let first_has_audio = first_streams.lines().any(|line| line.trim() == "audio");
-        
-        // Check second file for video/audio streams  
-        let second_file_args = vec!["-v", "quiet", "-show_entries", "stream=codec_type", "-of", "csv=p=0", audio_file];
-        let second_output = bundled_ffmpeg.run_ffprobe(&second_file_args)?;
-        let second_streams = String::from_utf8_lossy(&second_output.stdout);  
-        let second_has_video = second_streams.lines().any(|line| line.trim() == "video");
-        let second_has_audio = second_streams.lines().any(|line| line.trim() == "audio");
-        
-        log_debug!("First file streams - Video: {}, Audio: {}", first_has_video, first_has_audio);
-        log_debug!("Second file streams - Video: {}, Audio: {}", second_has_video, second_has_audio);
-        
-        // Map video stream (prefer first file, fallback to second)
-        if first_has_video {
-            cmd.arg("-map").arg("0:v");
-        } else if second_has_video {
-            cmd.arg("-map").arg("1:v");
-        } else {
-            return Err(anyhow::anyhow!("No video stream found in either input file"));
-        }
-        
-        // Map audio stream (prefer second file, fallback to first)
-        if second_has_audio {
-            cmd.arg("-map").arg("1:a");
-        } else if first_has_audio {
-            cmd.arg("-map").arg("0:a");
-        } else {
-            return Err(anyhow::anyhow!("No audio stream found in either input file"));
+
+        // End with the shorter stream instead of padding the longer one out
+        if task.audio_settings.as_ref().is_some_and(|audio_settings| audio_settings.merge_shortest) {
+            args.push("-shortest".to_string());
         }
+
+        args.push("-y".to_string());
+        args.push(output_file.clone());
+        Ok(args)
+    }
+
+    fn execute_video_audio_merge(task: &mut ProcessingTask) -> Result<()> {
+        let args = Self::build_video_audio_merge_args(task)?;
+        let mut cmd = create_ffmpeg_command()?;
+        cmd.arg("-v").arg("error"); // Show error level information
+        cmd.arg("-hide_banner"); // Hide banner information
+        cmd.arg("-nostats"); // Disable statistics
+        cmd.arg("-nostdin"); // Disable interactive input
         
-        cmd.arg(output_file);
+        // Set environment variables
+        cmd.env("AV_LOG_FORCE_NOCOLOR", "1");
+        cmd.args(&args);
         
         // Use common FFmpeg execution function
         Self::execute_ffmpeg_command_with_progress(cmd, None, None)
@@ -1508,6 +2661,7 @@ impl TaskExecutor {
         
         // Execute video extraction
         log_info!("Extracting video without audio...");
+        record_command(&video_cmd);
         let video_output_result = video_cmd.output()?;
         if !video_output_result.status.success() {
             let stderr = String::from_utf8_lossy(&video_output_result.stderr);
@@ -1516,6 +2670,7 @@ impl TaskExecutor {
         
         // Execute audio extraction
         log_info!("Extracting audio without video...");
+        record_command(&audio_cmd);
         let audio_output_result = audio_cmd.output()?;
         if !audio_output_result.status.success() {
             let stderr = String::from_utf8_lossy(&audio_output_result.stderr);
@@ -1692,8 +2847,8 @@ impl TaskExecutor {
                     cmd.arg("-s").arg(format!("{}x{}", video_settings.resolution.0, video_settings.resolution.1));
                 }
                 
-                if video_settings.fps != "auto" {
-                    cmd.arg("-r").arg(&video_settings.fps);
+                if let Some(rate) = crate::frame_rate::output_rate(&video_settings.fps) {
+                    cmd.arg("-r").arg(rate);
                 }
             }
         }
@@ -1730,23 +2885,29 @@ impl TaskExecutor {
             &output_ext
         };
         
-        let mut codec = if audio_settings.codec == "auto" {
-            CodecManager::get_best_audio_codec_for_format(target_format)
-        } else {
-            audio_settings.codec.clone()
-        };
+        let mut codec = resolve_audio_codec(audio_settings, target_format);
 
         // Validate encoder and format compatibility, silent fix
         if CodecManager::validate_codec_format_compatibility(&codec, target_format, true).is_err() {
             codec = CodecManager::get_best_audio_codec_for_format(target_format);
         }
 
-        // Normalize output file path
-        let normalized_output = Self::normalize_output_path_with_container(output_file, &codec, target_format, true);
+        // Normalize output file path, unless the user chose to keep a mismatched extension
+        let forced_format = crate::output_extension::forced_format_args(target_format, audio_settings.keep_output_extension);
+        let normalized_output = if forced_format.is_empty() {
+            Self::normalize_output_path_with_container(output_file, &codec, target_format, true)
+        } else {
+            output_file.clone()
+        };
 
+        let chapter_file = crate::chapters::preview_metadata_file(audio_settings.preserve_chapters, audio_settings.chapter_edits.as_deref());
         let mut cmd_parts = vec!["ffmpeg".to_string()];
         cmd_parts.push("-i".to_string());
         cmd_parts.push(format!("\"{}\"", input_file));
+        if let Some(chapter_file) = &chapter_file {
+            cmd_parts.push("-i".to_string());
+            cmd_parts.push(format!("\"{}\"", chapter_file));
+        }
         cmd_parts.push("-y".to_string());
         cmd_parts.push("-v".to_string());
         cmd_parts.push("info".to_string());
@@ -1802,10 +2963,11 @@ impl TaskExecutor {
             cmd_parts.push(audio_settings.channels.clone());
         }
 
-        // Volume adjustment
-        if (audio_settings.volume - 1.0).abs() > 0.01 {
-            cmd_parts.push("-filter:a".to_string());
-            cmd_parts.push(format!("volume={}", audio_settings.volume));
+        // Audio filters (noise reduction, volume, fades, normalization)
+        let audio_filters = ComprehensiveCommandBuilder::audio_filter_chain(audio_settings)?;
+        if !audio_filters.is_empty() {
+            cmd_parts.push("-af".to_string());
+            cmd_parts.push(format!("\"{}\"", audio_filters.join(",")));
         }
 
         // Add user custom parameters
@@ -1816,6 +2978,8 @@ impl TaskExecutor {
             }
         }
 
+        cmd_parts.extend(crate::chapters::map_args(audio_settings.preserve_chapters, chapter_file.as_ref().map(|_| 1)));
+        cmd_parts.extend(forced_format);
         cmd_parts.push(format!("\"{}\"", normalized_output));
 
         Ok(cmd_parts.join(" "))
@@ -1832,6 +2996,31 @@ impl TaskExecutor {
         let video_settings = task.video_settings.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
 
+        // Two-pass encodes run both passes, shown as one line like a shell would chain them
+        if video_settings.two_pass && !Self::is_hardware_encoder_codec(&video_settings.codec) {
+            let passlog = crate::temp_files::preview_path("ffmpeg2pass").display().to_string();
+            let (first_pass, second_pass) = ComprehensiveCommandBuilder::build_two_pass_commands(
+                input_file,
+                output_file,
+                video_settings,
+                task.audio_settings.as_ref(),
+                &passlog,
+            )?;
+            let quote = |arg: &String| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            };
+            let commands: Vec<String> = [first_pass, second_pass].iter()
+                .map(|args| {
+                    let mut cmd_parts = vec!["ffmpeg".to_string()];
+                    cmd_parts.extend(args.iter().map(quote));
+                    cmd_parts.join(" ")
+                })
+                .collect();
+            return Ok(commands.join(" && "));
+        }
+
         // Get output format
         let output_ext = std::path::Path::new(output_file)
             .extension()
@@ -1839,37 +3028,35 @@ impl TaskExecutor {
             .unwrap_or("")
             .to_lowercase();
 
-        // Smart video codec selection
-        let mut video_codec = if video_settings.codec == "auto" {
-            CodecManager::get_best_video_codec_for_format(&output_ext)
-        } else {
-            video_settings.codec.clone()
-        };
-
+        // Smart video codec selection; transparent sources get an alpha-preserving encoder
+        let mut video_codec = resolve_video_codec(video_settings, &output_ext);
+
         // Validate encoder and format compatibility, silent fix
         if CodecManager::validate_codec_format_compatibility(&video_codec, &output_ext, false).is_err() {
             video_codec = CodecManager::get_best_video_codec_for_format(&output_ext);
         }
 
         // Normalize output file path, prioritize user-specified container format
-        let normalized_output = Self::normalize_output_path_with_container(output_file, &video_codec, &video_settings.container_format, false);
+        let (normalized_output, _) = Self::video_output_path_and_container(output_file, &video_codec, video_settings);
 
         // Smart audio codec selection
-        let audio_codec = if let Some(audio_settings) = &task.audio_settings {
-            if audio_settings.codec == "auto" {
-                CodecManager::get_best_audio_codec_for_format(&output_ext)
-            } else if audio_settings.codec == "copy" {
-                "copy".to_string()
-            } else {
-                audio_settings.codec.clone()
-            }
-        } else {
-            "copy".to_string()
+        let audio_codec = match &task.audio_settings {
+            Some(audio_settings) => resolve_audio_codec(audio_settings, &output_ext),
+            None => "copy".to_string(),
         };
 
+        let program_stream = crate::transport_stream::needs_fixups(input_file);
         let mut cmd_parts = vec!["ffmpeg".to_string()];
+        if program_stream {
+            cmd_parts.extend(crate::transport_stream::input_args());
+        }
         cmd_parts.push("-i".to_string());
         cmd_parts.push(format!("\"{}\"", input_file));
+        let chapter_file = crate::chapters::preview_metadata_file(video_settings.preserve_chapters, video_settings.chapter_edits.as_deref());
+        if let Some(chapter_file) = &chapter_file {
+            cmd_parts.push("-i".to_string());
+            cmd_parts.push(format!("\"{}\"", chapter_file));
+        }
         cmd_parts.push("-y".to_string());
         cmd_parts.push("-v".to_string());
         cmd_parts.push("info".to_string());
@@ -1918,9 +3105,9 @@ impl TaskExecutor {
         }
 
         // Frame rate settings
-        if video_settings.fps != "auto" {
+        if let Some(rate) = crate::frame_rate::output_rate(&video_settings.fps) {
             cmd_parts.push("-r".to_string());
-            cmd_parts.push(video_settings.fps.clone());
+            cmd_parts.push(rate);
         }
 
         // Audio processing
@@ -1932,6 +3119,13 @@ impl TaskExecutor {
             cmd_parts.push(audio_codec);
         }
 
+        if program_stream {
+            cmd_parts.extend(crate::transport_stream::output_args(video_settings.input_program));
+        }
+
+        cmd_parts.extend(crate::chapters::map_args(video_settings.preserve_chapters, chapter_file.as_ref().map(|_| 1)));
+        cmd_parts.extend(ComprehensiveCommandBuilder::muxer_args(&normalized_output, video_settings));
+
         // Add user custom parameters
         if !video_settings.custom_args.is_empty() {
             let custom_args: Vec<&str> = video_settings.custom_args.split_whitespace().collect();
@@ -1946,53 +3140,13 @@ impl TaskExecutor {
     }
 
     fn preview_video_audio_merge(task: &ProcessingTask) -> Result<String> {
-        if task.input_files.len() < 2 {
-            return Err(anyhow::anyhow!("Need at least 2 files for video/audio merge"));
-        }
-
-        let video_file = &task.input_files[0];
-        let audio_file = &task.input_files[1];
-        let output_file = &task.output_file;
-
-        let output_ext = std::path::Path::new(output_file)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        let audio_codec = if let Some(audio_settings) = &task.audio_settings {
-            if audio_settings.codec == "auto" {
-                CodecManager::get_best_audio_codec_for_format(&output_ext)
-            } else {
-                audio_settings.codec.clone()
-            }
+        let args = Self::build_video_audio_merge_args(task)?;
+        let mut cmd_parts = vec!["ffmpeg".to_string(), "-v".to_string(), "info".to_string(), "-hide_banner".to_string(), "-nostats".to_string()];
+        cmd_parts.extend(args.iter().map(|arg| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+            format!("\"{}\"", arg)
         } else {
-            CodecManager::get_best_audio_codec_for_format(&output_ext)
-        };
-
-        // Normalize output file path
-        let normalized_output = Self::normalize_output_path_with_container(output_file, &audio_codec, "auto", true);
-
-        let mut cmd_parts = vec!["ffmpeg".to_string()];
-        cmd_parts.push("-i".to_string());
-        cmd_parts.push(format!("\"{}\"", video_file));
-        cmd_parts.push("-i".to_string());
-        cmd_parts.push(format!("\"{}\"", audio_file));
-        cmd_parts.push("-y".to_string());
-        cmd_parts.push("-v".to_string());
-        cmd_parts.push("info".to_string());
-        cmd_parts.push("-hide_banner".to_string());
-        cmd_parts.push("-nostats".to_string());
-        cmd_parts.push("-c:v".to_string());
-        cmd_parts.push("copy".to_string());
-        cmd_parts.push("-c:a".to_string());
-        cmd_parts.push(audio_codec);
-        cmd_parts.push("-map".to_string());
-        cmd_parts.push("0:v:0".to_string());
-        cmd_parts.push("-map".to_string());
-        cmd_parts.push("1:a:0".to_string());
-        cmd_parts.push(format!("\"{}\"", normalized_output));
-
+            arg.clone()
+        }));
         Ok(cmd_parts.join(" "))
     }
 
@@ -2241,11 +3395,7 @@ impl TaskExecutor {
 
         let normalized_output = Self::normalize_output_path_with_container(output_file, &video_codec, "auto", false);
 
-        let filter = if !video_settings.custom_args.is_empty() {
-            video_settings.custom_args.clone()
-        } else {
-            "unsharp=5:5:1.0:5:5:0.0,eq=contrast=1.1:brightness=0.05:saturation=1.1".to_string()
-        };
+        let filter = Self::video_filter_chain(input_file, video_settings)?;
 
         let mut cmd_parts = vec!["ffmpeg".to_string()];
         cmd_parts.push("-i".to_string());
@@ -2255,8 +3405,10 @@ impl TaskExecutor {
         cmd_parts.push("info".to_string());
         cmd_parts.push("-hide_banner".to_string());
         cmd_parts.push("-nostats".to_string());
-        cmd_parts.push("-filter:v".to_string());
-        cmd_parts.push(filter);
+        if !filter.is_empty() {
+            cmd_parts.push("-filter:v".to_string());
+            cmd_parts.push(filter);
+        }
         cmd_parts.push("-c:v".to_string());
         cmd_parts.push(video_codec);
         cmd_parts.push("-c:a".to_string());
@@ -2266,6 +3418,74 @@ impl TaskExecutor {
         Ok(cmd_parts.join(" "))
     }
 
+    fn preview_cut_and_join(task: &ProcessingTask) -> Result<String> {
+        let temp_dir = crate::temp_files::preview_path("cut");
+        let plan = Self::build_cut_and_join_plan(task, &temp_dir.display().to_string())?;
+
+        let quote = |arg: &String| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+            format!("\"{}\"", arg)
+        } else {
+            arg.clone()
+        };
+        let commands: Vec<String> = plan.commands.iter()
+            .map(|args| {
+                let mut cmd_parts = vec!["ffmpeg".to_string()];
+                cmd_parts.extend(args.iter().map(quote));
+                cmd_parts.join(" ")
+            })
+            .collect();
+        Ok(commands.join("\n"))
+    }
+
+    fn preview_audio_normalize(task: &ProcessingTask) -> Result<String> {
+        if task.input_files.is_empty() {
+            return Err(anyhow::anyhow!("No input files specified"));
+        }
+        let input_file = &task.input_files[0];
+        let audio_settings = task.audio_settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No audio settings specified"))?;
+        let output_settings = Self::loudness_output_settings(input_file, &task.output_file, audio_settings);
+        let measure_args = ComprehensiveCommandBuilder::build_loudness_measure_command(input_file, &output_settings);
+        let normalize_args = ComprehensiveCommandBuilder::build_loudness_normalize_command(input_file, &task.output_file, &output_settings, None)?;
+        
+        let quote = |arg: &String| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+            format!("\"{}\"", arg)
+        } else {
+            arg.clone()
+        };
+        let command = |args: &[String]| {
+            let mut cmd_parts = vec!["ffmpeg".to_string()];
+            cmd_parts.extend(args.iter().map(quote));
+            cmd_parts.join(" ")
+        };
+        Ok(format!(
+            "{}\n# Pass 2 adds the values measured by pass 1 (measured_I, measured_TP, measured_LRA, measured_thresh, offset)\n{}",
+            command(&measure_args), command(&normalize_args)
+        ))
+    }
+
+    fn preview_frame_rate_convert(task: &ProcessingTask) -> Result<String> {
+        let args = Self::build_frame_rate_args(task)?;
+        let mut cmd_parts = vec!["ffmpeg".to_string()];
+        cmd_parts.extend(args.iter().map(|arg| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+            format!("\"{}\"", arg)
+        } else {
+            arg.clone()
+        }));
+        Ok(cmd_parts.join(" "))
+    }
+
+    fn preview_review_clip(task: &ProcessingTask) -> Result<String> {
+        let args = Self::build_review_clip_args(task)?;
+        let mut cmd_parts = vec!["ffmpeg".to_string()];
+        cmd_parts.extend(args.iter().map(|arg| if arg.contains(' ') || arg.contains('\'') || arg.contains('/') || arg.contains('\\') {
+            format!("\"{}\"", arg)
+        } else {
+            arg.clone()
+        }));
+        Ok(cmd_parts.join(" "))
+    }
+
     fn preview_audio_trim(task: &ProcessingTask) -> Result<String> {
         if task.input_files.is_empty() {
             return Err(anyhow::anyhow!("No input files specified"));
@@ -2291,31 +3511,24 @@ impl TaskExecutor {
         let normalized_output = Self::normalize_output_path_with_container(output_file, &audio_codec, "auto", true);
 
         let mut cmd_parts = vec!["ffmpeg".to_string()];
-        cmd_parts.push("-i".to_string());
-        cmd_parts.push(format!("\"{}\"", input_file));
 
         // Audio trim parameters
-        if !audio_settings.custom_args.is_empty() {
-            let parts: Vec<&str> = audio_settings.custom_args.split(',').collect();
-            if parts.len() >= 2 {
-                cmd_parts.push("-ss".to_string());
-                cmd_parts.push(parts[0].to_string());
-                cmd_parts.push("-t".to_string());
-                cmd_parts.push(parts[1].to_string());
-            } else if parts.len() == 1 {
-                cmd_parts.push("-t".to_string());
-                cmd_parts.push(parts[0].to_string());
-            }
-        } else {
-            cmd_parts.push("-t".to_string());
-            cmd_parts.push("30".to_string());
-        }
+        cmd_parts.extend(Self::audio_trim_range_args(audio_settings)?);
 
+        cmd_parts.push("-i".to_string());
+        cmd_parts.push(format!("\"{}\"", input_file));
         cmd_parts.push("-y".to_string());
         cmd_parts.push("-v".to_string());
         cmd_parts.push("info".to_string());
         cmd_parts.push("-hide_banner".to_string());
         cmd_parts.push("-nostats".to_string());
+
+        let audio_filters = ComprehensiveCommandBuilder::audio_filter_chain(audio_settings)?;
+        if !audio_filters.is_empty() {
+            cmd_parts.push("-af".to_string());
+            cmd_parts.push(format!("\"{}\"", audio_filters.join(",")));
+        }
+
         cmd_parts.push("-c:a".to_string());
         cmd_parts.push(audio_codec);
         cmd_parts.push(format!("\"{}\"", normalized_output));
@@ -2359,12 +3572,9 @@ impl TaskExecutor {
         cmd_parts.push("-hide_banner".to_string());
         cmd_parts.push("-nostats".to_string());
 
-        let filter_complex = format!("{}concat=n={}:v=0:a=1[out]", 
-            (0..task.input_files.len()).map(|i| format!("[{}:0]", i)).collect::<Vec<_>>().join(""),
-            task.input_files.len()
-        );
+        let filter_complex = crate::audio_mix::merge_filtergraph(audio_settings, task.input_files.len());
         cmd_parts.push("-filter_complex".to_string());
-        cmd_parts.push(filter_complex);
+        cmd_parts.push(format!("\"{}\"", filter_complex));
         cmd_parts.push("-map".to_string());
         cmd_parts.push("[out]".to_string());
         cmd_parts.push("-c:a".to_string());
@@ -2450,41 +3660,9 @@ impl TaskExecutor {
             &video_settings.subtitle_file
         };
         
-        // Escape the subtitle file path for the subtitles filter
-        // Convert Windows paths to use forward slashes for FFmpeg compatibility
-        let escaped_subtitle_path = subtitle_file.replace("\\", "/");
-        
         if video_settings.subtitle_mode == "hard" {
             // Hard subtitle - burn into video with style
-            let mut filter_parts = vec![format!("subtitles={}", escaped_subtitle_path)];
-            
-            // Add font style if specified
-            if video_settings.subtitle_font_family != "Arial" {
-                filter_parts.push(format!("force_style='FontName={}'", video_settings.subtitle_font_family));
-            }
-            if video_settings.subtitle_font_size != 16 {
-                filter_parts.push(format!("force_style='FontSize={}'", video_settings.subtitle_font_size));
-            }
-            
-            // Convert color names to ASS format
-            let color_code = match video_settings.subtitle_font_color.as_str() {
-                "white" => "&Hffffff&",
-                "black" => "&H000000&",
-                "red" => "&H0000ff&",
-                "blue" => "&Hff0000&",
-                "green" => "&H00ff00&",
-                "yellow" => "&H00ffff&",
-                _ => "&Hffffff&",
-            };
-            if color_code != "&Hffffff&" {
-                filter_parts.push(format!("force_style='PrimaryColour={}'", color_code));
-            }
-            
-            let filter = if filter_parts.len() > 1 {
-                filter_parts.join(":")
-            } else {
-                filter_parts[0].clone()
-            };
+            let filter = ComprehensiveCommandBuilder::subtitles_filter(subtitle_file, video_settings);
             
             cmd_parts.push("-filter:v".to_string());
             cmd_parts.push(filter);
@@ -2515,15 +3693,7 @@ impl TaskExecutor {
         
         let video_settings = task.video_settings.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
-
-        if video_settings.watermark_file.is_empty() {
-            return Err(anyhow::anyhow!("No watermark file specified"));
-        }
-        
-        // Check if watermark file exists
-        if !std::path::Path::new(&video_settings.watermark_file).exists() {
-            return Err(anyhow::anyhow!("Watermark file not found: {}", video_settings.watermark_file));
-        }
+        let has_image = Self::check_watermark_inputs(video_settings)?;
 
         let output_ext = std::path::Path::new(output_file)
             .extension()
@@ -2533,46 +3703,16 @@ impl TaskExecutor {
 
         let video_codec = CodecManager::get_best_video_codec_for_format(&output_ext);
 
-        // Build watermark position
-        let position = match video_settings.watermark_position.as_str() {
-            "top-left" => "10:10",
-            "top-right" => "main_w-overlay_w-10:10",
-            "bottom-left" => "10:main_h-overlay_h-10",
-            "bottom-right" => "main_w-overlay_w-10:main_h-overlay_h-10",
-            "center" => "(main_w-overlay_w)/2:(main_h-overlay_h)/2",
-            _ => "main_w-overlay_w-10:10"
-        };
-        
         // Build a proper filter_complex chain for watermark preview
-        // Handle scaling and opacity
-        let opacity = video_settings.watermark_opacity;
-        let scale = video_settings.watermark_scale;
-        
-        let filter_complex = if (scale - 1.0).abs() > 0.01 || (opacity - 1.0).abs() > 0.01 {
-            // Apply scaling and/or opacity
-            if (opacity - 1.0).abs() > 0.01 {
-                // Apply opacity using alpha channel manipulation
-                format!(
-                    "[1:v]scale=iw*{}:ih*{},format=rgba,colorchannelmixer=aa={}[wm];[0:v][wm]overlay={}",
-                    scale, scale, opacity, position
-                )
-            } else {
-                // Only scale, no opacity change
-                format!(
-                    "[1:v]scale=iw*{}:ih*{}[wm];[0:v][wm]overlay={}",
-                    scale, scale, position
-                )
-            }
-        } else {
-            // No scaling or opacity change
-            format!("[0:v][1:v]overlay={}", position)
-        };
+        let filter_complex = ComprehensiveCommandBuilder::watermark_filter_complex(video_settings, has_image);
 
         let mut cmd_parts = vec!["ffmpeg".to_string()];
         cmd_parts.push("-i".to_string());
         cmd_parts.push(format!("\"{}\"", video_file));
-        cmd_parts.push("-i".to_string());
-        cmd_parts.push(format!("\"{}\"", video_settings.watermark_file));
+        if has_image {
+            cmd_parts.push("-i".to_string());
+            cmd_parts.push(format!("\"{}\"", video_settings.watermark_file));
+        }
         cmd_parts.push("-y".to_string());
         cmd_parts.push("-v".to_string());
         cmd_parts.push("info".to_string());
@@ -2589,6 +3729,89 @@ impl TaskExecutor {
         Ok(cmd_parts.join(" "))
     }
 
+    /// Output options for frame extraction: time range, selection mode, color conversion and image quality
+    fn build_frame_extract_args(input_file: &str, video_settings: &VideoSettings) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut filters = Vec::new();
+
+        // Time range applies to every mode
+        let (start, end) = crate::frame_extract::range_seconds(video_settings);
+        if start > 0.0 {
+            args.push("-ss".to_string());
+            args.push(crate::locale_format::format_ffmpeg_duration(start));
+        }
+        if let Some(end) = end {
+            args.push("-to".to_string());
+            args.push(crate::locale_format::format_ffmpeg_duration(end));
+        }
+
+        // Handle different extraction modes
+        if let Some(interval) = crate::frame_extract::interval_seconds(video_settings) {
+            // One frame every N seconds
+            filters.push(format!("fps=1/{}", interval));
+        } else if video_settings.frame_extract_mode == crate::frame_extract::MODE_ALL {
+            // Extract all frames - no fps filter, just extract every frame
+            args.push("-vsync".to_string());
+            args.push("0".to_string());
+        } else {
+            // Extract every N frames using select filter for more precision
+            let interval = if video_settings.frame_interval > 0 { video_settings.frame_interval } else { 30 };
+            filters.push(format!("select='not(mod(n,{}))'", interval));
+            args.push("-vsync".to_string());
+            args.push("0".to_string());
+        }
+
+        // Explicit range/matrix conversion so stills match what players show;
+        // silently skipped when the source doesn't report its color metadata
+        let color_filter = if video_settings.accurate_color_conversion {
+            crate::color_conversion::probe_color_metadata(input_file)
+                .and_then(|metadata| crate::color_conversion::image_conversion_filter(&metadata, &video_settings.frame_format))
+        } else {
+            None
+        };
+        let has_color_filter = color_filter.is_some();
+        filters.extend(color_filter);
+
+        if !filters.is_empty() {
+            args.push("-vf".to_string());
+            args.push(filters.join(","));
+        }
+
+        // Set quality based on format
+        match video_settings.frame_format.as_str() {
+            "jpg" | "jpeg" => {
+                args.push("-qscale:v".to_string());
+                args.push(video_settings.frame_quality.clamp(1, 31).to_string());
+            },
+            "png" => {
+                if !has_color_filter {
+                    args.push("-pix_fmt".to_string());
+                    args.push("rgb24".to_string());
+                }
+                args.push("-compression_level".to_string());
+                args.push(video_settings.frame_png_compression.clamp(0, 9).to_string());
+            },
+            "bmp" if !has_color_filter => {
+                args.push("-pix_fmt".to_string());
+                args.push("bgr24".to_string());
+            },
+            "bmp" => {},
+            _ => {
+                args.push("-qscale:v".to_string());
+                args.push("2".to_string());
+            }
+        }
+
+        args
+    }
+
+    /// Frames the extraction should write, from the probed duration and frame rate
+    fn expected_extracted_frames(input_file: &str, video_settings: &VideoSettings) -> Option<u64> {
+        let info = crate::probe_cache::probe_cache().get_file_info(input_file).ok()?;
+        let fps = info.video_streams.first().map(|stream| stream.fps).unwrap_or(0.0);
+        crate::frame_extract::expected_frame_count(video_settings, info.duration, fps)
+    }
+
     fn preview_frame_extract(task: &ProcessingTask) -> Result<String> {
         if task.input_files.is_empty() {
             return Err(anyhow::anyhow!("No input files specified"));
@@ -2610,99 +3833,13 @@ impl TaskExecutor {
         cmd_parts.push("-nostats".to_string());
         cmd_parts.push("-nostdin".to_string());
 
-        // Handle different extraction modes (preview)
-        match video_settings.frame_extract_mode.as_str() {
-            "all" => {
-                // Extract all frames - no fps filter, just extract every frame
-                cmd_parts.push("-vsync".to_string());
-                cmd_parts.push("0".to_string());
-            },
-            "interval" => {
-                // Extract every N frames using select filter for more precision
-                if video_settings.frame_interval > 0 {
-                    cmd_parts.push("-vf".to_string());
-                    cmd_parts.push(format!("\"select='not(mod(n,{}))'\"", video_settings.frame_interval));
-                    cmd_parts.push("-vsync".to_string());
-                    cmd_parts.push("0".to_string());
-                } else {
-                    cmd_parts.push("-vf".to_string());
-                    cmd_parts.push("\"select='not(mod(n,30))'\"".to_string());
-                    cmd_parts.push("-vsync".to_string());
-                    cmd_parts.push("0".to_string());
-                }
-            },
-            "time" => {
-                // Extract from time range
-                if !video_settings.frame_start_time.is_empty() {
-                    cmd_parts.push("-ss".to_string());
-                    cmd_parts.push(video_settings.frame_start_time.clone());
-                }
-                if !video_settings.frame_end_time.is_empty() {
-                    cmd_parts.push("-to".to_string());
-                    cmd_parts.push(video_settings.frame_end_time.clone());
-                }
-                // Extract one frame per second in the time range
-                cmd_parts.push("-vf".to_string());
-                cmd_parts.push("fps=1".to_string());
-            },
-            _ => {
-                // Default: extract every 30th frame
-                cmd_parts.push("-vf".to_string());
-                cmd_parts.push("\"select='not(mod(n,30))'\"".to_string());
-                cmd_parts.push("-vsync".to_string());
-                cmd_parts.push("0".to_string());
-            }
-        }
-
-        // Set quality based on format
-        match video_settings.frame_format.as_str() {
-            "jpg" | "jpeg" => {
-                cmd_parts.push("-q:v".to_string());
-                cmd_parts.push(video_settings.frame_quality.to_string());
-            },
-            "png" => {
-                cmd_parts.push("-pix_fmt".to_string());
-                cmd_parts.push("rgb24".to_string());
-            },
-            "bmp" => {
-                cmd_parts.push("-pix_fmt".to_string());
-                cmd_parts.push("bgr24".to_string());
-            },
-            _ => {
-                cmd_parts.push("-q:v".to_string());
-                cmd_parts.push("2".to_string());
-            }
+        let mut quote_next = false;
+        for arg in Self::build_frame_extract_args(input_file, video_settings) {
+            cmd_parts.push(if quote_next { format!("\"{}\"", arg) } else { arg.clone() });
+            quote_next = arg == "-vf";
         }
 
-        // Build proper output pattern for multiple frames (same logic as execute)
-        let output_with_extension = if output_file.contains("%") {
-            // User specified a pattern, use it
-            output_file.to_string()
-        } else {
-            // Create a pattern for multiple frame files
-            let output_path = std::path::Path::new(output_file);
-            
-            // Get directory
-            let dir = if let Some(parent) = output_path.parent() {
-                parent
-            } else {
-                std::path::Path::new(".")
-            };
-            
-            // Get base filename without extension
-            let base = output_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("frame");
-            
-            // Create pattern: dir/filename_001.ext, dir/filename_002.ext, etc.
-            format!("{}/{}_%03d.{}", 
-                dir.display(), 
-                base, 
-                video_settings.frame_format
-            )
-        };
-
+        let output_with_extension = crate::frame_extract::pattern_for_output(output_file, &video_settings.frame_format);
         cmd_parts.push(format!("\"{}\"", output_with_extension));
 
         Ok(cmd_parts.join(" "))
@@ -2715,52 +3852,187 @@ impl TaskExecutor {
             return Err(anyhow::anyhow!("No input files specified"));
         }
 
-        let input_file = &task.input_files[0];
-        let output_file = &task.output_file;
+        let input_file = &task.input_files[0];
+        let output_file = &task.output_file;
+        let video_settings = task.video_settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
+
+        let output_ext = std::path::Path::new(output_file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let video_codec = if video_settings.codec == "auto" {
+            CodecManager::get_best_video_codec_for_format(&output_ext)
+        } else {
+            video_settings.codec.clone()
+        };
+
+        let normalized_output = Self::normalize_output_path_with_container(output_file, &video_codec, "auto", false);
+
+        let mut cmd = create_ffmpeg_command()?;
+        cmd.arg("-i").arg(input_file);
+        cmd.arg("-y");
+        cmd.arg("-v").arg("error");
+        cmd.arg("-hide_banner");
+        cmd.arg("-nostats");
+        cmd.arg("-nostdin");
+
+        // Video crop filter - use resolution settings as crop parameters
+        if video_settings.resolution.0 > 0 && video_settings.resolution.1 > 0 {
+            cmd.arg("-filter:v").arg(format!("crop={}:{}", video_settings.resolution.0, video_settings.resolution.1));
+        } else {
+            // Default crop to centered 16:9 ratio
+            cmd.arg("-filter:v").arg("crop=iw*0.8:ih*0.8:(iw-ow)/2:(ih-oh)/2");
+        }
+
+        cmd.arg("-c:v").arg(&video_codec);
+        cmd.arg("-c:a").arg("copy");
+
+        if video_settings.quality > 0 && video_settings.quality <= 51 {
+            if video_codec == "libx264" || video_codec == "libx265" {
+                cmd.arg("-crf").arg(video_settings.quality.to_string());
+            }
+        }
+
+        cmd.arg(&normalized_output);
+        Self::execute_ffmpeg_command_with_progress(cmd, None, None)
+    }
+
+    /// Build the segment plan shared by execution and command preview
+    fn build_cut_and_join_plan(task: &ProcessingTask, temp_dir: &str) -> Result<crate::cut_join::CutJoinPlan> {
+        if task.input_files.is_empty() {
+            return Err(anyhow::anyhow!("No input files specified"));
+        }
+
+        let input_file = &task.input_files[0];
+        let video_settings = task.video_settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
+
+        if video_settings.cut_reencode {
+            let output_ext = std::path::Path::new(&task.output_file)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            let video_codec = if video_settings.codec == "auto" {
+                CodecManager::get_best_video_codec_for_format(&output_ext)
+            } else {
+                video_settings.codec.clone()
+            };
+            let audio_codec = CodecManager::get_best_audio_codec_for_format(&output_ext);
+            let has_audio = Self::has_audio_stream(input_file);
+            crate::cut_join::plan_reencode(input_file, &task.output_file, &video_settings.cut_segments, &video_codec, &audio_codec, has_audio)
+        } else {
+            crate::cut_join::plan_stream_copy(input_file, &task.output_file, &video_settings.cut_segments, temp_dir)
+        }
+    }
+
+    fn build_review_clip_args(task: &ProcessingTask) -> Result<Vec<String>> {
+        let input_file = task.input_files.first()
+            .ok_or_else(|| anyhow::anyhow!("No input files specified"))?;
         let video_settings = task.video_settings.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
+        ComprehensiveCommandBuilder::build_review_clip_command(input_file, &task.output_file, video_settings, Self::has_audio_stream(input_file))
+    }
 
-        let output_ext = std::path::Path::new(output_file)
+    /// Arguments of a frame rate conversion, with the codecs resolved for the output container as
+    /// for a video conversion
+    fn build_frame_rate_args(task: &ProcessingTask) -> Result<Vec<String>> {
+        let input_file = task.input_files.first()
+            .ok_or_else(|| anyhow::anyhow!("No input files specified"))?;
+        let video_settings = task.video_settings.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
+        
+        let output_ext = std::path::Path::new(&task.output_file)
             .extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("")
             .to_lowercase();
-
-        let video_codec = if video_settings.codec == "auto" {
-            CodecManager::get_best_video_codec_for_format(&output_ext)
+        let mut video_codec = resolve_video_codec(video_settings, &output_ext);
+        let (normalized_output, final_output_ext) = Self::video_output_path_and_container(&task.output_file, &video_codec, video_settings);
+        if CodecManager::validate_codec_format_compatibility(&video_codec, &final_output_ext, false).is_err() {
+            video_codec = CodecManager::get_best_video_codec_for_format(&final_output_ext);
+        }
+        let mut updated_video_settings = video_settings.clone();
+        updated_video_settings.codec = video_codec;
+        updated_video_settings.container_format = final_output_ext.clone();
+        let updated_audio_settings = task.audio_settings.as_ref().map(|audio_settings| {
+            let mut audio_copy = audio_settings.clone();
+            audio_copy.codec = resolve_audio_codec(audio_settings, &final_output_ext);
+            audio_copy.format = final_output_ext.clone();
+            audio_copy
+        });
+        
+        // Only a speed change looks at the source: its rate sets the speed, and audio gets retimed
+        let (source_rate, has_audio) = if video_settings.fps_conversion.needs_source_rate() {
+            let source_rate = crate::probe_cache::probe_cache().get_file_info(input_file).ok()
+                .and_then(|info| info.video_streams.first().and_then(|stream| stream.avg_frame_rate.or(stream.r_frame_rate)));
+            (source_rate, Self::has_audio_stream(input_file))
         } else {
-            video_settings.codec.clone()
+            (None, true)
         };
-
-        let normalized_output = Self::normalize_output_path_with_container(output_file, &video_codec, "auto", false);
-
+        ComprehensiveCommandBuilder::build_frame_rate_command(
+            input_file,
+            &normalized_output,
+            &updated_video_settings,
+            updated_audio_settings.as_ref(),
+            source_rate,
+            has_audio,
+        )
+    }
+    
+    fn execute_frame_rate_convert(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        let args = Self::build_frame_rate_args(task)?;
         let mut cmd = create_ffmpeg_command()?;
-        cmd.arg("-i").arg(input_file);
-        cmd.arg("-y");
-        cmd.arg("-v").arg("error");
         cmd.arg("-hide_banner");
-        cmd.arg("-nostats");
         cmd.arg("-nostdin");
+        cmd.args(&args);
+        Self::execute_ffmpeg_command_with_progress(cmd, tasks, task_id)
+    }
 
-        // Video crop filter - use resolution settings as crop parameters
-        if video_settings.resolution.0 > 0 && video_settings.resolution.1 > 0 {
-            cmd.arg("-filter:v").arg(format!("crop={}:{}", video_settings.resolution.0, video_settings.resolution.1));
-        } else {
-            // Default crop to centered 16:9 ratio
-            cmd.arg("-filter:v").arg("crop=iw*0.8:ih*0.8:(iw-ow)/2:(ih-oh)/2");
-        }
-
-        cmd.arg("-c:v").arg(&video_codec);
-        cmd.arg("-c:a").arg("copy");
+    fn execute_review_clip(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        let args = Self::build_review_clip_args(task)?;
+        let mut cmd = create_ffmpeg_command()?;
+        cmd.arg("-hide_banner");
+        cmd.arg("-nostdin");
+        cmd.args(&args);
+        Self::execute_ffmpeg_command_with_progress(cmd, tasks, task_id)
+    }
 
-        if video_settings.quality > 0 && video_settings.quality <= 51 {
-            if video_codec == "libx264" || video_codec == "libx265" {
-                cmd.arg("-crf").arg(video_settings.quality.to_string());
+    fn execute_cut_and_join(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
+        let temp_dir = crate::temp_files::scratch_path("cut");
+        std::fs::create_dir_all(&temp_dir)?;
+        let plan = Self::build_cut_and_join_plan(task, &temp_dir.display().to_string())?;
+
+        if !task.video_settings.as_ref().map(|s| s.cut_reencode).unwrap_or(false) {
+            log_info!("Cut and join using stream copy - cuts snap to the nearest keyframe");
+        }
+        if let Some((list_path, contents)) = &plan.concat_list {
+            std::fs::write(list_path, contents)?;
+        }
+
+        let last = plan.commands.len().saturating_sub(1);
+        let mut result = Ok(());
+        for (i, args) in plan.commands.iter().enumerate() {
+            let mut cmd = create_ffmpeg_command()?;
+            cmd.arg("-v").arg("error");
+            cmd.arg("-hide_banner");
+            cmd.arg("-nostdin");
+            cmd.args(args);
+            // Only the final command reports progress to the task list
+            result = if i == last {
+                Self::execute_ffmpeg_command_with_progress(cmd, tasks.clone(), task_id)
+            } else {
+                Self::execute_ffmpeg_command_with_progress(cmd, None, None)
+            };
+            if result.is_err() {
+                break;
             }
         }
 
-        cmd.arg(&normalized_output);
-        Self::execute_ffmpeg_command_with_progress(cmd, None, None)
+        result
     }
 
     fn execute_video_rotate(task: &mut ProcessingTask) -> Result<()> {
@@ -2874,48 +4146,8 @@ impl TaskExecutor {
         cmd.arg("-nostats");
         cmd.arg("-nostdin");
 
-        // Build video filter chain
-        let mut filters = Vec::new();
-        
-        // Add basic filters
-        if video_settings.denoise {
-            filters.push("hqdn3d=4:3:6:4.5".to_string()); // Enhanced noise reduction effect
-        }
-        
-        if video_settings.deinterlace {
-            filters.push("yadif=1".to_string());
-        }
-        
-        if video_settings.stabilize {
-            filters.push("vidstabdetect=stepsize=6:shakiness=8:accuracy=9".to_string());
-        }
-        
-        // Color and brightness adjustments - use more visible effects
-        let mut color_adjustments = Vec::new();
-        
-        if (video_settings.brightness - 0.0).abs() > 0.01 {
-            color_adjustments.push(format!("brightness={}", video_settings.brightness));
-        }
-        
-        if (video_settings.contrast - 1.0).abs() > 0.01 {
-            color_adjustments.push(format!("contrast={}", video_settings.contrast));
-        }
-        
-        if (video_settings.saturation - 1.0).abs() > 0.01 {
-            color_adjustments.push(format!("saturation={}", video_settings.saturation));
-        }
-        
-        if !color_adjustments.is_empty() {
-            filters.push(format!("eq={}", color_adjustments.join(":")));
-        }
-        
-        // If there are custom parameters, add them at the end
-        if !video_settings.custom_args.is_empty() {
-            filters.push(video_settings.custom_args.clone());
-        }
-        
-        if !filters.is_empty() {
-            let filter_chain = filters.join(",");
+        let filter_chain = Self::video_filter_chain(input_file, video_settings)?;
+        if !filter_chain.is_empty() {
             cmd.arg("-filter:v").arg(&filter_chain);
             log_info!("Applying video filters: {}", filter_chain);
         }
@@ -2932,6 +4164,34 @@ impl TaskExecutor {
         Self::execute_ffmpeg_command_with_progress(cmd, None, None)
     }
 
+    /// -vf chain of the VideoFilter operation: the ordered filter stack, then stabilization and custom filters
+    fn video_filter_chain(input_file: &str, video_settings: &VideoSettings) -> Result<String> {
+        let stack = crate::filter_stack::effective_stack(video_settings);
+        let mut filters = Vec::new();
+        
+        let graph = crate::filter_stack::build_filtergraph(&stack)?;
+        if !graph.is_empty() {
+            filters.push(graph);
+        }
+        
+        if video_settings.stabilize {
+            filters.push("vidstabdetect=stepsize=6:shakiness=8:accuracy=9".to_string());
+        }
+        
+        // If there are custom parameters, add them at the end
+        if !video_settings.custom_args.is_empty() {
+            filters.push(video_settings.custom_args.clone());
+        }
+        
+        let resolution = crate::probe_cache::probe_cache().get_file_info(input_file).ok()
+            .and_then(|info| info.video_streams.first().map(|video| (video.width, video.height)));
+        if crate::filter_stack::is_heavy_for_resolution(&stack, resolution) {
+            log_warn!("CPU-heavy filter (nlmeans) on {}x{} input, expect slow processing", resolution.map(|r| r.0).unwrap_or(0), resolution.map(|r| r.1).unwrap_or(0));
+        }
+        
+        Ok(filters.join(","))
+    }
+
     fn execute_audio_trim(task: &mut ProcessingTask) -> Result<()> {
         if task.input_files.is_empty() {
             return Err(anyhow::anyhow!("No input files specified"));
@@ -2957,6 +4217,8 @@ impl TaskExecutor {
         let normalized_output = Self::normalize_output_path_with_container(output_file, &audio_codec, "auto", true);
 
         let mut cmd = create_ffmpeg_command()?;
+        // Range as input options so the filters only see the trimmed audio
+        cmd.args(Self::audio_trim_range_args(audio_settings)?);
         cmd.arg("-i").arg(input_file);
         cmd.arg("-y");
         cmd.arg("-v").arg("error");
@@ -2964,18 +4226,9 @@ impl TaskExecutor {
         cmd.arg("-nostats");
         cmd.arg("-nostdin");
 
-        // Audio trimming - extract time range from custom parameters, default to first 30 seconds
-        if !audio_settings.custom_args.is_empty() {
-            // Expected format: "start_time,duration" e.g. "10,30" means start at 10s, duration 30s
-            let parts: Vec<&str> = audio_settings.custom_args.split(',').collect();
-            if parts.len() >= 2 {
-                cmd.arg("-ss").arg(parts[0]); // Start time
-                cmd.arg("-t").arg(parts[1]);  // Duration
-            } else if parts.len() == 1 {
-                cmd.arg("-t").arg(parts[0]);  // Only specify duration, start from beginning
-            }
-        } else {
-            cmd.arg("-t").arg("30"); // Default first 30 seconds
+        let audio_filters = ComprehensiveCommandBuilder::audio_filter_chain(audio_settings)?;
+        if !audio_filters.is_empty() {
+            cmd.arg("-af").arg(audio_filters.join(","));
         }
 
         cmd.arg("-c:a").arg(&audio_codec);
@@ -2992,6 +4245,45 @@ impl TaskExecutor {
         Self::execute_ffmpeg_command_with_progress(cmd, None, None)
     }
 
+    /// Time range of an audio trim: the start/end fields when set, otherwise the legacy
+    /// "start,duration" custom parameters, otherwise the first 30 seconds
+    fn audio_trim_range_args(audio_settings: &AudioSettings) -> Result<Vec<String>> {
+        let parse = |value: &str| crate::locale_format::parse_duration(value)
+            .map_err(|_| anyhow::anyhow!("Invalid trim time '{}'", value));
+        // The default start of 00:00:00 alone does not select a range
+        let start = if audio_settings.start_time.trim().is_empty() { None } else { Some(parse(&audio_settings.start_time)?) }
+            .filter(|start| *start > 0.0);
+        let end = if audio_settings.end_time.trim().is_empty() { None } else { Some(parse(&audio_settings.end_time)?) };
+
+        let mut args = Vec::new();
+        if start.is_some() || end.is_some() {
+            if let (Some(start), Some(end)) = (start, end) {
+                if end <= start {
+                    return Err(anyhow::anyhow!("Trim end time must be after the start time"));
+                }
+            }
+            if let Some(start) = start {
+                args.push("-ss".to_string());
+                args.push(crate::locale_format::format_ffmpeg_duration(start));
+            }
+            if let Some(end) = end {
+                args.push("-to".to_string());
+                args.push(crate::locale_format::format_ffmpeg_duration(end));
+            }
+        } else if !audio_settings.custom_args.is_empty() {
+            // Expected format: "start_time,duration" e.g. "10,30" means start at 10s, duration 30s
+            let parts: Vec<&str> = audio_settings.custom_args.split(',').collect();
+            if parts.len() >= 2 {
+                args.extend(["-ss".to_string(), parts[0].trim().to_string(), "-t".to_string(), parts[1].trim().to_string()]);
+            } else if parts.len() == 1 {
+                args.extend(["-t".to_string(), parts[0].trim().to_string()]);
+            }
+        } else {
+            args.extend(["-t".to_string(), "30".to_string()]); // Default first 30 seconds
+        }
+        Ok(args)
+    }
+
     fn execute_audio_merge(task: &mut ProcessingTask) -> Result<()> {
         if task.input_files.len() < 2 {
             return Err(anyhow::anyhow!("Need at least 2 files for audio merge"));
@@ -3028,11 +4320,8 @@ impl TaskExecutor {
         cmd.arg("-nostats");
         cmd.arg("-nostdin");
 
-        // Use concat filter to merge audio
-        let filter_complex = format!("{}concat=n={}:v=0:a=1[out]", 
-            (0..task.input_files.len()).map(|i| format!("[{}:0]", i)).collect::<Vec<_>>().join(""),
-            task.input_files.len()
-        );
+        // Concatenate (optionally crossfaded) or mix with per-input gain
+        let filter_complex = crate::audio_mix::merge_filtergraph(audio_settings, task.input_files.len());
         cmd.arg("-filter_complex").arg(&filter_complex);
         cmd.arg("-map").arg("[out]");
 
@@ -3107,8 +4396,22 @@ impl TaskExecutor {
                 status: TaskStatus::Running,
                 error_message: None,
                 start_time: Some(std::time::Instant::now()),
+                paused_at: None,
                 estimated_total_time: None,
+                progress_unknown: false,
                 completion_time: None,
+                environment: task.environment.clone(),
+                size_outcome: None,
+                executed_commands: Vec::new(),
+                label: None,
+                cancel_requested: false,
+                delete_partial_output: false,
+                checksum: Default::default(),
+                low_priority: false,
+                archive: None,
+                timestamps: Default::default(),
+                guards: Default::default(),
+                log: Default::default(),
             };
 
             // Execute the corresponding operation
@@ -3130,6 +4433,11 @@ impl TaskExecutor {
                 }
             }
 
+            if task.timestamps.requested() {
+                let warnings = crate::file_times::apply(input_path, &auto_output, &task.timestamps);
+                task.timestamps.warnings.extend(warnings);
+            }
+
             log_info!("Batch processing: {} → {} completed", input_file, auto_output.display());
         }
 
@@ -3152,16 +4460,22 @@ impl TaskExecutor {
             return Err(anyhow::anyhow!("No subtitle file specified"));
         }
         
-        let subtitle_file = &video_settings.subtitle_file;
-        
         // Check if files exist
         if !std::path::Path::new(video_file).exists() {
             return Err(anyhow::anyhow!("Video file not found: {}", video_file));
         }
-        if !std::path::Path::new(subtitle_file).exists() {
-            return Err(anyhow::anyhow!("Subtitle file not found: {}", subtitle_file));
+        if !std::path::Path::new(&video_settings.subtitle_file).exists() {
+            return Err(anyhow::anyhow!("Subtitle file not found: {}", video_settings.subtitle_file));
         }
         
+        // Apply timing offset/stretch to a temporary copy of the subtitle file
+        let shifted_subtitle_file = crate::subtitle_timing::prepare_shifted_subtitle(
+            &video_settings.subtitle_file,
+            video_settings.subtitle_offset_ms,
+            video_settings.subtitle_stretch,
+        )?;
+        let subtitle_file = &shifted_subtitle_file;
+        
         // Check if the video file is actually a video (not a subtitle file)
         let video_ext = std::path::Path::new(video_file)
             .extension()
@@ -3219,6 +4533,7 @@ impl TaskExecutor {
                         convert_cmd.arg("-nostdin");
                         convert_cmd.arg(&temp_srt);
                         
+                        record_command(&convert_cmd);
                         if let Err(e) = convert_cmd.output() {
                             return Err(anyhow::anyhow!("Failed to convert WebVTT to SRT: {}", e));
                         }
@@ -3265,11 +4580,7 @@ impl TaskExecutor {
         cmd.arg("-nostdin");
 
         if subtitle_mode == "hard" {
-            // Hard subtitle - use the exact format you specified
-            let subtitle_path = actual_subtitle_file
-                .replace("\\", "\\\\")
-                .replace(":", "\\:");
-            cmd.arg("-vf").arg(format!("subtitles='{}'", subtitle_path));
+            cmd.arg("-vf").arg(ComprehensiveCommandBuilder::subtitles_filter(&actual_subtitle_file, video_settings));
             
             // Copy audio stream
             cmd.arg("-c:a").arg("copy");
@@ -3310,6 +4621,9 @@ impl TaskExecutor {
         if needs_cleanup {
             let _ = std::fs::remove_file(&actual_subtitle_file);
         }
+        if shifted_subtitle_file != video_settings.subtitle_file {
+            let _ = std::fs::remove_file(&shifted_subtitle_file);
+        }
         
         result
     }
@@ -3324,60 +4638,21 @@ impl TaskExecutor {
         
         let video_settings = task.video_settings.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
-
-        if video_settings.watermark_file.is_empty() {
-            return Err(anyhow::anyhow!("No watermark file specified"));
-        }
-        
-        // Check if watermark file exists
-        if !std::path::Path::new(&video_settings.watermark_file).exists() {
-            return Err(anyhow::anyhow!("Watermark file not found: {}", video_settings.watermark_file));
-        }
+        let has_image = Self::check_watermark_inputs(video_settings)?;
 
         let mut cmd = create_ffmpeg_command()?;
         cmd.arg("-i").arg(video_file);
-        cmd.arg("-i").arg(&video_settings.watermark_file);
+        if has_image {
+            cmd.arg("-i").arg(&video_settings.watermark_file);
+        }
         cmd.arg("-y");
         cmd.arg("-v").arg("error");
         cmd.arg("-hide_banner");
         cmd.arg("-nostats");
         cmd.arg("-nostdin");
 
-        // Build watermark position
-        let position = match video_settings.watermark_position.as_str() {
-            "top-left" => "10:10",
-            "top-right" => "main_w-overlay_w-10:10",
-            "bottom-left" => "10:main_h-overlay_h-10",
-            "bottom-right" => "main_w-overlay_w-10:main_h-overlay_h-10",
-            "center" => "(main_w-overlay_w)/2:(main_h-overlay_h)/2",
-            _ => "main_w-overlay_w-10:10"
-        };
-        
         // Build a proper filter_complex chain for watermark execution
-        // Handle scaling and opacity
-        let opacity = video_settings.watermark_opacity;
-        let scale = video_settings.watermark_scale;
-        
-        let filter_complex = if (scale - 1.0).abs() > 0.01 || (opacity - 1.0).abs() > 0.01 {
-            // Apply scaling and/or opacity
-            if (opacity - 1.0).abs() > 0.01 {
-                // Apply opacity using alpha channel manipulation
-                format!(
-                    "[1:v]scale=iw*{}:ih*{},format=rgba,colorchannelmixer=aa={}[wm];[0:v][wm]overlay={}",
-                    scale, scale, opacity, position
-                )
-            } else {
-                // Only scale, no opacity change
-                format!(
-                    "[1:v]scale=iw*{}:ih*{}[wm];[0:v][wm]overlay={}",
-                    scale, scale, position
-                )
-            }
-        } else {
-            // No scaling or opacity change
-            format!("[0:v][1:v]overlay={}", position)
-        };
-        
+        let filter_complex = ComprehensiveCommandBuilder::watermark_filter_complex(video_settings, has_image);
         cmd.arg("-filter_complex").arg(&filter_complex);
         cmd.arg("-c:a").arg("copy");
 
@@ -3394,16 +4669,41 @@ impl TaskExecutor {
         Self::execute_ffmpeg_command_with_progress(cmd, None, None)
     }
 
-    fn execute_frame_extract(task: &mut ProcessingTask) -> Result<()> {
+    /// A watermark needs an image, a text or both; returns whether the image is used
+    fn check_watermark_inputs(video_settings: &VideoSettings) -> Result<bool> {
+        let has_text = !video_settings.watermark_text.trim().is_empty();
+        if video_settings.watermark_file.is_empty() {
+            if has_text {
+                return Ok(false);
+            }
+            return Err(anyhow::anyhow!("No watermark file or text specified"));
+        }
+        
+        // Check if watermark file exists
+        if !std::path::Path::new(&video_settings.watermark_file).exists() {
+            return Err(anyhow::anyhow!("Watermark file not found: {}", video_settings.watermark_file));
+        }
+        Ok(true)
+    }
+
+    fn execute_frame_extract(task: &mut ProcessingTask, tasks: Option<Arc<Mutex<Vec<ProcessingTask>>>>, task_id: Option<usize>) -> Result<()> {
         if task.input_files.is_empty() {
             return Err(anyhow::anyhow!("No input files specified"));
         }
 
         let input_file = &task.input_files[0];
-        let output_file = &task.output_file;
         
         let video_settings = task.video_settings.as_ref()
             .ok_or_else(|| anyhow::anyhow!("No video settings specified"))?;
+        let output_pattern = crate::frame_extract::pattern_for_output(&task.output_file, &video_settings.frame_format);
+
+        // The UI checked the folder when queueing; it may have been removed since
+        let output_dir = std::path::Path::new(&output_pattern).parent()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        if !output_dir.is_empty() {
+            crate::frame_extract::prepare_output_dir(&output_dir).map_err(|e| anyhow::anyhow!("Output directory: {}", e))?;
+        }
 
         let mut cmd = create_ffmpeg_command()?;
         cmd.arg("-i").arg(input_file);
@@ -3412,99 +4712,16 @@ impl TaskExecutor {
         cmd.arg("-hide_banner");
         cmd.arg("-nostats");
         cmd.arg("-nostdin");
+        // Machine-readable frame= lines for progress
+        cmd.arg("-progress").arg("pipe:2");
 
-        // Handle different extraction modes
-        match video_settings.frame_extract_mode.as_str() {
-            "all" => {
-                // Extract all frames - no fps filter, just extract every frame
-                // Use select filter to get every frame
-                cmd.arg("-vsync").arg("0");
-            },
-            "interval" => {
-                // Extract every N frames using select filter for more precision
-                if video_settings.frame_interval > 0 {
-                    cmd.arg("-vf").arg(format!("select='not(mod(n,{}))'", video_settings.frame_interval));
-                    cmd.arg("-vsync").arg("0");
-                } else {
-                    cmd.arg("-vf").arg("select='not(mod(n,30))'");
-                    cmd.arg("-vsync").arg("0");
-                }
-            },
-            "time" => {
-                // Extract from time range
-                if !video_settings.frame_start_time.is_empty() {
-                    cmd.arg("-ss").arg(&video_settings.frame_start_time);
-                }
-                if !video_settings.frame_end_time.is_empty() {
-                    cmd.arg("-to").arg(&video_settings.frame_end_time);
-                }
-                // Extract one frame per second in the time range
-                cmd.arg("-vf").arg("fps=1");
-            },
-            _ => {
-                // Default: extract every 30th frame
-                cmd.arg("-vf").arg("select='not(mod(n,30))'");
-                cmd.arg("-vsync").arg("0");
-            }
-        }
-
-        // Set quality based on format
-        match video_settings.frame_format.as_str() {
-            "jpg" | "jpeg" => {
-                cmd.arg("-q:v").arg(video_settings.frame_quality.to_string());
-            },
-            "png" => {
-                cmd.arg("-pix_fmt").arg("rgb24");
-            },
-            "bmp" => {
-                cmd.arg("-pix_fmt").arg("bgr24");
-            },
-            _ => {
-                cmd.arg("-q:v").arg("2");
-            }
-        }
-
-        // Build proper output pattern for multiple frames
-        let output_with_extension = if output_file.contains("%") {
-            // User specified a pattern, use it
-            output_file.to_string()
-        } else {
-            // Create a pattern for multiple frame files
-            let output_path = std::path::Path::new(output_file);
-            
-            // Get directory (create if needed)
-            let dir = if let Some(parent) = output_path.parent() {
-                parent
-            } else {
-                std::path::Path::new(".")
-            };
-            
-            // Create output directory if it doesn't exist
-            if let Err(e) = std::fs::create_dir_all(dir) {
-                log_warn!("Could not create output directory: {}", e);
-            }
-            
-            // Get base filename without extension
-            let base = output_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("frame");
-            
-            // Create pattern: dir/filename_001.ext, dir/filename_002.ext, etc.
-            format!("{}/{}_%03d.{}", 
-                dir.display(), 
-                base, 
-                video_settings.frame_format
-            )
-        };
-
-        cmd.arg(&output_with_extension);
+        cmd.args(Self::build_frame_extract_args(input_file, video_settings));
+        cmd.arg(&output_pattern);
         
-        // Debug logging
         log_debug!("Frame extraction command: {:?}", cmd);
-        log_debug!("Output pattern: {}", output_with_extension);
         
-        Self::execute_ffmpeg_command_with_progress(cmd, None, None)
+        let expected_frames = Self::expected_extracted_frames(input_file, video_settings);
+        Self::run_ffmpeg_with_progress(cmd, tasks, task_id, expected_frames)
     }
     
     // Video to GIF conversion
@@ -3741,4 +4958,346 @@ impl TaskExecutor {
         cmd.arg(output_file);
         Self::execute_ffmpeg_command_with_progress(cmd, None, None)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn task(output: &std::path::Path) -> ProcessingTask {
+        ProcessingTask::new(OperationType::VideoConvert, vec!["in.mp4".to_string()], output.display().to_string())
+    }
+
+    fn outputs_ids(tasks: &Mutex<Vec<ProcessingTask>>) -> Vec<usize> {
+        tasks.lock().unwrap().iter().map(|task| task.id).collect()
+    }
+
+    fn outputs(executor: &TaskExecutor) -> Vec<String> {
+        executor.tasks.lock().unwrap().iter().map(|task| task.output_file.clone()).collect()
+    }
+
+    #[test]
+    fn identical_tasks_enqueued_at_once_get_distinct_outputs() {
+        let dir = temp_dir("executor_simultaneous");
+        let executor = Arc::new(TaskExecutor::new(Arc::new(Mutex::new(Vec::new()))));
+        let output = dir.join("out.mp4");
+        let barrier = Arc::new(std::sync::Barrier::new(16));
+        let handles: Vec<_> = (0..16).map(|_| {
+            let executor = executor.clone();
+            let barrier = barrier.clone();
+            let task = task(&output);
+            thread::spawn(move || {
+                barrier.wait();
+                executor.enqueue_task(task)
+            })
+        }).collect();
+        let renamed = handles.into_iter().filter_map(|handle| handle.join().unwrap()).count();
+
+        let outputs = outputs(&executor);
+        assert_eq!(outputs.len(), 16);
+        assert_eq!(renamed, 15);
+        let keys: HashSet<String> = outputs.iter().map(|path| output_path_key(path)).collect();
+        assert_eq!(keys.len(), 16);
+        assert!(outputs.contains(&output.display().to_string()));
+        assert!(outputs.contains(&dir.join("out_15.mp4").display().to_string()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn spellings_of_one_path_share_a_claim() {
+        let dir = temp_dir("executor_spellings");
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let executor = TaskExecutor::new(Arc::new(Mutex::new(Vec::new())));
+        assert_eq!(executor.enqueue_task(task(&dir.join("out.mp4"))), None);
+        assert!(executor.enqueue_task(task(&dir.join(".").join("out.mp4"))).is_some());
+        assert!(executor.enqueue_task(task(&dir.join("sub").join("..").join("out.mp4"))).is_some());
+        assert!(executor.enqueue_task(task(&dir.join("missing").join("..").join("out.mp4"))).is_some());
+        // Another folder does not collide
+        assert_eq!(executor.enqueue_task(task(&dir.join("sub").join("out.mp4"))), None);
+
+        let upper = dir.join("OUT.mp4");
+        let renamed = executor.enqueue_task(task(&upper));
+        assert_eq!(renamed.is_some(), cfg!(any(windows, target_os = "macos")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_linked_folder_shares_the_claim_of_its_target() {
+        let dir = temp_dir("executor_link");
+        std::fs::create_dir_all(dir.join("real")).unwrap();
+        std::os::unix::fs::symlink(dir.join("real"), dir.join("link")).unwrap();
+        let executor = TaskExecutor::new(Arc::new(Mutex::new(Vec::new())));
+        executor.enqueue_task(task(&dir.join("real").join("out.mp4")));
+        assert!(executor.enqueue_task(task(&dir.join("link").join("out.mp4"))).is_some());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_files_are_replaced_unless_kept_but_claims_always_rename() {
+        let dir = temp_dir("executor_overwrite");
+        let existing = dir.join("existing.mp4");
+        std::fs::write(&existing, b"earlier output").unwrap();
+        std::fs::write(dir.join("existing_1.mp4"), b"earlier output").unwrap();
+
+        let executor = TaskExecutor::new(Arc::new(Mutex::new(Vec::new())));
+        assert_eq!(executor.enqueue_task(task(&existing)), None);
+        // The second task is renamed because the first claims the path, not because it exists
+        assert_eq!(executor.enqueue_task(task(&existing)), Some(dir.join("existing_1.mp4").display().to_string()));
+
+        let executor = TaskExecutor::new(Arc::new(Mutex::new(Vec::new())));
+        executor.set_keep_existing_outputs(true);
+        // Both the file and its first numbered name exist
+        assert_eq!(executor.enqueue_task(task(&existing)), Some(dir.join("existing_2.mp4").display().to_string()));
+        assert_eq!(executor.enqueue_task(task(&existing)), Some(dir.join("existing_3.mp4").display().to_string()));
+        assert_eq!(executor.enqueue_task(task(&dir.join("new.mp4"))), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn pending(count: usize) -> Vec<ProcessingTask> {
+        (0..count).map(|i| task(std::path::Path::new(&format!("/no/such/out_{}.mp4", i)))).collect()
+    }
+
+    #[test]
+    fn claims_stop_at_the_concurrency_limit_and_resume_when_a_slot_frees() {
+        let mut tasks = pending(5);
+        let ids: Vec<usize> = tasks.iter().map(|task| task.id).collect();
+        let claim = |tasks: &mut Vec<ProcessingTask>, limit| TaskExecutor::claim_next_task(tasks, limit, 0).map(|task| task.id);
+        assert_eq!(claim(&mut tasks, 2), Some(ids[0]));
+        assert_eq!(claim(&mut tasks, 2), Some(ids[1]));
+        assert_eq!(claim(&mut tasks, 2), None);
+        assert_eq!(tasks[1].status, TaskStatus::Running);
+        assert!(tasks[1].start_time.is_some());
+
+        // A paused task still holds its slot; a finished one frees it
+        tasks[0].status = TaskStatus::Paused;
+        assert_eq!(claim(&mut tasks, 2), None);
+        tasks[1].status = TaskStatus::Failed;
+        assert_eq!(claim(&mut tasks, 2), Some(ids[2]));
+        assert_eq!(claim(&mut tasks, 2), None);
+
+        // A raised limit applies to the next pickup; zero counts as one
+        assert_eq!(claim(&mut tasks, 3), Some(ids[3]));
+        tasks[0].status = TaskStatus::Completed;
+        tasks[2].status = TaskStatus::Completed;
+        tasks[3].status = TaskStatus::Completed;
+        assert_eq!(claim(&mut tasks, 0), Some(ids[4]));
+        assert_eq!(claim(&mut tasks, 0), None);
+    }
+
+    #[test]
+    fn low_priority_tasks_wait_for_normal_ones() {
+        let mut tasks = pending(3);
+        tasks[0].low_priority = true;
+        let first = TaskExecutor::claim_next_task(&mut tasks, 1, 0).unwrap();
+        assert_eq!(first.id, tasks[1].id);
+        tasks[1].status = TaskStatus::Completed;
+        assert_eq!(TaskExecutor::claim_next_task(&mut tasks, 1, 0).unwrap().id, tasks[2].id);
+        tasks[2].status = TaskStatus::Completed;
+        assert_eq!(TaskExecutor::claim_next_task(&mut tasks, 1, 0).unwrap().id, tasks[0].id);
+    }
+
+    #[test]
+    fn ui_actions_never_lose_executor_updates() {
+        const TASKS: usize = 40;
+        const STEPS: u32 = 8;
+        const SLOTS: usize = 3;
+        let tasks = Arc::new(Mutex::new((0..TASKS)
+            .map(|i| task(std::path::Path::new(&format!("/no/such/dir/stress_{}.mp4", i))))
+            .collect::<Vec<_>>()));
+        let ids = outputs_ids(&tasks);
+        let ui_done = Arc::new(AtomicBool::new(false));
+
+        // Fake executor: claims pending tasks into a few slots and writes progress and status in
+        // small steps, one lock at a time, recording every write it makes
+        let executor = {
+            let (tasks, ui_done) = (tasks.clone(), ui_done.clone());
+            thread::spawn(move || {
+                let mut writes: HashMap<usize, u32> = HashMap::new();
+                loop {
+                    {
+                        let mut tasks = tasks.lock().unwrap();
+                        let running = tasks.iter().filter(|task| task.status == TaskStatus::Running).count();
+                        if running < SLOTS {
+                            if let Some(task) = tasks.iter_mut().find(|task| task.status == TaskStatus::Pending) {
+                                task.status = TaskStatus::Running;
+                                task.start_time = Some(std::time::Instant::now());
+                                writes.insert(task.id, 0);
+                            }
+                        }
+                        for task in tasks.iter_mut().filter(|task| task.status == TaskStatus::Running) {
+                            let written = writes.get_mut(&task.id).unwrap();
+                            *written += 1;
+                            task.progress = *written as f32 / STEPS as f32;
+                            if *written == STEPS {
+                                task.status = if task.cancel_requested { TaskStatus::Cancelled } else { TaskStatus::Completed };
+                            }
+                        }
+                        let idle = tasks.iter().all(|task| !matches!(task.status, TaskStatus::Pending | TaskStatus::Running));
+                        if idle && ui_done.load(Ordering::SeqCst) {
+                            return writes;
+                        }
+                    }
+                    thread::sleep(Duration::from_micros(50));
+                }
+            })
+        };
+
+        // UI: a fixed pseudo-random stream of actions on random ids, applied as the panel does
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound as u64) as usize
+        };
+        let mut labels: HashMap<usize, String> = HashMap::new();
+        // Until the executor is done, so actions land while tasks are claimed, run and finished
+        let mut overlapped = 0;
+        for round in 0.. {
+            let busy = tasks.lock().unwrap().iter().any(|task| matches!(task.status, TaskStatus::Pending | TaskStatus::Running));
+            if !busy && round >= 1000 {
+                break;
+            }
+            if tasks.lock().unwrap().iter().any(|task| task.status == TaskStatus::Running) {
+                overlapped += 1;
+            }
+            let id = ids[next(TASKS)];
+            let action = match next(10) {
+                0 => TaskAction::Remove(id),
+                1 => TaskAction::Cancel { id, delete_partial: false },
+                2..=5 => TaskAction::Move { id, up: next(2) == 0 },
+                _ => TaskAction::Relabel { id, label: format!(" label {} ", round) },
+            };
+            if let TaskAction::Relabel { label, .. } = &action {
+                labels.insert(id, label.trim().to_string());
+            }
+            apply_task_actions(&tasks, &[action]);
+            if round % 16 == 0 {
+                thread::yield_now();
+            }
+        }
+        ui_done.store(true, Ordering::SeqCst);
+        let writes = executor.join().unwrap();
+        assert!(overlapped > 100, "only {} actions ran alongside the executor", overlapped);
+
+        let tasks = tasks.lock().unwrap();
+        let remaining: HashSet<usize> = tasks.iter().map(|task| task.id).collect();
+        // A running task is never removed, so every task the executor claimed got all its writes;
+        // finished tasks may be removed like pending ones
+        for (id, written) in &writes {
+            assert_eq!(*written, STEPS, "task {} was removed while the executor ran it", id);
+        }
+        assert!(remaining.iter().all(|id| writes.contains_key(id)), "a task was left pending");
+        for task in tasks.iter() {
+            // Every progress and status write of the executor survived the actions
+            assert_eq!(task.progress, 1.0, "task {}", task.id);
+            let expected = if task.cancel_requested { TaskStatus::Cancelled } else { TaskStatus::Completed };
+            assert_eq!(task.status, expected, "task {}", task.id);
+            // And every relabel survived the executor's writes
+            assert_eq!(task.label.as_ref(), labels.get(&task.id), "task {}", task.id);
+        }
+    }
+
+    #[test]
+    fn finished_tasks_release_their_claim() {
+        let dir = temp_dir("executor_release");
+        let output = dir.join("out.mp4");
+        let executor = TaskExecutor::new(Arc::new(Mutex::new(Vec::new())));
+        executor.enqueue_task(task(&output));
+        for status in [TaskStatus::Completed, TaskStatus::Failed, TaskStatus::Cancelled] {
+            executor.tasks.lock().unwrap()[0].status = TaskStatus::Running;
+            assert!(executor.enqueue_task(task(&output)).is_some());
+            executor.tasks.lock().unwrap().truncate(1);
+            executor.tasks.lock().unwrap()[0].status = status;
+            assert_eq!(executor.enqueue_task(task(&output)), None);
+            executor.tasks.lock().unwrap().truncate(1);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn frame_extract_task(mode: &str, format: &str) -> ProcessingTask {
+        let video = VideoSettings {
+            frame_extract_mode: mode.to_string(),
+            frame_interval_seconds: 2.5,
+            frame_start_time: "00:01:05".to_string(),
+            frame_end_time: "1:20.5".to_string(),
+            frame_format: format.to_string(),
+            frame_quality: 40,
+            accurate_color_conversion: false,
+            ..Default::default()
+        };
+        let mut task = ProcessingTask::new(OperationType::FrameExtract, vec!["in.mp4".to_string()], "/out/shots/clip_%04d.jpg".to_string());
+        task.video_settings = Some(video);
+        task
+    }
+
+    #[test]
+    fn frame_extraction_every_n_seconds_inside_a_range_as_jpeg() {
+        let task = frame_extract_task(crate::frame_extract::MODE_EVERY_N_SECONDS, "jpg");
+        let args = TaskExecutor::build_frame_extract_args("in.mp4", task.video_settings.as_ref().unwrap());
+        assert_eq!(args, [
+            "-ss", "00:01:05.000", "-to", "00:01:20.500",
+            "-vf", "fps=1/2.5",
+            "-qscale:v", "31",
+        ]);
+        assert_eq!(
+            TaskExecutor::preview_frame_extract(&task).unwrap(),
+            "ffmpeg -i \"in.mp4\" -y -v error -hide_banner -nostats -nostdin \
+             -ss 00:01:05.000 -to 00:01:20.500 -vf \"fps=1/2.5\" -qscale:v 31 \"/out/shots/clip_%04d.jpg\"",
+        );
+    }
+
+    #[test]
+    fn frame_extraction_modes_and_image_formats() {
+        let args = |mode: &str, format: &str| {
+            let mut task = frame_extract_task(mode, format);
+            let video = task.video_settings.as_mut().unwrap();
+            video.frame_start_time.clear();
+            video.frame_end_time.clear();
+            video.frame_interval = 12;
+            TaskExecutor::build_frame_extract_args("in.mp4", video)
+        };
+        assert_eq!(args(crate::frame_extract::MODE_ALL, "png"), ["-vsync", "0", "-pix_fmt", "rgb24", "-compression_level", "6"]);
+        assert_eq!(args(crate::frame_extract::MODE_EVERY_N_FRAMES, "bmp"), ["-vsync", "0", "-vf", "select='not(mod(n,12))'", "-pix_fmt", "bgr24"]);
+        assert_eq!(args("time", "jpeg"), ["-vf", "fps=1/1", "-qscale:v", "31"]);
+    }
+
+    #[test]
+    fn a_tripped_guard_fails_the_task_and_records_the_trip() {
+        use crate::output_guard::GuardTrip;
+        let dir = temp_dir("executor_guard");
+        let tasks = Arc::new(Mutex::new(Vec::new()));
+        let trip = GuardTrip::Size { limit_mb: 100, written_bytes: 150 * 1024 * 1024 };
+
+        let mut guarded = task(&dir.join("out.mp4"));
+        guarded.status = TaskStatus::Running;
+        TaskExecutor::finish_task(&tasks, &mut guarded, Err(anyhow::Error::new(trip.clone())));
+        assert_eq!(guarded.status, TaskStatus::Failed);
+        assert_eq!(guarded.guards.tripped, Some(trip.clone()));
+        assert_eq!(guarded.error_message.as_deref(), Some(trip.to_string().as_str()));
+
+        // Even with the destination gone the trip is a failure, not a resumable lost output
+        let mut unreachable = task(&dir.join("gone").join("out.mp4"));
+        let duration = GuardTrip::Duration { limit_secs: 3600 };
+        TaskExecutor::finish_task(&tasks, &mut unreachable, Err(anyhow::Error::new(duration.clone())));
+        assert_eq!(unreachable.status, TaskStatus::Failed);
+        assert_eq!(unreachable.guards.tripped, Some(duration));
+
+        // Other failures leave no trip behind
+        let mut failed = task(&dir.join("out.mp4"));
+        TaskExecutor::finish_task(&tasks, &mut failed, Err(anyhow::anyhow!("Conversion failed: Invalid data found when processing input")));
+        assert_eq!(failed.status, TaskStatus::Failed);
+        assert_eq!(failed.guards.tripped, None);
+
+        // A cancel that raced the trip wins
+        let mut cancelled = task(&dir.join("out.mp4"));
+        cancelled.cancel_requested = true;
+        TaskExecutor::finish_task(&tasks, &mut cancelled, Err(anyhow::Error::new(trip)));
+        assert_eq!(cancelled.status, TaskStatus::Cancelled);
+        assert_eq!(cancelled.guards.tripped, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
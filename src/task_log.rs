@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use eframe::egui;
+
+use crate::app_state::ProcessingTask;
+use crate::language::Translations;
+
+/// Lines kept per task; older ones are dropped, so a long encode's log stays small
+pub const CAPACITY: usize = 500;
+
+/// The last CAPACITY lines ffmpeg wrote to stderr for a task, across all of its runs
+#[derive(Clone, Debug, Default)]
+pub struct TaskLog {
+    lines: VecDeque<String>,
+    /// Lines dropped to stay within CAPACITY
+    dropped: usize,
+}
+
+impl TaskLog {
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == CAPACITY {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Move all lines of `other` to the end of this log
+    pub fn append(&mut self, other: &mut TaskLog) {
+        self.dropped += std::mem::take(&mut other.dropped);
+        for line in other.lines.drain(..) {
+            self.push(line);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.dropped = 0;
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.iter().map(String::as_str).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Hand the lines read so far to task `task_id`. The reader must not stall ffmpeg's stderr, so
+/// unless `wait` is set the lines stay in `pending` while the task list is locked elsewhere.
+pub fn flush(tasks: &Mutex<Vec<ProcessingTask>>, task_id: usize, pending: &mut TaskLog, wait: bool) {
+    if pending.is_empty() {
+        return;
+    }
+    let tasks_guard = if wait { tasks.lock().ok() } else { tasks.try_lock().ok() };
+    if let Some(mut tasks_guard) = tasks_guard {
+        match tasks_guard.iter_mut().find(|t| t.id == task_id) {
+            Some(task) => task.log.append(pending),
+            None => pending.clear(),
+        }
+    }
+}
+
+/// Monospace view of the log, newest line at the bottom, with a copy button
+pub fn show(ui: &mut egui::Ui, log: &TaskLog, task_id: usize, translations: &Translations) {
+    ui.horizontal(|ui| {
+        if ui.small_button(translations.task_log_copy()).clicked() {
+            ui.output_mut(|o| o.copied_text = log.text());
+        }
+        if log.dropped > 0 {
+            ui.weak(translations.task_log_dropped(log.dropped));
+        }
+    });
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::both()
+        .id_salt(("task_log_scroll", task_id))
+        .max_height(160.0)
+        .stick_to_bottom(true)
+        .auto_shrink([false, true])
+        .show_rows(ui, row_height, log.lines.len(), |ui, row_range| {
+            for line in log.lines.range(row_range) {
+                ui.add(egui::Label::new(egui::RichText::new(line).monospace()).extend());
+            }
+        });
+}
@@ -0,0 +1,178 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+thread_local! {
+    /// Scratch directory of the task running on this thread; tasks run start to finish on one thread
+    static CURRENT_TASK_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Temporary files of running tasks. Each run of a task gets a private directory under the root,
+/// so auxiliary files with fixed names (two-pass logs, concat lists) never collide between tasks.
+pub struct TempFileRegistry {
+    root: PathBuf,
+    /// Leave task directories behind for debugging instead of removing them
+    keep: AtomicBool,
+}
+
+impl TempFileRegistry {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, keep: AtomicBool::new(false) }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn set_keep(&self, keep: bool) {
+        self.keep.store(keep, Ordering::Relaxed);
+    }
+
+    /// Make `task_{id}_{uuid}` the scratch directory of this thread until the returned guard is
+    /// dropped. The directory is created when the first path in it is asked for.
+    pub fn enter_task(&self, task_id: usize) -> TaskScratch<'_> {
+        let dir = self.root.join(format!("task_{}_{}", task_id, uuid::Uuid::new_v4().simple()));
+        let previous = CURRENT_TASK_DIR.with(|current| current.replace(Some(dir.clone())));
+        TaskScratch { registry: self, dir, previous }
+    }
+}
+
+/// A task's scratch directory while it runs; dropping it (the task reached a terminal state)
+/// removes the directory unless the registry keeps them
+pub struct TaskScratch<'a> {
+    registry: &'a TempFileRegistry,
+    dir: PathBuf,
+    /// Scratch directory of an outer task, e.g. the batch a sub-task belongs to
+    previous: Option<PathBuf>,
+}
+
+impl Drop for TaskScratch<'_> {
+    fn drop(&mut self) {
+        CURRENT_TASK_DIR.with(|current| current.replace(self.previous.take()));
+        if !self.dir.exists() {
+            return;
+        }
+        if self.registry.keep.load(Ordering::Relaxed) {
+            log_info!("Keeping task scratch directory {}", self.dir.display());
+        } else if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            log_warn!("Could not remove task scratch directory {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+/// The application's registry, rooted in the system temp directory
+pub fn registry() -> &'static TempFileRegistry {
+    static REGISTRY: OnceLock<TempFileRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| TempFileRegistry::new(std::env::temp_dir().join("ffmpeg_gui_scratch")))
+}
+
+/// Stand-in for a scratch path in command previews, which run outside a task and create nothing
+pub fn preview_path(name: &str) -> PathBuf {
+    registry().root().join("task").join(name)
+}
+
+/// Path for an auxiliary file of the running task, inside its scratch directory. Outside a task
+/// run (e.g. the subtitle preview) the name gets a unique prefix in the registry root.
+pub fn scratch_path(name: &str) -> PathBuf {
+    let dir = CURRENT_TASK_DIR.with(|current| current.borrow().clone());
+    let path = match dir {
+        Some(dir) => dir.join(name),
+        None => registry().root().join(format!("{}_{}", uuid::Uuid::new_v4().simple(), name)),
+    };
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            log_warn!("Could not create scratch directory {}: {}", parent.display(), e);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_registry() -> TempFileRegistry {
+        TempFileRegistry::new(std::env::temp_dir().join(format!("ffgui_scratch_test_{}", uuid::Uuid::new_v4().simple())))
+    }
+
+    #[test]
+    fn parallel_two_pass_tasks_never_share_aux_paths() {
+        let registry = std::sync::Arc::new(test_registry());
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        // Same task id on purpose: a task re-run while its previous run is still cleaning up
+        let handles: Vec<_> = (0..2).map(|_| {
+            let registry = registry.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                let _scratch = registry.enter_task(7);
+                let passlog = scratch_path("ffmpeg2pass");
+                let concat_list = scratch_path("concat_list.txt");
+                std::fs::write(format!("{}-0.log", passlog.display()), "pass 1 stats").unwrap();
+                // Both runs are inside their scratch directory at the same time
+                barrier.wait();
+                let stats = std::fs::read_to_string(format!("{}-0.log", passlog.display())).unwrap();
+                barrier.wait();
+                (passlog, concat_list, stats)
+            })
+        }).collect();
+        let runs: Vec<(PathBuf, PathBuf, String)> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+
+        assert_ne!(runs[0].0, runs[1].0);
+        assert_ne!(runs[0].0.parent(), runs[1].0.parent());
+        for (passlog, concat_list, stats) in &runs {
+            assert_eq!(stats, "pass 1 stats");
+            assert_eq!(passlog.parent(), concat_list.parent());
+            let dir = passlog.parent().unwrap();
+            assert_eq!(dir.parent(), Some(registry.root()));
+            assert!(dir.file_name().unwrap().to_string_lossy().starts_with("task_7_"));
+            // Removed once the run ended
+            assert!(!dir.exists());
+        }
+        let _ = std::fs::remove_dir_all(registry.root());
+    }
+
+    #[test]
+    fn nested_runs_restore_the_outer_directory() {
+        let registry = test_registry();
+        let outer = registry.enter_task(1);
+        let batch_file = scratch_path("batch.txt");
+        assert_eq!(batch_file.parent(), Some(outer.dir.as_path()));
+        {
+            let _inner = registry.enter_task(2);
+            let sub_file = scratch_path("batch.txt");
+            assert_ne!(sub_file, batch_file);
+            assert!(sub_file.parent().unwrap().is_dir());
+        }
+        assert_eq!(scratch_path("batch.txt"), batch_file);
+        drop(outer);
+        assert!(!batch_file.parent().unwrap().exists());
+
+        // Outside a run every path is unique in the registry root
+        let first = scratch_path("preview.srt");
+        assert_ne!(first, scratch_path("preview.srt"));
+        assert_eq!(first.parent(), Some(super::registry().root()));
+        let _ = std::fs::remove_dir_all(registry.root());
+    }
+
+    #[test]
+    fn kept_directories_stay_for_debugging() {
+        let registry = test_registry();
+        registry.set_keep(true);
+        let dir = {
+            let _scratch = registry.enter_task(3);
+            let path = scratch_path("ffmpeg2pass");
+            std::fs::write(&path, "x").unwrap();
+            path.parent().unwrap().to_path_buf()
+        };
+        assert!(dir.join("ffmpeg2pass").exists());
+
+        // A run that never asked for a path leaves nothing behind
+        registry.set_keep(false);
+        let unused = registry.enter_task(4).dir.clone();
+        assert!(!unused.exists());
+        let _ = std::fs::remove_dir_all(registry.root());
+
+        assert!(preview_path("cut").ends_with(Path::new("task").join("cut")));
+    }
+}
@@ -0,0 +1,412 @@
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Deserialize};
+use crate::language::Translations;
+
+/// Gap between the text and the frame edge for the preset positions, in pixels
+const EDGE_MARGIN: u32 = 10;
+
+/// Where drawtext places the text; Custom uses the x/y expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    Custom,
+}
+
+impl TextPosition {
+    pub fn all() -> [TextPosition; 8] {
+        [
+            TextPosition::TopLeft, TextPosition::TopCenter, TextPosition::TopRight, TextPosition::Center,
+            TextPosition::BottomLeft, TextPosition::BottomCenter, TextPosition::BottomRight, TextPosition::Custom,
+        ]
+    }
+
+    /// Stable key used in node parameters
+    pub fn key(&self) -> &'static str {
+        match self {
+            TextPosition::TopLeft => "top_left",
+            TextPosition::TopCenter => "top_center",
+            TextPosition::TopRight => "top_right",
+            TextPosition::Center => "center",
+            TextPosition::BottomLeft => "bottom_left",
+            TextPosition::BottomCenter => "bottom_center",
+            TextPosition::BottomRight => "bottom_right",
+            TextPosition::Custom => "custom",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<TextPosition> {
+        TextPosition::all().into_iter().find(|position| position.key() == key.trim())
+    }
+}
+
+/// Font value the text starts with: a font every installation of the platform has. Elsewhere
+/// drawtext asks fontconfig for its default.
+pub fn default_font() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "arial.ttf"
+    } else if cfg!(target_os = "macos") {
+        "Helvetica.ttc"
+    } else {
+        ""
+    }
+}
+
+/// Text drawn over the video with drawtext, optionally on a box and only within a time range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextOverlaySettings {
+    pub text: String,
+    /// Family, font file name or path, as resolved by font_resolver
+    pub font: String,
+    pub font_size: u32,
+    /// RGBA
+    pub color: [u8; 4],
+    pub position: TextPosition,
+    /// drawtext x/y expressions for TextPosition::Custom, e.g. "(w-tw)/2" or "h-th-40"
+    pub x: String,
+    pub y: String,
+    pub background_box: bool,
+    pub box_color: [u8; 4],
+    /// Seconds into the video the text appears at and disappears at; None shows it from the start
+    /// or until the end
+    pub start_seconds: Option<f64>,
+    pub end_seconds: Option<f64>,
+}
+
+impl Default for TextOverlaySettings {
+    fn default() -> Self {
+        Self {
+            text: "Sample Text".to_string(),
+            font: default_font().to_string(),
+            font_size: 48,
+            color: [255, 255, 255, 255],
+            position: TextPosition::BottomCenter,
+            x: "(w-tw)/2".to_string(),
+            y: "h-th-40".to_string(),
+            background_box: false,
+            box_color: [0, 0, 0, 128],
+            start_seconds: None,
+            end_seconds: None,
+        }
+    }
+}
+
+/// drawtext color with alpha, "0xRRGGBBAA"
+pub fn color_value(color: [u8; 4]) -> String {
+    format!("0x{:02X}{:02X}{:02X}{:02X}", color[0], color[1], color[2], color[3])
+}
+
+/// Parse "0xRRGGBB[AA]" / "#RRGGBB[AA]"; without alpha the color is opaque
+pub fn parse_color(value: &str) -> Option<[u8; 4]> {
+    let hex = value.trim().trim_start_matches("0x").trim_start_matches('#');
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?, if hex.len() == 8 { channel(6)? } else { 255 }])
+}
+
+impl TextOverlaySettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.text.is_empty() {
+            return Err(anyhow!("There is no text to draw"));
+        }
+        if self.font_size == 0 {
+            return Err(anyhow!("The font size must be at least 1"));
+        }
+        if self.position == TextPosition::Custom && (self.x.trim().is_empty() || self.y.trim().is_empty()) {
+            return Err(anyhow!("A custom position needs both an x and a y expression"));
+        }
+        for seconds in [self.start_seconds, self.end_seconds].into_iter().flatten() {
+            if !seconds.is_finite() || seconds < 0.0 {
+                return Err(anyhow!("Times must be 0 seconds or more, got {}", seconds));
+            }
+        }
+        if let (Some(start), Some(end)) = (self.start_seconds, self.end_seconds) {
+            if end <= start {
+                return Err(anyhow!("The text must disappear after it appears ({}s to {}s)", start, end));
+            }
+        }
+        Ok(())
+    }
+
+    /// x and y expressions; tw/th are the rendered text's width and height
+    fn position_expressions(&self) -> (String, String) {
+        let margin = EDGE_MARGIN;
+        let left = margin.to_string();
+        let center_x = "(w-tw)/2".to_string();
+        let right = format!("w-tw-{}", margin);
+        let top = margin.to_string();
+        let bottom = format!("h-th-{}", margin);
+        match self.position {
+            TextPosition::TopLeft => (left, top),
+            TextPosition::TopCenter => (center_x, top),
+            TextPosition::TopRight => (right, top),
+            TextPosition::Center => (center_x, "(h-th)/2".to_string()),
+            TextPosition::BottomLeft => (left, bottom),
+            TextPosition::BottomCenter => (center_x, bottom),
+            TextPosition::BottomRight => (right, bottom),
+            TextPosition::Custom => (self.x.trim().to_string(), self.y.trim().to_string()),
+        }
+    }
+
+    /// The timeline expression limiting the text to its time range, None when it always shows
+    fn enable_expression(&self) -> Option<String> {
+        match (self.start_seconds, self.end_seconds) {
+            (Some(start), Some(end)) => Some(format!("between(t,{},{})", start, end)),
+            (Some(start), None) => Some(format!("gte(t,{})", start)),
+            (None, Some(end)) => Some(format!("lte(t,{})", end)),
+            (None, None) => None,
+        }
+    }
+
+    /// The drawtext filter. The text, the expressions and the font path are quoted for the
+    /// filtergraph, and text expansion is off, so ':', quotes, '%' and Windows paths stay literal.
+    pub fn filter(&self) -> Result<String> {
+        self.validate()?;
+        let (x, y) = self.position_expressions();
+        let mut filter = format!(
+            "drawtext=text={}:expansion=none:fontsize={}:fontcolor={}:x={}:y={}",
+            crate::comprehensive_command_builder::ComprehensiveCommandBuilder::quote_filter_value(&self.text),
+            self.font_size, color_value(self.color),
+            crate::overlay::filter_expression(&x), crate::overlay::filter_expression(&y)
+        );
+        if let Some(font) = crate::font_resolver::drawtext_font_option(&self.font) {
+            filter.push(':');
+            filter.push_str(&font);
+        }
+        if self.background_box {
+            filter.push_str(&format!(":box=1:boxcolor={}:boxborderw={}", color_value(self.box_color), self.font_size / 4 + 1));
+        }
+        if let Some(enable) = self.enable_expression() {
+            filter.push_str(&format!(":enable={}", crate::overlay::filter_expression(&enable)));
+        }
+        Ok(filter)
+    }
+}
+
+/// An optional time in seconds as a checkbox plus value
+fn show_optional_time(ui: &mut egui::Ui, label: &str, seconds: &mut Option<f64>, fallback: f64) -> bool {
+    let mut changed = false;
+    let mut enabled = seconds.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *seconds = enabled.then_some(fallback);
+        changed = true;
+    }
+    if let Some(value) = seconds {
+        changed |= ui.add(egui::DragValue::new(value).range(0.0..=86_400.0).speed(0.1).suffix(" s")).changed();
+    }
+    changed
+}
+
+fn position_label(position: TextPosition, translations: &Translations) -> &'static str {
+    match position {
+        TextPosition::TopLeft => translations.text_overlay_top_left(),
+        TextPosition::TopCenter => translations.text_overlay_top_center(),
+        TextPosition::TopRight => translations.text_overlay_top_right(),
+        TextPosition::Center => translations.text_overlay_center(),
+        TextPosition::BottomLeft => translations.text_overlay_bottom_left(),
+        TextPosition::BottomCenter => translations.text_overlay_bottom_center(),
+        TextPosition::BottomRight => translations.text_overlay_bottom_right(),
+        TextPosition::Custom => translations.text_overlay_custom(),
+    }
+}
+
+/// Text, font, size, colors, position and time range. Returns true when the settings changed.
+pub fn show_editor(ui: &mut egui::Ui, settings: &mut TextOverlaySettings, translations: &Translations) -> bool {
+    let mut changed = false;
+
+    ui.label(translations.text_overlay_text());
+    changed |= ui.add(egui::TextEdit::multiline(&mut settings.text).desired_rows(2).desired_width(260.0)).changed();
+    changed |= crate::font_resolver::show_font_row(ui, &mut settings.font, translations);
+    ui.horizontal(|ui| {
+        ui.label(translations.text_overlay_font_size());
+        changed |= ui.add(egui::DragValue::new(&mut settings.font_size).range(1..=1000)).changed();
+        ui.label(translations.text_overlay_color());
+        changed |= ui.color_edit_button_srgba_unmultiplied(&mut settings.color).changed();
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(translations.text_overlay_position());
+        egui::ComboBox::from_id_salt("text_overlay_position")
+            .selected_text(position_label(settings.position, translations))
+            .show_ui(ui, |ui| {
+                for position in TextPosition::all() {
+                    changed |= ui.selectable_value(&mut settings.position, position, position_label(position, translations)).changed();
+                }
+            });
+    });
+    if settings.position == TextPosition::Custom {
+        ui.horizontal(|ui| {
+            ui.label("x:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut settings.x).desired_width(110.0)).changed();
+            ui.label("y:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut settings.y).desired_width(110.0)).changed();
+        }).response.on_hover_text(translations.text_overlay_position_hint());
+    }
+
+    ui.horizontal(|ui| {
+        changed |= ui.checkbox(&mut settings.background_box, translations.text_overlay_box()).changed();
+        if settings.background_box {
+            changed |= ui.color_edit_button_srgba_unmultiplied(&mut settings.box_color).changed();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        changed |= show_optional_time(ui, translations.text_overlay_start(), &mut settings.start_seconds, 0.0);
+        let end_fallback = settings.start_seconds.unwrap_or(0.0) + 5.0;
+        changed |= show_optional_time(ui, translations.text_overlay_end(), &mut settings.end_seconds, end_fallback);
+    });
+
+    if let Err(e) = settings.validate() {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", e));
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// ffmpeg's av_get_token: up to the first unquoted, unescaped terminator, with '…' quoting,
+    /// \ escaping and surrounding whitespace dropped. Returns the token and the rest.
+    fn get_token<'a>(text: &'a str, terminators: &str) -> (String, &'a str) {
+        let text = text.trim_start();
+        let mut token = String::new();
+        let mut kept = 0;
+        let mut chars = text.char_indices().peekable();
+        while let Some((at, c)) = chars.next() {
+            if terminators.contains(c) {
+                return (token[..kept].to_string(), &text[at..]);
+            }
+            match c {
+                '\\' => {
+                    if let Some((_, next)) = chars.next() {
+                        token.push(next);
+                    }
+                    kept = token.len();
+                }
+                '\'' => {
+                    for (_, quoted) in chars.by_ref() {
+                        if quoted == '\'' {
+                            break;
+                        }
+                        token.push(quoted);
+                    }
+                    kept = token.len();
+                }
+                _ => {
+                    token.push(c);
+                    if !c.is_whitespace() {
+                        kept = token.len();
+                    }
+                }
+            }
+        }
+        (token[..kept].to_string(), "")
+    }
+
+    /// The options drawtext receives for `filter`, unescaped like ffmpeg: first by the
+    /// filtergraph parser, then by the option parser
+    fn drawtext_options(filter: &str) -> HashMap<String, String> {
+        let arguments = filter.strip_prefix("drawtext=").expect("a drawtext filter");
+        let (arguments, rest) = get_token(arguments, "[],;");
+        assert_eq!(rest, "", "the filter ends early in {}", filter);
+        let mut options = HashMap::new();
+        let mut rest = arguments.as_str();
+        while !rest.is_empty() {
+            let (key, value) = rest.split_once('=').expect("key=value");
+            let (value, after) = get_token(value, ":");
+            options.insert(key.to_string(), value);
+            rest = after.strip_prefix(':').unwrap_or(after);
+        }
+        options
+    }
+
+    fn settings(text: &str, font: &str) -> TextOverlaySettings {
+        TextOverlaySettings { text: text.to_string(), font: font.to_string(), ..TextOverlaySettings::default() }
+    }
+
+    #[test]
+    fn text_reaches_drawtext_unchanged() {
+        for text in [
+            "Time: 12:30:45",
+            "It's \"quoted\" and 'single'",
+            "100% done, %{pts} and %%",
+            r"back\slash and C:\temp",
+            "[brackets];semicolons,commas=equals",
+            "  padded  ",
+            "多语言: 字幕",
+        ] {
+            let filter = settings(text, "").filter().unwrap();
+            let options = drawtext_options(&filter);
+            assert_eq!(options["text"], text, "in {}", filter);
+            // Without expansion drawtext prints '%' as is
+            assert_eq!(options["expansion"], "none");
+        }
+    }
+
+    #[test]
+    fn windows_font_paths_keep_their_drive_colon() {
+        for font in [r"C:\Windows\Fonts\arial.ttf", r"C\:/Windows/Fonts/arial.ttf", "C:/Windows/Fonts/arial.ttf"] {
+            let filter = settings("a", font).filter().unwrap();
+            let options = drawtext_options(&filter);
+            assert_eq!(options["fontfile"], "C:/Windows/Fonts/arial.ttf", "for {} in {}", font, filter);
+            assert_eq!(options["text"], "a");
+        }
+        let filter = settings("a", "/no/such/o'brien's fonts/x.ttf").filter().unwrap();
+        assert_eq!(drawtext_options(&filter)["fontfile"], "/no/such/o'brien's fonts/x.ttf");
+    }
+
+    #[test]
+    fn expressions_and_times_survive_quoting() {
+        let custom = TextOverlaySettings {
+            position: TextPosition::Custom,
+            x: "if(gte(t,2),10,w-tw)".to_string(),
+            y: "h-th-40".to_string(),
+            start_seconds: Some(1.5),
+            end_seconds: Some(4.0),
+            background_box: true,
+            ..settings("a", "")
+        };
+        let filter = custom.filter().unwrap();
+        let options = drawtext_options(&filter);
+        assert_eq!(options["x"], "if(gte(t,2),10,w-tw)");
+        assert_eq!(options["y"], "h-th-40");
+        assert_eq!(options["enable"], "between(t,1.5,4)");
+        assert_eq!(options["boxcolor"], "0x00000080");
+        assert_eq!(options["fontcolor"], "0xFFFFFFFF");
+        assert_eq!(options["boxborderw"], "13");
+        assert!(!options.contains_key("fontfile") && !options.contains_key("font"));
+
+        let bottom_right = TextOverlaySettings { position: TextPosition::BottomRight, ..settings("a", "") };
+        let options = drawtext_options(&bottom_right.filter().unwrap());
+        assert_eq!((options["x"].as_str(), options["y"].as_str()), ("w-tw-10", "h-th-10"));
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(settings("", "").filter().is_err());
+        assert!(TextOverlaySettings { font_size: 0, ..settings("a", "") }.validate().is_err());
+        assert!(TextOverlaySettings { position: TextPosition::Custom, x: " ".to_string(), ..settings("a", "") }.validate().is_err());
+        assert!(TextOverlaySettings { start_seconds: Some(3.0), end_seconds: Some(3.0), ..settings("a", "") }.validate().is_err());
+        assert!(TextOverlaySettings { start_seconds: Some(-1.0), ..settings("a", "") }.validate().is_err());
+    }
+
+    #[test]
+    fn colors_round_trip() {
+        assert_eq!(parse_color("#FF8000"), Some([255, 128, 0, 255]));
+        assert_eq!(parse_color(&color_value([1, 2, 3, 4])), Some([1, 2, 3, 4]));
+        assert_eq!(parse_color("0x12345"), None);
+        assert_eq!(parse_color("#GG0000"), None);
+    }
+}
@@ -0,0 +1,268 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{Receiver, Sender};
+use crate::app_state::CutSegment;
+use crate::language::{Language, Translations};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+const STRIP_HEIGHT: f32 = 36.0;
+const THUMBNAIL_HEIGHT: f32 = 48.0;
+const HANDLE_WIDTH: f32 = 6.0;
+pub const MIN_SEGMENT_SECONDS: f64 = 0.1;
+
+/// Timeline strip state: selected handle and boundary thumbnails for the current input
+pub struct TimelineState {
+    /// Selected handle as (segment index, is end handle)
+    selected_handle: Option<(usize, bool)>,
+    source: String,
+    thumbnails: HashMap<u64, egui::TextureHandle>,
+    pending: HashSet<u64>,
+    sender: Sender<(String, u64, egui::ColorImage)>,
+    receiver: Receiver<(String, u64, egui::ColorImage)>,
+}
+
+impl Default for TimelineState {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            selected_handle: None,
+            source: String::new(),
+            thumbnails: HashMap::new(),
+            pending: HashSet::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl TimelineState {
+    /// Collect finished thumbnails and request missing ones for every segment boundary
+    fn update_thumbnails(&mut self, ctx: &egui::Context, input_file: &str, segments: &[CutSegment]) {
+        if self.source != input_file {
+            self.source = input_file.to_string();
+            self.thumbnails.clear();
+            self.pending.clear();
+        }
+
+        while let Ok((source, key, image)) = self.receiver.try_recv() {
+            self.pending.remove(&key);
+            if source == self.source {
+                let texture = ctx.load_texture(format!("timeline_thumb_{}", key), image, egui::TextureOptions::LINEAR);
+                self.thumbnails.insert(key, texture);
+            }
+        }
+
+        for segment in segments {
+            for time in [segment.start, segment.end] {
+                let key = thumbnail_key(time);
+                if self.thumbnails.contains_key(&key) || self.pending.contains(&key) {
+                    continue;
+                }
+                self.pending.insert(key);
+                let sender = self.sender.clone();
+                let source = self.source.clone();
+                let ctx = ctx.clone();
+                std::thread::spawn(move || {
+                    if let Some(image) = extract_thumbnail(&source, key as f64 / 1000.0) {
+                        let _ = sender.send((source, key, image));
+                        ctx.request_repaint();
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Thumbnails are cached at half-second granularity so dragging doesn't spawn a frame grab per pixel
+fn thumbnail_key(time: f64) -> u64 {
+    ((time * 2.0).round() * 500.0) as u64
+}
+
+fn extract_thumbnail(input_file: &str, time: f64) -> Option<egui::ColorImage> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    let time_arg = format!("{:.3}", time);
+    let color_filter = crate::color_conversion::probe_color_metadata(input_file)
+        .and_then(|metadata| crate::color_conversion::image_conversion_filter(&metadata, "png"));
+    let height_arg = match color_filter {
+        Some(filter) => format!("scale=-2:{},{}", THUMBNAIL_HEIGHT as u32, filter),
+        None => format!("scale=-2:{}", THUMBNAIL_HEIGHT as u32),
+    };
+    let output = ffmpeg.run_ffmpeg(&[
+        "-v", "error", "-ss", &time_arg, "-i", input_file,
+        "-frames:v", "1", "-vf", &height_arg,
+        "-f", "image2pipe", "-vcodec", "png", "-",
+    ]).ok()?;
+    let image = image::load_from_memory(&output.stdout).ok()?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    log_debug!("Extracted timeline thumbnail at {:.3}s", time);
+    Some(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+fn format_time(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let minutes = total_ms / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let ms = total_ms % 1000;
+    format!("{}:{:02}.{:03}", minutes, secs, ms)
+}
+
+/// Millisecond time field (m:ss.mmm) that also accepts typed durations
+pub fn time_field(value: &mut f64, duration: f64) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .speed(0.1)
+        .range(0.0..=duration)
+        .custom_formatter(|v, _| format_time(v))
+        .custom_parser(|text| crate::locale_format::parse_duration(text).ok())
+}
+
+pub struct TimelineStrip;
+
+impl TimelineStrip {
+    /// Draw the segment strip; returns true when the segment list changed
+    pub fn show(ui: &mut egui::Ui, state: &mut TimelineState, input_file: &str, duration: f64, segments: &mut Vec<CutSegment>, translations: &Translations) -> bool {
+        let is_chinese = translations.language == Language::Chinese;
+        let mut changed = false;
+
+        if duration <= 0.0 {
+            ui.label(if is_chinese { "无法获取输入时长，请先选择有效的视频文件" } else { "Input duration unknown - select a valid video file first" });
+            return false;
+        }
+
+        state.update_thumbnails(ui.ctx(), input_file, segments);
+
+        ui.horizontal(|ui| {
+            if ui.button(if is_chinese { "➕ 添加片段" } else { "➕ Add segment" }).clicked() {
+                let start = segments.last().map(|s| s.end).unwrap_or(0.0);
+                let length = (duration * 0.1).max(MIN_SEGMENT_SECONDS);
+                if start + MIN_SEGMENT_SECONDS < duration {
+                    segments.push(CutSegment { start, end: (start + length).min(duration) });
+                    state.selected_handle = Some((segments.len() - 1, true));
+                    changed = true;
+                }
+            }
+            let can_remove = state.selected_handle.map(|(i, _)| i < segments.len()).unwrap_or(false);
+            if ui.add_enabled(can_remove, egui::Button::new(if is_chinese { "➖ 删除所选片段" } else { "➖ Remove selected" })).clicked() {
+                if let Some((index, _)) = state.selected_handle.take() {
+                    segments.remove(index);
+                    changed = true;
+                }
+            }
+            ui.label(format!("{} {}", if is_chinese { "保留时长:" } else { "Kept:" }, format_time(crate::cut_join::kept_duration(segments))));
+        });
+
+        let width = ui.available_width().max(100.0);
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(width, THUMBNAIL_HEIGHT + STRIP_HEIGHT + 4.0), egui::Sense::click_and_drag());
+        let strip = egui::Rect::from_min_size(
+            egui::pos2(rect.min.x, rect.min.y + THUMBNAIL_HEIGHT + 4.0),
+            egui::vec2(width, STRIP_HEIGHT),
+        );
+        let time_to_x = |t: f64| strip.min.x + (t / duration) as f32 * strip.width();
+        let x_to_time = |x: f32| (((x - strip.min.x) / strip.width()) as f64 * duration).clamp(0.0, duration);
+
+        // Pick the handle nearest to the pointer when a drag starts or on click
+        if response.drag_started() || response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                state.selected_handle = segments.iter().enumerate()
+                    .flat_map(|(i, s)| [((i, false), time_to_x(s.start)), ((i, true), time_to_x(s.end))])
+                    .map(|(handle, x)| (handle, (x - pos.x).abs()))
+                    .filter(|(_, distance)| *distance <= HANDLE_WIDTH * 2.0)
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                    .map(|(handle, _)| handle);
+            }
+        }
+
+        if response.dragged() {
+            if let (Some(handle), Some(pos)) = (state.selected_handle, response.interact_pointer_pos()) {
+                changed |= Self::move_handle(segments, handle, x_to_time(pos.x), duration);
+            }
+        }
+
+        // Keyboard nudging: arrows move 0.1s, with Shift 1s
+        if state.selected_handle.is_some() && !ui.ctx().wants_keyboard_input() {
+            let (left, right, shift) = ui.input(|i| (
+                i.key_pressed(egui::Key::ArrowLeft),
+                i.key_pressed(egui::Key::ArrowRight),
+                i.modifiers.shift,
+            ));
+            let step = if shift { 1.0 } else { 0.1 };
+            let delta = if left { -step } else if right { step } else { 0.0 };
+            if delta != 0.0 {
+                if let Some((index, is_end)) = state.selected_handle {
+                    if let Some(segment) = segments.get(index) {
+                        let current = if is_end { segment.end } else { segment.start };
+                        changed |= Self::move_handle(segments, (index, is_end), current + delta, duration);
+                    }
+                }
+            }
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(strip, 4.0, ui.visuals().extreme_bg_color);
+
+        for (i, segment) in segments.iter().enumerate() {
+            let segment_rect = egui::Rect::from_x_y_ranges(time_to_x(segment.start)..=time_to_x(segment.end), strip.y_range());
+            painter.rect_filled(segment_rect, 2.0, egui::Color32::from_rgba_unmultiplied(70, 130, 180, 160));
+
+            for (is_end, time) in [(false, segment.start), (true, segment.end)] {
+                let x = time_to_x(time);
+                let selected = state.selected_handle == Some((i, is_end));
+                let handle_rect = egui::Rect::from_center_size(egui::pos2(x, strip.center().y), egui::vec2(HANDLE_WIDTH, STRIP_HEIGHT));
+                painter.rect_filled(handle_rect, 2.0, if selected { egui::Color32::YELLOW } else { egui::Color32::WHITE });
+
+                if let Some(texture) = state.thumbnails.get(&thumbnail_key(time)) {
+                    let size = texture.size_vec2();
+                    let thumb_x = if is_end { x - size.x } else { x };
+                    let thumb_rect = egui::Rect::from_min_size(egui::pos2(thumb_x, rect.min.y), size);
+                    painter.image(texture.id(), thumb_rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
+                }
+            }
+        }
+
+        // Segment list with exact times
+        let mut to_remove = None;
+        for (i, segment) in segments.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("#{}", i + 1));
+                let mut start = segment.start;
+                let mut end = segment.end;
+                ui.label(if is_chinese { "开始:" } else { "Start:" });
+                if ui.add(time_field(&mut start, duration)).changed() {
+                    segment.start = start.min(segment.end - MIN_SEGMENT_SECONDS).max(0.0);
+                    changed = true;
+                }
+                ui.label(if is_chinese { "结束:" } else { "End:" });
+                if ui.add(time_field(&mut end, duration)).changed() {
+                    segment.end = end.max(segment.start + MIN_SEGMENT_SECONDS).min(duration);
+                    changed = true;
+                }
+                if ui.small_button("🗑").clicked() {
+                    to_remove = Some(i);
+                }
+            });
+        }
+        if let Some(index) = to_remove {
+            segments.remove(index);
+            state.selected_handle = None;
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Move a handle while keeping it inside its segment and clear of neighbouring segments
+    fn move_handle(segments: &mut [CutSegment], (index, is_end): (usize, bool), time: f64, duration: f64) -> bool {
+        let previous_end = if index > 0 { segments.get(index - 1).map(|s| s.end).unwrap_or(0.0) } else { 0.0 };
+        let next_start = segments.get(index + 1).map(|s| s.start).unwrap_or(duration);
+        let Some(segment) = segments.get_mut(index) else {
+            return false;
+        };
+
+        let old = segment.clone();
+        if is_end {
+            segment.end = time.clamp(segment.start + MIN_SEGMENT_SECONDS, next_start.max(segment.start + MIN_SEGMENT_SECONDS));
+        } else {
+            segment.start = time.clamp(previous_end.min(segment.end - MIN_SEGMENT_SECONDS), segment.end - MIN_SEGMENT_SECONDS);
+        }
+        *segment != old
+    }
+}
@@ -0,0 +1,253 @@
+use serde::{Serialize, Deserialize};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+
+/// The container duration is considered wrong when stream analysis differs by more than
+/// this many seconds and this fraction of the container value
+const DURATION_TOLERANCE_SECS: f64 = 2.0;
+const DURATION_TOLERANCE_RATIO: f64 = 0.02;
+
+/// One program of an MPEG transport stream (a TV channel in a capture)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramInfo {
+    pub program_id: u32,
+    pub program_num: u32,
+    pub service_name: Option<String>,
+    /// Global stream indices belonging to this program
+    pub stream_indices: Vec<u32>,
+}
+
+impl ProgramInfo {
+    pub fn label(&self) -> String {
+        match &self.service_name {
+            Some(name) => format!("#{} {} ({} streams)", self.program_num, name, self.stream_indices.len()),
+            None => format!("#{} ({} streams)", self.program_num, self.stream_indices.len()),
+        }
+    }
+}
+
+/// MPEG-TS / MPEG-PS inputs (TV captures, Blu-ray M2TS, DVD VOB) need timestamp fix-ups
+pub fn is_program_stream(path: &str, format_name: &str) -> bool {
+    is_program_stream_path(path)
+        || format_name.split(',').any(|name| matches!(name.trim(), "mpegts" | "mpegps" | "vob"))
+}
+
+pub fn is_program_stream_path(path: &str) -> bool {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(extension.as_str(), "ts" | "m2ts" | "mts" | "vob")
+}
+
+/// Input options placed before `-i`: regenerate missing PTS
+pub fn input_args() -> Vec<String> {
+    vec!["-fflags".to_string(), "+genpts".to_string()]
+}
+
+/// Output options: map the chosen program explicitly and shift timestamps to start at zero,
+/// which avoids negative PTS warnings from the huge start offsets of broadcast captures
+pub fn output_args(program_id: Option<u32>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(program_id) = program_id {
+        args.push("-map".to_string());
+        args.push(format!("0:p:{}", program_id));
+    }
+    args.push("-avoid_negative_ts".to_string());
+    args.push("make_zero".to_string());
+    args
+}
+
+/// Parse the `programs` array of `ffprobe -show_programs -of json`
+pub fn parse_programs(json: &serde_json::Value) -> Vec<ProgramInfo> {
+    let programs = match json["programs"].as_array() {
+        Some(programs) => programs,
+        None => return Vec::new(),
+    };
+    programs.iter()
+        .filter_map(|program| {
+            let program_id = program["program_id"].as_u64()? as u32;
+            let stream_indices: Vec<u32> = program["streams"].as_array()
+                .map(|streams| streams.iter().filter_map(|s| s["index"].as_u64().map(|i| i as u32)).collect())
+                .unwrap_or_default();
+            Some(ProgramInfo {
+                program_id,
+                program_num: program["program_num"].as_u64().unwrap_or(program_id as u64) as u32,
+                service_name: program["tags"]["service_name"].as_str()
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty()),
+                stream_indices,
+            })
+        })
+        // Programs without streams (e.g. empty PMT entries) cannot be selected
+        .filter(|program| !program.stream_indices.is_empty())
+        .collect()
+}
+
+/// Duration from the packets of the first video (or audio) stream. Summing packet durations
+/// survives timestamp resets between VOB cells and PCR discontinuities in captures.
+pub fn analyze_stream_duration(path: &str) -> Option<f64> {
+    let ffmpeg = get_bundled_ffmpeg().ok()?;
+    for stream in ["v:0", "a:0"] {
+        let output = ffmpeg.run_ffprobe(&[
+            "-v", "error",
+            "-select_streams", stream,
+            "-show_entries", "packet=duration_time",
+            "-of", "csv=p=0",
+            path,
+        ]).ok()?;
+        let total = parse_packet_durations(&String::from_utf8_lossy(&output.stdout));
+        if total > 0.0 {
+            return Some(total);
+        }
+    }
+    None
+}
+
+/// Sum the `duration_time` column of ffprobe packet csv output
+pub fn parse_packet_durations(csv: &str) -> f64 {
+    csv.lines()
+        .filter_map(|line| line.trim().trim_end_matches(',').parse::<f64>().ok())
+        .sum()
+}
+
+/// Prefer the analyzed duration when it disagrees significantly with the container metadata
+pub fn reconcile_duration(container: f64, analyzed: Option<f64>) -> f64 {
+    match analyzed {
+        Some(analyzed) if analyzed > 0.0 => {
+            let difference = (analyzed - container).abs();
+            if container <= 0.0 || (difference > DURATION_TOLERANCE_SECS && difference > container * DURATION_TOLERANCE_RATIO) {
+                log_info!("Container duration {:.2}s disagrees with stream analysis {:.2}s, using the latter", container, analyzed);
+                analyzed
+            } else {
+                container
+            }
+        }
+        _ => container,
+    }
+}
+
+/// Whether timestamp fix-ups apply to `input`, by extension or by the probed container format
+pub fn needs_fixups(input: &str) -> bool {
+    is_program_stream_path(input)
+        || crate::probe_cache::probe_cache().get_file_info(input)
+            .map(|info| is_program_stream(input, &info.format_name))
+            .unwrap_or(false)
+}
+
+/// Duration to use for progress and trims of a TS/PS input; None for other containers
+pub fn corrected_duration(input: &str) -> Option<f64> {
+    if !needs_fixups(input) {
+        return None;
+    }
+    crate::probe_cache::probe_cache().get_file_info(input).ok()
+        .map(|info| info.duration)
+        .filter(|duration| *duration > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::VideoSettings;
+    use crate::comprehensive_command_builder::ComprehensiveCommandBuilder;
+
+    #[test]
+    fn program_streams_by_extension_or_format() {
+        for path in ["capture.ts", "BD/STREAM/00001.M2TS", "clip.mts", "VIDEO_TS/VTS_01_1.VOB"] {
+            assert!(is_program_stream_path(path), "{path}");
+            assert!(is_program_stream(path, "mov,mp4,m4a,3gp,3g2,mj2"), "{path}");
+        }
+        for path in ["movie.mp4", "movie.mkv", "ts", "movie.ts.mp4"] {
+            assert!(!is_program_stream_path(path), "{path}");
+        }
+        // Renamed or extensionless captures are recognised by the probed format
+        assert!(is_program_stream("recording", "mpegts"));
+        assert!(is_program_stream("dvd.bin", "mpeg, vob"));
+        assert!(is_program_stream("disc.mpg", "mpegps"));
+        assert!(!is_program_stream("movie.mp4", "mov,mp4,m4a,3gp,3g2,mj2"));
+    }
+
+    #[test]
+    fn fixup_arguments() {
+        assert_eq!(input_args(), ["-fflags", "+genpts"]);
+        assert_eq!(output_args(None), ["-avoid_negative_ts", "make_zero"]);
+        assert_eq!(output_args(Some(260)), ["-map", "0:p:260", "-avoid_negative_ts", "make_zero"]);
+    }
+
+    #[test]
+    fn conversion_command_of_a_ts_input() {
+        let settings = VideoSettings { input_program: Some(2), ..Default::default() };
+        let args = ComprehensiveCommandBuilder::build_video_conversion_command("capture.ts", "out.mp4", &settings, None).unwrap();
+        let position = |wanted: &[&str]| args.windows(wanted.len()).position(|window| window == wanted);
+
+        let genpts = position(&["-fflags", "+genpts"]).expect("genpts");
+        let input = position(&["-i", "capture.ts"]).expect("input");
+        let map = position(&["-map", "0:p:2"]).expect("program map");
+        let make_zero = position(&["-avoid_negative_ts", "make_zero"]).expect("make_zero");
+        assert!(genpts < input && input < map && map < make_zero);
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+    }
+
+    #[test]
+    fn conversion_command_of_an_ordinary_input() {
+        let dir = crate::test_support::temp_dir("ts");
+        let input = dir.join("movie.mp4");
+        std::fs::write(&input, b"not really a movie").unwrap();
+        let input = input.display().to_string();
+
+        let settings = VideoSettings { input_program: Some(2), ..Default::default() };
+        let args = ComprehensiveCommandBuilder::build_video_conversion_command(&input, "out.mp4", &settings, None).unwrap();
+        for flag in ["+genpts", "0:p:2", "make_zero"] {
+            assert!(!args.iter().any(|arg| arg == flag), "{flag} in {args:?}");
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn programs_from_ffprobe_json() {
+        let json = serde_json::json!({
+            "programs": [
+                {
+                    "program_id": 1, "program_num": 101,
+                    "tags": { "service_name": " News 24 " },
+                    "streams": [{ "index": 0 }, { "index": 1 }],
+                },
+                // No program_num: falls back to the id; blank names are dropped
+                { "program_id": 2, "tags": { "service_name": "  " }, "streams": [{ "index": 2 }] },
+                // Empty PMT entry
+                { "program_id": 3, "program_num": 103, "streams": [] },
+                // Malformed entry
+                { "program_num": 104, "streams": [{ "index": 3 }] },
+            ]
+        });
+        let programs = parse_programs(&json);
+        assert_eq!(programs, vec![
+            ProgramInfo { program_id: 1, program_num: 101, service_name: Some("News 24".to_string()), stream_indices: vec![0, 1] },
+            ProgramInfo { program_id: 2, program_num: 2, service_name: None, stream_indices: vec![2] },
+        ]);
+        assert_eq!(programs[0].label(), "#101 News 24 (2 streams)");
+        assert_eq!(programs[1].label(), "#2 (1 streams)");
+
+        assert!(parse_programs(&serde_json::json!({ "streams": [] })).is_empty());
+    }
+
+    #[test]
+    fn packet_durations_are_summed() {
+        assert!((parse_packet_durations("0.040000\n0.040000,\n\nN/A\n0.020000\n") - 0.1).abs() < 1e-9);
+        assert_eq!(parse_packet_durations(""), 0.0);
+    }
+
+    #[test]
+    fn analyzed_duration_wins_only_on_a_real_disagreement() {
+        assert_eq!(reconcile_duration(100.0, None), 100.0);
+        assert_eq!(reconcile_duration(100.0, Some(0.0)), 100.0);
+        // Within 2 s or within 2 %
+        assert_eq!(reconcile_duration(100.0, Some(101.5)), 100.0);
+        assert_eq!(reconcile_duration(1000.0, Some(1015.0)), 1000.0);
+        // Beyond both
+        assert_eq!(reconcile_duration(100.0, Some(103.0)), 103.0);
+        assert_eq!(reconcile_duration(5400.0, Some(2700.0)), 2700.0);
+        // Unknown container duration
+        assert_eq!(reconcile_duration(0.0, Some(12.5)), 12.5);
+    }
+}
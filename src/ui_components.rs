@@ -3,6 +3,10 @@ use crate::app_state::*;
 use crate::language::*;
 use crate::codec_manager::*;
 use crate::preset_manager::*;
+use crate::help_overlay::{self, PANEL_SETTINGS};
+use crate::parameter_limits;
+use crate::frame_rate::{self, FrameRate};
+use crate::task_executor::TaskAction;
 
 pub struct OperationSelector;
 
@@ -24,6 +28,8 @@ impl OperationSelector {
                         OperationType::VideoCrop,
                         OperationType::VideoRotate,
                         OperationType::VideoFilter,
+                        OperationType::CutAndJoin,
+                        OperationType::FrameRateConvert,
                     ]),
                     (translations.audio_processing(), vec![
                         OperationType::AudioConvert,
@@ -32,6 +38,7 @@ impl OperationSelector {
                         OperationType::AudioVolume,
                         OperationType::AudioTrim,
                         OperationType::AudioMerge,
+                        OperationType::AudioNormalize,
                     ]),
                     (translations.video_audio_operations(), vec![
                         OperationType::VideoAudioMerge,
@@ -208,6 +215,27 @@ impl FileSelector {
                 }
             });
             
+            // Remote inputs are downloaded into the download cache when the task runs
+            ui.horizontal(|ui| {
+                let id = egui::Id::new(("file_selector_url", label));
+                let mut url = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or_default();
+                ui.add(egui::TextEdit::singleline(&mut url)
+                    .hint_text(translations.download_cache_url_hint())
+                    .desired_width(ui.available_width() - 90.0));
+                let add = egui::Button::new(translations.download_cache_add_url());
+                if ui.add_enabled(crate::download_cache::is_remote(&url), add).clicked() {
+                    let url = std::mem::take(&mut url).trim().to_string();
+                    if !allow_multiple {
+                        files.clear();
+                    }
+                    if !files.contains(&url) {
+                        files.push(url);
+                    }
+                    on_files_changed();
+                }
+                ui.data_mut(|data| data.insert_temp(id, url));
+            });
+            
             // Show status
             if files.is_empty() {
                 ui.colored_label(egui::Color32::from_rgb(255, 150, 100), 
@@ -442,7 +470,7 @@ impl OutputSelector {
         match operation {
             OperationType::AudioConvert | OperationType::AudioCompress | 
             OperationType::AudioResample | OperationType::AudioVolume | 
-            OperationType::AudioTrim | OperationType::AudioMerge |
+            OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize |
             OperationType::ExtractAudio => {
                 if let Some(audio_settings) = audio_settings {
                     Self::get_audio_extension_and_filter(&audio_settings.codec, translations)
@@ -453,7 +481,7 @@ impl OutputSelector {
             
             OperationType::VideoConvert | OperationType::VideoCompress | 
             OperationType::VideoResize | OperationType::VideoCrop | 
-            OperationType::VideoRotate | OperationType::VideoFilter |
+            OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
             OperationType::ExtractVideo => {
                 if let Some(video_settings) = video_settings {
                     Self::get_video_extension_and_filter(&video_settings.codec, translations)
@@ -525,12 +553,12 @@ impl OutputSelector {
         match operation {
             OperationType::VideoConvert | OperationType::VideoCompress | 
             OperationType::VideoResize | OperationType::VideoCrop | 
-            OperationType::VideoRotate | OperationType::VideoFilter |
+            OperationType::VideoRotate | OperationType::VideoFilter | OperationType::CutAndJoin | OperationType::FrameRateConvert |
             OperationType::VideoAudioMerge => "mp4",
             
             OperationType::AudioConvert | OperationType::AudioCompress | 
             OperationType::AudioResample | OperationType::AudioVolume | 
-            OperationType::AudioTrim | OperationType::AudioMerge |
+            OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize |
             OperationType::ExtractAudio => "mp3",
             
             OperationType::ExtractVideo => "mp4",
@@ -546,11 +574,14 @@ impl SettingsPanel {
     pub fn show_video_settings(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations, cached_hw_encoders: &[String]) {
         ui.group(|ui| {
             ui.vertical(|ui| {
-                ui.heading(format!("🎬 {}", translations.video_settings()));
+                ui.horizontal(|ui| {
+                    ui.heading(format!("🎬 {}", translations.video_settings()));
+                    help_overlay::toggle_button(ui, PANEL_SETTINGS, translations);
+                });
                 
                 // Video encoder selection removed - now handled by comprehensive UI in operation settings
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.preset());
                     egui::ComboBox::from_id_salt("video_preset")
                         .selected_text(&settings.preset)
@@ -566,9 +597,10 @@ impl SettingsPanel {
                             ui.selectable_value(&mut settings.preset, "slower".to_string(), translations.preset_slower());
                             ui.selectable_value(&mut settings.preset, "veryslow".to_string(), translations.preset_veryslow());
                         });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.preset", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.profile());
                     egui::ComboBox::from_id_salt("video_profile")
                         .selected_text(&settings.profile)
@@ -581,9 +613,10 @@ impl SettingsPanel {
                             ui.selectable_value(&mut settings.profile, "high422".to_string(), "High 4:2:2");
                             ui.selectable_value(&mut settings.profile, "high444".to_string(), "High 4:4:4");
                         });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.profile", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.tune());
                     egui::ComboBox::from_id_salt("video_tune")
                         .selected_text(&settings.tune)
@@ -598,36 +631,75 @@ impl SettingsPanel {
                             ui.selectable_value(&mut settings.tune, "fastdecode".to_string(), translations.tune_fastdecode());
                             ui.selectable_value(&mut settings.tune, "zerolatency".to_string(), translations.tune_zerolatency());
                         });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.tune", translations);
 
-                ui.horizontal(|ui| {
+                let issues = parameter_limits::validate_video(settings);
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.quality());
-                    ui.add(egui::Slider::new(&mut settings.quality, 0..=51).text(""));
+                    let response = ui.add(egui::Slider::new(&mut settings.quality, 0..=51).text(""));
+                    if let Some(issue) = parameter_limits::issue_for(&issues, parameter_limits::Field::Quality) {
+                        if parameter_limits::show_field_issue(ui, &response, issue, translations) {
+                            parameter_limits::apply_video_clamp(issue, settings);
+                        }
+                    }
                     ui.label(if translations.language == Language::Chinese {
                         "(0=无损, 23=默认, 51=最低质量)"
                     } else {
                         "(0=lossless, 23=default, 51=lowest quality)"
                     });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.quality", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.bitrate());
-                    ui.text_edit_singleline(&mut settings.bitrate);
+                    let response = crate::locale_format::bitrate_edit(ui, "video_bitrate", &mut settings.bitrate, translations);
+                    if let Some(issue) = parameter_limits::issue_for(&issues, parameter_limits::Field::VideoBitrate) {
+                        if parameter_limits::show_field_issue(ui, &response, issue, translations) {
+                            parameter_limits::apply_video_clamp(issue, settings);
+                        }
+                    }
                     ui.label(if translations.language == Language::Chinese { "(如: 2M, 1000k, 或 auto)" } else { "(e.g.: 2M, 1000k, or auto)" });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.bitrate", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.framerate());
-                    ui.text_edit_singleline(&mut settings.fps);
-                    ui.label(if translations.language == Language::Chinese { "(如: 30, 60, 或 auto)" } else { "(e.g.: 30, 60, or auto)" });
-                });
+                    let selected = if frame_rate::is_same_as_source(&settings.fps) {
+                        translations.same_as_source_fps().to_string()
+                    } else {
+                        FrameRate::parse(&settings.fps).map(frame_rate::describe).unwrap_or_else(|| settings.fps.clone())
+                    };
+                    egui::ComboBox::from_id_salt("video_fps_preset")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut settings.fps, frame_rate::SAME_AS_SOURCE.to_string(), translations.same_as_source_fps());
+                            for rate in frame_rate::COMMON_RATES {
+                                ui.selectable_value(&mut settings.fps, rate.ffmpeg_arg(), frame_rate::describe(rate));
+                            }
+                        });
+                    let response = ui.add(egui::TextEdit::singleline(&mut settings.fps).desired_width(90.0));
+                    if let Some(issue) = parameter_limits::issue_for(&issues, parameter_limits::Field::Framerate) {
+                        if parameter_limits::show_field_issue(ui, &response, issue, translations) {
+                            parameter_limits::apply_video_clamp(issue, settings);
+                        }
+                    }
+                    ui.label(if translations.language == Language::Chinese { "(如: 25, 24000/1001, 或 auto = 与源相同)" } else { "(e.g.: 25, 24000/1001, or auto = same as source)" });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.fps", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.resolution());
-                    ui.add(egui::DragValue::new(&mut settings.resolution.0).prefix(translations.width()));
-                    ui.add(egui::DragValue::new(&mut settings.resolution.1).prefix(translations.height()));
+                    let response = ui.add(egui::DragValue::new(&mut settings.resolution.0).prefix(translations.width()))
+                        | ui.add(egui::DragValue::new(&mut settings.resolution.1).prefix(translations.height()));
+                    if let Some(issue) = parameter_limits::issue_for(&issues, parameter_limits::Field::Resolution) {
+                        if parameter_limits::show_field_issue(ui, &response, issue, translations) {
+                            parameter_limits::apply_video_clamp(issue, settings);
+                        }
+                    }
                     ui.label(translations.keep_original_hint());
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "video.resolution", translations);
 
                 ui.separator();
                 let smart_title = if translations.language == Language::Chinese {
@@ -635,7 +707,7 @@ impl SettingsPanel {
                 } else {
                     "🎯 Smart Encoder Recommendation"
                 };
-                ui.collapsing(smart_title, |ui| {
+                let smart_encoder = ui.collapsing(smart_title, |ui| {
                     ui.horizontal(|ui| {
                         let quality_label = if translations.language == Language::Chinese {
                             "质量预设:"
@@ -709,29 +781,71 @@ impl SettingsPanel {
                             });
                     }
                 });
+                help_overlay::callout(ui, &smart_encoder.header_response, PANEL_SETTINGS, "video.smart_encoder", translations);
+
+                let container_options = ui.collapsing(translations.container_options(), |ui| {
+                    Self::show_container_options(ui, settings, translations);
+                });
+                help_overlay::callout(ui, &container_options.header_response, PANEL_SETTINGS, "video.container_options", translations);
 
                 ui.separator();
                 ui.label(translations.custom_parameters());
-                ui.text_edit_multiline(&mut settings.custom_args);
+                let custom_args = ui.text_edit_multiline(&mut settings.custom_args);
+                help_overlay::callout(ui, &custom_args, PANEL_SETTINGS, "video.custom_args", translations);
                 ui.label(translations.advanced_users_hint());
             });
         });
     }
 
+    /// Publishing profile toggles of the selected container, seeded from the registry defaults
+    fn show_container_options(ui: &mut egui::Ui, settings: &mut VideoSettings, translations: &Translations) {
+        let format = settings.container_format.to_lowercase();
+        let options = crate::comprehensive_codec_registry::ComprehensiveCodecRegistry::get_container_options(&format);
+        if options.is_empty() {
+            ui.label(translations.no_container_options());
+            return;
+        }
+
+        for option in options {
+            let (label, description) = translations.container_option_text(option.key)
+                .unwrap_or((option.display_name, option.description));
+            let mut enabled = settings.container_options.get(option.key).copied().unwrap_or(option.default_enabled);
+            if ui.checkbox(&mut enabled, label).on_hover_text(description).changed() {
+                if enabled == option.default_enabled {
+                    settings.container_options.remove(option.key);
+                } else {
+                    settings.container_options.insert(option.key.to_string(), enabled);
+                }
+            }
+        }
+
+        let preview = crate::comprehensive_codec_registry::ComprehensiveCodecRegistry::build_muxer_args(
+            &format,
+            &settings.container_options,
+            &[],
+        );
+        if !preview.is_empty() {
+            ui.label(egui::RichText::new(preview.join(" ")).monospace().weak());
+        }
+    }
+
     pub fn show_audio_settings(ui: &mut egui::Ui, settings: &mut AudioSettings, translations: &Translations) {
         ui.group(|ui| {
             ui.vertical(|ui| {
-                ui.heading(format!("🎵 {}", translations.audio_settings()));
+                ui.horizontal(|ui| {
+                    ui.heading(format!("🎵 {}", translations.audio_settings()));
+                    help_overlay::toggle_button(ui, PANEL_SETTINGS, translations);
+                });
                 
                 // Audio format selection (for audio-only operations)
                 
                 let supports_bitrate = Self::codec_supports_bitrate(&settings.codec);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.bitrate());
                     ui.add_enabled_ui(supports_bitrate, |ui| {
                         egui::ComboBox::from_id_salt("audio_bitrate")
-                            .selected_text(&settings.bitrate)
+                            .selected_text(crate::locale_format::format_bitrate(&settings.bitrate, &translations.language))
                             .width(150.0)
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(&mut settings.bitrate, "auto".to_string(), "🎯 自动");
@@ -753,17 +867,8 @@ impl SettingsPanel {
                         
                         if supports_bitrate {
                             ui.add_space(10.0);
-                            let mut custom_bitrate = if settings.bitrate.ends_with("k") || settings.bitrate == "auto" {
-                                String::new()
-                            } else {
-                                settings.bitrate.clone()
-                            };
-                            
                             ui.label(translations.custom());
-                            ui.text_edit_singleline(&mut custom_bitrate);
-                            if !custom_bitrate.is_empty() && custom_bitrate != settings.bitrate {
-                                settings.bitrate = custom_bitrate;
-                            }
+                            crate::locale_format::bitrate_edit(ui, "audio_bitrate_custom", &mut settings.bitrate, translations);
                         }
                     });
                     
@@ -771,9 +876,10 @@ impl SettingsPanel {
                         ui.label(translations.lossless_encoding());
                         settings.bitrate = "auto".to_string();
                     }
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "audio.bitrate", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.sample_rate());
                     egui::ComboBox::from_id_salt("audio_sample_rate")
                         .selected_text(&settings.sample_rate)
@@ -793,9 +899,10 @@ impl SettingsPanel {
                             ui.selectable_value(&mut settings.sample_rate, "176400".to_string(), "176.4 kHz");
                             ui.selectable_value(&mut settings.sample_rate, "192000".to_string(), if translations.language == Language::Chinese { "🏆 192 kHz (最高质量)" } else { "🏆 192 kHz (Highest Quality)" });
                         });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "audio.sample_rate", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.channels());
                     egui::ComboBox::from_id_salt("audio_channels")
                         .selected_text(&settings.channels)
@@ -812,11 +919,12 @@ impl SettingsPanel {
                             ui.selectable_value(&mut settings.channels, "7".to_string(), if translations.language == Language::Chinese { "🔊 6.1声道" } else { "🔊 6.1 Channel" });
                             ui.selectable_value(&mut settings.channels, "8".to_string(), if translations.language == Language::Chinese { "🎬 7.1声道" } else { "🎬 7.1 Channel" });
                         });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "audio.channels", translations);
 
                 // VBR quality settings (only show for supported codecs)
                 let supports_vbr = Self::codec_supports_vbr_quality(&settings.codec);
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(translations.vbr_quality());
                     ui.add_enabled_ui(supports_vbr, |ui| {
                         ui.text_edit_singleline(&mut settings.quality);
@@ -833,9 +941,10 @@ impl SettingsPanel {
                             settings.quality = "auto".to_string();
                         }
                     });
-                });
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "audio.vbr_quality", translations);
 
-                ui.horizontal(|ui| {
+                let row = ui.horizontal(|ui| {
                     ui.label(if translations.language == Language::Chinese {
                         "🔊 音量:"
                     } else {
@@ -848,11 +957,18 @@ impl SettingsPanel {
                     if volume_value != 1.0 {
                         ui.label(format!("({:+.1} dB)", 20.0 * volume_value.log10()));
                     }
+                }).response;
+                help_overlay::callout(ui, &row, PANEL_SETTINGS, "audio.volume", translations);
+
+                let denoise = ui.collapsing(translations.audio_denoise(), |ui| {
+                    crate::audio_denoise::show_editor(ui, &mut settings.denoise, translations);
                 });
+                help_overlay::callout(ui, &denoise.header_response, PANEL_SETTINGS, "audio.denoise", translations);
 
                 ui.separator();
                 ui.label(translations.custom_parameters());
-                ui.text_edit_multiline(&mut settings.custom_args);
+                let custom_args = ui.text_edit_multiline(&mut settings.custom_args);
+                help_overlay::callout(ui, &custom_args, PANEL_SETTINGS, "audio.custom_args", translations);
                 ui.label(translations.advanced_users_hint());
             });
         });
@@ -862,17 +978,28 @@ impl SettingsPanel {
         ui: &mut egui::Ui, 
         video_settings: &mut VideoSettings, 
         audio_settings: &mut AudioSettings,
+        source: Option<&crate::ffmpeg_worker_simple::MediaInfo>,
         translations: &Translations
     ) -> bool {
         let mut preset_applied = false;
         
         ui.group(|ui| {
             ui.vertical(|ui| {
-                ui.heading(translations.encoding_presets());
+                ui.horizontal(|ui| {
+                    ui.heading(translations.encoding_presets());
+                    ui.menu_button(translations.preset_comparison_button(), |ui| {
+                        let presets = PresetManager::get_builtin_presets();
+                        let facts = source.and_then(crate::preset_comparison::SourceFacts::from_media_info);
+                        let table = crate::preset_comparison::build_table(&presets, facts.as_ref(), video_settings);
+                        if let Some(index) = crate::preset_comparison::show_table(ui, &table, translations) {
+                            PresetManager::apply_preset_to_settings(&presets[index], video_settings, audio_settings);
+                            preset_applied = true;
+                        }
+                    }).response.on_hover_text(translations.preset_comparison_tooltip());
+                });
                 ui.label(translations.select_preset_hint());
                 ui.separator();
                 
-                let _presets = PresetManager::get_builtin_presets();
                 let categories = PresetManager::get_all_categories();
                 
                 for category in categories {
@@ -1029,131 +1156,788 @@ impl SettingsPanel {
     }
 }
 
+/// Status filter tabs of the task panel
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TaskFilter {
+    #[default]
+    All,
+    Running,
+    Pending,
+    Failed,
+    Completed,
+}
+
+impl TaskFilter {
+    pub fn all() -> [TaskFilter; 5] {
+        [TaskFilter::All, TaskFilter::Running, TaskFilter::Pending, TaskFilter::Failed, TaskFilter::Completed]
+    }
+
+    fn matches(&self, status: &TaskStatus) -> bool {
+        match self {
+            TaskFilter::All => true,
+            TaskFilter::Running => matches!(status, TaskStatus::Running | TaskStatus::Paused),
+            TaskFilter::Pending => *status == TaskStatus::Pending,
+            TaskFilter::Failed => matches!(status, TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::NeedsOutputRecovery),
+            TaskFilter::Completed => *status == TaskStatus::Completed,
+        }
+    }
+
+    fn count(&self, counts: &TaskCounts) -> usize {
+        match self {
+            TaskFilter::All => counts.total,
+            TaskFilter::Running => counts.running,
+            TaskFilter::Pending => counts.pending,
+            TaskFilter::Failed => counts.failed,
+            TaskFilter::Completed => counts.completed,
+        }
+    }
+}
+
+/// Aggregate numbers over the whole queue
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TaskCounts {
+    pub total: usize,
+    pub running: usize,
+    pub pending: usize,
+    pub failed: usize,
+    pub completed: usize,
+    progress_sum: f32,
+}
+
+impl TaskCounts {
+    /// Fraction of the queue that is done; finished tasks count fully, running ones by their progress
+    pub fn overall_progress(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.progress_sum / self.total as f32
+        }
+    }
+}
+
+/// Confirmation asked before a running task is cancelled
+struct CancelPrompt {
+    task_id: usize,
+    /// Remove the partially written output once the task has stopped; off by default
+    delete_partial: bool,
+}
+
+/// Filtered index over the shared task list. Only positions are stored, so thousands of queued
+/// tasks are never cloned; the index is rebuilt when the task generation or the filter changes.
+#[derive(Default)]
+pub struct TaskListView {
+    pub filter: TaskFilter,
+    pub search: String,
+    pub counts: TaskCounts,
+    selected_task: Option<usize>,
+    /// Task whose label is being edited, with the text typed so far
+    editing_label: Option<(usize, String)>,
+    /// Running task the user clicked cancel on, waiting for confirmation
+    cancel_prompt: Option<CancelPrompt>,
+    generation: u64,
+    indexed_filter: TaskFilter,
+    indexed_search: String,
+    positions: Vec<usize>,
+}
+
+impl TaskListView {
+    /// Rebuild counts and the filtered index if anything they depend on changed
+    pub fn refresh(&mut self, tasks: &[ProcessingTask], generation: u64) {
+        if generation == self.generation && self.filter == self.indexed_filter && self.search == self.indexed_search {
+            return;
+        }
+
+        let search = self.search.trim().to_lowercase();
+        let mut counts = TaskCounts::default();
+        self.positions.clear();
+        for (position, task) in tasks.iter().enumerate() {
+            counts.total += 1;
+            match task.status {
+                TaskStatus::Running | TaskStatus::Paused => {
+                    counts.running += 1;
+                    counts.progress_sum += task.progress;
+                }
+                TaskStatus::Pending => counts.pending += 1,
+                TaskStatus::NeedsOutputRecovery => counts.failed += 1,
+                TaskStatus::Failed | TaskStatus::Cancelled => {
+                    counts.failed += 1;
+                    counts.progress_sum += 1.0;
+                }
+                TaskStatus::Completed => {
+                    counts.completed += 1;
+                    counts.progress_sum += 1.0;
+                }
+            }
+
+            if self.filter.matches(&task.status) && (search.is_empty() || Self::task_matches(task, &search)) {
+                self.positions.push(position);
+            }
+        }
+
+        self.counts = counts;
+        self.generation = generation;
+        self.indexed_filter = self.filter;
+        self.indexed_search = self.search.clone();
+    }
+
+    fn task_matches(task: &ProcessingTask, search: &str) -> bool {
+        task.output_file.to_lowercase().contains(search)
+            || task.label.as_ref().is_some_and(|label| label.to_lowercase().contains(search))
+            || task.input_files.iter().any(|input| input.to_lowercase().contains(search))
+    }
+}
+
 pub struct TaskPanel;
 
 impl TaskPanel {
-    pub fn show(ui: &mut egui::Ui, tasks: &mut Vec<ProcessingTask>, translations: &Translations) {
-        ui.vertical(|ui| {
-            if tasks.is_empty() {
-                ui.label(translations.no_tasks());
-                return;
+    const ROW_HEIGHT: f32 = 46.0;
+
+    /// Draw the queue; edits are returned as actions for the caller to apply by task id
+    pub fn show(
+        ui: &mut egui::Ui,
+        tasks: &std::sync::Arc<std::sync::Mutex<Vec<ProcessingTask>>>,
+        view: &mut TaskListView,
+        generation: u64,
+        translations: &Translations,
+    ) -> Vec<TaskAction> {
+        let mut actions = Vec::new();
+        let tasks_guard = match tasks.lock() {
+            Ok(guard) => guard,
+            Err(_) => return actions,
+        };
+
+        // Filter tabs and filename search
+        ui.horizontal_wrapped(|ui| {
+            for filter in TaskFilter::all() {
+                let label = format!("{} ({})", translations.task_filter_name(filter), filter.count(&view.counts));
+                ui.selectable_value(&mut view.filter, filter, label);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut view.search)
+                .hint_text(translations.filter_by_filename())
+                .desired_width(f32::INFINITY));
+        });
+        view.refresh(&tasks_guard, generation);
+        ui.separator();
+
+        if tasks_guard.is_empty() {
+            ui.label(translations.no_tasks());
+            return actions;
+        }
+
+        let details_height = if view.selected_task.is_some() { 180.0 } else { 0.0 };
+        let list_height = (ui.available_height() - details_height).max(Self::ROW_HEIGHT * 2.0);
+
+        // Only the rows inside the scroll viewport are laid out
+        egui::ScrollArea::vertical()
+            .id_salt("task_scroll")
+            .auto_shrink([false, false])
+            .max_height(list_height)
+            .show_rows(ui, Self::ROW_HEIGHT, view.positions.len(), |ui, row_range| {
+                for row in row_range {
+                    let Some(task) = view.positions.get(row).and_then(|&position| tasks_guard.get(position)) else {
+                        continue;
+                    };
+                    let selected = view.selected_task == Some(task.id);
+                    let row_size = egui::vec2(ui.available_width(), Self::ROW_HEIGHT - ui.spacing().item_spacing.y);
+                    let row_rect = egui::Rect::from_min_size(ui.cursor().min, row_size);
+                    // Registered before the row contents so the buttons stay clickable on top of it
+                    let row_response = ui.interact(row_rect, ui.id().with(("task_row", task.id)), egui::Sense::click());
+                    if selected {
+                        ui.painter().rect_filled(row_rect, 3.0, ui.visuals().selection.bg_fill.gamma_multiply(0.3));
+                    }
+                    ui.allocate_ui_with_layout(row_size, egui::Layout::top_down(egui::Align::Min), |ui| {
+                        ui.set_min_size(row_size);
+                        let editing = view.editing_label.as_mut().filter(|(id, _)| *id == task.id).map(|(_, text)| text);
+                        Self::show_row(ui, task, selected, editing, &mut view.cancel_prompt, &mut actions, translations)
+                    });
+                    if row_response.clicked() {
+                        view.selected_task = if selected { None } else { Some(task.id) };
+                    }
+                    row_response.context_menu(|ui| {
+                        Self::show_queue_menu(ui, task, &mut view.editing_label, &mut actions, translations);
+                        ui.separator();
+                        Self::show_command_menu(ui, task, translations);
+                    });
+                }
+            });
+
+        if let Some(prompt) = view.cancel_prompt.as_mut() {
+            match tasks_guard.iter().find(|t| t.id == prompt.task_id && t.is_started() && !t.cancel_requested) {
+                Some(task) => {
+                    if Self::show_cancel_prompt(ui.ctx(), task, prompt, &mut actions, translations) {
+                        view.cancel_prompt = None;
+                    }
+                }
+                // The task ended meanwhile
+                None => view.cancel_prompt = None,
             }
+        }
 
-            {
-                        let mut to_remove = Vec::new();
-                        
-                        for (i, task) in tasks.iter_mut().enumerate() {
-                            ui.group(|ui| {
-                                // First row: Task title and cancel/delete button on the same line
-                                ui.horizontal(|ui| {
-                                    ui.label(format!("{} {}: {}", translations.task(), i + 1, task.operation.display_name(translations)));
-                                    
-                                    // Push button to the right
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        let button_clicked = if task.status == TaskStatus::Running {
-                                            ui.small_button(egui::RichText::new(translations.cancel()).color(egui::Color32::RED)).clicked()
-                                        } else {
-                                            ui.small_button(egui::RichText::new(translations.delete()).color(egui::Color32::RED)).clicked()
-                                        };
-                                        
-                                        if button_clicked {
-                                            if task.status == TaskStatus::Running {
-                                                task.status = TaskStatus::Cancelled;
-                                                log_info!("🛑 User clicked cancel, terminating FFmpeg processes...");
-                                                Self::terminate_ffmpeg_processes();
-                                            } else {
-                                                to_remove.push(i);
-                                            }
-                                        }
-                                    });
-                                });
-                                
-                                // Second row: Status
-                                ui.label(format!("{}: {:?}", translations.status(), task.status));
-                                
-                                // Progress bar and timing information
-                                if task.status == TaskStatus::Running || task.status == TaskStatus::Completed {
-                                    let progress_bar = egui::ProgressBar::new(task.progress)
-                                        .text(format!("{:.1}%", task.progress * 100.0));
-                                    ui.add_sized([ui.available_width(), 20.0], progress_bar);
-                                    
-                                    // Show time information for running tasks
-                                    if task.status == TaskStatus::Running {
-                                        if let Some(start_time) = task.start_time {
-                                            let elapsed = start_time.elapsed();
-                                            let elapsed_secs = elapsed.as_secs();
-                                            
-                                            ui.horizontal(|ui| {
-                                                ui.label(format!("⏱ {}: {}:{:02}", 
-                                                    if translations.language == crate::language::Language::Chinese { "已用时间" } else { "Elapsed" },
-                                                    elapsed_secs / 60, elapsed_secs % 60
-                                                ));
-                                                
-                                                if task.progress > 0.0 {
-                                                    // Simple time estimation based on progress
-                                                    let estimated_total_secs = (elapsed_secs as f32 / task.progress) as u64;
-                                                    let remaining_secs = estimated_total_secs.saturating_sub(elapsed_secs);
-                                                    
-                                                    ui.separator();
-                                                    ui.label(format!("⏳ {}: {}:{:02}", 
-                                                        if translations.language == crate::language::Language::Chinese { "剩余时间" } else { "Remaining" },
-                                                        remaining_secs / 60, remaining_secs % 60
-                                                    ));
-                                                } else {
-                                                    ui.separator();
-                                                    ui.label(if translations.language == crate::language::Language::Chinese { 
-                                                        "⏳ 计算中..." 
-                                                    } else { 
-                                                        "⏳ Calculating..." 
-                                                    });
-                                                }
-                                            });
-                                        }
-                                    } else if task.status == TaskStatus::Completed {
-                                        if let Some(completion_time) = task.completion_time {
-                                            let total_secs = completion_time.as_secs();
-                                            ui.label(format!("✅ {}: {}:{:02}", 
-                                                if translations.language == crate::language::Language::Chinese { "总用时" } else { "Total Time" },
-                                                total_secs / 60, total_secs % 60
-                                            ));
-                                        } else if let Some(start_time) = task.start_time {
-                                            // Fallback for tasks completed before this fix
-                                            let total_time = start_time.elapsed();
-                                            let total_secs = total_time.as_secs();
-                                            ui.label(format!("✅ {}: {}:{:02}", 
-                                                if translations.language == crate::language::Language::Chinese { "总用时" } else { "Total Time" },
-                                                total_secs / 60, total_secs % 60
-                                            ));
-                                        }
-                                    }
-                                }
-                                
-                                if let Some(error) = &task.error_message {
-                                    ui.colored_label(egui::Color32::RED, format!("{}: {}", translations.error(), error));
-                                }
-                            });
+        if let Some(task) = view.selected_task.and_then(|id| tasks_guard.iter().find(|t| t.id == id)) {
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .id_salt("task_details_scroll")
+                .max_height(details_height)
+                .show(ui, |ui| Self::show_details(ui, task, translations));
+        }
+
+        if view.selected_task.is_some_and(|id| actions.contains(&TaskAction::Remove(id))) {
+            view.selected_task = None;
+        }
+        if actions.iter().any(|action| matches!(action, TaskAction::Relabel { .. })) {
+            view.editing_label = None;
+        }
+        actions
+    }
+
+    /// Asks before a running task is cancelled, offering to delete its partial output; returns
+    /// true once answered
+    fn show_cancel_prompt(
+        ctx: &egui::Context,
+        task: &ProcessingTask,
+        prompt: &mut CancelPrompt,
+        actions: &mut Vec<TaskAction>,
+        translations: &Translations,
+    ) -> bool {
+        let mut answered = false;
+        egui::Window::new(translations.task_cancel_title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("#{} {}", task.id, task.operation.display_name(translations)));
+                ui.weak(&task.output_file);
+                ui.label(translations.task_cancel_others_continue());
+                ui.add_space(6.0);
+                ui.checkbox(&mut prompt.delete_partial, translations.task_cancel_delete_partial())
+                    .on_hover_text(translations.task_cancel_delete_partial_hint());
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    let confirm = egui::Button::new(egui::RichText::new(translations.task_cancel_confirm()).color(egui::Color32::RED));
+                    if ui.add(confirm).clicked() {
+                        actions.push(TaskAction::Cancel { id: task.id, delete_partial: prompt.delete_partial });
+                        answered = true;
+                    }
+                    if ui.button(translations.task_cancel_keep_running()).clicked() {
+                        answered = true;
+                    }
+                });
+            });
+        answered
+    }
+
+    /// One fixed-height row: title (or label editor) with cancel/delete button, then the progress bar
+    fn show_row(
+        ui: &mut egui::Ui,
+        task: &ProcessingTask,
+        selected: bool,
+        editing: Option<&mut String>,
+        cancel_prompt: &mut Option<CancelPrompt>,
+        actions: &mut Vec<TaskAction>,
+        translations: &Translations,
+    ) {
+        let name = task.label.clone().unwrap_or_else(|| {
+            std::path::Path::new(&task.output_file)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+        ui.horizontal(|ui| {
+            if let Some(text) = editing {
+                let response = ui.add(egui::TextEdit::singleline(text).desired_width(ui.available_width() - 70.0));
+                if !response.has_focus() && !response.lost_focus() {
+                    response.request_focus();
+                }
+                if response.lost_focus() && !ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    actions.push(TaskAction::Relabel { id: task.id, label: text.clone() });
+                } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    actions.push(TaskAction::Relabel { id: task.id, label: task.label.clone().unwrap_or_default() });
+                }
+            } else {
+                let title = format!("#{} {} · {}", task.id, task.operation.display_name(translations), name);
+                let title = if selected { egui::RichText::new(title).strong() } else { egui::RichText::new(title) };
+                ui.add(egui::Label::new(title).truncate());
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if task.is_started() {
+                    let button = egui::Button::new(egui::RichText::new(translations.cancel()).color(egui::Color32::RED)).small();
+                    if ui.add_enabled(!task.cancel_requested, button).clicked() {
+                        *cancel_prompt = Some(CancelPrompt { task_id: task.id, delete_partial: false });
+                    }
+                    if task.status == TaskStatus::Paused {
+                        if ui.small_button(translations.task_pause_resume()).clicked() {
+                            actions.push(TaskAction::Resume(task.id));
                         }
-                        
-                        for &i in to_remove.iter().rev() {
-                            tasks.remove(i);
+                    } else if ui.add_enabled(!task.cancel_requested, egui::Button::new(translations.task_pause_pause()).small())
+                        .on_hover_text(translations.task_pause_pause_hint())
+                        .clicked()
+                    {
+                        actions.push(TaskAction::Pause(task.id));
+                    }
+                } else {
+                    if ui.small_button(egui::RichText::new(translations.delete()).color(egui::Color32::RED)).clicked() {
+                        actions.push(TaskAction::Remove(task.id));
+                    }
+                    if task.status == TaskStatus::Failed
+                        && ui.small_button(translations.task_retry_retry())
+                            .on_hover_text(translations.task_retry_retry_hint())
+                            .clicked()
+                    {
+                        actions.push(TaskAction::Retry(task.id));
+                    }
+                }
+                let audio_output = matches!(task.operation,
+                    OperationType::AudioConvert | OperationType::AudioCompress | OperationType::AudioResample |
+                    OperationType::AudioVolume | OperationType::AudioTrim | OperationType::AudioMerge | OperationType::AudioNormalize | OperationType::ExtractAudio);
+                if task.status == TaskStatus::Completed && audio_output {
+                    crate::audio_preview::show_button(ui, &task.output_file, translations);
+                }
+                if let Some(error) = &task.error_message {
+                    ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(error);
+                }
+                if !task.timestamps.warnings.is_empty() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 180, 0), "🕒")
+                        .on_hover_text(format!("{}\n{}", translations.file_times_warning(), task.timestamps.warnings.join("\n")));
+                }
+                if let Some(trip) = &task.guards.tripped {
+                    ui.colored_label(egui::Color32::RED, "🛡").on_hover_text(trip.to_string());
+                } else if task.is_started() && task.guards.limits.is_active() {
+                    ui.weak(format!("🛡 {}", task.guards.limits.summary()))
+                        .on_hover_text(translations.output_guard_active());
+                }
+                let volume = crate::destination::destination_volume(&task.output_file);
+                if !volume.is_empty() {
+                    ui.weak("💾").on_hover_text(translations.destination_volume(&volume));
+                }
+            });
+        });
+
+        if task.status == TaskStatus::NeedsOutputRecovery {
+            Self::show_recovery_banner(ui, task, actions, translations);
+            return;
+        }
+        
+        let clock = |duration: std::time::Duration| crate::locale_format::format_duration(duration.as_secs() as f64, &translations.language);
+        let row_status = crate::download_cache::row_status(task.id, translations).or(crate::archive::row_status(task.id, translations));
+        if task.status == TaskStatus::Running && task.progress_unknown && row_status.is_none() {
+            // Without a known duration there is no fraction to fill a bar with
+            ui.horizontal(|ui| {
+                ui.spinner();
+                let elapsed = task.active_elapsed().map(clock).unwrap_or_default();
+                ui.label(format!("{} · ⏱ {}", translations.task_progress_unknown_duration(), elapsed));
+            });
+            return;
+        }
+        let (mut progress, mut text) = match task.status {
+            TaskStatus::Running => match task.remaining_time() {
+                Some(remaining) => (task.progress, format!("{:.1}% · ⏳ {}", task.progress * 100.0, clock(remaining))),
+                None => (task.progress, format!("{:.1}%", task.progress * 100.0)),
+            },
+            TaskStatus::Paused => (task.progress, format!("{} · {:.1}%", translations.task_pause_paused(), task.progress * 100.0)),
+            TaskStatus::Completed => (1.0, format!("{:?}", task.status)),
+            _ => (0.0, format!("{:?}", task.status)),
+        };
+        if let Some((fraction, status)) = row_status.filter(|_| task.status == TaskStatus::Running) {
+            (progress, text) = (fraction, status);
+        }
+        if let Some(checksum) = crate::checksum::row_status(&task.checksum, translations) {
+            text = format!("{} · {}", text, checksum);
+        }
+        ui.add(egui::ProgressBar::new(progress).text(text).desired_height(14.0));
+    }
+
+    /// Replaces the progress bar of a task whose destination went away: waits for the destination,
+    /// then offers restart, resume (when the partial output checked out) and redirect
+    fn show_recovery_banner(ui: &mut egui::Ui, task: &ProcessingTask, actions: &mut Vec<TaskAction>, translations: &Translations) {
+        use crate::output_recovery::RecoveryChoice;
+
+        let status = crate::output_recovery::status(task.id);
+        let reachable = status.as_ref().is_some_and(|status| status.reachable);
+        ui.horizontal(|ui| {
+            if reachable {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), translations.output_recovery_reachable());
+                if ui.small_button(translations.output_recovery_restart()).clicked() {
+                    actions.push(TaskAction::RecoverOutput { id: task.id, choice: RecoveryChoice::Restart });
+                }
+                match status.and_then(|status| status.resume) {
+                    Some(Ok(resume_at)) => {
+                        let label = format!("{} {}:{:02}", translations.output_recovery_resume(), resume_at as u64 / 60, resume_at as u64 % 60);
+                        if ui.small_button(label).clicked() {
+                            actions.push(TaskAction::RecoverOutput { id: task.id, choice: RecoveryChoice::Resume(resume_at) });
                         }
+                    }
+                    Some(Err(reason)) => {
+                        ui.add_enabled(false, egui::Button::new(translations.output_recovery_resume_short()).small())
+                            .on_disabled_hover_text(reason);
+                    }
+                    None => {
+                        ui.spinner();
+                    }
+                }
+            } else {
+                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), translations.output_recovery_waiting())
+                    .on_hover_text(task.error_message.as_deref().unwrap_or_default());
+            }
+            if ui.small_button(translations.output_recovery_redirect()).clicked() {
+                let file_name = std::path::Path::new(&task.output_file).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
+                    actions.push(TaskAction::RecoverOutput { id: task.id, choice: RecoveryChoice::Redirect(path.display().to_string()) });
+                }
             }
         });
     }
-    
-    fn terminate_ffmpeg_processes() {
-        #[cfg(target_os = "windows")]
-        {
-            let _ = std::process::Command::new("taskkill")
-                .args(&["/F", "/IM", "ffmpeg.exe"])
-                .output();
+
+    /// Reorder, rename and cancel entries of the row's context menu
+    fn show_queue_menu(
+        ui: &mut egui::Ui,
+        task: &ProcessingTask,
+        editing_label: &mut Option<(usize, String)>,
+        actions: &mut Vec<TaskAction>,
+        translations: &Translations,
+    ) {
+        if task.status == TaskStatus::Pending {
+            if ui.button(translations.task_queue_move_up()).clicked() {
+                actions.push(TaskAction::Move { id: task.id, up: true });
+                ui.close_menu();
+            }
+            if ui.button(translations.task_queue_move_down()).clicked() {
+                actions.push(TaskAction::Move { id: task.id, up: false });
+                ui.close_menu();
+            }
+            if ui.add_enabled(!task.cancel_requested, egui::Button::new(translations.cancel())).clicked() {
+                actions.push(TaskAction::Cancel { id: task.id, delete_partial: false });
+                ui.close_menu();
+            }
+            let mut requested = task.checksum.requested;
+            let mut write_sidecar = task.checksum.write_sidecar;
+            let mut changed = ui.checkbox(&mut requested, translations.checksum_compute()).changed();
+            ui.add_enabled_ui(requested, |ui| {
+                changed |= ui.checkbox(&mut write_sidecar, translations.checksum_sidecar()).changed();
+            });
+            if changed {
+                actions.push(TaskAction::SetChecksum { id: task.id, requested, write_sidecar });
+            }
+            let mut copy_times = task.timestamps.copy_times;
+            let mut write_creation_time = task.timestamps.write_creation_time;
+            let mut changed = ui.checkbox(&mut copy_times, translations.file_times_copy_times()).changed();
+            changed |= ui.checkbox(&mut write_creation_time, translations.file_times_creation_time()).changed();
+            if changed {
+                actions.push(TaskAction::SetTimestamps { id: task.id, copy_times, write_creation_time });
+            }
+            let archive_text = match &task.archive {
+                Some(path) => format!("{}: {}", translations.archive_package_into(), path),
+                None => format!("{}…", translations.archive_package_into()),
+            };
+            if ui.button(archive_text).clicked() {
+                let file_name = std::path::Path::new(&task.output_file)
+                    .file_stem()
+                    .map(|stem| format!("{}.zip", stem.to_string_lossy()))
+                    .unwrap_or_else(|| "output.zip".to_string());
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Zip", &["zip"])
+                    .add_filter("Tar (gzip)", &["tar.gz", "tgz"])
+                    .set_file_name(file_name)
+                    .save_file()
+                {
+                    actions.push(TaskAction::SetArchive { id: task.id, path: Some(path.display().to_string()) });
+                }
+                ui.close_menu();
+            }
+            if task.archive.is_some() && ui.button(translations.archive_no_package()).clicked() {
+                actions.push(TaskAction::SetArchive { id: task.id, path: None });
+                ui.close_menu();
+            }
+        }
+        if task.checksum.is_hashing() && ui.button(translations.checksum_cancel_hashing()).clicked() {
+            actions.push(TaskAction::CancelChecksum(task.id));
+            ui.close_menu();
+        }
+        if ui.button(translations.task_queue_rename()).clicked() {
+            *editing_label = Some((task.id, task.label.clone().unwrap_or_default()));
+            ui.close_menu();
+        }
+    }
+
+    /// Copy the task's ffmpeg command; tasks that have not run a command yet fall back to the preview
+    fn show_command_menu(ui: &mut egui::Ui, task: &ProcessingTask, translations: &Translations) {
+        use crate::shell_quote::{join_commands, split_preview, Shell};
+
+        let commands = if task.executed_commands.is_empty() {
+            crate::task_executor::TaskExecutor::preview_command(task)
+                .map(|preview| split_preview(&preview))
+                .unwrap_or_default()
+        } else {
+            task.executed_commands.clone()
+        };
+
+        ui.add_enabled_ui(!commands.is_empty(), |ui| {
+            if ui.button(translations.copy_task_command()).clicked() {
+                ui.output_mut(|o| o.copied_text = join_commands(&commands, Shell::native()));
+                ui.close_menu();
+            }
+            ui.menu_button(translations.copy_task_command_for(), |ui| {
+                for shell in Shell::all() {
+                    if ui.button(shell.display_name()).clicked() {
+                        ui.output_mut(|o| o.copied_text = join_commands(&commands, shell));
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    }
+
+    /// SHA-256 line of the details panel, with copy and cancel buttons
+    fn show_checksum(ui: &mut egui::Ui, task: &ProcessingTask, translations: &Translations) {
+        use crate::checksum::ChecksumState;
+
+        match &task.checksum.state {
+            ChecksumState::NotStarted => {}
+            ChecksumState::Hashing(job) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("🔐 {}", translations.checksum_hashing()));
+                    ui.add(egui::ProgressBar::new(job.progress()).show_percentage().desired_width(160.0));
+                    if ui.small_button(translations.cancel()).clicked() {
+                        job.cancel();
+                    }
+                });
+            }
+            ChecksumState::Done(hash) => {
+                ui.horizontal(|ui| {
+                    ui.label("🔐 SHA-256:");
+                    ui.add(egui::Label::new(egui::RichText::new(hash).monospace()).truncate());
+                    if ui.small_button("📋").on_hover_text(translations.checksum_copy()).clicked() {
+                        ui.output_mut(|o| o.copied_text = hash.clone());
+                    }
+                });
+                if let Some(error) = &task.checksum.sidecar_error {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}: {}", translations.checksum_sidecar_failed(), error));
+                }
+            }
+            ChecksumState::Failed(error) => {
+                ui.colored_label(egui::Color32::RED, format!("🔐 {}: {}", translations.checksum_failed(), error));
+            }
+            ChecksumState::Cancelled => {
+                ui.weak(format!("🔐 {}", translations.checksum_cancelled_short()));
+            }
+        }
+    }
+
+    /// Timing, error and environment of the selected task
+    fn show_details(ui: &mut egui::Ui, task: &ProcessingTask, translations: &Translations) {
+        ui.label(format!("{} #{}: {}", translations.task(), task.id, task.operation.display_name(translations)));
+        ui.label(format!("{}: {:?}", translations.status(), task.status));
+        ui.add(egui::Label::new(egui::RichText::new(&task.output_file).weak()).truncate());
+
+        // Show time information for running tasks; both hold still while paused
+        if task.is_started() {
+            if let Some(elapsed) = task.active_elapsed() {
+                let elapsed_secs = elapsed.as_secs();
+                
+                ui.horizontal(|ui| {
+                    ui.label(format!("⏱ {}: {}:{:02}", 
+                        if translations.language == crate::language::Language::Chinese { "已用时间" } else { "Elapsed" },
+                        elapsed_secs / 60, elapsed_secs % 60
+                    ));
+                    
+                    if let Some(remaining) = task.remaining_time() {
+                        // From ffmpeg's speed, or extrapolated from the progress so far
+                        let remaining_secs = remaining.as_secs();
+                        
+                        ui.separator();
+                        ui.label(format!("⏳ {}: {}:{:02}", 
+                            if translations.language == crate::language::Language::Chinese { "剩余时间" } else { "Remaining" },
+                            remaining_secs / 60, remaining_secs % 60
+                        ));
+                    } else if task.progress_unknown {
+                        ui.separator();
+                        ui.label(format!("⏳ {}", translations.task_progress_unknown_duration()));
+                    } else {
+                        ui.separator();
+                        ui.label(if translations.language == crate::language::Language::Chinese { 
+                            "⏳ 计算中..." 
+                        } else { 
+                            "⏳ Calculating..." 
+                        });
+                    }
+                });
+            }
+        } else if task.status == TaskStatus::Completed {
+            let total_time = task.completion_time.or_else(|| task.start_time.map(|start| start.elapsed()));
+            if let Some(total_time) = total_time {
+                let total_secs = total_time.as_secs();
+                ui.label(format!("✅ {}: {}:{:02}", 
+                    if translations.language == crate::language::Language::Chinese { "总用时" } else { "Total Time" },
+                    total_secs / 60, total_secs % 60
+                ));
+            }
         }
         
-        #[cfg(not(target_os = "windows"))]
-        {
-            let _ = std::process::Command::new("pkill")
-                .args(&["-f", "ffmpeg"])
-                .output();
+        if let Some(error) = &task.error_message {
+            ui.colored_label(egui::Color32::RED, format!("{}: {}", translations.error(), error));
         }
+        
+        if let Some(outcome) = &task.size_outcome {
+            ui.label(format!("🎯 {}", translations.size_outcome(outcome)));
+        }
+        
+        Self::show_checksum(ui, task, translations);
+        
+        if !task.log.is_empty() {
+            egui::CollapsingHeader::new(format!("{} ({})", translations.task_log_log(), task.log.len()))
+                .id_salt(("task_log", task.id))
+                .show(ui, |ui| crate::task_log::show(ui, &task.log, task.id, translations));
+        }
+        
+        if let Some(environment) = &task.environment {
+            egui::CollapsingHeader::new(translations.task_environment())
+                .id_salt(("task_environment", task.id))
+                .show(ui, |ui| {
+                    egui::Grid::new(("task_environment_grid", task.id)).num_columns(2).show(ui, |ui| {
+                        for (label, value) in environment.fields() {
+                            ui.weak(label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                    if ui.small_button(translations.copy_environment_report()).clicked() {
+                        ui.output_mut(|o| o.copied_text = environment.report());
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const STRESS_TASKS: usize = 5000;
+
+    /// Dummy queue of `count` tasks cycling through every status, as a large batch leaves it
+    fn dummy_tasks(count: usize) -> Vec<ProcessingTask> {
+        (0..count).map(|i| {
+            let mut task = ProcessingTask::new(
+                OperationType::VideoConvert,
+                vec![format!("/batch/in/clip_{:04}.mov", i)],
+                format!("/batch/out/clip_{:04}.mp4", i),
+            );
+            task.id = i + 1;
+            task.status = match i % 5 {
+                0 => TaskStatus::Running,
+                1 => TaskStatus::Pending,
+                2 => TaskStatus::Failed,
+                3 => TaskStatus::Completed,
+                _ => TaskStatus::Pending,
+            };
+            if task.status == TaskStatus::Running {
+                task.progress = 0.5;
+            }
+            task
+        }).collect()
+    }
+
+    /// One headless frame of the task panel in an 800x600 window; returns the number of shapes drawn
+    fn render(ctx: &egui::Context, tasks: &Arc<Mutex<Vec<ProcessingTask>>>, view: &mut TaskListView, generation: u64) -> usize {
+        let translations = Translations::new(Language::English);
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0))),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                TaskPanel::show(ui, tasks, view, generation, &translations);
+            });
+        });
+        output.shapes.len()
+    }
+
+    #[test]
+    fn view_counts_and_filters_a_large_queue() {
+        let tasks = dummy_tasks(STRESS_TASKS);
+        let mut view = TaskListView::default();
+        view.refresh(&tasks, 1);
+        assert_eq!(view.counts.total, STRESS_TASKS);
+        assert_eq!((view.counts.running, view.counts.pending, view.counts.failed, view.counts.completed), (1000, 2000, 1000, 1000));
+        // Running tasks are half done, failed and completed ones count fully
+        assert!((view.counts.overall_progress() - 0.5).abs() < 1e-4);
+        assert_eq!(view.positions.len(), STRESS_TASKS);
+
+        view.filter = TaskFilter::Failed;
+        view.refresh(&tasks, 1);
+        assert_eq!(view.positions.len(), 1000);
+        assert!(view.positions.iter().all(|&position| tasks[position].status == TaskStatus::Failed));
+
+        // The filename filter matches inputs and outputs, case-insensitively
+        view.filter = TaskFilter::All;
+        view.search = "CLIP_01".to_string();
+        view.refresh(&tasks, 1);
+        assert_eq!(view.positions, (100..200).collect::<Vec<_>>());
+        view.search = ".mov".to_string();
+        view.refresh(&tasks, 1);
+        assert_eq!(view.positions.len(), STRESS_TASKS);
+        view.search = "no such file".to_string();
+        view.refresh(&tasks, 1);
+        assert!(view.positions.is_empty());
+    }
+
+    #[test]
+    fn view_is_rebuilt_only_when_something_changed() {
+        let mut tasks = dummy_tasks(10);
+        let mut view = TaskListView::default();
+        view.refresh(&tasks, 1);
+        assert_eq!(view.counts.completed, 2);
+
+        // Same generation, filter and search: the index is kept as is
+        tasks[0].status = TaskStatus::Completed;
+        view.refresh(&tasks, 1);
+        assert_eq!(view.counts.completed, 2);
+
+        view.refresh(&tasks, 2);
+        assert_eq!(view.counts.completed, 3);
+        tasks.truncate(4);
+        view.search = "clip".to_string();
+        view.refresh(&tasks, 2);
+        assert_eq!(view.counts.total, 4);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn panel_stays_responsive_with_5000_tasks() {
+        let small = Arc::new(Mutex::new(dummy_tasks(50)));
+        let large = Arc::new(Mutex::new(dummy_tasks(STRESS_TASKS)));
+
+        // Only the rows in the scroll viewport are drawn, so a huge queue costs no more shapes
+        // than one that merely fills the panel
+        let small_shapes = {
+            let ctx = egui::Context::default();
+            let mut view = TaskListView::default();
+            render(&ctx, &small, &mut view, 1);
+            render(&ctx, &small, &mut view, 1)
+        };
+        let ctx = egui::Context::default();
+        let mut view = TaskListView::default();
+        render(&ctx, &large, &mut view, 1);
+        let large_shapes = render(&ctx, &large, &mut view, 1);
+        assert!(large_shapes <= small_shapes + 10, "{large_shapes} shapes for {STRESS_TASKS} tasks, {small_shapes} for 50");
+
+        // Repaints while the queue changes, each one rebuilding the index, stay far below the
+        // time a frame-per-card panel took; the bound is loose enough for unoptimized builds
+        const FRAMES: u32 = 20;
+        let started = Instant::now();
+        for frame in 0..FRAMES {
+            if let Ok(mut tasks) = large.lock() {
+                tasks[frame as usize].progress = 0.9;
+            }
+            render(&ctx, &large, &mut view, 2 + frame as u64);
+        }
+        let per_frame = started.elapsed() / FRAMES;
+        assert!(per_frame < Duration::from_millis(250), "{per_frame:?} per frame");
+        assert_eq!(view.counts.total, STRESS_TASKS);
+    }
+}
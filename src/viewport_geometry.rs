@@ -0,0 +1,407 @@
+use eframe::egui::{self, Pos2, Rect, Vec2};
+
+/// Offset of a newly opened workflow viewport from the main window's top-left corner
+pub const CASCADE_OFFSET: Vec2 = egui::vec2(60.0, 60.0);
+
+/// Kept free at every monitor edge for taskbars and docks; egui does not report the work area
+pub const WORK_AREA_INSET: f32 = 48.0;
+
+/// Canvas port layout, shared by drawing, hit-testing and connection lines
+const PORT_TOP: f32 = 35.0;
+const PORT_SPACING: f32 = 25.0;
+const PORT_OUTSET: f32 = 5.0;
+
+/// Rectangle in physical pixels, the one unit that means the same on monitors with different scales
+pub fn to_physical(rect: Rect, pixels_per_point: f32) -> Rect {
+    Rect::from_min_max(rect.min * pixels_per_point, rect.max * pixels_per_point)
+}
+
+pub fn to_points(rect: Rect, pixels_per_point: f32) -> Rect {
+    Rect::from_min_max(rect.min / pixels_per_point, rect.max / pixels_per_point)
+}
+
+/// Monitor holding the centre of `window`. egui reports the size of a window's monitor but not
+/// where it is, so monitors are assumed to be that size and tiled from the origin, which also
+/// places monitors left of or above the primary one at negative coordinates.
+pub fn monitor_area_guess(window: Rect, monitor_size: Vec2) -> Rect {
+    if monitor_size.x <= 0.0 || monitor_size.y <= 0.0 {
+        return window;
+    }
+    let center = window.center();
+    let min = egui::pos2(
+        (center.x / monitor_size.x).floor() * monitor_size.x,
+        (center.y / monitor_size.y).floor() * monitor_size.y,
+    );
+    Rect::from_min_size(min, monitor_size)
+}
+
+/// Usable part of a monitor after leaving room for taskbars
+pub fn work_area(monitor: Rect, inset: f32) -> Rect {
+    let shrunk = monitor.shrink(inset);
+    if shrunk.is_positive() { shrunk } else { monitor }
+}
+
+/// Shrink `rect` to fit `area`, never below `min_size`, then move it inside. A rect that still
+/// does not fit is aligned with the area's top-left corner so the title bar stays reachable.
+pub fn clamp_to_area(rect: Rect, area: Rect, min_size: Vec2) -> Rect {
+    let size = rect.size().min(area.size()).max(min_size);
+    let min = egui::pos2(
+        rect.min.x.min(area.max.x - size.x).max(area.min.x),
+        rect.min.y.min(area.max.y - size.y).max(area.min.y),
+    );
+    Rect::from_min_size(min, size)
+}
+
+/// Where the workflow viewport opens: its last geometry when there is one, otherwise cascaded
+/// from the main window, in either case kept inside the work area of the main window's monitor.
+/// All rects are physical pixels.
+pub fn initial_rect(remembered: Option<Rect>, main_window: Rect, monitor_size: Vec2, size: Vec2, min_size: Vec2, pixels_per_point: f32) -> Rect {
+    let area = work_area(monitor_area_guess(main_window, monitor_size), WORK_AREA_INSET * pixels_per_point);
+    let rect = remembered.unwrap_or_else(|| Rect::from_min_size(main_window.min + CASCADE_OFFSET * pixels_per_point, size));
+    clamp_to_area(rect, area, min_size)
+}
+
+/// Offset of an input or output port from its node's top-left corner
+pub fn port_offset(node_size: Vec2, port_index: usize, output: bool) -> Vec2 {
+    let x = if output { node_size.x + PORT_OUTSET } else { -PORT_OUTSET };
+    egui::vec2(x, PORT_TOP + port_index as f32 * PORT_SPACING)
+}
+
+/// Node height that fits `ports` ports down one side
+pub fn node_height_for_ports(ports: usize) -> f32 {
+    PORT_TOP + ports as f32 * PORT_SPACING
+}
+
+/// Zoom limits of the workflow canvas
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 2.0;
+/// Space kept around the nodes by "Fit to view", in screen points
+const FIT_MARGIN: f32 = 40.0;
+
+/// Where the workflow sits on screen this frame: workflow coordinates are scaled by `zoom` and
+/// shifted by `offset` from the canvas's top-left corner. Drawing, hit-testing and connection
+/// lines all go through it, so they agree at every pan and zoom.
+#[derive(Debug, Clone, Copy)]
+pub struct CanvasView {
+    /// The canvas on screen
+    pub rect: Rect,
+    pub offset: Vec2,
+    pub zoom: f32,
+}
+
+impl CanvasView {
+    pub fn new(rect: Rect, offset: Vec2, zoom: f32) -> Self {
+        Self { rect, offset, zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM) }
+    }
+
+    pub fn to_screen(self, position: Pos2) -> Pos2 {
+        self.rect.min + self.offset + position.to_vec2() * self.zoom
+    }
+
+    pub fn to_workflow(self, screen: Pos2) -> Pos2 {
+        ((screen - self.rect.min - self.offset) / self.zoom).to_pos2()
+    }
+
+    /// A length in workflow units, in screen points
+    pub fn scale(&self, length: f32) -> f32 {
+        length * self.zoom
+    }
+
+    /// A node's rectangle on screen. The top-left is snapped to the pixel grid of the viewport's
+    /// current scale so node text stays sharp and ports keep matching their hit areas.
+    pub fn node_rect(&self, painter: &egui::Painter, position: Pos2, size: Vec2) -> Rect {
+        Rect::from_min_size(painter.round_pos_to_pixels(self.to_screen(position)), size * self.zoom)
+    }
+
+    /// A port's centre on screen, for a node whose screen rectangle is `node_rect`
+    pub fn port_position(&self, node_rect: Rect, node_size: Vec2, port_index: usize, output: bool) -> Pos2 {
+        node_rect.min + port_offset(node_size, port_index, output) * self.zoom
+    }
+
+    /// Offset and zoom after zooming by `factor` with the workflow point under `anchor` staying put
+    pub fn zoomed_at(&self, anchor: Pos2, factor: f32) -> (Vec2, f32) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let fixed = self.to_workflow(anchor);
+        (anchor - self.rect.min - fixed.to_vec2() * zoom, zoom)
+    }
+
+    /// Offset and zoom that frame `bounds`, given in workflow coordinates, in the canvas.
+    /// Zooming in is capped at 1 so a few small nodes are not blown up.
+    pub fn fit(&self, bounds: Rect) -> (Vec2, f32) {
+        let room = (self.rect.size() - Vec2::splat(2.0 * FIT_MARGIN)).max(Vec2::splat(1.0));
+        let zoom = (room.x / bounds.width().max(1.0)).min(room.y / bounds.height().max(1.0)).clamp(MIN_ZOOM, 1.0);
+        (self.rect.size() / 2.0 - bounds.center().to_vec2() * zoom, zoom)
+    }
+}
+
+/// Connections are drawn as this many straight segments and hit-tested against the same polyline
+pub const CONNECTION_SEGMENTS: usize = 20;
+/// How far from a connection's drawn curve a right-click still picks it
+pub const CONNECTION_HIT_RADIUS: f32 = 6.0;
+/// How close to a connection the pointer must drop a dragged node to splice it in
+pub const SPLICE_HIT_RADIUS: f32 = 12.0;
+
+pub fn cubic_bezier(p0: Pos2, p1: Pos2, p2: Pos2, p3: Pos2, t: f32) -> Pos2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let mt3 = mt2 * mt;
+
+    egui::pos2(
+        mt3 * p0.x + 3.0 * mt2 * t * p1.x + 3.0 * mt * t2 * p2.x + t3 * p3.x,
+        mt3 * p0.y + 3.0 * mt2 * t * p1.y + 3.0 * mt * t2 * p2.y + t3 * p3.y,
+    )
+}
+
+/// Point at `t` along the curve from an output port at `from` to an input port at `to`. The
+/// curve leaves and enters its ports horizontally.
+pub fn connection_point(from: Pos2, to: Pos2, t: f32) -> Pos2 {
+    let control_offset = (to.x - from.x).abs().clamp(30.0, 100.0);
+    let control1 = egui::pos2(from.x + control_offset, from.y);
+    let control2 = egui::pos2(to.x - control_offset, to.y);
+    cubic_bezier(from, control1, control2, to, t)
+}
+
+/// The polyline a connection is drawn as
+pub fn connection_points(from: Pos2, to: Pos2) -> Vec<Pos2> {
+    (0..=CONNECTION_SEGMENTS)
+        .map(|i| connection_point(from, to, i as f32 / CONNECTION_SEGMENTS as f32))
+        .collect()
+}
+
+pub fn distance_to_segment(a: Pos2, b: Pos2, point: Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq == 0.0 {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Distance from `point` to the connection curve as drawn, not to the chord between its ports
+pub fn distance_to_connection(from: Pos2, to: Pos2, point: Pos2) -> f32 {
+    connection_points(from, to)
+        .windows(2)
+        .map(|segment| distance_to_segment(segment[0], segment[1], point))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Keys of the connections within `radius` of `point`, nearest first
+pub fn connections_near<K>(connections: impl IntoIterator<Item = (K, Pos2, Pos2)>, point: Pos2, radius: f32) -> Vec<K> {
+    let mut near: Vec<(K, f32)> = connections.into_iter()
+        .map(|(key, from, to)| {
+            let distance = distance_to_connection(from, to, point);
+            (key, distance)
+        })
+        .filter(|(_, distance)| *distance <= radius)
+        .collect();
+    near.sort_by(|a, b| a.1.total_cmp(&b.1));
+    near.into_iter().map(|(key, _)| key).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Pos2, b: Pos2) -> bool {
+        a.distance(b) < 1e-3
+    }
+
+    #[test]
+    fn bezier_interpolates_from_first_to_last_point() {
+        let (p0, p1, p2, p3) = (egui::pos2(0.0, 0.0), egui::pos2(10.0, 40.0), egui::pos2(30.0, 40.0), egui::pos2(40.0, 0.0));
+        assert!(close(cubic_bezier(p0, p1, p2, p3, 0.0), p0));
+        assert!(close(cubic_bezier(p0, p1, p2, p3, 1.0), p3));
+        // Symmetric control points put the middle of the curve on the axis of symmetry, 3/4 of
+        // the way up to the control points
+        assert!(close(cubic_bezier(p0, p1, p2, p3, 0.5), egui::pos2(20.0, 30.0)));
+
+        // Collinear, evenly spaced points give a straight line walked at constant speed
+        let line = |t| cubic_bezier(egui::pos2(0.0, 0.0), egui::pos2(10.0, 0.0), egui::pos2(20.0, 0.0), egui::pos2(30.0, 0.0), t);
+        assert!(close(line(0.25), egui::pos2(7.5, 0.0)));
+        assert!(close(line(0.75), egui::pos2(22.5, 0.0)));
+    }
+
+    #[test]
+    fn connection_leaves_and_enters_ports_horizontally() {
+        let (from, to) = (egui::pos2(0.0, 0.0), egui::pos2(200.0, 100.0));
+        let points = connection_points(from, to);
+        assert_eq!(points.len(), CONNECTION_SEGMENTS + 1);
+        assert!(close(points[0], from));
+        assert!(close(points[CONNECTION_SEGMENTS], to));
+        assert!((points[1].y - from.y).abs() < 2.0 && points[1].x > from.x);
+        assert!((points[CONNECTION_SEGMENTS - 1].y - to.y).abs() < 2.0 && points[CONNECTION_SEGMENTS - 1].x < to.x);
+        // Point-symmetric about the midpoint of the ports
+        assert!(close(connection_point(from, to, 0.5), egui::pos2(100.0, 50.0)));
+        assert!(close(connection_point(from, to, 0.3) + connection_point(from, to, 0.7).to_vec2(), egui::pos2(200.0, 100.0)));
+    }
+
+    #[test]
+    fn segment_distance_clamps_to_the_ends() {
+        let (a, b) = (egui::pos2(0.0, 0.0), egui::pos2(10.0, 0.0));
+        assert_eq!(distance_to_segment(a, b, egui::pos2(5.0, 3.0)), 3.0);
+        assert_eq!(distance_to_segment(a, b, egui::pos2(-3.0, 4.0)), 5.0);
+        assert_eq!(distance_to_segment(a, b, egui::pos2(13.0, 4.0)), 5.0);
+        assert_eq!(distance_to_segment(a, a, egui::pos2(3.0, 4.0)), 5.0);
+    }
+
+    #[test]
+    fn distance_is_measured_to_the_curve_not_the_chord() {
+        let (from, to) = (egui::pos2(0.0, 0.0), egui::pos2(200.0, 100.0));
+
+        // A point on the chord a quarter of the way along is well off the drawn curve
+        let on_chord = egui::pos2(50.0, 25.0);
+        assert!(distance_to_segment(from, to, on_chord) < 1e-3);
+        assert!(distance_to_connection(from, to, on_chord) > CONNECTION_HIT_RADIUS);
+
+        // Points on the curve are hits even where the chord is far away
+        for t in [0.1, 0.25, 0.5, 0.8] {
+            let on_curve = connection_point(from, to, t);
+            assert!(distance_to_connection(from, to, on_curve) < 0.5, "t = {t}");
+        }
+        let on_curve = connection_point(from, to, 0.2);
+        assert!(distance_to_segment(from, to, on_curve) > CONNECTION_HIT_RADIUS);
+
+        // Just inside and just outside the hit radius, straight below a flat stretch of the curve
+        let start = connection_point(from, to, 0.02);
+        assert!(distance_to_connection(from, to, start + egui::vec2(0.0, CONNECTION_HIT_RADIUS - 1.0)) <= CONNECTION_HIT_RADIUS);
+        assert!(distance_to_connection(from, to, egui::pos2(-CONNECTION_HIT_RADIUS - 1.0, 0.0)) > CONNECTION_HIT_RADIUS);
+    }
+
+    #[test]
+    fn nearest_connection_wins_among_overlapping_curves() {
+        // Three curves fanning out of one port overlap near it and drift apart towards their ends
+        let from = egui::pos2(0.0, 0.0);
+        let curves = [
+            ("a", from, egui::pos2(200.0, 100.0)),
+            ("b", from, egui::pos2(200.0, 110.0)),
+            ("c", from, egui::pos2(200.0, 300.0)),
+        ];
+
+        // Right at the shared port every curve is a hit; ties keep their order
+        assert_eq!(connections_near(curves, from, CONNECTION_HIT_RADIUS), vec!["a", "b", "c"]);
+
+        // On curve b where a runs close by: b first, a second, c out of reach
+        let on_b = connection_point(from, curves[1].2, 0.6);
+        assert_eq!(connections_near(curves, on_b, SPLICE_HIT_RADIUS), vec!["b", "a"]);
+        assert_eq!(connections_near(curves, on_b, 0.5), vec!["b"]);
+
+        // Slightly to a's side of the midway line between a and b
+        let on_a = connection_point(from, curves[0].2, 0.6);
+        let between = on_a + (on_b - on_a) * 0.3;
+        assert_eq!(connections_near(curves, between, SPLICE_HIT_RADIUS), vec!["a", "b"]);
+
+        // Far from all of them
+        assert!(connections_near(curves, egui::pos2(150.0, -50.0), SPLICE_HIT_RADIUS).is_empty());
+    }
+
+    const MONITOR: Vec2 = egui::vec2(1920.0, 1080.0);
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect::from_min_size(egui::pos2(x, y), egui::vec2(w, h))
+    }
+
+    #[test]
+    fn monitors_left_of_and_above_the_primary_are_at_negative_coordinates() {
+        assert_eq!(monitor_area_guess(rect(100.0, 100.0, 800.0, 600.0), MONITOR), rect(0.0, 0.0, 1920.0, 1080.0));
+        assert_eq!(monitor_area_guess(rect(-900.0, 0.0, 800.0, 600.0), MONITOR), rect(-1920.0, 0.0, 1920.0, 1080.0));
+        assert_eq!(monitor_area_guess(rect(-500.0, -700.0, 800.0, 600.0), MONITOR), rect(-1920.0, -1080.0, 1920.0, 1080.0));
+        // A window straddling two monitors belongs to the one holding its centre
+        assert_eq!(monitor_area_guess(rect(-300.0, 100.0, 800.0, 600.0), MONITOR), rect(0.0, 0.0, 1920.0, 1080.0));
+        assert_eq!(monitor_area_guess(rect(-600.0, 100.0, 800.0, 600.0), MONITOR), rect(-1920.0, 0.0, 1920.0, 1080.0));
+        // Unknown monitor size: the window itself is the only area known
+        let window = rect(-600.0, 100.0, 800.0, 600.0);
+        assert_eq!(monitor_area_guess(window, Vec2::ZERO), window);
+    }
+
+    #[test]
+    fn work_area_keeps_the_monitor_when_the_inset_would_swallow_it() {
+        let left = rect(-1920.0, 0.0, 1920.0, 1080.0);
+        assert_eq!(work_area(left, WORK_AREA_INSET), Rect::from_min_max(egui::pos2(-1872.0, 48.0), egui::pos2(-48.0, 1032.0)));
+        let tiny = rect(-80.0, -80.0, 80.0, 80.0);
+        assert_eq!(work_area(tiny, WORK_AREA_INSET), tiny);
+    }
+
+    #[test]
+    fn clamping_moves_and_shrinks_rects_into_negative_areas() {
+        let area = work_area(rect(-1920.0, 0.0, 1920.0, 1080.0), WORK_AREA_INSET);
+        let min_size = egui::vec2(400.0, 300.0);
+
+        // Already inside: untouched
+        let inside = rect(-1500.0, 200.0, 800.0, 600.0);
+        assert_eq!(clamp_to_area(inside, area, min_size), inside);
+
+        // Off the far left and below the bottom edge: pulled back against both edges
+        assert_eq!(clamp_to_area(rect(-3000.0, 900.0, 800.0, 600.0), area, min_size), rect(-1872.0, 432.0, 800.0, 600.0));
+
+        // On the primary monitor to the right: pushed back left of the origin
+        assert_eq!(clamp_to_area(rect(200.0, 100.0, 800.0, 600.0), area, min_size), rect(-848.0, 100.0, 800.0, 600.0));
+
+        // Bigger than the area: shrunk to fill it exactly
+        assert_eq!(clamp_to_area(rect(-2500.0, -300.0, 3000.0, 2000.0), area, min_size), area);
+
+        // Minimum size bigger than the area: top-left stays reachable, the rest hangs off
+        let clamped = clamp_to_area(rect(-100.0, 500.0, 100.0, 100.0), area, egui::vec2(2000.0, 1000.0));
+        assert_eq!(clamped, Rect::from_min_size(area.min, egui::vec2(2000.0, 1000.0)));
+    }
+
+    #[test]
+    fn initial_rect_stays_on_the_main_windows_monitor_at_any_scale() {
+        let size = egui::vec2(1000.0, 700.0);
+        let min_size = egui::vec2(400.0, 300.0);
+
+        // Main window on a monitor above and left of the primary, at 2x: cascade fits as is
+        let main_window = rect(-1920.0, -1080.0, 920.0, 680.0);
+        let opened = initial_rect(None, main_window, MONITOR, size, min_size, 2.0);
+        assert_eq!(opened, rect(-1800.0, -960.0, 1000.0, 700.0));
+
+        // Cascade running past the monitor's bottom edge is pulled back up inside the inset area
+        let main_window = rect(-1200.0, -600.0, 900.0, 500.0);
+        let opened = initial_rect(None, main_window, MONITOR, size, min_size, 1.0);
+        assert_eq!(opened, rect(-1140.0, -748.0, 1000.0, 700.0));
+
+        // Remembered geometry from a monitor that is gone lands on the main window's monitor
+        let opened = initial_rect(Some(rect(5000.0, 5000.0, 800.0, 600.0)), main_window, MONITOR, size, min_size, 2.0);
+        assert_eq!(opened, rect(-896.0, -696.0, 800.0, 600.0));
+        assert!(work_area(monitor_area_guess(main_window, MONITOR), WORK_AREA_INSET * 2.0).contains_rect(opened));
+    }
+
+    #[test]
+    fn physical_and_point_rects_round_trip_with_negative_origins() {
+        let points = rect(-1536.0, -864.0, 800.0, 600.0);
+        for ppp in [1.0, 1.25, 1.5, 2.0] {
+            let physical = to_physical(points, ppp);
+            assert_eq!(physical.min, points.min * ppp);
+            let back = to_points(physical, ppp);
+            assert!(close(back.min, points.min) && close(back.max, points.max), "{ppp}: {back:?}");
+        }
+    }
+
+    #[test]
+    fn ports_sit_on_node_edges_and_node_origins_snap_to_each_scales_pixel_grid() {
+        let node = egui::vec2(150.0, 80.0);
+        assert_eq!(port_offset(node, 0, false), egui::vec2(-5.0, 35.0));
+        assert_eq!(port_offset(node, 2, true), egui::vec2(155.0, 85.0));
+
+        let view = CanvasView::new(Rect::from_min_size(egui::pos2(-300.3, 40.7), egui::vec2(800.0, 600.0)), Vec2::ZERO, 1.0);
+        let position = egui::pos2(120.45, 33.33);
+        for ppp in [1.0, 1.25, 1.5, 2.0] {
+            let ctx = egui::Context::default();
+            let mut input = egui::RawInput::default();
+            input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(ppp);
+            let mut origin = Pos2::ZERO;
+            let _ = ctx.run(input, |ctx| {
+                let painter = ctx.layer_painter(egui::LayerId::background());
+                origin = view.node_rect(&painter, position, node).min;
+            });
+            let unsnapped = view.to_screen(position);
+            for (snapped, raw) in [(origin.x, unsnapped.x), (origin.y, unsnapped.y)] {
+                let pixels = snapped * ppp;
+                assert!((pixels - pixels.round()).abs() < 1e-3, "{ppp}: {snapped} is off the pixel grid");
+                assert!((snapped - raw).abs() <= 0.5 / ppp + 1e-4, "{ppp}: {snapped} moved too far from {raw}");
+            }
+        }
+    }
+}
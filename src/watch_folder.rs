@@ -0,0 +1,490 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Serialize, Deserialize};
+use crate::automation_flow::{AutomationWorkflow, WorkflowExecutor};
+use crate::bundled_ffmpeg::get_bundled_ffmpeg;
+use crate::folder_run::FolderRunSettings;
+use crate::language::Translations;
+use crate::run_cancel::CancelToken;
+use crate::task_executor::was_recently_written;
+
+/// Metadata comment written into outputs of watch-folder tasks, used as a last-resort loop filter
+pub const OUTPUT_TAG: &str = "ffgui";
+
+/// Seconds a new file has to stay unchanged before it is processed
+pub const DEFAULT_DEBOUNCE_SECS: u64 = 5;
+
+/// How often the watch folder is listed
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watch folder definition: new files in `watch_dir` matching `include_glob` are processed into `output_dir`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchFolderConfig {
+    pub watch_dir: String,
+    pub output_dir: String,
+    /// Semicolon separated patterns, e.g. "*.mov;*.mkv"
+    pub include_glob: String,
+    /// Workflow file run once per new file
+    #[serde(default)]
+    pub workflow_path: String,
+    /// A file still being written keeps changing; it is picked up once unchanged this long
+    #[serde(default = "default_debounce_secs")]
+    pub debounce_secs: u64,
+}
+
+fn default_debounce_secs() -> u64 {
+    DEFAULT_DEBOUNCE_SECS
+}
+
+impl Default for WatchFolderConfig {
+    fn default() -> Self {
+        Self {
+            watch_dir: String::new(),
+            output_dir: String::new(),
+            include_glob: String::new(),
+            workflow_path: String::new(),
+            debounce_secs: DEFAULT_DEBOUNCE_SECS,
+        }
+    }
+}
+
+impl WatchFolderConfig {
+    /// The folder run each new file gets: the workflow's outputs go to `output_dir`
+    pub fn run_settings(&self) -> FolderRunSettings {
+        FolderRunSettings {
+            input_dir: self.watch_dir.clone(),
+            pattern: self.include_glob.clone(),
+            output_dir: self.output_dir.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Reject configurations that would re-process their own outputs forever
+    pub fn validate(&self, output_extension: &str) -> Result<(), String> {
+        if self.watch_dir.trim().is_empty() {
+            return Err("Watch folder is not set".to_string());
+        }
+        if self.output_dir.trim().is_empty() {
+            return Err("Output folder is not set".to_string());
+        }
+
+        let watch_dir = normalize_dir(&self.watch_dir);
+        let output_dir = normalize_dir(&self.output_dir);
+        if output_dir.starts_with(&watch_dir) {
+            let probe_name = format!("output.{}", output_extension);
+            if glob_matches(&self.include_glob, &probe_name) {
+                return Err(format!(
+                    "Output folder '{}' is inside watch folder '{}' and '*.{}' files match the include pattern '{}' - outputs would be processed again in a loop",
+                    self.output_dir, self.watch_dir, output_extension, self.include_glob
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a newly detected file should be picked up by this watcher
+    pub fn should_process(&self, path: &Path) -> bool {
+        let file_name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+        glob_matches(&self.include_glob, file_name) && !is_own_output(path)
+    }
+}
+
+thread_local! {
+    /// Set while a watch-folder run executes on this thread; a run stays on its thread start to finish
+    static TAG_OUTPUTS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `run` with the outputs of the ffmpeg commands it starts on this thread tagged as ours
+pub fn with_output_tag<T>(run: impl FnOnce() -> T) -> T {
+    let previous = TAG_OUTPUTS.replace(true);
+    let result = run();
+    TAG_OUTPUTS.set(previous);
+    result
+}
+
+/// ffmpeg arguments with the output tag placed after the last input, where it applies to the
+/// output, when a watch-folder run executes on this thread; None otherwise
+pub fn tagged_output_args(args: &[String]) -> Option<Vec<String>> {
+    if !TAG_OUTPUTS.get() {
+        return None;
+    }
+    Some(with_output_tag_args(args))
+}
+
+/// `args` with the ffmpeg arguments that tag an output as produced by this application
+fn with_output_tag_args(args: &[String]) -> Vec<String> {
+    let at = args.iter().rposition(|arg| arg == "-i").map_or(0, |i| (i + 2).min(args.len()));
+    let mut tagged = args[..at].to_vec();
+    tagged.extend(["-metadata".to_string(), format!("comment={}", OUTPUT_TAG)]);
+    tagged.extend_from_slice(&args[at..]);
+    tagged
+}
+
+/// Files recently written by our own tasks, or carrying our metadata tag, must never be re-processed
+pub fn is_own_output(path: &Path) -> bool {
+    was_recently_written(&path.display().to_string()) || has_output_tag(path)
+}
+
+fn has_output_tag(path: &Path) -> bool {
+    let ffmpeg = match get_bundled_ffmpeg() {
+        Ok(ffmpeg) => ffmpeg,
+        Err(_) => return false,
+    };
+    let path_str = path.display().to_string();
+    match ffmpeg.run_ffprobe(&[
+        "-v", "error",
+        "-show_entries", "format_tags=comment",
+        "-of", "default=noprint_wrappers=1:nokey=1",
+        &path_str,
+    ]) {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).trim() == OUTPUT_TAG,
+        Err(_) => false,
+    }
+}
+
+/// Canonical form of a directory for nesting checks; falls back to the literal path if it doesn't exist yet
+fn normalize_dir(dir: &str) -> PathBuf {
+    std::fs::canonicalize(dir).unwrap_or_else(|_| PathBuf::from(dir.trim_end_matches(['/', '\\'])))
+}
+
+/// Case-insensitive match against semicolon separated `*` / `?` patterns; an empty pattern matches everything
+pub fn glob_matches(patterns: &str, file_name: &str) -> bool {
+    let patterns: Vec<&str> = patterns.split(';').map(|p| p.trim()).filter(|p| !p.is_empty()).collect();
+    if patterns.is_empty() {
+        return true;
+    }
+    let name: Vec<char> = file_name.to_lowercase().chars().collect();
+    patterns.iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        wildcard_match(&pattern, &name)
+    })
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| wildcard_match(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
+
+/// Files already handled by the watcher with their modification time, kept across restarts; a
+/// file changed after it was processed is processed again
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProcessedFiles {
+    files: BTreeMap<String, u64>,
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    modified.duration_since(SystemTime::UNIX_EPOCH).ok().map(|since| since.as_secs())
+}
+
+impl ProcessedFiles {
+    /// Next to the preferences file in the per-user config directory
+    fn file_path() -> Option<PathBuf> {
+        Some(crate::app_state::UserPreferences::file_path()?.with_file_name("watch_folder_state.json"))
+    }
+
+    /// Entries of files that no longer exist are dropped
+    fn load() -> Self {
+        let Some(json) = Self::file_path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+            return Self::default();
+        };
+        let mut processed: Self = serde_json::from_str(&json).unwrap_or_else(|e| {
+            log_warn!("Ignoring unreadable watch folder state: {}", e);
+            Self::default()
+        });
+        processed.files.retain(|path, _| Path::new(path).exists());
+        processed
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::file_path() else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log_warn!("Could not save the watch folder state to {}: {}", path.display(), e);
+        }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.files.get(&path.display().to_string()).is_some_and(|seen| Some(*seen) == modified_secs(path))
+    }
+
+    fn insert(&mut self, path: &Path) {
+        self.files.insert(path.display().to_string(), modified_secs(path).unwrap_or_default());
+    }
+}
+
+/// What the watcher last did, for the status bar
+#[derive(Clone, Debug, Default)]
+pub struct WatchStatus {
+    /// File being processed right now
+    pub running: Option<String>,
+    /// Time and name of the last file processed
+    pub last_trigger: Option<(chrono::DateTime<chrono::Local>, String)>,
+    pub last_error: Option<String>,
+    /// Files processed since the watcher started
+    pub processed: usize,
+}
+
+/// Background thread polling the watch folder and running the workflow once per new file, one
+/// file at a time. Stops when dropped.
+pub struct FolderWatcher {
+    pub config: WatchFolderConfig,
+    paused: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<WatchStatus>>,
+    token: CancelToken,
+}
+
+impl FolderWatcher {
+    /// Start watching with the settings of `executor`. Fails when the workflow cannot run on single
+    /// files or its outputs would be picked up again.
+    pub fn start(config: WatchFolderConfig, workflow: &AutomationWorkflow, executor: &WorkflowExecutor) -> Result<Self, String> {
+        let workflow = workflow.flattened();
+        if crate::folder_run::check_workflow(&workflow).is_err() {
+            return Err(format!("Workflow '{}' needs exactly one Input File node and an Output File node", workflow.name));
+        }
+        if !Path::new(config.watch_dir.trim()).is_dir() {
+            return Err(format!("Watch folder '{}' does not exist", config.watch_dir));
+        }
+        let settings = config.run_settings();
+        let (_, outputs) = crate::folder_run::workflow_for_file(&workflow, &Path::new(config.watch_dir.trim()).join("probe"), &settings);
+        for output in &outputs {
+            config.validate(&output.extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default())?;
+        }
+
+        let paused = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let status = Arc::new(Mutex::new(WatchStatus::default()));
+        let token = CancelToken::default();
+        let mut runner = executor.runner(&token);
+        let thread_config = config.clone();
+        let (thread_paused, thread_stop, thread_status, thread_token) = (Arc::clone(&paused), Arc::clone(&stop), Arc::clone(&status), token.clone());
+        log_info!("Watching {} for {} with workflow '{}'", config.watch_dir, config.include_glob, workflow.name);
+        std::thread::spawn(move || {
+            let config = thread_config;
+            let set_status = |update: &dyn Fn(&mut WatchStatus)| update(&mut thread_status.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+            let debounce = Duration::from_secs(config.debounce_secs);
+            let mut processed = ProcessedFiles::load();
+            // Size and modification time last seen per waiting file, and since when they are unchanged
+            let mut waiting: HashMap<PathBuf, (u64, Option<SystemTime>, Instant)> = HashMap::new();
+            let mut next_poll = Instant::now();
+            while !thread_stop.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(200));
+                if Instant::now() < next_poll || thread_paused.load(Ordering::SeqCst) {
+                    continue;
+                }
+                next_poll = Instant::now() + POLL_INTERVAL;
+                if !Path::new(config.watch_dir.trim()).is_dir() {
+                    set_status(&|status| status.last_error = Some(format!("Watch folder '{}' is not available", config.watch_dir)));
+                    continue;
+                }
+
+                let files = crate::folder_run::matching_files(&settings);
+                waiting.retain(|path, _| files.contains(path));
+                let mut ready = Vec::new();
+                for path in files.into_iter().filter(|path| !processed.contains(path)) {
+                    let Ok(metadata) = std::fs::metadata(&path) else {
+                        continue;
+                    };
+                    let seen = (metadata.len(), metadata.modified().ok());
+                    let entry = waiting.entry(path.clone()).or_insert((seen.0, seen.1, Instant::now()));
+                    if (entry.0, entry.1) != seen {
+                        *entry = (seen.0, seen.1, Instant::now());
+                    } else if entry.2.elapsed() >= debounce {
+                        ready.push(path);
+                    }
+                }
+
+                for input in ready {
+                    if thread_stop.load(Ordering::SeqCst) || thread_paused.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    waiting.remove(&input);
+                    if !config.should_process(&input) {
+                        log_debug!("Watch folder: skipping our own output {}", input.display());
+                        processed.insert(&input);
+                        processed.save();
+                        continue;
+                    }
+                    let name = input.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                    log_info!("Watch folder: processing {}", input.display());
+                    set_status(&|status| status.running = Some(name.clone()));
+                    let (file_workflow, outputs) = crate::folder_run::workflow_for_file(&workflow, &input, &settings);
+                    for dir in outputs.iter().filter_map(|output| output.parent()) {
+                        if let Err(e) = std::fs::create_dir_all(dir) {
+                            log_warn!("Could not create output folder {}: {}", dir.display(), e);
+                        }
+                    }
+                    let result = crate::run_cancel::with_token(&thread_token, || with_output_tag(|| runner.execute_workflow(file_workflow)));
+                    // A failed file is not retried until it changes; outputs written into the watch
+                    // folder must not come back as new files
+                    processed.insert(&input);
+                    for output in outputs.iter().filter(|output| output.exists()) {
+                        crate::task_executor::record_written_output(&output.display().to_string());
+                        processed.insert(output);
+                    }
+                    processed.save();
+                    let now = chrono::Local::now();
+                    set_status(&|status| {
+                        status.running = None;
+                        status.last_trigger = Some((now, name.clone()));
+                        match &result {
+                            Ok(()) => status.processed += 1,
+                            Err(e) => status.last_error = Some(format!("{}: {}", name, e)),
+                        }
+                    });
+                    if let Err(e) = &result {
+                        log_warn!("Watch folder: {} failed: {}", input.display(), e);
+                    }
+                }
+            }
+            log_info!("Stopped watching {}", config.watch_dir);
+        });
+        Ok(Self { config, paused, stop, status, token })
+    }
+
+    pub fn status(&self) -> WatchStatus {
+        self.status.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// A paused watcher finishes the file it is processing; files arriving meanwhile are processed on resume
+    pub fn set_paused(&self, paused: bool) {
+        log_info!("Watch folder {}", if paused { "paused" } else { "resumed" });
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+}
+
+impl Drop for FolderWatcher {
+    /// Ends the thread after its current poll; a running workflow is stopped
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.token.cancel();
+    }
+}
+
+/// Folders, pattern, debounce and workflow file of the watch folder. Returns true when something changed.
+pub fn show_editor(ui: &mut egui::Ui, config: &mut WatchFolderConfig, focused_workflow: Option<&Path>, translations: &Translations) -> bool {
+    let mut changed = false;
+    egui::Grid::new("watch_folder_settings").num_columns(2).show(ui, |ui| {
+        for (label, dir) in [
+            (translations.watch_folder_watch_dir(), &mut config.watch_dir),
+            (translations.watch_folder_output_dir(), &mut config.output_dir),
+        ] {
+            ui.label(label);
+            ui.horizontal(|ui| {
+                changed |= ui.text_edit_singleline(dir).changed();
+                if ui.button(translations.folder_run_browse()).clicked() {
+                    if let Some(picked) = rfd::FileDialog::new().pick_folder() {
+                        *dir = picked.display().to_string();
+                        changed = true;
+                    }
+                }
+            });
+            ui.end_row();
+        }
+        ui.label(translations.folder_run_pattern());
+        changed |= ui.add(egui::TextEdit::singleline(&mut config.include_glob).hint_text("*.mkv;*.mp4")).changed();
+        ui.end_row();
+        ui.label(translations.watch_folder_debounce());
+        changed |= ui.add(egui::DragValue::new(&mut config.debounce_secs).range(1..=600).suffix(" s"))
+            .on_hover_text(translations.watch_folder_debounce_hint())
+            .changed();
+        ui.end_row();
+        ui.label(translations.watch_folder_workflow());
+        ui.horizontal(|ui| {
+            changed |= ui.text_edit_singleline(&mut config.workflow_path).changed();
+            if ui.button(translations.folder_run_browse()).clicked() {
+                if let Some(picked) = rfd::FileDialog::new().add_filter("Workflow Files", &["json"]).pick_file() {
+                    config.workflow_path = picked.display().to_string();
+                    changed = true;
+                }
+            }
+            if ui.add_enabled(focused_workflow.is_some(), egui::Button::new(translations.watch_folder_use_focused())).clicked() {
+                if let Some(path) = focused_workflow {
+                    config.workflow_path = path.display().to_string();
+                    changed = true;
+                }
+            }
+        });
+        ui.end_row();
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(watch_dir: &str, output_dir: &str, include_glob: &str) -> WatchFolderConfig {
+        WatchFolderConfig {
+            watch_dir: watch_dir.to_string(),
+            output_dir: output_dir.to_string(),
+            include_glob: include_glob.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn output_folder_inside_the_watch_folder_is_rejected() {
+        let looping = config("/no/such/watch", "/no/such/watch/out/", "*.mp4;*.mov");
+        assert_eq!(
+            looping.validate("mp4").unwrap_err(),
+            "Output folder '/no/such/watch/out/' is inside watch folder '/no/such/watch' and '*.mp4' files match the include pattern '*.mp4;*.mov' - outputs would be processed again in a loop",
+        );
+        // The same folder loops as well
+        assert!(config("/no/such/watch", "/no/such/watch", "").validate("mkv").is_err());
+    }
+
+    #[test]
+    fn configurations_that_cannot_loop_are_accepted() {
+        // Outputs inside the watch folder that the pattern does not pick up
+        assert_eq!(config("/no/such/watch", "/no/such/watch/out", "*.mov").validate("mp4"), Ok(()));
+        assert_eq!(config("/no/such/watch", "/no/such/done", "*.mp4").validate("mp4"), Ok(()));
+        assert_eq!(config("", "/no/such/done", "*.mp4").validate("mp4").unwrap_err(), "Watch folder is not set");
+        assert_eq!(config("/no/such/watch", " ", "*.mp4").validate("mp4").unwrap_err(), "Output folder is not set");
+    }
+
+    #[test]
+    fn outputs_are_tagged_only_inside_watch_folder_runs() {
+        let args: Vec<String> = ["-y", "-i", "in.mov", "-c:v", "libx264", "out.mp4"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(tagged_output_args(&args), None);
+        let tagged = with_output_tag(|| tagged_output_args(&args)).unwrap();
+        assert_eq!(tagged, ["-y", "-i", "in.mov", "-metadata", "comment=ffgui", "-c:v", "libx264", "out.mp4"]);
+        // The flag is cleared when the run returns
+        assert_eq!(tagged_output_args(&args), None);
+    }
+
+    #[test]
+    fn tag_goes_after_the_last_input() {
+        let args: Vec<String> = ["-i", "a.mp4", "-i", "b.wav", "-map", "0:v", "out.mkv"].iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(with_output_tag_args(&args), ["-i", "a.mp4", "-i", "b.wav", "-metadata", "comment=ffgui", "-map", "0:v", "out.mkv"]);
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_matches("", "anything.bin"));
+        assert!(glob_matches("*.MOV; *.mkv", "clip.mov"));
+        assert!(glob_matches("take_??.wav", "take_01.wav"));
+        assert!(!glob_matches("take_??.wav", "take_1.wav"));
+        assert!(!glob_matches("*.mp4", "clip.mp4.part"));
+    }
+}
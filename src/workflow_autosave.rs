@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+
+use crate::app_state::UserPreferences;
+use crate::automation_flow::AutomationWorkflow;
+use crate::workflow_file;
+
+/// Seconds between autosaves of unsaved workflow changes
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Generation handed to the next save or discard
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+/// Generation of the last write or removal; held while touching the file, so a save that
+/// finishes after a newer one or after a discard is dropped instead of overwriting it
+static WRITTEN_GENERATION: Mutex<u64> = Mutex::new(0);
+
+/// A workflow tab with unsaved changes
+pub struct UnsavedWorkflow {
+    pub workflow: AutomationWorkflow,
+    /// File the tab was loaded from or last saved to
+    pub file_path: Option<PathBuf>,
+}
+
+/// Unsaved workflows left behind by a session that did not save them
+pub struct Recovery {
+    pub saved_at: DateTime<Local>,
+    pub workflows: Vec<UnsavedWorkflow>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredWorkflow {
+    file_path: Option<PathBuf>,
+    /// Workflow file contents, read back with `workflow_file::parse`
+    workflow: serde_json::Value,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredAutosave {
+    saved_at: DateTime<Local>,
+    workflows: Vec<StoredWorkflow>,
+}
+
+/// Next to the preferences file in the per-user config directory
+pub fn file_path() -> Option<PathBuf> {
+    Some(UserPreferences::file_path()?.with_file_name("autosave_workflow.json"))
+}
+
+pub fn exists() -> bool {
+    file_path().is_some_and(|path| path.exists())
+}
+
+/// Write `workflows` on a background thread so a large workflow does not stall the editor
+pub fn save_in_background(workflows: Vec<UnsavedWorkflow>) {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    let saved_at = Local::now();
+    std::thread::spawn(move || {
+        let Some(path) = file_path() else {
+            return;
+        };
+        let result = workflows.iter()
+            .map(|unsaved| Ok(StoredWorkflow {
+                file_path: unsaved.file_path.clone(),
+                workflow: workflow_file::to_value(&unsaved.workflow)?,
+            }))
+            .collect::<serde_json::Result<Vec<_>>>()
+            .and_then(|workflows| serde_json::to_string(&StoredAutosave { saved_at, workflows }))
+            .map_err(|e| e.to_string());
+        let json = match result {
+            Ok(json) => json,
+            Err(e) => {
+                log_warn!("Could not serialize the workflow autosave: {}", e);
+                return;
+            }
+        };
+        let mut written = WRITTEN_GENERATION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if *written > generation {
+            return;
+        }
+        *written = generation;
+        // Written beside the target and renamed over it, so a crash mid-write keeps the previous autosave
+        let partial = path.with_extension("json.partial");
+        let result = path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&partial, json))
+            .and_then(|_| std::fs::rename(&partial, &path));
+        match result {
+            Ok(()) => log_debug!("Autosaved {} unsaved workflow(s) to {}", workflows.len(), path.display()),
+            Err(e) => log_warn!("Could not autosave workflows to {}: {}", path.display(), e),
+        }
+    });
+}
+
+/// Autosaved workflows worth offering: a workflow whose file was saved after the autosave is left
+/// out. Nothing is returned when no autosave exists or everything in it was saved since.
+pub fn load() -> Option<Recovery> {
+    let json = std::fs::read_to_string(file_path()?).ok()?;
+    let stored: StoredAutosave = match serde_json::from_str(&json) {
+        Ok(stored) => stored,
+        Err(e) => {
+            log_warn!("Ignoring unreadable workflow autosave: {}", e);
+            return None;
+        }
+    };
+    let saved_since = |path: &PathBuf| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| DateTime::<Local>::from(modified) > stored.saved_at)
+    };
+    let workflows: Vec<UnsavedWorkflow> = stored.workflows.into_iter()
+        .filter(|stored| !stored.file_path.as_ref().is_some_and(saved_since))
+        .filter_map(|stored| match workflow_file::parse(&stored.workflow.to_string()) {
+            Ok((workflow, _)) => Some(UnsavedWorkflow { workflow, file_path: stored.file_path }),
+            Err(e) => {
+                log_warn!("Skipping unreadable autosaved workflow: {}", e);
+                None
+            }
+        })
+        .collect();
+    if workflows.is_empty() {
+        discard();
+        return None;
+    }
+    Some(Recovery { saved_at: stored.saved_at, workflows })
+}
+
+/// Remove the autosave, e.g. once everything is saved or the user declined recovering it.
+/// Autosaves still being written are dropped.
+pub fn discard() {
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    let mut written = WRITTEN_GENERATION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *written = generation;
+    if let Some(path) = file_path().filter(|path| path.exists()) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => log_info!("Removed the workflow autosave"),
+            Err(e) => log_warn!("Could not remove {}: {}", path.display(), e),
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::automation_flow::{AutomationWorkflow, NodeType};
+use crate::checksum::{to_hex, Sha256};
+
+/// Cache entries unused for this long are dropped
+const MAX_ENTRY_AGE_SECS: i64 = 14 * 24 * 60 * 60;
+/// Total size of all cached intermediates; least recently used entries go first beyond it
+const MAX_CACHE_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+
+/// Directory holding one cache per workflow id
+fn cache_root() -> Option<PathBuf> {
+    crate::app_state::UserPreferences::file_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("workflow_cache")))
+}
+
+/// Size and modification time of an existing file, as text for a cache key. A folder (such as
+/// an image sequence) is identified by the names, sizes and times of the files in it.
+pub fn file_identity(path: &str) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if metadata.is_dir() {
+        let mut files: Vec<String> = std::fs::read_dir(path).ok()?
+            .flatten()
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| file_identity(&entry.path().to_string_lossy()))
+            .collect();
+        files.sort();
+        let mut hasher = Sha256::new();
+        hasher.update(files.join("\n").as_bytes());
+        return Some(format!("{}|dir|{}", path, to_hex(&hasher.finalize())));
+    }
+    let modified = metadata.modified().ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    Some(format!("{}|{}|{}", path, metadata.len(), modified))
+}
+
+/// Input and output nodes are never cached: reading an input is free and an output must be written
+pub fn is_cacheable(node_type: &NodeType) -> bool {
+    !matches!(node_type, NodeType::InputFile | NodeType::OutputFile)
+}
+
+/// Cache key of every node in `order` (a topological order of `workflow`). A key covers the node
+/// type, its parameters, the identity of any absolute file path a parameter names and the keys of
+/// the nodes feeding each input port, so a change anywhere upstream changes every key below it.
+pub fn node_keys(
+    workflow: &AutomationWorkflow,
+    order: &[String],
+    identity: &dyn Fn(&str) -> Option<String>,
+) -> HashMap<String, String> {
+    let mut keys: HashMap<String, String> = HashMap::new();
+    for node_id in order {
+        let Some(node) = workflow.nodes.get(node_id) else {
+            continue;
+        };
+        let mut lines = vec![format!("type:{:?}", node.node_type), format!("enabled:{}", node.enabled)];
+
+        let parameters: BTreeMap<&String, &String> = node.parameters.iter()
+            .map(|(name, parameter)| (name, &parameter.value))
+            .collect();
+        for (name, value) in parameters {
+            lines.push(format!("param:{}={}", name, value));
+            if Path::new(value).is_absolute() {
+                lines.push(format!("file:{}", identity(value).unwrap_or_else(|| "missing".to_string())));
+            }
+        }
+
+        let mut inputs: Vec<(usize, String)> = workflow.connections.values()
+            .filter(|connection| connection.to_node == *node_id)
+            .map(|connection| {
+                let upstream = keys.get(&connection.from_node).map(String::as_str).unwrap_or("unknown");
+                (connection.to_port, format!("in{}:{}:{}", connection.to_port, upstream, connection.from_port))
+            })
+            .collect();
+        inputs.sort();
+        lines.extend(inputs.into_iter().map(|(_, line)| line));
+
+        let mut hasher = Sha256::new();
+        hasher.update(lines.join("\n").as_bytes());
+        keys.insert(node_id.clone(), to_hex(&hasher.finalize()));
+    }
+    keys
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    files: Vec<String>,
+    bytes: u64,
+    /// Unix seconds of the last run that stored or reused the entry
+    last_used: i64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+/// Intermediate outputs of earlier runs of one workflow, by node cache key
+pub struct WorkflowCache {
+    dir: PathBuf,
+    index: CacheIndex,
+}
+
+impl WorkflowCache {
+    pub fn open(workflow_id: &str) -> Option<Self> {
+        let dir = cache_root()?.join(sanitize(workflow_id));
+        let index = std::fs::read_to_string(dir.join("index.json")).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Some(Self { dir, index })
+    }
+
+    /// Cached outputs for `key` if every file is still there
+    pub fn lookup(&mut self, key: &str) -> Option<Vec<String>> {
+        let entry = self.index.entries.get_mut(key)?;
+        if !entry.files.iter().all(|file| Path::new(file).is_file()) {
+            self.index.entries.remove(key);
+            return None;
+        }
+        entry.last_used = chrono::Utc::now().timestamp();
+        Some(entry.files.clone())
+    }
+
+    /// Move a node's outputs into the cache and return their new paths. Only files inside
+    /// `temp_dir` are taken, so a node that passes its input through never moves the user's file.
+    pub fn store(&mut self, key: &str, outputs: &[String], temp_dir: &str) -> Option<Vec<String>> {
+        if outputs.is_empty() || !outputs.iter().all(|file| Path::new(file).is_file() && Path::new(file).starts_with(temp_dir)) {
+            return None;
+        }
+        let entry_dir = self.dir.join(key);
+        if let Err(e) = std::fs::create_dir_all(&entry_dir) {
+            log_warn!("Failed to create workflow cache directory {}: {}", entry_dir.display(), e);
+            return None;
+        }
+        let mut files = Vec::new();
+        let mut bytes = 0;
+        for output in outputs {
+            let source = Path::new(output);
+            let target = entry_dir.join(source.file_name()?);
+            // The temp directory may be on another file system than the config directory
+            let moved = std::fs::rename(source, &target).is_ok()
+                || std::fs::copy(source, &target).map(|_| std::fs::remove_file(source)).is_ok();
+            if !moved {
+                log_warn!("Failed to cache {}", output);
+                let _ = std::fs::remove_dir_all(&entry_dir);
+                return None;
+            }
+            bytes += std::fs::metadata(&target).map(|metadata| metadata.len()).unwrap_or(0);
+            files.push(target.display().to_string());
+        }
+        self.index.entries.insert(key.to_string(), CacheEntry { files: files.clone(), bytes, last_used: chrono::Utc::now().timestamp() });
+        Some(files)
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.index.entries.remove(key).is_some() {
+            let _ = std::fs::remove_dir_all(self.dir.join(key));
+            log_debug!("Evicted workflow cache entry {}", key);
+        }
+    }
+
+    pub fn save(&self) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&self.index).map_err(|e| e.to_string()))
+            .and_then(|json| std::fs::write(self.dir.join("index.json"), json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log_warn!("Failed to save workflow cache index: {}", e);
+        }
+    }
+}
+
+fn sanitize(workflow_id: &str) -> String {
+    workflow_id.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+/// Drop entries older than the age limit, then the least recently used ones until all workflow
+/// caches together fit the size limit
+pub fn collect_garbage() {
+    let Some(root) = cache_root() else {
+        return;
+    };
+    let Ok(workflow_dirs) = std::fs::read_dir(&root) else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp();
+    let mut caches: Vec<WorkflowCache> = workflow_dirs
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| WorkflowCache::open(&entry.file_name().to_string_lossy()))
+        .collect();
+
+    let mut live: Vec<(i64, u64, usize, String)> = Vec::new();
+    for (index, cache) in caches.iter_mut().enumerate() {
+        let expired: Vec<String> = cache.index.entries.iter()
+            .filter(|(_, entry)| now - entry.last_used > MAX_ENTRY_AGE_SECS)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            cache.remove(&key);
+        }
+        live.extend(cache.index.entries.iter().map(|(key, entry)| (entry.last_used, entry.bytes, index, key.clone())));
+    }
+
+    let mut total: u64 = live.iter().map(|(_, bytes, _, _)| bytes).sum();
+    live.sort();
+    for (_, bytes, index, key) in live {
+        if total <= MAX_CACHE_BYTES {
+            break;
+        }
+        caches[index].remove(&key);
+        total = total.saturating_sub(bytes);
+    }
+
+    for cache in &caches {
+        if cache.index.entries.is_empty() {
+            let _ = std::fs::remove_dir_all(&cache.dir);
+        } else {
+            cache.save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation_flow::{new_connection_id, AutomationNode, NodeConnection, WorkflowExecutor};
+
+    fn add(workflow: &mut AutomationWorkflow, id: &str, node_type: NodeType, parameters: &[(&str, &str)]) {
+        let mut node = AutomationNode::new(id.to_string(), node_type, egui::Pos2::ZERO);
+        for (name, value) in parameters {
+            node.parameters.get_mut(*name).unwrap().value = value.to_string();
+        }
+        workflow.add_node(node);
+    }
+
+    fn connect(workflow: &mut AutomationWorkflow, from: &str, to: &str) {
+        let data_type = workflow.nodes[from].output_ports[0].data_type.clone();
+        workflow.add_connection(NodeConnection {
+            id: new_connection_id(),
+            from_node: from.to_string(),
+            from_port: 0,
+            to_node: to.to_string(),
+            to_port: 0,
+            data_type,
+        }).unwrap();
+    }
+
+    /// input → resize → rotate → output, with a second branch input → volume → audio_out
+    fn workflow() -> AutomationWorkflow {
+        let mut workflow = AutomationWorkflow::new("cache test".to_string());
+        let input = std::env::temp_dir().join("ffgui_cache_test_input.mp4").display().to_string();
+        add(&mut workflow, "input", NodeType::InputFile, &[("file_path", &input)]);
+        add(&mut workflow, "resize", NodeType::VideoResize, &[("width", "1920"), ("height", "1080")]);
+        add(&mut workflow, "rotate", NodeType::VideoRotate, &[("rotation", "90")]);
+        add(&mut workflow, "output", NodeType::OutputFile, &[("output_path", "/no/such/out.mp4")]);
+        add(&mut workflow, "volume", NodeType::AudioVolume, &[]);
+        add(&mut workflow, "audio_out", NodeType::OutputFile, &[("output_path", "/no/such/out.m4a")]);
+        connect(&mut workflow, "input", "resize");
+        connect(&mut workflow, "resize", "rotate");
+        connect(&mut workflow, "rotate", "output");
+        connect(&mut workflow, "input", "volume");
+        connect(&mut workflow, "volume", "audio_out");
+        workflow
+    }
+
+    fn keys(workflow: &AutomationWorkflow, identity: &dyn Fn(&str) -> Option<String>) -> HashMap<String, String> {
+        node_keys(workflow, &workflow.get_execution_order().unwrap(), identity)
+    }
+
+    fn fixed_identity(path: &str) -> Option<String> {
+        Some(format!("{}|1000|1", path))
+    }
+
+    fn set(workflow: &mut AutomationWorkflow, node: &str, parameter: &str, value: &str) {
+        workflow.nodes.get_mut(node).unwrap().parameters.get_mut(parameter).unwrap().value = value.to_string();
+    }
+
+    fn changed(before: &HashMap<String, String>, after: &HashMap<String, String>) -> Vec<String> {
+        let mut changed: Vec<String> = before.keys().filter(|id| before[*id] != after[*id]).cloned().collect();
+        changed.sort();
+        changed
+    }
+
+    #[test]
+    fn keys_are_stable_for_an_unchanged_workflow() {
+        let workflow = workflow();
+        let first = keys(&workflow, &fixed_identity);
+        assert_eq!(first.len(), 6);
+        assert_eq!(first, keys(&workflow, &fixed_identity));
+        assert_eq!(first, keys(&workflow.clone(), &fixed_identity));
+    }
+
+    #[test]
+    fn an_upstream_edit_changes_every_key_below_it_and_no_other() {
+        let mut workflow = workflow();
+        let before = keys(&workflow, &fixed_identity);
+        set(&mut workflow, "resize", "width", "1280");
+        assert_eq!(changed(&before, &keys(&workflow, &fixed_identity)), ["output", "resize", "rotate"]);
+
+        let before = keys(&workflow, &fixed_identity);
+        set(&mut workflow, "rotate", "rotation", "180");
+        assert_eq!(changed(&before, &keys(&workflow, &fixed_identity)), ["output", "rotate"]);
+
+        let before = keys(&workflow, &fixed_identity);
+        workflow.nodes.get_mut("volume").unwrap().enabled = false;
+        assert_eq!(changed(&before, &keys(&workflow, &fixed_identity)), ["audio_out", "volume"]);
+    }
+
+    #[test]
+    fn a_changed_input_file_changes_every_key() {
+        let workflow = workflow();
+        let before = keys(&workflow, &fixed_identity);
+        let touched = keys(&workflow, &|path| Some(format!("{}|1000|2", path)));
+        assert_eq!(changed(&before, &touched), ["audio_out", "input", "output", "resize", "rotate", "volume"]);
+        let missing = keys(&workflow, &|_| None);
+        assert_eq!(changed(&before, &missing).len(), 6);
+    }
+
+    #[test]
+    fn rewiring_changes_the_keys_below_the_new_connection() {
+        let mut workflow = workflow();
+        let before = keys(&workflow, &fixed_identity);
+        // The rotation now reads the input directly, skipping the resize
+        let connection = workflow.connections.values()
+            .find(|connection| connection.from_node == "resize" && connection.to_node == "rotate")
+            .unwrap().id.clone();
+        workflow.remove_connection(&connection);
+        connect(&mut workflow, "input", "rotate");
+        assert_eq!(changed(&before, &keys(&workflow, &fixed_identity)), ["output", "rotate"]);
+    }
+
+    #[test]
+    fn cached_artifacts_below_an_edit_are_not_reused() {
+        let root = std::env::temp_dir().join(format!("ffgui_cache_{}", uuid::Uuid::new_v4()));
+        let temp_dir = root.join("run");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let mut cache = WorkflowCache { dir: root.join("cache"), index: CacheIndex::default() };
+
+        let mut workflow = workflow();
+        let first = keys(&workflow, &fixed_identity);
+        let cacheable: Vec<&str> = ["input", "resize", "rotate", "output", "volume", "audio_out"].into_iter()
+            .filter(|id| is_cacheable(&workflow.nodes[*id].node_type))
+            .collect();
+        assert_eq!(cacheable, ["resize", "rotate", "volume"]);
+        for id in &cacheable {
+            let artifact = temp_dir.join(format!("{}.mp4", id));
+            std::fs::write(&artifact, id.as_bytes()).unwrap();
+            let stored = cache.store(&first[*id], &[artifact.display().to_string()], &temp_dir.display().to_string()).unwrap();
+            assert!(stored[0].starts_with(&cache.dir.display().to_string()));
+            assert!(!artifact.exists());
+        }
+
+        set(&mut workflow, "resize", "height", "720");
+        let second = keys(&workflow, &fixed_identity);
+        let reused: Vec<&str> = cacheable.iter().copied().filter(|id| cache.lookup(&second[*id]).is_some()).collect();
+        assert_eq!(reused, ["volume"]);
+        // The first run's entries are still there for an undo of the edit
+        assert!(cache.lookup(&first["rotate"]).is_some());
+
+        // An artifact deleted behind the cache's back is a miss and drops the entry
+        let volume = cache.lookup(&second["volume"]).unwrap();
+        std::fs::remove_file(&volume[0]).unwrap();
+        assert!(cache.lookup(&second["volume"]).is_none());
+        assert!(!cache.index.entries.contains_key(&second["volume"]));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    /// Commands without the run-wide counter in temp file names (`..._id_<n>.ext`)
+    fn without_temp_ids(commands: &[String]) -> Vec<String> {
+        commands.iter().map(|command| {
+            let mut out = String::new();
+            let mut rest = command.as_str();
+            while let Some(at) = rest.find("_id_") {
+                out.push_str(&rest[..at]);
+                rest = rest[at + "_id_".len()..].trim_start_matches(|c: char| c.is_ascii_digit());
+            }
+            out + rest
+        }).collect()
+    }
+
+    #[test]
+    fn dry_run_steps_below_an_edit_get_new_commands_and_keys() {
+        let executor = WorkflowExecutor::new();
+        let mut workflow = workflow();
+        let before_steps = executor.preview_workflow(&workflow).unwrap();
+        let before_keys = keys(&workflow.flattened(), &fixed_identity);
+        set(&mut workflow, "resize", "width", "640");
+        let after_steps = executor.preview_workflow(&workflow).unwrap();
+        let after_keys = keys(&workflow.flattened(), &fixed_identity);
+
+        // A run caches each step under the key of its last node
+        assert_eq!(before_steps.len(), after_steps.len());
+        let mut affected = Vec::new();
+        for (before, after) in before_steps.iter().zip(&after_steps) {
+            assert_eq!(before.nodes, after.nodes);
+            let tail = before.nodes.last().unwrap();
+            let key_changed = before_keys[tail] != after_keys[tail];
+            let below_edit = before.nodes.iter().any(|id| ["resize", "rotate", "output"].contains(&id.as_str()));
+            assert_eq!(key_changed, below_edit, "step {:?}", before.nodes);
+            if without_temp_ids(&before.commands) != without_temp_ids(&after.commands) {
+                affected.extend(before.nodes.iter().cloned());
+                assert!(key_changed, "step {:?} runs another command under the same key", before.nodes);
+            }
+        }
+        assert!(affected.iter().any(|id| id == "resize"), "the edited node's command did not change: {:?}", after_steps);
+    }
+}
@@ -0,0 +1,595 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use eframe::egui;
+
+use crate::automation_flow::{AutomationNode, AutomationWorkflow, NodeType};
+use crate::language::Translations;
+
+/// Nodes with regenerated ids are paired by type when they sit at most this far apart
+const MATCH_DISTANCE: f32 = 150.0;
+/// Moves shorter than this are layout noise, not changes
+const MOVE_THRESHOLD: f32 = 0.5;
+
+const ADDED_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 200, 110);
+const REMOVED_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 80, 80);
+const CHANGED_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 200, 60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn color(self) -> egui::Color32 {
+        match self {
+            ChangeKind::Added => ADDED_COLOR,
+            ChangeKind::Removed => REMOVED_COLOR,
+            ChangeKind::Changed => CHANGED_COLOR,
+        }
+    }
+}
+
+/// A parameter whose value differs; None where the node has no such parameter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeDiff {
+    pub kind: ChangeKind,
+    pub node_type: NodeType,
+    /// Id in the old workflow; None for added nodes
+    pub old_id: Option<String>,
+    /// Id in the new workflow; None for removed nodes
+    pub new_id: Option<String>,
+    /// Paired by type and position because the ids differ
+    pub matched_by_position: bool,
+    pub parameters: Vec<ParameterChange>,
+    pub enabled: Option<(bool, bool)>,
+    pub moved: Option<(egui::Pos2, egui::Pos2)>,
+    /// Where a removed node was, for drawing its outline on the canvas
+    pub old_rect: Option<egui::Rect>,
+}
+
+/// A connection present in only one of the workflows; node ids are those of the new workflow
+/// where the node was matched, otherwise of the old one
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConnectionDiff {
+    pub added: bool,
+    pub from_node: String,
+    pub from_port: usize,
+    pub to_node: String,
+    pub to_port: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkflowDiff {
+    pub name: Option<(String, String)>,
+    pub description: Option<(String, String)>,
+    pub nodes: Vec<NodeDiff>,
+    pub connections: Vec<ConnectionDiff>,
+}
+
+impl WorkflowDiff {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.nodes.is_empty() && self.connections.is_empty()
+    }
+
+    /// Outline colour of a node of the new workflow; nodes whose wiring changed count as changed
+    pub fn highlight(&self, new_id: &str) -> Option<egui::Color32> {
+        if let Some(node) = self.nodes.iter().find(|node| node.new_id.as_deref() == Some(new_id)) {
+            return Some(node.kind.color());
+        }
+        self.connections.iter()
+            .any(|connection| connection.from_node == new_id || connection.to_node == new_id)
+            .then_some(CHANGED_COLOR)
+    }
+}
+
+/// Pair old and new nodes: same id and type first, then unmatched nodes of the same type by
+/// distance, closest pairs first. Returns old id → new id.
+fn match_nodes(old: &AutomationWorkflow, new: &AutomationWorkflow) -> (HashMap<String, String>, HashSet<String>) {
+    let mut matched: HashMap<String, String> = old.nodes.iter()
+        .filter(|(id, node)| new.nodes.get(*id).is_some_and(|other| other.node_type == node.node_type))
+        .map(|(id, _)| (id.clone(), id.clone()))
+        .collect();
+    let matched_new: HashSet<String> = matched.values().cloned().collect();
+
+    let unmatched_old: Vec<&AutomationNode> = old.nodes.values().filter(|node| !matched.contains_key(&node.id)).collect();
+    let unmatched_new: Vec<&AutomationNode> = new.nodes.values().filter(|node| !matched_new.contains(&node.id)).collect();
+    let mut pairs: Vec<(f32, &str, &str)> = Vec::new();
+    for old_node in &unmatched_old {
+        for new_node in unmatched_new.iter().filter(|node| node.node_type == old_node.node_type) {
+            // A node that is the only one of its type on both sides is the same node wherever it moved
+            let alone = unmatched_old.iter().filter(|node| node.node_type == old_node.node_type).count() == 1
+                && unmatched_new.iter().filter(|node| node.node_type == old_node.node_type).count() == 1;
+            let distance = old_node.position.distance(new_node.position);
+            if alone || distance <= MATCH_DISTANCE {
+                pairs.push((distance, &old_node.id, &new_node.id));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(b.1)).then_with(|| a.2.cmp(b.2)));
+
+    let mut by_position = HashSet::new();
+    let mut taken_new: HashSet<&str> = HashSet::new();
+    for (_, old_id, new_id) in pairs {
+        if matched.contains_key(old_id) || taken_new.contains(new_id) {
+            continue;
+        }
+        matched.insert(old_id.to_string(), new_id.to_string());
+        taken_new.insert(new_id);
+        by_position.insert(old_id.to_string());
+    }
+    (matched, by_position)
+}
+
+fn parameter_changes(old: &AutomationNode, new: &AutomationNode) -> Vec<ParameterChange> {
+    let names: BTreeSet<&String> = old.parameters.keys().chain(new.parameters.keys()).collect();
+    names.into_iter()
+        .filter_map(|name| {
+            let old_value = old.parameters.get(name).map(|parameter| parameter.value.clone());
+            let new_value = new.parameters.get(name).map(|parameter| parameter.value.clone());
+            (old_value != new_value).then(|| ParameterChange { name: name.clone(), old: old_value, new: new_value })
+        })
+        .collect()
+}
+
+/// Structural difference from `old` to `new`. Pure position changes are ignored unless
+/// `include_positions`.
+pub fn diff(old: &AutomationWorkflow, new: &AutomationWorkflow, include_positions: bool) -> WorkflowDiff {
+    let (matched, by_position) = match_nodes(old, new);
+    let matched_new: HashSet<&String> = matched.values().collect();
+    let mut nodes = Vec::new();
+
+    for (old_id, new_id) in &matched {
+        let (old_node, new_node) = (&old.nodes[old_id], &new.nodes[new_id]);
+        let parameters = parameter_changes(old_node, new_node);
+        let enabled = (old_node.enabled != new_node.enabled).then_some((old_node.enabled, new_node.enabled));
+        let moved = (include_positions && old_node.position.distance(new_node.position) > MOVE_THRESHOLD)
+            .then_some((old_node.position, new_node.position));
+        if parameters.is_empty() && enabled.is_none() && moved.is_none() {
+            continue;
+        }
+        nodes.push(NodeDiff {
+            kind: ChangeKind::Changed,
+            node_type: new_node.node_type.clone(),
+            old_id: Some(old_id.clone()),
+            new_id: Some(new_id.clone()),
+            matched_by_position: by_position.contains(old_id),
+            parameters,
+            enabled,
+            moved,
+            old_rect: None,
+        });
+    }
+    for node in old.nodes.values().filter(|node| !matched.contains_key(&node.id)) {
+        nodes.push(NodeDiff {
+            kind: ChangeKind::Removed,
+            node_type: node.node_type.clone(),
+            old_id: Some(node.id.clone()),
+            new_id: None,
+            matched_by_position: false,
+            parameters: Vec::new(),
+            enabled: None,
+            moved: None,
+            old_rect: Some(egui::Rect::from_min_size(node.position, node.size)),
+        });
+    }
+    for node in new.nodes.values().filter(|node| !matched_new.contains(&node.id)) {
+        nodes.push(NodeDiff {
+            kind: ChangeKind::Added,
+            node_type: node.node_type.clone(),
+            old_id: None,
+            new_id: Some(node.id.clone()),
+            matched_by_position: false,
+            parameters: Vec::new(),
+            enabled: None,
+            moved: None,
+            old_rect: None,
+        });
+    }
+    // Stable order for display: removed, added, changed, each by id
+    nodes.sort_by_key(|node| (node.kind != ChangeKind::Removed, node.kind != ChangeKind::Added, node.new_id.clone().or(node.old_id.clone())));
+
+    // Connections are compared by endpoints after translating old node ids
+    let translate = |id: &String| matched.get(id).cloned().unwrap_or_else(|| id.clone());
+    let old_connections: BTreeSet<(String, usize, String, usize)> = old.connections.values()
+        .map(|connection| (translate(&connection.from_node), connection.from_port, translate(&connection.to_node), connection.to_port))
+        .collect();
+    let new_connections: BTreeSet<(String, usize, String, usize)> = new.connections.values()
+        .map(|connection| (connection.from_node.clone(), connection.from_port, connection.to_node.clone(), connection.to_port))
+        .collect();
+    let to_diff = |added: bool| move |(from_node, from_port, to_node, to_port): &(String, usize, String, usize)| ConnectionDiff {
+        added,
+        from_node: from_node.clone(),
+        from_port: *from_port,
+        to_node: to_node.clone(),
+        to_port: *to_port,
+    };
+    let mut connections: Vec<ConnectionDiff> = old_connections.difference(&new_connections).map(to_diff(false))
+        .chain(new_connections.difference(&old_connections).map(to_diff(true)))
+        .collect();
+    connections.sort();
+
+    WorkflowDiff {
+        name: (old.name != new.name).then(|| (old.name.clone(), new.name.clone())),
+        description: (old.description != new.description).then(|| (old.description.clone(), new.description.clone())),
+        nodes,
+        connections,
+    }
+}
+
+/// Load a workflow file for comparison
+pub fn load(path: &std::path::Path) -> Result<AutomationWorkflow, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    crate::workflow_file::parse(&content).map(|(workflow, _)| workflow).map_err(|e| e.to_string())
+}
+
+/// Comparison window. Against the saved file the new side is the live editor, so the list and the
+/// canvas outlines follow edits; two files are compared as loaded.
+pub struct DiffWindow {
+    old: AutomationWorkflow,
+    old_label: String,
+    /// Second file; None compares against the editor
+    new: Option<(AutomationWorkflow, String)>,
+    include_positions: bool,
+    highlight: bool,
+    diff: WorkflowDiff,
+}
+
+impl DiffWindow {
+    pub fn with_saved(saved: AutomationWorkflow, label: String) -> Self {
+        Self { old: saved, old_label: label, new: None, include_positions: false, highlight: true, diff: WorkflowDiff::default() }
+    }
+
+    pub fn with_files(old: (AutomationWorkflow, String), new: (AutomationWorkflow, String)) -> Self {
+        Self { old: old.0, old_label: old.1, new: Some(new), include_positions: false, highlight: false, diff: WorkflowDiff::default() }
+    }
+
+    /// The diff to outline on the editor's canvas, if the window compares against the editor
+    pub fn canvas_highlight(&self) -> Option<&WorkflowDiff> {
+        (self.new.is_none() && self.highlight).then_some(&self.diff)
+    }
+
+    /// Returns false once closed
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations, editor_workflow: &AutomationWorkflow) -> bool {
+        let (new, new_label) = match &self.new {
+            Some((workflow, label)) => (workflow, label.clone()),
+            None => (editor_workflow, translations.workflow_diff_editor().to_string()),
+        };
+        self.diff = diff(&self.old, new, self.include_positions);
+
+        let mut open = true;
+        egui::Window::new(translations.workflow_diff_title())
+            .open(&mut open)
+            .resizable(true)
+            .default_size([520.0, 420.0])
+            .show(ctx, |ui| {
+                ui.label(format!("{} → {}", self.old_label, new_label));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.include_positions, translations.workflow_diff_positions());
+                    ui.add_enabled_ui(self.new.is_none(), |ui| {
+                        ui.checkbox(&mut self.highlight, translations.workflow_diff_highlight());
+                    });
+                });
+                ui.separator();
+                if self.diff.is_empty() {
+                    ui.weak(translations.workflow_diff_identical());
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    show_diff(ui, &self.diff, &self.old, new, translations);
+                });
+            });
+        open
+    }
+}
+
+fn node_label(workflow: &AutomationWorkflow, id: &str, translations: &Translations) -> String {
+    match workflow.nodes.get(id) {
+        Some(node) => format!("{} ({})", translations.node_type_name(&node.node_type), short_id(id)),
+        None => short_id(id).to_string(),
+    }
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+fn value_text(value: &Option<String>, translations: &Translations) -> String {
+    match value {
+        Some(value) if value.is_empty() => "\"\"".to_string(),
+        Some(value) => value.clone(),
+        None => translations.workflow_diff_absent().to_string(),
+    }
+}
+
+fn show_diff(ui: &mut egui::Ui, diff: &WorkflowDiff, old: &AutomationWorkflow, new: &AutomationWorkflow, translations: &Translations) {
+    if let Some((before, after)) = &diff.name {
+        ui.label(format!("{}: {} → {}", translations.workflow_diff_name(), before, after));
+    }
+    if diff.description.is_some() {
+        ui.label(translations.workflow_diff_description());
+    }
+
+    for (kind, heading) in [
+        (ChangeKind::Removed, translations.workflow_diff_removed()),
+        (ChangeKind::Added, translations.workflow_diff_added()),
+        (ChangeKind::Changed, translations.workflow_diff_changed()),
+    ] {
+        let nodes: Vec<&NodeDiff> = diff.nodes.iter().filter(|node| node.kind == kind).collect();
+        if nodes.is_empty() {
+            continue;
+        }
+        ui.add_space(4.0);
+        ui.colored_label(kind.color(), format!("{} ({})", heading, nodes.len()));
+        for node in nodes {
+            let title = format!("{} ({})", translations.node_type_name(&node.node_type),
+                short_id(node.new_id.as_deref().or(node.old_id.as_deref()).unwrap_or_default()));
+            if kind != ChangeKind::Changed {
+                ui.label(format!("  {}", title));
+                continue;
+            }
+            ui.collapsing(title, |ui| {
+                if node.matched_by_position {
+                    ui.weak(translations.workflow_diff_matched_by_position());
+                }
+                if let Some((before, after)) = node.enabled {
+                    ui.label(format!("{}: {} → {}", translations.workflow_diff_enabled(), before, after));
+                }
+                if let Some((before, after)) = node.moved {
+                    ui.label(format!("{}: ({:.0}, {:.0}) → ({:.0}, {:.0})", translations.workflow_diff_moved(), before.x, before.y, after.x, after.y));
+                }
+                for change in &node.parameters {
+                    ui.label(format!("{}: {} → {}", change.name, value_text(&change.old, translations), value_text(&change.new, translations)));
+                }
+            });
+        }
+    }
+
+    if !diff.connections.is_empty() {
+        ui.add_space(4.0);
+        ui.strong(format!("{} ({})", translations.workflow_diff_connections(), diff.connections.len()));
+        for connection in &diff.connections {
+            let (sign, color) = if connection.added { ("+", ADDED_COLOR) } else { ("−", REMOVED_COLOR) };
+            // Endpoints of a removed connection may only exist in the old workflow
+            let label = |id: &str| if new.nodes.contains_key(id) { node_label(new, id, translations) } else { node_label(old, id, translations) };
+            ui.colored_label(color, format!("{} {}:{} → {}:{}", sign, label(&connection.from_node), connection.from_port, label(&connection.to_node), connection.to_port));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation_flow::{DataType, NodeConnection};
+
+    fn add(workflow: &mut AutomationWorkflow, id: &str, node_type: NodeType, x: f32) {
+        workflow.add_node(AutomationNode::new(id.to_string(), node_type, egui::pos2(x, 100.0)));
+    }
+
+    fn connect(workflow: &mut AutomationWorkflow, id: &str, from: &str, to: &str) {
+        workflow.connections.insert(id.to_string(), NodeConnection {
+            id: id.to_string(),
+            from_node: from.to_string(),
+            from_port: 0,
+            to_node: to.to_string(),
+            to_port: 0,
+            data_type: DataType::VideoStream,
+        });
+    }
+
+    fn set(workflow: &mut AutomationWorkflow, id: &str, name: &str, value: &str) {
+        workflow.nodes.get_mut(id).unwrap().parameters.get_mut(name).unwrap().value = value.to_string();
+    }
+
+    /// input → resize → rotate → output, left to right
+    fn chain() -> AutomationWorkflow {
+        let mut workflow = AutomationWorkflow::new("diff test".to_string());
+        add(&mut workflow, "input", NodeType::InputFile, 0.0);
+        add(&mut workflow, "resize", NodeType::VideoResize, 200.0);
+        add(&mut workflow, "rotate", NodeType::VideoRotate, 400.0);
+        add(&mut workflow, "output", NodeType::OutputFile, 600.0);
+        connect(&mut workflow, "c1", "input", "resize");
+        connect(&mut workflow, "c2", "resize", "rotate");
+        connect(&mut workflow, "c3", "rotate", "output");
+        workflow
+    }
+
+    /// The same node under a new id, as after deleting and re-adding it or pasting a copy
+    fn reid(workflow: &mut AutomationWorkflow, old_id: &str, new_id: &str) {
+        let mut node = workflow.nodes.remove(old_id).unwrap();
+        node.id = new_id.to_string();
+        workflow.nodes.insert(new_id.to_string(), node);
+        for connection in workflow.connections.values_mut() {
+            if connection.from_node == old_id {
+                connection.from_node = new_id.to_string();
+            }
+            if connection.to_node == old_id {
+                connection.to_node = new_id.to_string();
+            }
+        }
+    }
+
+    fn connection(added: bool, from: &str, to: &str) -> ConnectionDiff {
+        ConnectionDiff { added, from_node: from.to_string(), from_port: 0, to_node: to.to_string(), to_port: 0 }
+    }
+
+    #[test]
+    fn identical_workflows_have_no_diff() {
+        let old = chain();
+        let mut new = old.clone();
+        new.id = "another id".to_string();
+        new.modified_at = "later".to_string();
+        assert!(diff(&old, &new, true).is_empty());
+    }
+
+    #[test]
+    fn renaming_the_workflow_is_reported_without_touching_nodes() {
+        let old = chain();
+        let mut new = old.clone();
+        new.name = "renamed".to_string();
+        new.description = "now described".to_string();
+
+        let changes = diff(&old, &new, false);
+        assert_eq!(changes.name, Some(("diff test".to_string(), "renamed".to_string())));
+        assert_eq!(changes.description, Some((String::new(), "now described".to_string())));
+        assert!(changes.nodes.is_empty() && changes.connections.is_empty());
+    }
+
+    #[test]
+    fn regenerated_ids_are_matched_by_type_and_position() {
+        let old = chain();
+        let mut new = old.clone();
+        reid(&mut new, "resize", "resize-copy");
+        // Only rotate node on both sides: matched however far it moved
+        reid(&mut new, "rotate", "rotate-copy");
+        new.nodes.get_mut("rotate-copy").unwrap().position = egui::pos2(2000.0, 900.0);
+
+        let changes = diff(&old, &new, false);
+        assert!(changes.is_empty(), "a renamed id alone is not a change: {changes:?}");
+
+        set(&mut new, "resize-copy", "width", "640");
+        let changes = diff(&old, &new, false);
+        assert_eq!(changes.nodes.len(), 1);
+        let node = &changes.nodes[0];
+        assert_eq!((node.kind, node.old_id.as_deref(), node.new_id.as_deref()), (ChangeKind::Changed, Some("resize"), Some("resize-copy")));
+        assert!(node.matched_by_position);
+        assert!(changes.connections.is_empty(), "wiring is compared through the matched ids");
+
+        // With positions included, the far move shows up on the node matched by being alone
+        let changes = diff(&old, &new, true);
+        let rotate = changes.nodes.iter().find(|node| node.new_id.as_deref() == Some("rotate-copy")).unwrap();
+        assert_eq!(rotate.moved, Some((egui::pos2(400.0, 100.0), egui::pos2(2000.0, 900.0))));
+        assert!(rotate.matched_by_position);
+    }
+
+    #[test]
+    fn same_type_nodes_too_far_apart_are_removed_and_added() {
+        let mut old = chain();
+        add(&mut old, "resize2", NodeType::VideoResize, 200.0 + 4.0 * MATCH_DISTANCE);
+        let mut new = old.clone();
+        // Both resize nodes get new ids; the one left in place still pairs up by position
+        reid(&mut new, "resize2", "resize2-new");
+        reid(&mut new, "resize", "resize-new");
+        new.nodes.get_mut("resize-new").unwrap().position = egui::pos2(200.0, 100.0 + 2.0 * MATCH_DISTANCE);
+
+        let changes = diff(&old, &new, false);
+        let summary: Vec<(ChangeKind, Option<&str>, Option<&str>)> = changes.nodes.iter()
+            .map(|node| (node.kind, node.old_id.as_deref(), node.new_id.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            (ChangeKind::Removed, Some("resize"), None),
+            (ChangeKind::Added, None, Some("resize-new")),
+        ]);
+        assert_eq!(changes.nodes[0].old_rect, Some(egui::Rect::from_min_size(egui::pos2(200.0, 100.0), old.nodes["resize"].size)));
+        // Connections of the unmatched node keep their old ids on the removed side
+        assert_eq!(changes.connections, vec![
+            connection(false, "input", "resize"),
+            connection(false, "resize", "rotate"),
+            connection(true, "input", "resize-new"),
+            connection(true, "resize-new", "rotate"),
+        ]);
+    }
+
+    #[test]
+    fn parameter_and_enabled_edits_are_listed_per_node() {
+        let old = chain();
+        let mut new = old.clone();
+        set(&mut new, "resize", "width", "1920");
+        set(&mut new, "rotate", "rotation", "");
+        new.nodes.get_mut("rotate").unwrap().enabled = false;
+        let removed = new.nodes.get_mut("output").unwrap().parameters.remove("output_path").unwrap();
+        // Layout noise below the threshold is never a move
+        new.nodes.get_mut("input").unwrap().position.x += MOVE_THRESHOLD / 2.0;
+
+        let changes = diff(&old, &new, true);
+        let by_id: HashMap<&str, &NodeDiff> = changes.nodes.iter().map(|node| (node.new_id.as_deref().unwrap(), node)).collect();
+        assert_eq!(by_id.len(), 3);
+        assert!(!by_id.contains_key("input"));
+
+        let width = &by_id["resize"].parameters;
+        assert_eq!(width.len(), 1);
+        assert_eq!(width[0].name, "width");
+        assert_eq!(width[0].new.as_deref(), Some("1920"));
+        assert_ne!(width[0].old, width[0].new);
+
+        let rotate = by_id["rotate"];
+        assert_eq!(rotate.enabled, Some((true, false)));
+        assert_eq!(rotate.parameters.iter().map(|change| change.new.as_deref()).collect::<Vec<_>>(), vec![Some("")]);
+        assert!(!rotate.matched_by_position);
+
+        assert_eq!(by_id["output"].parameters, vec![ParameterChange { name: "output_path".to_string(), old: Some(removed.value), new: None }]);
+        assert!(changes.nodes.iter().all(|node| node.kind == ChangeKind::Changed && node.moved.is_none()));
+    }
+
+    #[test]
+    fn moves_count_only_when_positions_are_included() {
+        let old = chain();
+        let mut new = old.clone();
+        new.nodes.get_mut("resize").unwrap().position = egui::pos2(250.0, 180.0);
+
+        assert!(diff(&old, &new, false).is_empty());
+        let changes = diff(&old, &new, true);
+        assert_eq!(changes.nodes.len(), 1);
+        assert_eq!(changes.nodes[0].moved, Some((egui::pos2(200.0, 100.0), egui::pos2(250.0, 180.0))));
+        assert!(changes.nodes[0].parameters.is_empty());
+    }
+
+    #[test]
+    fn rewired_connections_are_removed_and_added_and_outline_their_nodes() {
+        let old = chain();
+        let mut new = old.clone();
+        // Bypass rotate: resize now feeds the output directly, and a new node hangs off the input
+        new.connections.remove("c2");
+        new.connections.remove("c3");
+        connect(&mut new, "c4", "resize", "output");
+        add(&mut new, "crop", NodeType::VideoCrop, 200.0);
+        new.nodes.get_mut("crop").unwrap().position.y = 400.0;
+        connect(&mut new, "c5", "input", "crop");
+
+        let changes = diff(&old, &new, false);
+        assert_eq!(changes.connections, vec![
+            connection(false, "resize", "rotate"),
+            connection(false, "rotate", "output"),
+            connection(true, "input", "crop"),
+            connection(true, "resize", "output"),
+        ]);
+        assert_eq!(changes.nodes.len(), 1);
+        assert_eq!(changes.nodes[0].kind, ChangeKind::Added);
+
+        assert_eq!(changes.highlight("crop"), Some(ADDED_COLOR));
+        for rewired in ["input", "resize", "rotate", "output"] {
+            assert_eq!(changes.highlight(rewired), Some(CHANGED_COLOR), "{rewired}");
+        }
+        assert_eq!(diff(&old, &old, false).highlight("resize"), None);
+
+        // A connection moved to another port of the same nodes is rewired too
+        let mut new = old.clone();
+        new.connections.get_mut("c1").unwrap().to_port = 1;
+        let changes = diff(&old, &new, false);
+        assert_eq!(changes.connections.len(), 2);
+        assert!(changes.connections.iter().any(|connection| connection.added && connection.to_port == 1));
+    }
+
+    #[test]
+    fn removed_nodes_take_their_connections_with_them() {
+        let old = chain();
+        let mut new = old.clone();
+        new.remove_node("rotate");
+
+        let changes = diff(&old, &new, false);
+        assert_eq!(changes.nodes.len(), 1);
+        assert_eq!((changes.nodes[0].kind, changes.nodes[0].old_id.as_deref()), (ChangeKind::Removed, Some("rotate")));
+        assert_eq!(changes.connections, vec![connection(false, "resize", "rotate"), connection(false, "rotate", "output")]);
+        // Removed nodes are drawn from old_rect; nothing in the new workflow carries their id
+        assert_eq!(changes.highlight("rotate"), Some(CHANGED_COLOR));
+        assert_eq!(changes.highlight("input"), None);
+    }
+}
@@ -0,0 +1,555 @@
+use eframe::egui;
+use serde_json::{Map, Value};
+
+use crate::automation_flow::{
+    AutomationNode, AutomationWorkflow, NodeConnection, NodePort, NodeType, SerializablePos2, SerializableVec2,
+};
+use crate::language::Translations;
+
+/// Version written into saved workflows; files without one are version 0
+pub const FORMAT_VERSION: u64 = 2;
+
+/// Upgrades the JSON of a workflow file by one format version
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[v]` turns a version `v` file into version `v + 1`. A format change bumps
+/// FORMAT_VERSION and adds its step here; loading runs the steps from the file's version on.
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// VideoFPS parameters renamed when frame rate conversion got its modes, as (old, new)
+const RENAMED_FPS_PARAMETERS: [(&str, &str); 2] = [("fps", "target_fps"), ("filter", "mode")];
+/// VideoFilter parameters replaced by `filter_stack`
+const LEGACY_FILTER_PARAMETERS: [&str; 3] = ["brightness", "contrast", "saturation"];
+
+/// Version 0 files are the bare serialized workflow. Their VideoFPS nodes may still use `fps` and
+/// `filter`, and their VideoFilter nodes the brightness/contrast/saturation trio.
+fn migrate_v0_to_v1(root: &mut Map<String, Value>) {
+    for_each_node(root, &mut upgrade_legacy_parameters);
+}
+
+/// Version 1 files were saved by a build that added the current parameters at their defaults next
+/// to the legacy ones without converting them, so the legacy values are converted again here
+fn migrate_v1_to_v2(root: &mut Map<String, Value>) {
+    for_each_node(root, &mut upgrade_legacy_parameters);
+}
+
+/// Call `f` on every node of the file, including the nodes inside groups
+fn for_each_node(root: &mut Map<String, Value>, f: &mut dyn FnMut(&mut Map<String, Value>)) {
+    let Some(Value::Object(nodes)) = root.get_mut("nodes") else {
+        return;
+    };
+    for node in nodes.values_mut() {
+        let Value::Object(node) = node else {
+            continue;
+        };
+        f(node);
+        if let Some(Value::Object(group)) = node.get_mut("group") {
+            for_each_node(group, f);
+        }
+    }
+}
+
+/// Move legacy parameter values into the parameters that replaced them and drop the legacy ones.
+/// A replacement still at its default takes the legacy value; one that was edited is kept.
+fn upgrade_legacy_parameters(node: &mut Map<String, Value>) {
+    let node_type = node.get("node_type").and_then(Value::as_str).unwrap_or_default().to_string();
+    let Some(Value::Object(parameters)) = node.get_mut("parameters") else {
+        return;
+    };
+    match node_type.as_str() {
+        "VideoFPS" => {
+            for (old, new) in RENAMED_FPS_PARAMETERS {
+                let Some(value) = parameters.remove(old).as_ref().and_then(parameter_value) else {
+                    continue;
+                };
+                set_unless_edited(parameters, NodeType::VideoFPS, new, value);
+            }
+        }
+        "VideoFilter" => {
+            let legacy: Vec<Option<f32>> = LEGACY_FILTER_PARAMETERS.iter()
+                .map(|name| parameters.remove(*name).as_ref().and_then(parameter_value).and_then(|value| value.trim().parse().ok()))
+                .collect();
+            if legacy.iter().all(Option::is_none) {
+                return;
+            }
+            let settings = crate::app_state::VideoSettings {
+                brightness: legacy[0].unwrap_or(0.0),
+                contrast: legacy[1].unwrap_or(1.0),
+                saturation: legacy[2].unwrap_or(1.0),
+                ..Default::default()
+            };
+            let stack = serde_json::to_string(&crate::filter_stack::from_legacy(&settings)).unwrap_or_else(|_| "[]".to_string());
+            set_unless_edited(parameters, NodeType::VideoFilter, "filter_stack", stack);
+        }
+        _ => {}
+    }
+}
+
+fn parameter_value(parameter: &Value) -> Option<String> {
+    parameter.get("value").and_then(Value::as_str).map(str::to_string)
+}
+
+/// Set parameter `name` to `value`, adding it as a `node_type` default would be, unless its
+/// current value differs from its default
+fn set_unless_edited(parameters: &mut Map<String, Value>, node_type: NodeType, name: &str, value: String) {
+    let edited = parameters.get(name)
+        .is_some_and(|parameter| parameter.get("value") != parameter.get("default_value"));
+    if edited {
+        return;
+    }
+    let template = AutomationNode::new(String::new(), node_type, egui::Pos2::ZERO).parameters.remove(name);
+    let Some(Ok(Value::Object(mut parameter))) = template.map(serde_json::to_value) else {
+        return;
+    };
+    parameter.insert("value".to_string(), Value::String(value));
+    parameters.insert(name.to_string(), Value::Object(parameter));
+}
+
+/// Bring a file of format `version` up to FORMAT_VERSION
+fn migrate(root: &mut Map<String, Value>, version: u64) {
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log_debug!("Migrating workflow file from format {} to {}", from, from + 1);
+        migration(root);
+    }
+}
+
+/// Something skipped or kept as a placeholder while loading a workflow
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadIssue {
+    /// The file was saved by a newer version of the format
+    NewerFormat(u64),
+    /// Kept as an Unknown placeholder; its JSON is written back on save
+    UnknownNodeType { node_id: String, type_name: String },
+    /// A node of a known type whose fields could not be read, e.g. a parameter of an unknown
+    /// kind; kept as an Unknown placeholder like an unknown type
+    UnreadableNode { node_id: String, type_name: String, error: String },
+    InvalidNode { node_id: String, error: String },
+    InvalidConnection { connection_id: String, error: String },
+    /// Dropped because a node it connects does not exist
+    DanglingConnection { connection_id: String, node_id: String },
+}
+
+/// Why a file could not be loaded at all
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadError {
+    /// Not valid JSON, e.g. a truncated file
+    Syntax { line: usize, column: usize, message: String },
+    /// Valid JSON without the shape of a workflow
+    NotAWorkflow(String),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Syntax { line, column, message } => write!(f, "line {}, column {}: {}", line, column, message),
+            LoadError::NotAWorkflow(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Name of the node type stored in a node's JSON: a plain string, or the tag of an enum object
+fn type_name(node: &Value) -> Option<String> {
+    match node.get("node_type")? {
+        Value::String(name) => Some(name.clone()),
+        Value::Object(tagged) => tagged.keys().next().cloned(),
+        _ => None,
+    }
+}
+
+/// Placeholder for a node of a type this build does not know, keeping its position and ports
+fn unknown_node(node_id: &str, type_name: String, raw: &Value) -> AutomationNode {
+    let field = |key: &str| raw.get(key).cloned().unwrap_or(Value::Null);
+    let position = serde_json::from_value::<SerializablePos2>(field("position"))
+        .map(egui::Pos2::from)
+        .unwrap_or(egui::pos2(100.0, 100.0));
+    let mut node = AutomationNode::new(node_id.to_string(), NodeType::Unknown(type_name), position);
+    if let Ok(size) = serde_json::from_value::<SerializableVec2>(field("size")) {
+        node.size = size.into();
+    }
+    node.input_ports = serde_json::from_value::<Vec<NodePort>>(field("input_ports")).unwrap_or_default();
+    node.output_ports = serde_json::from_value::<Vec<NodePort>>(field("output_ports")).unwrap_or_default();
+    node.enabled = false;
+    node.raw_json = Some(raw.clone());
+    node
+}
+
+/// Parameters the node's type has gained since the file was saved, at their defaults, so they
+/// show up in the properties panel. A parameter whose legacy form is still present, as in a file
+/// of a newer format that was not migrated, is left out so the legacy fallbacks keep reading it.
+fn add_missing_parameters(node: &mut AutomationNode) {
+    if matches!(node.node_type, NodeType::Group | NodeType::Unknown(_)) {
+        return;
+    }
+    let has_legacy_form = |name: &str| match node.node_type {
+        NodeType::VideoFPS => RENAMED_FPS_PARAMETERS.iter().any(|(old, new)| *new == name && node.parameters.contains_key(*old)),
+        NodeType::VideoFilter => name == "filter_stack" && LEGACY_FILTER_PARAMETERS.iter().any(|old| node.parameters.contains_key(*old)),
+        _ => false,
+    };
+    let defaults = AutomationNode::new(node.id.clone(), node.node_type.clone(), node.position).parameters;
+    let missing: Vec<_> = defaults.into_iter()
+        .filter(|(name, _)| !node.parameters.contains_key(name) && !has_legacy_form(name))
+        .collect();
+    node.parameters.extend(missing);
+}
+
+/// Read a workflow file node by node after migrating it to the current format: nodes that cannot
+/// be read become placeholders keeping their JSON, nodes without a type and broken connections
+/// are skipped, all reported. Fails only when the file is not a readable workflow.
+pub fn parse(content: &str) -> Result<(AutomationWorkflow, Vec<LoadIssue>), LoadError> {
+    let root: Value = serde_json::from_str(content).map_err(|e| LoadError::Syntax {
+        line: e.line(),
+        column: e.column(),
+        // serde_json appends the location, which the dialog shows up front
+        message: e.to_string().split(" at line ").next().unwrap_or_default().to_string(),
+    })?;
+    let Value::Object(mut root) = root else {
+        return Err(LoadError::NotAWorkflow("the file does not contain a JSON object".to_string()));
+    };
+    if !matches!(root.get("nodes"), Some(Value::Object(_))) {
+        return Err(LoadError::NotAWorkflow("the file has no node list".to_string()));
+    }
+
+    let mut issues = Vec::new();
+    let version = root.get("format_version").and_then(Value::as_u64).unwrap_or(0);
+    if version > FORMAT_VERSION {
+        issues.push(LoadIssue::NewerFormat(version));
+    } else {
+        migrate(&mut root, version);
+    }
+    let Some(Value::Object(nodes)) = root.get("nodes") else {
+        return Err(LoadError::NotAWorkflow("the file has no node list".to_string()));
+    };
+
+    let text = |key: &str| root.get(key).and_then(Value::as_str).map(str::to_string);
+    let mut workflow = AutomationWorkflow::new(text("name").unwrap_or_else(|| "Workflow".to_string()));
+    if let Some(id) = text("id") {
+        workflow.id = id;
+    }
+    workflow.description = text("description").unwrap_or_default();
+    if let Some(created_at) = text("created_at") {
+        workflow.created_at = created_at;
+    }
+    if let Some(modified_at) = text("modified_at") {
+        workflow.modified_at = modified_at;
+    }
+
+    for (node_id, raw) in nodes {
+        match serde_json::from_value::<AutomationNode>(raw.clone()) {
+            Ok(mut node) => {
+                add_missing_parameters(&mut node);
+                workflow.nodes.insert(node_id.clone(), node);
+            }
+            Err(e) => {
+                let Some(type_name) = type_name(raw) else {
+                    issues.push(LoadIssue::InvalidNode { node_id: node_id.clone(), error: e.to_string() });
+                    continue;
+                };
+                let known_type = raw.get("node_type").is_some_and(|t| serde_json::from_value::<NodeType>(t.clone()).is_ok());
+                issues.push(if known_type {
+                    LoadIssue::UnreadableNode { node_id: node_id.clone(), type_name: type_name.clone(), error: e.to_string() }
+                } else {
+                    LoadIssue::UnknownNodeType { node_id: node_id.clone(), type_name: type_name.clone() }
+                });
+                workflow.nodes.insert(node_id.clone(), unknown_node(node_id, type_name, raw));
+            }
+        }
+    }
+
+    if let Some(Value::Object(connections)) = root.get("connections") {
+        for (connection_id, raw) in connections {
+            let connection = match serde_json::from_value::<NodeConnection>(raw.clone()) {
+                Ok(connection) => connection,
+                Err(e) => {
+                    issues.push(LoadIssue::InvalidConnection { connection_id: connection_id.clone(), error: e.to_string() });
+                    continue;
+                }
+            };
+            let missing = [&connection.from_node, &connection.to_node].into_iter()
+                .find(|node_id| !workflow.nodes.contains_key(*node_id));
+            match missing {
+                Some(node_id) => issues.push(LoadIssue::DanglingConnection { connection_id: connection_id.clone(), node_id: node_id.clone() }),
+                None => {
+                    workflow.connections.insert(connection_id.clone(), connection);
+                }
+            }
+        }
+    }
+
+    for issue in &issues {
+        log_warn!("Workflow load: {:?}", issue);
+    }
+    Ok((workflow, issues))
+}
+
+/// Workflow file contents with the format version; unknown nodes are written as they were loaded
+pub fn to_json(workflow: &AutomationWorkflow) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&to_value(workflow)?)
+}
+
+/// JSON value of a workflow file, as written by `to_json` and read back by `parse`
+pub fn to_value(workflow: &AutomationWorkflow) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(workflow)?;
+    if let Value::Object(root) = &mut value {
+        root.insert("format_version".to_string(), FORMAT_VERSION.into());
+        if let Some(Value::Object(nodes)) = root.get_mut("nodes") {
+            for (node_id, node) in &workflow.nodes {
+                let Some(mut raw) = node.raw_json.clone() else {
+                    continue;
+                };
+                // The placeholder can still be moved around the canvas
+                if let Value::Object(fields) = &mut raw {
+                    fields.insert("position".to_string(), serde_json::to_value(SerializablePos2::from(node.position))?);
+                }
+                nodes.insert(node_id.clone(), raw);
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Result of opening a workflow file, shown when it was not a clean load
+pub struct LoadDialog {
+    file_name: String,
+    outcome: Result<Vec<LoadIssue>, LoadError>,
+}
+
+impl LoadDialog {
+    pub fn new(file_name: String, outcome: Result<Vec<LoadIssue>, LoadError>) -> Self {
+        Self { file_name, outcome }
+    }
+
+    /// Returns false once closed
+    pub fn show(&self, ctx: &egui::Context, translations: &Translations) -> bool {
+        let mut open = true;
+        let mut close = false;
+        let title = match &self.outcome {
+            Ok(_) => translations.workflow_load_report_title(),
+            Err(_) => translations.workflow_load_error_title(),
+        };
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.strong(&self.file_name);
+                match &self.outcome {
+                    Ok(issues) => {
+                        ui.label(translations.workflow_load_report_intro());
+                        ui.separator();
+                        egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                            for issue in issues {
+                                let color = match issue {
+                                    LoadIssue::InvalidNode { .. } | LoadIssue::InvalidConnection { .. } => egui::Color32::from_rgb(255, 100, 100),
+                                    _ => egui::Color32::from_rgb(255, 200, 100),
+                                };
+                                ui.colored_label(color, translations.workflow_load_issue(issue));
+                            }
+                        });
+                    }
+                    Err(error) => {
+                        ui.label(translations.workflow_load_error_intro());
+                        ui.add(egui::Label::new(egui::RichText::new(error.to_string()).monospace()).wrap());
+                    }
+                }
+                ui.separator();
+                if ui.button(translations.workflow_load_close()).clicked() {
+                    close = true;
+                }
+            });
+        open && !close
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V0: &str = include_str!("../tests/fixtures/workflows/v0.json");
+    const V1: &str = include_str!("../tests/fixtures/workflows/v1.json");
+    const V2: &str = include_str!("../tests/fixtures/workflows/v2.json");
+
+    fn value<'a>(workflow: &'a AutomationWorkflow, node_id: &str, name: &str) -> Option<&'a str> {
+        workflow.nodes[node_id].parameters.get(name).map(|p| p.value.as_str())
+    }
+
+    /// Every historical version loads to the same workflow as the current fixture
+    fn assert_current(workflow: &AutomationWorkflow) {
+        assert_eq!(value(workflow, "fps", "target_fps"), Some("24"));
+        assert_eq!(value(workflow, "fps", "mode"), Some("minterpolate"));
+        assert_eq!(value(workflow, "fps", "fps"), None);
+        assert_eq!(value(workflow, "fps", "filter"), None);
+        let conversion = crate::automation_flow::fps_conversion_from_params(&workflow.nodes["fps"].parameters);
+        assert_eq!(conversion.target_fps, "24");
+        assert_eq!(conversion.mode, crate::fps_conversion::FpsMode::Minterpolate);
+
+        let stack: Vec<crate::filter_stack::FilterStackEntry> =
+            serde_json::from_str(value(workflow, "filter", "filter_stack").unwrap()).unwrap();
+        assert_eq!(stack, vec![crate::filter_stack::FilterStackEntry::new(crate::filter_stack::FilterStage::Eq {
+            brightness: 0.25,
+            contrast: 1.5,
+            saturation: 1.0,
+        })]);
+        for legacy in LEGACY_FILTER_PARAMETERS {
+            assert_eq!(value(workflow, "filter", legacy), None);
+        }
+        assert_eq!(workflow.connections.len(), 3);
+    }
+
+    #[test]
+    fn loads_every_format_version() {
+        for (name, content) in [("v0", V0), ("v1", V1), ("v2", V2)] {
+            let (workflow, issues) = parse(content).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert!(issues.is_empty(), "{}: {:?}", name, issues);
+            assert_current(&workflow);
+        }
+    }
+
+    #[test]
+    fn saved_files_reload_unchanged() {
+        for content in [V0, V1, V2] {
+            let (workflow, _) = parse(content).unwrap();
+            let saved = to_json(&workflow).unwrap();
+            assert!(saved.contains(&format!("\"format_version\": {}", FORMAT_VERSION)));
+            let (reloaded, issues) = parse(&saved).unwrap();
+            assert!(issues.is_empty());
+            assert_current(&reloaded);
+        }
+    }
+
+    #[test]
+    fn edited_replacements_win_over_legacy_values() {
+        let mut root: Value = serde_json::from_str(V1).unwrap();
+        root["nodes"]["fps"]["parameters"]["target_fps"]["value"] = "50".into();
+        let (workflow, _) = parse(&root.to_string()).unwrap();
+        assert_eq!(value(&workflow, "fps", "target_fps"), Some("50"));
+        // The unedited mode still takes the legacy filter
+        assert_eq!(value(&workflow, "fps", "mode"), Some("minterpolate"));
+    }
+
+    #[test]
+    fn newer_formats_keep_legacy_parameters_readable() {
+        let mut root: Value = serde_json::from_str(V0).unwrap();
+        root["format_version"] = (FORMAT_VERSION + 1).into();
+        let (workflow, issues) = parse(&root.to_string()).unwrap();
+        assert_eq!(issues, vec![LoadIssue::NewerFormat(FORMAT_VERSION + 1)]);
+        assert_eq!(value(&workflow, "fps", "target_fps"), None);
+        assert_eq!(value(&workflow, "filter", "filter_stack"), None);
+        let conversion = crate::automation_flow::fps_conversion_from_params(&workflow.nodes["fps"].parameters);
+        assert_eq!(conversion.target_fps, "24");
+        // Parameters without a legacy form are still added
+        assert_eq!(value(&workflow, "fps", "mi_mode"), Some("mci"));
+    }
+
+    /// V2 with a node of a type added by a later version, wired between the filter and the output
+    fn with_future_node() -> Value {
+        let mut root: Value = serde_json::from_str(V2).unwrap();
+        root["nodes"]["upscale"] = serde_json::json!({
+            "id": "upscale",
+            "node_type": "AiUpscale",
+            "position": { "x": 500.0, "y": 250.0 },
+            "size": { "x": 180.0, "y": 90.0 },
+            "input_ports": root["nodes"]["filter"]["input_ports"].clone(),
+            "output_ports": root["nodes"]["filter"]["output_ports"].clone(),
+            "parameters": { "model": { "kind": "ModelPicker", "value": "x4" } },
+            "enabled": true,
+            "added_in": "9.0",
+        });
+        let mut connection = root["connections"]["conn_3"].clone();
+        connection["id"] = "conn_4".into();
+        connection["to_node"] = "upscale".into();
+        root["connections"]["conn_4"] = connection;
+        root
+    }
+
+    #[test]
+    fn future_node_types_survive_a_round_trip() {
+        let root = with_future_node();
+        let (mut workflow, issues) = parse(&root.to_string()).unwrap();
+        assert_eq!(issues, vec![LoadIssue::UnknownNodeType { node_id: "upscale".to_string(), type_name: "AiUpscale".to_string() }]);
+        let mut known = workflow.clone();
+        known.connections.remove("conn_4");
+        assert_current(&known);
+
+        let placeholder = &workflow.nodes["upscale"];
+        assert_eq!(placeholder.node_type, NodeType::Unknown("AiUpscale".to_string()));
+        assert!(!placeholder.enabled);
+        assert_eq!(placeholder.position, egui::pos2(500.0, 250.0));
+        assert_eq!(placeholder.size, egui::vec2(180.0, 90.0));
+        assert_eq!(placeholder.input_ports.len(), workflow.nodes["filter"].input_ports.len());
+        // Its connection is kept since both ends exist
+        assert!(workflow.connections.contains_key("conn_4"));
+
+        // Saved back as it was read, apart from a move on the canvas
+        workflow.nodes.get_mut("upscale").unwrap().position = egui::pos2(520.0, 260.0);
+        let saved = to_value(&workflow).unwrap();
+        let mut expected = root["nodes"]["upscale"].clone();
+        expected["position"] = serde_json::json!({ "x": 520.0, "y": 260.0 });
+        assert_eq!(saved["nodes"]["upscale"], expected);
+
+        let (reloaded, issues) = parse(&to_json(&workflow).unwrap()).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(reloaded.nodes["upscale"].raw_json.as_ref(), Some(&expected));
+        assert_eq!(reloaded.connections.len(), 4);
+    }
+
+    #[test]
+    fn tagged_and_unreadable_nodes_become_placeholders() {
+        let mut root: Value = serde_json::from_str(V2).unwrap();
+        root["nodes"]["tagged"] = serde_json::json!({ "node_type": { "Plugin": { "name": "lut" } }, "position": "not a position" });
+        root["nodes"]["fps"]["parameters"]["target_fps"] = serde_json::json!({ "kind": "Hologram", "value": "24" });
+        root["nodes"]["untyped"] = serde_json::json!({ "id": "untyped", "position": { "x": 1.0, "y": 2.0 } });
+        let (workflow, issues) = parse(&root.to_string()).unwrap();
+
+        assert!(issues.contains(&LoadIssue::UnknownNodeType { node_id: "tagged".to_string(), type_name: "Plugin".to_string() }));
+        assert!(issues.iter().any(|issue| matches!(issue,
+            LoadIssue::UnreadableNode { node_id, type_name, .. } if node_id == "fps" && type_name == "VideoFPS")));
+        assert!(issues.iter().any(|issue| matches!(issue, LoadIssue::InvalidNode { node_id, .. } if node_id == "untyped")));
+        assert_eq!(issues.len(), 3);
+
+        assert_eq!(workflow.nodes["tagged"].position, egui::pos2(100.0, 100.0));
+        assert_eq!(workflow.nodes["fps"].node_type, NodeType::Unknown("VideoFPS".to_string()));
+        assert!(!workflow.nodes.contains_key("untyped"));
+        // The unreadable node keeps its connections and is written back untouched
+        assert_eq!(workflow.connections.len(), 3);
+        assert_eq!(to_value(&workflow).unwrap()["nodes"]["fps"]["parameters"], root["nodes"]["fps"]["parameters"]);
+    }
+
+    #[test]
+    fn broken_connections_are_skipped() {
+        let mut root: Value = serde_json::from_str(V2).unwrap();
+        root["nodes"].as_object_mut().unwrap().remove("output");
+        root["connections"]["conn_4"] = serde_json::json!({ "id": "conn_4", "from_node": "input" });
+        let (workflow, mut issues) = parse(&root.to_string()).unwrap();
+        issues.sort_by_key(|issue| format!("{:?}", issue));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0], LoadIssue::DanglingConnection { connection_id: "conn_3".to_string(), node_id: "output".to_string() });
+        assert!(matches!(&issues[1], LoadIssue::InvalidConnection { connection_id, .. } if connection_id == "conn_4"));
+        let mut kept: Vec<&String> = workflow.connections.keys().collect();
+        kept.sort();
+        assert_eq!(kept, ["conn_1", "conn_2"]);
+    }
+
+    #[test]
+    fn truncated_files_report_where_they_end() {
+        for cut in [V2.len() / 3, V2.len() / 2, V2.len() - 2] {
+            match parse(&V2[..cut]) {
+                Err(LoadError::Syntax { line, column, message }) => {
+                    assert_eq!(line, V2[..cut].split('\n').count(), "cut at {}", cut);
+                    assert_eq!(column, V2[..cut].rsplit('\n').next().unwrap().len());
+                    assert!(message.starts_with("EOF while parsing"), "{}", message);
+                    assert!(!message.contains(" at line "));
+                }
+                other => panic!("cut at {}: {:?}", cut, other.map(|(_, issues)| issues)),
+            }
+        }
+        assert!(matches!(parse(""), Err(LoadError::Syntax { line: 1, .. })));
+        assert_eq!(parse("[]").err(), Some(LoadError::NotAWorkflow("the file does not contain a JSON object".to_string())));
+        assert_eq!(parse(r#"{"name": "x"}"#).err(), Some(LoadError::NotAWorkflow("the file has no node list".to_string())));
+        assert_eq!(
+            LoadError::Syntax { line: 3, column: 7, message: "EOF while parsing an object".to_string() }.to_string(),
+            "line 3, column 7: EOF while parsing an object",
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use serde_json::Value;
+
+use crate::automation_flow::AutomationWorkflow;
+use crate::language::Translations;
+use crate::workflow_file;
+
+/// Size of the layout thumbnails in the library window
+const THUMBNAIL_SIZE: [usize; 2] = [120, 72];
+const THUMBNAIL_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(32, 32, 36);
+const THUMBNAIL_EDGE: egui::Color32 = egui::Color32::from_rgb(120, 120, 130);
+
+/// Managed folder of saved workflows, next to the preferences file
+pub fn library_dir() -> PathBuf {
+    crate::app_state::UserPreferences::file_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("workflows")))
+        .unwrap_or_else(|| std::env::temp_dir().join("ffmpeg_gui_workflows"))
+}
+
+/// Whether `path` is a file in the library folder
+pub fn is_in_library(path: &Path) -> bool {
+    path.parent().is_some_and(|parent| parent == library_dir())
+}
+
+/// What the library shows of a readable workflow file
+pub struct WorkflowSummary {
+    pub name: String,
+    pub description: String,
+    pub node_count: usize,
+    thumbnail: egui::ColorImage,
+}
+
+/// One `.json` file in the library folder
+pub struct LibraryEntry {
+    pub path: PathBuf,
+    pub modified: Option<chrono::DateTime<chrono::Local>>,
+    /// Why the file could not be read as a workflow; foreign and corrupted files stay listed
+    pub workflow: Result<WorkflowSummary, String>,
+    texture: Option<egui::TextureHandle>,
+}
+
+impl LibraryEntry {
+    pub fn file_name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    }
+}
+
+/// Every workflow file in `dir`, most recently modified first
+pub fn scan(dir: &Path) -> Vec<LibraryEntry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<LibraryEntry> = read_dir
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .map(|path| LibraryEntry {
+            modified: std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok().map(chrono::DateTime::from),
+            workflow: summarize(&path),
+            texture: None,
+            path,
+        })
+        .collect();
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+    entries
+}
+
+fn summarize(path: &Path) -> Result<WorkflowSummary, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let (workflow, _) = workflow_file::parse(&content).map_err(|e| e.to_string())?;
+    Ok(WorkflowSummary {
+        name: workflow.name.clone(),
+        description: workflow.description.clone(),
+        node_count: workflow.nodes.len(),
+        thumbnail: render_thumbnail(&workflow),
+    })
+}
+
+/// Rasterize the graph layout from node positions: connections as lines, nodes as boxes in their
+/// type colour, scaled to fit the thumbnail
+pub fn render_thumbnail(workflow: &AutomationWorkflow) -> egui::ColorImage {
+    let [width, height] = THUMBNAIL_SIZE;
+    let mut image = egui::ColorImage::new(THUMBNAIL_SIZE, THUMBNAIL_BACKGROUND);
+    let Some(bounds) = workflow.nodes.values()
+        .map(|node| egui::Rect::from_min_size(node.position, node.size))
+        .reduce(|a, b| a.union(b))
+    else {
+        return image;
+    };
+    let margin = 4.0;
+    let scale = ((width as f32 - margin * 2.0) / bounds.width().max(1.0))
+        .min((height as f32 - margin * 2.0) / bounds.height().max(1.0));
+    // Centre the scaled layout
+    let offset = egui::vec2(
+        (width as f32 - bounds.width() * scale) / 2.0,
+        (height as f32 - bounds.height() * scale) / 2.0,
+    );
+    let map = |pos: egui::Pos2| egui::pos2((pos.x - bounds.min.x) * scale + offset.x, (pos.y - bounds.min.y) * scale + offset.y);
+
+    for connection in workflow.connections.values() {
+        let (Some(from), Some(to)) = (workflow.nodes.get(&connection.from_node), workflow.nodes.get(&connection.to_node)) else {
+            continue;
+        };
+        let start = map(egui::pos2(from.position.x + from.size.x, from.position.y + from.size.y / 2.0));
+        let end = map(egui::pos2(to.position.x, to.position.y + to.size.y / 2.0));
+        let steps = (end - start).length().ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let point = start.lerp(end, step as f32 / steps as f32);
+            set_pixel(&mut image, point.x as i64, point.y as i64, THUMBNAIL_EDGE);
+        }
+    }
+    for node in workflow.nodes.values() {
+        let min = map(node.position);
+        let max = map(node.position + node.size);
+        let color = node.node_type.get_color();
+        for y in min.y.floor() as i64..=(max.y.ceil() as i64).max(min.y as i64 + 1) {
+            for x in min.x.floor() as i64..=(max.x.ceil() as i64).max(min.x as i64 + 1) {
+                set_pixel(&mut image, x, y, color);
+            }
+        }
+    }
+    image
+}
+
+fn set_pixel(image: &mut egui::ColorImage, x: i64, y: i64, color: egui::Color32) {
+    let [width, height] = image.size;
+    if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+        image.pixels[y as usize * width + x as usize] = color;
+    }
+}
+
+/// File name for a workflow name, without characters file systems reject
+fn file_stem_for(name: &str) -> String {
+    let stem: String = name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '(' | ')') { c } else { '_' })
+        .collect();
+    let stem = stem.trim_matches(|c: char| c == '.' || c.is_whitespace()).to_string();
+    if stem.is_empty() { "workflow".to_string() } else { stem }
+}
+
+/// Free path in `dir` for a workflow called `name`; `keep` is the workflow's own file, which may be reused
+pub(crate) fn unique_path(dir: &Path, name: &str, keep: Option<&Path>) -> PathBuf {
+    let stem = file_stem_for(name);
+    (1..)
+        .map(|n| if n == 1 { dir.join(format!("{}.json", stem)) } else { dir.join(format!("{} ({}).json", stem, n)) })
+        .find(|path| !path.exists() || keep == Some(path.as_path()))
+        .unwrap()
+}
+
+/// Write a workflow into the library. A workflow already saved there is updated in place, others
+/// get a new file named after the workflow.
+pub fn save(workflow: &AutomationWorkflow, current_path: Option<&Path>) -> Result<PathBuf, String> {
+    let dir = library_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = match current_path.filter(|path| is_in_library(path)) {
+        Some(path) => path.to_path_buf(),
+        None => unique_path(&dir, &workflow.name, None),
+    };
+    let json = workflow_file::to_json(workflow).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    log_info!("Workflow '{}' saved to library as {}", workflow.name, path.display());
+    Ok(path)
+}
+
+/// Edit a workflow file's JSON directly, so nodes this build cannot load survive, and write it to
+/// the path picked from the edited name
+fn rewrite(path: &Path, edit: impl FnOnce(&mut serde_json::Map<String, Value>), keep_file: bool) -> Result<PathBuf, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut root: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let Value::Object(fields) = &mut root else {
+        return Err("not a workflow".to_string());
+    };
+    edit(fields);
+    fields.insert("modified_at".to_string(), chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string().into());
+    let name = fields.get("name").and_then(Value::as_str).unwrap_or("workflow").to_string();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let target = unique_path(dir, &name, keep_file.then_some(path));
+    let json = serde_json::to_string_pretty(&root).map_err(|e| e.to_string())?;
+    std::fs::write(&target, json).map_err(|e| e.to_string())?;
+    Ok(target)
+}
+
+/// Give a library workflow a new name; its file is renamed to match. Returns the new path.
+pub fn rename(path: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("empty name".to_string());
+    }
+    let target = rewrite(path, |fields| {
+        fields.insert("name".to_string(), name.into());
+    }, true)?;
+    if target != path {
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    log_info!("Renamed workflow {} to '{}'", path.display(), name);
+    Ok(target)
+}
+
+/// Copy a library workflow under a new id and "<name> (<suffix>)"
+pub fn duplicate(path: &Path, suffix: &str) -> Result<PathBuf, String> {
+    let now = chrono::Utc::now();
+    let target = rewrite(path, |fields| {
+        let name = fields.get("name").and_then(Value::as_str).unwrap_or("workflow").to_string();
+        fields.insert("name".to_string(), format!("{} ({})", name, suffix).into());
+        fields.insert("id".to_string(), format!("wf_{}", now.timestamp_millis()).into());
+        fields.insert("created_at".to_string(), now.format("%Y-%m-%d %H:%M:%S").to_string().into());
+    }, false)?;
+    log_info!("Duplicated workflow {} as {}", path.display(), target.display());
+    Ok(target)
+}
+
+/// Show the file selected in the platform file manager
+pub fn reveal(path: &Path) {
+    let result = if cfg!(target_os = "windows") {
+        std::process::Command::new("explorer").arg(format!("/select,{}", path.display())).spawn()
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg("-R").arg(path).spawn()
+    } else {
+        // xdg-open cannot select a file; open its folder instead
+        std::process::Command::new("xdg-open").arg(path.parent().unwrap_or(Path::new("."))).spawn()
+    };
+    if let Err(e) = result {
+        log_warn!("Could not open the file manager for {}: {}", path.display(), e);
+    }
+}
+
+/// Open a folder in the platform file manager
+pub fn open_folder(dir: &Path) {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = std::process::Command::new(program).arg(dir).spawn() {
+        log_warn!("Could not open the file manager for {}: {}", dir.display(), e);
+    }
+}
+
+/// Result of a frame of the library window
+pub enum LibraryAction {
+    None,
+    Close,
+    Open(PathBuf),
+    /// A file moved; open tabs pointing at `from` follow it
+    Renamed { from: PathBuf, to: PathBuf, name: String },
+    Deleted(PathBuf),
+}
+
+/// What a library row asked for; file operations run after the list is drawn
+enum RowAction {
+    Open(PathBuf),
+    Rename(PathBuf, String),
+    Duplicate(PathBuf),
+    Delete(PathBuf),
+}
+
+/// Automation → Workflow Library
+pub struct LibraryWindow {
+    entries: Vec<LibraryEntry>,
+    /// Entry being renamed inline and the name typed so far
+    renaming: Option<(PathBuf, String)>,
+    /// Entry whose delete button was clicked once and awaits confirmation
+    confirm_delete: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl LibraryWindow {
+    pub fn new() -> Self {
+        Self { entries: scan(&library_dir()), renaming: None, confirm_delete: None, error: None }
+    }
+
+    pub fn refresh(&mut self) {
+        self.entries = scan(&library_dir());
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, translations: &Translations) -> LibraryAction {
+        let mut open = true;
+        let mut row_action = None;
+        let mut refresh = false;
+        egui::Window::new(translations.workflow_library_title())
+            .open(&mut open)
+            .resizable(true)
+            .default_width(560.0)
+            .default_height(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(library_dir().display().to_string()).small().weak());
+                    if ui.small_button(translations.workflow_library_refresh()).clicked() {
+                        refresh = true;
+                    }
+                    if ui.small_button(translations.workflow_library_reveal()).clicked() {
+                        let dir = library_dir();
+                        if std::fs::create_dir_all(&dir).is_ok() {
+                            reveal(&dir);
+                        }
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                }
+                ui.separator();
+                if self.entries.is_empty() {
+                    ui.label(translations.workflow_library_empty());
+                }
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for entry in &mut self.entries {
+                        if let Some(action) = Self::show_entry(ui, entry, &mut self.renaming, &mut self.confirm_delete, translations) {
+                            row_action = Some(action);
+                        }
+                        ui.separator();
+                    }
+                });
+            });
+        if !open {
+            return LibraryAction::Close;
+        }
+
+        let (action, result) = match row_action {
+            None => (LibraryAction::None, Ok(())),
+            Some(RowAction::Open(path)) => (LibraryAction::Open(path), Ok(())),
+            Some(RowAction::Rename(from, name)) => match rename(&from, &name) {
+                Ok(to) => (LibraryAction::Renamed { from, to, name: name.trim().to_string() }, Ok(())),
+                Err(e) => (LibraryAction::None, Err(format!("{}: {}", translations.workflow_library_rename_failed(), e))),
+            },
+            Some(RowAction::Duplicate(path)) => match duplicate(&path, translations.workflow_library_copy_suffix()) {
+                Ok(_) => (LibraryAction::None, Ok(())),
+                Err(e) => (LibraryAction::None, Err(format!("{}: {}", translations.workflow_library_duplicate_failed(), e))),
+            },
+            Some(RowAction::Delete(path)) => match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    log_info!("Deleted workflow {}", path.display());
+                    (LibraryAction::Deleted(path), Ok(()))
+                }
+                Err(e) => (LibraryAction::None, Err(format!("{}: {}", translations.workflow_library_delete_failed(), e))),
+            },
+        };
+        if let Err(e) = result {
+            log_error!("{}", e);
+            self.error = Some(e);
+        } else if !matches!(action, LibraryAction::None | LibraryAction::Open(_)) || refresh {
+            self.error = None;
+            self.refresh();
+        }
+        action
+    }
+
+    fn show_entry(
+        ui: &mut egui::Ui,
+        entry: &mut LibraryEntry,
+        renaming: &mut Option<(PathBuf, String)>,
+        confirm_delete: &mut Option<PathBuf>,
+        translations: &Translations,
+    ) -> Option<RowAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            match &entry.workflow {
+                Ok(summary) => {
+                    let texture = entry.texture.get_or_insert_with(|| {
+                        ui.ctx().load_texture(
+                            format!("workflow_library_{}", entry.path.display()),
+                            summary.thumbnail.clone(),
+                            egui::TextureOptions::LINEAR,
+                        )
+                    });
+                    ui.image((texture.id(), egui::vec2(THUMBNAIL_SIZE[0] as f32, THUMBNAIL_SIZE[1] as f32)));
+                }
+                Err(_) => {
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(THUMBNAIL_SIZE[0] as f32, THUMBNAIL_SIZE[1] as f32), egui::Sense::hover());
+                    ui.painter().rect_filled(rect, 2.0, THUMBNAIL_BACKGROUND);
+                    ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, "⚠", egui::FontId::proportional(24.0), egui::Color32::from_rgb(255, 200, 100));
+                }
+            }
+
+            ui.vertical(|ui| {
+                match &entry.workflow {
+                    Ok(summary) => {
+                        let editing = renaming.as_mut().filter(|(path, _)| *path == entry.path);
+                        if let Some((_, name)) = editing {
+                            let response = ui.text_edit_singleline(name);
+                            if response.lost_focus() {
+                                if ui.input(|i| i.key_pressed(egui::Key::Enter)) && name.trim() != summary.name {
+                                    action = Some(RowAction::Rename(entry.path.clone(), name.clone()));
+                                }
+                                *renaming = None;
+                            } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                *renaming = None;
+                            } else {
+                                response.request_focus();
+                            }
+                        } else if ui.add(egui::Label::new(egui::RichText::new(&summary.name).strong()).sense(egui::Sense::click()))
+                            .on_hover_text(translations.workflow_library_rename_hint())
+                            .double_clicked()
+                        {
+                            *renaming = Some((entry.path.clone(), summary.name.clone()));
+                        }
+                        if summary.description.is_empty() {
+                            ui.label(egui::RichText::new(translations.workflow_library_no_description()).weak().italics());
+                        } else {
+                            ui.label(&summary.description);
+                        }
+                        ui.label(egui::RichText::new(format!(
+                            "{} {} · {} · {}",
+                            summary.node_count,
+                            translations.workflow_library_nodes(),
+                            entry.modified.map(|time| time.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+                            entry.file_name(),
+                        )).small().weak());
+                    }
+                    Err(reason) => {
+                        ui.strong(entry.file_name());
+                        ui.colored_label(egui::Color32::from_rgb(255, 200, 100), translations.workflow_library_unreadable());
+                        ui.label(egui::RichText::new(reason).small().weak());
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let readable = entry.workflow.is_ok();
+                    if ui.add_enabled(readable, egui::Button::new(translations.workflow_library_open())).clicked() {
+                        action = Some(RowAction::Open(entry.path.clone()));
+                    }
+                    if ui.add_enabled(readable, egui::Button::new(translations.workflow_library_rename())).clicked() {
+                        if let Ok(summary) = &entry.workflow {
+                            *renaming = Some((entry.path.clone(), summary.name.clone()));
+                        }
+                    }
+                    if ui.add_enabled(readable, egui::Button::new(translations.workflow_library_duplicate())).clicked() {
+                        action = Some(RowAction::Duplicate(entry.path.clone()));
+                    }
+                    if ui.button(translations.workflow_library_reveal()).clicked() {
+                        reveal(&entry.path);
+                    }
+                    if confirm_delete.as_ref() == Some(&entry.path) {
+                        if ui.button(egui::RichText::new(translations.workflow_library_confirm_delete()).color(egui::Color32::from_rgb(255, 100, 100))).clicked() {
+                            action = Some(RowAction::Delete(entry.path.clone()));
+                            *confirm_delete = None;
+                        }
+                        if ui.button(translations.cancel()).clicked() {
+                            *confirm_delete = None;
+                        }
+                    } else if ui.button(translations.workflow_library_delete()).clicked() {
+                        *confirm_delete = Some(entry.path.clone());
+                    }
+                });
+            });
+        });
+        action
+    }
+}
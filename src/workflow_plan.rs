@@ -0,0 +1,725 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Stdio};
+
+use crate::automation_flow::{AutomationNode, AutomationWorkflow, NodeType};
+
+/// Lines of stderr kept per process for the error message
+const STDERR_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    Video,
+    Audio,
+}
+
+impl StreamKind {
+    fn filter_flag(&self) -> &'static str {
+        match self {
+            StreamKind::Video => "-vf",
+            StreamKind::Audio => "-af",
+        }
+    }
+
+    /// Extension of a segment's output when its last node does not name a format
+    fn default_extension(&self) -> &'static str {
+        match self {
+            StreamKind::Video => "mp4",
+            StreamKind::Audio => "wav",
+        }
+    }
+}
+
+/// Why an edge keeps its intermediate file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaterializeReason {
+    /// The producer is an input file or the consumer writes the output file
+    FileEndpoint,
+    /// The output feeds several nodes or the consumer has several inputs; a pipe has one reader
+    Branch,
+    /// The consumer makes two passes over its input or seeks in it
+    NeedsFile,
+    /// One of the nodes runs through its own command builder, which reads and writes files
+    NotStreamable,
+    Disabled,
+}
+
+/// How the output of one node reaches the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Both filters run in one ffmpeg, joined into a single filtergraph
+    Collapse,
+    /// Separate ffmpeg processes, the producer writing NUT to the consumer's stdin
+    Pipe,
+    Materialize(MaterializeReason),
+}
+
+/// Consumers that read their input twice (two-pass encodes, palette and loudness analysis) or
+/// seek in it, or that take more than one input
+fn needs_file(node_type: &NodeType) -> bool {
+    matches!(node_type,
+        NodeType::MultiPassEncode | NodeType::VideoStabilize | NodeType::AudioNormalize |
+        NodeType::VideoToGif | NodeType::GifResize | NodeType::QualityAnalysis |
+        NodeType::FormatValidation | NodeType::ExtractMetadata | NodeType::AudioTrim |
+        NodeType::AudioVideoSync | NodeType::Combine | NodeType::AudioMerge |
+        NodeType::VideoOverlay | NodeType::VideoPiP | NodeType::VideoSideBySide |
+        NodeType::ImagesToVideo | NodeType::CreateArchive | NodeType::ExtractArchive |
+        NodeType::StreamPrep | NodeType::BatchProcess | NodeType::BatchConvert)
+}
+
+fn parameter<'a>(node: &'a AutomationNode, name: &str, default: &'a str) -> &'a str {
+    node.parameters.get(name).map(|p| p.value.trim()).filter(|value| !value.is_empty()).unwrap_or(default)
+}
+
+/// The filter a single-input node applies, for nodes whose whole job is one filter chain.
+/// "null" stands for a node that leaves the stream as it is.
+pub fn node_filter(node: &AutomationNode) -> Option<(StreamKind, String)> {
+    let video = |filter: String| Some((StreamKind::Video, filter));
+    let audio = |filter: String| Some((StreamKind::Audio, filter));
+    match node.node_type {
+        NodeType::VideoResize => {
+            let dimension = |name: &str| parameter(node, name, "0").parse::<i64>().ok().filter(|value| *value > 0);
+            match (dimension("width"), dimension("height")) {
+                (None, None) => video("null".to_string()),
+                // -2 keeps the aspect ratio with an even size
+                (width, height) => video(format!("scale={}:{}", width.unwrap_or(-2), height.unwrap_or(-2))),
+            }
+        }
+        NodeType::VideoRotate => {
+            let flag = |name: &str| parameter(node, name, "false") == "true";
+            let mut filters = Vec::new();
+            match parameter(node, "rotation", "0") {
+                "90" => filters.push("transpose=1"),
+                "180" => filters.push("transpose=2,transpose=2"),
+                "270" => filters.push("transpose=2"),
+                _ => {}
+            }
+            if flag("flip_horizontal") {
+                filters.push("hflip");
+            }
+            if flag("flip_vertical") {
+                filters.push("vflip");
+            }
+            video(merge_filters(&filters))
+        }
+        NodeType::VideoFilter => {
+            let stack: Vec<crate::filter_stack::FilterStackEntry> = serde_json::from_str(parameter(node, "filter_stack", "[]")).ok()?;
+            // An invalid stage is reported by the node's own run
+            let filter = crate::filter_stack::build_filtergraph(&stack).ok()?;
+            video(merge_filters(&[filter.as_str()]))
+        }
+        NodeType::VideoFPS => {
+            // Speed change also retimes the audio and needs the source rate, so it runs on its own
+            let conversion = crate::automation_flow::fps_conversion_from_params(&node.parameters);
+            video(conversion.video_filter(None).ok()?)
+        }
+        // An invalid text setting is reported by the node's own run
+        NodeType::AddText => video(crate::automation_flow::text_overlay_from_params(&node.parameters).filter().ok()?),
+        NodeType::VideoDeinterlace => video("yadif".to_string()),
+        NodeType::VideoColorCorrect => video(parameter(node, "correction", "colorbalance=rs=0.1:gs=0.1:bs=0.1").to_string()),
+        NodeType::VideoBrightness => video(format!(
+            "eq=brightness={}:contrast={}",
+            parameter(node, "brightness", "0.0"),
+            parameter(node, "contrast", "1.0"),
+        )),
+        NodeType::VideoSaturation => video(format!("eq=saturation={}", parameter(node, "saturation", "1.0"))),
+        NodeType::VideoGamma => video(format!("eq=gamma={}", parameter(node, "gamma", "1.2"))),
+        // Normalizing needs a loudness pass over the whole input first
+        NodeType::AudioVolume if parameter(node, "normalize", "false") != "true" => {
+            audio(format!("volume={}", parameter(node, "volume", "1.0")))
+        }
+        // Invalid settings are reported by the node's own run
+        NodeType::AudioEcho => audio(crate::automation_flow::echo_from_params(&node.parameters).filter().ok()?),
+        NodeType::AudioEqualizer => {
+            let filter = crate::automation_flow::equalizer_from_params(&node.parameters).filter().ok()?;
+            audio(filter.unwrap_or_else(|| "null".to_string()))
+        }
+        NodeType::AudioDeNoise => {
+            let denoise = crate::automation_flow::denoise_from_params(&node.parameters);
+            denoise.validate().ok()?;
+            audio(denoise.to_filter().unwrap_or_else(|| "null".to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// A plain chain ("a,b,c") can be appended to another; graphs with labels or several chains cannot
+pub fn is_simple_chain(filter: &str) -> bool {
+    !filter.contains(';') && !filter.contains('[')
+}
+
+/// Join simple filter chains in order, dropping pass-through stages; "null" when nothing is left
+pub fn merge_filters(filters: &[&str]) -> String {
+    let parts: Vec<&str> = filters.iter()
+        .map(|filter| filter.trim().trim_matches(','))
+        .filter(|filter| !filter.is_empty() && *filter != "null")
+        .collect();
+    if parts.is_empty() { "null".to_string() } else { parts.join(",") }
+}
+
+/// Decide how the output of `from` reaches `to`. `fan_out` counts the connections leaving `from`,
+/// `fan_in` the connections arriving at `to`.
+pub fn decide_edge(from: &AutomationNode, to: &AutomationNode, fan_out: usize, fan_in: usize) -> EdgeMode {
+    if from.node_type == NodeType::InputFile || to.node_type == NodeType::OutputFile {
+        return EdgeMode::Materialize(MaterializeReason::FileEndpoint);
+    }
+    if !from.enabled || !to.enabled {
+        return EdgeMode::Materialize(MaterializeReason::Disabled);
+    }
+    if fan_out > 1 || fan_in > 1 {
+        return EdgeMode::Materialize(MaterializeReason::Branch);
+    }
+    if needs_file(&to.node_type) {
+        return EdgeMode::Materialize(MaterializeReason::NeedsFile);
+    }
+    let (Some((from_kind, from_filter)), Some((to_kind, to_filter))) = (node_filter(from), node_filter(to)) else {
+        return EdgeMode::Materialize(MaterializeReason::NotStreamable);
+    };
+    if from_kind == to_kind && is_simple_chain(&from_filter) && is_simple_chain(&to_filter) {
+        EdgeMode::Collapse
+    } else {
+        EdgeMode::Pipe
+    }
+}
+
+/// Nodes run by one ffmpeg with their filters merged
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub nodes: Vec<String>,
+    pub kind: StreamKind,
+    pub filter: String,
+}
+
+/// A linear run of nodes executed together: one stage per ffmpeg process, consecutive stages piped.
+/// Only the last node's output is written to disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub nodes: Vec<String>,
+    pub stages: Vec<Stage>,
+}
+
+impl Segment {
+    pub fn head(&self) -> &str {
+        &self.nodes[0]
+    }
+
+    pub fn tail(&self) -> &str {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    /// Extension of the file the segment writes: the last node's format, if it names one
+    pub fn output_extension<'a>(&self, workflow: &'a AutomationWorkflow) -> &'a str {
+        let kind = self.stages[self.stages.len() - 1].kind;
+        workflow.nodes.get(self.tail())
+            .and_then(|node| node.parameters.get("format"))
+            .map(|p| p.value.trim())
+            .filter(|format| !format.is_empty())
+            .unwrap_or(kind.default_extension())
+    }
+
+    /// ffmpeg arguments of every stage: the first reads `input`, the last writes `output` and
+    /// the ones between read stdin and write lossless NUT to stdout
+    pub fn commands(&self, input: &str, output: &str) -> Vec<Vec<String>> {
+        let count = self.stages.len();
+        self.stages.iter().enumerate().map(|(index, stage)| {
+            let mut args: Vec<String> = vec!["-y".to_string(), "-hide_banner".to_string()];
+            args.extend(["-i".to_string(), if index == 0 { input.to_string() } else { "pipe:0".to_string() }]);
+            if stage.filter != "null" {
+                args.extend([stage.kind.filter_flag().to_string(), stage.filter.clone()]);
+            }
+            if stage.kind == StreamKind::Audio {
+                args.push("-vn".to_string());
+            }
+            if index + 1 < count {
+                args.extend(["-c:v", "rawvideo", "-c:a", "pcm_s16le", "-f", "nut", "pipe:1"].map(String::from));
+            } else {
+                args.push(output.to_string());
+            }
+            args
+        }).collect()
+    }
+
+    /// The commands as one shell pipeline, as recorded in run reports and shown in the plan preview
+    pub fn command_line(&self, input: &str, output: &str) -> String {
+        self.commands(input, output).iter()
+            .map(|args| {
+                let argv: Vec<String> = std::iter::once("ffmpeg".to_string()).chain(args.iter().cloned()).collect();
+                crate::shell_quote::command_line(&argv, crate::shell_quote::Shell::Posix)
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+/// One step of a command preview, in execution order
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewStep {
+    pub nodes: Vec<String>,
+    /// The nodes form a segment run as one ffmpeg, or several piped into each other
+    pub fused: bool,
+    /// Why a node run on its own writes a file for the node after it
+    pub barrier: Option<MaterializeReason>,
+    /// Commands the step runs, filled in by a dry run of the executor
+    pub commands: Vec<String>,
+    /// Files the step copies to an output path, as (from, to), filled in by the same dry run
+    pub copies: Vec<(String, String)>,
+    /// Why the step's commands could not be built
+    pub error: Option<String>,
+}
+
+/// Per-edge decisions for one run and the segments they form
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionPlan {
+    /// By connection id
+    pub edges: HashMap<String, EdgeMode>,
+    pub segments: Vec<Segment>,
+}
+
+impl ExecutionPlan {
+    /// Plan `workflow` for `order`, a topological order of its nodes
+    pub fn build(workflow: &AutomationWorkflow, order: &[String]) -> Self {
+        // Connections left behind by a deleted node carry nothing and are not counted
+        let linked = |connection: &&crate::automation_flow::NodeConnection| {
+            workflow.nodes.contains_key(&connection.from_node) && workflow.nodes.contains_key(&connection.to_node)
+        };
+        let count = |matches: &dyn Fn(&crate::automation_flow::NodeConnection) -> bool| {
+            workflow.connections.values().filter(linked).filter(|connection| matches(connection)).count()
+        };
+        let mut edges = HashMap::new();
+        // Streamed edge leaving each node, keyed by producer
+        let mut streamed: HashMap<&str, (&str, EdgeMode)> = HashMap::new();
+        for (id, connection) in &workflow.connections {
+            let (Some(from), Some(to)) = (workflow.nodes.get(&connection.from_node), workflow.nodes.get(&connection.to_node)) else {
+                continue;
+            };
+            let fan_out = count(&|c| c.from_node == connection.from_node);
+            let fan_in = count(&|c| c.to_node == connection.to_node);
+            let mode = decide_edge(from, to, fan_out, fan_in);
+            if matches!(mode, EdgeMode::Collapse | EdgeMode::Pipe) {
+                streamed.insert(connection.from_node.as_str(), (connection.to_node.as_str(), mode));
+            }
+            edges.insert(id.clone(), mode);
+        }
+
+        let consumers: HashSet<&str> = streamed.values().map(|(to, _)| *to).collect();
+        let mut segments = Vec::new();
+        // Heads come before the rest of their segment in a topological order
+        for node_id in order {
+            if consumers.contains(node_id.as_str()) || !streamed.contains_key(node_id.as_str()) {
+                continue;
+            }
+            let mut nodes = vec![node_id.clone()];
+            let mut stages: Vec<Stage> = Vec::new();
+            let mut current = node_id.as_str();
+            let mut stage_nodes = vec![current.to_string()];
+            let mut stage_filters = vec![];
+            let filter_of = |id: &str| workflow.nodes.get(id).and_then(node_filter);
+            let Some((mut kind, filter)) = filter_of(current) else {
+                continue;
+            };
+            stage_filters.push(filter);
+            while let Some(&(next, mode)) = streamed.get(current) {
+                let Some((next_kind, next_filter)) = filter_of(next) else {
+                    break;
+                };
+                if mode == EdgeMode::Pipe {
+                    let filters: Vec<&str> = stage_filters.iter().map(String::as_str).collect();
+                    stages.push(Stage { nodes: std::mem::take(&mut stage_nodes), kind, filter: merge_filters(&filters) });
+                    stage_filters.clear();
+                    kind = next_kind;
+                }
+                stage_nodes.push(next.to_string());
+                stage_filters.push(next_filter);
+                nodes.push(next.to_string());
+                current = next;
+            }
+            let filters: Vec<&str> = stage_filters.iter().map(String::as_str).collect();
+            stages.push(Stage { nodes: stage_nodes, kind, filter: merge_filters(&filters) });
+            segments.push(Segment { nodes, stages });
+        }
+        Self { edges, segments }
+    }
+
+    /// The segment `node_id` starts
+    pub fn segment_starting_at(&self, node_id: &str) -> Option<&Segment> {
+        self.segments.iter().find(|segment| segment.head() == node_id)
+    }
+
+    /// Whether `node_id` runs as part of a segment started by an earlier node
+    pub fn runs_with_earlier_node(&self, node_id: &str) -> bool {
+        self.segments.iter().any(|segment| segment.head() != node_id && segment.nodes.iter().any(|id| id == node_id))
+    }
+
+    /// The steps a run of `order` takes: one per segment and one per node running on its own.
+    /// Commands are left for the executor's dry run to fill in.
+    pub fn preview(&self, workflow: &AutomationWorkflow, order: &[String]) -> Vec<PreviewStep> {
+        order.iter()
+            .filter(|node_id| !self.runs_with_earlier_node(node_id))
+            .map(|node_id| match self.segment_starting_at(node_id) {
+                Some(segment) => PreviewStep { nodes: segment.nodes.clone(), fused: true, barrier: None, commands: Vec::new(), copies: Vec::new(), error: None },
+                None => {
+                    let barrier = workflow.connections.iter()
+                        .filter(|(_, connection)| connection.from_node == *node_id)
+                        .find_map(|(id, _)| match self.edges.get(id) {
+                            Some(EdgeMode::Materialize(reason)) => Some(*reason),
+                            _ => None,
+                        });
+                    PreviewStep { nodes: vec![node_id.clone()], fused: false, barrier, commands: Vec::new(), copies: Vec::new(), error: None }
+                }
+            })
+            .collect()
+    }
+
+    /// Edges that will not write an intermediate file, as (collapsed, piped)
+    pub fn streamed_edge_counts(&self) -> (usize, usize) {
+        let collapsed = self.edges.values().filter(|mode| **mode == EdgeMode::Collapse).count();
+        let piped = self.edges.values().filter(|mode| **mode == EdgeMode::Pipe).count();
+        (collapsed, piped)
+    }
+}
+
+/// "00:01:02.50" in seconds
+fn parse_clock(text: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        seconds = seconds * 60.0 + part.trim().parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Output time of an ffmpeg status line, e.g. "frame=  120 ... time=00:00:05.00 bitrate=..."
+fn status_time(line: &str) -> Option<f64> {
+    let start = line.find("time=")? + 5;
+    parse_clock(line[start..].split_whitespace().next()?)
+}
+
+fn push_tail(lines: &mut Vec<String>, line: &str) {
+    if line.trim().is_empty() {
+        return;
+    }
+    lines.push(line.trim().to_string());
+    if lines.len() > STDERR_TAIL_LINES {
+        lines.remove(0);
+    }
+}
+
+fn kill_all(children: &mut [Child]) {
+    for child in children.iter_mut() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Run the commands of a segment with each one's stdout feeding the next one's stdin. Progress is
+/// the output time of the last process, which writes the file, in seconds. Returns the last lines
+/// the last process logged, where filters such as psnr print their summaries.
+pub fn run_pipeline(commands: &[Vec<String>], on_progress: &mut dyn FnMut(f64)) -> Result<Vec<String>, String> {
+    let ffmpeg = crate::bundled_ffmpeg::get_bundled_ffmpeg().map_err(|e| e.to_string())?;
+    let mut children: Vec<Child> = Vec::new();
+    // Registered with the run's stop button until the pipeline returns
+    let mut cancel_watches = Vec::new();
+    let mut producer_logs = Vec::new();
+    let mut previous_stdout = None;
+    let mut consumer_stderr = None;
+
+    for (index, args) in commands.iter().enumerate() {
+        let last = index + 1 == commands.len();
+        let mut cmd = ffmpeg.command();
+        // Only the last stage writes the output file, which carries the watch-folder tag
+        let tagged = crate::watch_folder::tagged_output_args(args).filter(|_| last);
+        cmd.args(tagged.as_ref().unwrap_or(args))
+            .stdin(previous_stdout.take().map(Stdio::from).unwrap_or_else(Stdio::null))
+            .stdout(if last { Stdio::null() } else { Stdio::piped() })
+            .stderr(Stdio::piped())
+            .env("AV_LOG_FORCE_NOCOLOR", "1");
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+        log_debug!("Pipeline stage {}/{}: {:?}", index + 1, commands.len(), cmd);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                kill_all(&mut children);
+                return Err(format!("Failed to start ffmpeg for stage {}: {}", index + 1, e));
+            }
+        };
+        cancel_watches.extend(crate::run_cancel::watch_process(child.id()));
+        previous_stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        if last {
+            consumer_stderr = stderr;
+        } else if let Some(stderr) = stderr {
+            // Producers block once their stderr pipe fills, so it is drained all the time
+            producer_logs.push(std::thread::spawn(move || {
+                let mut tail = Vec::new();
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    push_tail(&mut tail, &line);
+                }
+                tail
+            }));
+        }
+        children.push(child);
+    }
+
+    // ffmpeg ends status lines with \r, so the consumer's stderr is split on both line endings
+    let mut consumer_tail = Vec::new();
+    if let Some(mut stderr) = consumer_stderr {
+        let mut buffer = [0u8; 4096];
+        let mut line = Vec::new();
+        while let Ok(read) = stderr.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            for &byte in &buffer[..read] {
+                if byte == b'\r' || byte == b'\n' {
+                    let text = String::from_utf8_lossy(&line);
+                    if let Some(seconds) = status_time(&text) {
+                        on_progress(seconds);
+                    } else {
+                        push_tail(&mut consumer_tail, &text);
+                    }
+                    line.clear();
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+    }
+
+    let mut consumer = children.pop().ok_or("Empty pipeline")?;
+    let consumer_status = consumer.wait().map_err(|e| e.to_string())?;
+    if !consumer_status.success() {
+        // Producers would otherwise wait on a pipe nobody reads
+        kill_all(&mut children);
+        return Err(format!("ffmpeg stage {} failed: {}", commands.len(), consumer_tail.join("\n")));
+    }
+    // The consumer also succeeds on a truncated stream, so every producer must have succeeded too
+    for (index, (mut child, log)) in children.into_iter().zip(producer_logs).enumerate() {
+        let status = child.wait().map_err(|e| e.to_string())?;
+        let tail = log.join().unwrap_or_default();
+        if !status.success() {
+            return Err(format!("ffmpeg stage {} failed: {}", index + 1, tail.join("\n")));
+        }
+    }
+    Ok(consumer_tail)
+}
+
+/// The steps of a command preview with copy buttons; steps that could not be built show their
+/// error, other nodes the reason they keep an intermediate file
+pub fn show_preview(ui: &mut egui::Ui, workflow: &AutomationWorkflow, steps: &[PreviewStep], translations: &crate::language::Translations) {
+    let node_name = |id: &String| workflow.nodes.get(id)
+        .map(|node| translations.node_type_name(&node.node_type).to_string())
+        .unwrap_or_else(|| id.clone());
+    let nodes: usize = steps.iter().map(|step| step.nodes.len()).sum();
+    let fused: usize = steps.iter().filter(|step| step.fused).map(|step| step.nodes.len()).sum();
+    ui.label(translations.workflow_plan_summary(nodes, steps.len(), fused));
+    ui.weak(translations.workflow_plan_placeholder_hint());
+
+    let commands: Vec<&str> = steps.iter().flat_map(|step| step.commands.iter().map(String::as_str)).collect();
+    if ui.add_enabled(!commands.is_empty(), egui::Button::new(translations.workflow_plan_copy_all())).clicked() {
+        ui.output_mut(|o| o.copied_text = commands.join("\n"));
+    }
+
+    ui.separator();
+    egui::ScrollArea::vertical().id_salt("workflow_plan_steps").max_height(420.0).show(ui, |ui| {
+        for (index, step) in steps.iter().enumerate() {
+            let names: Vec<String> = step.nodes.iter().map(node_name).collect();
+            ui.horizontal(|ui| {
+                ui.strong(format!("{}. {}", index + 1, names.join(" → ")));
+                if !step.commands.is_empty() && ui.small_button(translations.workflow_plan_copy()).clicked() {
+                    ui.output_mut(|o| o.copied_text = step.commands.join("\n"));
+                }
+            });
+            if step.fused {
+                ui.weak(translations.workflow_plan_fused());
+            } else if let Some(reason) = step.barrier {
+                let reason = match reason {
+                    MaterializeReason::FileEndpoint => translations.workflow_plan_file_endpoint(),
+                    MaterializeReason::Branch => translations.workflow_plan_branch(),
+                    MaterializeReason::NeedsFile => translations.workflow_plan_needs_file(),
+                    MaterializeReason::NotStreamable => translations.workflow_plan_not_streamable(),
+                    MaterializeReason::Disabled => translations.workflow_plan_disabled(),
+                };
+                ui.weak(format!("{} ({})", translations.workflow_plan_own(), reason));
+            }
+            if let Some(error) = &step.error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", error));
+            } else if step.commands.is_empty() {
+                ui.weak(translations.workflow_plan_no_command());
+            }
+            for command in &step.commands {
+                ui.add(egui::Label::new(egui::RichText::new(command).monospace().small()).wrap());
+            }
+            ui.add_space(4.0);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation_flow::NodeConnection;
+
+    fn add(workflow: &mut AutomationWorkflow, id: &str, node_type: NodeType, parameters: &[(&str, &str)]) {
+        let mut node = AutomationNode::new(id.to_string(), node_type, egui::Pos2::ZERO);
+        for (name, value) in parameters {
+            node.parameters.get_mut(*name).unwrap().value = value.to_string();
+        }
+        workflow.add_node(node);
+    }
+
+    /// Inserted as is, so connections to missing nodes or between mismatched ports can be made
+    fn connect(workflow: &mut AutomationWorkflow, id: &str, from: &str, to: &str) {
+        workflow.connections.insert(id.to_string(), NodeConnection {
+            id: id.to_string(),
+            from_node: from.to_string(),
+            from_port: 0,
+            to_node: to.to_string(),
+            to_port: 0,
+            data_type: crate::automation_flow::DataType::VideoStream,
+        });
+    }
+
+    fn plan(workflow: &AutomationWorkflow) -> ExecutionPlan {
+        let order = workflow.get_execution_order().unwrap();
+        ExecutionPlan::build(workflow, &order)
+    }
+
+    /// input → resize → rotate → brightness → output
+    fn chain() -> AutomationWorkflow {
+        let mut workflow = AutomationWorkflow::new("plan test".to_string());
+        add(&mut workflow, "input", NodeType::InputFile, &[("file_path", "/no/such/in.mp4")]);
+        add(&mut workflow, "resize", NodeType::VideoResize, &[("width", "1280")]);
+        add(&mut workflow, "rotate", NodeType::VideoRotate, &[("rotation", "90")]);
+        add(&mut workflow, "brightness", NodeType::VideoBrightness, &[]);
+        add(&mut workflow, "output", NodeType::OutputFile, &[("output_path", "/no/such/out.mp4")]);
+        connect(&mut workflow, "c1", "input", "resize");
+        connect(&mut workflow, "c2", "resize", "rotate");
+        connect(&mut workflow, "c3", "rotate", "brightness");
+        connect(&mut workflow, "c4", "brightness", "output");
+        workflow
+    }
+
+    #[test]
+    fn merged_filters_drop_pass_through_stages() {
+        assert_eq!(merge_filters(&["scale=1280:-2", "null", "", ",transpose=1,"]), "scale=1280:-2,transpose=1");
+        assert_eq!(merge_filters(&["null", " "]), "null");
+        assert_eq!(merge_filters(&[]), "null");
+        assert!(is_simple_chain("scale=1280:-2,transpose=1"));
+        assert!(!is_simple_chain("[0:v]split[a][b];[a][b]hstack"));
+        assert!(!is_simple_chain("scale=640:-2;volume=2"));
+    }
+
+    #[test]
+    fn a_linear_filter_chain_collapses_into_one_segment() {
+        let workflow = chain();
+        let plan = plan(&workflow);
+        assert_eq!(plan.edges["c1"], EdgeMode::Materialize(MaterializeReason::FileEndpoint));
+        assert_eq!(plan.edges["c2"], EdgeMode::Collapse);
+        assert_eq!(plan.edges["c3"], EdgeMode::Collapse);
+        assert_eq!(plan.edges["c4"], EdgeMode::Materialize(MaterializeReason::FileEndpoint));
+        assert_eq!(plan.streamed_edge_counts(), (2, 0));
+
+        assert_eq!(plan.segments.len(), 1);
+        let segment = &plan.segments[0];
+        assert_eq!(segment.nodes, ["resize", "rotate", "brightness"]);
+        assert_eq!(segment.stages.len(), 1);
+        assert_eq!(segment.stages[0].filter, "scale=1280:-2,transpose=1,eq=brightness=0.0:contrast=1.0");
+        assert_eq!(segment.output_extension(&workflow), "mp4");
+        assert!(plan.segment_starting_at("resize").is_some());
+        assert!(plan.runs_with_earlier_node("rotate") && plan.runs_with_earlier_node("brightness"));
+        assert!(!plan.runs_with_earlier_node("resize") && !plan.runs_with_earlier_node("output"));
+        assert_eq!(segment.commands("in.mp4", "out.mp4"), [[
+            "-y", "-hide_banner", "-i", "in.mp4", "-vf", "scale=1280:-2,transpose=1,eq=brightness=0.0:contrast=1.0", "out.mp4",
+        ]]);
+
+        let steps = plan.preview(&workflow, &workflow.get_execution_order().unwrap());
+        let nodes: Vec<&Vec<String>> = steps.iter().map(|step| &step.nodes).collect();
+        assert_eq!(nodes, [&vec!["input".to_string()], &vec!["resize".to_string(), "rotate".to_string(), "brightness".to_string()], &vec!["output".to_string()]]);
+        assert_eq!(steps[0].barrier, Some(MaterializeReason::FileEndpoint));
+        assert!(steps[1].fused);
+    }
+
+    #[test]
+    fn dangling_connections_are_ignored() {
+        let mut workflow = chain();
+        let expected = plan(&workflow).segments;
+        // Both ends, or one of them, refer to nodes that were deleted
+        connect(&mut workflow, "ghost_out", "rotate", "deleted");
+        connect(&mut workflow, "ghost_in", "deleted", "brightness");
+        connect(&mut workflow, "ghost_both", "gone", "deleted");
+        let order: Vec<String> = ["input", "resize", "rotate", "brightness", "output"].iter().map(|id| id.to_string()).collect();
+        let plan = ExecutionPlan::build(&workflow, &order);
+        assert!(!plan.edges.contains_key("ghost_both"));
+        assert!(!plan.edges.contains_key("ghost_out") && !plan.edges.contains_key("ghost_in"));
+        // The dangling ends are neither a second reader nor a second source
+        assert_eq!(plan.edges["c2"], EdgeMode::Collapse);
+        assert_eq!(plan.edges["c3"], EdgeMode::Collapse);
+        assert_eq!(plan.segments, expected);
+    }
+
+    #[test]
+    fn colliding_ids_do_not_merge_nodes() {
+        let mut workflow = chain();
+        // A second connection between the same two nodes, and connection ids equal to node ids
+        connect(&mut workflow, "rotate", "resize", "rotate");
+        connect(&mut workflow, "resize", "brightness", "output");
+        let plan = plan(&workflow);
+        assert_eq!(plan.edges.len(), 6);
+        assert_eq!(plan.edges["c2"], EdgeMode::Materialize(MaterializeReason::Branch));
+        assert_eq!(plan.edges["rotate"], EdgeMode::Materialize(MaterializeReason::Branch));
+        assert_eq!(plan.edges["resize"], EdgeMode::Materialize(MaterializeReason::FileEndpoint));
+        assert_eq!(plan.segments.len(), 1);
+        assert_eq!(plan.segments[0].nodes, ["rotate", "brightness"]);
+        // Every node is run exactly once
+        let steps = plan.preview(&workflow, &workflow.get_execution_order().unwrap());
+        let mut run: Vec<&String> = steps.iter().flat_map(|step| &step.nodes).collect();
+        run.sort();
+        assert_eq!(run, ["brightness", "input", "output", "resize", "rotate"]);
+    }
+
+    #[test]
+    fn edges_materialize_for_every_reason() {
+        let mut workflow = chain();
+        add(&mut workflow, "stabilize", NodeType::VideoStabilize, &[]);
+        add(&mut workflow, "split", NodeType::SplitAudioVideo, &[]);
+        let nodes = &workflow.nodes;
+        assert_eq!(decide_edge(&nodes["resize"], &nodes["stabilize"], 1, 1), EdgeMode::Materialize(MaterializeReason::NeedsFile));
+        assert_eq!(decide_edge(&nodes["resize"], &nodes["split"], 1, 1), EdgeMode::Materialize(MaterializeReason::NotStreamable));
+        assert_eq!(decide_edge(&nodes["resize"], &nodes["rotate"], 2, 1), EdgeMode::Materialize(MaterializeReason::Branch));
+        assert_eq!(decide_edge(&nodes["resize"], &nodes["rotate"], 1, 2), EdgeMode::Materialize(MaterializeReason::Branch));
+        let mut disabled = nodes["rotate"].clone();
+        disabled.enabled = false;
+        assert_eq!(decide_edge(&nodes["resize"], &disabled, 1, 1), EdgeMode::Materialize(MaterializeReason::Disabled));
+    }
+
+    #[test]
+    fn a_video_to_audio_edge_is_piped_between_stages() {
+        let mut workflow = chain();
+        add(&mut workflow, "volume", NodeType::AudioVolume, &[("volume", "0.5")]);
+        workflow.remove_node("output");
+        connect(&mut workflow, "c4", "brightness", "volume");
+        let plan = plan(&workflow);
+        assert_eq!(plan.edges["c4"], EdgeMode::Pipe);
+        assert_eq!(plan.streamed_edge_counts(), (2, 1));
+        let segment = &plan.segments[0];
+        assert_eq!(segment.nodes, ["resize", "rotate", "brightness", "volume"]);
+        assert_eq!(segment.stages.iter().map(|stage| stage.nodes.len()).collect::<Vec<_>>(), [3, 1]);
+        assert_eq!(segment.output_extension(&workflow), "wav");
+        let commands = segment.commands("in.mp4", "out.wav");
+        assert_eq!(commands[0][commands[0].len() - 7..], ["-c:v", "rawvideo", "-c:a", "pcm_s16le", "-f", "nut", "pipe:1"]);
+        assert_eq!(commands[1], ["-y", "-hide_banner", "-i", "pipe:0", "-af", "volume=0.5", "-vn", "out.wav"]);
+        assert!(segment.command_line("in.mp4", "out.wav").contains(" | ffmpeg -y -hide_banner -i pipe:0"));
+    }
+
+    #[test]
+    fn status_lines_give_the_output_time() {
+        assert_eq!(status_time("frame=  120 fps=30 time=00:01:02.50 bitrate=1000kbits/s"), Some(62.5));
+        assert_eq!(status_time("size=N/A time=N/A"), None);
+        assert_eq!(parse_clock("01:00:00"), Some(3600.0));
+    }
+}
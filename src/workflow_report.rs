@@ -0,0 +1,227 @@
+use serde::{Serialize, Deserialize};
+use crate::automation_flow::{AutomationWorkflow, NodeExecutionResult, NodeType};
+use crate::language::Translations;
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+/// A file a node produced, sized right after the node finished (temp files are cleaned up later)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactInfo {
+    pub path: String,
+    /// None for missing files and output patterns such as frame_%04d.png
+    pub size_bytes: Option<u64>,
+}
+
+impl ArtifactInfo {
+    pub fn from_path(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            size_bytes: std::fs::metadata(path).ok().filter(|m| m.is_file()).map(|m| m.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeRunRecord {
+    /// Position in the execution order, starting at 1
+    pub order: usize,
+    pub node_id: String,
+    pub node_type: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    /// FFmpeg commands the node ran, where the node goes through the task executor
+    #[serde(default)]
+    pub commands: Vec<String>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub artifacts: Vec<ArtifactInfo>,
+    /// Outputs came from the workflow cache; the node did not run
+    #[serde(default)]
+    pub cached: bool,
+}
+
+impl NodeRunRecord {
+    pub fn new(order: usize, node_type: &NodeType, started_at: String, result: &NodeExecutionResult, commands: Vec<String>) -> Self {
+        Self {
+            order,
+            node_id: result.node_id.clone(),
+            node_type: node_type.display_name().to_string(),
+            started_at,
+            finished_at: timestamp(),
+            duration_ms: result.execution_time.as_millis() as u64,
+            commands,
+            success: result.success,
+            error: result.error_message.clone(),
+            artifacts: result.output_files.iter().map(|path| ArtifactInfo::from_path(path)).collect(),
+            cached: result.cached,
+        }
+    }
+
+    pub fn artifact_bytes(&self) -> u64 {
+        self.artifacts.iter().filter_map(|a| a.size_bytes).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    Running,
+    Success,
+    Failed,
+    Cancelled,
+}
+
+/// What happened during one workflow run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowRunReport {
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub wall_time_ms: u64,
+    pub outcome: RunOutcome,
+    pub error: Option<String>,
+    pub nodes: Vec<NodeRunRecord>,
+    pub final_outputs: Vec<ArtifactInfo>,
+    #[serde(skip)]
+    started: Option<std::time::Instant>,
+}
+
+impl WorkflowRunReport {
+    pub fn start(workflow: &AutomationWorkflow) -> Self {
+        Self {
+            workflow_id: workflow.id.clone(),
+            workflow_name: workflow.name.clone(),
+            started_at: timestamp(),
+            finished_at: None,
+            wall_time_ms: 0,
+            outcome: RunOutcome::Running,
+            error: None,
+            nodes: Vec::new(),
+            final_outputs: Vec::new(),
+            started: Some(std::time::Instant::now()),
+        }
+    }
+
+    /// Add a node record; results of output nodes are the run's final outputs
+    pub fn record_node(&mut self, record: NodeRunRecord, is_output: bool) {
+        if is_output && record.success {
+            self.final_outputs.extend(record.artifacts.iter().cloned());
+        }
+        self.nodes.push(record);
+    }
+
+    pub fn finish(&mut self, outcome: RunOutcome, error: Option<String>) {
+        self.outcome = outcome;
+        self.error = error;
+        self.finished_at = Some(timestamp());
+        self.wall_time_ms = self.started.map(|s| s.elapsed().as_millis() as u64).unwrap_or(0);
+    }
+
+    /// Total size of intermediate and final files produced by all nodes
+    pub fn artifact_bytes(&self) -> u64 {
+        self.nodes.iter().map(|node| node.artifact_bytes()).sum()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Workflow run totals for the current session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    pub workflow_runs: u32,
+    pub failed_runs: u32,
+    pub nodes_executed: u32,
+    pub total_wall_time_ms: u64,
+    pub artifact_bytes: u64,
+}
+
+impl SessionStats {
+    pub fn record(&mut self, report: &WorkflowRunReport) {
+        self.workflow_runs += 1;
+        if report.outcome == RunOutcome::Failed {
+            self.failed_runs += 1;
+        }
+        self.nodes_executed += report.nodes.len() as u32;
+        self.total_wall_time_ms += report.wall_time_ms;
+        self.artifact_bytes += report.artifact_bytes();
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn format_size(size: Option<u64>) -> String {
+    size.map(format_bytes).unwrap_or_else(|| "-".to_string())
+}
+
+/// Report body: summary, per-node table with expandable commands and artifacts, final outputs
+pub fn show_report(ui: &mut egui::Ui, report: &WorkflowRunReport, translations: &Translations) {
+    let (outcome_text, outcome_color) = match report.outcome {
+        RunOutcome::Running => ("⏳", egui::Color32::from_rgb(100, 150, 255)),
+        RunOutcome::Success => ("✅", egui::Color32::from_rgb(100, 200, 100)),
+        RunOutcome::Failed => ("❌", egui::Color32::from_rgb(255, 100, 100)),
+        RunOutcome::Cancelled => ("⏹", egui::Color32::from_rgb(200, 200, 100)),
+    };
+
+    ui.horizontal(|ui| {
+        ui.colored_label(outcome_color, outcome_text);
+        ui.strong(&report.workflow_name);
+    });
+    ui.label(format!("{} {} → {}", translations.run_report_time(), report.started_at, report.finished_at.as_deref().unwrap_or("-")));
+    ui.label(translations.run_report_summary(report.nodes.len(), report.wall_time_ms, &format_bytes(report.artifact_bytes())));
+    if let Some(error) = &report.error {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+    }
+
+    ui.separator();
+    egui::ScrollArea::vertical().id_salt("run_report_nodes").max_height(320.0).show(ui, |ui| {
+        for node in &report.nodes {
+            let header = if node.cached {
+                format!("⏭ {}. {} — {}, {}", node.order, node.node_type, translations.run_report_cached(), format_bytes(node.artifact_bytes()))
+            } else {
+                let icon = if node.success { "✅" } else { "❌" };
+                format!("{} {}. {} — {} ms, {}", icon, node.order, node.node_type, node.duration_ms, format_bytes(node.artifact_bytes()))
+            };
+            egui::CollapsingHeader::new(header)
+                .id_salt(("run_report_node", node.order))
+                .show(ui, |ui| {
+                    ui.label(format!("{}: {}", translations.run_report_node_id(), node.node_id));
+                    ui.label(format!("{} {} → {}", translations.run_report_time(), node.started_at, node.finished_at));
+                    if let Some(error) = &node.error {
+                        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                    }
+                    for command in &node.commands {
+                        ui.add(egui::Label::new(egui::RichText::new(command).monospace().small()).wrap());
+                    }
+                    for artifact in &node.artifacts {
+                        ui.label(format!("📄 {} ({})", artifact.path, format_size(artifact.size_bytes)));
+                    }
+                });
+        }
+    });
+
+    if !report.final_outputs.is_empty() {
+        ui.separator();
+        ui.strong(translations.run_report_final_outputs());
+        for output in &report.final_outputs {
+            ui.label(format!("📦 {} ({})", output.path, format_size(output.size_bytes)));
+        }
+    }
+}
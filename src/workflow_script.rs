@@ -0,0 +1,522 @@
+use std::path::{Path, PathBuf};
+
+use crate::automation_flow::{AutomationWorkflow, NodeType, WorkflowExecutor, DRY_RUN_TEMP_DIR};
+use crate::language::Translations;
+use crate::shell_quote::{self, Shell};
+
+/// Node types whose run is more than a fixed list of commands: they loop over files, act on what
+/// ffmpeg reports, or do their work in the app
+const UNSCRIPTABLE: &[NodeType] = &[
+    NodeType::BatchConvert, NodeType::BatchProcess, NodeType::QualityAnalysis, NodeType::FormatValidation,
+    NodeType::MultiResOutput, NodeType::VideoEncrypt, NodeType::VideoDecrypt,
+    NodeType::CreateArchive, NodeType::ExtractArchive,
+];
+
+/// Variable of the folder the script keeps intermediate files in
+const TMP: &str = "TMP";
+
+/// Programs a script may call; anything else in a recorded command is app-specific
+const PROGRAMS: &[&str] = &["ffmpeg", "ffprobe"];
+
+/// Why a workflow cannot be exported as a script
+#[derive(Debug, Clone, PartialEq)]
+pub enum Refusal {
+    /// The command preview failed as a whole, e.g. on a cycle
+    Preview(String),
+    /// A node the script cannot express
+    Node { node_id: String, reason: NodeRefusal, detail: Option<String> },
+}
+
+/// What a node does that a script cannot express
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeRefusal {
+    /// A node type with no command of its own
+    Unscriptable,
+    /// The node's command preview failed
+    PreviewError,
+    /// Work the app does itself instead of running a command
+    AppWork,
+    /// A file that only exists while the workflow runs
+    RuntimeFile,
+    /// Pipes or redirects PowerShell cannot carry
+    ShellSyntax,
+    /// A program other than ffmpeg or ffprobe
+    UnknownProgram,
+}
+
+impl Refusal {
+    fn node(node_id: &str, reason: NodeRefusal, detail: Option<String>) -> Self {
+        Refusal::Node { node_id: node_id.to_string(), reason, detail }
+    }
+
+    pub fn message(&self, workflow: &AutomationWorkflow, translations: &Translations) -> String {
+        match self {
+            Refusal::Preview(error) => format!("{}: {}", translations.workflow_script_preview_failed(), error),
+            Refusal::Node { node_id, reason, detail } => {
+                let name = workflow.flattened().nodes.get(node_id)
+                    .map_or_else(|| node_id.clone(), |node| translations.node_type_name(&node.node_type));
+                let reason = match reason {
+                    NodeRefusal::Unscriptable => translations.workflow_script_unscriptable(),
+                    NodeRefusal::PreviewError => translations.workflow_script_preview_error(),
+                    NodeRefusal::AppWork => translations.workflow_script_app_work(),
+                    NodeRefusal::RuntimeFile => translations.workflow_script_runtime_file(),
+                    NodeRefusal::ShellSyntax => translations.workflow_script_shell_syntax(),
+                    NodeRefusal::UnknownProgram => translations.workflow_script_unknown_program(),
+                };
+                translations.workflow_script_refusal(&name, reason, detail.as_deref())
+            }
+        }
+    }
+}
+
+/// Shell the script for `path` is written for, from its extension
+pub fn shell_for_path(path: &Path) -> Shell {
+    match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("ps1") => Shell::PowerShell,
+        _ => Shell::Posix,
+    }
+}
+
+pub fn extension(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Posix => "sh",
+        Shell::PowerShell => "ps1",
+    }
+}
+
+/// Part of an argument: literal text or a script variable
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Var(String),
+}
+
+/// A word of a recorded command line
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Arg(String),
+    /// Unquoted `|`, `>` or `2>&1`
+    Operator(String),
+}
+
+/// Path a variable stands for, in the order the script declares them
+struct Variable {
+    name: String,
+    value: String,
+    /// Matched only as a whole leading path component, so `/out` does not match `/output`
+    is_dir: bool,
+    /// Declared by the script itself rather than set by the user
+    temporary: bool,
+}
+
+/// Script running `workflow` in `shell`, built from the same dry run as the command preview.
+/// Inputs, the output folder and the intermediate folder are variables at the top.
+pub fn export(workflow: &AutomationWorkflow, executor: &WorkflowExecutor, shell: Shell, translations: &Translations) -> Result<String, Refusal> {
+    let workflow = workflow.flattened();
+    let steps = executor.preview_workflow(&workflow).map_err(Refusal::Preview)?;
+    let variables = variables(&workflow, shell);
+    let substitute = |arg: &str| pieces(arg, &variables);
+
+    let mut body: Vec<String> = Vec::new();
+    let mut created_dirs: Vec<String> = Vec::new();
+    // Every argument so far, for telling files earlier commands write from files the app writes
+    let mut written: Vec<String> = Vec::new();
+    let mut mkdir = |raw: &str, body: &mut Vec<String>| {
+        let Some(parent) = Path::new(raw).parent().map(|parent| parent.display().to_string()) else {
+            return;
+        };
+        let dir = substitute(&parent);
+        if parent.is_empty() || dir == [Piece::Var(TMP.to_string())] || created_dirs.contains(&parent) {
+            return;
+        }
+        body.push(mkdir_line(&dir, shell));
+        created_dirs.push(parent);
+    };
+    let in_tmp = |arg: &str| substitute(arg).first() == Some(&Piece::Var(TMP.to_string()));
+    let mut step_number = 0;
+    for step in &steps {
+        let head = &step.nodes[0];
+        let Some(node) = workflow.nodes.get(head) else {
+            continue;
+        };
+        if let Some(member) = step.nodes.iter().find(|id| workflow.nodes.get(*id).is_some_and(|node| UNSCRIPTABLE.contains(&node.node_type))) {
+            return Err(Refusal::node(member, NodeRefusal::Unscriptable, None));
+        }
+        if let Some(error) = &step.error {
+            return Err(Refusal::node(head, NodeRefusal::PreviewError, Some(error.clone())));
+        }
+        if node.node_type == NodeType::InputFile || !node.enabled {
+            continue;
+        }
+        if step.commands.is_empty() && step.copies.is_empty() {
+            return Err(Refusal::node(head, NodeRefusal::AppWork, None));
+        }
+
+        step_number += 1;
+        let names: Vec<String> = step.nodes.iter()
+            .filter_map(|id| workflow.nodes.get(id))
+            .map(|node| translations.node_type_name(&node.node_type))
+            .collect();
+        body.push(String::new());
+        body.push(format!("# {}. {}", step_number, names.join(" -> ")));
+        for command in &step.commands {
+            for line in split_commands(&tokenize(command)) {
+                check_line(&line, shell).map_err(|(reason, detail)| Refusal::node(head, reason, detail))?;
+                let args: Vec<&str> = line.iter().filter_map(|token| match token {
+                    Token::Arg(arg) => Some(arg.as_str()),
+                    Token::Operator(_) => None,
+                }).collect();
+                for pair in args.windows(2).filter(|pair| pair[0] == "-i") {
+                    let input = pair[1];
+                    if in_tmp(input) && !written.iter().any(|arg| arg.contains(input)) {
+                        return Err(Refusal::node(head, NodeRefusal::RuntimeFile, Some(input.to_string())));
+                    }
+                }
+                // Folders of intermediate files, e.g. for two-pass logs, are made by the app in a run
+                for arg in args.iter().filter(|arg| in_tmp(arg)).copied().chain(outputs(&line)) {
+                    mkdir(arg, &mut body);
+                }
+                body.push(render_line(&line, &substitute, shell));
+                written.extend(args.iter().map(|arg| arg.to_string()));
+            }
+        }
+        for (from, to) in &step.copies {
+            if in_tmp(from) && !written.iter().any(|arg| arg.contains(from.as_str())) {
+                return Err(Refusal::node(head, NodeRefusal::RuntimeFile, Some(from.clone())));
+            }
+            mkdir(to, &mut body);
+            body.push(copy_line(&substitute(from), &substitute(to), shell));
+        }
+    }
+    if step_number == 0 {
+        return Err(Refusal::Preview(translations.workflow_script_nothing_to_run().to_string()));
+    }
+
+    Ok(assemble(&workflow.name, &variables, &body, shell))
+}
+
+/// The workflow's input files, its output folder and the intermediate folder
+fn variables(workflow: &AutomationWorkflow, shell: Shell) -> Vec<Variable> {
+    let mut variables = Vec::new();
+    // `$input` is an automatic variable in PowerShell
+    let input_name = match shell {
+        Shell::Posix => "INPUT",
+        Shell::PowerShell => "INPUT_FILE",
+    };
+    let mut inputs: Vec<&str> = Vec::new();
+    let mut input_nodes: Vec<_> = workflow.nodes.values().filter(|node| node.node_type == NodeType::InputFile).collect();
+    input_nodes.sort_by(|a, b| a.position.y.total_cmp(&b.position.y).then(a.position.x.total_cmp(&b.position.x)));
+    for node in input_nodes {
+        let Some(path) = node.parameters.get("file_path").map(|parameter| parameter.value.trim()).filter(|path| !path.is_empty()) else {
+            continue;
+        };
+        if inputs.contains(&path) {
+            continue;
+        }
+        inputs.push(path);
+        let name = match inputs.len() {
+            1 => input_name.to_string(),
+            n => format!("{}_{}", input_name, n),
+        };
+        variables.push(Variable { name, value: path.to_string(), is_dir: false, temporary: false });
+    }
+
+    let output_dirs: Vec<PathBuf> = workflow.nodes.values()
+        .filter(|node| node.node_type == NodeType::OutputFile)
+        .filter_map(|node| node.get_dynamic_output_path(workflow))
+        .map(PathBuf::from)
+        .filter_map(|path| if path.is_dir() { Some(path) } else { path.parent().map(Path::to_path_buf) })
+        .collect();
+    if let Some(output_dir) = common_dir(&output_dirs) {
+        variables.push(Variable { name: "OUTPUT_DIR".to_string(), value: output_dir.display().to_string(), is_dir: true, temporary: false });
+    }
+
+    variables.push(Variable { name: TMP.to_string(), value: DRY_RUN_TEMP_DIR.to_string(), is_dir: true, temporary: true });
+    // Scratch files of the task commands, e.g. two-pass logs, go to the app's own temp folder
+    let scratch = crate::temp_files::preview_path("scratch");
+    if let Some(temp_root) = scratch.parent().and_then(Path::parent) {
+        variables.push(Variable { name: TMP.to_string(), value: temp_root.display().to_string(), is_dir: true, temporary: true });
+    }
+    variables
+}
+
+/// Deepest folder containing every one of `dirs`
+fn common_dir(dirs: &[PathBuf]) -> Option<PathBuf> {
+    let (first, rest) = dirs.split_first()?;
+    first.ancestors()
+        .find(|ancestor| rest.iter().all(|dir| dir.starts_with(ancestor)))
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+}
+
+/// `arg` with every variable's path replaced by the variable
+fn pieces(arg: &str, variables: &[Variable]) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut rest = arg;
+    loop {
+        // Earliest match, the longest one where several start at the same place
+        let found = variables.iter()
+            .filter_map(|variable| {
+                let start = rest.find(&variable.value)?;
+                let after = &rest[start + variable.value.len()..];
+                let whole = !variable.is_dir || after.is_empty() || after.starts_with(['/', '\\']);
+                whole.then_some((start, variable))
+            })
+            .min_by_key(|(start, variable)| (*start, std::cmp::Reverse(variable.value.len())));
+        let Some((start, variable)) = found else {
+            break;
+        };
+        if start > 0 {
+            pieces.push(Piece::Text(rest[..start].to_string()));
+        }
+        pieces.push(Piece::Var(variable.name.clone()));
+        rest = &rest[start + variable.value.len()..];
+    }
+    if !rest.is_empty() || pieces.is_empty() {
+        pieces.push(Piece::Text(rest.to_string()));
+    }
+    pieces
+}
+
+/// Words of a recorded command. Recorded commands quote either with double quotes, as the task
+/// previews do, or with POSIX single quotes, as `shell_quote` does.
+fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut has_word = false;
+    let mut quoted = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+    let mut end_word = |current: &mut String, quoted: bool| {
+        let word = std::mem::take(current);
+        tokens.push(if !quoted && ["|", "&&", ">", "2>&1"].contains(&word.as_str()) {
+            Token::Operator(word)
+        } else {
+            Token::Arg(word)
+        });
+    };
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                has_word = true;
+                quoted = true;
+            }
+            // The `'\''` of a POSIX-quoted argument
+            (None, '\\') if chars.peek() == Some(&'\'') => {
+                current.extend(chars.next());
+                has_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if has_word {
+                    end_word(&mut current, quoted);
+                    has_word = false;
+                    quoted = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if has_word {
+        end_word(&mut current, quoted);
+    }
+    tokens
+}
+
+/// Commands chained with `&&`, each on its own line so a failing first one stops the script
+fn split_commands(tokens: &[Token]) -> Vec<Vec<Token>> {
+    tokens.split(|token| *token == Token::Operator("&&".to_string()))
+        .filter(|line| !line.is_empty())
+        .map(<[Token]>::to_vec)
+        .collect()
+}
+
+/// Every program in `line` is one a script can call, and PowerShell gets no pipes or redirects:
+/// Windows PowerShell garbles binary data piped between programs and writes redirected text as UTF-16
+fn check_line(line: &[Token], shell: Shell) -> Result<(), (NodeRefusal, Option<String>)> {
+    let mut expect_program = true;
+    for token in line {
+        match token {
+            Token::Operator(operator) => {
+                if shell == Shell::PowerShell {
+                    return Err((NodeRefusal::ShellSyntax, Some(operator.clone())));
+                }
+                expect_program = operator == "|";
+            }
+            Token::Arg(program) if expect_program => {
+                if !PROGRAMS.contains(&program_name(program).as_str()) {
+                    return Err((NodeRefusal::UnknownProgram, Some(program.clone())));
+                }
+                expect_program = false;
+            }
+            Token::Arg(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Program as a file name, so the script runs where ffmpeg is on PATH
+fn program_name(program: &str) -> String {
+    Path::new(program).file_stem().map_or_else(|| program.to_string(), |stem| stem.to_string_lossy().to_string())
+}
+
+/// Files `line` writes: the last argument of each ffmpeg and the target of each redirect
+fn outputs(line: &[Token]) -> Vec<&str> {
+    let mut outputs = Vec::new();
+    for command in line.split(|token| matches!(token, Token::Operator(operator) if operator == "|")) {
+        if let Some(Token::Arg(program)) = command.first() {
+            if program_name(program) == "ffmpeg" {
+                if let Some(Token::Arg(last)) = command.iter().rev().find(|token| matches!(token, Token::Arg(_))) {
+                    outputs.push(last.as_str());
+                }
+            }
+        }
+        for pair in command.windows(2) {
+            if let (Token::Operator(operator), Token::Arg(target)) = (&pair[0], &pair[1]) {
+                if operator == ">" {
+                    outputs.push(target.as_str());
+                }
+            }
+        }
+    }
+    outputs
+}
+
+fn render_line(line: &[Token], substitute: &dyn Fn(&str) -> Vec<Piece>, shell: Shell) -> String {
+    let mut words = Vec::new();
+    let mut expect_program = true;
+    for token in line {
+        match token {
+            Token::Operator(operator) => {
+                words.push(operator.clone());
+                expect_program = operator == "|";
+            }
+            Token::Arg(program) if expect_program => {
+                words.push(shell_quote::quote_arg(&program_name(program), shell));
+                expect_program = false;
+            }
+            Token::Arg(arg) => words.push(quote(&substitute(arg), shell)),
+        }
+    }
+    let command = words.join(" ");
+    match shell {
+        Shell::Posix => command,
+        // Native programs do not stop a PowerShell script when they fail
+        Shell::PowerShell => format!("{}\n    if ($LASTEXITCODE -ne 0) {{ throw \"{} failed with exit code $LASTEXITCODE\" }}", command, words[0]),
+    }
+}
+
+/// One argument for `shell`: single-quoted when it is all literal text, else a double-quoted
+/// string the variables expand in
+fn quote(pieces: &[Piece], shell: Shell) -> String {
+    if let [Piece::Text(text)] = pieces {
+        return shell_quote::quote_arg(text, shell);
+    }
+    let mut quoted = String::from("\"");
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => {
+                for c in text.chars() {
+                    match (shell, c) {
+                        (Shell::Posix, '\\' | '"' | '$' | '`') => quoted.push('\\'),
+                        // PowerShell also treats the typographic double quotes as quote characters
+                        (Shell::PowerShell, '`' | '"' | '$' | '\u{201C}' | '\u{201D}' | '\u{201E}') => quoted.push('`'),
+                        _ => {}
+                    }
+                    quoted.push(c);
+                }
+            }
+            Piece::Var(name) => quoted.push_str(&format!("${{{}}}", name)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn mkdir_line(dir: &[Piece], shell: Shell) -> String {
+    match shell {
+        Shell::Posix => format!("mkdir -p {}", quote(dir, shell)),
+        Shell::PowerShell => format!("New-Item -ItemType Directory -Force -Path {} | Out-Null", quote(dir, shell)),
+    }
+}
+
+/// Output File nodes copy their input; an image folder is copied as a folder
+fn copy_line(from: &[Piece], to: &[Piece], shell: Shell) -> String {
+    match shell {
+        Shell::Posix => format!("cp -R {} {}", quote(from, shell), quote(to, shell)),
+        Shell::PowerShell => format!("Copy-Item -Recurse -Force -LiteralPath {} -Destination {}", quote(from, shell), quote(to, shell)),
+    }
+}
+
+fn assemble(workflow_name: &str, variables: &[Variable], body: &[String], shell: Shell) -> String {
+    let name = workflow_name.replace(['\r', '\n'], " ");
+    let mut declared: Vec<&str> = Vec::new();
+    let mut settings: Vec<String> = Vec::new();
+    for variable in variables.iter().filter(|variable| !variable.temporary) {
+        if declared.contains(&variable.name.as_str()) {
+            continue;
+        }
+        declared.push(&variable.name);
+        let value = shell_quote::quote_arg(&variable.value, shell);
+        settings.push(match shell {
+            Shell::Posix => format!("{}={}", variable.name, value),
+            Shell::PowerShell => format!("${} = {}", variable.name, value),
+        });
+    }
+
+    let mut lines = Vec::new();
+    match shell {
+        Shell::Posix => {
+            lines.push("#!/usr/bin/env bash".to_string());
+            lines.push(format!("# Workflow '{}', exported from FF GUI. Needs ffmpeg on PATH; stops at the first failing command.", name));
+            lines.push("set -euo pipefail".to_string());
+            lines.push(String::new());
+            if !settings.is_empty() {
+                lines.push("# Files to process; change them to run the workflow on others".to_string());
+                lines.extend(settings);
+                lines.push(String::new());
+            }
+            lines.push("# Intermediate files, removed when the script ends".to_string());
+            lines.push("TMP=\"$(mktemp -d)\"".to_string());
+            lines.push("trap 'rm -rf \"$TMP\"' EXIT".to_string());
+            lines.extend(body.iter().cloned());
+        }
+        Shell::PowerShell => {
+            lines.push(format!("# Workflow '{}', exported from FF GUI. Needs ffmpeg on PATH; stops at the first failing command.", name));
+            lines.push("$ErrorActionPreference = 'Stop'".to_string());
+            lines.push(String::new());
+            if !settings.is_empty() {
+                lines.push("# Files to process; change them to run the workflow on others".to_string());
+                lines.extend(settings);
+                lines.push(String::new());
+            }
+            lines.push("# Intermediate files, removed when the script ends".to_string());
+            lines.push("$TMP = Join-Path ([System.IO.Path]::GetTempPath()) ([System.Guid]::NewGuid().ToString())".to_string());
+            lines.push("New-Item -ItemType Directory -Path $TMP | Out-Null".to_string());
+            lines.push("try {".to_string());
+            lines.extend(body.iter().map(|line| if line.is_empty() { String::new() } else { format!("    {}", line) }));
+            lines.push("} finally {".to_string());
+            lines.push("    Remove-Item -Recurse -Force -LiteralPath $TMP -ErrorAction SilentlyContinue".to_string());
+            lines.push("}".to_string());
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Write `script` to `path`; a POSIX script is made executable
+pub fn save(path: &Path, script: &str) -> Result<(), String> {
+    std::fs::write(path, script).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    if shell_for_path(path) == Shell::Posix {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+    }
+    log_info!("Workflow script exported to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+
+use crate::automation_flow::{AutomationWorkflow, ConnectionEnd};
+use crate::language::Translations;
+use crate::viewport_geometry::CanvasView;
+
+/// Comparable form of a workflow for unsaved-change detection; the modification time is ignored
+fn snapshot(workflow: &AutomationWorkflow) -> serde_json::Value {
+    let mut value = serde_json::to_value(workflow).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("modified_at");
+    }
+    value
+}
+
+/// Menu opened by right-clicking connection lines. Overlapping lines are all listed so the user
+/// picks one explicitly; `chosen` is set right away when only one was hit.
+pub struct ConnectionMenu {
+    /// Screen position of the click
+    pub position: egui::Pos2,
+    /// Connections under the click, nearest first
+    pub connections: Vec<String>,
+    pub chosen: Option<String>,
+    /// Candidate under the pointer while picking, outlined on the canvas
+    pub hovered: Option<String>,
+}
+
+impl ConnectionMenu {
+    pub fn new(position: egui::Pos2, connections: Vec<String>) -> Self {
+        let chosen = if connections.len() == 1 { connections.first().cloned() } else { None };
+        Self { position, connections, chosen, hovered: None }
+    }
+}
+
+/// Everything the editor keeps per open workflow: the workflow itself, undo history,
+/// selection, connection in progress and canvas transform
+pub struct WorkflowEditorState {
+    pub workflow: AutomationWorkflow,
+    /// File the workflow was loaded from or last saved to
+    pub file_path: Option<std::path::PathBuf>,
+    saved_snapshot: serde_json::Value,
+    dirty: bool,
+
+    /// Selected nodes; the properties panel shows the node when exactly one is selected
+    pub selected_nodes: HashSet<String>,
+    pub dragging_node: Option<String>,
+    /// Screen position where a selection rectangle drag on empty canvas started
+    pub marquee_start: Option<egui::Pos2>,
+    pub canvas_zoom: f32,
+    /// Screen offset of the workflow origin from the canvas's top-left corner
+    pub canvas_offset: egui::Vec2,
+    /// A middle-button or Space+drag pan is in progress
+    pub panning: bool,
+    /// Frame all nodes on the canvas's next frame, once its size is known
+    pub fit_pending: bool,
+
+    pub creating_connection: bool,
+    pub connection_source_node: Option<String>,
+    pub connection_source_port: Option<usize>,
+    pub connection_target_pos: egui::Pos2,
+    /// Right-click menu over connection lines
+    pub connection_menu: Option<ConnectionMenu>,
+    /// Connection whose endpoints are outlined on the canvas
+    pub highlighted_connection: Option<String>,
+    /// Connection end being dragged to another port
+    pub rerouting: Option<(String, ConnectionEnd)>,
+    /// Connection the dragged node is spliced into when dropped
+    pub splice_target: Option<String>,
+
+    pub history: Vec<AutomationWorkflow>,
+    pub history_index: isize,
+}
+
+impl WorkflowEditorState {
+    pub fn new(workflow: AutomationWorkflow) -> Self {
+        Self {
+            saved_snapshot: snapshot(&workflow),
+            workflow,
+            file_path: None,
+            dirty: false,
+            selected_nodes: HashSet::new(),
+            dragging_node: None,
+            marquee_start: None,
+            canvas_zoom: 1.0,
+            canvas_offset: egui::Vec2::ZERO,
+            panning: false,
+            fit_pending: false,
+            creating_connection: false,
+            connection_source_node: None,
+            connection_source_port: None,
+            connection_target_pos: egui::Pos2::ZERO,
+            connection_menu: None,
+            highlighted_connection: None,
+            rerouting: None,
+            splice_target: None,
+            history: Vec::new(),
+            history_index: -1,
+        }
+    }
+
+    pub fn from_file(workflow: AutomationWorkflow, path: std::path::PathBuf) -> Self {
+        let mut state = Self::new(workflow);
+        state.file_path = Some(path);
+        state.fit_pending = true;
+        state
+    }
+
+    /// Workflow recovered from an autosave; it stays unsaved until saved again
+    pub fn recovered(workflow: AutomationWorkflow, path: Option<std::path::PathBuf>) -> Self {
+        let mut state = Self::new(workflow);
+        state.file_path = path;
+        state.saved_snapshot = serde_json::Value::Null;
+        state.dirty = true;
+        state.fit_pending = true;
+        state
+    }
+
+    /// Untouched new workflow that a loaded file may replace instead of opening another tab
+    pub fn is_blank(&self) -> bool {
+        self.workflow.nodes.is_empty() && self.file_path.is_none() && !self.dirty
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Re-check for unsaved changes; called once per frame for the focused tab, the only one that can change
+    pub fn refresh_dirty(&mut self) {
+        self.dirty = snapshot(&self.workflow) != self.saved_snapshot;
+    }
+
+    pub fn mark_saved(&mut self, path: std::path::PathBuf) {
+        self.saved_snapshot = snapshot(&self.workflow);
+        self.file_path = Some(path);
+        self.dirty = false;
+    }
+
+    pub fn title(&self) -> String {
+        if self.dirty {
+            format!("{} •", self.workflow.name)
+        } else {
+            self.workflow.name.clone()
+        }
+    }
+
+    pub fn canvas_view(&self, rect: egui::Rect) -> CanvasView {
+        CanvasView::new(rect, self.canvas_offset, self.canvas_zoom)
+    }
+
+    /// Pan and zoom so every node fits in the canvas; an empty workflow goes back to the origin
+    pub fn fit_to_view(&mut self, view: &CanvasView) {
+        let bounds = self.workflow.nodes.values()
+            .map(|node| egui::Rect::from_min_size(node.position, node.size))
+            .reduce(|a, b| a.union(b));
+        (self.canvas_offset, self.canvas_zoom) = match bounds {
+            Some(bounds) => view.fit(bounds),
+            None => (egui::Vec2::ZERO, 1.0),
+        };
+    }
+
+    /// The selected node when it is the only one
+    pub fn single_selected(&self) -> Option<String> {
+        match self.selected_nodes.len() {
+            1 => self.selected_nodes.iter().next().cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn is_selected(&self, node_id: &str) -> bool {
+        self.selected_nodes.contains(node_id)
+    }
+
+    pub fn select_only(&mut self, node_id: &str) {
+        self.selected_nodes.clear();
+        self.selected_nodes.insert(node_id.to_string());
+    }
+
+    /// Ctrl+click: add the node to the selection or take it out
+    pub fn toggle_selected(&mut self, node_id: &str) {
+        if !self.selected_nodes.remove(node_id) {
+            self.selected_nodes.insert(node_id.to_string());
+        }
+    }
+
+    /// Select the nodes overlapping `rect`, given in workflow coordinates, adding to the
+    /// selection when `add` is set
+    pub fn select_in_rect(&mut self, rect: egui::Rect, add: bool) {
+        if !add {
+            self.selected_nodes.clear();
+        }
+        let hits = self.workflow.nodes.values()
+            .filter(|node| rect.intersects(egui::Rect::from_min_size(node.position, node.size)))
+            .map(|node| node.id.clone());
+        self.selected_nodes.extend(hits);
+    }
+
+    pub fn cancel_connection(&mut self) {
+        self.creating_connection = false;
+        self.connection_source_node = None;
+        self.connection_source_port = None;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history_index > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history_index < (self.history.len() as isize - 1)
+    }
+
+    /// Push the current workflow onto the undo history, dropping any redo steps
+    pub fn save_state(&mut self, max_history_size: usize) {
+        if self.history_index >= 0 && (self.history_index as usize) < self.history.len() - 1 {
+            self.history.truncate((self.history_index + 1) as usize);
+        }
+
+        self.history.push(self.workflow.clone());
+        self.history_index = self.history.len() as isize - 1;
+
+        if self.history.len() > max_history_size {
+            self.history.remove(0);
+            self.history_index -= 1;
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.history_index -= 1;
+        match self.history.get(self.history_index as usize) {
+            Some(workflow) => {
+                self.workflow = workflow.clone();
+                self.selected_nodes.retain(|id| self.workflow.nodes.contains_key(id));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        self.history_index += 1;
+        match self.history.get(self.history_index as usize) {
+            Some(workflow) => {
+                self.workflow = workflow.clone();
+                self.selected_nodes.retain(|id| self.workflow.nodes.contains_key(id));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub enum TabAction {
+    Select(usize),
+    Close(usize),
+    New,
+}
+
+/// One button per open workflow plus "+"; the caller applies the returned action
+pub fn show_tab_bar(ui: &mut egui::Ui, tabs: &[WorkflowEditorState], active: usize, translations: &Translations) -> Option<TabAction> {
+    let mut action = None;
+    egui::ScrollArea::horizontal().id_salt("workflow_tabs").show(ui, |ui| {
+        ui.horizontal(|ui| {
+            for (index, tab) in tabs.iter().enumerate() {
+                let response = ui.selectable_label(index == active, tab.title());
+                let response = match &tab.file_path {
+                    Some(path) => response.on_hover_text(path.display().to_string()),
+                    None => response,
+                };
+                if response.clicked() {
+                    action = Some(TabAction::Select(index));
+                }
+                if response.middle_clicked() || ui.small_button("✖").on_hover_text(translations.close_workflow_tab()).clicked() {
+                    action = Some(TabAction::Close(index));
+                }
+                ui.separator();
+            }
+            if ui.small_button("➕").on_hover_text(translations.new_workflow_tab()).clicked() {
+                action = Some(TabAction::New);
+            }
+        });
+    });
+    action
+}